@@ -0,0 +1,2 @@
+-- Per-project ingestion settings (policies that don't warrant their own column)
+ALTER TABLE projects ADD COLUMN IF NOT EXISTS settings JSONB NOT NULL DEFAULT '{}'::jsonb;
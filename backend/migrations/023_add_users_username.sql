@@ -0,0 +1,5 @@
+-- Add username column to users table
+-- Optional unique handle (stored lowercase) that can be used instead of email to log in
+ALTER TABLE users ADD COLUMN IF NOT EXISTS username VARCHAR(30);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_username ON users(username) WHERE username IS NOT NULL;
@@ -0,0 +1,3 @@
+-- Add preferred default organization to users table
+-- Used as the org context on login; cleared when the user is no longer a member
+ALTER TABLE users ADD COLUMN IF NOT EXISTS default_org_id VARCHAR(36) REFERENCES organizations(id) ON DELETE SET NULL;
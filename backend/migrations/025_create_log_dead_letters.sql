@@ -0,0 +1,13 @@
+-- Dead-letter store for log lines that could not be parsed at ingest
+-- Keeps the raw payload so it can be inspected or replayed later
+CREATE TABLE IF NOT EXISTS log_dead_letters (
+    id VARCHAR(36) PRIMARY KEY,
+    project_id VARCHAR(36) NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    format VARCHAR(32) NOT NULL,
+    payload TEXT NOT NULL,
+    error TEXT NOT NULL,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_log_dead_letters_project_received
+    ON log_dead_letters (project_id, received_at DESC);
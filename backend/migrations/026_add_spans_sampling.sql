@@ -0,0 +1,4 @@
+-- Keep the W3C tracestate and the sampling probability derived from it
+-- so counts can be scaled up by the inverse sampling rate
+ALTER TABLE spans ADD COLUMN IF NOT EXISTS trace_state TEXT;
+ALTER TABLE spans ADD COLUMN IF NOT EXISTS sampling_probability DOUBLE PRECISION;
@@ -0,0 +1,16 @@
+-- Audit trail of authentication events (logins, refreshes, credential changes)
+-- user_id has no foreign key so records outlive the accounts they describe
+CREATE TABLE IF NOT EXISTS auth_audit_events (
+    id VARCHAR(36) PRIMARY KEY,
+    event_type VARCHAR(50) NOT NULL,
+    success BOOLEAN NOT NULL,
+    user_id VARCHAR(36),
+    identifier VARCHAR(255),
+    ip_address VARCHAR(64),
+    user_agent TEXT,
+    failure_reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_auth_audit_events_user_created ON auth_audit_events(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_auth_audit_events_created ON auth_audit_events(created_at DESC);
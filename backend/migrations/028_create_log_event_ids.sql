@@ -0,0 +1,11 @@
+-- Client-supplied log event ids seen per project, so retried ingests are stored once
+-- Rows older than the dedup window are pruned at ingest
+CREATE TABLE IF NOT EXISTS log_event_ids (
+    project_id VARCHAR(36) NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    event_id VARCHAR(255) NOT NULL,
+    seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (project_id, event_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_log_event_ids_project_seen
+    ON log_event_ids (project_id, seen_at);
@@ -0,0 +1,10 @@
+-- Alert channels can be owned by an organization and shared with all of its projects.
+-- Exactly one of project_id / organization_id is set.
+ALTER TABLE alert_channels
+    ADD COLUMN IF NOT EXISTS organization_id VARCHAR(36) REFERENCES organizations(id) ON DELETE CASCADE;
+ALTER TABLE alert_channels ALTER COLUMN project_id DROP NOT NULL;
+ALTER TABLE alert_channels ADD CONSTRAINT alert_channels_single_scope
+    CHECK ((project_id IS NULL) <> (organization_id IS NULL));
+
+CREATE INDEX IF NOT EXISTS idx_alert_channels_organization
+    ON alert_channels(organization_id) WHERE organization_id IS NOT NULL;
@@ -0,0 +1,2 @@
+-- Seconds after an alert resolves during which its rule won't fire again
+ALTER TABLE alert_rules ADD COLUMN IF NOT EXISTS cooldown_seconds INT NOT NULL DEFAULT 0;
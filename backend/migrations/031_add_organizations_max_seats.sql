@@ -0,0 +1,2 @@
+-- Member cap for tiered plans (members plus pending invites); NULL means unlimited
+ALTER TABLE organizations ADD COLUMN IF NOT EXISTS max_seats INT CHECK (max_seats > 0);
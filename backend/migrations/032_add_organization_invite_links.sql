@@ -0,0 +1,6 @@
+-- Multi-use invite links: no invitee, admitting up to max_uses users
+ALTER TABLE organization_invites ALTER COLUMN invitee_email DROP NOT NULL;
+
+ALTER TABLE organization_invites
+    ADD COLUMN IF NOT EXISTS max_uses INTEGER NOT NULL DEFAULT 1 CHECK (max_uses >= 1),
+    ADD COLUMN IF NOT EXISTS uses INTEGER NOT NULL DEFAULT 0 CHECK (uses >= 0);
@@ -0,0 +1,3 @@
+-- Sign-in methods an organization's members may use: password, oidc or both
+ALTER TABLE organizations ADD COLUMN IF NOT EXISTS allowed_auth_methods VARCHAR(20) NOT NULL DEFAULT 'both'
+    CHECK (allowed_auth_methods IN ('password', 'oidc', 'both'));
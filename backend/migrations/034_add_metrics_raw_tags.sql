@@ -0,0 +1,2 @@
+-- Labels as received, kept when a project's label normalization preserves them; NULL otherwise
+ALTER TABLE metrics ADD COLUMN IF NOT EXISTS raw_tags JSONB;
@@ -0,0 +1,3 @@
+-- How urgently a rule's alerts need attention; critical alerts bypass notification digests
+ALTER TABLE alert_rules ADD COLUMN IF NOT EXISTS severity VARCHAR(20) NOT NULL DEFAULT 'warning'
+    CHECK (severity IN ('info', 'warning', 'critical'));
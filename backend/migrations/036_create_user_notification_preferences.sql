@@ -0,0 +1,29 @@
+-- Per-user alert notification preferences, shared across all of a user's organizations
+CREATE TABLE IF NOT EXISTS user_notification_preferences (
+    user_id VARCHAR(36) PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    channel_config JSONB,               -- personal webhook {"url": "...", "headers": {...}}
+    severities TEXT[] NOT NULL DEFAULT ARRAY['info', 'warning', 'critical'],
+    mute_windows JSONB NOT NULL DEFAULT '[]',  -- [{"start": "22:00:00", "end": "07:00:00"}] in UTC
+    digest_enabled BOOLEAN NOT NULL DEFAULT false,
+    digest_interval_minutes INTEGER NOT NULL DEFAULT 60 CHECK (digest_interval_minutes > 0),
+    last_digest_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_notification_preferences_digest
+    ON user_notification_preferences(user_id) WHERE digest_enabled;
+
+-- Alerts held for a user's next digest
+CREATE TABLE IF NOT EXISTS notification_digest_entries (
+    id BIGSERIAL PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    alert_id VARCHAR(36) NOT NULL,
+    rule_name VARCHAR(255) NOT NULL,
+    project_name VARCHAR(255) NOT NULL,
+    severity VARCHAR(20) NOT NULL,
+    message TEXT NOT NULL,
+    triggered_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_digest_entries_user
+    ON notification_digest_entries(user_id, id);
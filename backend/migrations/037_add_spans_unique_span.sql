@@ -0,0 +1,13 @@
+-- A span is stored once per (trace_id, span_id) so retried exports don't duplicate it.
+-- The hypertable's partition column must be part of any unique index, so start_time is
+-- included; a resent span carries the same start time.
+DELETE FROM spans a
+USING spans b
+WHERE a.project_id = b.project_id
+  AND a.trace_id = b.trace_id
+  AND a.span_id = b.span_id
+  AND a.start_time = b.start_time
+  AND (a.received_at, a.id) < (b.received_at, b.id);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_spans_unique_span
+    ON spans(project_id, trace_id, span_id, start_time);
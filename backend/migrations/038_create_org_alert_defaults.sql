@@ -0,0 +1,10 @@
+-- Alerting every new project in an organization starts with (opt-in per org).
+-- Default rules are created in each new project, bound to the org-shared channel.
+CREATE TABLE IF NOT EXISTS org_alert_defaults (
+    organization_id VARCHAR(36) PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+    enabled BOOLEAN NOT NULL DEFAULT false,
+    channel_id VARCHAR(36) NOT NULL REFERENCES alert_channels(id) ON DELETE CASCADE,
+    rules JSONB NOT NULL DEFAULT '[]',  -- rule templates, without channels
+    updated_by VARCHAR(36) NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,11 @@
+-- Human-friendly details for the services reporting into a project, joined
+-- onto log and trace query results by raw service name
+CREATE TABLE IF NOT EXISTS service_metadata (
+    project_id VARCHAR(36) NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    service_name VARCHAR(255) NOT NULL,
+    display_name VARCHAR(100) NOT NULL,
+    team VARCHAR(100),
+    links JSONB NOT NULL DEFAULT '[]',  -- [{"title": ..., "url": ...}]
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (project_id, service_name)
+);
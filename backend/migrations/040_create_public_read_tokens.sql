@@ -0,0 +1,9 @@
+-- Tokens granting unauthenticated read-only access to a project's queries,
+-- for publicly shared dashboards. At most one per project; only the hash is kept.
+CREATE TABLE IF NOT EXISTS public_read_tokens (
+    project_id VARCHAR(36) PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    token_prefix VARCHAR(20) NOT NULL,
+    created_by VARCHAR(36) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
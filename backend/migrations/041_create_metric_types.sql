@@ -0,0 +1,9 @@
+-- Type each metric name was first ingested with, so later points sending the
+-- same name as a different type can be rejected before they skew aggregations
+CREATE TABLE IF NOT EXISTS metric_types (
+    project_id VARCHAR(36) NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    metric_type VARCHAR(20) NOT NULL,
+    registered_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (project_id, name)
+);
@@ -0,0 +1,4 @@
+-- Days an organization's activity log is kept before pruning; NULL uses the
+-- server-wide default
+ALTER TABLE organizations ADD COLUMN IF NOT EXISTS activity_retention_days INTEGER
+    CHECK (activity_retention_days > 0);
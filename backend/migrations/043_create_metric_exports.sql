@@ -0,0 +1,9 @@
+-- Days of rolled-up metrics already written to object storage, so the
+-- scheduled Parquet export writes each project's day once
+CREATE TABLE IF NOT EXISTS metric_exports (
+    project_id VARCHAR(36) NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    object_key TEXT NOT NULL,
+    exported_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (project_id, day)
+);
@@ -0,0 +1,3 @@
+-- A child rule stays quiet while its parent rule is firing
+ALTER TABLE alert_rules ADD COLUMN IF NOT EXISTS parent_rule_id VARCHAR(36)
+    REFERENCES alert_rules(id) ON DELETE SET NULL;
@@ -0,0 +1,8 @@
+-- Ingest order for logs sharing a timestamp. Logs stored before this have no
+-- sequence and keep ordering by timestamp alone.
+CREATE SEQUENCE IF NOT EXISTS logs_sequence_seq;
+ALTER TABLE logs ADD COLUMN sequence BIGINT;
+ALTER TABLE logs ALTER COLUMN sequence SET DEFAULT nextval('logs_sequence_seq');
+
+-- Sub-microsecond part of the timestamp (0-999ns), which TIMESTAMPTZ can't hold
+ALTER TABLE logs ADD COLUMN timestamp_nanos SMALLINT NOT NULL DEFAULT 0;
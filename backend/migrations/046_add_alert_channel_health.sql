@@ -0,0 +1,4 @@
+-- Delivery health per channel; channels failing too often are disabled
+ALTER TABLE alert_channels ADD COLUMN IF NOT EXISTS consecutive_failures INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE alert_channels ADD COLUMN IF NOT EXISTS last_delivery_error TEXT;
+ALTER TABLE alert_channels ADD COLUMN IF NOT EXISTS auto_disabled_at TIMESTAMPTZ;
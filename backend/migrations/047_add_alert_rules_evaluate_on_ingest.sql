@@ -0,0 +1,2 @@
+-- Rules evaluated as soon as matching logs are ingested, besides the regular schedule
+ALTER TABLE alert_rules ADD COLUMN IF NOT EXISTS evaluate_on_ingest BOOLEAN NOT NULL DEFAULT false;
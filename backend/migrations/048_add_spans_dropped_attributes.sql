@@ -0,0 +1,2 @@
+-- Attributes dropped from a span, by its sender or at ingest
+ALTER TABLE spans ADD COLUMN IF NOT EXISTS dropped_attributes_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+-- CIDR ranges members may use the dashboard API from; empty allows every address
+ALTER TABLE organizations ADD COLUMN IF NOT EXISTS allowed_ips TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,2 @@
+-- Days and local hours during which a rule may notify; NULL notifies at any time
+ALTER TABLE alert_rules ADD COLUMN IF NOT EXISTS schedule JSONB;
@@ -0,0 +1,21 @@
+-- Dedup keys claimed at ingest, shared by every replica and kept across restarts until they expire
+CREATE TABLE IF NOT EXISTS ingest_dedup_keys (
+    project_id VARCHAR(36) NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    -- What kind of key this is, e.g. 'log_event_id'
+    scope VARCHAR(32) NOT NULL,
+    key VARCHAR(255) NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (project_id, scope, key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ingest_dedup_keys_expires
+    ON ingest_dedup_keys (expires_at);
+
+-- Carry over event ids still inside the default one-hour window
+INSERT INTO ingest_dedup_keys (project_id, scope, key, expires_at)
+SELECT project_id, 'log_event_id', event_id, seen_at + INTERVAL '1 hour'
+FROM log_event_ids
+WHERE seen_at > NOW() - INTERVAL '1 hour'
+ON CONFLICT DO NOTHING;
+
+DROP TABLE IF EXISTS log_event_ids;
@@ -0,0 +1,25 @@
+-- TOTP second factor per user; the secret is encrypted by the application before it is stored
+CREATE TABLE IF NOT EXISTS user_totp (
+    user_id VARCHAR(36) PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    -- AES-256-GCM nonce followed by ciphertext
+    secret_encrypted BYTEA NOT NULL,
+    -- SHA256 of each unused recovery code
+    recovery_code_hashes TEXT[] NOT NULL DEFAULT '{}',
+    -- NULL while setup is waiting for the first valid code
+    confirmed_at TIMESTAMPTZ,
+    -- Time step of the last accepted code, to reject replays
+    last_used_step BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Logins that passed the password check and are waiting for a code
+CREATE TABLE IF NOT EXISTS totp_challenges (
+    token_hash VARCHAR(64) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    device_fingerprint VARCHAR(64) NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    failed_attempts INT NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_totp_challenges_user_id ON totp_challenges(user_id);
+CREATE INDEX IF NOT EXISTS idx_totp_challenges_expires_at ON totp_challenges(expires_at);
@@ -0,0 +1,2 @@
+-- Lets alert history retention find old resolved alerts without a full scan
+CREATE INDEX IF NOT EXISTS idx_alerts_resolved_at ON alerts(resolved_at) WHERE status = 'resolved';
@@ -1,14 +1,115 @@
 use std::env;
 
+use ipnet::IpNet;
+
+use crate::modules::auth::infrastructure::{OAuthClientCredentials, OAuthConfig, SmtpConfig};
+use crate::modules::metrics::domain::RollupInterval;
+use crate::modules::metrics_export::MetricsExportSettings;
+use crate::modules::projects::application::ApiKeyCacheConfig;
+use crate::modules::projects::domain::parse_network;
+use crate::shared::{
+    BodyLimits, IdFormatPolicy, JsonLimits, QueryCacheConfig, S3Config, TraceKeepSetConfig,
+};
+
 /// Application configuration loaded from environment variables
 #[derive(Debug, Clone)]
 pub struct Config {
     pub database_url: String,
+    /// How long startup waits for the database to accept connections; 0 fails immediately
+    pub db_connect_timeout_secs: u64,
     pub jwt_access_secret: String,
     pub jwt_refresh_secret: String,
     pub refresh_token_duration_days: i64,
+    pub refresh_token_sliding: bool,
+    pub refresh_token_max_lifetime_days: i64,
+    /// AES-256 key encrypting TOTP secrets at rest
+    pub totp_encryption_key: [u8; 32],
+    /// Argon2 memory cost in KiB for new password hashes
+    pub argon2_memory_kib: u32,
+    pub argon2_iterations: u32,
+    pub argon2_parallelism: u32,
+    /// Sign-in with GitHub and Google; None when neither provider is set up
+    pub oauth: Option<OAuthConfig>,
+    /// Outbound mail for email verification and password reset; None when SMTP_URL is unset
+    pub smtp: Option<SmtpConfig>,
     pub host: String,
     pub port: u16,
+    pub pagination_default_limit: i64,
+    pub pagination_max_limit: i64,
+    pub metrics_out_of_order_tolerance_secs: i64,
+    pub zipkin_ingest_enabled: bool,
+    pub prometheus_remote_write_enabled: bool,
+    /// Largest remote-write payload accepted once snappy-decompressed
+    pub prometheus_max_decompressed_bytes: usize,
+    pub syslog_ingest_enabled: bool,
+    pub syslog_udp_port: Option<u16>,
+    pub syslog_udp_project_id: Option<String>,
+    pub gelf_ingest_enabled: bool,
+    pub gelf_udp_port: Option<u16>,
+    pub gelf_udp_project_id: Option<String>,
+    pub statsd_ingest_enabled: bool,
+    pub statsd_udp_port: Option<u16>,
+    pub statsd_udp_project_id: Option<String>,
+    /// Seconds StatsD UDP samples are aggregated before being written
+    pub statsd_flush_interval_secs: u64,
+    pub cloudwatch_ingest_enabled: bool,
+    pub loki_ingest_enabled: bool,
+    pub deployment_region: Option<String>,
+    pub auth_audit_enabled: bool,
+    /// Users must verify their email address before they can create API keys
+    pub email_verification_required: bool,
+    pub alert_aligned_windows: bool,
+    /// Most alert rules a project may have; None when set to 0 (unlimited)
+    pub alert_rules_max_per_project: Option<u32>,
+    /// Failed deliveries in a row that disable an alert channel; 0 never disables
+    pub alert_channel_max_failures: u32,
+    /// Least seconds between two ingest-triggered evaluations of one alert rule
+    pub alert_ingest_min_interval_secs: u64,
+    pub log_dedup_window_secs: i64,
+    /// Log batches that may wait in the async ingest write queue
+    pub log_write_queue_capacity: usize,
+    /// CSV database (network,country,region,city) for GeoIP log enrichment;
+    /// the bundled one is used when unset
+    pub geoip_database_path: Option<String>,
+    /// Days organization activity is kept unless the org overrides it
+    pub org_activity_retention_days: u32,
+    /// Days membership and role activity is kept; 0 keeps it forever
+    pub org_activity_security_retention_days: u32,
+    /// Days resolved alerts are kept; 0 keeps them forever
+    pub alert_history_retention_days: u32,
+    /// Most rows one log/metric/span retention delete removes
+    pub retention_delete_batch_size: u32,
+    /// Pause between retention delete batches, in milliseconds
+    pub retention_delete_pause_ms: u64,
+    pub trace_id_policy: IdFormatPolicy,
+    /// Most spans accepted in a single trace ingest request
+    pub trace_ingest_max_spans: usize,
+    /// Largest trace ingest request body in bytes
+    pub trace_ingest_max_bytes: usize,
+    /// Structural limits on native log, metric and span ingest bodies
+    pub ingest_json_limits: JsonLimits,
+    /// Largest request body accepted by the auth, ingest and other API routes
+    pub body_limits: BodyLimits,
+    pub leader_check_interval_secs: u64,
+    pub trusted_proxies: Vec<IpNet>,
+    pub admin_api_token: Option<String>,
+    pub notifier_connect_timeout_ms: u64,
+    pub notifier_request_timeout_ms: u64,
+    /// Scheduled Parquet export of rolled-up metrics; None when no bucket is set
+    pub metrics_export: Option<MetricsExportConfig>,
+    /// Caching of metric and trace aggregation results; None when the TTL is 0
+    pub query_cache: Option<QueryCacheConfig>,
+    /// How long trace ids seen in metric exemplars escape trace sampling; None when 0
+    pub exemplar_trace_keep: Option<TraceKeepSetConfig>,
+    /// Caching of ingest API key lookups, valid and invalid
+    pub api_key_cache: ApiKeyCacheConfig,
+}
+
+/// Where and what the metrics export writes
+#[derive(Debug, Clone)]
+pub struct MetricsExportConfig {
+    pub store: S3Config,
+    pub settings: MetricsExportSettings,
 }
 
 impl Config {
@@ -16,9 +117,13 @@ impl Config {
     pub fn from_env() -> Result<Self, ConfigError> {
         dotenvy::dotenv().ok(); // Load .env file if present
 
-        Ok(Self {
+        Self {
             database_url: env::var("DATABASE_URL")
                 .map_err(|_| ConfigError::MissingEnv("DATABASE_URL"))?,
+            db_connect_timeout_secs: env::var("DB_CONNECT_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("DB_CONNECT_TIMEOUT_SECS"))?,
             jwt_access_secret: env::var("JWT_ACCESS_SECRET")
                 .map_err(|_| ConfigError::MissingEnv("JWT_ACCESS_SECRET"))?,
             jwt_refresh_secret: env::var("JWT_REFRESH_SECRET")
@@ -27,19 +132,410 @@ impl Config {
                 .unwrap_or_else(|_| "7".to_string())
                 .parse()
                 .map_err(|_| ConfigError::InvalidValue("REFRESH_TOKEN_DURATION_DAYS"))?,
+            refresh_token_sliding: env::var("REFRESH_TOKEN_SLIDING")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("REFRESH_TOKEN_SLIDING"))?,
+            refresh_token_max_lifetime_days: env::var("REFRESH_TOKEN_MAX_LIFETIME_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("REFRESH_TOKEN_MAX_LIFETIME_DAYS"))?,
+            totp_encryption_key: parse_encryption_key(
+                &env::var("TOTP_ENCRYPTION_KEY")
+                    .map_err(|_| ConfigError::MissingEnv("TOTP_ENCRYPTION_KEY"))?,
+            )
+            .ok_or(ConfigError::InvalidValue("TOTP_ENCRYPTION_KEY"))?,
+            argon2_memory_kib: env::var("ARGON2_MEMORY_KIB")
+                .unwrap_or_else(|_| argon2::Params::DEFAULT_M_COST.to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("ARGON2_MEMORY_KIB"))?,
+            argon2_iterations: env::var("ARGON2_ITERATIONS")
+                .unwrap_or_else(|_| argon2::Params::DEFAULT_T_COST.to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("ARGON2_ITERATIONS"))?,
+            argon2_parallelism: env::var("ARGON2_PARALLELISM")
+                .unwrap_or_else(|_| argon2::Params::DEFAULT_P_COST.to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("ARGON2_PARALLELISM"))?,
+            oauth: Self::oauth_from_env()?,
+            smtp: Self::smtp_from_env()?,
             host: env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
             port: env::var("PORT")
                 .unwrap_or_else(|_| "3000".to_string())
                 .parse()
                 .map_err(|_| ConfigError::InvalidValue("PORT"))?,
+            pagination_default_limit: env::var("PAGINATION_DEFAULT_LIMIT")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("PAGINATION_DEFAULT_LIMIT"))?,
+            pagination_max_limit: env::var("PAGINATION_MAX_LIMIT")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("PAGINATION_MAX_LIMIT"))?,
+            metrics_out_of_order_tolerance_secs: env::var("METRICS_OUT_OF_ORDER_TOLERANCE_SECS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("METRICS_OUT_OF_ORDER_TOLERANCE_SECS"))?,
+            zipkin_ingest_enabled: env::var("ZIPKIN_INGEST_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("ZIPKIN_INGEST_ENABLED"))?,
+            prometheus_remote_write_enabled: env::var("PROMETHEUS_REMOTE_WRITE_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("PROMETHEUS_REMOTE_WRITE_ENABLED"))?,
+            prometheus_max_decompressed_bytes: env::var("PROMETHEUS_MAX_DECOMPRESSED_BYTES")
+                .unwrap_or_else(|_| "67108864".to_string())
+                .parse()
+                .ok()
+                .filter(|bytes| *bytes > 0)
+                .ok_or(ConfigError::InvalidValue(
+                    "PROMETHEUS_MAX_DECOMPRESSED_BYTES",
+                ))?,
+            syslog_ingest_enabled: env::var("SYSLOG_INGEST_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("SYSLOG_INGEST_ENABLED"))?,
+            syslog_udp_port: env::var("SYSLOG_UDP_PORT")
+                .ok()
+                .map(|p| p.parse())
+                .transpose()
+                .map_err(|_| ConfigError::InvalidValue("SYSLOG_UDP_PORT"))?,
+            syslog_udp_project_id: env::var("SYSLOG_UDP_PROJECT_ID").ok(),
+            gelf_ingest_enabled: env::var("GELF_INGEST_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("GELF_INGEST_ENABLED"))?,
+            gelf_udp_port: env::var("GELF_UDP_PORT")
+                .ok()
+                .map(|p| p.parse())
+                .transpose()
+                .map_err(|_| ConfigError::InvalidValue("GELF_UDP_PORT"))?,
+            gelf_udp_project_id: env::var("GELF_UDP_PROJECT_ID").ok(),
+            statsd_ingest_enabled: env::var("STATSD_INGEST_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("STATSD_INGEST_ENABLED"))?,
+            statsd_udp_port: env::var("STATSD_UDP_PORT")
+                .ok()
+                .map(|p| p.parse())
+                .transpose()
+                .map_err(|_| ConfigError::InvalidValue("STATSD_UDP_PORT"))?,
+            statsd_udp_project_id: env::var("STATSD_UDP_PROJECT_ID").ok(),
+            statsd_flush_interval_secs: env::var("STATSD_FLUSH_INTERVAL_SECS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .ok()
+                .filter(|secs| *secs > 0)
+                .ok_or(ConfigError::InvalidValue("STATSD_FLUSH_INTERVAL_SECS"))?,
+            cloudwatch_ingest_enabled: env::var("CLOUDWATCH_INGEST_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("CLOUDWATCH_INGEST_ENABLED"))?,
+            loki_ingest_enabled: env::var("LOKI_INGEST_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("LOKI_INGEST_ENABLED"))?,
+            deployment_region: env::var("DEPLOYMENT_REGION").ok().filter(|r| !r.is_empty()),
+            auth_audit_enabled: env::var("AUTH_AUDIT_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("AUTH_AUDIT_ENABLED"))?,
+            email_verification_required: env::var("EMAIL_VERIFICATION_REQUIRED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("EMAIL_VERIFICATION_REQUIRED"))?,
+            alert_aligned_windows: env::var("ALERT_ALIGNED_WINDOWS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("ALERT_ALIGNED_WINDOWS"))?,
+            alert_rules_max_per_project: env::var("ALERT_RULES_MAX_PER_PROJECT")
+                .unwrap_or_else(|_| "200".to_string())
+                .parse::<u32>()
+                .map(|max| (max > 0).then_some(max))
+                .map_err(|_| ConfigError::InvalidValue("ALERT_RULES_MAX_PER_PROJECT"))?,
+            alert_channel_max_failures: env::var("ALERT_CHANNEL_MAX_FAILURES")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("ALERT_CHANNEL_MAX_FAILURES"))?,
+            alert_ingest_min_interval_secs: env::var("ALERT_INGEST_MIN_INTERVAL_SECS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .ok()
+                .filter(|secs| *secs > 0)
+                .ok_or(ConfigError::InvalidValue("ALERT_INGEST_MIN_INTERVAL_SECS"))?,
+            log_dedup_window_secs: env::var("LOG_DEDUP_WINDOW_SECS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("LOG_DEDUP_WINDOW_SECS"))?,
+            log_write_queue_capacity: env::var("LOG_WRITE_QUEUE_CAPACITY")
+                .unwrap_or_else(|_| "1024".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("LOG_WRITE_QUEUE_CAPACITY"))?,
+            geoip_database_path: env::var("GEOIP_DATABASE_PATH")
+                .ok()
+                .filter(|p| !p.is_empty()),
+            org_activity_retention_days: env::var("ORG_ACTIVITY_RETENTION_DAYS")
+                .unwrap_or_else(|_| "90".to_string())
+                .parse()
+                .ok()
+                .filter(|days| *days > 0)
+                .ok_or(ConfigError::InvalidValue("ORG_ACTIVITY_RETENTION_DAYS"))?,
+            org_activity_security_retention_days: env::var("ORG_ACTIVITY_SECURITY_RETENTION_DAYS")
+                .unwrap_or_else(|_| "365".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("ORG_ACTIVITY_SECURITY_RETENTION_DAYS"))?,
+            alert_history_retention_days: env::var("ALERT_HISTORY_RETENTION_DAYS")
+                .unwrap_or_else(|_| "90".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("ALERT_HISTORY_RETENTION_DAYS"))?,
+            retention_delete_batch_size: env::var("RETENTION_DELETE_BATCH_SIZE")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .ok()
+                .filter(|size| *size > 0)
+                .ok_or(ConfigError::InvalidValue("RETENTION_DELETE_BATCH_SIZE"))?,
+            retention_delete_pause_ms: env::var("RETENTION_DELETE_PAUSE_MS")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("RETENTION_DELETE_PAUSE_MS"))?,
+            trace_id_policy: IdFormatPolicy::from_str(
+                &env::var("TRACE_ID_POLICY").unwrap_or_else(|_| "strict".to_string()),
+            )
+            .ok_or(ConfigError::InvalidValue("TRACE_ID_POLICY"))?,
+            trace_ingest_max_spans: env::var("TRACE_INGEST_MAX_SPANS")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("TRACE_INGEST_MAX_SPANS"))?,
+            trace_ingest_max_bytes: env::var("TRACE_INGEST_MAX_BYTES")
+                .unwrap_or_else(|_| "10485760".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("TRACE_INGEST_MAX_BYTES"))?,
+            body_limits: Self::body_limits_from_env()?,
+            ingest_json_limits: JsonLimits {
+                max_depth: env::var("INGEST_JSON_MAX_DEPTH")
+                    .unwrap_or_else(|_| "64".to_string())
+                    .parse()
+                    .map_err(|_| ConfigError::InvalidValue("INGEST_JSON_MAX_DEPTH"))?,
+                max_entries: env::var("INGEST_JSON_MAX_ENTRIES")
+                    .unwrap_or_else(|_| "1000000".to_string())
+                    .parse()
+                    .map_err(|_| ConfigError::InvalidValue("INGEST_JSON_MAX_ENTRIES"))?,
+            },
+            leader_check_interval_secs: env::var("LEADER_CHECK_INTERVAL_SECS")
+                .unwrap_or_else(|_| "15".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("LEADER_CHECK_INTERVAL_SECS"))?,
+            trusted_proxies: env::var("TRUSTED_PROXIES")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty())
+                .map(|entry| {
+                    parse_network(entry).ok_or(ConfigError::InvalidValue("TRUSTED_PROXIES"))
+                })
+                .collect::<Result<_, _>>()?,
+            admin_api_token: env::var("ADMIN_API_TOKEN").ok().filter(|t| !t.is_empty()),
+            notifier_connect_timeout_ms: env::var("NOTIFIER_CONNECT_TIMEOUT_MS")
+                .unwrap_or_else(|_| "3000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("NOTIFIER_CONNECT_TIMEOUT_MS"))?,
+            notifier_request_timeout_ms: env::var("NOTIFIER_REQUEST_TIMEOUT_MS")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("NOTIFIER_REQUEST_TIMEOUT_MS"))?,
+            metrics_export: Self::metrics_export_from_env()?,
+            query_cache: Self::query_cache_from_env()?,
+            exemplar_trace_keep: Self::exemplar_trace_keep_from_env()?,
+            api_key_cache: ApiKeyCacheConfig {
+                ttl: std::time::Duration::from_secs(
+                    env::var("API_KEY_CACHE_TTL_SECS")
+                        .unwrap_or_else(|_| "30".to_string())
+                        .parse()
+                        .map_err(|_| ConfigError::InvalidValue("API_KEY_CACHE_TTL_SECS"))?,
+                ),
+                negative_ttl: std::time::Duration::from_secs(
+                    env::var("API_KEY_NEGATIVE_CACHE_TTL_SECS")
+                        .unwrap_or_else(|_| "10".to_string())
+                        .parse()
+                        .map_err(|_| {
+                            ConfigError::InvalidValue("API_KEY_NEGATIVE_CACHE_TTL_SECS")
+                        })?,
+                ),
+                max_entries: env::var("API_KEY_CACHE_MAX_ENTRIES")
+                    .unwrap_or_else(|_| "10000".to_string())
+                    .parse()
+                    .ok()
+                    .filter(|entries| *entries > 0)
+                    .ok_or(ConfigError::InvalidValue("API_KEY_CACHE_MAX_ENTRIES"))?,
+            },
+        }
+        .validated()
+    }
+
+    /// Reject settings that can't work together
+    fn validated(self) -> Result<Self, ConfigError> {
+        // Nobody could verify without verification emails being delivered
+        if self.email_verification_required && self.smtp.is_none() {
+            return Err(ConfigError::MissingEnv("SMTP_URL"));
+        }
+        Ok(self)
+    }
+
+    fn body_limits_from_env() -> Result<BodyLimits, ConfigError> {
+        let limit = |var: &'static str, default: usize| {
+            env::var(var)
+                .ok()
+                .map_or(Some(default), |value| value.parse().ok())
+                .filter(|bytes| *bytes > 0)
+                .ok_or(ConfigError::InvalidValue(var))
+        };
+        let defaults = BodyLimits::default();
+        Ok(BodyLimits {
+            auth: limit("AUTH_BODY_LIMIT_BYTES", defaults.auth)?,
+            ingest: limit("INGEST_BODY_LIMIT_BYTES", defaults.ingest)?,
+            query: limit("QUERY_BODY_LIMIT_BYTES", defaults.query)?,
         })
     }
 
+    fn oauth_from_env() -> Result<Option<OAuthConfig>, ConfigError> {
+        let credentials = |id_var: &'static str, secret_var: &'static str| {
+            let client_id = env::var(id_var).unwrap_or_default();
+            if client_id.is_empty() {
+                return Ok(None);
+            }
+            Ok(Some(OAuthClientCredentials {
+                client_id,
+                client_secret: env::var(secret_var)
+                    .map_err(|_| ConfigError::MissingEnv(secret_var))?,
+            }))
+        };
+        let github = credentials("OAUTH_GITHUB_CLIENT_ID", "OAUTH_GITHUB_CLIENT_SECRET")?;
+        let google = credentials("OAUTH_GOOGLE_CLIENT_ID", "OAUTH_GOOGLE_CLIENT_SECRET")?;
+        if github.is_none() && google.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(OAuthConfig {
+            redirect_base_url: env::var("OAUTH_REDIRECT_BASE_URL")
+                .map_err(|_| ConfigError::MissingEnv("OAUTH_REDIRECT_BASE_URL"))?,
+            github,
+            google,
+        }))
+    }
+
+    fn smtp_from_env() -> Result<Option<SmtpConfig>, ConfigError> {
+        let url = env::var("SMTP_URL").unwrap_or_default();
+        if url.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(SmtpConfig {
+            url,
+            from: env::var("SMTP_FROM")
+                .map_err(|_| ConfigError::MissingEnv("SMTP_FROM"))?
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("SMTP_FROM"))?,
+        }))
+    }
+
+    fn query_cache_from_env() -> Result<Option<QueryCacheConfig>, ConfigError> {
+        let ttl_secs: u64 = env::var("QUERY_CACHE_TTL_SECS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("QUERY_CACHE_TTL_SECS"))?;
+        if ttl_secs == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(QueryCacheConfig {
+            ttl: std::time::Duration::from_secs(ttl_secs),
+            max_entries: env::var("QUERY_CACHE_MAX_ENTRIES")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .ok()
+                .filter(|entries| *entries > 0)
+                .ok_or(ConfigError::InvalidValue("QUERY_CACHE_MAX_ENTRIES"))?,
+            live_window: chrono::Duration::seconds(
+                env::var("QUERY_CACHE_LIVE_WINDOW_SECS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .map_err(|_| ConfigError::InvalidValue("QUERY_CACHE_LIVE_WINDOW_SECS"))?,
+            ),
+        }))
+    }
+
+    fn exemplar_trace_keep_from_env() -> Result<Option<TraceKeepSetConfig>, ConfigError> {
+        let ttl_secs: u64 = env::var("EXEMPLAR_TRACE_KEEP_SECS")
+            .unwrap_or_else(|_| "600".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("EXEMPLAR_TRACE_KEEP_SECS"))?;
+        if ttl_secs == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(TraceKeepSetConfig {
+            ttl: std::time::Duration::from_secs(ttl_secs),
+            max_entries: env::var("EXEMPLAR_TRACE_KEEP_MAX_ENTRIES")
+                .unwrap_or_else(|_| "100000".to_string())
+                .parse()
+                .ok()
+                .filter(|entries| *entries > 0)
+                .ok_or(ConfigError::InvalidValue("EXEMPLAR_TRACE_KEEP_MAX_ENTRIES"))?,
+        }))
+    }
+
+    fn metrics_export_from_env() -> Result<Option<MetricsExportConfig>, ConfigError> {
+        let bucket = env::var("METRICS_EXPORT_BUCKET").unwrap_or_default();
+        if bucket.is_empty() {
+            return Ok(None);
+        }
+
+        let rollup = RollupInterval::from_str(
+            &env::var("METRICS_EXPORT_ROLLUP").unwrap_or_else(|_| "1h".to_string()),
+        );
+        if rollup == RollupInterval::Raw {
+            return Err(ConfigError::InvalidValue("METRICS_EXPORT_ROLLUP"));
+        }
+
+        Ok(Some(MetricsExportConfig {
+            store: S3Config {
+                endpoint: env::var("METRICS_EXPORT_ENDPOINT")
+                    .unwrap_or_else(|_| "https://s3.amazonaws.com".to_string()),
+                bucket,
+                region: env::var("METRICS_EXPORT_REGION")
+                    .unwrap_or_else(|_| "us-east-1".to_string()),
+                access_key_id: env::var("METRICS_EXPORT_ACCESS_KEY_ID")
+                    .map_err(|_| ConfigError::MissingEnv("METRICS_EXPORT_ACCESS_KEY_ID"))?,
+                secret_access_key: env::var("METRICS_EXPORT_SECRET_ACCESS_KEY")
+                    .map_err(|_| ConfigError::MissingEnv("METRICS_EXPORT_SECRET_ACCESS_KEY"))?,
+            },
+            settings: MetricsExportSettings {
+                rollup,
+                min_age_days: env::var("METRICS_EXPORT_MIN_AGE_DAYS")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse()
+                    .map_err(|_| ConfigError::InvalidValue("METRICS_EXPORT_MIN_AGE_DAYS"))?,
+                lookback_days: env::var("METRICS_EXPORT_LOOKBACK_DAYS")
+                    .unwrap_or_else(|_| "7".to_string())
+                    .parse()
+                    .map_err(|_| ConfigError::InvalidValue("METRICS_EXPORT_LOOKBACK_DAYS"))?,
+                prefix: env::var("METRICS_EXPORT_PREFIX").unwrap_or_else(|_| "metrics".to_string()),
+            },
+        }))
+    }
+
     pub fn addr(&self) -> String {
         format!("{}:{}", self.host, self.port)
     }
 }
 
+/// Decode a base64 AES-256 key, None unless it is exactly 32 bytes
+fn parse_encryption_key(value: &str) -> Option<[u8; 32]> {
+    use base64::{Engine, engine::general_purpose::STANDARD};
+    STANDARD.decode(value.trim()).ok()?.try_into().ok()
+}
+
 #[derive(Debug)]
 pub enum ConfigError {
     MissingEnv(&'static str),
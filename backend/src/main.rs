@@ -1,59 +1,98 @@
 mod config;
 mod modules;
+mod shared;
 
+use std::net::SocketAddr;
 use std::sync::Arc;
 
-use axum::Router;
+use axum::{Extension, Router};
 use sqlx::postgres::PgPoolOptions;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::config::Config;
+use crate::modules::admin::admin_routes;
+use crate::modules::alerts::infrastructure::notifiers::http_client as notifier_http_client;
+use crate::modules::alerts::{
+    AlertChannelService, AlertRuleService, AlertService as AlertHistoryService, EvaluatorHealth,
+    IngestRuleTrigger, NotificationPreferenceService, OrgAlertDefaultsService,
+    PostgresAlertChannelRepository, PostgresAlertRepository, PostgresAlertRuleRepository,
+    PostgresNotificationPreferenceRepository, PostgresOrgAlertDefaultsRepository, RuleEvaluator,
+    UserNotificationDispatcher, WebhookNotifier, alert_routes, channel_routes,
+    notification_preference_routes, org_alert_defaults_routes, rule_routes,
+};
 use crate::modules::auth::{
     application::AuthService,
-    domain::RefreshTokenRepository,
+    domain::{PasswordResetTokenRepository, RefreshTokenRepository, VerificationTokenRepository},
+    infrastructure::{
+        Argon2PasswordHasher, IpRateLimiter, JwtConfig, JwtTokenService, OAuthClient,
+        OrgIpAllowlist, PersonalAccessTokenAuth, PostgresAuthAuditRepository,
+        PostgresOAuthIdentityRepository, PostgresPasswordResetTokenRepository,
+        PostgresPersonalAccessTokenRepository, PostgresRefreshTokenRepository,
+        PostgresUserRepository, PostgresUserTotpRepository, PostgresVerificationTokenRepository,
+        SmtpEmailSender, UuidGenerator, auth_routes, oauth_routes,
+    },
+};
+use crate::modules::cloudwatch::cloudwatch_routes;
+use crate::modules::gelf::{gelf_routes, start_gelf_udp_listener};
+use crate::modules::leader::{LeaderElection, PgAdvisoryLock};
+use crate::modules::logging::{
+    application::services::{FilterPresetService, LogService, LogWriteQueue},
+    domain::{GeoIpDatabase, log::BUNDLED_GEOIP_DATABASE},
     infrastructure::{
-        Argon2PasswordHasher, IpRateLimiter, JwtConfig, JwtTokenService,
-        PostgresRefreshTokenRepository, PostgresUserRepository, UuidGenerator, auth_routes,
+        LogBroadcaster, PostgresFilterPresetRepository, TimescaleLogRepository,
+        filter_preset_routes, ingest_routes, log_query_routes, public_log_routes, sse_routes,
+        start_cleanup_task, start_log_listener,
     },
 };
+use crate::modules::loki::loki_routes;
+use crate::modules::metrics::{
+    application::MetricsService,
+    infrastructure::{
+        TimescaleMetricsRepository, ingest_routes as metrics_ingest_routes,
+        query_routes as metrics_query_routes,
+    },
+};
+use crate::modules::metrics_export::start_metrics_export;
 use crate::modules::organizations::{
     application::services::{InviteService, OrgService},
     domain::OrganizationInviteRepository,
     infrastructure::{
-        org_invite_routes, org_routes, user_invite_routes, PostgresInviteRepository,
-        PostgresOrgActivityRepository, PostgresOrganizationMemberRepository,
-        PostgresOrganizationRepository,
+        PostgresInviteRepository, PostgresOrgActivityRepository,
+        PostgresOrganizationMemberRepository, PostgresOrganizationRepository, org_invite_routes,
+        org_routes, user_invite_routes,
     },
 };
+use crate::modules::otlp::{otlp_logs_routes, otlp_metrics_routes, otlp_traces_routes};
 use crate::modules::projects::{
-    application::ProjectService,
-    infrastructure::{project_routes, PostgresApiKeyRepository, PostgresProjectRepository},
-};
-use crate::modules::logging::{
-    application::services::{FilterPresetService, LogService},
+    application::{ProjectService, PublicReadTokenService, ServiceMetadataService},
     infrastructure::{
-        filter_preset_routes, ingest_routes, log_query_routes, sse_routes, start_cleanup_task,
-        start_log_listener, LogBroadcaster, PostgresFilterPresetRepository, TimescaleLogRepository,
+        PostgresApiKeyRepository, PostgresProjectRepository, PostgresPublicReadTokenRepository,
+        PostgresServiceMetadataRepository, project_routes, public_token_routes,
+        service_metadata_routes,
     },
 };
-use crate::modules::alerts::{
-    AlertChannelService, AlertRuleService, AlertService as AlertHistoryService,
-    PostgresAlertChannelRepository, PostgresAlertRepository, PostgresAlertRuleRepository,
-    RuleEvaluator, WebhookNotifier,
-    alert_routes, channel_routes, rule_routes,
-};
-use crate::modules::metrics::{
-    application::MetricsService,
-    infrastructure::{TimescaleMetricsRepository, ingest_routes as metrics_ingest_routes, query_routes as metrics_query_routes},
+use crate::modules::prometheus::prometheus_routes;
+use crate::modules::retention::{
+    ActivityRetention, DeleteBatching, start_alert_history_cleanup, start_dedup_key_prune,
+    start_logs_cleanup, start_metrics_cleanup, start_org_activity_cleanup, start_traces_cleanup,
 };
+use crate::modules::span_metrics::start_span_metrics_derivation;
+use crate::modules::statsd::{start_statsd_udp_listener, statsd_routes};
+use crate::modules::syslog::{start_syslog_udp_listener, syslog_routes};
 use crate::modules::traces::{
     application::TraceService,
-    infrastructure::{TimescaleSpanRepository, ingest_routes as traces_ingest_routes, query_routes as traces_query_routes},
+    domain::SpanBatchLimits,
+    infrastructure::{
+        TimescaleSpanRepository, ingest_routes as traces_ingest_routes,
+        query_routes as traces_query_routes,
+    },
+};
+use crate::modules::zipkin::zipkin_routes;
+use crate::shared::{
+    PaginationConfig, QueryCache, RetryPolicy, S3ObjectStore, TraceKeepSet, retry_with_backoff,
 };
-use crate::modules::otlp::{otlp_logs_routes, otlp_metrics_routes, otlp_traces_routes};
-use crate::modules::retention::{start_metrics_cleanup, start_traces_cleanup};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -69,11 +108,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = Config::from_env()?;
     tracing::info!("Starting server on {}", config.addr());
 
-    // Create database connection pool
-    let pool = PgPoolOptions::new()
-        .max_connections(10)
-        .connect(&config.database_url)
-        .await?;
+    // Create database connection pool, waiting for the database if it's still starting
+    let db_retry = RetryPolicy::new(std::time::Duration::from_secs(
+        config.db_connect_timeout_secs,
+    ));
+    let pool = retry_with_backoff("database", db_retry, || {
+        PgPoolOptions::new()
+            .max_connections(10)
+            .connect(&config.database_url)
+    })
+    .await?;
     let pool = Arc::new(pool);
 
     tracing::info!("Connected to database");
@@ -83,39 +127,96 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let token_repo = Arc::new(PostgresRefreshTokenRepository::new(pool.clone()));
     let org_repo = Arc::new(PostgresOrganizationRepository::new(pool.clone()));
     let member_repo = Arc::new(PostgresOrganizationMemberRepository::new(pool.clone()));
-    let password_hasher = Arc::new(Argon2PasswordHasher::new());
+    let password_hasher = Arc::new(Argon2PasswordHasher::with_params(
+        config.argon2_memory_kib,
+        config.argon2_iterations,
+        config.argon2_parallelism,
+    )?);
     let jwt_config = JwtConfig::new(
         config.jwt_access_secret.clone(),
         config.jwt_refresh_secret.clone(),
-        15 * 60, // 15 minutes for access token
+        15 * 60,                                           // 15 minutes for access token
         config.refresh_token_duration_days * 24 * 60 * 60, // days to seconds
+    )
+    .with_sliding_sessions(
+        config.refresh_token_sliding,
+        config.refresh_token_max_lifetime_days * 24 * 60 * 60,
     );
     let token_service = Arc::new(JwtTokenService::new(jwt_config));
     let id_generator = Arc::new(UuidGenerator::new());
+    let pagination =
+        PaginationConfig::new(config.pagination_default_limit, config.pagination_max_limit);
+
+    // Singleton background tasks run on whichever replica holds the task's advisory lock
+    let leader_interval = std::time::Duration::from_secs(config.leader_check_interval_secs);
+    let leader_election = Arc::new(
+        LeaderElection::new(Arc::new(PgAdvisoryLock::new(pool.clone())))
+            .with_intervals(leader_interval, leader_interval),
+    );
 
     // Spawn background task for token cleanup
     {
         let cleanup_repo = token_repo.clone();
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60 * 60)); // 1 hour
-            loop {
-                interval.tick().await;
-                match cleanup_repo.delete_expired().await {
-                    Ok(count) if count > 0 => {
-                        tracing::info!(deleted_count = count, "Cleaned up expired refresh tokens");
+        tokio::spawn(leader_election.clone().run_as_leader("refresh-token-cleanup", move || {
+            let cleanup_repo = cleanup_repo.clone();
+            async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(60 * 60)); // 1 hour
+                loop {
+                    interval.tick().await;
+                    match cleanup_repo.delete_expired().await {
+                        Ok(count) if count > 0 => {
+                            tracing::info!(deleted_count = count, "Cleaned up expired refresh tokens");
+                        }
+                        Err(e) => {
+                            tracing::error!(error = %e, "Failed to clean up expired refresh tokens");
+                        }
+                        _ => {}
                     }
-                    Err(e) => {
-                        tracing::error!(error = %e, "Failed to clean up expired refresh tokens");
+                }
+            }
+        }));
+        tracing::info!("Token cleanup task started (runs every hour on the leader)");
+    }
+
+    // Email verification and password reset tokens expire within a day; clear them out on the same schedule
+    let verification_repo = Arc::new(PostgresVerificationTokenRepository::new(pool.clone()));
+    let password_reset_repo = Arc::new(PostgresPasswordResetTokenRepository::new(pool.clone()));
+    {
+        let cleanup_repo = verification_repo.clone();
+        let reset_cleanup_repo = password_reset_repo.clone();
+        tokio::spawn(leader_election.clone().run_as_leader("verification-token-cleanup", move || {
+            let cleanup_repo = cleanup_repo.clone();
+            let reset_cleanup_repo = reset_cleanup_repo.clone();
+            async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(60 * 60)); // 1 hour
+                loop {
+                    interval.tick().await;
+                    match cleanup_repo.delete_expired().await {
+                        Ok(count) if count > 0 => {
+                            tracing::info!(deleted_count = count, "Cleaned up expired email verification tokens");
+                        }
+                        Err(e) => {
+                            tracing::error!(error = %e, "Failed to clean up expired email verification tokens");
+                        }
+                        _ => {}
+                    }
+                    match reset_cleanup_repo.delete_expired().await {
+                        Ok(count) if count > 0 => {
+                            tracing::info!(deleted_count = count, "Cleaned up expired password reset tokens");
+                        }
+                        Err(e) => {
+                            tracing::error!(error = %e, "Failed to clean up expired password reset tokens");
+                        }
+                        _ => {}
                     }
-                    _ => {}
                 }
             }
-        });
-        tracing::info!("Token cleanup task started (runs every hour)");
+        }));
+        tracing::info!("Verification token cleanup task started (runs every hour on the leader)");
     }
 
     // Create auth service (with org repos for personal org creation on register)
-    let auth_service = Arc::new(AuthService::new(
+    let mut auth_service = AuthService::new(
         user_repo.clone(),
         token_repo,
         password_hasher,
@@ -123,7 +224,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         id_generator.clone(),
         org_repo.clone(),
         member_repo.clone(),
-    ));
+        Arc::new(PostgresAuthAuditRepository::new(pool.clone())),
+        Arc::new(PostgresUserTotpRepository::new(
+            pool.clone(),
+            config.totp_encryption_key,
+        )),
+        config.auth_audit_enabled,
+    )
+    .with_oauth(Arc::new(PostgresOAuthIdentityRepository::new(pool.clone())))
+    .with_personal_access_tokens(Arc::new(PostgresPersonalAccessTokenRepository::new(
+        pool.clone(),
+    )))
+    .with_pagination(pagination);
+    // Verification and reset tokens must reach the user's inbox, so both flows need a real mail transport
+    match &config.smtp {
+        Some(smtp) => {
+            let email_sender = Arc::new(SmtpEmailSender::new(smtp)?);
+            auth_service = auth_service
+                .with_email_verification(verification_repo, email_sender.clone())
+                .with_password_reset(password_reset_repo, email_sender);
+            tracing::info!("Email verification and password reset enabled (mail sent over SMTP)");
+        }
+        None => {
+            tracing::warn!("Email verification and password reset disabled: SMTP_URL is not set")
+        }
+    }
+    let auth_service = Arc::new(auth_service);
 
     // Create activity repository
     let activity_repo = Arc::new(PostgresOrgActivityRepository::new(pool.clone()));
@@ -139,6 +265,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         token_service.clone(),
         id_generator.clone(),
         activity_repo.clone(),
+        invite_repo.clone(),
+        pagination,
     ));
 
     // Create invite service
@@ -147,7 +275,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         member_repo.clone(),
         user_repo,
         invite_repo.clone(),
-        activity_repo,
+        activity_repo.clone(),
         id_generator.clone(),
     ));
 
@@ -155,26 +283,91 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let project_repo = Arc::new(PostgresProjectRepository::new(pool.clone()));
     let api_key_repo = Arc::new(PostgresApiKeyRepository::new(pool.clone()));
 
+    // Create alert repositories
+    let alert_rule_repo = Arc::new(PostgresAlertRuleRepository::new(pool.clone()));
+    let alert_channel_repo = Arc::new(PostgresAlertChannelRepository::new(pool.clone()));
+
+    // Org alert defaults are applied to each project as it's created
+    let org_alert_defaults_service = Arc::new(OrgAlertDefaultsService::new(
+        Arc::new(PostgresOrgAlertDefaultsRepository::new(pool.clone())),
+        alert_channel_repo.clone(),
+        alert_rule_repo.clone(),
+        member_repo.clone(),
+        id_generator.clone(),
+    ));
+
     // Create project service
-    let project_service = Arc::new(ProjectService::new(
+    let mut project_service = ProjectService::new(
         project_repo.clone(),
         api_key_repo.clone(),
         org_repo.clone(),
         member_repo.clone(),
         id_generator.clone(),
+        pagination,
+        config.deployment_region.clone(),
+        config.trusted_proxies.clone(),
+    )
+    .with_created_hook(org_alert_defaults_service.clone())
+    .with_api_key_cache(config.api_key_cache);
+    if config.email_verification_required {
+        tracing::info!("Email verification required before creating API keys");
+        project_service = project_service.with_email_verification(auth_service.clone());
+    }
+    let project_service = Arc::new(project_service);
+
+    // Service display metadata, joined onto log and trace query results
+    let service_metadata_repo = Arc::new(PostgresServiceMetadataRepository::new(pool.clone()));
+    let service_metadata_service = Arc::new(ServiceMetadataService::new(
+        service_metadata_repo.clone(),
+        project_repo.clone(),
+        member_repo.clone(),
+    ));
+
+    // Tokens for sharing a project's query results publicly, read-only
+    let public_token_service = Arc::new(PublicReadTokenService::new(
+        Arc::new(PostgresPublicReadTokenRepository::new(pool.clone())),
+        project_repo.clone(),
+        member_repo.clone(),
     ));
 
     // Create logging infrastructure
     let log_repo = Arc::new(TimescaleLogRepository::new(pool.clone()));
     let log_broadcaster = Arc::new(LogBroadcaster::new(1000)); // Buffer up to 1000 messages per channel
 
+    // Critical alert rules matching ingested logs are evaluated right away
+    let ingest_rule_trigger = Arc::new(IngestRuleTrigger::new());
+
+    let log_dedup_window = chrono::Duration::seconds(config.log_dedup_window_secs);
+    let log_write_queue = LogWriteQueue::start(
+        log_repo.clone(),
+        config.log_write_queue_capacity,
+        log_dedup_window,
+        Some(ingest_rule_trigger.clone()),
+    );
+
+    // GeoIP blocks are loaded once; projects opt in with a GeoIP field
+    let geoip_csv = match &config.geoip_database_path {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => BUNDLED_GEOIP_DATABASE.to_string(),
+    };
+    let geoip = Arc::new(GeoIpDatabase::parse(&geoip_csv)?);
+
     // Create log service
-    let log_service = Arc::new(LogService::new(
-        log_repo,
-        project_repo.clone(),
-        member_repo.clone(),
-        id_generator.clone(),
-    ));
+    let log_service = Arc::new(
+        LogService::new(
+            log_repo,
+            project_repo.clone(),
+            member_repo.clone(),
+            id_generator.clone(),
+            pagination,
+        )
+        .with_dedup_window(log_dedup_window)
+        .with_id_format_policy(config.trace_id_policy)
+        .with_service_metadata(service_metadata_repo.clone())
+        .with_write_queue(log_write_queue)
+        .with_geoip(geoip)
+        .with_ingest_observer(ingest_rule_trigger.clone()),
+    );
 
     // Create filter preset repository and service
     let filter_preset_repo = Arc::new(PostgresFilterPresetRepository::new(pool.clone()));
@@ -185,74 +378,152 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         id_generator.clone(),
     ));
 
-    // Create alert repositories
-    let alert_rule_repo = Arc::new(PostgresAlertRuleRepository::new(pool.clone()));
-    let alert_channel_repo = Arc::new(PostgresAlertChannelRepository::new(pool.clone()));
     let alert_repo = Arc::new(PostgresAlertRepository::new(pool.clone()));
+    let notification_preference_repo =
+        Arc::new(PostgresNotificationPreferenceRepository::new(pool.clone()));
 
     // Create alert services
-    let alert_channel_service = Arc::new(AlertChannelService::new(
-        alert_channel_repo.clone(),
-        project_repo.clone(),
-        member_repo.clone(),
-        id_generator.clone(),
-    ));
+    let alert_channel_service = Arc::new(
+        AlertChannelService::new(
+            alert_channel_repo.clone(),
+            project_repo.clone(),
+            member_repo.clone(),
+            id_generator.clone(),
+        )
+        .with_channel_failure_limit(config.alert_channel_max_failures),
+    );
 
-    let alert_rule_service = Arc::new(AlertRuleService::new(
-        alert_rule_repo.clone(),
-        alert_channel_repo.clone(),
-        project_repo.clone(),
-        member_repo.clone(),
-        id_generator.clone(),
-    ));
+    let alert_rule_service = Arc::new(
+        AlertRuleService::new(
+            alert_rule_repo.clone(),
+            alert_channel_repo.clone(),
+            project_repo.clone(),
+            member_repo.clone(),
+            id_generator.clone(),
+        )
+        .with_max_rules_per_project(config.alert_rules_max_per_project),
+    );
 
     let alert_history_service = Arc::new(AlertHistoryService::new(
         alert_repo.clone(),
         alert_rule_repo.clone(),
         project_repo.clone(),
         member_repo.clone(),
+        pagination,
+    ));
+
+    let notification_preference_service = Arc::new(NotificationPreferenceService::new(
+        notification_preference_repo.clone(),
     ));
 
     // Create webhook notifier
-    let webhook_notifier = Arc::new(WebhookNotifier::new());
+    let webhook_notifier = Arc::new(WebhookNotifier::new(notifier_http_client(
+        std::time::Duration::from_millis(config.notifier_connect_timeout_ms),
+        std::time::Duration::from_millis(config.notifier_request_timeout_ms),
+    )));
+
+    // Delivers triggered alerts to members' personal channels and sends their digests
+    let user_notification_dispatcher = Arc::new(UserNotificationDispatcher::new(
+        notification_preference_repo,
+        member_repo.clone(),
+        webhook_notifier.clone(),
+        60, // Check for due digests every 60 seconds
+    ));
+
+    // One result cache shared by the metric and trace aggregation queries
+    let query_cache = config.query_cache.map(|c| Arc::new(QueryCache::new(c)));
+
+    // Trace ids from metric exemplars, kept by the trace sampler
+    let trace_keep_set = config
+        .exemplar_trace_keep
+        .map(|c| Arc::new(TraceKeepSet::new(c)));
 
     // Create metrics infrastructure
     let metrics_repo = Arc::new(TimescaleMetricsRepository::new(pool.clone()));
-    let metrics_service = Arc::new(MetricsService::new(
+    let mut metrics_service = MetricsService::new(
         metrics_repo.clone(),
         project_repo.clone(),
         member_repo.clone(),
         id_generator.clone(),
-    ));
+        chrono::Duration::seconds(config.metrics_out_of_order_tolerance_secs),
+    );
+    if let Some(cache) = &query_cache {
+        metrics_service = metrics_service.with_query_cache(cache.clone());
+    }
+    if let Some(keep_set) = &trace_keep_set {
+        metrics_service = metrics_service.with_trace_keep_set(keep_set.clone());
+    }
+    let metrics_service = Arc::new(metrics_service);
 
     // Create traces infrastructure
     let spans_repo = Arc::new(TimescaleSpanRepository::new((*pool).clone()));
-    let trace_service = Arc::new(TraceService::new(
+    let mut trace_service = TraceService::new(
         spans_repo.clone(),
         project_repo.clone(),
         member_repo.clone(),
         id_generator.clone(),
-    ));
+        pagination,
+    )
+    .with_id_format_policy(config.trace_id_policy)
+    .with_batch_limits(SpanBatchLimits {
+        max_spans: config.trace_ingest_max_spans,
+        max_bytes: config.trace_ingest_max_bytes,
+    })
+    .with_service_metadata(service_metadata_repo);
+    if let Some(cache) = query_cache {
+        trace_service = trace_service.with_query_cache(cache);
+    }
+    if let Some(keep_set) = trace_keep_set {
+        trace_service = trace_service.with_trace_keep_set(keep_set);
+    }
+    let trace_service = Arc::new(trace_service);
 
     // Create and start the rule evaluator background task
+    let evaluator_health = Arc::new(EvaluatorHealth::new());
     {
-        let evaluator = Arc::new(RuleEvaluator::new(
-            alert_rule_repo,
-            alert_repo,
-            alert_channel_repo,
-            log_service.log_repo(),
-            project_repo.clone(),
-            id_generator,
-            webhook_notifier,
-            60, // Evaluate every 60 seconds
-        ));
-        tokio::spawn(async move {
-            evaluator.start().await;
-        });
-        tracing::info!("Alert rule evaluator started (runs every 60 seconds)");
+        let evaluator = Arc::new(
+            RuleEvaluator::new(
+                alert_rule_repo,
+                alert_repo.clone(),
+                alert_channel_repo,
+                log_service.log_repo(),
+                project_repo.clone(),
+                id_generator.clone(),
+                webhook_notifier,
+                60, // Evaluate every 60 seconds
+            )
+            .with_aligned_windows(config.alert_aligned_windows)
+            .with_channel_failure_limit(config.alert_channel_max_failures)
+            .with_health(evaluator_health.clone())
+            .with_user_notifications(user_notification_dispatcher.clone())
+            .with_spans(spans_repo.clone())
+            .with_ingest_evaluation(
+                ingest_rule_trigger,
+                std::time::Duration::from_secs(config.alert_ingest_min_interval_secs),
+            ),
+        );
+        // Logs are ingested on every replica, so ingest evaluation runs on each
+        tokio::spawn(evaluator.clone().start_ingest_evaluation());
+        tokio::spawn(
+            leader_election
+                .clone()
+                .run_as_leader("alert-evaluator", move || evaluator.clone().start()),
+        );
+        tracing::info!("Alert rule evaluator started (runs every 60 seconds on the leader)");
+    }
+
+    // Start the notification digest background task
+    {
+        let dispatcher = user_notification_dispatcher;
+        tokio::spawn(
+            leader_election
+                .clone()
+                .run_as_leader("notification-digest", move || dispatcher.clone().start()),
+        );
     }
 
-    // Spawn log listener background task (listens to pg_notify for real-time streaming)
+    // Spawn log listener background task (listens to pg_notify for real-time streaming).
+    // Runs on every replica: each one streams to its own connected clients.
     {
         let listener_pool = pool.clone();
         let listener_broadcaster = log_broadcaster.clone();
@@ -287,84 +558,489 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tracing::info!("Rate limiter cleanup task started (runs every 5 minutes, max 10k entries)");
     }
 
+    // Retention deletes run in bounded batches
+    let delete_batching = DeleteBatching {
+        batch_size: config.retention_delete_batch_size,
+        pause: std::time::Duration::from_millis(config.retention_delete_pause_ms),
+    };
+
+    // Spawn logs retention cleanup task
+    {
+        let cleanup_log_repo = log_service.log_repo();
+        let cleanup_project_repo = project_repo.clone();
+        tokio::spawn(
+            leader_election
+                .clone()
+                .run_as_leader("logs-retention", move || {
+                    start_logs_cleanup(
+                        cleanup_log_repo.clone(),
+                        cleanup_project_repo.clone(),
+                        delete_batching,
+                        60 * 60, // Run every hour
+                    )
+                }),
+        );
+        tracing::info!("Logs retention cleanup task started (runs every hour on the leader)");
+    }
+
+    // Spawn ingest dedup key prune task
+    {
+        let prune_log_repo = log_service.log_repo();
+        tokio::spawn(
+            leader_election
+                .clone()
+                .run_as_leader("dedup-prune", move || {
+                    start_dedup_key_prune(
+                        prune_log_repo.clone(),
+                        delete_batching,
+                        10 * 60, // Run every 10 minutes
+                    )
+                }),
+        );
+        tracing::info!("Ingest dedup key prune task started (runs every 10 minutes on the leader)");
+    }
+
     // Spawn metrics retention cleanup task
     {
         let cleanup_metrics_repo = metrics_repo.clone();
         let cleanup_project_repo = project_repo.clone();
-        tokio::spawn(start_metrics_cleanup(
-            cleanup_metrics_repo,
-            cleanup_project_repo,
-            60 * 60, // Run every hour
-        ));
-        tracing::info!("Metrics retention cleanup task started (runs every hour)");
+        tokio::spawn(
+            leader_election
+                .clone()
+                .run_as_leader("metrics-retention", move || {
+                    start_metrics_cleanup(
+                        cleanup_metrics_repo.clone(),
+                        cleanup_project_repo.clone(),
+                        delete_batching,
+                        60 * 60, // Run every hour
+                    )
+                }),
+        );
+        tracing::info!("Metrics retention cleanup task started (runs every hour on the leader)");
     }
 
     // Spawn traces retention cleanup task
     {
         let cleanup_spans_repo = spans_repo.clone();
         let cleanup_project_repo = project_repo.clone();
-        tokio::spawn(start_traces_cleanup(
-            cleanup_spans_repo,
-            cleanup_project_repo,
-            60 * 60, // Run every hour
-        ));
-        tracing::info!("Traces retention cleanup task started (runs every hour)");
+        tokio::spawn(
+            leader_election
+                .clone()
+                .run_as_leader("traces-retention", move || {
+                    start_traces_cleanup(
+                        cleanup_spans_repo.clone(),
+                        cleanup_project_repo.clone(),
+                        delete_batching,
+                        60 * 60, // Run every hour
+                    )
+                }),
+        );
+        tracing::info!("Traces retention cleanup task started (runs every hour on the leader)");
+    }
+
+    // Spawn metrics Parquet export task
+    if let Some(export) = config.metrics_export.clone() {
+        let export_metrics_repo = metrics_repo.clone();
+        let export_project_repo = project_repo.clone();
+        let store = Arc::new(S3ObjectStore::new(reqwest::Client::new(), export.store));
+        let settings = export.settings;
+        tokio::spawn(
+            leader_election
+                .clone()
+                .run_as_leader("metrics-export", move || {
+                    start_metrics_export(
+                        export_metrics_repo.clone(),
+                        export_project_repo.clone(),
+                        store.clone(),
+                        settings.clone(),
+                        60 * 60, // Run every hour
+                    )
+                }),
+        );
+        tracing::info!("Metrics export task started (runs every hour on the leader)");
+    }
+
+    // Spawn organization activity retention cleanup task
+    {
+        let cleanup_activity_repo = activity_repo.clone();
+        let cleanup_org_repo = org_repo.clone();
+        let retention = ActivityRetention {
+            default_days: config.org_activity_retention_days,
+            security_days: config.org_activity_security_retention_days,
+        };
+        tokio::spawn(
+            leader_election
+                .clone()
+                .run_as_leader("org-activity-retention", move || {
+                    start_org_activity_cleanup(
+                        cleanup_activity_repo.clone(),
+                        cleanup_org_repo.clone(),
+                        retention,
+                        60 * 60, // Run every hour
+                    )
+                }),
+        );
+        tracing::info!(
+            "Organization activity retention cleanup task started (runs every hour on the leader)"
+        );
+    }
+
+    // Spawn alert history retention cleanup task
+    if config.alert_history_retention_days > 0 {
+        let cleanup_alert_repo = alert_repo.clone();
+        let retention_days = config.alert_history_retention_days;
+        tokio::spawn(
+            leader_election
+                .clone()
+                .run_as_leader("alert-history-retention", move || {
+                    start_alert_history_cleanup(
+                        cleanup_alert_repo.clone(),
+                        retention_days,
+                        delete_batching,
+                        60 * 60, // Run every hour
+                    )
+                }),
+        );
+        tracing::info!(
+            retention_days,
+            "Alert history retention cleanup task started (runs every hour on the leader)"
+        );
+    }
+
+    // Spawn span metrics derivation task (RED metrics for opted-in projects)
+    {
+        let derivation_spans_repo = spans_repo.clone();
+        let derivation_metrics_repo = metrics_repo.clone();
+        let derivation_project_repo = project_repo.clone();
+        let derivation_id_generator = id_generator.clone();
+        tokio::spawn(
+            leader_election
+                .clone()
+                .run_as_leader("span-metrics", move || {
+                    start_span_metrics_derivation(
+                        derivation_spans_repo.clone(),
+                        derivation_metrics_repo.clone(),
+                        derivation_project_repo.clone(),
+                        derivation_id_generator.clone(),
+                        60, // Aggregate every minute
+                    )
+                }),
+        );
+        tracing::info!("Span metrics derivation task started (runs every minute on the leader)");
     }
 
     // Spawn invite expiration cleanup task
     {
         let cleanup_invite_repo = invite_repo.clone();
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60 * 60)); // 1 hour
-            loop {
-                interval.tick().await;
-                match cleanup_invite_repo.mark_expired().await {
-                    Ok(count) if count > 0 => {
-                        tracing::info!(expired_count = count, "Marked expired organization invites");
-                    }
-                    Err(e) => {
-                        tracing::error!(error = %e, "Failed to mark expired invites");
+        tokio::spawn(
+            leader_election
+                .clone()
+                .run_as_leader("invite-expiration", move || {
+                    let cleanup_invite_repo = cleanup_invite_repo.clone();
+                    async move {
+                        let mut interval =
+                            tokio::time::interval(std::time::Duration::from_secs(60 * 60)); // 1 hour
+                        loop {
+                            interval.tick().await;
+                            match cleanup_invite_repo.mark_expired().await {
+                                Ok(count) if count > 0 => {
+                                    tracing::info!(
+                                        expired_count = count,
+                                        "Marked expired organization invites"
+                                    );
+                                }
+                                Err(e) => {
+                                    tracing::error!(error = %e, "Failed to mark expired invites");
+                                }
+                                _ => {}
+                            }
+                        }
                     }
-                    _ => {}
-                }
-            }
-        });
-        tracing::info!("Invite expiration cleanup task started (runs every hour)");
+                }),
+        );
+        tracing::info!("Invite expiration cleanup task started (runs every hour on the leader)");
     }
 
-    // Create router
-    let app = Router::new()
-        .nest("/api/auth", auth_routes(auth_service, token_service.clone(), rate_limiter))
-        .nest("/api", org_routes(org_service, token_service.clone()))
+    // Zipkin ingest is optional (Zipkin v2 JSON at /api/v2/spans)
+    let zipkin_router = if config.zipkin_ingest_enabled {
+        tracing::info!("Zipkin span ingestion enabled at /api/v2/spans");
+        Router::new().nest(
+            "/api/v2",
+            zipkin_routes(trace_service.clone(), project_service.clone()),
+        )
+    } else {
+        Router::new()
+    };
+
+    // Prometheus remote-write and text exposition ingest are optional
+    let prometheus_router = if config.prometheus_remote_write_enabled {
+        tracing::info!(
+            "Prometheus ingest enabled at /api/v1/ingest/prometheus/write and /api/v1/ingest/prometheus/exposition"
+        );
+        Router::new().nest(
+            "/api/v1/ingest",
+            prometheus_routes(
+                metrics_service.clone(),
+                project_service.clone(),
+                config.prometheus_max_decompressed_bytes,
+            ),
+        )
+    } else {
+        Router::new()
+    };
+
+    // StatsD ingest is optional (HTTP, plus UDP when configured)
+    let statsd_router = if config.statsd_ingest_enabled {
+        tracing::info!("StatsD ingestion enabled at /api/v1/ingest/statsd");
+        Router::new().nest(
+            "/api/v1/ingest",
+            statsd_routes(metrics_service.clone(), project_service.clone()),
+        )
+    } else {
+        Router::new()
+    };
+    if let (Some(port), Some(project_id)) =
+        (config.statsd_udp_port, config.statsd_udp_project_id.clone())
+    {
+        tokio::spawn(start_statsd_udp_listener(
+            metrics_service.clone(),
+            config.host.clone(),
+            port,
+            project_id,
+            config.statsd_flush_interval_secs,
+        ));
+        tracing::info!(port, "StatsD UDP listener started");
+    }
+
+    // Syslog ingest is optional (RFC 5424 over HTTP, plus UDP when configured)
+    let syslog_router = if config.syslog_ingest_enabled {
+        tracing::info!("Syslog ingestion enabled at /api/v1/ingest/syslog");
+        Router::new().nest(
+            "/api/v1/ingest",
+            syslog_routes(log_service.clone(), project_service.clone()),
+        )
+    } else {
+        Router::new()
+    };
+    if let (Some(port), Some(project_id)) =
+        (config.syslog_udp_port, config.syslog_udp_project_id.clone())
+    {
+        tokio::spawn(start_syslog_udp_listener(
+            log_service.clone(),
+            config.host.clone(),
+            port,
+            project_id,
+        ));
+        tracing::info!(port, "Syslog UDP listener started");
+    }
+
+    // GELF ingest is optional (HTTP, plus UDP when configured)
+    let gelf_router = if config.gelf_ingest_enabled {
+        tracing::info!("GELF ingestion enabled at /api/v1/ingest/gelf");
+        Router::new().nest(
+            "/api/v1/ingest",
+            gelf_routes(log_service.clone(), project_service.clone()),
+        )
+    } else {
+        Router::new()
+    };
+    if let (Some(port), Some(project_id)) =
+        (config.gelf_udp_port, config.gelf_udp_project_id.clone())
+    {
+        tokio::spawn(start_gelf_udp_listener(
+            log_service.clone(),
+            config.host.clone(),
+            port,
+            project_id,
+        ));
+        tracing::info!(port, "GELF UDP listener started");
+    }
+
+    // CloudWatch Logs ingest is optional (subscription records, direct or via Firehose)
+    let cloudwatch_router = if config.cloudwatch_ingest_enabled {
+        tracing::info!("CloudWatch Logs ingestion enabled at /api/v1/ingest/cloudwatch");
+        Router::new().nest(
+            "/api/v1/ingest",
+            cloudwatch_routes(log_service.clone(), project_service.clone()),
+        )
+    } else {
+        Router::new()
+    };
+
+    // Loki push ingest is optional, mounted at Loki's own path so clients need no path config
+    let loki_router = if config.loki_ingest_enabled {
+        tracing::info!("Loki push ingestion enabled at /loki/api/v1/push");
+        Router::new().nest(
+            "/loki/api/v1",
+            loki_routes(log_service.clone(), project_service.clone()),
+        )
+    } else {
+        Router::new()
+    };
+
+    // OAuth sign-in is only mounted when a provider is configured
+    let oauth_router = match config.oauth.clone() {
+        Some(oauth) => {
+            tracing::info!("OAuth sign-in enabled at /api/auth/oauth");
+            let oauth_client = Arc::new(OAuthClient::new(
+                reqwest::Client::new(),
+                oauth,
+                &config.jwt_access_secret,
+            ));
+            Router::new().nest(
+                "/api/auth",
+                oauth_routes(auth_service.clone(), oauth_client, rate_limiter.clone()),
+            )
+        }
+        None => Router::new(),
+    };
+
+    // Operator endpoints are only mounted when an admin token is configured
+    let admin_router = match config.admin_api_token.clone() {
+        Some(token) => {
+            tracing::info!("Admin endpoints enabled at /api/admin");
+            Router::new().nest("/api/admin", admin_routes(evaluator_health, token))
+        }
+        None => Router::new(),
+    };
+
+    // Personal access tokens are only accepted by the routes this is layered on
+    let pat_auth = Extension(PersonalAccessTokenAuth::new(auth_service.clone()));
+
+    // Routers are grouped by the body size limit they get
+    let auth_router = Router::new()
+        .nest(
+            "/api/auth",
+            auth_routes(auth_service, token_service.clone(), rate_limiter),
+        )
+        .merge(oauth_router);
+
+    let ingest_router = Router::new()
+        .nest(
+            "/api/v1/ingest",
+            ingest_routes(log_service.clone(), project_service.clone()),
+        )
+        .nest(
+            "/api/v1/ingest",
+            metrics_ingest_routes(metrics_service.clone(), project_service.clone()),
+        )
+        .nest(
+            "/api/v1/ingest",
+            traces_ingest_routes(trace_service.clone(), project_service.clone()),
+        )
+        // OTLP routes (under /v1 for compatibility)
+        .nest(
+            "/v1",
+            otlp_logs_routes(log_service.clone(), project_service.clone()),
+        )
+        .nest(
+            "/v1",
+            otlp_metrics_routes(metrics_service.clone(), project_service.clone()),
+        )
+        .nest(
+            "/v1",
+            otlp_traces_routes(trace_service.clone(), project_service.clone()),
+        )
+        .merge(zipkin_router)
+        .merge(prometheus_router)
+        .merge(statsd_router)
+        .merge(syslog_router)
+        .merge(gelf_router)
+        .merge(cloudwatch_router)
+        .merge(loki_router);
+
+    let query_router = Router::new()
+        .nest(
+            "/api",
+            org_routes(org_service, token_service.clone()).layer(pat_auth.clone()),
+        )
         // Invite routes
-        .nest("/api", org_invite_routes(invite_service.clone(), token_service.clone()))
-        .nest("/api", user_invite_routes(invite_service, token_service.clone()))
-        .nest("/api", project_routes(project_service.clone(), token_service.clone()))
+        .nest(
+            "/api",
+            org_invite_routes(invite_service.clone(), token_service.clone()),
+        )
+        .nest(
+            "/api",
+            user_invite_routes(invite_service, token_service.clone()),
+        )
+        .nest(
+            "/api",
+            project_routes(project_service.clone(), token_service.clone()).layer(pat_auth),
+        )
+        .nest(
+            "/api",
+            service_metadata_routes(service_metadata_service, token_service.clone()),
+        )
         // Logging routes
-        .nest("/api/v1/ingest", ingest_routes(log_service.clone(), project_service.clone()))
-        .nest("/api", log_query_routes(log_service.clone(), token_service.clone()))
-        .nest("/api", sse_routes(
-            log_broadcaster,
-            project_repo.clone(),
-            member_repo.clone(),
-            token_service.clone(),
-        ))
+        .nest(
+            "/api",
+            log_query_routes(log_service.clone(), token_service.clone()),
+        )
+        .nest(
+            "/api",
+            public_token_routes(public_token_service.clone(), token_service.clone()),
+        )
+        .nest(
+            "/api/public",
+            public_log_routes(log_service.clone(), public_token_service),
+        )
+        .nest(
+            "/api",
+            sse_routes(
+                log_broadcaster,
+                project_repo.clone(),
+                member_repo.clone(),
+                token_service.clone(),
+            ),
+        )
         // Filter preset routes
-        .nest("/api", filter_preset_routes(filter_preset_service, token_service.clone()))
+        .nest(
+            "/api",
+            filter_preset_routes(filter_preset_service, token_service.clone()),
+        )
         // Alert routes
-        .nest("/api", channel_routes(alert_channel_service, token_service.clone()))
-        .nest("/api", rule_routes(alert_rule_service, token_service.clone()))
-        .nest("/api", alert_routes(alert_history_service, token_service.clone()))
+        .nest(
+            "/api",
+            channel_routes(alert_channel_service, token_service.clone()),
+        )
+        .nest(
+            "/api",
+            rule_routes(alert_rule_service, token_service.clone()),
+        )
+        .nest(
+            "/api",
+            alert_routes(alert_history_service, token_service.clone()),
+        )
+        .nest(
+            "/api",
+            org_alert_defaults_routes(org_alert_defaults_service, token_service.clone()),
+        )
+        .nest(
+            "/api",
+            notification_preference_routes(notification_preference_service, token_service.clone()),
+        )
         // Metrics routes
-        .nest("/api/v1/ingest", metrics_ingest_routes(metrics_service.clone(), project_service.clone()))
-        .nest("/api/projects/{project_id}/observability/metrics", metrics_query_routes(metrics_service.clone(), token_service.clone()))
+        .nest(
+            "/api/projects/{project_id}/observability/metrics",
+            metrics_query_routes(metrics_service, token_service.clone()),
+        )
         // Traces routes
-        .nest("/api/v1/ingest", traces_ingest_routes(trace_service.clone(), project_service.clone()))
-        .nest("/api/projects/{project_id}/observability/traces", traces_query_routes(trace_service.clone(), token_service.clone()))
-        // OTLP routes (under /v1 for compatibility)
-        .nest("/v1", otlp_logs_routes(log_service, project_service.clone()))
-        .nest("/v1", otlp_metrics_routes(metrics_service, project_service.clone()))
-        .nest("/v1", otlp_traces_routes(trace_service, project_service))
+        .nest(
+            "/api/projects/{project_id}/observability/traces",
+            traces_query_routes(trace_service, token_service.clone()),
+        )
+        .merge(admin_router);
+
+    // Create router
+    let app = config
+        .body_limits
+        .apply(auth_router, ingest_router, query_router)
+        .layer(Extension(config.ingest_json_limits))
+        .layer(Extension(OrgIpAllowlist::new(
+            org_repo,
+            project_repo,
+            config.trusted_proxies.clone(),
+        )))
         .layer(
             CorsLayer::new()
                 .allow_origin(Any)
@@ -377,7 +1053,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let listener = tokio::net::TcpListener::bind(config.addr()).await?;
     tracing::info!("Server listening on {}", config.addr());
 
-    axum::serve(listener, app).await?;
+    // Peer addresses are needed for rate limiting and ingest IP allowlists
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
@@ -0,0 +1,111 @@
+//! Operator endpoints
+//!
+//! Routes for running the deployment rather than using it: alert evaluator
+//! health as JSON and a Prometheus exporter for the backend's own metrics.
+//! They are authenticated with a static token from the configuration and are
+//! only mounted when one is set.
+
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::State,
+    http::{Request, StatusCode, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::modules::alerts::{EvaluatorHealth, RuleEvaluationStats};
+
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub code: String,
+}
+
+/// One rule's evaluation health
+#[derive(Debug, Serialize)]
+pub struct RuleHealthResponse {
+    #[serde(flatten)]
+    pub stats: RuleEvaluationStats,
+    pub stale: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EvaluatorHealthResponse {
+    pub rules: Vec<RuleHealthResponse>,
+}
+
+/// Create admin routes (static token auth)
+pub fn admin_routes(health: Arc<EvaluatorHealth>, token: String) -> Router {
+    let token = Arc::new(token);
+    Router::new()
+        .route("/evaluator/rules", get(evaluator_health))
+        .route("/metrics", get(self_metrics))
+        .layer(middleware::from_fn(move |req, next| {
+            let token = token.clone();
+            async move { admin_token_middleware(token, req, next).await }
+        }))
+        .with_state(health)
+}
+
+/// GET /api/admin/evaluator/rules
+async fn evaluator_health(
+    State(health): State<Arc<EvaluatorHealth>>,
+) -> Json<EvaluatorHealthResponse> {
+    let now = Utc::now();
+    let rules = health
+        .snapshot()
+        .into_iter()
+        .map(|stats| RuleHealthResponse {
+            stale: stats.is_stale(now),
+            stats,
+        })
+        .collect();
+    Json(EvaluatorHealthResponse { rules })
+}
+
+/// GET /api/admin/metrics - Prometheus text format
+async fn self_metrics(State(health): State<Arc<EvaluatorHealth>>) -> Response {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        health.render_prometheus(Utc::now()),
+    )
+        .into_response()
+}
+
+/// Require `Authorization: Bearer <token>` matching the configured admin token
+async fn admin_token_middleware(
+    token: Arc<String>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    match provided {
+        Some(provided) if constant_time_eq(provided.as_bytes(), token.as_bytes()) => {
+            next.run(request).await
+        }
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Missing or invalid admin token".to_string(),
+                code: "UNAUTHORIZED".to_string(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Compare without returning early, so timing doesn't reveal the token
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::modules::alerts::domain::{AlertSchedule, MuteWindow};
+
 // ==================== Alert Rule DTOs ====================
 
 #[derive(Debug, Clone, Deserialize)]
@@ -15,8 +17,24 @@ pub struct CreateAlertRuleRequest {
     pub threshold_operator: String,
     #[serde(default = "default_time_window")]
     pub time_window_seconds: i32,
+    /// Seconds after a resolve during which the rule won't fire again
+    #[serde(default)]
+    pub cooldown_seconds: i32,
+    /// info, warning (default) or critical
+    #[serde(default)]
+    pub severity: Option<String>,
     #[serde(default)]
     pub channel_ids: Vec<String>,
+    /// Rule whose firing alert suppresses this rule's notifications
+    #[serde(default)]
+    pub parent_rule_id: Option<String>,
+    /// Also evaluate as soon as matching logs are ingested; critical rules only
+    #[serde(default)]
+    pub evaluate_on_ingest: bool,
+    /// `timezone` and `windows` of `days`, `start` and `end` during which the
+    /// rule may notify; unset notifies at any time
+    #[serde(default)]
+    pub schedule: Option<Value>,
 }
 
 fn default_time_window() -> i32 {
@@ -38,9 +56,21 @@ pub struct UpdateAlertRuleRequest {
     #[serde(default)]
     pub time_window_seconds: Option<i32>,
     #[serde(default)]
+    pub cooldown_seconds: Option<i32>,
+    #[serde(default)]
+    pub severity: Option<String>,
+    #[serde(default)]
     pub is_enabled: Option<bool>,
     #[serde(default)]
     pub channel_ids: Option<Vec<String>>,
+    /// An empty string removes the parent
+    #[serde(default)]
+    pub parent_rule_id: Option<String>,
+    #[serde(default)]
+    pub evaluate_on_ingest: Option<bool>,
+    /// Null removes the schedule
+    #[serde(default, deserialize_with = "double_option")]
+    pub schedule: Option<Option<Value>>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -55,6 +85,8 @@ pub struct AlertRuleResponse {
     pub threshold_value: f64,
     pub threshold_operator: String,
     pub time_window_seconds: i32,
+    pub cooldown_seconds: i32,
+    pub severity: String,
     pub is_enabled: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_evaluated_at: Option<DateTime<Utc>>,
@@ -63,6 +95,86 @@ pub struct AlertRuleResponse {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub channel_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_rule_id: Option<String>,
+    pub evaluate_on_ingest: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<AlertSchedule>,
+}
+
+// ==================== Alert Rule Import/Export ====================
+
+/// Current version of the alert rules document format
+pub const ALERT_RULES_DOCUMENT_VERSION: u32 = 1;
+
+/// Portable alert rule configuration for a project.
+/// Rules are matched by name and channels are referenced by name, so a
+/// document exported from one project can be imported into another.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertRulesDocument {
+    pub version: u32,
+    pub rules: Vec<AlertRuleDefinition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertRuleDefinition {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub rule_type: String,
+    pub config: Value,
+    pub threshold_value: f64,
+    pub threshold_operator: String,
+    #[serde(default = "default_time_window")]
+    pub time_window_seconds: i32,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub cooldown_seconds: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub is_enabled: bool,
+    /// Channel names within the project
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn is_zero(value: &i32) -> bool {
+    *value == 0
+}
+
+/// How an import is applied
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    #[default]
+    Apply,
+    /// Report what would change without writing anything
+    DryRun,
+}
+
+/// How many rules a project has against its cap
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertRuleUsageResponse {
+    pub count: i64,
+    /// None when rules per project are unlimited
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportRulesResponse {
+    pub dry_run: bool,
+    /// Names of rules created (or that would be created)
+    pub created: Vec<String>,
+    /// Names of rules updated (or that would be updated)
+    pub updated: Vec<String>,
+    /// Names of rules that already match the document
+    pub unchanged: Vec<String>,
 }
 
 // ==================== Alert Channel DTOs ====================
@@ -87,15 +199,35 @@ pub struct UpdateAlertChannelRequest {
 #[derive(Debug, Clone, Serialize)]
 pub struct AlertChannelResponse {
     pub id: String,
-    pub project_id: String,
+    /// Set for project channels
+    pub project_id: Option<String>,
+    /// Set for channels shared across an organization
+    pub organization_id: Option<String>,
     pub name: String,
     pub channel_type: String,
     pub config: Value,
     pub is_enabled: bool,
+    /// "healthy", "failing" or "disabled"
+    pub health: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelHealthResponse {
+    pub channel_id: String,
+    /// "healthy", "failing" or "disabled"
+    pub status: String,
+    pub is_enabled: bool,
+    /// Deliveries failed in a row since the last success
+    pub consecutive_failures: u32,
+    pub last_delivery_error: Option<String>,
+    /// Set when the channel was disabled for failing too often
+    pub auto_disabled_at: Option<DateTime<Utc>>,
+    /// Failures in a row that disable the channel; None when unlimited
+    pub failure_limit: Option<u32>,
+}
+
 // ==================== Alert DTOs ====================
 
 #[derive(Debug, Clone, Serialize)]
@@ -120,6 +252,69 @@ pub struct AlertListResponse {
     pub alerts: Vec<AlertResponse>,
     pub total: i64,
     pub has_more: bool,
+    /// Effective page size after applying the default and maximum; sent as
+    /// the `x-pagination-limit` header
+    #[serde(skip)]
+    pub limit: i64,
+}
+
+// ==================== Org Alert Defaults DTOs ====================
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateOrgAlertDefaultsRequest {
+    /// Create the default rules in each new project of the organization
+    pub enabled: bool,
+    /// Org-shared channel the default rules notify
+    pub channel_id: String,
+    /// Rules in the export format, without `channels`
+    #[serde(default)]
+    pub rules: Vec<AlertRuleDefinition>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrgAlertDefaultsResponse {
+    pub org_id: String,
+    pub enabled: bool,
+    pub channel_id: Option<String>,
+    pub rules: Vec<AlertRuleDefinition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+// ==================== Notification Preference DTOs ====================
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateNotificationPreferencesRequest {
+    /// Personal webhook config (`url`, optional `headers`); null turns personal notifications off
+    #[serde(default, deserialize_with = "double_option")]
+    pub channel_config: Option<Option<Value>>,
+    #[serde(default)]
+    pub severities: Option<Vec<String>>,
+    #[serde(default)]
+    pub mute_windows: Option<Vec<MuteWindow>>,
+    #[serde(default)]
+    pub digest_enabled: Option<bool>,
+    #[serde(default)]
+    pub digest_interval_minutes: Option<i32>,
+}
+
+/// Distinguish an absent field (None) from an explicit null (Some(None))
+fn double_option<'de, D>(deserializer: D) -> Result<Option<Option<Value>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Option::<Value>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationPreferencesResponse {
+    pub channel_config: Option<Value>,
+    pub severities: Vec<String>,
+    pub mute_windows: Vec<MuteWindow>,
+    pub digest_enabled: bool,
+    pub digest_interval_minutes: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_digest_at: Option<DateTime<Utc>>,
 }
 
 // ==================== Webhook Payload ====================
@@ -136,7 +331,54 @@ pub struct WebhookPayload {
     pub trigger_value: f64,
     pub threshold: f64,
     pub threshold_operator: String,
+    pub severity: String,
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<Value>,
 }
+
+/// Alerts from several rules sharing a grouping key, sent as one notification
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupedAlertPayload {
+    pub project_id: String,
+    pub project_name: String,
+    /// The rule config field alerts were grouped by, e.g. "source"
+    pub group_key: String,
+    pub group_value: String,
+    pub alert_count: usize,
+    pub alerts: Vec<WebhookPayload>,
+    pub message: String,
+}
+
+/// Summary of the non-critical alerts held for a user since their last digest
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestPayload {
+    pub user_id: String,
+    pub alert_count: usize,
+    pub alerts: Vec<DigestAlert>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestAlert {
+    pub alert_id: String,
+    pub rule_name: String,
+    pub project_name: String,
+    pub severity: String,
+    pub message: String,
+    pub triggered_at: DateTime<Utc>,
+}
+
+/// Sent to organization admins when a channel is disabled for failing deliveries
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelDisabledPayload {
+    pub channel_id: String,
+    pub channel_name: String,
+    /// Set for project channels
+    pub project_id: Option<String>,
+    pub organization_id: String,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    pub disabled_at: DateTime<Utc>,
+    pub message: String,
+}
@@ -1,10 +1,12 @@
 use std::sync::Arc;
 
 use crate::modules::alerts::application::dto::{
-    AlertChannelResponse, CreateAlertChannelRequest, UpdateAlertChannelRequest,
+    AlertChannelResponse, ChannelHealthResponse, CreateAlertChannelRequest,
+    UpdateAlertChannelRequest,
 };
 use crate::modules::alerts::domain::{
-    AlertChannel, AlertChannelId, AlertChannelRepository, AlertDomainError, ChannelType,
+    AlertChannel, AlertChannelId, AlertChannelRepository, AlertDomainError, ChannelHealth,
+    ChannelType, WebhookPayloadVersion,
 };
 use crate::modules::auth::application::ports::IdGenerator;
 use crate::modules::auth::domain::UserId;
@@ -22,6 +24,8 @@ where
     project_repo: Arc<PR>,
     member_repo: Arc<MR>,
     id_generator: Arc<ID>,
+    /// Failed deliveries in a row that disable a channel (0 = never)
+    channel_failure_limit: u32,
 }
 
 impl<CR, PR, MR, ID> AlertChannelService<CR, PR, MR, ID>
@@ -42,9 +46,16 @@ where
             project_repo,
             member_repo,
             id_generator,
+            channel_failure_limit: 0,
         }
     }
 
+    /// Report the failure limit the evaluator disables channels at
+    pub fn with_channel_failure_limit(mut self, channel_failure_limit: u32) -> Self {
+        self.channel_failure_limit = channel_failure_limit;
+        self
+    }
+
     async fn verify_project_access(
         &self,
         project_id: &ProjectId,
@@ -77,14 +88,81 @@ where
         Ok(())
     }
 
+    /// Verify the user belongs to the organization.
+    /// Managing shared channels additionally requires admin or owner.
+    async fn verify_org_access(
+        &self,
+        org_id: &OrgId,
+        user_id: &str,
+        require_admin: bool,
+    ) -> Result<(), AlertDomainError> {
+        let user_id = UserId::new(user_id.to_string());
+
+        let membership = self
+            .member_repo
+            .find_by_org_and_user(org_id, &user_id)
+            .await
+            .map_err(|e| AlertDomainError::InternalError(e.to_string()))?
+            .ok_or(AlertDomainError::NotOrgMember)?;
+
+        if require_admin && !membership.role().can_update_org() {
+            return Err(AlertDomainError::NotAuthorized);
+        }
+
+        Ok(())
+    }
+
+    /// Load a shared channel, hiding channels owned by other orgs or by projects
+    async fn find_shared_channel(
+        &self,
+        org_id: &OrgId,
+        channel_id: &AlertChannelId,
+    ) -> Result<AlertChannel, AlertDomainError> {
+        self.channel_repo
+            .find_by_id(channel_id)
+            .await?
+            .filter(|c| {
+                c.organization_id()
+                    .is_some_and(|id| id.as_str() == org_id.as_str())
+            })
+            .ok_or(AlertDomainError::ChannelNotFound)
+    }
+
+    /// Load a project's own channel; shared channels are managed at the org level
+    async fn find_project_channel(
+        &self,
+        project_id: &ProjectId,
+        channel_id: &AlertChannelId,
+    ) -> Result<AlertChannel, AlertDomainError> {
+        self.channel_repo
+            .find_by_id(channel_id)
+            .await?
+            .filter(|c| c.belongs_to_project(project_id))
+            .ok_or(AlertDomainError::ChannelNotFound)
+    }
+
+    fn to_health_response(&self, channel: &AlertChannel) -> ChannelHealthResponse {
+        ChannelHealthResponse {
+            channel_id: channel.id().as_str().to_string(),
+            status: channel.health().as_str().to_string(),
+            is_enabled: channel.is_enabled(),
+            consecutive_failures: channel.consecutive_failures(),
+            last_delivery_error: channel.last_delivery_error().map(str::to_string),
+            auto_disabled_at: channel.auto_disabled_at(),
+            failure_limit: (self.channel_failure_limit > 0).then_some(self.channel_failure_limit),
+        }
+    }
+
     fn to_response(&self, channel: &AlertChannel) -> AlertChannelResponse {
         AlertChannelResponse {
             id: channel.id().as_str().to_string(),
-            project_id: channel.project_id().as_str().to_string(),
+            project_id: channel.project_id().map(|id| id.as_str().to_string()),
+            organization_id: channel.organization_id().map(|id| id.as_str().to_string()),
             name: channel.name().to_string(),
             channel_type: channel.channel_type().as_str().to_string(),
             config: channel.config().clone(),
             is_enabled: channel.is_enabled(),
+            health: channel.health().as_str().to_string(),
             created_at: channel.created_at(),
             updated_at: channel.updated_at(),
         }
@@ -111,21 +189,16 @@ where
             return Err(AlertDomainError::ChannelNameExists(request.name));
         }
 
-        // Validate webhook config
-        if matches!(channel_type, ChannelType::Webhook) {
-            if request.config.get("url").is_none() {
-                return Err(AlertDomainError::InvalidChannelConfig(
-                    "Webhook channel requires 'url' in config".to_string(),
-                ));
-            }
-        }
+        validate_channel_config(&channel_type, &request.config)?;
+        let config =
+            with_payload_version(&channel_type, request.config, WebhookPayloadVersion::LATEST);
 
         let channel = AlertChannel::new(
             AlertChannelId::new(self.id_generator.generate()),
             project_id,
             request.name,
             channel_type,
-            request.config,
+            config,
         );
 
         self.channel_repo.save(&channel).await?;
@@ -149,8 +222,8 @@ where
             .await?
             .ok_or(AlertDomainError::ChannelNotFound)?;
 
-        // Verify channel belongs to project
-        if channel.project_id().as_str() != project_id.as_str() {
+        // Verify channel belongs to project; shared channels are managed at the org level
+        if !channel.belongs_to_project(&project_id) {
             return Err(AlertDomainError::ChannelNotFound);
         }
 
@@ -170,6 +243,43 @@ where
         Ok(channels.iter().map(|c| self.to_response(c)).collect())
     }
 
+    pub async fn channel_health(
+        &self,
+        project_id: &str,
+        channel_id: &str,
+        user_id: &str,
+    ) -> Result<ChannelHealthResponse, AlertDomainError> {
+        let project_id = ProjectId::new(project_id.to_string());
+        self.verify_project_access(&project_id, user_id).await?;
+
+        let channel = self
+            .find_project_channel(&project_id, &AlertChannelId::new(channel_id.to_string()))
+            .await?;
+
+        Ok(self.to_health_response(&channel))
+    }
+
+    /// Clear a channel's delivery failures and re-enable it
+    pub async fn reset_channel_health(
+        &self,
+        project_id: &str,
+        channel_id: &str,
+        user_id: &str,
+    ) -> Result<ChannelHealthResponse, AlertDomainError> {
+        let project_id = ProjectId::new(project_id.to_string());
+        self.verify_project_access(&project_id, user_id).await?;
+
+        let mut channel = self
+            .find_project_channel(&project_id, &AlertChannelId::new(channel_id.to_string()))
+            .await?;
+        if channel.health() != ChannelHealth::Healthy {
+            channel.reset_health();
+            self.channel_repo.update(&channel).await?;
+        }
+
+        Ok(self.to_health_response(&channel))
+    }
+
     pub async fn update_channel(
         &self,
         project_id: &str,
@@ -187,8 +297,8 @@ where
             .await?
             .ok_or(AlertDomainError::ChannelNotFound)?;
 
-        // Verify channel belongs to project
-        if channel.project_id().as_str() != project_id.as_str() {
+        // Verify channel belongs to project; shared channels are managed at the org level
+        if !channel.belongs_to_project(&project_id) {
             return Err(AlertDomainError::ChannelNotFound);
         }
 
@@ -204,8 +314,11 @@ where
             channel.update_name(name);
         }
 
-        // Update config if provided
+        // Update config if provided, keeping the channel's payload version unless it's changed
         if let Some(config) = request.config {
+            validate_channel_config(channel.channel_type(), &config)?;
+            let version = WebhookPayloadVersion::from_config(channel.config())?;
+            let config = with_payload_version(channel.channel_type(), config, version);
             channel.update_config(config);
         }
 
@@ -239,8 +352,8 @@ where
             .await?
             .ok_or(AlertDomainError::ChannelNotFound)?;
 
-        // Verify channel belongs to project
-        if channel.project_id().as_str() != project_id.as_str() {
+        // Verify channel belongs to project; shared channels are managed at the org level
+        if !channel.belongs_to_project(&project_id) {
             return Err(AlertDomainError::ChannelNotFound);
         }
 
@@ -248,4 +361,146 @@ where
 
         Ok(())
     }
+
+    // ==================== Organization-shared channels ====================
+
+    /// Create a channel shared with every project in the organization
+    pub async fn create_shared_channel(
+        &self,
+        org_id: &str,
+        request: CreateAlertChannelRequest,
+        user_id: &str,
+    ) -> Result<AlertChannelResponse, AlertDomainError> {
+        let org_id = OrgId::new(org_id.to_string());
+        self.verify_org_access(&org_id, user_id, true).await?;
+
+        let channel_type = ChannelType::from_str(&request.channel_type)?;
+
+        if self
+            .channel_repo
+            .org_name_exists(&org_id, &request.name, None)
+            .await?
+        {
+            return Err(AlertDomainError::ChannelNameExists(request.name));
+        }
+
+        validate_channel_config(&channel_type, &request.config)?;
+        let config =
+            with_payload_version(&channel_type, request.config, WebhookPayloadVersion::LATEST);
+
+        let channel = AlertChannel::new_shared(
+            AlertChannelId::new(self.id_generator.generate()),
+            org_id,
+            request.name,
+            channel_type,
+            config,
+        );
+
+        self.channel_repo.save(&channel).await?;
+
+        Ok(self.to_response(&channel))
+    }
+
+    pub async fn list_shared_channels(
+        &self,
+        org_id: &str,
+        user_id: &str,
+    ) -> Result<Vec<AlertChannelResponse>, AlertDomainError> {
+        let org_id = OrgId::new(org_id.to_string());
+        self.verify_org_access(&org_id, user_id, false).await?;
+
+        let channels = self.channel_repo.find_by_organization(&org_id).await?;
+
+        Ok(channels.iter().map(|c| self.to_response(c)).collect())
+    }
+
+    pub async fn update_shared_channel(
+        &self,
+        org_id: &str,
+        channel_id: &str,
+        request: UpdateAlertChannelRequest,
+        user_id: &str,
+    ) -> Result<AlertChannelResponse, AlertDomainError> {
+        let org_id = OrgId::new(org_id.to_string());
+        self.verify_org_access(&org_id, user_id, true).await?;
+
+        let channel_id = AlertChannelId::new(channel_id.to_string());
+        let mut channel = self.find_shared_channel(&org_id, &channel_id).await?;
+
+        if let Some(name) = request.name {
+            if self
+                .channel_repo
+                .org_name_exists(&org_id, &name, Some(&channel_id))
+                .await?
+            {
+                return Err(AlertDomainError::ChannelNameExists(name));
+            }
+            channel.update_name(name);
+        }
+
+        if let Some(config) = request.config {
+            validate_channel_config(channel.channel_type(), &config)?;
+            let version = WebhookPayloadVersion::from_config(channel.config())?;
+            let config = with_payload_version(channel.channel_type(), config, version);
+            channel.update_config(config);
+        }
+
+        if let Some(is_enabled) = request.is_enabled {
+            if is_enabled {
+                channel.enable();
+            } else {
+                channel.disable();
+            }
+        }
+
+        self.channel_repo.update(&channel).await?;
+
+        Ok(self.to_response(&channel))
+    }
+
+    pub async fn delete_shared_channel(
+        &self,
+        org_id: &str,
+        channel_id: &str,
+        user_id: &str,
+    ) -> Result<(), AlertDomainError> {
+        let org_id = OrgId::new(org_id.to_string());
+        self.verify_org_access(&org_id, user_id, true).await?;
+
+        let channel_id = AlertChannelId::new(channel_id.to_string());
+        self.find_shared_channel(&org_id, &channel_id).await?;
+
+        self.channel_repo.delete(&channel_id).await?;
+
+        Ok(())
+    }
+}
+
+fn validate_channel_config(
+    channel_type: &ChannelType,
+    config: &serde_json::Value,
+) -> Result<(), AlertDomainError> {
+    if matches!(channel_type, ChannelType::Webhook) {
+        if config.get("url").is_none() {
+            return Err(AlertDomainError::InvalidChannelConfig(
+                "Webhook channel requires 'url' in config".to_string(),
+            ));
+        }
+        WebhookPayloadVersion::from_config(config)?;
+    }
+    Ok(())
+}
+
+/// Set `payload_version` on a webhook config that doesn't choose one
+fn with_payload_version(
+    channel_type: &ChannelType,
+    mut config: serde_json::Value,
+    version: WebhookPayloadVersion,
+) -> serde_json::Value {
+    if let (ChannelType::Webhook, Some(fields)) = (channel_type, config.as_object_mut()) {
+        fields
+            .entry("payload_version")
+            .or_insert_with(|| version.as_u8().into());
+    }
+    config
 }
@@ -1,17 +1,59 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use serde_json::Value;
+
 use crate::modules::alerts::application::dto::{
-    AlertRuleResponse, CreateAlertRuleRequest, UpdateAlertRuleRequest,
+    ALERT_RULES_DOCUMENT_VERSION, AlertRuleDefinition, AlertRuleResponse, AlertRuleUsageResponse,
+    AlertRulesDocument, CreateAlertRuleRequest, ImportMode, ImportRulesResponse,
+    UpdateAlertRuleRequest,
 };
 use crate::modules::alerts::domain::{
-    AlertChannelRepository, AlertDomainError, AlertRule, AlertRuleId, AlertRuleRepository,
-    RuleType, ThresholdOperator,
+    AlertChannel, AlertChannelRepository, AlertDomainError, AlertRule, AlertRuleId,
+    AlertRuleRepository, AlertSchedule, AlertSeverity, LogRatioConfig, RuleType, ThresholdOperator,
+    TraceErrorRateConfig, resolve_project_channels,
 };
 use crate::modules::auth::application::ports::IdGenerator;
 use crate::modules::auth::domain::UserId;
 use crate::modules::organizations::domain::{OrgId, OrganizationMemberRepository};
 use crate::modules::projects::domain::{ProjectId, ProjectRepository};
 
+/// A rule from an import document, validated and with channels resolved to IDs
+struct ImportedRule {
+    name: String,
+    description: Option<String>,
+    rule_type: RuleType,
+    config: Value,
+    threshold_value: f64,
+    threshold_operator: ThresholdOperator,
+    time_window_seconds: i32,
+    cooldown_seconds: i32,
+    severity: AlertSeverity,
+    is_enabled: bool,
+    channel_ids: Vec<String>,
+}
+
+impl ImportedRule {
+    /// Whether an existing rule already matches this definition
+    fn matches(&self, rule: &AlertRule) -> bool {
+        let mut current_channels = rule.channel_ids().to_vec();
+        current_channels.sort();
+        let mut channels = self.channel_ids.clone();
+        channels.sort();
+
+        rule.description() == self.description.as_deref()
+            && *rule.rule_type() == self.rule_type
+            && *rule.config() == self.config
+            && rule.threshold_value() == self.threshold_value
+            && *rule.threshold_operator() == self.threshold_operator
+            && rule.time_window_seconds() == self.time_window_seconds
+            && rule.cooldown_seconds() == self.cooldown_seconds
+            && rule.severity() == self.severity
+            && rule.is_enabled() == self.is_enabled
+            && current_channels == channels
+    }
+}
+
 pub struct AlertRuleService<RR, CR, PR, MR, ID>
 where
     RR: AlertRuleRepository,
@@ -25,6 +67,8 @@ where
     project_repo: Arc<PR>,
     member_repo: Arc<MR>,
     id_generator: Arc<ID>,
+    /// Most rules a project may have; None means unlimited
+    max_rules_per_project: Option<u32>,
 }
 
 impl<RR, CR, PR, MR, ID> AlertRuleService<RR, CR, PR, MR, ID>
@@ -48,14 +92,38 @@ where
             project_repo,
             member_repo,
             id_generator,
+            max_rules_per_project: None,
         }
     }
 
+    /// Cap the rules in each project, so one project can't swamp the evaluator
+    pub fn with_max_rules_per_project(mut self, max_rules: Option<u32>) -> Self {
+        self.max_rules_per_project = max_rules;
+        self
+    }
+
+    /// Fail if adding `new_rules` would take the project past its rule cap
+    async fn check_rule_limit(
+        &self,
+        project_id: &ProjectId,
+        new_rules: usize,
+    ) -> Result<(), AlertDomainError> {
+        let Some(limit) = self.max_rules_per_project else {
+            return Ok(());
+        };
+        let count = self.rule_repo.count_by_project(project_id).await?;
+        if count + new_rules as i64 > i64::from(limit) {
+            return Err(AlertDomainError::RuleLimitExceeded(limit));
+        }
+        Ok(())
+    }
+
+    /// Verify the user can access the project, returning the project's organization
     async fn verify_project_access(
         &self,
         project_id: &ProjectId,
         user_id: &str,
-    ) -> Result<(), AlertDomainError> {
+    ) -> Result<OrgId, AlertDomainError> {
         let project = self
             .project_repo
             .find_by_id(project_id)
@@ -80,6 +148,75 @@ where
             return Err(AlertDomainError::NotAuthorized);
         }
 
+        Ok(org_id)
+    }
+
+    /// Channels rules in the project can bind to: project channels plus the
+    /// org's shared channels, with project channels shadowing same-named shared ones
+    async fn available_channels(
+        &self,
+        project_id: &ProjectId,
+        org_id: &OrgId,
+    ) -> Result<Vec<AlertChannel>, AlertDomainError> {
+        let project_channels = self.channel_repo.find_by_project(project_id).await?;
+        let shared_channels = self.channel_repo.find_by_organization(org_id).await?;
+        Ok(resolve_project_channels(project_channels, shared_channels))
+    }
+
+    /// Ensure every channel ID is available to the project
+    async fn validate_channel_ids(
+        &self,
+        project_id: &ProjectId,
+        org_id: &OrgId,
+        channel_ids: &[String],
+    ) -> Result<(), AlertDomainError> {
+        if channel_ids.is_empty() {
+            return Ok(());
+        }
+
+        let available: HashSet<String> = self
+            .available_channels(project_id, org_id)
+            .await?
+            .iter()
+            .filter(|c| c.is_enabled())
+            .map(|c| c.id().as_str().to_string())
+            .collect();
+        if channel_ids.iter().any(|id| !available.contains(id)) {
+            return Err(AlertDomainError::ChannelNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Ensure the parent is a rule in the same project and that following
+    /// parents from it never leads back to `rule_id`
+    async fn validate_parent_rule(
+        &self,
+        project_id: &ProjectId,
+        rule_id: &AlertRuleId,
+        parent_id: &AlertRuleId,
+    ) -> Result<(), AlertDomainError> {
+        let mut visited = HashSet::from([rule_id.clone()]);
+        let mut next = Some(parent_id.clone());
+        while let Some(id) = next {
+            if !visited.insert(id.clone()) {
+                return Err(AlertDomainError::ValidationError(
+                    "parent_rule_id would create a dependency cycle".to_string(),
+                ));
+            }
+            let rule = self
+                .rule_repo
+                .find_by_id(&id)
+                .await?
+                .filter(|r| r.project_id().as_str() == project_id.as_str())
+                .ok_or_else(|| {
+                    AlertDomainError::ValidationError(format!(
+                        "Parent rule '{}' not found in project",
+                        id.as_str()
+                    ))
+                })?;
+            next = rule.parent_rule_id().cloned();
+        }
         Ok(())
     }
 
@@ -94,12 +231,17 @@ where
             threshold_value: rule.threshold_value(),
             threshold_operator: rule.threshold_operator().as_str().to_string(),
             time_window_seconds: rule.time_window_seconds(),
+            cooldown_seconds: rule.cooldown_seconds(),
+            severity: rule.severity().as_str().to_string(),
             is_enabled: rule.is_enabled(),
             last_evaluated_at: rule.last_evaluated_at(),
             last_triggered_at: rule.last_triggered_at(),
             created_at: rule.created_at(),
             updated_at: rule.updated_at(),
             channel_ids: rule.channel_ids().to_vec(),
+            parent_rule_id: rule.parent_rule_id().map(|id| id.as_str().to_string()),
+            evaluate_on_ingest: rule.evaluate_on_ingest(),
+            schedule: rule.schedule().cloned(),
         }
     }
 
@@ -110,10 +252,11 @@ where
         user_id: &str,
     ) -> Result<AlertRuleResponse, AlertDomainError> {
         let project_id = ProjectId::new(project_id.to_string());
-        self.verify_project_access(&project_id, user_id).await?;
+        let org_id = self.verify_project_access(&project_id, user_id).await?;
 
         // Validate rule type
         let rule_type = RuleType::from_str(&request.rule_type)?;
+        validate_rule_config(&rule_type, &request.config)?;
 
         // Validate threshold operator
         let threshold_operator = ThresholdOperator::from_str(&request.threshold_operator)?;
@@ -127,21 +270,35 @@ where
             return Err(AlertDomainError::RuleNameExists(request.name));
         }
 
-        // Validate channels exist and belong to project
-        if !request.channel_ids.is_empty() {
-            let channels = self.channel_repo.find_by_ids(&request.channel_ids).await?;
-            for channel in &channels {
-                if channel.project_id().as_str() != project_id.as_str() {
-                    return Err(AlertDomainError::ChannelNotFound);
-                }
-            }
-            if channels.len() != request.channel_ids.len() {
-                return Err(AlertDomainError::ChannelNotFound);
-            }
+        validate_cooldown(request.cooldown_seconds)?;
+        let severity = request
+            .severity
+            .as_deref()
+            .map(AlertSeverity::from_str)
+            .transpose()?
+            .unwrap_or_default();
+        validate_evaluate_on_ingest(request.evaluate_on_ingest, severity)?;
+        let schedule = request
+            .schedule
+            .as_ref()
+            .map(AlertSchedule::from_value)
+            .transpose()?;
+
+        // Validate channels exist and belong to the project or its organization
+        self.validate_channel_ids(&project_id, &org_id, &request.channel_ids)
+            .await?;
+
+        self.check_rule_limit(&project_id, 1).await?;
+
+        let rule_id = AlertRuleId::new(self.id_generator.generate());
+        let parent_rule_id = request.parent_rule_id.map(AlertRuleId::new);
+        if let Some(parent_id) = &parent_rule_id {
+            self.validate_parent_rule(&project_id, &rule_id, parent_id)
+                .await?;
         }
 
         let mut rule = AlertRule::new(
-            AlertRuleId::new(self.id_generator.generate()),
+            rule_id,
             project_id,
             request.name,
             request.description,
@@ -152,6 +309,11 @@ where
             request.time_window_seconds,
             UserId::new(user_id.to_string()),
         );
+        rule.update_cooldown(request.cooldown_seconds);
+        rule.update_severity(severity);
+        rule.set_parent_rule(parent_rule_id);
+        rule.set_evaluate_on_ingest(request.evaluate_on_ingest);
+        rule.set_schedule(schedule);
 
         // Set channel IDs if provided
         if !request.channel_ids.is_empty() {
@@ -200,6 +362,21 @@ where
         Ok(rules.iter().map(|r| self.to_response(r)).collect())
     }
 
+    /// Rules in the project and the configured cap
+    pub async fn rule_usage(
+        &self,
+        project_id: &str,
+        user_id: &str,
+    ) -> Result<AlertRuleUsageResponse, AlertDomainError> {
+        let project_id = ProjectId::new(project_id.to_string());
+        self.verify_project_access(&project_id, user_id).await?;
+
+        Ok(AlertRuleUsageResponse {
+            count: self.rule_repo.count_by_project(&project_id).await?,
+            limit: self.max_rules_per_project,
+        })
+    }
+
     pub async fn update_rule(
         &self,
         project_id: &str,
@@ -208,7 +385,7 @@ where
         user_id: &str,
     ) -> Result<AlertRuleResponse, AlertDomainError> {
         let project_id = ProjectId::new(project_id.to_string());
-        self.verify_project_access(&project_id, user_id).await?;
+        let org_id = self.verify_project_access(&project_id, user_id).await?;
 
         let rule_id = AlertRuleId::new(rule_id.to_string());
         let mut rule = self
@@ -241,6 +418,7 @@ where
 
         // Update config if provided
         if let Some(config) = request.config {
+            validate_rule_config(rule.rule_type(), &config)?;
             rule.update_config(config);
         }
 
@@ -262,6 +440,29 @@ where
             rule.update_time_window(time_window);
         }
 
+        if let Some(cooldown) = request.cooldown_seconds {
+            validate_cooldown(cooldown)?;
+            rule.update_cooldown(cooldown);
+        }
+
+        if let Some(severity) = request.severity.as_deref() {
+            rule.update_severity(AlertSeverity::from_str(severity)?);
+        }
+
+        if let Some(evaluate_on_ingest) = request.evaluate_on_ingest {
+            rule.set_evaluate_on_ingest(evaluate_on_ingest);
+        }
+        validate_evaluate_on_ingest(rule.evaluate_on_ingest(), rule.severity())?;
+
+        if let Some(schedule) = request.schedule {
+            rule.set_schedule(
+                schedule
+                    .as_ref()
+                    .map(AlertSchedule::from_value)
+                    .transpose()?,
+            );
+        }
+
         // Update enabled status if provided
         if let Some(is_enabled) = request.is_enabled {
             if is_enabled {
@@ -273,18 +474,9 @@ where
 
         // Update channels if provided
         if let Some(channel_ids) = request.channel_ids {
-            // Validate channels exist and belong to project
-            if !channel_ids.is_empty() {
-                let channels = self.channel_repo.find_by_ids(&channel_ids).await?;
-                for channel in &channels {
-                    if channel.project_id().as_str() != project_id.as_str() {
-                        return Err(AlertDomainError::ChannelNotFound);
-                    }
-                }
-                if channels.len() != channel_ids.len() {
-                    return Err(AlertDomainError::ChannelNotFound);
-                }
-            }
+            // Validate channels exist and belong to the project or its organization
+            self.validate_channel_ids(&project_id, &org_id, &channel_ids)
+                .await?;
             rule.set_channel_ids(channel_ids);
             // Update channels in the junction table
             self.rule_repo
@@ -292,6 +484,15 @@ where
                 .await?;
         }
 
+        if let Some(parent_id) = request.parent_rule_id {
+            let parent_id = Some(AlertRuleId::new(parent_id)).filter(|id| !id.as_str().is_empty());
+            if let Some(parent_id) = &parent_id {
+                self.validate_parent_rule(&project_id, &rule_id, parent_id)
+                    .await?;
+            }
+            rule.set_parent_rule(parent_id);
+        }
+
         self.rule_repo.update(&rule).await?;
 
         Ok(self.to_response(&rule))
@@ -322,4 +523,666 @@ where
 
         Ok(())
     }
+
+    /// Export all rules in a project as a portable document.
+    /// Channel references are included by name when `include_channels` is set.
+    pub async fn export_rules(
+        &self,
+        project_id: &str,
+        include_channels: bool,
+        user_id: &str,
+    ) -> Result<AlertRulesDocument, AlertDomainError> {
+        let project_id = ProjectId::new(project_id.to_string());
+        let org_id = self.verify_project_access(&project_id, user_id).await?;
+
+        let channel_names: HashMap<String, String> = if include_channels {
+            self.available_channels(&project_id, &org_id)
+                .await?
+                .into_iter()
+                .map(|c| (c.id().as_str().to_string(), c.name().to_string()))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
+        let mut rules = self.rule_repo.find_by_project(&project_id).await?;
+        rules.sort_by(|a, b| a.name().cmp(b.name()));
+
+        let rules = rules
+            .iter()
+            .map(|rule| AlertRuleDefinition {
+                name: rule.name().to_string(),
+                description: rule.description().map(|s| s.to_string()),
+                rule_type: rule.rule_type().as_str().to_string(),
+                config: rule.config().clone(),
+                threshold_value: rule.threshold_value(),
+                threshold_operator: rule.threshold_operator().as_str().to_string(),
+                time_window_seconds: rule.time_window_seconds(),
+                cooldown_seconds: rule.cooldown_seconds(),
+                severity: (rule.severity() != AlertSeverity::default())
+                    .then(|| rule.severity().as_str().to_string()),
+                is_enabled: rule.is_enabled(),
+                channels: rule
+                    .channel_ids()
+                    .iter()
+                    .filter_map(|id| channel_names.get(id).cloned())
+                    .collect(),
+            })
+            .collect();
+
+        Ok(AlertRulesDocument {
+            version: ALERT_RULES_DOCUMENT_VERSION,
+            rules,
+        })
+    }
+
+    /// Create or update rules from a document, matching existing rules by name.
+    /// The whole document is validated before anything is written.
+    pub async fn import_rules(
+        &self,
+        project_id: &str,
+        document: AlertRulesDocument,
+        mode: ImportMode,
+        user_id: &str,
+    ) -> Result<ImportRulesResponse, AlertDomainError> {
+        let project_id = ProjectId::new(project_id.to_string());
+        let org_id = self.verify_project_access(&project_id, user_id).await?;
+
+        // 1. Validate the document and resolve channel names
+        let imported = self
+            .validate_document(&project_id, &org_id, document)
+            .await?;
+
+        // 2. Compare against existing rules by name
+        let existing: HashMap<String, AlertRule> = self
+            .rule_repo
+            .find_by_project(&project_id)
+            .await?
+            .into_iter()
+            .map(|r| (r.name().to_string(), r))
+            .collect();
+
+        let new_rules = imported
+            .iter()
+            .filter(|rule| !existing.contains_key(&rule.name))
+            .count();
+        self.check_rule_limit(&project_id, new_rules).await?;
+
+        let dry_run = mode == ImportMode::DryRun;
+        let mut response = ImportRulesResponse {
+            dry_run,
+            ..Default::default()
+        };
+
+        // 3. Apply changes unless this is a dry run
+        for rule in imported {
+            match existing.get(&rule.name) {
+                Some(current) if rule.matches(current) => response.unchanged.push(rule.name),
+                Some(current) => {
+                    if !dry_run {
+                        let mut current = current.clone();
+                        current.update_description(rule.description);
+                        current.update_rule_type(rule.rule_type);
+                        current.update_config(rule.config);
+                        current.update_threshold(rule.threshold_value, rule.threshold_operator);
+                        current.update_time_window(rule.time_window_seconds);
+                        current.update_cooldown(rule.cooldown_seconds);
+                        current.update_severity(rule.severity);
+                        if rule.is_enabled {
+                            current.enable();
+                        } else {
+                            current.disable();
+                        }
+                        current.set_channel_ids(rule.channel_ids);
+                        self.rule_repo
+                            .set_channels(current.id(), current.channel_ids())
+                            .await?;
+                        self.rule_repo.update(&current).await?;
+                    }
+                    response.updated.push(rule.name);
+                }
+                None => {
+                    if !dry_run {
+                        let mut new_rule = AlertRule::new(
+                            AlertRuleId::new(self.id_generator.generate()),
+                            project_id.clone(),
+                            rule.name.clone(),
+                            rule.description,
+                            rule.rule_type,
+                            rule.config,
+                            rule.threshold_value,
+                            rule.threshold_operator,
+                            rule.time_window_seconds,
+                            UserId::new(user_id.to_string()),
+                        );
+                        new_rule.update_cooldown(rule.cooldown_seconds);
+                        new_rule.update_severity(rule.severity);
+                        if !rule.is_enabled {
+                            new_rule.disable();
+                        }
+                        new_rule.set_channel_ids(rule.channel_ids);
+                        self.rule_repo.save(&new_rule).await?;
+                    }
+                    response.created.push(rule.name);
+                }
+            }
+        }
+
+        Ok(response)
+    }
+
+    async fn validate_document(
+        &self,
+        project_id: &ProjectId,
+        org_id: &OrgId,
+        document: AlertRulesDocument,
+    ) -> Result<Vec<ImportedRule>, AlertDomainError> {
+        if document.version != ALERT_RULES_DOCUMENT_VERSION {
+            return Err(AlertDomainError::ValidationError(format!(
+                "Unsupported document version: {}. Expected {}",
+                document.version, ALERT_RULES_DOCUMENT_VERSION
+            )));
+        }
+
+        let channel_ids: HashMap<String, String> = self
+            .available_channels(project_id, org_id)
+            .await?
+            .into_iter()
+            .map(|c| (c.name().to_string(), c.id().as_str().to_string()))
+            .collect();
+
+        let mut names = HashSet::new();
+        let mut imported = Vec::with_capacity(document.rules.len());
+        for definition in document.rules {
+            let name = definition.name.trim().to_string();
+            if name.is_empty() {
+                return Err(AlertDomainError::InvalidRuleName(
+                    "Rule name cannot be empty".to_string(),
+                ));
+            }
+            if !names.insert(name.clone()) {
+                return Err(AlertDomainError::ValidationError(format!(
+                    "Duplicate rule name in document: {}",
+                    name
+                )));
+            }
+            if definition.time_window_seconds <= 0 {
+                return Err(AlertDomainError::ValidationError(format!(
+                    "Rule '{}': time_window_seconds must be positive",
+                    name
+                )));
+            }
+            if definition.cooldown_seconds < 0 {
+                return Err(AlertDomainError::ValidationError(format!(
+                    "Rule '{}': cooldown_seconds cannot be negative",
+                    name
+                )));
+            }
+
+            let rule_channel_ids = definition
+                .channels
+                .iter()
+                .map(|channel| {
+                    channel_ids.get(channel).cloned().ok_or_else(|| {
+                        AlertDomainError::ValidationError(format!(
+                            "Rule '{}' references unknown channel '{}'",
+                            name, channel
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let rule_type = RuleType::from_str(&definition.rule_type)?;
+            validate_rule_config(&rule_type, &definition.config)?;
+            imported.push(ImportedRule {
+                rule_type,
+                threshold_operator: ThresholdOperator::from_str(&definition.threshold_operator)?,
+                name,
+                description: definition.description,
+                config: definition.config,
+                threshold_value: definition.threshold_value,
+                time_window_seconds: definition.time_window_seconds,
+                cooldown_seconds: definition.cooldown_seconds,
+                severity: definition
+                    .severity
+                    .as_deref()
+                    .map(AlertSeverity::from_str)
+                    .transpose()?
+                    .unwrap_or_default(),
+                is_enabled: definition.is_enabled,
+                channel_ids: rule_channel_ids,
+            });
+        }
+
+        Ok(imported)
+    }
+}
+
+/// Reject configs a rule type can't evaluate; older types read their config
+/// leniently and accept anything
+fn validate_rule_config(rule_type: &RuleType, config: &Value) -> Result<(), AlertDomainError> {
+    match rule_type {
+        RuleType::LogRatio => {
+            LogRatioConfig::from_config(config)?;
+        }
+        RuleType::TraceErrorRate => {
+            TraceErrorRateConfig::from_config(config)?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn validate_cooldown(cooldown_seconds: i32) -> Result<(), AlertDomainError> {
+    if cooldown_seconds < 0 {
+        return Err(AlertDomainError::ValidationError(
+            "cooldown_seconds cannot be negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Ingest-time evaluation is reserved for critical rules, which are few and
+/// worth the extra evaluations
+fn validate_evaluate_on_ingest(
+    evaluate_on_ingest: bool,
+    severity: AlertSeverity,
+) -> Result<(), AlertDomainError> {
+    if evaluate_on_ingest && severity != AlertSeverity::Critical {
+        return Err(AlertDomainError::ValidationError(
+            "evaluate_on_ingest is only available for critical rules".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::modules::alerts::domain::{AlertChannel, AlertChannelId, ChannelType};
+    use crate::modules::organizations::domain::OrgRole;
+    use crate::shared::testing::{
+        InMemoryAlertChannelRepository, InMemoryAlertRuleRepository, InMemoryMemberRepository,
+        InMemoryProjectRepository, SequentialIdGenerator,
+    };
+
+    type TestService = AlertRuleService<
+        InMemoryAlertRuleRepository,
+        InMemoryAlertChannelRepository,
+        InMemoryProjectRepository,
+        InMemoryMemberRepository,
+        SequentialIdGenerator,
+    >;
+
+    fn create_service() -> (TestService, Arc<InMemoryAlertChannelRepository>) {
+        let channel_repo = Arc::new(InMemoryAlertChannelRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        project_repo.seed("project-1", "org-1");
+        project_repo.seed("project-2", "org-1");
+        member_repo.seed("org-1", "user-1", OrgRole::Member);
+
+        for (id, project_id) in [("channel-1", "project-1"), ("channel-2", "project-2")] {
+            channel_repo.seed(AlertChannel::new(
+                AlertChannelId::new(id.to_string()),
+                ProjectId::new(project_id.to_string()),
+                "on-call".to_string(),
+                ChannelType::Webhook,
+                json!({"url": "https://example.com/hook"}),
+            ));
+        }
+
+        let service = AlertRuleService::new(
+            Arc::new(InMemoryAlertRuleRepository::new()),
+            channel_repo.clone(),
+            project_repo,
+            member_repo,
+            Arc::new(SequentialIdGenerator::new()),
+        );
+        (service, channel_repo)
+    }
+
+    fn rule_request(name: &str, channel_ids: Vec<String>) -> CreateAlertRuleRequest {
+        CreateAlertRuleRequest {
+            name: name.to_string(),
+            description: Some("Too many errors".to_string()),
+            rule_type: "error_rate".to_string(),
+            config: json!({"level": "error"}),
+            threshold_value: 5.0,
+            threshold_operator: "gt".to_string(),
+            time_window_seconds: 600,
+            cooldown_seconds: 0,
+            severity: None,
+            channel_ids,
+            parent_rule_id: None,
+            evaluate_on_ingest: false,
+            schedule: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_on_ingest_requires_critical_severity() {
+        let (service, _) = create_service();
+        let request = CreateAlertRuleRequest {
+            evaluate_on_ingest: true,
+            ..rule_request("errors", vec![])
+        };
+        let result = service
+            .create_rule("project-1", request.clone(), "user-1")
+            .await;
+        assert!(matches!(result, Err(AlertDomainError::ValidationError(_))));
+
+        let rule = service
+            .create_rule(
+                "project-1",
+                CreateAlertRuleRequest {
+                    severity: Some("critical".to_string()),
+                    ..request
+                },
+                "user-1",
+            )
+            .await
+            .unwrap();
+        assert!(rule.evaluate_on_ingest);
+    }
+
+    #[tokio::test]
+    async fn test_export_then_import_into_fresh_project() {
+        let (service, _) = create_service();
+        service
+            .create_rule(
+                "project-1",
+                rule_request("errors", vec!["channel-1".to_string()]),
+                "user-1",
+            )
+            .await
+            .unwrap();
+        service
+            .create_rule("project-1", rule_request("quiet", vec![]), "user-1")
+            .await
+            .unwrap();
+
+        let document = service
+            .export_rules("project-1", true, "user-1")
+            .await
+            .unwrap();
+        assert_eq!(document.rules.len(), 2);
+        assert_eq!(document.rules[0].channels, vec!["on-call".to_string()]);
+
+        // Round-trip through JSON as a user would
+        let document: AlertRulesDocument =
+            serde_json::from_str(&serde_json::to_string(&document).unwrap()).unwrap();
+
+        let response = service
+            .import_rules("project-2", document.clone(), ImportMode::Apply, "user-1")
+            .await
+            .unwrap();
+        assert_eq!(response.created, vec!["errors", "quiet"]);
+        assert!(response.updated.is_empty());
+
+        let imported = service.list_rules("project-2", "user-1").await.unwrap();
+        let errors = imported.iter().find(|r| r.name == "errors").unwrap();
+        assert_eq!(errors.channel_ids, vec!["channel-2".to_string()]);
+        assert_eq!(errors.time_window_seconds, 600);
+
+        // Exporting the imported project yields the same document
+        let reexported = service
+            .export_rules("project-2", true, "user-1")
+            .await
+            .unwrap();
+        assert_eq!(reexported, document);
+
+        // Importing again changes nothing
+        let response = service
+            .import_rules("project-2", document, ImportMode::Apply, "user-1")
+            .await
+            .unwrap();
+        assert!(response.created.is_empty());
+        assert_eq!(response.unchanged.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_reports_without_writing() {
+        let (service, _) = create_service();
+        service
+            .create_rule("project-1", rule_request("errors", vec![]), "user-1")
+            .await
+            .unwrap();
+        let mut document = service
+            .export_rules("project-1", false, "user-1")
+            .await
+            .unwrap();
+        document.rules[0].threshold_value = 10.0;
+        let mut new_rule = document.rules[0].clone();
+        new_rule.name = "new".to_string();
+        document.rules.push(new_rule);
+
+        let response = service
+            .import_rules("project-1", document, ImportMode::DryRun, "user-1")
+            .await
+            .unwrap();
+
+        assert!(response.dry_run);
+        assert_eq!(response.created, vec!["new"]);
+        assert_eq!(response.updated, vec!["errors"]);
+        let rules = service.list_rules("project-1", "user-1").await.unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].threshold_value, 5.0);
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_unknown_channel() {
+        let (service, _) = create_service();
+        let document = AlertRulesDocument {
+            version: ALERT_RULES_DOCUMENT_VERSION,
+            rules: vec![AlertRuleDefinition {
+                name: "errors".to_string(),
+                description: None,
+                rule_type: "error_rate".to_string(),
+                config: json!({}),
+                threshold_value: 1.0,
+                threshold_operator: "gt".to_string(),
+                time_window_seconds: 300,
+                cooldown_seconds: 0,
+                severity: None,
+                is_enabled: true,
+                channels: vec!["missing".to_string()],
+            }],
+        };
+
+        let result = service
+            .import_rules("project-1", document, ImportMode::Apply, "user-1")
+            .await;
+
+        assert!(matches!(result, Err(AlertDomainError::ValidationError(_))));
+        assert!(
+            service
+                .list_rules("project-1", "user-1")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    fn shared_channel(id: &str, org_id: &str, name: &str) -> AlertChannel {
+        AlertChannel::new_shared(
+            AlertChannelId::new(id.to_string()),
+            OrgId::new(org_id.to_string()),
+            name.to_string(),
+            ChannelType::Webhook,
+            json!({"url": "https://example.com/shared"}),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_rule_binds_to_org_shared_channel() {
+        let (service, channel_repo) = create_service();
+        channel_repo.seed(shared_channel("shared-ops", "org-1", "ops"));
+        channel_repo.seed(shared_channel("other-org", "org-2", "ops"));
+
+        let rule = service
+            .create_rule(
+                "project-1",
+                rule_request("errors", vec!["shared-ops".to_string()]),
+                "user-1",
+            )
+            .await
+            .unwrap();
+        assert_eq!(rule.channel_ids, vec!["shared-ops".to_string()]);
+
+        let document = service
+            .export_rules("project-1", true, "user-1")
+            .await
+            .unwrap();
+        assert_eq!(document.rules[0].channels, vec!["ops".to_string()]);
+
+        // Another organization's shared channels are not visible
+        let result = service
+            .create_rule(
+                "project-1",
+                rule_request("other", vec!["other-org".to_string()]),
+                "user-1",
+            )
+            .await;
+        assert!(matches!(result, Err(AlertDomainError::ChannelNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_project_channel_shadows_org_channel_with_same_name() {
+        let (service, channel_repo) = create_service();
+        channel_repo.seed(shared_channel("shared-on-call", "org-1", "on-call"));
+
+        // Name resolution picks the project's own channel
+        let document = AlertRulesDocument {
+            version: ALERT_RULES_DOCUMENT_VERSION,
+            rules: vec![AlertRuleDefinition {
+                name: "errors".to_string(),
+                description: None,
+                rule_type: "error_rate".to_string(),
+                config: json!({}),
+                threshold_value: 1.0,
+                threshold_operator: "gt".to_string(),
+                time_window_seconds: 300,
+                cooldown_seconds: 0,
+                severity: None,
+                is_enabled: true,
+                channels: vec!["on-call".to_string()],
+            }],
+        };
+        service
+            .import_rules("project-1", document, ImportMode::Apply, "user-1")
+            .await
+            .unwrap();
+        let rules = service.list_rules("project-1", "user-1").await.unwrap();
+        assert_eq!(rules[0].channel_ids, vec!["channel-1".to_string()]);
+
+        // The shadowed shared channel can't be bound directly either
+        let result = service
+            .create_rule(
+                "project-1",
+                rule_request("shadowed", vec!["shared-on-call".to_string()]),
+                "user-1",
+            )
+            .await;
+        assert!(matches!(result, Err(AlertDomainError::ChannelNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_parent_rule_cycle_is_rejected() {
+        let (service, _) = create_service();
+        let service_down = service
+            .create_rule("project-1", rule_request("service down", vec![]), "user-1")
+            .await
+            .unwrap();
+        let endpoint = service
+            .create_rule(
+                "project-1",
+                CreateAlertRuleRequest {
+                    parent_rule_id: Some(service_down.id.clone()),
+                    ..rule_request("endpoint errors", vec![])
+                },
+                "user-1",
+            )
+            .await
+            .unwrap();
+        assert_eq!(endpoint.parent_rule_id, Some(service_down.id.clone()));
+
+        let update = UpdateAlertRuleRequest {
+            name: None,
+            description: None,
+            config: None,
+            threshold_value: None,
+            threshold_operator: None,
+            time_window_seconds: None,
+            cooldown_seconds: None,
+            severity: None,
+            is_enabled: None,
+            channel_ids: None,
+            parent_rule_id: Some(endpoint.id.clone()),
+            evaluate_on_ingest: None,
+            schedule: None,
+        };
+        let result = service
+            .update_rule("project-1", &service_down.id, update, "user-1")
+            .await;
+        assert!(matches!(result, Err(AlertDomainError::ValidationError(_))));
+
+        // Parents must belong to the same project
+        let other = service
+            .create_rule("project-2", rule_request("other", vec![]), "user-1")
+            .await
+            .unwrap();
+        let result = service
+            .create_rule(
+                "project-1",
+                CreateAlertRuleRequest {
+                    parent_rule_id: Some(other.id),
+                    ..rule_request("cross project", vec![])
+                },
+                "user-1",
+            )
+            .await;
+        assert!(matches!(result, Err(AlertDomainError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rule_limit_rejects_creation_until_a_rule_is_deleted() {
+        let (service, _) = create_service();
+        let service = service.with_max_rules_per_project(Some(2));
+        let first = service
+            .create_rule("project-1", rule_request("first", vec![]), "user-1")
+            .await
+            .unwrap();
+        service
+            .create_rule("project-1", rule_request("second", vec![]), "user-1")
+            .await
+            .unwrap();
+
+        let result = service
+            .create_rule("project-1", rule_request("third", vec![]), "user-1")
+            .await;
+        assert!(matches!(
+            result,
+            Err(AlertDomainError::RuleLimitExceeded(2))
+        ));
+        let usage = service.rule_usage("project-1", "user-1").await.unwrap();
+        assert_eq!((usage.count, usage.limit), (2, Some(2)));
+
+        // The cap is per project
+        service
+            .create_rule("project-2", rule_request("third", vec![]), "user-1")
+            .await
+            .unwrap();
+
+        service
+            .delete_rule("project-1", &first.id, "user-1")
+            .await
+            .unwrap();
+        service
+            .create_rule("project-1", rule_request("third", vec![]), "user-1")
+            .await
+            .unwrap();
+    }
 }
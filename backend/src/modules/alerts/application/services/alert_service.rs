@@ -7,6 +7,7 @@ use crate::modules::alerts::domain::{
 use crate::modules::auth::domain::UserId;
 use crate::modules::organizations::domain::{OrgId, OrganizationMemberRepository};
 use crate::modules::projects::domain::{ProjectId, ProjectRepository};
+use crate::shared::PaginationConfig;
 
 pub struct AlertService<AR, RR, PR, MR>
 where
@@ -19,6 +20,7 @@ where
     rule_repo: Arc<RR>,
     project_repo: Arc<PR>,
     member_repo: Arc<MR>,
+    pagination: PaginationConfig,
 }
 
 impl<AR, RR, PR, MR> AlertService<AR, RR, PR, MR>
@@ -33,12 +35,14 @@ where
         rule_repo: Arc<RR>,
         project_repo: Arc<PR>,
         member_repo: Arc<MR>,
+        pagination: PaginationConfig,
     ) -> Self {
         Self {
             alert_repo,
             rule_repo,
             project_repo,
             member_repo,
+            pagination,
         }
     }
 
@@ -74,10 +78,7 @@ where
         Ok(())
     }
 
-    fn to_response(
-        &self,
-        alert: &crate::modules::alerts::domain::Alert,
-    ) -> AlertResponse {
+    fn to_response(&self, alert: &crate::modules::alerts::domain::Alert) -> AlertResponse {
         AlertResponse {
             id: alert.id().as_str().to_string(),
             rule_id: alert.rule_id().as_str().to_string(),
@@ -101,12 +102,12 @@ where
         let project_id = ProjectId::new(project_id.to_string());
         self.verify_project_access(&project_id, user_id).await?;
 
-        let limit = limit.unwrap_or(50).min(100);
-        let offset = offset.unwrap_or(0);
+        let pagination = self.pagination.resolve(limit, offset);
+        let limit = pagination.limit;
 
         let alerts = self
             .alert_repo
-            .find_by_project(&project_id, limit + 1, offset)
+            .find_by_project(&project_id, limit + 1, pagination.offset)
             .await?;
 
         let total = self.alert_repo.count_by_project(&project_id).await?;
@@ -122,6 +123,7 @@ where
             alerts,
             total,
             has_more,
+            limit,
         })
     }
 
@@ -171,7 +173,7 @@ where
             return Err(AlertDomainError::RuleNotFound);
         }
 
-        let limit = limit.unwrap_or(50).min(100);
+        let limit = self.pagination.resolve(limit, None).limit;
         let alerts = self.alert_repo.find_by_rule(&rule_id, limit).await?;
 
         Ok(alerts.iter().map(|a| self.to_response(a)).collect())
@@ -208,3 +210,77 @@ where
         Ok(self.to_response(&alert))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::alerts::domain::Alert;
+    use crate::modules::organizations::domain::OrgRole;
+    use crate::shared::testing::{
+        InMemoryAlertRepository, InMemoryAlertRuleRepository, InMemoryMemberRepository,
+        InMemoryProjectRepository,
+    };
+
+    async fn create_service_with_alerts(
+        count: usize,
+    ) -> AlertService<
+        InMemoryAlertRepository,
+        InMemoryAlertRuleRepository,
+        InMemoryProjectRepository,
+        InMemoryMemberRepository,
+    > {
+        let alert_repo = Arc::new(InMemoryAlertRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        project_repo.seed("project-1", "org-1");
+        member_repo.seed("org-1", "user-1", OrgRole::Member);
+
+        for i in 0..count {
+            let alert = Alert::new(
+                AlertId::new(format!("alert-{}", i)),
+                AlertRuleId::new("rule-1".to_string()),
+                ProjectId::new("project-1".to_string()),
+                1.0,
+                "fired".to_string(),
+                None,
+            );
+            alert_repo.save(&alert).await.unwrap();
+        }
+
+        AlertService::new(
+            alert_repo,
+            Arc::new(InMemoryAlertRuleRepository::new()),
+            project_repo,
+            member_repo,
+            PaginationConfig::new(3, 5),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_list_alerts_clamps_limit_to_max() {
+        let service = create_service_with_alerts(10).await;
+
+        let response = service
+            .list_alerts("project-1", Some(500), None, "user-1")
+            .await
+            .unwrap();
+
+        assert_eq!(response.limit, 5);
+        assert_eq!(response.alerts.len(), 5);
+        assert!(response.has_more);
+    }
+
+    #[tokio::test]
+    async fn test_list_alerts_uses_default_limit() {
+        let service = create_service_with_alerts(10).await;
+
+        let response = service
+            .list_alerts("project-1", None, None, "user-1")
+            .await
+            .unwrap();
+
+        assert_eq!(response.limit, 3);
+        assert_eq!(response.alerts.len(), 3);
+        assert_eq!(response.total, 10);
+    }
+}
@@ -1,7 +1,11 @@
 mod alert_channel_service;
 mod alert_rule_service;
 mod alert_service;
+mod notification_preference_service;
+mod org_alert_defaults_service;
 
 pub use alert_channel_service::AlertChannelService;
 pub use alert_rule_service::AlertRuleService;
 pub use alert_service::AlertService;
+pub use notification_preference_service::NotificationPreferenceService;
+pub use org_alert_defaults_service::OrgAlertDefaultsService;
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use crate::modules::alerts::application::dto::{
+    NotificationPreferencesResponse, UpdateNotificationPreferencesRequest,
+};
+use crate::modules::alerts::domain::{
+    AlertDomainError, AlertSeverity, NotificationPreference, NotificationPreferenceRepository,
+};
+use crate::modules::auth::domain::UserId;
+
+pub struct NotificationPreferenceService<NR>
+where
+    NR: NotificationPreferenceRepository,
+{
+    preference_repo: Arc<NR>,
+}
+
+impl<NR> NotificationPreferenceService<NR>
+where
+    NR: NotificationPreferenceRepository,
+{
+    pub fn new(preference_repo: Arc<NR>) -> Self {
+        Self { preference_repo }
+    }
+
+    async fn load(&self, user_id: &UserId) -> Result<NotificationPreference, AlertDomainError> {
+        Ok(self
+            .preference_repo
+            .find_by_user(user_id)
+            .await?
+            .unwrap_or_else(|| NotificationPreference::new(user_id.clone())))
+    }
+
+    fn to_response(&self, preference: &NotificationPreference) -> NotificationPreferencesResponse {
+        NotificationPreferencesResponse {
+            channel_config: preference.channel_config().cloned(),
+            severities: preference
+                .severities()
+                .iter()
+                .map(|s| s.as_str().to_string())
+                .collect(),
+            mute_windows: preference.mute_windows().to_vec(),
+            digest_enabled: preference.digest_enabled(),
+            digest_interval_minutes: preference.digest_interval_minutes(),
+            last_digest_at: preference.last_digest_at(),
+        }
+    }
+
+    /// Get the caller's notification preferences, or the defaults if never set
+    pub async fn get_preferences(
+        &self,
+        user_id: &str,
+    ) -> Result<NotificationPreferencesResponse, AlertDomainError> {
+        let preference = self.load(&UserId::new(user_id.to_string())).await?;
+        Ok(self.to_response(&preference))
+    }
+
+    /// Update the caller's notification preferences
+    pub async fn update_preferences(
+        &self,
+        user_id: &str,
+        req: UpdateNotificationPreferencesRequest,
+    ) -> Result<NotificationPreferencesResponse, AlertDomainError> {
+        let mut preference = self.load(&UserId::new(user_id.to_string())).await?;
+
+        if let Some(channel_config) = req.channel_config {
+            preference.update_channel_config(channel_config)?;
+        }
+
+        if let Some(severities) = req.severities {
+            let severities = severities
+                .iter()
+                .map(|s| AlertSeverity::from_str(s))
+                .collect::<Result<Vec<_>, _>>()?;
+            preference.update_severities(severities);
+        }
+
+        if let Some(mute_windows) = req.mute_windows {
+            preference.update_mute_windows(mute_windows);
+        }
+
+        if req.digest_enabled.is_some() || req.digest_interval_minutes.is_some() {
+            preference.update_digest(
+                req.digest_enabled.unwrap_or(preference.digest_enabled()),
+                req.digest_interval_minutes
+                    .unwrap_or(preference.digest_interval_minutes()),
+            )?;
+        }
+
+        self.preference_repo.save(&preference).await?;
+
+        Ok(self.to_response(&preference))
+    }
+}
@@ -0,0 +1,393 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::modules::alerts::application::dto::{
+    AlertRuleDefinition, OrgAlertDefaultsResponse, UpdateOrgAlertDefaultsRequest,
+};
+use crate::modules::alerts::domain::{
+    AlertChannelId, AlertChannelRepository, AlertDomainError, AlertRuleId, AlertRuleRepository,
+    AlertRuleTemplate, AlertSeverity, OrgAlertDefaults, OrgAlertDefaultsRepository, RuleType,
+    ThresholdOperator,
+};
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::auth::domain::UserId;
+use crate::modules::organizations::domain::{OrgId, OrganizationMemberRepository};
+use crate::modules::projects::application::ports::ProjectCreatedHook;
+use crate::modules::projects::domain::{Project, ProjectDomainError};
+
+/// Manages an organization's alert defaults and applies them to its new projects
+pub struct OrgAlertDefaultsService<DR, CR, RR, MR, ID>
+where
+    DR: OrgAlertDefaultsRepository,
+    CR: AlertChannelRepository,
+    RR: AlertRuleRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    defaults_repo: Arc<DR>,
+    channel_repo: Arc<CR>,
+    rule_repo: Arc<RR>,
+    member_repo: Arc<MR>,
+    id_generator: Arc<ID>,
+}
+
+impl<DR, CR, RR, MR, ID> OrgAlertDefaultsService<DR, CR, RR, MR, ID>
+where
+    DR: OrgAlertDefaultsRepository,
+    CR: AlertChannelRepository,
+    RR: AlertRuleRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    pub fn new(
+        defaults_repo: Arc<DR>,
+        channel_repo: Arc<CR>,
+        rule_repo: Arc<RR>,
+        member_repo: Arc<MR>,
+        id_generator: Arc<ID>,
+    ) -> Self {
+        Self {
+            defaults_repo,
+            channel_repo,
+            rule_repo,
+            member_repo,
+            id_generator,
+        }
+    }
+
+    async fn verify_org_access(
+        &self,
+        org_id: &OrgId,
+        user_id: &str,
+        require_admin: bool,
+    ) -> Result<(), AlertDomainError> {
+        let membership = self
+            .member_repo
+            .find_by_org_and_user(org_id, &UserId::new(user_id.to_string()))
+            .await
+            .map_err(|e| AlertDomainError::InternalError(e.to_string()))?
+            .ok_or(AlertDomainError::NotOrgMember)?;
+
+        if require_admin && !membership.role().can_update_org() {
+            return Err(AlertDomainError::NotAuthorized);
+        }
+
+        Ok(())
+    }
+
+    fn to_response(
+        &self,
+        org_id: &OrgId,
+        defaults: Option<&OrgAlertDefaults>,
+    ) -> OrgAlertDefaultsResponse {
+        OrgAlertDefaultsResponse {
+            org_id: org_id.as_str().to_string(),
+            enabled: defaults.is_some_and(|d| d.enabled()),
+            channel_id: defaults.map(|d| d.channel_id().as_str().to_string()),
+            rules: defaults
+                .map(|d| d.rules().iter().map(to_definition).collect())
+                .unwrap_or_default(),
+            updated_at: defaults.map(|d| d.updated_at()),
+        }
+    }
+
+    /// Get an organization's alert defaults (any member)
+    pub async fn get_defaults(
+        &self,
+        org_id: &str,
+        user_id: &str,
+    ) -> Result<OrgAlertDefaultsResponse, AlertDomainError> {
+        let org_id = OrgId::new(org_id.to_string());
+        self.verify_org_access(&org_id, user_id, false).await?;
+
+        let defaults = self.defaults_repo.find_by_org(&org_id).await?;
+        Ok(self.to_response(&org_id, defaults.as_ref()))
+    }
+
+    /// Replace an organization's alert defaults (admin only)
+    pub async fn update_defaults(
+        &self,
+        org_id: &str,
+        request: UpdateOrgAlertDefaultsRequest,
+        user_id: &str,
+    ) -> Result<OrgAlertDefaultsResponse, AlertDomainError> {
+        let org_id = OrgId::new(org_id.to_string());
+        self.verify_org_access(&org_id, user_id, true).await?;
+
+        // The default channel must be shared by this organization
+        let channel_id = AlertChannelId::new(request.channel_id);
+        self.channel_repo
+            .find_by_id(&channel_id)
+            .await?
+            .filter(|c| {
+                c.organization_id()
+                    .is_some_and(|id| id.as_str() == org_id.as_str())
+            })
+            .ok_or(AlertDomainError::ChannelNotFound)?;
+
+        let rules = request
+            .rules
+            .into_iter()
+            .map(to_template)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let defaults = OrgAlertDefaults::new(
+            org_id.clone(),
+            request.enabled,
+            channel_id,
+            rules,
+            UserId::new(user_id.to_string()),
+        )?;
+        self.defaults_repo.save(&defaults).await?;
+
+        Ok(self.to_response(&org_id, Some(&defaults)))
+    }
+
+    /// Create the organization's default rules in a new project
+    async fn provision(&self, project: &Project) -> Result<(), AlertDomainError> {
+        let Some(defaults) = self
+            .defaults_repo
+            .find_by_org(project.organization_id())
+            .await?
+            .filter(|d| d.enabled())
+        else {
+            return Ok(());
+        };
+
+        for template in defaults.rules() {
+            let rule = template.instantiate(
+                AlertRuleId::new(self.id_generator.generate()),
+                project.id().clone(),
+                defaults.updated_by().clone(),
+                defaults.channel_id(),
+            );
+            self.rule_repo.save(&rule).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<DR, CR, RR, MR, ID> ProjectCreatedHook for OrgAlertDefaultsService<DR, CR, RR, MR, ID>
+where
+    DR: OrgAlertDefaultsRepository,
+    CR: AlertChannelRepository,
+    RR: AlertRuleRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    async fn on_project_created(&self, project: &Project) -> Result<(), ProjectDomainError> {
+        self.provision(project)
+            .await
+            .map_err(|e| ProjectDomainError::InternalError(e.to_string()))
+    }
+}
+
+fn to_template(definition: AlertRuleDefinition) -> Result<AlertRuleTemplate, AlertDomainError> {
+    if !definition.channels.is_empty() {
+        return Err(AlertDomainError::ValidationError(format!(
+            "Rule '{}': default rules notify the default channel; remove 'channels'",
+            definition.name
+        )));
+    }
+
+    Ok(AlertRuleTemplate {
+        name: definition.name.trim().to_string(),
+        description: definition.description,
+        rule_type: RuleType::from_str(&definition.rule_type)?,
+        config: definition.config,
+        threshold_value: definition.threshold_value,
+        threshold_operator: ThresholdOperator::from_str(&definition.threshold_operator)?,
+        time_window_seconds: definition.time_window_seconds,
+        cooldown_seconds: definition.cooldown_seconds,
+        severity: definition
+            .severity
+            .as_deref()
+            .map(AlertSeverity::from_str)
+            .transpose()?
+            .unwrap_or_default(),
+        is_enabled: definition.is_enabled,
+    })
+}
+
+fn to_definition(template: &AlertRuleTemplate) -> AlertRuleDefinition {
+    AlertRuleDefinition {
+        name: template.name.clone(),
+        description: template.description.clone(),
+        rule_type: template.rule_type.as_str().to_string(),
+        config: template.config.clone(),
+        threshold_value: template.threshold_value,
+        threshold_operator: template.threshold_operator.as_str().to_string(),
+        time_window_seconds: template.time_window_seconds,
+        cooldown_seconds: template.cooldown_seconds,
+        severity: (template.severity != AlertSeverity::default())
+            .then(|| template.severity.as_str().to_string()),
+        is_enabled: template.is_enabled,
+        channels: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::modules::alerts::domain::{AlertChannel, ChannelType};
+    use crate::modules::organizations::domain::{
+        OrgName, OrgRole, OrgSlug, Organization, OrganizationRepository,
+    };
+    use crate::modules::projects::application::dto::CreateProjectCommand;
+    use crate::modules::projects::application::services::ProjectService;
+    use crate::modules::projects::domain::ProjectId;
+    use crate::shared::PaginationConfig;
+    use crate::shared::testing::{
+        InMemoryAlertChannelRepository, InMemoryAlertRuleRepository, InMemoryApiKeyRepository,
+        InMemoryMemberRepository, InMemoryOrgAlertDefaultsRepository,
+        InMemoryOrganizationRepository, InMemoryProjectRepository, SequentialIdGenerator,
+    };
+
+    fn rule_definition(name: &str) -> AlertRuleDefinition {
+        AlertRuleDefinition {
+            name: name.to_string(),
+            description: None,
+            rule_type: "error_rate".to_string(),
+            config: json!({"level": "error"}),
+            threshold_value: 10.0,
+            threshold_operator: "gt".to_string(),
+            time_window_seconds: 300,
+            cooldown_seconds: 0,
+            severity: Some("critical".to_string()),
+            is_enabled: true,
+            channels: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_new_project_gets_default_rule_bound_to_default_channel() {
+        let org_repo = Arc::new(InMemoryOrganizationRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        let channel_repo = Arc::new(InMemoryAlertChannelRepository::new());
+        let rule_repo = Arc::new(InMemoryAlertRuleRepository::new());
+        let id_generator = Arc::new(SequentialIdGenerator::new());
+
+        for org_id in ["org-1", "org-2"] {
+            org_repo
+                .save(&Organization::new(
+                    OrgId::new(org_id.to_string()),
+                    OrgName::new("Acme".to_string()).unwrap(),
+                    OrgSlug::generate(&OrgName::new("Acme".to_string()).unwrap(), org_id),
+                ))
+                .await
+                .unwrap();
+            member_repo.seed(org_id, "admin", OrgRole::Admin);
+        }
+        channel_repo.seed(AlertChannel::new_shared(
+            AlertChannelId::new("channel-1".to_string()),
+            OrgId::new("org-1".to_string()),
+            "on-call".to_string(),
+            ChannelType::Webhook,
+            json!({"url": "https://example.com/hook"}),
+        ));
+
+        let defaults_service = Arc::new(OrgAlertDefaultsService::new(
+            Arc::new(InMemoryOrgAlertDefaultsRepository::new()),
+            channel_repo,
+            rule_repo.clone(),
+            member_repo.clone(),
+            id_generator.clone(),
+        ));
+        defaults_service
+            .update_defaults(
+                "org-1",
+                UpdateOrgAlertDefaultsRequest {
+                    enabled: true,
+                    channel_id: "channel-1".to_string(),
+                    rules: vec![rule_definition("High error rate")],
+                },
+                "admin",
+            )
+            .await
+            .unwrap();
+
+        let project_service = ProjectService::new(
+            Arc::new(InMemoryProjectRepository::new()),
+            Arc::new(InMemoryApiKeyRepository::new()),
+            org_repo,
+            member_repo,
+            id_generator,
+            PaginationConfig::default(),
+            None,
+            Vec::new(),
+        )
+        .with_created_hook(defaults_service);
+
+        let create = |org_id: &str| CreateProjectCommand {
+            org_id: org_id.to_string(),
+            name: "checkout".to_string(),
+            description: None,
+            retention_days: None,
+            metrics_retention_days: None,
+            traces_retention_days: None,
+            requesting_user_id: "admin".to_string(),
+        };
+
+        let project = project_service
+            .create_project(create("org-1"))
+            .await
+            .unwrap();
+        let rules = rule_repo
+            .find_by_project(&ProjectId::new(project.id))
+            .await
+            .unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].name(), "High error rate");
+        assert_eq!(rules[0].severity(), AlertSeverity::Critical);
+        assert_eq!(rules[0].channel_ids(), ["channel-1".to_string()]);
+
+        // Orgs that haven't opted in start without alerting
+        let project = project_service
+            .create_project(create("org-2"))
+            .await
+            .unwrap();
+        let rules = rule_repo
+            .find_by_project(&ProjectId::new(project.id))
+            .await
+            .unwrap();
+        assert!(rules.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_default_channel_must_be_shared_by_the_org() {
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        let channel_repo = Arc::new(InMemoryAlertChannelRepository::new());
+        member_repo.seed("org-1", "admin", OrgRole::Admin);
+        channel_repo.seed(AlertChannel::new(
+            AlertChannelId::new("channel-1".to_string()),
+            ProjectId::new("project-1".to_string()),
+            "on-call".to_string(),
+            ChannelType::Webhook,
+            json!({"url": "https://example.com/hook"}),
+        ));
+        let service = OrgAlertDefaultsService::new(
+            Arc::new(InMemoryOrgAlertDefaultsRepository::new()),
+            channel_repo,
+            Arc::new(InMemoryAlertRuleRepository::new()),
+            member_repo,
+            Arc::new(SequentialIdGenerator::new()),
+        );
+
+        let result = service
+            .update_defaults(
+                "org-1",
+                UpdateOrgAlertDefaultsRequest {
+                    enabled: true,
+                    channel_id: "channel-1".to_string(),
+                    rules: vec![rule_definition("High error rate")],
+                },
+                "admin",
+            )
+            .await;
+        assert!(matches!(result, Err(AlertDomainError::ChannelNotFound)));
+    }
+}
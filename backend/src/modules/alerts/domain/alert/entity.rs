@@ -1,7 +1,8 @@
 use chrono::{DateTime, Utc};
-use serde_json::Value;
+use serde_json::{Value, json};
 
 use crate::modules::alerts::domain::alert_rule::AlertRuleId;
+use crate::modules::alerts::domain::errors::AlertDomainError;
 use crate::modules::projects::domain::ProjectId;
 
 /// Alert ID
@@ -156,4 +157,37 @@ impl Alert {
         self.status = AlertStatus::Resolved;
         self.resolved_at = Some(Utc::now());
     }
+
+    /// Record the outcome of notifying a channel under `metadata.notifications`
+    pub fn record_notification(
+        &mut self,
+        channel_id: &str,
+        result: &Result<(), AlertDomainError>,
+        at: DateTime<Utc>,
+    ) {
+        let entry = match result {
+            Ok(()) => json!({ "channel_id": channel_id, "status": "sent", "at": at }),
+            Err(e) => json!({
+                "channel_id": channel_id,
+                "status": "failed",
+                "error": e.to_string(),
+                "retryable": e.is_retryable(),
+                "at": at,
+            }),
+        };
+
+        let metadata = self.metadata.get_or_insert_with(|| json!({}));
+        if !metadata.is_object() {
+            *metadata = json!({ "value": metadata.take() });
+        }
+        let notifications = metadata
+            .as_object_mut()
+            .unwrap()
+            .entry("notifications")
+            .or_insert_with(|| json!([]));
+        match notifications.as_array_mut() {
+            Some(list) => list.push(entry),
+            None => *notifications = json!([entry]),
+        }
+    }
 }
@@ -1,8 +1,9 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 use super::entity::{Alert, AlertId};
-use crate::modules::alerts::domain::alert_rule::AlertRuleId;
 use crate::modules::alerts::domain::AlertDomainError;
+use crate::modules::alerts::domain::alert_rule::AlertRuleId;
 use crate::modules::projects::domain::ProjectId;
 
 #[async_trait]
@@ -39,4 +40,12 @@ pub trait AlertRepository: Send + Sync {
 
     /// Count alerts for a project
     async fn count_by_project(&self, project_id: &ProjectId) -> Result<i64, AlertDomainError>;
+
+    /// Delete up to `limit` alerts resolved before `before`, returning how
+    /// many were deleted. Firing alerts are never deleted.
+    async fn delete_resolved_before(
+        &self,
+        before: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<u64, AlertDomainError>;
 }
@@ -1,18 +1,25 @@
 use chrono::{DateTime, Utc};
 use serde_json::Value;
 
-use super::value_objects::{AlertChannelId, ChannelType};
+use super::value_objects::{AlertChannelId, ChannelHealth, ChannelScope, ChannelType};
+use crate::modules::alerts::domain::AlertDomainError;
+use crate::modules::organizations::domain::OrgId;
 use crate::modules::projects::domain::ProjectId;
 
 /// Alert Channel - notification destination
 #[derive(Debug, Clone)]
 pub struct AlertChannel {
     id: AlertChannelId,
-    project_id: ProjectId,
+    scope: ChannelScope,
     name: String,
     channel_type: ChannelType,
     config: Value,
     is_enabled: bool,
+    /// Deliveries failed in a row since the last success
+    consecutive_failures: u32,
+    last_delivery_error: Option<String>,
+    /// Set when the channel was disabled for failing too often
+    auto_disabled_at: Option<DateTime<Utc>>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
 }
@@ -24,15 +31,51 @@ impl AlertChannel {
         name: String,
         channel_type: ChannelType,
         config: Value,
+    ) -> Self {
+        Self::with_scope(
+            id,
+            ChannelScope::Project(project_id),
+            name,
+            channel_type,
+            config,
+        )
+    }
+
+    /// Create a channel owned by an organization and shared with all of its projects
+    pub fn new_shared(
+        id: AlertChannelId,
+        org_id: OrgId,
+        name: String,
+        channel_type: ChannelType,
+        config: Value,
+    ) -> Self {
+        Self::with_scope(
+            id,
+            ChannelScope::Organization(org_id),
+            name,
+            channel_type,
+            config,
+        )
+    }
+
+    fn with_scope(
+        id: AlertChannelId,
+        scope: ChannelScope,
+        name: String,
+        channel_type: ChannelType,
+        config: Value,
     ) -> Self {
         let now = Utc::now();
         Self {
             id,
-            project_id,
+            scope,
             name,
             channel_type,
             config,
             is_enabled: true,
+            consecutive_failures: 0,
+            last_delivery_error: None,
+            auto_disabled_at: None,
             created_at: now,
             updated_at: now,
         }
@@ -42,21 +85,27 @@ impl AlertChannel {
     #[allow(clippy::too_many_arguments)]
     pub fn from_db(
         id: AlertChannelId,
-        project_id: ProjectId,
+        scope: ChannelScope,
         name: String,
         channel_type: ChannelType,
         config: Value,
         is_enabled: bool,
+        consecutive_failures: u32,
+        last_delivery_error: Option<String>,
+        auto_disabled_at: Option<DateTime<Utc>>,
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
     ) -> Self {
         Self {
             id,
-            project_id,
+            scope,
             name,
             channel_type,
             config,
             is_enabled,
+            consecutive_failures,
+            last_delivery_error,
+            auto_disabled_at,
             created_at,
             updated_at,
         }
@@ -67,8 +116,26 @@ impl AlertChannel {
         &self.id
     }
 
-    pub fn project_id(&self) -> &ProjectId {
-        &self.project_id
+    /// Owning project, or None for an organization channel
+    pub fn project_id(&self) -> Option<&ProjectId> {
+        match &self.scope {
+            ChannelScope::Project(project_id) => Some(project_id),
+            ChannelScope::Organization(_) => None,
+        }
+    }
+
+    /// Owning organization, or None for a project channel
+    pub fn organization_id(&self) -> Option<&OrgId> {
+        match &self.scope {
+            ChannelScope::Organization(org_id) => Some(org_id),
+            ChannelScope::Project(_) => None,
+        }
+    }
+
+    /// Whether the channel is owned by the given project
+    pub fn belongs_to_project(&self, project_id: &ProjectId) -> bool {
+        self.project_id()
+            .is_some_and(|id| id.as_str() == project_id.as_str())
     }
 
     pub fn name(&self) -> &str {
@@ -87,6 +154,28 @@ impl AlertChannel {
         self.is_enabled
     }
 
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    pub fn last_delivery_error(&self) -> Option<&str> {
+        self.last_delivery_error.as_deref()
+    }
+
+    pub fn auto_disabled_at(&self) -> Option<DateTime<Utc>> {
+        self.auto_disabled_at
+    }
+
+    pub fn health(&self) -> ChannelHealth {
+        if !self.is_enabled {
+            ChannelHealth::Disabled
+        } else if self.consecutive_failures > 0 {
+            ChannelHealth::Failing
+        } else {
+            ChannelHealth::Healthy
+        }
+    }
+
     pub fn created_at(&self) -> DateTime<Utc> {
         self.created_at
     }
@@ -106,7 +195,11 @@ impl AlertChannel {
         self.updated_at = Utc::now();
     }
 
+    /// Re-enabling a disabled channel also clears its failure history
     pub fn enable(&mut self) {
+        if !self.is_enabled {
+            self.clear_failures();
+        }
         self.is_enabled = true;
         self.updated_at = Utc::now();
     }
@@ -115,4 +208,84 @@ impl AlertChannel {
         self.is_enabled = false;
         self.updated_at = Utc::now();
     }
+
+    /// Record the outcome of a delivery. A success resets the failure count;
+    /// once `max_failures` deliveries (0 = no limit) fail in a row the channel
+    /// is disabled. Returns true when this delivery disabled it.
+    pub fn record_delivery(
+        &mut self,
+        result: &Result<(), AlertDomainError>,
+        max_failures: u32,
+        now: DateTime<Utc>,
+    ) -> bool {
+        match result {
+            Ok(()) => {
+                self.consecutive_failures = 0;
+                self.last_delivery_error = None;
+                false
+            }
+            Err(e) => {
+                self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+                self.last_delivery_error = Some(e.to_string());
+                let limit_reached = max_failures > 0 && self.consecutive_failures >= max_failures;
+                if self.is_enabled && limit_reached {
+                    self.is_enabled = false;
+                    self.auto_disabled_at = Some(now);
+                    self.updated_at = now;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    /// Clear the failure history and re-enable the channel
+    pub fn reset_health(&mut self) {
+        self.clear_failures();
+        self.enable();
+    }
+
+    fn clear_failures(&mut self) {
+        self.consecutive_failures = 0;
+        self.last_delivery_error = None;
+        self.auto_disabled_at = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel() -> AlertChannel {
+        AlertChannel::new(
+            AlertChannelId::new("channel-1".to_string()),
+            ProjectId::new("project-1".to_string()),
+            "Ops".to_string(),
+            ChannelType::Webhook,
+            serde_json::json!({"url": "https://example.com/hook"}),
+        )
+    }
+
+    #[test]
+    fn test_success_resets_failure_count() {
+        let mut channel = channel();
+        let failed = Err(AlertDomainError::WebhookFailed("status 500".to_string()));
+        assert!(!channel.record_delivery(&failed, 3, Utc::now()));
+        assert!(!channel.record_delivery(&failed, 3, Utc::now()));
+        assert_eq!(channel.health(), ChannelHealth::Failing);
+        assert_eq!(channel.consecutive_failures(), 2);
+
+        assert!(!channel.record_delivery(&Ok(()), 3, Utc::now()));
+        assert_eq!(channel.health(), ChannelHealth::Healthy);
+        assert_eq!(channel.last_delivery_error(), None);
+
+        // The count starts over, so two more failures stay under the limit
+        assert!(!channel.record_delivery(&failed, 3, Utc::now()));
+        assert!(!channel.record_delivery(&failed, 3, Utc::now()));
+        assert!(channel.is_enabled());
+        assert!(channel.record_delivery(&failed, 3, Utc::now()));
+        assert_eq!(channel.health(), ChannelHealth::Disabled);
+        assert!(channel.auto_disabled_at().is_some());
+    }
 }
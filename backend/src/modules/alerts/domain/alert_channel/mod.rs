@@ -1,7 +1,11 @@
 mod entity;
 mod repository;
+mod resolution;
 mod value_objects;
 
 pub use entity::AlertChannel;
 pub use repository::AlertChannelRepository;
-pub use value_objects::{AlertChannelId, ChannelType};
+pub use resolution::resolve_project_channels;
+pub use value_objects::{
+    AlertChannelId, ChannelHealth, ChannelScope, ChannelType, WebhookPayloadVersion,
+};
@@ -3,6 +3,7 @@ use async_trait::async_trait;
 use super::entity::AlertChannel;
 use super::value_objects::AlertChannelId;
 use crate::modules::alerts::domain::AlertDomainError;
+use crate::modules::organizations::domain::OrgId;
 use crate::modules::projects::domain::ProjectId;
 
 #[async_trait]
@@ -22,12 +23,15 @@ pub trait AlertChannelRepository: Send + Sync {
         project_id: &ProjectId,
     ) -> Result<Vec<AlertChannel>, AlertDomainError>;
 
-    /// Find channels by IDs
-    async fn find_by_ids(
+    /// Find all channels an organization shares with its projects
+    async fn find_by_organization(
         &self,
-        ids: &[String],
+        org_id: &OrgId,
     ) -> Result<Vec<AlertChannel>, AlertDomainError>;
 
+    /// Find channels by IDs
+    async fn find_by_ids(&self, ids: &[String]) -> Result<Vec<AlertChannel>, AlertDomainError>;
+
     /// Update a channel
     async fn update(&self, channel: &AlertChannel) -> Result<(), AlertDomainError>;
 
@@ -41,4 +45,12 @@ pub trait AlertChannelRepository: Send + Sync {
         name: &str,
         exclude_id: Option<&AlertChannelId>,
     ) -> Result<bool, AlertDomainError>;
+
+    /// Check if shared channel name exists in organization
+    async fn org_name_exists(
+        &self,
+        org_id: &OrgId,
+        name: &str,
+        exclude_id: Option<&AlertChannelId>,
+    ) -> Result<bool, AlertDomainError>;
 }
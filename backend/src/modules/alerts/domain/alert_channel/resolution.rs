@@ -0,0 +1,22 @@
+use std::collections::HashSet;
+
+use super::entity::AlertChannel;
+
+/// Channels a project can bind rules to: its own channels plus the org's
+/// shared channels. A project channel shadows a shared channel with the same name.
+pub fn resolve_project_channels(
+    project_channels: Vec<AlertChannel>,
+    shared_channels: Vec<AlertChannel>,
+) -> Vec<AlertChannel> {
+    let shadowed: HashSet<String> = project_channels
+        .iter()
+        .map(|c| c.name().to_string())
+        .collect();
+
+    let inherited: Vec<AlertChannel> = shared_channels
+        .into_iter()
+        .filter(|c| !shadowed.contains(c.name()))
+        .collect();
+
+    project_channels.into_iter().chain(inherited).collect()
+}
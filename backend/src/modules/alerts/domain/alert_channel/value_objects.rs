@@ -1,4 +1,8 @@
+use serde_json::Value;
+
 use crate::modules::alerts::domain::AlertDomainError;
+use crate::modules::organizations::domain::OrgId;
+use crate::modules::projects::domain::ProjectId;
 
 /// Alert Channel ID
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -44,3 +48,69 @@ impl std::fmt::Display for ChannelType {
         write!(f, "{}", self.as_str())
     }
 }
+
+/// Webhook Payload Version - the JSON schema a webhook channel receives,
+/// set as `payload_version` in the channel config
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookPayloadVersion {
+    /// The flat payload sent before payloads were versioned
+    V1,
+    /// An envelope with `version`, `event` and `data`, alerts nesting their rule and project
+    V2,
+}
+
+impl WebhookPayloadVersion {
+    /// Version given to new channels
+    pub const LATEST: Self = Self::V2;
+
+    /// Version configured for a channel; channels created before versioning have none and get V1
+    pub fn from_config(config: &Value) -> Result<Self, AlertDomainError> {
+        match config.get("payload_version") {
+            None | Some(Value::Null) => Ok(Self::V1),
+            Some(version) => match version.as_u64() {
+                Some(1) => Ok(Self::V1),
+                Some(2) => Ok(Self::V2),
+                _ => Err(AlertDomainError::InvalidChannelConfig(format!(
+                    "Unsupported payload_version: {}. Supported versions: 1, 2",
+                    version
+                ))),
+            },
+        }
+    }
+
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            Self::V1 => 1,
+            Self::V2 => 2,
+        }
+    }
+}
+
+/// Channel Health - how recent deliveries to a channel went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelHealth {
+    /// The last delivery succeeded, or none was attempted yet
+    Healthy,
+    /// Recent deliveries failed, but the channel is still in use
+    Failing,
+    /// Disabled by a user or after too many failed deliveries
+    Disabled,
+}
+
+impl ChannelHealth {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Healthy => "healthy",
+            Self::Failing => "failing",
+            Self::Disabled => "disabled",
+        }
+    }
+}
+
+/// Channel Scope - who owns a channel.
+/// Organization channels are shared with every project in the org.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChannelScope {
+    Project(ProjectId),
+    Organization(OrgId),
+}
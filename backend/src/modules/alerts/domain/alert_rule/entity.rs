@@ -1,7 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde_json::Value;
 
-use super::value_objects::{AlertRuleId, RuleType, ThresholdOperator};
+use super::value_objects::{
+    AlertRuleId, AlertSchedule, AlertSeverity, RuleType, ThresholdOperator,
+};
 use crate::modules::auth::domain::UserId;
 use crate::modules::projects::domain::ProjectId;
 
@@ -17,6 +19,9 @@ pub struct AlertRule {
     threshold_value: f64,
     threshold_operator: ThresholdOperator,
     time_window_seconds: i32,
+    /// Seconds after a resolve during which the rule won't fire again
+    cooldown_seconds: i32,
+    severity: AlertSeverity,
     is_enabled: bool,
     last_evaluated_at: Option<DateTime<Utc>>,
     last_triggered_at: Option<DateTime<Utc>>,
@@ -25,6 +30,12 @@ pub struct AlertRule {
     created_by: UserId,
     /// Channel IDs associated with this rule
     channel_ids: Vec<String>,
+    /// Rule whose firing alert suppresses this rule's notifications
+    parent_rule_id: Option<AlertRuleId>,
+    /// Also evaluate as soon as matching logs are ingested (critical rules only)
+    evaluate_on_ingest: bool,
+    /// When the rule may notify; unset notifies at any time
+    schedule: Option<AlertSchedule>,
 }
 
 impl AlertRule {
@@ -52,6 +63,8 @@ impl AlertRule {
             threshold_value,
             threshold_operator,
             time_window_seconds,
+            cooldown_seconds: 0,
+            severity: AlertSeverity::default(),
             is_enabled: true,
             last_evaluated_at: None,
             last_triggered_at: None,
@@ -59,6 +72,9 @@ impl AlertRule {
             updated_at: now,
             created_by,
             channel_ids: Vec::new(),
+            parent_rule_id: None,
+            evaluate_on_ingest: false,
+            schedule: None,
         }
     }
 
@@ -74,6 +90,8 @@ impl AlertRule {
         threshold_value: f64,
         threshold_operator: ThresholdOperator,
         time_window_seconds: i32,
+        cooldown_seconds: i32,
+        severity: AlertSeverity,
         is_enabled: bool,
         last_evaluated_at: Option<DateTime<Utc>>,
         last_triggered_at: Option<DateTime<Utc>>,
@@ -81,6 +99,9 @@ impl AlertRule {
         updated_at: DateTime<Utc>,
         created_by: UserId,
         channel_ids: Vec<String>,
+        parent_rule_id: Option<AlertRuleId>,
+        evaluate_on_ingest: bool,
+        schedule: Option<AlertSchedule>,
     ) -> Self {
         Self {
             id,
@@ -92,6 +113,8 @@ impl AlertRule {
             threshold_value,
             threshold_operator,
             time_window_seconds,
+            cooldown_seconds,
+            severity,
             is_enabled,
             last_evaluated_at,
             last_triggered_at,
@@ -99,6 +122,9 @@ impl AlertRule {
             updated_at,
             created_by,
             channel_ids,
+            parent_rule_id,
+            evaluate_on_ingest,
+            schedule,
         }
     }
 
@@ -139,6 +165,14 @@ impl AlertRule {
         self.time_window_seconds
     }
 
+    pub fn cooldown_seconds(&self) -> i32 {
+        self.cooldown_seconds
+    }
+
+    pub fn severity(&self) -> AlertSeverity {
+        self.severity
+    }
+
     pub fn is_enabled(&self) -> bool {
         self.is_enabled
     }
@@ -167,6 +201,23 @@ impl AlertRule {
         &self.channel_ids
     }
 
+    pub fn parent_rule_id(&self) -> Option<&AlertRuleId> {
+        self.parent_rule_id.as_ref()
+    }
+
+    pub fn evaluate_on_ingest(&self) -> bool {
+        self.evaluate_on_ingest
+    }
+
+    pub fn schedule(&self) -> Option<&AlertSchedule> {
+        self.schedule.as_ref()
+    }
+
+    /// Whether the rule's schedule lets it notify at `at`
+    pub fn notifies_at(&self, at: DateTime<Utc>) -> bool {
+        self.schedule.as_ref().is_none_or(|s| s.is_active(at))
+    }
+
     // Mutators
     pub fn update_name(&mut self, name: String) {
         self.name = name;
@@ -178,6 +229,11 @@ impl AlertRule {
         self.updated_at = Utc::now();
     }
 
+    pub fn update_rule_type(&mut self, rule_type: RuleType) {
+        self.rule_type = rule_type;
+        self.updated_at = Utc::now();
+    }
+
     pub fn update_config(&mut self, config: Value) {
         self.config = config;
         self.updated_at = Utc::now();
@@ -194,6 +250,16 @@ impl AlertRule {
         self.updated_at = Utc::now();
     }
 
+    pub fn update_cooldown(&mut self, seconds: i32) {
+        self.cooldown_seconds = seconds;
+        self.updated_at = Utc::now();
+    }
+
+    pub fn update_severity(&mut self, severity: AlertSeverity) {
+        self.severity = severity;
+        self.updated_at = Utc::now();
+    }
+
     pub fn enable(&mut self) {
         self.is_enabled = true;
         self.updated_at = Utc::now();
@@ -217,6 +283,21 @@ impl AlertRule {
         self.updated_at = Utc::now();
     }
 
+    pub fn set_parent_rule(&mut self, parent_rule_id: Option<AlertRuleId>) {
+        self.parent_rule_id = parent_rule_id;
+        self.updated_at = Utc::now();
+    }
+
+    pub fn set_evaluate_on_ingest(&mut self, evaluate_on_ingest: bool) {
+        self.evaluate_on_ingest = evaluate_on_ingest;
+        self.updated_at = Utc::now();
+    }
+
+    pub fn set_schedule(&mut self, schedule: Option<AlertSchedule>) {
+        self.schedule = schedule;
+        self.updated_at = Utc::now();
+    }
+
     /// Check if the threshold condition is met
     pub fn evaluate(&self, actual_value: f64) -> bool {
         self.threshold_operator
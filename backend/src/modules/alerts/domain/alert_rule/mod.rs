@@ -4,4 +4,7 @@ mod value_objects;
 
 pub use entity::AlertRule;
 pub use repository::AlertRuleRepository;
-pub use value_objects::{AlertRuleId, RuleType, ThresholdOperator};
+pub use value_objects::{
+    AlertRuleId, AlertSchedule, AlertSeverity, LogRatioConfig, LogSelector, RuleType,
+    ThresholdOperator, TraceErrorRateConfig, ZeroDenominator,
+};
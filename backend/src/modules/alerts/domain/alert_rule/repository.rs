@@ -19,6 +19,9 @@ pub trait AlertRuleRepository: Send + Sync {
         project_id: &ProjectId,
     ) -> Result<Vec<AlertRule>, AlertDomainError>;
 
+    /// Count rules in a project, enabled or not
+    async fn count_by_project(&self, project_id: &ProjectId) -> Result<i64, AlertDomainError>;
+
     /// Find all enabled rules (for background evaluation)
     async fn find_all_enabled(&self) -> Result<Vec<AlertRule>, AlertDomainError>;
 
@@ -44,8 +47,6 @@ pub trait AlertRuleRepository: Send + Sync {
     ) -> Result<(), AlertDomainError>;
 
     /// Get channel IDs for a rule
-    async fn get_channel_ids(
-        &self,
-        rule_id: &AlertRuleId,
-    ) -> Result<Vec<String>, AlertDomainError>;
+    async fn get_channel_ids(&self, rule_id: &AlertRuleId)
+    -> Result<Vec<String>, AlertDomainError>;
 }
@@ -1,4 +1,11 @@
+use chrono::{DateTime, Datelike, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
 use crate::modules::alerts::domain::AlertDomainError;
+use crate::modules::logging::domain::LogLevel;
+use crate::modules::traces::domain::ErrorRateUnit;
+use crate::shared::{Tz, parse_timezone};
 
 /// Alert Rule ID
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -23,6 +30,10 @@ pub enum RuleType {
     LogCount,
     /// Logs matching a pattern
     PatternMatch,
+    /// Percentage of logs matching one filter among those matching another
+    LogRatio,
+    /// Percentage of error spans, or traces, of a service
+    TraceErrorRate,
 }
 
 impl RuleType {
@@ -31,8 +42,10 @@ impl RuleType {
             "error_rate" => Ok(Self::ErrorRate),
             "log_count" => Ok(Self::LogCount),
             "pattern_match" => Ok(Self::PatternMatch),
+            "log_ratio" => Ok(Self::LogRatio),
+            "trace_error_rate" => Ok(Self::TraceErrorRate),
             _ => Err(AlertDomainError::InvalidRuleType(format!(
-                "Unknown rule type: {}. Valid types: error_rate, log_count, pattern_match",
+                "Unknown rule type: {}. Valid types: error_rate, log_count, pattern_match, log_ratio, trace_error_rate",
                 s
             ))),
         }
@@ -43,6 +56,8 @@ impl RuleType {
             Self::ErrorRate => "error_rate",
             Self::LogCount => "log_count",
             Self::PatternMatch => "pattern_match",
+            Self::LogRatio => "log_ratio",
+            Self::TraceErrorRate => "trace_error_rate",
         }
     }
 }
@@ -53,6 +68,222 @@ impl std::fmt::Display for RuleType {
     }
 }
 
+/// Logs one side of a `log_ratio` rule counts; unset criteria match every log
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogSelector {
+    pub levels: Option<Vec<String>>,
+    pub source: Option<String>,
+    /// Case-insensitive substring of the message
+    pub pattern: Option<String>,
+}
+
+impl LogSelector {
+    fn is_empty(&self) -> bool {
+        self.levels.is_none() && self.source.is_none() && self.pattern.is_none()
+    }
+}
+
+/// What a `log_ratio` rule does when no log matches its denominator
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZeroDenominator {
+    /// Treat the window as healthy
+    #[default]
+    NoFire,
+    /// Fire, e.g. when silence itself is a problem
+    Fire,
+}
+
+/// Config of a `log_ratio` rule: the count of `numerator` logs as a
+/// percentage of the count of `denominator` logs is compared to the
+/// threshold. The numerator is normally a narrower filter than the
+/// denominator, e.g. errors of a source among all its logs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogRatioConfig {
+    pub numerator: LogSelector,
+    /// Defaults to every log of the project
+    pub denominator: LogSelector,
+    pub on_zero_denominator: ZeroDenominator,
+}
+
+impl LogRatioConfig {
+    pub fn from_config(config: &Value) -> Result<Self, AlertDomainError> {
+        let parsed: Self = serde_json::from_value(config.clone()).map_err(|e| {
+            AlertDomainError::ValidationError(format!("Invalid log_ratio config: {}", e))
+        })?;
+        if parsed.numerator.is_empty() {
+            return Err(AlertDomainError::ValidationError(
+                "log_ratio config needs a numerator with levels, source or pattern".to_string(),
+            ));
+        }
+        for level in [&parsed.numerator, &parsed.denominator]
+            .into_iter()
+            .flat_map(|side| side.levels.iter().flatten())
+        {
+            LogLevel::from_str(level).map_err(|_| {
+                AlertDomainError::ValidationError(format!(
+                    "Invalid log_ratio config: unknown level '{}'",
+                    level
+                ))
+            })?;
+        }
+        Ok(parsed)
+    }
+}
+
+/// Config of a `trace_error_rate` rule: error spans, or traces, of a service
+/// as a percentage of all of them is compared to the threshold
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TraceErrorRateConfig {
+    /// Defaults to every service of the project
+    pub service: Option<String>,
+    pub unit: ErrorRateUnit,
+    /// Fewest spans (or traces) a window needs before the rule can fire, so
+    /// one failed request in a quiet window doesn't read as a 100% error rate
+    pub min_count: i64,
+}
+
+impl Default for TraceErrorRateConfig {
+    fn default() -> Self {
+        Self {
+            service: None,
+            unit: ErrorRateUnit::default(),
+            min_count: 10,
+        }
+    }
+}
+
+impl TraceErrorRateConfig {
+    pub fn from_config(config: &Value) -> Result<Self, AlertDomainError> {
+        let parsed: Self = serde_json::from_value(config.clone()).map_err(|e| {
+            AlertDomainError::ValidationError(format!("Invalid trace_error_rate config: {}", e))
+        })?;
+        if parsed.min_count < 0 {
+            return Err(AlertDomainError::ValidationError(
+                "Invalid trace_error_rate config: min_count cannot be negative".to_string(),
+            ));
+        }
+        Ok(parsed)
+    }
+}
+
+/// Local time range on some days of the week. A window ending before it
+/// starts wraps past midnight and belongs to the day it starts on, e.g.
+/// Friday 22:00-06:00 runs into Saturday morning.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleWindow {
+    pub days: Vec<Weekday>,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl ScheduleWindow {
+    fn contains(&self, day: Weekday, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.days.contains(&day) && self.start <= time && time < self.end
+        } else {
+            (self.days.contains(&day) && time >= self.start)
+                || (self.days.contains(&day.pred()) && time < self.end)
+        }
+    }
+}
+
+/// When a rule may notify, as windows of wall-clock time in a timezone.
+/// Outside every window breaches are still recorded as alerts, but nobody
+/// is notified.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertSchedule {
+    /// IANA name such as "Europe/Paris"; windows follow its DST changes
+    pub timezone: String,
+    pub windows: Vec<ScheduleWindow>,
+}
+
+impl AlertSchedule {
+    pub fn from_value(value: &Value) -> Result<Self, AlertDomainError> {
+        let invalid = |msg: String| AlertDomainError::ValidationError(msg);
+        let schedule: Self = serde_json::from_value(value.clone())
+            .map_err(|e| invalid(format!("Invalid schedule: {}", e)))?;
+        if parse_timezone(&schedule.timezone).is_none() {
+            return Err(invalid(format!(
+                "Invalid schedule timezone '{}': use an IANA name like Europe/Paris",
+                schedule.timezone
+            )));
+        }
+        if schedule.windows.is_empty() {
+            return Err(invalid("Schedule needs at least one window".to_string()));
+        }
+        for window in &schedule.windows {
+            if window.days.is_empty() {
+                return Err(invalid(
+                    "Schedule windows need at least one day".to_string(),
+                ));
+            }
+            if window.start == window.end {
+                return Err(invalid(format!(
+                    "Schedule window {}-{} is empty",
+                    window.start, window.end
+                )));
+            }
+        }
+        Ok(schedule)
+    }
+
+    fn tz(&self) -> Tz {
+        parse_timezone(&self.timezone).unwrap_or(Tz::UTC)
+    }
+
+    /// Whether `at` falls inside one of the windows, read in local time
+    pub fn is_active(&self, at: DateTime<Utc>) -> bool {
+        let local = at.with_timezone(&self.tz());
+        self.windows
+            .iter()
+            .any(|window| window.contains(local.weekday(), local.time()))
+    }
+}
+
+/// Alert Severity - how urgently an alert needs attention.
+/// Critical alerts always notify immediately, bypassing digests and mute windows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AlertSeverity {
+    Info,
+    #[default]
+    Warning,
+    Critical,
+}
+
+impl AlertSeverity {
+    pub fn from_str(s: &str) -> Result<Self, AlertDomainError> {
+        match s.to_lowercase().as_str() {
+            "info" => Ok(Self::Info),
+            "warning" => Ok(Self::Warning),
+            "critical" => Ok(Self::Critical),
+            _ => Err(AlertDomainError::ValidationError(format!(
+                "Unknown severity: {}. Valid severities: info, warning, critical",
+                s
+            ))),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+impl std::fmt::Display for AlertSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// Threshold Operator - how to compare the value
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ThresholdOperator {
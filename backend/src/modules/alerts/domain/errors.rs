@@ -50,6 +50,9 @@ pub enum AlertDomainError {
     #[error("Rule name already exists: {0}")]
     RuleNameExists(String),
 
+    #[error("Project has reached its limit of {0} alert rules")]
+    RuleLimitExceeded(u32),
+
     #[error("Channel name already exists: {0}")]
     ChannelNameExists(String),
 
@@ -59,6 +62,16 @@ pub enum AlertDomainError {
     #[error("Webhook request failed: {0}")]
     WebhookFailed(String),
 
+    #[error("Webhook request timed out: {0}")]
+    WebhookTimeout(String),
+
     #[error("Internal error: {0}")]
     InternalError(String),
 }
+
+impl AlertDomainError {
+    /// Whether a failed notification may succeed if sent again
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::WebhookFailed(_) | Self::WebhookTimeout(_))
+    }
+}
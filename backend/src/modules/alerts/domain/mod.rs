@@ -2,10 +2,20 @@ pub mod alert;
 pub mod alert_channel;
 pub mod alert_rule;
 mod errors;
+pub mod notification_preference;
+pub mod org_alert_defaults;
 
 pub use alert::{Alert, AlertId, AlertRepository, AlertStatus};
-pub use alert_channel::{AlertChannel, AlertChannelId, AlertChannelRepository, ChannelType};
+pub use alert_channel::{
+    AlertChannel, AlertChannelId, AlertChannelRepository, ChannelHealth, ChannelScope, ChannelType,
+    WebhookPayloadVersion, resolve_project_channels,
+};
 pub use alert_rule::{
-    AlertRule, AlertRuleId, AlertRuleRepository, RuleType, ThresholdOperator,
+    AlertRule, AlertRuleId, AlertRuleRepository, AlertSchedule, AlertSeverity, LogRatioConfig,
+    LogSelector, RuleType, ThresholdOperator, TraceErrorRateConfig, ZeroDenominator,
 };
 pub use errors::AlertDomainError;
+pub use notification_preference::{
+    Delivery, DigestEntry, MuteWindow, NotificationPreference, NotificationPreferenceRepository,
+};
+pub use org_alert_defaults::{AlertRuleTemplate, OrgAlertDefaults, OrgAlertDefaultsRepository};
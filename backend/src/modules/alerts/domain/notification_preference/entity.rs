@@ -0,0 +1,270 @@
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+
+use super::value_objects::{Delivery, MuteWindow};
+use crate::modules::alerts::domain::{AlertDomainError, AlertSeverity};
+use crate::modules::auth::domain::UserId;
+
+/// Digest interval for users who turn digests on without choosing one
+pub const DEFAULT_DIGEST_INTERVAL_MINUTES: i32 = 60;
+
+/// Notification Preference - how alerts reach one user, across all their organizations
+#[derive(Debug, Clone)]
+pub struct NotificationPreference {
+    user_id: UserId,
+    /// The user's own webhook, shaped like a webhook channel config. None turns
+    /// personal notifications off.
+    channel_config: Option<Value>,
+    /// Severities the user is notified about; critical alerts are always sent
+    severities: Vec<AlertSeverity>,
+    mute_windows: Vec<MuteWindow>,
+    /// Batch non-critical alerts into a periodic summary
+    digest_enabled: bool,
+    digest_interval_minutes: i32,
+    last_digest_at: Option<DateTime<Utc>>,
+    updated_at: DateTime<Utc>,
+}
+
+impl NotificationPreference {
+    /// Preferences a user starts with: no personal channel, every severity, no digest
+    pub fn new(user_id: UserId) -> Self {
+        Self {
+            user_id,
+            channel_config: None,
+            severities: vec![
+                AlertSeverity::Info,
+                AlertSeverity::Warning,
+                AlertSeverity::Critical,
+            ],
+            mute_windows: Vec::new(),
+            digest_enabled: false,
+            digest_interval_minutes: DEFAULT_DIGEST_INTERVAL_MINUTES,
+            last_digest_at: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Reconstruct from database
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_db(
+        user_id: UserId,
+        channel_config: Option<Value>,
+        severities: Vec<AlertSeverity>,
+        mute_windows: Vec<MuteWindow>,
+        digest_enabled: bool,
+        digest_interval_minutes: i32,
+        last_digest_at: Option<DateTime<Utc>>,
+        updated_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            user_id,
+            channel_config,
+            severities,
+            mute_windows,
+            digest_enabled,
+            digest_interval_minutes,
+            last_digest_at,
+            updated_at,
+        }
+    }
+
+    // Getters
+    pub fn user_id(&self) -> &UserId {
+        &self.user_id
+    }
+
+    pub fn channel_config(&self) -> Option<&Value> {
+        self.channel_config.as_ref()
+    }
+
+    pub fn severities(&self) -> &[AlertSeverity] {
+        &self.severities
+    }
+
+    pub fn mute_windows(&self) -> &[MuteWindow] {
+        &self.mute_windows
+    }
+
+    pub fn digest_enabled(&self) -> bool {
+        self.digest_enabled
+    }
+
+    pub fn digest_interval_minutes(&self) -> i32 {
+        self.digest_interval_minutes
+    }
+
+    pub fn last_digest_at(&self) -> Option<DateTime<Utc>> {
+        self.last_digest_at
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    // Mutators
+    pub fn update_channel_config(
+        &mut self,
+        channel_config: Option<Value>,
+    ) -> Result<(), AlertDomainError> {
+        if let Some(config) = &channel_config
+            && config.get("url").and_then(|v| v.as_str()).is_none()
+        {
+            return Err(AlertDomainError::InvalidChannelConfig(
+                "Personal webhook requires 'url' in config".to_string(),
+            ));
+        }
+        self.channel_config = channel_config;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    pub fn update_severities(&mut self, mut severities: Vec<AlertSeverity>) {
+        severities.sort();
+        severities.dedup();
+        self.severities = severities;
+        self.updated_at = Utc::now();
+    }
+
+    pub fn update_mute_windows(&mut self, mute_windows: Vec<MuteWindow>) {
+        self.mute_windows = mute_windows;
+        self.updated_at = Utc::now();
+    }
+
+    pub fn update_digest(
+        &mut self,
+        enabled: bool,
+        interval_minutes: i32,
+    ) -> Result<(), AlertDomainError> {
+        if interval_minutes <= 0 {
+            return Err(AlertDomainError::ValidationError(
+                "digest_interval_minutes must be positive".to_string(),
+            ));
+        }
+        self.digest_enabled = enabled;
+        self.digest_interval_minutes = interval_minutes;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    pub fn mark_digest_sent(&mut self, at: DateTime<Utc>) {
+        self.last_digest_at = Some(at);
+    }
+
+    /// Whether `at` falls in one of the user's mute windows
+    pub fn is_muted(&self, at: DateTime<Utc>) -> bool {
+        let time = at.time();
+        self.mute_windows.iter().any(|w| w.contains(time))
+    }
+
+    /// How an alert of `severity` triggered at `at` reaches this user. Critical
+    /// alerts go out immediately; others respect the severity filter, and are held
+    /// for the digest when it's on, or dropped during a mute window when it's off.
+    pub fn delivery(&self, severity: AlertSeverity, at: DateTime<Utc>) -> Delivery {
+        if self.channel_config.is_none() {
+            return Delivery::Skip;
+        }
+        if severity == AlertSeverity::Critical {
+            return Delivery::Immediate;
+        }
+        if !self.severities.contains(&severity) {
+            return Delivery::Skip;
+        }
+        if self.digest_enabled {
+            Delivery::Digest
+        } else if self.is_muted(at) {
+            Delivery::Skip
+        } else {
+            Delivery::Immediate
+        }
+    }
+
+    /// Whether the next digest should go out at `now`: the interval has passed
+    /// since the last one and the user isn't in a mute window
+    pub fn digest_due(&self, now: DateTime<Utc>) -> bool {
+        self.digest_enabled
+            && !self.is_muted(now)
+            && self.last_digest_at.is_none_or(|last| {
+                now >= last + Duration::minutes(self.digest_interval_minutes as i64)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+    use serde_json::json;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_006_400, 0)
+            .unwrap()
+            .date_naive()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+            .and_utc()
+    }
+
+    fn preference() -> NotificationPreference {
+        let mut preference = NotificationPreference::new(UserId::new("user-1".to_string()));
+        preference
+            .update_channel_config(Some(json!({ "url": "https://example.com/me" })))
+            .unwrap();
+        preference
+    }
+
+    #[test]
+    fn test_mute_window_wraps_midnight() {
+        let window = MuteWindow {
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+        };
+        assert!(window.contains(NaiveTime::from_hms_opt(23, 30, 0).unwrap()));
+        assert!(window.contains(NaiveTime::from_hms_opt(6, 59, 0).unwrap()));
+        assert!(!window.contains(NaiveTime::from_hms_opt(7, 0, 0).unwrap()));
+        assert!(!window.contains(NaiveTime::from_hms_opt(12, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_delivery_respects_severity_and_mute_windows() {
+        let mut preference = preference();
+        preference.update_severities(vec![AlertSeverity::Warning]);
+        preference.update_mute_windows(vec![MuteWindow {
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+        }]);
+
+        assert_eq!(
+            preference.delivery(AlertSeverity::Warning, at(12, 0)),
+            Delivery::Immediate
+        );
+        assert_eq!(
+            preference.delivery(AlertSeverity::Info, at(12, 0)),
+            Delivery::Skip
+        );
+        assert_eq!(
+            preference.delivery(AlertSeverity::Warning, at(23, 0)),
+            Delivery::Skip
+        );
+        // Critical ignores both the severity filter and the mute window
+        assert_eq!(
+            preference.delivery(AlertSeverity::Critical, at(23, 0)),
+            Delivery::Immediate
+        );
+    }
+
+    #[test]
+    fn test_digest_due_after_interval_outside_mute_windows() {
+        let mut preference = preference();
+        preference.update_digest(true, 30).unwrap();
+        preference.update_mute_windows(vec![MuteWindow {
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(7, 0, 0).unwrap(),
+        }]);
+        preference.mark_digest_sent(at(12, 0));
+
+        assert!(!preference.digest_due(at(12, 29)));
+        assert!(preference.digest_due(at(12, 30)));
+        assert!(!preference.digest_due(at(23, 0)));
+        assert!(preference.update_digest(true, 0).is_err());
+    }
+}
@@ -0,0 +1,7 @@
+mod entity;
+mod repository;
+mod value_objects;
+
+pub use entity::NotificationPreference;
+pub use repository::NotificationPreferenceRepository;
+pub use value_objects::{Delivery, DigestEntry, MuteWindow};
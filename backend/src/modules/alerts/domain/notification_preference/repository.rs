@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+
+use super::entity::NotificationPreference;
+use super::value_objects::DigestEntry;
+use crate::modules::alerts::domain::AlertDomainError;
+use crate::modules::auth::domain::UserId;
+
+#[async_trait]
+pub trait NotificationPreferenceRepository: Send + Sync {
+    /// Find a user's preferences
+    async fn find_by_user(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Option<NotificationPreference>, AlertDomainError>;
+
+    /// Find the preferences of any of the given users that have set them
+    async fn find_by_users(
+        &self,
+        user_ids: &[UserId],
+    ) -> Result<Vec<NotificationPreference>, AlertDomainError>;
+
+    /// Find every user with digest mode on
+    async fn find_digest_enabled(&self) -> Result<Vec<NotificationPreference>, AlertDomainError>;
+
+    /// Create or replace a user's preferences
+    async fn save(&self, preference: &NotificationPreference) -> Result<(), AlertDomainError>;
+
+    /// Hold an alert for a user's next digest
+    async fn queue_digest_entry(&self, entry: &DigestEntry) -> Result<(), AlertDomainError>;
+
+    /// Remove and return a user's held alerts, oldest first
+    async fn take_digest_entries(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<DigestEntry>, AlertDomainError>;
+}
@@ -0,0 +1,46 @@
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::modules::alerts::domain::AlertSeverity;
+use crate::modules::auth::domain::UserId;
+
+/// A daily window (UTC) during which a user's non-critical notifications are held.
+/// A window ending before it starts wraps past midnight, e.g. 22:00-07:00.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MuteWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl MuteWindow {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// How one alert reaches one user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// Send right away
+    Immediate,
+    /// Hold for the user's next digest
+    Digest,
+    /// Don't notify the user
+    Skip,
+}
+
+/// An alert held for a user's next digest
+#[derive(Debug, Clone)]
+pub struct DigestEntry {
+    pub user_id: UserId,
+    pub alert_id: String,
+    pub rule_name: String,
+    pub project_name: String,
+    pub severity: AlertSeverity,
+    pub message: String,
+    pub triggered_at: DateTime<Utc>,
+}
@@ -0,0 +1,165 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::modules::alerts::domain::{
+    AlertChannelId, AlertDomainError, AlertRule, AlertRuleId, AlertSeverity, RuleType,
+    ThresholdOperator,
+};
+use crate::modules::auth::domain::UserId;
+use crate::modules::organizations::domain::OrgId;
+use crate::modules::projects::domain::ProjectId;
+
+/// A rule created in every new project of an organization
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertRuleTemplate {
+    pub name: String,
+    pub description: Option<String>,
+    pub rule_type: RuleType,
+    pub config: Value,
+    pub threshold_value: f64,
+    pub threshold_operator: ThresholdOperator,
+    pub time_window_seconds: i32,
+    pub cooldown_seconds: i32,
+    pub severity: AlertSeverity,
+    pub is_enabled: bool,
+}
+
+impl AlertRuleTemplate {
+    /// Build this template's rule for a project, bound to `channel_id`
+    pub fn instantiate(
+        &self,
+        id: AlertRuleId,
+        project_id: ProjectId,
+        created_by: UserId,
+        channel_id: &AlertChannelId,
+    ) -> AlertRule {
+        let mut rule = AlertRule::new(
+            id,
+            project_id,
+            self.name.clone(),
+            self.description.clone(),
+            self.rule_type.clone(),
+            self.config.clone(),
+            self.threshold_value,
+            self.threshold_operator.clone(),
+            self.time_window_seconds,
+            created_by,
+        );
+        rule.update_cooldown(self.cooldown_seconds);
+        rule.update_severity(self.severity);
+        if !self.is_enabled {
+            rule.disable();
+        }
+        rule.set_channel_ids(vec![channel_id.as_str().to_string()]);
+        rule
+    }
+}
+
+/// Org Alert Defaults - alerting every new project in an organization starts with.
+/// Opt-in: nothing is created for new projects until an admin turns them on.
+#[derive(Debug, Clone)]
+pub struct OrgAlertDefaults {
+    org_id: OrgId,
+    enabled: bool,
+    /// Org-shared channel the default rules notify
+    channel_id: AlertChannelId,
+    rules: Vec<AlertRuleTemplate>,
+    /// Admin who last changed the defaults; default rules are created on their behalf
+    updated_by: UserId,
+    updated_at: DateTime<Utc>,
+}
+
+impl OrgAlertDefaults {
+    pub fn new(
+        org_id: OrgId,
+        enabled: bool,
+        channel_id: AlertChannelId,
+        rules: Vec<AlertRuleTemplate>,
+        updated_by: UserId,
+    ) -> Result<Self, AlertDomainError> {
+        validate_rules(&rules)?;
+        Ok(Self {
+            org_id,
+            enabled,
+            channel_id,
+            rules,
+            updated_by,
+            updated_at: Utc::now(),
+        })
+    }
+
+    /// Reconstruct from database
+    pub fn from_db(
+        org_id: OrgId,
+        enabled: bool,
+        channel_id: AlertChannelId,
+        rules: Vec<AlertRuleTemplate>,
+        updated_by: UserId,
+        updated_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            org_id,
+            enabled,
+            channel_id,
+            rules,
+            updated_by,
+            updated_at,
+        }
+    }
+
+    // Getters
+    pub fn org_id(&self) -> &OrgId {
+        &self.org_id
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn channel_id(&self) -> &AlertChannelId {
+        &self.channel_id
+    }
+
+    pub fn rules(&self) -> &[AlertRuleTemplate] {
+        &self.rules
+    }
+
+    pub fn updated_by(&self) -> &UserId {
+        &self.updated_by
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+}
+
+fn validate_rules(rules: &[AlertRuleTemplate]) -> Result<(), AlertDomainError> {
+    let mut names = HashSet::new();
+    for rule in rules {
+        if rule.name.trim().is_empty() {
+            return Err(AlertDomainError::InvalidRuleName(
+                "Rule name cannot be empty".to_string(),
+            ));
+        }
+        if !names.insert(rule.name.as_str()) {
+            return Err(AlertDomainError::ValidationError(format!(
+                "Duplicate default rule name: {}",
+                rule.name
+            )));
+        }
+        if rule.time_window_seconds <= 0 {
+            return Err(AlertDomainError::ValidationError(format!(
+                "Rule '{}': time_window_seconds must be positive",
+                rule.name
+            )));
+        }
+        if rule.cooldown_seconds < 0 {
+            return Err(AlertDomainError::ValidationError(format!(
+                "Rule '{}': cooldown_seconds cannot be negative",
+                rule.name
+            )));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,5 @@
+mod entity;
+mod repository;
+
+pub use entity::{AlertRuleTemplate, OrgAlertDefaults};
+pub use repository::OrgAlertDefaultsRepository;
@@ -0,0 +1,17 @@
+use async_trait::async_trait;
+
+use super::entity::OrgAlertDefaults;
+use crate::modules::alerts::domain::AlertDomainError;
+use crate::modules::organizations::domain::OrgId;
+
+#[async_trait]
+pub trait OrgAlertDefaultsRepository: Send + Sync {
+    /// Find an organization's alert defaults
+    async fn find_by_org(
+        &self,
+        org_id: &OrgId,
+    ) -> Result<Option<OrgAlertDefaults>, AlertDomainError>;
+
+    /// Create or replace an organization's alert defaults
+    async fn save(&self, defaults: &OrgAlertDefaults) -> Result<(), AlertDomainError>;
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::modules::alerts::domain::{AlertDomainError, AlertRule};
+
+/// A rule is stale once it goes this many evaluation intervals without being evaluated
+pub const STALE_INTERVALS: i64 = 3;
+
+/// How the evaluator has been doing on one rule
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleEvaluationStats {
+    pub rule_id: String,
+    pub project_id: String,
+    pub last_evaluated_at: DateTime<Utc>,
+    pub last_duration_ms: f64,
+    pub evaluations: u64,
+    pub errors: u64,
+    pub last_error: Option<String>,
+    /// How often the rule is expected to be evaluated
+    pub interval_secs: i64,
+}
+
+impl RuleEvaluationStats {
+    /// Whether the rule has gone too long without being evaluated
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        now - self.last_evaluated_at > Duration::seconds(self.interval_secs * STALE_INTERVALS)
+    }
+}
+
+/// Per-rule evaluation health, shared between the evaluator and the admin endpoints.
+/// Only the replica running the evaluator has entries.
+#[derive(Debug, Default)]
+pub struct EvaluatorHealth {
+    rules: Mutex<HashMap<String, RuleEvaluationStats>>,
+}
+
+impl EvaluatorHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one evaluation of `rule`
+    pub fn record(
+        &self,
+        rule: &AlertRule,
+        evaluated_at: DateTime<Utc>,
+        interval_secs: i64,
+        duration: StdDuration,
+        error: Option<&AlertDomainError>,
+    ) {
+        let mut rules = self.rules.lock().unwrap();
+        let stats = rules
+            .entry(rule.id().as_str().to_string())
+            .or_insert_with(|| RuleEvaluationStats {
+                rule_id: rule.id().as_str().to_string(),
+                project_id: rule.project_id().as_str().to_string(),
+                last_evaluated_at: evaluated_at,
+                last_duration_ms: 0.0,
+                evaluations: 0,
+                errors: 0,
+                last_error: None,
+                interval_secs,
+            });
+        stats.last_evaluated_at = evaluated_at;
+        stats.last_duration_ms = duration.as_secs_f64() * 1000.0;
+        stats.evaluations += 1;
+        stats.interval_secs = interval_secs;
+        if let Some(error) = error {
+            stats.errors += 1;
+            stats.last_error = Some(error.to_string());
+        }
+    }
+
+    pub fn get(&self, rule_id: &str) -> Option<RuleEvaluationStats> {
+        self.rules.lock().unwrap().get(rule_id).cloned()
+    }
+
+    /// Stats for every rule evaluated so far, ordered by rule id
+    pub fn snapshot(&self) -> Vec<RuleEvaluationStats> {
+        let mut stats: Vec<_> = self.rules.lock().unwrap().values().cloned().collect();
+        stats.sort_by(|a, b| a.rule_id.cmp(&b.rule_id));
+        stats
+    }
+
+    /// Render the stats in the Prometheus text exposition format
+    pub fn render_prometheus(&self, now: DateTime<Utc>) -> String {
+        let stats = self.snapshot();
+        let mut out = String::new();
+        let mut family =
+            |name: &str, kind: &str, help: &str, value: &dyn Fn(&RuleEvaluationStats) -> f64| {
+                let _ = writeln!(out, "# HELP {} {}", name, help);
+                let _ = writeln!(out, "# TYPE {} {}", name, kind);
+                for s in &stats {
+                    let _ = writeln!(
+                        out,
+                        "{}{{rule_id=\"{}\",project_id=\"{}\"}} {}",
+                        name,
+                        escape_label(&s.rule_id),
+                        escape_label(&s.project_id),
+                        value(s)
+                    );
+                }
+            };
+
+        family(
+            "altenia_alert_rule_last_evaluation_timestamp_seconds",
+            "gauge",
+            "When the rule was last evaluated",
+            &|s| s.last_evaluated_at.timestamp() as f64,
+        );
+        family(
+            "altenia_alert_rule_evaluation_duration_seconds",
+            "gauge",
+            "How long the rule's last evaluation took",
+            &|s| s.last_duration_ms / 1000.0,
+        );
+        family(
+            "altenia_alert_rule_evaluations_total",
+            "counter",
+            "Evaluations of the rule since the evaluator started",
+            &|s| s.evaluations as f64,
+        );
+        family(
+            "altenia_alert_rule_evaluation_errors_total",
+            "counter",
+            "Failed evaluations of the rule since the evaluator started",
+            &|s| s.errors as f64,
+        );
+        family(
+            "altenia_alert_rule_stale",
+            "gauge",
+            "1 if the rule has not been evaluated within its expected interval",
+            &|s| if s.is_stale(now) { 1.0 } else { 0.0 },
+        );
+        out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,220 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, RwLock};
+
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use crate::modules::alerts::domain::{AlertRule, AlertRuleId, LogRatioConfig, RuleType};
+use crate::modules::logging::domain::{LogEntry, LogIngestObserver};
+use crate::modules::projects::domain::ProjectId;
+
+/// Rules evaluated on ingest, by project. Ingested logs are matched against
+/// them and each matching rule is queued for the evaluator once, however
+/// many logs match before the evaluator picks it up.
+pub struct IngestRuleTrigger {
+    rules: RwLock<HashMap<String, Vec<AlertRule>>>,
+    /// Rules waiting in the queue
+    queued: Mutex<HashSet<String>>,
+    sender: mpsc::UnboundedSender<AlertRule>,
+    receiver: Mutex<Option<mpsc::UnboundedReceiver<AlertRule>>>,
+}
+
+impl IngestRuleTrigger {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Self {
+            rules: RwLock::new(HashMap::new()),
+            queued: Mutex::new(HashSet::new()),
+            sender,
+            receiver: Mutex::new(Some(receiver)),
+        }
+    }
+
+    /// Match logs against the enabled rules among `rules` that evaluate on ingest
+    pub fn set_rules(&self, rules: &[AlertRule]) {
+        let mut by_project: HashMap<String, Vec<AlertRule>> = HashMap::new();
+        for rule in rules
+            .iter()
+            .filter(|rule| rule.is_enabled() && rule.evaluate_on_ingest())
+        {
+            by_project
+                .entry(rule.project_id().as_str().to_string())
+                .or_default()
+                .push(rule.clone());
+        }
+        *self.rules.write().unwrap() = by_project;
+    }
+
+    /// The queue of matched rules; only the first caller gets it
+    pub(super) fn take_receiver(&self) -> Option<mpsc::UnboundedReceiver<AlertRule>> {
+        self.receiver.lock().unwrap().take()
+    }
+
+    /// Called as a rule leaves the queue, so further matches queue it again
+    pub(super) fn dequeued(&self, rule_id: &AlertRuleId) {
+        self.queued.lock().unwrap().remove(rule_id.as_str());
+    }
+}
+
+impl Default for IngestRuleTrigger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogIngestObserver for IngestRuleTrigger {
+    fn logs_ingested(&self, project_id: &ProjectId, logs: &[LogEntry]) {
+        let rules = self.rules.read().unwrap();
+        let Some(rules) = rules.get(project_id.as_str()) else {
+            return;
+        };
+        for rule in rules {
+            if logs.iter().any(|log| matches_log(rule, log))
+                && self
+                    .queued
+                    .lock()
+                    .unwrap()
+                    .insert(rule.id().as_str().to_string())
+            {
+                // The receiver only goes away with the evaluator; nothing to queue for then
+                let _ = self.sender.send(rule.clone());
+            }
+        }
+    }
+}
+
+/// Whether the log is one the rule counts, using the same config as its
+/// scheduled evaluation
+fn matches_log(rule: &AlertRule, log: &LogEntry) -> bool {
+    let config = rule.config();
+    let levels: Option<Vec<&str>> = config
+        .get("levels")
+        .and_then(Value::as_array)
+        .map(|levels| levels.iter().filter_map(Value::as_str).collect());
+    let has_level = |levels: &[&str]| {
+        levels
+            .iter()
+            .any(|level| level.eq_ignore_ascii_case(log.level().as_str()))
+    };
+
+    match rule.rule_type() {
+        RuleType::ErrorRate => has_level(levels.as_deref().unwrap_or(&["error", "fatal"])),
+        RuleType::LogCount => {
+            let source = config.get("source").and_then(Value::as_str);
+            levels.as_deref().is_none_or(has_level)
+                && source.is_none_or(|source| log.source() == Some(source))
+        }
+        RuleType::PatternMatch => {
+            let pattern = config
+                .get("pattern")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            !pattern.is_empty()
+                && log
+                    .message()
+                    .to_lowercase()
+                    .contains(&pattern.to_lowercase())
+        }
+        // Span-based rules don't react to logs
+        RuleType::TraceErrorRate => false,
+        // Only logs that can raise the ratio are worth an evaluation
+        RuleType::LogRatio => LogRatioConfig::from_config(config).is_ok_and(|ratio| {
+            let numerator = ratio.numerator;
+            numerator.levels.as_deref().is_none_or(|levels| {
+                levels
+                    .iter()
+                    .any(|level| level.eq_ignore_ascii_case(log.level().as_str()))
+            }) && numerator
+                .source
+                .as_deref()
+                .is_none_or(|source| log.source() == Some(source))
+                && numerator.pattern.as_deref().is_none_or(|pattern| {
+                    log.message()
+                        .to_lowercase()
+                        .contains(&pattern.to_lowercase())
+                })
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::modules::alerts::domain::{AlertSeverity, ThresholdOperator};
+    use crate::modules::auth::domain::UserId;
+    use crate::modules::logging::domain::{LogId, LogLevel};
+
+    fn rule(id: &str, rule_type: RuleType, config: Value) -> AlertRule {
+        let mut rule = AlertRule::new(
+            AlertRuleId::new(id.to_string()),
+            ProjectId::new("project-1".to_string()),
+            id.to_string(),
+            None,
+            rule_type,
+            config,
+            0.0,
+            ThresholdOperator::GreaterThan,
+            60,
+            UserId::new("user-1".to_string()),
+        );
+        rule.update_severity(AlertSeverity::Critical);
+        rule.set_evaluate_on_ingest(true);
+        rule
+    }
+
+    fn log(level: LogLevel, message: &str) -> LogEntry {
+        LogEntry::new(
+            LogId::new("log-1".to_string()),
+            ProjectId::new("project-1".to_string()),
+            level,
+            message.to_string(),
+            None,
+            Some("api".to_string()),
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_matching_rules_are_queued_once() {
+        let trigger = IngestRuleTrigger::new();
+        let mut scheduled_only = rule("scheduled", RuleType::LogCount, json!({}));
+        scheduled_only.set_evaluate_on_ingest(false);
+        trigger.set_rules(&[
+            rule("errors", RuleType::ErrorRate, json!({})),
+            rule(
+                "timeouts",
+                RuleType::PatternMatch,
+                json!({"pattern": "TIMEOUT"}),
+            ),
+            rule("worker", RuleType::LogCount, json!({"source": "worker"})),
+            scheduled_only,
+        ]);
+        let mut queue = trigger.take_receiver().unwrap();
+        let project_id = ProjectId::new("project-1".to_string());
+
+        trigger.logs_ingested(&project_id, &[log(LogLevel::Info, "all good")]);
+        assert!(queue.try_recv().is_err());
+
+        let logs = [
+            log(LogLevel::Error, "upstream timeout"),
+            log(LogLevel::Error, "upstream timeout"),
+        ];
+        trigger.logs_ingested(&project_id, &logs);
+        // Still queued: a second batch doesn't queue them again
+        trigger.logs_ingested(&project_id, &logs);
+        let mut queued = Vec::new();
+        while let Ok(rule) = queue.try_recv() {
+            queued.push(rule.name().to_string());
+        }
+        queued.sort();
+        assert_eq!(queued, vec!["errors", "timeouts"]);
+
+        trigger.dequeued(&AlertRuleId::new("errors".to_string()));
+        trigger.logs_ingested(&project_id, &logs);
+        assert_eq!(queue.try_recv().unwrap().name(), "errors");
+    }
+}
@@ -1,3 +1,7 @@
+mod health;
+mod ingest_trigger;
 mod rule_evaluator;
 
+pub use health::{EvaluatorHealth, RuleEvaluationStats};
+pub use ingest_trigger::IngestRuleTrigger;
 pub use rule_evaluator::RuleEvaluator;
@@ -1,17 +1,46 @@
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde_json::json;
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tokio::time;
 
-use crate::modules::alerts::application::dto::WebhookPayload;
+use super::health::EvaluatorHealth;
+use super::ingest_trigger::IngestRuleTrigger;
+use crate::modules::alerts::application::dto::{
+    ChannelDisabledPayload, GroupedAlertPayload, WebhookPayload,
+};
 use crate::modules::alerts::domain::{
-    Alert, AlertChannelRepository, AlertDomainError, AlertId, AlertRepository, AlertRule,
-    AlertRuleId, AlertRuleRepository, RuleType, ThresholdOperator,
+    Alert, AlertChannel, AlertChannelRepository, AlertDomainError, AlertId, AlertRepository,
+    AlertRule, AlertRuleId, AlertRuleRepository, LogRatioConfig, LogSelector, RuleType,
+    ThresholdOperator, TraceErrorRateConfig, ZeroDenominator,
 };
-use crate::modules::alerts::infrastructure::notifiers::Notifier;
+use crate::modules::alerts::infrastructure::notifiers::{Notifier, UserAlertNotifier};
 use crate::modules::auth::application::ports::IdGenerator;
 use crate::modules::logging::domain::{LogFilters, LogLevel, LogRepository};
 use crate::modules::projects::domain::{ProjectId, ProjectRepository};
+use crate::modules::traces::domain::{SpansRepository, TraceFilters};
+
+/// Most missed aligned windows evaluated to catch up after downtime or a slow
+/// tick; older ones are skipped
+const MAX_CATCH_UP_WINDOWS: i32 = 100;
+
+/// A fired alert held back to be sent along with others sharing its grouping key
+struct PendingAlert {
+    alert_id: AlertId,
+    payload: WebhookPayload,
+    channels: Vec<AlertChannel>,
+}
+
+/// Fired alerts of one project whose rules share a grouping key value
+struct AlertGroup {
+    project_id: String,
+    project_name: String,
+    key: String,
+    value: String,
+    flush_at: DateTime<Utc>,
+    alerts: Vec<PendingAlert>,
+}
 
 pub struct RuleEvaluator<RR, AR, CR, LR, PR, ID, N>
 where
@@ -31,6 +60,28 @@ where
     id_generator: Arc<ID>,
     notifier: Arc<N>,
     evaluation_interval_secs: u64,
+    /// Evaluate tumbling windows aligned to each rule's time window instead of a sliding window
+    aligned_windows: bool,
+    /// End of the last window evaluated per rule (aligned mode)
+    window_ends: Mutex<HashMap<String, DateTime<Utc>>>,
+    /// When each rule's last alert was resolved, for the post-resolve cooldown
+    resolved_at: Mutex<HashMap<String, DateTime<Utc>>>,
+    /// Per-rule evaluation times, durations and errors
+    health: Arc<EvaluatorHealth>,
+    /// Personal notifications to organization members, when enabled
+    user_notifier: Option<Arc<dyn UserAlertNotifier>>,
+    /// Span storage for trace error rate rules
+    spans_repo: Option<Arc<dyn SpansRepository>>,
+    /// Fired alerts waiting for their group's window to close, by project and grouping value
+    alert_groups: Mutex<HashMap<(String, String), AlertGroup>>,
+    /// Failed deliveries in a row that disable a channel (0 = never)
+    channel_failure_limit: u32,
+    /// Rules queued by ingest for evaluation outside the schedule
+    ingest_trigger: Option<Arc<IngestRuleTrigger>>,
+    /// Least time between two ingest-triggered evaluations of a rule
+    ingest_min_interval: time::Duration,
+    /// When each rule was last evaluated because of ingest
+    ingest_evaluated_at: Mutex<HashMap<String, Instant>>,
 }
 
 impl<RR, AR, CR, LR, PR, ID, N> RuleEvaluator<RR, AR, CR, LR, PR, ID, N>
@@ -62,9 +113,62 @@ where
             id_generator,
             notifier,
             evaluation_interval_secs,
+            aligned_windows: false,
+            window_ends: Mutex::new(HashMap::new()),
+            resolved_at: Mutex::new(HashMap::new()),
+            health: Arc::new(EvaluatorHealth::new()),
+            user_notifier: None,
+            spans_repo: None,
+            alert_groups: Mutex::new(HashMap::new()),
+            channel_failure_limit: 0,
+            ingest_trigger: None,
+            ingest_min_interval: time::Duration::from_secs(5),
+            ingest_evaluated_at: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Record evaluation health in `health`, e.g. to share it with the admin endpoints
+    pub fn with_health(mut self, health: Arc<EvaluatorHealth>) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Also notify the project's organization members per their notification preferences
+    pub fn with_user_notifications(mut self, user_notifier: Arc<dyn UserAlertNotifier>) -> Self {
+        self.user_notifier = Some(user_notifier);
+        self
+    }
+
+    /// Evaluate trace error rate rules against these spans
+    pub fn with_spans(mut self, spans_repo: Arc<dyn SpansRepository>) -> Self {
+        self.spans_repo = Some(spans_repo);
+        self
+    }
+
+    /// Evaluate aligned, non-overlapping windows so each record is counted once
+    pub fn with_aligned_windows(mut self, aligned_windows: bool) -> Self {
+        self.aligned_windows = aligned_windows;
+        self
+    }
+
+    /// Disable a channel once this many deliveries to it fail in a row (0 = never)
+    pub fn with_channel_failure_limit(mut self, channel_failure_limit: u32) -> Self {
+        self.channel_failure_limit = channel_failure_limit;
+        self
+    }
+
+    /// Also evaluate rules that `trigger` queues as matching logs are ingested,
+    /// each at most once per `min_interval`
+    pub fn with_ingest_evaluation(
+        mut self,
+        trigger: Arc<IngestRuleTrigger>,
+        min_interval: time::Duration,
+    ) -> Self {
+        self.ingest_trigger = Some(trigger);
+        self.ingest_min_interval = min_interval;
+        self
+    }
+
     /// Start the evaluation loop (runs forever)
     pub async fn start(self: Arc<Self>) {
         let mut interval = time::interval(time::Duration::from_secs(self.evaluation_interval_secs));
@@ -83,13 +187,97 @@ where
         }
     }
 
+    /// Evaluate rules queued by ingest as they arrive (runs forever). Unlike the
+    /// scheduled loop this runs wherever logs are ingested, not only on the leader.
+    /// Returns at once without ingest evaluation or if the loop already runs.
+    pub async fn start_ingest_evaluation(self: Arc<Self>) {
+        let Some(trigger) = self.ingest_trigger.clone() else {
+            return;
+        };
+        let Some(mut queue) = trigger.take_receiver() else {
+            return;
+        };
+        let mut refresh = time::interval(time::Duration::from_secs(self.evaluation_interval_secs));
+        let mut retry = time::interval(self.ingest_min_interval);
+        // Rules matched again while throttled, evaluated once the throttle lapses
+        let mut deferred: HashMap<String, AlertRule> = HashMap::new();
+
+        tracing::info!(
+            min_interval_ms = self.ingest_min_interval.as_millis() as u64,
+            "Starting ingest-triggered alert evaluation"
+        );
+
+        loop {
+            tokio::select! {
+                _ = refresh.tick() => match self.rule_repo.find_all_enabled().await {
+                    Ok(rules) => trigger.set_rules(&rules),
+                    Err(e) => tracing::error!(error = %e, "Error loading ingest-evaluated rules"),
+                },
+                Some(rule) = queue.recv() => {
+                    trigger.dequeued(rule.id());
+                    if self.ingest_throttled(&rule) {
+                        deferred.insert(rule.id().as_str().to_string(), rule);
+                    } else {
+                        self.evaluate_ingested(&rule).await;
+                    }
+                }
+                _ = retry.tick() => {
+                    let ready: Vec<String> = deferred
+                        .values()
+                        .filter(|rule| !self.ingest_throttled(rule))
+                        .map(|rule| rule.id().as_str().to_string())
+                        .collect();
+                    for rule in ready.iter().filter_map(|id| deferred.remove(id)) {
+                        self.evaluate_ingested(&rule).await;
+                    }
+                    self.flush_alert_groups(Utc::now()).await;
+                }
+            }
+        }
+    }
+
+    /// Whether the rule was evaluated because of ingest too recently to be again
+    fn ingest_throttled(&self, rule: &AlertRule) -> bool {
+        self.ingest_evaluated_at
+            .lock()
+            .unwrap()
+            .get(rule.id().as_str())
+            .is_some_and(|at| at.elapsed() < self.ingest_min_interval)
+    }
+
+    /// Evaluate a rule after matching logs were ingested, over the time window
+    /// ending now. This only fires alerts; resolving them is left to the schedule.
+    async fn evaluate_ingested(&self, rule: &AlertRule) {
+        self.ingest_evaluated_at
+            .lock()
+            .unwrap()
+            .insert(rule.id().as_str().to_string(), Instant::now());
+
+        let now = Utc::now();
+        let window = Duration::seconds((rule.time_window_seconds() as i64).max(1));
+        let result = match self.measure(rule, now - window, None).await {
+            Ok((value, true)) => self.fire(rule, value, now).await,
+            Ok((_, false)) => Ok(()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            tracing::warn!(
+                rule_id = %rule.id().as_str(),
+                error = %e,
+                "Error evaluating rule on ingest"
+            );
+        }
+    }
+
     async fn evaluate_all_rules(&self) -> Result<(), AlertDomainError> {
         let rules = self.rule_repo.find_all_enabled().await?;
 
         tracing::debug!(count = rules.len(), "Evaluating alert rules");
 
         for rule in rules {
-            if let Err(e) = self.evaluate_rule(&rule).await {
+            let now = Utc::now();
+            self.warn_if_stale(&rule, now);
+            if let Err(e) = self.evaluate_and_record(&rule, now).await {
                 tracing::warn!(
                     rule_id = %rule.id().as_str(),
                     error = %e,
@@ -98,39 +286,145 @@ where
             }
         }
 
+        self.flush_alert_groups(Utc::now()).await;
+
         Ok(())
     }
 
-    async fn evaluate_rule(&self, rule: &AlertRule) -> Result<(), AlertDomainError> {
-        let now = Utc::now();
-        let time_window = Duration::seconds(rule.time_window_seconds() as i64);
-        let start_time = now - time_window;
-
-        // Evaluate based on rule type
-        let (current_value, should_trigger) = match rule.rule_type() {
-            RuleType::ErrorRate => self.evaluate_error_rate(rule, start_time).await?,
-            RuleType::LogCount => self.evaluate_log_count(rule, start_time).await?,
-            RuleType::PatternMatch => self.evaluate_pattern_match(rule, start_time).await?,
+    /// Evaluate a rule and record how the evaluation went
+    async fn evaluate_and_record(
+        &self,
+        rule: &AlertRule,
+        now: DateTime<Utc>,
+    ) -> Result<(), AlertDomainError> {
+        let started = Instant::now();
+        let result = self.evaluate_rule_at(rule, now).await;
+        self.health.record(
+            rule,
+            now,
+            self.evaluation_interval_secs as i64,
+            started.elapsed(),
+            result.as_ref().err(),
+        );
+        result
+    }
+
+    /// Warn when a rule's last evaluation is several intervals old: the evaluator is falling behind
+    fn warn_if_stale(&self, rule: &AlertRule, now: DateTime<Utc>) {
+        if let Some(stats) = self.health.get(rule.id().as_str())
+            && stats.is_stale(now)
+        {
+            tracing::warn!(
+                rule_id = %rule.id().as_str(),
+                last_evaluated_at = %stats.last_evaluated_at,
+                interval_secs = stats.interval_secs,
+                "Alert rule not evaluated within its interval; the evaluator is falling behind"
+            );
+        }
+    }
+
+    /// Window `[start, end)` to evaluate at `now`, or None if the current window was already evaluated.
+    /// Aligned windows follow on from the last one evaluated, so missed windows are caught up
+    /// one at a time. Sliding mode looks back one time window with no end bound.
+    fn evaluation_window(
+        &self,
+        rule: &AlertRule,
+        now: DateTime<Utc>,
+    ) -> Option<(DateTime<Utc>, Option<DateTime<Utc>>)> {
+        let window_secs = (rule.time_window_seconds() as i64).max(1);
+        let time_window = Duration::seconds(window_secs);
+        if !self.aligned_windows {
+            return Some((now - time_window, None));
+        }
+
+        // Latest complete window, aligned to multiples of the time window since the epoch
+        let align = |t: DateTime<Utc>| {
+            DateTime::from_timestamp(t.timestamp().div_euclid(window_secs) * window_secs, 0)
+                .unwrap_or(t)
         };
+        let end = align(now);
+        let start = end - time_window;
+
+        // After a restart, fall back to the persisted evaluation time: any window
+        // ending at or before it has already been evaluated
+        let last_end = self
+            .window_ends
+            .lock()
+            .unwrap()
+            .get(rule.id().as_str())
+            .copied()
+            .or_else(|| rule.last_evaluated_at().map(align));
+
+        let next_start = match last_end {
+            Some(last_end) if last_end >= end => return None,
+            Some(last_end) => {
+                let resume = align(last_end);
+                let earliest = end - time_window * MAX_CATCH_UP_WINDOWS;
+                if resume < earliest {
+                    tracing::warn!(
+                        rule_id = %rule.id().as_str(),
+                        skipped_from = %resume,
+                        skipped_to = %earliest,
+                        "Evaluator fell too far behind; skipping alert windows beyond the catch-up limit"
+                    );
+                    earliest
+                } else {
+                    resume
+                }
+            }
+            None => start,
+        };
+
+        Some((next_start, Some(next_start + time_window)))
+    }
+
+    async fn evaluate_rule_at(
+        &self,
+        rule: &AlertRule,
+        now: DateTime<Utc>,
+    ) -> Result<(), AlertDomainError> {
+        let Some(mut window) = self.evaluation_window(rule, now) else {
+            return Ok(());
+        };
+        loop {
+            let (start_time, window_end) = window;
+            self.evaluate_window(rule, start_time, window_end, now)
+                .await?;
+
+            // Keep going through aligned windows missed while the evaluator was behind
+            match window_end.and_then(|_| self.evaluation_window(rule, now)) {
+                Some(next) => window = next,
+                None => break,
+            }
+        }
 
         // Update last_evaluated_at
         let mut updated_rule = rule.clone();
         updated_rule.mark_evaluated();
         self.rule_repo.update(&updated_rule).await?;
 
-        if should_trigger {
-            // Check if there's already a firing alert for this rule
-            let existing_alert = self.alert_repo.find_firing_by_rule(rule.id()).await?;
+        Ok(())
+    }
 
-            if existing_alert.is_none() {
-                // Create new alert
-                self.trigger_alert(rule, current_value).await?;
-            } else {
-                tracing::debug!(
-                    rule_id = %rule.id().as_str(),
-                    "Alert already firing, skipping"
-                );
-            }
+    /// Measure one window, then fire or resolve the rule's alert accordingly
+    async fn evaluate_window(
+        &self,
+        rule: &AlertRule,
+        start_time: DateTime<Utc>,
+        window_end: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Result<(), AlertDomainError> {
+        let (current_value, should_trigger) = self.measure(rule, start_time, window_end).await?;
+
+        if let Some(end) = window_end {
+            self.window_ends
+                .lock()
+                .unwrap()
+                .insert(rule.id().as_str().to_string(), end);
+        }
+
+        if should_trigger {
+            self.fire(rule, current_value, now).await?;
         } else {
             // Check if there's a firing alert that should be resolved
             if let Some(mut alert) = self.alert_repo.find_firing_by_rule(rule.id()).await? {
@@ -141,16 +435,91 @@ where
                 );
                 alert.resolve();
                 self.alert_repo.update(&alert).await?;
+                self.resolved_at
+                    .lock()
+                    .unwrap()
+                    .insert(rule.id().as_str().to_string(), now);
             }
         }
 
         Ok(())
     }
 
+    /// The rule's value over `[start_time, window_end)`, or since `start_time`
+    /// without an end, and whether it breaches the threshold
+    async fn measure(
+        &self,
+        rule: &AlertRule,
+        start_time: DateTime<Utc>,
+        window_end: Option<DateTime<Utc>>,
+    ) -> Result<(f64, bool), AlertDomainError> {
+        // Log filters treat the end bound as inclusive; stop just short of it
+        let end_time = window_end.map(|end| end - Duration::microseconds(1));
+
+        match rule.rule_type() {
+            RuleType::ErrorRate => self.evaluate_error_rate(rule, start_time, end_time).await,
+            RuleType::LogCount => self.evaluate_log_count(rule, start_time, end_time).await,
+            RuleType::PatternMatch => {
+                self.evaluate_pattern_match(rule, start_time, end_time)
+                    .await
+            }
+            RuleType::LogRatio => self.evaluate_log_ratio(rule, start_time, end_time).await,
+            RuleType::TraceErrorRate => {
+                self.evaluate_trace_error_rate(rule, start_time, end_time)
+                    .await
+            }
+        }
+    }
+
+    /// Trigger an alert for a breaching rule, unless one is already firing or
+    /// the rule is cooling down after a resolve
+    async fn fire(
+        &self,
+        rule: &AlertRule,
+        current_value: f64,
+        now: DateTime<Utc>,
+    ) -> Result<(), AlertDomainError> {
+        // Check if there's already a firing alert for this rule
+        let existing_alert = self.alert_repo.find_firing_by_rule(rule.id()).await?;
+
+        if existing_alert.is_some() {
+            tracing::debug!(
+                rule_id = %rule.id().as_str(),
+                "Alert already firing, skipping"
+            );
+        } else if let Some(cooldown_until) = self.cooldown_until(rule)
+            && now < cooldown_until
+        {
+            tracing::debug!(
+                rule_id = %rule.id().as_str(),
+                cooldown_until = %cooldown_until,
+                "Rule in post-resolve cooldown, skipping"
+            );
+        } else {
+            // Create new alert
+            self.trigger_alert(rule, current_value, now).await?;
+        }
+
+        Ok(())
+    }
+
+    /// End of the rule's post-resolve cooldown, if it has one
+    fn cooldown_until(&self, rule: &AlertRule) -> Option<DateTime<Utc>> {
+        if rule.cooldown_seconds() <= 0 {
+            return None;
+        }
+        self.resolved_at
+            .lock()
+            .unwrap()
+            .get(rule.id().as_str())
+            .map(|resolved_at| *resolved_at + Duration::seconds(rule.cooldown_seconds() as i64))
+    }
+
     async fn evaluate_error_rate(
         &self,
         rule: &AlertRule,
-        start_time: chrono::DateTime<Utc>,
+        start_time: DateTime<Utc>,
+        end_time: Option<DateTime<Utc>>,
     ) -> Result<(f64, bool), AlertDomainError> {
         // Get error levels from config, default to ["error", "fatal"]
         let error_levels: Vec<String> = rule
@@ -169,8 +538,9 @@ where
         // Get total log count in the time window
         let total_filters = LogFilters {
             levels: None,
+            min_level: None,
             start_time: Some(start_time),
-            end_time: None,
+            end_time,
             source: None,
             search: None,
             trace_id: None,
@@ -194,8 +564,9 @@ where
 
         let error_filters = LogFilters {
             levels: Some(error_log_levels),
+            min_level: None,
             start_time: Some(start_time),
-            end_time: None,
+            end_time,
             source: None,
             search: None,
             trace_id: None,
@@ -208,8 +579,11 @@ where
             .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
 
         let error_rate = (error_count as f64 / total_count as f64) * 100.0;
-        let should_trigger =
-            self.compare_threshold(error_rate, rule.threshold_value(), rule.threshold_operator());
+        let should_trigger = self.compare_threshold(
+            error_rate,
+            rule.threshold_value(),
+            rule.threshold_operator(),
+        );
 
         tracing::debug!(
             rule_id = %rule.id().as_str(),
@@ -225,7 +599,8 @@ where
     async fn evaluate_log_count(
         &self,
         rule: &AlertRule,
-        start_time: chrono::DateTime<Utc>,
+        start_time: DateTime<Utc>,
+        end_time: Option<DateTime<Utc>>,
     ) -> Result<(f64, bool), AlertDomainError> {
         // Get levels from config
         let levels: Option<Vec<LogLevel>> = rule
@@ -250,8 +625,9 @@ where
 
         let filters = LogFilters {
             levels,
+            min_level: None,
             start_time: Some(start_time),
-            end_time: None,
+            end_time,
             source,
             search: None,
             trace_id: None,
@@ -282,7 +658,8 @@ where
     async fn evaluate_pattern_match(
         &self,
         rule: &AlertRule,
-        start_time: chrono::DateTime<Utc>,
+        start_time: DateTime<Utc>,
+        end_time: Option<DateTime<Utc>>,
     ) -> Result<(f64, bool), AlertDomainError> {
         // Get pattern from config
         let pattern: String = rule
@@ -300,8 +677,9 @@ where
 
         let filters = LogFilters {
             levels: None,
+            min_level: None,
             start_time: Some(start_time),
-            end_time: None,
+            end_time,
             source: None,
             search: Some(pattern),
             trace_id: None,
@@ -329,12 +707,111 @@ where
         Ok((count_f64, should_trigger))
     }
 
-    fn compare_threshold(
+    async fn evaluate_log_ratio(
         &self,
-        value: f64,
-        threshold: f64,
-        operator: &ThresholdOperator,
-    ) -> bool {
+        rule: &AlertRule,
+        start_time: DateTime<Utc>,
+        end_time: Option<DateTime<Utc>>,
+    ) -> Result<(f64, bool), AlertDomainError> {
+        let config = LogRatioConfig::from_config(rule.config())?;
+        let filters = |selector: &LogSelector| LogFilters {
+            levels: selector.levels.as_ref().map(|levels| {
+                levels
+                    .iter()
+                    .filter_map(|l| LogLevel::from_str(l).ok())
+                    .collect()
+            }),
+            min_level: None,
+            start_time: Some(start_time),
+            end_time,
+            source: selector.source.clone(),
+            search: selector.pattern.clone(),
+            trace_id: None,
+            metadata_filters: vec![],
+        };
+
+        let (matching, total) = self
+            .log_repo
+            .count_pair(
+                rule.project_id(),
+                &filters(&config.numerator),
+                &filters(&config.denominator),
+            )
+            .await
+            .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+
+        if total == 0 {
+            let should_trigger = config.on_zero_denominator == ZeroDenominator::Fire;
+            return Ok((0.0, should_trigger));
+        }
+
+        let ratio = (matching as f64 / total as f64) * 100.0;
+        let should_trigger =
+            self.compare_threshold(ratio, rule.threshold_value(), rule.threshold_operator());
+
+        tracing::debug!(
+            rule_id = %rule.id().as_str(),
+            ratio = ratio,
+            matching,
+            total,
+            threshold = rule.threshold_value(),
+            should_trigger,
+            "Evaluated log ratio rule"
+        );
+
+        Ok((ratio, should_trigger))
+    }
+
+    async fn evaluate_trace_error_rate(
+        &self,
+        rule: &AlertRule,
+        start_time: DateTime<Utc>,
+        end_time: Option<DateTime<Utc>>,
+    ) -> Result<(f64, bool), AlertDomainError> {
+        let config = TraceErrorRateConfig::from_config(rule.config())?;
+        let spans_repo = self.spans_repo.as_ref().ok_or_else(|| {
+            AlertDomainError::InternalError("Trace error rate rules need span storage".to_string())
+        })?;
+        let filters = TraceFilters {
+            service_name: config.service.clone(),
+            start_time: Some(start_time),
+            end_time,
+            ..TraceFilters::default()
+        };
+
+        let (errors, total) = spans_repo
+            .count_errors(rule.project_id(), &filters, config.unit)
+            .await
+            .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+
+        if total == 0 {
+            return Ok((0.0, false));
+        }
+
+        let error_rate = (errors as f64 / total as f64) * 100.0;
+        // Too few spans for the rate to mean much: report it, but don't fire
+        let should_trigger = total >= config.min_count
+            && self.compare_threshold(
+                error_rate,
+                rule.threshold_value(),
+                rule.threshold_operator(),
+            );
+
+        tracing::debug!(
+            rule_id = %rule.id().as_str(),
+            error_rate = error_rate,
+            errors,
+            total,
+            min_count = config.min_count,
+            threshold = rule.threshold_value(),
+            should_trigger,
+            "Evaluated trace error rate rule"
+        );
+
+        Ok((error_rate, should_trigger))
+    }
+
+    fn compare_threshold(&self, value: f64, threshold: f64, operator: &ThresholdOperator) -> bool {
         match operator {
             ThresholdOperator::GreaterThan => value > threshold,
             ThresholdOperator::GreaterThanOrEqual => value >= threshold,
@@ -347,6 +824,7 @@ where
         &self,
         rule: &AlertRule,
         trigger_value: f64,
+        now: DateTime<Utc>,
     ) -> Result<(), AlertDomainError> {
         // Get project name
         let project = self
@@ -364,17 +842,35 @@ where
             rule.threshold_operator().as_str()
         );
 
+        // A firing parent rule already covers this condition
+        let parent_alert = match rule.parent_rule_id() {
+            Some(parent_id) => self.alert_repo.find_firing_by_rule(parent_id).await?,
+            None => None,
+        };
+
+        let mut metadata = json!({
+            "rule_type": rule.rule_type().as_str(),
+            "time_window_seconds": rule.time_window_seconds()
+        });
+        if let Some(parent) = &parent_alert {
+            metadata["suppressed_by"] = json!({
+                "parent_rule_id": parent.rule_id().as_str(),
+                "parent_alert_id": parent.id().as_str()
+            });
+        }
+        let outside_schedule = !rule.notifies_at(now);
+        if outside_schedule {
+            metadata["outside_schedule"] = json!(true);
+        }
+
         // Create alert
-        let alert = Alert::new(
+        let mut alert = Alert::new(
             AlertId::new(self.id_generator.generate()),
             AlertRuleId::new(rule.id().as_str().to_string()),
             ProjectId::new(rule.project_id().as_str().to_string()),
             trigger_value,
             message.clone(),
-            Some(json!({
-                "rule_type": rule.rule_type().as_str(),
-                "time_window_seconds": rule.time_window_seconds()
-            })),
+            Some(metadata),
         );
 
         self.alert_repo.save(&alert).await?;
@@ -391,6 +887,23 @@ where
             "Alert triggered"
         );
 
+        // Suppressed alerts are kept in history but not sent anywhere
+        if let Some(parent) = parent_alert {
+            tracing::info!(
+                alert_id = %alert.id().as_str(),
+                parent_alert_id = %parent.id().as_str(),
+                "Notifications suppressed, parent rule is firing"
+            );
+            return Ok(());
+        }
+        if outside_schedule {
+            tracing::info!(
+                alert_id = %alert.id().as_str(),
+                "Notifications skipped, outside the rule's schedule"
+            );
+            return Ok(());
+        }
+
         // Send notifications
         let webhook_payload = WebhookPayload {
             alert_id: alert.id().as_str().to_string(),
@@ -403,26 +916,1161 @@ where
             trigger_value,
             threshold: rule.threshold_value(),
             threshold_operator: rule.threshold_operator().as_str().to_string(),
+            severity: rule.severity().as_str().to_string(),
             message,
             metadata: alert.metadata().cloned(),
         };
 
-        // Get channels and send notifications
+        // Alerts with a grouping value wait for others sharing it; the rest are sent now
         let channels = self.channel_repo.find_by_ids(rule.channel_ids()).await?;
-        for channel in channels {
-            if let Err(e) = self
-                .notifier
-                .send(&webhook_payload, channel.config())
+        let grouping = &project.settings().alert_grouping;
+        let group_value = rule
+            .config()
+            .get(&grouping.key)
+            .and_then(|v| v.as_str())
+            .filter(|_| grouping.window_seconds > 0 && !channels.is_empty());
+        match group_value {
+            Some(value) => {
+                self.alert_groups
+                    .lock()
+                    .unwrap()
+                    .entry((rule.project_id().as_str().to_string(), value.to_string()))
+                    .or_insert_with(|| AlertGroup {
+                        project_id: rule.project_id().as_str().to_string(),
+                        project_name: project.name().as_str().to_string(),
+                        key: grouping.key.clone(),
+                        value: value.to_string(),
+                        flush_at: now + Duration::seconds(grouping.window_seconds as i64),
+                        alerts: Vec::new(),
+                    })
+                    .alerts
+                    .push(PendingAlert {
+                        alert_id: alert.id().clone(),
+                        payload: webhook_payload.clone(),
+                        channels,
+                    });
+            }
+            None => {
+                self.notify_channels(&mut alert, &webhook_payload, &channels)
+                    .await?
+            }
+        }
+
+        if let Some(user_notifier) = &self.user_notifier
+            && let Err(e) = user_notifier
+                .notify_members(project.organization_id(), &webhook_payload, rule.severity())
                 .await
+        {
+            tracing::warn!(
+                alert_id = %alert.id().as_str(),
+                error = %e,
+                "Failed to notify organization members"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Send the alert to each channel, recording each outcome on the alert
+    async fn notify_channels(
+        &self,
+        alert: &mut Alert,
+        payload: &WebhookPayload,
+        channels: &[AlertChannel],
+    ) -> Result<(), AlertDomainError> {
+        for channel in channels {
+            let result = self.notifier.send(payload, channel.config()).await;
+            if let Err(e) = &result {
+                tracing::warn!(
+                    channel_id = %channel.id().as_str(),
+                    error = %e,
+                    retryable = e.is_retryable(),
+                    "Failed to send notification"
+                );
+            }
+            alert.record_notification(channel.id().as_str(), &result, Utc::now());
+            self.record_channel_delivery(channel, &result).await;
+        }
+        if !channels.is_empty() {
+            self.alert_repo.update(alert).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Send every alert group whose window has closed by `now`
+    async fn flush_alert_groups(&self, now: DateTime<Utc>) {
+        let due: Vec<AlertGroup> = {
+            let mut groups = self.alert_groups.lock().unwrap();
+            let keys: Vec<(String, String)> = groups
+                .iter()
+                .filter(|(_, group)| group.flush_at <= now)
+                .map(|(key, _)| key.clone())
+                .collect();
+            keys.iter().filter_map(|key| groups.remove(key)).collect()
+        };
+
+        for group in due {
+            let (key, value) = (group.key.clone(), group.value.clone());
+            if let Err(e) = self.send_alert_group(group).await {
+                tracing::warn!(
+                    group_key = %key,
+                    group_value = %value,
+                    error = %e,
+                    "Failed to send grouped alert notification"
+                );
+            }
+        }
+    }
+
+    /// Notify each channel once about the group's alerts routed to it; a channel
+    /// with a single alert gets the usual per-alert notification
+    async fn send_alert_group(&self, group: AlertGroup) -> Result<(), AlertDomainError> {
+        // Alerts resolved while held back no longer need a notification
+        let mut held: Vec<(Alert, PendingAlert)> = Vec::new();
+        for pending in group.alerts {
+            if let Some(alert) = self.alert_repo.find_by_id(&pending.alert_id).await?
+                && alert.is_firing()
             {
+                held.push((alert, pending));
+            }
+        }
+
+        let mut by_channel: BTreeMap<String, (AlertChannel, Vec<usize>)> = BTreeMap::new();
+        for (i, (_, pending)) in held.iter().enumerate() {
+            for channel in &pending.channels {
+                by_channel
+                    .entry(channel.id().as_str().to_string())
+                    .or_insert_with(|| (channel.clone(), Vec::new()))
+                    .1
+                    .push(i);
+            }
+        }
+
+        for (channel, indices) in by_channel.into_values() {
+            let result = if let [i] = indices[..] {
+                self.notifier
+                    .send(&held[i].1.payload, channel.config())
+                    .await
+            } else {
+                let alerts: Vec<WebhookPayload> =
+                    indices.iter().map(|&i| held[i].1.payload.clone()).collect();
+                let payload = GroupedAlertPayload {
+                    project_id: group.project_id.clone(),
+                    project_name: group.project_name.clone(),
+                    group_key: group.key.clone(),
+                    group_value: group.value.clone(),
+                    alert_count: alerts.len(),
+                    message: format!(
+                        "{} alerts firing for {} {}",
+                        alerts.len(),
+                        group.key,
+                        group.value
+                    ),
+                    alerts,
+                };
+                self.notifier.send_group(&payload, channel.config()).await
+            };
+            if let Err(e) = &result {
                 tracing::warn!(
                     channel_id = %channel.id().as_str(),
                     error = %e,
+                    retryable = e.is_retryable(),
                     "Failed to send notification"
                 );
             }
+            for &i in &indices {
+                held[i]
+                    .0
+                    .record_notification(channel.id().as_str(), &result, Utc::now());
+            }
+            self.record_channel_delivery(&channel, &result).await;
+        }
+
+        for (alert, _) in &held {
+            self.alert_repo.update(alert).await?;
         }
 
         Ok(())
     }
+
+    /// Track the channel's delivery health, disabling it once it fails too often
+    async fn record_channel_delivery(
+        &self,
+        channel: &AlertChannel,
+        result: &Result<(), AlertDomainError>,
+    ) {
+        // Nothing changes when a healthy channel delivers
+        if result.is_ok() && channel.consecutive_failures() == 0 {
+            return;
+        }
+
+        // Reload so edits and other deliveries since the channel was loaded aren't lost
+        let mut channel = match self.channel_repo.find_by_id(channel.id()).await {
+            Ok(Some(channel)) => channel,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!(
+                    channel_id = %channel.id().as_str(),
+                    error = %e,
+                    "Failed to load channel to record delivery"
+                );
+                return;
+            }
+        };
+        let disabled = channel.record_delivery(result, self.channel_failure_limit, Utc::now());
+        if let Err(e) = self.channel_repo.update(&channel).await {
+            tracing::warn!(
+                channel_id = %channel.id().as_str(),
+                error = %e,
+                "Failed to record channel delivery"
+            );
+            return;
+        }
+
+        if disabled {
+            tracing::warn!(
+                channel_id = %channel.id().as_str(),
+                consecutive_failures = channel.consecutive_failures(),
+                "Alert channel disabled after repeated delivery failures"
+            );
+            if let Err(e) = self.notify_channel_disabled(&channel).await {
+                tracing::warn!(
+                    channel_id = %channel.id().as_str(),
+                    error = %e,
+                    "Failed to notify admins about disabled channel"
+                );
+            }
+        }
+    }
+
+    /// Tell the owning organization's admins, when personal notifications are enabled
+    async fn notify_channel_disabled(
+        &self,
+        channel: &AlertChannel,
+    ) -> Result<(), AlertDomainError> {
+        let Some(user_notifier) = &self.user_notifier else {
+            return Ok(());
+        };
+        let org_id = match (channel.organization_id(), channel.project_id()) {
+            (Some(org_id), _) => org_id.clone(),
+            (None, Some(project_id)) => self
+                .project_repo
+                .find_by_id(project_id)
+                .await
+                .map_err(|e| AlertDomainError::InternalError(e.to_string()))?
+                .ok_or(AlertDomainError::ProjectNotFound)?
+                .organization_id()
+                .clone(),
+            (None, None) => return Ok(()),
+        };
+
+        let payload = ChannelDisabledPayload {
+            channel_id: channel.id().as_str().to_string(),
+            channel_name: channel.name().to_string(),
+            project_id: channel.project_id().map(|id| id.as_str().to_string()),
+            organization_id: org_id.as_str().to_string(),
+            consecutive_failures: channel.consecutive_failures(),
+            last_error: channel.last_delivery_error().map(str::to_string),
+            disabled_at: channel.auto_disabled_at().unwrap_or_else(Utc::now),
+            message: format!(
+                "Alert channel '{}' was disabled after {} failed deliveries in a row",
+                channel.name(),
+                channel.consecutive_failures()
+            ),
+        };
+        user_notifier
+            .notify_channel_disabled(&org_id, &payload)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use serde_json::Value;
+
+    use crate::modules::alerts::application::dto::DigestPayload;
+    use crate::modules::alerts::domain::{
+        AlertChannelId, AlertSchedule, AlertSeverity, AlertStatus, ChannelHealth, ChannelType,
+    };
+    use crate::modules::auth::domain::UserId;
+    use crate::modules::logging::domain::{LogEntry, LogId, LogIngestObserver};
+    use crate::modules::traces::domain::{Span, SpanKind, SpanStatusCode};
+    use crate::shared::testing::{
+        InMemoryAlertChannelRepository, InMemoryAlertRepository, InMemoryAlertRuleRepository,
+        InMemoryLogRepository, InMemoryProjectRepository, InMemorySpansRepository,
+        SequentialIdGenerator,
+    };
+
+    /// Aligned to a minute boundary
+    const T0: i64 = 1_700_000_040;
+
+    struct NoopNotifier;
+
+    #[async_trait]
+    impl Notifier for NoopNotifier {
+        async fn send(
+            &self,
+            _payload: &WebhookPayload,
+            _channel_config: &Value,
+        ) -> Result<(), AlertDomainError> {
+            Ok(())
+        }
+
+        async fn send_digest(
+            &self,
+            _payload: &DigestPayload,
+            _channel_config: &Value,
+        ) -> Result<(), AlertDomainError> {
+            Ok(())
+        }
+
+        async fn send_group(
+            &self,
+            _payload: &GroupedAlertPayload,
+            _channel_config: &Value,
+        ) -> Result<(), AlertDomainError> {
+            Ok(())
+        }
+
+        async fn send_channel_disabled(
+            &self,
+            _payload: &ChannelDisabledPayload,
+            _channel_config: &Value,
+        ) -> Result<(), AlertDomainError> {
+            Ok(())
+        }
+    }
+
+    /// Records how many alerts each notification covered
+    #[derive(Default)]
+    struct RecordingNotifier {
+        single: Mutex<usize>,
+        grouped: Mutex<Vec<usize>>,
+        /// Fail every per-alert delivery
+        failing: bool,
+    }
+
+    #[async_trait]
+    impl Notifier for RecordingNotifier {
+        async fn send(
+            &self,
+            _payload: &WebhookPayload,
+            _channel_config: &Value,
+        ) -> Result<(), AlertDomainError> {
+            *self.single.lock().unwrap() += 1;
+            if self.failing {
+                return Err(AlertDomainError::WebhookFailed("status 500".to_string()));
+            }
+            Ok(())
+        }
+
+        async fn send_digest(
+            &self,
+            _payload: &DigestPayload,
+            _channel_config: &Value,
+        ) -> Result<(), AlertDomainError> {
+            Ok(())
+        }
+
+        async fn send_group(
+            &self,
+            payload: &GroupedAlertPayload,
+            _channel_config: &Value,
+        ) -> Result<(), AlertDomainError> {
+            self.grouped.lock().unwrap().push(payload.alert_count);
+            Ok(())
+        }
+
+        async fn send_channel_disabled(
+            &self,
+            _payload: &ChannelDisabledPayload,
+            _channel_config: &Value,
+        ) -> Result<(), AlertDomainError> {
+            Ok(())
+        }
+    }
+
+    type TestEvaluator = RuleEvaluator<
+        InMemoryAlertRuleRepository,
+        InMemoryAlertRepository,
+        InMemoryAlertChannelRepository,
+        InMemoryLogRepository,
+        InMemoryProjectRepository,
+        SequentialIdGenerator,
+        NoopNotifier,
+    >;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(T0 + secs, 0).unwrap()
+    }
+
+    fn evaluator(
+        log_repo: Arc<InMemoryLogRepository>,
+        alert_repo: Arc<InMemoryAlertRepository>,
+    ) -> TestEvaluator {
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        RuleEvaluator::new(
+            Arc::new(InMemoryAlertRuleRepository::new()),
+            alert_repo,
+            Arc::new(InMemoryAlertChannelRepository::new()),
+            log_repo,
+            project_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            Arc::new(NoopNotifier),
+            60,
+        )
+        .with_aligned_windows(true)
+    }
+
+    /// Fires when any log lands in a one-minute window
+    fn any_log_rule() -> AlertRule {
+        AlertRule::new(
+            AlertRuleId::new("rule-1".to_string()),
+            ProjectId::new("project-1".to_string()),
+            "any log".to_string(),
+            None,
+            RuleType::LogCount,
+            json!({}),
+            0.0,
+            ThresholdOperator::GreaterThan,
+            60,
+            UserId::new("user-1".to_string()),
+        )
+    }
+
+    fn log_at(id: &str, timestamp: DateTime<Utc>) -> LogEntry {
+        LogEntry::new(
+            LogId::new(id.to_string()),
+            ProjectId::new("project-1".to_string()),
+            LogLevel::Info,
+            "hello".to_string(),
+            Some(timestamp),
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_ingest_rule_fires_promptly_after_matching_log() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let alert_repo = Arc::new(InMemoryAlertRepository::new());
+        let rule_repo = Arc::new(InMemoryAlertRuleRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        let mut rule = any_log_rule();
+        rule.update_config(json!({"levels": ["error"]}));
+        rule.update_severity(AlertSeverity::Critical);
+        rule.set_evaluate_on_ingest(true);
+        rule_repo.save(&rule).await.unwrap();
+
+        let trigger = Arc::new(IngestRuleTrigger::new());
+        // Scheduled evaluation would only run in an hour
+        let evaluator = Arc::new(
+            RuleEvaluator::new(
+                rule_repo,
+                alert_repo.clone(),
+                Arc::new(InMemoryAlertChannelRepository::new()),
+                log_repo.clone(),
+                project_repo,
+                Arc::new(SequentialIdGenerator::new()),
+                Arc::new(NoopNotifier),
+                3600,
+            )
+            .with_ingest_evaluation(trigger.clone(), time::Duration::from_secs(5)),
+        );
+        trigger.set_rules(std::slice::from_ref(&rule));
+        let handle = tokio::spawn(evaluator.start_ingest_evaluation());
+
+        let log = LogEntry::new(
+            LogId::new("log-1".to_string()),
+            ProjectId::new("project-1".to_string()),
+            LogLevel::Error,
+            "boom".to_string(),
+            Some(Utc::now()),
+            None,
+            None,
+            None,
+            None,
+        );
+        log_repo
+            .save_batch(std::slice::from_ref(&log))
+            .await
+            .unwrap();
+        trigger.logs_ingested(log.project_id(), std::slice::from_ref(&log));
+
+        let project_id = ProjectId::new("project-1".to_string());
+        let started = Instant::now();
+        let alerts = loop {
+            let alerts = alert_repo
+                .find_by_project(&project_id, 10, 0)
+                .await
+                .unwrap();
+            if !alerts.is_empty() || started.elapsed() > time::Duration::from_secs(2) {
+                break alerts;
+            }
+            time::sleep(time::Duration::from_millis(10)).await;
+        };
+        handle.abort();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].trigger_value(), Some(1.0));
+        assert_eq!(*alerts[0].status(), AlertStatus::Firing);
+    }
+
+    #[tokio::test]
+    async fn test_aligned_windows_count_each_record_once() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let alert_repo = Arc::new(InMemoryAlertRepository::new());
+        // One log mid-window, one exactly on the next window's start
+        log_repo
+            .save_batch(&[log_at("log-1", at(30)), log_at("log-2", at(120))])
+            .await
+            .unwrap();
+        let evaluator = evaluator(log_repo, alert_repo.clone());
+        let rule = any_log_rule();
+        let project_id = ProjectId::new("project-1".to_string());
+
+        // [0, 60) holds log-1
+        evaluator.evaluate_rule_at(&rule, at(65)).await.unwrap();
+        // Same window again on the next tick: not re-evaluated
+        evaluator.evaluate_rule_at(&rule, at(100)).await.unwrap();
+        // [60, 120) is empty: log-2 sits on the boundary and belongs to the next window
+        evaluator.evaluate_rule_at(&rule, at(125)).await.unwrap();
+        // [120, 180) holds log-2
+        evaluator.evaluate_rule_at(&rule, at(185)).await.unwrap();
+
+        let alerts = alert_repo
+            .find_by_project(&project_id, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(alerts.len(), 2);
+        assert!(alerts.iter().all(|a| a.trigger_value() == Some(1.0)));
+        assert_eq!(
+            alerts
+                .iter()
+                .filter(|a| *a.status() == AlertStatus::Resolved)
+                .count(),
+            1
+        );
+    }
+
+    /// Alerts from evaluating a ">5% errors" ratio rule over one window of
+    /// `total` logs, `errors` of them at error level
+    async fn error_ratio_alerts(errors: usize, total: usize) -> Vec<Alert> {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let alert_repo = Arc::new(InMemoryAlertRepository::new());
+        let logs: Vec<LogEntry> = (0..total)
+            .map(|i| {
+                let level = if i < errors {
+                    LogLevel::Error
+                } else {
+                    LogLevel::Info
+                };
+                LogEntry::new(
+                    LogId::new(format!("log-{}", i)),
+                    ProjectId::new("project-1".to_string()),
+                    level,
+                    "request handled".to_string(),
+                    Some(at(i as i64 % 60)),
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+            })
+            .collect();
+        log_repo.save_batch(&logs).await.unwrap();
+        let evaluator = evaluator(log_repo, alert_repo.clone());
+        let mut rule = any_log_rule();
+        rule.update_rule_type(RuleType::LogRatio);
+        rule.update_config(json!({"numerator": {"levels": ["error", "fatal"]}}));
+        rule.update_threshold(5.0, ThresholdOperator::GreaterThan);
+
+        evaluator.evaluate_rule_at(&rule, at(65)).await.unwrap();
+        alert_repo
+            .find_by_project(&ProjectId::new("project-1".to_string()), 10, 0)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_log_ratio_rule_fires_above_threshold_only() {
+        let alerts = error_ratio_alerts(10, 100).await;
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].trigger_value(), Some(10.0));
+
+        assert!(error_ratio_alerts(2, 100).await.is_empty());
+        // No logs at all: the default is not to fire
+        assert!(error_ratio_alerts(0, 0).await.is_empty());
+    }
+
+    /// Alerts from evaluating a ">10% error spans" rule for `service` over one
+    /// window holding checkout spans at 20% errors, search spans at 5% and
+    /// two quiet-service spans, one failed
+    async fn trace_error_rate_alerts(service: &str) -> Vec<Alert> {
+        let spans_repo = Arc::new(InMemorySpansRepository::new());
+        let seeded = [("checkout", 15, 3), ("search", 20, 1), ("quiet", 2, 1)];
+        let spans: Vec<Span> = seeded
+            .iter()
+            .flat_map(|&(service, total, errors)| {
+                (0..total).map(move |i| {
+                    let status = if i < errors {
+                        SpanStatusCode::Error
+                    } else {
+                        SpanStatusCode::Ok
+                    };
+                    let start = at(i as i64 % 60);
+                    Span::new(
+                        format!("{}-{}", service, i),
+                        ProjectId::new("project-1".to_string()),
+                        format!("trace-{}-{}", service, i),
+                        format!("span-{}", i),
+                        None,
+                        "GET /".to_string(),
+                        SpanKind::Server,
+                        start,
+                        Some(start + Duration::milliseconds(20)),
+                        status,
+                        None,
+                        Some(service.to_string()),
+                        None,
+                        json!({}),
+                        json!({}),
+                        vec![],
+                        vec![],
+                    )
+                })
+            })
+            .collect();
+        spans_repo.save_batch(&spans, false).await.unwrap();
+        let alert_repo = Arc::new(InMemoryAlertRepository::new());
+        let evaluator = evaluator(Arc::new(InMemoryLogRepository::new()), alert_repo.clone())
+            .with_spans(spans_repo);
+        let mut rule = any_log_rule();
+        rule.update_rule_type(RuleType::TraceErrorRate);
+        rule.update_config(json!({"service": service}));
+        rule.update_threshold(10.0, ThresholdOperator::GreaterThan);
+
+        evaluator.evaluate_rule_at(&rule, at(65)).await.unwrap();
+        alert_repo
+            .find_by_project(&ProjectId::new("project-1".to_string()), 10, 0)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_trace_error_rate_rule_fires_for_failing_service_only() {
+        let alerts = trace_error_rate_alerts("checkout").await;
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].trigger_value(), Some(20.0));
+
+        assert!(trace_error_rate_alerts("search").await.is_empty());
+        // 50% errors, but two spans are below the default minimum of ten
+        assert!(trace_error_rate_alerts("quiet").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_aligned_window_after_first_evaluation_and_downtime() {
+        let evaluator = evaluator(
+            Arc::new(InMemoryLogRepository::new()),
+            Arc::new(InMemoryAlertRepository::new()),
+        );
+        let rule = any_log_rule();
+
+        // First evaluation covers the latest complete window
+        assert_eq!(
+            evaluator.evaluation_window(&rule, at(90)),
+            Some((at(0), Some(at(60))))
+        );
+        evaluator.evaluate_rule_at(&rule, at(90)).await.unwrap();
+        assert_eq!(evaluator.evaluation_window(&rule, at(119)), None);
+
+        // After downtime, resume right after the last evaluated window
+        assert_eq!(
+            evaluator.evaluation_window(&rule, at(610)),
+            Some((at(60), Some(at(120))))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_aligned_windows_missed_during_downtime_are_caught_up() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let alert_repo = Arc::new(InMemoryAlertRepository::new());
+        // Only a window in the middle of the gap has logs
+        log_repo
+            .save_batch(&[log_at("log-1", at(250))])
+            .await
+            .unwrap();
+        let evaluator = evaluator(log_repo, alert_repo.clone());
+        let rule = any_log_rule();
+
+        evaluator.evaluate_rule_at(&rule, at(90)).await.unwrap();
+        evaluator.evaluate_rule_at(&rule, at(610)).await.unwrap();
+
+        let alerts = alert_repo
+            .find_by_project(&ProjectId::new("project-1".to_string()), 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(alerts.len(), 1);
+        // Every window up to the latest one was evaluated
+        assert_eq!(evaluator.evaluation_window(&rule, at(619)), None);
+    }
+
+    #[tokio::test]
+    async fn test_aligned_catch_up_is_capped() {
+        let evaluator = evaluator(
+            Arc::new(InMemoryLogRepository::new()),
+            Arc::new(InMemoryAlertRepository::new()),
+        );
+        let rule = any_log_rule();
+        evaluator.evaluate_rule_at(&rule, at(90)).await.unwrap();
+
+        // 109 windows behind: the oldest nine are skipped
+        let windows = MAX_CATCH_UP_WINDOWS as i64 + 10;
+        assert_eq!(
+            evaluator.evaluation_window(&rule, at(60 * windows)),
+            Some((at(600), Some(at(660))))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_breach_within_post_resolve_cooldown_does_not_fire() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let alert_repo = Arc::new(InMemoryAlertRepository::new());
+        log_repo
+            .save_batch(&[
+                log_at("log-1", at(30)),
+                log_at("log-2", at(150)),
+                log_at("log-3", at(430)),
+            ])
+            .await
+            .unwrap();
+        let evaluator = evaluator(log_repo, alert_repo.clone());
+        let mut rule = any_log_rule();
+        rule.update_cooldown(300);
+        let project_id = ProjectId::new("project-1".to_string());
+        let alert_count = || async {
+            alert_repo
+                .find_by_project(&project_id, 10, 0)
+                .await
+                .unwrap()
+                .len()
+        };
+
+        // Fires on log-1, then resolves at 125 on the empty window
+        evaluator.evaluate_rule_at(&rule, at(65)).await.unwrap();
+        evaluator.evaluate_rule_at(&rule, at(125)).await.unwrap();
+        assert_eq!(alert_count().await, 1);
+
+        // log-2 breaches within the cooldown (until 425)
+        evaluator.evaluate_rule_at(&rule, at(185)).await.unwrap();
+        assert_eq!(alert_count().await, 1);
+
+        // log-3 breaches after it
+        evaluator.evaluate_rule_at(&rule, at(485)).await.unwrap();
+        assert_eq!(alert_count().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_evaluation_cycle_updates_rule_health() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        // A log for a project that no longer exists makes that rule's alert fail
+        let orphan_log = LogEntry::new(
+            LogId::new("log-2".to_string()),
+            ProjectId::new("project-2".to_string()),
+            LogLevel::Info,
+            "hello".to_string(),
+            Some(at(30)),
+            None,
+            None,
+            None,
+            None,
+        );
+        log_repo
+            .save_batch(&[log_at("log-1", at(30)), orphan_log])
+            .await
+            .unwrap();
+        let health = Arc::new(EvaluatorHealth::new());
+        let evaluator = evaluator(log_repo, Arc::new(InMemoryAlertRepository::new()))
+            .with_health(health.clone());
+        let rule = any_log_rule();
+        let orphan_rule = AlertRule::new(
+            AlertRuleId::new("rule-2".to_string()),
+            ProjectId::new("project-2".to_string()),
+            "orphan".to_string(),
+            None,
+            RuleType::LogCount,
+            json!({}),
+            0.0,
+            ThresholdOperator::GreaterThan,
+            60,
+            UserId::new("user-1".to_string()),
+        );
+        assert!(health.get("rule-1").is_none());
+
+        evaluator.evaluate_and_record(&rule, at(65)).await.unwrap();
+        assert!(
+            evaluator
+                .evaluate_and_record(&orphan_rule, at(65))
+                .await
+                .is_err()
+        );
+        evaluator.evaluate_and_record(&rule, at(125)).await.unwrap();
+
+        let stats = health.get("rule-1").unwrap();
+        assert_eq!((stats.evaluations, stats.errors), (2, 0));
+        assert_eq!(stats.last_evaluated_at, at(125));
+        assert_eq!(stats.interval_secs, 60);
+
+        let failing = health.get("rule-2").unwrap();
+        assert_eq!((failing.evaluations, failing.errors), (1, 1));
+        assert!(failing.last_error.is_some());
+
+        // Stale once three intervals pass without an evaluation
+        assert!(!stats.is_stale(at(125 + 180)));
+        assert!(stats.is_stale(at(125 + 181)));
+
+        let exported = health.render_prometheus(at(130));
+        assert!(exported.contains(
+            "altenia_alert_rule_evaluation_errors_total{rule_id=\"rule-2\",project_id=\"project-2\"} 1"
+        ));
+        assert!(exported.contains(
+            "altenia_alert_rule_evaluations_total{rule_id=\"rule-1\",project_id=\"project-1\"} 2"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rules_sharing_group_key_send_one_notification() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let log = LogEntry::new(
+            LogId::new("log-1".to_string()),
+            ProjectId::new("project-1".to_string()),
+            LogLevel::Error,
+            "payment failed".to_string(),
+            Some(at(30)),
+            Some("checkout".to_string()),
+            None,
+            None,
+            None,
+        );
+        log_repo.save_batch(&[log]).await.unwrap();
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let mut project = project_repo.seed("project-1", "org-1");
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({ "alert_grouping": { "window_seconds": 120 } }))
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+        let channel_repo = Arc::new(InMemoryAlertChannelRepository::new());
+        channel_repo.seed(AlertChannel::new(
+            AlertChannelId::new("channel-1".to_string()),
+            ProjectId::new("project-1".to_string()),
+            "on-call".to_string(),
+            ChannelType::Webhook,
+            json!({"url": "https://example.com/hook"}),
+        ));
+        let notifier = Arc::new(RecordingNotifier::default());
+        let evaluator = RuleEvaluator::new(
+            Arc::new(InMemoryAlertRuleRepository::new()),
+            Arc::new(InMemoryAlertRepository::new()),
+            channel_repo,
+            log_repo,
+            project_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            notifier.clone(),
+            60,
+        )
+        .with_aligned_windows(true);
+
+        for id in ["rule-1", "rule-2"] {
+            let mut rule = AlertRule::new(
+                AlertRuleId::new(id.to_string()),
+                ProjectId::new("project-1".to_string()),
+                format!("checkout {}", id),
+                None,
+                RuleType::LogCount,
+                json!({ "source": "checkout" }),
+                0.0,
+                ThresholdOperator::GreaterThan,
+                60,
+                UserId::new("user-1".to_string()),
+            );
+            rule.set_channel_ids(vec!["channel-1".to_string()]);
+            evaluator.evaluate_rule_at(&rule, at(65)).await.unwrap();
+        }
+
+        // Held until the window closes
+        evaluator.flush_alert_groups(at(120)).await;
+        assert_eq!(*notifier.single.lock().unwrap(), 0);
+        assert!(notifier.grouped.lock().unwrap().is_empty());
+
+        evaluator.flush_alert_groups(at(186)).await;
+        assert_eq!(*notifier.single.lock().unwrap(), 0);
+        assert_eq!(*notifier.grouped.lock().unwrap(), vec![2]);
+    }
+
+    #[tokio::test]
+    async fn test_child_rule_notification_suppressed_while_parent_fires() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let log = LogEntry::new(
+            LogId::new("log-1".to_string()),
+            ProjectId::new("project-1".to_string()),
+            LogLevel::Error,
+            "checkout unavailable".to_string(),
+            Some(at(30)),
+            Some("checkout".to_string()),
+            None,
+            None,
+            None,
+        );
+        log_repo.save_batch(&[log]).await.unwrap();
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        let channel_repo = Arc::new(InMemoryAlertChannelRepository::new());
+        channel_repo.seed(AlertChannel::new(
+            AlertChannelId::new("channel-1".to_string()),
+            ProjectId::new("project-1".to_string()),
+            "on-call".to_string(),
+            ChannelType::Webhook,
+            json!({"url": "https://example.com/hook"}),
+        ));
+        let alert_repo = Arc::new(InMemoryAlertRepository::new());
+        alert_repo
+            .save(&Alert::new(
+                AlertId::new("parent-alert".to_string()),
+                AlertRuleId::new("service-down".to_string()),
+                ProjectId::new("project-1".to_string()),
+                1.0,
+                "service down".to_string(),
+                None,
+            ))
+            .await
+            .unwrap();
+        let notifier = Arc::new(RecordingNotifier::default());
+        let evaluator = RuleEvaluator::new(
+            Arc::new(InMemoryAlertRuleRepository::new()),
+            alert_repo.clone(),
+            channel_repo,
+            log_repo.clone(),
+            project_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            notifier.clone(),
+            60,
+        )
+        .with_aligned_windows(true);
+
+        let mut rule = any_log_rule();
+        rule.set_channel_ids(vec!["channel-1".to_string()]);
+        rule.set_parent_rule(Some(AlertRuleId::new("service-down".to_string())));
+        evaluator.evaluate_rule_at(&rule, at(65)).await.unwrap();
+
+        // Recorded in history, but nobody is notified
+        assert_eq!(*notifier.single.lock().unwrap(), 0);
+        let child = alert_repo
+            .find_firing_by_rule(rule.id())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            child.metadata().unwrap()["suppressed_by"]["parent_alert_id"],
+            "parent-alert"
+        );
+
+        // Once the parent resolves, the child notifies again
+        let mut parent = alert_repo
+            .find_by_id(&AlertId::new("parent-alert".to_string()))
+            .await
+            .unwrap()
+            .unwrap();
+        parent.resolve();
+        alert_repo.update(&parent).await.unwrap();
+        let mut resolved = child;
+        resolved.resolve();
+        alert_repo.update(&resolved).await.unwrap();
+        log_repo
+            .save_batch(&[log_at("log-2", at(90))])
+            .await
+            .unwrap();
+        evaluator.evaluate_rule_at(&rule, at(125)).await.unwrap();
+        assert_eq!(*notifier.single.lock().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_breach_outside_schedule_recorded_without_notifying() {
+        // T0 is a Tuesday, 23:14 in Paris
+        let evaluate = |schedule: Value| async move {
+            let log_repo = Arc::new(InMemoryLogRepository::new());
+            log_repo
+                .save_batch(&[log_at("log-1", at(30))])
+                .await
+                .unwrap();
+            let project_repo = Arc::new(InMemoryProjectRepository::new());
+            project_repo.seed("project-1", "org-1");
+            let channel_repo = Arc::new(InMemoryAlertChannelRepository::new());
+            channel_repo.seed(AlertChannel::new(
+                AlertChannelId::new("channel-1".to_string()),
+                ProjectId::new("project-1".to_string()),
+                "on-call".to_string(),
+                ChannelType::Webhook,
+                json!({"url": "https://example.com/hook"}),
+            ));
+            let alert_repo = Arc::new(InMemoryAlertRepository::new());
+            let notifier = Arc::new(RecordingNotifier::default());
+            let evaluator = RuleEvaluator::new(
+                Arc::new(InMemoryAlertRuleRepository::new()),
+                alert_repo.clone(),
+                channel_repo,
+                log_repo,
+                project_repo,
+                Arc::new(SequentialIdGenerator::new()),
+                notifier.clone(),
+                60,
+            )
+            .with_aligned_windows(true);
+
+            let mut rule = any_log_rule();
+            rule.set_channel_ids(vec!["channel-1".to_string()]);
+            rule.set_schedule(Some(AlertSchedule::from_value(&schedule).unwrap()));
+            evaluator.evaluate_rule_at(&rule, at(65)).await.unwrap();
+
+            let alert = alert_repo.find_firing_by_rule(rule.id()).await.unwrap();
+            let notified = *notifier.single.lock().unwrap() == 1;
+            (alert.unwrap(), notified)
+        };
+
+        let (alert, notified) = evaluate(json!({
+            "timezone": "Europe/Paris",
+            "windows": [{"days": ["Mon", "Tue", "Wed", "Thu", "Fri"], "start": "09:00:00", "end": "17:00:00"}]
+        }))
+        .await;
+        assert!(!notified);
+        assert_eq!(alert.metadata().unwrap()["outside_schedule"], true);
+
+        // An overnight window starting Tuesday evening covers it
+        let (alert, notified) = evaluate(json!({
+            "timezone": "Europe/Paris",
+            "windows": [{"days": ["Tue"], "start": "22:00:00", "end": "06:00:00"}]
+        }))
+        .await;
+        assert!(notified);
+        assert!(alert.metadata().unwrap().get("outside_schedule").is_none());
+    }
+
+    #[test]
+    fn test_schedule_windows_follow_dst() {
+        let schedule = AlertSchedule::from_value(&json!({
+            "timezone": "Europe/Paris",
+            "windows": [{"days": ["Sun"], "start": "09:00:00", "end": "10:00:00"}]
+        }))
+        .unwrap();
+        let utc = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+
+        // 09:30 local is 08:30 UTC in winter and 07:30 UTC after the switch
+        assert!(schedule.is_active(utc("2024-03-24T08:30:00Z")));
+        assert!(schedule.is_active(utc("2024-03-31T07:30:00Z")));
+        assert!(!schedule.is_active(utc("2024-03-31T08:30:00Z")));
+        assert!(!schedule.is_active(utc("2024-03-30T08:30:00Z")));
+
+        assert!(
+            AlertSchedule::from_value(&json!({
+                "timezone": "Mars/Olympus",
+                "windows": [{"days": ["Sun"], "start": "09:00:00", "end": "10:00:00"}]
+            }))
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_channel_disabled_after_repeated_failures_until_reset() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        log_repo
+            .save_batch(&[log_at("log-1", at(30))])
+            .await
+            .unwrap();
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        let channel_repo = Arc::new(InMemoryAlertChannelRepository::new());
+        let channel_id = AlertChannelId::new("channel-1".to_string());
+        channel_repo.seed(AlertChannel::new(
+            channel_id.clone(),
+            ProjectId::new("project-1".to_string()),
+            "on-call".to_string(),
+            ChannelType::Webhook,
+            json!({"url": "https://example.com/hook"}),
+        ));
+        let notifier = Arc::new(RecordingNotifier {
+            failing: true,
+            ..Default::default()
+        });
+        let evaluator = RuleEvaluator::new(
+            Arc::new(InMemoryAlertRuleRepository::new()),
+            Arc::new(InMemoryAlertRepository::new()),
+            channel_repo.clone(),
+            log_repo,
+            project_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            notifier.clone(),
+            60,
+        )
+        .with_aligned_windows(true)
+        .with_channel_failure_limit(3);
+
+        // Each rule fires its own alert and delivers to the channel once
+        let rule = |id: &str| {
+            let mut rule = AlertRule::new(
+                AlertRuleId::new(id.to_string()),
+                ProjectId::new("project-1".to_string()),
+                id.to_string(),
+                None,
+                RuleType::LogCount,
+                json!({}),
+                0.0,
+                ThresholdOperator::GreaterThan,
+                60,
+                UserId::new("user-1".to_string()),
+            );
+            rule.set_channel_ids(vec!["channel-1".to_string()]);
+            rule
+        };
+        for id in ["rule-1", "rule-2"] {
+            evaluator.evaluate_rule_at(&rule(id), at(65)).await.unwrap();
+        }
+        let channel = channel_repo.find_by_id(&channel_id).await.unwrap().unwrap();
+        assert_eq!(channel.health(), ChannelHealth::Failing);
+        assert_eq!(channel.consecutive_failures(), 2);
+
+        evaluator
+            .evaluate_rule_at(&rule("rule-3"), at(65))
+            .await
+            .unwrap();
+        let mut channel = channel_repo.find_by_id(&channel_id).await.unwrap().unwrap();
+        assert_eq!(channel.health(), ChannelHealth::Disabled);
+        assert!(channel.auto_disabled_at().is_some());
+
+        // Disabled channels receive nothing
+        evaluator
+            .evaluate_rule_at(&rule("rule-4"), at(65))
+            .await
+            .unwrap();
+        assert_eq!(*notifier.single.lock().unwrap(), 3);
+
+        channel.reset_health();
+        channel_repo.update(&channel).await.unwrap();
+        evaluator
+            .evaluate_rule_at(&rule("rule-5"), at(65))
+            .await
+            .unwrap();
+        assert_eq!(*notifier.single.lock().unwrap(), 4);
+        let channel = channel_repo.find_by_id(&channel_id).await.unwrap().unwrap();
+        assert!(channel.is_enabled());
+        assert_eq!(channel.consecutive_failures(), 1);
+    }
 }
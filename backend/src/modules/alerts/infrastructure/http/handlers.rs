@@ -1,22 +1,25 @@
 use axum::{
+    Extension, Json,
     extract::{Path, Query, State},
     http::StatusCode,
-    Extension, Json,
 };
 use serde::Deserialize;
 use std::sync::Arc;
 
 use crate::modules::alerts::application::dto::*;
 use crate::modules::alerts::application::services::{
-    AlertChannelService, AlertRuleService, AlertService,
+    AlertChannelService, AlertRuleService, AlertService, NotificationPreferenceService,
+    OrgAlertDefaultsService,
 };
 use crate::modules::alerts::domain::{
     AlertChannelRepository, AlertDomainError, AlertRepository, AlertRuleRepository,
+    NotificationPreferenceRepository, OrgAlertDefaultsRepository,
 };
 use crate::modules::auth::application::ports::IdGenerator;
 use crate::modules::auth::infrastructure::http::extractors::AuthClaims;
 use crate::modules::organizations::domain::OrganizationMemberRepository;
 use crate::modules::projects::domain::ProjectRepository;
+use crate::shared::PAGINATION_LIMIT_HEADER;
 use serde::Serialize;
 
 #[derive(Debug, Serialize)]
@@ -34,6 +37,20 @@ pub struct AlertQueryParams {
     pub offset: Option<i64>,
 }
 
+// Query params for rule export
+#[derive(Debug, Deserialize)]
+pub struct ExportRulesQuery {
+    #[serde(default)]
+    pub include_channels: bool,
+}
+
+// Query params for rule import
+#[derive(Debug, Deserialize)]
+pub struct ImportRulesQuery {
+    #[serde(default)]
+    pub mode: ImportMode,
+}
+
 fn to_error_response(e: AlertDomainError) -> (StatusCode, Json<ErrorResponse>) {
     match e {
         AlertDomainError::InvalidRuleName(msg)
@@ -85,6 +102,13 @@ fn to_error_response(e: AlertDomainError) -> (StatusCode, Json<ErrorResponse>) {
                 code: "RULE_NAME_EXISTS".to_string(),
             }),
         ),
+        AlertDomainError::RuleLimitExceeded(limit) => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: format!("Project has reached its limit of {} alert rules", limit),
+                code: "RULE_LIMIT_EXCEEDED".to_string(),
+            }),
+        ),
         AlertDomainError::ChannelNameExists(name) => (
             StatusCode::CONFLICT,
             Json(ErrorResponse {
@@ -106,7 +130,7 @@ fn to_error_response(e: AlertDomainError) -> (StatusCode, Json<ErrorResponse>) {
                 code: "FORBIDDEN".to_string(),
             }),
         ),
-        AlertDomainError::WebhookFailed(ref msg) => {
+        AlertDomainError::WebhookFailed(ref msg) | AlertDomainError::WebhookTimeout(ref msg) => {
             tracing::warn!(error = %msg, "Webhook failed");
             (
                 StatusCode::BAD_GATEWAY,
@@ -191,6 +215,44 @@ where
     Ok(Json(channel))
 }
 
+pub async fn channel_health<CR, PR, MR, ID>(
+    State(service): State<Arc<AlertChannelService<CR, PR, MR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path((project_id, channel_id)): Path<(String, String)>,
+) -> Result<Json<ChannelHealthResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    CR: AlertChannelRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let health = service
+        .channel_health(&project_id, &channel_id, &claims.user_id)
+        .await
+        .map_err(to_error_response)?;
+
+    Ok(Json(health))
+}
+
+pub async fn reset_channel_health<CR, PR, MR, ID>(
+    State(service): State<Arc<AlertChannelService<CR, PR, MR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path((project_id, channel_id)): Path<(String, String)>,
+) -> Result<Json<ChannelHealthResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    CR: AlertChannelRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let health = service
+        .reset_channel_health(&project_id, &channel_id, &claims.user_id)
+        .await
+        .map_err(to_error_response)?;
+
+    Ok(Json(health))
+}
+
 pub async fn update_channel<CR, PR, MR, ID>(
     State(service): State<Arc<AlertChannelService<CR, PR, MR, ID>>>,
     Extension(claims): Extension<AuthClaims>,
@@ -230,6 +292,84 @@ where
     Ok(StatusCode::NO_CONTENT)
 }
 
+pub async fn create_shared_channel<CR, PR, MR, ID>(
+    State(service): State<Arc<AlertChannelService<CR, PR, MR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(org_id): Path<String>,
+    Json(request): Json<CreateAlertChannelRequest>,
+) -> Result<(StatusCode, Json<AlertChannelResponse>), (StatusCode, Json<ErrorResponse>)>
+where
+    CR: AlertChannelRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let response = service
+        .create_shared_channel(&org_id, request, &claims.user_id)
+        .await
+        .map_err(to_error_response)?;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+pub async fn list_shared_channels<CR, PR, MR, ID>(
+    State(service): State<Arc<AlertChannelService<CR, PR, MR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(org_id): Path<String>,
+) -> Result<Json<Vec<AlertChannelResponse>>, (StatusCode, Json<ErrorResponse>)>
+where
+    CR: AlertChannelRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let channels = service
+        .list_shared_channels(&org_id, &claims.user_id)
+        .await
+        .map_err(to_error_response)?;
+
+    Ok(Json(channels))
+}
+
+pub async fn update_shared_channel<CR, PR, MR, ID>(
+    State(service): State<Arc<AlertChannelService<CR, PR, MR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path((org_id, channel_id)): Path<(String, String)>,
+    Json(request): Json<UpdateAlertChannelRequest>,
+) -> Result<Json<AlertChannelResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    CR: AlertChannelRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let channel = service
+        .update_shared_channel(&org_id, &channel_id, request, &claims.user_id)
+        .await
+        .map_err(to_error_response)?;
+
+    Ok(Json(channel))
+}
+
+pub async fn delete_shared_channel<CR, PR, MR, ID>(
+    State(service): State<Arc<AlertChannelService<CR, PR, MR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path((org_id, channel_id)): Path<(String, String)>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
+where
+    CR: AlertChannelRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    service
+        .delete_shared_channel(&org_id, &channel_id, &claims.user_id)
+        .await
+        .map_err(to_error_response)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 // ============================================================================
 // Alert Rule Handlers
 // ============================================================================
@@ -275,6 +415,27 @@ where
     Ok(Json(rules))
 }
 
+#[allow(clippy::type_complexity)]
+pub async fn rule_usage<RR, CR, PR, MR, ID>(
+    State(service): State<Arc<AlertRuleService<RR, CR, PR, MR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(project_id): Path<String>,
+) -> Result<Json<AlertRuleUsageResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    RR: AlertRuleRepository,
+    CR: AlertChannelRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let usage = service
+        .rule_usage(&project_id, &claims.user_id)
+        .await
+        .map_err(to_error_response)?;
+
+    Ok(Json(usage))
+}
+
 pub async fn get_rule<RR, CR, PR, MR, ID>(
     State(service): State<Arc<AlertRuleService<RR, CR, PR, MR, ID>>>,
     Extension(claims): Extension<AuthClaims>,
@@ -336,6 +497,51 @@ where
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[allow(clippy::type_complexity)]
+pub async fn export_rules<RR, CR, PR, MR, ID>(
+    State(service): State<Arc<AlertRuleService<RR, CR, PR, MR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(project_id): Path<String>,
+    Query(params): Query<ExportRulesQuery>,
+) -> Result<Json<AlertRulesDocument>, (StatusCode, Json<ErrorResponse>)>
+where
+    RR: AlertRuleRepository,
+    CR: AlertChannelRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let document = service
+        .export_rules(&project_id, params.include_channels, &claims.user_id)
+        .await
+        .map_err(to_error_response)?;
+
+    Ok(Json(document))
+}
+
+#[allow(clippy::type_complexity)]
+pub async fn import_rules<RR, CR, PR, MR, ID>(
+    State(service): State<Arc<AlertRuleService<RR, CR, PR, MR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(project_id): Path<String>,
+    Query(params): Query<ImportRulesQuery>,
+    Json(document): Json<AlertRulesDocument>,
+) -> Result<Json<ImportRulesResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    RR: AlertRuleRepository,
+    CR: AlertChannelRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let response = service
+        .import_rules(&project_id, document, params.mode, &claims.user_id)
+        .await
+        .map_err(to_error_response)?;
+
+    Ok(Json(response))
+}
+
 // ============================================================================
 // Alert Handlers
 // ============================================================================
@@ -345,7 +551,7 @@ pub async fn list_alerts<AR, RR, PR, MR>(
     Extension(claims): Extension<AuthClaims>,
     Path(project_id): Path<String>,
     Query(params): Query<AlertQueryParams>,
-) -> Result<Json<AlertListResponse>, (StatusCode, Json<ErrorResponse>)>
+) -> Result<([(&'static str, String); 1], Json<AlertListResponse>), (StatusCode, Json<ErrorResponse>)>
 where
     AR: AlertRepository,
     RR: AlertRuleRepository,
@@ -357,7 +563,10 @@ where
         .await
         .map_err(to_error_response)?;
 
-    Ok(Json(response))
+    Ok((
+        [(PAGINATION_LIMIT_HEADER, response.limit.to_string())],
+        Json(response),
+    ))
 }
 
 pub async fn get_alert<AR, RR, PR, MR>(
@@ -397,3 +606,83 @@ where
 
     Ok(Json(alert))
 }
+
+// ============================================================================
+// Org Alert Defaults Handlers
+// ============================================================================
+
+pub async fn get_org_alert_defaults<DR, CR, RR, MR, ID>(
+    State(service): State<Arc<OrgAlertDefaultsService<DR, CR, RR, MR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(org_id): Path<String>,
+) -> Result<Json<OrgAlertDefaultsResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    DR: OrgAlertDefaultsRepository,
+    CR: AlertChannelRepository,
+    RR: AlertRuleRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let response = service
+        .get_defaults(&org_id, &claims.user_id)
+        .await
+        .map_err(to_error_response)?;
+
+    Ok(Json(response))
+}
+
+pub async fn update_org_alert_defaults<DR, CR, RR, MR, ID>(
+    State(service): State<Arc<OrgAlertDefaultsService<DR, CR, RR, MR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(org_id): Path<String>,
+    Json(req): Json<UpdateOrgAlertDefaultsRequest>,
+) -> Result<Json<OrgAlertDefaultsResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    DR: OrgAlertDefaultsRepository,
+    CR: AlertChannelRepository,
+    RR: AlertRuleRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let response = service
+        .update_defaults(&org_id, req, &claims.user_id)
+        .await
+        .map_err(to_error_response)?;
+
+    Ok(Json(response))
+}
+
+// ============================================================================
+// Notification Preference Handlers
+// ============================================================================
+
+pub async fn get_notification_preferences<NR>(
+    State(service): State<Arc<NotificationPreferenceService<NR>>>,
+    Extension(claims): Extension<AuthClaims>,
+) -> Result<Json<NotificationPreferencesResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    NR: NotificationPreferenceRepository,
+{
+    let response = service
+        .get_preferences(&claims.user_id)
+        .await
+        .map_err(to_error_response)?;
+
+    Ok(Json(response))
+}
+
+pub async fn update_notification_preferences<NR>(
+    State(service): State<Arc<NotificationPreferenceService<NR>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Json(req): Json<UpdateNotificationPreferencesRequest>,
+) -> Result<Json<NotificationPreferencesResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    NR: NotificationPreferenceRepository,
+{
+    let response = service
+        .update_preferences(&claims.user_id, req)
+        .await
+        .map_err(to_error_response)?;
+
+    Ok(Json(response))
+}
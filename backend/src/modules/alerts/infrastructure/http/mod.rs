@@ -1,4 +1,7 @@
 pub mod handlers;
 pub mod routes;
 
-pub use routes::{alert_routes, channel_routes, rule_routes};
+pub use routes::{
+    alert_routes, channel_routes, notification_preference_routes, org_alert_defaults_routes,
+    rule_routes,
+};
@@ -1,15 +1,18 @@
 use axum::{
-    middleware,
+    Router, middleware,
     routing::{delete, get, post, put},
-    Router,
 };
 use std::sync::Arc;
 
 use super::handlers;
 use crate::modules::alerts::application::services::{
-    AlertChannelService, AlertRuleService, AlertService,
+    AlertChannelService, AlertRuleService, AlertService, NotificationPreferenceService,
+    OrgAlertDefaultsService,
+};
+use crate::modules::alerts::domain::{
+    AlertChannelRepository, AlertRepository, AlertRuleRepository, NotificationPreferenceRepository,
+    OrgAlertDefaultsRepository,
 };
-use crate::modules::alerts::domain::{AlertChannelRepository, AlertRepository, AlertRuleRepository};
 use crate::modules::auth::application::ports::{IdGenerator, TokenService};
 use crate::modules::auth::infrastructure::http::middleware::auth_middleware;
 use crate::modules::organizations::domain::OrganizationMemberRepository;
@@ -39,6 +42,24 @@ where
                 .put(handlers::update_channel::<CR, PR, MR, ID>)
                 .delete(handlers::delete_channel::<CR, PR, MR, ID>),
         )
+        .route(
+            "/projects/{project_id}/alert-channels/{channel_id}/health",
+            get(handlers::channel_health::<CR, PR, MR, ID>),
+        )
+        .route(
+            "/projects/{project_id}/alert-channels/{channel_id}/health/reset",
+            post(handlers::reset_channel_health::<CR, PR, MR, ID>),
+        )
+        .route(
+            "/orgs/{org_id}/alert-channels",
+            post(handlers::create_shared_channel::<CR, PR, MR, ID>)
+                .get(handlers::list_shared_channels::<CR, PR, MR, ID>),
+        )
+        .route(
+            "/orgs/{org_id}/alert-channels/{channel_id}",
+            put(handlers::update_shared_channel::<CR, PR, MR, ID>)
+                .delete(handlers::delete_shared_channel::<CR, PR, MR, ID>),
+        )
         .layer(middleware::from_fn_with_state(
             token_service,
             auth_middleware::<TS>,
@@ -65,6 +86,18 @@ where
             post(handlers::create_rule::<RR, CR, PR, MR, ID>)
                 .get(handlers::list_rules::<RR, CR, PR, MR, ID>),
         )
+        .route(
+            "/projects/{id}/alert-rules/usage",
+            get(handlers::rule_usage::<RR, CR, PR, MR, ID>),
+        )
+        .route(
+            "/projects/{id}/alert-rules/export",
+            get(handlers::export_rules::<RR, CR, PR, MR, ID>),
+        )
+        .route(
+            "/projects/{id}/alert-rules/import",
+            post(handlers::import_rules::<RR, CR, PR, MR, ID>),
+        )
         .route(
             "/projects/{project_id}/alert-rules/{rule_id}",
             get(handlers::get_rule::<RR, CR, PR, MR, ID>)
@@ -109,3 +142,51 @@ where
         ))
         .with_state(alert_service)
 }
+
+/// Create organization alert defaults routes (JWT auth)
+pub fn org_alert_defaults_routes<DR, CR, RR, MR, ID, TS>(
+    defaults_service: Arc<OrgAlertDefaultsService<DR, CR, RR, MR, ID>>,
+    token_service: Arc<TS>,
+) -> Router
+where
+    DR: OrgAlertDefaultsRepository + 'static,
+    CR: AlertChannelRepository + 'static,
+    RR: AlertRuleRepository + 'static,
+    MR: OrganizationMemberRepository + 'static,
+    ID: IdGenerator + 'static,
+    TS: TokenService + 'static,
+{
+    Router::new()
+        .route(
+            "/orgs/{org_id}/alert-defaults",
+            get(handlers::get_org_alert_defaults::<DR, CR, RR, MR, ID>)
+                .put(handlers::update_org_alert_defaults::<DR, CR, RR, MR, ID>),
+        )
+        .layer(middleware::from_fn_with_state(
+            token_service,
+            auth_middleware::<TS>,
+        ))
+        .with_state(defaults_service)
+}
+
+/// Create the caller's notification preference routes (JWT auth)
+pub fn notification_preference_routes<NR, TS>(
+    preference_service: Arc<NotificationPreferenceService<NR>>,
+    token_service: Arc<TS>,
+) -> Router
+where
+    NR: NotificationPreferenceRepository + 'static,
+    TS: TokenService + 'static,
+{
+    Router::new()
+        .route(
+            "/users/me/notification-preferences",
+            get(handlers::get_notification_preferences::<NR>)
+                .put(handlers::update_notification_preferences::<NR>),
+        )
+        .layer(middleware::from_fn_with_state(
+            token_service,
+            auth_middleware::<TS>,
+        ))
+        .with_state(preference_service)
+}
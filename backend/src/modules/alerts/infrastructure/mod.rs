@@ -3,9 +3,13 @@ pub mod http;
 pub mod notifiers;
 pub mod persistence;
 
-pub use evaluator::RuleEvaluator;
-pub use http::{alert_routes, channel_routes, rule_routes};
-pub use notifiers::{Notifier, WebhookNotifier};
+pub use evaluator::{EvaluatorHealth, IngestRuleTrigger, RuleEvaluationStats, RuleEvaluator};
+pub use http::{
+    alert_routes, channel_routes, notification_preference_routes, org_alert_defaults_routes,
+    rule_routes,
+};
+pub use notifiers::{Notifier, UserNotificationDispatcher, WebhookNotifier};
 pub use persistence::{
     PostgresAlertChannelRepository, PostgresAlertRepository, PostgresAlertRuleRepository,
+    PostgresNotificationPreferenceRepository, PostgresOrgAlertDefaultsRepository,
 };
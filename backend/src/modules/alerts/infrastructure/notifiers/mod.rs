@@ -1,5 +1,27 @@
 mod notifier;
+mod user_notifications;
 mod webhook;
 
+use std::time::Duration;
+
+use reqwest::Client;
+
 pub use notifier::Notifier;
+pub use user_notifications::{UserAlertNotifier, UserNotificationDispatcher};
 pub use webhook::WebhookNotifier;
+
+/// Default time allowed to establish a connection to a notification receiver
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Default time allowed for a whole notification request, including reading the response
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// HTTP client shared by the HTTP-based notifiers. Requests exceeding either
+/// timeout fail instead of waiting on a slow receiver.
+pub fn http_client(connect_timeout: Duration, request_timeout: Duration) -> Client {
+    Client::builder()
+        .connect_timeout(connect_timeout)
+        .timeout(request_timeout)
+        .build()
+        .expect("Failed to create HTTP client")
+}
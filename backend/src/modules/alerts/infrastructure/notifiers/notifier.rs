@@ -1,7 +1,9 @@
 use async_trait::async_trait;
 use serde_json::Value;
 
-use crate::modules::alerts::application::dto::WebhookPayload;
+use crate::modules::alerts::application::dto::{
+    ChannelDisabledPayload, DigestPayload, GroupedAlertPayload, WebhookPayload,
+};
 use crate::modules::alerts::domain::AlertDomainError;
 
 /// Trait for sending notifications through different channels
@@ -13,4 +15,25 @@ pub trait Notifier: Send + Sync {
         payload: &WebhookPayload,
         channel_config: &Value,
     ) -> Result<(), AlertDomainError>;
+
+    /// Send alerts grouped across rules as a single notification to the configured channel
+    async fn send_group(
+        &self,
+        payload: &GroupedAlertPayload,
+        channel_config: &Value,
+    ) -> Result<(), AlertDomainError>;
+
+    /// Send a digest summarizing several alerts to the configured channel
+    async fn send_digest(
+        &self,
+        payload: &DigestPayload,
+        channel_config: &Value,
+    ) -> Result<(), AlertDomainError>;
+
+    /// Tell an admin that an alert channel was disabled for failing deliveries
+    async fn send_channel_disabled(
+        &self,
+        payload: &ChannelDisabledPayload,
+        channel_config: &Value,
+    ) -> Result<(), AlertDomainError>;
 }
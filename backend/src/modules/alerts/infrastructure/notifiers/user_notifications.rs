@@ -0,0 +1,463 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tokio::time;
+
+use super::notifier::Notifier;
+use crate::modules::alerts::application::dto::{
+    ChannelDisabledPayload, DigestAlert, DigestPayload, WebhookPayload,
+};
+use crate::modules::alerts::domain::{
+    AlertDomainError, AlertSeverity, Delivery, DigestEntry, NotificationPreference,
+    NotificationPreferenceRepository,
+};
+use crate::modules::auth::domain::UserId;
+use crate::modules::organizations::domain::{OrgId, OrganizationMemberRepository};
+
+/// Delivers triggered alerts to individual organization members
+#[async_trait]
+pub trait UserAlertNotifier: Send + Sync {
+    /// Notify the organization's members about an alert, per their preferences
+    async fn notify_members(
+        &self,
+        org_id: &OrgId,
+        payload: &WebhookPayload,
+        severity: AlertSeverity,
+    ) -> Result<(), AlertDomainError>;
+
+    /// Tell the organization's admins and owners that an alert channel was disabled
+    async fn notify_channel_disabled(
+        &self,
+        org_id: &OrgId,
+        payload: &ChannelDisabledPayload,
+    ) -> Result<(), AlertDomainError>;
+}
+
+/// Sends alerts to members' personal channels, right away or held for their
+/// digest, and periodically flushes due digests
+pub struct UserNotificationDispatcher<NR, MR, N>
+where
+    NR: NotificationPreferenceRepository,
+    MR: OrganizationMemberRepository,
+    N: Notifier,
+{
+    preference_repo: Arc<NR>,
+    member_repo: Arc<MR>,
+    notifier: Arc<N>,
+    digest_check_interval_secs: u64,
+}
+
+impl<NR, MR, N> UserNotificationDispatcher<NR, MR, N>
+where
+    NR: NotificationPreferenceRepository + 'static,
+    MR: OrganizationMemberRepository + 'static,
+    N: Notifier + 'static,
+{
+    pub fn new(
+        preference_repo: Arc<NR>,
+        member_repo: Arc<MR>,
+        notifier: Arc<N>,
+        digest_check_interval_secs: u64,
+    ) -> Self {
+        Self {
+            preference_repo,
+            member_repo,
+            notifier,
+            digest_check_interval_secs,
+        }
+    }
+
+    /// Start the digest loop (runs forever)
+    pub async fn start(self: Arc<Self>) {
+        let mut interval =
+            time::interval(time::Duration::from_secs(self.digest_check_interval_secs));
+
+        tracing::info!(
+            interval_secs = self.digest_check_interval_secs,
+            "Starting notification digest scheduler"
+        );
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.flush_digests(Utc::now()).await {
+                tracing::error!(error = %e, "Error sending notification digests");
+            }
+        }
+    }
+
+    async fn dispatch(
+        &self,
+        org_id: &OrgId,
+        payload: &WebhookPayload,
+        severity: AlertSeverity,
+    ) -> Result<(), AlertDomainError> {
+        let user_ids: Vec<UserId> = self
+            .member_repo
+            .find_all_by_org(org_id)
+            .await
+            .map_err(|e| AlertDomainError::InternalError(e.to_string()))?
+            .iter()
+            .map(|m| m.user_id().clone())
+            .collect();
+        if user_ids.is_empty() {
+            return Ok(());
+        }
+
+        for preference in self.preference_repo.find_by_users(&user_ids).await? {
+            let Some(channel_config) = preference.channel_config() else {
+                continue;
+            };
+            match preference.delivery(severity, payload.triggered_at) {
+                Delivery::Immediate => {
+                    if let Err(e) = self.notifier.send(payload, channel_config).await {
+                        tracing::warn!(
+                            user_id = %preference.user_id().as_str(),
+                            alert_id = %payload.alert_id,
+                            error = %e,
+                            "Failed to send personal notification"
+                        );
+                    }
+                }
+                Delivery::Digest => {
+                    self.preference_repo
+                        .queue_digest_entry(&DigestEntry {
+                            user_id: preference.user_id().clone(),
+                            alert_id: payload.alert_id.clone(),
+                            rule_name: payload.rule_name.clone(),
+                            project_name: payload.project_name.clone(),
+                            severity,
+                            message: payload.message.clone(),
+                            triggered_at: payload.triggered_at,
+                        })
+                        .await?;
+                }
+                Delivery::Skip => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send to the personal channel of each admin and owner that set one up
+    async fn dispatch_to_admins(
+        &self,
+        org_id: &OrgId,
+        payload: &ChannelDisabledPayload,
+    ) -> Result<(), AlertDomainError> {
+        let user_ids: Vec<UserId> = self
+            .member_repo
+            .find_all_by_org(org_id)
+            .await
+            .map_err(|e| AlertDomainError::InternalError(e.to_string()))?
+            .iter()
+            .filter(|m| m.role().can_manage_members())
+            .map(|m| m.user_id().clone())
+            .collect();
+        if user_ids.is_empty() {
+            return Ok(());
+        }
+
+        for preference in self.preference_repo.find_by_users(&user_ids).await? {
+            let Some(channel_config) = preference.channel_config() else {
+                continue;
+            };
+            if let Err(e) = self
+                .notifier
+                .send_channel_disabled(payload, channel_config)
+                .await
+            {
+                tracing::warn!(
+                    user_id = %preference.user_id().as_str(),
+                    channel_id = %payload.channel_id,
+                    error = %e,
+                    "Failed to notify admin about disabled channel"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send every digest due at `now`. Returns how many were sent.
+    pub async fn flush_digests(&self, now: DateTime<Utc>) -> Result<u32, AlertDomainError> {
+        let mut sent = 0;
+        for mut preference in self.preference_repo.find_digest_enabled().await? {
+            if !preference.digest_due(now) {
+                continue;
+            }
+            let entries = self
+                .preference_repo
+                .take_digest_entries(preference.user_id())
+                .await?;
+            if !entries.is_empty() {
+                if !self.send_digest(&preference, &entries).await {
+                    // Hold the alerts for the next attempt rather than dropping them
+                    for entry in &entries {
+                        self.preference_repo.queue_digest_entry(entry).await?;
+                    }
+                    continue;
+                }
+                sent += 1;
+            }
+            // Empty periods still count, so digests keep a steady schedule
+            preference.mark_digest_sent(now);
+            self.preference_repo.save(&preference).await?;
+        }
+        Ok(sent)
+    }
+
+    async fn send_digest(
+        &self,
+        preference: &NotificationPreference,
+        entries: &[DigestEntry],
+    ) -> bool {
+        let Some(channel_config) = preference.channel_config() else {
+            return false;
+        };
+        let payload = DigestPayload {
+            user_id: preference.user_id().as_str().to_string(),
+            alert_count: entries.len(),
+            alerts: entries
+                .iter()
+                .map(|e| DigestAlert {
+                    alert_id: e.alert_id.clone(),
+                    rule_name: e.rule_name.clone(),
+                    project_name: e.project_name.clone(),
+                    severity: e.severity.as_str().to_string(),
+                    message: e.message.clone(),
+                    triggered_at: e.triggered_at,
+                })
+                .collect(),
+            message: format!("{} alerts since your last digest", entries.len()),
+        };
+        match self.notifier.send_digest(&payload, channel_config).await {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!(
+                    user_id = %preference.user_id().as_str(),
+                    error = %e,
+                    "Failed to send notification digest"
+                );
+                false
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<NR, MR, N> UserAlertNotifier for UserNotificationDispatcher<NR, MR, N>
+where
+    NR: NotificationPreferenceRepository + 'static,
+    MR: OrganizationMemberRepository + 'static,
+    N: Notifier + 'static,
+{
+    async fn notify_members(
+        &self,
+        org_id: &OrgId,
+        payload: &WebhookPayload,
+        severity: AlertSeverity,
+    ) -> Result<(), AlertDomainError> {
+        self.dispatch(org_id, payload, severity).await
+    }
+
+    async fn notify_channel_disabled(
+        &self,
+        org_id: &OrgId,
+        payload: &ChannelDisabledPayload,
+    ) -> Result<(), AlertDomainError> {
+        self.dispatch_to_admins(org_id, payload).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use serde_json::{Value, json};
+    use std::sync::Mutex;
+
+    use crate::modules::alerts::application::dto::GroupedAlertPayload;
+    use crate::modules::organizations::domain::OrgRole;
+    use crate::shared::testing::{
+        InMemoryMemberRepository, InMemoryNotificationPreferenceRepository,
+    };
+
+    #[derive(Default)]
+    struct RecordingNotifier {
+        sent: Mutex<Vec<String>>,
+        digests: Mutex<Vec<DigestPayload>>,
+        /// Channel config URL of each channel-disabled notice
+        channel_disabled: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Notifier for RecordingNotifier {
+        async fn send(
+            &self,
+            payload: &WebhookPayload,
+            _channel_config: &Value,
+        ) -> Result<(), AlertDomainError> {
+            self.sent.lock().unwrap().push(payload.alert_id.clone());
+            Ok(())
+        }
+
+        async fn send_group(
+            &self,
+            _payload: &GroupedAlertPayload,
+            _channel_config: &Value,
+        ) -> Result<(), AlertDomainError> {
+            Ok(())
+        }
+
+        async fn send_digest(
+            &self,
+            payload: &DigestPayload,
+            _channel_config: &Value,
+        ) -> Result<(), AlertDomainError> {
+            self.digests.lock().unwrap().push(payload.clone());
+            Ok(())
+        }
+
+        async fn send_channel_disabled(
+            &self,
+            _payload: &ChannelDisabledPayload,
+            channel_config: &Value,
+        ) -> Result<(), AlertDomainError> {
+            self.channel_disabled.lock().unwrap().push(
+                channel_config["url"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            );
+            Ok(())
+        }
+    }
+
+    fn payload(alert_id: &str, severity: AlertSeverity, at: DateTime<Utc>) -> WebhookPayload {
+        WebhookPayload {
+            alert_id: alert_id.to_string(),
+            rule_id: "rule-1".to_string(),
+            rule_name: "High error rate".to_string(),
+            project_id: "proj-1".to_string(),
+            project_name: "Checkout".to_string(),
+            status: "active".to_string(),
+            triggered_at: at,
+            trigger_value: 12.0,
+            threshold: 10.0,
+            threshold_operator: "gt".to_string(),
+            severity: severity.as_str().to_string(),
+            message: "error_rate is 12 (threshold: gt 10)".to_string(),
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_warnings_batched_in_digest_while_critical_sends_immediately() {
+        let t0 = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let preference_repo = Arc::new(InMemoryNotificationPreferenceRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        let notifier = Arc::new(RecordingNotifier::default());
+        member_repo.seed("org-1", "user-1", OrgRole::Member);
+
+        let mut preference = NotificationPreference::new(UserId::new("user-1".to_string()));
+        preference
+            .update_channel_config(Some(json!({"url": "https://example.com/me"})))
+            .unwrap();
+        preference.update_digest(true, 60).unwrap();
+        preference.mark_digest_sent(t0);
+        preference_repo.save(&preference).await.unwrap();
+
+        let dispatcher = UserNotificationDispatcher::new(
+            preference_repo.clone(),
+            member_repo,
+            notifier.clone(),
+            60,
+        );
+        let org_id = OrgId::new("org-1".to_string());
+        for (id, severity) in [
+            ("alert-1", AlertSeverity::Warning),
+            ("alert-2", AlertSeverity::Critical),
+            ("alert-3", AlertSeverity::Warning),
+        ] {
+            dispatcher
+                .notify_members(&org_id, &payload(id, severity, t0), severity)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(*notifier.sent.lock().unwrap(), vec!["alert-2".to_string()]);
+        assert_eq!(preference_repo.queued().len(), 2);
+
+        // Inside the digest window nothing goes out yet
+        assert_eq!(
+            dispatcher
+                .flush_digests(t0 + Duration::minutes(30))
+                .await
+                .unwrap(),
+            0
+        );
+        assert!(notifier.digests.lock().unwrap().is_empty());
+
+        assert_eq!(
+            dispatcher
+                .flush_digests(t0 + Duration::minutes(61))
+                .await
+                .unwrap(),
+            1
+        );
+        let digests = notifier.digests.lock().unwrap();
+        assert_eq!(digests.len(), 1);
+        assert_eq!(digests[0].alert_count, 2);
+        assert_eq!(digests[0].alerts[0].alert_id, "alert-1");
+        assert_eq!(digests[0].alerts[1].alert_id, "alert-3");
+        assert!(preference_repo.queued().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_channel_disabled_notice_goes_to_admins_only() {
+        let preference_repo = Arc::new(InMemoryNotificationPreferenceRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        let notifier = Arc::new(RecordingNotifier::default());
+        for (user, role) in [
+            ("owner", OrgRole::Owner),
+            ("admin", OrgRole::Admin),
+            ("member", OrgRole::Member),
+        ] {
+            member_repo.seed("org-1", user, role);
+            let mut preference = NotificationPreference::new(UserId::new(user.to_string()));
+            let url = format!("https://example.com/{}", user);
+            preference
+                .update_channel_config(Some(json!({ "url": url })))
+                .unwrap();
+            preference_repo.save(&preference).await.unwrap();
+        }
+        let dispatcher =
+            UserNotificationDispatcher::new(preference_repo, member_repo, notifier.clone(), 60);
+
+        let payload = ChannelDisabledPayload {
+            channel_id: "channel-1".to_string(),
+            channel_name: "on-call".to_string(),
+            project_id: Some("proj-1".to_string()),
+            organization_id: "org-1".to_string(),
+            consecutive_failures: 10,
+            last_error: Some("status 500".to_string()),
+            disabled_at: Utc::now(),
+            message: "disabled".to_string(),
+        };
+        dispatcher
+            .notify_channel_disabled(&OrgId::new("org-1".to_string()), &payload)
+            .await
+            .unwrap();
+
+        let mut urls = notifier.channel_disabled.lock().unwrap().clone();
+        urls.sort();
+        assert_eq!(
+            urls,
+            vec![
+                "https://example.com/admin".to_string(),
+                "https://example.com/owner".to_string()
+            ]
+        );
+    }
+}
@@ -1,40 +1,44 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use reqwest::Client;
+use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::time::Duration;
 
 use super::notifier::Notifier;
-use crate::modules::alerts::application::dto::WebhookPayload;
-use crate::modules::alerts::domain::AlertDomainError;
+use super::{DEFAULT_CONNECT_TIMEOUT, DEFAULT_REQUEST_TIMEOUT, http_client};
+use crate::modules::alerts::application::dto::{
+    ChannelDisabledPayload, DigestPayload, GroupedAlertPayload, WebhookPayload,
+};
+use crate::modules::alerts::domain::{AlertDomainError, WebhookPayloadVersion};
 
-/// Webhook notifier - sends alerts to HTTP endpoints
+/// Webhook notifier - sends alerts to HTTP endpoints, in the payload
+/// version each channel is configured for (see [`WebhookPayloadVersion`])
 pub struct WebhookNotifier {
     client: Client,
 }
 
 impl WebhookNotifier {
-    pub fn new() -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(10))
-            .build()
-            .expect("Failed to create HTTP client");
-
+    /// Create a notifier sending through `client`, see [`http_client`]
+    pub fn new(client: Client) -> Self {
         Self { client }
     }
 }
 
 impl Default for WebhookNotifier {
     fn default() -> Self {
-        Self::new()
+        Self::new(http_client(
+            DEFAULT_CONNECT_TIMEOUT,
+            DEFAULT_REQUEST_TIMEOUT,
+        ))
     }
 }
 
-#[async_trait]
-impl Notifier for WebhookNotifier {
-    async fn send(
+impl WebhookNotifier {
+    /// POST `body` as JSON to the webhook described by `channel_config`
+    async fn post<T: Serialize + Sync>(
         &self,
-        payload: &WebhookPayload,
+        body: &T,
         channel_config: &Value,
     ) -> Result<(), AlertDomainError> {
         // Get URL from config
@@ -63,16 +67,21 @@ impl Notifier for WebhookNotifier {
             .map(String::from);
 
         // Build request
-        let mut request = self.client.post(url).json(&payload);
+        let mut request = self.client.post(url).json(body);
 
         // Add custom headers
         for (key, value) in headers {
             request = request.header(&key, &value);
         }
 
-        // Send request
+        // Send request; timeouts and connection errors are worth retrying
         let response = request.send().await.map_err(|e| {
-            AlertDomainError::InternalError(format!("Webhook request failed: {}", e))
+            if e.is_timeout() {
+                tracing::warn!(url, error = %e, "Webhook request timed out");
+                AlertDomainError::WebhookTimeout(e.to_string())
+            } else {
+                AlertDomainError::WebhookFailed(e.to_string())
+            }
         })?;
 
         if !response.status().is_success() {
@@ -89,7 +98,7 @@ impl Notifier for WebhookNotifier {
                 "Webhook returned non-success status"
             );
 
-            return Err(AlertDomainError::InternalError(format!(
+            return Err(AlertDomainError::WebhookFailed(format!(
                 "Webhook returned status {}: {}",
                 status, body
             )));
@@ -100,3 +109,334 @@ impl Notifier for WebhookNotifier {
         Ok(())
     }
 }
+
+// ==================== Payload v2 ====================
+
+/// Every v2 payload: what happened in `event`, the details in `data`
+#[derive(Serialize)]
+struct EnvelopeV2<T: Serialize> {
+    version: u8,
+    event: &'static str,
+    sent_at: DateTime<Utc>,
+    data: T,
+}
+
+impl<T: Serialize> EnvelopeV2<T> {
+    fn new(event: &'static str, data: T) -> Self {
+        Self {
+            version: WebhookPayloadVersion::V2.as_u8(),
+            event,
+            sent_at: Utc::now(),
+            data,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct AlertV2<'a> {
+    alert: AlertDetailsV2<'a>,
+    rule: RefV2<'a>,
+    project: RefV2<'a>,
+}
+
+#[derive(Serialize)]
+struct AlertDetailsV2<'a> {
+    id: &'a str,
+    status: &'a str,
+    severity: &'a str,
+    message: &'a str,
+    triggered_at: DateTime<Utc>,
+    value: f64,
+    threshold: ThresholdV2<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<&'a Value>,
+}
+
+#[derive(Serialize)]
+struct ThresholdV2<'a> {
+    operator: &'a str,
+    value: f64,
+}
+
+#[derive(Serialize)]
+struct RefV2<'a> {
+    id: &'a str,
+    name: &'a str,
+}
+
+#[derive(Serialize)]
+struct AlertGroupV2<'a> {
+    project: RefV2<'a>,
+    group: GroupKeyV2<'a>,
+    message: &'a str,
+    alerts: Vec<AlertV2<'a>>,
+}
+
+#[derive(Serialize)]
+struct GroupKeyV2<'a> {
+    key: &'a str,
+    value: &'a str,
+}
+
+impl<'a> From<&'a WebhookPayload> for AlertV2<'a> {
+    fn from(p: &'a WebhookPayload) -> Self {
+        Self {
+            alert: AlertDetailsV2 {
+                id: &p.alert_id,
+                status: &p.status,
+                severity: &p.severity,
+                message: &p.message,
+                triggered_at: p.triggered_at,
+                value: p.trigger_value,
+                threshold: ThresholdV2 {
+                    operator: &p.threshold_operator,
+                    value: p.threshold,
+                },
+                metadata: p.metadata.as_ref(),
+            },
+            rule: RefV2 {
+                id: &p.rule_id,
+                name: &p.rule_name,
+            },
+            project: RefV2 {
+                id: &p.project_id,
+                name: &p.project_name,
+            },
+        }
+    }
+}
+
+impl<'a> From<&'a GroupedAlertPayload> for AlertGroupV2<'a> {
+    fn from(p: &'a GroupedAlertPayload) -> Self {
+        Self {
+            project: RefV2 {
+                id: &p.project_id,
+                name: &p.project_name,
+            },
+            group: GroupKeyV2 {
+                key: &p.group_key,
+                value: &p.group_value,
+            },
+            message: &p.message,
+            alerts: p.alerts.iter().map(AlertV2::from).collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn send(
+        &self,
+        payload: &WebhookPayload,
+        channel_config: &Value,
+    ) -> Result<(), AlertDomainError> {
+        match WebhookPayloadVersion::from_config(channel_config)? {
+            WebhookPayloadVersion::V1 => self.post(payload, channel_config).await,
+            WebhookPayloadVersion::V2 => {
+                let body = EnvelopeV2::new("alert", AlertV2::from(payload));
+                self.post(&body, channel_config).await
+            }
+        }
+    }
+
+    async fn send_group(
+        &self,
+        payload: &GroupedAlertPayload,
+        channel_config: &Value,
+    ) -> Result<(), AlertDomainError> {
+        match WebhookPayloadVersion::from_config(channel_config)? {
+            WebhookPayloadVersion::V1 => self.post(payload, channel_config).await,
+            WebhookPayloadVersion::V2 => {
+                let body = EnvelopeV2::new("alert_group", AlertGroupV2::from(payload));
+                self.post(&body, channel_config).await
+            }
+        }
+    }
+
+    async fn send_digest(
+        &self,
+        payload: &DigestPayload,
+        channel_config: &Value,
+    ) -> Result<(), AlertDomainError> {
+        match WebhookPayloadVersion::from_config(channel_config)? {
+            WebhookPayloadVersion::V1 => self.post(payload, channel_config).await,
+            WebhookPayloadVersion::V2 => {
+                self.post(&EnvelopeV2::new("digest", payload), channel_config)
+                    .await
+            }
+        }
+    }
+
+    async fn send_channel_disabled(
+        &self,
+        payload: &ChannelDisabledPayload,
+        channel_config: &Value,
+    ) -> Result<(), AlertDomainError> {
+        match WebhookPayloadVersion::from_config(channel_config)? {
+            WebhookPayloadVersion::V1 => self.post(payload, channel_config).await,
+            WebhookPayloadVersion::V2 => {
+                self.post(
+                    &EnvelopeV2::new("channel_disabled", payload),
+                    channel_config,
+                )
+                .await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use crate::modules::alerts::domain::{Alert, AlertId, AlertRuleId};
+    use crate::modules::projects::domain::ProjectId;
+
+    fn payload() -> WebhookPayload {
+        WebhookPayload {
+            alert_id: "alert-1".to_string(),
+            rule_id: "rule-1".to_string(),
+            rule_name: "errors".to_string(),
+            project_id: "project-1".to_string(),
+            project_name: "Project 1".to_string(),
+            status: "firing".to_string(),
+            triggered_at: Utc::now(),
+            trigger_value: 5.0,
+            threshold: 1.0,
+            threshold_operator: "gt".to_string(),
+            severity: "warning".to_string(),
+            message: "too many errors".to_string(),
+            metadata: None,
+        }
+    }
+
+    /// Receive one webhook and return its JSON body
+    async fn receive_one(listener: TcpListener) -> Value {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = socket.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let content_length = text[..header_end]
+                    .lines()
+                    .find_map(|l| {
+                        let l = l.to_lowercase();
+                        l.strip_prefix("content-length:")?
+                            .trim()
+                            .parse::<usize>()
+                            .ok()
+                    })
+                    .unwrap_or(0);
+                let body = &request[header_end + 4..];
+                if body.len() >= content_length {
+                    socket
+                        .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                        .await
+                        .unwrap();
+                    return serde_json::from_slice(body).unwrap();
+                }
+            }
+        }
+    }
+
+    async fn delivered(channel_config: Value) -> Value {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut config = channel_config;
+        config["url"] = json!(format!("http://{}/hook", listener.local_addr().unwrap()));
+        let receiver = tokio::spawn(receive_one(listener));
+
+        WebhookNotifier::default()
+            .send(&payload(), &config)
+            .await
+            .unwrap();
+        receiver.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_v1_channel_receives_legacy_payload() {
+        // Channels created before versioning have no payload_version
+        for config in [json!({}), json!({ "payload_version": 1 })] {
+            let body = delivered(config).await;
+            assert_eq!(body["alert_id"], "alert-1");
+            assert_eq!(body["rule_name"], "errors");
+            assert_eq!(body["trigger_value"], 5.0);
+            assert_eq!(body["threshold_operator"], "gt");
+            assert!(body.get("version").is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_v2_channel_receives_versioned_envelope() {
+        let body = delivered(json!({ "payload_version": 2 })).await;
+
+        assert_eq!(body["version"], 2);
+        assert_eq!(body["event"], "alert");
+        assert_eq!(body["data"]["alert"]["id"], "alert-1");
+        assert_eq!(body["data"]["alert"]["value"], 5.0);
+        assert_eq!(body["data"]["alert"]["threshold"]["operator"], "gt");
+        assert_eq!(body["data"]["rule"]["name"], "errors");
+        assert_eq!(body["data"]["project"]["id"], "project-1");
+        assert!(body.get("alert_id").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_payload_version_is_rejected() {
+        let result = WebhookNotifier::default()
+            .send(
+                &payload(),
+                &json!({ "url": "http://127.0.0.1:1/hook", "payload_version": 9 }),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(AlertDomainError::InvalidChannelConfig(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_slow_receiver_times_out_and_is_recorded_as_failed() {
+        // Accepts and reads the request but never responds
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        });
+
+        let notifier = WebhookNotifier::new(http_client(
+            Duration::from_millis(200),
+            Duration::from_millis(200),
+        ));
+        let result = notifier
+            .send(
+                &payload(),
+                &json!({ "url": format!("http://{}/hook", addr) }),
+            )
+            .await;
+
+        assert!(matches!(result, Err(AlertDomainError::WebhookTimeout(_))));
+
+        let mut alert = Alert::new(
+            AlertId::new("alert-1".to_string()),
+            AlertRuleId::new("rule-1".to_string()),
+            ProjectId::new("project-1".to_string()),
+            5.0,
+            "too many errors".to_string(),
+            None,
+        );
+        alert.record_notification("channel-1", &result, Utc::now());
+        let notification = &alert.metadata().unwrap()["notifications"][0];
+        assert_eq!(notification["status"], "failed");
+        assert_eq!(notification["retryable"], true);
+    }
+}
@@ -2,7 +2,11 @@ mod models;
 mod postgres_alert_channel_repo;
 mod postgres_alert_repo;
 mod postgres_alert_rule_repo;
+mod postgres_notification_preference_repo;
+mod postgres_org_alert_defaults_repo;
 
 pub use postgres_alert_channel_repo::PostgresAlertChannelRepository;
 pub use postgres_alert_repo::PostgresAlertRepository;
 pub use postgres_alert_rule_repo::PostgresAlertRuleRepository;
+pub use postgres_notification_preference_repo::PostgresNotificationPreferenceRepository;
+pub use postgres_org_alert_defaults_repo::PostgresOrgAlertDefaultsRepository;
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::FromRow;
 use uuid::Uuid;
@@ -14,22 +15,31 @@ pub struct AlertRuleRow {
     pub threshold_value: f64,
     pub threshold_operator: String,
     pub time_window_seconds: i32,
+    pub cooldown_seconds: i32,
+    pub severity: String,
     pub is_enabled: bool,
     pub last_evaluated_at: Option<DateTime<Utc>>,
     pub last_triggered_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub created_by: Uuid,
+    pub parent_rule_id: Option<Uuid>,
+    pub evaluate_on_ingest: bool,
+    pub schedule: Option<Value>,
 }
 
 #[derive(Debug, FromRow)]
 pub struct AlertChannelRow {
     pub id: Uuid,
-    pub project_id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub organization_id: Option<Uuid>,
     pub name: String,
     pub channel_type: String,
     pub config: Value,
     pub is_enabled: bool,
+    pub consecutive_failures: i32,
+    pub last_delivery_error: Option<String>,
+    pub auto_disabled_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -52,3 +62,51 @@ pub struct RuleChannelRow {
     pub rule_id: Uuid,
     pub channel_id: Uuid,
 }
+
+#[derive(Debug, FromRow)]
+pub struct NotificationPreferenceRow {
+    pub user_id: String,
+    pub channel_config: Option<Value>,
+    pub severities: Vec<String>,
+    pub mute_windows: Value,
+    pub digest_enabled: bool,
+    pub digest_interval_minutes: i32,
+    pub last_digest_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct DigestEntryRow {
+    pub user_id: String,
+    pub alert_id: String,
+    pub rule_name: String,
+    pub project_name: String,
+    pub severity: String,
+    pub message: String,
+    pub triggered_at: DateTime<Utc>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct OrgAlertDefaultsRow {
+    pub organization_id: String,
+    pub enabled: bool,
+    pub channel_id: String,
+    pub rules: Value,
+    pub updated_by: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A default rule as stored in `org_alert_defaults.rules`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RuleTemplateRecord {
+    pub name: String,
+    pub description: Option<String>,
+    pub rule_type: String,
+    pub config: Value,
+    pub threshold_value: f64,
+    pub threshold_operator: String,
+    pub time_window_seconds: i32,
+    pub cooldown_seconds: i32,
+    pub severity: String,
+    pub is_enabled: bool,
+}
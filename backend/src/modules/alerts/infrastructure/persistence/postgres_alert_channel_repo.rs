@@ -5,8 +5,10 @@ use uuid::Uuid;
 
 use super::models::AlertChannelRow;
 use crate::modules::alerts::domain::{
-    AlertChannel, AlertChannelId, AlertChannelRepository, AlertDomainError, ChannelType,
+    AlertChannel, AlertChannelId, AlertChannelRepository, AlertDomainError, ChannelScope,
+    ChannelType,
 };
+use crate::modules::organizations::domain::OrgId;
 use crate::modules::projects::domain::ProjectId;
 
 pub struct PostgresAlertChannelRepository {
@@ -19,13 +21,24 @@ impl PostgresAlertChannelRepository {
     }
 
     fn row_to_entity(&self, row: AlertChannelRow) -> AlertChannel {
+        // The single-scope check constraint guarantees exactly one owner is set
+        let scope = match (row.project_id, row.organization_id) {
+            (None, Some(org_id)) => ChannelScope::Organization(OrgId::new(org_id.to_string())),
+            (project_id, _) => {
+                ChannelScope::Project(ProjectId::new(project_id.unwrap_or_default().to_string()))
+            }
+        };
+
         AlertChannel::from_db(
             AlertChannelId::new(row.id.to_string()),
-            ProjectId::new(row.project_id.to_string()),
+            scope,
             row.name,
             ChannelType::from_str(&row.channel_type).unwrap_or(ChannelType::Webhook),
             row.config,
             row.is_enabled,
+            row.consecutive_failures.max(0) as u32,
+            row.last_delivery_error,
+            row.auto_disabled_at,
             row.created_at,
             row.updated_at,
         )
@@ -37,19 +50,28 @@ impl AlertChannelRepository for PostgresAlertChannelRepository {
     async fn save(&self, channel: &AlertChannel) -> Result<(), AlertDomainError> {
         let id = Uuid::parse_str(channel.id().as_str())
             .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
-        let project_id = Uuid::parse_str(channel.project_id().as_str())
+        let project_id = channel
+            .project_id()
+            .map(|id| Uuid::parse_str(id.as_str()))
+            .transpose()
+            .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+        let organization_id = channel
+            .organization_id()
+            .map(|id| Uuid::parse_str(id.as_str()))
+            .transpose()
             .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
 
         sqlx::query(
             r#"
             INSERT INTO alert_channels (
-                id, project_id, name, channel_type, config,
+                id, project_id, organization_id, name, channel_type, config,
                 is_enabled, created_at, updated_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             "#,
         )
         .bind(id)
         .bind(project_id)
+        .bind(organization_id)
         .bind(channel.name())
         .bind(channel.channel_type().as_str())
         .bind(channel.config())
@@ -98,6 +120,24 @@ impl AlertChannelRepository for PostgresAlertChannelRepository {
         Ok(rows.into_iter().map(|r| self.row_to_entity(r)).collect())
     }
 
+    async fn find_by_organization(
+        &self,
+        org_id: &OrgId,
+    ) -> Result<Vec<AlertChannel>, AlertDomainError> {
+        let uuid = Uuid::parse_str(org_id.as_str())
+            .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+
+        let rows: Vec<AlertChannelRow> = sqlx::query_as(
+            r#"SELECT * FROM alert_channels WHERE organization_id = $1 ORDER BY created_at DESC"#,
+        )
+        .bind(uuid)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|r| self.row_to_entity(r)).collect())
+    }
+
     async fn find_by_ids(&self, ids: &[String]) -> Result<Vec<AlertChannel>, AlertDomainError> {
         if ids.is_empty() {
             return Ok(Vec::new());
@@ -131,7 +171,10 @@ impl AlertChannelRepository for PostgresAlertChannelRepository {
                 name = $2,
                 config = $3,
                 is_enabled = $4,
-                updated_at = $5
+                consecutive_failures = $5,
+                last_delivery_error = $6,
+                auto_disabled_at = $7,
+                updated_at = $8
             WHERE id = $1
             "#,
         )
@@ -139,6 +182,9 @@ impl AlertChannelRepository for PostgresAlertChannelRepository {
         .bind(channel.name())
         .bind(channel.config())
         .bind(channel.is_enabled())
+        .bind(channel.consecutive_failures() as i32)
+        .bind(channel.last_delivery_error())
+        .bind(channel.auto_disabled_at())
         .bind(channel.updated_at())
         .execute(self.pool.as_ref())
         .await
@@ -194,4 +240,39 @@ impl AlertChannelRepository for PostgresAlertChannelRepository {
             .await
             .map_err(|e| AlertDomainError::InternalError(e.to_string()))
     }
+
+    async fn org_name_exists(
+        &self,
+        org_id: &OrgId,
+        name: &str,
+        exclude_id: Option<&AlertChannelId>,
+    ) -> Result<bool, AlertDomainError> {
+        let org_uuid = Uuid::parse_str(org_id.as_str())
+            .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+
+        let query = match exclude_id {
+            Some(id) => {
+                let exclude_uuid = Uuid::parse_str(id.as_str())
+                    .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+                sqlx::query_scalar::<_, bool>(
+                    r#"SELECT EXISTS(SELECT 1 FROM alert_channels WHERE organization_id = $1 AND name = $2 AND id != $3)"#,
+                )
+                .bind(org_uuid)
+                .bind(name)
+                .bind(exclude_uuid)
+            }
+            None => {
+                sqlx::query_scalar::<_, bool>(
+                    r#"SELECT EXISTS(SELECT 1 FROM alert_channels WHERE organization_id = $1 AND name = $2)"#,
+                )
+                .bind(org_uuid)
+                .bind(name)
+            }
+        };
+
+        query
+            .fetch_one(self.pool.as_ref())
+            .await
+            .map_err(|e| AlertDomainError::InternalError(e.to_string()))
+    }
 }
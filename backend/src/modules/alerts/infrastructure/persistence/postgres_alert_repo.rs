@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -148,13 +149,15 @@ impl AlertRepository for PostgresAlertRepository {
             r#"
             UPDATE alerts SET
                 status = $2,
-                resolved_at = $3
+                resolved_at = $3,
+                metadata = $4
             WHERE id = $1
             "#,
         )
         .bind(id)
         .bind(alert.status().as_str())
         .bind(alert.resolved_at())
+        .bind(alert.metadata())
         .execute(self.pool.as_ref())
         .await
         .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
@@ -166,13 +169,38 @@ impl AlertRepository for PostgresAlertRepository {
         let uuid = Uuid::parse_str(project_id.as_str())
             .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
 
-        let count: i64 =
-            sqlx::query_scalar(r#"SELECT COUNT(*) FROM alerts WHERE project_id = $1"#)
-                .bind(uuid)
-                .fetch_one(self.pool.as_ref())
-                .await
-                .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+        let count: i64 = sqlx::query_scalar(r#"SELECT COUNT(*) FROM alerts WHERE project_id = $1"#)
+            .bind(uuid)
+            .fetch_one(self.pool.as_ref())
+            .await
+            .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
 
         Ok(count)
     }
+
+    async fn delete_resolved_before(
+        &self,
+        before: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<u64, AlertDomainError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM alerts
+            WHERE id IN (
+                SELECT id
+                FROM alerts
+                WHERE status = 'resolved' AND resolved_at < $1
+                ORDER BY resolved_at
+                LIMIT $2
+            )
+            "#,
+        )
+        .bind(before)
+        .bind(limit as i64)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
 }
@@ -5,7 +5,8 @@ use uuid::Uuid;
 
 use super::models::{AlertRuleRow, RuleChannelRow};
 use crate::modules::alerts::domain::{
-    AlertDomainError, AlertRule, AlertRuleId, AlertRuleRepository, RuleType, ThresholdOperator,
+    AlertDomainError, AlertRule, AlertRuleId, AlertRuleRepository, AlertSchedule, AlertSeverity,
+    RuleType, ThresholdOperator,
 };
 use crate::modules::auth::domain::UserId;
 use crate::modules::projects::domain::ProjectId;
@@ -31,6 +32,8 @@ impl PostgresAlertRuleRepository {
             ThresholdOperator::from_str(&row.threshold_operator)
                 .unwrap_or(ThresholdOperator::GreaterThan),
             row.time_window_seconds,
+            row.cooldown_seconds,
+            AlertSeverity::from_str(&row.severity).unwrap_or_default(),
             row.is_enabled,
             row.last_evaluated_at,
             row.last_triggered_at,
@@ -38,6 +41,11 @@ impl PostgresAlertRuleRepository {
             row.updated_at,
             UserId::new(row.created_by.to_string()),
             channel_ids,
+            row.parent_rule_id
+                .map(|id| AlertRuleId::new(id.to_string())),
+            row.evaluate_on_ingest,
+            row.schedule
+                .and_then(|schedule| AlertSchedule::from_value(&schedule).ok()),
         )
     }
 }
@@ -51,6 +59,7 @@ impl AlertRuleRepository for PostgresAlertRuleRepository {
             .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
         let created_by = Uuid::parse_str(rule.created_by().as_str())
             .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+        let parent_rule_id = parse_parent_rule_id(rule)?;
 
         sqlx::query(
             r#"
@@ -58,8 +67,9 @@ impl AlertRuleRepository for PostgresAlertRuleRepository {
                 id, project_id, name, description, rule_type, config,
                 threshold_value, threshold_operator, time_window_seconds,
                 is_enabled, last_evaluated_at, last_triggered_at,
-                created_at, updated_at, created_by
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+                created_at, updated_at, created_by, cooldown_seconds, severity,
+                parent_rule_id, evaluate_on_ingest, schedule
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
             "#,
         )
         .bind(id)
@@ -77,6 +87,11 @@ impl AlertRuleRepository for PostgresAlertRuleRepository {
         .bind(rule.created_at())
         .bind(rule.updated_at())
         .bind(created_by)
+        .bind(rule.cooldown_seconds())
+        .bind(rule.severity().as_str())
+        .bind(parent_rule_id)
+        .bind(rule.evaluate_on_ingest())
+        .bind(schedule_json(rule))
         .execute(self.pool.as_ref())
         .await
         .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
@@ -93,13 +108,12 @@ impl AlertRuleRepository for PostgresAlertRuleRepository {
         let uuid = Uuid::parse_str(id.as_str())
             .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
 
-        let row: Option<AlertRuleRow> = sqlx::query_as(
-            r#"SELECT * FROM alert_rules WHERE id = $1"#,
-        )
-        .bind(uuid)
-        .fetch_optional(self.pool.as_ref())
-        .await
-        .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+        let row: Option<AlertRuleRow> =
+            sqlx::query_as(r#"SELECT * FROM alert_rules WHERE id = $1"#)
+                .bind(uuid)
+                .fetch_optional(self.pool.as_ref())
+                .await
+                .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
 
         match row {
             Some(r) => {
@@ -135,13 +149,23 @@ impl AlertRuleRepository for PostgresAlertRuleRepository {
         Ok(rules)
     }
 
+    async fn count_by_project(&self, project_id: &ProjectId) -> Result<i64, AlertDomainError> {
+        let uuid = Uuid::parse_str(project_id.as_str())
+            .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+
+        sqlx::query_scalar(r#"SELECT COUNT(*) FROM alert_rules WHERE project_id = $1"#)
+            .bind(uuid)
+            .fetch_one(self.pool.as_ref())
+            .await
+            .map_err(|e| AlertDomainError::InternalError(e.to_string()))
+    }
+
     async fn find_all_enabled(&self) -> Result<Vec<AlertRule>, AlertDomainError> {
-        let rows: Vec<AlertRuleRow> = sqlx::query_as(
-            r#"SELECT * FROM alert_rules WHERE is_enabled = true"#,
-        )
-        .fetch_all(self.pool.as_ref())
-        .await
-        .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+        let rows: Vec<AlertRuleRow> =
+            sqlx::query_as(r#"SELECT * FROM alert_rules WHERE is_enabled = true"#)
+                .fetch_all(self.pool.as_ref())
+                .await
+                .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
 
         let mut rules = Vec::new();
         for row in rows {
@@ -156,6 +180,7 @@ impl AlertRuleRepository for PostgresAlertRuleRepository {
     async fn update(&self, rule: &AlertRule) -> Result<(), AlertDomainError> {
         let id = Uuid::parse_str(rule.id().as_str())
             .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+        let parent_rule_id = parse_parent_rule_id(rule)?;
 
         sqlx::query(
             r#"
@@ -169,7 +194,13 @@ impl AlertRuleRepository for PostgresAlertRuleRepository {
                 is_enabled = $8,
                 last_evaluated_at = $9,
                 last_triggered_at = $10,
-                updated_at = $11
+                updated_at = $11,
+                rule_type = $12,
+                cooldown_seconds = $13,
+                severity = $14,
+                parent_rule_id = $15,
+                evaluate_on_ingest = $16,
+                schedule = $17
             WHERE id = $1
             "#,
         )
@@ -184,6 +215,12 @@ impl AlertRuleRepository for PostgresAlertRuleRepository {
         .bind(rule.last_evaluated_at())
         .bind(rule.last_triggered_at())
         .bind(rule.updated_at())
+        .bind(rule.rule_type().as_str())
+        .bind(rule.cooldown_seconds())
+        .bind(rule.severity().as_str())
+        .bind(parent_rule_id)
+        .bind(rule.evaluate_on_ingest())
+        .bind(schedule_json(rule))
         .execute(self.pool.as_ref())
         .await
         .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
@@ -224,13 +261,11 @@ impl AlertRuleRepository for PostgresAlertRuleRepository {
                 .bind(name)
                 .bind(exclude_uuid)
             }
-            None => {
-                sqlx::query_scalar::<_, bool>(
-                    r#"SELECT EXISTS(SELECT 1 FROM alert_rules WHERE project_id = $1 AND name = $2)"#,
-                )
-                .bind(project_uuid)
-                .bind(name)
-            }
+            None => sqlx::query_scalar::<_, bool>(
+                r#"SELECT EXISTS(SELECT 1 FROM alert_rules WHERE project_id = $1 AND name = $2)"#,
+            )
+            .bind(project_uuid)
+            .bind(name),
         };
 
         query
@@ -259,14 +294,12 @@ impl AlertRuleRepository for PostgresAlertRuleRepository {
             let channel_uuid = Uuid::parse_str(channel_id)
                 .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
 
-            sqlx::query(
-                r#"INSERT INTO alert_rule_channels (rule_id, channel_id) VALUES ($1, $2)"#,
-            )
-            .bind(rule_uuid)
-            .bind(channel_uuid)
-            .execute(self.pool.as_ref())
-            .await
-            .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+            sqlx::query(r#"INSERT INTO alert_rule_channels (rule_id, channel_id) VALUES ($1, $2)"#)
+                .bind(rule_uuid)
+                .bind(channel_uuid)
+                .execute(self.pool.as_ref())
+                .await
+                .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
         }
 
         Ok(())
@@ -290,3 +323,15 @@ impl AlertRuleRepository for PostgresAlertRuleRepository {
         Ok(rows.into_iter().map(|r| r.channel_id.to_string()).collect())
     }
 }
+
+fn parse_parent_rule_id(rule: &AlertRule) -> Result<Option<Uuid>, AlertDomainError> {
+    rule.parent_rule_id()
+        .map(|id| Uuid::parse_str(id.as_str()))
+        .transpose()
+        .map_err(|e| AlertDomainError::InternalError(e.to_string()))
+}
+
+fn schedule_json(rule: &AlertRule) -> Option<serde_json::Value> {
+    rule.schedule()
+        .and_then(|schedule| serde_json::to_value(schedule).ok())
+}
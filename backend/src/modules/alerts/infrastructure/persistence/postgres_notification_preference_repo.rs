@@ -0,0 +1,170 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::models::{DigestEntryRow, NotificationPreferenceRow};
+use crate::modules::alerts::domain::{
+    AlertDomainError, AlertSeverity, DigestEntry, NotificationPreference,
+    NotificationPreferenceRepository,
+};
+use crate::modules::auth::domain::UserId;
+
+pub struct PostgresNotificationPreferenceRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresNotificationPreferenceRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_entity(&self, row: NotificationPreferenceRow) -> NotificationPreference {
+        NotificationPreference::from_db(
+            UserId::new(row.user_id),
+            row.channel_config,
+            row.severities
+                .iter()
+                .filter_map(|s| AlertSeverity::from_str(s).ok())
+                .collect(),
+            serde_json::from_value(row.mute_windows).unwrap_or_default(),
+            row.digest_enabled,
+            row.digest_interval_minutes,
+            row.last_digest_at,
+            row.updated_at,
+        )
+    }
+}
+
+#[async_trait]
+impl NotificationPreferenceRepository for PostgresNotificationPreferenceRepository {
+    async fn find_by_user(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Option<NotificationPreference>, AlertDomainError> {
+        let row: Option<NotificationPreferenceRow> =
+            sqlx::query_as(r#"SELECT * FROM user_notification_preferences WHERE user_id = $1"#)
+                .bind(user_id.as_str())
+                .fetch_optional(self.pool.as_ref())
+                .await
+                .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+
+        Ok(row.map(|r| self.row_to_entity(r)))
+    }
+
+    async fn find_by_users(
+        &self,
+        user_ids: &[UserId],
+    ) -> Result<Vec<NotificationPreference>, AlertDomainError> {
+        let ids: Vec<&str> = user_ids.iter().map(|id| id.as_str()).collect();
+
+        let rows: Vec<NotificationPreferenceRow> = sqlx::query_as(
+            r#"SELECT * FROM user_notification_preferences WHERE user_id = ANY($1)"#,
+        )
+        .bind(&ids)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|r| self.row_to_entity(r)).collect())
+    }
+
+    async fn find_digest_enabled(&self) -> Result<Vec<NotificationPreference>, AlertDomainError> {
+        let rows: Vec<NotificationPreferenceRow> = sqlx::query_as(
+            r#"SELECT * FROM user_notification_preferences WHERE digest_enabled = true"#,
+        )
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|r| self.row_to_entity(r)).collect())
+    }
+
+    async fn save(&self, preference: &NotificationPreference) -> Result<(), AlertDomainError> {
+        let severities: Vec<&str> = preference.severities().iter().map(|s| s.as_str()).collect();
+        let mute_windows = serde_json::to_value(preference.mute_windows())
+            .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_notification_preferences (
+                user_id, channel_config, severities, mute_windows, digest_enabled,
+                digest_interval_minutes, last_digest_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (user_id) DO UPDATE SET
+                channel_config = EXCLUDED.channel_config,
+                severities = EXCLUDED.severities,
+                mute_windows = EXCLUDED.mute_windows,
+                digest_enabled = EXCLUDED.digest_enabled,
+                digest_interval_minutes = EXCLUDED.digest_interval_minutes,
+                last_digest_at = EXCLUDED.last_digest_at,
+                updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(preference.user_id().as_str())
+        .bind(preference.channel_config())
+        .bind(&severities)
+        .bind(mute_windows)
+        .bind(preference.digest_enabled())
+        .bind(preference.digest_interval_minutes())
+        .bind(preference.last_digest_at())
+        .bind(preference.updated_at())
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn queue_digest_entry(&self, entry: &DigestEntry) -> Result<(), AlertDomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO notification_digest_entries (
+                user_id, alert_id, rule_name, project_name, severity, message, triggered_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(entry.user_id.as_str())
+        .bind(&entry.alert_id)
+        .bind(&entry.rule_name)
+        .bind(&entry.project_name)
+        .bind(entry.severity.as_str())
+        .bind(&entry.message)
+        .bind(entry.triggered_at)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn take_digest_entries(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<DigestEntry>, AlertDomainError> {
+        let mut rows: Vec<DigestEntryRow> = sqlx::query_as(
+            r#"
+            DELETE FROM notification_digest_entries WHERE user_id = $1
+            RETURNING user_id, alert_id, rule_name, project_name, severity, message, triggered_at
+            "#,
+        )
+        .bind(user_id.as_str())
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+        // DELETE ... RETURNING has no ORDER BY
+        rows.sort_by_key(|r| r.triggered_at);
+
+        Ok(rows
+            .into_iter()
+            .map(|r| DigestEntry {
+                user_id: UserId::new(r.user_id),
+                alert_id: r.alert_id,
+                rule_name: r.rule_name,
+                project_name: r.project_name,
+                severity: AlertSeverity::from_str(&r.severity).unwrap_or_default(),
+                message: r.message,
+                triggered_at: r.triggered_at,
+            })
+            .collect())
+    }
+}
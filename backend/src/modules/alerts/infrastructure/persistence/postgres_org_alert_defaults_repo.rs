@@ -0,0 +1,114 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::models::{OrgAlertDefaultsRow, RuleTemplateRecord};
+use crate::modules::alerts::domain::{
+    AlertChannelId, AlertDomainError, AlertRuleTemplate, AlertSeverity, OrgAlertDefaults,
+    OrgAlertDefaultsRepository, RuleType, ThresholdOperator,
+};
+use crate::modules::auth::domain::UserId;
+use crate::modules::organizations::domain::OrgId;
+
+pub struct PostgresOrgAlertDefaultsRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresOrgAlertDefaultsRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_entity(&self, row: OrgAlertDefaultsRow) -> OrgAlertDefaults {
+        let records: Vec<RuleTemplateRecord> =
+            serde_json::from_value(row.rules).unwrap_or_default();
+        let rules = records
+            .into_iter()
+            .map(|r| AlertRuleTemplate {
+                name: r.name,
+                description: r.description,
+                rule_type: RuleType::from_str(&r.rule_type).unwrap_or(RuleType::ErrorRate),
+                config: r.config,
+                threshold_value: r.threshold_value,
+                threshold_operator: ThresholdOperator::from_str(&r.threshold_operator)
+                    .unwrap_or(ThresholdOperator::GreaterThan),
+                time_window_seconds: r.time_window_seconds,
+                cooldown_seconds: r.cooldown_seconds,
+                severity: AlertSeverity::from_str(&r.severity).unwrap_or_default(),
+                is_enabled: r.is_enabled,
+            })
+            .collect();
+
+        OrgAlertDefaults::from_db(
+            OrgId::new(row.organization_id),
+            row.enabled,
+            AlertChannelId::new(row.channel_id),
+            rules,
+            UserId::new(row.updated_by),
+            row.updated_at,
+        )
+    }
+}
+
+#[async_trait]
+impl OrgAlertDefaultsRepository for PostgresOrgAlertDefaultsRepository {
+    async fn find_by_org(
+        &self,
+        org_id: &OrgId,
+    ) -> Result<Option<OrgAlertDefaults>, AlertDomainError> {
+        let row: Option<OrgAlertDefaultsRow> =
+            sqlx::query_as(r#"SELECT * FROM org_alert_defaults WHERE organization_id = $1"#)
+                .bind(org_id.as_str())
+                .fetch_optional(self.pool.as_ref())
+                .await
+                .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+
+        Ok(row.map(|r| self.row_to_entity(r)))
+    }
+
+    async fn save(&self, defaults: &OrgAlertDefaults) -> Result<(), AlertDomainError> {
+        let records: Vec<RuleTemplateRecord> = defaults
+            .rules()
+            .iter()
+            .map(|r| RuleTemplateRecord {
+                name: r.name.clone(),
+                description: r.description.clone(),
+                rule_type: r.rule_type.as_str().to_string(),
+                config: r.config.clone(),
+                threshold_value: r.threshold_value,
+                threshold_operator: r.threshold_operator.as_str().to_string(),
+                time_window_seconds: r.time_window_seconds,
+                cooldown_seconds: r.cooldown_seconds,
+                severity: r.severity.as_str().to_string(),
+                is_enabled: r.is_enabled,
+            })
+            .collect();
+        let rules = serde_json::to_value(records)
+            .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO org_alert_defaults (
+                organization_id, enabled, channel_id, rules, updated_by, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (organization_id) DO UPDATE SET
+                enabled = EXCLUDED.enabled,
+                channel_id = EXCLUDED.channel_id,
+                rules = EXCLUDED.rules,
+                updated_by = EXCLUDED.updated_by,
+                updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(defaults.org_id().as_str())
+        .bind(defaults.enabled())
+        .bind(defaults.channel_id().as_str())
+        .bind(rules)
+        .bind(defaults.updated_by().as_str())
+        .bind(defaults.updated_at())
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| AlertDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -3,13 +3,19 @@ pub mod domain;
 pub mod infrastructure;
 
 pub use application::dto;
-pub use application::services::{AlertChannelService, AlertRuleService, AlertService};
+pub use application::services::{
+    AlertChannelService, AlertRuleService, AlertService, NotificationPreferenceService,
+    OrgAlertDefaultsService,
+};
 pub use domain::{
     Alert, AlertChannel, AlertChannelId, AlertChannelRepository, AlertDomainError, AlertId,
     AlertRepository, AlertRule, AlertRuleId, AlertRuleRepository, AlertStatus, ChannelType,
     RuleType, ThresholdOperator,
 };
 pub use infrastructure::{
-    alert_routes, channel_routes, rule_routes, Notifier, PostgresAlertChannelRepository,
-    PostgresAlertRepository, PostgresAlertRuleRepository, RuleEvaluator, WebhookNotifier,
+    EvaluatorHealth, IngestRuleTrigger, Notifier, PostgresAlertChannelRepository,
+    PostgresAlertRepository, PostgresAlertRuleRepository, PostgresNotificationPreferenceRepository,
+    PostgresOrgAlertDefaultsRepository, RuleEvaluationStats, RuleEvaluator,
+    UserNotificationDispatcher, WebhookNotifier, alert_routes, channel_routes,
+    notification_preference_routes, org_alert_defaults_routes, rule_routes,
 };
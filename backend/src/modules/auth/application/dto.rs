@@ -1,23 +1,49 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::modules::auth::domain::OAuthProvider;
+
 // ============================================================================
 // Commands (inputs)
 // ============================================================================
 
+/// Where a request came from, recorded on audit events
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
 /// Command to register a new user
 #[derive(Debug, Clone)]
 pub struct RegisterUserCommand {
     pub email: String,
+    pub username: Option<String>,
     pub password: String,
     pub device_fingerprint: String, // Hash of User-Agent + IP subnet
+    pub client: ClientInfo,
 }
 
 /// Command to login
 #[derive(Debug, Clone)]
 pub struct LoginCommand {
-    pub email: String,
+    pub identifier: String, // Email or username
     pub password: String,
     pub device_fingerprint: String, // Hash of User-Agent + IP subnet
+    pub client: ClientInfo,
+}
+
+/// Command to sign in with an account a provider has authenticated
+#[derive(Debug, Clone)]
+pub struct OAuthLoginCommand {
+    pub provider: OAuthProvider,
+    /// The provider's stable account id
+    pub external_id: String,
+    /// Address the provider has verified
+    pub email: String,
+    pub display_name: Option<String>,
+    pub device_fingerprint: String,
+    pub client: ClientInfo,
 }
 
 /// Command to logout
@@ -25,6 +51,7 @@ pub struct LoginCommand {
 pub struct LogoutCommand {
     pub user_id: String,
     pub refresh_token: Option<String>, // If provided, revoke specific token only
+    pub client: ClientInfo,
 }
 
 /// Command to refresh tokens
@@ -32,6 +59,7 @@ pub struct LogoutCommand {
 pub struct RefreshTokenCommand {
     pub refresh_token: String,
     pub device_fingerprint: String, // Hash of User-Agent + IP subnet
+    pub client: ClientInfo,
 }
 
 /// Command to change user's email
@@ -40,6 +68,7 @@ pub struct ChangeEmailCommand {
     pub user_id: String,
     pub current_password: String,
     pub new_email: String,
+    pub client: ClientInfo,
 }
 
 /// Command to change user's password
@@ -48,6 +77,7 @@ pub struct ChangePasswordCommand {
     pub user_id: String,
     pub current_password: String,
     pub new_password: String,
+    pub client: ClientInfo,
 }
 
 /// Command to delete user's account
@@ -55,6 +85,104 @@ pub struct ChangePasswordCommand {
 pub struct DeleteAccountCommand {
     pub user_id: String,
     pub current_password: String,
+    pub client: ClientInfo,
+}
+
+/// Command to verify an email address with the token sent to it
+#[derive(Debug, Clone)]
+pub struct VerifyEmailCommand {
+    pub token: String,
+    pub client: ClientInfo,
+}
+
+/// Command to send a new verification email
+#[derive(Debug, Clone)]
+pub struct ResendVerificationCommand {
+    pub user_id: String,
+}
+
+/// Command to email a password reset token
+#[derive(Debug, Clone)]
+pub struct RequestPasswordResetCommand {
+    pub email: String,
+    pub client: ClientInfo,
+}
+
+/// Command to set a new password with a password reset token
+#[derive(Debug, Clone)]
+pub struct ResetPasswordCommand {
+    pub token: String,
+    pub new_password: String,
+    pub client: ClientInfo,
+}
+
+/// Command to sign out one of the user's sessions
+#[derive(Debug, Clone)]
+pub struct RevokeSessionCommand {
+    pub user_id: String,
+    pub session_id: String,
+    pub client: ClientInfo,
+}
+
+/// Command to create a personal access token
+#[derive(Debug, Clone)]
+pub struct CreatePersonalAccessTokenCommand {
+    pub user_id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    /// Days until the token stops working; None never expires
+    pub expires_in_days: Option<i64>,
+    pub client: ClientInfo,
+}
+
+/// Command to revoke one of the user's personal access tokens
+#[derive(Debug, Clone)]
+pub struct RevokePersonalAccessTokenCommand {
+    pub user_id: String,
+    pub token_id: String,
+    pub client: ClientInfo,
+}
+
+/// Command to sign out every session but the caller's own
+#[derive(Debug, Clone)]
+pub struct RevokeOtherSessionsCommand {
+    pub user_id: String,
+    /// Refresh token of the session to keep
+    pub refresh_token: String,
+    pub client: ClientInfo,
+}
+
+/// Command to start TOTP setup
+#[derive(Debug, Clone)]
+pub struct EnableTotpCommand {
+    pub user_id: String,
+}
+
+/// Command to finish TOTP setup with a code from the authenticator app
+#[derive(Debug, Clone)]
+pub struct ConfirmTotpCommand {
+    pub user_id: String,
+    pub code: String,
+    pub client: ClientInfo,
+}
+
+/// Command to turn TOTP off; OAuth-only users re-authenticate with a
+/// current code instead of a password
+#[derive(Debug, Clone)]
+pub struct DisableTotpCommand {
+    pub user_id: String,
+    pub current_password: Option<String>,
+    pub code: Option<String>,
+    pub client: ClientInfo,
+}
+
+/// Command to finish a login that needs a second factor
+#[derive(Debug, Clone)]
+pub struct VerifyTotpCommand {
+    pub challenge_token: String,
+    pub code: String,               // App code or recovery code
+    pub device_fingerprint: String, // Hash of User-Agent + IP subnet
+    pub client: ClientInfo,
 }
 
 /// Command to update user's display name
@@ -69,6 +197,21 @@ pub struct UpdateDisplayNameCommand {
 pub struct UpdateSettingsCommand {
     pub user_id: String,
     pub allow_invites: Option<bool>,
+    pub default_org_id: Option<String>, // Empty string clears the preference
+}
+
+/// Command to list auth audit events
+#[derive(Debug, Clone)]
+pub struct ListAuditEventsCommand {
+    pub requesting_user_id: String,
+    pub org_id: Option<String>, // Org the caller's token is scoped to
+    pub user_id: Option<String>,
+    pub event_type: Option<String>,
+    pub success: Option<bool>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
 }
 
 // ============================================================================
@@ -80,7 +223,9 @@ pub struct UpdateSettingsCommand {
 pub struct AuthResponse {
     pub user_id: String,
     pub email: String,
+    pub username: Option<String>,
     pub display_name: Option<String>,
+    pub default_org_id: Option<String>, // Org the issued tokens are scoped to
     pub access_token: String,
     pub refresh_token: String,
     pub token_type: String,
@@ -88,10 +233,13 @@ pub struct AuthResponse {
 }
 
 impl AuthResponse {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         user_id: String,
         email: String,
+        username: Option<String>,
         display_name: Option<String>,
+        default_org_id: Option<String>,
         access_token: String,
         refresh_token: String,
         expires_in: i64,
@@ -99,7 +247,9 @@ impl AuthResponse {
         Self {
             user_id,
             email,
+            username,
             display_name,
+            default_org_id,
             access_token,
             refresh_token,
             token_type: "Bearer".to_string(),
@@ -108,12 +258,40 @@ impl AuthResponse {
     }
 }
 
+/// Outcome of a password login
+#[derive(Debug, Clone)]
+pub enum LoginResult {
+    /// Signed in; tokens issued
+    Authenticated(AuthResponse),
+    /// Password accepted, but the account has 2FA: finish with `verify_totp`
+    TotpRequired(TotpChallengeResponse),
+}
+
+/// Token to present with the second factor
+#[derive(Debug, Clone, Serialize)]
+pub struct TotpChallengeResponse {
+    pub challenge_token: String,
+    pub expires_in: i64, // seconds until the challenge expires
+}
+
+/// What the user needs to add the account to an authenticator app.
+/// Recovery codes are only ever shown here.
+#[derive(Debug, Clone, Serialize)]
+pub struct TotpSetupResponse {
+    pub secret: String,      // base32, for manual entry
+    pub otpauth_uri: String, // for rendering as a QR code
+    pub recovery_codes: Vec<String>,
+}
+
 /// User data transfer object
 #[derive(Debug, Clone, Serialize)]
 pub struct UserDto {
     pub id: String,
     pub email: String,
+    pub email_verified: bool,
+    pub username: Option<String>,
     pub display_name: Option<String>,
+    pub default_org_id: Option<String>,
     pub created_at: String,
 }
 
@@ -121,4 +299,58 @@ pub struct UserDto {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserSettingsResponse {
     pub allow_invites: bool,
+    pub default_org_id: Option<String>,
+}
+
+/// A personal access token, without its secret
+#[derive(Debug, Clone, Serialize)]
+pub struct PersonalAccessTokenResponse {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A newly created personal access token; `token` is never shown again
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedPersonalAccessTokenResponse {
+    pub token: String,
+    pub details: PersonalAccessTokenResponse,
+}
+
+/// A signed-in session (device) of the user
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionResponse {
+    pub id: String,
+    /// When the session signed in
+    pub created_at: DateTime<Utc>,
+    /// When its tokens were last issued or refreshed
+    pub last_used_at: DateTime<Utc>,
+    pub ip_address: Option<String>,
+    /// Truncated user agent of the client
+    pub user_agent: Option<String>,
+}
+
+/// Auth audit event response
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEventResponse {
+    pub id: String,
+    pub event_type: String,
+    pub success: bool,
+    pub user_id: Option<String>,
+    pub identifier: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub failure_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A page of auth audit events
+#[derive(Debug, Clone)]
+pub struct AuditEventListResponse {
+    pub events: Vec<AuditEventResponse>,
+    /// Effective page size after applying the default and maximum
+    pub limit: i64,
 }
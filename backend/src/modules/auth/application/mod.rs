@@ -2,6 +2,17 @@ pub mod dto;
 pub mod ports;
 pub mod services;
 
-pub use dto::{AuthResponse, ChangeEmailCommand, ChangePasswordCommand, DeleteAccountCommand, LoginCommand, LogoutCommand, RefreshTokenCommand, RegisterUserCommand, UpdateDisplayNameCommand, UpdateSettingsCommand, UserDto, UserSettingsResponse};
+pub use dto::{
+    AuditEventResponse, AuthResponse, ChangeEmailCommand, ChangePasswordCommand, ClientInfo,
+    ConfirmTotpCommand, CreatePersonalAccessTokenCommand, CreatedPersonalAccessTokenResponse,
+    DeleteAccountCommand, DisableTotpCommand, EnableTotpCommand, ListAuditEventsCommand,
+    LoginCommand, LoginResult, LogoutCommand, OAuthLoginCommand, PersonalAccessTokenResponse,
+    RefreshTokenCommand, RegisterUserCommand, RequestPasswordResetCommand,
+    ResendVerificationCommand, ResetPasswordCommand, RevokeOtherSessionsCommand,
+    RevokePersonalAccessTokenCommand, RevokeSessionCommand, SessionResponse, TotpSetupResponse,
+    UpdateDisplayNameCommand, UpdateSettingsCommand, UserDto, UserSettingsResponse,
+    VerifyEmailCommand, VerifyTotpCommand,
+};
+pub use ports::PersonalAccessTokenVerifier;
 pub use ports::{IdGenerator, TokenClaims, TokenPair, TokenService};
 pub use services::AuthService;
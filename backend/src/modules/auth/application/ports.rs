@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 
-use crate::modules::auth::domain::{AuthDomainError, UserId};
+use crate::modules::auth::domain::{AuthDomainError, TokenScope, UserId};
 
 /// Organization context for token generation
 #[derive(Debug, Clone)]
@@ -18,6 +18,7 @@ pub struct TokenClaims {
     pub org_role: Option<String>,
     pub exp: i64,
     pub iat: i64,
+    pub auth_time: i64, // when the session was authenticated
 }
 
 /// Token pair returned after successful authentication
@@ -41,6 +42,16 @@ pub trait TokenService: Send + Sync {
         org_context: Option<OrgContext>,
     ) -> Result<TokenPair, AuthDomainError>;
 
+    /// Generate a token pair continuing a session authenticated at `session_started_at`.
+    /// Fails with `TokenExpired` once the session has reached its maximum lifetime.
+    async fn refresh_token_pair(
+        &self,
+        user_id: &UserId,
+        email: &str,
+        org_context: Option<OrgContext>,
+        session_started_at: i64,
+    ) -> Result<TokenPair, AuthDomainError>;
+
     /// Validate access token and extract claims
     fn validate_access_token(&self, token: &str) -> Result<TokenClaims, AuthDomainError>;
 
@@ -51,8 +62,41 @@ pub trait TokenService: Send + Sync {
     fn hash_refresh_token(&self, token: &str) -> String;
 }
 
+/// The user a personal access token acts for, and what it may do
+#[derive(Debug, Clone)]
+pub struct PersonalAccessTokenIdentity {
+    pub user_id: String,
+    pub email: String,
+    pub scopes: Vec<TokenScope>,
+}
+
+/// Port for authenticating `pat_...` bearer tokens
+/// Lets the HTTP layer check tokens without knowing the auth service's types
+#[async_trait]
+pub trait PersonalAccessTokenVerifier: Send + Sync {
+    async fn verify_personal_access_token(
+        &self,
+        token: &str,
+    ) -> Result<PersonalAccessTokenIdentity, AuthDomainError>;
+}
+
 /// Port for ID generation
 /// Infrastructure layer implements this with UUID
 pub trait IdGenerator: Send + Sync {
     fn generate(&self) -> String;
 }
+
+/// An email to one recipient
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String, // Plain text
+}
+
+/// Port for sending account emails such as address verification
+/// Infrastructure layer decides how mail is delivered
+#[async_trait]
+pub trait EmailSender: Send + Sync {
+    async fn send(&self, message: &EmailMessage) -> Result<(), AuthDomainError>;
+}
@@ -1,25 +1,56 @@
 use std::sync::Arc;
 
-use chrono::{Duration, Utc};
+use async_trait::async_trait;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Duration, Utc};
 use rand::Rng;
 
 use crate::modules::auth::application::dto::{
-    AuthResponse, ChangeEmailCommand, ChangePasswordCommand, DeleteAccountCommand, LoginCommand,
-    LogoutCommand, RefreshTokenCommand, RegisterUserCommand, UpdateDisplayNameCommand,
-    UpdateSettingsCommand, UserSettingsResponse,
+    AuditEventListResponse, AuditEventResponse, AuthResponse, ChangeEmailCommand,
+    ChangePasswordCommand, ClientInfo, ConfirmTotpCommand, CreatePersonalAccessTokenCommand,
+    CreatedPersonalAccessTokenResponse, DeleteAccountCommand, DisableTotpCommand,
+    EnableTotpCommand, ListAuditEventsCommand, LoginCommand, LoginResult, LogoutCommand,
+    OAuthLoginCommand, PersonalAccessTokenResponse, RefreshTokenCommand, RegisterUserCommand,
+    RequestPasswordResetCommand, ResendVerificationCommand, ResetPasswordCommand,
+    RevokeOtherSessionsCommand, RevokePersonalAccessTokenCommand, RevokeSessionCommand,
+    SessionResponse, TotpChallengeResponse, TotpSetupResponse, UpdateDisplayNameCommand,
+    UpdateSettingsCommand, UserSettingsResponse, VerifyEmailCommand, VerifyTotpCommand,
+};
+use crate::modules::auth::application::ports::{
+    EmailMessage, EmailSender, IdGenerator, OrgContext, PersonalAccessTokenIdentity,
+    PersonalAccessTokenVerifier, TokenService,
 };
-use crate::modules::auth::application::ports::{IdGenerator, OrgContext, TokenService};
 use crate::modules::auth::domain::{
-    AuthDomainError, DisplayName, Email, PasswordHash, PasswordHasher, PlainPassword, RefreshToken,
-    RefreshTokenRepository, TokenId, User, UserId, UserRepository,
+    AuditEventId, AuthAuditEvent, AuthAuditEventType, AuthAuditFilters, AuthAuditRepository,
+    AuthDomainError, DisplayName, Email, OAuthIdentity, OAuthIdentityRepository, PasswordHash,
+    PasswordHasher, PasswordResetToken, PasswordResetTokenRepository, PersonalAccessToken,
+    PersonalAccessTokenRepository, PlainPassword, RefreshToken, RefreshTokenRepository, TokenId,
+    TokenScope, TotpChallenge, TotpSecret, User, UserId, UserRepository, UserTotp,
+    UserTotpRepository, Username, VerificationToken, VerificationTokenRepository,
+    hash_one_time_secret, hash_token,
 };
 use crate::modules::organizations::domain::{
-    MemberId, OrgId, OrgName, OrgRole, OrgSlug, Organization, OrganizationMember,
+    AuthMethods, MemberId, OrgId, OrgName, OrgRole, OrgSlug, Organization, OrganizationMember,
     OrganizationMemberRepository, OrganizationRepository,
 };
+use crate::modules::projects::application::ports::EmailVerificationCheck;
+use crate::modules::projects::domain::ProjectDomainError;
+use crate::shared::PaginationConfig;
+
+/// Issuer shown next to the account in authenticator apps
+const TOTP_ISSUER: &str = "Altenia";
+
+/// Recovery codes generated when 2FA is set up
+const TOTP_RECOVERY_CODE_COUNT: usize = 10;
+
+/// How long a login has to present its second factor
+const TOTP_CHALLENGE_TTL_SECS: i64 = 300;
+
+/// Characters of a session's user agent shown in the session list
+const SESSION_USER_AGENT_MAX_CHARS: usize = 120;
 
 /// Authentication service - orchestrates all auth use cases
-pub struct AuthService<U, T, P, TS, ID, OR, MR>
+pub struct AuthService<U, T, P, TS, ID, OR, MR, AA, TT>
 where
     U: UserRepository,
     T: RefreshTokenRepository,
@@ -28,6 +59,8 @@ where
     ID: IdGenerator,
     OR: OrganizationRepository,
     MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
 {
     user_repo: Arc<U>,
     token_repo: Arc<T>,
@@ -36,11 +69,26 @@ where
     id_generator: Arc<ID>,
     org_repo: Arc<OR>,
     member_repo: Arc<MR>,
+    audit_repo: Arc<AA>,
+    totp_repo: Arc<TT>,
+    /// When false, no auth audit events are recorded
+    audit_enabled: bool,
+    /// Stores the tokens sent by `email_sender`; without both, no verification emails are sent
+    verification_repo: Option<Arc<dyn VerificationTokenRepository>>,
+    email_sender: Option<Arc<dyn EmailSender>>,
+    /// Stores the tokens of password reset emails; without it, passwords can't be reset
+    password_reset_repo: Option<Arc<dyn PasswordResetTokenRepository>>,
+    /// Provider accounts linked to users; without it, OAuth sign-in is refused
+    oauth_identity_repo: Option<Arc<dyn OAuthIdentityRepository>>,
+    /// Stores personal access tokens; without it, they can't be created or used
+    personal_access_token_repo: Option<Arc<dyn PersonalAccessTokenRepository>>,
+    /// Page size limits of the audit event list
+    pagination: PaginationConfig,
     /// Pre-computed dummy hash for timing attack mitigation
     dummy_password_hash: PasswordHash,
 }
 
-impl<U, T, P, TS, ID, OR, MR> AuthService<U, T, P, TS, ID, OR, MR>
+impl<U, T, P, TS, ID, OR, MR, AA, TT> AuthService<U, T, P, TS, ID, OR, MR, AA, TT>
 where
     U: UserRepository,
     T: RefreshTokenRepository,
@@ -49,7 +97,10 @@ where
     ID: IdGenerator,
     OR: OrganizationRepository,
     MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         user_repo: Arc<U>,
         token_repo: Arc<T>,
@@ -58,6 +109,9 @@ where
         id_generator: Arc<ID>,
         org_repo: Arc<OR>,
         member_repo: Arc<MR>,
+        audit_repo: Arc<AA>,
+        totp_repo: Arc<TT>,
+        audit_enabled: bool,
     ) -> Self {
         // Pre-computed Argon2 hash for timing attack mitigation
         // This ensures login takes consistent time whether user exists or not
@@ -73,10 +127,62 @@ where
             id_generator,
             org_repo,
             member_repo,
+            audit_repo,
+            totp_repo,
+            audit_enabled,
+            verification_repo: None,
+            email_sender: None,
+            password_reset_repo: None,
+            oauth_identity_repo: None,
+            personal_access_token_repo: None,
+            pagination: PaginationConfig::default(),
             dummy_password_hash,
         }
     }
 
+    /// Send new users, and users changing their address, a link to verify it
+    pub fn with_email_verification(
+        mut self,
+        verification_repo: Arc<dyn VerificationTokenRepository>,
+        email_sender: Arc<dyn EmailSender>,
+    ) -> Self {
+        self.verification_repo = Some(verification_repo);
+        self.email_sender = Some(email_sender);
+        self
+    }
+
+    /// Let users who forgot their password reset it with a link emailed to them
+    pub fn with_password_reset(
+        mut self,
+        password_reset_repo: Arc<dyn PasswordResetTokenRepository>,
+        email_sender: Arc<dyn EmailSender>,
+    ) -> Self {
+        self.password_reset_repo = Some(password_reset_repo);
+        self.email_sender = Some(email_sender);
+        self
+    }
+
+    /// Let users sign in with accounts at OAuth providers
+    pub fn with_oauth(mut self, oauth_identity_repo: Arc<dyn OAuthIdentityRepository>) -> Self {
+        self.oauth_identity_repo = Some(oauth_identity_repo);
+        self
+    }
+
+    /// Let users create scoped tokens for calling the API from scripts
+    pub fn with_personal_access_tokens(
+        mut self,
+        personal_access_token_repo: Arc<dyn PersonalAccessTokenRepository>,
+    ) -> Self {
+        self.personal_access_token_repo = Some(personal_access_token_repo);
+        self
+    }
+
+    /// Page size limits for list endpoints, instead of the defaults
+    pub fn with_pagination(mut self, pagination: PaginationConfig) -> Self {
+        self.pagination = pagination;
+        self
+    }
+
     /// Generate a random 4-character suffix for slugs
     fn generate_random_suffix(&self) -> String {
         const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
@@ -116,12 +222,8 @@ where
 
         // Create membership as owner with last_accessed set
         let member_id = MemberId::new(self.id_generator.generate());
-        let mut member = OrganizationMember::new(
-            member_id,
-            org_id.clone(),
-            user_id.clone(),
-            OrgRole::Owner,
-        );
+        let mut member =
+            OrganizationMember::new(member_id, org_id.clone(), user_id.clone(), OrgRole::Owner);
         member.touch_last_accessed();
         self.member_repo
             .save(&member)
@@ -131,12 +233,53 @@ where
         Ok((org_id, OrgRole::Owner))
     }
 
-    /// Get the default organization for a user (last accessed or personal)
-    async fn get_default_org_for_user(
+    /// Get the user's role in an organization, if they are a member and it isn't deleted
+    async fn find_active_membership_role(
         &self,
         user_id: &UserId,
+        org_id: &OrgId,
+    ) -> Result<Option<OrgRole>, AuthDomainError> {
+        let membership = self
+            .member_repo
+            .find_by_org_and_user(org_id, user_id)
+            .await
+            .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+        let Some(membership) = membership else {
+            return Ok(None);
+        };
+
+        let org = self
+            .org_repo
+            .find_by_id(org_id)
+            .await
+            .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(org
+            .filter(|org| !org.is_deleted())
+            .map(|_| *membership.role()))
+    }
+
+    /// Get the default organization for a user (preferred, last accessed or personal)
+    async fn get_default_org_for_user(
+        &self,
+        user: &User,
     ) -> Result<Option<(OrgId, OrgRole)>, AuthDomainError> {
-        // Try last accessed first
+        let user_id = user.id();
+
+        // Use the configured default while the user can still access it
+        if let Some(default_org_id) = user.default_org_id() {
+            let org_id = OrgId::new(default_org_id.to_string());
+            if let Some(role) = self.find_active_membership_role(user_id, &org_id).await? {
+                return Ok(Some((org_id, role)));
+            }
+
+            // Membership was lost - clear the stale preference
+            let mut user = user.clone();
+            user.update_default_org(None);
+            self.user_repo.save(&user).await?;
+        }
+
+        // Try last accessed next
         if let Some(membership) = self
             .member_repo
             .find_last_accessed_by_user(user_id)
@@ -176,25 +319,57 @@ where
     }
 
     /// Register a new user
-    pub async fn register(&self, cmd: RegisterUserCommand) -> Result<AuthResponse, AuthDomainError> {
+    pub async fn register(
+        &self,
+        cmd: RegisterUserCommand,
+    ) -> Result<AuthResponse, AuthDomainError> {
+        let identifier = cmd.email.trim().to_string();
+        let client = cmd.client.clone();
+        let result = self.create_account(cmd).await;
+        let user_id = result.as_ref().ok().map(|r| UserId::new(r.user_id.clone()));
+        self.record_audit(
+            AuthAuditEventType::Register,
+            user_id,
+            Some(identifier),
+            &client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
+    }
+
+    async fn create_account(
+        &self,
+        cmd: RegisterUserCommand,
+    ) -> Result<AuthResponse, AuthDomainError> {
         // 1. Validate and create value objects
         let email = Email::new(cmd.email)?;
+        let username = cmd.username.map(Username::new).transpose()?;
         let password = PlainPassword::new(cmd.password)?;
 
         // 2. Check if user already exists
         if self.user_repo.exists_by_email(&email).await? {
             return Err(AuthDomainError::UserAlreadyExists);
         }
+        if let Some(username) = &username
+            && self.user_repo.exists_by_username(username).await?
+        {
+            return Err(AuthDomainError::UsernameAlreadyTaken);
+        }
 
         // 3. Hash password
         let password_hash = self.password_hasher.hash(&password).await?;
 
         // 4. Create user
         let user_id = UserId::new(self.id_generator.generate());
-        let user = User::new(user_id.clone(), email.clone(), password_hash);
+        let mut user = User::new(user_id.clone(), email.clone(), password_hash);
+        if let Some(username) = username {
+            user.update_username(username);
+        }
 
         // 5. Save user
         self.user_repo.save(&user).await?;
+        self.send_verification_best_effort(&user).await;
 
         // 6. Create personal organization for the new user
         let (org_id, org_role) = self
@@ -217,28 +392,67 @@ where
             &token_pair.refresh_token,
             &cmd.device_fingerprint,
             token_pair.refresh_expires_in,
+            &cmd.client,
+            None,
         )
         .await?;
 
         Ok(AuthResponse::new(
             user_id.into_inner(),
             email.into_inner(),
+            user.username().map(|u| u.as_str().to_string()),
             None, // New users don't have display_name yet
+            Some(org_id.into_inner()),
             token_pair.access_token,
             token_pair.refresh_token,
             token_pair.access_expires_in,
         ))
     }
 
-    /// Login with email and password
-    pub async fn login(&self, cmd: LoginCommand) -> Result<AuthResponse, AuthDomainError> {
-        // 1. Validate email format
-        let email = Email::new(cmd.email)?;
+    /// Login with email or username and password. Accounts with 2FA get a
+    /// challenge to complete with `verify_totp` instead of tokens.
+    pub async fn login(&self, cmd: LoginCommand) -> Result<LoginResult, AuthDomainError> {
+        let identifier = cmd.identifier.trim().to_string();
+        let client = cmd.client.clone();
+        let mut user_id = None;
+        let result = self.authenticate(cmd, &mut user_id).await;
+        self.record_audit(
+            AuthAuditEventType::Login,
+            user_id,
+            Some(identifier),
+            &client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
+    }
+
+    /// Sets `user_id` once the account is known, so failed attempts against
+    /// an existing account are attributed to it
+    async fn authenticate(
+        &self,
+        cmd: LoginCommand,
+        user_id: &mut Option<UserId>,
+    ) -> Result<LoginResult, AuthDomainError> {
+        // 1. Validate identifier format - anything with an @ is treated as an email
+        let identifier = cmd.identifier.trim().to_string();
         // Use for_verification to skip strength validation - we only need to compare against hash
         let password = PlainPassword::for_verification(cmd.password);
 
-        // 2. Find user (don't early-return to prevent timing attacks)
-        let user_opt = self.user_repo.find_by_email(&email).await?;
+        // 2. Find user (don't early-return to prevent timing attacks).
+        // A malformed identifier matches no one, so it fails like a wrong
+        // password instead of revealing which validation rule it broke.
+        let user_opt = if identifier.contains('@') {
+            match Email::new(identifier) {
+                Ok(email) => self.user_repo.find_by_email(&email).await?,
+                Err(_) => None,
+            }
+        } else {
+            match Username::new(identifier) {
+                Ok(username) => self.user_repo.find_by_username(&username).await?,
+                Err(_) => None,
+            }
+        };
 
         // 3. Get password hash or use dummy for timing consistency
         let (user, password_hash) = match &user_opt {
@@ -251,6 +465,7 @@ where
             }
             None => (None, self.dummy_password_hash.clone()),
         };
+        *user_id = user.map(|u| u.id().clone());
 
         // 4. Always verify password (timing-safe: takes same time regardless of user existence)
         let is_valid = self
@@ -264,45 +479,269 @@ where
             _ => return Err(AuthDomainError::InvalidCredentials),
         };
 
-        // 6. Get default organization for user (last accessed or personal)
-        let org_context = if let Some((org_id, org_role)) =
-            self.get_default_org_for_user(user.id()).await?
+        // 6. Bring a hash made with older cost parameters up to the current ones
+        if self.password_hasher.needs_rehash(&password_hash) {
+            self.upgrade_password_hash(user.id(), &password).await;
+        }
+
+        // 7. Checked only after the password, so SSO enforcement isn't revealed to guessers
+        self.ensure_sign_in_allowed(user.id(), AuthMethods::Password)
+            .await?;
+
+        // 8. Hold back tokens until the second factor is presented
+        if let Some(totp) = self.totp_repo.find_by_user(user.id()).await?
+            && totp.is_confirmed()
         {
-            Some(OrgContext {
+            let challenge = self
+                .start_totp_challenge(user.id(), &cmd.device_fingerprint)
+                .await?;
+            return Ok(LoginResult::TotpRequired(challenge));
+        }
+
+        // 9. Issue tokens scoped to the user's default organization
+        let response = self
+            .start_session(user, &cmd.device_fingerprint, &cmd.client)
+            .await?;
+        Ok(LoginResult::Authenticated(response))
+    }
+
+    /// Re-hash a just-verified password with the current parameters. A
+    /// failure only delays the upgrade to a later login.
+    async fn upgrade_password_hash(&self, user_id: &UserId, password: &PlainPassword) {
+        let result = match self.password_hasher.hash(password).await {
+            Ok(hash) => self.user_repo.update_password_hash(user_id, &hash).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            tracing::warn!(
+                error = %e,
+                user_id = user_id.as_str(),
+                "Failed to upgrade password hash"
+            );
+        }
+    }
+
+    /// Store a challenge for the second factor and return its token
+    async fn start_totp_challenge(
+        &self,
+        user_id: &UserId,
+        device_fingerprint: &str,
+    ) -> Result<TotpChallengeResponse, AuthDomainError> {
+        let mut token = [0u8; 32];
+        rand::rng().fill(&mut token);
+        let challenge_token = URL_SAFE_NO_PAD.encode(token);
+
+        let challenge = TotpChallenge::new(
+            hash_token(&challenge_token),
+            user_id.clone(),
+            device_fingerprint.to_string(),
+            Utc::now() + Duration::seconds(TOTP_CHALLENGE_TTL_SECS),
+        );
+        self.totp_repo.save_challenge(&challenge).await?;
+
+        Ok(TotpChallengeResponse {
+            challenge_token,
+            expires_in: TOTP_CHALLENGE_TTL_SECS,
+        })
+    }
+
+    /// Issue a token pair for a fully authenticated user
+    async fn start_session(
+        &self,
+        user: &User,
+        device_fingerprint: &str,
+        client: &ClientInfo,
+    ) -> Result<AuthResponse, AuthDomainError> {
+        // 1. Get default organization for user (preferred, last accessed or personal)
+        let org_context = self
+            .get_default_org_for_user(user)
+            .await?
+            .map(|(org_id, org_role)| OrgContext {
                 org_id: org_id.as_str().to_string(),
                 org_role: org_role.as_str().to_string(),
-            })
-        } else {
-            None
-        };
+            });
+        let default_org_id = org_context.as_ref().map(|c| c.org_id.clone());
 
-        // 7. Generate tokens with org context
+        // 2. Generate tokens with org context
         let token_pair = self
             .token_service
             .generate_token_pair(user.id(), user.email().as_str(), org_context)
             .await?;
 
-        // 8. Store refresh token with device fingerprint
+        // 3. Store refresh token with device fingerprint
         self.store_refresh_token(
             user.id(),
             &token_pair.refresh_token,
-            &cmd.device_fingerprint,
+            device_fingerprint,
             token_pair.refresh_expires_in,
+            client,
+            None,
         )
         .await?;
 
         Ok(AuthResponse::new(
             user.id().as_str().to_string(),
             user.email().as_str().to_string(),
+            user.username().map(|u| u.as_str().to_string()),
             user.display_name().map(|d| d.as_str().to_string()),
+            default_org_id,
             token_pair.access_token,
             token_pair.refresh_token,
             token_pair.access_expires_in,
         ))
     }
 
+    /// Sign in with an account an OAuth provider has authenticated, creating
+    /// the user on first sign-in. A user found by email gets the provider
+    /// account linked only if they already sign in through a provider; an
+    /// email belonging to a password account is a conflict instead, so a
+    /// provider can't take over an account it never vouched for.
+    pub async fn login_or_register_oauth(
+        &self,
+        cmd: OAuthLoginCommand,
+    ) -> Result<LoginResult, AuthDomainError> {
+        let identifier = cmd.email.trim().to_string();
+        let client = cmd.client.clone();
+        let mut user_id = None;
+        let result = self.authenticate_oauth(cmd, &mut user_id).await;
+        self.record_audit(
+            AuthAuditEventType::OAuthLogin,
+            user_id,
+            Some(identifier),
+            &client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
+    }
+
+    async fn authenticate_oauth(
+        &self,
+        cmd: OAuthLoginCommand,
+        user_id: &mut Option<UserId>,
+    ) -> Result<LoginResult, AuthDomainError> {
+        let identity_repo = self
+            .oauth_identity_repo
+            .as_ref()
+            .ok_or(AuthDomainError::OAuthUnavailable)?;
+        let email = Email::new(cmd.email)?;
+
+        // 1. A linked provider account signs in as its user
+        let user = match identity_repo.find(cmd.provider, &cmd.external_id).await? {
+            Some(identity) => self
+                .user_repo
+                .find_by_id(identity.user_id())
+                .await?
+                .ok_or(AuthDomainError::InvalidCredentials)?,
+            None => {
+                let user = match self.user_repo.find_by_email(&email).await? {
+                    // 2. Link another provider to a user who signs in through one already
+                    Some(user) => {
+                        let linked = identity_repo.find_by_user(user.id()).await?;
+                        if user.has_password() && linked.is_empty() {
+                            *user_id = Some(user.id().clone());
+                            return Err(AuthDomainError::OAuthAccountConflict);
+                        }
+                        user
+                    }
+                    // 3. First sign-in creates the account
+                    None => self.create_oauth_account(email, cmd.display_name).await?,
+                };
+                identity_repo
+                    .save(&OAuthIdentity::new(
+                        user.id().clone(),
+                        cmd.provider,
+                        cmd.external_id,
+                        user.email().as_str().to_string(),
+                    ))
+                    .await?;
+                user
+            }
+        };
+        *user_id = Some(user.id().clone());
+
+        // 4. Same checks as a password sign-in from here on
+        self.ensure_sign_in_allowed(user.id(), AuthMethods::Oidc)
+            .await?;
+        if let Some(totp) = self.totp_repo.find_by_user(user.id()).await?
+            && totp.is_confirmed()
+        {
+            let challenge = self
+                .start_totp_challenge(user.id(), &cmd.device_fingerprint)
+                .await?;
+            return Ok(LoginResult::TotpRequired(challenge));
+        }
+
+        let response = self
+            .start_session(&user, &cmd.device_fingerprint, &cmd.client)
+            .await?;
+        Ok(LoginResult::Authenticated(response))
+    }
+
+    /// Create a password-less user whose email the provider has verified
+    async fn create_oauth_account(
+        &self,
+        email: Email,
+        display_name: Option<String>,
+    ) -> Result<User, AuthDomainError> {
+        let user_id = UserId::new(self.id_generator.generate());
+        let mut user = User::new_oauth(user_id.clone(), email.clone());
+        // A provider name that doesn't fit our rules is left for the user to set
+        if let Some(display_name) = display_name.and_then(|name| DisplayName::new(name).ok()) {
+            user.update_display_name(display_name);
+        }
+        self.user_repo.save(&user).await?;
+        self.create_personal_org_for_user(&user_id, email.as_str())
+            .await?;
+        Ok(user)
+    }
+
+    /// Reject a sign-in using `method` if any organization the user belongs to
+    /// disallows it. Personal orgs always allow both methods.
+    async fn ensure_sign_in_allowed(
+        &self,
+        user_id: &UserId,
+        method: AuthMethods,
+    ) -> Result<(), AuthDomainError> {
+        let memberships = self
+            .member_repo
+            .find_all_by_user(user_id)
+            .await
+            .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        for membership in memberships {
+            let org = self
+                .org_repo
+                .find_by_id(membership.organization_id())
+                .await
+                .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+            if let Some(org) = org
+                && !org.is_deleted()
+                && !org.auth_methods().permits(method)
+            {
+                return Err(AuthDomainError::SsoRequired);
+            }
+        }
+        Ok(())
+    }
+
     /// Logout - revoke refresh tokens
     pub async fn logout(&self, cmd: LogoutCommand) -> Result<(), AuthDomainError> {
+        let user_id = UserId::new(cmd.user_id.clone());
+        let client = cmd.client.clone();
+        let result = self.revoke_tokens(cmd).await;
+        self.record_audit(
+            AuthAuditEventType::Logout,
+            Some(user_id),
+            None,
+            &client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
+    }
+
+    async fn revoke_tokens(&self, cmd: LogoutCommand) -> Result<(), AuthDomainError> {
         let user_id = UserId::new(cmd.user_id);
 
         if let Some(refresh_token) = cmd.refresh_token {
@@ -321,10 +760,30 @@ where
 
     /// Refresh access token using refresh token
     pub async fn refresh(&self, cmd: RefreshTokenCommand) -> Result<AuthResponse, AuthDomainError> {
+        let client = cmd.client.clone();
+        let mut user_id = None;
+        let result = self.rotate_refresh_token(cmd, &mut user_id).await;
+        self.record_audit(
+            AuthAuditEventType::TokenRefresh,
+            user_id,
+            None,
+            &client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
+    }
+
+    async fn rotate_refresh_token(
+        &self,
+        cmd: RefreshTokenCommand,
+        audit_user_id: &mut Option<UserId>,
+    ) -> Result<AuthResponse, AuthDomainError> {
         // 1. Decode and validate refresh token
         let claims = self
             .token_service
             .decode_refresh_token(&cmd.refresh_token)?;
+        *audit_user_id = Some(UserId::new(claims.user_id.clone()));
 
         // 2. Find stored token by hash
         let token_hash = self.token_service.hash_refresh_token(&cmd.refresh_token);
@@ -349,46 +808,48 @@ where
         // 5. Revoke old token (token rotation)
         self.token_repo.revoke(stored_token.id()).await?;
 
-        // 6. Re-query default organization for user (last accessed or personal)
-        // This ensures tokens reflect current org context even if user switched orgs
+        // 6. Fetch user to get current display_name and org preference
         let user_id = UserId::new(claims.user_id);
-        let org_context = if let Some((org_id, org_role)) =
-            self.get_default_org_for_user(&user_id).await?
-        {
-            Some(OrgContext {
+        let user = self
+            .user_repo
+            .find_by_id(&user_id)
+            .await?
+            .ok_or(AuthDomainError::UserNotFound)?;
+
+        // 7. Re-query default organization for user (preferred, last accessed or personal)
+        // This ensures tokens reflect current org context even if user switched orgs
+        let org_context = self
+            .get_default_org_for_user(&user)
+            .await?
+            .map(|(org_id, org_role)| OrgContext {
                 org_id: org_id.as_str().to_string(),
                 org_role: org_role.as_str().to_string(),
-            })
-        } else {
-            None
-        };
+            });
+        let default_org_id = org_context.as_ref().map(|c| c.org_id.clone());
 
-        // 7. Generate new token pair with org context
+        // 8. Generate new token pair with org context, within the session's lifetime
         let token_pair = self
             .token_service
-            .generate_token_pair(&user_id, &claims.email, org_context)
+            .refresh_token_pair(&user_id, &claims.email, org_context, claims.auth_time)
             .await?;
 
-        // 8. Store new refresh token with same device fingerprint
+        // 9. Store new refresh token with same device fingerprint, in the same session
         self.store_refresh_token(
             &user_id,
             &token_pair.refresh_token,
             &cmd.device_fingerprint,
             token_pair.refresh_expires_in,
+            &cmd.client,
+            Some(stored_token.session_started_at()),
         )
         .await?;
 
-        // 9. Fetch user to get current display_name
-        let user = self
-            .user_repo
-            .find_by_id(&user_id)
-            .await?
-            .ok_or(AuthDomainError::UserNotFound)?;
-
         Ok(AuthResponse::new(
             user.id().as_str().to_string(),
             user.email().as_str().to_string(),
+            user.username().map(|u| u.as_str().to_string()),
             user.display_name().map(|d| d.as_str().to_string()),
+            default_org_id,
             token_pair.access_token,
             token_pair.refresh_token,
             token_pair.access_expires_in,
@@ -428,7 +889,10 @@ where
     }
 
     /// Get user's settings
-    pub async fn get_settings(&self, user_id: &str) -> Result<UserSettingsResponse, AuthDomainError> {
+    pub async fn get_settings(
+        &self,
+        user_id: &str,
+    ) -> Result<UserSettingsResponse, AuthDomainError> {
         let user_id = UserId::new(user_id.to_string());
         let user = self
             .user_repo
@@ -438,11 +902,15 @@ where
 
         Ok(UserSettingsResponse {
             allow_invites: user.allow_invites(),
+            default_org_id: user.default_org_id().map(String::from),
         })
     }
 
     /// Update user's settings
-    pub async fn update_settings(&self, cmd: UpdateSettingsCommand) -> Result<UserSettingsResponse, AuthDomainError> {
+    pub async fn update_settings(
+        &self,
+        cmd: UpdateSettingsCommand,
+    ) -> Result<UserSettingsResponse, AuthDomainError> {
         let user_id = UserId::new(cmd.user_id);
         let mut user = self
             .user_repo
@@ -454,15 +922,47 @@ where
             user.update_allow_invites(allow_invites);
         }
 
+        match cmd.default_org_id.as_deref().map(str::trim) {
+            Some("") => user.update_default_org(None),
+            Some(default_org_id) => {
+                let org_id = OrgId::new(default_org_id.to_string());
+                if self
+                    .find_active_membership_role(&user_id, &org_id)
+                    .await?
+                    .is_none()
+                {
+                    return Err(AuthDomainError::InvalidDefaultOrg);
+                }
+                user.update_default_org(Some(org_id.into_inner()));
+            }
+            None => {}
+        }
+
         self.user_repo.save(&user).await?;
 
         Ok(UserSettingsResponse {
             allow_invites: user.allow_invites(),
+            default_org_id: user.default_org_id().map(String::from),
         })
     }
 
     /// Change user's email address
     pub async fn change_email(&self, cmd: ChangeEmailCommand) -> Result<(), AuthDomainError> {
+        let user_id = UserId::new(cmd.user_id.clone());
+        let client = cmd.client.clone();
+        let result = self.update_email(cmd).await;
+        self.record_audit(
+            AuthAuditEventType::EmailChange,
+            Some(user_id),
+            None,
+            &client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
+    }
+
+    async fn update_email(&self, cmd: ChangeEmailCommand) -> Result<(), AuthDomainError> {
         // 1. Validate new email format
         let new_email = Email::new(cmd.new_email)?;
 
@@ -499,15 +999,32 @@ where
             return Ok(());
         }
 
-        // 6. Update email
+        // 6. Update email; the new address has to be verified again
         user.update_email(new_email);
 
         // 7. Persist changes
-        self.user_repo.save(&user).await
+        self.user_repo.save(&user).await?;
+        self.send_verification_best_effort(&user).await;
+        Ok(())
     }
 
     /// Change user's password
     pub async fn change_password(&self, cmd: ChangePasswordCommand) -> Result<(), AuthDomainError> {
+        let user_id = UserId::new(cmd.user_id.clone());
+        let client = cmd.client.clone();
+        let result = self.update_password(cmd).await;
+        self.record_audit(
+            AuthAuditEventType::PasswordChange,
+            Some(user_id),
+            None,
+            &client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
+    }
+
+    async fn update_password(&self, cmd: ChangePasswordCommand) -> Result<(), AuthDomainError> {
         // 1. Validate new password strength
         let new_password = PlainPassword::new(cmd.new_password)?;
 
@@ -545,11 +1062,169 @@ where
         self.user_repo.save(&user).await
     }
 
+    /// Email a single-use password reset token to the account with this address.
+    /// Succeeds whether or not the address belongs to an account, so it can't
+    /// be used to find out which addresses are registered.
+    pub async fn request_password_reset(
+        &self,
+        cmd: RequestPasswordResetCommand,
+    ) -> Result<(), AuthDomainError> {
+        if self.password_reset_repo.is_none() || self.email_sender.is_none() {
+            return Err(AuthDomainError::PasswordResetUnavailable);
+        }
+
+        let identifier = cmd.email.trim().to_string();
+        let mut user_id = None;
+        let result = self.send_password_reset(&identifier, &mut user_id).await;
+        self.record_audit(
+            AuthAuditEventType::PasswordResetRequest,
+            user_id.clone(),
+            Some(identifier),
+            &cmd.client,
+            result.as_ref().err(),
+        )
+        .await;
+        if let Err(e) = result {
+            tracing::warn!(
+                error = %e,
+                user_id = user_id.as_ref().map(|id| id.as_str()),
+                "Failed to send password reset email"
+            );
+        }
+        Ok(())
+    }
+
+    /// Sets `user_id` once the account is known; unknown addresses are not an error
+    async fn send_password_reset(
+        &self,
+        email: &str,
+        audit_user_id: &mut Option<UserId>,
+    ) -> Result<(), AuthDomainError> {
+        let (Some(password_reset_repo), Some(email_sender)) =
+            (&self.password_reset_repo, &self.email_sender)
+        else {
+            return Err(AuthDomainError::PasswordResetUnavailable);
+        };
+
+        // 1. Find the account, quietly doing nothing for unknown addresses
+        let Ok(email) = Email::new(email.to_string()) else {
+            return Ok(());
+        };
+        let Some(user) = self
+            .user_repo
+            .find_by_email(&email)
+            .await?
+            .filter(|user| !user.is_deleted())
+        else {
+            return Ok(());
+        };
+        *audit_user_id = Some(user.id().clone());
+
+        // 2. Replace any earlier token
+        let mut bytes = [0u8; 32];
+        rand::rng().fill(&mut bytes);
+        let token = URL_SAFE_NO_PAD.encode(bytes);
+
+        password_reset_repo.delete_for_user(user.id()).await?;
+        password_reset_repo
+            .save(&PasswordResetToken::new(
+                hash_token(&token),
+                user.id().clone(),
+                Utc::now() + Duration::hours(PasswordResetToken::TTL_HOURS),
+            ))
+            .await?;
+
+        // 3. Email it
+        email_sender
+            .send(&EmailMessage {
+                to: user.email().as_str().to_string(),
+                subject: "Reset your password".to_string(),
+                body: format!(
+                    "Use this token to reset your password: {}\n\nIt expires in {} hour. If you didn't ask for a reset, you can ignore this email.",
+                    token,
+                    PasswordResetToken::TTL_HOURS
+                ),
+            })
+            .await
+    }
+
+    /// Set a new password with the token from a password reset email.
+    /// Each token works once, and every existing session of the user is signed out.
+    pub async fn reset_password(&self, cmd: ResetPasswordCommand) -> Result<(), AuthDomainError> {
+        let client = cmd.client.clone();
+        let mut user_id = None;
+        let result = self.consume_password_reset_token(cmd, &mut user_id).await;
+        self.record_audit(
+            AuthAuditEventType::PasswordReset,
+            user_id,
+            None,
+            &client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
+    }
+
+    async fn consume_password_reset_token(
+        &self,
+        cmd: ResetPasswordCommand,
+        audit_user_id: &mut Option<UserId>,
+    ) -> Result<(), AuthDomainError> {
+        let password_reset_repo = self
+            .password_reset_repo
+            .as_ref()
+            .ok_or(AuthDomainError::PasswordResetUnavailable)?;
+
+        // 1. Validate new password strength
+        let new_password = PlainPassword::new(cmd.new_password)?;
+
+        // 2. Find the token
+        let token = password_reset_repo
+            .find_by_hash(&hash_token(&cmd.token))
+            .await?
+            .ok_or(AuthDomainError::TokenInvalid)?;
+        *audit_user_id = Some(token.user_id().clone());
+        if token.is_expired() {
+            return Err(AuthDomainError::TokenExpired);
+        }
+
+        let mut user = self
+            .user_repo
+            .find_by_id(token.user_id())
+            .await?
+            .filter(|user| !user.is_deleted())
+            .ok_or(AuthDomainError::UserNotFound)?;
+
+        // 3. Hash and store the new password
+        let new_password_hash = self.password_hasher.hash(&new_password).await?;
+        user.update_password(new_password_hash);
+        self.user_repo.save(&user).await?;
+
+        // 4. Spend the token and sign out every session using the old password
+        password_reset_repo.delete_for_user(user.id()).await?;
+        self.token_repo.revoke_all_for_user(user.id()).await
+    }
+
     /// Delete user's account
     /// - Transfers ownership to the highest-ranking, longest-tenured member for each org
     /// - Soft deletes organizations where user is the only member
     /// - Soft deletes the user (data retained for 30 days)
     pub async fn delete_account(&self, cmd: DeleteAccountCommand) -> Result<(), AuthDomainError> {
+        let user_id = UserId::new(cmd.user_id.clone());
+        let client = cmd.client.clone();
+        let result = self.remove_account(cmd).await;
+        self.record_audit(
+            AuthAuditEventType::AccountDeletion,
+            Some(user_id),
+            None,
+            &client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
+    }
+
+    async fn remove_account(&self, cmd: DeleteAccountCommand) -> Result<(), AuthDomainError> {
         // 1. Get current user
         let user_id = UserId::new(cmd.user_id);
         let mut user = self
@@ -626,22 +1301,20 @@ where
                 } else if membership.role() == &OrgRole::Owner {
                     // User is owner - need to transfer ownership
                     // Find the best successor: highest role first, then longest tenure (earliest created_at)
-                    let successor = other_members
-                        .iter()
-                        .max_by(|a, b| {
-                            // Compare by role priority first (Owner > Admin > Member)
-                            let role_priority = |r: &OrgRole| match r {
-                                OrgRole::Owner => 2,
-                                OrgRole::Admin => 1,
-                                OrgRole::Member => 0,
-                            };
-                            let role_cmp = role_priority(a.role()).cmp(&role_priority(b.role()));
-                            if role_cmp != std::cmp::Ordering::Equal {
-                                return role_cmp;
-                            }
-                            // If same role, compare by tenure (earlier created_at = longer tenure)
-                            b.created_at().cmp(&a.created_at())
-                        });
+                    let successor = other_members.iter().max_by(|a, b| {
+                        // Compare by role priority first (Owner > Admin > Member)
+                        let role_priority = |r: &OrgRole| match r {
+                            OrgRole::Owner => 2,
+                            OrgRole::Admin => 1,
+                            OrgRole::Member => 0,
+                        };
+                        let role_cmp = role_priority(a.role()).cmp(&role_priority(b.role()));
+                        if role_cmp != std::cmp::Ordering::Equal {
+                            return role_cmp;
+                        }
+                        // If same role, compare by tenure (earlier created_at = longer tenure)
+                        b.created_at().cmp(&a.created_at())
+                    });
 
                     if let Some(successor) = successor {
                         // Promote the successor to owner
@@ -672,767 +1345,3317 @@ where
         Ok(())
     }
 
-    /// Helper: store refresh token in database
-    async fn store_refresh_token(
+    /// Mark the user's email as verified with the token from their verification email.
+    /// Each token works once.
+    pub async fn verify_email(&self, cmd: VerifyEmailCommand) -> Result<(), AuthDomainError> {
+        let client = cmd.client.clone();
+        let mut user_id = None;
+        let result = self
+            .consume_verification_token(&cmd.token, &mut user_id)
+            .await;
+        self.record_audit(
+            AuthAuditEventType::EmailVerify,
+            user_id,
+            None,
+            &client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
+    }
+
+    async fn consume_verification_token(
         &self,
-        user_id: &UserId,
-        refresh_token: &str,
-        device_fingerprint: &str,
-        expires_in_secs: i64,
+        token: &str,
+        audit_user_id: &mut Option<UserId>,
     ) -> Result<(), AuthDomainError> {
-        let token_hash = self.token_service.hash_refresh_token(refresh_token);
-        let token_id = TokenId::new(self.id_generator.generate());
-        let expires_at = Utc::now() + Duration::seconds(expires_in_secs);
+        let verification_repo = self
+            .verification_repo
+            .as_ref()
+            .ok_or(AuthDomainError::EmailVerificationUnavailable)?;
+
+        // 1. Find the token
+        let token = verification_repo
+            .find_by_hash(&hash_token(token))
+            .await?
+            .ok_or(AuthDomainError::TokenInvalid)?;
+        *audit_user_id = Some(token.user_id().clone());
+        if token.is_expired() {
+            return Err(AuthDomainError::TokenExpired);
+        }
 
-        let token = RefreshToken::new(
-            token_id,
-            user_id.clone(),
-            token_hash,
-            device_fingerprint.to_string(),
-            expires_at,
-        );
+        // 2. Mark the address verified
+        let mut user = self
+            .user_repo
+            .find_by_id(token.user_id())
+            .await?
+            .ok_or(AuthDomainError::UserNotFound)?;
+        user.mark_email_verified();
+        self.user_repo.save(&user).await?;
 
-        self.token_repo.save(&token).await
+        // 3. Spend this token and any older ones
+        verification_repo.delete_for_user(user.id()).await
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::modules::auth::application::ports::{TokenClaims, TokenPair};
-    use crate::modules::auth::domain::PasswordHasher;
-    use std::collections::HashMap;
-    use std::sync::Mutex;
-
-    // ==================== Mock Implementations ====================
-
-    /// Mock User Repository
-    struct MockUserRepository {
-        users: Mutex<HashMap<String, User>>,
-    }
-
-    impl MockUserRepository {
-        fn new() -> Self {
-            Self {
-                users: Mutex::new(HashMap::new()),
-            }
-        }
 
-        fn with_user(user: User) -> Self {
-            let repo = Self::new();
-            repo.users.lock().unwrap().insert(user.email().as_str().to_string(), user);
-            repo
+    /// Send a fresh verification email, replacing earlier tokens
+    pub async fn resend_verification(
+        &self,
+        cmd: ResendVerificationCommand,
+    ) -> Result<(), AuthDomainError> {
+        let user = self
+            .user_repo
+            .find_by_id(&UserId::new(cmd.user_id))
+            .await?
+            .ok_or(AuthDomainError::UserNotFound)?;
+        if user.is_email_verified() {
+            return Err(AuthDomainError::EmailAlreadyVerified);
         }
+        self.send_verification(&user).await
     }
 
-    #[async_trait::async_trait]
-    impl UserRepository for MockUserRepository {
-        async fn find_by_id(&self, id: &UserId) -> Result<Option<User>, AuthDomainError> {
-            let users = self.users.lock().unwrap();
-            Ok(users.values().find(|u| u.id().as_str() == id.as_str()).cloned())
-        }
-
-        async fn find_by_email(&self, email: &Email) -> Result<Option<User>, AuthDomainError> {
-            let users = self.users.lock().unwrap();
-            Ok(users.get(email.as_str()).cloned())
-        }
+    /// Whether the user has verified their email address
+    pub async fn is_email_verified(&self, user_id: &str) -> Result<bool, AuthDomainError> {
+        Ok(self.get_current_user(user_id).await?.is_email_verified())
+    }
 
-        async fn save(&self, user: &User) -> Result<(), AuthDomainError> {
-            let mut users = self.users.lock().unwrap();
-            users.insert(user.email().as_str().to_string(), user.clone());
-            Ok(())
-        }
+    /// Helper: issue a verification token and email it to the user
+    async fn send_verification(&self, user: &User) -> Result<(), AuthDomainError> {
+        let (Some(verification_repo), Some(email_sender)) =
+            (&self.verification_repo, &self.email_sender)
+        else {
+            return Err(AuthDomainError::EmailVerificationUnavailable);
+        };
 
-        async fn exists_by_email(&self, email: &Email) -> Result<bool, AuthDomainError> {
-            let users = self.users.lock().unwrap();
-            Ok(users.contains_key(email.as_str()))
-        }
-    }
+        let mut bytes = [0u8; 32];
+        rand::rng().fill(&mut bytes);
+        let token = URL_SAFE_NO_PAD.encode(bytes);
+
+        verification_repo.delete_for_user(user.id()).await?;
+        verification_repo
+            .save(&VerificationToken::new(
+                hash_token(&token),
+                user.id().clone(),
+                Utc::now() + Duration::hours(VerificationToken::TTL_HOURS),
+            ))
+            .await?;
 
-    /// Mock Refresh Token Repository
-    struct MockRefreshTokenRepository {
-        tokens: Mutex<HashMap<String, RefreshToken>>,
+        email_sender
+            .send(&EmailMessage {
+                to: user.email().as_str().to_string(),
+                subject: "Verify your email address".to_string(),
+                body: format!(
+                    "Use this token to verify your email address: {}\n\nIt expires in {} hours.",
+                    token,
+                    VerificationToken::TTL_HOURS
+                ),
+            })
+            .await
     }
 
-    impl MockRefreshTokenRepository {
-        fn new() -> Self {
-            Self {
-                tokens: Mutex::new(HashMap::new()),
-            }
+    /// Helper: send a verification email where failing shouldn't fail the
+    /// surrounding action; the user can ask for another one
+    async fn send_verification_best_effort(&self, user: &User) {
+        if self.verification_repo.is_none() || self.email_sender.is_none() {
+            return;
         }
-
-        fn with_token(token: RefreshToken) -> Self {
-            let repo = Self::new();
-            repo.tokens.lock().unwrap().insert(token.token_hash().to_string(), token);
-            repo
+        if let Err(e) = self.send_verification(user).await {
+            tracing::warn!(
+                error = %e,
+                user_id = user.id().as_str(),
+                "Failed to send verification email"
+            );
         }
     }
 
-    #[async_trait::async_trait]
-    impl RefreshTokenRepository for MockRefreshTokenRepository {
-        async fn save(&self, token: &RefreshToken) -> Result<(), AuthDomainError> {
-            let mut tokens = self.tokens.lock().unwrap();
-            tokens.insert(token.token_hash().to_string(), token.clone());
-            Ok(())
-        }
+    /// Finish a login with a code from the authenticator app or a recovery code
+    pub async fn verify_totp(
+        &self,
+        cmd: VerifyTotpCommand,
+    ) -> Result<AuthResponse, AuthDomainError> {
+        let client = cmd.client.clone();
+        let mut user_id = None;
+        let result = self.complete_totp_login(cmd, &mut user_id).await;
+        self.record_audit(
+            AuthAuditEventType::TotpVerify,
+            user_id,
+            None,
+            &client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
+    }
 
-        async fn find_by_id(&self, id: &TokenId) -> Result<Option<RefreshToken>, AuthDomainError> {
-            let tokens = self.tokens.lock().unwrap();
-            Ok(tokens.values().find(|t| t.id().as_str() == id.as_str()).cloned())
-        }
+    async fn complete_totp_login(
+        &self,
+        cmd: VerifyTotpCommand,
+        audit_user_id: &mut Option<UserId>,
+    ) -> Result<AuthResponse, AuthDomainError> {
+        // 1. Find the pending login
+        let token_hash = hash_token(&cmd.challenge_token);
+        let mut challenge = self
+            .totp_repo
+            .find_challenge(&token_hash)
+            .await?
+            .ok_or(AuthDomainError::TokenInvalid)?;
+        *audit_user_id = Some(challenge.user_id().clone());
 
-        async fn find_by_hash(&self, hash: &str) -> Result<Option<RefreshToken>, AuthDomainError> {
-            let tokens = self.tokens.lock().unwrap();
-            Ok(tokens.get(hash).cloned())
+        // 2. It must be fresh and continue on the device that entered the password
+        if challenge.is_expired() {
+            self.totp_repo.delete_challenge(&token_hash).await?;
+            return Err(AuthDomainError::TokenExpired);
         }
-
-        async fn revoke(&self, id: &TokenId) -> Result<(), AuthDomainError> {
-            let mut tokens = self.tokens.lock().unwrap();
-            if let Some(token) = tokens.values_mut().find(|t| t.id().as_str() == id.as_str()) {
-                token.revoke();
-            }
-            Ok(())
+        if challenge.device_fingerprint() != cmd.device_fingerprint {
+            self.totp_repo.delete_challenge(&token_hash).await?;
+            return Err(AuthDomainError::TokenInvalid);
         }
 
-        async fn revoke_all_for_user(&self, user_id: &UserId) -> Result<(), AuthDomainError> {
-            let mut tokens = self.tokens.lock().unwrap();
-            for token in tokens.values_mut() {
-                if token.user_id().as_str() == user_id.as_str() {
-                    token.revoke();
-                }
+        // 3. Check the code against the user's confirmed TOTP
+        let user = self
+            .user_repo
+            .find_by_id(challenge.user_id())
+            .await?
+            .ok_or(AuthDomainError::UserNotFound)?;
+        let Some(mut totp) = self
+            .totp_repo
+            .find_by_user(user.id())
+            .await?
+            .filter(UserTotp::is_confirmed)
+        else {
+            self.totp_repo.delete_challenge(&token_hash).await?;
+            return Err(AuthDomainError::TotpNotEnabled);
+        };
+        if !totp.verify_code(&cmd.code, Utc::now()) && !totp.use_recovery_code(&cmd.code) {
+            // Too many wrong codes: the password has to be entered again
+            if challenge.record_failure() {
+                self.totp_repo.save_challenge(&challenge).await?;
+            } else {
+                self.totp_repo.delete_challenge(&token_hash).await?;
             }
-            Ok(())
+            return Err(AuthDomainError::InvalidTotpCode);
         }
 
-        async fn delete_expired(&self) -> Result<u64, AuthDomainError> {
-            let mut tokens = self.tokens.lock().unwrap();
-            let before = tokens.len();
-            tokens.retain(|_, t| !t.is_expired());
-            Ok((before - tokens.len()) as u64)
-        }
+        // 4. Remember the used code, consume the challenge and sign in
+        self.totp_repo.save(&totp).await?;
+        self.totp_repo.delete_challenge(&token_hash).await?;
+        self.start_session(&user, &cmd.device_fingerprint, &cmd.client)
+            .await
     }
 
-    /// Mock Password Hasher
-    struct MockPasswordHasher;
+    /// Start 2FA setup with a new secret and recovery codes. 2FA isn't
+    /// enforced until `confirm_totp` proves the app produces codes; starting
+    /// again before then replaces the secret.
+    pub async fn enable_totp(
+        &self,
+        cmd: EnableTotpCommand,
+    ) -> Result<TotpSetupResponse, AuthDomainError> {
+        let user_id = UserId::new(cmd.user_id);
+        let user = self
+            .user_repo
+            .find_by_id(&user_id)
+            .await?
+            .ok_or(AuthDomainError::UserNotFound)?;
 
-    #[async_trait::async_trait]
-    impl PasswordHasher for MockPasswordHasher {
-        async fn hash(&self, password: &PlainPassword) -> Result<PasswordHash, AuthDomainError> {
-            Ok(PasswordHash::from_hash(format!("hashed_{}", password.as_str())))
+        if let Some(existing) = self.totp_repo.find_by_user(&user_id).await?
+            && existing.is_confirmed()
+        {
+            return Err(AuthDomainError::TotpAlreadyEnabled);
         }
 
-        async fn verify(
-            &self,
-            password: &PlainPassword,
-            hash: &PasswordHash,
-        ) -> Result<bool, AuthDomainError> {
-            Ok(hash.as_str() == format!("hashed_{}", password.as_str()))
-        }
+        let secret = TotpSecret::generate();
+        let recovery_codes: Vec<String> = (0..TOTP_RECOVERY_CODE_COUNT)
+            .map(|_| self.generate_recovery_code())
+            .collect();
+        let totp = UserTotp::new(
+            user_id,
+            secret.clone(),
+            recovery_codes
+                .iter()
+                .map(|c| hash_one_time_secret(c))
+                .collect(),
+        );
+        self.totp_repo.save(&totp).await?;
+
+        Ok(TotpSetupResponse {
+            secret: secret.to_base32(),
+            otpauth_uri: secret.provisioning_uri(TOTP_ISSUER, user.email().as_str()),
+            recovery_codes,
+        })
     }
 
-    /// Mock Token Service
-    struct MockTokenService {
-        should_fail_decode: bool,
+    /// Generate a recovery code like "k3f9x-2mq7d"
+    fn generate_recovery_code(&self) -> String {
+        const CHARSET: &[u8] = b"abcdefghijkmnpqrstuvwxyz23456789";
+        let mut rng = rand::rng();
+        let mut half = || -> String {
+            (0..5)
+                .map(|_| CHARSET[rng.random_range(0..CHARSET.len())] as char)
+                .collect()
+        };
+        format!("{}-{}", half(), half())
     }
 
-    impl MockTokenService {
-        fn new() -> Self {
-            Self { should_fail_decode: false }
-        }
+    /// Turn on 2FA once the user enters a code from their app
+    pub async fn confirm_totp(&self, cmd: ConfirmTotpCommand) -> Result<(), AuthDomainError> {
+        let user_id = UserId::new(cmd.user_id.clone());
+        let client = cmd.client.clone();
+        let result = self.activate_totp(cmd).await;
+        self.record_audit(
+            AuthAuditEventType::TotpEnable,
+            Some(user_id),
+            None,
+            &client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
+    }
 
-        fn failing_decode() -> Self {
-            Self { should_fail_decode: true }
+    async fn activate_totp(&self, cmd: ConfirmTotpCommand) -> Result<(), AuthDomainError> {
+        let user_id = UserId::new(cmd.user_id);
+        let mut totp = self
+            .totp_repo
+            .find_by_user(&user_id)
+            .await?
+            .ok_or(AuthDomainError::TotpNotEnabled)?;
+        if totp.is_confirmed() {
+            return Err(AuthDomainError::TotpAlreadyEnabled);
+        }
+        if !totp.confirm(&cmd.code, Utc::now()) {
+            return Err(AuthDomainError::InvalidTotpCode);
         }
+        self.totp_repo.save(&totp).await
     }
 
-    #[async_trait::async_trait]
-    impl TokenService for MockTokenService {
-        async fn generate_token_pair(
-            &self,
-            user_id: &UserId,
-            _email: &str,
-            _org_context: Option<crate::modules::auth::application::ports::OrgContext>,
-        ) -> Result<TokenPair, AuthDomainError> {
-            Ok(TokenPair {
-                access_token: format!("access_token_{}", user_id.as_str()),
-                refresh_token: format!("refresh_token_{}", user_id.as_str()),
-                access_expires_in: 900,
-                refresh_expires_in: 604800,
-            })
-        }
+    /// Turn off 2FA; requires the current password
+    pub async fn disable_totp(&self, cmd: DisableTotpCommand) -> Result<(), AuthDomainError> {
+        let user_id = UserId::new(cmd.user_id.clone());
+        let client = cmd.client.clone();
+        let result = self.remove_totp(cmd).await;
+        self.record_audit(
+            AuthAuditEventType::TotpDisable,
+            Some(user_id),
+            None,
+            &client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
+    }
 
-        fn validate_access_token(&self, token: &str) -> Result<TokenClaims, AuthDomainError> {
-            if token.starts_with("access_token_") {
-                let user_id = token.replace("access_token_", "");
-                Ok(TokenClaims {
-                    user_id,
-                    email: "test@example.com".to_string(),
-                    org_id: None,
-                    org_role: None,
-                    exp: Utc::now().timestamp() + 900,
-                    iat: Utc::now().timestamp(),
-                })
-            } else {
-                Err(AuthDomainError::TokenInvalid)
-            }
-        }
+    async fn remove_totp(&self, cmd: DisableTotpCommand) -> Result<(), AuthDomainError> {
+        // 1. Get current user
+        let user_id = UserId::new(cmd.user_id);
+        let user = self
+            .user_repo
+            .find_by_id(&user_id)
+            .await?
+            .ok_or(AuthDomainError::UserNotFound)?;
 
-        fn decode_refresh_token(&self, token: &str) -> Result<TokenClaims, AuthDomainError> {
-            if self.should_fail_decode {
-                return Err(AuthDomainError::TokenInvalid);
+        let mut totp = self.totp_repo.find_by_user(&user_id).await?;
+
+        // 2. Re-authenticate: the password, or a current code for OAuth-only users
+        if let Some(password_hash) = user.password_hash() {
+            let current_password = cmd
+                .current_password
+                .ok_or(AuthDomainError::InvalidCredentials)?;
+            let current_password = PlainPassword::for_verification(current_password);
+            let is_valid = self
+                .password_hasher
+                .verify(&current_password, password_hash)
+                .await?;
+            if !is_valid {
+                return Err(AuthDomainError::InvalidCredentials);
             }
-            if token.starts_with("refresh_token_") {
-                let user_id = token.replace("refresh_token_", "");
-                Ok(TokenClaims {
-                    user_id,
-                    email: "test@example.com".to_string(),
-                    org_id: None,
-                    org_role: None,
-                    exp: Utc::now().timestamp() + 604800,
-                    iat: Utc::now().timestamp(),
-                })
-            } else {
-                Err(AuthDomainError::TokenInvalid)
+        } else {
+            let code = cmd.code.ok_or(AuthDomainError::InvalidCredentials)?;
+            let totp = totp.as_mut().ok_or(AuthDomainError::TotpNotEnabled)?;
+            if !totp.verify_code(&code, Utc::now()) {
+                return Err(AuthDomainError::InvalidTotpCode);
             }
         }
 
-        fn hash_refresh_token(&self, token: &str) -> String {
-            format!("hash_{}", token)
+        // 3. Remove the secret, recovery codes and pending challenges
+        if totp.is_none() {
+            return Err(AuthDomainError::TotpNotEnabled);
         }
+        self.totp_repo.delete(&user_id).await
     }
 
-    /// Mock ID Generator
-    struct MockIdGenerator {
-        counter: Mutex<u32>,
-    }
+    /// Helper: store refresh token in database
+    /// `session_started_at` is the sign-in time of the token being rotated;
+    /// None starts a new session
+    async fn store_refresh_token(
+        &self,
+        user_id: &UserId,
+        refresh_token: &str,
+        device_fingerprint: &str,
+        expires_in_secs: i64,
+        client: &ClientInfo,
+        session_started_at: Option<DateTime<Utc>>,
+    ) -> Result<(), AuthDomainError> {
+        let token_hash = self.token_service.hash_refresh_token(refresh_token);
+        let token_id = TokenId::new(self.id_generator.generate());
+        let expires_at = Utc::now() + Duration::seconds(expires_in_secs);
 
-    impl MockIdGenerator {
-        fn new() -> Self {
-            Self { counter: Mutex::new(0) }
+        let mut token = RefreshToken::new(
+            token_id,
+            user_id.clone(),
+            token_hash,
+            device_fingerprint.to_string(),
+            expires_at,
+        )
+        .with_client(client.ip_address.clone(), client.user_agent.clone());
+        if let Some(started_at) = session_started_at {
+            token = token.continuing_session(started_at);
         }
-    }
 
-    impl IdGenerator for MockIdGenerator {
-        fn generate(&self) -> String {
-            let mut counter = self.counter.lock().unwrap();
-            *counter += 1;
-            format!("generated-id-{}", counter)
-        }
+        self.token_repo.save(&token).await
     }
 
-    /// Mock Organization Repository
-    struct MockOrganizationRepository {
-        orgs: Mutex<HashMap<String, Organization>>,
+    /// List the user's signed-in sessions (unrevoked refresh tokens), most
+    /// recently used first
+    pub async fn list_sessions(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<SessionResponse>, AuthDomainError> {
+        let tokens = self
+            .token_repo
+            .find_active_by_user(&UserId::new(user_id.to_string()))
+            .await?;
+
+        Ok(tokens
+            .into_iter()
+            .map(|t| SessionResponse {
+                id: t.id().as_str().to_string(),
+                created_at: t.session_started_at(),
+                last_used_at: t.created_at(),
+                ip_address: t.ip_address().map(String::from),
+                user_agent: t
+                    .user_agent()
+                    .map(|ua| ua.chars().take(SESSION_USER_AGENT_MAX_CHARS).collect()),
+            })
+            .collect())
     }
 
-    impl MockOrganizationRepository {
-        fn new() -> Self {
-            Self {
-                orgs: Mutex::new(HashMap::new()),
-            }
-        }
+    /// Sign out one of the user's sessions
+    pub async fn revoke_session(&self, cmd: RevokeSessionCommand) -> Result<(), AuthDomainError> {
+        let user_id = UserId::new(cmd.user_id.clone());
+        let client = cmd.client.clone();
+        let result = self.revoke_own_session(cmd).await;
+        self.record_audit(
+            AuthAuditEventType::SessionRevoke,
+            Some(user_id),
+            None,
+            &client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
     }
 
-    #[async_trait::async_trait]
-    impl OrganizationRepository for MockOrganizationRepository {
-        async fn find_by_id(
-            &self,
-            id: &OrgId,
-        ) -> Result<Option<Organization>, crate::modules::organizations::domain::OrgDomainError> {
-            let orgs = self.orgs.lock().unwrap();
-            Ok(orgs.get(id.as_str()).cloned())
+    async fn revoke_own_session(&self, cmd: RevokeSessionCommand) -> Result<(), AuthDomainError> {
+        // Other users' sessions look the same as missing ones
+        let token = self
+            .token_repo
+            .find_by_id(&TokenId::new(cmd.session_id))
+            .await?
+            .filter(|t| t.user_id().as_str() == cmd.user_id && t.is_valid())
+            .ok_or(AuthDomainError::SessionNotFound)?;
+
+        self.token_repo.revoke(token.id()).await
+    }
+
+    /// Sign out every session of the user except the one holding `refresh_token`,
+    /// returning how many were signed out
+    pub async fn revoke_other_sessions(
+        &self,
+        cmd: RevokeOtherSessionsCommand,
+    ) -> Result<u64, AuthDomainError> {
+        let user_id = UserId::new(cmd.user_id.clone());
+        let client = cmd.client.clone();
+        let result = self.revoke_sessions_except_current(cmd).await;
+        self.record_audit(
+            AuthAuditEventType::SessionRevoke,
+            Some(user_id),
+            None,
+            &client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
+    }
+
+    async fn revoke_sessions_except_current(
+        &self,
+        cmd: RevokeOtherSessionsCommand,
+    ) -> Result<u64, AuthDomainError> {
+        let user_id = UserId::new(cmd.user_id);
+
+        // 1. Identify the caller's own session
+        let token_hash = self.token_service.hash_refresh_token(&cmd.refresh_token);
+        let current = self
+            .token_repo
+            .find_by_hash(&token_hash)
+            .await?
+            .filter(|t| t.user_id() == &user_id && t.is_valid())
+            .ok_or(AuthDomainError::TokenInvalid)?;
+
+        // 2. Revoke the rest
+        let mut revoked = 0;
+        for token in self.token_repo.find_active_by_user(&user_id).await? {
+            if token.id() != current.id() {
+                self.token_repo.revoke(token.id()).await?;
+                revoked += 1;
+            }
         }
+        Ok(revoked)
+    }
 
-        async fn find_by_slug(
-            &self,
-            slug: &str,
-        ) -> Result<Option<Organization>, crate::modules::organizations::domain::OrgDomainError> {
-            let orgs = self.orgs.lock().unwrap();
-            Ok(orgs.values().find(|o| o.slug().as_str() == slug).cloned())
+    /// Create a personal access token. The returned token is the only time
+    /// its secret is available; only its hash is stored.
+    pub async fn create_personal_access_token(
+        &self,
+        cmd: CreatePersonalAccessTokenCommand,
+    ) -> Result<CreatedPersonalAccessTokenResponse, AuthDomainError> {
+        let user_id = UserId::new(cmd.user_id.clone());
+        let client = cmd.client.clone();
+        let result = self.issue_personal_access_token(cmd).await;
+        self.record_audit(
+            AuthAuditEventType::PersonalAccessTokenCreate,
+            Some(user_id),
+            None,
+            &client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
+    }
+
+    async fn issue_personal_access_token(
+        &self,
+        cmd: CreatePersonalAccessTokenCommand,
+    ) -> Result<CreatedPersonalAccessTokenResponse, AuthDomainError> {
+        let repo = self
+            .personal_access_token_repo
+            .as_ref()
+            .ok_or(AuthDomainError::PersonalAccessTokensUnavailable)?;
+
+        // 1. Validate input
+        let name = cmd.name.trim().to_string();
+        if name.is_empty() || name.chars().count() > PersonalAccessToken::MAX_NAME_CHARS {
+            return Err(AuthDomainError::InvalidPersonalAccessToken(format!(
+                "name must be 1 to {} characters",
+                PersonalAccessToken::MAX_NAME_CHARS
+            )));
+        }
+        let mut scopes: Vec<TokenScope> = Vec::new();
+        for scope in &cmd.scopes {
+            let scope = TokenScope::from_str(scope.trim())?;
+            if !scopes.contains(&scope) {
+                scopes.push(scope);
+            }
+        }
+        if scopes.is_empty() {
+            return Err(AuthDomainError::InvalidPersonalAccessToken(
+                "at least one scope is required".to_string(),
+            ));
         }
+        let expires_at = match cmd.expires_in_days {
+            Some(days) if days <= 0 => {
+                return Err(AuthDomainError::InvalidPersonalAccessToken(
+                    "expires_in_days must be positive".to_string(),
+                ));
+            }
+            Some(days) => Some(
+                Utc::now()
+                    .checked_add_signed(Duration::days(days))
+                    .ok_or_else(|| {
+                        AuthDomainError::InvalidPersonalAccessToken(
+                            "expires_in_days is too large".to_string(),
+                        )
+                    })?,
+            ),
+            None => None,
+        };
 
-        async fn save(
-            &self,
-            org: &Organization,
-        ) -> Result<(), crate::modules::organizations::domain::OrgDomainError> {
-            let mut orgs = self.orgs.lock().unwrap();
-            orgs.insert(org.id().as_str().to_string(), org.clone());
-            Ok(())
+        // 2. Generate the secret and store its hash
+        let mut bytes = [0u8; 32];
+        rand::rng().fill(&mut bytes);
+        let secret: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        let token = format!("{}{}", PersonalAccessToken::PREFIX, secret);
+
+        let pat = PersonalAccessToken::new(
+            self.id_generator.generate(),
+            UserId::new(cmd.user_id),
+            name,
+            hash_token(&token),
+            scopes,
+            expires_at,
+        );
+        repo.save(&pat).await?;
+
+        Ok(CreatedPersonalAccessTokenResponse {
+            token,
+            details: Self::personal_access_token_response(&pat),
+        })
+    }
+
+    /// List the user's personal access tokens, newest first
+    pub async fn list_personal_access_tokens(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<PersonalAccessTokenResponse>, AuthDomainError> {
+        let repo = self
+            .personal_access_token_repo
+            .as_ref()
+            .ok_or(AuthDomainError::PersonalAccessTokensUnavailable)?;
+
+        Ok(repo
+            .find_by_user(&UserId::new(user_id.to_string()))
+            .await?
+            .iter()
+            .map(Self::personal_access_token_response)
+            .collect())
+    }
+
+    /// Revoke one of the user's personal access tokens
+    pub async fn revoke_personal_access_token(
+        &self,
+        cmd: RevokePersonalAccessTokenCommand,
+    ) -> Result<(), AuthDomainError> {
+        let user_id = UserId::new(cmd.user_id);
+        let result = match &self.personal_access_token_repo {
+            Some(repo) => match repo.delete(&user_id, &cmd.token_id).await {
+                Ok(true) => Ok(()),
+                Ok(false) => Err(AuthDomainError::PersonalAccessTokenNotFound),
+                Err(e) => Err(e),
+            },
+            None => Err(AuthDomainError::PersonalAccessTokensUnavailable),
+        };
+        self.record_audit(
+            AuthAuditEventType::PersonalAccessTokenRevoke,
+            Some(user_id),
+            None,
+            &cmd.client,
+            result.as_ref().err(),
+        )
+        .await;
+        result
+    }
+
+    fn personal_access_token_response(pat: &PersonalAccessToken) -> PersonalAccessTokenResponse {
+        PersonalAccessTokenResponse {
+            id: pat.id().to_string(),
+            name: pat.name().to_string(),
+            scopes: pat
+                .scopes()
+                .iter()
+                .map(|s| s.as_str().to_string())
+                .collect(),
+            expires_at: pat.expires_at(),
+            last_used_at: pat.last_used_at(),
+            created_at: pat.created_at(),
         }
+    }
 
-        async fn slug_exists(
-            &self,
-            slug: &str,
-        ) -> Result<bool, crate::modules::organizations::domain::OrgDomainError> {
-            let orgs = self.orgs.lock().unwrap();
-            Ok(orgs.values().any(|o| o.slug().as_str() == slug))
+    /// List auth audit events for members of the caller's current organization.
+    /// Only organization admins and owners may view them.
+    pub async fn list_audit_events(
+        &self,
+        cmd: ListAuditEventsCommand,
+    ) -> Result<AuditEventListResponse, AuthDomainError> {
+        let pagination = self.pagination.resolve(cmd.limit, cmd.offset);
+
+        // 1. Verify the caller administers their current organization
+        let org_id = OrgId::new(cmd.org_id.ok_or(AuthDomainError::InsufficientPermissions)?);
+        let requesting_user_id = UserId::new(cmd.requesting_user_id);
+        let role = self
+            .find_active_membership_role(&requesting_user_id, &org_id)
+            .await?
+            .ok_or(AuthDomainError::InsufficientPermissions)?;
+        if !role.can_manage_members() {
+            return Err(AuthDomainError::InsufficientPermissions);
+        }
+
+        // 2. Scope the query to the organization's members
+        let mut user_ids: Vec<UserId> = self
+            .member_repo
+            .find_all_by_org(&org_id)
+            .await
+            .map_err(|e| AuthDomainError::InternalError(e.to_string()))?
+            .into_iter()
+            .map(|m| m.user_id().clone())
+            .collect();
+        if let Some(user_id) = cmd.user_id {
+            user_ids.retain(|id| id.as_str() == user_id);
+            if user_ids.is_empty() {
+                return Ok(AuditEventListResponse {
+                    events: vec![],
+                    limit: pagination.limit,
+                });
+            }
         }
+
+        let filters = AuthAuditFilters {
+            user_ids,
+            event_type: cmd
+                .event_type
+                .as_deref()
+                .map(AuthAuditEventType::from_str)
+                .transpose()?,
+            success: cmd.success,
+            start_time: cmd.start_time,
+            end_time: cmd.end_time,
+        };
+
+        // 3. Query events
+        let events = self
+            .audit_repo
+            .find(&filters, pagination.limit, pagination.offset)
+            .await?;
+
+        let events = events
+            .into_iter()
+            .map(|e| AuditEventResponse {
+                id: e.id().as_str().to_string(),
+                event_type: e.event_type().as_str().to_string(),
+                success: e.success(),
+                user_id: e.user_id().map(|u| u.as_str().to_string()),
+                identifier: e.identifier().map(String::from),
+                ip_address: e.ip_address().map(String::from),
+                user_agent: e.user_agent().map(String::from),
+                failure_reason: e.failure_reason().map(String::from),
+                created_at: e.created_at(),
+            })
+            .collect();
+        Ok(AuditEventListResponse {
+            events,
+            limit: pagination.limit,
+        })
     }
 
-    /// Mock Organization Member Repository
-    struct MockOrganizationMemberRepository {
-        members: Mutex<HashMap<String, OrganizationMember>>,
+    /// Helper: record an auth audit event. Audit failures are logged but
+    /// never fail the action being audited.
+    async fn record_audit(
+        &self,
+        event_type: AuthAuditEventType,
+        user_id: Option<UserId>,
+        identifier: Option<String>,
+        client: &ClientInfo,
+        error: Option<&AuthDomainError>,
+    ) {
+        if !self.audit_enabled {
+            return;
+        }
+
+        // Internal errors may carry driver messages; keep them out of the audit trail
+        let failure_reason = error.map(|e| match e {
+            AuthDomainError::InternalError(_) => "Internal error".to_string(),
+            e => e.to_string(),
+        });
+
+        let event = AuthAuditEvent::new(
+            AuditEventId::new(self.id_generator.generate()),
+            event_type,
+            user_id,
+            identifier,
+            client.ip_address.clone(),
+            client.user_agent.clone(),
+            failure_reason,
+        );
+
+        if let Err(e) = self.audit_repo.save(&event).await {
+            tracing::warn!(
+                error = %e,
+                event_type = event_type.as_str(),
+                "Failed to record auth audit event"
+            );
+        }
     }
+}
 
-    impl MockOrganizationMemberRepository {
+#[async_trait]
+impl<U, T, P, TS, ID, OR, MR, AA, TT> EmailVerificationCheck
+    for AuthService<U, T, P, TS, ID, OR, MR, AA, TT>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    async fn is_email_verified(&self, user_id: &str) -> Result<bool, ProjectDomainError> {
+        AuthService::is_email_verified(self, user_id)
+            .await
+            .map_err(|e| ProjectDomainError::InternalError(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl<U, T, P, TS, ID, OR, MR, AA, TT> PersonalAccessTokenVerifier
+    for AuthService<U, T, P, TS, ID, OR, MR, AA, TT>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    async fn verify_personal_access_token(
+        &self,
+        token: &str,
+    ) -> Result<PersonalAccessTokenIdentity, AuthDomainError> {
+        let repo = self
+            .personal_access_token_repo
+            .as_ref()
+            .ok_or(AuthDomainError::PersonalAccessTokensUnavailable)?;
+        if !token.starts_with(PersonalAccessToken::PREFIX) {
+            return Err(AuthDomainError::TokenInvalid);
+        }
+
+        // 1. Find the token and check it is still usable
+        let pat = repo
+            .find_by_hash(&hash_token(token))
+            .await?
+            .ok_or(AuthDomainError::TokenInvalid)?;
+        if pat.is_expired() {
+            return Err(AuthDomainError::TokenExpired);
+        }
+
+        // 2. The owner must still exist
+        let user = self
+            .user_repo
+            .find_by_id(pat.user_id())
+            .await?
+            .filter(|u| !u.is_deleted())
+            .ok_or(AuthDomainError::TokenInvalid)?;
+
+        // 3. Record use, at most once a minute to spare writes on busy scripts
+        let now = Utc::now();
+        if pat
+            .last_used_at()
+            .is_none_or(|last| now - last > Duration::seconds(60))
+            && let Err(e) = repo.touch(pat.id(), now).await
+        {
+            tracing::warn!(error = %e, "Failed to record personal access token use");
+        }
+
+        Ok(PersonalAccessTokenIdentity {
+            user_id: user.id().as_str().to_string(),
+            email: user.email().as_str().to_string(),
+            scopes: pat.scopes().to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::auth::application::ports::{TokenClaims, TokenPair};
+    use crate::modules::auth::domain::{OAuthProvider, PasswordHasher};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    // ==================== Mock Implementations ====================
+
+    /// Mock User Repository
+    struct MockUserRepository {
+        users: Mutex<HashMap<String, User>>,
+    }
+
+    impl MockUserRepository {
         fn new() -> Self {
             Self {
-                members: Mutex::new(HashMap::new()),
+                users: Mutex::new(HashMap::new()),
             }
         }
-    }
 
-    #[async_trait::async_trait]
-    impl OrganizationMemberRepository for MockOrganizationMemberRepository {
-        async fn find_by_id(
-            &self,
-            id: &MemberId,
-        ) -> Result<Option<OrganizationMember>, crate::modules::organizations::domain::OrgDomainError>
-        {
-            let members = self.members.lock().unwrap();
-            Ok(members.get(id.as_str()).cloned())
+        fn with_user(user: User) -> Self {
+            let repo = Self::new();
+            repo.users
+                .lock()
+                .unwrap()
+                .insert(user.email().as_str().to_string(), user);
+            repo
         }
+    }
 
-        async fn find_by_org_and_user(
-            &self,
-            org_id: &OrgId,
-            user_id: &UserId,
-        ) -> Result<Option<OrganizationMember>, crate::modules::organizations::domain::OrgDomainError>
-        {
-            let members = self.members.lock().unwrap();
-            Ok(members
+    #[async_trait::async_trait]
+    impl UserRepository for MockUserRepository {
+        async fn find_by_id(&self, id: &UserId) -> Result<Option<User>, AuthDomainError> {
+            let users = self.users.lock().unwrap();
+            Ok(users
                 .values()
-                .find(|m| {
-                    m.organization_id().as_str() == org_id.as_str()
-                        && m.user_id().as_str() == user_id.as_str()
-                })
+                .find(|u| u.id().as_str() == id.as_str())
                 .cloned())
         }
 
-        async fn find_all_by_org(
-            &self,
-            org_id: &OrgId,
-        ) -> Result<Vec<OrganizationMember>, crate::modules::organizations::domain::OrgDomainError>
-        {
-            let members = self.members.lock().unwrap();
-            Ok(members
-                .values()
-                .filter(|m| m.organization_id().as_str() == org_id.as_str())
-                .cloned()
-                .collect())
-        }
-
-        async fn find_all_by_user(
-            &self,
-            user_id: &UserId,
-        ) -> Result<Vec<OrganizationMember>, crate::modules::organizations::domain::OrgDomainError>
-        {
-            let members = self.members.lock().unwrap();
-            Ok(members
-                .values()
-                .filter(|m| m.user_id().as_str() == user_id.as_str())
-                .cloned()
-                .collect())
+        async fn find_by_email(&self, email: &Email) -> Result<Option<User>, AuthDomainError> {
+            let users = self.users.lock().unwrap();
+            Ok(users.get(email.as_str()).cloned())
         }
 
-        async fn find_last_accessed_by_user(
+        async fn find_by_username(
             &self,
-            user_id: &UserId,
-        ) -> Result<Option<OrganizationMember>, crate::modules::organizations::domain::OrgDomainError>
-        {
-            let members = self.members.lock().unwrap();
-            Ok(members
+            username: &Username,
+        ) -> Result<Option<User>, AuthDomainError> {
+            let users = self.users.lock().unwrap();
+            Ok(users
                 .values()
-                .filter(|m| m.user_id().as_str() == user_id.as_str() && m.last_accessed_at().is_some())
-                .max_by_key(|m| m.last_accessed_at())
+                .find(|u| u.username() == Some(username))
                 .cloned())
         }
 
-        async fn find_personal_org_membership(
-            &self,
-            _user_id: &UserId,
-        ) -> Result<Option<OrganizationMember>, crate::modules::organizations::domain::OrgDomainError>
-        {
-            // For tests, we don't track personal orgs, just return None
-            Ok(None)
+        async fn save(&self, user: &User) -> Result<(), AuthDomainError> {
+            let mut users = self.users.lock().unwrap();
+            // Drop the entry under the old address when the email changed
+            users.retain(|_, u| u.id() != user.id());
+            users.insert(user.email().as_str().to_string(), user.clone());
+            Ok(())
         }
 
-        async fn save(
+        async fn update_password_hash(
             &self,
-            member: &OrganizationMember,
-        ) -> Result<(), crate::modules::organizations::domain::OrgDomainError> {
-            let mut members = self.members.lock().unwrap();
-            members.insert(member.id().as_str().to_string(), member.clone());
+            id: &UserId,
+            password_hash: &PasswordHash,
+        ) -> Result<(), AuthDomainError> {
+            let mut users = self.users.lock().unwrap();
+            if let Some(user) = users.values_mut().find(|u| u.id() == id) {
+                user.update_password(password_hash.clone());
+            }
             Ok(())
         }
 
-        async fn delete(
-            &self,
-            id: &MemberId,
-        ) -> Result<(), crate::modules::organizations::domain::OrgDomainError> {
-            let mut members = self.members.lock().unwrap();
-            members.remove(id.as_str());
-            Ok(())
+        async fn exists_by_email(&self, email: &Email) -> Result<bool, AuthDomainError> {
+            let users = self.users.lock().unwrap();
+            Ok(users.contains_key(email.as_str()))
         }
 
-        async fn count_owners(
+        async fn exists_by_username(&self, username: &Username) -> Result<bool, AuthDomainError> {
+            let users = self.users.lock().unwrap();
+            Ok(users.values().any(|u| u.username() == Some(username)))
+        }
+    }
+
+    /// Mock Refresh Token Repository
+    struct MockRefreshTokenRepository {
+        tokens: Mutex<HashMap<String, RefreshToken>>,
+    }
+
+    impl MockRefreshTokenRepository {
+        fn new() -> Self {
+            Self {
+                tokens: Mutex::new(HashMap::new()),
+            }
+        }
+
+        fn with_token(token: RefreshToken) -> Self {
+            let repo = Self::new();
+            repo.tokens
+                .lock()
+                .unwrap()
+                .insert(token.token_hash().to_string(), token);
+            repo
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl RefreshTokenRepository for MockRefreshTokenRepository {
+        async fn save(&self, token: &RefreshToken) -> Result<(), AuthDomainError> {
+            let mut tokens = self.tokens.lock().unwrap();
+            tokens.insert(token.token_hash().to_string(), token.clone());
+            Ok(())
+        }
+
+        async fn find_by_id(&self, id: &TokenId) -> Result<Option<RefreshToken>, AuthDomainError> {
+            let tokens = self.tokens.lock().unwrap();
+            Ok(tokens
+                .values()
+                .find(|t| t.id().as_str() == id.as_str())
+                .cloned())
+        }
+
+        async fn find_by_hash(&self, hash: &str) -> Result<Option<RefreshToken>, AuthDomainError> {
+            let tokens = self.tokens.lock().unwrap();
+            Ok(tokens.get(hash).cloned())
+        }
+
+        async fn find_active_by_user(
             &self,
-            org_id: &OrgId,
-        ) -> Result<u32, crate::modules::organizations::domain::OrgDomainError> {
-            let members = self.members.lock().unwrap();
-            Ok(members
+            user_id: &UserId,
+        ) -> Result<Vec<RefreshToken>, AuthDomainError> {
+            let tokens = self.tokens.lock().unwrap();
+            let mut active: Vec<RefreshToken> = tokens
                 .values()
-                .filter(|m| {
-                    m.organization_id().as_str() == org_id.as_str()
-                        && m.role() == &OrgRole::Owner
+                .filter(|t| t.user_id() == user_id && t.is_valid())
+                .cloned()
+                .collect();
+            active.sort_by_key(|t| std::cmp::Reverse(t.created_at()));
+            Ok(active)
+        }
+
+        async fn revoke(&self, id: &TokenId) -> Result<(), AuthDomainError> {
+            let mut tokens = self.tokens.lock().unwrap();
+            if let Some(token) = tokens.values_mut().find(|t| t.id().as_str() == id.as_str()) {
+                token.revoke();
+            }
+            Ok(())
+        }
+
+        async fn revoke_all_for_user(&self, user_id: &UserId) -> Result<(), AuthDomainError> {
+            let mut tokens = self.tokens.lock().unwrap();
+            for token in tokens.values_mut() {
+                if token.user_id().as_str() == user_id.as_str() {
+                    token.revoke();
+                }
+            }
+            Ok(())
+        }
+
+        async fn delete_expired(&self) -> Result<u64, AuthDomainError> {
+            let mut tokens = self.tokens.lock().unwrap();
+            let before = tokens.len();
+            tokens.retain(|_, t| !t.is_expired());
+            Ok((before - tokens.len()) as u64)
+        }
+    }
+
+    /// Mock Password Hasher
+    /// Also accepts `legacy_<password>`, standing in for a hash made with
+    /// older, weaker parameters
+    struct MockPasswordHasher;
+
+    #[async_trait::async_trait]
+    impl PasswordHasher for MockPasswordHasher {
+        async fn hash(&self, password: &PlainPassword) -> Result<PasswordHash, AuthDomainError> {
+            Ok(PasswordHash::from_hash(format!(
+                "hashed_{}",
+                password.as_str()
+            )))
+        }
+
+        async fn verify(
+            &self,
+            password: &PlainPassword,
+            hash: &PasswordHash,
+        ) -> Result<bool, AuthDomainError> {
+            Ok(hash.as_str() == format!("hashed_{}", password.as_str())
+                || hash.as_str() == format!("legacy_{}", password.as_str()))
+        }
+
+        fn needs_rehash(&self, hash: &PasswordHash) -> bool {
+            hash.as_str().starts_with("legacy_")
+        }
+    }
+
+    /// Mock Token Service
+    struct MockTokenService {
+        should_fail_decode: bool,
+    }
+
+    impl MockTokenService {
+        fn new() -> Self {
+            Self {
+                should_fail_decode: false,
+            }
+        }
+
+        fn failing_decode() -> Self {
+            Self {
+                should_fail_decode: true,
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl TokenService for MockTokenService {
+        async fn generate_token_pair(
+            &self,
+            user_id: &UserId,
+            _email: &str,
+            _org_context: Option<crate::modules::auth::application::ports::OrgContext>,
+        ) -> Result<TokenPair, AuthDomainError> {
+            Ok(TokenPair {
+                access_token: format!("access_token_{}", user_id.as_str()),
+                refresh_token: format!("refresh_token_{}", user_id.as_str()),
+                access_expires_in: 900,
+                refresh_expires_in: 604800,
+            })
+        }
+
+        async fn refresh_token_pair(
+            &self,
+            user_id: &UserId,
+            email: &str,
+            org_context: Option<crate::modules::auth::application::ports::OrgContext>,
+            _session_started_at: i64,
+        ) -> Result<TokenPair, AuthDomainError> {
+            self.generate_token_pair(user_id, email, org_context).await
+        }
+
+        fn validate_access_token(&self, token: &str) -> Result<TokenClaims, AuthDomainError> {
+            if token.starts_with("access_token_") {
+                let user_id = token.replace("access_token_", "");
+                Ok(TokenClaims {
+                    user_id,
+                    email: "test@example.com".to_string(),
+                    org_id: None,
+                    org_role: None,
+                    exp: Utc::now().timestamp() + 900,
+                    iat: Utc::now().timestamp(),
+                    auth_time: Utc::now().timestamp(),
                 })
-                .count() as u32)
+            } else {
+                Err(AuthDomainError::TokenInvalid)
+            }
+        }
+
+        fn decode_refresh_token(&self, token: &str) -> Result<TokenClaims, AuthDomainError> {
+            if self.should_fail_decode {
+                return Err(AuthDomainError::TokenInvalid);
+            }
+            if token.starts_with("refresh_token_") {
+                let user_id = token.replace("refresh_token_", "");
+                Ok(TokenClaims {
+                    user_id,
+                    email: "test@example.com".to_string(),
+                    org_id: None,
+                    org_role: None,
+                    exp: Utc::now().timestamp() + 604800,
+                    iat: Utc::now().timestamp(),
+                    auth_time: Utc::now().timestamp(),
+                })
+            } else {
+                Err(AuthDomainError::TokenInvalid)
+            }
+        }
+
+        fn hash_refresh_token(&self, token: &str) -> String {
+            format!("hash_{}", token)
+        }
+    }
+
+    /// Mock ID Generator
+    struct MockIdGenerator {
+        counter: Mutex<u32>,
+    }
+
+    impl MockIdGenerator {
+        fn new() -> Self {
+            Self {
+                counter: Mutex::new(0),
+            }
+        }
+    }
+
+    impl IdGenerator for MockIdGenerator {
+        fn generate(&self) -> String {
+            let mut counter = self.counter.lock().unwrap();
+            *counter += 1;
+            format!("generated-id-{}", counter)
+        }
+    }
+
+    /// Mock Organization Repository
+    struct MockOrganizationRepository {
+        orgs: Mutex<HashMap<String, Organization>>,
+    }
+
+    impl MockOrganizationRepository {
+        fn new() -> Self {
+            Self {
+                orgs: Mutex::new(HashMap::new()),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl OrganizationRepository for MockOrganizationRepository {
+        async fn find_by_id(
+            &self,
+            id: &OrgId,
+        ) -> Result<Option<Organization>, crate::modules::organizations::domain::OrgDomainError>
+        {
+            let orgs = self.orgs.lock().unwrap();
+            Ok(orgs.get(id.as_str()).cloned())
+        }
+
+        async fn find_by_slug(
+            &self,
+            slug: &str,
+        ) -> Result<Option<Organization>, crate::modules::organizations::domain::OrgDomainError>
+        {
+            let orgs = self.orgs.lock().unwrap();
+            Ok(orgs.values().find(|o| o.slug().as_str() == slug).cloned())
+        }
+
+        async fn save(
+            &self,
+            org: &Organization,
+        ) -> Result<(), crate::modules::organizations::domain::OrgDomainError> {
+            let mut orgs = self.orgs.lock().unwrap();
+            orgs.insert(org.id().as_str().to_string(), org.clone());
+            Ok(())
+        }
+
+        async fn find_all_active(
+            &self,
+        ) -> Result<Vec<Organization>, crate::modules::organizations::domain::OrgDomainError>
+        {
+            let orgs = self.orgs.lock().unwrap();
+            Ok(orgs.values().filter(|o| !o.is_deleted()).cloned().collect())
+        }
+
+        async fn slug_exists(
+            &self,
+            slug: &str,
+        ) -> Result<bool, crate::modules::organizations::domain::OrgDomainError> {
+            let orgs = self.orgs.lock().unwrap();
+            Ok(orgs.values().any(|o| o.slug().as_str() == slug))
         }
+    }
+
+    /// Mock Organization Member Repository
+    struct MockOrganizationMemberRepository {
+        members: Mutex<HashMap<String, OrganizationMember>>,
+    }
+
+    impl MockOrganizationMemberRepository {
+        fn new() -> Self {
+            Self {
+                members: Mutex::new(HashMap::new()),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl OrganizationMemberRepository for MockOrganizationMemberRepository {
+        async fn find_by_id(
+            &self,
+            id: &MemberId,
+        ) -> Result<Option<OrganizationMember>, crate::modules::organizations::domain::OrgDomainError>
+        {
+            let members = self.members.lock().unwrap();
+            Ok(members.get(id.as_str()).cloned())
+        }
+
+        async fn find_by_org_and_user(
+            &self,
+            org_id: &OrgId,
+            user_id: &UserId,
+        ) -> Result<Option<OrganizationMember>, crate::modules::organizations::domain::OrgDomainError>
+        {
+            let members = self.members.lock().unwrap();
+            Ok(members
+                .values()
+                .find(|m| {
+                    m.organization_id().as_str() == org_id.as_str()
+                        && m.user_id().as_str() == user_id.as_str()
+                })
+                .cloned())
+        }
+
+        async fn find_all_by_org(
+            &self,
+            org_id: &OrgId,
+        ) -> Result<Vec<OrganizationMember>, crate::modules::organizations::domain::OrgDomainError>
+        {
+            let members = self.members.lock().unwrap();
+            Ok(members
+                .values()
+                .filter(|m| m.organization_id().as_str() == org_id.as_str())
+                .cloned()
+                .collect())
+        }
+
+        async fn find_page_by_org(
+            &self,
+            org_id: &OrgId,
+            pagination: &crate::shared::Pagination,
+        ) -> Result<Vec<OrganizationMember>, crate::modules::organizations::domain::OrgDomainError>
+        {
+            Ok(self
+                .find_all_by_org(org_id)
+                .await?
+                .into_iter()
+                .skip(pagination.offset as usize)
+                .take(pagination.limit as usize)
+                .collect())
+        }
+
+        async fn find_all_by_user(
+            &self,
+            user_id: &UserId,
+        ) -> Result<Vec<OrganizationMember>, crate::modules::organizations::domain::OrgDomainError>
+        {
+            let members = self.members.lock().unwrap();
+            Ok(members
+                .values()
+                .filter(|m| m.user_id().as_str() == user_id.as_str())
+                .cloned()
+                .collect())
+        }
+
+        async fn find_last_accessed_by_user(
+            &self,
+            user_id: &UserId,
+        ) -> Result<Option<OrganizationMember>, crate::modules::organizations::domain::OrgDomainError>
+        {
+            let members = self.members.lock().unwrap();
+            Ok(members
+                .values()
+                .filter(|m| {
+                    m.user_id().as_str() == user_id.as_str() && m.last_accessed_at().is_some()
+                })
+                .max_by_key(|m| m.last_accessed_at())
+                .cloned())
+        }
+
+        async fn find_personal_org_membership(
+            &self,
+            _user_id: &UserId,
+        ) -> Result<Option<OrganizationMember>, crate::modules::organizations::domain::OrgDomainError>
+        {
+            // For tests, we don't track personal orgs, just return None
+            Ok(None)
+        }
+
+        async fn save(
+            &self,
+            member: &OrganizationMember,
+        ) -> Result<(), crate::modules::organizations::domain::OrgDomainError> {
+            let mut members = self.members.lock().unwrap();
+            members.insert(member.id().as_str().to_string(), member.clone());
+            Ok(())
+        }
+
+        async fn delete(
+            &self,
+            id: &MemberId,
+        ) -> Result<(), crate::modules::organizations::domain::OrgDomainError> {
+            let mut members = self.members.lock().unwrap();
+            members.remove(id.as_str());
+            Ok(())
+        }
+
+        async fn count_owners(
+            &self,
+            org_id: &OrgId,
+        ) -> Result<u32, crate::modules::organizations::domain::OrgDomainError> {
+            let members = self.members.lock().unwrap();
+            Ok(members
+                .values()
+                .filter(|m| {
+                    m.organization_id().as_str() == org_id.as_str() && m.role() == &OrgRole::Owner
+                })
+                .count() as u32)
+        }
+
+        async fn count_owners_for_update(
+            &self,
+            org_id: &OrgId,
+        ) -> Result<u32, crate::modules::organizations::domain::OrgDomainError> {
+            self.count_owners(org_id).await
+        }
+    }
+
+    /// Mock Auth Audit Repository
+    struct MockAuthAuditRepository {
+        events: Mutex<Vec<AuthAuditEvent>>,
+    }
+
+    impl MockAuthAuditRepository {
+        fn new() -> Self {
+            Self {
+                events: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn events(&self) -> Vec<AuthAuditEvent> {
+            self.events.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl AuthAuditRepository for MockAuthAuditRepository {
+        async fn save(&self, event: &AuthAuditEvent) -> Result<(), AuthDomainError> {
+            self.events.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+
+        async fn find(
+            &self,
+            filters: &AuthAuditFilters,
+            limit: i64,
+            offset: i64,
+        ) -> Result<Vec<AuthAuditEvent>, AuthDomainError> {
+            let events = self.events.lock().unwrap();
+            Ok(events
+                .iter()
+                .rev()
+                .filter(|e| e.user_id().is_some_and(|u| filters.user_ids.contains(u)))
+                .filter(|e| filters.event_type.is_none_or(|t| e.event_type() == t))
+                .filter(|e| filters.success.is_none_or(|s| e.success() == s))
+                .skip(offset as usize)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+    }
+
+    /// Mock TOTP Repository
+    #[derive(Default)]
+    struct MockTotpRepository {
+        totps: Mutex<HashMap<String, UserTotp>>,
+        challenges: Mutex<HashMap<String, TotpChallenge>>,
+    }
+
+    impl MockTotpRepository {
+        fn new() -> Self {
+            Self::default()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl UserTotpRepository for MockTotpRepository {
+        async fn find_by_user(
+            &self,
+            user_id: &UserId,
+        ) -> Result<Option<UserTotp>, AuthDomainError> {
+            Ok(self.totps.lock().unwrap().get(user_id.as_str()).cloned())
+        }
+
+        async fn save(&self, totp: &UserTotp) -> Result<(), AuthDomainError> {
+            self.totps
+                .lock()
+                .unwrap()
+                .insert(totp.user_id().as_str().to_string(), totp.clone());
+            Ok(())
+        }
+
+        async fn delete(&self, user_id: &UserId) -> Result<(), AuthDomainError> {
+            self.totps.lock().unwrap().remove(user_id.as_str());
+            self.challenges
+                .lock()
+                .unwrap()
+                .retain(|_, c| c.user_id() != user_id);
+            Ok(())
+        }
+
+        async fn save_challenge(&self, challenge: &TotpChallenge) -> Result<(), AuthDomainError> {
+            self.challenges
+                .lock()
+                .unwrap()
+                .insert(challenge.token_hash().to_string(), challenge.clone());
+            Ok(())
+        }
+
+        async fn find_challenge(
+            &self,
+            token_hash: &str,
+        ) -> Result<Option<TotpChallenge>, AuthDomainError> {
+            Ok(self.challenges.lock().unwrap().get(token_hash).cloned())
+        }
+
+        async fn delete_challenge(&self, token_hash: &str) -> Result<(), AuthDomainError> {
+            self.challenges.lock().unwrap().remove(token_hash);
+            Ok(())
+        }
+    }
+
+    /// Mock Verification Token Repository
+    #[derive(Default)]
+    struct MockVerificationTokenRepository {
+        tokens: Mutex<HashMap<String, VerificationToken>>,
+    }
+
+    #[async_trait::async_trait]
+    impl VerificationTokenRepository for MockVerificationTokenRepository {
+        async fn save(&self, token: &VerificationToken) -> Result<(), AuthDomainError> {
+            self.tokens
+                .lock()
+                .unwrap()
+                .insert(token.token_hash().to_string(), token.clone());
+            Ok(())
+        }
+
+        async fn find_by_hash(
+            &self,
+            token_hash: &str,
+        ) -> Result<Option<VerificationToken>, AuthDomainError> {
+            Ok(self.tokens.lock().unwrap().get(token_hash).cloned())
+        }
+
+        async fn delete_for_user(&self, user_id: &UserId) -> Result<(), AuthDomainError> {
+            self.tokens
+                .lock()
+                .unwrap()
+                .retain(|_, t| t.user_id() != user_id);
+            Ok(())
+        }
+
+        async fn delete_expired(&self) -> Result<u64, AuthDomainError> {
+            let mut tokens = self.tokens.lock().unwrap();
+            let before = tokens.len();
+            tokens.retain(|_, t| !t.is_expired());
+            Ok((before - tokens.len()) as u64)
+        }
+    }
+
+    /// Mock Password Reset Token Repository
+    #[derive(Default)]
+    struct MockPasswordResetTokenRepository {
+        tokens: Mutex<HashMap<String, PasswordResetToken>>,
+    }
+
+    #[async_trait::async_trait]
+    impl PasswordResetTokenRepository for MockPasswordResetTokenRepository {
+        async fn save(&self, token: &PasswordResetToken) -> Result<(), AuthDomainError> {
+            self.tokens
+                .lock()
+                .unwrap()
+                .insert(token.token_hash().to_string(), token.clone());
+            Ok(())
+        }
+
+        async fn find_by_hash(
+            &self,
+            token_hash: &str,
+        ) -> Result<Option<PasswordResetToken>, AuthDomainError> {
+            Ok(self.tokens.lock().unwrap().get(token_hash).cloned())
+        }
+
+        async fn delete_for_user(&self, user_id: &UserId) -> Result<(), AuthDomainError> {
+            self.tokens
+                .lock()
+                .unwrap()
+                .retain(|_, t| t.user_id() != user_id);
+            Ok(())
+        }
+
+        async fn delete_expired(&self) -> Result<u64, AuthDomainError> {
+            let mut tokens = self.tokens.lock().unwrap();
+            let before = tokens.len();
+            tokens.retain(|_, t| !t.is_expired());
+            Ok((before - tokens.len()) as u64)
+        }
+    }
+
+    /// Mock OAuth Identity Repository
+    #[derive(Default)]
+    struct MockOAuthIdentityRepository {
+        identities: Mutex<Vec<OAuthIdentity>>,
+    }
+
+    #[async_trait::async_trait]
+    impl OAuthIdentityRepository for MockOAuthIdentityRepository {
+        async fn find(
+            &self,
+            provider: OAuthProvider,
+            external_id: &str,
+        ) -> Result<Option<OAuthIdentity>, AuthDomainError> {
+            let identities = self.identities.lock().unwrap();
+            Ok(identities
+                .iter()
+                .find(|i| i.provider() == provider && i.external_id() == external_id)
+                .cloned())
+        }
+
+        async fn find_by_user(
+            &self,
+            user_id: &UserId,
+        ) -> Result<Vec<OAuthIdentity>, AuthDomainError> {
+            let identities = self.identities.lock().unwrap();
+            Ok(identities
+                .iter()
+                .filter(|i| i.user_id() == user_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn save(&self, identity: &OAuthIdentity) -> Result<(), AuthDomainError> {
+            self.identities.lock().unwrap().push(identity.clone());
+            Ok(())
+        }
+    }
+
+    /// Mock Personal Access Token Repository
+    #[derive(Default)]
+    struct MockPersonalAccessTokenRepository {
+        tokens: Mutex<Vec<PersonalAccessToken>>,
+    }
+
+    #[async_trait::async_trait]
+    impl PersonalAccessTokenRepository for MockPersonalAccessTokenRepository {
+        async fn save(&self, token: &PersonalAccessToken) -> Result<(), AuthDomainError> {
+            self.tokens.lock().unwrap().push(token.clone());
+            Ok(())
+        }
+
+        async fn find_by_hash(
+            &self,
+            token_hash: &str,
+        ) -> Result<Option<PersonalAccessToken>, AuthDomainError> {
+            let tokens = self.tokens.lock().unwrap();
+            Ok(tokens
+                .iter()
+                .find(|t| t.token_hash() == token_hash)
+                .cloned())
+        }
+
+        async fn find_by_user(
+            &self,
+            user_id: &UserId,
+        ) -> Result<Vec<PersonalAccessToken>, AuthDomainError> {
+            let tokens = self.tokens.lock().unwrap();
+            Ok(tokens
+                .iter()
+                .rev()
+                .filter(|t| t.user_id() == user_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn delete(&self, user_id: &UserId, id: &str) -> Result<bool, AuthDomainError> {
+            let mut tokens = self.tokens.lock().unwrap();
+            let before = tokens.len();
+            tokens.retain(|t| !(t.user_id() == user_id && t.id() == id));
+            Ok(tokens.len() < before)
+        }
+
+        async fn touch(&self, id: &str, used_at: DateTime<Utc>) -> Result<(), AuthDomainError> {
+            let mut tokens = self.tokens.lock().unwrap();
+            if let Some(token) = tokens.iter_mut().find(|t| t.id() == id) {
+                *token = PersonalAccessToken::reconstruct(
+                    token.id().to_string(),
+                    token.user_id().clone(),
+                    token.name().to_string(),
+                    token.token_hash().to_string(),
+                    token.scopes().to_vec(),
+                    token.expires_at(),
+                    Some(used_at),
+                    token.created_at(),
+                );
+            }
+            Ok(())
+        }
+    }
+
+    /// Email sender that keeps every message
+    #[derive(Default)]
+    struct RecordingEmailSender {
+        sent: Mutex<Vec<EmailMessage>>,
+    }
+
+    impl RecordingEmailSender {
+        /// Token from the most recent email
+        fn last_token(&self) -> String {
+            let sent = self.sent.lock().unwrap();
+            let body = &sent.last().expect("no email sent").body;
+            body.split(": ")
+                .nth(1)
+                .unwrap()
+                .lines()
+                .next()
+                .unwrap()
+                .to_string()
+        }
+
+        fn count(&self) -> usize {
+            self.sent.lock().unwrap().len()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl EmailSender for RecordingEmailSender {
+        async fn send(&self, message: &EmailMessage) -> Result<(), AuthDomainError> {
+            self.sent.lock().unwrap().push(message.clone());
+            Ok(())
+        }
+    }
+
+    // ==================== Test Helpers ====================
+
+    fn create_auth_service() -> AuthService<
+        MockUserRepository,
+        MockRefreshTokenRepository,
+        MockPasswordHasher,
+        MockTokenService,
+        MockIdGenerator,
+        MockOrganizationRepository,
+        MockOrganizationMemberRepository,
+        MockAuthAuditRepository,
+        MockTotpRepository,
+    > {
+        AuthService::new(
+            Arc::new(MockUserRepository::new()),
+            Arc::new(MockRefreshTokenRepository::new()),
+            Arc::new(MockPasswordHasher),
+            Arc::new(MockTokenService::new()),
+            Arc::new(MockIdGenerator::new()),
+            Arc::new(MockOrganizationRepository::new()),
+            Arc::new(MockOrganizationMemberRepository::new()),
+            Arc::new(MockAuthAuditRepository::new()),
+            Arc::new(MockTotpRepository::new()),
+            true,
+        )
+    }
+
+    fn create_auth_service_with_user(
+        user: User,
+    ) -> AuthService<
+        MockUserRepository,
+        MockRefreshTokenRepository,
+        MockPasswordHasher,
+        MockTokenService,
+        MockIdGenerator,
+        MockOrganizationRepository,
+        MockOrganizationMemberRepository,
+        MockAuthAuditRepository,
+        MockTotpRepository,
+    > {
+        AuthService::new(
+            Arc::new(MockUserRepository::with_user(user)),
+            Arc::new(MockRefreshTokenRepository::new()),
+            Arc::new(MockPasswordHasher),
+            Arc::new(MockTokenService::new()),
+            Arc::new(MockIdGenerator::new()),
+            Arc::new(MockOrganizationRepository::new()),
+            Arc::new(MockOrganizationMemberRepository::new()),
+            Arc::new(MockAuthAuditRepository::new()),
+            Arc::new(MockTotpRepository::new()),
+            true,
+        )
+    }
+
+    fn signed_in(result: LoginResult) -> AuthResponse {
+        match result {
+            LoginResult::Authenticated(response) => response,
+            LoginResult::TotpRequired(_) => panic!("expected tokens, got a TOTP challenge"),
+        }
+    }
+
+    fn create_test_user(id: &str, email: &str, password: &str) -> User {
+        User::new(
+            UserId::new(id.to_string()),
+            Email::new(email.to_string()).unwrap(),
+            PasswordHash::from_hash(format!("hashed_{}", password)),
+        )
+    }
+
+    // ==================== Registration Tests ====================
+
+    #[tokio::test]
+    async fn test_register_success() {
+        let service = create_auth_service();
+
+        let cmd = RegisterUserCommand {
+            email: "newuser@example.com".to_string(),
+            username: None,
+            password: "SecurePass123!".to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
+        };
+
+        let result = service.register(cmd).await;
+
+        assert!(result.is_ok());
+        let response = result.unwrap();
+        assert_eq!(response.email, "newuser@example.com");
+        assert!(!response.access_token.is_empty());
+        assert!(!response.refresh_token.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_register_duplicate_email() {
+        let existing_user = create_test_user("user-1", "existing@example.com", "password123");
+        let service = create_auth_service_with_user(existing_user);
+
+        let cmd = RegisterUserCommand {
+            email: "existing@example.com".to_string(),
+            username: None,
+            password: "SecurePass123!".to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
+        };
+
+        let result = service.register(cmd).await;
+
+        assert!(matches!(result, Err(AuthDomainError::UserAlreadyExists)));
+    }
+
+    #[tokio::test]
+    async fn test_register_invalid_email() {
+        let service = create_auth_service();
+
+        let cmd = RegisterUserCommand {
+            email: "invalid-email".to_string(),
+            username: None,
+            password: "SecurePass123!".to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
+        };
+
+        let result = service.register(cmd).await;
+
+        assert!(matches!(result, Err(AuthDomainError::InvalidEmail(_))));
+    }
+
+    #[tokio::test]
+    async fn test_register_weak_password() {
+        let service = create_auth_service();
+
+        let cmd = RegisterUserCommand {
+            email: "user@example.com".to_string(),
+            username: None,
+            password: "short".to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
+        };
+
+        let result = service.register(cmd).await;
+
+        assert!(matches!(result, Err(AuthDomainError::WeakPassword(_))));
+    }
+
+    #[tokio::test]
+    async fn test_register_with_username() {
+        let service = create_auth_service();
+
+        let cmd = RegisterUserCommand {
+            email: "newuser@example.com".to_string(),
+            username: Some("NewUser".to_string()),
+            password: "SecurePass123!".to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
+        };
+
+        let response = service.register(cmd).await.unwrap();
+
+        assert_eq!(response.username.as_deref(), Some("newuser"));
+    }
+
+    #[tokio::test]
+    async fn test_register_duplicate_username() {
+        let mut existing_user = create_test_user("user-1", "existing@example.com", "password123");
+        existing_user.update_username(Username::new("taken".to_string()).unwrap());
+        let service = create_auth_service_with_user(existing_user);
+
+        let cmd = RegisterUserCommand {
+            email: "other@example.com".to_string(),
+            username: Some("Taken".to_string()),
+            password: "SecurePass123!".to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
+        };
+
+        let result = service.register(cmd).await;
+
+        assert!(matches!(result, Err(AuthDomainError::UsernameAlreadyTaken)));
+    }
+
+    #[tokio::test]
+    async fn test_register_invalid_username() {
+        let service = create_auth_service();
+
+        let cmd = RegisterUserCommand {
+            email: "user@example.com".to_string(),
+            username: Some("no spaces".to_string()),
+            password: "SecurePass123!".to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
+        };
+
+        let result = service.register(cmd).await;
+
+        assert!(matches!(result, Err(AuthDomainError::InvalidUsername(_))));
+    }
+
+    // ==================== Login Tests ====================
+
+    #[tokio::test]
+    async fn test_login_success() {
+        let user = create_test_user("user-1", "test@example.com", "CorrectPass1!");
+        let service = create_auth_service_with_user(user);
+
+        let cmd = LoginCommand {
+            identifier: "test@example.com".to_string(),
+            password: "CorrectPass1!".to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
+        };
+
+        let result = service.login(cmd).await;
+
+        assert!(result.is_ok());
+        let response = signed_in(result.unwrap());
+        assert_eq!(response.email, "test@example.com");
+        assert_eq!(response.user_id, "user-1");
+    }
+
+    #[tokio::test]
+    async fn test_login_wrong_password() {
+        let user = create_test_user("user-1", "test@example.com", "CorrectPass1!");
+        let service = create_auth_service_with_user(user);
+
+        let cmd = LoginCommand {
+            identifier: "test@example.com".to_string(),
+            password: "WrongPass1!".to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
+        };
+
+        let result = service.login(cmd).await;
+
+        assert!(matches!(result, Err(AuthDomainError::InvalidCredentials)));
+    }
+
+    #[tokio::test]
+    async fn test_login_user_not_found() {
+        let service = create_auth_service();
+
+        let cmd = LoginCommand {
+            identifier: "nonexistent@example.com".to_string(),
+            password: "SomePass1!".to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
+        };
+
+        let result = service.login(cmd).await;
+
+        assert!(matches!(result, Err(AuthDomainError::InvalidCredentials)));
+    }
+
+    #[tokio::test]
+    async fn test_login_invalid_email_format() {
+        let service = create_auth_service();
+
+        let cmd = LoginCommand {
+            identifier: "not-an-email".to_string(),
+            password: "SomePass1!".to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
+        };
+
+        let result = service.login(cmd).await;
+
+        // Malformed identifiers fail like any other bad login
+        assert!(matches!(result, Err(AuthDomainError::InvalidCredentials)));
+
+        let cmd = LoginCommand {
+            identifier: "not-an-email@".to_string(),
+            password: "SomePass1!".to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
+        };
+        let result = service.login(cmd).await;
+        assert!(matches!(result, Err(AuthDomainError::InvalidCredentials)));
+    }
+
+    #[tokio::test]
+    async fn test_login_with_username_or_email() {
+        let mut user = create_test_user("user-1", "test@example.com", "CorrectPass1!");
+        user.update_username(Username::new("tester".to_string()).unwrap());
+        let service = create_auth_service_with_user(user);
+
+        for identifier in ["Tester", "test@example.com"] {
+            let cmd = LoginCommand {
+                identifier: identifier.to_string(),
+                password: "CorrectPass1!".to_string(),
+                device_fingerprint: "test-fingerprint".to_string(),
+                client: ClientInfo::default(),
+            };
+
+            let response = signed_in(service.login(cmd).await.unwrap());
+
+            assert_eq!(response.user_id, "user-1");
+            assert_eq!(response.username.as_deref(), Some("tester"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_login_unknown_username() {
+        let service = create_auth_service();
+
+        let cmd = LoginCommand {
+            identifier: "nobody".to_string(),
+            password: "SomePass1!".to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
+        };
+
+        let result = service.login(cmd).await;
+
+        assert!(matches!(result, Err(AuthDomainError::InvalidCredentials)));
+    }
+
+    // ==================== Default Organization Tests ====================
+
+    /// Register a user and add them to a second, non-personal org
+    async fn register_with_team_org(
+        service: &AuthService<
+            MockUserRepository,
+            MockRefreshTokenRepository,
+            MockPasswordHasher,
+            MockTokenService,
+            MockIdGenerator,
+            MockOrganizationRepository,
+            MockOrganizationMemberRepository,
+            MockAuthAuditRepository,
+            MockTotpRepository,
+        >,
+    ) -> (AuthResponse, MemberId) {
+        let registered = service
+            .register(RegisterUserCommand {
+                email: "multi@example.com".to_string(),
+                username: None,
+                password: "SecurePass123!".to_string(),
+                device_fingerprint: "test-fingerprint".to_string(),
+                client: ClientInfo::default(),
+            })
+            .await
+            .unwrap();
+
+        let team_id = OrgId::new("team-org".to_string());
+        let name = OrgName::new("Team".to_string()).unwrap();
+        let slug = OrgSlug::generate(&name, "abcd");
+        service
+            .org_repo
+            .save(&Organization::new(team_id.clone(), name, slug))
+            .await
+            .unwrap();
+
+        let member_id = MemberId::new("team-membership".to_string());
+        service
+            .member_repo
+            .save(&OrganizationMember::new(
+                member_id.clone(),
+                team_id,
+                UserId::new(registered.user_id.clone()),
+                OrgRole::Member,
+            ))
+            .await
+            .unwrap();
+
+        (registered, member_id)
+    }
+
+    fn login_command(identifier: &str) -> LoginCommand {
+        LoginCommand {
+            identifier: identifier.to_string(),
+            password: "SecurePass123!".to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_login_upgrades_weaker_password_hash() {
+        let user = User::new(
+            UserId::new("user-1".to_string()),
+            Email::new("test@example.com".to_string()).unwrap(),
+            PasswordHash::from_hash("legacy_SecurePass123!".to_string()),
+        );
+        let service = create_auth_service_with_user(user);
+
+        signed_in(
+            service
+                .login(login_command("test@example.com"))
+                .await
+                .unwrap(),
+        );
+
+        let user = service.get_current_user("user-1").await.unwrap();
+        assert_eq!(
+            user.password_hash().unwrap().as_str(),
+            "hashed_SecurePass123!"
+        );
+        // The upgraded hash still signs in
+        signed_in(
+            service
+                .login(login_command("test@example.com"))
+                .await
+                .unwrap(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_login_uses_configured_default_org() {
+        let service = create_auth_service();
+        let (registered, _) = register_with_team_org(&service).await;
+
+        let settings = service
+            .update_settings(UpdateSettingsCommand {
+                user_id: registered.user_id.clone(),
+                allow_invites: None,
+                default_org_id: Some("team-org".to_string()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(settings.default_org_id.as_deref(), Some("team-org"));
+
+        let response = signed_in(
+            service
+                .login(login_command("multi@example.com"))
+                .await
+                .unwrap(),
+        );
+
+        assert_eq!(response.default_org_id.as_deref(), Some("team-org"));
+    }
+
+    #[tokio::test]
+    async fn test_login_falls_back_when_default_org_membership_lost() {
+        let service = create_auth_service();
+        let (registered, member_id) = register_with_team_org(&service).await;
+        service
+            .update_settings(UpdateSettingsCommand {
+                user_id: registered.user_id.clone(),
+                allow_invites: None,
+                default_org_id: Some("team-org".to_string()),
+            })
+            .await
+            .unwrap();
+
+        service.member_repo.delete(&member_id).await.unwrap();
+
+        let response = signed_in(
+            service
+                .login(login_command("multi@example.com"))
+                .await
+                .unwrap(),
+        );
+
+        // Personal org created at registration
+        assert_eq!(response.default_org_id, registered.default_org_id);
+        let settings = service.get_settings(&registered.user_id).await.unwrap();
+        assert_eq!(settings.default_org_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_login_rejects_password_when_org_requires_sso() {
+        let service = create_auth_service();
+        let (registered, _) = register_with_team_org(&service).await;
+
+        let team_id = OrgId::new("team-org".to_string());
+        let mut team = service
+            .org_repo
+            .find_by_id(&team_id)
+            .await
+            .unwrap()
+            .unwrap();
+        team.set_auth_methods(AuthMethods::Oidc).unwrap();
+        service.org_repo.save(&team).await.unwrap();
+
+        let result = service.login(login_command("multi@example.com")).await;
+        assert!(matches!(result, Err(AuthDomainError::SsoRequired)));
+
+        let user_id = UserId::new(registered.user_id);
+        assert!(
+            service
+                .ensure_sign_in_allowed(&user_id, AuthMethods::Oidc)
+                .await
+                .is_ok()
+        );
+    }
 
-        async fn count_owners_for_update(
-            &self,
-            org_id: &OrgId,
-        ) -> Result<u32, crate::modules::organizations::domain::OrgDomainError> {
-            self.count_owners(org_id).await
-        }
+    #[tokio::test]
+    async fn test_login_wrong_password_does_not_reveal_sso_requirement() {
+        let service = create_auth_service();
+        register_with_team_org(&service).await;
+
+        let team_id = OrgId::new("team-org".to_string());
+        let mut team = service
+            .org_repo
+            .find_by_id(&team_id)
+            .await
+            .unwrap()
+            .unwrap();
+        team.set_auth_methods(AuthMethods::Oidc).unwrap();
+        service.org_repo.save(&team).await.unwrap();
+
+        let mut cmd = login_command("multi@example.com");
+        cmd.password = "WrongPass1!".to_string();
+        let result = service.login(cmd).await;
+
+        assert!(matches!(result, Err(AuthDomainError::InvalidCredentials)));
     }
 
-    // ==================== Test Helpers ====================
+    #[tokio::test]
+    async fn test_update_settings_rejects_default_org_without_membership() {
+        let service = create_auth_service();
+        let (registered, _) = register_with_team_org(&service).await;
 
-    fn create_auth_service() -> AuthService<
-        MockUserRepository,
-        MockRefreshTokenRepository,
-        MockPasswordHasher,
-        MockTokenService,
-        MockIdGenerator,
-        MockOrganizationRepository,
-        MockOrganizationMemberRepository,
-    > {
-        AuthService::new(
-            Arc::new(MockUserRepository::new()),
-            Arc::new(MockRefreshTokenRepository::new()),
-            Arc::new(MockPasswordHasher),
-            Arc::new(MockTokenService::new()),
-            Arc::new(MockIdGenerator::new()),
-            Arc::new(MockOrganizationRepository::new()),
-            Arc::new(MockOrganizationMemberRepository::new()),
-        )
+        let result = service
+            .update_settings(UpdateSettingsCommand {
+                user_id: registered.user_id,
+                allow_invites: None,
+                default_org_id: Some("other-org".to_string()),
+            })
+            .await;
+
+        assert!(matches!(result, Err(AuthDomainError::InvalidDefaultOrg)));
     }
 
-    fn create_auth_service_with_user(
-        user: User,
-    ) -> AuthService<
-        MockUserRepository,
-        MockRefreshTokenRepository,
-        MockPasswordHasher,
-        MockTokenService,
-        MockIdGenerator,
-        MockOrganizationRepository,
-        MockOrganizationMemberRepository,
-    > {
-        AuthService::new(
+    // ==================== Logout Tests ====================
+
+    #[tokio::test]
+    async fn test_logout_single_token() {
+        let user = create_test_user("user-1", "test@example.com", "password");
+        let token = RefreshToken::new(
+            TokenId::new("token-1".to_string()),
+            UserId::new("user-1".to_string()),
+            "hash_refresh_token_user-1".to_string(),
+            "test-fingerprint".to_string(),
+            Utc::now() + Duration::days(7),
+        );
+
+        let service = AuthService::new(
             Arc::new(MockUserRepository::with_user(user)),
-            Arc::new(MockRefreshTokenRepository::new()),
+            Arc::new(MockRefreshTokenRepository::with_token(token)),
             Arc::new(MockPasswordHasher),
             Arc::new(MockTokenService::new()),
             Arc::new(MockIdGenerator::new()),
             Arc::new(MockOrganizationRepository::new()),
             Arc::new(MockOrganizationMemberRepository::new()),
-        )
+            Arc::new(MockAuthAuditRepository::new()),
+            Arc::new(MockTotpRepository::new()),
+            true,
+        );
+
+        let cmd = LogoutCommand {
+            user_id: "user-1".to_string(),
+            refresh_token: Some("refresh_token_user-1".to_string()),
+            client: ClientInfo::default(),
+        };
+
+        let result = service.logout(cmd).await;
+
+        assert!(result.is_ok());
     }
 
-    fn create_test_user(id: &str, email: &str, password: &str) -> User {
-        User::new(
-            UserId::new(id.to_string()),
-            Email::new(email.to_string()).unwrap(),
-            PasswordHash::from_hash(format!("hashed_{}", password)),
-        )
+    #[tokio::test]
+    async fn test_logout_all_tokens() {
+        let service = create_auth_service();
+
+        let cmd = LogoutCommand {
+            user_id: "user-1".to_string(),
+            refresh_token: None,
+            client: ClientInfo::default(),
+        };
+
+        let result = service.logout(cmd).await;
+
+        assert!(result.is_ok());
     }
 
-    // ==================== Registration Tests ====================
+    // ==================== Refresh Token Tests ====================
 
     #[tokio::test]
-    async fn test_register_success() {
-        let service = create_auth_service();
+    async fn test_refresh_success() {
+        let user_id = UserId::new("user-1".to_string());
+        let token = RefreshToken::new(
+            TokenId::new("token-1".to_string()),
+            user_id.clone(),
+            "hash_refresh_token_user-1".to_string(),
+            "test-fingerprint".to_string(),
+            Utc::now() + Duration::days(7),
+        );
 
-        let cmd = RegisterUserCommand {
-            email: "newuser@example.com".to_string(),
-            password: "SecurePass123!".to_string(),
+        let user = create_test_user("user-1", "test@example.com", "password");
+
+        let service = AuthService::new(
+            Arc::new(MockUserRepository::with_user(user)),
+            Arc::new(MockRefreshTokenRepository::with_token(token)),
+            Arc::new(MockPasswordHasher),
+            Arc::new(MockTokenService::new()),
+            Arc::new(MockIdGenerator::new()),
+            Arc::new(MockOrganizationRepository::new()),
+            Arc::new(MockOrganizationMemberRepository::new()),
+            Arc::new(MockAuthAuditRepository::new()),
+            Arc::new(MockTotpRepository::new()),
+            true,
+        );
+
+        let cmd = RefreshTokenCommand {
+            refresh_token: "refresh_token_user-1".to_string(),
             device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
         };
 
-        let result = service.register(cmd).await;
+        let result = service.refresh(cmd).await;
 
         assert!(result.is_ok());
         let response = result.unwrap();
-        assert_eq!(response.email, "newuser@example.com");
-        assert!(!response.access_token.is_empty());
-        assert!(!response.refresh_token.is_empty());
+        assert_eq!(response.user_id, "user-1");
     }
 
     #[tokio::test]
-    async fn test_register_duplicate_email() {
-        let existing_user = create_test_user("user-1", "existing@example.com", "password123");
-        let service = create_auth_service_with_user(existing_user);
+    async fn test_refresh_invalid_token() {
+        let service = AuthService::new(
+            Arc::new(MockUserRepository::new()),
+            Arc::new(MockRefreshTokenRepository::new()),
+            Arc::new(MockPasswordHasher),
+            Arc::new(MockTokenService::failing_decode()),
+            Arc::new(MockIdGenerator::new()),
+            Arc::new(MockOrganizationRepository::new()),
+            Arc::new(MockOrganizationMemberRepository::new()),
+            Arc::new(MockAuthAuditRepository::new()),
+            Arc::new(MockTotpRepository::new()),
+            true,
+        );
 
-        let cmd = RegisterUserCommand {
-            email: "existing@example.com".to_string(),
-            password: "SecurePass123!".to_string(),
+        let cmd = RefreshTokenCommand {
+            refresh_token: "invalid_token".to_string(),
             device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
         };
 
-        let result = service.register(cmd).await;
+        let result = service.refresh(cmd).await;
 
-        assert!(matches!(result, Err(AuthDomainError::UserAlreadyExists)));
+        assert!(matches!(result, Err(AuthDomainError::TokenInvalid)));
     }
 
     #[tokio::test]
-    async fn test_register_invalid_email() {
+    async fn test_refresh_token_not_in_database() {
         let service = create_auth_service();
 
-        let cmd = RegisterUserCommand {
-            email: "invalid-email".to_string(),
-            password: "SecurePass123!".to_string(),
+        let cmd = RefreshTokenCommand {
+            refresh_token: "refresh_token_user-1".to_string(),
             device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
         };
 
-        let result = service.register(cmd).await;
+        let result = service.refresh(cmd).await;
 
-        assert!(matches!(result, Err(AuthDomainError::InvalidEmail(_))));
+        assert!(matches!(result, Err(AuthDomainError::TokenInvalid)));
     }
 
     #[tokio::test]
-    async fn test_register_weak_password() {
-        let service = create_auth_service();
+    async fn test_refresh_revoked_token() {
+        let user_id = UserId::new("user-1".to_string());
+        let mut token = RefreshToken::new(
+            TokenId::new("token-1".to_string()),
+            user_id.clone(),
+            "hash_refresh_token_user-1".to_string(),
+            "test-fingerprint".to_string(),
+            Utc::now() + Duration::days(7),
+        );
+        token.revoke();
 
-        let cmd = RegisterUserCommand {
-            email: "user@example.com".to_string(),
-            password: "short".to_string(),
+        let service = AuthService::new(
+            Arc::new(MockUserRepository::new()),
+            Arc::new(MockRefreshTokenRepository::with_token(token)),
+            Arc::new(MockPasswordHasher),
+            Arc::new(MockTokenService::new()),
+            Arc::new(MockIdGenerator::new()),
+            Arc::new(MockOrganizationRepository::new()),
+            Arc::new(MockOrganizationMemberRepository::new()),
+            Arc::new(MockAuthAuditRepository::new()),
+            Arc::new(MockTotpRepository::new()),
+            true,
+        );
+
+        let cmd = RefreshTokenCommand {
+            refresh_token: "refresh_token_user-1".to_string(),
             device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
         };
 
-        let result = service.register(cmd).await;
+        let result = service.refresh(cmd).await;
 
-        assert!(matches!(result, Err(AuthDomainError::WeakPassword(_))));
+        assert!(matches!(result, Err(AuthDomainError::TokenRevoked)));
     }
 
-    // ==================== Login Tests ====================
+    // ==================== Audit Tests ====================
+
+    fn client() -> ClientInfo {
+        ClientInfo {
+            ip_address: Some("203.0.113.7".to_string()),
+            user_agent: Some("test-agent/1.0".to_string()),
+        }
+    }
 
     #[tokio::test]
-    async fn test_login_success() {
+    async fn test_failed_login_is_audited() {
         let user = create_test_user("user-1", "test@example.com", "CorrectPass1!");
         let service = create_auth_service_with_user(user);
 
-        let cmd = LoginCommand {
-            email: "test@example.com".to_string(),
-            password: "CorrectPass1!".to_string(),
-            device_fingerprint: "test-fingerprint".to_string(),
-        };
+        let result = service
+            .login(LoginCommand {
+                identifier: "test@example.com".to_string(),
+                password: "WrongPass1!".to_string(),
+                device_fingerprint: "test-fingerprint".to_string(),
+                client: client(),
+            })
+            .await;
+        assert!(result.is_err());
+
+        let events = service.audit_repo.events();
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.event_type(), AuthAuditEventType::Login);
+        assert!(!event.success());
+        assert_eq!(event.user_id().map(|u| u.as_str()), Some("user-1"));
+        assert_eq!(event.identifier(), Some("test@example.com"));
+        assert_eq!(event.ip_address(), Some("203.0.113.7"));
+        assert_eq!(event.user_agent(), Some("test-agent/1.0"));
+        assert_eq!(event.failure_reason(), Some("Invalid credentials"));
+        assert!(!format!("{:?}", event).contains("WrongPass1!"));
+    }
 
-        let result = service.login(cmd).await;
+    #[tokio::test]
+    async fn test_successful_login_is_audited() {
+        let user = create_test_user("user-1", "test@example.com", "CorrectPass1!");
+        let service = create_auth_service_with_user(user);
 
-        assert!(result.is_ok());
-        let response = result.unwrap();
-        assert_eq!(response.email, "test@example.com");
-        assert_eq!(response.user_id, "user-1");
+        let response = signed_in(
+            service
+                .login(LoginCommand {
+                    identifier: "test@example.com".to_string(),
+                    password: "CorrectPass1!".to_string(),
+                    device_fingerprint: "test-fingerprint".to_string(),
+                    client: client(),
+                })
+                .await
+                .unwrap(),
+        );
+
+        let events = service.audit_repo.events();
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.event_type(), AuthAuditEventType::Login);
+        assert!(event.success());
+        assert_eq!(event.user_id().map(|u| u.as_str()), Some("user-1"));
+        assert_eq!(event.ip_address(), Some("203.0.113.7"));
+        assert!(event.failure_reason().is_none());
+
+        let recorded = format!("{:?}", event);
+        assert!(!recorded.contains("CorrectPass1!"));
+        assert!(!recorded.contains(&response.refresh_token));
+        assert!(!recorded.contains(&response.access_token));
     }
 
     #[tokio::test]
-    async fn test_login_wrong_password() {
+    async fn test_audit_disabled_records_nothing() {
         let user = create_test_user("user-1", "test@example.com", "CorrectPass1!");
-        let service = create_auth_service_with_user(user);
+        let service = AuthService::new(
+            Arc::new(MockUserRepository::with_user(user)),
+            Arc::new(MockRefreshTokenRepository::new()),
+            Arc::new(MockPasswordHasher),
+            Arc::new(MockTokenService::new()),
+            Arc::new(MockIdGenerator::new()),
+            Arc::new(MockOrganizationRepository::new()),
+            Arc::new(MockOrganizationMemberRepository::new()),
+            Arc::new(MockAuthAuditRepository::new()),
+            Arc::new(MockTotpRepository::new()),
+            false,
+        );
 
-        let cmd = LoginCommand {
-            email: "test@example.com".to_string(),
-            password: "WrongPass1!".to_string(),
-            device_fingerprint: "test-fingerprint".to_string(),
+        service.login(login_command("test@example.com")).await.ok();
+
+        assert!(service.audit_repo.events().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_audit_events_requires_org_admin() {
+        let service = create_auth_service();
+        let (registered, _) = register_with_team_org(&service).await;
+        service
+            .login(login_command("multi@example.com"))
+            .await
+            .unwrap();
+
+        let list = |org_id: &str, success: Option<bool>| ListAuditEventsCommand {
+            requesting_user_id: registered.user_id.clone(),
+            org_id: Some(org_id.to_string()),
+            user_id: None,
+            event_type: None,
+            success,
+            start_time: None,
+            end_time: None,
+            limit: Some(10_000),
+            offset: Some(-5),
         };
 
-        let result = service.login(cmd).await;
+        // Owner of their personal org
+        let personal_org = registered.default_org_id.clone().unwrap();
+        let page = service
+            .list_audit_events(list(&personal_org, None))
+            .await
+            .unwrap();
+        let types: Vec<_> = page.events.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(types, vec!["login", "register"]);
+        // Oversized limits are clamped to the configured maximum
+        assert_eq!(page.limit, 1000);
+
+        let failures = service
+            .list_audit_events(list(&personal_org, Some(false)))
+            .await
+            .unwrap();
+        assert!(failures.events.is_empty());
+
+        // Plain member of the team org
+        let result = service.list_audit_events(list("team-org", None)).await;
+        assert!(matches!(
+            result,
+            Err(AuthDomainError::InsufficientPermissions)
+        ));
+    }
+
+    // ==================== Email Verification Tests ====================
+
+    fn register_command(email: &str) -> RegisterUserCommand {
+        RegisterUserCommand {
+            email: email.to_string(),
+            username: None,
+            password: "SecurePass123!".to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
+        }
+    }
+
+    fn verify_email_command(token: &str) -> VerifyEmailCommand {
+        VerifyEmailCommand {
+            token: token.to_string(),
+            client: ClientInfo::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_sends_single_use_verification_token() {
+        let verification_repo = Arc::new(MockVerificationTokenRepository::default());
+        let sender = Arc::new(RecordingEmailSender::default());
+        let service = create_auth_service()
+            .with_email_verification(verification_repo.clone(), sender.clone());
+
+        let registered = service
+            .register(register_command("new@example.com"))
+            .await
+            .unwrap();
+        assert!(
+            !service
+                .is_email_verified(&registered.user_id)
+                .await
+                .unwrap()
+        );
+        assert_eq!(sender.sent.lock().unwrap()[0].to, "new@example.com");
 
-        assert!(matches!(result, Err(AuthDomainError::InvalidCredentials)));
+        // A resend replaces the first token
+        let first = sender.last_token();
+        service
+            .resend_verification(ResendVerificationCommand {
+                user_id: registered.user_id.clone(),
+            })
+            .await
+            .unwrap();
+        let second = sender.last_token();
+        assert_ne!(first, second);
+        assert!(matches!(
+            service.verify_email(verify_email_command(&first)).await,
+            Err(AuthDomainError::TokenInvalid)
+        ));
+
+        service
+            .verify_email(verify_email_command(&second))
+            .await
+            .unwrap();
+        assert!(
+            service
+                .is_email_verified(&registered.user_id)
+                .await
+                .unwrap()
+        );
+        assert!(matches!(
+            service.verify_email(verify_email_command(&second)).await,
+            Err(AuthDomainError::TokenInvalid)
+        ));
+        assert!(matches!(
+            service
+                .resend_verification(ResendVerificationCommand {
+                    user_id: registered.user_id.clone(),
+                })
+                .await,
+            Err(AuthDomainError::EmailAlreadyVerified)
+        ));
+
+        // Changing address needs a new verification
+        service
+            .change_email(ChangeEmailCommand {
+                user_id: registered.user_id.clone(),
+                new_email: "moved@example.com".to_string(),
+                current_password: "SecurePass123!".to_string(),
+                client: ClientInfo::default(),
+            })
+            .await
+            .unwrap();
+        assert!(
+            !service
+                .is_email_verified(&registered.user_id)
+                .await
+                .unwrap()
+        );
+        assert_eq!(sender.count(), 3);
+        assert_eq!(sender.sent.lock().unwrap()[2].to, "moved@example.com");
     }
 
     #[tokio::test]
-    async fn test_login_user_not_found() {
-        let service = create_auth_service();
+    async fn test_expired_verification_token_is_rejected_and_cleaned_up() {
+        let user = create_test_user("user-1", "test@example.com", "SecurePass123!");
+        let verification_repo = Arc::new(MockVerificationTokenRepository::default());
+        let service = create_auth_service_with_user(user).with_email_verification(
+            verification_repo.clone(),
+            Arc::new(RecordingEmailSender::default()),
+        );
+        verification_repo
+            .save(&VerificationToken::new(
+                hash_token("stale-token"),
+                UserId::new("user-1".to_string()),
+                Utc::now() - Duration::minutes(1),
+            ))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            service
+                .verify_email(verify_email_command("stale-token"))
+                .await,
+            Err(AuthDomainError::TokenExpired)
+        ));
+        assert!(!service.is_email_verified("user-1").await.unwrap());
+        assert_eq!(verification_repo.delete_expired().await.unwrap(), 1);
+    }
 
-        let cmd = LoginCommand {
-            email: "nonexistent@example.com".to_string(),
-            password: "SomePass1!".to_string(),
-            device_fingerprint: "test-fingerprint".to_string(),
-        };
+    // ==================== Password Reset Tests ====================
 
-        let result = service.login(cmd).await;
+    fn request_reset_command(email: &str) -> RequestPasswordResetCommand {
+        RequestPasswordResetCommand {
+            email: email.to_string(),
+            client: ClientInfo::default(),
+        }
+    }
 
-        assert!(matches!(result, Err(AuthDomainError::InvalidCredentials)));
+    fn reset_password_command(token: &str, new_password: &str) -> ResetPasswordCommand {
+        ResetPasswordCommand {
+            token: token.to_string(),
+            new_password: new_password.to_string(),
+            client: ClientInfo::default(),
+        }
     }
 
     #[tokio::test]
-    async fn test_login_invalid_email_format() {
-        let service = create_auth_service();
-
-        let cmd = LoginCommand {
-            email: "not-an-email".to_string(),
-            password: "SomePass1!".to_string(),
-            device_fingerprint: "test-fingerprint".to_string(),
-        };
+    async fn test_password_reset_request_does_not_reveal_unknown_emails() {
+        let user = create_test_user("user-1", "test@example.com", "SecurePass123!");
+        let sender = Arc::new(RecordingEmailSender::default());
+        let service = create_auth_service_with_user(user).with_password_reset(
+            Arc::new(MockPasswordResetTokenRepository::default()),
+            sender.clone(),
+        );
 
-        let result = service.login(cmd).await;
+        service
+            .request_password_reset(request_reset_command("nobody@example.com"))
+            .await
+            .unwrap();
+        service
+            .request_password_reset(request_reset_command("not-an-email"))
+            .await
+            .unwrap();
+        assert_eq!(sender.count(), 0);
 
-        assert!(matches!(result, Err(AuthDomainError::InvalidEmail(_))));
+        service
+            .request_password_reset(request_reset_command("test@example.com"))
+            .await
+            .unwrap();
+        assert_eq!(sender.count(), 1);
+        assert_eq!(sender.sent.lock().unwrap()[0].to, "test@example.com");
     }
 
-    // ==================== Logout Tests ====================
-
     #[tokio::test]
-    async fn test_logout_single_token() {
-        let user = create_test_user("user-1", "test@example.com", "password");
-        let token = RefreshToken::new(
+    async fn test_password_reset_sets_password_and_revokes_sessions() {
+        let user = create_test_user("user-1", "test@example.com", "OldPass123!");
+        let session = RefreshToken::new(
             TokenId::new("token-1".to_string()),
             UserId::new("user-1".to_string()),
             "hash_refresh_token_user-1".to_string(),
             "test-fingerprint".to_string(),
             Utc::now() + Duration::days(7),
         );
-
+        let sender = Arc::new(RecordingEmailSender::default());
         let service = AuthService::new(
             Arc::new(MockUserRepository::with_user(user)),
-            Arc::new(MockRefreshTokenRepository::with_token(token)),
+            Arc::new(MockRefreshTokenRepository::with_token(session)),
             Arc::new(MockPasswordHasher),
             Arc::new(MockTokenService::new()),
             Arc::new(MockIdGenerator::new()),
             Arc::new(MockOrganizationRepository::new()),
             Arc::new(MockOrganizationMemberRepository::new()),
+            Arc::new(MockAuthAuditRepository::new()),
+            Arc::new(MockTotpRepository::new()),
+            true,
+        )
+        .with_password_reset(
+            Arc::new(MockPasswordResetTokenRepository::default()),
+            sender.clone(),
         );
 
-        let cmd = LogoutCommand {
-            user_id: "user-1".to_string(),
-            refresh_token: Some("refresh_token_user-1".to_string()),
+        service
+            .request_password_reset(request_reset_command("test@example.com"))
+            .await
+            .unwrap();
+        let token = sender.last_token();
+
+        // Tokens are case-sensitive
+        let swapped: String = token
+            .chars()
+            .map(|c| {
+                if c.is_ascii_lowercase() {
+                    c.to_ascii_uppercase()
+                } else {
+                    c.to_ascii_lowercase()
+                }
+            })
+            .collect();
+        assert!(matches!(
+            service
+                .reset_password(reset_password_command(&swapped, "NewSecurePass456!"))
+                .await,
+            Err(AuthDomainError::TokenInvalid)
+        ));
+        assert!(matches!(
+            service
+                .reset_password(reset_password_command(&token, "weak"))
+                .await,
+            Err(AuthDomainError::WeakPassword(_))
+        ));
+        service
+            .reset_password(reset_password_command(&token, "NewSecurePass456!"))
+            .await
+            .unwrap();
+
+        // Sessions from before the reset are signed out
+        let refreshed = service
+            .refresh(RefreshTokenCommand {
+                refresh_token: "refresh_token_user-1".to_string(),
+                device_fingerprint: "test-fingerprint".to_string(),
+                client: ClientInfo::default(),
+            })
+            .await;
+        assert!(matches!(refreshed, Err(AuthDomainError::TokenRevoked)));
+
+        let login = |password: &str| LoginCommand {
+            identifier: "test@example.com".to_string(),
+            password: password.to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
         };
+        assert!(matches!(
+            service.login(login("OldPass123!")).await,
+            Err(AuthDomainError::InvalidCredentials)
+        ));
+        signed_in(service.login(login("NewSecurePass456!")).await.unwrap());
+
+        // The token works once
+        assert!(matches!(
+            service
+                .reset_password(reset_password_command(&token, "OtherPass789!"))
+                .await,
+            Err(AuthDomainError::TokenInvalid)
+        ));
+    }
 
-        let result = service.logout(cmd).await;
+    // ==================== Session Tests ====================
 
-        assert!(result.is_ok());
+    fn session(id: &str, user_id: &str, token_hash: &str) -> RefreshToken {
+        RefreshToken::new(
+            TokenId::new(id.to_string()),
+            UserId::new(user_id.to_string()),
+            token_hash.to_string(),
+            "test-fingerprint".to_string(),
+            Utc::now() + Duration::days(7),
+        )
     }
 
     #[tokio::test]
-    async fn test_logout_all_tokens() {
-        let service = create_auth_service();
-
-        let cmd = LogoutCommand {
-            user_id: "user-1".to_string(),
-            refresh_token: None,
+    async fn test_sessions_show_client_and_survive_refresh() {
+        let user = create_test_user("user-1", "test@example.com", "CorrectPass1!");
+        let service = create_auth_service_with_user(user);
+        let laptop = ClientInfo {
+            ip_address: Some("198.51.100.4".to_string()),
+            user_agent: Some(format!("Mozilla/5.0 {}", "x".repeat(300))),
         };
 
-        let result = service.logout(cmd).await;
-
-        assert!(result.is_ok());
+        signed_in(
+            service
+                .login(LoginCommand {
+                    identifier: "test@example.com".to_string(),
+                    password: "CorrectPass1!".to_string(),
+                    device_fingerprint: "test-fingerprint".to_string(),
+                    client: laptop.clone(),
+                })
+                .await
+                .unwrap(),
+        );
+        let signed_in_at = service.list_sessions("user-1").await.unwrap()[0].created_at;
+
+        service
+            .refresh(RefreshTokenCommand {
+                refresh_token: "refresh_token_user-1".to_string(),
+                device_fingerprint: "test-fingerprint".to_string(),
+                client: ClientInfo {
+                    ip_address: Some("198.51.100.9".to_string()),
+                    ..laptop
+                },
+            })
+            .await
+            .unwrap();
+
+        let sessions = service.list_sessions("user-1").await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        // Still the same sign-in, last used from the refreshing client
+        assert_eq!(sessions[0].created_at, signed_in_at);
+        assert!(sessions[0].last_used_at >= signed_in_at);
+        assert_eq!(sessions[0].ip_address.as_deref(), Some("198.51.100.9"));
+        assert_eq!(
+            sessions[0].user_agent.as_ref().unwrap().chars().count(),
+            SESSION_USER_AGENT_MAX_CHARS
+        );
     }
 
-    // ==================== Refresh Token Tests ====================
-
     #[tokio::test]
-    async fn test_refresh_success() {
-        let user_id = UserId::new("user-1".to_string());
-        let token = RefreshToken::new(
-            TokenId::new("token-1".to_string()),
-            user_id.clone(),
-            "hash_refresh_token_user-1".to_string(),
-            "test-fingerprint".to_string(),
-            Utc::now() + Duration::days(7),
-        );
-
-        let user = create_test_user("user-1", "test@example.com", "password");
-
+    async fn test_revoke_sessions() {
+        let token_repo = MockRefreshTokenRepository::new();
+        for token in [
+            session("phone", "user-1", "hash_refresh_token_user-1"),
+            session("laptop", "user-1", "hash_laptop"),
+            session("tablet", "user-1", "hash_tablet"),
+            session("other", "user-2", "hash_other"),
+        ] {
+            token_repo.save(&token).await.unwrap();
+        }
         let service = AuthService::new(
-            Arc::new(MockUserRepository::with_user(user)),
-            Arc::new(MockRefreshTokenRepository::with_token(token)),
+            Arc::new(MockUserRepository::new()),
+            Arc::new(token_repo),
             Arc::new(MockPasswordHasher),
             Arc::new(MockTokenService::new()),
             Arc::new(MockIdGenerator::new()),
             Arc::new(MockOrganizationRepository::new()),
             Arc::new(MockOrganizationMemberRepository::new()),
+            Arc::new(MockAuthAuditRepository::new()),
+            Arc::new(MockTotpRepository::new()),
+            true,
         );
-
-        let cmd = RefreshTokenCommand {
-            refresh_token: "refresh_token_user-1".to_string(),
-            device_fingerprint: "test-fingerprint".to_string(),
+        let revoke = |session_id: &str| RevokeSessionCommand {
+            user_id: "user-1".to_string(),
+            session_id: session_id.to_string(),
+            client: ClientInfo::default(),
         };
 
-        let result = service.refresh(cmd).await;
+        service.revoke_session(revoke("tablet")).await.unwrap();
+        // Another user's session can't be revoked, nor an already revoked one
+        assert!(matches!(
+            service.revoke_session(revoke("other")).await,
+            Err(AuthDomainError::SessionNotFound)
+        ));
+        assert!(matches!(
+            service.revoke_session(revoke("tablet")).await,
+            Err(AuthDomainError::SessionNotFound)
+        ));
+
+        let revoked = service
+            .revoke_other_sessions(RevokeOtherSessionsCommand {
+                user_id: "user-1".to_string(),
+                refresh_token: "refresh_token_user-1".to_string(),
+                client: ClientInfo::default(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(revoked, 1);
 
-        assert!(result.is_ok());
-        let response = result.unwrap();
-        assert_eq!(response.user_id, "user-1");
+        let remaining: Vec<String> = service
+            .list_sessions("user-1")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(remaining, vec!["phone"]);
+        assert_eq!(service.list_sessions("user-2").await.unwrap().len(), 1);
+    }
+
+    // ==================== TOTP Tests ====================
+
+    fn verify_command(challenge: &TotpChallengeResponse, code: &str) -> VerifyTotpCommand {
+        VerifyTotpCommand {
+            challenge_token: challenge.challenge_token.clone(),
+            code: code.to_string(),
+            device_fingerprint: "test-fingerprint".to_string(),
+            client: ClientInfo::default(),
+        }
+    }
+
+    fn totp_challenge(result: LoginResult) -> TotpChallengeResponse {
+        match result {
+            LoginResult::TotpRequired(challenge) => challenge,
+            LoginResult::Authenticated(_) => panic!("expected a TOTP challenge, got tokens"),
+        }
     }
 
     #[tokio::test]
-    async fn test_refresh_invalid_token() {
-        let service = AuthService::new(
-            Arc::new(MockUserRepository::new()),
-            Arc::new(MockRefreshTokenRepository::new()),
-            Arc::new(MockPasswordHasher),
-            Arc::new(MockTokenService::failing_decode()),
-            Arc::new(MockIdGenerator::new()),
-            Arc::new(MockOrganizationRepository::new()),
-            Arc::new(MockOrganizationMemberRepository::new()),
+    async fn test_totp_login_requires_second_factor() {
+        let user = create_test_user("user-1", "test@example.com", "SecurePass123!");
+        let service = create_auth_service_with_user(user);
+
+        let setup = service
+            .enable_totp(EnableTotpCommand {
+                user_id: "user-1".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(setup.recovery_codes.len(), TOTP_RECOVERY_CODE_COUNT);
+        assert!(setup.otpauth_uri.starts_with("otpauth://totp/Altenia:"));
+
+        // Unconfirmed setup doesn't change login
+        signed_in(
+            service
+                .login(login_command("test@example.com"))
+                .await
+                .unwrap(),
         );
 
-        let cmd = RefreshTokenCommand {
-            refresh_token: "invalid_token".to_string(),
-            device_fingerprint: "test-fingerprint".to_string(),
-        };
+        let user_id = UserId::new("user-1".to_string());
+        let totp = service
+            .totp_repo
+            .find_by_user(&user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let now = Utc::now();
+        let code = totp.secret().code_at(now);
+        service
+            .confirm_totp(ConfirmTotpCommand {
+                user_id: "user-1".to_string(),
+                code: code.clone(),
+                client: ClientInfo::default(),
+            })
+            .await
+            .unwrap();
 
-        let result = service.refresh(cmd).await;
+        let challenge = totp_challenge(
+            service
+                .login(login_command("test@example.com"))
+                .await
+                .unwrap(),
+        );
+
+        // The code used to confirm can't be replayed
+        let result = service.verify_totp(verify_command(&challenge, &code)).await;
+        assert!(matches!(result, Err(AuthDomainError::InvalidTotpCode)));
 
+        // Another device can't finish the login
+        let mut other_device = verify_command(&challenge, &setup.recovery_codes[0]);
+        other_device.device_fingerprint = "other-fingerprint".to_string();
+        let result = service.verify_totp(other_device).await;
         assert!(matches!(result, Err(AuthDomainError::TokenInvalid)));
+
+        // A recovery code works once
+        let challenge = totp_challenge(
+            service
+                .login(login_command("test@example.com"))
+                .await
+                .unwrap(),
+        );
+        let response = service
+            .verify_totp(verify_command(&challenge, &setup.recovery_codes[0]))
+            .await
+            .unwrap();
+        assert_eq!(response.user_id, "user-1");
+        // The challenge is consumed
+        let result = service
+            .verify_totp(verify_command(&challenge, &setup.recovery_codes[1]))
+            .await;
+        assert!(matches!(result, Err(AuthDomainError::TokenInvalid)));
+
+        let challenge = totp_challenge(
+            service
+                .login(login_command("test@example.com"))
+                .await
+                .unwrap(),
+        );
+        let result = service
+            .verify_totp(verify_command(&challenge, &setup.recovery_codes[0]))
+            .await;
+        assert!(matches!(result, Err(AuthDomainError::InvalidTotpCode)));
+
+        // The app's next code is within the allowed drift and signs in
+        let next_code = totp.secret().code_at(now + Duration::seconds(30));
+        let response = service
+            .verify_totp(verify_command(&challenge, &next_code))
+            .await;
+        assert!(response.is_ok());
+
+        let audited: Vec<_> = service
+            .audit_repo
+            .events()
+            .iter()
+            .filter(|e| e.event_type() == AuthAuditEventType::TotpVerify)
+            .map(|e| e.success())
+            .collect();
+        assert_eq!(audited, vec![false, false, true, false, false, true]);
     }
 
     #[tokio::test]
-    async fn test_refresh_token_not_in_database() {
-        let service = create_auth_service();
+    async fn test_disable_totp_requires_password() {
+        let user = create_test_user("user-1", "test@example.com", "SecurePass123!");
+        let service = create_auth_service_with_user(user);
 
-        let cmd = RefreshTokenCommand {
-            refresh_token: "refresh_token_user-1".to_string(),
-            device_fingerprint: "test-fingerprint".to_string(),
-        };
+        service
+            .enable_totp(EnableTotpCommand {
+                user_id: "user-1".to_string(),
+            })
+            .await
+            .unwrap();
+        let user_id = UserId::new("user-1".to_string());
+        let totp = service
+            .totp_repo
+            .find_by_user(&user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        service
+            .confirm_totp(ConfirmTotpCommand {
+                user_id: "user-1".to_string(),
+                code: totp.secret().code_at(Utc::now()),
+                client: ClientInfo::default(),
+            })
+            .await
+            .unwrap();
 
-        let result = service.refresh(cmd).await;
+        let result = service
+            .enable_totp(EnableTotpCommand {
+                user_id: "user-1".to_string(),
+            })
+            .await;
+        assert!(matches!(result, Err(AuthDomainError::TotpAlreadyEnabled)));
 
-        assert!(matches!(result, Err(AuthDomainError::TokenInvalid)));
+        let disable = |password: &str| DisableTotpCommand {
+            user_id: "user-1".to_string(),
+            current_password: Some(password.to_string()),
+            code: None,
+            client: ClientInfo::default(),
+        };
+        let result = service.disable_totp(disable("WrongPass1!")).await;
+        assert!(matches!(result, Err(AuthDomainError::InvalidCredentials)));
+
+        service
+            .disable_totp(disable("SecurePass123!"))
+            .await
+            .unwrap();
+        signed_in(
+            service
+                .login(login_command("test@example.com"))
+                .await
+                .unwrap(),
+        );
     }
 
     #[tokio::test]
-    async fn test_refresh_revoked_token() {
-        let user_id = UserId::new("user-1".to_string());
-        let mut token = RefreshToken::new(
-            TokenId::new("token-1".to_string()),
-            user_id.clone(),
-            "hash_refresh_token_user-1".to_string(),
-            "test-fingerprint".to_string(),
-            Utc::now() + Duration::days(7),
+    async fn test_oauth_only_user_disables_totp_with_a_code() {
+        let user = User::new_oauth(
+            UserId::new("user-1".to_string()),
+            Email::new("octo@example.com".to_string()).unwrap(),
         );
-        token.revoke();
+        let service = create_auth_service_with_user(user);
 
-        let service = AuthService::new(
-            Arc::new(MockUserRepository::new()),
-            Arc::new(MockRefreshTokenRepository::with_token(token)),
-            Arc::new(MockPasswordHasher),
-            Arc::new(MockTokenService::new()),
-            Arc::new(MockIdGenerator::new()),
-            Arc::new(MockOrganizationRepository::new()),
-            Arc::new(MockOrganizationMemberRepository::new()),
+        service
+            .enable_totp(EnableTotpCommand {
+                user_id: "user-1".to_string(),
+            })
+            .await
+            .unwrap();
+        let user_id = UserId::new("user-1".to_string());
+        let totp = service
+            .totp_repo
+            .find_by_user(&user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let confirm_code = totp.secret().code_at(Utc::now());
+        service
+            .confirm_totp(ConfirmTotpCommand {
+                user_id: "user-1".to_string(),
+                code: confirm_code.clone(),
+                client: ClientInfo::default(),
+            })
+            .await
+            .unwrap();
+
+        let disable = |code: Option<String>| DisableTotpCommand {
+            user_id: "user-1".to_string(),
+            current_password: None,
+            code,
+            client: ClientInfo::default(),
+        };
+        let result = service.disable_totp(disable(None)).await;
+        assert!(matches!(result, Err(AuthDomainError::InvalidCredentials)));
+        // The code that confirmed setup can't be replayed
+        let result = service.disable_totp(disable(Some(confirm_code))).await;
+        assert!(matches!(result, Err(AuthDomainError::InvalidTotpCode)));
+
+        let next_code = totp.secret().code_at(Utc::now() + Duration::seconds(30));
+        service
+            .disable_totp(disable(Some(next_code)))
+            .await
+            .unwrap();
+        assert!(
+            service
+                .totp_repo
+                .find_by_user(&user_id)
+                .await
+                .unwrap()
+                .is_none()
         );
+    }
 
-        let cmd = RefreshTokenCommand {
-            refresh_token: "refresh_token_user-1".to_string(),
+    // ==================== OAuth Tests ====================
+
+    fn oauth_command(provider: OAuthProvider, external_id: &str, email: &str) -> OAuthLoginCommand {
+        OAuthLoginCommand {
+            provider,
+            external_id: external_id.to_string(),
+            email: email.to_string(),
+            display_name: Some("Octo Cat".to_string()),
             device_fingerprint: "test-fingerprint".to_string(),
-        };
+            client: ClientInfo::default(),
+        }
+    }
 
-        let result = service.refresh(cmd).await;
+    #[tokio::test]
+    async fn test_oauth_links_providers_to_one_account() {
+        let identities = Arc::new(MockOAuthIdentityRepository::default());
+        let service = create_auth_service().with_oauth(identities.clone());
+
+        // First sign-in creates a password-less account
+        let first = signed_in(
+            service
+                .login_or_register_oauth(oauth_command(
+                    OAuthProvider::GitHub,
+                    "42",
+                    "octo@example.com",
+                ))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(first.display_name.as_deref(), Some("Octo Cat"));
+        let user = service.get_current_user(&first.user_id).await.unwrap();
+        assert!(!user.has_password());
+
+        // The same provider account signs in again as that user
+        let again = signed_in(
+            service
+                .login_or_register_oauth(oauth_command(
+                    OAuthProvider::GitHub,
+                    "42",
+                    "octo@example.com",
+                ))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(again.user_id, first.user_id);
+
+        // Another provider with the same verified email is linked, not a new account
+        let google = signed_in(
+            service
+                .login_or_register_oauth(oauth_command(
+                    OAuthProvider::Google,
+                    "g-1",
+                    "octo@example.com",
+                ))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(google.user_id, first.user_id);
+        let linked = identities
+            .find_by_user(&UserId::new(first.user_id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(linked.len(), 2);
+    }
 
-        assert!(matches!(result, Err(AuthDomainError::TokenRevoked)));
+    #[tokio::test]
+    async fn test_oauth_does_not_take_over_password_account() {
+        let user = create_test_user("user-1", "test@example.com", "SecurePass123!");
+        let identities = Arc::new(MockOAuthIdentityRepository::default());
+        let service = create_auth_service_with_user(user).with_oauth(identities.clone());
+
+        let result = service
+            .login_or_register_oauth(oauth_command(
+                OAuthProvider::GitHub,
+                "42",
+                "test@example.com",
+            ))
+            .await;
+        assert!(matches!(result, Err(AuthDomainError::OAuthAccountConflict)));
+        assert!(identities.identities.lock().unwrap().is_empty());
+
+        // Without identity persistence OAuth is off entirely
+        let result = create_auth_service()
+            .login_or_register_oauth(oauth_command(
+                OAuthProvider::GitHub,
+                "42",
+                "new@example.com",
+            ))
+            .await;
+        assert!(matches!(result, Err(AuthDomainError::OAuthUnavailable)));
+    }
+
+    // ==================== Personal Access Token Tests ====================
+
+    fn create_token_command(
+        scopes: &[&str],
+        expires_in_days: Option<i64>,
+    ) -> CreatePersonalAccessTokenCommand {
+        CreatePersonalAccessTokenCommand {
+            user_id: "user-1".to_string(),
+            name: " ci deploy ".to_string(),
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+            expires_in_days,
+            client: client(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_personal_access_token_authenticates_with_its_scopes() {
+        let user = create_test_user("user-1", "test@example.com", "password");
+        let tokens = Arc::new(MockPersonalAccessTokenRepository::default());
+        let service =
+            create_auth_service_with_user(user).with_personal_access_tokens(tokens.clone());
+
+        let created = service
+            .create_personal_access_token(create_token_command(
+                &["projects:write", "orgs:read", "projects:write"],
+                Some(30),
+            ))
+            .await
+            .unwrap();
+        assert!(created.token.starts_with("pat_"));
+        assert_eq!(created.details.name, "ci deploy");
+        assert_eq!(created.details.scopes, vec!["projects:write", "orgs:read"]);
+        // Only the hash is stored
+        let stored = tokens.tokens.lock().unwrap()[0].clone();
+        assert_ne!(stored.token_hash(), created.token);
+        assert_eq!(stored.token_hash(), hash_token(&created.token));
+
+        let identity = service
+            .verify_personal_access_token(&created.token)
+            .await
+            .unwrap();
+        assert_eq!(identity.user_id, "user-1");
+        assert_eq!(
+            identity.scopes,
+            vec![TokenScope::ProjectsWrite, TokenScope::OrgsRead]
+        );
+
+        let listed = service.list_personal_access_tokens("user-1").await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].last_used_at.is_some());
+
+        let result = service.verify_personal_access_token("pat_unknown").await;
+        assert!(matches!(result, Err(AuthDomainError::TokenInvalid)));
+    }
+
+    #[tokio::test]
+    async fn test_personal_access_token_input_is_validated() {
+        let tokens = Arc::new(MockPersonalAccessTokenRepository::default());
+        let service = create_auth_service().with_personal_access_tokens(tokens.clone());
+
+        let result = service
+            .create_personal_access_token(create_token_command(&["logs:read"], None))
+            .await;
+        assert!(matches!(result, Err(AuthDomainError::InvalidTokenScope(_))));
+        let result = service
+            .create_personal_access_token(create_token_command(&[], None))
+            .await;
+        assert!(matches!(
+            result,
+            Err(AuthDomainError::InvalidPersonalAccessToken(_))
+        ));
+        let result = service
+            .create_personal_access_token(create_token_command(&["orgs:read"], Some(0)))
+            .await;
+        assert!(matches!(
+            result,
+            Err(AuthDomainError::InvalidPersonalAccessToken(_))
+        ));
+        assert!(tokens.tokens.lock().unwrap().is_empty());
+
+        // Without persistence tokens are off entirely
+        let result = create_auth_service()
+            .create_personal_access_token(create_token_command(&["orgs:read"], None))
+            .await;
+        assert!(matches!(
+            result,
+            Err(AuthDomainError::PersonalAccessTokensUnavailable)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_expired_and_revoked_personal_access_tokens_are_rejected() {
+        let user = create_test_user("user-1", "test@example.com", "password");
+        let tokens = Arc::new(MockPersonalAccessTokenRepository::default());
+        let service =
+            create_auth_service_with_user(user).with_personal_access_tokens(tokens.clone());
+
+        tokens
+            .save(&PersonalAccessToken::new(
+                "pat-old".to_string(),
+                UserId::new("user-1".to_string()),
+                "old".to_string(),
+                hash_token("pat_expired"),
+                vec![TokenScope::OrgsRead],
+                Some(Utc::now() - Duration::days(1)),
+            ))
+            .await
+            .unwrap();
+        let result = service.verify_personal_access_token("pat_expired").await;
+        assert!(matches!(result, Err(AuthDomainError::TokenExpired)));
+
+        let created = service
+            .create_personal_access_token(create_token_command(&["orgs:read"], None))
+            .await
+            .unwrap();
+
+        // Another user can't revoke it
+        let result = service
+            .revoke_personal_access_token(RevokePersonalAccessTokenCommand {
+                user_id: "user-2".to_string(),
+                token_id: created.details.id.clone(),
+                client: client(),
+            })
+            .await;
+        assert!(matches!(
+            result,
+            Err(AuthDomainError::PersonalAccessTokenNotFound)
+        ));
+
+        service
+            .revoke_personal_access_token(RevokePersonalAccessTokenCommand {
+                user_id: "user-1".to_string(),
+                token_id: created.details.id,
+                client: client(),
+            })
+            .await
+            .unwrap();
+        let result = service.verify_personal_access_token(&created.token).await;
+        assert!(matches!(result, Err(AuthDomainError::TokenInvalid)));
     }
 
     // ==================== Get Current User Tests ====================
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+
+use super::value_objects::{AuditEventId, AuthAuditEventType};
+use crate::modules::auth::domain::user::UserId;
+
+/// AuthAuditEvent - a record of an authentication action and its outcome.
+/// Never holds passwords or tokens; `identifier` is the email or username as entered.
+#[derive(Debug, Clone)]
+pub struct AuthAuditEvent {
+    id: AuditEventId,
+    event_type: AuthAuditEventType,
+    success: bool,
+    user_id: Option<UserId>,
+    identifier: Option<String>,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+    failure_reason: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl AuthAuditEvent {
+    /// Create a new audit event
+    pub fn new(
+        id: AuditEventId,
+        event_type: AuthAuditEventType,
+        user_id: Option<UserId>,
+        identifier: Option<String>,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+        failure_reason: Option<String>,
+    ) -> Self {
+        Self {
+            id,
+            event_type,
+            success: failure_reason.is_none(),
+            user_id,
+            identifier,
+            ip_address,
+            user_agent,
+            failure_reason,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Reconstruct from persistence layer
+    #[allow(clippy::too_many_arguments)]
+    pub fn reconstruct(
+        id: AuditEventId,
+        event_type: AuthAuditEventType,
+        success: bool,
+        user_id: Option<UserId>,
+        identifier: Option<String>,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+        failure_reason: Option<String>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            event_type,
+            success,
+            user_id,
+            identifier,
+            ip_address,
+            user_agent,
+            failure_reason,
+            created_at,
+        }
+    }
+
+    // Getters
+    pub fn id(&self) -> &AuditEventId {
+        &self.id
+    }
+
+    pub fn event_type(&self) -> AuthAuditEventType {
+        self.event_type
+    }
+
+    pub fn success(&self) -> bool {
+        self.success
+    }
+
+    pub fn user_id(&self) -> Option<&UserId> {
+        self.user_id.as_ref()
+    }
+
+    pub fn identifier(&self) -> Option<&str> {
+        self.identifier.as_deref()
+    }
+
+    pub fn ip_address(&self) -> Option<&str> {
+        self.ip_address.as_deref()
+    }
+
+    pub fn user_agent(&self) -> Option<&str> {
+        self.user_agent.as_deref()
+    }
+
+    pub fn failure_reason(&self) -> Option<&str> {
+        self.failure_reason.as_deref()
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
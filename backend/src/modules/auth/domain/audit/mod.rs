@@ -0,0 +1,7 @@
+mod entity;
+mod repository;
+mod value_objects;
+
+pub use entity::AuthAuditEvent;
+pub use repository::{AuthAuditFilters, AuthAuditRepository};
+pub use value_objects::{AuditEventId, AuthAuditEventType};
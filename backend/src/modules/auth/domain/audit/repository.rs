@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::entity::AuthAuditEvent;
+use super::value_objects::AuthAuditEventType;
+use crate::modules::auth::domain::errors::AuthDomainError;
+use crate::modules::auth::domain::user::UserId;
+
+/// Filters for audit event queries
+#[derive(Debug, Clone, Default)]
+pub struct AuthAuditFilters {
+    /// Only events attributed to one of these users
+    pub user_ids: Vec<UserId>,
+    pub event_type: Option<AuthAuditEventType>,
+    pub success: Option<bool>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+}
+
+/// Repository trait for AuthAuditEvent persistence
+#[async_trait]
+pub trait AuthAuditRepository: Send + Sync {
+    /// Save a new audit event
+    async fn save(&self, event: &AuthAuditEvent) -> Result<(), AuthDomainError>;
+
+    /// Find events matching filters (paginated, most recent first)
+    async fn find(
+        &self,
+        filters: &AuthAuditFilters,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AuthAuditEvent>, AuthDomainError>;
+}
@@ -0,0 +1,122 @@
+use crate::modules::auth::domain::errors::AuthDomainError;
+
+/// Audit event ID - wrapper around UUID string
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AuditEventId(String);
+
+impl AuditEventId {
+    pub fn new(id: String) -> Self {
+        Self(id)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Auth Audit Event Type - the authentication action being audited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthAuditEventType {
+    Register,
+    Login,
+    OAuthLogin,
+    Logout,
+    TokenRefresh,
+    SessionRevoke,
+    PersonalAccessTokenCreate,
+    PersonalAccessTokenRevoke,
+    PasswordChange,
+    PasswordResetRequest,
+    PasswordReset,
+    EmailChange,
+    EmailVerify,
+    AccountDeletion,
+    TotpEnable,
+    TotpDisable,
+    TotpVerify,
+}
+
+impl AuthAuditEventType {
+    pub fn from_str(s: &str) -> Result<Self, AuthDomainError> {
+        match s {
+            "register" => Ok(Self::Register),
+            "login" => Ok(Self::Login),
+            "oauth_login" => Ok(Self::OAuthLogin),
+            "logout" => Ok(Self::Logout),
+            "token_refresh" => Ok(Self::TokenRefresh),
+            "session_revoke" => Ok(Self::SessionRevoke),
+            "personal_access_token_create" => Ok(Self::PersonalAccessTokenCreate),
+            "personal_access_token_revoke" => Ok(Self::PersonalAccessTokenRevoke),
+            "password_change" => Ok(Self::PasswordChange),
+            "password_reset_request" => Ok(Self::PasswordResetRequest),
+            "password_reset" => Ok(Self::PasswordReset),
+            "email_change" => Ok(Self::EmailChange),
+            "email_verify" => Ok(Self::EmailVerify),
+            "account_deletion" => Ok(Self::AccountDeletion),
+            "totp_enable" => Ok(Self::TotpEnable),
+            "totp_disable" => Ok(Self::TotpDisable),
+            "totp_verify" => Ok(Self::TotpVerify),
+            _ => Err(AuthDomainError::InvalidAuditEventType(s.to_string())),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Register => "register",
+            Self::Login => "login",
+            Self::OAuthLogin => "oauth_login",
+            Self::Logout => "logout",
+            Self::TokenRefresh => "token_refresh",
+            Self::SessionRevoke => "session_revoke",
+            Self::PersonalAccessTokenCreate => "personal_access_token_create",
+            Self::PersonalAccessTokenRevoke => "personal_access_token_revoke",
+            Self::PasswordChange => "password_change",
+            Self::PasswordResetRequest => "password_reset_request",
+            Self::PasswordReset => "password_reset",
+            Self::EmailChange => "email_change",
+            Self::EmailVerify => "email_verify",
+            Self::AccountDeletion => "account_deletion",
+            Self::TotpEnable => "totp_enable",
+            Self::TotpDisable => "totp_disable",
+            Self::TotpVerify => "totp_verify",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_type_round_trip() {
+        for event_type in [
+            AuthAuditEventType::Register,
+            AuthAuditEventType::Login,
+            AuthAuditEventType::OAuthLogin,
+            AuthAuditEventType::Logout,
+            AuthAuditEventType::TokenRefresh,
+            AuthAuditEventType::SessionRevoke,
+            AuthAuditEventType::PersonalAccessTokenCreate,
+            AuthAuditEventType::PersonalAccessTokenRevoke,
+            AuthAuditEventType::PasswordChange,
+            AuthAuditEventType::PasswordResetRequest,
+            AuthAuditEventType::PasswordReset,
+            AuthAuditEventType::EmailChange,
+            AuthAuditEventType::EmailVerify,
+            AuthAuditEventType::AccountDeletion,
+            AuthAuditEventType::TotpEnable,
+            AuthAuditEventType::TotpDisable,
+            AuthAuditEventType::TotpVerify,
+        ] {
+            assert_eq!(
+                AuthAuditEventType::from_str(event_type.as_str()).unwrap(),
+                event_type
+            );
+        }
+    }
+
+    #[test]
+    fn test_event_type_from_str_invalid() {
+        assert!(AuthAuditEventType::from_str("login_attempt").is_err());
+    }
+}
@@ -7,20 +7,56 @@ pub enum AuthDomainError {
     InvalidPassword(String),
     WeakPassword(String),
     InvalidDisplayName(String),
+    InvalidUsername(String),
 
     // User errors
     UserNotFound,
     UserAlreadyExists,
     UserAlreadyDeleted,
     InvalidCredentials,
+    /// An organization the user belongs to only allows single sign-on
+    SsoRequired,
     NoPasswordSet,
     EmailAlreadyInUse,
+    UsernameAlreadyTaken,
+    InvalidDefaultOrg,
+    InsufficientPermissions,
+
+    // Email verification errors
+    EmailAlreadyVerified,
+    EmailVerificationUnavailable,
+    PasswordResetUnavailable,
+    SessionNotFound,
+
+    // OAuth errors
+    OAuthUnavailable,
+    UnsupportedOAuthProvider(String),
+    InvalidOAuthState,
+    /// The provider didn't vouch for the account's email address
+    OAuthEmailUnverified,
+    /// The email belongs to an account that signs in with a password
+    OAuthAccountConflict,
+    OAuthProviderError(String),
+
+    // Personal access token errors
+    InvalidTokenScope(String),
+    InvalidPersonalAccessToken(String),
+    PersonalAccessTokensUnavailable,
+    PersonalAccessTokenNotFound,
+
+    // Two-factor errors
+    TotpAlreadyEnabled,
+    TotpNotEnabled,
+    InvalidTotpCode,
 
     // Token errors
     TokenExpired,
     TokenInvalid,
     TokenRevoked,
 
+    // Audit errors
+    InvalidAuditEventType(String),
+
     // Infrastructure errors (will be mapped from infra layer)
     InternalError(String),
 }
@@ -32,15 +68,60 @@ impl fmt::Display for AuthDomainError {
             Self::InvalidPassword(msg) => write!(f, "Invalid password: {}", msg),
             Self::WeakPassword(reason) => write!(f, "Password is too weak: {}", reason),
             Self::InvalidDisplayName(reason) => write!(f, "Invalid display name: {}", reason),
+            Self::InvalidUsername(reason) => write!(f, "Invalid username: {}", reason),
             Self::UserNotFound => write!(f, "User not found"),
             Self::UserAlreadyExists => write!(f, "User already exists"),
             Self::UserAlreadyDeleted => write!(f, "User account has already been deleted"),
             Self::InvalidCredentials => write!(f, "Invalid credentials"),
+            Self::SsoRequired => write!(
+                f,
+                "Password sign-in is disabled by your organization; sign in with SSO (OIDC)"
+            ),
             Self::NoPasswordSet => write!(f, "No password set for this account"),
             Self::EmailAlreadyInUse => write!(f, "Email is already in use"),
+            Self::UsernameAlreadyTaken => write!(f, "Username is already taken"),
+            Self::InvalidDefaultOrg => {
+                write!(f, "Default organization must be one you are a member of")
+            }
+            Self::InsufficientPermissions => {
+                write!(f, "Only organization admins can view audit events")
+            }
+            Self::EmailAlreadyVerified => write!(f, "Email address is already verified"),
+            Self::EmailVerificationUnavailable => {
+                write!(f, "Email verification is not configured")
+            }
+            Self::PasswordResetUnavailable => write!(f, "Password reset is not configured"),
+            Self::SessionNotFound => write!(f, "Session not found"),
+            Self::OAuthUnavailable => write!(f, "OAuth sign-in is not configured"),
+            Self::UnsupportedOAuthProvider(provider) => {
+                write!(f, "Unsupported OAuth provider: {}", provider)
+            }
+            Self::InvalidOAuthState => {
+                write!(f, "OAuth sign-in expired or was tampered with; start again")
+            }
+            Self::OAuthEmailUnverified => {
+                write!(f, "Your email address is not verified with this provider")
+            }
+            Self::OAuthAccountConflict => write!(
+                f,
+                "An account with this email already exists; sign in with your password"
+            ),
+            Self::OAuthProviderError(msg) => write!(f, "OAuth provider error: {}", msg),
+            Self::InvalidTokenScope(scope) => write!(f, "Unknown token scope: {}", scope),
+            Self::InvalidPersonalAccessToken(reason) => {
+                write!(f, "Invalid personal access token: {}", reason)
+            }
+            Self::PersonalAccessTokensUnavailable => {
+                write!(f, "Personal access tokens are not configured")
+            }
+            Self::PersonalAccessTokenNotFound => write!(f, "Personal access token not found"),
+            Self::TotpAlreadyEnabled => write!(f, "Two-factor authentication is already enabled"),
+            Self::TotpNotEnabled => write!(f, "Two-factor authentication is not enabled"),
+            Self::InvalidTotpCode => write!(f, "Invalid two-factor code"),
             Self::TokenExpired => write!(f, "Token has expired"),
             Self::TokenInvalid => write!(f, "Token is invalid"),
             Self::TokenRevoked => write!(f, "Token has been revoked"),
+            Self::InvalidAuditEventType(t) => write!(f, "Invalid audit event type: {}", t),
             Self::InternalError(msg) => write!(f, "Internal error: {}", msg),
         }
     }
@@ -1,9 +1,26 @@
+pub mod audit;
 pub mod errors;
+pub mod oauth;
+pub mod password_reset;
+pub mod personal_access_token;
 pub mod services;
 pub mod token;
+pub mod totp;
 pub mod user;
+pub mod verification;
 
+pub use audit::{
+    AuditEventId, AuthAuditEvent, AuthAuditEventType, AuthAuditFilters, AuthAuditRepository,
+};
 pub use errors::AuthDomainError;
+pub use oauth::{OAuthIdentity, OAuthIdentityRepository, OAuthProvider};
+pub use password_reset::{PasswordResetToken, PasswordResetTokenRepository};
+pub use personal_access_token::{PersonalAccessToken, PersonalAccessTokenRepository, TokenScope};
 pub use services::PasswordHasher;
 pub use token::{RefreshToken, RefreshTokenRepository, TokenId};
-pub use user::{DisplayName, Email, PasswordHash, PlainPassword, User, UserId, UserRepository};
+pub use totp::{TotpChallenge, UserTotp, UserTotpRepository, hash_one_time_secret, hash_token};
+pub use user::{
+    DisplayName, Email, PasswordHash, PlainPassword, TotpSecret, User, UserId, UserRepository,
+    Username,
+};
+pub use verification::{VerificationToken, VerificationTokenRepository};
@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+
+use crate::modules::auth::domain::errors::AuthDomainError;
+use crate::modules::auth::domain::user::UserId;
+
+/// Social login providers users can sign in with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OAuthProvider {
+    GitHub,
+    Google,
+}
+
+impl OAuthProvider {
+    pub fn from_str(s: &str) -> Result<Self, AuthDomainError> {
+        match s.to_lowercase().as_str() {
+            "github" => Ok(Self::GitHub),
+            "google" => Ok(Self::Google),
+            _ => Err(AuthDomainError::UnsupportedOAuthProvider(s.to_string())),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::GitHub => "github",
+            Self::Google => "google",
+        }
+    }
+}
+
+/// An account at an OAuth provider linked to a user. A user may have one
+/// per provider; each provider account belongs to a single user.
+#[derive(Debug, Clone)]
+pub struct OAuthIdentity {
+    user_id: UserId,
+    provider: OAuthProvider,
+    external_id: String, // The provider's stable account id, not the email
+    email: String,       // Verified address the provider reported when linked
+    created_at: DateTime<Utc>,
+}
+
+impl OAuthIdentity {
+    pub fn new(
+        user_id: UserId,
+        provider: OAuthProvider,
+        external_id: String,
+        email: String,
+    ) -> Self {
+        Self {
+            user_id,
+            provider,
+            external_id,
+            email,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Reconstruct from persistence layer
+    pub fn reconstruct(
+        user_id: UserId,
+        provider: OAuthProvider,
+        external_id: String,
+        email: String,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            user_id,
+            provider,
+            external_id,
+            email,
+            created_at,
+        }
+    }
+
+    // Getters
+    pub fn user_id(&self) -> &UserId {
+        &self.user_id
+    }
+
+    pub fn provider(&self) -> OAuthProvider {
+        self.provider
+    }
+
+    pub fn external_id(&self) -> &str {
+        &self.external_id
+    }
+
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+
+use super::entity::{OAuthIdentity, OAuthProvider};
+use crate::modules::auth::domain::errors::AuthDomainError;
+use crate::modules::auth::domain::user::UserId;
+
+/// Port for linked OAuth identity persistence
+/// Infrastructure layer implements this with PostgreSQL
+#[async_trait]
+pub trait OAuthIdentityRepository: Send + Sync {
+    /// Find the identity a provider knows by `external_id`
+    async fn find(
+        &self,
+        provider: OAuthProvider,
+        external_id: &str,
+    ) -> Result<Option<OAuthIdentity>, AuthDomainError>;
+
+    /// Every identity linked to the user
+    async fn find_by_user(&self, user_id: &UserId) -> Result<Vec<OAuthIdentity>, AuthDomainError>;
+
+    /// Link a new identity
+    async fn save(&self, identity: &OAuthIdentity) -> Result<(), AuthDomainError>;
+}
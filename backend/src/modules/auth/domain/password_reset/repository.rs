@@ -0,0 +1,25 @@
+use async_trait::async_trait;
+
+use super::entity::PasswordResetToken;
+use crate::modules::auth::domain::errors::AuthDomainError;
+use crate::modules::auth::domain::user::UserId;
+
+/// Port for password reset token persistence
+/// Infrastructure layer implements this with PostgreSQL
+#[async_trait]
+pub trait PasswordResetTokenRepository: Send + Sync {
+    /// Save a new reset token
+    async fn save(&self, token: &PasswordResetToken) -> Result<(), AuthDomainError>;
+
+    /// Find a token by the hash of its value
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<PasswordResetToken>, AuthDomainError>;
+
+    /// Delete every token issued to the user
+    async fn delete_for_user(&self, user_id: &UserId) -> Result<(), AuthDomainError>;
+
+    /// Delete expired tokens (cleanup job), returning how many were removed
+    async fn delete_expired(&self) -> Result<u64, AuthDomainError>;
+}
@@ -0,0 +1,189 @@
+use chrono::{DateTime, Utc};
+
+use crate::modules::auth::domain::errors::AuthDomainError;
+use crate::modules::auth::domain::user::UserId;
+
+/// What a personal access token may do. A write scope also grants reading
+/// the same resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenScope {
+    OrgsRead,
+    OrgsWrite,
+    ProjectsRead,
+    ProjectsWrite,
+}
+
+impl TokenScope {
+    pub fn from_str(s: &str) -> Result<Self, AuthDomainError> {
+        match s {
+            "orgs:read" => Ok(Self::OrgsRead),
+            "orgs:write" => Ok(Self::OrgsWrite),
+            "projects:read" => Ok(Self::ProjectsRead),
+            "projects:write" => Ok(Self::ProjectsWrite),
+            _ => Err(AuthDomainError::InvalidTokenScope(s.to_string())),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OrgsRead => "orgs:read",
+            Self::OrgsWrite => "orgs:write",
+            Self::ProjectsRead => "projects:read",
+            Self::ProjectsWrite => "projects:write",
+        }
+    }
+
+    /// Whether holding this scope allows what `required` guards
+    pub fn grants(&self, required: TokenScope) -> bool {
+        *self == required
+            || matches!(
+                (self, required),
+                (Self::OrgsWrite, Self::OrgsRead) | (Self::ProjectsWrite, Self::ProjectsRead)
+            )
+    }
+}
+
+/// A long-lived token a user creates to call the API from scripts
+/// Only the hash of the secret is stored; the token is shown once at creation
+#[derive(Debug, Clone)]
+pub struct PersonalAccessToken {
+    id: String,
+    user_id: UserId,
+    name: String,
+    token_hash: String, // SHA256 of the full `pat_...` token
+    scopes: Vec<TokenScope>,
+    expires_at: Option<DateTime<Utc>>, // None never expires
+    last_used_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl PersonalAccessToken {
+    /// Prefix of every token, so they are recognizable in headers and secret scanners
+    pub const PREFIX: &'static str = "pat_";
+
+    /// Longest token name accepted
+    pub const MAX_NAME_CHARS: usize = 100;
+
+    pub fn new(
+        id: String,
+        user_id: UserId,
+        name: String,
+        token_hash: String,
+        scopes: Vec<TokenScope>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            name,
+            token_hash,
+            scopes,
+            expires_at,
+            last_used_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Reconstruct from persistence layer
+    #[allow(clippy::too_many_arguments)]
+    pub fn reconstruct(
+        id: String,
+        user_id: UserId,
+        name: String,
+        token_hash: String,
+        scopes: Vec<TokenScope>,
+        expires_at: Option<DateTime<Utc>>,
+        last_used_at: Option<DateTime<Utc>>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            name,
+            token_hash,
+            scopes,
+            expires_at,
+            last_used_at,
+            created_at,
+        }
+    }
+
+    // Getters
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn user_id(&self) -> &UserId {
+        &self.user_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn token_hash(&self) -> &str {
+        &self.token_hash
+    }
+
+    pub fn scopes(&self) -> &[TokenScope] {
+        &self.scopes
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    pub fn last_used_at(&self) -> Option<DateTime<Utc>> {
+        self.last_used_at
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    // Domain behavior
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| Utc::now() > expires_at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_scope_parsing_and_write_implies_read() {
+        for scope in [
+            TokenScope::OrgsRead,
+            TokenScope::OrgsWrite,
+            TokenScope::ProjectsRead,
+            TokenScope::ProjectsWrite,
+        ] {
+            assert_eq!(TokenScope::from_str(scope.as_str()).unwrap(), scope);
+        }
+        assert!(TokenScope::from_str("logs:read").is_err());
+
+        assert!(TokenScope::OrgsWrite.grants(TokenScope::OrgsRead));
+        assert!(!TokenScope::OrgsRead.grants(TokenScope::OrgsWrite));
+        assert!(!TokenScope::ProjectsWrite.grants(TokenScope::OrgsRead));
+    }
+
+    #[test]
+    fn test_expiry() {
+        let token = |expires_at| {
+            PersonalAccessToken::new(
+                "pat-1".to_string(),
+                UserId::new("user-1".to_string()),
+                "ci".to_string(),
+                "hash".to_string(),
+                vec![TokenScope::OrgsRead],
+                expires_at,
+            )
+        };
+        assert!(!token(None).is_expired());
+        assert!(!token(Some(Utc::now() + Duration::days(1))).is_expired());
+        assert!(token(Some(Utc::now() - Duration::seconds(1))).is_expired());
+    }
+}
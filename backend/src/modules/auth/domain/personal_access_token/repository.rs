@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::entity::PersonalAccessToken;
+use crate::modules::auth::domain::errors::AuthDomainError;
+use crate::modules::auth::domain::user::UserId;
+
+/// Port for personal access token persistence
+/// Infrastructure layer implements this with PostgreSQL
+#[async_trait]
+pub trait PersonalAccessTokenRepository: Send + Sync {
+    async fn save(&self, token: &PersonalAccessToken) -> Result<(), AuthDomainError>;
+
+    /// Find the token whose secret hashes to `token_hash`
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<PersonalAccessToken>, AuthDomainError>;
+
+    /// Every token of the user, newest first
+    async fn find_by_user(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<PersonalAccessToken>, AuthDomainError>;
+
+    /// Delete one of the user's tokens; false when the user has no such token
+    async fn delete(&self, user_id: &UserId, id: &str) -> Result<bool, AuthDomainError>;
+
+    /// Record that the token authenticated a request
+    async fn touch(&self, id: &str, used_at: DateTime<Utc>) -> Result<(), AuthDomainError>;
+}
@@ -16,4 +16,10 @@ pub trait PasswordHasher: Send + Sync {
         password: &PlainPassword,
         hash: &PasswordHash,
     ) -> Result<bool, AuthDomainError>;
+
+    /// Whether a hash was made with weaker settings than new hashes get, so
+    /// it should be replaced the next time the password is known
+    fn needs_rehash(&self, _hash: &PasswordHash) -> bool {
+        false
+    }
 }
@@ -32,11 +32,14 @@ impl From<String> for TokenId {
 pub struct RefreshToken {
     id: TokenId,
     user_id: UserId,
-    token_hash: String, // SHA256 hash of the actual token
+    token_hash: String,         // SHA256 hash of the actual token
     device_fingerprint: String, // Hash of User-Agent + IP subnet for device binding
     expires_at: DateTime<Utc>,
     created_at: DateTime<Utc>,
     revoked_at: Option<DateTime<Utc>>,
+    session_started_at: DateTime<Utc>, // Sign-in this token was rotated from; kept across refreshes
+    ip_address: Option<String>,        // Client that was issued this token
+    user_agent: Option<String>,
 }
 
 impl RefreshToken {
@@ -48,18 +51,23 @@ impl RefreshToken {
         device_fingerprint: String,
         expires_at: DateTime<Utc>,
     ) -> Self {
+        let created_at = Utc::now();
         Self {
             id,
             user_id,
             token_hash,
             device_fingerprint,
             expires_at,
-            created_at: Utc::now(),
+            created_at,
             revoked_at: None,
+            session_started_at: created_at,
+            ip_address: None,
+            user_agent: None,
         }
     }
 
     /// Reconstruct from persistence layer
+    #[allow(clippy::too_many_arguments)]
     pub fn reconstruct(
         id: TokenId,
         user_id: UserId,
@@ -68,6 +76,9 @@ impl RefreshToken {
         expires_at: DateTime<Utc>,
         created_at: DateTime<Utc>,
         revoked_at: Option<DateTime<Utc>>,
+        session_started_at: DateTime<Utc>,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
     ) -> Self {
         Self {
             id,
@@ -77,9 +88,25 @@ impl RefreshToken {
             expires_at,
             created_at,
             revoked_at,
+            session_started_at,
+            ip_address,
+            user_agent,
         }
     }
 
+    /// Record the client the token is issued to
+    pub fn with_client(mut self, ip_address: Option<String>, user_agent: Option<String>) -> Self {
+        self.ip_address = ip_address;
+        self.user_agent = user_agent;
+        self
+    }
+
+    /// Carry over the sign-in time of the token this one replaces on refresh
+    pub fn continuing_session(mut self, session_started_at: DateTime<Utc>) -> Self {
+        self.session_started_at = session_started_at;
+        self
+    }
+
     // Getters
     pub fn id(&self) -> &TokenId {
         &self.id
@@ -109,6 +136,18 @@ impl RefreshToken {
         self.revoked_at
     }
 
+    pub fn session_started_at(&self) -> DateTime<Utc> {
+        self.session_started_at
+    }
+
+    pub fn ip_address(&self) -> Option<&str> {
+        self.ip_address.as_deref()
+    }
+
+    pub fn user_agent(&self) -> Option<&str> {
+        self.user_agent.as_deref()
+    }
+
     // Domain behavior
     pub fn is_expired(&self) -> bool {
         Utc::now() > self.expires_at
@@ -160,7 +199,13 @@ mod tests {
         let fingerprint = create_test_fingerprint();
         let expires_at = Utc::now() + Duration::days(7);
 
-        let token = RefreshToken::new(token_id, user_id, token_hash.clone(), fingerprint.clone(), expires_at);
+        let token = RefreshToken::new(
+            token_id,
+            user_id,
+            token_hash.clone(),
+            fingerprint.clone(),
+            expires_at,
+        );
 
         assert_eq!(token.id().as_str(), "test-token-id");
         assert_eq!(token.user_id().as_str(), "test-user-id");
@@ -259,12 +304,17 @@ mod tests {
             expires_at,
             created_at,
             revoked_at,
+            created_at - Duration::days(1),
+            Some("203.0.113.7".to_string()),
+            None,
         );
 
         assert_eq!(token.id().as_str(), "test-token-id");
         assert_eq!(token.token_hash(), token_hash);
         assert_eq!(token.device_fingerprint(), fingerprint);
         assert_eq!(token.created_at(), created_at);
+        assert_eq!(token.session_started_at(), created_at - Duration::days(1));
+        assert_eq!(token.ip_address(), Some("203.0.113.7"));
         assert!(token.is_revoked());
     }
 
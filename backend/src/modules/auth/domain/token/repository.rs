@@ -17,6 +17,12 @@ pub trait RefreshTokenRepository: Send + Sync {
     /// Find token by hash
     async fn find_by_hash(&self, hash: &str) -> Result<Option<RefreshToken>, AuthDomainError>;
 
+    /// Find the user's unrevoked, unexpired tokens, most recently issued first
+    async fn find_active_by_user(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<RefreshToken>, AuthDomainError>;
+
     /// Revoke a specific token
     async fn revoke(&self, id: &TokenId) -> Result<(), AuthDomainError>;
 
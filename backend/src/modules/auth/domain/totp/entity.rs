@@ -0,0 +1,271 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+use crate::modules::auth::domain::user::{TotpSecret, UserId};
+
+/// SHA256 of a recovery code, the only form stored. Codes are typed by
+/// hand, so surrounding whitespace and case are ignored
+pub fn hash_one_time_secret(value: &str) -> String {
+    hash_token(&value.trim().to_lowercase())
+}
+
+/// SHA256 of a generated token (login challenge, email verification,
+/// password reset, personal access token), the only form stored.
+/// Hashed as given, since these tokens are case-sensitive
+pub fn hash_token(value: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(value.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// A user's TOTP second factor
+/// Created unconfirmed when setup starts; login only asks for a code once
+/// the user has proven their app produces them
+#[derive(Debug, Clone)]
+pub struct UserTotp {
+    user_id: UserId,
+    secret: TotpSecret,
+    recovery_code_hashes: Vec<String>, // SHA256 of each unused recovery code
+    confirmed_at: Option<DateTime<Utc>>,
+    last_used_step: Option<i64>, // Time step of the last accepted code, so it can't be replayed
+    created_at: DateTime<Utc>,
+}
+
+impl UserTotp {
+    /// Start setup with a fresh secret and recovery codes
+    pub fn new(user_id: UserId, secret: TotpSecret, recovery_code_hashes: Vec<String>) -> Self {
+        Self {
+            user_id,
+            secret,
+            recovery_code_hashes,
+            confirmed_at: None,
+            last_used_step: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Reconstruct from persistence layer
+    pub fn reconstruct(
+        user_id: UserId,
+        secret: TotpSecret,
+        recovery_code_hashes: Vec<String>,
+        confirmed_at: Option<DateTime<Utc>>,
+        last_used_step: Option<i64>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            user_id,
+            secret,
+            recovery_code_hashes,
+            confirmed_at,
+            last_used_step,
+            created_at,
+        }
+    }
+
+    // Getters
+    pub fn user_id(&self) -> &UserId {
+        &self.user_id
+    }
+
+    pub fn secret(&self) -> &TotpSecret {
+        &self.secret
+    }
+
+    pub fn recovery_code_hashes(&self) -> &[String] {
+        &self.recovery_code_hashes
+    }
+
+    pub fn confirmed_at(&self) -> Option<DateTime<Utc>> {
+        self.confirmed_at
+    }
+
+    pub fn last_used_step(&self) -> Option<i64> {
+        self.last_used_step
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    // Domain behavior
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+
+    /// Accept a code from the authenticator app, at most once per time step
+    pub fn verify_code(&mut self, code: &str, at: DateTime<Utc>) -> bool {
+        match self.secret.verify(code, at) {
+            Some(step) if self.last_used_step.is_none_or(|last| step > last) => {
+                self.last_used_step = Some(step);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Confirm setup with a valid code
+    pub fn confirm(&mut self, code: &str, at: DateTime<Utc>) -> bool {
+        if !self.verify_code(code, at) {
+            return false;
+        }
+        if self.confirmed_at.is_none() {
+            self.confirmed_at = Some(at);
+        }
+        true
+    }
+
+    /// Accept a recovery code in place of an app code; each works once
+    pub fn use_recovery_code(&mut self, code: &str) -> bool {
+        let hash = hash_one_time_secret(code);
+        let before = self.recovery_code_hashes.len();
+        self.recovery_code_hashes.retain(|h| *h != hash);
+        self.recovery_code_hashes.len() < before
+    }
+}
+
+/// A login that passed the password check and is waiting for a second factor
+#[derive(Debug, Clone)]
+pub struct TotpChallenge {
+    token_hash: String, // SHA256 of the token handed to the client
+    user_id: UserId,
+    device_fingerprint: String, // The code must come from the device that entered the password
+    expires_at: DateTime<Utc>,
+    failed_attempts: i32,
+}
+
+impl TotpChallenge {
+    /// Wrong codes a challenge tolerates before it is discarded
+    pub const MAX_FAILED_ATTEMPTS: i32 = 5;
+
+    pub fn new(
+        token_hash: String,
+        user_id: UserId,
+        device_fingerprint: String,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            token_hash,
+            user_id,
+            device_fingerprint,
+            expires_at,
+            failed_attempts: 0,
+        }
+    }
+
+    /// Reconstruct from persistence layer
+    pub fn reconstruct(
+        token_hash: String,
+        user_id: UserId,
+        device_fingerprint: String,
+        expires_at: DateTime<Utc>,
+        failed_attempts: i32,
+    ) -> Self {
+        Self {
+            token_hash,
+            user_id,
+            device_fingerprint,
+            expires_at,
+            failed_attempts,
+        }
+    }
+
+    // Getters
+    pub fn token_hash(&self) -> &str {
+        &self.token_hash
+    }
+
+    pub fn user_id(&self) -> &UserId {
+        &self.user_id
+    }
+
+    pub fn device_fingerprint(&self) -> &str {
+        &self.device_fingerprint
+    }
+
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    pub fn failed_attempts(&self) -> i32 {
+        self.failed_attempts
+    }
+
+    // Domain behavior
+    pub fn is_expired(&self) -> bool {
+        Utc::now() > self.expires_at
+    }
+
+    /// Count a wrong code; false once the challenge has used up its attempts
+    pub fn record_failure(&mut self) -> bool {
+        self.failed_attempts += 1;
+        self.failed_attempts < Self::MAX_FAILED_ATTEMPTS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn totp() -> UserTotp {
+        UserTotp::new(
+            UserId::new("user-1".to_string()),
+            TotpSecret::from_bytes(b"12345678901234567890".to_vec()),
+            vec![hash_one_time_secret("abcd-efgh")],
+        )
+    }
+
+    #[test]
+    fn test_code_accepted_once_per_step() {
+        let mut totp = totp();
+        let now = Utc::now();
+        let code = totp.secret().code_at(now);
+
+        assert!(totp.confirm(&code, now));
+        assert!(totp.is_confirmed());
+        // The same code can't be replayed within its window
+        assert!(!totp.verify_code(&code, now));
+        let next = now + Duration::seconds(30);
+        assert!(totp.verify_code(&totp.secret().code_at(next), next));
+    }
+
+    #[test]
+    fn test_wrong_code_does_not_confirm() {
+        let mut totp = totp();
+        assert!(!totp.confirm("000000x", Utc::now()));
+        assert!(!totp.is_confirmed());
+    }
+
+    #[test]
+    fn test_recovery_code_works_once() {
+        let mut totp = totp();
+        assert!(totp.use_recovery_code(" ABCD-EFGH "));
+        assert!(!totp.use_recovery_code("abcd-efgh"));
+        assert!(totp.recovery_code_hashes().is_empty());
+    }
+
+    #[test]
+    fn test_tokens_hash_case_sensitively() {
+        assert_eq!(
+            hash_one_time_secret(" ABCD-EFGH "),
+            hash_one_time_secret("abcd-efgh")
+        );
+        assert_ne!(hash_token("AbCd"), hash_token("abcd"));
+        assert_eq!(hash_token("abcd-efgh"), hash_one_time_secret("abcd-efgh"));
+    }
+
+    #[test]
+    fn test_challenge_discarded_after_max_failures() {
+        let mut challenge = TotpChallenge::new(
+            "hash".to_string(),
+            UserId::new("user-1".to_string()),
+            "fingerprint".to_string(),
+            Utc::now() + Duration::minutes(5),
+        );
+        for _ in 1..TotpChallenge::MAX_FAILED_ATTEMPTS {
+            assert!(challenge.record_failure());
+        }
+        assert!(!challenge.record_failure());
+    }
+}
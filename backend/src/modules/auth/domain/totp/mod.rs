@@ -0,0 +1,5 @@
+pub mod entity;
+pub mod repository;
+
+pub use entity::{TotpChallenge, UserTotp, hash_one_time_secret, hash_token};
+pub use repository::UserTotpRepository;
@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+
+use super::entity::{TotpChallenge, UserTotp};
+use crate::modules::auth::domain::errors::AuthDomainError;
+use crate::modules::auth::domain::user::UserId;
+
+/// Port for TOTP second factor persistence
+/// Infrastructure layer implements this with PostgreSQL, encrypting secrets at rest
+#[async_trait]
+pub trait UserTotpRepository: Send + Sync {
+    /// Find the user's TOTP, confirmed or still in setup
+    async fn find_by_user(&self, user_id: &UserId) -> Result<Option<UserTotp>, AuthDomainError>;
+
+    /// Insert or replace the user's TOTP
+    async fn save(&self, totp: &UserTotp) -> Result<(), AuthDomainError>;
+
+    /// Remove the user's TOTP and any pending login challenges
+    async fn delete(&self, user_id: &UserId) -> Result<(), AuthDomainError>;
+
+    /// Insert or update a login challenge
+    async fn save_challenge(&self, challenge: &TotpChallenge) -> Result<(), AuthDomainError>;
+
+    /// Find a login challenge by the hash of its token
+    async fn find_challenge(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<TotpChallenge>, AuthDomainError>;
+
+    /// Delete a login challenge once used or abandoned
+    async fn delete_challenge(&self, token_hash: &str) -> Result<(), AuthDomainError>;
+}
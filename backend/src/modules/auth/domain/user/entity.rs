@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 
-use super::value_objects::{DisplayName, Email, PasswordHash, UserId};
+use super::value_objects::{DisplayName, Email, PasswordHash, UserId, Username};
 use crate::modules::auth::domain::errors::AuthDomainError;
 
 /// User aggregate root
@@ -9,8 +9,11 @@ pub struct User {
     id: UserId,
     email: Email,
     password_hash: Option<PasswordHash>, // None for OAuth-only users
+    email_verified: bool,
     display_name: Option<DisplayName>,
+    username: Option<Username>,
     allow_invites: bool,
+    default_org_id: Option<String>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
     deleted_at: Option<DateTime<Utc>>,
@@ -24,8 +27,11 @@ impl User {
             id,
             email,
             password_hash: Some(password_hash),
+            email_verified: false,
             display_name: None,
+            username: None,
             allow_invites: true,
+            default_org_id: None,
             created_at: now,
             updated_at: now,
             deleted_at: None,
@@ -39,8 +45,11 @@ impl User {
             id,
             email,
             password_hash: None,
+            email_verified: true, // The provider has already checked the address
             display_name: None,
+            username: None,
             allow_invites: true,
+            default_org_id: None,
             created_at: now,
             updated_at: now,
             deleted_at: None,
@@ -48,12 +57,16 @@ impl User {
     }
 
     /// Reconstruct user from persistence layer
+    #[allow(clippy::too_many_arguments)]
     pub fn reconstruct(
         id: UserId,
         email: Email,
         password_hash: Option<PasswordHash>,
+        email_verified: bool,
         display_name: Option<DisplayName>,
+        username: Option<Username>,
         allow_invites: bool,
+        default_org_id: Option<String>,
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
         deleted_at: Option<DateTime<Utc>>,
@@ -62,8 +75,11 @@ impl User {
             id,
             email,
             password_hash,
+            email_verified,
             display_name,
+            username,
             allow_invites,
+            default_org_id,
             created_at,
             updated_at,
             deleted_at,
@@ -83,6 +99,10 @@ impl User {
         self.password_hash.as_ref()
     }
 
+    pub fn is_email_verified(&self) -> bool {
+        self.email_verified
+    }
+
     pub fn created_at(&self) -> DateTime<Utc> {
         self.created_at
     }
@@ -99,10 +119,18 @@ impl User {
         self.display_name.as_ref()
     }
 
+    pub fn username(&self) -> Option<&Username> {
+        self.username.as_ref()
+    }
+
     pub fn allow_invites(&self) -> bool {
         self.allow_invites
     }
 
+    pub fn default_org_id(&self) -> Option<&str> {
+        self.default_org_id.as_deref()
+    }
+
     pub fn is_deleted(&self) -> bool {
         self.deleted_at.is_some()
     }
@@ -116,9 +144,16 @@ impl User {
         self.display_name.is_some()
     }
 
-    /// Update the user's email
+    /// Update the user's email; the new address has to be verified again
     pub fn update_email(&mut self, new_email: Email) {
         self.email = new_email;
+        self.email_verified = false;
+        self.updated_at = Utc::now();
+    }
+
+    /// Record that the user proved they own their email address
+    pub fn mark_email_verified(&mut self) {
+        self.email_verified = true;
         self.updated_at = Utc::now();
     }
 
@@ -134,12 +169,24 @@ impl User {
         self.updated_at = Utc::now();
     }
 
+    /// Update the user's username
+    pub fn update_username(&mut self, new_username: Username) {
+        self.username = Some(new_username);
+        self.updated_at = Utc::now();
+    }
+
     /// Update the user's allow_invites setting
     pub fn update_allow_invites(&mut self, allow_invites: bool) {
         self.allow_invites = allow_invites;
         self.updated_at = Utc::now();
     }
 
+    /// Update the user's preferred default organization (None clears it)
+    pub fn update_default_org(&mut self, default_org_id: Option<String>) {
+        self.default_org_id = default_org_id;
+        self.updated_at = Utc::now();
+    }
+
     /// Soft delete the user (data retained for 30 days)
     pub fn soft_delete(&mut self) -> Result<(), AuthDomainError> {
         if self.deleted_at.is_some() {
@@ -217,10 +264,23 @@ mod tests {
         let created_at = Utc::now();
         let updated_at = Utc::now();
 
-        let user = User::reconstruct(id, email, password_hash, None, created_at, updated_at, None);
+        let user = User::reconstruct(
+            id,
+            email,
+            password_hash,
+            true,
+            None,
+            None,
+            true,
+            None,
+            created_at,
+            updated_at,
+            None,
+        );
 
         assert_eq!(user.id().as_str(), "test-user-id");
         assert!(user.has_password());
+        assert!(user.is_email_verified());
         assert_eq!(user.created_at(), created_at);
         assert_eq!(user.updated_at(), updated_at);
         assert!(!user.is_deleted());
@@ -252,4 +312,20 @@ mod tests {
             Err(AuthDomainError::UserAlreadyDeleted)
         ));
     }
+
+    #[test]
+    fn test_email_change_requires_new_verification() {
+        let mut user = User::new(
+            create_test_user_id(),
+            create_test_email(),
+            create_test_password_hash(),
+        );
+        assert!(!user.is_email_verified());
+
+        user.mark_email_verified();
+        assert!(user.is_email_verified());
+
+        user.update_email(Email::new("new@example.com".to_string()).unwrap());
+        assert!(!user.is_email_verified());
+    }
 }
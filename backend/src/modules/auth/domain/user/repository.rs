@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 
 use super::entity::User;
-use super::value_objects::{Email, UserId};
+use super::value_objects::{Email, PasswordHash, UserId, Username};
 use crate::modules::auth::domain::errors::AuthDomainError;
 
 /// Port for user persistence operations
@@ -14,9 +14,22 @@ pub trait UserRepository: Send + Sync {
     /// Find user by email
     async fn find_by_email(&self, email: &Email) -> Result<Option<User>, AuthDomainError>;
 
+    /// Find user by username
+    async fn find_by_username(&self, username: &Username) -> Result<Option<User>, AuthDomainError>;
+
     /// Save a user (insert or update)
     async fn save(&self, user: &User) -> Result<(), AuthDomainError>;
 
+    /// Replace only the user's password hash
+    async fn update_password_hash(
+        &self,
+        id: &UserId,
+        password_hash: &PasswordHash,
+    ) -> Result<(), AuthDomainError>;
+
     /// Check if email is already registered
     async fn exists_by_email(&self, email: &Email) -> Result<bool, AuthDomainError>;
+
+    /// Check if username is already taken
+    async fn exists_by_username(&self, username: &Username) -> Result<bool, AuthDomainError>;
 }
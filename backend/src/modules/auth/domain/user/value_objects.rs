@@ -1,3 +1,8 @@
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha1::Sha1;
+
 use crate::modules::auth::domain::errors::AuthDomainError;
 
 /// User ID - wrapper around UUID string
@@ -54,6 +59,49 @@ impl Email {
     }
 }
 
+/// Username - unique handle that can be used instead of email to log in
+/// 3-30 characters, ASCII letters, digits, dots, dashes, underscores; stored lowercase
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Username(String);
+
+impl Username {
+    const MIN_LENGTH: usize = 3;
+    const MAX_LENGTH: usize = 30;
+
+    pub fn new(username: String) -> Result<Self, AuthDomainError> {
+        let username = username.trim().to_lowercase();
+
+        if username.len() < Self::MIN_LENGTH || username.len() > Self::MAX_LENGTH {
+            return Err(AuthDomainError::InvalidUsername(format!(
+                "must be between {} and {} characters",
+                Self::MIN_LENGTH,
+                Self::MAX_LENGTH
+            )));
+        }
+
+        if !username.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+            return Err(AuthDomainError::InvalidUsername(
+                "must start with a letter or digit".to_string(),
+            ));
+        }
+
+        if !username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_')
+        {
+            return Err(AuthDomainError::InvalidUsername(
+                "can only contain letters, digits, dots, dashes, and underscores".to_string(),
+            ));
+        }
+
+        Ok(Self(username))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
 /// Password hash - opaque wrapper for hashed password
 /// Domain doesn't know the hashing algorithm (argon2, bcrypt, etc.)
 #[derive(Debug, Clone)]
@@ -92,9 +140,10 @@ impl DisplayName {
         }
 
         if name.chars().count() > Self::MAX_LENGTH {
-            return Err(AuthDomainError::InvalidDisplayName(
-                format!("must not exceed {} characters", Self::MAX_LENGTH),
-            ));
+            return Err(AuthDomainError::InvalidDisplayName(format!(
+                "must not exceed {} characters",
+                Self::MAX_LENGTH
+            )));
         }
 
         // Validate characters: Unicode letters, spaces, dashes, apostrophes
@@ -129,9 +178,10 @@ impl PlainPassword {
     pub fn new(password: String) -> Result<Self, AuthDomainError> {
         // Maximum length to prevent DoS via slow hashing
         if password.len() > Self::MAX_LENGTH {
-            return Err(AuthDomainError::WeakPassword(
-                format!("must not exceed {} characters", Self::MAX_LENGTH),
-            ));
+            return Err(AuthDomainError::WeakPassword(format!(
+                "must not exceed {} characters",
+                Self::MAX_LENGTH
+            )));
         }
 
         // Minimum 8 characters
@@ -189,6 +239,125 @@ impl PlainPassword {
     }
 }
 
+/// TOTP secret (RFC 6238) shared with the user's authenticator app
+/// 20 random bytes, shown to the user as unpadded base32; codes are 6 digits
+/// from HMAC-SHA1 over 30-second steps, the defaults every app understands
+#[derive(Clone, PartialEq, Eq)]
+pub struct TotpSecret(Vec<u8>);
+
+impl TotpSecret {
+    const LENGTH: usize = 20;
+    const PERIOD_SECS: i64 = 30;
+    const DIGITS: u32 = 6;
+    /// Steps either side of the current one a code may come from, for clock drift
+    const ALLOWED_DRIFT: i64 = 1;
+
+    pub fn generate() -> Self {
+        let mut bytes = vec![0u8; Self::LENGTH];
+        rand::rng().fill(&mut bytes[..]);
+        Self(bytes)
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Unpadded RFC 4648 base32, the form authenticator apps accept
+    pub fn to_base32(&self) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+        let mut encoded = String::new();
+        let (mut buffer, mut bits) = (0u32, 0);
+        for byte in &self.0 {
+            buffer = (buffer << 8) | *byte as u32;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                encoded.push(ALPHABET[((buffer >> bits) & 31) as usize] as char);
+            }
+        }
+        if bits > 0 {
+            encoded.push(ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+        }
+        encoded
+    }
+
+    /// otpauth:// URI to render as a QR code for the authenticator app
+    pub fn provisioning_uri(&self, issuer: &str, account: &str) -> String {
+        format!(
+            "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+            uri_encode(issuer),
+            uri_encode(account),
+            self.to_base32(),
+            uri_encode(issuer),
+            Self::DIGITS,
+            Self::PERIOD_SECS
+        )
+    }
+
+    /// The code for the time step containing `at`
+    pub fn code_at(&self, at: DateTime<Utc>) -> String {
+        let step = at.timestamp().div_euclid(Self::PERIOD_SECS);
+        let mut mac =
+            Hmac::<Sha1>::new_from_slice(&self.0).expect("HMAC accepts keys of any length");
+        mac.update(&step.to_be_bytes());
+        let digest = mac.finalize().into_bytes();
+
+        // Dynamic truncation (RFC 4226 section 5.3)
+        let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+        let value = u32::from_be_bytes([
+            digest[offset] & 0x7f,
+            digest[offset + 1],
+            digest[offset + 2],
+            digest[offset + 3],
+        ]);
+        format!(
+            "{:0width$}",
+            value % 10u32.pow(Self::DIGITS),
+            width = Self::DIGITS as usize
+        )
+    }
+
+    /// The time step `code` belongs to, if it is the code for the step
+    /// containing `at` or one step either side of it
+    pub fn verify(&self, code: &str, at: DateTime<Utc>) -> Option<i64> {
+        let code = code.trim();
+        (-Self::ALLOWED_DRIFT..=Self::ALLOWED_DRIFT)
+            .map(|drift| at + Duration::seconds(drift * Self::PERIOD_SECS))
+            .find(|candidate| {
+                constant_time_eq(self.code_at(*candidate).as_bytes(), code.as_bytes())
+            })
+            .map(|candidate| candidate.timestamp().div_euclid(Self::PERIOD_SECS))
+    }
+}
+
+impl std::fmt::Debug for TotpSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TotpSecret(..)")
+    }
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters
+fn uri_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Compare without returning early, so timing doesn't reveal how much matched
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,6 +377,21 @@ mod tests {
         assert!(Email::new("test@example".to_string()).is_err());
     }
 
+    #[test]
+    fn test_valid_username() {
+        let username = Username::new("  Jane.Doe_42 ".to_string()).unwrap();
+        assert_eq!(username.as_str(), "jane.doe_42");
+    }
+
+    #[test]
+    fn test_invalid_username() {
+        assert!(Username::new("ab".to_string()).is_err());
+        assert!(Username::new("a".repeat(31)).is_err());
+        assert!(Username::new("_jane".to_string()).is_err());
+        assert!(Username::new("jane doe".to_string()).is_err());
+        assert!(Username::new("jane@doe".to_string()).is_err());
+    }
+
     #[test]
     fn test_valid_password() {
         assert!(PlainPassword::new("Password1!".to_string()).is_ok());
@@ -218,19 +402,25 @@ mod tests {
     #[test]
     fn test_weak_password_too_short() {
         let result = PlainPassword::new("Pass1!".to_string());
-        assert!(matches!(result, Err(AuthDomainError::WeakPassword(msg)) if msg.contains("8 characters")));
+        assert!(
+            matches!(result, Err(AuthDomainError::WeakPassword(msg)) if msg.contains("8 characters"))
+        );
     }
 
     #[test]
     fn test_weak_password_no_uppercase() {
         let result = PlainPassword::new("password1!".to_string());
-        assert!(matches!(result, Err(AuthDomainError::WeakPassword(msg)) if msg.contains("uppercase")));
+        assert!(
+            matches!(result, Err(AuthDomainError::WeakPassword(msg)) if msg.contains("uppercase"))
+        );
     }
 
     #[test]
     fn test_weak_password_no_lowercase() {
         let result = PlainPassword::new("PASSWORD1!".to_string());
-        assert!(matches!(result, Err(AuthDomainError::WeakPassword(msg)) if msg.contains("lowercase")));
+        assert!(
+            matches!(result, Err(AuthDomainError::WeakPassword(msg)) if msg.contains("lowercase"))
+        );
     }
 
     #[test]
@@ -242,7 +432,9 @@ mod tests {
     #[test]
     fn test_weak_password_no_special_char() {
         let result = PlainPassword::new("Password1".to_string());
-        assert!(matches!(result, Err(AuthDomainError::WeakPassword(msg)) if msg.contains("special")));
+        assert!(
+            matches!(result, Err(AuthDomainError::WeakPassword(msg)) if msg.contains("special"))
+        );
     }
 
     #[test]
@@ -252,6 +444,42 @@ mod tests {
         assert!(matches!(result, Err(AuthDomainError::WeakPassword(msg)) if msg.contains("128")));
     }
 
+    /// RFC 6238 appendix B secret and times, truncated to 6 digits
+    #[test]
+    fn test_totp_codes_match_rfc_vectors() {
+        let secret = TotpSecret::from_bytes(b"12345678901234567890".to_vec());
+        let at = |secs| DateTime::from_timestamp(secs, 0).unwrap();
+
+        assert_eq!(secret.code_at(at(59)), "287082");
+        assert_eq!(secret.code_at(at(1_111_111_109)), "081804");
+        assert_eq!(secret.code_at(at(2_000_000_000)), "279037");
+        assert_eq!(secret.to_base32(), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+    }
+
+    #[test]
+    fn test_totp_verify_allows_one_step_of_drift() {
+        let secret = TotpSecret::from_bytes(b"12345678901234567890".to_vec());
+        let now = DateTime::from_timestamp(1_700_000_010, 0).unwrap();
+        let step = now.timestamp() / 30;
+
+        assert_eq!(secret.verify(&secret.code_at(now), now), Some(step));
+        let previous = secret.code_at(now - Duration::seconds(30));
+        assert_eq!(secret.verify(&previous, now), Some(step - 1));
+        let stale = secret.code_at(now - Duration::seconds(60));
+        assert_eq!(secret.verify(&stale, now), None);
+        assert_eq!(secret.verify("12345", now), None);
+    }
+
+    #[test]
+    fn test_totp_provisioning_uri() {
+        let secret = TotpSecret::from_bytes(b"12345678901234567890".to_vec());
+        assert_eq!(
+            secret.provisioning_uri("Altenia", "jane@example.com"),
+            "otpauth://totp/Altenia:jane%40example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ\
+             &issuer=Altenia&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+
     #[test]
     fn test_for_verification_skips_validation() {
         // This would fail new() validation but should work with for_verification
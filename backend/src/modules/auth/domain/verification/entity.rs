@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+
+use crate::modules::auth::domain::user::UserId;
+
+/// Token emailed to a user to prove they own their address
+/// Only its hash is stored; it is deleted once used
+#[derive(Debug, Clone)]
+pub struct VerificationToken {
+    token_hash: String, // SHA256 of the token sent in the email
+    user_id: UserId,
+    expires_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+}
+
+impl VerificationToken {
+    /// Hours a verification email stays valid
+    pub const TTL_HOURS: i64 = 24;
+
+    pub fn new(token_hash: String, user_id: UserId, expires_at: DateTime<Utc>) -> Self {
+        Self {
+            token_hash,
+            user_id,
+            expires_at,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Reconstruct from persistence layer
+    pub fn reconstruct(
+        token_hash: String,
+        user_id: UserId,
+        expires_at: DateTime<Utc>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            token_hash,
+            user_id,
+            expires_at,
+            created_at,
+        }
+    }
+
+    // Getters
+    pub fn token_hash(&self) -> &str {
+        &self.token_hash
+    }
+
+    pub fn user_id(&self) -> &UserId {
+        &self.user_id
+    }
+
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    // Domain behavior
+    pub fn is_expired(&self) -> bool {
+        Utc::now() > self.expires_at
+    }
+}
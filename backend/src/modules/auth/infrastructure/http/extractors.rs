@@ -1,15 +1,92 @@
 use axum::http::StatusCode;
 use std::sync::Arc;
 
-use crate::modules::auth::application::TokenService;
+use crate::modules::auth::application::{PersonalAccessTokenVerifier, TokenService};
+use crate::modules::auth::domain::{AuthDomainError, TokenScope};
 
-/// Authenticated user claims extracted from JWT
+/// Authenticated user claims extracted from JWT or a personal access token
 #[derive(Debug, Clone)]
 pub struct AuthClaims {
     pub user_id: String,
     pub email: String,
     pub org_id: Option<String>,
     pub org_role: Option<String>,
+    /// Scopes of the personal access token the request used; None for
+    /// dashboard sessions, which may do anything the user can
+    pub scopes: Option<Vec<TokenScope>>,
+    /// When the dashboard session was authenticated (unix seconds); None for
+    /// personal access tokens
+    pub session_started_at: Option<i64>,
+}
+
+impl AuthClaims {
+    /// Whether the request may do what `scope` guards
+    pub fn has_scope(&self, scope: TokenScope) -> bool {
+        self.scopes
+            .as_ref()
+            .is_none_or(|scopes| scopes.iter().any(|s| s.grants(scope)))
+    }
+
+    /// `has_scope` for handlers: `?` turns the error into their 403
+    pub fn require_scope(&self, scope: TokenScope) -> Result<(), MissingScope> {
+        if self.has_scope(scope) {
+            Ok(())
+        } else {
+            Err(MissingScope(scope))
+        }
+    }
+}
+
+/// A personal access token lacks the scope a route needs
+#[derive(Debug)]
+pub struct MissingScope(pub TokenScope);
+
+impl MissingScope {
+    pub const CODE: &'static str = "INSUFFICIENT_SCOPE";
+}
+
+impl std::fmt::Display for MissingScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Token is missing the {} scope", self.0.as_str())
+    }
+}
+
+/// Authenticates `Bearer pat_...` tokens. Added as an `Extension` to the
+/// routes that accept personal access tokens; elsewhere `auth_middleware`
+/// rejects them.
+#[derive(Clone)]
+pub struct PersonalAccessTokenAuth {
+    verifier: Arc<dyn PersonalAccessTokenVerifier>,
+}
+
+impl PersonalAccessTokenAuth {
+    pub fn new(verifier: Arc<dyn PersonalAccessTokenVerifier>) -> Self {
+        Self { verifier }
+    }
+
+    pub async fn authenticate(&self, token: &str) -> Result<AuthClaims, StatusCode> {
+        let identity = self
+            .verifier
+            .verify_personal_access_token(token)
+            .await
+            .map_err(|e| match e {
+                AuthDomainError::InternalError(_) => {
+                    tracing::error!(error = %e, "Failed to verify personal access token");
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+                _ => StatusCode::UNAUTHORIZED,
+            })?;
+
+        // Tokens act for the user alone, not an organization session
+        Ok(AuthClaims {
+            user_id: identity.user_id,
+            email: identity.email,
+            org_id: None,
+            org_role: None,
+            scopes: Some(identity.scopes),
+            session_started_at: None,
+        })
+    }
 }
 
 /// Application state containing token service
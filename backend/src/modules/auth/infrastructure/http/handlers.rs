@@ -1,27 +1,39 @@
+// Handlers are generic over every repository the auth service uses
+#![allow(clippy::type_complexity)]
+
 use axum::{
-    extract::State,
-    http::{header::USER_AGENT, HeaderMap, StatusCode},
     Extension, Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode, header::USER_AGENT},
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::sync::Arc;
 
 use super::extractors::AuthClaims;
+use crate::modules::auth::application::ports::{IdGenerator, TokenService};
 use crate::modules::auth::application::{
-    AuthResponse, AuthService, ChangeEmailCommand, ChangePasswordCommand, DeleteAccountCommand,
-    LoginCommand, LogoutCommand, RefreshTokenCommand, RegisterUserCommand, UpdateDisplayNameCommand,
-    UpdateSettingsCommand, UserSettingsResponse,
+    AuditEventResponse, AuthResponse, AuthService, ChangeEmailCommand, ChangePasswordCommand,
+    ClientInfo, ConfirmTotpCommand, CreatePersonalAccessTokenCommand,
+    CreatedPersonalAccessTokenResponse, DeleteAccountCommand, DisableTotpCommand,
+    EnableTotpCommand, ListAuditEventsCommand, LoginCommand, LoginResult, LogoutCommand,
+    PersonalAccessTokenResponse, RefreshTokenCommand, RegisterUserCommand,
+    RequestPasswordResetCommand, ResendVerificationCommand, ResetPasswordCommand,
+    RevokeOtherSessionsCommand, RevokePersonalAccessTokenCommand, RevokeSessionCommand,
+    SessionResponse, TotpSetupResponse, UpdateDisplayNameCommand, UpdateSettingsCommand,
+    UserSettingsResponse, VerifyEmailCommand, VerifyTotpCommand,
 };
 use crate::modules::auth::domain::{
-    AuthDomainError, PasswordHasher, RefreshTokenRepository, UserRepository,
+    AuthAuditRepository, AuthDomainError, PasswordHasher, RefreshTokenRepository, UserRepository,
+    UserTotpRepository,
 };
-use crate::modules::auth::application::ports::{IdGenerator, TokenService};
 use crate::modules::organizations::domain::{OrganizationMemberRepository, OrganizationRepository};
+use crate::shared::PAGINATION_LIMIT_HEADER;
 
 /// Generate device fingerprint from User-Agent and X-Forwarded-For headers
 /// Uses /24 subnet for IPv4 to allow for NAT variations
-fn generate_device_fingerprint(headers: &HeaderMap) -> String {
+pub(crate) fn generate_device_fingerprint(headers: &HeaderMap) -> String {
     let user_agent = headers
         .get(USER_AGENT)
         .and_then(|h| h.to_str().ok())
@@ -57,6 +69,25 @@ fn generate_device_fingerprint(headers: &HeaderMap) -> String {
     format!("{:x}", hasher.finalize())
 }
 
+/// Client IP and User-Agent for audit records
+pub(crate) fn client_info(headers: &HeaderMap) -> ClientInfo {
+    let ip_address = headers
+        .get("X-Forwarded-For")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| s.split(',').next())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    let user_agent = headers
+        .get(USER_AGENT)
+        .and_then(|h| h.to_str().ok())
+        .map(String::from);
+
+    ClientInfo {
+        ip_address,
+        user_agent,
+    }
+}
+
 // ============================================================================
 // Request/Response DTOs for HTTP layer
 // ============================================================================
@@ -64,15 +95,52 @@ fn generate_device_fingerprint(headers: &HeaderMap) -> String {
 #[derive(Debug, Deserialize)]
 pub struct RegisterRequest {
     pub email: String,
+    pub username: Option<String>,
     pub password: String,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct LoginRequest {
-    pub email: String,
+    /// Email or username
+    #[serde(alias = "email", alias = "username")]
+    pub identifier: String,
     pub password: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PasswordResetRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfirmPasswordResetRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyTotpRequest {
+    pub challenge_token: String,
+    /// Code from the authenticator app, or a recovery code
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfirmTotpRequest {
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DisableTotpRequest {
+    pub current_password: Option<String>,
+    pub code: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct RefreshRequest {
     pub refresh_token: String,
@@ -83,6 +151,21 @@ pub struct LogoutRequest {
     pub refresh_token: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RevokeOtherSessionsRequest {
+    /// Refresh token of the session to keep signed in
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePersonalAccessTokenRequest {
+    pub name: String,
+    /// e.g. "orgs:read", "projects:write"
+    pub scopes: Vec<String>,
+    /// Omit for a token that never expires
+    pub expires_in_days: Option<i64>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ChangeEmailRequest {
     pub current_password: String,
@@ -108,17 +191,122 @@ pub struct UpdateDisplayNameRequest {
 #[derive(Debug, Deserialize)]
 pub struct UpdateSettingsRequest {
     pub allow_invites: Option<bool>,
+    /// Preferred org on login; an empty string clears it
+    pub default_org_id: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct SettingsResponseDto {
     pub allow_invites: bool,
+    pub default_org_id: Option<String>,
 }
 
 impl From<UserSettingsResponse> for SettingsResponseDto {
     fn from(r: UserSettingsResponse) -> Self {
         Self {
             allow_invites: r.allow_invites,
+            default_org_id: r.default_org_id,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListAuditEventsQuery {
+    pub user_id: Option<String>,
+    pub event_type: Option<String>,
+    pub success: Option<bool>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditEventResponseDto {
+    pub id: String,
+    pub event_type: String,
+    pub success: bool,
+    pub user_id: Option<String>,
+    pub identifier: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub failure_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AuditEventResponse> for AuditEventResponseDto {
+    fn from(r: AuditEventResponse) -> Self {
+        Self {
+            id: r.id,
+            event_type: r.event_type,
+            success: r.success,
+            user_id: r.user_id,
+            identifier: r.identifier,
+            ip_address: r.ip_address,
+            user_agent: r.user_agent,
+            failure_reason: r.failure_reason,
+            created_at: r.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionResponseDto {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl From<SessionResponse> for SessionResponseDto {
+    fn from(r: SessionResponse) -> Self {
+        Self {
+            id: r.id,
+            created_at: r.created_at,
+            last_used_at: r.last_used_at,
+            ip_address: r.ip_address,
+            user_agent: r.user_agent,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PersonalAccessTokenResponseDto {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<PersonalAccessTokenResponse> for PersonalAccessTokenResponseDto {
+    fn from(r: PersonalAccessTokenResponse) -> Self {
+        Self {
+            id: r.id,
+            name: r.name,
+            scopes: r.scopes,
+            expires_at: r.expires_at,
+            last_used_at: r.last_used_at,
+            created_at: r.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatedPersonalAccessTokenResponseDto {
+    /// Shown only once; only its hash is kept
+    pub token: String,
+    #[serde(flatten)]
+    pub details: PersonalAccessTokenResponseDto,
+}
+
+impl From<CreatedPersonalAccessTokenResponse> for CreatedPersonalAccessTokenResponseDto {
+    fn from(r: CreatedPersonalAccessTokenResponse) -> Self {
+        Self {
+            token: r.token,
+            details: r.details.into(),
         }
     }
 }
@@ -127,18 +315,65 @@ impl From<UserSettingsResponse> for SettingsResponseDto {
 pub struct AuthResponseDto {
     pub user_id: String,
     pub email: String,
+    pub username: Option<String>,
     pub display_name: Option<String>,
+    pub default_org_id: Option<String>,
     pub access_token: String,
     pub refresh_token: String,
     pub token_type: String,
     pub expires_in: i64,
 }
 
+/// Login response: tokens, or a challenge when the account has 2FA
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum LoginResponseDto {
+    Authenticated(AuthResponseDto),
+    TotpRequired {
+        totp_required: bool,
+        challenge_token: String,
+        expires_in: i64,
+    },
+}
+
+impl From<LoginResult> for LoginResponseDto {
+    fn from(r: LoginResult) -> Self {
+        match r {
+            LoginResult::Authenticated(response) => Self::Authenticated(response.into()),
+            LoginResult::TotpRequired(challenge) => Self::TotpRequired {
+                totp_required: true,
+                challenge_token: challenge.challenge_token,
+                expires_in: challenge.expires_in,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct TotpSetupResponseDto {
+    pub secret: String,
+    pub otpauth_uri: String,
+    pub recovery_codes: Vec<String>,
+}
+
+impl From<TotpSetupResponse> for TotpSetupResponseDto {
+    fn from(r: TotpSetupResponse) -> Self {
+        Self {
+            secret: r.secret,
+            otpauth_uri: r.otpauth_uri,
+            recovery_codes: r.recovery_codes,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct UserResponseDto {
     pub id: String,
     pub email: String,
+    pub email_verified: bool,
+    pub username: Option<String>,
     pub display_name: Option<String>,
+    pub default_org_id: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -152,7 +387,9 @@ impl From<AuthResponse> for AuthResponseDto {
         Self {
             user_id: r.user_id,
             email: r.email,
+            username: r.username,
             display_name: r.display_name,
+            default_org_id: r.default_org_id,
             access_token: r.access_token,
             refresh_token: r.refresh_token,
             token_type: r.token_type,
@@ -165,11 +402,13 @@ impl From<AuthResponse> for AuthResponseDto {
 // Error handling
 // ============================================================================
 
-fn to_error_response(e: AuthDomainError) -> (StatusCode, Json<ErrorResponse>) {
+pub(crate) fn to_error_response(e: AuthDomainError) -> (StatusCode, Json<ErrorResponse>) {
     match e {
         AuthDomainError::InvalidEmail(_)
         | AuthDomainError::InvalidPassword(_)
-        | AuthDomainError::InvalidDisplayName(_) => (
+        | AuthDomainError::InvalidDisplayName(_)
+        | AuthDomainError::InvalidUsername(_)
+        | AuthDomainError::InvalidAuditEventType(_) => (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 error: e.to_string(),
@@ -211,6 +450,13 @@ fn to_error_response(e: AuthDomainError) -> (StatusCode, Json<ErrorResponse>) {
                 code: "INVALID_CREDENTIALS".to_string(),
             }),
         ),
+        AuthDomainError::SsoRequired => (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "SSO_REQUIRED".to_string(),
+            }),
+        ),
         AuthDomainError::NoPasswordSet => (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
@@ -225,6 +471,150 @@ fn to_error_response(e: AuthDomainError) -> (StatusCode, Json<ErrorResponse>) {
                 code: "EMAIL_IN_USE".to_string(),
             }),
         ),
+        AuthDomainError::UsernameAlreadyTaken => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "Username is already taken".to_string(),
+                code: "USERNAME_TAKEN".to_string(),
+            }),
+        ),
+        AuthDomainError::InvalidDefaultOrg => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "INVALID_DEFAULT_ORG".to_string(),
+            }),
+        ),
+        AuthDomainError::InsufficientPermissions => (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "FORBIDDEN".to_string(),
+            }),
+        ),
+        AuthDomainError::EmailAlreadyVerified => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "EMAIL_ALREADY_VERIFIED".to_string(),
+            }),
+        ),
+        AuthDomainError::EmailVerificationUnavailable => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "EMAIL_VERIFICATION_UNAVAILABLE".to_string(),
+            }),
+        ),
+        AuthDomainError::PasswordResetUnavailable => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "PASSWORD_RESET_UNAVAILABLE".to_string(),
+            }),
+        ),
+        AuthDomainError::SessionNotFound => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "SESSION_NOT_FOUND".to_string(),
+            }),
+        ),
+        AuthDomainError::OAuthUnavailable => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "OAUTH_UNAVAILABLE".to_string(),
+            }),
+        ),
+        AuthDomainError::UnsupportedOAuthProvider(_) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "OAUTH_PROVIDER_NOT_FOUND".to_string(),
+            }),
+        ),
+        AuthDomainError::InvalidOAuthState => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "INVALID_OAUTH_STATE".to_string(),
+            }),
+        ),
+        AuthDomainError::OAuthEmailUnverified => (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "OAUTH_EMAIL_UNVERIFIED".to_string(),
+            }),
+        ),
+        AuthDomainError::OAuthAccountConflict => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "OAUTH_ACCOUNT_CONFLICT".to_string(),
+            }),
+        ),
+        AuthDomainError::OAuthProviderError(ref msg) => {
+            tracing::warn!(error = %msg, "OAuth provider request failed");
+            (
+                StatusCode::BAD_GATEWAY,
+                Json(ErrorResponse {
+                    error: "The sign-in provider could not be reached. Please try again."
+                        .to_string(),
+                    code: "OAUTH_PROVIDER_ERROR".to_string(),
+                }),
+            )
+        }
+        AuthDomainError::InvalidTokenScope(_) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "INVALID_TOKEN_SCOPE".to_string(),
+            }),
+        ),
+        AuthDomainError::InvalidPersonalAccessToken(_) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "INVALID_PERSONAL_ACCESS_TOKEN".to_string(),
+            }),
+        ),
+        AuthDomainError::PersonalAccessTokensUnavailable => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "PERSONAL_ACCESS_TOKENS_UNAVAILABLE".to_string(),
+            }),
+        ),
+        AuthDomainError::PersonalAccessTokenNotFound => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "PERSONAL_ACCESS_TOKEN_NOT_FOUND".to_string(),
+            }),
+        ),
+        AuthDomainError::TotpAlreadyEnabled => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "TOTP_ALREADY_ENABLED".to_string(),
+            }),
+        ),
+        AuthDomainError::TotpNotEnabled => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "TOTP_NOT_ENABLED".to_string(),
+            }),
+        ),
+        AuthDomainError::InvalidTotpCode => (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "INVALID_TOTP_CODE".to_string(),
+            }),
+        ),
         AuthDomainError::TokenExpired => (
             StatusCode::UNAUTHORIZED,
             Json(ErrorResponse {
@@ -257,8 +647,8 @@ fn to_error_response(e: AuthDomainError) -> (StatusCode, Json<ErrorResponse>) {
 // ============================================================================
 
 /// POST /api/auth/register
-pub async fn register<U, T, P, TS, ID, OR, MR>(
-    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR>>>,
+pub async fn register<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
     headers: HeaderMap,
     Json(req): Json<RegisterRequest>,
 ) -> Result<Json<AuthResponseDto>, (StatusCode, Json<ErrorResponse>)>
@@ -270,13 +660,17 @@ where
     ID: IdGenerator,
     OR: OrganizationRepository,
     MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
 {
     let device_fingerprint = generate_device_fingerprint(&headers);
 
     let cmd = RegisterUserCommand {
         email: req.email,
+        username: req.username,
         password: req.password,
         device_fingerprint,
+        client: client_info(&headers),
     };
 
     auth_service
@@ -287,11 +681,11 @@ where
 }
 
 /// POST /api/auth/login
-pub async fn login<U, T, P, TS, ID, OR, MR>(
-    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR>>>,
+pub async fn login<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
     headers: HeaderMap,
     Json(req): Json<LoginRequest>,
-) -> Result<Json<AuthResponseDto>, (StatusCode, Json<ErrorResponse>)>
+) -> Result<Json<LoginResponseDto>, (StatusCode, Json<ErrorResponse>)>
 where
     U: UserRepository,
     T: RefreshTokenRepository,
@@ -300,13 +694,16 @@ where
     ID: IdGenerator,
     OR: OrganizationRepository,
     MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
 {
     let device_fingerprint = generate_device_fingerprint(&headers);
 
     let cmd = LoginCommand {
-        email: req.email,
+        identifier: req.identifier,
         password: req.password,
         device_fingerprint,
+        client: client_info(&headers),
     };
 
     auth_service
@@ -316,9 +713,131 @@ where
         .map_err(to_error_response)
 }
 
+/// POST /api/auth/login/totp
+pub async fn verify_totp<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
+    headers: HeaderMap,
+    Json(req): Json<VerifyTotpRequest>,
+) -> Result<Json<AuthResponseDto>, (StatusCode, Json<ErrorResponse>)>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    let device_fingerprint = generate_device_fingerprint(&headers);
+
+    let cmd = VerifyTotpCommand {
+        challenge_token: req.challenge_token,
+        code: req.code,
+        device_fingerprint,
+        client: client_info(&headers),
+    };
+
+    auth_service
+        .verify_totp(cmd)
+        .await
+        .map(|r| Json(r.into()))
+        .map_err(to_error_response)
+}
+
+/// POST /api/auth/verify-email
+pub async fn verify_email<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
+    headers: HeaderMap,
+    Json(req): Json<VerifyEmailRequest>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    let cmd = VerifyEmailCommand {
+        token: req.token,
+        client: client_info(&headers),
+    };
+
+    auth_service
+        .verify_email(cmd)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(to_error_response)
+}
+
+/// POST /api/auth/password-reset/request
+/// Accepted whether or not the email belongs to an account
+pub async fn request_password_reset<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
+    headers: HeaderMap,
+    Json(req): Json<PasswordResetRequest>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    let cmd = RequestPasswordResetCommand {
+        email: req.email,
+        client: client_info(&headers),
+    };
+
+    auth_service
+        .request_password_reset(cmd)
+        .await
+        .map(|_| StatusCode::ACCEPTED)
+        .map_err(to_error_response)
+}
+
+/// POST /api/auth/password-reset/confirm
+pub async fn confirm_password_reset<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
+    headers: HeaderMap,
+    Json(req): Json<ConfirmPasswordResetRequest>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    let cmd = ResetPasswordCommand {
+        token: req.token,
+        new_password: req.new_password,
+        client: client_info(&headers),
+    };
+
+    auth_service
+        .reset_password(cmd)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(to_error_response)
+}
+
 /// POST /api/auth/refresh
-pub async fn refresh<U, T, P, TS, ID, OR, MR>(
-    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR>>>,
+pub async fn refresh<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
     headers: HeaderMap,
     Json(req): Json<RefreshRequest>,
 ) -> Result<Json<AuthResponseDto>, (StatusCode, Json<ErrorResponse>)>
@@ -330,12 +849,15 @@ where
     ID: IdGenerator,
     OR: OrganizationRepository,
     MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
 {
     let device_fingerprint = generate_device_fingerprint(&headers);
 
     let cmd = RefreshTokenCommand {
         refresh_token: req.refresh_token,
         device_fingerprint,
+        client: client_info(&headers),
     };
 
     auth_service
@@ -346,9 +868,10 @@ where
 }
 
 /// POST /api/auth/logout (protected)
-pub async fn logout<U, T, P, TS, ID, OR, MR>(
-    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR>>>,
+pub async fn logout<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
     Extension(claims): Extension<AuthClaims>,
+    headers: HeaderMap,
     Json(req): Json<LogoutRequest>,
 ) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
 where
@@ -359,10 +882,13 @@ where
     ID: IdGenerator,
     OR: OrganizationRepository,
     MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
 {
     let cmd = LogoutCommand {
         user_id: claims.user_id,
         refresh_token: req.refresh_token,
+        client: client_info(&headers),
     };
 
     auth_service
@@ -372,9 +898,185 @@ where
         .map_err(to_error_response)
 }
 
+/// GET /api/auth/sessions (protected)
+pub async fn list_sessions<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
+    Extension(claims): Extension<AuthClaims>,
+) -> Result<Json<Vec<SessionResponseDto>>, (StatusCode, Json<ErrorResponse>)>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    auth_service
+        .list_sessions(&claims.user_id)
+        .await
+        .map(|sessions| Json(sessions.into_iter().map(|s| s.into()).collect()))
+        .map_err(to_error_response)
+}
+
+/// DELETE /api/auth/sessions/{id} (protected)
+pub async fn revoke_session<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(session_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    let cmd = RevokeSessionCommand {
+        user_id: claims.user_id,
+        session_id,
+        client: client_info(&headers),
+    };
+
+    auth_service
+        .revoke_session(cmd)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(to_error_response)
+}
+
+/// DELETE /api/auth/sessions (protected)
+/// Signs out every session except the one whose refresh token is sent
+pub async fn revoke_other_sessions<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
+    Extension(claims): Extension<AuthClaims>,
+    headers: HeaderMap,
+    Json(req): Json<RevokeOtherSessionsRequest>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    let cmd = RevokeOtherSessionsCommand {
+        user_id: claims.user_id,
+        refresh_token: req.refresh_token,
+        client: client_info(&headers),
+    };
+
+    auth_service
+        .revoke_other_sessions(cmd)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(to_error_response)
+}
+
+/// GET /api/auth/me/tokens (protected)
+pub async fn list_personal_access_tokens<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
+    Extension(claims): Extension<AuthClaims>,
+) -> Result<Json<Vec<PersonalAccessTokenResponseDto>>, (StatusCode, Json<ErrorResponse>)>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    auth_service
+        .list_personal_access_tokens(&claims.user_id)
+        .await
+        .map(|tokens| Json(tokens.into_iter().map(|t| t.into()).collect()))
+        .map_err(to_error_response)
+}
+
+/// POST /api/auth/me/tokens (protected)
+pub async fn create_personal_access_token<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
+    Extension(claims): Extension<AuthClaims>,
+    headers: HeaderMap,
+    Json(req): Json<CreatePersonalAccessTokenRequest>,
+) -> Result<
+    (StatusCode, Json<CreatedPersonalAccessTokenResponseDto>),
+    (StatusCode, Json<ErrorResponse>),
+>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    let cmd = CreatePersonalAccessTokenCommand {
+        user_id: claims.user_id,
+        name: req.name,
+        scopes: req.scopes,
+        expires_in_days: req.expires_in_days,
+        client: client_info(&headers),
+    };
+
+    auth_service
+        .create_personal_access_token(cmd)
+        .await
+        .map(|r| (StatusCode::CREATED, Json(r.into())))
+        .map_err(to_error_response)
+}
+
+/// DELETE /api/auth/me/tokens/{id} (protected)
+pub async fn revoke_personal_access_token<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(token_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    let cmd = RevokePersonalAccessTokenCommand {
+        user_id: claims.user_id,
+        token_id,
+        client: client_info(&headers),
+    };
+
+    auth_service
+        .revoke_personal_access_token(cmd)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(to_error_response)
+}
+
 /// GET /api/auth/me (protected)
-pub async fn me<U, T, P, TS, ID, OR, MR>(
-    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR>>>,
+pub async fn me<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
     Extension(claims): Extension<AuthClaims>,
 ) -> Result<Json<UserResponseDto>, (StatusCode, Json<ErrorResponse>)>
 where
@@ -385,6 +1087,8 @@ where
     ID: IdGenerator,
     OR: OrganizationRepository,
     MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
 {
     auth_service
         .get_current_user(&claims.user_id)
@@ -393,16 +1097,20 @@ where
             Json(UserResponseDto {
                 id: user.id().as_str().to_string(),
                 email: user.email().as_str().to_string(),
+                email_verified: user.is_email_verified(),
+                username: user.username().map(|u| u.as_str().to_string()),
                 display_name: user.display_name().map(|d| d.as_str().to_string()),
+                default_org_id: user.default_org_id().map(String::from),
             })
         })
         .map_err(to_error_response)
 }
 
 /// PATCH /api/auth/me/email (protected)
-pub async fn change_email<U, T, P, TS, ID, OR, MR>(
-    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR>>>,
+pub async fn change_email<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
     Extension(claims): Extension<AuthClaims>,
+    headers: HeaderMap,
     Json(req): Json<ChangeEmailRequest>,
 ) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
 where
@@ -413,11 +1121,14 @@ where
     ID: IdGenerator,
     OR: OrganizationRepository,
     MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
 {
     let cmd = ChangeEmailCommand {
         user_id: claims.user_id,
         current_password: req.current_password,
         new_email: req.new_email,
+        client: client_info(&headers),
     };
 
     auth_service
@@ -428,9 +1139,10 @@ where
 }
 
 /// PATCH /api/auth/me/password (protected)
-pub async fn change_password<U, T, P, TS, ID, OR, MR>(
-    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR>>>,
+pub async fn change_password<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
     Extension(claims): Extension<AuthClaims>,
+    headers: HeaderMap,
     Json(req): Json<ChangePasswordRequest>,
 ) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
 where
@@ -441,11 +1153,14 @@ where
     ID: IdGenerator,
     OR: OrganizationRepository,
     MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
 {
     let cmd = ChangePasswordCommand {
         user_id: claims.user_id,
         current_password: req.current_password,
         new_password: req.new_password,
+        client: client_info(&headers),
     };
 
     auth_service
@@ -456,8 +1171,8 @@ where
 }
 
 /// PATCH /api/auth/me/display-name (protected)
-pub async fn update_display_name<U, T, P, TS, ID, OR, MR>(
-    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR>>>,
+pub async fn update_display_name<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
     Extension(claims): Extension<AuthClaims>,
     Json(req): Json<UpdateDisplayNameRequest>,
 ) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
@@ -469,6 +1184,8 @@ where
     ID: IdGenerator,
     OR: OrganizationRepository,
     MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
 {
     let cmd = UpdateDisplayNameCommand {
         user_id: claims.user_id,
@@ -483,9 +1200,10 @@ where
 }
 
 /// DELETE /api/auth/me (protected)
-pub async fn delete_account<U, T, P, TS, ID, OR, MR>(
-    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR>>>,
+pub async fn delete_account<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
     Extension(claims): Extension<AuthClaims>,
+    headers: HeaderMap,
     Json(req): Json<DeleteAccountRequest>,
 ) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
 where
@@ -496,10 +1214,13 @@ where
     ID: IdGenerator,
     OR: OrganizationRepository,
     MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
 {
     let cmd = DeleteAccountCommand {
         user_id: claims.user_id,
         current_password: req.current_password,
+        client: client_info(&headers),
     };
 
     auth_service
@@ -509,9 +1230,126 @@ where
         .map_err(to_error_response)
 }
 
+/// POST /api/auth/me/totp (protected)
+pub async fn enable_totp<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
+    Extension(claims): Extension<AuthClaims>,
+) -> Result<Json<TotpSetupResponseDto>, (StatusCode, Json<ErrorResponse>)>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    let cmd = EnableTotpCommand {
+        user_id: claims.user_id,
+    };
+
+    auth_service
+        .enable_totp(cmd)
+        .await
+        .map(|r| Json(r.into()))
+        .map_err(to_error_response)
+}
+
+/// POST /api/auth/me/verify-email (protected)
+pub async fn resend_verification<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
+    Extension(claims): Extension<AuthClaims>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    let cmd = ResendVerificationCommand {
+        user_id: claims.user_id,
+    };
+
+    auth_service
+        .resend_verification(cmd)
+        .await
+        .map(|_| StatusCode::ACCEPTED)
+        .map_err(to_error_response)
+}
+
+/// POST /api/auth/me/totp/confirm (protected)
+pub async fn confirm_totp<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
+    Extension(claims): Extension<AuthClaims>,
+    headers: HeaderMap,
+    Json(req): Json<ConfirmTotpRequest>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    let cmd = ConfirmTotpCommand {
+        user_id: claims.user_id,
+        code: req.code,
+        client: client_info(&headers),
+    };
+
+    auth_service
+        .confirm_totp(cmd)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(to_error_response)
+}
+
+/// DELETE /api/auth/me/totp (protected)
+pub async fn disable_totp<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
+    Extension(claims): Extension<AuthClaims>,
+    headers: HeaderMap,
+    Json(req): Json<DisableTotpRequest>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    let cmd = DisableTotpCommand {
+        user_id: claims.user_id,
+        current_password: req.current_password,
+        code: req.code,
+        client: client_info(&headers),
+    };
+
+    auth_service
+        .disable_totp(cmd)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(to_error_response)
+}
+
 /// GET /api/auth/me/settings (protected)
-pub async fn get_settings<U, T, P, TS, ID, OR, MR>(
-    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR>>>,
+pub async fn get_settings<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
     Extension(claims): Extension<AuthClaims>,
 ) -> Result<Json<SettingsResponseDto>, (StatusCode, Json<ErrorResponse>)>
 where
@@ -522,6 +1360,8 @@ where
     ID: IdGenerator,
     OR: OrganizationRepository,
     MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
 {
     auth_service
         .get_settings(&claims.user_id)
@@ -531,8 +1371,8 @@ where
 }
 
 /// PATCH /api/auth/me/settings (protected)
-pub async fn update_settings<U, T, P, TS, ID, OR, MR>(
-    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR>>>,
+pub async fn update_settings<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
     Extension(claims): Extension<AuthClaims>,
     Json(req): Json<UpdateSettingsRequest>,
 ) -> Result<Json<SettingsResponseDto>, (StatusCode, Json<ErrorResponse>)>
@@ -544,10 +1384,13 @@ where
     ID: IdGenerator,
     OR: OrganizationRepository,
     MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
 {
     let cmd = UpdateSettingsCommand {
         user_id: claims.user_id,
         allow_invites: req.allow_invites,
+        default_org_id: req.default_org_id,
     };
 
     auth_service
@@ -556,3 +1399,50 @@ where
         .map(|settings| Json(settings.into()))
         .map_err(to_error_response)
 }
+
+/// GET /api/auth/audit-events (protected, org admins only)
+pub async fn list_audit_events<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State(auth_service): State<Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Query(query): Query<ListAuditEventsQuery>,
+) -> Result<
+    (
+        [(&'static str, String); 1],
+        Json<Vec<AuditEventResponseDto>>,
+    ),
+    (StatusCode, Json<ErrorResponse>),
+>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    let cmd = ListAuditEventsCommand {
+        requesting_user_id: claims.user_id,
+        org_id: claims.org_id,
+        user_id: query.user_id,
+        event_type: query.event_type,
+        success: query.success,
+        start_time: query.start_time,
+        end_time: query.end_time,
+        limit: query.limit,
+        offset: query.offset,
+    };
+
+    auth_service
+        .list_audit_events(cmd)
+        .await
+        .map(|page| {
+            (
+                [(PAGINATION_LIMIT_HEADER, page.limit.to_string())],
+                Json(page.events.into_iter().map(|e| e.into()).collect()),
+            )
+        })
+        .map_err(to_error_response)
+}
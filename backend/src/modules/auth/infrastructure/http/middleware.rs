@@ -1,34 +1,150 @@
 use axum::{
     body::Body,
-    extract::State,
-    http::{header::AUTHORIZATION, Request, StatusCode},
+    extract::{MatchedPath, OriginalUri, State},
+    http::{Request, StatusCode, header::AUTHORIZATION},
     middleware::Next,
     response::Response,
 };
+use ipnet::IpNet;
+use std::net::IpAddr;
 use std::sync::Arc;
 
-use super::extractors::AuthClaims;
+use super::extractors::{AuthClaims, PersonalAccessTokenAuth};
 use crate::modules::auth::application::TokenService;
+use crate::modules::auth::domain::PersonalAccessToken;
+use crate::modules::organizations::domain::{OrgId, OrganizationRepository};
+use crate::modules::projects::domain::{ProjectId, ProjectRepository};
+use crate::shared::client_ip;
+
+/// Enforces dashboard IP allowlists: that of the organization a request's
+/// token is scoped to, and that of the organization owning the org or project
+/// named in the request path. Added to the app as an `Extension`;
+/// `auth_middleware` skips the check when it's absent.
+#[derive(Clone)]
+pub struct OrgIpAllowlist {
+    org_repo: Arc<dyn OrganizationRepository>,
+    project_repo: Arc<dyn ProjectRepository>,
+    trusted_proxies: Arc<[IpNet]>,
+}
+
+/// The resource a dashboard route acts on, taken from its path parameters
+enum PathResource {
+    Org(String),
+    Project(String),
+}
+
+impl PathResource {
+    /// Find the first `/orgs/{..}` or `/projects/{..}` parameter in the route
+    fn from_request(req: &Request<Body>) -> Option<Self> {
+        let route = req.extensions().get::<MatchedPath>()?.as_str();
+        // The matched route includes any `nest` prefix, so pair it with the unstripped URI
+        let path = req
+            .extensions()
+            .get::<OriginalUri>()
+            .map_or_else(|| req.uri().path(), |uri| uri.path());
+        let mut segments = route.split('/').zip(path.split('/')).peekable();
+        while let Some((literal, _)) = segments.next() {
+            let Some((param, value)) = segments.peek() else {
+                break;
+            };
+            if !param.starts_with('{') {
+                continue;
+            }
+            match literal {
+                "orgs" => return Some(Self::Org(value.to_string())),
+                "projects" => return Some(Self::Project(value.to_string())),
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+impl OrgIpAllowlist {
+    pub fn new(
+        org_repo: Arc<dyn OrganizationRepository>,
+        project_repo: Arc<dyn ProjectRepository>,
+        trusted_proxies: Vec<IpNet>,
+    ) -> Self {
+        Self {
+            org_repo,
+            project_repo,
+            trusted_proxies: trusted_proxies.into(),
+        }
+    }
+
+    /// Check the token's organization and the one owning the path resource
+    async fn check_request(
+        &self,
+        source: Option<IpAddr>,
+        resource: Option<PathResource>,
+        token_org_id: Option<&str>,
+    ) -> Result<(), StatusCode> {
+        if let Some(org_id) = token_org_id {
+            self.check(org_id, source).await?;
+        }
+
+        let path_org_id = match resource {
+            Some(PathResource::Org(org_id)) => Some(org_id),
+            Some(PathResource::Project(project_id)) => self
+                .project_repo
+                .find_by_id(&ProjectId::new(project_id))
+                .await
+                .map_err(|e| {
+                    tracing::error!(error = %e, "Failed to load project for IP allowlist");
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?
+                .map(|project| project.organization_id().as_str().to_string()),
+            None => None,
+        };
+        match path_org_id {
+            Some(org_id) if Some(org_id.as_str()) != token_org_id => {
+                self.check(&org_id, source).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn check(&self, org_id: &str, source: Option<IpAddr>) -> Result<(), StatusCode> {
+        let org = self
+            .org_repo
+            .find_by_id(&OrgId::new(org_id.to_string()))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, "Failed to load organization IP allowlist");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        // A missing organization is left to the handlers to report
+        let Some(org) = org else {
+            return Ok(());
+        };
+
+        org.ensure_ip_allowed(source).map_err(|e| {
+            tracing::warn!(org_id, error = %e, "Dashboard request rejected by IP allowlist");
+            StatusCode::FORBIDDEN
+        })
+    }
+}
 
 /// Extract token from query string (for SSE which doesn't support headers)
 fn extract_token_from_query(uri: &axum::http::Uri) -> Option<String> {
     uri.query().and_then(|query| {
-        query.split('&')
-            .find_map(|pair| {
-                let mut parts = pair.splitn(2, '=');
-                let key = parts.next()?;
-                let value = parts.next()?;
-                if key == "token" {
-                    Some(value.to_string())
-                } else {
-                    None
-                }
-            })
+        query.split('&').find_map(|pair| {
+            let mut parts = pair.splitn(2, '=');
+            let key = parts.next()?;
+            let value = parts.next()?;
+            if key == "token" {
+                Some(value.to_string())
+            } else {
+                None
+            }
+        })
     })
 }
 
 /// Authentication middleware - validates JWT and injects AuthClaims into request
-/// Supports both Authorization header and query parameter (for SSE endpoints)
+/// Supports both Authorization header and query parameter (for SSE endpoints).
+/// Personal access tokens are accepted only where `PersonalAccessTokenAuth` is layered.
 pub async fn auth_middleware<TS: TokenService + 'static>(
     State(token_service): State<Arc<TS>>,
     mut req: Request<Body>,
@@ -45,18 +161,252 @@ pub async fn auth_middleware<TS: TokenService + 'static>(
         .or_else(|| extract_token_from_query(req.uri()))
         .ok_or(StatusCode::UNAUTHORIZED)?;
 
-    // Validate token
-    let claims = token_service
-        .validate_access_token(&token)
-        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let claims = if token.starts_with(PersonalAccessToken::PREFIX) {
+        let pat_auth = req
+            .extensions()
+            .get::<PersonalAccessTokenAuth>()
+            .cloned()
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+        pat_auth.authenticate(&token).await?
+    } else {
+        // Validate token
+        let claims = token_service
+            .validate_access_token(&token)
+            .map_err(|_| StatusCode::UNAUTHORIZED)?;
+        AuthClaims {
+            user_id: claims.user_id,
+            email: claims.email,
+            org_id: claims.org_id,
+            org_role: claims.org_role,
+            scopes: None,
+            session_started_at: Some(claims.auth_time),
+        }
+    };
+
+    // Enforce the organizations' dashboard IP allowlists
+    let allowlist = req.extensions().get::<OrgIpAllowlist>().cloned();
+    if let Some(allowlist) = allowlist {
+        let source = client_ip(&req, &allowlist.trusted_proxies);
+        let resource = PathResource::from_request(&req);
+        allowlist
+            .check_request(source, resource, claims.org_id.as_deref())
+            .await?;
+    }
 
     // Insert claims into request extensions
-    req.extensions_mut().insert(AuthClaims {
-        user_id: claims.user_id,
-        email: claims.email,
-        org_id: claims.org_id,
-        org_role: claims.org_role,
-    });
+    req.extensions_mut().insert(claims);
 
     Ok(next.run(req).await)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Extension, Router, extract::ConnectInfo, middleware, routing::get};
+    use std::net::SocketAddr;
+    use tower::ServiceExt;
+
+    use crate::modules::auth::application::ports::{
+        OrgContext, PersonalAccessTokenIdentity, PersonalAccessTokenVerifier,
+    };
+    use crate::modules::auth::domain::{AuthDomainError, TokenScope, UserId};
+    use crate::modules::auth::infrastructure::{JwtConfig, JwtTokenService};
+    use crate::modules::organizations::domain::{OrgName, OrgSlug, Organization};
+    use crate::shared::testing::{InMemoryOrganizationRepository, InMemoryProjectRepository};
+
+    async fn save_org(org_repo: &InMemoryOrganizationRepository, id: &str, allowed_ips: &[&str]) {
+        let name = OrgName::new(format!("Org {}", id)).unwrap();
+        let mut org = Organization::new(
+            OrgId::new(id.to_string()),
+            name.clone(),
+            OrgSlug::generate(&name, id),
+        );
+        org.set_allowed_ips(allowed_ips.iter().map(|ip| ip.to_string()).collect())
+            .unwrap();
+        org_repo.save(&org).await.unwrap();
+    }
+
+    /// The token is scoped to org-1; org-2, which owns project proj-2,
+    /// only allows 203.0.113.0/24
+    async fn app_and_token(allowed_ips: &[&str]) -> (Router, String) {
+        let org_repo = Arc::new(InMemoryOrganizationRepository::new());
+        save_org(&org_repo, "org-1", allowed_ips).await;
+        save_org(&org_repo, "org-2", &["203.0.113.0/24"]).await;
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("proj-2", "org-2");
+
+        let token_service = Arc::new(JwtTokenService::new(JwtConfig::default_with_secrets(
+            "access-secret".to_string(),
+            "refresh-secret".to_string(),
+        )));
+        let token = token_service
+            .generate_token_pair(
+                &UserId::new("user-1".to_string()),
+                "member@example.com",
+                Some(OrgContext {
+                    org_id: "org-1".to_string(),
+                    org_role: "member".to_string(),
+                }),
+            )
+            .await
+            .unwrap()
+            .access_token;
+
+        let routes = Router::new()
+            .route("/projects", get(|| async { "ok" }))
+            .route("/orgs/{id}/members", get(|| async { "ok" }))
+            .route("/projects/{id}", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(
+                token_service,
+                auth_middleware::<JwtTokenService>,
+            ));
+        let app = Router::new()
+            .nest("/api", routes)
+            .layer(Extension(OrgIpAllowlist::new(
+                org_repo,
+                project_repo,
+                Vec::new(),
+            )));
+        (app, token)
+    }
+
+    async fn status_from(app: &Router, token: &str, peer: &str) -> StatusCode {
+        status_at(app, "/api/projects", token, peer).await
+    }
+
+    async fn status_at(app: &Router, uri: &str, token: &str, peer: &str) -> StatusCode {
+        let mut request = Request::builder()
+            .uri(uri)
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::new(peer.parse().unwrap(), 40000)));
+        app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_member_outside_org_allowlist_is_rejected() {
+        let (app, token) = app_and_token(&["203.0.113.0/24"]).await;
+
+        assert_eq!(
+            status_from(&app, &token, "203.0.113.7").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status_from(&app, &token, "198.51.100.7").await,
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[tokio::test]
+    async fn test_empty_org_allowlist_allows_every_address() {
+        let (app, token) = app_and_token(&[]).await;
+
+        assert_eq!(
+            status_from(&app, &token, "198.51.100.7").await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn test_allowlist_of_org_named_in_path_is_enforced() {
+        let (app, token) = app_and_token(&[]).await;
+
+        for uri in ["/api/orgs/org-2/members", "/api/projects/proj-2"] {
+            assert_eq!(
+                status_at(&app, uri, &token, "203.0.113.7").await,
+                StatusCode::OK
+            );
+            assert_eq!(
+                status_at(&app, uri, &token, "198.51.100.7").await,
+                StatusCode::FORBIDDEN
+            );
+        }
+        // The token's own org has no allowlist
+        assert_eq!(
+            status_at(&app, "/api/orgs/org-1/members", &token, "198.51.100.7").await,
+            StatusCode::OK
+        );
+    }
+
+    struct StubVerifier;
+
+    #[async_trait::async_trait]
+    impl PersonalAccessTokenVerifier for StubVerifier {
+        async fn verify_personal_access_token(
+            &self,
+            token: &str,
+        ) -> Result<PersonalAccessTokenIdentity, AuthDomainError> {
+            match token {
+                "pat_good" => Ok(PersonalAccessTokenIdentity {
+                    user_id: "user-1".to_string(),
+                    email: "member@example.com".to_string(),
+                    scopes: vec![TokenScope::ProjectsRead],
+                }),
+                _ => Err(AuthDomainError::TokenInvalid),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_personal_access_tokens_only_work_where_enabled() {
+        let token_service = Arc::new(JwtTokenService::new(JwtConfig::default_with_secrets(
+            "access-secret".to_string(),
+            "refresh-secret".to_string(),
+        )));
+        let scoped = |Extension(claims): Extension<AuthClaims>| async move {
+            if claims.has_scope(TokenScope::ProjectsWrite) {
+                StatusCode::OK
+            } else {
+                StatusCode::FORBIDDEN
+            }
+        };
+        let router = Router::new().nest(
+            "/api",
+            Router::new()
+                .route("/projects", get(scoped))
+                .layer(middleware::from_fn_with_state(
+                    token_service,
+                    auth_middleware::<JwtTokenService>,
+                )),
+        );
+        let enabled = router
+            .clone()
+            .layer(Extension(PersonalAccessTokenAuth::new(Arc::new(
+                StubVerifier,
+            ))));
+
+        assert_eq!(
+            status_from(&router, "pat_good", "203.0.113.7").await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status_from(&enabled, "pat_bad", "203.0.113.7").await,
+            StatusCode::UNAUTHORIZED
+        );
+        // A read-only token authenticates but can't write
+        assert_eq!(
+            status_from(&enabled, "pat_good", "203.0.113.7").await,
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[tokio::test]
+    async fn test_personal_access_token_outside_org_allowlist_is_rejected() {
+        let (app, _) = app_and_token(&[]).await;
+        let app = app.layer(Extension(PersonalAccessTokenAuth::new(Arc::new(
+            StubVerifier,
+        ))));
+
+        assert_eq!(
+            status_at(&app, "/api/projects/proj-2", "pat_good", "203.0.113.7").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status_at(&app, "/api/projects/proj-2", "pat_good", "198.51.100.7").await,
+            StatusCode::FORBIDDEN
+        );
+    }
+}
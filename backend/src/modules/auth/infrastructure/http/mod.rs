@@ -4,6 +4,7 @@ pub mod middleware;
 pub mod rate_limit;
 pub mod routes;
 
-pub use extractors::{AuthClaims, AuthError, AuthState};
+pub use extractors::{AuthClaims, AuthError, AuthState, PersonalAccessTokenAuth};
+pub use middleware::OrgIpAllowlist;
 pub use rate_limit::IpRateLimiter;
 pub use routes::auth_routes;
@@ -1,21 +1,23 @@
 use axum::{
-    middleware,
+    Router, middleware,
     routing::{delete, get, patch, post},
-    Router,
 };
 use std::sync::Arc;
 
 use super::handlers;
 use super::middleware::auth_middleware;
-use super::rate_limit::{rate_limit_middleware, IpRateLimiter};
-use crate::modules::auth::application::ports::{IdGenerator, TokenService};
+use super::rate_limit::{IpRateLimiter, rate_limit_middleware};
 use crate::modules::auth::application::AuthService;
-use crate::modules::auth::domain::{PasswordHasher, RefreshTokenRepository, UserRepository};
+use crate::modules::auth::application::ports::{IdGenerator, TokenService};
+use crate::modules::auth::domain::{
+    AuthAuditRepository, PasswordHasher, RefreshTokenRepository, UserRepository, UserTotpRepository,
+};
 use crate::modules::organizations::domain::{OrganizationMemberRepository, OrganizationRepository};
 
 /// Create auth routes
-pub fn auth_routes<U, T, P, TS, ID, OR, MR>(
-    auth_service: Arc<AuthService<U, T, P, TS, ID, OR, MR>>,
+#[allow(clippy::type_complexity)]
+pub fn auth_routes<U, T, P, TS, ID, OR, MR, AA, TT>(
+    auth_service: Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>,
     token_service: Arc<TS>,
     rate_limiter: Arc<IpRateLimiter>,
 ) -> Router
@@ -27,12 +29,39 @@ where
     ID: IdGenerator + 'static,
     OR: OrganizationRepository + 'static,
     MR: OrganizationMemberRepository + 'static,
+    AA: AuthAuditRepository + 'static,
+    TT: UserTotpRepository + 'static,
 {
     // Public routes with rate limiting
     let public_routes = Router::new()
-        .route("/register", post(handlers::register::<U, T, P, TS, ID, OR, MR>))
-        .route("/login", post(handlers::login::<U, T, P, TS, ID, OR, MR>))
-        .route("/refresh", post(handlers::refresh::<U, T, P, TS, ID, OR, MR>))
+        .route(
+            "/register",
+            post(handlers::register::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
+        .route(
+            "/login",
+            post(handlers::login::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
+        .route(
+            "/login/totp",
+            post(handlers::verify_totp::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
+        .route(
+            "/refresh",
+            post(handlers::refresh::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
+        .route(
+            "/verify-email",
+            post(handlers::verify_email::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
+        .route(
+            "/password-reset/request",
+            post(handlers::request_password_reset::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
+        .route(
+            "/password-reset/confirm",
+            post(handlers::confirm_password_reset::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
         .layer(middleware::from_fn(move |req, next| {
             let limiter = rate_limiter.clone();
             async move { rate_limit_middleware(limiter, req, next).await }
@@ -40,28 +69,66 @@ where
 
     // Protected routes (require authentication, no rate limiting needed)
     let protected_routes = Router::new()
-        .route("/logout", post(handlers::logout::<U, T, P, TS, ID, OR, MR>))
+        .route(
+            "/logout",
+            post(handlers::logout::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
+        .route(
+            "/sessions",
+            get(handlers::list_sessions::<U, T, P, TS, ID, OR, MR, AA, TT>)
+                .delete(handlers::revoke_other_sessions::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
+        .route(
+            "/sessions/{id}",
+            delete(handlers::revoke_session::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
         .route(
             "/me",
-            get(handlers::me::<U, T, P, TS, ID, OR, MR>)
-                .delete(handlers::delete_account::<U, T, P, TS, ID, OR, MR>),
+            get(handlers::me::<U, T, P, TS, ID, OR, MR, AA, TT>)
+                .delete(handlers::delete_account::<U, T, P, TS, ID, OR, MR, AA, TT>),
         )
         .route(
             "/me/email",
-            patch(handlers::change_email::<U, T, P, TS, ID, OR, MR>),
+            patch(handlers::change_email::<U, T, P, TS, ID, OR, MR, AA, TT>),
         )
         .route(
             "/me/password",
-            patch(handlers::change_password::<U, T, P, TS, ID, OR, MR>),
+            patch(handlers::change_password::<U, T, P, TS, ID, OR, MR, AA, TT>),
         )
         .route(
             "/me/display-name",
-            patch(handlers::update_display_name::<U, T, P, TS, ID, OR, MR>),
+            patch(handlers::update_display_name::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
+        .route(
+            "/me/verify-email",
+            post(handlers::resend_verification::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
+        .route(
+            "/me/totp",
+            post(handlers::enable_totp::<U, T, P, TS, ID, OR, MR, AA, TT>)
+                .delete(handlers::disable_totp::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
+        .route(
+            "/me/totp/confirm",
+            post(handlers::confirm_totp::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
+        .route(
+            "/me/tokens",
+            get(handlers::list_personal_access_tokens::<U, T, P, TS, ID, OR, MR, AA, TT>)
+                .post(handlers::create_personal_access_token::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
+        .route(
+            "/me/tokens/{id}",
+            delete(handlers::revoke_personal_access_token::<U, T, P, TS, ID, OR, MR, AA, TT>),
         )
         .route(
             "/me/settings",
-            get(handlers::get_settings::<U, T, P, TS, ID, OR, MR>)
-                .patch(handlers::update_settings::<U, T, P, TS, ID, OR, MR>),
+            get(handlers::get_settings::<U, T, P, TS, ID, OR, MR, AA, TT>)
+                .patch(handlers::update_settings::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
+        .route(
+            "/audit-events",
+            get(handlers::list_audit_events::<U, T, P, TS, ID, OR, MR, AA, TT>),
         )
         .layer(middleware::from_fn_with_state(
             token_service,
@@ -1,7 +1,16 @@
 pub mod http;
+pub mod oauth;
 pub mod persistence;
 pub mod services;
 
-pub use http::{auth_routes, AuthClaims, AuthError, AuthState, IpRateLimiter};
-pub use persistence::{PostgresRefreshTokenRepository, PostgresUserRepository};
+pub use http::{AuthClaims, AuthError, AuthState, IpRateLimiter, auth_routes};
+pub use http::{OrgIpAllowlist, PersonalAccessTokenAuth};
+pub use oauth::{OAuthClient, OAuthClientCredentials, OAuthConfig, oauth_routes};
+pub use persistence::{
+    PostgresAuthAuditRepository, PostgresOAuthIdentityRepository,
+    PostgresPasswordResetTokenRepository, PostgresPersonalAccessTokenRepository,
+    PostgresRefreshTokenRepository, PostgresUserRepository, PostgresUserTotpRepository,
+    PostgresVerificationTokenRepository,
+};
 pub use services::{Argon2PasswordHasher, JwtConfig, JwtTokenService, UuidGenerator};
+pub use services::{SmtpConfig, SmtpEmailSender};
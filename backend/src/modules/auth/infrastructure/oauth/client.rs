@@ -0,0 +1,257 @@
+//! Authorization-code flow against GitHub and Google
+
+use reqwest::{Client, Url, header};
+use serde::Deserialize;
+
+use super::state::{OAuthStateSigner, code_challenge, new_code_verifier};
+use crate::modules::auth::domain::{AuthDomainError, OAuthProvider};
+
+const GITHUB_AUTHORIZE_URL: &str = "https://github.com/login/oauth/authorize";
+const GITHUB_TOKEN_URL: &str = "https://github.com/login/oauth/access_token";
+const GITHUB_USER_URL: &str = "https://api.github.com/user";
+const GITHUB_EMAILS_URL: &str = "https://api.github.com/user/emails";
+const GITHUB_SCOPES: &str = "read:user user:email";
+
+const GOOGLE_AUTHORIZE_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const GOOGLE_USERINFO_URL: &str = "https://openidconnect.googleapis.com/v1/userinfo";
+const GOOGLE_SCOPES: &str = "openid email profile";
+
+/// GitHub's API rejects requests without a User-Agent
+const USER_AGENT: &str = "Altenia";
+
+/// App credentials registered with a provider
+#[derive(Debug, Clone)]
+pub struct OAuthClientCredentials {
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+/// Providers users may sign in with; unset ones are unavailable
+#[derive(Debug, Clone)]
+pub struct OAuthConfig {
+    /// Base of the redirect URIs registered with the providers; each
+    /// provider redirects to `<base>/<provider>/callback`
+    pub redirect_base_url: String,
+    pub github: Option<OAuthClientCredentials>,
+    pub google: Option<OAuthClientCredentials>,
+}
+
+/// Where to send the user, and the PKCE verifier the browser must keep
+/// until the callback
+#[derive(Debug, Clone)]
+pub struct OAuthStart {
+    pub authorization_url: String,
+    pub code_verifier: String,
+}
+
+/// The signed-in account as the provider reports it
+#[derive(Debug, Clone)]
+pub struct OAuthProfile {
+    pub external_id: String,
+    /// Only ever an address the provider has verified
+    pub email: String,
+    pub display_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubUser {
+    id: u64,
+    login: String,
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubEmail {
+    email: String,
+    primary: bool,
+    verified: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleUserInfo {
+    sub: String,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    name: Option<String>,
+}
+
+/// Sends users to a provider and turns the code it returns into a profile
+pub struct OAuthClient {
+    http: Client,
+    config: OAuthConfig,
+    state: OAuthStateSigner,
+}
+
+impl OAuthClient {
+    pub fn new(http: Client, config: OAuthConfig, state_secret: &str) -> Self {
+        Self {
+            http,
+            config,
+            state: OAuthStateSigner::new(state_secret),
+        }
+    }
+
+    fn credentials(
+        &self,
+        provider: OAuthProvider,
+    ) -> Result<&OAuthClientCredentials, AuthDomainError> {
+        match provider {
+            OAuthProvider::GitHub => self.config.github.as_ref(),
+            OAuthProvider::Google => self.config.google.as_ref(),
+        }
+        .ok_or_else(|| AuthDomainError::UnsupportedOAuthProvider(provider.as_str().to_string()))
+    }
+
+    fn redirect_uri(&self, provider: OAuthProvider) -> String {
+        format!(
+            "{}/{}/callback",
+            self.config.redirect_base_url.trim_end_matches('/'),
+            provider.as_str()
+        )
+    }
+
+    /// Provider page the user signs in at, carrying a fresh signed state and
+    /// PKCE challenge
+    pub fn start(&self, provider: OAuthProvider) -> Result<OAuthStart, AuthDomainError> {
+        let credentials = self.credentials(provider)?;
+        let code_verifier = new_code_verifier();
+        let (base, scopes) = match provider {
+            OAuthProvider::GitHub => (GITHUB_AUTHORIZE_URL, GITHUB_SCOPES),
+            OAuthProvider::Google => (GOOGLE_AUTHORIZE_URL, GOOGLE_SCOPES),
+        };
+        let url = Url::parse_with_params(
+            base,
+            [
+                ("client_id", credentials.client_id.as_str()),
+                ("redirect_uri", self.redirect_uri(provider).as_str()),
+                ("response_type", "code"),
+                ("scope", scopes),
+                ("state", self.state.issue(provider, &code_verifier).as_str()),
+                ("code_challenge", code_challenge(&code_verifier).as_str()),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+        Ok(OAuthStart {
+            authorization_url: url.into(),
+            code_verifier,
+        })
+    }
+
+    /// Check the state against the browser's code verifier, exchange the code
+    /// for an access token and read the account
+    pub async fn complete(
+        &self,
+        provider: OAuthProvider,
+        code: &str,
+        state: &str,
+        code_verifier: &str,
+    ) -> Result<OAuthProfile, AuthDomainError> {
+        let credentials = self.credentials(provider)?;
+        self.state.verify(state, provider, code_verifier)?;
+
+        let token_url = match provider {
+            OAuthProvider::GitHub => GITHUB_TOKEN_URL,
+            OAuthProvider::Google => GOOGLE_TOKEN_URL,
+        };
+        let redirect_uri = self.redirect_uri(provider);
+        let token: TokenResponse = self
+            .http
+            .post(token_url)
+            .header(header::ACCEPT, "application/json")
+            .form(&[
+                ("client_id", credentials.client_id.as_str()),
+                ("client_secret", credentials.client_secret.as_str()),
+                ("code", code),
+                ("redirect_uri", redirect_uri.as_str()),
+                ("grant_type", "authorization_code"),
+                ("code_verifier", code_verifier),
+            ])
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(provider_error)?
+            .json()
+            .await
+            .map_err(provider_error)?;
+        let access_token = match token {
+            TokenResponse {
+                access_token: Some(access_token),
+                ..
+            } => access_token,
+            TokenResponse { error, .. } => {
+                return Err(AuthDomainError::OAuthProviderError(format!(
+                    "code exchange failed: {}",
+                    error.unwrap_or_else(|| "no access token".to_string())
+                )));
+            }
+        };
+
+        match provider {
+            OAuthProvider::GitHub => self.github_profile(&access_token).await,
+            OAuthProvider::Google => self.google_profile(&access_token).await,
+        }
+    }
+
+    async fn github_profile(&self, access_token: &str) -> Result<OAuthProfile, AuthDomainError> {
+        let user: GitHubUser = self.get_json(GITHUB_USER_URL, access_token).await?;
+        // The profile email may be hidden or unverified; the emails list says which are verified
+        let emails: Vec<GitHubEmail> = self.get_json(GITHUB_EMAILS_URL, access_token).await?;
+        let email = emails
+            .into_iter()
+            .find(|e| e.primary && e.verified)
+            .ok_or(AuthDomainError::OAuthEmailUnverified)?
+            .email;
+
+        Ok(OAuthProfile {
+            external_id: user.id.to_string(),
+            email,
+            display_name: user.name.or(Some(user.login)),
+        })
+    }
+
+    async fn google_profile(&self, access_token: &str) -> Result<OAuthProfile, AuthDomainError> {
+        let info: GoogleUserInfo = self.get_json(GOOGLE_USERINFO_URL, access_token).await?;
+        let email = info
+            .email
+            .filter(|_| info.email_verified)
+            .ok_or(AuthDomainError::OAuthEmailUnverified)?;
+
+        Ok(OAuthProfile {
+            external_id: info.sub,
+            email,
+            display_name: info.name,
+        })
+    }
+
+    async fn get_json<T: for<'de> Deserialize<'de>>(
+        &self,
+        url: &str,
+        access_token: &str,
+    ) -> Result<T, AuthDomainError> {
+        self.http
+            .get(url)
+            .bearer_auth(access_token)
+            .header(header::ACCEPT, "application/json")
+            .header(header::USER_AGENT, USER_AGENT)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(provider_error)?
+            .json()
+            .await
+            .map_err(provider_error)
+    }
+}
+
+fn provider_error(e: reqwest::Error) -> AuthDomainError {
+    AuthDomainError::OAuthProviderError(e.to_string())
+}
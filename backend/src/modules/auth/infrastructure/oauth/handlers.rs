@@ -0,0 +1,180 @@
+//! HTTP handlers for signing in with an OAuth provider
+
+// Handlers are generic over every repository the auth service uses
+#![allow(clippy::type_complexity)]
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{
+        HeaderMap, HeaderName, StatusCode,
+        header::{COOKIE, SET_COOKIE},
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::client::OAuthClient;
+use super::state::STATE_TTL_MINUTES;
+use crate::modules::auth::application::ports::{IdGenerator, TokenService};
+use crate::modules::auth::application::{AuthService, OAuthLoginCommand};
+use crate::modules::auth::domain::{
+    AuthAuditRepository, AuthDomainError, OAuthProvider, PasswordHasher, RefreshTokenRepository,
+    UserRepository, UserTotpRepository,
+};
+use crate::modules::auth::infrastructure::http::handlers::{
+    ErrorResponse, LoginResponseDto, client_info, generate_device_fingerprint, to_error_response,
+};
+use crate::modules::organizations::domain::{OrganizationMemberRepository, OrganizationRepository};
+
+/// State shared by the OAuth routes
+pub type OAuthState<U, T, P, TS, ID, OR, MR, AA, TT> = (
+    Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>,
+    Arc<OAuthClient>,
+);
+
+/// Holds the PKCE verifier between start and callback, so only the browser
+/// that started a sign-in can finish it
+const VERIFIER_COOKIE: &str = "altenia_oauth_verifier";
+/// Both OAuth endpoints, and nothing else, receive the cookie
+const VERIFIER_COOKIE_PATH: &str = "/api/auth/oauth";
+
+fn verifier_cookie(value: &str, max_age_secs: i64) -> String {
+    format!(
+        "{}={}; Path={}; Max-Age={}; HttpOnly; Secure; SameSite=Lax",
+        VERIFIER_COOKIE, value, VERIFIER_COOKIE_PATH, max_age_secs
+    )
+}
+
+fn read_verifier_cookie(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|h| h.to_str().ok())
+        .flat_map(|h| h.split(';'))
+        .find_map(|pair| {
+            let (name, value) = pair.trim().split_once('=')?;
+            (name == VERIFIER_COOKIE && !value.is_empty()).then_some(value)
+        })
+}
+
+#[derive(Debug, Serialize)]
+pub struct OAuthStartResponse {
+    pub authorization_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OAuthCallbackQuery {
+    pub code: String,
+    pub state: String,
+}
+
+/// GET /api/auth/oauth/{provider}/start
+pub async fn start_oauth<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State((_, oauth_client)): State<OAuthState<U, T, P, TS, ID, OR, MR, AA, TT>>,
+    Path(provider): Path<String>,
+) -> Result<([(HeaderName, String); 1], Json<OAuthStartResponse>), (StatusCode, Json<ErrorResponse>)>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    let provider = OAuthProvider::from_str(&provider).map_err(to_error_response)?;
+
+    let start = oauth_client.start(provider).map_err(to_error_response)?;
+    let cookie = verifier_cookie(&start.code_verifier, STATE_TTL_MINUTES * 60);
+
+    Ok((
+        [(SET_COOKIE, cookie)],
+        Json(OAuthStartResponse {
+            authorization_url: start.authorization_url,
+        }),
+    ))
+}
+
+/// GET /api/auth/oauth/{provider}/callback
+pub async fn oauth_callback<U, T, P, TS, ID, OR, MR, AA, TT>(
+    State((auth_service, oauth_client)): State<OAuthState<U, T, P, TS, ID, OR, MR, AA, TT>>,
+    Path(provider): Path<String>,
+    headers: HeaderMap,
+    Query(query): Query<OAuthCallbackQuery>,
+) -> Result<([(HeaderName, String); 1], Json<LoginResponseDto>), (StatusCode, Json<ErrorResponse>)>
+where
+    U: UserRepository,
+    T: RefreshTokenRepository,
+    P: PasswordHasher,
+    TS: TokenService,
+    ID: IdGenerator,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    AA: AuthAuditRepository,
+    TT: UserTotpRepository,
+{
+    let provider = OAuthProvider::from_str(&provider).map_err(to_error_response)?;
+    let code_verifier = read_verifier_cookie(&headers)
+        .ok_or_else(|| to_error_response(AuthDomainError::InvalidOAuthState))?;
+    let profile = oauth_client
+        .complete(provider, &query.code, &query.state, code_verifier)
+        .await
+        .map_err(to_error_response)?;
+
+    let cmd = OAuthLoginCommand {
+        provider,
+        external_id: profile.external_id,
+        email: profile.email,
+        display_name: profile.display_name,
+        device_fingerprint: generate_device_fingerprint(&headers),
+        client: client_info(&headers),
+    };
+
+    auth_service
+        .login_or_register_oauth(cmd)
+        .await
+        // The verifier is single-use
+        .map(|r| ([(SET_COOKIE, verifier_cookie("", 0))], Json(r.into())))
+        .map_err(to_error_response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verifier_cookie_read_back_from_request() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(read_verifier_cookie(&headers), None);
+
+        headers.insert(
+            COOKIE,
+            format!("theme=dark; {}=abc-123_x; other=1", VERIFIER_COOKIE)
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(read_verifier_cookie(&headers), Some("abc-123_x"));
+
+        // A cleared cookie doesn't count
+        headers.insert(COOKIE, format!("{}=", VERIFIER_COOKIE).parse().unwrap());
+        assert_eq!(read_verifier_cookie(&headers), None);
+    }
+
+    #[test]
+    fn test_verifier_cookie_is_scoped_and_hidden_from_scripts() {
+        let cookie = verifier_cookie("abc", 600);
+        assert!(cookie.starts_with("altenia_oauth_verifier=abc;"));
+        for attribute in [
+            "Path=/api/auth/oauth",
+            "Max-Age=600",
+            "HttpOnly",
+            "Secure",
+            "SameSite=Lax",
+        ] {
+            assert!(cookie.contains(attribute), "missing {}", attribute);
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod client;
+pub mod handlers;
+pub mod routes;
+pub mod state;
+
+pub use client::{OAuthClient, OAuthClientCredentials, OAuthConfig};
+pub use routes::oauth_routes;
@@ -0,0 +1,48 @@
+use axum::{Router, middleware, routing::get};
+use std::sync::Arc;
+
+use super::client::OAuthClient;
+use super::handlers;
+use crate::modules::auth::application::AuthService;
+use crate::modules::auth::application::ports::{IdGenerator, TokenService};
+use crate::modules::auth::domain::{
+    AuthAuditRepository, PasswordHasher, RefreshTokenRepository, UserRepository, UserTotpRepository,
+};
+use crate::modules::auth::infrastructure::http::rate_limit::{
+    IpRateLimiter, rate_limit_middleware,
+};
+use crate::modules::organizations::domain::{OrganizationMemberRepository, OrganizationRepository};
+
+/// Create OAuth sign-in routes
+#[allow(clippy::type_complexity)]
+pub fn oauth_routes<U, T, P, TS, ID, OR, MR, AA, TT>(
+    auth_service: Arc<AuthService<U, T, P, TS, ID, OR, MR, AA, TT>>,
+    oauth_client: Arc<OAuthClient>,
+    rate_limiter: Arc<IpRateLimiter>,
+) -> Router
+where
+    U: UserRepository + 'static,
+    T: RefreshTokenRepository + 'static,
+    P: PasswordHasher + 'static,
+    TS: TokenService + 'static,
+    ID: IdGenerator + 'static,
+    OR: OrganizationRepository + 'static,
+    MR: OrganizationMemberRepository + 'static,
+    AA: AuthAuditRepository + 'static,
+    TT: UserTotpRepository + 'static,
+{
+    Router::new()
+        .route(
+            "/oauth/{provider}/start",
+            get(handlers::start_oauth::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
+        .route(
+            "/oauth/{provider}/callback",
+            get(handlers::oauth_callback::<U, T, P, TS, ID, OR, MR, AA, TT>),
+        )
+        .layer(middleware::from_fn(move |req, next| {
+            let limiter = rate_limiter.clone();
+            async move { rate_limit_middleware(limiter, req, next).await }
+        }))
+        .with_state((auth_service, oauth_client))
+}
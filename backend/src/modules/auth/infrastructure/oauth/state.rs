@@ -0,0 +1,157 @@
+//! Signed `state` parameter and PKCE verifier of the authorization-code flow
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{Duration, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::modules::auth::domain::{AuthDomainError, OAuthProvider};
+
+/// How long a user has to finish signing in at the provider
+pub const STATE_TTL_MINUTES: i64 = 10;
+
+/// A fresh PKCE code verifier. It's also kept in a cookie on the browser that
+/// started the sign-in, binding the state to that browser
+pub fn new_code_verifier() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// PKCE `S256` challenge of a verifier
+pub fn code_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// Issues and checks `state` values without storing them: each one names its
+/// provider, expiry and the challenge of the browser's code verifier and is
+/// signed, so a callback only accepts states this server recently handed out
+/// for that provider to the same browser
+pub struct OAuthStateSigner {
+    key: Vec<u8>,
+}
+
+impl OAuthStateSigner {
+    pub fn new(secret: &str) -> Self {
+        Self {
+            // Separate from any other use of the same secret
+            key: format!("oauth-state:{}", secret).into_bytes(),
+        }
+    }
+
+    /// A fresh state for a sign-in starting now from the browser holding `verifier`
+    pub fn issue(&self, provider: OAuthProvider, verifier: &str) -> String {
+        let expires_at = (Utc::now() + Duration::minutes(STATE_TTL_MINUTES)).timestamp();
+        let payload = format!(
+            "{}.{}.{}",
+            provider.as_str(),
+            expires_at,
+            code_challenge(verifier)
+        );
+        let signature = URL_SAFE_NO_PAD.encode(self.mac(&payload).finalize().into_bytes());
+        format!("{}.{}", payload, signature)
+    }
+
+    /// Accept a state issued for `provider` and `verifier` that hasn't expired
+    pub fn verify(
+        &self,
+        state: &str,
+        provider: OAuthProvider,
+        verifier: &str,
+    ) -> Result<(), AuthDomainError> {
+        let (payload, signature) = state
+            .rsplit_once('.')
+            .ok_or(AuthDomainError::InvalidOAuthState)?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| AuthDomainError::InvalidOAuthState)?;
+        self.mac(payload)
+            .verify_slice(&signature)
+            .map_err(|_| AuthDomainError::InvalidOAuthState)?;
+
+        let mut parts = payload.split('.');
+        let issued_for = parts.next();
+        let expires_at = parts.next().and_then(|ts| ts.parse::<i64>().ok());
+        let challenge = parts.next();
+        match (issued_for, expires_at, challenge) {
+            (Some(name), Some(expires_at), Some(challenge))
+                if name == provider.as_str()
+                    && expires_at > Utc::now().timestamp()
+                    && challenge == code_challenge(verifier) =>
+            {
+                Ok(())
+            }
+            _ => Err(AuthDomainError::InvalidOAuthState),
+        }
+    }
+
+    fn mac(&self, payload: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(payload.as_bytes());
+        mac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_only_accepted_for_its_provider_and_signer() {
+        let signer = OAuthStateSigner::new("secret");
+        let verifier = new_code_verifier();
+        let state = signer.issue(OAuthProvider::GitHub, &verifier);
+
+        assert!(
+            signer
+                .verify(&state, OAuthProvider::GitHub, &verifier)
+                .is_ok()
+        );
+        assert!(
+            signer
+                .verify(&state, OAuthProvider::Google, &verifier)
+                .is_err()
+        );
+        assert!(
+            OAuthStateSigner::new("other")
+                .verify(&state, OAuthProvider::GitHub, &verifier)
+                .is_err()
+        );
+
+        let tampered = state.replacen("github", "google", 1);
+        assert!(
+            signer
+                .verify(&tampered, OAuthProvider::Google, &verifier)
+                .is_err()
+        );
+        assert!(
+            signer
+                .verify("garbage", OAuthProvider::GitHub, &verifier)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_state_only_accepted_from_the_browser_it_was_issued_to() {
+        let signer = OAuthStateSigner::new("secret");
+        let verifier = new_code_verifier();
+        let state = signer.issue(OAuthProvider::GitHub, &verifier);
+
+        assert!(
+            signer
+                .verify(&state, OAuthProvider::GitHub, &new_code_verifier())
+                .is_err()
+        );
+        assert!(signer.verify(&state, OAuthProvider::GitHub, "").is_err());
+    }
+
+    #[test]
+    fn test_code_challenge_matches_rfc_7636_example() {
+        assert_eq!(
+            code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+}
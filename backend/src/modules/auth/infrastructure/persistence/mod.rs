@@ -1,6 +1,18 @@
 pub mod models;
+pub mod postgres_audit_repo;
+pub mod postgres_oauth_identity_repo;
+pub mod postgres_password_reset_repo;
+pub mod postgres_personal_access_token_repo;
 pub mod postgres_token_repo;
+pub mod postgres_totp_repo;
 pub mod postgres_user_repo;
+pub mod postgres_verification_repo;
 
+pub use postgres_audit_repo::PostgresAuthAuditRepository;
+pub use postgres_oauth_identity_repo::PostgresOAuthIdentityRepository;
+pub use postgres_password_reset_repo::PostgresPasswordResetTokenRepository;
+pub use postgres_personal_access_token_repo::PostgresPersonalAccessTokenRepository;
 pub use postgres_token_repo::PostgresRefreshTokenRepository;
+pub use postgres_totp_repo::PostgresUserTotpRepository;
 pub use postgres_user_repo::PostgresUserRepository;
+pub use postgres_verification_repo::PostgresVerificationTokenRepository;
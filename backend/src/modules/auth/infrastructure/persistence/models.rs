@@ -7,8 +7,11 @@ pub struct UserRow {
     pub id: String,
     pub email: String,
     pub password_hash: Option<String>,
+    pub email_verified: bool,
     pub display_name: Option<String>,
+    pub username: Option<String>,
     pub allow_invites: bool,
+    pub default_org_id: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub deleted_at: Option<DateTime<Utc>>,
@@ -24,4 +27,83 @@ pub struct RefreshTokenRow {
     pub expires_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub revoked_at: Option<DateTime<Utc>>,
+    pub session_started_at: DateTime<Utc>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// Database row for auth_audit_events table
+#[derive(Debug, FromRow)]
+pub struct AuthAuditEventRow {
+    pub id: String,
+    pub event_type: String,
+    pub success: bool,
+    pub user_id: Option<String>,
+    pub identifier: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub failure_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database row for user_totp table
+#[derive(Debug, FromRow)]
+pub struct UserTotpRow {
+    pub user_id: String,
+    pub secret_encrypted: Vec<u8>,
+    pub recovery_code_hashes: Vec<String>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub last_used_step: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database row for totp_challenges table
+#[derive(Debug, FromRow)]
+pub struct TotpChallengeRow {
+    pub token_hash: String,
+    pub user_id: String,
+    pub device_fingerprint: String,
+    pub expires_at: DateTime<Utc>,
+    pub failed_attempts: i32,
+}
+
+/// Database row for email_verification_tokens table
+#[derive(Debug, FromRow)]
+pub struct VerificationTokenRow {
+    pub token_hash: String,
+    pub user_id: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database row for user_oauth_identities table
+#[derive(Debug, FromRow)]
+pub struct OAuthIdentityRow {
+    pub provider: String,
+    pub external_id: String,
+    pub user_id: String,
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database row for password_reset_tokens table
+#[derive(Debug, FromRow)]
+pub struct PasswordResetTokenRow {
+    pub token_hash: String,
+    pub user_id: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Database row for personal_access_tokens table
+#[derive(Debug, FromRow)]
+pub struct PersonalAccessTokenRow {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub token_hash: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
 }
@@ -0,0 +1,125 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::models::AuthAuditEventRow;
+use crate::modules::auth::domain::{
+    AuditEventId, AuthAuditEvent, AuthAuditEventType, AuthAuditFilters, AuthAuditRepository,
+    AuthDomainError, UserId,
+};
+
+pub struct PostgresAuthAuditRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresAuthAuditRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_event(row: AuthAuditEventRow) -> Result<AuthAuditEvent, AuthDomainError> {
+        Ok(AuthAuditEvent::reconstruct(
+            AuditEventId::new(row.id),
+            AuthAuditEventType::from_str(&row.event_type)?,
+            row.success,
+            row.user_id.map(UserId::new),
+            row.identifier,
+            row.ip_address,
+            row.user_agent,
+            row.failure_reason,
+            row.created_at,
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthAuditRepository for PostgresAuthAuditRepository {
+    async fn save(&self, event: &AuthAuditEvent) -> Result<(), AuthDomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO auth_audit_events
+                (id, event_type, success, user_id, identifier, ip_address, user_agent, failure_reason, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(event.id().as_str())
+        .bind(event.event_type().as_str())
+        .bind(event.success())
+        .bind(event.user_id().map(|u| u.as_str()))
+        .bind(event.identifier())
+        .bind(event.ip_address())
+        .bind(event.user_agent())
+        .bind(event.failure_reason())
+        .bind(event.created_at())
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find(
+        &self,
+        filters: &AuthAuditFilters,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AuthAuditEvent>, AuthDomainError> {
+        let mut conditions = vec!["user_id = ANY($1)".to_string()];
+        let mut param_idx = 2;
+
+        if filters.event_type.is_some() {
+            conditions.push(format!("event_type = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.success.is_some() {
+            conditions.push(format!("success = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.start_time.is_some() {
+            conditions.push(format!("created_at >= ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.end_time.is_some() {
+            conditions.push(format!("created_at <= ${}", param_idx));
+            param_idx += 1;
+        }
+
+        let query = format!(
+            r#"
+            SELECT id, event_type, success, user_id, identifier, ip_address, user_agent, failure_reason, created_at
+            FROM auth_audit_events
+            WHERE {}
+            ORDER BY created_at DESC
+            LIMIT ${} OFFSET ${}
+            "#,
+            conditions.join(" AND "),
+            param_idx,
+            param_idx + 1
+        );
+
+        let user_ids: Vec<&str> = filters.user_ids.iter().map(|u| u.as_str()).collect();
+        let mut query_builder = sqlx::query_as::<_, AuthAuditEventRow>(&query).bind(user_ids);
+
+        if let Some(event_type) = filters.event_type {
+            query_builder = query_builder.bind(event_type.as_str());
+        }
+        if let Some(success) = filters.success {
+            query_builder = query_builder.bind(success);
+        }
+        if let Some(start_time) = filters.start_time {
+            query_builder = query_builder.bind(start_time);
+        }
+        if let Some(end_time) = filters.end_time {
+            query_builder = query_builder.bind(end_time);
+        }
+
+        let rows = query_builder
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        rows.into_iter().map(Self::row_to_event).collect()
+    }
+}
@@ -0,0 +1,89 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::models::OAuthIdentityRow;
+use crate::modules::auth::domain::{
+    AuthDomainError, OAuthIdentity, OAuthIdentityRepository, OAuthProvider, UserId,
+};
+
+/// PostgreSQL implementation of OAuthIdentityRepository
+pub struct PostgresOAuthIdentityRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresOAuthIdentityRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_identity(row: OAuthIdentityRow) -> Result<OAuthIdentity, AuthDomainError> {
+        Ok(OAuthIdentity::reconstruct(
+            UserId::new(row.user_id),
+            OAuthProvider::from_str(&row.provider)?,
+            row.external_id,
+            row.email,
+            row.created_at,
+        ))
+    }
+}
+
+#[async_trait]
+impl OAuthIdentityRepository for PostgresOAuthIdentityRepository {
+    async fn find(
+        &self,
+        provider: OAuthProvider,
+        external_id: &str,
+    ) -> Result<Option<OAuthIdentity>, AuthDomainError> {
+        let row: Option<OAuthIdentityRow> = sqlx::query_as(
+            r#"
+            SELECT provider, external_id, user_id, email, created_at
+            FROM user_oauth_identities
+            WHERE provider = $1 AND external_id = $2
+            "#,
+        )
+        .bind(provider.as_str())
+        .bind(external_id)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        row.map(Self::row_to_identity).transpose()
+    }
+
+    async fn find_by_user(&self, user_id: &UserId) -> Result<Vec<OAuthIdentity>, AuthDomainError> {
+        let rows: Vec<OAuthIdentityRow> = sqlx::query_as(
+            r#"
+            SELECT provider, external_id, user_id, email, created_at
+            FROM user_oauth_identities
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id.as_str())
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        rows.into_iter().map(Self::row_to_identity).collect()
+    }
+
+    async fn save(&self, identity: &OAuthIdentity) -> Result<(), AuthDomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_oauth_identities (provider, external_id, user_id, email, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(identity.provider().as_str())
+        .bind(identity.external_id())
+        .bind(identity.user_id().as_str())
+        .bind(identity.email())
+        .bind(identity.created_at())
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+}
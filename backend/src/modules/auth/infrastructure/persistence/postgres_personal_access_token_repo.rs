@@ -0,0 +1,127 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::models::PersonalAccessTokenRow;
+use crate::modules::auth::domain::{
+    AuthDomainError, PersonalAccessToken, PersonalAccessTokenRepository, TokenScope, UserId,
+};
+
+/// PostgreSQL implementation of PersonalAccessTokenRepository
+pub struct PostgresPersonalAccessTokenRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresPersonalAccessTokenRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_token(row: PersonalAccessTokenRow) -> Result<PersonalAccessToken, AuthDomainError> {
+        let scopes = row
+            .scopes
+            .iter()
+            .map(|s| TokenScope::from_str(s))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(PersonalAccessToken::reconstruct(
+            row.id,
+            UserId::new(row.user_id),
+            row.name,
+            row.token_hash,
+            scopes,
+            row.expires_at,
+            row.last_used_at,
+            row.created_at,
+        ))
+    }
+}
+
+#[async_trait]
+impl PersonalAccessTokenRepository for PostgresPersonalAccessTokenRepository {
+    async fn save(&self, token: &PersonalAccessToken) -> Result<(), AuthDomainError> {
+        let scopes: Vec<&str> = token.scopes().iter().map(|s| s.as_str()).collect();
+
+        sqlx::query(
+            r#"
+            INSERT INTO personal_access_tokens (id, user_id, name, token_hash, scopes, expires_at, last_used_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(token.id())
+        .bind(token.user_id().as_str())
+        .bind(token.name())
+        .bind(token.token_hash())
+        .bind(scopes)
+        .bind(token.expires_at())
+        .bind(token.last_used_at())
+        .bind(token.created_at())
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<PersonalAccessToken>, AuthDomainError> {
+        let row: Option<PersonalAccessTokenRow> = sqlx::query_as(
+            r#"
+            SELECT id, user_id, name, token_hash, scopes, expires_at, last_used_at, created_at
+            FROM personal_access_tokens
+            WHERE token_hash = $1
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        row.map(Self::row_to_token).transpose()
+    }
+
+    async fn find_by_user(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<PersonalAccessToken>, AuthDomainError> {
+        let rows: Vec<PersonalAccessTokenRow> = sqlx::query_as(
+            r#"
+            SELECT id, user_id, name, token_hash, scopes, expires_at, last_used_at, created_at
+            FROM personal_access_tokens
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(user_id.as_str())
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        rows.into_iter().map(Self::row_to_token).collect()
+    }
+
+    async fn delete(&self, user_id: &UserId, id: &str) -> Result<bool, AuthDomainError> {
+        let result =
+            sqlx::query("DELETE FROM personal_access_tokens WHERE id = $1 AND user_id = $2")
+                .bind(id)
+                .bind(user_id.as_str())
+                .execute(self.pool.as_ref())
+                .await
+                .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn touch(&self, id: &str, used_at: DateTime<Utc>) -> Result<(), AuthDomainError> {
+        sqlx::query("UPDATE personal_access_tokens SET last_used_at = $2 WHERE id = $1")
+            .bind(id)
+            .bind(used_at)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+}
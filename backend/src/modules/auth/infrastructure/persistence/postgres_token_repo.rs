@@ -27,6 +27,9 @@ impl PostgresRefreshTokenRepository {
             row.expires_at,
             row.created_at,
             row.revoked_at,
+            row.session_started_at,
+            row.ip_address,
+            row.user_agent,
         )
     }
 }
@@ -36,8 +39,9 @@ impl RefreshTokenRepository for PostgresRefreshTokenRepository {
     async fn save(&self, token: &RefreshToken) -> Result<(), AuthDomainError> {
         sqlx::query(
             r#"
-            INSERT INTO refresh_tokens (id, user_id, token_hash, device_fingerprint, expires_at, created_at, revoked_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO refresh_tokens (id, user_id, token_hash, device_fingerprint, expires_at, created_at, revoked_at,
+                                        session_started_at, ip_address, user_agent)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             "#,
         )
         .bind(token.id().as_str())
@@ -47,6 +51,9 @@ impl RefreshTokenRepository for PostgresRefreshTokenRepository {
         .bind(token.expires_at())
         .bind(token.created_at())
         .bind(token.revoked_at())
+        .bind(token.session_started_at())
+        .bind(token.ip_address())
+        .bind(token.user_agent())
         .execute(self.pool.as_ref())
         .await
         .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
@@ -57,7 +64,8 @@ impl RefreshTokenRepository for PostgresRefreshTokenRepository {
     async fn find_by_id(&self, id: &TokenId) -> Result<Option<RefreshToken>, AuthDomainError> {
         let row: Option<RefreshTokenRow> = sqlx::query_as(
             r#"
-            SELECT id, user_id, token_hash, device_fingerprint, expires_at, created_at, revoked_at
+            SELECT id, user_id, token_hash, device_fingerprint, expires_at, created_at, revoked_at,
+                   session_started_at, ip_address, user_agent
             FROM refresh_tokens
             WHERE id = $1
             "#,
@@ -73,7 +81,8 @@ impl RefreshTokenRepository for PostgresRefreshTokenRepository {
     async fn find_by_hash(&self, hash: &str) -> Result<Option<RefreshToken>, AuthDomainError> {
         let row: Option<RefreshTokenRow> = sqlx::query_as(
             r#"
-            SELECT id, user_id, token_hash, device_fingerprint, expires_at, created_at, revoked_at
+            SELECT id, user_id, token_hash, device_fingerprint, expires_at, created_at, revoked_at,
+                   session_started_at, ip_address, user_agent
             FROM refresh_tokens
             WHERE token_hash = $1
             "#,
@@ -86,6 +95,28 @@ impl RefreshTokenRepository for PostgresRefreshTokenRepository {
         Ok(row.map(Self::row_to_token))
     }
 
+    async fn find_active_by_user(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<RefreshToken>, AuthDomainError> {
+        let rows: Vec<RefreshTokenRow> = sqlx::query_as(
+            r#"
+            SELECT id, user_id, token_hash, device_fingerprint, expires_at, created_at, revoked_at,
+                   session_started_at, ip_address, user_agent
+            FROM refresh_tokens
+            WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > $2
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(user_id.as_str())
+        .bind(Utc::now())
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(rows.into_iter().map(Self::row_to_token).collect())
+    }
+
     async fn revoke(&self, id: &TokenId) -> Result<(), AuthDomainError> {
         sqlx::query(
             r#"
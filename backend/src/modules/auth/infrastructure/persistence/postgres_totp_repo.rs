@@ -0,0 +1,202 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use async_trait::async_trait;
+use rand::RngCore;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::models::{TotpChallengeRow, UserTotpRow};
+use crate::modules::auth::domain::{
+    AuthDomainError, TotpChallenge, TotpSecret, UserId, UserTotp, UserTotpRepository,
+};
+
+const NONCE_LEN: usize = 12;
+
+/// PostgreSQL implementation of UserTotpRepository
+/// Secrets are encrypted with AES-256-GCM; the random nonce is stored in front of the ciphertext
+pub struct PostgresUserTotpRepository {
+    pool: Arc<PgPool>,
+    cipher: Aes256Gcm,
+}
+
+impl PostgresUserTotpRepository {
+    pub fn new(pool: Arc<PgPool>, encryption_key: [u8; 32]) -> Self {
+        Self {
+            pool,
+            cipher: Aes256Gcm::new(&encryption_key.into()),
+        }
+    }
+
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, AuthDomainError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::rng().fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .map_err(|_| {
+                AuthDomainError::InternalError("Failed to encrypt TOTP secret".to_string())
+            })?;
+
+        let mut out = nonce.to_vec();
+        out.extend(ciphertext);
+        Ok(out)
+    }
+
+    fn decrypt(&self, stored: &[u8]) -> Result<Vec<u8>, AuthDomainError> {
+        if stored.len() <= NONCE_LEN {
+            return Err(AuthDomainError::InternalError(
+                "Stored TOTP secret is truncated".to_string(),
+            ));
+        }
+        let (nonce, ciphertext) = stored.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                AuthDomainError::InternalError("Failed to decrypt TOTP secret".to_string())
+            })
+    }
+
+    fn row_to_totp(&self, row: UserTotpRow) -> Result<UserTotp, AuthDomainError> {
+        let secret = TotpSecret::from_bytes(self.decrypt(&row.secret_encrypted)?);
+        Ok(UserTotp::reconstruct(
+            UserId::new(row.user_id),
+            secret,
+            row.recovery_code_hashes,
+            row.confirmed_at,
+            row.last_used_step,
+            row.created_at,
+        ))
+    }
+
+    fn row_to_challenge(row: TotpChallengeRow) -> TotpChallenge {
+        TotpChallenge::reconstruct(
+            row.token_hash,
+            UserId::new(row.user_id),
+            row.device_fingerprint,
+            row.expires_at,
+            row.failed_attempts,
+        )
+    }
+}
+
+#[async_trait]
+impl UserTotpRepository for PostgresUserTotpRepository {
+    async fn find_by_user(&self, user_id: &UserId) -> Result<Option<UserTotp>, AuthDomainError> {
+        let row: Option<UserTotpRow> = sqlx::query_as(
+            r#"
+            SELECT user_id, secret_encrypted, recovery_code_hashes, confirmed_at, last_used_step, created_at
+            FROM user_totp
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id.as_str())
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        row.map(|r| self.row_to_totp(r)).transpose()
+    }
+
+    async fn save(&self, totp: &UserTotp) -> Result<(), AuthDomainError> {
+        let secret_encrypted = self.encrypt(totp.secret().as_bytes())?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_totp (user_id, secret_encrypted, recovery_code_hashes, confirmed_at, last_used_step, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (user_id) DO UPDATE SET
+                secret_encrypted = EXCLUDED.secret_encrypted,
+                recovery_code_hashes = EXCLUDED.recovery_code_hashes,
+                confirmed_at = EXCLUDED.confirmed_at,
+                last_used_step = EXCLUDED.last_used_step,
+                created_at = EXCLUDED.created_at
+            "#,
+        )
+        .bind(totp.user_id().as_str())
+        .bind(secret_encrypted)
+        .bind(totp.recovery_code_hashes())
+        .bind(totp.confirmed_at())
+        .bind(totp.last_used_step())
+        .bind(totp.created_at())
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, user_id: &UserId) -> Result<(), AuthDomainError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        sqlx::query("DELETE FROM totp_challenges WHERE user_id = $1")
+            .bind(user_id.as_str())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        sqlx::query("DELETE FROM user_totp WHERE user_id = $1")
+            .bind(user_id.as_str())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn save_challenge(&self, challenge: &TotpChallenge) -> Result<(), AuthDomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO totp_challenges (token_hash, user_id, device_fingerprint, expires_at, failed_attempts)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (token_hash) DO UPDATE SET failed_attempts = EXCLUDED.failed_attempts
+            "#,
+        )
+        .bind(challenge.token_hash())
+        .bind(challenge.user_id().as_str())
+        .bind(challenge.device_fingerprint())
+        .bind(challenge.expires_at())
+        .bind(challenge.failed_attempts())
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_challenge(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<TotpChallenge>, AuthDomainError> {
+        let row: Option<TotpChallengeRow> = sqlx::query_as(
+            r#"
+            SELECT token_hash, user_id, device_fingerprint, expires_at, failed_attempts
+            FROM totp_challenges
+            WHERE token_hash = $1
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(row.map(Self::row_to_challenge))
+    }
+
+    async fn delete_challenge(&self, token_hash: &str) -> Result<(), AuthDomainError> {
+        sqlx::query("DELETE FROM totp_challenges WHERE token_hash = $1")
+            .bind(token_hash)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use super::models::UserRow;
 use crate::modules::auth::domain::{
-    AuthDomainError, DisplayName, Email, PasswordHash, User, UserId, UserRepository,
+    AuthDomainError, DisplayName, Email, PasswordHash, User, UserId, UserRepository, Username,
 };
 
 /// PostgreSQL implementation of UserRepository
@@ -21,17 +21,18 @@ impl PostgresUserRepository {
         let email = Email::new(row.email)?;
         let user_id = UserId::new(row.id);
         let password_hash = row.password_hash.map(PasswordHash::from_hash);
-        let display_name = row
-            .display_name
-            .map(DisplayName::new)
-            .transpose()?;
+        let display_name = row.display_name.map(DisplayName::new).transpose()?;
+        let username = row.username.map(Username::new).transpose()?;
 
         Ok(User::reconstruct(
             user_id,
             email,
             password_hash,
+            row.email_verified,
             display_name,
+            username,
             row.allow_invites,
+            row.default_org_id,
             row.created_at,
             row.updated_at,
             row.deleted_at,
@@ -44,7 +45,7 @@ impl UserRepository for PostgresUserRepository {
     async fn find_by_id(&self, id: &UserId) -> Result<Option<User>, AuthDomainError> {
         let row: Option<UserRow> = sqlx::query_as(
             r#"
-            SELECT id, email, password_hash, display_name, allow_invites, created_at, updated_at, deleted_at
+            SELECT id, email, password_hash, email_verified, display_name, username, allow_invites, default_org_id, created_at, updated_at, deleted_at
             FROM users
             WHERE id = $1 AND deleted_at IS NULL
             "#,
@@ -60,7 +61,7 @@ impl UserRepository for PostgresUserRepository {
     async fn find_by_email(&self, email: &Email) -> Result<Option<User>, AuthDomainError> {
         let row: Option<UserRow> = sqlx::query_as(
             r#"
-            SELECT id, email, password_hash, display_name, allow_invites, created_at, updated_at, deleted_at
+            SELECT id, email, password_hash, email_verified, display_name, username, allow_invites, default_org_id, created_at, updated_at, deleted_at
             FROM users
             WHERE email = $1 AND deleted_at IS NULL
             "#,
@@ -73,16 +74,35 @@ impl UserRepository for PostgresUserRepository {
         row.map(Self::row_to_user).transpose()
     }
 
+    async fn find_by_username(&self, username: &Username) -> Result<Option<User>, AuthDomainError> {
+        let row: Option<UserRow> = sqlx::query_as(
+            r#"
+            SELECT id, email, password_hash, email_verified, display_name, username, allow_invites, default_org_id, created_at, updated_at, deleted_at
+            FROM users
+            WHERE username = $1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(username.as_str())
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        row.map(Self::row_to_user).transpose()
+    }
+
     async fn save(&self, user: &User) -> Result<(), AuthDomainError> {
         sqlx::query(
             r#"
-            INSERT INTO users (id, email, password_hash, display_name, allow_invites, created_at, updated_at, deleted_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            INSERT INTO users (id, email, password_hash, email_verified, display_name, username, allow_invites, default_org_id, created_at, updated_at, deleted_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             ON CONFLICT (id) DO UPDATE SET
                 email = EXCLUDED.email,
                 password_hash = EXCLUDED.password_hash,
+                email_verified = EXCLUDED.email_verified,
                 display_name = EXCLUDED.display_name,
+                username = EXCLUDED.username,
                 allow_invites = EXCLUDED.allow_invites,
+                default_org_id = EXCLUDED.default_org_id,
                 updated_at = EXCLUDED.updated_at,
                 deleted_at = EXCLUDED.deleted_at
             "#,
@@ -90,8 +110,11 @@ impl UserRepository for PostgresUserRepository {
         .bind(user.id().as_str())
         .bind(user.email().as_str())
         .bind(user.password_hash().map(|h| h.as_str()))
+        .bind(user.is_email_verified())
         .bind(user.display_name().map(|d| d.as_str()))
+        .bind(user.username().map(|u| u.as_str()))
         .bind(user.allow_invites())
+        .bind(user.default_org_id())
         .bind(user.created_at())
         .bind(user.updated_at())
         .bind(user.deleted_at())
@@ -102,6 +125,21 @@ impl UserRepository for PostgresUserRepository {
         Ok(())
     }
 
+    async fn update_password_hash(
+        &self,
+        id: &UserId,
+        password_hash: &PasswordHash,
+    ) -> Result<(), AuthDomainError> {
+        sqlx::query("UPDATE users SET password_hash = $2, updated_at = NOW() WHERE id = $1")
+            .bind(id.as_str())
+            .bind(password_hash.as_str())
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
     async fn exists_by_email(&self, email: &Email) -> Result<bool, AuthDomainError> {
         let count: (i64,) = sqlx::query_as(
             r#"
@@ -115,4 +153,18 @@ impl UserRepository for PostgresUserRepository {
 
         Ok(count.0 > 0)
     }
+
+    async fn exists_by_username(&self, username: &Username) -> Result<bool, AuthDomainError> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM users WHERE username = $1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(username.as_str())
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(count.0 > 0)
+    }
 }
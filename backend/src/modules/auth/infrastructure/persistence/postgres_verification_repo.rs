@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::models::VerificationTokenRow;
+use crate::modules::auth::domain::{
+    AuthDomainError, UserId, VerificationToken, VerificationTokenRepository,
+};
+
+/// PostgreSQL implementation of VerificationTokenRepository
+pub struct PostgresVerificationTokenRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresVerificationTokenRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_token(row: VerificationTokenRow) -> VerificationToken {
+        VerificationToken::reconstruct(
+            row.token_hash,
+            UserId::new(row.user_id),
+            row.expires_at,
+            row.created_at,
+        )
+    }
+}
+
+#[async_trait]
+impl VerificationTokenRepository for PostgresVerificationTokenRepository {
+    async fn save(&self, token: &VerificationToken) -> Result<(), AuthDomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO email_verification_tokens (token_hash, user_id, expires_at, created_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(token.token_hash())
+        .bind(token.user_id().as_str())
+        .bind(token.expires_at())
+        .bind(token.created_at())
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<VerificationToken>, AuthDomainError> {
+        let row: Option<VerificationTokenRow> = sqlx::query_as(
+            r#"
+            SELECT token_hash, user_id, expires_at, created_at
+            FROM email_verification_tokens
+            WHERE token_hash = $1
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(row.map(Self::row_to_token))
+    }
+
+    async fn delete_for_user(&self, user_id: &UserId) -> Result<(), AuthDomainError> {
+        sqlx::query("DELETE FROM email_verification_tokens WHERE user_id = $1")
+            .bind(user_id.as_str())
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn delete_expired(&self) -> Result<u64, AuthDomainError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM email_verification_tokens
+            WHERE expires_at < $1
+            "#,
+        )
+        .bind(Utc::now())
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| AuthDomainError::InternalError(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+}
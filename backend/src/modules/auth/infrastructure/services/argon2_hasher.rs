@@ -1,12 +1,16 @@
 use argon2::{
-    password_hash::{rand_core::OsRng, PasswordHasher as Argon2Hasher, PasswordVerifier, SaltString},
-    Argon2, PasswordHash as Argon2Hash,
+    Algorithm, Argon2, Params, PasswordHash as Argon2Hash, Version,
+    password_hash::{
+        PasswordHasher as Argon2Hasher, PasswordVerifier, SaltString, rand_core::OsRng,
+    },
 };
 use async_trait::async_trait;
 
 use crate::modules::auth::domain::{AuthDomainError, PasswordHash, PasswordHasher, PlainPassword};
 
-/// Argon2 implementation of PasswordHasher
+/// Argon2id implementation of PasswordHasher
+/// Cost parameters are stored in each PHC hash string, so hashes made with
+/// other parameters still verify and can be spotted for rehashing
 pub struct Argon2PasswordHasher {
     argon2: Argon2<'static>,
 }
@@ -23,6 +27,16 @@ impl Argon2PasswordHasher {
             argon2: Argon2::default(),
         }
     }
+
+    /// Hash with the given memory (KiB), iterations and parallelism
+    pub fn with_params(m_cost: u32, t_cost: u32, p_cost: u32) -> Result<Self, AuthDomainError> {
+        let params = Params::new(m_cost, t_cost, p_cost, None).map_err(|e| {
+            AuthDomainError::InternalError(format!("Invalid Argon2 parameters: {}", e))
+        })?;
+        Ok(Self {
+            argon2: Argon2::new(Algorithm::Argon2id, Version::V0x13, params),
+        })
+    }
 }
 
 #[async_trait]
@@ -52,6 +66,24 @@ impl PasswordHasher for Argon2PasswordHasher {
             .verify_password(password.as_str().as_bytes(), &parsed_hash)
             .is_ok())
     }
+
+    fn needs_rehash(&self, hash: &PasswordHash) -> bool {
+        let Ok(parsed_hash) = Argon2Hash::new(hash.as_str()) else {
+            return false;
+        };
+        if parsed_hash.algorithm != Algorithm::Argon2id.ident()
+            || parsed_hash.version != Some(Version::V0x13.into())
+        {
+            return true;
+        }
+        let Ok(stored) = Params::try_from(&parsed_hash) else {
+            return false;
+        };
+        let current = self.argon2.params();
+        stored.m_cost() < current.m_cost()
+            || stored.t_cost() < current.t_cost()
+            || stored.p_cost() < current.p_cost()
+    }
 }
 
 #[cfg(test)]
@@ -78,4 +110,21 @@ mod tests {
 
         assert!(!hasher.verify(&wrong_password, &hash).await.unwrap());
     }
+
+    #[tokio::test]
+    async fn test_weaker_hash_verifies_but_needs_rehash() {
+        let old = Argon2PasswordHasher::with_params(8192, 1, 1).unwrap();
+        let current = Argon2PasswordHasher::with_params(16384, 2, 1).unwrap();
+        let password = PlainPassword::new("Password123!".to_string()).unwrap();
+
+        let old_hash = old.hash(&password).await.unwrap();
+        assert!(old_hash.as_str().contains("m=8192,t=1,p=1"));
+        assert!(current.verify(&password, &old_hash).await.unwrap());
+        assert!(current.needs_rehash(&old_hash));
+
+        let new_hash = current.hash(&password).await.unwrap();
+        assert!(!current.needs_rehash(&new_hash));
+        // A stronger hash is left alone by a hasher configured lower
+        assert!(!old.needs_rehash(&new_hash));
+    }
 }
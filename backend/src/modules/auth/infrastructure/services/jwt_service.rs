@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use chrono::Utc;
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
@@ -10,13 +10,15 @@ use crate::modules::auth::domain::{AuthDomainError, UserId};
 /// JWT claims structure
 #[derive(Debug, Serialize, Deserialize)]
 struct Claims {
-    sub: String,                 // user_id
-    email: String,               // user email
-    org_id: Option<String>,      // organization id
-    org_role: Option<String>,    // role in organization
-    exp: i64,                    // expiration time
-    iat: i64,                    // issued at
-    token_type: String,          // "access" or "refresh"
+    sub: String,              // user_id
+    email: String,            // user email
+    org_id: Option<String>,   // organization id
+    org_role: Option<String>, // role in organization
+    exp: i64,                 // expiration time
+    iat: i64,                 // issued at
+    token_type: String,       // "access" or "refresh"
+    #[serde(default)]
+    auth_time: Option<i64>, // when the session was authenticated
 }
 
 /// JWT token service configuration
@@ -25,6 +27,10 @@ pub struct JwtConfig {
     pub refresh_secret: String,
     pub access_expiry_secs: i64,
     pub refresh_expiry_secs: i64,
+    /// Whether each refresh extends the session by `refresh_expiry_secs`
+    pub sliding_sessions: bool,
+    /// Absolute session lifetime; re-authentication is required after it
+    pub max_session_lifetime_secs: i64,
 }
 
 impl JwtConfig {
@@ -39,16 +45,39 @@ impl JwtConfig {
             refresh_secret,
             access_expiry_secs,
             refresh_expiry_secs,
+            sliding_sessions: false,
+            max_session_lifetime_secs: refresh_expiry_secs,
         }
     }
 
+    /// Extend the session on every refresh, up to `max_lifetime_secs` after login
+    pub fn with_sliding_sessions(mut self, sliding: bool, max_lifetime_secs: i64) -> Self {
+        self.sliding_sessions = sliding;
+        self.max_session_lifetime_secs = max_lifetime_secs;
+        self
+    }
+
+    /// Refresh token expiry for a session authenticated at `session_started_at`,
+    /// or None once the session can no longer be extended
+    fn refresh_expiry(&self, session_started_at: i64, now: i64) -> Option<i64> {
+        let expiry = if self.sliding_sessions {
+            now + self.refresh_expiry_secs
+        } else {
+            session_started_at + self.refresh_expiry_secs
+        };
+        let expiry = expiry.min(session_started_at + self.max_session_lifetime_secs);
+        (expiry > now).then_some(expiry)
+    }
+
     /// Default configuration with 15 min access and 7 day refresh
     pub fn default_with_secrets(access_secret: String, refresh_secret: String) -> Self {
         Self {
             access_secret,
             refresh_secret,
-            access_expiry_secs: 15 * 60,        // 15 minutes
+            access_expiry_secs: 15 * 60,           // 15 minutes
             refresh_expiry_secs: 7 * 24 * 60 * 60, // 7 days
+            sliding_sessions: false,
+            max_session_lifetime_secs: 7 * 24 * 60 * 60,
         }
     }
 }
@@ -62,17 +91,19 @@ impl JwtTokenService {
     pub fn new(config: JwtConfig) -> Self {
         Self { config }
     }
-}
 
-#[async_trait]
-impl TokenService for JwtTokenService {
-    async fn generate_token_pair(
+    fn issue_token_pair(
         &self,
         user_id: &UserId,
         email: &str,
         org_context: Option<OrgContext>,
+        session_started_at: i64,
     ) -> Result<TokenPair, AuthDomainError> {
         let now = Utc::now().timestamp();
+        let refresh_exp = self
+            .config
+            .refresh_expiry(session_started_at, now)
+            .ok_or(AuthDomainError::TokenExpired)?;
 
         let (org_id, org_role) = match org_context {
             Some(ctx) => (Some(ctx.org_id), Some(ctx.org_role)),
@@ -88,6 +119,7 @@ impl TokenService for JwtTokenService {
             exp: now + self.config.access_expiry_secs,
             iat: now,
             token_type: "access".to_string(),
+            auth_time: Some(session_started_at),
         };
 
         let access_token = encode(
@@ -95,7 +127,9 @@ impl TokenService for JwtTokenService {
             &access_claims,
             &EncodingKey::from_secret(self.config.access_secret.as_bytes()),
         )
-        .map_err(|e| AuthDomainError::InternalError(format!("Failed to create access token: {}", e)))?;
+        .map_err(|e| {
+            AuthDomainError::InternalError(format!("Failed to create access token: {}", e))
+        })?;
 
         // Generate refresh token
         let refresh_claims = Claims {
@@ -103,9 +137,10 @@ impl TokenService for JwtTokenService {
             email: email.to_string(),
             org_id,
             org_role,
-            exp: now + self.config.refresh_expiry_secs,
+            exp: refresh_exp,
             iat: now,
             token_type: "refresh".to_string(),
+            auth_time: Some(session_started_at),
         };
 
         let refresh_token = encode(
@@ -113,15 +148,39 @@ impl TokenService for JwtTokenService {
             &refresh_claims,
             &EncodingKey::from_secret(self.config.refresh_secret.as_bytes()),
         )
-        .map_err(|e| AuthDomainError::InternalError(format!("Failed to create refresh token: {}", e)))?;
+        .map_err(|e| {
+            AuthDomainError::InternalError(format!("Failed to create refresh token: {}", e))
+        })?;
 
         Ok(TokenPair {
             access_token,
             refresh_token,
             access_expires_in: self.config.access_expiry_secs,
-            refresh_expires_in: self.config.refresh_expiry_secs,
+            refresh_expires_in: refresh_exp - now,
         })
     }
+}
+
+#[async_trait]
+impl TokenService for JwtTokenService {
+    async fn generate_token_pair(
+        &self,
+        user_id: &UserId,
+        email: &str,
+        org_context: Option<OrgContext>,
+    ) -> Result<TokenPair, AuthDomainError> {
+        self.issue_token_pair(user_id, email, org_context, Utc::now().timestamp())
+    }
+
+    async fn refresh_token_pair(
+        &self,
+        user_id: &UserId,
+        email: &str,
+        org_context: Option<OrgContext>,
+        session_started_at: i64,
+    ) -> Result<TokenPair, AuthDomainError> {
+        self.issue_token_pair(user_id, email, org_context, session_started_at)
+    }
 
     fn validate_access_token(&self, token: &str) -> Result<TokenClaims, AuthDomainError> {
         let token_data = decode::<Claims>(
@@ -145,6 +204,8 @@ impl TokenService for JwtTokenService {
             org_role: token_data.claims.org_role,
             exp: token_data.claims.exp,
             iat: token_data.claims.iat,
+            // Tokens issued before auth_time was recorded started their session at issue
+            auth_time: token_data.claims.auth_time.unwrap_or(token_data.claims.iat),
         })
     }
 
@@ -170,6 +231,8 @@ impl TokenService for JwtTokenService {
             org_role: token_data.claims.org_role,
             exp: token_data.claims.exp,
             iat: token_data.claims.iat,
+            // Tokens issued before auth_time was recorded started their session at issue
+            auth_time: token_data.claims.auth_time.unwrap_or(token_data.claims.iat),
         })
     }
 
@@ -188,7 +251,7 @@ mod tests {
         JwtConfig::new(
             "test_access_secret".to_string(),
             "test_refresh_secret".to_string(),
-            900,  // 15 minutes
+            900,    // 15 minutes
             604800, // 7 days
         )
     }
@@ -202,7 +265,9 @@ mod tests {
         let service = create_test_service();
         let user_id = UserId::new("user-123".to_string());
 
-        let result = service.generate_token_pair(&user_id, "test@example.com", None).await;
+        let result = service
+            .generate_token_pair(&user_id, "test@example.com", None)
+            .await;
 
         assert!(result.is_ok());
         let token_pair = result.unwrap();
@@ -221,13 +286,17 @@ mod tests {
             org_role: "owner".to_string(),
         });
 
-        let result = service.generate_token_pair(&user_id, "test@example.com", org_context).await;
+        let result = service
+            .generate_token_pair(&user_id, "test@example.com", org_context)
+            .await;
 
         assert!(result.is_ok());
         let token_pair = result.unwrap();
 
         // Validate the token contains org claims
-        let claims = service.validate_access_token(&token_pair.access_token).unwrap();
+        let claims = service
+            .validate_access_token(&token_pair.access_token)
+            .unwrap();
         assert_eq!(claims.org_id, Some("org-456".to_string()));
         assert_eq!(claims.org_role, Some("owner".to_string()));
     }
@@ -238,7 +307,10 @@ mod tests {
         let user_id = UserId::new("user-123".to_string());
         let email = "test@example.com";
 
-        let token_pair = service.generate_token_pair(&user_id, email, None).await.unwrap();
+        let token_pair = service
+            .generate_token_pair(&user_id, email, None)
+            .await
+            .unwrap();
         let claims = service.validate_access_token(&token_pair.access_token);
 
         assert!(claims.is_ok());
@@ -254,7 +326,10 @@ mod tests {
         let service = create_test_service();
         let user_id = UserId::new("user-123".to_string());
 
-        let token_pair = service.generate_token_pair(&user_id, "test@example.com", None).await.unwrap();
+        let token_pair = service
+            .generate_token_pair(&user_id, "test@example.com", None)
+            .await
+            .unwrap();
         let result = service.validate_access_token(&token_pair.refresh_token);
 
         assert!(matches!(result, Err(AuthDomainError::TokenInvalid)));
@@ -274,7 +349,10 @@ mod tests {
         let user_id = UserId::new("user-456".to_string());
         let email = "refresh@example.com";
 
-        let token_pair = service.generate_token_pair(&user_id, email, None).await.unwrap();
+        let token_pair = service
+            .generate_token_pair(&user_id, email, None)
+            .await
+            .unwrap();
         let claims = service.decode_refresh_token(&token_pair.refresh_token);
 
         assert!(claims.is_ok());
@@ -288,7 +366,10 @@ mod tests {
         let service = create_test_service();
         let user_id = UserId::new("user-123".to_string());
 
-        let token_pair = service.generate_token_pair(&user_id, "test@example.com", None).await.unwrap();
+        let token_pair = service
+            .generate_token_pair(&user_id, "test@example.com", None)
+            .await
+            .unwrap();
         let result = service.decode_refresh_token(&token_pair.access_token);
 
         assert!(matches!(result, Err(AuthDomainError::TokenInvalid)));
@@ -318,14 +399,96 @@ mod tests {
 
     #[test]
     fn test_jwt_config_default_with_secrets() {
-        let config = JwtConfig::default_with_secrets(
-            "access".to_string(),
-            "refresh".to_string(),
-        );
+        let config = JwtConfig::default_with_secrets("access".to_string(), "refresh".to_string());
 
         assert_eq!(config.access_secret, "access");
         assert_eq!(config.refresh_secret, "refresh");
         assert_eq!(config.access_expiry_secs, 15 * 60);
         assert_eq!(config.refresh_expiry_secs, 7 * 24 * 60 * 60);
     }
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    fn create_session_service(sliding: bool) -> JwtTokenService {
+        JwtTokenService::new(create_test_config().with_sliding_sessions(sliding, 30 * DAY))
+    }
+
+    #[tokio::test]
+    async fn test_sliding_refresh_extends_session() {
+        let service = create_session_service(true);
+        let user_id = UserId::new("user-123".to_string());
+        let started = Utc::now().timestamp() - 20 * DAY;
+
+        let token_pair = service
+            .refresh_token_pair(&user_id, "test@example.com", None, started)
+            .await
+            .unwrap();
+
+        assert_eq!(token_pair.refresh_expires_in, 7 * DAY);
+        let claims = service
+            .decode_refresh_token(&token_pair.refresh_token)
+            .unwrap();
+        assert_eq!(claims.auth_time, started);
+    }
+
+    #[tokio::test]
+    async fn test_sliding_refresh_capped_at_max_lifetime() {
+        let service = create_session_service(true);
+        let user_id = UserId::new("user-123".to_string());
+        let now = Utc::now().timestamp();
+
+        // Two days left before the absolute maximum
+        let token_pair = service
+            .refresh_token_pair(&user_id, "test@example.com", None, now - 28 * DAY)
+            .await
+            .unwrap();
+        assert!(token_pair.refresh_expires_in <= 2 * DAY);
+        assert!(token_pair.refresh_expires_in > 2 * DAY - 60);
+
+        let result = service
+            .refresh_token_pair(&user_id, "test@example.com", None, now - 30 * DAY)
+            .await;
+        assert!(matches!(result, Err(AuthDomainError::TokenExpired)));
+    }
+
+    #[tokio::test]
+    async fn test_fixed_session_refresh_keeps_expiry() {
+        let service = create_session_service(false);
+        let user_id = UserId::new("user-123".to_string());
+        let now = Utc::now().timestamp();
+
+        let token_pair = service
+            .refresh_token_pair(&user_id, "test@example.com", None, now - 2 * DAY)
+            .await
+            .unwrap();
+        assert!(token_pair.refresh_expires_in <= 5 * DAY);
+        assert!(token_pair.refresh_expires_in > 5 * DAY - 60);
+
+        let result = service
+            .refresh_token_pair(&user_id, "test@example.com", None, now - 7 * DAY)
+            .await;
+        assert!(matches!(result, Err(AuthDomainError::TokenExpired)));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_continues_session_from_refresh_token() {
+        let service = create_session_service(true);
+        let user_id = UserId::new("user-123".to_string());
+
+        let login = service
+            .generate_token_pair(&user_id, "test@example.com", None)
+            .await
+            .unwrap();
+        let login_claims = service.decode_refresh_token(&login.refresh_token).unwrap();
+
+        let refreshed = service
+            .refresh_token_pair(&user_id, "test@example.com", None, login_claims.auth_time)
+            .await
+            .unwrap();
+        let refreshed_claims = service
+            .decode_refresh_token(&refreshed.refresh_token)
+            .unwrap();
+
+        assert_eq!(refreshed_claims.auth_time, login_claims.auth_time);
+    }
 }
@@ -1,7 +1,9 @@
 pub mod argon2_hasher;
 pub mod jwt_service;
+pub mod smtp_email_sender;
 pub mod uuid_generator;
 
 pub use argon2_hasher::Argon2PasswordHasher;
 pub use jwt_service::{JwtConfig, JwtTokenService};
+pub use smtp_email_sender::{SmtpConfig, SmtpEmailSender};
 pub use uuid_generator::UuidGenerator;
@@ -0,0 +1,226 @@
+//! Convert CloudWatch Logs subscription messages to internal log format
+
+use base64::Engine;
+use chrono::DateTime;
+use serde_json::{Map, Value, json};
+
+use crate::modules::cloudwatch::types::{CONTROL_MESSAGE, LogEvent, SubscriptionMessage};
+use crate::modules::gelf::payload::decompress_payload;
+use crate::modules::logging::application::dto::{DeadLetterInput, IngestRawLogsCommand, LogInput};
+
+/// Format name recorded on dead letters
+pub const CLOUDWATCH_FORMAT: &str = "cloudwatch";
+
+/// CloudWatch events carry no severity
+const DEFAULT_LEVEL: &str = "info";
+
+/// Decode one subscription record: base64 text of a gzip-compressed JSON message.
+/// Raw gzip bytes are accepted too, for senders that skip the base64 step.
+pub fn decode_record(record: &[u8]) -> Result<Vec<u8>, String> {
+    let compressed = if record.starts_with(&[0x1f, 0x8b]) {
+        record.to_vec()
+    } else {
+        base64::engine::general_purpose::STANDARD
+            .decode(record.trim_ascii())
+            .map_err(|e| format!("Invalid base64 record: {}", e))?
+    };
+    decompress_payload(&compressed)
+}
+
+/// Build an ingest command from raw subscription records. Control messages are
+/// acknowledged and skipped; records or events that can't be converted become dead letters.
+/// Returns the command and how many control messages were seen.
+pub fn parse_subscription_records(
+    project_id: &str,
+    records: &[Vec<u8>],
+) -> (IngestRawLogsCommand, u32) {
+    let mut logs = Vec::new();
+    let mut dead_letters = Vec::new();
+    let mut control_messages = 0;
+
+    for (idx, record) in records.iter().enumerate() {
+        let message = decode_record(record).and_then(|payload| {
+            serde_json::from_slice::<SubscriptionMessage>(&payload)
+                .map_err(|e| format!("Invalid subscription message: {}", e))
+        });
+        let message = match message {
+            Ok(message) => message,
+            Err(e) => {
+                dead_letters.push(DeadLetterInput {
+                    payload: String::from_utf8_lossy(record).into_owned(),
+                    error: format!("Record {}: {}", idx, e),
+                });
+                continue;
+            }
+        };
+
+        if message.message_type == CONTROL_MESSAGE {
+            control_messages += 1;
+            continue;
+        }
+
+        for event in &message.log_events {
+            match convert_log_event(&message, event) {
+                Ok(log) => logs.push(log),
+                Err(e) => dead_letters.push(DeadLetterInput {
+                    payload: event.message.clone(),
+                    error: format!("Record {} event {}: {}", idx, event.id, e),
+                }),
+            }
+        }
+    }
+
+    let cmd = IngestRawLogsCommand {
+        project_id: project_id.to_string(),
+        format: CLOUDWATCH_FORMAT.to_string(),
+        logs,
+        dead_letters,
+    };
+    (cmd, control_messages)
+}
+
+/// Convert one log event. The log group becomes the source, and the group,
+/// stream and account land in metadata. The event id makes redeliveries idempotent.
+pub fn convert_log_event(
+    message: &SubscriptionMessage,
+    event: &LogEvent,
+) -> Result<LogInput, String> {
+    let timestamp = DateTime::from_timestamp_millis(event.timestamp)
+        .ok_or_else(|| format!("Invalid timestamp: {}", event.timestamp))?;
+
+    let mut metadata = Map::new();
+    for (key, value) in [
+        ("log_group", &message.log_group),
+        ("log_stream", &message.log_stream),
+        ("aws_account_id", &message.owner),
+    ] {
+        if let Some(value) = value {
+            metadata.insert(key.to_string(), json!(value));
+        }
+    }
+
+    Ok(LogInput {
+        level: DEFAULT_LEVEL.to_string(),
+        message: event.message.trim_end_matches('\n').to_string(),
+        timestamp: Some(timestamp),
+        source: message.log_group.clone(),
+        metadata: (!metadata.is_empty()).then_some(Value::Object(metadata)),
+        trace_id: None,
+        span_id: None,
+        event_id: Some(event.id.clone()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::Arc;
+
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+
+    use crate::modules::logging::application::LogService;
+    use crate::shared::PaginationConfig;
+    use crate::shared::testing::{
+        InMemoryLogRepository, InMemoryMemberRepository, InMemoryProjectRepository,
+        SequentialIdGenerator,
+    };
+
+    const DATA_MESSAGE: &str = r#"{
+        "messageType": "DATA_MESSAGE",
+        "owner": "123456789012",
+        "logGroup": "/aws/lambda/checkout",
+        "logStream": "2024/01/15/[$LATEST]abc123",
+        "subscriptionFilters": ["altenia"],
+        "logEvents": [
+            {"id": "37516444000000000000000000000000000000000000000000000000", "timestamp": 1705312800000, "message": "START RequestId: 42\n"},
+            {"id": "37516444000000000000000000000000000000000000000000000001", "timestamp": 1705312800123, "message": "payment declined"}
+        ]
+    }"#;
+
+    const CONTROL: &str = r#"{
+        "messageType": "CONTROL_MESSAGE",
+        "owner": "CloudwatchLogs",
+        "logGroup": "",
+        "logStream": "",
+        "subscriptionFilters": [],
+        "logEvents": [
+            {"id": "", "timestamp": 1705312800000, "message": "CWL CONTROL MESSAGE: Checking health of destination Firehose."}
+        ]
+    }"#;
+
+    fn encode(message: &str) -> Vec<u8> {
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(message.as_bytes()).unwrap();
+        base64::engine::general_purpose::STANDARD
+            .encode(gzip.finish().unwrap())
+            .into_bytes()
+    }
+
+    fn service(
+        log_repo: Arc<InMemoryLogRepository>,
+    ) -> LogService<
+        InMemoryLogRepository,
+        InMemoryProjectRepository,
+        InMemoryMemberRepository,
+        SequentialIdGenerator,
+    > {
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        LogService::new(
+            log_repo,
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_ingest_subscription_payload() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let (cmd, control_messages) =
+            parse_subscription_records("project-1", &[encode(DATA_MESSAGE), encode(CONTROL)]);
+        assert_eq!(control_messages, 1);
+
+        let response = service(log_repo.clone()).ingest_raw(cmd).await.unwrap();
+        assert_eq!(response.accepted, 2);
+        assert_eq!(response.rejected, 0);
+
+        let saved = log_repo.saved();
+        let log = saved
+            .iter()
+            .find(|l| l.message() == "payment declined")
+            .unwrap();
+        assert_eq!(log.source(), Some("/aws/lambda/checkout"));
+        assert_eq!(log.timestamp().timestamp_millis(), 1_705_312_800_123);
+        assert_eq!(
+            log.event_id(),
+            Some("37516444000000000000000000000000000000000000000000000001")
+        );
+        let metadata = log.metadata().unwrap();
+        assert_eq!(metadata["log_group"], "/aws/lambda/checkout");
+        assert_eq!(metadata["log_stream"], "2024/01/15/[$LATEST]abc123");
+        assert_eq!(metadata["aws_account_id"], "123456789012");
+
+        assert!(saved.iter().any(|l| l.message() == "START RequestId: 42"));
+    }
+
+    #[tokio::test]
+    async fn test_undecodable_record_goes_to_dead_letter() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let (cmd, _) = parse_subscription_records(
+            "project-1",
+            &[b"not base64!".to_vec(), encode(DATA_MESSAGE)],
+        );
+
+        let response = service(log_repo.clone()).ingest_raw(cmd).await.unwrap();
+
+        assert_eq!(response.accepted, 2);
+        assert_eq!(response.rejected, 1);
+        let dead_letters = log_repo.dead_letters();
+        assert_eq!(dead_letters[0].format(), CLOUDWATCH_FORMAT);
+        assert!(dead_letters[0].error().starts_with("Record 0"));
+    }
+}
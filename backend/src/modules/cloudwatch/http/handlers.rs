@@ -0,0 +1,123 @@
+//! CloudWatch Logs HTTP handlers
+
+use axum::{Extension, Json, body::Bytes, extract::State, http::StatusCode};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::cloudwatch::conversion::parse_subscription_records;
+use crate::modules::cloudwatch::types::FirehoseRequest;
+use crate::modules::logging::application::LogService;
+use crate::modules::logging::application::dto::{IngestRawLogsCommand, IngestResponse};
+use crate::modules::logging::domain::{LogDomainError, LogRepository};
+use crate::modules::logging::infrastructure::ApiKeyContext;
+use crate::modules::organizations::domain::OrganizationMemberRepository;
+use crate::modules::projects::domain::ProjectRepository;
+
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CloudWatchIngestResponse {
+    pub accepted: u32,
+    pub rejected: u32,
+    /// Subscription confirmation messages acknowledged without ingesting
+    pub control_messages: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// Firehose requires the request id echoed back to mark a delivery as successful
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirehoseResponse {
+    pub request_id: String,
+    pub timestamp: i64,
+}
+
+/// POST /api/v1/ingest/cloudwatch - one subscription record (base64 of gzip JSON)
+pub async fn ingest_cloudwatch<LR, PR, OMR, ID>(
+    State(service): State<Arc<LogService<LR, PR, OMR, ID>>>,
+    Extension(ctx): Extension<ApiKeyContext>,
+    body: Bytes,
+) -> Result<Json<CloudWatchIngestResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    LR: LogRepository,
+    PR: ProjectRepository,
+    OMR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let (cmd, control_messages) =
+        parse_subscription_records(ctx.project_id.as_str(), &[body.to_vec()]);
+    let response = ingest(&service, cmd).await?;
+
+    Ok(Json(CloudWatchIngestResponse {
+        accepted: response.accepted,
+        rejected: response.rejected,
+        control_messages,
+        errors: response.errors,
+    }))
+}
+
+/// POST /api/v1/ingest/cloudwatch/firehose - Kinesis Data Firehose HTTP endpoint delivery
+pub async fn ingest_firehose<LR, PR, OMR, ID>(
+    State(service): State<Arc<LogService<LR, PR, OMR, ID>>>,
+    Extension(ctx): Extension<ApiKeyContext>,
+    Json(request): Json<FirehoseRequest>,
+) -> Result<Json<FirehoseResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    LR: LogRepository,
+    PR: ProjectRepository,
+    OMR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let records: Vec<Vec<u8>> = request
+        .records
+        .into_iter()
+        .map(|record| record.data.into_bytes())
+        .collect();
+    let (cmd, _) = parse_subscription_records(ctx.project_id.as_str(), &records);
+    ingest(&service, cmd).await?;
+
+    Ok(Json(FirehoseResponse {
+        request_id: request.request_id,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    }))
+}
+
+async fn ingest<LR, PR, OMR, ID>(
+    service: &LogService<LR, PR, OMR, ID>,
+    cmd: IngestRawLogsCommand,
+) -> Result<IngestResponse, (StatusCode, Json<ErrorResponse>)>
+where
+    LR: LogRepository,
+    PR: ProjectRepository,
+    OMR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    service.ingest_raw(cmd).await.map_err(|e| {
+        let (status, msg) = match e {
+            LogDomainError::ProjectNotFound | LogDomainError::ProjectDeleted => {
+                (StatusCode::NOT_FOUND, "Project not found".to_string())
+            }
+            LogDomainError::InternalError(ref msg) => {
+                tracing::error!(error = %msg, "Internal error during CloudWatch ingestion");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal error".to_string(),
+                )
+            }
+            other => (StatusCode::BAD_REQUEST, other.to_string()),
+        };
+        (
+            status,
+            Json(ErrorResponse {
+                error: msg,
+                code: "INGESTION_ERROR".to_string(),
+            }),
+        )
+    })
+}
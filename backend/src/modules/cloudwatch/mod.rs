@@ -0,0 +1,5 @@
+pub mod conversion;
+pub mod http;
+pub mod types;
+
+pub use http::cloudwatch_routes;
@@ -0,0 +1,45 @@
+//! CloudWatch Logs subscription payload types
+
+use serde::Deserialize;
+
+/// Sent by CloudWatch to check the destination is reachable; carries no log events
+pub const CONTROL_MESSAGE: &str = "CONTROL_MESSAGE";
+
+/// A decoded subscription record (gzip-compressed JSON on the wire)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionMessage {
+    /// DATA_MESSAGE or CONTROL_MESSAGE
+    pub message_type: String,
+    /// AWS account id of the originating log group
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub log_group: Option<String>,
+    #[serde(default)]
+    pub log_stream: Option<String>,
+    #[serde(default)]
+    pub log_events: Vec<LogEvent>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LogEvent {
+    pub id: String,
+    /// Milliseconds since the epoch
+    pub timestamp: i64,
+    pub message: String,
+}
+
+/// Kinesis Data Firehose HTTP endpoint delivery, wrapping base64 subscription records
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirehoseRequest {
+    pub request_id: String,
+    pub records: Vec<FirehoseRecord>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FirehoseRecord {
+    /// Base64 of the gzip-compressed subscription message
+    pub data: String,
+}
@@ -0,0 +1,220 @@
+//! Convert GELF messages to internal log format
+
+use chrono::DateTime;
+use serde_json::{Map, Value, json};
+
+use crate::modules::gelf::types::GelfMessage;
+use crate::modules::logging::application::dto::{DeadLetterInput, IngestRawLogsCommand, LogInput};
+use crate::modules::syslog::parser::severity_to_level;
+
+/// Format name recorded on dead letters
+pub const GELF_FORMAT: &str = "gelf";
+
+/// GELF defaults a missing level to 1 (alert)
+const DEFAULT_LEVEL: u8 = 1;
+
+/// Build an ingest command from a decompressed GELF payload holding one
+/// message or an array of messages. Messages that can't be converted become dead letters.
+pub fn parse_gelf_payload(project_id: &str, payload: &[u8]) -> IngestRawLogsCommand {
+    let mut logs = Vec::new();
+    let mut dead_letters = Vec::new();
+
+    let values = match serde_json::from_slice::<Value>(payload) {
+        Ok(Value::Array(values)) => values,
+        Ok(value) => vec![value],
+        Err(e) => {
+            dead_letters.push(DeadLetterInput {
+                payload: String::from_utf8_lossy(payload).into_owned(),
+                error: format!("Invalid JSON: {}", e),
+            });
+            vec![]
+        }
+    };
+
+    for (idx, value) in values.into_iter().enumerate() {
+        let payload = value.to_string();
+        let converted = serde_json::from_value::<GelfMessage>(value)
+            .map_err(|e| e.to_string())
+            .and_then(convert_gelf_message);
+        match converted {
+            Ok(log) => logs.push(log),
+            Err(e) => dead_letters.push(DeadLetterInput {
+                payload,
+                error: format!("Message {}: {}", idx, e),
+            }),
+        }
+    }
+
+    IngestRawLogsCommand {
+        project_id: project_id.to_string(),
+        format: GELF_FORMAT.to_string(),
+        logs,
+        dead_letters,
+    }
+}
+
+/// Convert a GELF message into a LogInput.
+/// `_`-prefixed additional fields land in metadata without their prefix.
+pub fn convert_gelf_message(message: GelfMessage) -> Result<LogInput, String> {
+    let short_message = message
+        .short_message
+        .filter(|m| !m.trim().is_empty())
+        .ok_or("Missing short_message")?;
+
+    let level = message.level.unwrap_or(DEFAULT_LEVEL);
+    if level > 7 {
+        return Err(format!("Invalid level: {}", level));
+    }
+
+    let timestamp = message
+        .timestamp
+        .map(|ts| {
+            DateTime::from_timestamp_micros((ts * 1_000_000.0).round() as i64)
+                .ok_or_else(|| format!("Invalid timestamp: {}", ts))
+        })
+        .transpose()?;
+
+    let mut metadata = Map::new();
+    for (key, value) in message.additional {
+        match key.strip_prefix('_') {
+            // "_id" is reserved by GELF
+            Some("id") | Some("") | None => continue,
+            Some(field) => {
+                metadata.insert(field.to_string(), value);
+            }
+        }
+    }
+    if let Some(host) = &message.host {
+        metadata.insert("host".to_string(), json!(host));
+    }
+    for (key, value) in [
+        ("full_message", message.full_message),
+        ("facility", message.facility),
+        ("file", message.file),
+    ] {
+        if let Some(value) = value {
+            metadata.insert(key.to_string(), json!(value));
+        }
+    }
+    if let Some(line) = message.line {
+        metadata.insert("line".to_string(), json!(line));
+    }
+
+    Ok(LogInput {
+        level: severity_to_level(level).to_string(),
+        message: short_message,
+        timestamp,
+        source: message.host,
+        metadata: (!metadata.is_empty()).then_some(Value::Object(metadata)),
+        trace_id: None,
+        span_id: None,
+        event_id: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::Arc;
+
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+
+    use crate::modules::gelf::payload::decompress_payload;
+    use crate::modules::logging::application::LogService;
+    use crate::modules::logging::domain::LogLevel;
+    use crate::shared::PaginationConfig;
+    use crate::shared::testing::{
+        InMemoryLogRepository, InMemoryMemberRepository, InMemoryProjectRepository,
+        SequentialIdGenerator,
+    };
+
+    const MESSAGE: &str = r#"{
+        "version": "1.1",
+        "host": "example.org",
+        "short_message": "A short message that helps you identify what is going on",
+        "full_message": "Backtrace here\n\nmore stuff",
+        "timestamp": 1385053862.3072,
+        "level": 4,
+        "_user_id": 9001,
+        "_some_info": "foo",
+        "_id": "ignored"
+    }"#;
+
+    fn service(
+        log_repo: Arc<InMemoryLogRepository>,
+    ) -> LogService<
+        InMemoryLogRepository,
+        InMemoryProjectRepository,
+        InMemoryMemberRepository,
+        SequentialIdGenerator,
+    > {
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        LogService::new(
+            log_repo,
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_ingest_compressed_gelf_message() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(MESSAGE.as_bytes()).unwrap();
+        let payload = decompress_payload(&gzip.finish().unwrap()).unwrap();
+
+        let response = service(log_repo.clone())
+            .ingest_raw(parse_gelf_payload("project-1", &payload))
+            .await
+            .unwrap();
+        assert_eq!(response.accepted, 1);
+
+        let saved = log_repo.saved();
+        let log = &saved[0];
+        assert_eq!(
+            log.message(),
+            "A short message that helps you identify what is going on"
+        );
+        assert_eq!(log.level(), LogLevel::Warn);
+        assert_eq!(log.source(), Some("example.org"));
+        assert_eq!(log.timestamp().timestamp_millis(), 1_385_053_862_307);
+
+        let metadata = log.metadata().unwrap();
+        assert_eq!(metadata["user_id"], 9001);
+        assert_eq!(metadata["some_info"], "foo");
+        assert_eq!(metadata["full_message"], "Backtrace here\n\nmore stuff");
+        assert!(metadata.get("id").is_none());
+        assert!(metadata.get("_user_id").is_none());
+    }
+
+    #[test]
+    fn test_missing_level_defaults_to_alert() {
+        let message: GelfMessage =
+            serde_json::from_str(r#"{"version": "1.1", "host": "h", "short_message": "boom"}"#)
+                .unwrap();
+        assert_eq!(convert_gelf_message(message).unwrap().level, "fatal");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_message_goes_to_dead_letter() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let payload = format!(r#"[{}, {{"host": "h", "level": 3}}]"#, MESSAGE);
+
+        let response = service(log_repo.clone())
+            .ingest_raw(parse_gelf_payload("project-1", payload.as_bytes()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.accepted, 1);
+        assert_eq!(response.rejected, 1);
+        let dead_letters = log_repo.dead_letters();
+        assert_eq!(dead_letters.len(), 1);
+        assert_eq!(dead_letters[0].format(), GELF_FORMAT);
+        assert!(dead_letters[0].error().contains("short_message"));
+    }
+}
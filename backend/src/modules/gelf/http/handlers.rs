@@ -0,0 +1,82 @@
+//! GELF HTTP handlers
+
+use axum::{Extension, Json, body::Bytes, extract::State, http::StatusCode};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::gelf::conversion::parse_gelf_payload;
+use crate::modules::gelf::payload::decompress_payload;
+use crate::modules::logging::application::LogService;
+use crate::modules::logging::domain::{LogDomainError, LogRepository};
+use crate::modules::logging::infrastructure::ApiKeyContext;
+use crate::modules::organizations::domain::OrganizationMemberRepository;
+use crate::modules::projects::domain::ProjectRepository;
+
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GelfIngestResponse {
+    pub accepted: u32,
+    pub rejected: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// POST /api/v1/ingest/gelf - GELF JSON message(s), optionally gzip or zlib compressed
+pub async fn ingest_gelf<LR, PR, OMR, ID>(
+    State(service): State<Arc<LogService<LR, PR, OMR, ID>>>,
+    Extension(ctx): Extension<ApiKeyContext>,
+    body: Bytes,
+) -> Result<Json<GelfIngestResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    LR: LogRepository,
+    PR: ProjectRepository,
+    OMR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let payload = decompress_payload(&body).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: e,
+                code: "INVALID_PAYLOAD".to_string(),
+            }),
+        )
+    })?;
+
+    let cmd = parse_gelf_payload(ctx.project_id.as_str(), &payload);
+
+    let response = service.ingest_raw(cmd).await.map_err(|e| {
+        let (status, msg) = match e {
+            LogDomainError::ProjectNotFound | LogDomainError::ProjectDeleted => {
+                (StatusCode::NOT_FOUND, "Project not found".to_string())
+            }
+            LogDomainError::InternalError(ref msg) => {
+                tracing::error!(error = %msg, "Internal error during GELF ingestion");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal error".to_string(),
+                )
+            }
+            other => (StatusCode::BAD_REQUEST, other.to_string()),
+        };
+        (
+            status,
+            Json(ErrorResponse {
+                error: msg,
+                code: "INGESTION_ERROR".to_string(),
+            }),
+        )
+    })?;
+
+    Ok(Json(GelfIngestResponse {
+        accepted: response.accepted,
+        rejected: response.rejected,
+        errors: response.errors,
+    }))
+}
@@ -0,0 +1,4 @@
+pub mod handlers;
+pub mod routes;
+
+pub use routes::gelf_routes;
@@ -0,0 +1,36 @@
+//! GELF HTTP routes
+
+use axum::{Router, middleware, routing::post};
+use std::sync::Arc;
+
+use super::handlers;
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::logging::application::LogService;
+use crate::modules::logging::domain::LogRepository;
+use crate::modules::logging::infrastructure::http::middleware::api_key_middleware;
+use crate::modules::organizations::domain::{OrganizationMemberRepository, OrganizationRepository};
+use crate::modules::projects::application::ProjectService;
+use crate::modules::projects::domain::{ApiKeyRepository, ProjectRepository};
+
+/// GELF routes for log ingestion (requires API key middleware)
+pub fn gelf_routes<LR, PR, OMR, ID, PPR, AR, OR>(
+    service: Arc<LogService<LR, PR, OMR, ID>>,
+    project_service: Arc<ProjectService<PPR, AR, OR, OMR, ID>>,
+) -> Router
+where
+    LR: LogRepository + 'static,
+    PR: ProjectRepository + 'static,
+    OMR: OrganizationMemberRepository + 'static,
+    ID: IdGenerator + 'static,
+    PPR: ProjectRepository + 'static,
+    AR: ApiKeyRepository + 'static,
+    OR: OrganizationRepository + 'static,
+{
+    Router::new()
+        .route("/gelf", post(handlers::ingest_gelf::<LR, PR, OMR, ID>))
+        .layer(middleware::from_fn_with_state(
+            project_service,
+            api_key_middleware::<PPR, AR, OR, OMR, ID>,
+        ))
+        .with_state(service)
+}
@@ -0,0 +1,84 @@
+//! GELF UDP listener
+//!
+//! Datagrams may be chunked and/or compressed. UDP senders cannot present an
+//! API key, so every message is attributed to a single configured project.
+
+use std::sync::Arc;
+
+use tokio::net::UdpSocket;
+
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::gelf::conversion::{GELF_FORMAT, parse_gelf_payload};
+use crate::modules::gelf::payload::{ChunkAssembler, decompress_payload};
+use crate::modules::logging::application::LogService;
+use crate::modules::logging::application::dto::{DeadLetterInput, IngestRawLogsCommand};
+use crate::modules::logging::domain::LogRepository;
+use crate::modules::organizations::domain::OrganizationMemberRepository;
+use crate::modules::projects::domain::ProjectRepository;
+
+/// Largest datagram a GELF sender can transmit over UDP
+const MAX_DATAGRAM_SIZE: usize = 65_535;
+
+/// Start the GELF UDP listener
+pub async fn start_gelf_udp_listener<LR, PR, OMR, ID>(
+    service: Arc<LogService<LR, PR, OMR, ID>>,
+    host: String,
+    port: u16,
+    project_id: String,
+) where
+    LR: LogRepository + 'static,
+    PR: ProjectRepository + 'static,
+    OMR: OrganizationMemberRepository + 'static,
+    ID: IdGenerator + 'static,
+{
+    let socket = match UdpSocket::bind((host.as_str(), port)).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            tracing::error!(error = %e, port, "Failed to bind GELF UDP listener");
+            return;
+        }
+    };
+
+    let mut assembler = ChunkAssembler::new();
+    let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+    loop {
+        let len = match socket.recv_from(&mut buf).await {
+            Ok((len, _)) => len,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to receive GELF datagram");
+                continue;
+            }
+        };
+
+        let cmd = match assembler
+            .push(&buf[..len])
+            .and_then(|complete| complete.map(|p| decompress_payload(&p)).transpose())
+        {
+            Ok(Some(payload)) => parse_gelf_payload(&project_id, &payload),
+            // Waiting for more chunks
+            Ok(None) => continue,
+            Err(e) => IngestRawLogsCommand {
+                project_id: project_id.clone(),
+                format: GELF_FORMAT.to_string(),
+                logs: vec![],
+                dead_letters: vec![DeadLetterInput {
+                    payload: String::from_utf8_lossy(&buf[..len]).into_owned(),
+                    error: e,
+                }],
+            },
+        };
+
+        match service.ingest_raw(cmd).await {
+            Ok(response) if response.rejected > 0 => {
+                tracing::debug!(
+                    rejected = response.rejected,
+                    "GELF datagram contained malformed messages"
+                );
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to ingest GELF datagram");
+            }
+            _ => {}
+        }
+    }
+}
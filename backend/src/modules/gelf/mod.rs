@@ -0,0 +1,8 @@
+pub mod conversion;
+pub mod http;
+pub mod listener;
+pub mod payload;
+pub mod types;
+
+pub use http::gelf_routes;
+pub use listener::start_gelf_udp_listener;
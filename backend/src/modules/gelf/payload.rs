@@ -0,0 +1,284 @@
+//! GELF transport framing: compression and UDP chunking
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+
+/// Magic bytes that open every chunked GELF datagram
+const CHUNK_MAGIC: [u8; 2] = [0x1e, 0x0f];
+
+/// Magic (2) + message ID (8) + sequence number (1) + sequence count (1)
+const CHUNK_HEADER_LEN: usize = 12;
+
+/// GELF limits a message to 128 chunks
+const MAX_CHUNKS: u8 = 128;
+
+/// Incomplete messages are dropped after this long, per the GELF spec
+const CHUNK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Largest payload a compressed message may expand to
+pub const MAX_DECOMPRESSED: usize = 32 * 1024 * 1024;
+
+/// Most incomplete chunked messages held at once
+const MAX_PENDING_MESSAGES: usize = 1024;
+
+/// Most chunk bytes held across all incomplete messages
+const MAX_PENDING_BYTES: usize = 64 * 1024 * 1024;
+
+/// Decompress a GELF payload, detecting gzip and zlib by their magic bytes.
+/// Uncompressed payloads are returned unchanged.
+pub fn decompress_payload(payload: &[u8]) -> Result<Vec<u8>, String> {
+    decompress_with_limit(payload, MAX_DECOMPRESSED)
+}
+
+/// Decompress, rejecting payloads that expand past `max` bytes so a small
+/// compressed body can't exhaust memory
+fn decompress_with_limit(payload: &[u8], max: usize) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let cap = max as u64 + 1;
+    match payload {
+        [0x1f, 0x8b, ..] => {
+            GzDecoder::new(payload)
+                .take(cap)
+                .read_to_end(&mut out)
+                .map_err(|e| format!("Invalid gzip payload: {}", e))?;
+        }
+        // zlib header: deflate method with a valid header checksum
+        [cmf, flg, ..] if cmf & 0x0f == 8 && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0 => {
+            ZlibDecoder::new(payload)
+                .take(cap)
+                .read_to_end(&mut out)
+                .map_err(|e| format!("Invalid zlib payload: {}", e))?;
+        }
+        _ => out.extend_from_slice(payload),
+    }
+    if out.len() > max {
+        return Err(format!("Payload decompresses to more than {} bytes", max));
+    }
+    Ok(out)
+}
+
+/// Chunks received so far for one message
+struct PartialMessage {
+    chunks: Vec<Option<Vec<u8>>>,
+    received: usize,
+    bytes: usize,
+    first_seen: Instant,
+}
+
+/// Reassembles chunked GELF datagrams into complete payloads.
+/// Incomplete messages are bounded in number and total size; the oldest
+/// are dropped first when either limit is reached.
+pub struct ChunkAssembler {
+    pending: HashMap<[u8; 8], PartialMessage>,
+    pending_bytes: usize,
+    max_messages: usize,
+    max_bytes: usize,
+}
+
+impl Default for ChunkAssembler {
+    fn default() -> Self {
+        Self::with_limits(MAX_PENDING_MESSAGES, MAX_PENDING_BYTES)
+    }
+}
+
+impl ChunkAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_limits(max_messages: usize, max_bytes: usize) -> Self {
+        Self {
+            pending: HashMap::new(),
+            pending_bytes: 0,
+            max_messages,
+            max_bytes,
+        }
+    }
+
+    /// Feed one datagram. Returns the full payload once every chunk of a
+    /// message has arrived, or the datagram itself when it isn't chunked.
+    pub fn push(&mut self, datagram: &[u8]) -> Result<Option<Vec<u8>>, String> {
+        if !datagram.starts_with(&CHUNK_MAGIC) {
+            return Ok(Some(datagram.to_vec()));
+        }
+        if datagram.len() < CHUNK_HEADER_LEN {
+            return Err("Truncated GELF chunk header".to_string());
+        }
+
+        let mut message_id = [0u8; 8];
+        message_id.copy_from_slice(&datagram[2..10]);
+        let sequence = datagram[10];
+        let count = datagram[11];
+        if count == 0 || count > MAX_CHUNKS || sequence >= count {
+            return Err(format!("Invalid GELF chunk {} of {}", sequence, count));
+        }
+
+        self.evict_expired();
+
+        if !self.pending.contains_key(&message_id) {
+            while self.pending.len() >= self.max_messages {
+                self.evict_oldest(&message_id);
+            }
+            self.pending.insert(
+                message_id,
+                PartialMessage {
+                    chunks: vec![None; count as usize],
+                    received: 0,
+                    bytes: 0,
+                    first_seen: Instant::now(),
+                },
+            );
+        }
+        if self.pending[&message_id].chunks.len() != count as usize {
+            self.remove(&message_id);
+            return Err("GELF chunk count changed mid-message".to_string());
+        }
+
+        let data = &datagram[CHUNK_HEADER_LEN..];
+        if self.pending[&message_id].chunks[sequence as usize].is_none() {
+            // Make room by dropping older messages, never the one being filled
+            while self.pending_bytes + data.len() > self.max_bytes && self.pending.len() > 1 {
+                self.evict_oldest(&message_id);
+            }
+            if self.pending_bytes + data.len() > self.max_bytes {
+                self.remove(&message_id);
+                return Err("GELF message exceeds the chunk buffer".to_string());
+            }
+
+            let partial = self.pending.get_mut(&message_id).expect("inserted above");
+            partial.chunks[sequence as usize] = Some(data.to_vec());
+            partial.received += 1;
+            partial.bytes += data.len();
+            self.pending_bytes += data.len();
+        }
+        let partial = &self.pending[&message_id];
+        if partial.received < partial.chunks.len() {
+            return Ok(None);
+        }
+
+        let complete = self
+            .remove(&message_id)
+            .map(|p| p.chunks.into_iter().flatten().flatten().collect());
+        Ok(complete)
+    }
+
+    fn remove(&mut self, message_id: &[u8; 8]) -> Option<PartialMessage> {
+        let partial = self.pending.remove(message_id)?;
+        self.pending_bytes -= partial.bytes;
+        Some(partial)
+    }
+
+    /// Drop the incomplete message that arrived first, other than `keep`
+    fn evict_oldest(&mut self, keep: &[u8; 8]) {
+        let oldest = self
+            .pending
+            .iter()
+            .filter(|(id, _)| *id != keep)
+            .min_by_key(|(_, partial)| partial.first_seen)
+            .map(|(id, _)| *id);
+        if let Some(id) = oldest {
+            self.remove(&id);
+        }
+    }
+
+    fn evict_expired(&mut self) {
+        let pending_bytes = &mut self.pending_bytes;
+        self.pending.retain(|_, partial| {
+            let keep = partial.first_seen.elapsed() < CHUNK_TIMEOUT;
+            if !keep {
+                *pending_bytes -= partial.bytes;
+            }
+            keep
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    use flate2::Compression;
+    use flate2::write::{GzEncoder, ZlibEncoder};
+
+    fn chunk(id: u8, sequence: u8, count: u8, data: &[u8]) -> Vec<u8> {
+        let mut datagram = CHUNK_MAGIC.to_vec();
+        datagram.extend_from_slice(&[id; 8]);
+        datagram.extend_from_slice(&[sequence, count]);
+        datagram.extend_from_slice(data);
+        datagram
+    }
+
+    #[test]
+    fn test_decompress_gzip_and_zlib() {
+        let raw = br#"{"short_message":"hi"}"#;
+
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(raw).unwrap();
+        assert_eq!(decompress_payload(&gzip.finish().unwrap()).unwrap(), raw);
+
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(raw).unwrap();
+        assert_eq!(decompress_payload(&zlib.finish().unwrap()).unwrap(), raw);
+
+        assert_eq!(decompress_payload(raw).unwrap(), raw);
+    }
+
+    #[test]
+    fn test_reassembles_out_of_order_chunks() {
+        let mut assembler = ChunkAssembler::new();
+
+        assert_eq!(assembler.push(&chunk(1, 1, 2, b"world")).unwrap(), None);
+        assert_eq!(assembler.push(&chunk(2, 0, 2, b"other")).unwrap(), None);
+        assert_eq!(
+            assembler.push(&chunk(1, 0, 2, b"hello ")).unwrap(),
+            Some(b"hello world".to_vec())
+        );
+        assert!(assembler.pending.contains_key(&[2; 8]));
+    }
+
+    #[test]
+    fn test_rejects_payload_expanding_past_limit() {
+        let raw = vec![b'a'; 4096];
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(&raw).unwrap();
+        let compressed = gzip.finish().unwrap();
+
+        assert_eq!(decompress_with_limit(&compressed, 4096).unwrap(), raw);
+        assert!(decompress_with_limit(&compressed, 4095).is_err());
+    }
+
+    #[test]
+    fn test_pending_messages_are_bounded_oldest_first() {
+        let mut assembler = ChunkAssembler::with_limits(2, 12);
+
+        assembler.push(&chunk(1, 0, 2, b"aaaa")).unwrap();
+        assembler.push(&chunk(2, 0, 2, b"bbbb")).unwrap();
+        // A third message pushes out the first
+        assembler.push(&chunk(3, 0, 2, b"cccc")).unwrap();
+        assert!(!assembler.pending.contains_key(&[1; 8]));
+
+        // Filling message 3 past the byte budget drops message 2, not 3
+        assert_eq!(
+            assembler.push(&chunk(3, 1, 2, b"dddddddd")).unwrap(),
+            Some(b"ccccdddddddd".to_vec())
+        );
+        assert!(assembler.pending.is_empty());
+        assert_eq!(assembler.pending_bytes, 0);
+
+        // A message too large for the buffer on its own is dropped
+        assert!(assembler.push(&chunk(4, 0, 2, &[0; 13])).is_err());
+        assert!(assembler.pending.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_invalid_chunk_header() {
+        let mut assembler = ChunkAssembler::new();
+        assert!(assembler.push(&CHUNK_MAGIC).is_err());
+        assert!(assembler.push(&chunk(1, 3, 2, b"x")).is_err());
+        assert!(assembler.push(&chunk(1, 0, 0, b"x")).is_err());
+    }
+}
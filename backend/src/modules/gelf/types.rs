@@ -0,0 +1,31 @@
+//! GELF (Graylog Extended Log Format) 1.1 message type
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// A single GELF message.
+/// Additional fields are `_`-prefixed and collected into `additional` (along with `version`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct GelfMessage {
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub short_message: Option<String>,
+    #[serde(default)]
+    pub full_message: Option<String>,
+    /// Seconds since the epoch, with optional fractional milliseconds
+    #[serde(default)]
+    pub timestamp: Option<f64>,
+    /// Syslog severity (0-7)
+    #[serde(default)]
+    pub level: Option<u8>,
+    // Deprecated in GELF 1.1 but still sent by older clients
+    #[serde(default)]
+    pub facility: Option<String>,
+    #[serde(default)]
+    pub file: Option<String>,
+    #[serde(default)]
+    pub line: Option<u64>,
+    #[serde(flatten)]
+    pub additional: Map<String, Value>,
+}
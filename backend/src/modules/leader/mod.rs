@@ -0,0 +1,253 @@
+//! Leader election for singleton background tasks
+//!
+//! With several backend replicas, tasks such as the alert evaluator and the
+//! retention cleanups must run on only one of them. Each task contends for a
+//! Postgres advisory lock; the replica holding it runs the task. Advisory locks
+//! belong to a database session, so if the leader dies its connection closes,
+//! the lock is released and another replica takes over on its next attempt.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use sqlx::pool::PoolConnection;
+use sqlx::{Connection, PgConnection, PgPool, Postgres};
+
+/// First key of every advisory lock taken here, so task locks don't collide
+/// with advisory locks used for anything else
+const LOCK_NAMESPACE: i32 = 0x616c74;
+
+/// How long a follower waits before contending again
+const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How often the leader checks it still holds the lock
+const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Leadership of one task, held until released or lost
+#[async_trait]
+pub trait LeaderLease: Send {
+    /// Whether leadership is still held
+    async fn is_held(&mut self) -> bool;
+
+    /// Give up leadership
+    async fn release(self: Box<Self>);
+}
+
+/// A lock replicas contend for to run a task
+#[async_trait]
+pub trait LeaderLock: Send + Sync {
+    /// Try to become leader for `task`. Returns None if another replica holds it.
+    async fn try_acquire(&self, task: &str) -> Result<Option<Box<dyn LeaderLease>>, String>;
+}
+
+/// Leader lock backed by `pg_try_advisory_lock`
+pub struct PgAdvisoryLock {
+    pool: Arc<PgPool>,
+}
+
+impl PgAdvisoryLock {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl LeaderLock for PgAdvisoryLock {
+    async fn try_acquire(&self, task: &str) -> Result<Option<Box<dyn LeaderLease>>, String> {
+        let mut conn: PoolConnection<Postgres> =
+            self.pool.acquire().await.map_err(|e| e.to_string())?;
+
+        let acquired: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1, hashtext($2))")
+            .bind(LOCK_NAMESPACE)
+            .bind(task)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !acquired {
+            return Ok(None);
+        }
+
+        // Keep the session out of the pool: the lock lives as long as it does
+        Ok(Some(Box::new(PgAdvisoryLease {
+            conn: conn.detach(),
+            task: task.to_string(),
+        })))
+    }
+}
+
+struct PgAdvisoryLease {
+    conn: PgConnection,
+    task: String,
+}
+
+#[async_trait]
+impl LeaderLease for PgAdvisoryLease {
+    async fn is_held(&mut self) -> bool {
+        self.conn.ping().await.is_ok()
+    }
+
+    async fn release(self: Box<Self>) {
+        let Self { mut conn, task } = *self;
+        let released = sqlx::query("SELECT pg_advisory_unlock($1, hashtext($2))")
+            .bind(LOCK_NAMESPACE)
+            .bind(&task)
+            .execute(&mut conn)
+            .await;
+        if let Err(e) = released {
+            tracing::warn!(task = %task, error = %e, "Failed to release leader lock");
+        }
+        // Closing the session releases the lock regardless
+        let _ = conn.close().await;
+    }
+}
+
+/// Runs singleton tasks on whichever replica holds each task's lock
+pub struct LeaderElection<L: LeaderLock> {
+    lock: Arc<L>,
+    retry_interval: Duration,
+    check_interval: Duration,
+}
+
+impl<L: LeaderLock + 'static> LeaderElection<L> {
+    pub fn new(lock: Arc<L>) -> Self {
+        Self {
+            lock,
+            retry_interval: DEFAULT_RETRY_INTERVAL,
+            check_interval: DEFAULT_CHECK_INTERVAL,
+        }
+    }
+
+    /// Set how often followers contend and the leader re-checks its lock
+    pub fn with_intervals(mut self, retry_interval: Duration, check_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self.check_interval = check_interval;
+        self
+    }
+
+    /// Run `task` only while this replica is leader for `task_name` (runs forever).
+    /// If leadership is lost the task is stopped and this replica contends again;
+    /// `task` is called to start a fresh run each time leadership is gained.
+    pub async fn run_as_leader<F, Fut>(self: Arc<Self>, task_name: &'static str, task: F)
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        loop {
+            match self.lock.try_acquire(task_name).await {
+                Ok(Some(mut lease)) => {
+                    tracing::info!(task = task_name, "Acquired leadership; starting task");
+
+                    let run = task();
+                    tokio::pin!(run);
+                    let mut check = tokio::time::interval(self.check_interval);
+                    check.tick().await;
+
+                    loop {
+                        tokio::select! {
+                            _ = &mut run => {
+                                tracing::warn!(task = task_name, "Leader task exited; releasing leadership");
+                                lease.release().await;
+                                break;
+                            }
+                            _ = check.tick() => {
+                                if !lease.is_held().await {
+                                    tracing::warn!(task = task_name, "Lost leadership; stopping task");
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                }
+                Ok(None) => {
+                    tracing::debug!(task = task_name, "Another replica is leader");
+                }
+                Err(e) => {
+                    tracing::warn!(task = task_name, error = %e, "Failed to contend for leadership");
+                }
+            }
+
+            tokio::time::sleep(self.retry_interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+
+    /// Lock shared by "replicas" in one process; a lease is released when dropped,
+    /// like a dead replica's database session
+    #[derive(Default)]
+    struct InMemoryLeaderLock {
+        held: Arc<Mutex<HashSet<String>>>,
+    }
+
+    struct InMemoryLease {
+        held: Arc<Mutex<HashSet<String>>>,
+        task: String,
+    }
+
+    impl Drop for InMemoryLease {
+        fn drop(&mut self) {
+            self.held.lock().unwrap().remove(&self.task);
+        }
+    }
+
+    #[async_trait]
+    impl LeaderLease for InMemoryLease {
+        async fn is_held(&mut self) -> bool {
+            true
+        }
+
+        async fn release(self: Box<Self>) {}
+    }
+
+    #[async_trait]
+    impl LeaderLock for InMemoryLeaderLock {
+        async fn try_acquire(&self, task: &str) -> Result<Option<Box<dyn LeaderLease>>, String> {
+            if !self.held.lock().unwrap().insert(task.to_string()) {
+                return Ok(None);
+            }
+            Ok(Some(Box::new(InMemoryLease {
+                held: self.held.clone(),
+                task: task.to_string(),
+            })))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_one_replica_runs_the_task() {
+        let lock = Arc::new(InMemoryLeaderLock::default());
+        let started = Arc::new(Mutex::new(Vec::new()));
+
+        let replicas: Vec<_> = (0..2)
+            .map(|replica| {
+                let election = Arc::new(
+                    LeaderElection::new(lock.clone())
+                        .with_intervals(Duration::from_millis(10), Duration::from_millis(10)),
+                );
+                let started = started.clone();
+                tokio::spawn(election.run_as_leader("evaluator", move || {
+                    started.lock().unwrap().push(replica);
+                    std::future::pending::<()>()
+                }))
+            })
+            .collect();
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let leader = {
+            let started = started.lock().unwrap();
+            assert_eq!(started.len(), 1);
+            started[0]
+        };
+
+        // The leader dies: the other replica takes over
+        replicas[leader].abort();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(*started.lock().unwrap(), vec![leader, 1 - leader]);
+    }
+}
@@ -1,6 +1,10 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeSet;
+
+use crate::modules::logging::domain::LogDomainError;
+use crate::modules::projects::domain::{IngestMode, ServiceDisplay};
 
 // ==================== Commands ====================
 
@@ -19,6 +23,9 @@ pub struct LogInput {
     pub trace_id: Option<String>,
     #[serde(default)]
     pub span_id: Option<String>,
+    /// Client-supplied id; retries with the same id are stored once
+    #[serde(default)]
+    pub event_id: Option<String>,
 }
 
 /// Command to ingest logs
@@ -28,11 +35,51 @@ pub struct IngestLogsCommand {
     pub logs: Vec<LogInput>,
 }
 
+/// Command to ingest JSON logs that are reshaped by the project's field mapping
+#[derive(Debug, Clone)]
+pub struct IngestJsonLogsCommand {
+    pub project_id: String,
+    pub logs: Vec<Value>,
+    /// Acknowledgment mode requested by the client; None uses the project's
+    pub mode: Option<IngestMode>,
+}
+
+/// Command to ingest a newline-delimited plain-text body, one log per line
+#[derive(Debug, Clone)]
+pub struct IngestTextLogsCommand {
+    pub project_id: String,
+    pub body: String,
+    /// Level of lines without an extracted level; None uses the project's default
+    pub level: Option<String>,
+    /// Acknowledgment mode requested by the client; None uses the project's
+    pub mode: Option<IngestMode>,
+}
+
+/// Raw payload that could not be parsed into a LogInput
+#[derive(Debug, Clone)]
+pub struct DeadLetterInput {
+    pub payload: String,
+    pub error: String,
+}
+
+/// Command to ingest logs parsed from a text wire format (e.g. syslog).
+/// Lines that failed to parse are kept as dead letters.
+#[derive(Debug, Clone)]
+pub struct IngestRawLogsCommand {
+    pub project_id: String,
+    pub format: String,
+    pub logs: Vec<LogInput>,
+    pub dead_letters: Vec<DeadLetterInput>,
+}
+
 /// Query filters for logs
 #[derive(Debug, Clone, Default, Deserialize)]
 pub struct QueryFilters {
     #[serde(default)]
     pub levels: Option<Vec<String>>,
+    /// Minimum severity; combines with `levels` if both are given
+    #[serde(default)]
+    pub min_level: Option<String>,
     #[serde(default)]
     pub start_time: Option<DateTime<Utc>>,
     #[serde(default)]
@@ -68,6 +115,10 @@ pub struct QueryLogsCommand {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
     pub sort: Option<String>,
+    /// Comma-separated fields to return; None returns every field
+    pub fields: Option<String>,
+    /// Count every matching log for `total`; costly on large projects
+    pub include_total: bool,
     pub requesting_user_id: String,
 }
 
@@ -76,20 +127,35 @@ pub struct QueryLogsCommand {
 /// Response after ingesting logs
 #[derive(Debug, Clone, Serialize)]
 pub struct IngestResponse {
+    /// Logs stored, or in async mode queued for writing
     pub accepted: u32,
     pub rejected: u32,
+    /// Logs skipped because their event id was already ingested; always 0 in
+    /// async mode, where duplicates are only found once the batch is written
+    pub duplicates: u32,
+    /// Accepted logs stamped with the server's receive time because they had no timestamp
+    pub server_timestamps: u32,
+    /// Valid logs dropped for being less severe than the project's minimum ingest level
+    pub below_min_level: u32,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub errors: Vec<String>,
+    pub mode: IngestMode,
 }
 
-/// Single log entry response
+/// Single log entry response. Every field is present unless a query's field
+/// projection leaves it out.
 #[derive(Debug, Clone, Serialize)]
 pub struct LogResponse {
-    pub id: String,
-    pub level: String,
-    pub message: String,
-    pub timestamp: DateTime<Utc>,
-    pub received_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub received_at: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -98,14 +164,191 @@ pub struct LogResponse {
     pub trace_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub span_id: Option<String>,
+    /// Ingest order among logs with the same timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<i64>,
+    /// Display details for the service in `source`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<ServiceDisplay>,
+}
+
+/// Fields a log query can return
+const LOG_FIELDS: [&str; 11] = [
+    "id",
+    "level",
+    "message",
+    "timestamp",
+    "received_at",
+    "source",
+    "metadata",
+    "trace_id",
+    "span_id",
+    "sequence",
+    "service",
+];
+
+/// The fields a log query returns. `metadata.<key>` selects a single metadata
+/// key; plain `metadata` keeps all of them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogFieldProjection {
+    /// None returns every field
+    fields: Option<BTreeSet<String>>,
+    metadata_keys: BTreeSet<String>,
+}
+
+impl LogFieldProjection {
+    /// Parse a comma-separated field list
+    pub fn parse(spec: &str) -> Result<Self, LogDomainError> {
+        let mut fields = BTreeSet::new();
+        let mut metadata_keys = BTreeSet::new();
+        for field in spec.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+            if let Some(key) = field.strip_prefix("metadata.") {
+                if key.is_empty() {
+                    return Err(LogDomainError::InvalidField(
+                        "metadata key must not be empty".to_string(),
+                    ));
+                }
+                metadata_keys.insert(key.to_string());
+            } else if LOG_FIELDS.contains(&field) {
+                fields.insert(field.to_string());
+            } else {
+                return Err(LogDomainError::InvalidField(format!(
+                    "Unknown field '{}'. Valid fields: {}, metadata.<key>",
+                    field,
+                    LOG_FIELDS.join(", ")
+                )));
+            }
+        }
+        if fields.is_empty() && metadata_keys.is_empty() {
+            return Ok(Self::default());
+        }
+        Ok(Self {
+            fields: Some(fields),
+            metadata_keys,
+        })
+    }
+
+    fn includes(&self, field: &str) -> bool {
+        self.fields.as_ref().is_none_or(|f| f.contains(field))
+    }
+
+    /// Drop the fields and metadata keys that weren't selected
+    pub fn apply(&self, mut log: LogResponse) -> LogResponse {
+        if self.fields.is_none() {
+            return log;
+        }
+        if !self.includes("id") {
+            log.id = None;
+        }
+        if !self.includes("level") {
+            log.level = None;
+        }
+        if !self.includes("message") {
+            log.message = None;
+        }
+        if !self.includes("timestamp") {
+            log.timestamp = None;
+        }
+        if !self.includes("received_at") {
+            log.received_at = None;
+        }
+        if !self.includes("source") {
+            log.source = None;
+        }
+        if !self.includes("trace_id") {
+            log.trace_id = None;
+        }
+        if !self.includes("span_id") {
+            log.span_id = None;
+        }
+        if !self.includes("sequence") {
+            log.sequence = None;
+        }
+        if !self.includes("service") {
+            log.service = None;
+        }
+        if !self.includes("metadata") {
+            log.metadata = log.metadata.and_then(|metadata| {
+                let metadata = metadata.as_object()?;
+                let mut selected = serde_json::Map::new();
+                for key in &self.metadata_keys {
+                    select_metadata(metadata, key, &mut selected);
+                }
+                (!selected.is_empty()).then_some(Value::Object(selected))
+            });
+        }
+        log
+    }
+}
+
+/// Copy `key` from `metadata` into `selected`: a literal top-level key if there
+/// is one, otherwise the dotted path, keeping its nesting
+fn select_metadata(
+    metadata: &serde_json::Map<String, Value>,
+    key: &str,
+    selected: &mut serde_json::Map<String, Value>,
+) {
+    if let Some(value) = metadata.get(key) {
+        selected.insert(key.to_string(), value.clone());
+        return;
+    }
+    let Some((head, rest)) = key.split_once('.') else {
+        return;
+    };
+    let Some(Value::Object(inner)) = metadata.get(head) else {
+        return;
+    };
+    let mut nested = match selected.remove(head) {
+        Some(Value::Object(nested)) => nested,
+        _ => serde_json::Map::new(),
+    };
+    select_metadata(inner, rest, &mut nested);
+    if !nested.is_empty() {
+        selected.insert(head.to_string(), Value::Object(nested));
+    }
 }
 
 /// Response for log query
 #[derive(Debug, Clone, Serialize)]
 pub struct LogQueryResponse {
     pub logs: Vec<LogResponse>,
-    pub total: i64,
+    /// Only present when the query asked for it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
     pub has_more: bool,
+    /// Effective page size after applying the default and maximum; sent as
+    /// the `x-pagination-limit` header
+    pub limit: i64,
+}
+
+/// Command to group logs by the project's primary field
+#[derive(Debug, Clone)]
+pub struct GroupLogsCommand {
+    pub project_id: String,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+    /// Most groups to return; largest first
+    pub limit: Option<i32>,
+    /// Most recent logs to include per group
+    pub samples: Option<i32>,
+    pub requesting_user_id: String,
+}
+
+/// Logs sharing one value of the grouping field
+#[derive(Debug, Clone, Serialize)]
+pub struct LogGroupResponse {
+    /// Null for logs without the field
+    pub value: Option<String>,
+    pub count: i64,
+    pub samples: Vec<LogResponse>,
+}
+
+/// Response for a grouped log query
+#[derive(Debug, Clone, Serialize)]
+pub struct LogGroupsResponse {
+    /// Field the logs were grouped by, e.g. "source" or "metadata.logger"
+    pub field: String,
+    pub groups: Vec<LogGroupResponse>,
 }
 
 /// Log level count
@@ -248,6 +491,8 @@ pub struct MetricsQuery {
     pub start_time: Option<DateTime<Utc>>,
     pub end_time: Option<DateTime<Utc>>,
     pub top_sources_limit: i32,
+    /// Timezone overriding the project's for bucket alignment
+    pub timezone: Option<String>,
     pub requesting_user_id: String,
 }
 
@@ -1,19 +1,40 @@
+use std::collections::HashMap;
 use std::io::{Cursor, Write};
 use std::sync::Arc;
 
-use chrono::Utc;
-use zip::write::SimpleFileOptions;
+use chrono::{Duration, Utc};
 use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
 
 use crate::modules::auth::application::ports::IdGenerator;
 use crate::modules::auth::domain::UserId;
 use crate::modules::logging::application::dto::*;
+use crate::modules::logging::application::services::log_write_queue::{LogWriteQueue, store_logs};
 use crate::modules::logging::domain::{
-    LogDomainError, LogEntry, LogFilters, LogId, LogLevel, LogRepository, LogStats,
-    MetadataFilter, MetadataOperator, Pagination, SortOrder, SpanId, TraceId,
+    DeadLetter, GeoIpDatabase, LogDomainError, LogEntry, LogFieldMapper, LogFilters, LogId,
+    LogIngestObserver, LogLevel, LogRedactor, LogRepository, LogStats, MetadataFilter,
+    MetadataOperator, Pagination, PlainTextLineParser, SortOrder, SpanId, TraceId,
 };
 use crate::modules::organizations::domain::{OrgId, OrganizationMemberRepository};
-use crate::modules::projects::domain::{ProjectId, ProjectRepository};
+use crate::modules::projects::domain::{
+    FeatureFlag, IngestMode, MissingTimestampPolicy, Project, ProjectId, ProjectRepository,
+    ProjectSettings, ServiceDisplay, ServiceMetadataRepository, resolve_service_displays,
+};
+use crate::shared::{IdFormatPolicy, PaginationConfig, parse_timezone};
+
+/// Event ids suppress duplicates for an hour unless configured otherwise
+const DEFAULT_DEDUP_WINDOW_SECS: i64 = 3600;
+
+/// Groups returned by a grouped log query unless asked otherwise, and at most
+const DEFAULT_LOG_GROUPS: i32 = 20;
+const MAX_LOG_GROUPS: i32 = 100;
+
+/// Sample logs per group unless asked otherwise, and at most
+const DEFAULT_LOG_GROUP_SAMPLES: i32 = 3;
+const MAX_LOG_GROUP_SAMPLES: i32 = 20;
+
+/// Longest accepted client event id
+const MAX_EVENT_ID_LEN: usize = 255;
 
 /// Log service - orchestrates all logging use cases
 pub struct LogService<LR, PR, MR, ID>
@@ -27,6 +48,19 @@ where
     project_repo: Arc<PR>,
     member_repo: Arc<MR>,
     id_generator: Arc<ID>,
+    pagination: PaginationConfig,
+    /// How long a client event id blocks duplicates of the same log
+    dedup_window: Duration,
+    id_format_policy: IdFormatPolicy,
+    /// Display names for the services in query results; raw names are shown without it
+    service_metadata: Option<Arc<dyn ServiceMetadataRepository>>,
+    /// Writer for async-mode ingest; without it every ingest is synchronous
+    write_queue: Option<LogWriteQueue>,
+    /// Locations for client IPs in projects with a GeoIP field; none are
+    /// looked up without it
+    geoip: Option<Arc<GeoIpDatabase>>,
+    /// Told about synchronously written logs
+    ingest_observer: Option<Arc<dyn LogIngestObserver>>,
 }
 
 impl<LR, PR, MR, ID> LogService<LR, PR, MR, ID>
@@ -41,27 +75,67 @@ where
         project_repo: Arc<PR>,
         member_repo: Arc<MR>,
         id_generator: Arc<ID>,
+        pagination: PaginationConfig,
     ) -> Self {
         Self {
             log_repo,
             project_repo,
             member_repo,
             id_generator,
+            pagination,
+            dedup_window: Duration::seconds(DEFAULT_DEDUP_WINDOW_SECS),
+            id_format_policy: IdFormatPolicy::default(),
+            service_metadata: None,
+            write_queue: None,
+            geoip: None,
+            ingest_observer: None,
         }
     }
 
+    /// Set how trace and span ids on ingested logs are validated
+    pub fn with_id_format_policy(mut self, id_format_policy: IdFormatPolicy) -> Self {
+        self.id_format_policy = id_format_policy;
+        self
+    }
+
+    /// Set how long an event id suppresses duplicates
+    pub fn with_dedup_window(mut self, dedup_window: Duration) -> Self {
+        self.dedup_window = dedup_window;
+        self
+    }
+
+    /// Resolve service display names in query results from `repo`
+    pub fn with_service_metadata(mut self, repo: Arc<dyn ServiceMetadataRepository>) -> Self {
+        self.service_metadata = Some(repo);
+        self
+    }
+
+    /// Queue async-mode ingest batches on `queue`
+    pub fn with_write_queue(mut self, queue: LogWriteQueue) -> Self {
+        self.write_queue = Some(queue);
+        self
+    }
+
+    /// Enrich logs carrying a client IP with its location from `db`
+    pub fn with_geoip(mut self, db: Arc<GeoIpDatabase>) -> Self {
+        self.geoip = Some(db);
+        self
+    }
+
+    /// Tell `observer` about logs written by synchronous ingest. Queued
+    /// writes are reported by the write queue's own observer.
+    pub fn with_ingest_observer(mut self, observer: Arc<dyn LogIngestObserver>) -> Self {
+        self.ingest_observer = Some(observer);
+        self
+    }
+
     /// Get a reference to the log repository (for use by alert evaluator)
     pub fn log_repo(&self) -> Arc<LR> {
         self.log_repo.clone()
     }
 
-    /// Verify user has access to project via org membership
-    async fn verify_project_access(
-        &self,
-        project_id: &ProjectId,
-        user_id: &str,
-    ) -> Result<(), LogDomainError> {
-        // Get project
+    /// Load the project, failing if it doesn't exist or was deleted
+    async fn active_project(&self, project_id: &ProjectId) -> Result<Project, LogDomainError> {
         let project = self
             .project_repo
             .find_by_id(project_id)
@@ -73,6 +147,17 @@ where
             return Err(LogDomainError::ProjectDeleted);
         }
 
+        Ok(project)
+    }
+
+    /// Verify user has access to project via org membership
+    async fn verify_project_access(
+        &self,
+        project_id: &ProjectId,
+        user_id: &str,
+    ) -> Result<Project, LogDomainError> {
+        let project = self.active_project(project_id).await?;
+
         // Verify user is member of the org
         let user_id = UserId::new(user_id.to_string());
         let org_id = OrgId::new(project.organization_id().as_str().to_string());
@@ -87,22 +172,180 @@ where
             return Err(LogDomainError::NotOrgMember);
         }
 
-        Ok(())
+        Ok(project)
+    }
+
+    /// Load the project's ingestion settings
+    async fn ingest_settings(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<ProjectSettings, LogDomainError> {
+        let project = self
+            .project_repo
+            .find_by_id(project_id)
+            .await
+            .map_err(|e| LogDomainError::InternalError(e.to_string()))?
+            .ok_or(LogDomainError::ProjectNotFound)?;
+
+        Ok(project.settings().clone())
+    }
+
+    /// Build the project's redactor. Fails closed: logs are never stored unredacted.
+    fn redactor(settings: &ProjectSettings) -> Result<LogRedactor, LogDomainError> {
+        LogRedactor::new(&settings.redaction)
+            .map_err(|e| LogDomainError::InternalError(format!("Invalid redaction pattern: {}", e)))
     }
 
     /// Ingest a batch of logs
     /// Called after API key validation (project_id comes from validated key)
     pub async fn ingest(&self, cmd: IngestLogsCommand) -> Result<IngestResponse, LogDomainError> {
         let project_id = ProjectId::new(cmd.project_id);
-        let mut accepted = 0u32;
+        let settings = self.ingest_settings(&project_id).await?;
+        self.ingest_inputs(
+            project_id,
+            &settings,
+            cmd.logs.into_iter().map(Ok).collect(),
+            IngestMode::Sync,
+        )
+        .await
+    }
+
+    /// Ingest JSON logs from the native endpoint, reshaping each one with the
+    /// project's field mapping. Logs that don't map to a valid entry are rejected.
+    pub async fn ingest_json(
+        &self,
+        cmd: IngestJsonLogsCommand,
+    ) -> Result<IngestResponse, LogDomainError> {
+        let project_id = ProjectId::new(cmd.project_id);
+        let settings = self.ingest_settings(&project_id).await?;
+        let mapper = LogFieldMapper::new(&settings.field_mapping);
+        let mode = cmd.mode.unwrap_or(settings.ingest_mode);
+
+        let inputs = cmd
+            .logs
+            .into_iter()
+            .map(|log| {
+                mapper
+                    .map(log)
+                    .and_then(|log| serde_json::from_value(log).map_err(|e| e.to_string()))
+                    .map_err(LogDomainError::InvalidLogShape)
+            })
+            .collect();
+        self.ingest_inputs(project_id, &settings, inputs, mode)
+            .await
+    }
+
+    /// Ingest a plain-text body with one log per non-blank line. Level and
+    /// timestamp are extracted with the project's plain-text patterns; lines
+    /// without a recognized level get the requested (or project) default.
+    pub async fn ingest_text(
+        &self,
+        cmd: IngestTextLogsCommand,
+    ) -> Result<IngestResponse, LogDomainError> {
+        let project_id = ProjectId::new(cmd.project_id);
+        let settings = self.ingest_settings(&project_id).await?;
+        let parser = PlainTextLineParser::new(&settings.plain_text).map_err(|e| {
+            LogDomainError::InternalError(format!("Invalid plain-text pattern: {}", e))
+        })?;
+        let default_level = match cmd.level {
+            Some(level) => LogLevel::from_str(&level)?,
+            None => settings
+                .plain_text
+                .default_level
+                .as_deref()
+                .and_then(|level| LogLevel::from_str(level).ok())
+                .unwrap_or(LogLevel::Info),
+        };
+        let mode = cmd.mode.unwrap_or(settings.ingest_mode);
+
+        let inputs = parser
+            .parse_body(&cmd.body)
+            .into_iter()
+            .map(|line| {
+                let level = line
+                    .level
+                    .and_then(|level| LogLevel::from_str(&level).ok())
+                    .unwrap_or(default_level);
+                Ok(LogInput {
+                    level: level.as_str().to_string(),
+                    message: line.message,
+                    timestamp: line.timestamp,
+                    source: None,
+                    metadata: None,
+                    trace_id: None,
+                    span_id: None,
+                    event_id: None,
+                })
+            })
+            .collect();
+        self.ingest_inputs(project_id, &settings, inputs, mode)
+            .await
+    }
+
+    async fn ingest_inputs(
+        &self,
+        project_id: ProjectId,
+        settings: &ProjectSettings,
+        logs: Vec<Result<LogInput, LogDomainError>>,
+        mode: IngestMode,
+    ) -> Result<IngestResponse, LogDomainError> {
+        let redactor = Self::redactor(settings)?;
         let mut rejected = 0u32;
+        let mut server_timestamps = 0u32;
+        let mut below_min_level = 0u32;
         let mut errors = Vec::new();
         let mut valid_logs = Vec::new();
-
-        // Validate and convert each log entry
-        for (idx, input) in cmd.logs.into_iter().enumerate() {
-            match self.validate_and_convert_log(&project_id, input) {
+        let received_at = Utc::now();
+        let min_level = settings
+            .min_ingest_level
+            .as_deref()
+            .and_then(|level| LogLevel::from_str(level).ok());
+        let geoip = self
+            .geoip
+            .as_deref()
+            .zip(settings.geoip_field.as_deref())
+            .filter(|_| settings.feature_enabled(FeatureFlag::GeoipEnrichment));
+
+        // Validate and convert each log entry, redacting secrets before anything is stored
+        for (idx, input) in logs.into_iter().enumerate() {
+            let mut stamped = false;
+            let converted = input.and_then(|mut input| {
+                if input.timestamp.is_none() {
+                    if settings.missing_timestamp_policy == MissingTimestampPolicy::Reject {
+                        return Err(LogDomainError::InvalidTimestamp(
+                            "timestamp is required".to_string(),
+                        ));
+                    }
+                    input.timestamp = Some(received_at);
+                    stamped = true;
+                } else {
+                    input.timestamp = input
+                        .timestamp
+                        .map(|ts| settings.log_timestamp_precision.truncate(ts));
+                }
+                // Enriched before redaction, so the IP itself can still be redacted
+                if let (Some((db, field)), Some(metadata)) = (geoip, input.metadata.as_mut()) {
+                    db.enrich(metadata, field);
+                }
+                if !redactor.is_noop() {
+                    input.message = redactor.redact_text(&input.message).into_owned();
+                    if let Some(metadata) = input.metadata.as_mut() {
+                        redactor.redact_metadata(metadata);
+                    }
+                }
+                self.validate_and_convert_log(&project_id, input)
+            });
+            match converted {
+                Ok(log_entry)
+                    if min_level
+                        .is_some_and(|min| log_entry.level().severity() < min.severity()) =>
+                {
+                    below_min_level += 1;
+                }
                 Ok(log_entry) => {
+                    if stamped {
+                        server_timestamps += 1;
+                    }
                     valid_logs.push(log_entry);
                 }
                 Err(e) => {
@@ -112,27 +355,90 @@ where
             }
         }
 
-        // Save valid logs in batch
-        if !valid_logs.is_empty() {
-            match self.log_repo.save_batch(&valid_logs).await {
-                Ok(count) => {
-                    accepted = count;
-                }
-                Err(e) => {
-                    // If batch save fails, all are rejected
-                    rejected += valid_logs.len() as u32;
-                    errors.push(format!("Batch save failed: {}", e));
-                }
+        // Without a write queue, async requests fall back to a synchronous write
+        if mode == IngestMode::Async
+            && let Some(queue) = &self.write_queue
+        {
+            let accepted = valid_logs.len() as u32;
+            if !valid_logs.is_empty() {
+                queue.enqueue(valid_logs)?;
             }
+            return Ok(IngestResponse {
+                accepted,
+                rejected,
+                duplicates: 0,
+                server_timestamps,
+                below_min_level,
+                errors,
+                mode: IngestMode::Async,
+            });
         }
 
+        let stored = store_logs(
+            self.log_repo.as_ref(),
+            valid_logs,
+            self.dedup_window,
+            self.ingest_observer.as_deref(),
+        )
+        .await;
+        errors.extend(stored.errors);
+
         Ok(IngestResponse {
-            accepted,
-            rejected,
+            accepted: stored.accepted,
+            rejected: rejected + stored.rejected,
+            duplicates: stored.duplicates,
+            server_timestamps,
+            below_min_level,
             errors,
+            mode: IngestMode::Sync,
         })
     }
 
+    /// Ingest logs parsed from a text wire format, storing unparseable lines as dead letters.
+    /// Dead letters count as rejected in the response.
+    pub async fn ingest_raw(
+        &self,
+        cmd: IngestRawLogsCommand,
+    ) -> Result<IngestResponse, LogDomainError> {
+        let mut response = self
+            .ingest(IngestLogsCommand {
+                project_id: cmd.project_id.clone(),
+                logs: cmd.logs,
+            })
+            .await?;
+
+        if cmd.dead_letters.is_empty() {
+            return Ok(response);
+        }
+
+        let project_id = ProjectId::new(cmd.project_id);
+        // Raw payloads may contain the same secrets as messages
+        let redactor = Self::redactor(&self.ingest_settings(&project_id).await?)?;
+        let letters: Vec<DeadLetter> = cmd
+            .dead_letters
+            .into_iter()
+            .map(|input| {
+                response.errors.push(input.error.clone());
+                DeadLetter::new(
+                    self.id_generator.generate(),
+                    project_id.clone(),
+                    cmd.format.clone(),
+                    redactor.redact_text(&input.payload).into_owned(),
+                    input.error,
+                )
+            })
+            .collect();
+        response.rejected += letters.len() as u32;
+
+        if let Err(e) = self.log_repo.save_dead_letters(&letters).await {
+            response
+                .errors
+                .push(format!("Dead-letter save failed: {}", e));
+        }
+
+        Ok(response)
+    }
+
     /// Validate and convert LogInput to LogEntry
     fn validate_and_convert_log(
         &self,
@@ -149,9 +455,22 @@ where
             ));
         }
 
-        // Parse trace_id and span_id (they return Option, so flatten)
-        let trace_id = input.trace_id.and_then(TraceId::new);
-        let span_id = input.span_id.and_then(SpanId::new);
+        if let Some(event_id) = &input.event_id
+            && event_id.len() > MAX_EVENT_ID_LEN
+        {
+            return Err(LogDomainError::InvalidEventId(format!(
+                "Event id cannot exceed {} characters",
+                MAX_EVENT_ID_LEN
+            )));
+        }
+
+        // Malformed correlation ids are dropped rather than failing the log
+        let trace_id = input
+            .trace_id
+            .and_then(|id| TraceId::parse(&id, self.id_format_policy));
+        let span_id = input
+            .span_id
+            .and_then(|id| SpanId::parse(&id, self.id_format_policy));
 
         // Create log entry
         let log_id = LogId::new(self.id_generator.generate());
@@ -166,25 +485,58 @@ where
             input.metadata,
             trace_id,
             span_id,
-        ))
+        )
+        .with_event_id(input.event_id))
     }
 
     /// Query logs with filters
     pub async fn query(&self, cmd: QueryLogsCommand) -> Result<LogQueryResponse, LogDomainError> {
-        let project_id = ProjectId::new(cmd.project_id);
-
-        // Verify user access
+        let project_id = ProjectId::new(cmd.project_id.clone());
         self.verify_project_access(&project_id, &cmd.requesting_user_id)
             .await?;
 
-        // Convert query filters
-        let filters = self.convert_query_filters(cmd.filters)?;
+        self.query_project(project_id, cmd).await
+    }
 
-        // Build pagination
-        let pagination = Pagination {
-            limit: cmd.limit.unwrap_or(100),
-            offset: cmd.offset.unwrap_or(0),
-        };
+    /// Query logs for a request holding the project's public read token;
+    /// `requesting_user_id` is ignored
+    pub async fn query_public(
+        &self,
+        cmd: QueryLogsCommand,
+    ) -> Result<LogQueryResponse, LogDomainError> {
+        let project_id = ProjectId::new(cmd.project_id.clone());
+        self.active_project(&project_id).await?;
+
+        self.query_project(project_id, cmd).await
+    }
+
+    async fn query_project(
+        &self,
+        project_id: ProjectId,
+        cmd: QueryLogsCommand,
+    ) -> Result<LogQueryResponse, LogDomainError> {
+        // Convert query filters, expanding the project's metadata aliases
+        let settings = self.ingest_settings(&project_id).await?;
+        let filters = self.convert_query_filters(cmd.filters, &settings)?;
+        let projection = cmd
+            .fields
+            .map(|fields| {
+                fields
+                    .split(',')
+                    .map(|field| match settings.metadata_alias(field.trim()) {
+                        Some(path) => format!("metadata.{}", path),
+                        None => field.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .as_deref()
+            .map(LogFieldProjection::parse)
+            .transpose()?
+            .unwrap_or_default();
+
+        // Build pagination (default and clamp from config)
+        let pagination = self.pagination.resolve(cmd.limit, cmd.offset);
 
         // Parse sort order
         let sort = match cmd.sort.as_deref() {
@@ -195,35 +547,113 @@ where
         // Execute query
         let result = self
             .log_repo
-            .query(&project_id, &filters, &pagination, sort)
+            .query(&project_id, &filters, &pagination, sort, cmd.include_total)
             .await?;
 
-        // Convert to response
+        let services = self.service_displays(&project_id, &result.logs).await?;
         let logs = result
             .logs
-            .into_iter()
-            .map(|log| LogResponse {
-                id: log.id().as_str().to_string(),
-                level: log.level().to_string(),
-                message: log.message().to_string(),
-                timestamp: log.timestamp(),
-                received_at: log.received_at(),
-                source: log.source().map(|s| s.to_string()),
-                metadata: log.metadata().cloned(),
-                trace_id: log.trace_id().map(|t| t.as_str().to_string()),
-                span_id: log.span_id().map(|s| s.as_str().to_string()),
-            })
+            .iter()
+            .map(|log| Self::log_response(log, &services))
+            .map(|log| projection.apply(log))
             .collect();
 
         Ok(LogQueryResponse {
             logs,
             total: result.total,
             has_more: result.has_more,
+            limit: pagination.limit,
         })
     }
 
+    /// Count logs per value of the project's primary grouping field, with the
+    /// most recent logs of each group
+    pub async fn group(&self, cmd: GroupLogsCommand) -> Result<LogGroupsResponse, LogDomainError> {
+        let project_id = ProjectId::new(cmd.project_id);
+
+        self.verify_project_access(&project_id, &cmd.requesting_user_id)
+            .await?;
+
+        let field = self.ingest_settings(&project_id).await?.log_group_field();
+        let limit = cmd
+            .limit
+            .unwrap_or(DEFAULT_LOG_GROUPS)
+            .clamp(1, MAX_LOG_GROUPS);
+        let samples = cmd
+            .samples
+            .unwrap_or(DEFAULT_LOG_GROUP_SAMPLES)
+            .clamp(1, MAX_LOG_GROUP_SAMPLES);
+
+        let groups = self
+            .log_repo
+            .group_by_field(
+                &project_id,
+                &field,
+                cmd.start_time,
+                cmd.end_time,
+                limit,
+                samples,
+            )
+            .await?;
+
+        let logs: Vec<LogEntry> = groups.iter().flat_map(|g| g.samples.clone()).collect();
+        let services = self.service_displays(&project_id, &logs).await?;
+        let groups = groups
+            .into_iter()
+            .map(|group| LogGroupResponse {
+                value: group.value,
+                count: group.count,
+                samples: group
+                    .samples
+                    .iter()
+                    .map(|log| Self::log_response(log, &services))
+                    .collect(),
+            })
+            .collect();
+
+        Ok(LogGroupsResponse {
+            field: field.to_string(),
+            groups,
+        })
+    }
+
+    /// Look up every source's display details at once
+    async fn service_displays(
+        &self,
+        project_id: &ProjectId,
+        logs: &[LogEntry],
+    ) -> Result<HashMap<String, ServiceDisplay>, LogDomainError> {
+        let sources: Vec<String> = logs
+            .iter()
+            .filter_map(|log| log.source().map(String::from))
+            .collect();
+        resolve_service_displays(self.service_metadata.as_deref(), project_id, &sources)
+            .await
+            .map_err(|e| LogDomainError::InternalError(e.to_string()))
+    }
+
+    fn log_response(log: &LogEntry, services: &HashMap<String, ServiceDisplay>) -> LogResponse {
+        LogResponse {
+            id: Some(log.id().as_str().to_string()),
+            level: Some(log.level().to_string()),
+            message: Some(log.message().to_string()),
+            timestamp: Some(log.timestamp()),
+            received_at: Some(log.received_at()),
+            source: log.source().map(|s| s.to_string()),
+            metadata: log.metadata().cloned(),
+            trace_id: log.trace_id().map(|t| t.as_str().to_string()),
+            span_id: log.span_id().map(|s| s.as_str().to_string()),
+            sequence: log.sequence(),
+            service: log.source().and_then(|s| services.get(s).cloned()),
+        }
+    }
+
     /// Convert DTO filters to domain filters
-    fn convert_query_filters(&self, filters: QueryFilters) -> Result<LogFilters, LogDomainError> {
+    fn convert_query_filters(
+        &self,
+        filters: QueryFilters,
+        settings: &ProjectSettings,
+    ) -> Result<LogFilters, LogDomainError> {
         // Convert level strings to LogLevel enums
         let levels = filters
             .levels
@@ -234,6 +664,11 @@ where
                     .collect::<Result<Vec<_>, _>>()
             })
             .transpose()?;
+        let min_level = filters
+            .min_level
+            .as_deref()
+            .map(LogLevel::from_str)
+            .transpose()?;
 
         // Convert metadata filter inputs to domain MetadataFilter
         let metadata_filters = filters
@@ -242,12 +677,17 @@ where
             .into_iter()
             .map(|input| {
                 let operator = MetadataOperator::from_str(&input.operator)?;
-                MetadataFilter::new(input.key, operator, input.value)
+                let key = match settings.metadata_alias(&input.key) {
+                    Some(path) => path.to_string(),
+                    None => input.key,
+                };
+                MetadataFilter::new(key, operator, input.value)
             })
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(LogFilters {
             levels,
+            min_level,
             start_time: filters.start_time,
             end_time: filters.end_time,
             source: filters.source,
@@ -291,26 +731,60 @@ where
     }
 
     /// Get metrics for dashboard charts
-    pub async fn get_metrics(&self, query: MetricsQuery) -> Result<MetricsResponse, LogDomainError> {
+    pub async fn get_metrics(
+        &self,
+        query: MetricsQuery,
+    ) -> Result<MetricsResponse, LogDomainError> {
         let project_id = ProjectId::new(query.project_id.clone());
-
-        // Verify user access
-        self.verify_project_access(&project_id, &query.requesting_user_id)
+        let project = self
+            .verify_project_access(&project_id, &query.requesting_user_id)
             .await?;
 
+        self.project_metrics(&project, query).await
+    }
+
+    /// Dashboard metrics for a request holding the project's public read token;
+    /// `requesting_user_id` is ignored
+    pub async fn get_metrics_public(
+        &self,
+        query: MetricsQuery,
+    ) -> Result<MetricsResponse, LogDomainError> {
+        let project_id = ProjectId::new(query.project_id.clone());
+        let project = self.active_project(&project_id).await?;
+
+        self.project_metrics(&project, query).await
+    }
+
+    async fn project_metrics(
+        &self,
+        project: &Project,
+        query: MetricsQuery,
+    ) -> Result<MetricsResponse, LogDomainError> {
+        let project_id = project.id().clone();
         let bucket_interval = query.bucket.to_interval();
+        let timezone = match query.timezone.as_deref() {
+            Some(name) => parse_timezone(name).ok_or_else(|| {
+                LogDomainError::InvalidTimezone(format!(
+                    "unknown timezone '{}': use an IANA name like Europe/Paris",
+                    name
+                ))
+            })?,
+            None => project.settings().timezone(),
+        };
 
         // Fetch all metrics data in parallel
         let (volume_result, levels_result, sources_result, stats_result) = tokio::join!(
             self.log_repo.get_volume_over_time(
                 &project_id,
                 bucket_interval,
+                timezone,
                 query.start_time,
                 query.end_time
             ),
             self.log_repo.get_levels_over_time(
                 &project_id,
                 bucket_interval,
+                timezone,
                 query.start_time,
                 query.end_time
             ),
@@ -449,6 +923,7 @@ where
                         .collect()
                 })
                 .filter(|v: &Vec<LogLevel>| !v.is_empty()),
+            min_level: None,
             start_time: request.start_time,
             end_time: request.end_time,
             source: request.source.clone(),
@@ -471,7 +946,13 @@ where
 
             let result = self
                 .log_repo
-                .query(&project_id_typed, &filters, &pagination, SortOrder::Descending)
+                .query(
+                    &project_id_typed,
+                    &filters,
+                    &pagination,
+                    SortOrder::Descending,
+                    false,
+                )
                 .await?;
 
             let batch_len = result.logs.len() as i64;
@@ -479,15 +960,17 @@ where
             // Convert logs to response format
             for log in result.logs {
                 all_logs.push(LogResponse {
-                    id: log.id().as_str().to_string(),
-                    level: log.level().to_string(),
-                    message: log.message().to_string(),
-                    timestamp: log.timestamp(),
-                    received_at: log.received_at(),
+                    id: Some(log.id().as_str().to_string()),
+                    level: Some(log.level().to_string()),
+                    message: Some(log.message().to_string()),
+                    timestamp: Some(log.timestamp()),
+                    received_at: Some(log.received_at()),
                     source: log.source().map(|s| s.to_string()),
                     metadata: log.metadata().cloned(),
                     trace_id: log.trace_id().map(|t| t.as_str().to_string()),
                     span_id: log.span_id().map(|s| s.as_str().to_string()),
+                    sequence: log.sequence(),
+                    service: None,
                 });
             }
 
@@ -519,8 +1002,8 @@ where
         let mut buffer = Cursor::new(Vec::new());
         {
             let mut zip = ZipWriter::new(&mut buffer);
-            let options = SimpleFileOptions::default()
-                .compression_method(zip::CompressionMethod::Deflated);
+            let options =
+                SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
 
             // Add metadata.json
             zip.start_file("metadata.json", options)
@@ -545,3 +1028,1040 @@ where
         Ok(buffer.into_inner())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, SubsecRound};
+    use serde_json::json;
+
+    use crate::modules::logging::domain::log::redaction::REDACTION_MASK;
+    use crate::modules::organizations::domain::OrgRole;
+    use crate::modules::projects::domain::ProjectRepository;
+    use crate::shared::testing::{
+        InMemoryLogRepository, InMemoryMemberRepository, InMemoryProjectRepository,
+        InMemoryServiceMetadataRepository, SequentialIdGenerator,
+    };
+
+    /// Service whose project-1 has the given redaction settings
+    async fn service_with_redaction(
+        redaction: serde_json::Value,
+    ) -> (
+        LogService<
+            InMemoryLogRepository,
+            InMemoryProjectRepository,
+            InMemoryMemberRepository,
+            SequentialIdGenerator,
+        >,
+        Arc<InMemoryLogRepository>,
+    ) {
+        service_with_settings(json!({ "redaction": redaction })).await
+    }
+
+    /// Service whose project-1 has the given settings patch applied
+    async fn service_with_settings(
+        settings: serde_json::Value,
+    ) -> (
+        LogService<
+            InMemoryLogRepository,
+            InMemoryProjectRepository,
+            InMemoryMemberRepository,
+            SequentialIdGenerator,
+        >,
+        Arc<InMemoryLogRepository>,
+    ) {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let mut project = project_repo.seed("project-1", "org-1");
+        project.update_settings(project.settings().merge(settings).unwrap());
+        project_repo.save(&project).await.unwrap();
+
+        let service = LogService::new(
+            log_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+        (service, log_repo)
+    }
+
+    fn log_input(message: &str, metadata: serde_json::Value) -> LogInput {
+        LogInput {
+            level: "info".to_string(),
+            message: message.to_string(),
+            timestamp: None,
+            source: None,
+            metadata: Some(metadata),
+            trace_id: None,
+            span_id: None,
+            event_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_field_projection_omits_unselected_fields() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        project_repo.seed("project-1", "org-1");
+        member_repo.seed("org-1", "user-1", OrgRole::Member);
+        let service = LogService::new(
+            log_repo,
+            project_repo,
+            member_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+        service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs: vec![log_input(
+                    "checkout failed",
+                    json!({ "user_id": "u-42", "cart": { "items": 3 } }),
+                )],
+            })
+            .await
+            .unwrap();
+
+        let query = |fields: Option<&str>| QueryLogsCommand {
+            project_id: "project-1".to_string(),
+            filters: QueryFilters::default(),
+            limit: None,
+            offset: None,
+            sort: None,
+            fields: fields.map(String::from),
+            include_total: false,
+            requesting_user_id: "user-1".to_string(),
+        };
+
+        let response = service
+            .query(query(Some("timestamp,level,message")))
+            .await
+            .unwrap();
+        let log = serde_json::to_value(&response.logs[0]).unwrap();
+        let mut keys: Vec<&str> = log
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort();
+        assert_eq!(keys, vec!["level", "message", "timestamp"]);
+
+        let response = service
+            .query(query(Some("message,metadata.user_id")))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.logs[0].metadata,
+            Some(json!({ "user_id": "u-42" }))
+        );
+
+        // Every field by default
+        let response = service.query(query(None)).await.unwrap();
+        assert!(response.logs[0].id.is_some());
+        assert!(response.logs[0].metadata.is_some());
+
+        assert!(matches!(
+            service.query(query(Some("message,body"))).await,
+            Err(LogDomainError::InvalidField(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_query_counts_total_only_on_request() {
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        project_repo.seed("project-1", "org-1");
+        member_repo.seed("org-1", "user-1", OrgRole::Member);
+        let service = LogService::new(
+            Arc::new(InMemoryLogRepository::new()),
+            project_repo,
+            member_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+        service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs: (0..3)
+                    .map(|_| log_input("request handled", json!({})))
+                    .collect(),
+            })
+            .await
+            .unwrap();
+
+        let query = |offset: i64, include_total: bool| QueryLogsCommand {
+            project_id: "project-1".to_string(),
+            filters: QueryFilters::default(),
+            limit: Some(2),
+            offset: Some(offset),
+            sort: None,
+            fields: None,
+            include_total,
+            requesting_user_id: "user-1".to_string(),
+        };
+
+        let response = service.query(query(0, false)).await.unwrap();
+        assert_eq!(response.logs.len(), 2);
+        assert!(response.has_more);
+        assert_eq!(response.total, None);
+        assert!(
+            serde_json::to_value(&response)
+                .unwrap()
+                .get("total")
+                .is_none()
+        );
+
+        let response = service.query(query(0, true)).await.unwrap();
+        assert_eq!(response.total, Some(3));
+        assert!(response.has_more);
+
+        let response = service.query(query(2, true)).await.unwrap();
+        assert_eq!(response.logs.len(), 1);
+        assert!(!response.has_more);
+        assert_eq!(response.total, Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_query_resolves_service_display_names() {
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        project_repo.seed("project-1", "org-1");
+        member_repo.seed("org-1", "user-1", OrgRole::Member);
+        let metadata_repo = Arc::new(InMemoryServiceMetadataRepository::new());
+        metadata_repo.seed("project-1", "checkout-svc", "Checkout");
+        let service = LogService::new(
+            Arc::new(InMemoryLogRepository::new()),
+            project_repo,
+            member_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        )
+        .with_service_metadata(metadata_repo.clone());
+        let from = |source: &str| LogInput {
+            source: Some(source.to_string()),
+            ..log_input("request handled", json!({}))
+        };
+        service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs: vec![
+                    from("checkout-svc"),
+                    from("billing-svc"),
+                    from("checkout-svc"),
+                ],
+            })
+            .await
+            .unwrap();
+
+        let response = service
+            .query(QueryLogsCommand {
+                project_id: "project-1".to_string(),
+                filters: QueryFilters::default(),
+                limit: None,
+                offset: None,
+                sort: None,
+                fields: None,
+                include_total: false,
+                requesting_user_id: "user-1".to_string(),
+            })
+            .await
+            .unwrap();
+
+        for log in &response.logs {
+            let display = log.service.as_ref().unwrap();
+            match log.source.as_deref() {
+                Some("checkout-svc") => assert_eq!(display.display_name, "Checkout"),
+                // No metadata: falls back to the raw name
+                _ => assert_eq!(display.display_name, "billing-svc"),
+            }
+        }
+        assert_eq!(response.logs.len(), 3);
+        assert_eq!(metadata_repo.lookups(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_group_by_configured_metadata_field() {
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        let mut project = project_repo.seed("project-1", "org-1");
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({ "log_group_field": "metadata.logger" }))
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+        member_repo.seed("org-1", "user-1", OrgRole::Member);
+        let service = LogService::new(
+            Arc::new(InMemoryLogRepository::new()),
+            project_repo,
+            member_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+        let base = Utc::now() - Duration::minutes(10);
+        let logs = [("db", 0), ("http", 1), ("db", 2), ("db", 3), ("http", 4)]
+            .into_iter()
+            .map(|(logger, minute)| LogInput {
+                timestamp: Some(base + Duration::minutes(minute)),
+                ..log_input(
+                    &format!("{} {}", logger, minute),
+                    json!({ "logger": logger }),
+                )
+            })
+            .chain(std::iter::once(log_input("no logger", json!({}))))
+            .collect();
+        service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs,
+            })
+            .await
+            .unwrap();
+
+        let response = service
+            .group(GroupLogsCommand {
+                project_id: "project-1".to_string(),
+                start_time: None,
+                end_time: None,
+                limit: None,
+                samples: Some(2),
+                requesting_user_id: "user-1".to_string(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.field, "metadata.logger");
+        let summary: Vec<(Option<&str>, i64, Vec<&str>)> = response
+            .groups
+            .iter()
+            .map(|g| {
+                (
+                    g.value.as_deref(),
+                    g.count,
+                    g.samples
+                        .iter()
+                        .map(|l| l.message.as_deref().unwrap())
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Some("db"), 3, vec!["db 3", "db 2"]),
+                (Some("http"), 2, vec!["http 4", "http 1"]),
+                (None, 1, vec!["no logger"]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_card_number_in_message_is_masked_before_storage() {
+        let (service, log_repo) = service_with_redaction(json!({
+            "message_patterns": [r"\b\d(?:[ -]?\d){12,15}\b"]
+        }))
+        .await;
+
+        service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs: vec![log_input(
+                    "charged card 4111 1111 1111 1111 for order 42",
+                    json!({}),
+                )],
+            })
+            .await
+            .unwrap();
+
+        let saved = log_repo.saved();
+        assert_eq!(
+            saved[0].message(),
+            format!("charged card {} for order 42", REDACTION_MASK)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sensitive_field_is_dropped_before_storage() {
+        let (service, log_repo) = service_with_redaction(json!({
+            "fields": ["authorization"]
+        }))
+        .await;
+
+        service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs: vec![log_input(
+                    "request handled",
+                    json!({"Authorization": "Bearer secret", "path": "/api"}),
+                )],
+            })
+            .await
+            .unwrap();
+
+        let saved = log_repo.saved();
+        assert_eq!(saved[0].metadata(), Some(&json!({"path": "/api"})));
+    }
+
+    #[tokio::test]
+    async fn test_public_client_ip_gets_geo_metadata_and_private_one_does_not() {
+        let (service, log_repo) = service_with_settings(json!({
+            "geoip_field": "client_ip",
+            "redaction": {"fields": ["client_ip"]}
+        }))
+        .await;
+        let service = service.with_geoip(Arc::new(
+            GeoIpDatabase::parse("0.0.0.0/0,DE,Berlin,Berlin").unwrap(),
+        ));
+
+        service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs: vec![
+                    log_input("from public", json!({"client_ip": "93.184.216.34"})),
+                    log_input("from private", json!({"client_ip": "10.0.0.7"})),
+                    log_input("unparseable", json!({"client_ip": "unknown"})),
+                ],
+            })
+            .await
+            .unwrap();
+
+        let saved = log_repo.saved();
+        // The IP itself is still redacted after the lookup
+        assert_eq!(
+            saved[0].metadata(),
+            Some(&json!({"geo": {"country": "DE", "region": "Berlin", "city": "Berlin"}}))
+        );
+        assert_eq!(saved[1].metadata(), Some(&json!({})));
+        assert_eq!(saved[2].metadata(), Some(&json!({})));
+    }
+
+    #[tokio::test]
+    async fn test_geoip_enrichment_flag_takes_effect_on_next_ingest() {
+        let (service, log_repo) = service_with_settings(json!({
+            "geoip_field": "client_ip",
+            "feature_flags": {"geoip_enrichment": false}
+        }))
+        .await;
+        let service = service.with_geoip(Arc::new(
+            GeoIpDatabase::parse("0.0.0.0/0,DE,Berlin,Berlin").unwrap(),
+        ));
+        let ingest = || {
+            service.ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs: vec![log_input(
+                    "from public",
+                    json!({"client_ip": "93.184.216.34"}),
+                )],
+            })
+        };
+
+        ingest().await.unwrap();
+
+        let project_id = ProjectId::new("project-1".to_string());
+        let mut project = service
+            .project_repo
+            .find_by_id(&project_id)
+            .await
+            .unwrap()
+            .unwrap();
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({"feature_flags": {"geoip_enrichment": true}}))
+                .unwrap(),
+        );
+        service.project_repo.save(&project).await.unwrap();
+        ingest().await.unwrap();
+
+        let saved = log_repo.saved();
+        assert!(saved[0].metadata().unwrap().get("geo").is_none());
+        assert_eq!(
+            saved[1].metadata().unwrap()["geo"],
+            json!({"country": "DE", "region": "Berlin", "city": "Berlin"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_repeated_event_id_is_stored_once() {
+        let (service, log_repo) = service_with_redaction(json!({})).await;
+        let with_event_id = |message: &str| LogInput {
+            event_id: Some("evt-1".to_string()),
+            ..log_input(message, json!({}))
+        };
+
+        let response = service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs: vec![
+                    with_event_id("payment captured"),
+                    with_event_id("payment captured (retry)"),
+                    log_input("no event id", json!({})),
+                ],
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.accepted, 2);
+        assert_eq!(response.duplicates, 1);
+        assert_eq!(response.rejected, 0);
+        let saved = log_repo.saved();
+        assert_eq!(saved.len(), 2);
+        assert_eq!(
+            saved
+                .iter()
+                .filter(|log| log.event_id() == Some("evt-1"))
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_event_id_deduplicated_across_restart_until_window_expires() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        let start = || {
+            LogService::new(
+                log_repo.clone(),
+                project_repo.clone(),
+                Arc::new(InMemoryMemberRepository::new()),
+                Arc::new(SequentialIdGenerator::new()),
+                PaginationConfig::default(),
+            )
+            .with_dedup_window(Duration::hours(1))
+        };
+        let send = || IngestLogsCommand {
+            project_id: "project-1".to_string(),
+            logs: vec![LogInput {
+                event_id: Some("evt-1".to_string()),
+                ..log_input("payment captured", json!({}))
+            }],
+        };
+
+        let first = start().ingest(send()).await.unwrap();
+        assert_eq!(first.accepted, 1);
+
+        // A fresh service shares nothing in memory with the first one
+        let restarted = start();
+        let replay = restarted.ingest(send()).await.unwrap();
+        assert_eq!(replay.accepted, 0);
+        assert_eq!(replay.duplicates, 1);
+
+        // Keys still inside the window survive a prune
+        assert_eq!(log_repo.prune_dedup_keys(Utc::now(), 100).await.unwrap(), 0);
+        assert_eq!(log_repo.dedup_key_count(), 1);
+
+        let pruned = log_repo
+            .prune_dedup_keys(Utc::now() + Duration::hours(2), 100)
+            .await
+            .unwrap();
+        assert_eq!(pruned, 1);
+        assert_eq!(log_repo.dedup_key_count(), 0);
+
+        let after_window = restarted.ingest(send()).await.unwrap();
+        assert_eq!(after_window.accepted, 1);
+        assert_eq!(log_repo.saved().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_trace_id_is_stripped_and_log_kept() {
+        let (service, log_repo) = service_with_redaction(json!({})).await;
+        let input = LogInput {
+            trace_id: Some("trace-123".to_string()),
+            span_id: Some("00f067aa0ba902b7".to_string()),
+            ..log_input("request handled", json!({}))
+        };
+
+        let response = service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs: vec![input],
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.accepted, 1);
+        assert_eq!(response.rejected, 0);
+        let saved = log_repo.saved();
+        assert_eq!(saved[0].trace_id(), None);
+        assert_eq!(
+            saved[0].span_id().map(|id| id.as_str()),
+            Some("00f067aa0ba902b7")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_non_standard_log_shape_is_mapped_to_canonical_fields() {
+        let (service, log_repo) = service_with_settings(json!({
+            "field_mapping": {
+                "message": "msg",
+                "level": "severity",
+                "timestamp": "@timestamp",
+                "source": "service.name",
+                "trace_id": "otel.trace",
+                "rename": {"http.status": "status_code"},
+                "drop": ["hostname"]
+            }
+        }))
+        .await;
+
+        let response = service
+            .ingest_json(IngestJsonLogsCommand {
+                project_id: "project-1".to_string(),
+                logs: vec![
+                    json!({
+                        "msg": "upstream timed out",
+                        "severity": "ERROR",
+                        "@timestamp": "2026-01-02T03:04:05Z",
+                        "service": {"name": "checkout"},
+                        "otel": {"trace": "4bf92f3577b34da6a3ce929d0e0e4736"},
+                        "http": {"status": 504},
+                        "hostname": "web-7",
+                        "region": "eu"
+                    }),
+                    json!({"severity": "info"}),
+                ],
+                mode: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.accepted, 1);
+        assert_eq!(response.rejected, 1);
+        assert!(response.errors[0].starts_with("Log 1: Invalid log"));
+
+        let saved = log_repo.saved();
+        let log = &saved[0];
+        assert_eq!(log.message(), "upstream timed out");
+        assert_eq!(log.level(), LogLevel::Error);
+        assert_eq!(log.timestamp().to_rfc3339(), "2026-01-02T03:04:05+00:00");
+        assert_eq!(log.source(), Some("checkout"));
+        assert_eq!(
+            log.trace_id().map(|id| id.as_str()),
+            Some("4bf92f3577b34da6a3ce929d0e0e4736")
+        );
+        assert_eq!(
+            log.metadata(),
+            Some(&json!({"status_code": 504, "region": "eu"}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_min_level_filter_keeps_logs_at_least_as_severe() {
+        let (service, log_repo) = service_with_settings(json!({})).await;
+        let logs = ["info", "warn", "error", "fatal"]
+            .into_iter()
+            .map(|level| LogInput {
+                level: level.to_string(),
+                ..log_input(level, json!({}))
+            })
+            .collect();
+        service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs,
+            })
+            .await
+            .unwrap();
+
+        let filters = service
+            .convert_query_filters(
+                QueryFilters {
+                    min_level: Some("error".to_string()),
+                    ..Default::default()
+                },
+                &ProjectSettings::default(),
+            )
+            .unwrap();
+        assert_eq!(filters.min_level, Some(LogLevel::Error));
+
+        let project_id = ProjectId::new("project-1".to_string());
+        // Info and warn are excluded; error and fatal are kept
+        assert_eq!(log_repo.count(&project_id, &filters).await.unwrap(), 2);
+        let warn_and_up = LogFilters {
+            min_level: Some(LogLevel::Warn),
+            ..Default::default()
+        };
+        assert_eq!(log_repo.count(&project_id, &warn_and_up).await.unwrap(), 3);
+
+        // An unknown level is rejected rather than ignored
+        assert!(
+            service
+                .convert_query_filters(
+                    QueryFilters {
+                        min_level: Some("loud".to_string()),
+                        ..Default::default()
+                    },
+                    &ProjectSettings::default(),
+                )
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_metadata_alias_filters_and_projects_underlying_path() {
+        let aliases = json!({"metadata_aliases": {"route": "metadata.request.http.route"}});
+        let (service, log_repo) = service_with_settings(aliases.clone()).await;
+        service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs: vec![
+                    log_input(
+                        "checkout",
+                        json!({"request": {"http": {"route": "/checkout"}}}),
+                    ),
+                    log_input("cart", json!({"request": {"http": {"route": "/cart"}}})),
+                    // A top-level key named like the alias is not what it stands for
+                    log_input("top-level", json!({"route": "/checkout"})),
+                ],
+            })
+            .await
+            .unwrap();
+
+        let settings = ProjectSettings::default().merge(aliases).unwrap();
+        let filters = service
+            .convert_query_filters(
+                QueryFilters {
+                    metadata_filters: Some(vec![MetadataFilterInput {
+                        key: "route".to_string(),
+                        operator: "eq".to_string(),
+                        value: Some(json!("/checkout")),
+                    }]),
+                    ..Default::default()
+                },
+                &settings,
+            )
+            .unwrap();
+        assert_eq!(filters.metadata_filters[0].key, "request.http.route");
+        let project_id = ProjectId::new("project-1".to_string());
+        assert_eq!(log_repo.count(&project_id, &filters).await.unwrap(), 1);
+
+        let response = service
+            .query_public(QueryLogsCommand {
+                project_id: "project-1".to_string(),
+                filters: QueryFilters::default(),
+                limit: None,
+                offset: None,
+                sort: None,
+                fields: Some("message,route".to_string()),
+                include_total: false,
+                requesting_user_id: String::new(),
+            })
+            .await
+            .unwrap();
+        let metadata = |message: &str| {
+            response
+                .logs
+                .iter()
+                .find(|log| log.message.as_deref() == Some(message))
+                .unwrap()
+                .metadata
+                .clone()
+        };
+        assert_eq!(
+            metadata("checkout"),
+            Some(json!({"request": {"http": {"route": "/checkout"}}}))
+        );
+        assert_eq!(metadata("top-level"), None);
+    }
+
+    #[tokio::test]
+    async fn test_log_without_timestamp_gets_server_time() {
+        let (service, log_repo) = service_with_settings(json!({})).await;
+        let before = Utc::now();
+
+        let mut timestamped = log_input("from the client", json!({}));
+        timestamped.timestamp = Some(before - Duration::hours(1));
+        let response = service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs: vec![log_input("no clock", json!({})), timestamped],
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.accepted, 2);
+        assert_eq!(response.server_timestamps, 1);
+        let saved = log_repo.saved();
+        assert!(saved[0].timestamp() >= before && saved[0].timestamp() <= Utc::now());
+        // Client timestamps are kept to the microsecond by default
+        assert_eq!(
+            saved[1].timestamp(),
+            (before - Duration::hours(1)).trunc_subsecs(6)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_log_without_timestamp_rejected_when_required() {
+        let (service, log_repo) =
+            service_with_settings(json!({ "missing_timestamp_policy": "reject" })).await;
+
+        let response = service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs: vec![log_input("no clock", json!({}))],
+            })
+            .await
+            .unwrap();
+
+        assert_eq!((response.accepted, response.rejected), (0, 1));
+        assert_eq!(response.server_timestamps, 0);
+        assert!(log_repo.saved().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_logs_below_min_ingest_level_are_dropped() {
+        let (service, log_repo) =
+            service_with_settings(json!({ "min_ingest_level": "info" })).await;
+        let logs = ["trace", "debug", "info", "warn", "error"]
+            .into_iter()
+            .map(|level| LogInput {
+                level: level.to_string(),
+                ..log_input(level, json!({}))
+            })
+            .collect();
+
+        let response = service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!((response.accepted, response.rejected), (3, 0));
+        assert_eq!(response.below_min_level, 2);
+        let stored: Vec<&str> = log_repo
+            .saved()
+            .iter()
+            .map(|l| l.level().as_str())
+            .collect();
+        assert_eq!(stored, vec!["info", "warn", "error"]);
+    }
+
+    fn text_command(body: &str, level: Option<&str>) -> IngestTextLogsCommand {
+        IngestTextLogsCommand {
+            project_id: "project-1".to_string(),
+            body: body.to_string(),
+            level: level.map(String::from),
+            mode: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plain_text_body_ingests_one_log_per_line() {
+        let (service, log_repo) = service_with_settings(json!({})).await;
+
+        let response = service
+            .ingest_text(text_command(
+                "server started\r\n\nlistening on :8080\nready\n",
+                Some("warn"),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!((response.accepted, response.rejected), (3, 0));
+        assert_eq!(response.server_timestamps, 3);
+        let stored: Vec<(String, &str)> = log_repo
+            .saved()
+            .iter()
+            .map(|l| (l.message().to_string(), l.level().as_str()))
+            .collect();
+        assert_eq!(
+            stored,
+            vec![
+                ("server started".to_string(), "warn"),
+                ("listening on :8080".to_string(), "warn"),
+                ("ready".to_string(), "warn"),
+            ]
+        );
+
+        assert!(matches!(
+            service.ingest_text(text_command("hi", Some("loud"))).await,
+            Err(LogDomainError::InvalidLevel(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_plain_text_extraction_pulls_out_level_and_timestamp() {
+        let (service, log_repo) = service_with_settings(json!({
+            "plain_text": {
+                "default_level": "debug",
+                "level_pattern": r"\[(?P<level>[A-Za-z]+)\]",
+                "timestamp_pattern": r"^(\S+)"
+            }
+        }))
+        .await;
+
+        let response = service
+            .ingest_text(text_command(
+                "2024-05-01T10:00:00Z [ERROR] payment declined\nno prefix [NOTICE] here\n",
+                None,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!((response.accepted, response.server_timestamps), (2, 1));
+        let saved = log_repo.saved();
+        assert_eq!(saved[0].level().as_str(), "error");
+        assert_eq!(
+            saved[0].timestamp().to_rfc3339(),
+            "2024-05-01T10:00:00+00:00"
+        );
+        assert_eq!(
+            saved[0].message(),
+            "2024-05-01T10:00:00Z [ERROR] payment declined"
+        );
+        // Unknown levels and unparseable timestamps fall back to the defaults
+        assert_eq!(saved[1].level().as_str(), "debug");
+    }
+
+    #[tokio::test]
+    async fn test_logs_with_identical_timestamps_keep_ingest_order() {
+        let (service, log_repo) = service_with_settings(json!({})).await;
+        let timestamp: DateTime<Utc> = "2026-01-01T00:00:00.000001Z".parse().unwrap();
+        let logs = ["first", "second", "third"]
+            .into_iter()
+            .map(|message| LogInput {
+                timestamp: Some(timestamp),
+                ..log_input(message, json!({}))
+            })
+            .collect();
+        service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs,
+            })
+            .await
+            .unwrap();
+
+        let query = |sort| {
+            let log_repo = log_repo.clone();
+            async move {
+                let result = log_repo
+                    .query(
+                        &ProjectId::new("project-1".to_string()),
+                        &LogFilters::default(),
+                        &Pagination {
+                            limit: 10,
+                            offset: 0,
+                        },
+                        sort,
+                        false,
+                    )
+                    .await
+                    .unwrap();
+                result
+                    .logs
+                    .iter()
+                    .map(|l| (l.message().to_string(), l.sequence().unwrap()))
+                    .collect::<Vec<_>>()
+            }
+        };
+        let ascending = query(SortOrder::Ascending).await;
+        let messages: Vec<&str> = ascending.iter().map(|(m, _)| m.as_str()).collect();
+        assert_eq!(messages, vec!["first", "second", "third"]);
+        assert!(ascending.windows(2).all(|pair| pair[0].1 < pair[1].1));
+
+        let descending = query(SortOrder::Descending).await;
+        let messages: Vec<&str> = descending.iter().map(|(m, _)| m.as_str()).collect();
+        assert_eq!(messages, vec!["third", "second", "first"]);
+    }
+
+    #[tokio::test]
+    async fn test_sub_microsecond_timestamps_kept_at_nanosecond_precision() {
+        let timestamp: DateTime<Utc> = "2026-01-01T00:00:00.000001250Z".parse().unwrap();
+        let stored = |settings| async move {
+            let (service, log_repo) = service_with_settings(settings).await;
+            service
+                .ingest(IngestLogsCommand {
+                    project_id: "project-1".to_string(),
+                    logs: vec![LogInput {
+                        timestamp: Some(timestamp),
+                        ..log_input("tick", json!({}))
+                    }],
+                })
+                .await
+                .unwrap();
+            log_repo.saved()[0].timestamp().timestamp_subsec_nanos()
+        };
+
+        assert_eq!(stored(json!({})).await, 1_000);
+        assert_eq!(
+            stored(json!({"log_timestamp_precision": "nanosecond"})).await,
+            1_250
+        );
+        assert_eq!(
+            stored(json!({"log_timestamp_precision": "millisecond"})).await,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_daily_buckets_split_at_project_local_midnight() {
+        let (service, _) = service_with_settings(json!({ "timezone": "America/New_York" })).await;
+        // New York is UTC-5 in January: the first two are still January 14th there
+        let logs = [
+            "2024-01-15T03:00:00Z",
+            "2024-01-15T04:59:00Z",
+            "2024-01-15T06:00:00Z",
+        ]
+        .into_iter()
+        .map(|ts| LogInput {
+            timestamp: Some(ts.parse().unwrap()),
+            ..log_input("request handled", json!({}))
+        })
+        .collect();
+        service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs,
+            })
+            .await
+            .unwrap();
+
+        let daily = |timezone: Option<&str>| MetricsQuery {
+            project_id: "project-1".to_string(),
+            bucket: TimeBucket::Day,
+            start_time: None,
+            end_time: None,
+            top_sources_limit: 10,
+            timezone: timezone.map(String::from),
+            requesting_user_id: String::new(),
+        };
+        let volume = |response: MetricsResponse| -> Vec<(String, i64)> {
+            response
+                .volume_over_time
+                .into_iter()
+                .map(|b| (b.bucket.to_rfc3339(), b.count))
+                .collect()
+        };
+
+        let local = service.get_metrics_public(daily(None)).await.unwrap();
+        assert_eq!(
+            volume(local),
+            vec![
+                ("2024-01-14T05:00:00+00:00".to_string(), 2),
+                ("2024-01-15T05:00:00+00:00".to_string(), 1),
+            ]
+        );
+
+        // A request may bucket in another timezone, but only a real one
+        let utc = service
+            .get_metrics_public(daily(Some("UTC")))
+            .await
+            .unwrap();
+        assert_eq!(
+            volume(utc),
+            vec![("2024-01-15T00:00:00+00:00".to_string(), 3)]
+        );
+        assert!(matches!(
+            service.get_metrics_public(daily(Some("Mars/Base"))).await,
+            Err(LogDomainError::InvalidTimezone(_))
+        ));
+    }
+}
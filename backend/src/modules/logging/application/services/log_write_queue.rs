@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use chrono::Duration;
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+use crate::modules::logging::domain::{LogDomainError, LogEntry, LogIngestObserver, LogRepository};
+
+/// Bounded queue of validated log batches, written by a background task so
+/// asynchronous ingest can acknowledge before the database commit
+#[derive(Clone)]
+pub struct LogWriteQueue {
+    sender: mpsc::Sender<Vec<LogEntry>>,
+}
+
+impl LogWriteQueue {
+    /// Start the writer task; at most `capacity` batches wait to be written.
+    /// `observer` is told about each batch once it is written.
+    pub fn start<LR>(
+        log_repo: Arc<LR>,
+        capacity: usize,
+        dedup_window: Duration,
+        observer: Option<Arc<dyn LogIngestObserver>>,
+    ) -> Self
+    where
+        LR: LogRepository + 'static,
+    {
+        let (sender, mut receiver) = mpsc::channel::<Vec<LogEntry>>(capacity);
+
+        tokio::spawn(async move {
+            while let Some(logs) = receiver.recv().await {
+                let stored =
+                    store_logs(log_repo.as_ref(), logs, dedup_window, observer.as_deref()).await;
+                if !stored.errors.is_empty() {
+                    tracing::error!(
+                        rejected = stored.rejected,
+                        errors = ?stored.errors,
+                        "Failed to write queued logs"
+                    );
+                }
+            }
+        });
+
+        Self { sender }
+    }
+
+    /// Queue a batch for writing, failing rather than waiting when the queue is full
+    pub fn enqueue(&self, logs: Vec<LogEntry>) -> Result<(), LogDomainError> {
+        self.sender.try_send(logs).map_err(|e| match e {
+            TrySendError::Full(_) => LogDomainError::IngestQueueFull,
+            TrySendError::Closed(_) => {
+                LogDomainError::InternalError("log write queue is closed".to_string())
+            }
+        })
+    }
+}
+
+/// Outcome of writing a batch of validated logs
+#[derive(Debug, Default)]
+pub(crate) struct StoredLogs {
+    pub accepted: u32,
+    pub rejected: u32,
+    pub duplicates: u32,
+    pub errors: Vec<String>,
+}
+
+/// Write validated logs. Logs carrying a client event id are stored at most
+/// once per dedup window; a failed batch counts all of its logs as rejected.
+/// The observer is told about each batch that was written.
+pub(crate) async fn store_logs<LR>(
+    log_repo: &LR,
+    logs: Vec<LogEntry>,
+    dedup_window: Duration,
+    observer: Option<&dyn LogIngestObserver>,
+) -> StoredLogs
+where
+    LR: LogRepository + ?Sized,
+{
+    let mut stored = StoredLogs::default();
+    let (keyed_logs, plain_logs): (Vec<_>, Vec<_>) =
+        logs.into_iter().partition(|log| log.event_id().is_some());
+
+    if !plain_logs.is_empty() {
+        match log_repo.save_batch(&plain_logs).await {
+            Ok(count) => {
+                stored.accepted += count;
+                notify(observer, &plain_logs);
+            }
+            Err(e) => {
+                stored.rejected += plain_logs.len() as u32;
+                stored.errors.push(format!("Batch save failed: {}", e));
+            }
+        }
+    }
+
+    if !keyed_logs.is_empty() {
+        match log_repo.save_batch_dedup(&keyed_logs, dedup_window).await {
+            Ok(result) => {
+                stored.accepted += result.saved;
+                stored.duplicates = result.duplicates;
+                // Duplicates are included; observers only react to what the logs match
+                notify(observer, &keyed_logs);
+            }
+            Err(e) => {
+                stored.rejected += keyed_logs.len() as u32;
+                stored.errors.push(format!("Batch save failed: {}", e));
+            }
+        }
+    }
+
+    stored
+}
+
+fn notify(observer: Option<&dyn LogIngestObserver>, logs: &[LogEntry]) {
+    if let (Some(observer), Some(first)) = (observer, logs.first()) {
+        observer.logs_ingested(first.project_id(), logs);
+    }
+}
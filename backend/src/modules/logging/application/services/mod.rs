@@ -1,5 +1,7 @@
 pub mod filter_preset_service;
 pub mod log_service;
+pub mod log_write_queue;
 
 pub use filter_preset_service::FilterPresetService;
 pub use log_service::LogService;
+pub use log_write_queue::LogWriteQueue;
@@ -6,6 +6,10 @@ pub enum LogDomainError {
     InvalidLevel(String),
     InvalidTimestamp(String),
     InvalidMessage(String),
+    InvalidEventId(String),
+    InvalidLogShape(String),
+    InvalidField(String),
+    InvalidTimezone(String),
 
     // Project errors
     ProjectNotFound,
@@ -26,6 +30,7 @@ pub enum LogDomainError {
     NotOrgMember,
 
     // Infrastructure errors
+    IngestQueueFull,
     InternalError(String),
 }
 
@@ -33,6 +38,10 @@ impl fmt::Display for LogDomainError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::InvalidLevel(msg) => write!(f, "Invalid log level: {}", msg),
+            Self::InvalidEventId(msg) => write!(f, "Invalid event id: {}", msg),
+            Self::InvalidLogShape(msg) => write!(f, "Invalid log: {}", msg),
+            Self::InvalidField(msg) => write!(f, "Invalid field: {}", msg),
+            Self::InvalidTimezone(msg) => write!(f, "Invalid timezone: {}", msg),
             Self::InvalidTimestamp(msg) => write!(f, "Invalid timestamp: {}", msg),
             Self::InvalidMessage(msg) => write!(f, "Invalid message: {}", msg),
             Self::ProjectNotFound => write!(f, "Project not found"),
@@ -42,9 +51,12 @@ impl fmt::Display for LogDomainError {
             Self::ApiKeyExpired => write!(f, "API key has expired"),
             Self::InvalidFilterPreset(msg) => write!(f, "Invalid filter preset: {}", msg),
             Self::FilterPresetNotFound => write!(f, "Filter preset not found"),
-            Self::FilterPresetNameExists => write!(f, "A filter preset with this name already exists"),
+            Self::FilterPresetNameExists => {
+                write!(f, "A filter preset with this name already exists")
+            }
             Self::InsufficientPermissions => write!(f, "Insufficient permissions for this action"),
             Self::NotOrgMember => write!(f, "User is not a member of this organization"),
+            Self::IngestQueueFull => write!(f, "Log write queue is full"),
             Self::InternalError(msg) => write!(f, "Internal error: {}", msg),
         }
     }
@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+
+use crate::modules::projects::domain::ProjectId;
+
+/// DeadLetter - a raw log payload that could not be parsed at ingest
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    id: String,
+    project_id: ProjectId,
+    format: String,
+    payload: String,
+    error: String,
+    received_at: DateTime<Utc>,
+}
+
+impl DeadLetter {
+    /// Create a new dead letter for a rejected payload
+    pub fn new(
+        id: String,
+        project_id: ProjectId,
+        format: String,
+        payload: String,
+        error: String,
+    ) -> Self {
+        Self {
+            id,
+            project_id,
+            format,
+            payload,
+            error,
+            received_at: Utc::now(),
+        }
+    }
+
+    // Getters
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn project_id(&self) -> &ProjectId {
+        &self.project_id
+    }
+
+    /// Wire format the payload arrived in (e.g. "syslog")
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+
+    pub fn payload(&self) -> &str {
+        &self.payload
+    }
+
+    pub fn error(&self) -> &str {
+        &self.error
+    }
+
+    pub fn received_at(&self) -> DateTime<Utc> {
+        self.received_at
+    }
+}
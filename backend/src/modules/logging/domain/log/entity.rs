@@ -17,6 +17,10 @@ pub struct LogEntry {
     metadata: Option<Value>,
     trace_id: Option<TraceId>,
     span_id: Option<SpanId>,
+    /// Client-supplied id used to drop retried duplicates (not persisted with the log)
+    event_id: Option<String>,
+    /// Ingest order assigned when stored, breaking ties between equal timestamps
+    sequence: Option<i64>,
 }
 
 impl LogEntry {
@@ -44,6 +48,8 @@ impl LogEntry {
             metadata,
             trace_id,
             span_id,
+            event_id: None,
+            sequence: None,
         }
     }
 
@@ -71,6 +77,8 @@ impl LogEntry {
             metadata,
             trace_id,
             span_id,
+            event_id: None,
+            sequence: None,
         }
     }
 
@@ -114,6 +122,26 @@ impl LogEntry {
     pub fn span_id(&self) -> Option<&SpanId> {
         self.span_id.as_ref()
     }
+
+    pub fn event_id(&self) -> Option<&str> {
+        self.event_id.as_deref()
+    }
+
+    pub fn sequence(&self) -> Option<i64> {
+        self.sequence
+    }
+
+    /// Attach the client-supplied event id, ignoring blank ones
+    pub fn with_event_id(mut self, event_id: Option<String>) -> Self {
+        self.event_id = event_id.filter(|id| !id.trim().is_empty());
+        self
+    }
+
+    /// Attach the ingest sequence the log was stored with
+    pub fn with_sequence(mut self, sequence: Option<i64>) -> Self {
+        self.sequence = sequence;
+        self
+    }
 }
 
 #[cfg(test)]
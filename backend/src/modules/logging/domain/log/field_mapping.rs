@@ -0,0 +1,120 @@
+use serde_json::{Map, Value};
+
+use crate::modules::projects::domain::FieldMappingSettings;
+
+/// Reshapes incoming JSON logs into the canonical ingest shape
+/// (`message`, `level`, `timestamp`, ..., `metadata`) using a project's field mapping
+#[derive(Debug)]
+pub struct LogFieldMapper {
+    settings: FieldMappingSettings,
+}
+
+impl LogFieldMapper {
+    pub fn new(settings: &FieldMappingSettings) -> Self {
+        Self {
+            settings: settings.clone(),
+        }
+    }
+
+    /// Map one incoming log. Without a configured mapping the log passes through unchanged.
+    pub fn map(&self, log: Value) -> Result<Value, String> {
+        let Value::Object(mut log) = log else {
+            return Err("log must be a JSON object".to_string());
+        };
+        if self.settings.is_empty() {
+            return Ok(Value::Object(log));
+        }
+
+        for path in &self.settings.drop {
+            take_path(&mut log, path);
+        }
+        for (from, to) in &self.settings.rename {
+            if let Some(value) = take_path(&mut log, from) {
+                log.insert(to.clone(), value);
+            }
+        }
+
+        let mut canonical = Map::new();
+        for (field, path) in self.settings.fields() {
+            if let Some(value) = take_path(&mut log, path.unwrap_or(field)) {
+                canonical.insert(field.to_string(), coerce(field, value));
+            }
+        }
+
+        // Anything not mapped to a field is kept alongside the explicit metadata
+        let mut metadata = match log.remove("metadata") {
+            Some(Value::Object(metadata)) => metadata,
+            Some(other) => Map::from_iter([("metadata".to_string(), other)]),
+            None => Map::new(),
+        };
+        for (key, value) in log {
+            metadata.entry(key).or_insert(value);
+        }
+        if !metadata.is_empty() {
+            canonical.insert("metadata".to_string(), Value::Object(metadata));
+        }
+
+        Ok(Value::Object(canonical))
+    }
+}
+
+/// Remove the value at a dot-separated path, pruning parents left empty
+fn take_path(map: &mut Map<String, Value>, path: &str) -> Option<Value> {
+    let Some((head, rest)) = path.split_once('.') else {
+        return map.remove(path);
+    };
+    let Some(Value::Object(child)) = map.get_mut(head) else {
+        return None;
+    };
+    let value = take_path(child, rest);
+    if child.is_empty() {
+        map.remove(head);
+    }
+    value
+}
+
+/// Emitters often send numeric levels or ids; text fields accept any scalar
+fn coerce(field: &str, value: Value) -> Value {
+    match value {
+        Value::Number(n) if field != "timestamp" => Value::String(n.to_string()),
+        Value::Bool(b) => Value::String(b.to_string()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_empty_mapping_passes_log_through() {
+        let log = json!({"level": "info", "message": "hi", "extra": 1});
+        let mapper = LogFieldMapper::new(&FieldMappingSettings::default());
+        assert_eq!(mapper.map(log.clone()).unwrap(), log);
+        assert!(mapper.map(json!("hi")).is_err());
+    }
+
+    #[test]
+    fn test_rename_and_drop_apply_before_fields_are_mapped() {
+        let settings: FieldMappingSettings = serde_json::from_value(json!({
+            "message": "msg",
+            "rename": {"ctx.req_id": "request_id"},
+            "drop": ["ctx.password"]
+        }))
+        .unwrap();
+
+        let mapped = LogFieldMapper::new(&settings)
+            .map(json!({
+                "msg": "login",
+                "level": "warn",
+                "ctx": {"req_id": "r-1", "password": "hunter2"}
+            }))
+            .unwrap();
+
+        assert_eq!(
+            mapped,
+            json!({"message": "login", "level": "warn", "metadata": {"request_id": "r-1"}})
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use ipnet::IpNet;
+use serde_json::Value;
+
+/// Ranges compiled into the server, used unless another database is configured
+pub const BUNDLED_GEOIP_DATABASE: &str = include_str!("../../../../../data/geoip.csv");
+
+/// Metadata key the looked-up location is added under
+pub const GEO_METADATA_KEY: &str = "geo";
+
+/// Where an address is located; empty parts are unknown
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeoLocation {
+    pub country: String,
+    pub region: String,
+    pub city: String,
+}
+
+impl GeoLocation {
+    fn to_json(&self) -> Value {
+        let fields = [
+            ("country", &self.country),
+            ("region", &self.region),
+            ("city", &self.city),
+        ];
+        Value::Object(
+            fields
+                .into_iter()
+                .filter(|(_, value)| !value.is_empty())
+                .map(|(key, value)| (key.to_string(), Value::String(value.clone())))
+                .collect(),
+        )
+    }
+}
+
+/// Address blocks with their location, read from a MaxMind-style CSV export
+/// (`network,country,region,city`, one non-overlapping CIDR block per line).
+/// Blocks are kept sorted so a lookup is a binary search.
+#[derive(Debug, Default)]
+pub struct GeoIpDatabase {
+    v4: Vec<(u32, u32, GeoLocation)>,
+    v6: Vec<(u128, u128, GeoLocation)>,
+}
+
+impl GeoIpDatabase {
+    /// Parse a CSV export; blank lines, `#` comments and a header line are skipped
+    pub fn parse(csv: &str) -> Result<Self, String> {
+        let mut db = Self::default();
+        for (idx, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("network,") {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [network, country, region, city] = fields[..] else {
+                return Err(format!(
+                    "line {}: expected network,country,region,city",
+                    idx + 1
+                ));
+            };
+            let network: IpNet = network
+                .parse()
+                .map_err(|_| format!("line {}: invalid network '{}'", idx + 1, network))?;
+            let location = GeoLocation {
+                country: country.to_string(),
+                region: region.to_string(),
+                city: city.to_string(),
+            };
+            match network {
+                IpNet::V4(net) => db.v4.push((
+                    u32::from(net.network()),
+                    u32::from(net.broadcast()),
+                    location,
+                )),
+                IpNet::V6(net) => db.v6.push((
+                    u128::from(net.network()),
+                    u128::from(net.broadcast()),
+                    location,
+                )),
+            }
+        }
+        sort_blocks(&mut db.v4)?;
+        sort_blocks(&mut db.v6)?;
+        Ok(db)
+    }
+
+    /// Location of a public address; private and reserved addresses have none
+    pub fn lookup(&self, ip: IpAddr) -> Option<&GeoLocation> {
+        if !is_public(ip) {
+            return None;
+        }
+        match ip {
+            IpAddr::V4(ip) => find_block(&self.v4, u32::from(ip)),
+            IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+                Some(ip) => find_block(&self.v4, u32::from(ip)),
+                None => find_block(&self.v6, u128::from(ip)),
+            },
+        }
+    }
+
+    /// Add the location of the address at `ip_path` (dot-separated) in the
+    /// metadata under "geo". Metadata without a resolvable public address, or
+    /// already carrying "geo", is left untouched.
+    pub fn enrich(&self, metadata: &mut Value, ip_path: &str) {
+        let location = get_path(metadata, ip_path)
+            .and_then(Value::as_str)
+            .and_then(parse_ip)
+            .and_then(|ip| self.lookup(ip));
+        if let (Some(location), Value::Object(map)) = (location, metadata) {
+            map.entry(GEO_METADATA_KEY)
+                .or_insert_with(|| location.to_json());
+        }
+    }
+}
+
+/// Sort blocks by start address, rejecting any that overlap
+fn sort_blocks<T: Ord + Copy>(blocks: &mut [(T, T, GeoLocation)]) -> Result<(), String> {
+    blocks.sort_by_key(|(start, _, _)| *start);
+    for pair in blocks.windows(2) {
+        if pair[1].0 <= pair[0].1 {
+            return Err("network blocks must not overlap".to_string());
+        }
+    }
+    Ok(())
+}
+
+fn find_block<T: Ord + Copy>(blocks: &[(T, T, GeoLocation)], ip: T) -> Option<&GeoLocation> {
+    let idx = blocks.partition_point(|(start, _, _)| *start <= ip);
+    let (_, end, location) = blocks.get(idx.checked_sub(1)?)?;
+    (ip <= *end).then_some(location)
+}
+
+/// An address, an address with a port, or the first of a forwarded-for list
+fn parse_ip(value: &str) -> Option<IpAddr> {
+    let value = value.split(',').next()?.trim();
+    value
+        .parse::<IpAddr>()
+        .ok()
+        .or_else(|| value.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+fn get_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(value, |value, segment| value.as_object()?.get(segment))
+}
+
+/// Whether an address is routable on the public internet
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_v4(ip),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    // Carrier-grade NAT, 100.64.0.0/10
+    let shared = a == 100 && (b & 0xc0) == 64;
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || shared)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    // Unique local fc00::/7 and link-local fe80::/10
+    let unique_local = (first & 0xfe00) == 0xfc00;
+    let link_local = (first & 0xffc0) == 0xfe80;
+    !(ip.is_loopback() || ip.is_unspecified() || ip.is_multicast() || unique_local || link_local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn db() -> GeoIpDatabase {
+        GeoIpDatabase::parse(BUNDLED_GEOIP_DATABASE).unwrap()
+    }
+
+    #[test]
+    fn test_bundled_database_parses() {
+        let db = db();
+        assert!(!db.v4.is_empty() && !db.v6.is_empty());
+    }
+
+    #[test]
+    fn test_lookup_finds_enclosing_block() {
+        let db = db();
+        let location = db.lookup("8.8.8.8".parse().unwrap()).unwrap();
+        assert_eq!(location.country, "US");
+        assert_eq!(location.city, "Mountain View");
+        assert!(db.lookup("2606:4700:4700::1111".parse().unwrap()).is_some());
+        assert!(db.lookup("8.8.9.1".parse().unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_private_and_reserved_addresses_are_not_resolved() {
+        let db = GeoIpDatabase::parse("0.0.0.0/0,US,,").unwrap();
+        for ip in [
+            "10.1.2.3",
+            "192.168.0.1",
+            "127.0.0.1",
+            "100.64.0.1",
+            "::1",
+            "fd00::1",
+        ] {
+            assert!(db.lookup(ip.parse().unwrap()).is_none(), "{}", ip);
+        }
+        assert!(db.lookup("93.184.216.34".parse().unwrap()).is_some());
+    }
+
+    #[test]
+    fn test_overlapping_blocks_are_rejected() {
+        assert!(GeoIpDatabase::parse("10.0.0.0/8,US,,\n10.1.0.0/16,US,,").is_err());
+        assert!(GeoIpDatabase::parse("not-a-network,US,,").is_err());
+    }
+
+    #[test]
+    fn test_enrich_reads_nested_path_and_keeps_existing_geo() {
+        let db = db();
+        let mut metadata = json!({"http": {"client_ip": "81.2.69.160:51234"}});
+        db.enrich(&mut metadata, "http.client_ip");
+        assert_eq!(
+            metadata["geo"],
+            json!({"country": "GB", "region": "England", "city": "London"})
+        );
+
+        let mut metadata = json!({"client_ip": "8.8.8.8", "geo": "set by client"});
+        db.enrich(&mut metadata, "client_ip");
+        assert_eq!(metadata["geo"], "set by client");
+    }
+}
@@ -0,0 +1,9 @@
+use super::entity::LogEntry;
+use crate::modules::projects::domain::ProjectId;
+
+/// Told about logs once they are stored, e.g. to react to them without
+/// waiting for a scheduled query. Called on the ingest path, so it must
+/// return quickly and hand any real work off to a background task.
+pub trait LogIngestObserver: Send + Sync {
+    fn logs_ingested(&self, project_id: &ProjectId, logs: &[LogEntry]);
+}
@@ -1,7 +1,22 @@
+pub mod dead_letter;
 pub mod entity;
+pub mod field_mapping;
+pub mod geoip;
+pub mod ingest_observer;
+pub mod plain_text;
+pub mod redaction;
 pub mod repository;
 pub mod value_objects;
 
+pub use dead_letter::DeadLetter;
 pub use entity::LogEntry;
-pub use repository::{LogFilters, LogQueryResult, LogRepository, LogStats, Pagination, SortOrder};
+pub use field_mapping::LogFieldMapper;
+pub use geoip::{BUNDLED_GEOIP_DATABASE, GeoIpDatabase};
+pub use ingest_observer::LogIngestObserver;
+pub use plain_text::PlainTextLineParser;
+pub use redaction::LogRedactor;
+pub use repository::{
+    DedupSaveResult, LogCutoffs, LogFilters, LogGroup, LogQueryResult, LogRepository, LogStats,
+    Pagination, SortOrder, TaggedLogCutoff,
+};
 pub use value_objects::{LogId, LogLevel, SpanId, TraceId};
@@ -0,0 +1,120 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use regex::Regex;
+
+use crate::modules::projects::domain::PlainTextParsingSettings;
+
+/// Fields pulled out of one plain-text log line
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlainTextLine {
+    pub message: String,
+    pub level: Option<String>,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// Splits newline-delimited text into log lines, extracting level and
+/// timestamp with a project's plain-text patterns
+#[derive(Debug)]
+pub struct PlainTextLineParser {
+    level_pattern: Option<Regex>,
+    timestamp_pattern: Option<Regex>,
+    timestamp_format: Option<String>,
+}
+
+impl PlainTextLineParser {
+    pub fn new(settings: &PlainTextParsingSettings) -> Result<Self, regex::Error> {
+        let compile = |pattern: &Option<String>| pattern.as_deref().map(Regex::new).transpose();
+        Ok(Self {
+            level_pattern: compile(&settings.level_pattern)?,
+            timestamp_pattern: compile(&settings.timestamp_pattern)?,
+            timestamp_format: settings.timestamp_format.clone(),
+        })
+    }
+
+    /// Parse every non-blank line of a body
+    pub fn parse_body(&self, body: &str) -> Vec<PlainTextLine> {
+        body.lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| self.parse_line(line))
+            .collect()
+    }
+
+    /// Parse one line. Fields the patterns don't find (or, for timestamps,
+    /// that don't parse) are left unset.
+    pub fn parse_line(&self, line: &str) -> PlainTextLine {
+        let level = self
+            .level_pattern
+            .as_ref()
+            .and_then(|re| extract(re, "level", line))
+            .map(str::to_lowercase);
+        let timestamp = self
+            .timestamp_pattern
+            .as_ref()
+            .and_then(|re| extract(re, "timestamp", line))
+            .and_then(|value| self.parse_timestamp(value));
+        PlainTextLine {
+            message: line.to_string(),
+            level,
+            timestamp,
+        }
+    }
+
+    fn parse_timestamp(&self, value: &str) -> Option<DateTime<Utc>> {
+        let format = self.timestamp_format.as_deref();
+        let parsed = match format {
+            Some(format) => DateTime::parse_from_str(value, format),
+            None => DateTime::parse_from_rfc3339(value),
+        };
+        parsed.map(|ts| ts.with_timezone(&Utc)).ok().or_else(|| {
+            NaiveDateTime::parse_from_str(value, format.unwrap_or("%Y-%m-%d %H:%M:%S%.f"))
+                .ok()
+                .map(|ts| ts.and_utc())
+        })
+    }
+}
+
+/// Value of the named group, else the first group, else the whole match
+fn extract<'a>(re: &Regex, name: &str, line: &'a str) -> Option<&'a str> {
+    let caps = re.captures(line)?;
+    caps.name(name)
+        .or_else(|| caps.get(1))
+        .or_else(|| caps.get(0))
+        .map(|m| m.as_str().trim())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_without_patterns_keep_only_the_message() {
+        let parser = PlainTextLineParser::new(&PlainTextParsingSettings::default()).unwrap();
+        let lines = parser.parse_body("first\r\n\n  \nsecond [ERROR]\n");
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].message, "first");
+        assert_eq!(lines[1].message, "second [ERROR]");
+        assert!(
+            lines
+                .iter()
+                .all(|l| l.level.is_none() && l.timestamp.is_none())
+        );
+    }
+
+    #[test]
+    fn test_custom_timestamp_format_and_unparseable_values() {
+        let settings = PlainTextParsingSettings {
+            timestamp_pattern: Some(r"^\[([^\]]+)\]".to_string()),
+            timestamp_format: Some("%d/%b/%Y:%H:%M:%S %z".to_string()),
+            ..Default::default()
+        };
+        let parser = PlainTextLineParser::new(&settings).unwrap();
+
+        let line = parser.parse_line("[10/Oct/2024:13:55:36 +0200] GET /");
+        assert_eq!(
+            line.timestamp.unwrap().to_rfc3339(),
+            "2024-10-10T11:55:36+00:00"
+        );
+        assert!(parser.parse_line("[yesterday] GET /").timestamp.is_none());
+    }
+}
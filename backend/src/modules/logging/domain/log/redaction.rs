@@ -0,0 +1,112 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::modules::projects::domain::{RedactedFieldAction, RedactionSettings};
+
+/// Replacement for redacted values
+pub const REDACTION_MASK: &str = "[REDACTED]";
+
+/// Applies a project's redaction settings to incoming logs
+#[derive(Debug)]
+pub struct LogRedactor {
+    fields: HashSet<String>,
+    field_action: RedactedFieldAction,
+    patterns: Vec<Regex>,
+}
+
+impl LogRedactor {
+    pub fn new(settings: &RedactionSettings) -> Result<Self, regex::Error> {
+        Ok(Self {
+            fields: settings.fields.iter().map(|f| f.to_lowercase()).collect(),
+            field_action: settings.field_action,
+            patterns: settings
+                .message_patterns
+                .iter()
+                .map(|p| Regex::new(p))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Whether redaction would leave every log untouched
+    pub fn is_noop(&self) -> bool {
+        self.fields.is_empty() && self.patterns.is_empty()
+    }
+
+    /// Mask every pattern match in `text`
+    pub fn redact_text<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for pattern in &self.patterns {
+            if let Cow::Owned(replaced) = pattern.replace_all(&text, REDACTION_MASK) {
+                text = Cow::Owned(replaced);
+            }
+        }
+        text
+    }
+
+    /// Drop or mask configured fields anywhere in the metadata
+    pub fn redact_metadata(&self, metadata: &mut Value) {
+        if self.fields.is_empty() {
+            return;
+        }
+        match metadata {
+            Value::Object(map) => {
+                match self.field_action {
+                    RedactedFieldAction::Drop => {
+                        map.retain(|key, _| !self.fields.contains(&key.to_lowercase()))
+                    }
+                    RedactedFieldAction::Mask => {
+                        for (key, value) in map.iter_mut() {
+                            if self.fields.contains(&key.to_lowercase()) {
+                                *value = Value::String(REDACTION_MASK.to_string());
+                            }
+                        }
+                    }
+                }
+                map.values_mut().for_each(|v| self.redact_metadata(v));
+            }
+            Value::Array(items) => items.iter_mut().for_each(|v| self.redact_metadata(v)),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn redactor(fields: &[&str], field_action: RedactedFieldAction) -> LogRedactor {
+        LogRedactor::new(&RedactionSettings {
+            fields: fields.iter().map(|f| f.to_string()).collect(),
+            field_action,
+            message_patterns: vec![r"\d{4}-\d{4}".to_string()],
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mask_nested_fields_case_insensitively() {
+        let mut metadata =
+            json!({"user": {"Password": "hunter2", "name": "bob"}, "items": [{"password": 1}]});
+
+        redactor(&["password"], RedactedFieldAction::Mask).redact_metadata(&mut metadata);
+
+        assert_eq!(
+            metadata,
+            json!({"user": {"Password": REDACTION_MASK, "name": "bob"}, "items": [{"password": REDACTION_MASK}]})
+        );
+    }
+
+    #[test]
+    fn test_text_without_matches_is_borrowed() {
+        let redactor = redactor(&[], RedactedFieldAction::Drop);
+        assert!(matches!(
+            redactor.redact_text("nothing here"),
+            Cow::Borrowed(_)
+        ));
+        assert_eq!(redactor.redact_text("pin 1234-5678"), "pin [REDACTED]");
+    }
+}
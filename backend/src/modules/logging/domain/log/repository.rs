@@ -1,17 +1,24 @@
+use std::collections::BTreeMap;
+
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::dead_letter::DeadLetter;
 use super::entity::LogEntry;
 use super::value_objects::LogLevel;
 use crate::modules::logging::domain::errors::LogDomainError;
 use crate::modules::logging::domain::filter_preset::MetadataFilter;
-use crate::modules::projects::domain::ProjectId;
+use crate::modules::projects::domain::{LogGroupField, ProjectId};
+pub use crate::shared::Pagination;
+use crate::shared::Tz;
 
 /// Query filters for logs
 #[derive(Debug, Clone, Default)]
 pub struct LogFilters {
     pub levels: Option<Vec<LogLevel>>,
+    /// Only logs at least this severe (e.g. Warn keeps Warn, Error and Fatal)
+    pub min_level: Option<LogLevel>,
     pub start_time: Option<DateTime<Utc>>,
     pub end_time: Option<DateTime<Utc>>,
     pub source: Option<String>,
@@ -21,19 +28,29 @@ pub struct LogFilters {
     pub metadata_filters: Vec<MetadataFilter>,
 }
 
-/// Pagination options
+/// Retention cutoff for logs whose metadata has every one of `tags`
 #[derive(Debug, Clone)]
-pub struct Pagination {
-    pub limit: i64,
-    pub offset: i64,
+pub struct TaggedLogCutoff {
+    pub tags: BTreeMap<String, String>,
+    pub cutoff: DateTime<Utc>,
 }
 
-impl Default for Pagination {
-    fn default() -> Self {
-        Self {
-            limit: 100,
-            offset: 0,
-        }
+/// Per-log retention cutoffs. A log is deleted once its timestamp is before
+/// the earliest cutoff among the tag rules it matches (its longest retention),
+/// or before `default` when it matches none.
+#[derive(Debug, Clone)]
+pub struct LogCutoffs {
+    pub default: DateTime<Utc>,
+    pub tagged: Vec<TaggedLogCutoff>,
+}
+
+impl LogCutoffs {
+    /// The latest of all cutoffs: no log after it can expire
+    pub fn latest(&self) -> DateTime<Utc> {
+        self.tagged
+            .iter()
+            .map(|rule| rule.cutoff)
+            .fold(self.default, DateTime::max)
     }
 }
 
@@ -64,7 +81,8 @@ impl SortOrder {
 #[derive(Debug, Clone)]
 pub struct LogQueryResult {
     pub logs: Vec<LogEntry>,
-    pub total: i64,
+    /// Exact number of matching logs; only counted when asked for
+    pub total: Option<i64>,
     pub has_more: bool,
 }
 
@@ -77,19 +95,59 @@ pub struct LogStats {
     pub newest_log: Option<DateTime<Utc>>,
 }
 
+/// Logs sharing one value of the grouping field
+#[derive(Debug, Clone)]
+pub struct LogGroup {
+    /// None groups logs without the field
+    pub value: Option<String>,
+    pub count: i64,
+    /// Most recent logs in the group, newest first
+    pub samples: Vec<LogEntry>,
+}
+
+/// Outcome of saving logs that carry event ids
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DedupSaveResult {
+    pub saved: u32,
+    /// Logs skipped because their event id was seen within the window
+    pub duplicates: u32,
+}
+
 /// Repository trait for Log persistence
 #[async_trait]
 pub trait LogRepository: Send + Sync {
     /// Save a batch of log entries
     async fn save_batch(&self, logs: &[LogEntry]) -> Result<u32, LogDomainError>;
 
-    /// Query logs with filters and pagination
+    /// Save logs carrying event ids, skipping any whose id was already seen for the
+    /// project within `window`. Recording an id and storing its log happen atomically.
+    /// Claimed ids are stored until they expire, so they outlive restarts and are
+    /// shared by every replica.
+    async fn save_batch_dedup(
+        &self,
+        logs: &[LogEntry],
+        window: Duration,
+    ) -> Result<DedupSaveResult, LogDomainError>;
+
+    /// Forget up to `limit` dedup keys that expired before `now`, returning how
+    /// many were removed. Expired keys no longer block ingest; this only
+    /// reclaims their space.
+    async fn prune_dedup_keys(&self, now: DateTime<Utc>, limit: u32)
+    -> Result<u64, LogDomainError>;
+
+    /// Save payloads that failed to parse so they can be inspected later
+    async fn save_dead_letters(&self, letters: &[DeadLetter]) -> Result<u32, LogDomainError>;
+
+    /// Query logs with filters and pagination. `has_more` is found by fetching
+    /// one row past the page; the exact total needs a full count and is only
+    /// computed when `include_total` is set.
     async fn query(
         &self,
         project_id: &ProjectId,
         filters: &LogFilters,
         pagination: &Pagination,
         sort: SortOrder,
+        include_total: bool,
     ) -> Result<LogQueryResult, LogDomainError>;
 
     /// Count logs matching filters
@@ -99,32 +157,55 @@ pub trait LogRepository: Send + Sync {
         filters: &LogFilters,
     ) -> Result<i64, LogDomainError>;
 
+    /// Count logs matching each of two filters in a single pass, e.g. the two
+    /// sides of a ratio
+    async fn count_pair(
+        &self,
+        project_id: &ProjectId,
+        first: &LogFilters,
+        second: &LogFilters,
+    ) -> Result<(i64, i64), LogDomainError>;
+
     /// Get log statistics for a project
     async fn get_stats(&self, project_id: &ProjectId) -> Result<LogStats, LogDomainError>;
 
-    /// Delete logs older than a given timestamp (for retention)
+    /// Delete up to `limit` of the oldest logs older than a given timestamp
+    /// (for retention), returning how many were deleted
     async fn delete_before(
         &self,
         project_id: &ProjectId,
         before: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<u64, LogDomainError>;
+
+    /// Delete up to `limit` of the oldest logs past their cutoff by metadata
+    /// tags, returning how many were deleted
+    async fn delete_expired(
+        &self,
+        project_id: &ProjectId,
+        cutoffs: &LogCutoffs,
+        limit: u32,
     ) -> Result<u64, LogDomainError>;
 
     // ==================== Metrics Methods ====================
 
-    /// Get log volume over time using time buckets
+    /// Get log volume over time using time buckets aligned to `timezone`'s
+    /// wall clock
     async fn get_volume_over_time(
         &self,
         project_id: &ProjectId,
         bucket_interval: &str,
+        timezone: Tz,
         start_time: Option<DateTime<Utc>>,
         end_time: Option<DateTime<Utc>>,
     ) -> Result<Vec<(DateTime<Utc>, i64)>, LogDomainError>;
 
-    /// Get log counts by level over time
+    /// Get log counts by level over time, bucketed like the volume
     async fn get_levels_over_time(
         &self,
         project_id: &ProjectId,
         bucket_interval: &str,
+        timezone: Tz,
         start_time: Option<DateTime<Utc>>,
         end_time: Option<DateTime<Utc>>,
     ) -> Result<Vec<(String, DateTime<Utc>, i64)>, LogDomainError>;
@@ -137,4 +218,16 @@ pub trait LogRepository: Send + Sync {
         start_time: Option<DateTime<Utc>>,
         end_time: Option<DateTime<Utc>>,
     ) -> Result<Vec<(String, i64, i64)>, LogDomainError>;
+
+    /// Count logs per distinct value of `field` within the time range, returning the
+    /// `limit` largest groups with up to `samples` of their most recent logs
+    async fn group_by_field(
+        &self,
+        project_id: &ProjectId,
+        field: &LogGroupField,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+        limit: i32,
+        samples: i32,
+    ) -> Result<Vec<LogGroup>, LogDomainError>;
 }
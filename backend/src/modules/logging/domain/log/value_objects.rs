@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::modules::logging::domain::errors::LogDomainError;
+use crate::shared::IdFormatPolicy;
 
 /// Log ID - wrapper around UUID string
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -39,6 +40,16 @@ pub enum LogLevel {
 }
 
 impl LogLevel {
+    /// Every level, least severe first
+    pub const ALL: [LogLevel; 6] = [
+        Self::Trace,
+        Self::Debug,
+        Self::Info,
+        Self::Warn,
+        Self::Error,
+        Self::Fatal,
+    ];
+
     pub fn from_str(s: &str) -> Result<Self, LogDomainError> {
         match s.to_lowercase().as_str() {
             "trace" => Ok(Self::Trace),
@@ -97,6 +108,11 @@ impl TraceId {
         }
     }
 
+    /// Parse an ingested id under the configured format policy
+    pub fn parse(id: &str, policy: IdFormatPolicy) -> Option<Self> {
+        policy.trace_id(id).ok().map(Self)
+    }
+
     pub fn as_str(&self) -> &str {
         &self.0
     }
@@ -119,6 +135,11 @@ impl SpanId {
         }
     }
 
+    /// Parse an ingested id under the configured format policy
+    pub fn parse(id: &str, policy: IdFormatPolicy) -> Option<Self> {
+        policy.span_id(id).ok().map(Self)
+    }
+
     pub fn as_str(&self) -> &str {
         &self.0
     }
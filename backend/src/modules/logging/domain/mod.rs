@@ -8,6 +8,7 @@ pub use filter_preset::{
     MetadataFilter, MetadataOperator,
 };
 pub use log::{
-    LogEntry, LogFilters, LogId, LogLevel, LogQueryResult, LogRepository, LogStats, Pagination,
-    SortOrder, SpanId, TraceId,
+    DeadLetter, DedupSaveResult, GeoIpDatabase, LogCutoffs, LogEntry, LogFieldMapper, LogFilters,
+    LogGroup, LogId, LogIngestObserver, LogLevel, LogQueryResult, LogRedactor, LogRepository,
+    LogStats, Pagination, PlainTextLineParser, SortOrder, SpanId, TaggedLogCutoff, TraceId,
 };
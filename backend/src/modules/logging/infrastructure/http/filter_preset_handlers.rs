@@ -1,7 +1,7 @@
 use axum::{
+    Extension, Json,
     extract::{Path, State},
     http::StatusCode,
-    Extension, Json,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -101,7 +101,9 @@ fn to_error_response(e: LogDomainError) -> (StatusCode, Json<ErrorResponse>) {
                 code: "FORBIDDEN".to_string(),
             }),
         ),
-        LogDomainError::InvalidLevel(msg) | LogDomainError::InvalidMessage(msg) => (
+        LogDomainError::InvalidLevel(msg)
+        | LogDomainError::InvalidMessage(msg)
+        | LogDomainError::InvalidEventId(msg) => (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 error: msg,
@@ -1,48 +1,40 @@
 use axum::{
+    Extension, Json,
+    body::Bytes,
     extract::{Path, Query, State},
-    http::{header, StatusCode},
+    http::{HeaderMap, StatusCode, header},
     response::IntoResponse,
-    Extension, Json,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
 
-use super::middleware::ApiKeyContext;
+use super::middleware::{ApiKeyContext, PublicReadContext};
 use crate::modules::auth::application::ports::IdGenerator;
 use crate::modules::auth::infrastructure::http::extractors::AuthClaims;
 use crate::modules::logging::application::dto::*;
 use crate::modules::logging::application::services::LogService;
 use crate::modules::logging::domain::LogDomainError;
 use crate::modules::organizations::domain::OrganizationMemberRepository;
-use crate::modules::projects::domain::ProjectRepository;
+use crate::modules::projects::domain::{IngestMode, ProjectRepository, ServiceDisplay};
+use crate::shared::{LimitedJson, PAGINATION_LIMIT_HEADER};
+
+/// Per-request override of the project's ingest acknowledgment mode ("sync" or "async")
+const INGEST_MODE_HEADER: &str = "X-Ingest-Mode";
+
+/// Level given to plain-text lines without an extracted level
+const LOG_LEVEL_HEADER: &str = "X-Log-Level";
 
 // ============================================================================
 // Request/Response DTOs for HTTP layer
 // ============================================================================
 
-/// Ingestion request body
+/// Ingestion request body. Logs are JSON objects in the canonical shape
+/// (level, message, timestamp, ...) or in any shape the project's field mapping covers.
 #[derive(Debug, Deserialize)]
 pub struct IngestRequest {
-    pub logs: Vec<LogInputDto>,
-}
-
-/// Single log entry in ingestion request
-#[derive(Debug, Deserialize)]
-pub struct LogInputDto {
-    pub level: String,
-    pub message: String,
-    #[serde(default)]
-    pub timestamp: Option<DateTime<Utc>>,
-    #[serde(default)]
-    pub source: Option<String>,
-    #[serde(default)]
-    pub metadata: Option<Value>,
-    #[serde(default)]
-    pub trace_id: Option<String>,
-    #[serde(default)]
-    pub span_id: Option<String>,
+    pub logs: Vec<Value>,
 }
 
 /// Query parameters for log queries
@@ -51,6 +43,8 @@ pub struct LogQueryParams {
     #[serde(default)]
     pub levels: Option<String>, // Comma-separated list
     #[serde(default)]
+    pub min_level: Option<String>,
+    #[serde(default)]
     pub start_time: Option<DateTime<Utc>>,
     #[serde(default)]
     pub end_time: Option<DateTime<Utc>>,
@@ -69,23 +63,38 @@ pub struct LogQueryParams {
     /// JSON-encoded array of metadata filters: [{"key":"x","operator":"eq","value":"y"}]
     #[serde(default)]
     pub metadata_filters: Option<String>,
+    /// Comma-separated fields to return, e.g. "timestamp,level,message,metadata.user_id"
+    #[serde(default)]
+    pub fields: Option<String>,
+    /// Also return the exact number of matching logs, which is slow on large projects
+    #[serde(default)]
+    pub include_total: bool,
 }
 
 #[derive(Debug, Serialize)]
 pub struct IngestResponseDto {
     pub accepted: u32,
     pub rejected: u32,
+    pub duplicates: u32,
+    pub server_timestamps: u32,
+    pub below_min_level: u32,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub errors: Vec<String>,
+    pub mode: IngestMode,
 }
 
 #[derive(Debug, Serialize)]
 pub struct LogResponseDto {
-    pub id: String,
-    pub level: String,
-    pub message: String,
-    pub timestamp: DateTime<Utc>,
-    pub received_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub received_at: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -94,12 +103,15 @@ pub struct LogResponseDto {
     pub trace_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub span_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<ServiceDisplay>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct LogQueryResponseDto {
     pub logs: Vec<LogResponseDto>,
-    pub total: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
     pub has_more: bool,
 }
 
@@ -129,26 +141,16 @@ pub struct ErrorResponse {
 // Conversions
 // ============================================================================
 
-impl From<LogInputDto> for LogInput {
-    fn from(dto: LogInputDto) -> Self {
-        Self {
-            level: dto.level,
-            message: dto.message,
-            timestamp: dto.timestamp,
-            source: dto.source,
-            metadata: dto.metadata,
-            trace_id: dto.trace_id,
-            span_id: dto.span_id,
-        }
-    }
-}
-
 impl From<IngestResponse> for IngestResponseDto {
     fn from(r: IngestResponse) -> Self {
         Self {
             accepted: r.accepted,
             rejected: r.rejected,
+            duplicates: r.duplicates,
+            server_timestamps: r.server_timestamps,
+            below_min_level: r.below_min_level,
             errors: r.errors,
+            mode: r.mode,
         }
     }
 }
@@ -165,6 +167,7 @@ impl From<LogResponse> for LogResponseDto {
             metadata: r.metadata,
             trace_id: r.trace_id,
             span_id: r.span_id,
+            service: r.service,
         }
     }
 }
@@ -205,7 +208,12 @@ impl From<LogStatsResponse> for LogStatsResponseDto {
 
 fn to_error_response(e: LogDomainError) -> (StatusCode, Json<ErrorResponse>) {
     match e {
-        LogDomainError::InvalidLevel(msg) | LogDomainError::InvalidMessage(msg) => (
+        LogDomainError::InvalidLevel(msg)
+        | LogDomainError::InvalidMessage(msg)
+        | LogDomainError::InvalidEventId(msg)
+        | LogDomainError::InvalidLogShape(msg)
+        | LogDomainError::InvalidField(msg)
+        | LogDomainError::InvalidTimezone(msg) => (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 error: msg,
@@ -263,6 +271,13 @@ fn to_error_response(e: LogDomainError) -> (StatusCode, Json<ErrorResponse>) {
                 code: "FILTER_PRESET_NAME_EXISTS".to_string(),
             }),
         ),
+        LogDomainError::IngestQueueFull => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse {
+                error: "Too many logs waiting to be written, retry shortly".to_string(),
+                code: "INGEST_QUEUE_FULL".to_string(),
+            }),
+        ),
         LogDomainError::InternalError(ref msg) => {
             tracing::error!(error = %msg, "Internal error occurred");
             (
@@ -280,28 +295,106 @@ fn to_error_response(e: LogDomainError) -> (StatusCode, Json<ErrorResponse>) {
 // Ingestion Handlers (API Key Auth)
 // ============================================================================
 
-/// Ingest logs (authenticated via API key)
+/// Ingest logs (authenticated via API key). Answers 202 when the logs were
+/// only queued for writing, 200 once they are committed.
 pub async fn ingest_logs<LR, PR, MR, ID>(
     State(service): State<Arc<LogService<LR, PR, MR, ID>>>,
     Extension(ctx): Extension<ApiKeyContext>,
-    Json(req): Json<IngestRequest>,
-) -> Result<Json<IngestResponseDto>, (StatusCode, Json<ErrorResponse>)>
+    headers: HeaderMap,
+    LimitedJson(req): LimitedJson<IngestRequest>,
+) -> Result<(StatusCode, Json<IngestResponseDto>), (StatusCode, Json<ErrorResponse>)>
 where
     LR: crate::modules::logging::domain::LogRepository,
     PR: ProjectRepository,
     MR: OrganizationMemberRepository,
     ID: IdGenerator,
 {
-    let cmd = IngestLogsCommand {
+    let cmd = IngestJsonLogsCommand {
         project_id: ctx.project_id.as_str().to_string(),
-        logs: req.logs.into_iter().map(Into::into).collect(),
+        logs: req.logs,
+        mode: ingest_mode(&headers)?,
     };
 
-    service
-        .ingest(cmd)
-        .await
-        .map(|r| Json(r.into()))
-        .map_err(to_error_response)
+    let response = service.ingest_json(cmd).await.map_err(to_error_response)?;
+    Ok((ingest_status(&response), Json(response.into())))
+}
+
+/// Ingest a newline-delimited `text/plain` body, one log per line. The
+/// `X-Log-Level` header sets the level of lines without an extracted one.
+pub async fn ingest_text_logs<LR, PR, MR, ID>(
+    State(service): State<Arc<LogService<LR, PR, MR, ID>>>,
+    Extension(ctx): Extension<ApiKeyContext>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<IngestResponseDto>), (StatusCode, Json<ErrorResponse>)>
+where
+    LR: crate::modules::logging::domain::LogRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let validation_error = |error: String| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error,
+                code: "VALIDATION_ERROR".to_string(),
+            }),
+        )
+    };
+    let body = String::from_utf8(body.to_vec())
+        .map_err(|_| validation_error("Body must be UTF-8 text".to_string()))?;
+    let level = headers
+        .get(LOG_LEVEL_HEADER)
+        .map(|value| {
+            value
+                .to_str()
+                .map(str::to_string)
+                .map_err(|_| validation_error(format!("{} must be a log level", LOG_LEVEL_HEADER)))
+        })
+        .transpose()?;
+
+    let cmd = IngestTextLogsCommand {
+        project_id: ctx.project_id.as_str().to_string(),
+        body,
+        level,
+        mode: ingest_mode(&headers)?,
+    };
+
+    let response = service.ingest_text(cmd).await.map_err(to_error_response)?;
+    Ok((ingest_status(&response), Json(response.into())))
+}
+
+/// Ingest mode requested through the `X-Ingest-Mode` header, if any
+fn ingest_mode(
+    headers: &HeaderMap,
+) -> Result<Option<IngestMode>, (StatusCode, Json<ErrorResponse>)> {
+    headers
+        .get(INGEST_MODE_HEADER)
+        .map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(IngestMode::parse)
+                .ok_or_else(|| {
+                    (
+                        StatusCode::BAD_REQUEST,
+                        Json(ErrorResponse {
+                            error: format!("{} must be 'sync' or 'async'", INGEST_MODE_HEADER),
+                            code: "VALIDATION_ERROR".to_string(),
+                        }),
+                    )
+                })
+        })
+        .transpose()
+}
+
+/// 202 when the logs were only queued for writing, 200 once they are committed
+fn ingest_status(response: &IngestResponse) -> StatusCode {
+    match response.mode {
+        IngestMode::Async => StatusCode::ACCEPTED,
+        IngestMode::Sync => StatusCode::OK,
+    }
 }
 
 // ============================================================================
@@ -314,13 +407,64 @@ pub async fn query_logs<LR, PR, MR, ID>(
     Extension(claims): Extension<AuthClaims>,
     Path(project_id): Path<String>,
     Query(params): Query<LogQueryParams>,
-) -> Result<Json<LogQueryResponseDto>, (StatusCode, Json<ErrorResponse>)>
+) -> Result<
+    ([(&'static str, String); 1], Json<LogQueryResponseDto>),
+    (StatusCode, Json<ErrorResponse>),
+>
+where
+    LR: crate::modules::logging::domain::LogRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let cmd = query_logs_command(project_id, params, claims.user_id)?;
+
+    service
+        .query(cmd)
+        .await
+        .map(|r| {
+            (
+                [(PAGINATION_LIMIT_HEADER, r.limit.to_string())],
+                Json(r.into()),
+            )
+        })
+        .map_err(to_error_response)
+}
+
+/// Query logs with a public read token; read-only and limited to the token's project
+pub async fn public_query_logs<LR, PR, MR, ID>(
+    State(service): State<Arc<LogService<LR, PR, MR, ID>>>,
+    Extension(ctx): Extension<PublicReadContext>,
+    Query(params): Query<LogQueryParams>,
+) -> Result<
+    ([(&'static str, String); 1], Json<LogQueryResponseDto>),
+    (StatusCode, Json<ErrorResponse>),
+>
 where
     LR: crate::modules::logging::domain::LogRepository,
     PR: ProjectRepository,
     MR: OrganizationMemberRepository,
     ID: IdGenerator,
 {
+    let cmd = query_logs_command(ctx.project_id.as_str().to_string(), params, String::new())?;
+
+    service
+        .query_public(cmd)
+        .await
+        .map(|r| {
+            (
+                [(PAGINATION_LIMIT_HEADER, r.limit.to_string())],
+                Json(r.into()),
+            )
+        })
+        .map_err(to_error_response)
+}
+
+fn query_logs_command(
+    project_id: String,
+    params: LogQueryParams,
+    requesting_user_id: String,
+) -> Result<QueryLogsCommand, (StatusCode, Json<ErrorResponse>)> {
     // Parse comma-separated levels
     let levels = params.levels.map(|s| {
         s.split(',')
@@ -333,17 +477,16 @@ where
     let metadata_filters = params
         .metadata_filters
         .map(|json_str| {
-            serde_json::from_str::<Vec<MetadataFilterInput>>(&json_str)
-                .map_err(|e| LogDomainError::InvalidFilterPreset(format!(
-                    "Invalid metadata_filters JSON: {}",
-                    e
-                )))
+            serde_json::from_str::<Vec<MetadataFilterInput>>(&json_str).map_err(|e| {
+                LogDomainError::InvalidFilterPreset(format!("Invalid metadata_filters JSON: {}", e))
+            })
         })
         .transpose()
         .map_err(to_error_response)?;
 
     let filters = QueryFilters {
         levels,
+        min_level: params.min_level,
         start_time: params.start_time,
         end_time: params.end_time,
         source: params.source,
@@ -353,20 +496,16 @@ where
         preset_id: None,
     };
 
-    let cmd = QueryLogsCommand {
+    Ok(QueryLogsCommand {
         project_id,
         filters,
         limit: params.limit,
         offset: params.offset,
         sort: params.sort,
-        requesting_user_id: claims.user_id,
-    };
-
-    service
-        .query(cmd)
-        .await
-        .map(|r| Json(r.into()))
-        .map_err(to_error_response)
+        fields: params.fields,
+        include_total: params.include_total,
+        requesting_user_id,
+    })
 }
 
 /// Get log statistics for a project
@@ -388,6 +527,50 @@ where
         .map_err(to_error_response)
 }
 
+/// Query parameters for grouped logs
+#[derive(Debug, Deserialize)]
+pub struct LogGroupsQueryParams {
+    #[serde(default)]
+    pub start_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub end_time: Option<DateTime<Utc>>,
+    /// Most groups to return (default: 20)
+    #[serde(default)]
+    pub limit: Option<i32>,
+    /// Recent logs per group (default: 3)
+    #[serde(default)]
+    pub samples: Option<i32>,
+}
+
+/// Group logs by the project's primary field
+pub async fn group_logs<LR, PR, MR, ID>(
+    State(service): State<Arc<LogService<LR, PR, MR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(project_id): Path<String>,
+    Query(params): Query<LogGroupsQueryParams>,
+) -> Result<Json<LogGroupsResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    LR: crate::modules::logging::domain::LogRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let cmd = GroupLogsCommand {
+        project_id,
+        start_time: params.start_time,
+        end_time: params.end_time,
+        limit: params.limit,
+        samples: params.samples,
+        requesting_user_id: claims.user_id,
+    };
+
+    service
+        .group(cmd)
+        .await
+        .map(Json)
+        .map_err(to_error_response)
+}
+
 /// Query parameters for metrics
 #[derive(Debug, Deserialize)]
 pub struct MetricsQueryParams {
@@ -403,6 +586,9 @@ pub struct MetricsQueryParams {
     /// Limit for top sources (default: 10)
     #[serde(default = "default_top_sources_limit")]
     pub top_sources_limit: i32,
+    /// IANA timezone buckets align to (default: the project's, else UTC)
+    #[serde(default)]
+    pub timezone: Option<String>,
 }
 
 fn default_top_sources_limit() -> i32 {
@@ -428,6 +614,7 @@ where
         start_time: params.start_time,
         end_time: params.end_time,
         top_sources_limit: params.top_sources_limit,
+        timezone: params.timezone,
         requesting_user_id: claims.user_id,
     };
 
@@ -438,6 +625,35 @@ where
         .map_err(to_error_response)
 }
 
+/// Dashboard metrics with a public read token; read-only and limited to the token's project
+pub async fn public_get_metrics<LR, PR, MR, ID>(
+    State(service): State<Arc<LogService<LR, PR, MR, ID>>>,
+    Extension(ctx): Extension<PublicReadContext>,
+    Query(params): Query<MetricsQueryParams>,
+) -> Result<Json<MetricsResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    LR: crate::modules::logging::domain::LogRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let query = MetricsQuery {
+        project_id: ctx.project_id.as_str().to_string(),
+        bucket: params.bucket,
+        start_time: params.start_time,
+        end_time: params.end_time,
+        top_sources_limit: params.top_sources_limit,
+        timezone: params.timezone,
+        requesting_user_id: String::new(),
+    };
+
+    service
+        .get_metrics_public(query)
+        .await
+        .map(Json)
+        .map_err(to_error_response)
+}
+
 /// Export logs to a ZIP file
 pub async fn export_logs<LR, PR, MR, ID>(
     State(service): State<Arc<LogService<LR, PR, MR, ID>>>,
@@ -479,3 +695,110 @@ where
         bytes,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Duration;
+    use tokio::sync::Semaphore;
+
+    use crate::modules::logging::application::services::LogWriteQueue;
+    use crate::modules::projects::domain::ProjectId;
+    use crate::shared::PaginationConfig;
+    use crate::shared::testing::{
+        InMemoryLogRepository, InMemoryMemberRepository, InMemoryProjectRepository,
+        SequentialIdGenerator,
+    };
+
+    type TestLogService = LogService<
+        InMemoryLogRepository,
+        InMemoryProjectRepository,
+        InMemoryMemberRepository,
+        SequentialIdGenerator,
+    >;
+
+    /// Service over a repository whose writes wait for permits from the returned gate
+    fn gated_service() -> (
+        Arc<TestLogService>,
+        Arc<InMemoryLogRepository>,
+        Arc<Semaphore>,
+        ApiKeyContext,
+    ) {
+        let gate = Arc::new(Semaphore::new(0));
+        let log_repo = Arc::new(InMemoryLogRepository::gated(gate.clone()));
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let project = project_repo.seed("project-1", "org-1");
+        let service = LogService::new(
+            log_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        )
+        .with_write_queue(LogWriteQueue::start(
+            log_repo.clone(),
+            16,
+            chrono::Duration::hours(1),
+            None,
+        ));
+        let ctx = ApiKeyContext {
+            project_id: ProjectId::new("project-1".to_string()),
+            project,
+        };
+        (Arc::new(service), log_repo, gate, ctx)
+    }
+
+    async fn ingest(
+        service: Arc<TestLogService>,
+        ctx: ApiKeyContext,
+        mode: &str,
+    ) -> (StatusCode, IngestResponseDto) {
+        let mut headers = HeaderMap::new();
+        headers.insert(INGEST_MODE_HEADER, mode.parse().unwrap());
+        let body = IngestRequest {
+            logs: vec![json!({"level": "info", "message": "order placed"})],
+        };
+        let (status, Json(response)) =
+            ingest_logs(State(service), Extension(ctx), headers, LimitedJson(body))
+                .await
+                .unwrap();
+        (status, response)
+    }
+
+    #[tokio::test]
+    async fn test_async_mode_acknowledges_before_write_completes() {
+        let (service, log_repo, gate, ctx) = gated_service();
+
+        let (status, response) = ingest(service, ctx, "async").await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert_eq!(response.mode, IngestMode::Async);
+        assert_eq!(response.accepted, 1);
+        assert!(log_repo.saved().is_empty());
+
+        gate.add_permits(1);
+        for _ in 0..100 {
+            if !log_repo.saved().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(log_repo.saved().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sync_mode_returns_counts_after_commit() {
+        let (service, log_repo, gate, ctx) = gated_service();
+
+        let request = tokio::spawn(ingest(service, ctx, "sync"));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!request.is_finished());
+
+        gate.add_permits(1);
+        let (status, response) = request.await.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response.mode, IngestMode::Sync);
+        assert_eq!(response.accepted, 1);
+        assert_eq!(log_repo.saved().len(), 1);
+    }
+}
@@ -1,18 +1,23 @@
 use axum::{
+    Json,
     body::Body,
-    extract::State,
-    http::{header, Request, StatusCode},
+    extract::{RawPathParams, State},
+    http::{Method, Request, StatusCode, header},
     middleware::Next,
     response::{IntoResponse, Response},
-    Json,
 };
+use ipnet::IpNet;
 use serde::Serialize;
 use std::sync::Arc;
 
 use crate::modules::auth::application::ports::IdGenerator;
 use crate::modules::organizations::domain::{OrganizationMemberRepository, OrganizationRepository};
-use crate::modules::projects::application::services::ProjectService;
-use crate::modules::projects::domain::{ApiKeyRepository, Project, ProjectId, ProjectRepository};
+use crate::modules::projects::application::services::{ProjectService, PublicReadTokenService};
+use crate::modules::projects::domain::{
+    ApiKeyRepository, Project, ProjectDomainError, ProjectId, ProjectRepository,
+    PublicReadTokenRepository,
+};
+use crate::shared::client_ip;
 
 /// Context injected after API key validation
 #[derive(Debug, Clone)]
@@ -32,6 +37,7 @@ pub struct ApiKeyErrorResponse {
 /// Extracts API key from:
 /// - X-API-Key header
 /// - Authorization: Bearer <key>
+/// - X-Amz-Firehose-Access-Key header (Kinesis Data Firehose deliveries)
 pub async fn api_key_middleware<PR, AR, OR, MR, ID>(
     State(service): State<Arc<ProjectService<PR, AR, OR, MR, ID>>>,
     mut request: Request<Body>,
@@ -53,7 +59,8 @@ where
             return (
                 StatusCode::UNAUTHORIZED,
                 Json(ApiKeyErrorResponse {
-                    error: "Missing API key. Provide via X-API-Key header or Authorization: Bearer".to_string(),
+                    error: "Missing API key. Provide via X-API-Key header or Authorization: Bearer"
+                        .to_string(),
                     code: "MISSING_API_KEY".to_string(),
                 }),
             )
@@ -64,6 +71,19 @@ where
     // Validate the API key
     match service.validate_api_key(&api_key).await {
         Ok((project_id, project)) => {
+            // Enforce the project's IP allowlist
+            if let Err(e) = ensure_source_allowed(&project, &request, service.trusted_proxies()) {
+                tracing::warn!(project_id = %project_id.as_str(), error = %e, "Ingest rejected by IP allowlist");
+                return (
+                    StatusCode::FORBIDDEN,
+                    Json(ApiKeyErrorResponse {
+                        error: e.to_string(),
+                        code: "IP_NOT_ALLOWED".to_string(),
+                    }),
+                )
+                    .into_response();
+            }
+
             // Inject context into request extensions
             request.extensions_mut().insert(ApiKeyContext {
                 project_id,
@@ -73,21 +93,43 @@ where
         }
         Err(e) => {
             let (status, error, code) = match e {
-                crate::modules::projects::domain::ProjectDomainError::ApiKeyInvalid => {
-                    (StatusCode::UNAUTHORIZED, "Invalid API key", "INVALID_API_KEY")
-                }
-                crate::modules::projects::domain::ProjectDomainError::ApiKeyRevoked => {
-                    (StatusCode::UNAUTHORIZED, "API key has been revoked", "API_KEY_REVOKED")
-                }
-                crate::modules::projects::domain::ProjectDomainError::ApiKeyExpired => {
-                    (StatusCode::UNAUTHORIZED, "API key has expired", "API_KEY_EXPIRED")
-                }
-                crate::modules::projects::domain::ProjectDomainError::ProjectNotFound => {
-                    (StatusCode::FORBIDDEN, "Project not found or deleted", "PROJECT_NOT_FOUND")
+                crate::modules::projects::domain::ProjectDomainError::ApiKeyInvalid => (
+                    StatusCode::UNAUTHORIZED,
+                    "Invalid API key",
+                    "INVALID_API_KEY",
+                ),
+                crate::modules::projects::domain::ProjectDomainError::ApiKeyRevoked => (
+                    StatusCode::UNAUTHORIZED,
+                    "API key has been revoked",
+                    "API_KEY_REVOKED",
+                ),
+                crate::modules::projects::domain::ProjectDomainError::ApiKeyExpired => (
+                    StatusCode::UNAUTHORIZED,
+                    "API key has expired",
+                    "API_KEY_EXPIRED",
+                ),
+                crate::modules::projects::domain::ProjectDomainError::ProjectNotFound => (
+                    StatusCode::FORBIDDEN,
+                    "Project not found or deleted",
+                    "PROJECT_NOT_FOUND",
+                ),
+                crate::modules::projects::domain::ProjectDomainError::RegionMismatch { .. } => {
+                    return (
+                        StatusCode::MISDIRECTED_REQUEST,
+                        Json(ApiKeyErrorResponse {
+                            error: e.to_string(),
+                            code: "REGION_MISMATCH".to_string(),
+                        }),
+                    )
+                        .into_response();
                 }
                 _ => {
                     tracing::error!(error = %e, "API key validation error");
-                    (StatusCode::INTERNAL_SERVER_ERROR, "Internal error", "INTERNAL_ERROR")
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Internal error",
+                        "INTERNAL_ERROR",
+                    )
                 }
             };
 
@@ -128,5 +170,293 @@ fn extract_api_key(request: &Request<Body>) -> Option<String> {
         }
     }
 
+    // Firehose HTTP endpoints send the configured access key in their own header
+    if let Some(key) = request
+        .headers()
+        .get("X-Amz-Firehose-Access-Key")
+        .and_then(|v| v.to_str().ok())
+    {
+        return Some(key.to_string());
+    }
+
     None
 }
+
+/// Context injected after public read token validation
+#[derive(Debug, Clone)]
+pub struct PublicReadContext {
+    pub project_id: ProjectId,
+}
+
+/// Middleware admitting unauthenticated reads that carry a project's public
+/// read token in the X-Public-Token header
+///
+/// Must be applied as a route layer: it only lets GET and HEAD requests
+/// through, and only when the `{id}` path parameter is the token's project.
+pub async fn public_read_middleware<TR, PR, MR>(
+    State(service): State<Arc<PublicReadTokenService<TR, PR, MR>>>,
+    path_params: RawPathParams,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response
+where
+    TR: PublicReadTokenRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+{
+    let reject = |status: StatusCode, error: &str, code: &str| {
+        (
+            status,
+            Json(ApiKeyErrorResponse {
+                error: error.to_string(),
+                code: code.to_string(),
+            }),
+        )
+            .into_response()
+    };
+
+    if !matches!(*request.method(), Method::GET | Method::HEAD) {
+        return reject(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "Public read tokens only allow read requests",
+            "READ_ONLY",
+        );
+    }
+
+    let Some(token) = request
+        .headers()
+        .get("X-Public-Token")
+        .and_then(|v| v.to_str().ok())
+    else {
+        return reject(
+            StatusCode::UNAUTHORIZED,
+            "Missing public read token. Provide via X-Public-Token header",
+            "MISSING_PUBLIC_TOKEN",
+        );
+    };
+
+    let project_id = match service.validate_token(token).await {
+        Ok(project_id) => project_id,
+        Err(ProjectDomainError::PublicTokenInvalid | ProjectDomainError::ProjectNotFound) => {
+            return reject(
+                StatusCode::UNAUTHORIZED,
+                "Invalid public read token",
+                "INVALID_PUBLIC_TOKEN",
+            );
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Public read token validation error");
+            return reject(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Internal error",
+                "INTERNAL_ERROR",
+            );
+        }
+    };
+
+    let requested = path_params
+        .iter()
+        .find(|(name, _)| *name == "id")
+        .map(|(_, value)| value);
+    if requested != Some(project_id.as_str()) {
+        return reject(
+            StatusCode::FORBIDDEN,
+            "Public read token does not grant access to this project",
+            "PROJECT_MISMATCH",
+        );
+    }
+
+    request
+        .extensions_mut()
+        .insert(PublicReadContext { project_id });
+    next.run(request).await
+}
+
+/// Check the request's client IP against the project's ingest allowlist
+fn ensure_source_allowed(
+    project: &Project,
+    request: &Request<Body>,
+    trusted_proxies: &[IpNet],
+) -> Result<(), ProjectDomainError> {
+    project.ensure_ip_allowed(client_ip(request, trusted_proxies))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::extract::ConnectInfo;
+    use serde_json::json;
+    use std::net::{IpAddr, SocketAddr};
+    use tower::ServiceExt;
+
+    use crate::modules::logging::application::dto::{IngestLogsCommand, LogInput};
+    use crate::modules::logging::application::services::LogService;
+    use crate::modules::logging::infrastructure::http::routes::{ingest_routes, public_log_routes};
+    use crate::modules::organizations::domain::OrgRole;
+    use crate::shared::PaginationConfig;
+    use crate::shared::testing::{
+        InMemoryApiKeyRepository, InMemoryLogRepository, InMemoryMemberRepository,
+        InMemoryOrganizationRepository, InMemoryProjectRepository,
+        InMemoryPublicReadTokenRepository, SequentialIdGenerator,
+    };
+
+    fn project(allowed_ips: &[&str]) -> Project {
+        let mut project = InMemoryProjectRepository::new().seed("project-1", "org-1");
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({ "allowed_ips": allowed_ips }))
+                .unwrap(),
+        );
+        project
+    }
+
+    fn request(peer: &str, forwarded_for: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().uri("/ingest/logs");
+        if let Some(forwarded_for) = forwarded_for {
+            builder = builder.header("X-Forwarded-For", forwarded_for);
+        }
+        let mut request = builder.body(Body::empty()).unwrap();
+        let peer: IpAddr = peer.parse().unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::new(peer, 40000)));
+        request
+    }
+
+    #[test]
+    fn test_allowlist_accepts_inside_and_rejects_outside() {
+        let allowlisted = project(&["203.0.113.0/24", "2001:db8::1"]);
+
+        assert!(ensure_source_allowed(&allowlisted, &request("203.0.113.7", None), &[]).is_ok());
+        assert!(ensure_source_allowed(&allowlisted, &request("2001:db8::1", None), &[]).is_ok());
+        assert_eq!(
+            ensure_source_allowed(&allowlisted, &request("198.51.100.7", None), &[]),
+            Err(ProjectDomainError::IpNotAllowed("198.51.100.7".to_string()))
+        );
+
+        // An empty allowlist allows everyone
+        assert!(ensure_source_allowed(&project(&[]), &request("198.51.100.7", None), &[]).is_ok());
+    }
+
+    #[test]
+    fn test_forwarded_for_is_only_trusted_from_trusted_proxies() {
+        let project = project(&["203.0.113.0/24"]);
+        let proxies: Vec<IpNet> = vec!["10.0.0.0/8".parse().unwrap()];
+
+        // Behind the proxy, the client's forwarded address is checked
+        let inside = request("10.0.0.2", Some("203.0.113.7, 10.0.0.9"));
+        assert!(ensure_source_allowed(&project, &inside, &proxies).is_ok());
+        let outside = request("10.0.0.2", Some("203.0.113.7, 198.51.100.7"));
+        assert!(ensure_source_allowed(&project, &outside, &proxies).is_err());
+
+        // From an untrusted peer the header is ignored
+        let spoofed = request("198.51.100.7", Some("203.0.113.7"));
+        assert!(ensure_source_allowed(&project, &spoofed, &proxies).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_public_token_reads_own_project_only() {
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        project_repo.seed("project-1", "org-1");
+        project_repo.seed("project-2", "org-1");
+        member_repo.seed("org-1", "user-1", OrgRole::Admin);
+        let token_service = Arc::new(PublicReadTokenService::new(
+            Arc::new(InMemoryPublicReadTokenRepository::new()),
+            project_repo.clone(),
+            member_repo.clone(),
+        ));
+        let log_service = Arc::new(LogService::new(
+            Arc::new(InMemoryLogRepository::new()),
+            project_repo.clone(),
+            member_repo.clone(),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        ));
+        let project_service = Arc::new(ProjectService::new(
+            project_repo,
+            Arc::new(InMemoryApiKeyRepository::new()),
+            Arc::new(InMemoryOrganizationRepository::new()),
+            member_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+            None,
+            Vec::new(),
+        ));
+        log_service
+            .ingest(IngestLogsCommand {
+                project_id: "project-1".to_string(),
+                logs: vec![LogInput {
+                    level: "info".to_string(),
+                    message: "hello".to_string(),
+                    timestamp: None,
+                    source: None,
+                    metadata: None,
+                    trace_id: None,
+                    span_id: None,
+                    event_id: None,
+                }],
+            })
+            .await
+            .unwrap();
+        let token = token_service
+            .create_token("project-1", "user-1")
+            .await
+            .unwrap()
+            .plain_token;
+        let app = Router::new()
+            .nest(
+                "/public",
+                public_log_routes(log_service.clone(), token_service.clone()),
+            )
+            .merge(ingest_routes(log_service, project_service));
+        let send = |method: &str, uri: &str, header: &str| {
+            let request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header, &token)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"logs":[{"level":"info","message":"x"}]}"#))
+                .unwrap();
+            app.clone().oneshot(request)
+        };
+
+        let response = send("GET", "/public/projects/project-1/logs", "X-Public-Token")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["logs"][0]["message"], "hello");
+
+        // Scoped to its own project
+        let response = send("GET", "/public/projects/project-2/logs", "X-Public-Token")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Never accepted for writes, whether on the public routes or at ingest
+        let response = send("POST", "/public/projects/project-1/logs", "X-Public-Token")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        for header in ["X-Public-Token", "X-API-Key"] {
+            let response = send("POST", "/ingest/logs", header).await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        // Revoked tokens stop working
+        token_service
+            .revoke_token("project-1", "user-1")
+            .await
+            .unwrap();
+        let response = send("GET", "/public/projects/project-1/logs", "X-Public-Token")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -5,5 +5,7 @@ pub mod routes;
 pub mod sse;
 
 pub use middleware::ApiKeyContext;
-pub use routes::{filter_preset_routes, ingest_routes, log_query_routes, sse_routes};
+pub use routes::{
+    filter_preset_routes, ingest_routes, log_query_routes, public_log_routes, sse_routes,
+};
 pub use sse::stream_logs;
@@ -1,13 +1,12 @@
 use axum::{
-    middleware,
+    Router, middleware,
     routing::{delete, get, post, put},
-    Router,
 };
 use std::sync::Arc;
 
 use super::filter_preset_handlers;
 use super::handlers;
-use super::middleware::api_key_middleware;
+use super::middleware::{api_key_middleware, public_read_middleware};
 use super::sse;
 use crate::modules::auth::application::ports::{IdGenerator, TokenService};
 use crate::modules::auth::infrastructure::http::middleware::auth_middleware;
@@ -15,8 +14,10 @@ use crate::modules::logging::application::services::{FilterPresetService, LogSer
 use crate::modules::logging::domain::{FilterPresetRepository, LogRepository};
 use crate::modules::logging::infrastructure::broadcast::LogBroadcaster;
 use crate::modules::organizations::domain::{OrganizationMemberRepository, OrganizationRepository};
-use crate::modules::projects::application::services::ProjectService;
-use crate::modules::projects::domain::{ApiKeyRepository, ProjectRepository};
+use crate::modules::projects::application::services::{ProjectService, PublicReadTokenService};
+use crate::modules::projects::domain::{
+    ApiKeyRepository, ProjectRepository, PublicReadTokenRepository,
+};
 
 /// Create ingestion routes (API key auth)
 pub fn ingest_routes<LR, PR, MR, ID, PPR, AR, OR>(
@@ -37,6 +38,10 @@ where
             "/ingest/logs",
             post(handlers::ingest_logs::<LR, PR, MR, ID>),
         )
+        .route(
+            "/ingest/logs/text",
+            post(handlers::ingest_text_logs::<LR, PR, MR, ID>),
+        )
         .layer(middleware::from_fn_with_state(
             project_service,
             api_key_middleware::<PPR, AR, OR, MR, ID>,
@@ -44,6 +49,37 @@ where
         .with_state(log_service)
 }
 
+/// Create read-only log routes for holders of a project's public read token
+pub fn public_log_routes<LR, PR, MR, ID, TR, TPR, TMR>(
+    log_service: Arc<LogService<LR, PR, MR, ID>>,
+    token_service: Arc<PublicReadTokenService<TR, TPR, TMR>>,
+) -> Router
+where
+    LR: LogRepository + 'static,
+    PR: ProjectRepository + 'static,
+    MR: OrganizationMemberRepository + 'static,
+    ID: IdGenerator + 'static,
+    TR: PublicReadTokenRepository + 'static,
+    TPR: ProjectRepository + 'static,
+    TMR: OrganizationMemberRepository + 'static,
+{
+    Router::new()
+        .route(
+            "/projects/{id}/logs",
+            get(handlers::public_query_logs::<LR, PR, MR, ID>),
+        )
+        .route(
+            "/projects/{id}/metrics",
+            get(handlers::public_get_metrics::<LR, PR, MR, ID>),
+        )
+        // Route layer so the middleware sees the matched project id
+        .route_layer(middleware::from_fn_with_state(
+            token_service,
+            public_read_middleware::<TR, TPR, TMR>,
+        ))
+        .with_state(log_service)
+}
+
 /// Create log query routes (JWT auth)
 pub fn log_query_routes<LR, PR, MR, ID, TS>(
     log_service: Arc<LogService<LR, PR, MR, ID>>,
@@ -65,6 +101,10 @@ where
             "/projects/{id}/logs/stats",
             get(handlers::get_log_stats::<LR, PR, MR, ID>),
         )
+        .route(
+            "/projects/{id}/logs/groups",
+            get(handlers::group_logs::<LR, PR, MR, ID>),
+        )
         .route(
             "/projects/{id}/logs/export",
             post(handlers::export_logs::<LR, PR, MR, ID>),
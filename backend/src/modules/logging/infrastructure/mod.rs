@@ -2,6 +2,9 @@ pub mod broadcast;
 pub mod http;
 pub mod persistence;
 
-pub use broadcast::{start_cleanup_task, start_log_listener, LogBroadcaster, LogNotification};
-pub use http::{filter_preset_routes, ingest_routes, log_query_routes, sse_routes, stream_logs, ApiKeyContext};
+pub use broadcast::{LogBroadcaster, LogNotification, start_cleanup_task, start_log_listener};
+pub use http::{
+    ApiKeyContext, filter_preset_routes, ingest_routes, log_query_routes, public_log_routes,
+    sse_routes, stream_logs,
+};
 pub use persistence::{PostgresFilterPresetRepository, TimescaleLogRepository};
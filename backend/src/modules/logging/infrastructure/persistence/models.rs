@@ -15,6 +15,18 @@ pub struct LogRow {
     pub metadata: Option<Value>,
     pub trace_id: Option<String>,
     pub span_id: Option<String>,
+    /// Sub-microsecond part of `timestamp`
+    pub timestamp_nanos: i16,
+    pub sequence: Option<i64>,
+}
+
+/// Log row tagged with the group it samples
+#[derive(Debug, FromRow)]
+pub struct LogGroupRow {
+    pub group_value: Option<String>,
+    pub group_count: i64,
+    #[sqlx(flatten)]
+    pub log: LogRow,
 }
 
 /// Row for level counts
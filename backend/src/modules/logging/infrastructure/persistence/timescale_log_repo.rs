@@ -1,14 +1,21 @@
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
-use sqlx::PgPool;
+use chrono::{DateTime, Duration, SubsecRound, Utc};
+use sqlx::{PgConnection, PgPool};
 use std::sync::Arc;
 
-use super::models::{LevelBucketRow, LevelCountRow, LogRow, LogStatsRow, SourceCountRow, TimeBucketRow};
+use super::models::{
+    LevelBucketRow, LevelCountRow, LogGroupRow, LogRow, LogStatsRow, SourceCountRow, TimeBucketRow,
+};
 use crate::modules::logging::domain::{
-    LogDomainError, LogEntry, LogFilters, LogId, LogLevel, LogQueryResult, LogRepository,
-    LogStats, MetadataFilter, MetadataOperator, Pagination, SortOrder, SpanId, TraceId,
+    DeadLetter, DedupSaveResult, LogCutoffs, LogDomainError, LogEntry, LogFilters, LogGroup, LogId,
+    LogLevel, LogQueryResult, LogRepository, LogStats, MetadataFilter, MetadataOperator,
+    Pagination, SortOrder, SpanId, TraceId,
 };
-use crate::modules::projects::domain::ProjectId;
+use crate::modules::projects::domain::{LogGroupField, ProjectId};
+use crate::shared::Tz;
+
+/// Scope of log event ids in the shared ingest dedup index
+const EVENT_ID_DEDUP_SCOPE: &str = "log_event_id";
 
 pub struct TimescaleLogRepository {
     pool: Arc<PgPool>,
@@ -19,6 +26,30 @@ impl TimescaleLogRepository {
         Self { pool }
     }
 
+    async fn insert_log(conn: &mut PgConnection, log: &LogEntry) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO logs (id, project_id, level, message, timestamp, received_at,
+                              source, metadata, trace_id, span_id, timestamp_nanos)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            "#,
+        )
+        .bind(log.id().as_str())
+        .bind(log.project_id().as_str())
+        .bind(log.level().as_str())
+        .bind(log.message())
+        .bind(log.timestamp().trunc_subsecs(6))
+        .bind(log.received_at())
+        .bind(log.source())
+        .bind(log.metadata())
+        .bind(log.trace_id().map(|t| t.as_str()))
+        .bind(log.span_id().map(|s| s.as_str()))
+        .bind((log.timestamp().timestamp_subsec_nanos() % 1000) as i16)
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
     fn row_to_log_entry(row: LogRow) -> Result<LogEntry, LogDomainError> {
         let id = LogId::new(row.id);
         let project_id = ProjectId::new(row.project_id);
@@ -31,13 +62,23 @@ impl TimescaleLogRepository {
             project_id,
             level,
             row.message,
-            row.timestamp,
+            row.timestamp + Duration::nanoseconds(row.timestamp_nanos.into()),
             row.received_at,
             row.source,
             row.metadata,
             trace_id,
             span_id,
-        ))
+        )
+        .with_sequence(row.sequence))
+    }
+
+    /// SQL expression for a row's numeric level severity, matching `LogLevel::severity`
+    fn severity_sql() -> String {
+        let arms: String = LogLevel::ALL
+            .iter()
+            .map(|level| format!(" WHEN '{}' THEN {}", level.as_str(), level.severity()))
+            .collect();
+        format!("(CASE level{} END)", arms)
     }
 
     /// Build WHERE clause from filters
@@ -48,12 +89,24 @@ impl TimescaleLogRepository {
 
         if let Some(ref levels) = filters.levels {
             if !levels.is_empty() {
-                let placeholders: Vec<String> =
-                    levels.iter().map(|_| format!("${}", { idx += 1; idx })).collect();
+                let placeholders: Vec<String> = levels
+                    .iter()
+                    .map(|_| {
+                        format!("${}", {
+                            idx += 1;
+                            idx
+                        })
+                    })
+                    .collect();
                 conditions.push(format!("level IN ({})", placeholders.join(", ")));
             }
         }
 
+        if filters.min_level.is_some() {
+            idx += 1;
+            conditions.push(format!("{} >= ${}", Self::severity_sql(), idx));
+        }
+
         if filters.start_time.is_some() {
             idx += 1;
             conditions.push(format!("timestamp >= ${}", idx));
@@ -100,26 +153,35 @@ impl TimescaleLogRepository {
 
         match filter.operator {
             MetadataOperator::Exists => {
-                // Check if key exists in JSONB
-                format!("metadata ? '{}'", filter.key.replace('\'', "''"))
+                // Check if key exists in JSONB, as a literal key or a nested path
+                let literal = format!("metadata ? '{}'", filter.key.replace('\'', "''"));
+                match filter.key.rsplit_once('.') {
+                    Some((parent, last)) => format!(
+                        "({} OR {} ? '{}')",
+                        literal,
+                        Self::build_jsonb_path(parent),
+                        last.replace('\'', "''")
+                    ),
+                    None => literal,
+                }
             }
             MetadataOperator::Eq => {
                 *idx += 1;
                 // Exact match using @> containment
-                format!("metadata @> jsonb_build_object('{}', ${}::jsonb)",
-                    filter.key.replace('\'', "''"), *idx)
+                Self::build_containment(&filter.key, *idx)
             }
             MetadataOperator::Neq => {
                 *idx += 1;
                 // Not equal
-                format!("NOT (metadata @> jsonb_build_object('{}', ${}::jsonb))",
-                    filter.key.replace('\'', "''"), *idx)
+                format!("NOT {}", Self::build_containment(&filter.key, *idx))
             }
             MetadataOperator::Contains => {
                 *idx += 1;
                 // String contains (case-insensitive)
-                format!("({} IS NOT NULL AND {}::text ILIKE ${})",
-                    key_path, key_path, *idx)
+                format!(
+                    "({} IS NOT NULL AND {}::text ILIKE ${})",
+                    key_path, key_path, *idx
+                )
             }
             MetadataOperator::Gt => {
                 *idx += 1;
@@ -144,6 +206,30 @@ impl TimescaleLogRepository {
         }
     }
 
+    /// Build a containment check of `key` against parameter `idx`. Dotted keys
+    /// match either a literal top-level key (as OTLP attributes are stored) or
+    /// the nested path.
+    fn build_containment(key: &str, idx: usize) -> String {
+        let literal = format!(
+            "metadata @> jsonb_build_object('{}', ${}::jsonb)",
+            key.replace('\'', "''"),
+            idx
+        );
+        if !key.contains('.') {
+            return literal;
+        }
+        let nested = key
+            .rsplit('.')
+            .fold(format!("${}::jsonb", idx), |inner, part| {
+                format!(
+                    "jsonb_build_object('{}', {})",
+                    part.replace('\'', "''"),
+                    inner
+                )
+            });
+        format!("({} OR metadata @> {})", literal, nested)
+    }
+
     /// Build JSONB path accessor for nested keys (e.g., "request.path" -> metadata->'request'->'path')
     fn build_jsonb_path(key: &str) -> String {
         let parts: Vec<&str> = key.split('.').collect();
@@ -158,6 +244,23 @@ impl TimescaleLogRepository {
         }
     }
 
+    /// SQL text expression for a grouping field on the logs table aliased `alias`
+    fn group_expr(field: &LogGroupField, alias: &str) -> String {
+        match field {
+            LogGroupField::Source => format!("{}.source", alias),
+            LogGroupField::Level => format!("{}.level", alias),
+            LogGroupField::Metadata(path) => {
+                let (last, parents) = path.split_last().expect("metadata path is non-empty");
+                let mut expr = format!("{}.metadata", alias);
+                for part in parents {
+                    expr.push_str(&format!("->'{}'", part.replace('\'', "''")));
+                }
+                expr.push_str(&format!("->>'{}'", last.replace('\'', "''")));
+                expr
+            }
+        }
+    }
+
     /// Bind metadata filter value to query builder
     fn bind_metadata_filter_value<'q, O>(
         query_builder: sqlx::query::QueryAs<'q, sqlx::Postgres, O, sqlx::postgres::PgArguments>,
@@ -185,7 +288,10 @@ impl TimescaleLogRepository {
                     query_builder
                 }
             }
-            MetadataOperator::Gt | MetadataOperator::Lt | MetadataOperator::Gte | MetadataOperator::Lte => {
+            MetadataOperator::Gt
+            | MetadataOperator::Lt
+            | MetadataOperator::Gte
+            | MetadataOperator::Lte => {
                 // Bind numeric value as string for casting
                 if let Some(ref value) = filter.value {
                     let num_str = match value {
@@ -201,14 +307,51 @@ impl TimescaleLogRepository {
     }
 
     /// Bind metadata filter value to scalar query builder
+    /// Bind the parameters of a clause built by `build_filter_clause`, in order
+    fn bind_filters<'q, O>(
+        mut query_builder: sqlx::query::QueryAs<'q, sqlx::Postgres, O, sqlx::postgres::PgArguments>,
+        filters: &'q LogFilters,
+    ) -> sqlx::query::QueryAs<'q, sqlx::Postgres, O, sqlx::postgres::PgArguments> {
+        if let Some(ref levels) = filters.levels {
+            for level in levels {
+                query_builder = query_builder.bind(level.as_str());
+            }
+        }
+        if let Some(min_level) = filters.min_level {
+            query_builder = query_builder.bind(min_level.severity() as i32);
+        }
+        if let Some(ref start_time) = filters.start_time {
+            query_builder = query_builder.bind(start_time);
+        }
+        if let Some(ref end_time) = filters.end_time {
+            query_builder = query_builder.bind(end_time);
+        }
+        if let Some(ref source) = filters.source {
+            query_builder = query_builder.bind(source);
+        }
+        if let Some(ref search) = filters.search {
+            query_builder = query_builder.bind(format!("%{}%", search));
+        }
+        if let Some(ref trace_id) = filters.trace_id {
+            query_builder = query_builder.bind(trace_id);
+        }
+        for filter in &filters.metadata_filters {
+            query_builder = Self::bind_metadata_filter_value(query_builder, filter);
+        }
+        query_builder
+    }
+
     fn bind_metadata_filter_value_scalar<'q>(
-        query_builder: sqlx::query::QueryScalar<'q, sqlx::Postgres, i64, sqlx::postgres::PgArguments>,
+        query_builder: sqlx::query::QueryScalar<
+            'q,
+            sqlx::Postgres,
+            i64,
+            sqlx::postgres::PgArguments,
+        >,
         filter: &'q MetadataFilter,
     ) -> sqlx::query::QueryScalar<'q, sqlx::Postgres, i64, sqlx::postgres::PgArguments> {
         match filter.operator {
-            MetadataOperator::Exists => {
-                query_builder
-            }
+            MetadataOperator::Exists => query_builder,
             MetadataOperator::Eq | MetadataOperator::Neq => {
                 if let Some(ref value) = filter.value {
                     query_builder.bind(value.to_string())
@@ -224,7 +367,10 @@ impl TimescaleLogRepository {
                     query_builder
                 }
             }
-            MetadataOperator::Gt | MetadataOperator::Lt | MetadataOperator::Gte | MetadataOperator::Lte => {
+            MetadataOperator::Gt
+            | MetadataOperator::Lt
+            | MetadataOperator::Gte
+            | MetadataOperator::Lte => {
                 if let Some(ref value) = filter.value {
                     let num_str = match value {
                         serde_json::Value::Number(n) => n.to_string(),
@@ -254,25 +400,7 @@ impl LogRepository for TimescaleLogRepository {
 
         let mut count = 0u32;
         for log in logs {
-            let result = sqlx::query(
-                r#"
-                INSERT INTO logs (id, project_id, level, message, timestamp, received_at,
-                                  source, metadata, trace_id, span_id)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-                "#,
-            )
-            .bind(log.id().as_str())
-            .bind(log.project_id().as_str())
-            .bind(log.level().as_str())
-            .bind(log.message())
-            .bind(log.timestamp())
-            .bind(log.received_at())
-            .bind(log.source())
-            .bind(log.metadata())
-            .bind(log.trace_id().map(|t| t.as_str()))
-            .bind(log.span_id().map(|s| s.as_str()))
-            .execute(&mut *tx)
-            .await;
+            let result = Self::insert_log(&mut tx, log).await;
 
             match result {
                 Ok(_) => count += 1,
@@ -289,12 +417,130 @@ impl LogRepository for TimescaleLogRepository {
         Ok(count)
     }
 
+    async fn save_batch_dedup(
+        &self,
+        logs: &[LogEntry],
+        window: Duration,
+    ) -> Result<DedupSaveResult, LogDomainError> {
+        let mut result = DedupSaveResult::default();
+        if logs.is_empty() {
+            return Ok(result);
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| LogDomainError::InternalError(e.to_string()))?;
+
+        let now = Utc::now();
+        for log in logs {
+            // A key left over from an expired window is claimed anew
+            let claimed = sqlx::query(
+                r#"
+                INSERT INTO ingest_dedup_keys (project_id, scope, key, expires_at)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (project_id, scope, key) DO UPDATE SET expires_at = EXCLUDED.expires_at
+                WHERE ingest_dedup_keys.expires_at <= $5
+                "#,
+            )
+            .bind(log.project_id().as_str())
+            .bind(EVENT_ID_DEDUP_SCOPE)
+            .bind(log.event_id())
+            .bind(now + window)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| LogDomainError::InternalError(e.to_string()))?
+            .rows_affected()
+                > 0;
+
+            if !claimed {
+                result.duplicates += 1;
+                continue;
+            }
+
+            Self::insert_log(&mut tx, log)
+                .await
+                .map_err(|e| LogDomainError::InternalError(e.to_string()))?;
+            result.saved += 1;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| LogDomainError::InternalError(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    async fn prune_dedup_keys(
+        &self,
+        now: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<u64, LogDomainError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM ingest_dedup_keys
+            WHERE (project_id, scope, key) IN (
+                SELECT project_id, scope, key
+                FROM ingest_dedup_keys
+                WHERE expires_at <= $1
+                LIMIT $2
+            )
+            "#,
+        )
+        .bind(now)
+        .bind(limit as i64)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| LogDomainError::InternalError(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn save_dead_letters(&self, letters: &[DeadLetter]) -> Result<u32, LogDomainError> {
+        if letters.is_empty() {
+            return Ok(0);
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| LogDomainError::InternalError(e.to_string()))?;
+
+        for letter in letters {
+            sqlx::query(
+                r#"
+                INSERT INTO log_dead_letters (id, project_id, format, payload, error, received_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#,
+            )
+            .bind(letter.id())
+            .bind(letter.project_id().as_str())
+            .bind(letter.format())
+            .bind(letter.payload())
+            .bind(letter.error())
+            .bind(letter.received_at())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| LogDomainError::InternalError(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| LogDomainError::InternalError(e.to_string()))?;
+
+        Ok(letters.len() as u32)
+    }
+
     async fn query(
         &self,
         project_id: &ProjectId,
         filters: &LogFilters,
         pagination: &Pagination,
         sort: SortOrder,
+        include_total: bool,
     ) -> Result<LogQueryResult, LogDomainError> {
         let order = match sort {
             SortOrder::Ascending => "ASC",
@@ -303,17 +549,19 @@ impl LogRepository for TimescaleLogRepository {
 
         let (filter_clause, _param_count) = Self::build_filter_clause(filters, 1);
 
-        // Build query with dynamic filters
+        // Build query with dynamic filters; one extra row tells whether another page exists
         let query = format!(
             r#"
             SELECT id, project_id, level, message, timestamp, received_at,
-                   source, metadata, trace_id, span_id
+                   source, metadata, trace_id, span_id, timestamp_nanos, sequence
             FROM logs
             WHERE project_id = $1 {}
-            ORDER BY timestamp {}
+            ORDER BY timestamp {order}, timestamp_nanos {order}, sequence {order}
             LIMIT {} OFFSET {}
             "#,
-            filter_clause, order, pagination.limit, pagination.offset
+            filter_clause,
+            pagination.limit + 1,
+            pagination.offset
         );
 
         // We need to use raw query since we have dynamic parameters
@@ -325,6 +573,9 @@ impl LogRepository for TimescaleLogRepository {
                 query_builder = query_builder.bind(level.as_str());
             }
         }
+        if let Some(min_level) = filters.min_level {
+            query_builder = query_builder.bind(min_level.severity() as i32);
+        }
         if let Some(ref start_time) = filters.start_time {
             query_builder = query_builder.bind(start_time);
         }
@@ -346,15 +597,20 @@ impl LogRepository for TimescaleLogRepository {
             query_builder = Self::bind_metadata_filter_value(query_builder, filter);
         }
 
-        let rows: Vec<LogRow> = query_builder
+        let mut rows: Vec<LogRow> = query_builder
             .fetch_all(self.pool.as_ref())
             .await
             .map_err(|e| LogDomainError::InternalError(e.to_string()))?;
 
-        // Get total count
-        let total = self.count(project_id, filters).await?;
-        let fetched_count = rows.len() as i64;
-        let has_more = pagination.offset + fetched_count < total;
+        let has_more = rows.len() as i64 > pagination.limit;
+        rows.truncate(pagination.limit as usize);
+
+        // Counting scans every matching row, so only do it on request
+        let total = if include_total {
+            Some(self.count(project_id, filters).await?)
+        } else {
+            None
+        };
 
         let logs = rows
             .into_iter()
@@ -387,6 +643,9 @@ impl LogRepository for TimescaleLogRepository {
                 query_builder = query_builder.bind(level.as_str());
             }
         }
+        if let Some(min_level) = filters.min_level {
+            query_builder = query_builder.bind(min_level.severity() as i32);
+        }
         if let Some(ref start_time) = filters.start_time {
             query_builder = query_builder.bind(start_time);
         }
@@ -416,6 +675,38 @@ impl LogRepository for TimescaleLogRepository {
         Ok(count)
     }
 
+    async fn count_pair(
+        &self,
+        project_id: &ProjectId,
+        first: &LogFilters,
+        second: &LogFilters,
+    ) -> Result<(i64, i64), LogDomainError> {
+        let (first_clause, first_params) = Self::build_filter_clause(first, 1);
+        let (second_clause, _) = Self::build_filter_clause(second, 1 + first_params);
+
+        // Scan rows matching either side once and count each with a FILTER
+        let query = format!(
+            r#"
+            SELECT COUNT(*) FILTER (WHERE TRUE {first}) AS first_count,
+                   COUNT(*) FILTER (WHERE TRUE {second}) AS second_count
+            FROM logs
+            WHERE project_id = $1 AND ((TRUE {first}) OR (TRUE {second}))
+            "#,
+            first = first_clause,
+            second = second_clause,
+        );
+
+        let mut query_builder = sqlx::query_as::<_, (i64, i64)>(&query).bind(project_id.as_str());
+        for filters in [first, second] {
+            query_builder = Self::bind_filters(query_builder, filters);
+        }
+
+        query_builder
+            .fetch_one(self.pool.as_ref())
+            .await
+            .map_err(|e| LogDomainError::InternalError(e.to_string()))
+    }
+
     async fn get_stats(&self, project_id: &ProjectId) -> Result<LogStats, LogDomainError> {
         // Get total count and time range
         let stats_row: LogStatsRow = sqlx::query_as(
@@ -469,15 +760,23 @@ impl LogRepository for TimescaleLogRepository {
         &self,
         project_id: &ProjectId,
         before: DateTime<Utc>,
+        limit: u32,
     ) -> Result<u64, LogDomainError> {
         let result = sqlx::query(
             r#"
             DELETE FROM logs
-            WHERE project_id = $1 AND timestamp < $2
+            WHERE project_id = $1 AND (timestamp, id) IN (
+                SELECT timestamp, id
+                FROM logs
+                WHERE project_id = $1 AND timestamp < $2
+                ORDER BY timestamp
+                LIMIT $3
+            )
             "#,
         )
         .bind(project_id.as_str())
         .bind(before)
+        .bind(limit as i64)
         .execute(self.pool.as_ref())
         .await
         .map_err(|e| LogDomainError::InternalError(e.to_string()))?;
@@ -485,19 +784,73 @@ impl LogRepository for TimescaleLogRepository {
         Ok(result.rows_affected())
     }
 
+    async fn delete_expired(
+        &self,
+        project_id: &ProjectId,
+        cutoffs: &LogCutoffs,
+        limit: u32,
+    ) -> Result<u64, LogDomainError> {
+        // Each matching rule contributes its cutoff; the earliest one wins
+        let tagged: Vec<String> = (0..cutoffs.tagged.len())
+            .map(|i| {
+                format!(
+                    "CASE WHEN metadata @> ${}::jsonb THEN ${}::timestamptz END",
+                    5 + 2 * i,
+                    6 + 2 * i
+                )
+            })
+            .collect();
+        let cutoff = if tagged.is_empty() {
+            "$3".to_string()
+        } else {
+            format!("COALESCE(LEAST({}), $3)", tagged.join(", "))
+        };
+        let query = format!(
+            r#"
+            DELETE FROM logs
+            WHERE project_id = $1 AND (timestamp, id) IN (
+                SELECT timestamp, id
+                FROM logs
+                WHERE project_id = $1 AND timestamp < $2 AND timestamp < {}
+                ORDER BY timestamp
+                LIMIT $4
+            )
+            "#,
+            cutoff
+        );
+
+        let mut query = sqlx::query(&query)
+            .bind(project_id.as_str())
+            .bind(cutoffs.latest())
+            .bind(cutoffs.default)
+            .bind(limit as i64);
+        for rule in &cutoffs.tagged {
+            query = query
+                .bind(serde_json::to_value(&rule.tags).unwrap_or_default())
+                .bind(rule.cutoff);
+        }
+        let result = query
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| LogDomainError::InternalError(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+
     // ==================== Metrics Methods ====================
 
     async fn get_volume_over_time(
         &self,
         project_id: &ProjectId,
         bucket_interval: &str,
+        timezone: Tz,
         start_time: Option<DateTime<Utc>>,
         end_time: Option<DateTime<Utc>>,
     ) -> Result<Vec<(DateTime<Utc>, i64)>, LogDomainError> {
         let rows: Vec<TimeBucketRow> = sqlx::query_as(
             r#"
             SELECT
-                time_bucket($1::interval, timestamp) AS bucket,
+                time_bucket($1::interval, timestamp, $5) AS bucket,
                 COUNT(*) AS count
             FROM logs
             WHERE project_id = $2
@@ -511,6 +864,7 @@ impl LogRepository for TimescaleLogRepository {
         .bind(project_id.as_str())
         .bind(start_time)
         .bind(end_time)
+        .bind(timezone.name())
         .fetch_all(self.pool.as_ref())
         .await
         .map_err(|e| LogDomainError::InternalError(e.to_string()))?;
@@ -522,6 +876,7 @@ impl LogRepository for TimescaleLogRepository {
         &self,
         project_id: &ProjectId,
         bucket_interval: &str,
+        timezone: Tz,
         start_time: Option<DateTime<Utc>>,
         end_time: Option<DateTime<Utc>>,
     ) -> Result<Vec<(String, DateTime<Utc>, i64)>, LogDomainError> {
@@ -529,7 +884,7 @@ impl LogRepository for TimescaleLogRepository {
             r#"
             SELECT
                 level,
-                time_bucket($1::interval, timestamp) AS bucket,
+                time_bucket($1::interval, timestamp, $5) AS bucket,
                 COUNT(*) AS count
             FROM logs
             WHERE project_id = $2
@@ -543,6 +898,7 @@ impl LogRepository for TimescaleLogRepository {
         .bind(project_id.as_str())
         .bind(start_time)
         .bind(end_time)
+        .bind(timezone.name())
         .fetch_all(self.pool.as_ref())
         .await
         .map_err(|e| LogDomainError::InternalError(e.to_string()))?;
@@ -588,4 +944,75 @@ impl LogRepository for TimescaleLogRepository {
             .map(|r| (r.source, r.total, r.error_count))
             .collect())
     }
+
+    async fn group_by_field(
+        &self,
+        project_id: &ProjectId,
+        field: &LogGroupField,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+        limit: i32,
+        samples: i32,
+    ) -> Result<Vec<LogGroup>, LogDomainError> {
+        // Source and level group on their per-project indexes; metadata paths are
+        // extracted from the JSONB column
+        let query = format!(
+            r#"
+            WITH groups AS (
+                SELECT {group} AS value, COUNT(*) AS count
+                FROM logs g
+                WHERE g.project_id = $1
+                  AND ($2::timestamptz IS NULL OR g.timestamp >= $2)
+                  AND ($3::timestamptz IS NULL OR g.timestamp <= $3)
+                GROUP BY 1
+                ORDER BY count DESC
+                LIMIT $4
+            )
+            SELECT groups.value AS group_value, groups.count AS group_count,
+                   s.id, s.project_id, s.level, s.message, s.timestamp, s.received_at,
+                   s.source, s.metadata, s.trace_id, s.span_id, s.timestamp_nanos, s.sequence
+            FROM groups
+            CROSS JOIN LATERAL (
+                SELECT *
+                FROM logs l
+                WHERE l.project_id = $1
+                  AND ($2::timestamptz IS NULL OR l.timestamp >= $2)
+                  AND ($3::timestamptz IS NULL OR l.timestamp <= $3)
+                  AND {sample} IS NOT DISTINCT FROM groups.value
+                ORDER BY l.timestamp DESC, l.timestamp_nanos DESC, l.sequence DESC
+                LIMIT $5
+            ) s
+            ORDER BY groups.count DESC, groups.value, s.timestamp DESC, s.timestamp_nanos DESC,
+                     s.sequence DESC
+            "#,
+            group = Self::group_expr(field, "g"),
+            sample = Self::group_expr(field, "l"),
+        );
+
+        let rows: Vec<LogGroupRow> = sqlx::query_as(&query)
+            .bind(project_id.as_str())
+            .bind(start_time)
+            .bind(end_time)
+            .bind(limit)
+            .bind(samples.max(1))
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| LogDomainError::InternalError(e.to_string()))?;
+
+        // Rows arrive grouped, so each group's samples are contiguous
+        let mut groups: Vec<LogGroup> = Vec::new();
+        for row in rows {
+            let log = Self::row_to_log_entry(row.log)?;
+            match groups.last_mut() {
+                Some(group) if group.value == row.group_value => group.samples.push(log),
+                _ => groups.push(LogGroup {
+                    value: row.group_value,
+                    count: row.group_count,
+                    samples: vec![log],
+                }),
+            }
+        }
+
+        Ok(groups)
+    }
 }
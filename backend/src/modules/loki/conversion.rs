@@ -0,0 +1,312 @@
+//! Convert Loki push streams to internal log format
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+
+use crate::modules::logging::application::dto::{DeadLetterInput, IngestRawLogsCommand, LogInput};
+use crate::modules::logging::domain::LogLevel;
+use crate::modules::loki::types::{PushRequest, PushStream, StreamLabels};
+
+/// Format name recorded on dead letters
+pub const LOKI_FORMAT: &str = "loki";
+
+/// Loki lines carry no severity unless a label says so
+const DEFAULT_LEVEL: &str = "info";
+
+/// Labels read as the level, first match wins
+const LEVEL_LABELS: [&str; 3] = ["level", "detected_level", "severity"];
+
+/// Labels read as the source, first match wins
+const SOURCE_LABELS: [&str; 3] = ["service_name", "job", "app"];
+
+/// Build an ingest command from a push request. Every label and structured
+/// metadata field lands in metadata; entries or streams that can't be
+/// converted become dead letters.
+pub fn parse_push_request(project_id: &str, request: PushRequest) -> IngestRawLogsCommand {
+    let mut logs = Vec::new();
+    let mut dead_letters = Vec::new();
+
+    for (idx, stream) in request.streams.into_iter().enumerate() {
+        let labels = match stream_labels(&stream) {
+            Ok(labels) => labels,
+            Err(e) => {
+                dead_letters.push(DeadLetterInput {
+                    payload: Value::Array(stream.values.into_iter().map(Value::Array).collect())
+                        .to_string(),
+                    error: format!("Stream {}: {}", idx, e),
+                });
+                continue;
+            }
+        };
+
+        for (entry_idx, entry) in stream.values.iter().enumerate() {
+            match convert_entry(&labels, entry) {
+                Ok(log) => logs.push(log),
+                Err(e) => dead_letters.push(DeadLetterInput {
+                    payload: Value::Array(entry.clone()).to_string(),
+                    error: format!("Stream {} entry {}: {}", idx, entry_idx, e),
+                }),
+            }
+        }
+    }
+
+    IngestRawLogsCommand {
+        project_id: project_id.to_string(),
+        format: LOKI_FORMAT.to_string(),
+        logs,
+        dead_letters,
+    }
+}
+
+fn stream_labels(stream: &PushStream) -> Result<BTreeMap<String, String>, String> {
+    match &stream.stream {
+        StreamLabels::Map(labels) => Ok(labels.clone()),
+        StreamLabels::Selector(selector) => parse_selector(selector),
+    }
+}
+
+/// Parse a label selector such as `{job="api", env="prod"}`
+pub fn parse_selector(selector: &str) -> Result<BTreeMap<String, String>, String> {
+    let inner = selector
+        .trim()
+        .strip_prefix('{')
+        .and_then(|s| s.strip_suffix('}'))
+        .ok_or_else(|| format!("Invalid label selector: {}", selector))?;
+
+    let mut labels = BTreeMap::new();
+    let mut chars = inner.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace() || *c == ',').is_some() {}
+        if chars.peek().is_none() {
+            return Ok(labels);
+        }
+
+        let name: String = std::iter::from_fn(|| chars.next_if(|c| *c != '=')).collect();
+        let name = name.trim();
+        if name.is_empty() || chars.next() != Some('=') {
+            return Err(format!("Invalid label selector: {}", selector));
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.next() != Some('"') {
+            return Err(format!("Unquoted value for label {}", name));
+        }
+
+        let mut value = String::new();
+        loop {
+            match chars.next() {
+                Some('"') => break,
+                Some('\\') => match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some(c) => value.push(c),
+                    None => return Err(format!("Unterminated value for label {}", name)),
+                },
+                Some(c) => value.push(c),
+                None => return Err(format!("Unterminated value for label {}", name)),
+            }
+        }
+        labels.insert(name.to_string(), value);
+    }
+}
+
+/// Unix epoch nanoseconds, as a string (the Loki wire format) or a number
+pub fn parse_timestamp(value: &Value) -> Result<DateTime<Utc>, String> {
+    let nanos = match value {
+        Value::String(s) => s.trim().parse::<i64>().ok(),
+        Value::Number(n) => n.as_i64(),
+        _ => None,
+    }
+    .ok_or_else(|| format!("Invalid timestamp: {}", value))?;
+    Ok(DateTime::from_timestamp_nanos(nanos))
+}
+
+/// Convert one `[timestamp, line, structured metadata?]` entry
+pub fn convert_entry(
+    labels: &BTreeMap<String, String>,
+    entry: &[Value],
+) -> Result<LogInput, String> {
+    let (timestamp, line, structured) = match entry {
+        [timestamp, line] => (timestamp, line, None),
+        [timestamp, line, structured] => (timestamp, line, Some(structured)),
+        _ => {
+            return Err(format!(
+                "Expected [timestamp, line], got {} elements",
+                entry.len()
+            ));
+        }
+    };
+    let timestamp = parse_timestamp(timestamp)?;
+    let line = line
+        .as_str()
+        .ok_or_else(|| "Log line must be a string".to_string())?;
+
+    let mut metadata: Map<String, Value> = labels
+        .iter()
+        .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+        .collect();
+    match structured {
+        None | Some(Value::Null) => {}
+        Some(Value::Object(fields)) => metadata.extend(fields.clone()),
+        Some(_) => return Err("Structured metadata must be an object".to_string()),
+    }
+
+    let level = LEVEL_LABELS
+        .iter()
+        .filter_map(|key| labels.get(*key))
+        .find_map(|level| LogLevel::from_str(level).ok())
+        .map_or(DEFAULT_LEVEL, |level| level.as_str());
+    let source = SOURCE_LABELS
+        .iter()
+        .find_map(|key| labels.get(*key))
+        .cloned();
+    let text_field = |key: &str| {
+        metadata
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+
+    Ok(LogInput {
+        level: level.to_string(),
+        message: line.trim_end_matches('\n').to_string(),
+        timestamp: Some(timestamp),
+        source,
+        trace_id: text_field("trace_id"),
+        span_id: text_field("span_id"),
+        metadata: (!metadata.is_empty()).then_some(Value::Object(metadata)),
+        event_id: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::modules::logging::application::LogService;
+    use crate::shared::PaginationConfig;
+    use crate::shared::testing::{
+        InMemoryLogRepository, InMemoryMemberRepository, InMemoryProjectRepository,
+        SequentialIdGenerator,
+    };
+
+    const PUSH: &str = r#"{
+        "streams": [
+            {
+                "stream": {"service_name": "checkout", "env": "prod", "level": "warning"},
+                "values": [
+                    ["1705312800000000000", "payment retry scheduled\n"],
+                    ["1705312800123456789", "card declined", {"trace_id": "4bf92f3577b34da6a3ce929d0e0e4736"}]
+                ]
+            },
+            {
+                "labels": "{job=\"nginx\", host=\"web-1\"}",
+                "values": [["1705312801000000000", "GET /health 200"]]
+            }
+        ]
+    }"#;
+
+    fn service(
+        log_repo: Arc<InMemoryLogRepository>,
+    ) -> LogService<
+        InMemoryLogRepository,
+        InMemoryProjectRepository,
+        InMemoryMemberRepository,
+        SequentialIdGenerator,
+    > {
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        LogService::new(
+            log_repo,
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_ingest_push_payload() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let cmd = parse_push_request("project-1", serde_json::from_str(PUSH).unwrap());
+
+        let response = service(log_repo.clone()).ingest_raw(cmd).await.unwrap();
+        assert_eq!(response.accepted, 3);
+        assert_eq!(response.rejected, 0);
+
+        let saved = log_repo.saved();
+        let declined = saved
+            .iter()
+            .find(|l| l.message() == "card declined")
+            .unwrap();
+        assert_eq!(declined.level(), LogLevel::Warn);
+        assert_eq!(declined.source(), Some("checkout"));
+        // Stored at microsecond precision
+        assert_eq!(
+            declined.timestamp().timestamp_micros(),
+            1_705_312_800_123_456
+        );
+        assert_eq!(
+            declined.trace_id().map(|t| t.as_str()),
+            Some("4bf92f3577b34da6a3ce929d0e0e4736")
+        );
+        let metadata = declined.metadata().unwrap();
+        assert_eq!(metadata["env"], "prod");
+        assert_eq!(metadata["service_name"], "checkout");
+
+        assert!(
+            saved
+                .iter()
+                .any(|l| l.message() == "payment retry scheduled")
+        );
+
+        let health = saved
+            .iter()
+            .find(|l| l.message() == "GET /health 200")
+            .unwrap();
+        assert_eq!(health.level(), LogLevel::Info);
+        assert_eq!(health.source(), Some("nginx"));
+        assert_eq!(health.metadata().unwrap()["host"], "web-1");
+    }
+
+    #[tokio::test]
+    async fn test_malformed_entry_goes_to_dead_letter() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let request = serde_json::from_str(
+            r#"{"streams": [{"stream": {"job": "api"}, "values": [
+                ["yesterday", "bad timestamp"],
+                ["1705312800000000000", "fine"]
+            ]}]}"#,
+        )
+        .unwrap();
+        let cmd = parse_push_request("project-1", request);
+
+        let response = service(log_repo.clone()).ingest_raw(cmd).await.unwrap();
+
+        assert_eq!(response.accepted, 1);
+        assert_eq!(response.rejected, 1);
+        let dead_letters = log_repo.dead_letters();
+        assert_eq!(dead_letters[0].format(), LOKI_FORMAT);
+        assert!(dead_letters[0].error().starts_with("Stream 0 entry 0"));
+    }
+
+    #[test]
+    fn test_parse_nanosecond_timestamp() {
+        let timestamp = parse_timestamp(&serde_json::json!("1705312800123456789")).unwrap();
+        assert_eq!(
+            timestamp.timestamp_nanos_opt(),
+            Some(1_705_312_800_123_456_789)
+        );
+        assert!(parse_timestamp(&serde_json::json!("yesterday")).is_err());
+    }
+
+    #[test]
+    fn test_parse_selector_with_escapes() {
+        let labels = parse_selector(r#"{job="api", msg="say \"hi\"",}"#).unwrap();
+        assert_eq!(labels["job"], "api");
+        assert_eq!(labels["msg"], "say \"hi\"");
+        assert!(parse_selector("job=api").is_err());
+    }
+}
@@ -0,0 +1,98 @@
+//! Loki push API HTTP handlers
+
+use axum::{
+    Extension, Json,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode, header},
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::gelf::payload::decompress_payload;
+use crate::modules::logging::application::LogService;
+use crate::modules::logging::domain::{LogDomainError, LogRepository};
+use crate::modules::logging::infrastructure::ApiKeyContext;
+use crate::modules::loki::conversion::parse_push_request;
+use crate::modules::loki::types::PushRequest;
+use crate::modules::organizations::domain::OrganizationMemberRepository;
+use crate::modules::projects::domain::ProjectRepository;
+
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub code: String,
+}
+
+fn error(status: StatusCode, error: String, code: &str) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        status,
+        Json(ErrorResponse {
+            error,
+            code: code.to_string(),
+        }),
+    )
+}
+
+/// POST /loki/api/v1/push - Loki JSON push format, optionally gzip compressed.
+/// Answers 204 like Loki does; lines that can't be converted become dead letters.
+pub async fn push<LR, PR, OMR, ID>(
+    State(service): State<Arc<LogService<LR, PR, OMR, ID>>>,
+    Extension(ctx): Extension<ApiKeyContext>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
+where
+    LR: LogRepository,
+    PR: ProjectRepository,
+    OMR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    // Vector and Promtail default to snappy-compressed protobuf
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if content_type.starts_with("application/x-protobuf") {
+        return Err(error(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Only the JSON push format is supported; configure the client to send JSON".to_string(),
+            "UNSUPPORTED_ENCODING",
+        ));
+    }
+
+    let payload = decompress_payload(&body)
+        .map_err(|e| error(StatusCode::BAD_REQUEST, e, "INVALID_PAYLOAD"))?;
+    let request: PushRequest = serde_json::from_slice(&payload).map_err(|e| {
+        error(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid JSON: {}", e),
+            "INVALID_JSON",
+        )
+    })?;
+
+    let cmd = parse_push_request(ctx.project_id.as_str(), request);
+    service.ingest_raw(cmd).await.map_err(|e| match e {
+        LogDomainError::ProjectNotFound | LogDomainError::ProjectDeleted => error(
+            StatusCode::NOT_FOUND,
+            "Project not found".to_string(),
+            "INGESTION_ERROR",
+        ),
+        LogDomainError::InternalError(ref msg) => {
+            tracing::error!(error = %msg, "Internal error during Loki push ingestion");
+            error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Internal error".to_string(),
+                "INGESTION_ERROR",
+            )
+        }
+        other => error(
+            StatusCode::BAD_REQUEST,
+            other.to_string(),
+            "INGESTION_ERROR",
+        ),
+    })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
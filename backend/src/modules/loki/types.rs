@@ -0,0 +1,33 @@
+//! Loki push API payload types (JSON encoding)
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Body of POST /loki/api/v1/push
+#[derive(Debug, Clone, Deserialize)]
+pub struct PushRequest {
+    #[serde(default)]
+    pub streams: Vec<PushStream>,
+}
+
+/// One label set and the lines pushed for it
+#[derive(Debug, Clone, Deserialize)]
+pub struct PushStream {
+    #[serde(alias = "labels")]
+    pub stream: StreamLabels,
+    /// Each entry is `[timestamp, line]` or `[timestamp, line, structured metadata]`.
+    /// Kept loose so one malformed entry doesn't reject the whole push.
+    #[serde(default)]
+    pub values: Vec<Vec<Value>>,
+}
+
+/// Labels as a JSON object, or as a selector string (`{job="api", env="prod"}`)
+/// as sent by older clients
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum StreamLabels {
+    Map(BTreeMap<String, String>),
+    Selector(String),
+}
@@ -47,6 +47,12 @@ pub struct MetricQueryFilters {
     pub tags: Option<HashMap<String, String>>,
     pub trace_id: Option<String>,
     pub rollup: Option<String>,
+    /// Output resolution (e.g. "15s"); requires start_time and end_time
+    pub step: Option<String>,
+    /// IANA timezone steps align to, overriding the project's
+    pub timezone: Option<String>,
+    /// Percentile (0-100) of raw values per step, e.g. 95; requires step
+    pub percentile: Option<f64>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
@@ -57,6 +63,37 @@ pub struct QueryMetricsCommand {
     pub project_id: String,
     pub filters: MetricQueryFilters,
     pub requesting_user_id: String,
+    /// Skip the query result cache
+    pub bypass_cache: bool,
+}
+
+/// A derived series computed from two metrics, e.g. `errors / total`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MetricExpressionQuery {
+    pub left: String,
+    /// ratio, sum or difference
+    pub op: String,
+    pub right: String,
+    /// Aggregate of each bucket to combine: avg, min, max, sum (default) or count
+    pub value: Option<String>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+    pub tags: Option<HashMap<String, String>>,
+    pub rollup: Option<String>,
+    /// Output resolution (e.g. "15s"); requires start_time and end_time
+    pub step: Option<String>,
+    /// IANA timezone steps align to, overriding the project's
+    pub timezone: Option<String>,
+}
+
+/// Command to query a derived series
+#[derive(Debug, Clone)]
+pub struct QueryMetricExpressionCommand {
+    pub project_id: String,
+    pub query: MetricExpressionQuery,
+    pub requesting_user_id: String,
+    /// Skip the query result cache
+    pub bypass_cache: bool,
 }
 
 /// Command to list metric names
@@ -72,6 +109,17 @@ pub struct ListMetricNamesCommand {
 #[derive(Debug, Clone, Serialize)]
 pub struct IngestMetricsResponse {
     pub ingested: u32,
+    /// Points rejected for arriving outside the out-of-order tolerance window,
+    /// or without a timestamp when the project requires one
+    pub rejected: u32,
+    /// Points stamped with the server's receive time because they had no timestamp
+    pub server_timestamps: u32,
+    /// Points rejected because their type differs from the one registered for their name
+    pub type_conflicts: u32,
+    /// Points stored with only the first labels, in key order, up to the project's limit
+    pub labels_trimmed: u32,
+    /// Points rejected for having more labels than the project allows
+    pub label_limit_rejected: u32,
 }
 
 /// Single aggregated metric data point
@@ -85,6 +133,9 @@ pub struct MetricDataPoint {
     pub max_value: f64,
     pub sum_value: f64,
     pub sample_count: i64,
+    /// Requested percentile of the step's values; null for steps without samples
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percentile_value: Option<Option<f64>>,
 }
 
 /// Response for metric queries
@@ -94,6 +145,20 @@ pub struct MetricQueryResponse {
     pub total: i64,
 }
 
+/// One bucket of a derived series; `value` is null where it is undefined
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricExpressionPoint {
+    pub timestamp: DateTime<Utc>,
+    pub value: Option<f64>,
+}
+
+/// Response for derived series queries
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricExpressionResponse {
+    pub expression: String,
+    pub data: Vec<MetricExpressionPoint>,
+}
+
 /// Response for metric names list
 #[derive(Debug, Clone, Serialize)]
 pub struct MetricNamesResponse {
@@ -1,17 +1,23 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
-use chrono::Utc;
+use chrono::{Duration, Utc};
 
 use crate::modules::auth::application::ports::IdGenerator;
 use crate::modules::auth::domain::UserId;
 use crate::modules::metrics::application::dto::*;
+use crate::modules::metrics::domain::metric::step::MAX_STEP_POINTS;
 use crate::modules::metrics::domain::{
-    HistogramData, MetricFilters, MetricPoint, MetricType, MetricsDomainError, MetricsRepository,
-    RollupInterval,
+    AggregatedMetric, BinaryOperator, HistogramData, LabelNormalizer, MetricFilters, MetricPoint,
+    MetricType, MetricsDomainError, MetricsRepository, RollupInterval, SeriesExpression,
+    SeriesValue, StepRange,
 };
 use crate::modules::organizations::domain::{OrgId, OrganizationMemberRepository};
-use crate::modules::projects::domain::{ProjectId, ProjectRepository};
+use crate::modules::projects::domain::{
+    LabelLimitPolicy, LateMetricsPolicy, MetricTypePolicy, MissingTimestampPolicy, Project,
+    ProjectId, ProjectRepository,
+};
+use crate::shared::{QueryCache, TraceKeepSet, Tz, parse_timezone};
 
 pub struct MetricsService<MR, PR, OMR, ID>
 where
@@ -24,6 +30,11 @@ where
     project_repo: Arc<PR>,
     member_repo: Arc<OMR>,
     id_generator: Arc<ID>,
+    /// Points older than `now - out_of_order_tolerance` are considered late
+    out_of_order_tolerance: Duration,
+    query_cache: Option<Arc<QueryCache>>,
+    /// Where trace ids of ingested exemplars are recorded for the trace sampler
+    trace_keep_set: Option<Arc<TraceKeepSet>>,
 }
 
 impl<MR, PR, OMR, ID> MetricsService<MR, PR, OMR, ID>
@@ -38,20 +49,50 @@ where
         project_repo: Arc<PR>,
         member_repo: Arc<OMR>,
         id_generator: Arc<ID>,
+        out_of_order_tolerance: Duration,
     ) -> Self {
         Self {
             metrics_repo,
             project_repo,
             member_repo,
             id_generator,
+            out_of_order_tolerance,
+            query_cache: None,
+            trace_keep_set: None,
         }
     }
 
+    /// Cache results of queries over ranges that have stopped changing
+    pub fn with_query_cache(mut self, cache: Arc<QueryCache>) -> Self {
+        self.query_cache = Some(cache);
+        self
+    }
+
+    /// Record the trace ids of ingested exemplars so those traces escape sampling
+    pub fn with_trace_keep_set(mut self, keep_set: Arc<TraceKeepSet>) -> Self {
+        self.trace_keep_set = Some(keep_set);
+        self
+    }
+
+    /// The cache and key for a query, unless the query isn't cached
+    fn query_cache_key(
+        &self,
+        bypass: bool,
+        kind: &str,
+        project_id: &ProjectId,
+        end: Option<chrono::DateTime<Utc>>,
+        params: &impl std::fmt::Debug,
+    ) -> Option<(&QueryCache, String)> {
+        let cache = self.query_cache.as_deref().filter(|_| !bypass)?;
+        let key = cache.key(kind, project_id.as_str(), end, params)?;
+        Some((cache, key))
+    }
+
     async fn verify_project_access(
         &self,
         project_id: &ProjectId,
         user_id: &str,
-    ) -> Result<(), MetricsDomainError> {
+    ) -> Result<Project, MetricsDomainError> {
         let project = self
             .project_repo
             .find_by_id(project_id)
@@ -76,7 +117,20 @@ where
             return Err(MetricsDomainError::NotAuthorized);
         }
 
-        Ok(())
+        Ok(project)
+    }
+
+    /// Timezone steps align to: the request's if given, else the project's
+    fn step_timezone(project: &Project, requested: Option<&str>) -> Result<Tz, MetricsDomainError> {
+        match requested {
+            Some(name) => parse_timezone(name).ok_or_else(|| {
+                MetricsDomainError::InvalidQuery(format!(
+                    "unknown timezone '{}': use an IANA name like Europe/Paris",
+                    name
+                ))
+            }),
+            None => Ok(project.settings().timezone()),
+        }
     }
 
     /// Ingest metrics (called via API key auth, no user verification needed)
@@ -86,11 +140,86 @@ where
     ) -> Result<IngestMetricsResponse, MetricsDomainError> {
         let project_id = ProjectId::new(cmd.project_id);
 
+        let project = self
+            .project_repo
+            .find_by_id(&project_id)
+            .await
+            .map_err(|e| MetricsDomainError::InternalError(e.to_string()))?
+            .ok_or(MetricsDomainError::ProjectNotFound)?;
+        let late_policy = project.settings().late_metrics_policy;
+        let missing_timestamp_policy = project.settings().missing_timestamp_policy;
+        let metric_type_policy = project.settings().metric_type_policy;
+        let label_settings = &project.settings().label_normalization;
+        let labels = (!label_settings.is_empty()).then(|| LabelNormalizer::new(label_settings));
+        let label_limit = project.settings().metric_label_limit;
+        let received_at = Utc::now();
+        let late_cutoff = received_at - self.out_of_order_tolerance;
+
         let mut metric_points = Vec::with_capacity(cmd.metrics.len());
+        let mut late_points = Vec::new();
+        let mut rejected = 0u32;
+        let mut server_timestamps = 0u32;
+        let mut type_conflicts = 0u32;
+        let mut labels_trimmed = 0u32;
+        let mut label_limit_rejected = 0u32;
+
+        let mut names: Vec<String> = cmd.metrics.iter().map(|m| m.name.clone()).collect();
+        names.sort();
+        names.dedup();
+        let mut registered_types = self
+            .metrics_repo
+            .find_metric_types(&project_id, &names)
+            .await?;
+        let mut new_types = Vec::new();
 
         for input in cmd.metrics {
             let metric_type = MetricType::from_str(&input.metric_type)?;
-            let timestamp = input.timestamp.unwrap_or_else(Utc::now);
+            match registered_types.get(&input.name) {
+                Some(registered) if *registered != metric_type => {
+                    if metric_type_policy == MetricTypePolicy::Enforce {
+                        type_conflicts += 1;
+                        continue;
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    // The first point for a name fixes its type, including later points in this batch
+                    registered_types.insert(input.name.clone(), metric_type);
+                    new_types.push((input.name.clone(), metric_type));
+                }
+            }
+            let timestamp = match input.timestamp {
+                Some(timestamp) => timestamp,
+                None if missing_timestamp_policy == MissingTimestampPolicy::Reject => {
+                    rejected += 1;
+                    continue;
+                }
+                None => {
+                    server_timestamps += 1;
+                    received_at
+                }
+            };
+            let (mut tags, raw_tags) = match &labels {
+                Some(labels) => {
+                    let tags = labels.normalize(&input.tags);
+                    let raw =
+                        (label_settings.preserve_raw && tags != input.tags).then_some(input.tags);
+                    (tags, raw)
+                }
+                None => (input.tags, None),
+            };
+            if tags.len() > label_limit.max_labels {
+                match label_limit.policy {
+                    LabelLimitPolicy::Reject => {
+                        label_limit_rejected += 1;
+                        continue;
+                    }
+                    LabelLimitPolicy::Trim => {
+                        trim_labels(&mut tags, label_limit.max_labels);
+                        labels_trimmed += 1;
+                    }
+                }
+            }
             let id = self.id_generator.generate();
 
             let metric = if metric_type == MetricType::Histogram {
@@ -119,7 +248,7 @@ where
                     timestamp,
                     input.unit,
                     input.description,
-                    input.tags,
+                    tags,
                     histogram_data,
                     input.trace_id,
                     input.span_id,
@@ -134,18 +263,59 @@ where
                     timestamp,
                     input.unit,
                     input.description,
-                    input.tags,
+                    tags,
                     input.trace_id,
                     input.span_id,
                 )
             };
+            let metric = match raw_tags {
+                Some(raw_tags) => metric.with_raw_tags(raw_tags),
+                None => metric,
+            };
 
-            metric_points.push(metric);
+            if metric.timestamp() < late_cutoff {
+                match late_policy {
+                    LateMetricsPolicy::Strict => rejected += 1,
+                    LateMetricsPolicy::Accept => late_points.push(metric),
+                }
+            } else {
+                metric_points.push(metric);
+            }
         }
 
-        let ingested = self.metrics_repo.save_batch(&metric_points).await?;
+        if let Some(keep_set) = &self.trace_keep_set {
+            for trace_id in metric_points
+                .iter()
+                .chain(&late_points)
+                .filter_map(|point| point.trace_id())
+            {
+                keep_set.insert(project_id.as_str(), trace_id);
+            }
+        }
 
-        Ok(IngestMetricsResponse { ingested })
+        self.metrics_repo
+            .register_metric_types(&project_id, &new_types)
+            .await?;
+        let mut ingested = self.metrics_repo.save_batch(&metric_points).await?;
+
+        // Late points go in their own batch so the in-order batch only touches hot chunks
+        if !late_points.is_empty() {
+            tracing::warn!(
+                project_id = %project_id.as_str(),
+                count = late_points.len(),
+                "Ingesting metric points older than the out-of-order tolerance window"
+            );
+            ingested += self.metrics_repo.save_batch(&late_points).await?;
+        }
+
+        Ok(IngestMetricsResponse {
+            ingested,
+            rejected,
+            server_timestamps,
+            type_conflicts,
+            labels_trimmed,
+            label_limit_rejected,
+        })
     }
 
     /// Query metrics (requires user auth)
@@ -154,18 +324,62 @@ where
         cmd: QueryMetricsCommand,
     ) -> Result<MetricQueryResponse, MetricsDomainError> {
         let project_id = ProjectId::new(cmd.project_id);
-        self.verify_project_access(&project_id, &cmd.requesting_user_id)
+        let project = self
+            .verify_project_access(&project_id, &cmd.requesting_user_id)
             .await?;
+        let timezone = Self::step_timezone(&project, cmd.filters.timezone.as_deref())?;
+
+        let filters = &cmd.filters;
+        let cached = self.query_cache_key(
+            cmd.bypass_cache,
+            "metrics.query",
+            &project_id,
+            filters.end_time,
+            &(
+                sorted(filters.names.as_deref()),
+                sorted(filters.types.as_deref()),
+                filters
+                    .tags
+                    .as_ref()
+                    .map(|t| t.iter().collect::<BTreeMap<_, _>>()),
+                filters.start_time,
+                filters.end_time,
+                &filters.trace_id,
+                &filters.rollup,
+                &filters.step,
+                timezone.name(),
+                filters.percentile.map(f64::to_bits),
+                filters.limit,
+                filters.offset,
+            ),
+        );
+        if let Some((cache, key)) = &cached
+            && let Some(response) = cache.get(key)
+        {
+            return Ok(response);
+        }
+
+        let response = self.run_query(&project_id, cmd.filters, timezone).await?;
+        if let Some((cache, key)) = cached {
+            cache.insert(key, response.clone());
+        }
+        Ok(response)
+    }
 
-        let rollup = cmd
-            .filters
+    async fn run_query(
+        &self,
+        project_id: &ProjectId,
+        query: MetricQueryFilters,
+        timezone: Tz,
+    ) -> Result<MetricQueryResponse, MetricsDomainError> {
+        let rollup = query
             .rollup
             .as_deref()
             .map(RollupInterval::from_str)
             .unwrap_or_default();
 
         // Convert types to MetricType
-        let metric_types = cmd.filters.types.map(|types| {
+        let metric_types = query.types.map(|types| {
             types
                 .iter()
                 .filter_map(|t| MetricType::from_str(t).ok())
@@ -173,42 +387,82 @@ where
         });
 
         // Convert tags HashMap to Vec of tuples
-        let tags = cmd.filters.tags.map(|t| t.into_iter().collect());
+        let tags = query.tags.map(|t| t.into_iter().collect());
 
         let filters = MetricFilters {
-            names: cmd.filters.names,
+            names: query.names,
             metric_types,
-            start_time: cmd.filters.start_time,
-            end_time: cmd.filters.end_time,
+            start_time: query.start_time,
+            end_time: query.end_time,
             tags,
-            trace_id: cmd.filters.trace_id,
+            trace_id: query.trace_id,
         };
 
+        // A percentile per step, read from raw points
+        if let Some(percentile) = query.percentile {
+            let (Some(start), Some(end), Some(step)) =
+                (filters.start_time, filters.end_time, query.step.as_deref())
+            else {
+                return Err(MetricsDomainError::InvalidQuery(
+                    "percentile requires step, start_time and end_time".to_string(),
+                ));
+            };
+            if !(0.0..=100.0).contains(&percentile) {
+                return Err(MetricsDomainError::InvalidQuery(
+                    "percentile must be between 0 and 100".to_string(),
+                ));
+            }
+            let step_secs = StepRange::parse_step(step)?;
+            if step_secs > (end - start).num_seconds() {
+                return Err(MetricsDomainError::InvalidQuery(
+                    "step must not be wider than the range between start_time and end_time"
+                        .to_string(),
+                ));
+            }
+            let range = StepRange::new(start, end, step_secs)?.with_timezone(timezone);
+            let buckets = self
+                .metrics_repo
+                .query_step_percentiles(project_id, &filters, &range, percentile / 100.0)
+                .await?;
+
+            return Ok(MetricQueryResponse {
+                total: buckets.len() as i64,
+                data: buckets
+                    .into_iter()
+                    .map(|b| MetricDataPoint {
+                        percentile_value: Some(b.value),
+                        ..data_point(b.metric)
+                    })
+                    .collect(),
+            });
+        }
+
+        // A step resamples the range to the requested resolution instead of a stored one
+        if let Some(step) = query.step.as_deref() {
+            let (Some(start), Some(end)) = (filters.start_time, filters.end_time) else {
+                return Err(MetricsDomainError::InvalidQuery(
+                    "step requires start_time and end_time".to_string(),
+                ));
+            };
+            let range =
+                StepRange::new(start, end, StepRange::parse_step(step)?)?.with_timezone(timezone);
+            let metrics = self
+                .metrics_repo
+                .query_steps(project_id, &filters, &range)
+                .await?;
+
+            return Ok(MetricQueryResponse {
+                total: metrics.len() as i64,
+                data: metrics.into_iter().map(data_point).collect(),
+            });
+        }
+
         let result = self
             .metrics_repo
-            .query(
-                &project_id,
-                &filters,
-                rollup,
-                cmd.filters.limit,
-                cmd.filters.offset,
-            )
+            .query(project_id, &filters, rollup, query.limit, query.offset)
             .await?;
 
-        let data = result
-            .metrics
-            .into_iter()
-            .map(|m| MetricDataPoint {
-                name: m.name,
-                metric_type: m.metric_type,
-                timestamp: m.bucket,
-                avg_value: m.avg_value,
-                min_value: m.min_value,
-                max_value: m.max_value,
-                sum_value: m.sum_value,
-                sample_count: m.sample_count,
-            })
-            .collect();
+        let data = result.metrics.into_iter().map(data_point).collect();
 
         Ok(MetricQueryResponse {
             data,
@@ -216,6 +470,153 @@ where
         })
     }
 
+    /// Query a series derived from two metrics, combined bucket by bucket
+    /// (requires user auth)
+    pub async fn query_expression(
+        &self,
+        cmd: QueryMetricExpressionCommand,
+    ) -> Result<MetricExpressionResponse, MetricsDomainError> {
+        let project_id = ProjectId::new(cmd.project_id);
+        let project = self
+            .verify_project_access(&project_id, &cmd.requesting_user_id)
+            .await?;
+        let timezone = Self::step_timezone(&project, cmd.query.timezone.as_deref())?;
+
+        let query = &cmd.query;
+        let cached = self.query_cache_key(
+            cmd.bypass_cache,
+            "metrics.expression",
+            &project_id,
+            query.end_time,
+            &(
+                &query.left,
+                &query.op,
+                &query.right,
+                &query.value,
+                query.start_time,
+                query.end_time,
+                query
+                    .tags
+                    .as_ref()
+                    .map(|t| t.iter().collect::<BTreeMap<_, _>>()),
+                &query.rollup,
+                &query.step,
+                timezone.name(),
+            ),
+        );
+        if let Some((cache, key)) = &cached
+            && let Some(response) = cache.get(key)
+        {
+            return Ok(response);
+        }
+
+        let response = self
+            .run_expression(&project_id, cmd.query, timezone)
+            .await?;
+        if let Some((cache, key)) = cached {
+            cache.insert(key, response.clone());
+        }
+        Ok(response)
+    }
+
+    async fn run_expression(
+        &self,
+        project_id: &ProjectId,
+        query: MetricExpressionQuery,
+        timezone: Tz,
+    ) -> Result<MetricExpressionResponse, MetricsDomainError> {
+        let expression = SeriesExpression {
+            operator: BinaryOperator::from_str(&query.op)?,
+            value: query
+                .value
+                .as_deref()
+                .map(SeriesValue::from_str)
+                .transpose()?
+                .unwrap_or_default(),
+            left: query.left,
+            right: query.right,
+        };
+
+        // Both operands must name metrics the project has
+        let names = self.metrics_repo.get_metric_names(project_id).await?;
+        for operand in [&expression.left, &expression.right] {
+            if !names.contains(operand) {
+                return Err(MetricsDomainError::InvalidQuery(format!(
+                    "unknown metric '{}'",
+                    operand
+                )));
+            }
+        }
+
+        let filters = |name: &str| MetricFilters {
+            names: Some(vec![name.to_string()]),
+            metric_types: None,
+            start_time: query.start_time,
+            end_time: query.end_time,
+            tags: query.tags.clone().map(|t| t.into_iter().collect()),
+            trace_id: None,
+        };
+
+        // Both operands are fetched at the same resolution so their buckets line up
+        let (left, right, bucket_secs) = if let Some(step) = query.step.as_deref() {
+            let (Some(start), Some(end)) = (query.start_time, query.end_time) else {
+                return Err(MetricsDomainError::InvalidQuery(
+                    "step requires start_time and end_time".to_string(),
+                ));
+            };
+            let range =
+                StepRange::new(start, end, StepRange::parse_step(step)?)?.with_timezone(timezone);
+            let left = self
+                .metrics_repo
+                .query_steps(project_id, &filters(&expression.left), &range)
+                .await?;
+            let right = self
+                .metrics_repo
+                .query_steps(project_id, &filters(&expression.right), &range)
+                .await?;
+            // Steps are already aligned by the range, which may not be to UTC
+            (left, right, 1)
+        } else {
+            let rollup = query
+                .rollup
+                .as_deref()
+                .map(RollupInterval::from_str)
+                .unwrap_or_default();
+            let left = self
+                .metrics_repo
+                .query(
+                    project_id,
+                    &filters(&expression.left),
+                    rollup,
+                    Some(MAX_STEP_POINTS),
+                    None,
+                )
+                .await?;
+            let right = self
+                .metrics_repo
+                .query(
+                    project_id,
+                    &filters(&expression.right),
+                    rollup,
+                    Some(MAX_STEP_POINTS),
+                    None,
+                )
+                .await?;
+            (left.metrics, right.metrics, rollup.width_secs())
+        };
+
+        let data = expression
+            .evaluate(&left, &right, bucket_secs)
+            .into_iter()
+            .map(|(timestamp, value)| MetricExpressionPoint { timestamp, value })
+            .collect();
+
+        Ok(MetricExpressionResponse {
+            expression: expression.name(),
+            data,
+        })
+    }
+
     /// List metric names for a project (requires user auth)
     pub async fn list_names(
         &self,
@@ -230,3 +631,669 @@ where
         Ok(MetricNamesResponse { names })
     }
 }
+
+fn data_point(m: AggregatedMetric) -> MetricDataPoint {
+    MetricDataPoint {
+        name: m.name,
+        metric_type: m.metric_type,
+        timestamp: m.bucket,
+        avg_value: m.avg_value,
+        min_value: m.min_value,
+        max_value: m.max_value,
+        sum_value: m.sum_value,
+        sample_count: m.sample_count,
+        percentile_value: None,
+    }
+}
+
+/// Keep the first `max` labels in key order, so the same label set is always
+/// trimmed to the same series
+fn trim_labels(tags: &mut HashMap<String, String>, max: usize) {
+    let mut keys: Vec<&String> = tags.keys().collect();
+    keys.sort();
+    let dropped: Vec<String> = keys.into_iter().skip(max).cloned().collect();
+    for key in dropped {
+        tags.remove(&key);
+    }
+}
+
+/// Sorted copy of a list filter, so its order doesn't split the cache
+fn sorted(values: Option<&[String]>) -> Option<Vec<&String>> {
+    values.map(|values| {
+        let mut values: Vec<_> = values.iter().collect();
+        values.sort();
+        values
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, NaiveDate};
+
+    use crate::modules::metrics::domain::{
+        AggregatedMetric, LabeledRollup, MetricPoint, MetricQueryResult, PercentileBucket,
+    };
+    use crate::modules::organizations::domain::OrgRole;
+    use crate::modules::projects::domain::ProjectSettings;
+    use crate::shared::testing::{
+        InMemoryMemberRepository, InMemoryMetricsRepository, InMemoryProjectRepository,
+        SequentialIdGenerator,
+    };
+
+    #[derive(Default)]
+    struct RecordingMetricsRepository {
+        saved: Mutex<Vec<MetricPoint>>,
+    }
+
+    #[async_trait]
+    impl MetricsRepository for RecordingMetricsRepository {
+        async fn save_batch(&self, metrics: &[MetricPoint]) -> Result<u32, MetricsDomainError> {
+            self.saved.lock().unwrap().extend_from_slice(metrics);
+            Ok(metrics.len() as u32)
+        }
+
+        async fn query(
+            &self,
+            _project_id: &ProjectId,
+            _filters: &MetricFilters,
+            _rollup: RollupInterval,
+            _limit: Option<i64>,
+            _offset: Option<i64>,
+        ) -> Result<MetricQueryResult, MetricsDomainError> {
+            Ok(MetricQueryResult {
+                metrics: vec![],
+                total: 0,
+            })
+        }
+
+        async fn query_steps(
+            &self,
+            _project_id: &ProjectId,
+            _filters: &MetricFilters,
+            _range: &StepRange,
+        ) -> Result<Vec<AggregatedMetric>, MetricsDomainError> {
+            Ok(vec![])
+        }
+
+        async fn query_step_percentiles(
+            &self,
+            _project_id: &ProjectId,
+            _filters: &MetricFilters,
+            _range: &StepRange,
+            _percentile: f64,
+        ) -> Result<Vec<PercentileBucket>, MetricsDomainError> {
+            Ok(vec![])
+        }
+
+        async fn find_metric_types(
+            &self,
+            _project_id: &ProjectId,
+            _names: &[String],
+        ) -> Result<HashMap<String, MetricType>, MetricsDomainError> {
+            Ok(HashMap::new())
+        }
+
+        async fn register_metric_types(
+            &self,
+            _project_id: &ProjectId,
+            _types: &[(String, MetricType)],
+        ) -> Result<(), MetricsDomainError> {
+            Ok(())
+        }
+
+        async fn get_metric_names(
+            &self,
+            _project_id: &ProjectId,
+        ) -> Result<Vec<String>, MetricsDomainError> {
+            Ok(vec![])
+        }
+
+        async fn query_labeled_rollups(
+            &self,
+            _project_id: &ProjectId,
+            _start: DateTime<Utc>,
+            _end: DateTime<Utc>,
+            _rollup: RollupInterval,
+        ) -> Result<Vec<LabeledRollup>, MetricsDomainError> {
+            Ok(vec![])
+        }
+
+        async fn exported_days(
+            &self,
+            _project_id: &ProjectId,
+            _from: NaiveDate,
+            _to: NaiveDate,
+        ) -> Result<Vec<NaiveDate>, MetricsDomainError> {
+            Ok(vec![])
+        }
+
+        async fn record_export(
+            &self,
+            _project_id: &ProjectId,
+            _day: NaiveDate,
+            _object_key: &str,
+        ) -> Result<(), MetricsDomainError> {
+            Ok(())
+        }
+
+        async fn delete_before(
+            &self,
+            _project_id: &ProjectId,
+            _before: DateTime<Utc>,
+            _limit: u32,
+        ) -> Result<u64, MetricsDomainError> {
+            Ok(0)
+        }
+    }
+
+    async fn create_service(
+        policy: LateMetricsPolicy,
+    ) -> (
+        MetricsService<
+            RecordingMetricsRepository,
+            InMemoryProjectRepository,
+            InMemoryMemberRepository,
+            SequentialIdGenerator,
+        >,
+        Arc<RecordingMetricsRepository>,
+    ) {
+        let metrics_repo = Arc::new(RecordingMetricsRepository::default());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let mut project = project_repo.seed("project-1", "org-1");
+        let settings = ProjectSettings {
+            late_metrics_policy: policy,
+            ..Default::default()
+        };
+        project.update_settings(settings);
+        project_repo.save(&project).await.unwrap();
+
+        let service = MetricsService::new(
+            metrics_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            Duration::minutes(10),
+        );
+        (service, metrics_repo)
+    }
+
+    fn gauge(timestamp: DateTime<Utc>) -> MetricInput {
+        MetricInput {
+            name: "cpu.usage".to_string(),
+            metric_type: "gauge".to_string(),
+            value: 0.5,
+            timestamp: Some(timestamp),
+            unit: None,
+            description: None,
+            tags: HashMap::new(),
+            bucket_bounds: None,
+            bucket_counts: None,
+            histogram_sum: None,
+            histogram_count: None,
+            histogram_min: None,
+            histogram_max: None,
+            trace_id: None,
+            span_id: None,
+        }
+    }
+
+    fn ingest_command() -> IngestMetricsCommand {
+        IngestMetricsCommand {
+            project_id: "project-1".to_string(),
+            metrics: vec![gauge(Utc::now()), gauge(Utc::now() - Duration::hours(2))],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_late_point_rejected_when_policy_is_strict() {
+        let (service, repo) = create_service(LateMetricsPolicy::Strict).await;
+
+        let response = service.ingest(ingest_command()).await.unwrap();
+
+        assert_eq!(response.ingested, 1);
+        assert_eq!(response.rejected, 1);
+        assert_eq!(repo.saved.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_late_point_accepted_by_default() {
+        let (service, repo) = create_service(LateMetricsPolicy::default()).await;
+
+        let response = service.ingest(ingest_command()).await.unwrap();
+
+        assert_eq!(response.ingested, 2);
+        assert_eq!(response.rejected, 0);
+        // In-order point is written first, the late one in a separate batch
+        let saved = repo.saved.lock().unwrap();
+        assert!(saved[0].timestamp() > saved[1].timestamp());
+    }
+
+    #[tokio::test]
+    async fn test_exemplar_trace_ids_recorded_for_sampling() {
+        let (service, _) = create_service(LateMetricsPolicy::default()).await;
+        let keep_set = Arc::new(TraceKeepSet::new(crate::shared::TraceKeepSetConfig {
+            ttl: std::time::Duration::from_secs(60),
+            max_entries: 100,
+        }));
+        let service = service.with_trace_keep_set(keep_set.clone());
+
+        service
+            .ingest(IngestMetricsCommand {
+                project_id: "project-1".to_string(),
+                metrics: vec![
+                    MetricInput {
+                        trace_id: Some("4bf92f3577b34da6a3ce929d0e0e4736".to_string()),
+                        ..gauge(Utc::now())
+                    },
+                    gauge(Utc::now()),
+                ],
+            })
+            .await
+            .unwrap();
+
+        assert!(keep_set.contains("project-1", "4bf92f3577b34da6a3ce929d0e0e4736"));
+        assert!(!keep_set.contains("project-2", "4bf92f3577b34da6a3ce929d0e0e4736"));
+    }
+
+    #[tokio::test]
+    async fn test_label_key_styles_merge_after_normalization() {
+        let (service, repo) = create_service(LateMetricsPolicy::default()).await;
+        let project_id = ProjectId::new("project-1".to_string());
+        let mut project = service
+            .project_repo
+            .find_by_id(&project_id)
+            .await
+            .unwrap()
+            .unwrap();
+        project.update_settings(
+            project
+                .settings()
+                .merge(serde_json::json!({
+                    "label_normalization": { "normalize_keys": true, "preserve_raw": true }
+                }))
+                .unwrap(),
+        );
+        service.project_repo.save(&project).await.unwrap();
+
+        let labelled = |key: &str| MetricInput {
+            tags: HashMap::from([(key.to_string(), "GET".to_string())]),
+            ..gauge(Utc::now())
+        };
+        service
+            .ingest(IngestMetricsCommand {
+                project_id: "project-1".to_string(),
+                metrics: vec![labelled("http.method"), labelled("http_method")],
+            })
+            .await
+            .unwrap();
+
+        let saved = repo.saved.lock().unwrap();
+        let expected = HashMap::from([("http_method".to_string(), "GET".to_string())]);
+        assert_eq!(saved[0].tags(), &expected);
+        assert_eq!(saved[1].tags(), &expected);
+        assert_eq!(
+            saved[0].raw_tags().unwrap().get("http.method").unwrap(),
+            "GET"
+        );
+        assert!(saved[1].raw_tags().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_points_over_label_limit_trimmed_or_rejected() {
+        let metrics_repo = Arc::new(InMemoryMetricsRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let mut project = project_repo.seed("project-1", "org-1");
+        project.update_settings(
+            project
+                .settings()
+                .merge(serde_json::json!({"metric_label_limit": {"max_labels": 2}}))
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+        let service = MetricsService::new(
+            metrics_repo.clone(),
+            project_repo.clone(),
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            Duration::minutes(10),
+        );
+        let labeled = |keys: &[&str]| MetricInput {
+            tags: keys
+                .iter()
+                .map(|k| (k.to_string(), "x".to_string()))
+                .collect(),
+            ..gauge(Utc::now())
+        };
+        let ingest = |metrics| IngestMetricsCommand {
+            project_id: "project-1".to_string(),
+            metrics,
+        };
+
+        let response = service
+            .ingest(ingest(vec![
+                labeled(&["pod", "host", "env"]),
+                labeled(&["host"]),
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(response.ingested, 2);
+        assert_eq!(response.labels_trimmed, 1);
+        let mut kept: Vec<String> = metrics_repo.saved()[0].tags().keys().cloned().collect();
+        kept.sort();
+        assert_eq!(kept, vec!["env", "host"]);
+
+        project.update_settings(
+            project
+                .settings()
+                .merge(serde_json::json!({"metric_label_limit": {"max_labels": 2, "policy": "reject"}}))
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+        let response = service
+            .ingest(ingest(vec![
+                labeled(&["pod", "host", "env"]),
+                labeled(&["host"]),
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(response.ingested, 1);
+        assert_eq!(response.labels_trimmed, 0);
+        assert_eq!(response.label_limit_rejected, 1);
+    }
+
+    #[tokio::test]
+    async fn test_gauge_point_for_counter_name_is_rejected() {
+        let metrics_repo = Arc::new(InMemoryMetricsRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let mut project = project_repo.seed("project-1", "org-1");
+        let service = MetricsService::new(
+            metrics_repo.clone(),
+            project_repo.clone(),
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            Duration::minutes(10),
+        );
+        let typed = |metric_type: &str| MetricInput {
+            metric_type: metric_type.to_string(),
+            ..gauge(Utc::now())
+        };
+        let ingest = |metrics| IngestMetricsCommand {
+            project_id: "project-1".to_string(),
+            metrics,
+        };
+
+        let first = service
+            .ingest(ingest(vec![typed("counter")]))
+            .await
+            .unwrap();
+        assert_eq!(first.ingested, 1);
+
+        let response = service
+            .ingest(ingest(vec![typed("gauge"), typed("counter")]))
+            .await
+            .unwrap();
+        assert_eq!(response.ingested, 1);
+        assert_eq!(response.type_conflicts, 1);
+        assert!(
+            metrics_repo
+                .saved()
+                .iter()
+                .all(|m| m.metric_type() == MetricType::Counter)
+        );
+
+        // With the override the point is stored, and the name stays a counter
+        project.update_settings(
+            project
+                .settings()
+                .merge(serde_json::json!({"metric_type_policy": "allow"}))
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+        let response = service.ingest(ingest(vec![typed("gauge")])).await.unwrap();
+        assert_eq!(response.ingested, 1);
+        assert_eq!(response.type_conflicts, 0);
+        let registered = metrics_repo
+            .find_metric_types(project.id(), &["cpu.usage".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(registered["cpu.usage"], MetricType::Counter);
+    }
+
+    #[tokio::test]
+    async fn test_ratio_expression_per_bucket_with_gaps_where_total_is_zero() {
+        let metrics_repo = Arc::new(InMemoryMetricsRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        member_repo.seed("org-1", "user-1", OrgRole::Member);
+        let service = MetricsService::new(
+            metrics_repo.clone(),
+            project_repo,
+            member_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            Duration::minutes(10),
+        );
+
+        // Minute 0: 1 error of 4 requests; minute 1: 3 of 6; minute 2: no requests
+        let start = DateTime::from_timestamp(1_700_000_040, 0).unwrap();
+        let counter = |name: &str, minute: i64, value: f64| {
+            MetricPoint::new(
+                format!("{}-{}-{}", name, minute, value),
+                ProjectId::new("project-1".to_string()),
+                name.to_string(),
+                MetricType::Counter,
+                value,
+                start + Duration::minutes(minute) + Duration::seconds(5),
+                None,
+                None,
+                HashMap::new(),
+                None,
+                None,
+            )
+        };
+        metrics_repo
+            .save_batch(&[
+                counter("errors", 0, 1.0),
+                counter("total", 0, 4.0),
+                counter("errors", 1, 3.0),
+                counter("total", 1, 2.0),
+                counter("total", 1, 4.0),
+            ])
+            .await
+            .unwrap();
+
+        let query = MetricExpressionQuery {
+            left: "errors".to_string(),
+            op: "ratio".to_string(),
+            right: "total".to_string(),
+            start_time: Some(start),
+            end_time: Some(start + Duration::seconds(179)),
+            step: Some("1m".to_string()),
+            ..Default::default()
+        };
+        let response = service
+            .query_expression(QueryMetricExpressionCommand {
+                project_id: "project-1".to_string(),
+                query: query.clone(),
+                requesting_user_id: "user-1".to_string(),
+                bypass_cache: false,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.expression, "errors / total");
+        assert_eq!(
+            response.data.iter().map(|p| p.value).collect::<Vec<_>>(),
+            vec![Some(0.25), Some(0.5), None]
+        );
+
+        // Operands must resolve to known metrics
+        let unknown = service
+            .query_expression(QueryMetricExpressionCommand {
+                project_id: "project-1".to_string(),
+                query: MetricExpressionQuery {
+                    right: "requests".to_string(),
+                    ..query
+                },
+                requesting_user_id: "user-1".to_string(),
+                bypass_cache: false,
+            })
+            .await;
+        assert!(matches!(unknown, Err(MetricsDomainError::InvalidQuery(_))));
+    }
+
+    #[tokio::test]
+    async fn test_repeated_query_over_past_range_is_served_from_cache() {
+        let metrics_repo = Arc::new(InMemoryMetricsRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        member_repo.seed("org-1", "user-1", OrgRole::Member);
+        let service = MetricsService::new(
+            metrics_repo.clone(),
+            project_repo,
+            member_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            Duration::minutes(10),
+        )
+        .with_query_cache(Arc::new(QueryCache::new(crate::shared::QueryCacheConfig {
+            ttl: std::time::Duration::from_secs(60),
+            max_entries: 10,
+            live_window: Duration::minutes(1),
+        })));
+
+        let now = Utc::now();
+        let point = |id: &str, at: chrono::DateTime<Utc>| {
+            MetricPoint::new(
+                id.to_string(),
+                ProjectId::new("project-1".to_string()),
+                "requests".to_string(),
+                MetricType::Counter,
+                1.0,
+                at,
+                None,
+                None,
+                HashMap::new(),
+                None,
+                None,
+            )
+        };
+        let query = |end_time, bypass_cache| QueryMetricsCommand {
+            project_id: "project-1".to_string(),
+            filters: MetricQueryFilters {
+                names: Some(vec!["requests".to_string()]),
+                start_time: Some(now - Duration::hours(2)),
+                end_time: Some(end_time),
+                ..Default::default()
+            },
+            requesting_user_id: "user-1".to_string(),
+            bypass_cache,
+        };
+        let total = |response: MetricQueryResponse| {
+            response.data.iter().map(|p| p.sample_count).sum::<i64>()
+        };
+        let past = now - Duration::hours(1);
+        metrics_repo
+            .save_batch(&[point("m-1", past - Duration::minutes(5))])
+            .await
+            .unwrap();
+
+        assert_eq!(total(service.query(query(past, false)).await.unwrap()), 1);
+        assert_eq!(total(service.query(query(now, false)).await.unwrap()), 1);
+
+        // Late data: the cached past range doesn't see it, a live range does
+        metrics_repo
+            .save_batch(&[
+                point("m-2", past - Duration::minutes(4)),
+                point("m-3", now - Duration::seconds(1)),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(total(service.query(query(past, false)).await.unwrap()), 1);
+        assert_eq!(total(service.query(query(now, false)).await.unwrap()), 3);
+        assert_eq!(total(service.query(query(past, true)).await.unwrap()), 2);
+    }
+
+    #[tokio::test]
+    async fn test_p95_per_minute_over_seeded_series() {
+        let metrics_repo = Arc::new(InMemoryMetricsRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        member_repo.seed("org-1", "user-1", OrgRole::Member);
+        let service = MetricsService::new(
+            metrics_repo.clone(),
+            project_repo,
+            member_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            Duration::minutes(10),
+        );
+
+        // Minute 0: latencies 1..=20; minute 1: 100..=109; minute 2: nothing
+        let start = DateTime::from_timestamp(1_700_000_040, 0).unwrap();
+        let latency = |minute: i64, value: f64| {
+            MetricPoint::new(
+                format!("latency-{}-{}", minute, value),
+                ProjectId::new("project-1".to_string()),
+                "http.latency".to_string(),
+                MetricType::Gauge,
+                value,
+                start + Duration::minutes(minute) + Duration::seconds(value as i64 % 60),
+                None,
+                None,
+                HashMap::new(),
+                None,
+                None,
+            )
+        };
+        let points: Vec<MetricPoint> = (1..=20)
+            .map(|v| latency(0, v as f64))
+            .chain((100..=109).map(|v| latency(1, v as f64)))
+            .collect();
+        metrics_repo.save_batch(&points).await.unwrap();
+
+        let query = |percentile: f64, step: &str| QueryMetricsCommand {
+            project_id: "project-1".to_string(),
+            filters: MetricQueryFilters {
+                names: Some(vec!["http.latency".to_string()]),
+                start_time: Some(start),
+                end_time: Some(start + Duration::seconds(179)),
+                step: Some(step.to_string()),
+                percentile: Some(percentile),
+                ..Default::default()
+            },
+            requesting_user_id: "user-1".to_string(),
+            bypass_cache: false,
+        };
+        let response = service.query(query(95.0, "1m")).await.unwrap();
+
+        let values: Vec<Option<f64>> = response
+            .data
+            .iter()
+            .map(|p| p.percentile_value.unwrap())
+            .collect();
+        assert_eq!(values.len(), 3);
+        assert!((values[0].unwrap() - 19.05).abs() < 1e-9);
+        assert!((values[1].unwrap() - 108.55).abs() < 1e-9);
+        assert_eq!(values[2], None);
+        assert_eq!(
+            response
+                .data
+                .iter()
+                .map(|p| p.sample_count)
+                .collect::<Vec<_>>(),
+            vec![20, 10, 0]
+        );
+
+        for (percentile, step) in [(101.0, "1m"), (95.0, "5m")] {
+            assert!(matches!(
+                service.query(query(percentile, step)).await,
+                Err(MetricsDomainError::InvalidQuery(_))
+            ));
+        }
+    }
+}
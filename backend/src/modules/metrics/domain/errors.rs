@@ -17,6 +17,9 @@ pub enum MetricsDomainError {
     #[error("Invalid histogram data: {0}")]
     InvalidHistogramData(String),
 
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+
     #[error("Project not found")]
     ProjectNotFound,
 
@@ -17,6 +17,8 @@ pub struct MetricPoint {
     unit: Option<String>,
     description: Option<String>,
     tags: HashMap<String, String>,
+    /// Labels as received, kept when label normalization is set to preserve them
+    raw_tags: Option<HashMap<String, String>>,
     histogram_data: Option<HistogramData>,
     trace_id: Option<String>,
     span_id: Option<String>,
@@ -48,6 +50,7 @@ impl MetricPoint {
             unit,
             description,
             tags,
+            raw_tags: None,
             histogram_data: None,
             trace_id,
             span_id,
@@ -79,6 +82,7 @@ impl MetricPoint {
             unit,
             description,
             tags,
+            raw_tags: None,
             histogram_data: Some(histogram_data),
             trace_id,
             span_id,
@@ -113,12 +117,19 @@ impl MetricPoint {
             unit,
             description,
             tags,
+            raw_tags: None,
             histogram_data,
             trace_id,
             span_id,
         }
     }
 
+    /// Keep the labels as received alongside the normalized ones
+    pub fn with_raw_tags(mut self, raw_tags: HashMap<String, String>) -> Self {
+        self.raw_tags = Some(raw_tags);
+        self
+    }
+
     // Getters
     pub fn id(&self) -> &str {
         &self.id
@@ -160,6 +171,10 @@ impl MetricPoint {
         &self.tags
     }
 
+    pub fn raw_tags(&self) -> Option<&HashMap<String, String>> {
+        self.raw_tags.as_ref()
+    }
+
     pub fn histogram_data(&self) -> Option<&HistogramData> {
         self.histogram_data.as_ref()
     }
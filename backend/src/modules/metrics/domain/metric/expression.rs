@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use super::repository::AggregatedMetric;
+use crate::modules::metrics::domain::errors::MetricsDomainError;
+
+/// Operator combining two series bucket by bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOperator {
+    Add,
+    Subtract,
+    Divide,
+}
+
+impl BinaryOperator {
+    pub fn from_str(s: &str) -> Result<Self, MetricsDomainError> {
+        match s.to_lowercase().as_str() {
+            "sum" | "add" => Ok(Self::Add),
+            "difference" | "sub" | "-" => Ok(Self::Subtract),
+            "ratio" | "div" | "/" => Ok(Self::Divide),
+            _ => Err(MetricsDomainError::InvalidQuery(format!(
+                "invalid operator '{}': use ratio, sum or difference",
+                s
+            ))),
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Add => "+",
+            Self::Subtract => "-",
+            Self::Divide => "/",
+        }
+    }
+
+    /// Apply the operator; dividing by zero gives no value
+    pub fn apply(&self, left: f64, right: f64) -> Option<f64> {
+        match self {
+            Self::Add => Some(left + right),
+            Self::Subtract => Some(left - right),
+            Self::Divide if right == 0.0 => None,
+            Self::Divide => Some(left / right),
+        }
+    }
+}
+
+/// Which aggregate of each bucket an expression operates on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SeriesValue {
+    Avg,
+    Min,
+    Max,
+    #[default]
+    Sum,
+    Count,
+}
+
+impl SeriesValue {
+    pub fn from_str(s: &str) -> Result<Self, MetricsDomainError> {
+        match s.to_lowercase().as_str() {
+            "avg" => Ok(Self::Avg),
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            "sum" => Ok(Self::Sum),
+            "count" => Ok(Self::Count),
+            _ => Err(MetricsDomainError::InvalidQuery(format!(
+                "invalid value '{}': use avg, min, max, sum or count",
+                s
+            ))),
+        }
+    }
+
+    fn of(&self, m: &AggregatedMetric) -> f64 {
+        match self {
+            Self::Avg => m.avg_value,
+            Self::Min => m.min_value,
+            Self::Max => m.max_value,
+            Self::Sum => m.sum_value,
+            Self::Count => m.sample_count as f64,
+        }
+    }
+}
+
+/// A binary expression over two metric series, e.g. `errors / total`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeriesExpression {
+    pub left: String,
+    pub operator: BinaryOperator,
+    pub right: String,
+    pub value: SeriesValue,
+}
+
+impl SeriesExpression {
+    /// Human readable form, used as the derived series' name
+    pub fn name(&self) -> String {
+        format!("{} {} {}", self.left, self.operator.symbol(), self.right)
+    }
+
+    /// Combine the operands' buckets into one derived series. Buckets are aligned
+    /// to multiples of `bucket_secs` since the Unix epoch; a bucket missing from
+    /// either side, or dividing by zero, has no value.
+    pub fn evaluate(
+        &self,
+        left: &[AggregatedMetric],
+        right: &[AggregatedMetric],
+        bucket_secs: i64,
+    ) -> Vec<(DateTime<Utc>, Option<f64>)> {
+        let left = self.by_bucket(left, bucket_secs);
+        let right = self.by_bucket(right, bucket_secs);
+
+        let mut buckets: Vec<_> = left.keys().chain(right.keys()).copied().collect();
+        buckets.sort();
+        buckets.dedup();
+
+        buckets
+            .into_iter()
+            .map(|bucket| {
+                let value = match (left.get(&bucket), right.get(&bucket)) {
+                    (Some(l), Some(r)) => self.operator.apply(*l, *r),
+                    _ => None,
+                };
+                (Utc.timestamp_opt(bucket, 0).unwrap(), value)
+            })
+            .collect()
+    }
+
+    /// Operand values keyed by aligned bucket start. Buckets landing in the same
+    /// aligned bucket are merged first.
+    fn by_bucket(&self, metrics: &[AggregatedMetric], bucket_secs: i64) -> BTreeMap<i64, f64> {
+        let mut merged: BTreeMap<i64, AggregatedMetric> = BTreeMap::new();
+        for m in metrics {
+            let bucket = m.bucket.timestamp().div_euclid(bucket_secs) * bucket_secs;
+            merged
+                .entry(bucket)
+                .and_modify(|existing| {
+                    existing.min_value = existing.min_value.min(m.min_value);
+                    existing.max_value = existing.max_value.max(m.max_value);
+                    existing.sum_value += m.sum_value;
+                    existing.sample_count += m.sample_count;
+                    existing.avg_value = if existing.sample_count > 0 {
+                        existing.sum_value / existing.sample_count as f64
+                    } else {
+                        0.0
+                    };
+                })
+                .or_insert_with(|| m.clone());
+        }
+        merged
+            .into_iter()
+            .map(|(bucket, m)| (bucket, self.value.of(&m)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_040 + secs, 0).unwrap()
+    }
+
+    fn bucket(name: &str, secs: i64, sum: f64) -> AggregatedMetric {
+        AggregatedMetric {
+            project_id: "project-1".to_string(),
+            name: name.to_string(),
+            metric_type: "counter".to_string(),
+            bucket: at(secs),
+            avg_value: sum,
+            min_value: sum,
+            max_value: sum,
+            sum_value: sum,
+            sample_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_misaligned_buckets_are_aligned_before_combining() {
+        let expression = SeriesExpression {
+            left: "errors".to_string(),
+            operator: BinaryOperator::Subtract,
+            right: "total".to_string(),
+            value: SeriesValue::Sum,
+        };
+        // The right side reports 10s into the same minute, twice
+        let derived = expression.evaluate(
+            &[bucket("errors", 0, 5.0)],
+            &[bucket("total", 10, 8.0), bucket("total", 20, 4.0)],
+            60,
+        );
+
+        assert_eq!(derived, vec![(at(0), Some(-7.0))]);
+        assert_eq!(expression.name(), "errors - total");
+    }
+
+    #[test]
+    fn test_operator_parsing() {
+        assert_eq!(
+            BinaryOperator::from_str("ratio").unwrap(),
+            BinaryOperator::Divide
+        );
+        assert_eq!(
+            BinaryOperator::from_str("/").unwrap(),
+            BinaryOperator::Divide
+        );
+        assert_eq!(
+            BinaryOperator::from_str("sum").unwrap(),
+            BinaryOperator::Add
+        );
+        assert!(BinaryOperator::from_str("*").is_err());
+        assert_eq!(BinaryOperator::Divide.apply(1.0, 0.0), None);
+    }
+}
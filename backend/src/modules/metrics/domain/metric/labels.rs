@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use crate::modules::projects::domain::{LabelNormalizationSettings, normalize_label_key};
+
+/// Rewrites metric label keys using a project's label normalization, so series
+/// reported as `http.method` and `http_method` land in the same series
+#[derive(Debug)]
+pub struct LabelNormalizer {
+    normalize_keys: bool,
+    /// Rename rules keyed by the normalized source key
+    rename: HashMap<String, String>,
+}
+
+impl LabelNormalizer {
+    pub fn new(settings: &LabelNormalizationSettings) -> Self {
+        let normalize_keys = settings.normalize_keys;
+        let rename = settings
+            .rename
+            .iter()
+            .map(|(from, to)| {
+                let from = if normalize_keys {
+                    normalize_label_key(from)
+                } else {
+                    from.clone()
+                };
+                (from, to.clone())
+            })
+            .collect();
+        Self {
+            normalize_keys,
+            rename,
+        }
+    }
+
+    /// Normalize one point's labels. When several labels end up with the same
+    /// key, one already in its final form wins, then the first in key order.
+    pub fn normalize(&self, tags: &HashMap<String, String>) -> HashMap<String, String> {
+        let mut renamed: Vec<(String, &String, &String)> = tags
+            .iter()
+            .map(|(key, value)| (self.key(key), key, value))
+            .collect();
+        renamed.sort_by(|(a_new, a_raw, _), (b_new, b_raw, _)| {
+            (a_new != *a_raw, a_raw).cmp(&(b_new != *b_raw, b_raw))
+        });
+
+        let mut normalized = HashMap::with_capacity(renamed.len());
+        for (key, _, value) in renamed {
+            normalized.entry(key).or_insert_with(|| value.clone());
+        }
+        normalized
+    }
+
+    fn key(&self, key: &str) -> String {
+        let key = if self.normalize_keys {
+            normalize_label_key(key)
+        } else {
+            key.to_string()
+        };
+        self.rename.get(&key).cloned().unwrap_or(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_normalizes_and_renames_keys() {
+        let normalizer = LabelNormalizer::new(&LabelNormalizationSettings {
+            normalize_keys: true,
+            rename: [("Method".to_string(), "http_method".to_string())].into(),
+            preserve_raw: false,
+        });
+
+        assert_eq!(
+            normalizer.normalize(&tags(&[("HTTP.Route", "/users"), ("method", "GET")])),
+            tags(&[("http_route", "/users"), ("http_method", "GET")])
+        );
+    }
+
+    #[test]
+    fn test_colliding_keys_prefer_normalized_form() {
+        let normalizer = LabelNormalizer::new(&LabelNormalizationSettings {
+            normalize_keys: true,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            normalizer.normalize(&tags(&[("http.method", "get"), ("http_method", "GET")])),
+            tags(&[("http_method", "GET")])
+        );
+    }
+}
@@ -1,7 +1,16 @@
 pub mod entity;
+pub mod expression;
+pub mod labels;
 pub mod repository;
+pub mod step;
 pub mod value_objects;
 
 pub use entity::MetricPoint;
-pub use repository::{AggregatedMetric, MetricFilters, MetricQueryResult, MetricsRepository, RollupInterval};
+pub use expression::{BinaryOperator, SeriesExpression, SeriesValue};
+pub use labels::LabelNormalizer;
+pub use repository::{
+    AggregatedMetric, LabeledRollup, MetricFilters, MetricQueryResult, MetricsRepository,
+    PercentileBucket, RollupInterval,
+};
+pub use step::StepRange;
 pub use value_objects::{HistogramData, MetricType};
@@ -1,7 +1,10 @@
+use std::collections::{BTreeMap, HashMap};
+
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 
 use super::entity::MetricPoint;
+use super::step::StepRange;
 use super::value_objects::MetricType;
 use crate::modules::metrics::domain::errors::MetricsDomainError;
 use crate::modules::projects::domain::ProjectId;
@@ -38,6 +41,24 @@ impl RollupInterval {
         }
     }
 
+    /// Coarsest stored resolution whose buckets fit evenly into `step_secs`
+    pub fn for_step(step_secs: i64) -> Self {
+        [Self::OneDay, Self::OneHour, Self::OneMinute]
+            .into_iter()
+            .find(|rollup| step_secs % rollup.width_secs() == 0)
+            .unwrap_or(Self::Raw)
+    }
+
+    /// Bucket width in seconds (1 for raw points)
+    pub fn width_secs(&self) -> i64 {
+        match self {
+            Self::Raw => 1,
+            Self::OneMinute => 60,
+            Self::OneHour => 3600,
+            Self::OneDay => 86400,
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Raw => "raw",
@@ -62,6 +83,28 @@ pub struct AggregatedMetric {
     pub sample_count: i64,
 }
 
+/// A step's aggregates together with a percentile of its raw values
+#[derive(Debug, Clone)]
+pub struct PercentileBucket {
+    pub metric: AggregatedMetric,
+    /// None for steps without samples
+    pub value: Option<f64>,
+}
+
+/// Aggregated metric bucket for one label set
+#[derive(Debug, Clone)]
+pub struct LabeledRollup {
+    pub name: String,
+    pub metric_type: String,
+    pub labels: BTreeMap<String, String>,
+    pub bucket: DateTime<Utc>,
+    pub avg_value: f64,
+    pub min_value: f64,
+    pub max_value: f64,
+    pub sum_value: f64,
+    pub sample_count: i64,
+}
+
 /// Query result for metrics
 #[derive(Debug, Clone)]
 pub struct MetricQueryResult {
@@ -85,13 +128,76 @@ pub trait MetricsRepository: Send + Sync {
         offset: Option<i64>,
     ) -> Result<MetricQueryResult, MetricsDomainError>;
 
+    /// Query metrics bucketed into the range's steps, one point per step per series
+    async fn query_steps(
+        &self,
+        project_id: &ProjectId,
+        filters: &MetricFilters,
+        range: &StepRange,
+    ) -> Result<Vec<AggregatedMetric>, MetricsDomainError>;
+
+    /// Query raw points bucketed into the range's steps with the `percentile`
+    /// (0-1) of each step's values, one point per step per series
+    async fn query_step_percentiles(
+        &self,
+        project_id: &ProjectId,
+        filters: &MetricFilters,
+        range: &StepRange,
+        percentile: f64,
+    ) -> Result<Vec<PercentileBucket>, MetricsDomainError>;
+
+    /// Types registered for the given metric names; names never ingested are absent
+    async fn find_metric_types(
+        &self,
+        project_id: &ProjectId,
+        names: &[String],
+    ) -> Result<HashMap<String, MetricType>, MetricsDomainError>;
+
+    /// Register the type of newly seen metric names, keeping any already registered
+    async fn register_metric_types(
+        &self,
+        project_id: &ProjectId,
+        types: &[(String, MetricType)],
+    ) -> Result<(), MetricsDomainError>;
+
     /// Get distinct metric names for a project
-    async fn get_metric_names(&self, project_id: &ProjectId) -> Result<Vec<String>, MetricsDomainError>;
+    async fn get_metric_names(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Vec<String>, MetricsDomainError>;
+
+    /// Aggregate points in `[start, end)` into `rollup` buckets per name, type
+    /// and label set
+    async fn query_labeled_rollups(
+        &self,
+        project_id: &ProjectId,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        rollup: RollupInterval,
+    ) -> Result<Vec<LabeledRollup>, MetricsDomainError>;
+
+    /// Days between `from` and `to` (inclusive) already exported for the project
+    async fn exported_days(
+        &self,
+        project_id: &ProjectId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<NaiveDate>, MetricsDomainError>;
+
+    /// Record that the project's day was exported to `object_key`
+    async fn record_export(
+        &self,
+        project_id: &ProjectId,
+        day: NaiveDate,
+        object_key: &str,
+    ) -> Result<(), MetricsDomainError>;
 
-    /// Delete metrics older than a given timestamp
+    /// Delete up to `limit` of the oldest metrics older than a given timestamp,
+    /// returning how many were deleted
     async fn delete_before(
         &self,
         project_id: &ProjectId,
         before: DateTime<Utc>,
+        limit: u32,
     ) -> Result<u64, MetricsDomainError>;
 }
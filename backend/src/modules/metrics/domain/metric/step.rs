@@ -0,0 +1,349 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Offset, TimeZone, Utc};
+
+use super::repository::{AggregatedMetric, PercentileBucket, RollupInterval};
+use super::value_objects::MetricType;
+use crate::modules::metrics::domain::errors::MetricsDomainError;
+use crate::shared::timezone::{from_local_secs, local_secs};
+use crate::shared::{Tz, local_bucket_start};
+
+/// Most points a step query may return per series
+pub const MAX_STEP_POINTS: i64 = 11_000;
+
+/// A query range split into fixed-width steps. Steps are aligned to multiples
+/// of the step width since the Unix epoch on the wall clock of the range's
+/// timezone (UTC unless set), so the same step always yields the same
+/// timestamps whatever the range, and daily steps start at local midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepRange {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    step_secs: i64,
+    timezone: Tz,
+}
+
+impl StepRange {
+    pub fn new(
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        step_secs: i64,
+    ) -> Result<Self, MetricsDomainError> {
+        if step_secs <= 0 {
+            return Err(MetricsDomainError::InvalidQuery(
+                "step must be a positive duration".to_string(),
+            ));
+        }
+        if end < start {
+            return Err(MetricsDomainError::InvalidQuery(
+                "end_time must not be before start_time".to_string(),
+            ));
+        }
+
+        let range = Self {
+            start,
+            end,
+            step_secs,
+            timezone: Tz::UTC,
+        };
+        if range.point_count() > MAX_STEP_POINTS {
+            return Err(MetricsDomainError::InvalidQuery(format!(
+                "range and step give {} points per series; at most {} are allowed, use a larger step",
+                range.point_count(),
+                MAX_STEP_POINTS
+            )));
+        }
+        Ok(range)
+    }
+
+    /// Parse a step such as "15s", "5m", "1h", "1d" or a bare number of seconds
+    pub fn parse_step(step: &str) -> Result<i64, MetricsDomainError> {
+        let step = step.trim();
+        let (number, unit) = match step.find(|c: char| !c.is_ascii_digit()) {
+            Some(idx) => step.split_at(idx),
+            None => (step, "s"),
+        };
+        let multiplier = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 3600,
+            "d" => 86400,
+            _ => 0,
+        };
+        match number.parse::<i64>() {
+            Ok(n) if multiplier > 0 => Ok(n.saturating_mul(multiplier)),
+            _ => Err(MetricsDomainError::InvalidQuery(format!(
+                "invalid step '{}': use e.g. 15s, 5m, 1h or 1d",
+                step
+            ))),
+        }
+    }
+
+    /// Align steps to the wall clock of `timezone` instead of UTC
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    pub fn step_secs(&self) -> i64 {
+        self.step_secs
+    }
+
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    /// Coarsest stored resolution whose buckets fit evenly into the steps.
+    /// Stored hourly and daily buckets are UTC-aligned, so they only fit
+    /// local steps when the timezone is a whole number of hours from UTC.
+    pub fn rollup(&self) -> RollupInterval {
+        let rollup = RollupInterval::for_step(self.step_secs);
+        if self.timezone == Tz::UTC || rollup == RollupInterval::Raw {
+            return rollup;
+        }
+        let whole_hours = [self.start, self.end].iter().all(|t| {
+            self.timezone
+                .offset_from_utc_datetime(&t.naive_utc())
+                .fix()
+                .local_minus_utc()
+                % 3600
+                == 0
+        });
+        match rollup {
+            RollupInterval::OneDay if whole_hours => RollupInterval::OneHour,
+            RollupInterval::OneDay | RollupInterval::OneHour if !whole_hours => {
+                RollupInterval::OneMinute
+            }
+            rollup => rollup,
+        }
+    }
+
+    /// Start of the first step, at or before the requested start
+    pub fn aligned_start(&self) -> DateTime<Utc> {
+        self.align(self.start)
+    }
+
+    pub fn end(&self) -> DateTime<Utc> {
+        self.end
+    }
+
+    /// Start of the step containing `t`
+    pub fn align(&self, t: DateTime<Utc>) -> DateTime<Utc> {
+        local_bucket_start(t, self.step_secs, self.timezone)
+    }
+
+    /// Number of steps between the aligned start and the end, inclusive
+    pub fn point_count(&self) -> i64 {
+        self.step_index(self.end) - self.step_index(self.start) + 1
+    }
+
+    pub fn steps(&self) -> impl Iterator<Item = DateTime<Utc>> + '_ {
+        let first = self.step_index(self.start);
+        (0..self.point_count())
+            .map(move |i| from_local_secs((first + i) * self.step_secs, self.timezone))
+    }
+
+    /// Steps since the epoch on the local wall clock up to the one holding `t`
+    fn step_index(&self, t: DateTime<Utc>) -> i64 {
+        local_secs(t, self.timezone).div_euclid(self.step_secs)
+    }
+
+    /// Turn per-step aggregates into one point per step for each series.
+    /// Steps without samples carry the last value forward for gauges and
+    /// histograms (leading empty steps are left out) and are zero for counters.
+    pub fn fill(&self, buckets: Vec<AggregatedMetric>) -> Vec<AggregatedMetric> {
+        let mut series: BTreeMap<(String, String), BTreeMap<DateTime<Utc>, AggregatedMetric>> =
+            BTreeMap::new();
+        for bucket in buckets {
+            series
+                .entry((bucket.name.clone(), bucket.metric_type.clone()))
+                .or_default()
+                .insert(self.align(bucket.bucket), bucket);
+        }
+
+        let mut points = Vec::new();
+        for ((name, metric_type), mut by_step) in series {
+            let is_counter = MetricType::from_str(&metric_type).ok() == Some(MetricType::Counter);
+            let project_id = by_step
+                .values()
+                .next()
+                .map(|m| m.project_id.clone())
+                .unwrap_or_default();
+            let mut last: Option<AggregatedMetric> = None;
+
+            for step in self.steps() {
+                let point = match by_step.remove(&step) {
+                    Some(point) => point,
+                    None if is_counter => AggregatedMetric {
+                        project_id: project_id.clone(),
+                        name: name.clone(),
+                        metric_type: metric_type.clone(),
+                        bucket: step,
+                        avg_value: 0.0,
+                        min_value: 0.0,
+                        max_value: 0.0,
+                        sum_value: 0.0,
+                        sample_count: 0,
+                    },
+                    None => match &last {
+                        Some(previous) => AggregatedMetric {
+                            bucket: step,
+                            sample_count: 0,
+                            ..previous.clone()
+                        },
+                        None => continue,
+                    },
+                };
+                last = Some(point.clone());
+                points.push(point);
+            }
+        }
+        points
+    }
+
+    /// Turn per-step percentiles into one point per step for each series.
+    /// A percentile of nothing is undefined, so steps without samples get no value.
+    pub fn fill_percentiles(&self, buckets: Vec<PercentileBucket>) -> Vec<PercentileBucket> {
+        let mut series: BTreeMap<(String, String), BTreeMap<DateTime<Utc>, PercentileBucket>> =
+            BTreeMap::new();
+        for bucket in buckets {
+            series
+                .entry((
+                    bucket.metric.name.clone(),
+                    bucket.metric.metric_type.clone(),
+                ))
+                .or_default()
+                .insert(self.align(bucket.metric.bucket), bucket);
+        }
+
+        let mut points = Vec::new();
+        for ((name, metric_type), mut by_step) in series {
+            let project_id = by_step
+                .values()
+                .next()
+                .map(|b| b.metric.project_id.clone())
+                .unwrap_or_default();
+            for step in self.steps() {
+                points.push(by_step.remove(&step).unwrap_or_else(|| PercentileBucket {
+                    metric: AggregatedMetric {
+                        project_id: project_id.clone(),
+                        name: name.clone(),
+                        metric_type: metric_type.clone(),
+                        bucket: step,
+                        avg_value: 0.0,
+                        min_value: 0.0,
+                        max_value: 0.0,
+                        sum_value: 0.0,
+                        sample_count: 0,
+                    },
+                    value: None,
+                }));
+            }
+        }
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    fn bucket(metric_type: &str, secs: i64, value: f64) -> AggregatedMetric {
+        AggregatedMetric {
+            project_id: "project-1".to_string(),
+            name: "requests".to_string(),
+            metric_type: metric_type.to_string(),
+            bucket: at(secs),
+            avg_value: value,
+            min_value: value,
+            max_value: value,
+            sum_value: value,
+            sample_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_range_and_step_give_aligned_point_count() {
+        // 1_700_000_000 is a multiple of 20 but not of 15: the first step starts 5s early
+        let range = StepRange::new(at(0), at(300), 15).unwrap();
+        let steps: Vec<_> = range.steps().collect();
+
+        assert_eq!(range.point_count(), 21);
+        assert_eq!(steps.len(), 21);
+        assert_eq!(steps[0], at(-5));
+        assert!(steps.iter().all(|t| t.timestamp() % 15 == 0));
+        assert!(*steps.last().unwrap() <= at(300));
+    }
+
+    #[test]
+    fn test_step_is_bounded_by_max_points() {
+        assert!(StepRange::new(at(0), at(86_400), 1).is_err());
+        assert!(StepRange::new(at(0), at(86_400), 15).is_ok());
+        assert!(StepRange::new(at(10), at(0), 15).is_err());
+        assert!(StepRange::new(at(0), at(10), 0).is_err());
+    }
+
+    #[test]
+    fn test_parse_step() {
+        assert_eq!(StepRange::parse_step("15s").unwrap(), 15);
+        assert_eq!(StepRange::parse_step("5m").unwrap(), 300);
+        assert_eq!(StepRange::parse_step("30").unwrap(), 30);
+        assert!(StepRange::parse_step("5w").is_err());
+        assert!(StepRange::parse_step("m").is_err());
+    }
+
+    #[test]
+    fn test_daily_steps_start_at_local_midnight() {
+        let tz = crate::shared::parse_timezone("America/New_York").unwrap();
+        let start: DateTime<Utc> = "2024-01-14T12:00:00Z".parse().unwrap();
+        let end: DateTime<Utc> = "2024-01-16T12:00:00Z".parse().unwrap();
+        let range = StepRange::new(start, end, 86_400)
+            .unwrap()
+            .with_timezone(tz);
+
+        let steps: Vec<_> = range.steps().map(|t| t.to_rfc3339()).collect();
+        assert_eq!(
+            steps,
+            vec![
+                "2024-01-14T05:00:00+00:00",
+                "2024-01-15T05:00:00+00:00",
+                "2024-01-16T05:00:00+00:00",
+            ]
+        );
+        // UTC-aligned daily rollups would straddle local midnight
+        assert_eq!(range.rollup(), RollupInterval::OneHour);
+
+        let kolkata = crate::shared::parse_timezone("Asia/Kolkata").unwrap();
+        assert_eq!(
+            range.with_timezone(kolkata).rollup(),
+            RollupInterval::OneMinute
+        );
+    }
+
+    #[test]
+    fn test_fill_carries_gauges_forward_and_zeroes_counters() {
+        let range = StepRange::new(at(0), at(59), 20).unwrap();
+        let filled = range.fill(vec![bucket("gauge", 20, 7.0), bucket("counter", 0, 3.0)]);
+
+        let gauge: Vec<_> = filled.iter().filter(|m| m.metric_type == "gauge").collect();
+        let counter: Vec<_> = filled
+            .iter()
+            .filter(|m| m.metric_type == "counter")
+            .collect();
+
+        // The gauge has no value before its first sample, then carries 7.0 forward
+        assert_eq!(gauge.len(), 2);
+        assert_eq!(
+            (gauge[1].bucket, gauge[1].avg_value, gauge[1].sample_count),
+            (at(40), 7.0, 0)
+        );
+        assert_eq!(
+            counter.iter().map(|m| m.sum_value).collect::<Vec<_>>(),
+            vec![3.0, 0.0, 0.0]
+        );
+    }
+}
@@ -3,6 +3,7 @@ pub mod metric;
 
 pub use errors::MetricsDomainError;
 pub use metric::{
-    AggregatedMetric, HistogramData, MetricFilters, MetricPoint, MetricQueryResult,
-    MetricsRepository, MetricType, RollupInterval,
+    AggregatedMetric, BinaryOperator, HistogramData, LabelNormalizer, LabeledRollup, MetricFilters,
+    MetricPoint, MetricQueryResult, MetricType, MetricsRepository, PercentileBucket,
+    RollupInterval, SeriesExpression, SeriesValue, StepRange,
 };
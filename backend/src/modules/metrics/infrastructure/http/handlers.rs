@@ -1,7 +1,7 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
     Extension, Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -9,11 +9,12 @@ use std::sync::Arc;
 use crate::modules::auth::application::ports::IdGenerator;
 use crate::modules::auth::infrastructure::http::extractors::AuthClaims;
 use crate::modules::logging::infrastructure::ApiKeyContext;
-use crate::modules::metrics::application::dto::*;
 use crate::modules::metrics::application::MetricsService;
+use crate::modules::metrics::application::dto::*;
 use crate::modules::metrics::domain::{MetricsDomainError, MetricsRepository};
 use crate::modules::organizations::domain::OrganizationMemberRepository;
 use crate::modules::projects::domain::ProjectRepository;
+use crate::shared::{LimitedJson, bypasses_query_cache};
 
 #[derive(Debug, Serialize)]
 pub struct ErrorResponse {
@@ -27,7 +28,8 @@ fn to_error_response(e: MetricsDomainError) -> (StatusCode, Json<ErrorResponse>)
         | MetricsDomainError::InvalidMetricType(msg)
         | MetricsDomainError::InvalidMetricValue(msg)
         | MetricsDomainError::InvalidTimestamp(msg)
-        | MetricsDomainError::InvalidHistogramData(msg) => (
+        | MetricsDomainError::InvalidHistogramData(msg)
+        | MetricsDomainError::InvalidQuery(msg) => (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 error: msg,
@@ -73,7 +75,7 @@ pub struct IngestMetricsRequest {
 pub async fn ingest_metrics<MR, PR, OMR, ID>(
     State(service): State<Arc<MetricsService<MR, PR, OMR, ID>>>,
     Extension(ctx): Extension<ApiKeyContext>,
-    Json(request): Json<IngestMetricsRequest>,
+    LimitedJson(request): LimitedJson<IngestMetricsRequest>,
 ) -> Result<(StatusCode, Json<IngestMetricsResponse>), (StatusCode, Json<ErrorResponse>)>
 where
     MR: MetricsRepository,
@@ -103,6 +105,9 @@ pub struct QueryMetricsParams {
     pub end_time: Option<String>,
     pub trace_id: Option<String>,
     pub rollup: Option<String>,
+    pub step: Option<String>,
+    pub timezone: Option<String>,
+    pub percentile: Option<f64>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
@@ -111,6 +116,7 @@ pub async fn query_metrics<MR, PR, OMR, ID>(
     State(service): State<Arc<MetricsService<MR, PR, OMR, ID>>>,
     Extension(claims): Extension<AuthClaims>,
     Path(project_id): Path<String>,
+    headers: HeaderMap,
     Query(params): Query<QueryMetricsParams>,
 ) -> Result<Json<MetricQueryResponse>, (StatusCode, Json<ErrorResponse>)>
 where
@@ -120,13 +126,20 @@ where
     ID: IdGenerator,
 {
     let filters = MetricQueryFilters {
-        names: params.names.map(|s| s.split(',').map(String::from).collect()),
-        types: params.types.map(|s| s.split(',').map(String::from).collect()),
+        names: params
+            .names
+            .map(|s| s.split(',').map(String::from).collect()),
+        types: params
+            .types
+            .map(|s| s.split(',').map(String::from).collect()),
         start_time: params.start_time.and_then(|s| s.parse().ok()),
         end_time: params.end_time.and_then(|s| s.parse().ok()),
         tags: None,
         trace_id: params.trace_id,
         rollup: params.rollup,
+        step: params.step,
+        timezone: params.timezone,
+        percentile: params.percentile,
         limit: params.limit,
         offset: params.offset,
     };
@@ -135,6 +148,7 @@ where
         project_id,
         filters,
         requesting_user_id: claims.user_id,
+        bypass_cache: bypasses_query_cache(&headers),
     };
 
     let response = service.query(cmd).await.map_err(to_error_response)?;
@@ -142,6 +156,60 @@ where
     Ok(Json(response))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct MetricExpressionParams {
+    pub left: String,
+    pub op: String,
+    pub right: String,
+    pub value: Option<String>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub rollup: Option<String>,
+    pub step: Option<String>,
+    pub timezone: Option<String>,
+}
+
+pub async fn query_metric_expression<MR, PR, OMR, ID>(
+    State(service): State<Arc<MetricsService<MR, PR, OMR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(project_id): Path<String>,
+    headers: HeaderMap,
+    Query(params): Query<MetricExpressionParams>,
+) -> Result<Json<MetricExpressionResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    MR: MetricsRepository,
+    PR: ProjectRepository,
+    OMR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let query = MetricExpressionQuery {
+        left: params.left,
+        op: params.op,
+        right: params.right,
+        value: params.value,
+        start_time: params.start_time.and_then(|s| s.parse().ok()),
+        end_time: params.end_time.and_then(|s| s.parse().ok()),
+        tags: None,
+        rollup: params.rollup,
+        step: params.step,
+        timezone: params.timezone,
+    };
+
+    let cmd = QueryMetricExpressionCommand {
+        project_id,
+        query,
+        requesting_user_id: claims.user_id,
+        bypass_cache: bypasses_query_cache(&headers),
+    };
+
+    let response = service
+        .query_expression(cmd)
+        .await
+        .map_err(to_error_response)?;
+
+    Ok(Json(response))
+}
+
 pub async fn list_metric_names<MR, PR, OMR, ID>(
     State(service): State<Arc<MetricsService<MR, PR, OMR, ID>>>,
     Extension(claims): Extension<AuthClaims>,
@@ -1,7 +1,6 @@
 use axum::{
-    middleware,
+    Router, middleware,
     routing::{get, post},
-    Router,
 };
 use std::sync::Arc;
 
@@ -30,7 +29,10 @@ where
     OR: OrganizationRepository + 'static,
 {
     Router::new()
-        .route("/metrics", post(handlers::ingest_metrics::<MR, PR, OMR, ID>))
+        .route(
+            "/metrics",
+            post(handlers::ingest_metrics::<MR, PR, OMR, ID>),
+        )
         .layer(middleware::from_fn_with_state(
             project_service,
             api_key_middleware::<PPR, AR, OR, OMR, ID>,
@@ -52,7 +54,14 @@ where
 {
     Router::new()
         .route("/", get(handlers::query_metrics::<MR, PR, OMR, ID>))
-        .route("/names", get(handlers::list_metric_names::<MR, PR, OMR, ID>))
+        .route(
+            "/names",
+            get(handlers::list_metric_names::<MR, PR, OMR, ID>),
+        )
+        .route(
+            "/expression",
+            get(handlers::query_metric_expression::<MR, PR, OMR, ID>),
+        )
         .layer(middleware::from_fn_with_state(
             token_service,
             auth_middleware::<TS>,
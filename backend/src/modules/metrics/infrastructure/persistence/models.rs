@@ -41,8 +41,44 @@ pub struct AggregatedMetricRow {
     pub sample_count: Option<i64>,
 }
 
+/// Step bucket row with a percentile of its raw values
+#[derive(Debug, FromRow)]
+pub struct PercentileBucketRow {
+    pub project_id: String,
+    pub name: String,
+    pub metric_type: String,
+    pub bucket: DateTime<Utc>,
+    pub avg_value: Option<f64>,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    pub sum_value: Option<f64>,
+    pub sample_count: Option<i64>,
+    pub percentile_value: Option<f64>,
+}
+
+/// Aggregated metric row for one label set, from the raw hypertable
+#[derive(Debug, FromRow)]
+pub struct LabeledRollupRow {
+    pub name: String,
+    pub metric_type: String,
+    pub tags: Option<Value>,
+    pub bucket: DateTime<Utc>,
+    pub avg_value: Option<f64>,
+    pub min_value: Option<f64>,
+    pub max_value: Option<f64>,
+    pub sum_value: Option<f64>,
+    pub sample_count: Option<i64>,
+}
+
 /// Metric name row
 #[derive(Debug, FromRow)]
 pub struct MetricNameRow {
     pub name: String,
 }
+
+/// Registered type of a metric name
+#[derive(Debug, FromRow)]
+pub struct MetricTypeRow {
+    pub name: String,
+    pub metric_type: String,
+}
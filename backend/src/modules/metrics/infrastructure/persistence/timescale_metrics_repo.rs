@@ -1,13 +1,16 @@
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde_json::json;
 use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
-use super::models::{AggregatedMetricRow, MetricNameRow};
+use super::models::{
+    AggregatedMetricRow, LabeledRollupRow, MetricNameRow, MetricTypeRow, PercentileBucketRow,
+};
 use crate::modules::metrics::domain::{
-    AggregatedMetric, MetricFilters, MetricPoint, MetricQueryResult, MetricsDomainError,
-    MetricsRepository, RollupInterval,
+    AggregatedMetric, LabeledRollup, MetricFilters, MetricPoint, MetricQueryResult, MetricType,
+    MetricsDomainError, MetricsRepository, PercentileBucket, RollupInterval, StepRange,
 };
 use crate::modules::projects::domain::ProjectId;
 
@@ -38,20 +41,27 @@ impl MetricsRepository for TimescaleMetricsRepository {
 
         for metric in metrics {
             let tags_json = json!(metric.tags());
-
-            let (bucket_bounds, bucket_counts, histogram_sum, histogram_count, histogram_min, histogram_max) =
-                if let Some(h) = metric.histogram_data() {
-                    (
-                        Some(h.bucket_bounds().to_vec()),
-                        Some(h.bucket_counts().to_vec()),
-                        Some(h.sum()),
-                        Some(h.count()),
-                        Some(h.min()),
-                        Some(h.max()),
-                    )
-                } else {
-                    (None, None, None, None, None, None)
-                };
+            let raw_tags_json = metric.raw_tags().map(|raw| json!(raw));
+
+            let (
+                bucket_bounds,
+                bucket_counts,
+                histogram_sum,
+                histogram_count,
+                histogram_min,
+                histogram_max,
+            ) = if let Some(h) = metric.histogram_data() {
+                (
+                    Some(h.bucket_bounds().to_vec()),
+                    Some(h.bucket_counts().to_vec()),
+                    Some(h.sum()),
+                    Some(h.count()),
+                    Some(h.min()),
+                    Some(h.max()),
+                )
+            } else {
+                (None, None, None, None, None, None)
+            };
 
             sqlx::query(
                 r#"
@@ -59,9 +69,9 @@ impl MetricsRepository for TimescaleMetricsRepository {
                     id, project_id, name, metric_type, value, timestamp, received_at,
                     unit, description, tags,
                     bucket_bounds, bucket_counts, histogram_sum, histogram_count,
-                    histogram_min, histogram_max, trace_id, span_id
+                    histogram_min, histogram_max, trace_id, span_id, raw_tags
                 )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
                 "#,
             )
             .bind(metric.id())
@@ -82,6 +92,7 @@ impl MetricsRepository for TimescaleMetricsRepository {
             .bind(histogram_max)
             .bind(metric.trace_id())
             .bind(metric.span_id())
+            .bind(&raw_tags_json)
             .execute(&mut *tx)
             .await
             .map_err(|e| MetricsDomainError::InternalError(e.to_string()))?;
@@ -252,7 +263,270 @@ impl MetricsRepository for TimescaleMetricsRepository {
         Ok(MetricQueryResult { metrics, total })
     }
 
-    async fn get_metric_names(&self, project_id: &ProjectId) -> Result<Vec<String>, MetricsDomainError> {
+    async fn query_steps(
+        &self,
+        project_id: &ProjectId,
+        filters: &MetricFilters,
+        range: &StepRange,
+    ) -> Result<Vec<AggregatedMetric>, MetricsDomainError> {
+        // Read the coarsest stored resolution that divides the step, re-aggregating its buckets
+        let rollup = range.rollup();
+        let table = match rollup {
+            RollupInterval::Raw => "metrics",
+            RollupInterval::OneMinute => "metrics_1m",
+            RollupInterval::OneHour => "metrics_1h",
+            RollupInterval::OneDay => "metrics_1d",
+        };
+
+        let (timestamp_col, value_cols) = if rollup == RollupInterval::Raw {
+            (
+                "timestamp",
+                "AVG(value) AS avg_value, MIN(value) AS min_value, MAX(value) AS max_value, \
+                 SUM(value) AS sum_value, COUNT(*)::bigint AS sample_count",
+            )
+        } else {
+            (
+                "bucket",
+                "SUM(sum_value) / NULLIF(SUM(sample_count), 0) AS avg_value, \
+                 MIN(min_value) AS min_value, MAX(max_value) AS max_value, \
+                 SUM(sum_value) AS sum_value, SUM(sample_count)::bigint AS sample_count",
+            )
+        };
+
+        let mut conditions = vec![
+            "project_id = $1".to_string(),
+            format!("{} >= $3", timestamp_col),
+            format!("{} <= $4", timestamp_col),
+        ];
+        let mut param_idx = 6;
+
+        if let Some(ref names) = filters.names
+            && !names.is_empty()
+        {
+            let placeholders: Vec<String> = (0..names.len())
+                .map(|i| format!("${}", param_idx + i))
+                .collect();
+            param_idx += names.len();
+            conditions.push(format!("name IN ({})", placeholders.join(", ")));
+        }
+
+        if filters.trace_id.is_some() && rollup == RollupInterval::Raw {
+            conditions.push(format!("trace_id = ${}", param_idx));
+        }
+
+        // Buckets are aligned to multiples of the step since the epoch on the
+        // wall clock of the range's timezone ($5), matching StepRange
+        let query = format!(
+            r#"
+            SELECT project_id, name, metric_type,
+                   (to_timestamp(floor(extract(epoch FROM {ts} AT TIME ZONE $5)::float8 / $2) * $2)
+                       AT TIME ZONE 'UTC') AT TIME ZONE $5 AS bucket,
+                   {values}
+            FROM {table}
+            WHERE {conditions}
+            GROUP BY project_id, name, metric_type, 4
+            ORDER BY name, bucket
+            "#,
+            ts = timestamp_col,
+            values = value_cols,
+            table = table,
+            conditions = conditions.join(" AND "),
+        );
+
+        let mut sql_query = sqlx::query_as::<_, AggregatedMetricRow>(&query)
+            .bind(project_id.as_str())
+            .bind(range.step_secs() as f64)
+            .bind(range.aligned_start())
+            .bind(range.end())
+            .bind(range.timezone().name());
+
+        if let Some(ref names) = filters.names {
+            for name in names {
+                sql_query = sql_query.bind(name);
+            }
+        }
+
+        if let Some(ref trace_id) = filters.trace_id
+            && rollup == RollupInterval::Raw
+        {
+            sql_query = sql_query.bind(trace_id);
+        }
+
+        let rows: Vec<AggregatedMetricRow> = sql_query
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| MetricsDomainError::InternalError(e.to_string()))?;
+
+        let buckets = rows
+            .into_iter()
+            .map(|row| AggregatedMetric {
+                project_id: row.project_id,
+                name: row.name,
+                metric_type: row.metric_type,
+                bucket: row.bucket,
+                avg_value: row.avg_value.unwrap_or(0.0),
+                min_value: row.min_value.unwrap_or(0.0),
+                max_value: row.max_value.unwrap_or(0.0),
+                sum_value: row.sum_value.unwrap_or(0.0),
+                sample_count: row.sample_count.unwrap_or(0),
+            })
+            .collect();
+
+        Ok(range.fill(buckets))
+    }
+
+    async fn query_step_percentiles(
+        &self,
+        project_id: &ProjectId,
+        filters: &MetricFilters,
+        range: &StepRange,
+        percentile: f64,
+    ) -> Result<Vec<PercentileBucket>, MetricsDomainError> {
+        // Percentiles can't be re-aggregated from rollups, so this always reads raw points
+        let mut conditions = vec![
+            "project_id = $1".to_string(),
+            "timestamp >= $3".to_string(),
+            "timestamp <= $4".to_string(),
+        ];
+        let mut param_idx = 7;
+
+        if let Some(ref names) = filters.names
+            && !names.is_empty()
+        {
+            let placeholders: Vec<String> = (0..names.len())
+                .map(|i| format!("${}", param_idx + i))
+                .collect();
+            param_idx += names.len();
+            conditions.push(format!("name IN ({})", placeholders.join(", ")));
+        }
+
+        if filters.trace_id.is_some() {
+            conditions.push(format!("trace_id = ${}", param_idx));
+        }
+
+        let query = format!(
+            r#"
+            SELECT project_id, name, metric_type,
+                   (to_timestamp(floor(extract(epoch FROM timestamp AT TIME ZONE $5)::float8 / $2) * $2)
+                       AT TIME ZONE 'UTC') AT TIME ZONE $5 AS bucket,
+                   AVG(value) AS avg_value, MIN(value) AS min_value, MAX(value) AS max_value,
+                   SUM(value) AS sum_value, COUNT(*)::bigint AS sample_count,
+                   percentile_cont($6) WITHIN GROUP (ORDER BY value) AS percentile_value
+            FROM metrics
+            WHERE {conditions}
+            GROUP BY project_id, name, metric_type, 4
+            ORDER BY name, bucket
+            "#,
+            conditions = conditions.join(" AND "),
+        );
+
+        let mut sql_query = sqlx::query_as::<_, PercentileBucketRow>(&query)
+            .bind(project_id.as_str())
+            .bind(range.step_secs() as f64)
+            .bind(range.aligned_start())
+            .bind(range.end())
+            .bind(range.timezone().name())
+            .bind(percentile);
+
+        if let Some(ref names) = filters.names {
+            for name in names {
+                sql_query = sql_query.bind(name);
+            }
+        }
+
+        if let Some(ref trace_id) = filters.trace_id {
+            sql_query = sql_query.bind(trace_id);
+        }
+
+        let rows: Vec<PercentileBucketRow> = sql_query
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| MetricsDomainError::InternalError(e.to_string()))?;
+
+        let buckets = rows
+            .into_iter()
+            .map(|row| PercentileBucket {
+                metric: AggregatedMetric {
+                    project_id: row.project_id,
+                    name: row.name,
+                    metric_type: row.metric_type,
+                    bucket: row.bucket,
+                    avg_value: row.avg_value.unwrap_or(0.0),
+                    min_value: row.min_value.unwrap_or(0.0),
+                    max_value: row.max_value.unwrap_or(0.0),
+                    sum_value: row.sum_value.unwrap_or(0.0),
+                    sample_count: row.sample_count.unwrap_or(0),
+                },
+                value: row.percentile_value,
+            })
+            .collect();
+
+        Ok(range.fill_percentiles(buckets))
+    }
+
+    async fn find_metric_types(
+        &self,
+        project_id: &ProjectId,
+        names: &[String],
+    ) -> Result<HashMap<String, MetricType>, MetricsDomainError> {
+        if names.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows: Vec<MetricTypeRow> = sqlx::query_as(
+            r#"
+            SELECT name, metric_type
+            FROM metric_types
+            WHERE project_id = $1 AND name = ANY($2)
+            "#,
+        )
+        .bind(project_id.as_str())
+        .bind(names)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| MetricsDomainError::InternalError(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|r| Ok((r.name, MetricType::from_str(&r.metric_type)?)))
+            .collect()
+    }
+
+    async fn register_metric_types(
+        &self,
+        project_id: &ProjectId,
+        types: &[(String, MetricType)],
+    ) -> Result<(), MetricsDomainError> {
+        if types.is_empty() {
+            return Ok(());
+        }
+
+        let (names, metric_types): (Vec<&str>, Vec<&str>) = types
+            .iter()
+            .map(|(name, metric_type)| (name.as_str(), metric_type.as_str()))
+            .unzip();
+
+        sqlx::query(
+            r#"
+            INSERT INTO metric_types (project_id, name, metric_type)
+            SELECT $1, name, metric_type
+            FROM UNNEST($2::text[], $3::text[]) AS t(name, metric_type)
+            ON CONFLICT (project_id, name) DO NOTHING
+            "#,
+        )
+        .bind(project_id.as_str())
+        .bind(&names)
+        .bind(&metric_types)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| MetricsDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get_metric_names(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Vec<String>, MetricsDomainError> {
         let rows: Vec<MetricNameRow> = sqlx::query_as(
             r#"
             SELECT DISTINCT name
@@ -270,19 +544,122 @@ impl MetricsRepository for TimescaleMetricsRepository {
         Ok(rows.into_iter().map(|r| r.name).collect())
     }
 
+    async fn query_labeled_rollups(
+        &self,
+        project_id: &ProjectId,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        rollup: RollupInterval,
+    ) -> Result<Vec<LabeledRollup>, MetricsDomainError> {
+        // The continuous aggregates drop tags, so bucket the raw points
+        let rows: Vec<LabeledRollupRow> = sqlx::query_as(
+            r#"
+            SELECT name, metric_type, tags,
+                   time_bucket(make_interval(secs => $4), timestamp) AS bucket,
+                   AVG(value) AS avg_value,
+                   MIN(value) AS min_value,
+                   MAX(value) AS max_value,
+                   SUM(value) AS sum_value,
+                   COUNT(*) AS sample_count
+            FROM metrics
+            WHERE project_id = $1 AND timestamp >= $2 AND timestamp < $3
+            GROUP BY name, metric_type, tags, bucket
+            ORDER BY name, bucket
+            "#,
+        )
+        .bind(project_id.as_str())
+        .bind(start)
+        .bind(end)
+        .bind(rollup.width_secs() as f64)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| MetricsDomainError::InternalError(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| LabeledRollup {
+                name: row.name,
+                metric_type: row.metric_type,
+                labels: row
+                    .tags
+                    .and_then(|t| serde_json::from_value::<BTreeMap<String, String>>(t).ok())
+                    .unwrap_or_default(),
+                bucket: row.bucket,
+                avg_value: row.avg_value.unwrap_or(0.0),
+                min_value: row.min_value.unwrap_or(0.0),
+                max_value: row.max_value.unwrap_or(0.0),
+                sum_value: row.sum_value.unwrap_or(0.0),
+                sample_count: row.sample_count.unwrap_or(0),
+            })
+            .collect())
+    }
+
+    async fn exported_days(
+        &self,
+        project_id: &ProjectId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<NaiveDate>, MetricsDomainError> {
+        sqlx::query_scalar(
+            r#"
+            SELECT day FROM metric_exports
+            WHERE project_id = $1 AND day BETWEEN $2 AND $3
+            "#,
+        )
+        .bind(project_id.as_str())
+        .bind(from)
+        .bind(to)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| MetricsDomainError::InternalError(e.to_string()))
+    }
+
+    async fn record_export(
+        &self,
+        project_id: &ProjectId,
+        day: NaiveDate,
+        object_key: &str,
+    ) -> Result<(), MetricsDomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO metric_exports (project_id, day, object_key)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (project_id, day) DO UPDATE SET
+                object_key = EXCLUDED.object_key,
+                exported_at = NOW()
+            "#,
+        )
+        .bind(project_id.as_str())
+        .bind(day)
+        .bind(object_key)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| MetricsDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
     async fn delete_before(
         &self,
         project_id: &ProjectId,
         before: DateTime<Utc>,
+        limit: u32,
     ) -> Result<u64, MetricsDomainError> {
         let result = sqlx::query(
             r#"
             DELETE FROM metrics
-            WHERE project_id = $1 AND timestamp < $2
+            WHERE project_id = $1 AND (timestamp, id) IN (
+                SELECT timestamp, id
+                FROM metrics
+                WHERE project_id = $1 AND timestamp < $2
+                ORDER BY timestamp
+                LIMIT $3
+            )
             "#,
         )
         .bind(project_id.as_str())
         .bind(before)
+        .bind(limit as i64)
         .execute(self.pool.as_ref())
         .await
         .map_err(|e| MetricsDomainError::InternalError(e.to_string()))?;
@@ -0,0 +1,304 @@
+//! Metrics export module
+//!
+//! This module provides a background task that offloads rolled-up metrics to
+//! Parquet files in object storage for long-term analytics, one file per
+//! project and day.
+
+mod parquet_writer;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::modules::metrics::domain::{MetricsRepository, RollupInterval};
+use crate::modules::projects::domain::{ProjectId, ProjectRepository};
+use crate::shared::ObjectStore;
+
+pub use parquet_writer::encode_rollups;
+
+const PARQUET_CONTENT_TYPE: &str = "application/vnd.apache.parquet";
+
+/// What the export writes and which days it picks up
+#[derive(Debug, Clone)]
+pub struct MetricsExportSettings {
+    /// Bucket width of the exported rollups
+    pub rollup: RollupInterval,
+    /// Days that must pass after a day ends before it is exported, so late
+    /// points have arrived
+    pub min_age_days: u32,
+    /// How many days before the newest eligible day are retried if missing
+    pub lookback_days: u32,
+    /// Key prefix inside the bucket
+    pub prefix: String,
+}
+
+impl MetricsExportSettings {
+    /// Complete days old enough to export as of `now`, oldest first
+    fn due_days(&self, now: DateTime<Utc>) -> Vec<NaiveDate> {
+        let newest = (now - chrono::Duration::days(self.min_age_days as i64)).date_naive()
+            - chrono::Duration::days(1);
+        (0..self.lookback_days.max(1) as i64)
+            .rev()
+            .map(|offset| newest - chrono::Duration::days(offset))
+            .collect()
+    }
+
+    /// Hive-style key partitioned by project and day
+    fn object_key(&self, project_id: &ProjectId, day: NaiveDate) -> String {
+        format!(
+            "{}/project_id={}/date={}/metrics_{}.parquet",
+            self.prefix.trim_end_matches('/'),
+            project_id.as_str(),
+            day.format("%Y-%m-%d"),
+            self.rollup.as_str()
+        )
+    }
+}
+
+/// Export one project's day of rollups. Returns the object key, or `None`
+/// when the day has no metrics.
+async fn export_project_day<MR, OS>(
+    metrics_repo: &MR,
+    store: &OS,
+    settings: &MetricsExportSettings,
+    project_id: &ProjectId,
+    day: NaiveDate,
+) -> Result<Option<String>, String>
+where
+    MR: MetricsRepository + ?Sized,
+    OS: ObjectStore + ?Sized,
+{
+    let start = day.and_hms_opt(0, 0, 0).unwrap().and_utc();
+    let end = start + chrono::Duration::days(1);
+    let rollups = metrics_repo
+        .query_labeled_rollups(project_id, start, end, settings.rollup)
+        .await
+        .map_err(|e| e.to_string())?;
+    if rollups.is_empty() {
+        return Ok(None);
+    }
+
+    let body = encode_rollups(&rollups).map_err(|e| e.to_string())?;
+    let key = settings.object_key(project_id, day);
+    store
+        .put(&key, body, PARQUET_CONTENT_TYPE)
+        .await
+        .map_err(|e| e.to_string())?;
+    metrics_repo
+        .record_export(project_id, day, &key)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(Some(key))
+}
+
+/// Export the project's due days that have not been exported yet. Returns the
+/// number of files written.
+async fn export_project<MR, OS>(
+    metrics_repo: &MR,
+    store: &OS,
+    settings: &MetricsExportSettings,
+    project_id: &ProjectId,
+    now: DateTime<Utc>,
+) -> Result<u32, String>
+where
+    MR: MetricsRepository + ?Sized,
+    OS: ObjectStore + ?Sized,
+{
+    let days = settings.due_days(now);
+    let (Some(first), Some(last)) = (days.first(), days.last()) else {
+        return Ok(0);
+    };
+    let exported: HashSet<NaiveDate> = metrics_repo
+        .exported_days(project_id, *first, *last)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+
+    let mut written = 0;
+    for day in days.into_iter().filter(|d| !exported.contains(d)) {
+        if export_project_day(metrics_repo, store, settings, project_id, day)
+            .await?
+            .is_some()
+        {
+            written += 1;
+        }
+    }
+    Ok(written)
+}
+
+/// Start the metrics export background task.
+/// Each tick writes any due, not yet exported project days to object storage.
+pub async fn start_metrics_export<MR, PR, OS>(
+    metrics_repo: Arc<MR>,
+    project_repo: Arc<PR>,
+    store: Arc<OS>,
+    settings: MetricsExportSettings,
+    interval_secs: u64,
+) where
+    MR: MetricsRepository + 'static,
+    PR: ProjectRepository + 'static,
+    OS: ObjectStore + ?Sized + 'static,
+{
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+    loop {
+        interval.tick().await;
+
+        let projects = match project_repo.find_all_active().await {
+            Ok(p) => p,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to fetch projects for metrics export");
+                continue;
+            }
+        };
+
+        for project in projects {
+            match export_project(
+                metrics_repo.as_ref(),
+                store.as_ref(),
+                &settings,
+                project.id(),
+                Utc::now(),
+            )
+            .await
+            {
+                Ok(written) if written > 0 => {
+                    tracing::info!(
+                        project_id = %project.id().as_str(),
+                        files = written,
+                        "Exported metric rollups"
+                    );
+                }
+                Err(e) => {
+                    tracing::error!(
+                        error = %e,
+                        project_id = %project.id().as_str(),
+                        "Failed to export metric rollups"
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use bytes::Bytes;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::RowAccessor;
+
+    use crate::modules::metrics::domain::{MetricPoint, MetricType};
+    use crate::shared::testing::{InMemoryMetricsRepository, InMemoryObjectStore};
+
+    fn settings() -> MetricsExportSettings {
+        MetricsExportSettings {
+            rollup: RollupInterval::OneHour,
+            min_age_days: 2,
+            lookback_days: 3,
+            prefix: "metrics/".to_string(),
+        }
+    }
+
+    fn point(id: &str, value: f64, timestamp: DateTime<Utc>, route: &str) -> MetricPoint {
+        MetricPoint::new(
+            id.to_string(),
+            ProjectId::new("project-1".to_string()),
+            "http.requests".to_string(),
+            MetricType::Counter,
+            value,
+            timestamp,
+            None,
+            None,
+            HashMap::from([("route".to_string(), route.to_string())]),
+            None,
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_exports_rollups_to_partitioned_parquet() {
+        let now = "2026-03-10T08:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let old_day = "2026-03-07T10:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let recent = "2026-03-09T10:00:00Z".parse::<DateTime<Utc>>().unwrap();
+
+        let metrics_repo = InMemoryMetricsRepository::new();
+        metrics_repo
+            .save_batch(&[
+                point("m1", 1.0, old_day, "/a"),
+                point("m2", 3.0, old_day + chrono::Duration::minutes(30), "/a"),
+                point("m3", 5.0, old_day, "/b"),
+                // Younger than the minimum age
+                point("m4", 7.0, recent, "/a"),
+            ])
+            .await
+            .unwrap();
+        let store = InMemoryObjectStore::new();
+        let project_id = ProjectId::new("project-1".to_string());
+
+        let written = export_project(&metrics_repo, &store, &settings(), &project_id, now)
+            .await
+            .unwrap();
+
+        let key = "metrics/project_id=project-1/date=2026-03-07/metrics_1h.parquet";
+        assert_eq!(written, 1);
+        assert_eq!(store.keys(), vec![key.to_string()]);
+
+        let reader = SerializedFileReader::new(Bytes::from(store.get(key).unwrap())).unwrap();
+        let columns: Vec<String> = reader
+            .metadata()
+            .file_metadata()
+            .schema_descr()
+            .columns()
+            .iter()
+            .map(|c| c.name().to_string())
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                "name",
+                "metric_type",
+                "labels",
+                "timestamp",
+                "avg_value",
+                "min_value",
+                "max_value",
+                "sum_value",
+                "sample_count"
+            ]
+        );
+
+        let rows: Vec<_> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(rows.len(), 2);
+        let route_a = &rows[0];
+        assert_eq!(route_a.get_string(0).unwrap(), "http.requests");
+        assert_eq!(route_a.get_string(2).unwrap(), r#"{"route":"/a"}"#);
+        assert_eq!(
+            route_a.get_timestamp_millis(3).unwrap(),
+            "2026-03-07T10:00:00Z"
+                .parse::<DateTime<Utc>>()
+                .unwrap()
+                .timestamp_millis()
+        );
+        assert_eq!(route_a.get_double(4).unwrap(), 2.0);
+        assert_eq!(route_a.get_double(7).unwrap(), 4.0);
+        assert_eq!(route_a.get_long(8).unwrap(), 2);
+
+        // Already exported days are not written again
+        let written = export_project(&metrics_repo, &store, &settings(), &project_id, now)
+            .await
+            .unwrap();
+        assert_eq!(written, 0);
+    }
+}
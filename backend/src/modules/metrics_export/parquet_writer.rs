@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int64Type};
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::{SerializedColumnWriter, SerializedFileWriter};
+use parquet::schema::parser::parse_message_type;
+
+use crate::modules::metrics::domain::LabeledRollup;
+
+/// One row per rollup bucket and label set. Labels are a JSON object so the
+/// file stays readable by engines without MAP support.
+const ROLLUP_SCHEMA: &str = "
+message metric_rollup {
+    REQUIRED BYTE_ARRAY name (UTF8);
+    REQUIRED BYTE_ARRAY metric_type (UTF8);
+    REQUIRED BYTE_ARRAY labels (JSON);
+    REQUIRED INT64 timestamp (TIMESTAMP(MILLIS,true));
+    REQUIRED DOUBLE avg_value;
+    REQUIRED DOUBLE min_value;
+    REQUIRED DOUBLE max_value;
+    REQUIRED DOUBLE sum_value;
+    REQUIRED INT64 sample_count;
+}
+";
+
+/// Encode rollups as a single row group Parquet file
+pub fn encode_rollups(rollups: &[LabeledRollup]) -> Result<Vec<u8>, ParquetError> {
+    let schema = Arc::new(parse_message_type(ROLLUP_SCHEMA)?);
+    let properties = Arc::new(WriterProperties::builder().build());
+    let mut buffer = Vec::new();
+    let mut writer = SerializedFileWriter::new(&mut buffer, schema, properties)?;
+
+    let mut row_group = writer.next_row_group()?;
+    let mut index = 0;
+    while let Some(mut column) = row_group.next_column()? {
+        match index {
+            0 => write_strings(&mut column, rollups.iter().map(|r| r.name.clone()))?,
+            1 => write_strings(&mut column, rollups.iter().map(|r| r.metric_type.clone()))?,
+            2 => write_strings(
+                &mut column,
+                rollups
+                    .iter()
+                    .map(|r| serde_json::to_string(&r.labels).unwrap_or_else(|_| "{}".to_string())),
+            )?,
+            3 => write_longs(
+                &mut column,
+                rollups.iter().map(|r| r.bucket.timestamp_millis()),
+            )?,
+            4 => write_doubles(&mut column, rollups.iter().map(|r| r.avg_value))?,
+            5 => write_doubles(&mut column, rollups.iter().map(|r| r.min_value))?,
+            6 => write_doubles(&mut column, rollups.iter().map(|r| r.max_value))?,
+            7 => write_doubles(&mut column, rollups.iter().map(|r| r.sum_value))?,
+            8 => write_longs(&mut column, rollups.iter().map(|r| r.sample_count))?,
+            _ => unreachable!("schema has nine columns"),
+        }
+        column.close()?;
+        index += 1;
+    }
+    row_group.close()?;
+    writer.close()?;
+
+    Ok(buffer)
+}
+
+fn write_strings(
+    column: &mut SerializedColumnWriter<'_>,
+    values: impl Iterator<Item = String>,
+) -> Result<(), ParquetError> {
+    let values: Vec<ByteArray> = values.map(|s| ByteArray::from(s.as_str())).collect();
+    column
+        .typed::<ByteArrayType>()
+        .write_batch(&values, None, None)?;
+    Ok(())
+}
+
+fn write_longs(
+    column: &mut SerializedColumnWriter<'_>,
+    values: impl Iterator<Item = i64>,
+) -> Result<(), ParquetError> {
+    let values: Vec<i64> = values.collect();
+    column
+        .typed::<Int64Type>()
+        .write_batch(&values, None, None)?;
+    Ok(())
+}
+
+fn write_doubles(
+    column: &mut SerializedColumnWriter<'_>,
+    values: impl Iterator<Item = f64>,
+) -> Result<(), ParquetError> {
+    let values: Vec<f64> = values.collect();
+    column
+        .typed::<DoubleType>()
+        .write_batch(&values, None, None)?;
+    Ok(())
+}
@@ -1,12 +1,23 @@
+pub mod admin;
 pub mod alerts;
 pub mod auth;
+pub mod cloudwatch;
+pub mod gelf;
+pub mod leader;
 pub mod logging;
+pub mod loki;
 pub mod metrics;
+pub mod metrics_export;
 pub mod organizations;
 pub mod otlp;
 pub mod projects;
+pub mod prometheus;
 pub mod retention;
+pub mod span_metrics;
+pub mod statsd;
+pub mod syslog;
 pub mod traces;
+pub mod zipkin;
 
 pub use alerts::*;
 pub use auth::*;
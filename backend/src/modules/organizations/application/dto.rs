@@ -22,6 +22,12 @@ pub struct CreatePersonalOrgCommand {
 pub struct UpdateOrgCommand {
     pub org_id: String,
     pub name: Option<String>,
+    /// Allowed sign-in methods: "password", "oidc" or "both"
+    pub auth_methods: Option<String>,
+    /// Days activity is kept before pruning, overriding the server default
+    pub activity_retention_days: Option<u32>,
+    /// Replaces the dashboard IP allowlist; empty allows every address
+    pub allowed_ips: Option<Vec<String>>,
     pub requesting_user_id: String,
 }
 
@@ -79,6 +85,8 @@ pub struct SwitchOrgCommand {
     pub org_id: String,
     pub user_id: String,
     pub device_fingerprint: String,
+    /// When the caller's session was authenticated; the new tokens continue it
+    pub session_started_at: i64,
 }
 
 // ==================== Responses ====================
@@ -90,6 +98,10 @@ pub struct OrgResponse {
     pub name: String,
     pub slug: String,
     pub is_personal: bool,
+    pub auth_methods: String,
+    /// None when the server default applies
+    pub activity_retention_days: Option<u32>,
+    pub allowed_ips: Vec<String>,
     pub role: String,
     pub created_at: DateTime<Utc>,
 }
@@ -105,6 +117,24 @@ pub struct MemberResponse {
     pub joined_at: DateTime<Utc>,
 }
 
+/// Seat usage of an organization
+#[derive(Debug, Clone)]
+pub struct SeatUsageResponse {
+    pub max_seats: Option<u32>,
+    pub members: u32,
+    pub pending_invites: u32,
+    pub used: u32,
+    pub available: Option<u32>,
+}
+
+/// A page of organization members
+#[derive(Debug, Clone)]
+pub struct MemberListResponse {
+    pub members: Vec<MemberResponse>,
+    /// Effective page size after applying the default and maximum
+    pub limit: i64,
+}
+
 /// Response for organization switch
 #[derive(Debug, Clone)]
 pub struct SwitchOrgResponse {
@@ -133,6 +163,19 @@ pub struct SendInviteCommand {
     pub org_id: String,
     pub invitee_email: String,
     pub role: String,
+    /// Defaults to 7 days
+    pub expires_in_days: Option<i64>,
+    pub inviter_user_id: String,
+}
+
+/// Command to create a multi-use invite link
+#[derive(Debug, Clone)]
+pub struct CreateInviteLinkCommand {
+    pub org_id: String,
+    pub role: String,
+    pub max_uses: i32,
+    /// Defaults to 7 days
+    pub expires_in_days: Option<i64>,
     pub inviter_user_id: String,
 }
 
@@ -167,9 +210,12 @@ pub struct InviteResponse {
     pub organization_id: String,
     pub organization_name: String,
     pub inviter_email: String,
-    pub invitee_email: String,
+    /// None for invite links
+    pub invitee_email: Option<String>,
     pub role: String,
     pub status: String,
+    pub max_uses: i32,
+    pub remaining_uses: i32,
     pub expires_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
 }
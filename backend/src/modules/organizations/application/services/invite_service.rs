@@ -1,18 +1,60 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 
 use crate::modules::auth::application::ports::IdGenerator;
-use crate::modules::auth::domain::{Email, UserRepository, UserId};
+use crate::modules::auth::domain::{Email, UserId, UserRepository};
 use crate::modules::organizations::application::dto::*;
 use crate::modules::organizations::domain::{
-    ActivityId, ActivityType, InviteId, InviteStatus, MemberId, OrgActivity,
-    OrgActivityRepository, OrgDomainError, OrgId, OrgRole, OrganizationInvite,
-    OrganizationInviteRepository, OrganizationMember, OrganizationMemberRepository,
-    OrganizationRepository,
+    ActivityId, ActivityType, InviteId, InviteStatus, MemberId, OrgActivity, OrgActivityRepository,
+    OrgDomainError, OrgId, OrgRole, OrganizationInvite, OrganizationInviteRepository,
+    OrganizationMember, OrganizationMemberRepository, OrganizationRepository, SeatUsage,
 };
 
+use super::seats::seat_usage;
+
+/// How long an invite stays valid unless the inviter chooses otherwise
+const DEFAULT_INVITE_EXPIRY_DAYS: i64 = 7;
+
+/// Longest an invite may stay valid
+const MAX_INVITE_EXPIRY_DAYS: i64 = 30;
+
+/// Most users a single invite link may admit
+const MAX_INVITE_LINK_USES: i32 = 100;
+
+/// Expiry for an invite created now, valid for `days` (default 7)
+fn invite_expiry(days: Option<i64>) -> Result<DateTime<Utc>, OrgDomainError> {
+    let days = days.unwrap_or(DEFAULT_INVITE_EXPIRY_DAYS);
+    if !(1..=MAX_INVITE_EXPIRY_DAYS).contains(&days) {
+        return Err(OrgDomainError::InvalidInviteOptions(format!(
+            "expiry must be between 1 and {} days",
+            MAX_INVITE_EXPIRY_DAYS
+        )));
+    }
+    Ok(Utc::now() + Duration::days(days))
+}
+
+fn invite_response(
+    invite: &OrganizationInvite,
+    organization_name: String,
+    inviter_email: String,
+) -> InviteResponse {
+    InviteResponse {
+        id: invite.id().to_string(),
+        organization_id: invite.organization_id().to_string(),
+        organization_name,
+        inviter_email,
+        invitee_email: invite.invitee_email().map(String::from),
+        role: invite.role().as_str().to_string(),
+        status: invite.status().as_str().to_string(),
+        max_uses: invite.max_uses(),
+        remaining_uses: invite.remaining_uses(),
+        expires_at: invite.expires_at(),
+        created_at: invite.created_at(),
+    }
+}
+
 /// Service for managing organization invites
 pub struct InviteService<OR, MR, UR, IR, AR, ID>
 where
@@ -59,7 +101,10 @@ where
     }
 
     /// Send an invite to join an organization
-    pub async fn send_invite(&self, cmd: SendInviteCommand) -> Result<InviteResponse, OrgDomainError> {
+    pub async fn send_invite(
+        &self,
+        cmd: SendInviteCommand,
+    ) -> Result<InviteResponse, OrgDomainError> {
         let org_id = OrgId::new(cmd.org_id.clone());
         let inviter_user_id = UserId::new(cmd.inviter_user_id.clone());
 
@@ -88,6 +133,8 @@ where
             return Err(OrgDomainError::InsufficientPermissions);
         }
 
+        let expires_at = invite_expiry(cmd.expires_in_days)?;
+
         // 4. Check invitee email
         let invitee_email = Email::new(cmd.invitee_email.clone())
             .map_err(|_| OrgDomainError::InternalError("Invalid email".to_string()))?;
@@ -138,9 +185,13 @@ where
             return Err(OrgDomainError::InviteAlreadyExists);
         }
 
-        // 10. Create invite (expires in 7 days)
+        // Pending invites hold a seat, so one must be free to invite
+        seat_usage(&org, self.member_repo.as_ref(), self.invite_repo.as_ref())
+            .await?
+            .ensure_seat_available()?;
+
+        // 10. Create invite
         let invite_id = InviteId::new(self.id_generator.generate());
-        let expires_at = Utc::now() + Duration::days(7);
 
         let invite = OrganizationInvite::new(
             invite_id,
@@ -162,26 +213,109 @@ where
             inviter_user_id,
             None,
             Some(HashMap::from([
-                ("invitee_email".to_string(), invitee_email.as_str().to_string()),
+                (
+                    "invitee_email".to_string(),
+                    invitee_email.as_str().to_string(),
+                ),
                 ("role".to_string(), invite_role.as_str().to_string()),
             ])),
         );
         let _ = self.activity_repo.save(&activity).await;
 
-        Ok(InviteResponse {
-            id: invite.id().to_string(),
-            organization_id: invite.organization_id().to_string(),
-            organization_name: org.name().as_str().to_string(),
-            inviter_email: inviter.email().as_str().to_string(),
-            invitee_email: invite.invitee_email().to_string(),
-            role: invite.role().as_str().to_string(),
-            status: invite.status().as_str().to_string(),
-            expires_at: invite.expires_at(),
-            created_at: invite.created_at(),
-        })
+        Ok(invite_response(
+            &invite,
+            org.name().as_str().to_string(),
+            inviter.email().as_str().to_string(),
+        ))
     }
 
-    /// Accept an invite
+    /// Create an invite link admitting up to `max_uses` users with the given role
+    pub async fn create_invite_link(
+        &self,
+        cmd: CreateInviteLinkCommand,
+    ) -> Result<InviteResponse, OrgDomainError> {
+        let org_id = OrgId::new(cmd.org_id.clone());
+        let inviter_user_id = UserId::new(cmd.inviter_user_id.clone());
+
+        // 1. Verify org exists
+        let org = self
+            .org_repo
+            .find_by_id(&org_id)
+            .await?
+            .ok_or(OrgDomainError::OrgNotFound)?;
+
+        // 2. Verify requester is admin/owner
+        let requester_member = self
+            .member_repo
+            .find_by_org_and_user(&org_id, &inviter_user_id)
+            .await?
+            .ok_or(OrgDomainError::NotOrgMember)?;
+
+        let requester_role = OrgRole::from_str(requester_member.role().as_str())?;
+        if !requester_role.can_manage_members() {
+            return Err(OrgDomainError::InsufficientPermissions);
+        }
+
+        // 3. Validate role and options
+        let invite_role = OrgRole::from_str(&cmd.role)?;
+        if matches!(invite_role, OrgRole::Owner) {
+            return Err(OrgDomainError::InsufficientPermissions);
+        }
+        if !(1..=MAX_INVITE_LINK_USES).contains(&cmd.max_uses) {
+            return Err(OrgDomainError::InvalidInviteOptions(format!(
+                "max_uses must be between 1 and {}",
+                MAX_INVITE_LINK_USES
+            )));
+        }
+        let expires_at = invite_expiry(cmd.expires_in_days)?;
+
+        // 4. The link holds a seat for each of its uses
+        seat_usage(&org, self.member_repo.as_ref(), self.invite_repo.as_ref())
+            .await?
+            .ensure_seats_available(cmd.max_uses as u32)?;
+
+        let inviter = self
+            .user_repo
+            .find_by_id(&inviter_user_id)
+            .await
+            .map_err(|e| OrgDomainError::InternalError(e.to_string()))?
+            .ok_or(OrgDomainError::UserNotFound)?;
+
+        // 5. Create the link
+        let invite = OrganizationInvite::new_link(
+            InviteId::new(self.id_generator.generate()),
+            cmd.org_id.clone(),
+            cmd.inviter_user_id.clone(),
+            invite_role,
+            cmd.max_uses,
+            expires_at,
+        );
+        self.invite_repo.save(&invite).await?;
+
+        // 6. Log activity
+        let activity = OrgActivity::new(
+            ActivityId::new(self.id_generator.generate()),
+            org.id().clone(),
+            ActivityType::InviteSent,
+            inviter_user_id,
+            None,
+            Some(HashMap::from([
+                ("max_uses".to_string(), cmd.max_uses.to_string()),
+                ("role".to_string(), invite_role.as_str().to_string()),
+            ])),
+        );
+        let _ = self.activity_repo.save(&activity).await;
+
+        Ok(invite_response(
+            &invite,
+            org.name().as_str().to_string(),
+            inviter.email().as_str().to_string(),
+        ))
+    }
+
+    /// Accept an invite, or join through an invite link. Accepting is
+    /// idempotent: a user who is already a member gets success, so a retried
+    /// or double-submitted accept neither errors nor uses the invite twice.
     pub async fn accept_invite(&self, cmd: AcceptInviteCommand) -> Result<(), OrgDomainError> {
         // 1. Find invite
         let invite_id = InviteId::new(cmd.invite_id);
@@ -199,36 +333,57 @@ where
             .map_err(|e| OrgDomainError::InternalError(e.to_string()))?
             .ok_or(OrgDomainError::UserNotFound)?;
 
-        if invite.invitee_id() != Some(user.id().as_str()) && invite.invitee_email() != user.email().as_str() {
+        // Invite links admit anyone holding them
+        if !invite.is_link()
+            && invite.invitee_id() != Some(user.id().as_str())
+            && invite.invitee_email() != Some(user.email().as_str())
+        {
             return Err(OrgDomainError::InsufficientPermissions);
         }
 
-        // 3. Check invite status
+        // 3. Already a member (e.g. a repeated accept): nothing left to do.
+        // A personal invite is done with, a link keeps its uses.
+        let invite_org_id = OrgId::new(invite.organization_id().to_string());
+        let existing_member = self
+            .member_repo
+            .find_by_org_and_user(&invite_org_id, user.id())
+            .await?;
+        if existing_member.is_some() {
+            if !invite.is_link() && invite.status() == InviteStatus::Pending {
+                invite.accept();
+                self.invite_repo.update(&invite).await?;
+            }
+            return Ok(());
+        }
+
+        // 4. Check invite status (a used-up link is accepted)
         if invite.status() != InviteStatus::Pending {
             return Err(OrgDomainError::InviteAlreadyProcessed);
         }
 
-        // 4. Check if expired
+        // 5. Check if expired
         if invite.is_expired() {
             invite.mark_expired();
             self.invite_repo.update(&invite).await?;
             return Err(OrgDomainError::InviteExpired);
         }
 
-        // 5. Check if already a member
-        let invite_org_id = OrgId::new(invite.organization_id().to_string());
-        let existing_member = self
-            .member_repo
-            .find_by_org_and_user(&invite_org_id, user.id())
-            .await?;
-        if existing_member.is_some() {
-            // Already a member, just mark invite as accepted
-            invite.accept();
-            self.invite_repo.update(&invite).await?;
-            return Err(OrgDomainError::AlreadyMember);
+        // 6. Enforce the seat cap. This invite already holds one of the counted seats,
+        // so accepting only fails if the cap was lowered after it was sent.
+        let org = self
+            .org_repo
+            .find_by_id(&invite_org_id)
+            .await?
+            .ok_or(OrgDomainError::OrgNotFound)?;
+        let usage = seat_usage(&org, self.member_repo.as_ref(), self.invite_repo.as_ref()).await?;
+        SeatUsage {
+            pending_invites: usage.pending_invites.saturating_sub(1),
+            ..usage
         }
+        .ensure_seat_available()?;
 
-        // 6. Create membership
+        // 7. Use the invite (single-use invites and used-up links are accepted)
+        // and create the membership with its role, together so neither applies alone
         let member_id = MemberId::new(self.id_generator.generate());
         let member = OrganizationMember::new(
             member_id,
@@ -236,11 +391,11 @@ where
             user.id().clone(),
             invite.role(),
         );
-        self.member_repo.save(&member).await?;
-
-        // 7. Mark invite as accepted
-        invite.accept();
-        self.invite_repo.update(&invite).await?;
+        invite.record_use();
+        if !self.invite_repo.accept(&invite, &member).await? {
+            // A concurrent accept added the membership first
+            return Ok(());
+        }
 
         // 8. Log activity
         let activity = OrgActivity::new(
@@ -249,9 +404,10 @@ where
             ActivityType::InviteAccepted,
             user.id().clone(),
             None,
-            Some(HashMap::from([
-                ("role".to_string(), invite.role().as_str().to_string()),
-            ])),
+            Some(HashMap::from([(
+                "role".to_string(),
+                invite.role().as_str().to_string(),
+            )])),
         );
         let _ = self.activity_repo.save(&activity).await;
 
@@ -276,7 +432,9 @@ where
             .map_err(|e| OrgDomainError::InternalError(e.to_string()))?
             .ok_or(OrgDomainError::UserNotFound)?;
 
-        if invite.invitee_id() != Some(user.id().as_str()) && invite.invitee_email() != user.email().as_str() {
+        if invite.invitee_id() != Some(user.id().as_str())
+            && invite.invitee_email() != Some(user.email().as_str())
+        {
             return Err(OrgDomainError::InsufficientPermissions);
         }
 
@@ -383,24 +541,21 @@ where
                 .map(|u| u.email().as_str().to_string())
                 .unwrap_or_else(|| "unknown".to_string());
 
-            responses.push(InviteResponse {
-                id: invite.id().to_string(),
-                organization_id: invite.organization_id().to_string(),
-                organization_name: org.name().as_str().to_string(),
+            responses.push(invite_response(
+                &invite,
+                org.name().as_str().to_string(),
                 inviter_email,
-                invitee_email: invite.invitee_email().to_string(),
-                role: invite.role().as_str().to_string(),
-                status: invite.status().as_str().to_string(),
-                expires_at: invite.expires_at(),
-                created_at: invite.created_at(),
-            });
+            ));
         }
 
         Ok(responses)
     }
 
     /// List pending invites for the current user
-    pub async fn list_user_invites(&self, user_id: &str) -> Result<Vec<InviteResponse>, OrgDomainError> {
+    pub async fn list_user_invites(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<InviteResponse>, OrgDomainError> {
         let invites = self.invite_repo.list_pending_by_user(user_id).await?;
 
         let mut responses = Vec::new();
@@ -426,24 +581,17 @@ where
                 .map(|u| u.email().as_str().to_string())
                 .unwrap_or_else(|| "unknown".to_string());
 
-            responses.push(InviteResponse {
-                id: invite.id().to_string(),
-                organization_id: invite.organization_id().to_string(),
-                organization_name: org_name,
-                inviter_email,
-                invitee_email: invite.invitee_email().to_string(),
-                role: invite.role().as_str().to_string(),
-                status: invite.status().as_str().to_string(),
-                expires_at: invite.expires_at(),
-                created_at: invite.created_at(),
-            });
+            responses.push(invite_response(&invite, org_name, inviter_email));
         }
 
         Ok(responses)
     }
 
     /// Get count of pending invites for user (for badge)
-    pub async fn count_user_invites(&self, user_id: &str) -> Result<InviteCountResponse, OrgDomainError> {
+    pub async fn count_user_invites(
+        &self,
+        user_id: &str,
+    ) -> Result<InviteCountResponse, OrgDomainError> {
         let count = self.invite_repo.count_pending_by_user(user_id).await?;
         Ok(InviteCountResponse { count })
     }
@@ -1,5 +1,6 @@
 mod invite_service;
 mod org_service;
+mod seats;
 
 pub use invite_service::InviteService;
 pub use org_service::OrgService;
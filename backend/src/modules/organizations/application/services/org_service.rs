@@ -4,16 +4,19 @@ use std::sync::Arc;
 use rand::Rng;
 
 use crate::modules::auth::application::ports::{IdGenerator, OrgContext, TokenService};
-use crate::modules::auth::domain::{AuthDomainError, Email, UserRepository, UserId};
+use crate::modules::auth::domain::{AuthDomainError, Email, UserId, UserRepository};
 use crate::modules::organizations::application::dto::*;
 use crate::modules::organizations::domain::{
-    ActivityId, ActivityType, MemberId, OrgActivity, OrgActivityRepository, OrgDomainError,
-    OrgId, OrgName, OrgRole, OrgSlug, Organization, OrganizationMember,
-    OrganizationMemberRepository, OrganizationRepository,
+    ActivityId, ActivityType, AuthMethods, MemberId, OrgActivity, OrgActivityRepository,
+    OrgDomainError, OrgId, OrgName, OrgRole, OrgSlug, Organization, OrganizationInviteRepository,
+    OrganizationMember, OrganizationMemberRepository, OrganizationRepository,
 };
+use crate::shared::PaginationConfig;
+
+use super::seats::seat_usage;
 
 /// Organization service - orchestrates all organization use cases
-pub struct OrgService<OR, MR, UR, TS, ID, AR>
+pub struct OrgService<OR, MR, UR, TS, ID, AR, IR>
 where
     OR: OrganizationRepository,
     MR: OrganizationMemberRepository,
@@ -21,6 +24,7 @@ where
     TS: TokenService,
     ID: IdGenerator,
     AR: OrgActivityRepository,
+    IR: OrganizationInviteRepository,
 {
     org_repo: Arc<OR>,
     member_repo: Arc<MR>,
@@ -28,9 +32,11 @@ where
     token_service: Arc<TS>,
     id_generator: Arc<ID>,
     activity_repo: Arc<AR>,
+    invite_repo: Arc<IR>,
+    pagination: PaginationConfig,
 }
 
-impl<OR, MR, UR, TS, ID, AR> OrgService<OR, MR, UR, TS, ID, AR>
+impl<OR, MR, UR, TS, ID, AR, IR> OrgService<OR, MR, UR, TS, ID, AR, IR>
 where
     OR: OrganizationRepository,
     MR: OrganizationMemberRepository,
@@ -38,7 +44,9 @@ where
     TS: TokenService,
     ID: IdGenerator,
     AR: OrgActivityRepository,
+    IR: OrganizationInviteRepository,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         org_repo: Arc<OR>,
         member_repo: Arc<MR>,
@@ -46,6 +54,8 @@ where
         token_service: Arc<TS>,
         id_generator: Arc<ID>,
         activity_repo: Arc<AR>,
+        invite_repo: Arc<IR>,
+        pagination: PaginationConfig,
     ) -> Self {
         Self {
             org_repo,
@@ -54,6 +64,8 @@ where
             token_service,
             id_generator,
             activity_repo,
+            invite_repo,
+            pagination,
         }
     }
 
@@ -88,12 +100,8 @@ where
         // 5. Create membership as owner
         let user_id = UserId::new(cmd.user_id);
         let member_id = MemberId::new(self.id_generator.generate());
-        let mut member = OrganizationMember::new(
-            member_id,
-            org_id.clone(),
-            user_id.clone(),
-            OrgRole::Owner,
-        );
+        let mut member =
+            OrganizationMember::new(member_id, org_id.clone(), user_id.clone(), OrgRole::Owner);
         member.touch_last_accessed();
         self.member_repo.save(&member).await?;
 
@@ -113,6 +121,9 @@ where
             name: org.name().as_str().to_string(),
             slug: org.slug().as_str().to_string(),
             is_personal: org.is_personal(),
+            auth_methods: org.auth_methods().as_str().to_string(),
+            activity_retention_days: org.activity_retention_days(),
+            allowed_ips: org.allowed_ips().to_vec(),
             role: OrgRole::Owner.as_str().to_string(),
             created_at: org.created_at(),
         })
@@ -141,12 +152,8 @@ where
         // 5. Create membership as owner
         let user_id = UserId::new(cmd.user_id);
         let member_id = MemberId::new(self.id_generator.generate());
-        let mut member = OrganizationMember::new(
-            member_id,
-            org_id.clone(),
-            user_id,
-            OrgRole::Owner,
-        );
+        let mut member =
+            OrganizationMember::new(member_id, org_id.clone(), user_id, OrgRole::Owner);
         member.touch_last_accessed();
         self.member_repo.save(&member).await?;
 
@@ -160,13 +167,20 @@ where
 
         let mut orgs = Vec::new();
         for membership in memberships {
-            if let Some(org) = self.org_repo.find_by_id(membership.organization_id()).await? {
+            if let Some(org) = self
+                .org_repo
+                .find_by_id(membership.organization_id())
+                .await?
+            {
                 if !org.is_deleted() {
                     orgs.push(OrgResponse {
                         id: org.id().as_str().to_string(),
                         name: org.name().as_str().to_string(),
                         slug: org.slug().as_str().to_string(),
                         is_personal: org.is_personal(),
+                        auth_methods: org.auth_methods().as_str().to_string(),
+                        activity_retention_days: org.activity_retention_days(),
+                        allowed_ips: org.allowed_ips().to_vec(),
                         role: membership.role().as_str().to_string(),
                         created_at: org.created_at(),
                     });
@@ -209,6 +223,9 @@ where
             name: org.name().as_str().to_string(),
             slug: org.slug().as_str().to_string(),
             is_personal: org.is_personal(),
+            auth_methods: org.auth_methods().as_str().to_string(),
+            activity_retention_days: org.activity_retention_days(),
+            allowed_ips: org.allowed_ips().to_vec(),
             role: membership.role().as_str().to_string(),
             created_at: org.created_at(),
         })
@@ -265,11 +282,32 @@ where
             let _ = self.activity_repo.save(&activity).await;
         }
 
+        // 4. Update allowed sign-in methods if provided
+        if let Some(methods) = cmd.auth_methods {
+            org.set_auth_methods(AuthMethods::from_str(&methods)?)?;
+            self.org_repo.save(&org).await?;
+        }
+
+        // 5. Update activity retention if provided
+        if let Some(days) = cmd.activity_retention_days {
+            org.set_activity_retention_days(days)?;
+            self.org_repo.save(&org).await?;
+        }
+
+        // 6. Replace the dashboard IP allowlist if provided
+        if let Some(allowed_ips) = cmd.allowed_ips {
+            org.set_allowed_ips(allowed_ips)?;
+            self.org_repo.save(&org).await?;
+        }
+
         Ok(OrgResponse {
             id: org.id().as_str().to_string(),
             name: org.name().as_str().to_string(),
             slug: org.slug().as_str().to_string(),
             is_personal: org.is_personal(),
+            auth_methods: org.auth_methods().as_str().to_string(),
+            activity_retention_days: org.activity_retention_days(),
+            allowed_ips: org.allowed_ips().to_vec(),
             role: membership.role().as_str().to_string(),
             created_at: org.created_at(),
         })
@@ -306,7 +344,10 @@ where
     }
 
     /// Add a member to an organization (admin+ only)
-    pub async fn add_member(&self, cmd: AddMemberCommand) -> Result<MemberResponse, OrgDomainError> {
+    pub async fn add_member(
+        &self,
+        cmd: AddMemberCommand,
+    ) -> Result<MemberResponse, OrgDomainError> {
         let org_id = OrgId::new(cmd.org_id);
         let requesting_user_id = UserId::new(cmd.requesting_user_id);
 
@@ -339,8 +380,7 @@ where
         }
 
         // 3. Find user by email
-        let email = Email::new(cmd.email.clone())
-            .map_err(|_| OrgDomainError::UserNotFound)?;
+        let email = Email::new(cmd.email.clone()).map_err(|_| OrgDomainError::UserNotFound)?;
         let user = self
             .user_repo
             .find_by_email(&email)
@@ -360,12 +400,18 @@ where
             return Err(OrgDomainError::AlreadyMember);
         }
 
-        // 5. Create membership
+        // 5. Enforce the seat cap (pending invites count as taken seats)
+        seat_usage(&org, self.member_repo.as_ref(), self.invite_repo.as_ref())
+            .await?
+            .ensure_seat_available()?;
+
+        // 6. Create membership
         let member_id = MemberId::new(self.id_generator.generate());
-        let member = OrganizationMember::new(member_id, org_id.clone(), target_user_id.clone(), new_role);
+        let member =
+            OrganizationMember::new(member_id, org_id.clone(), target_user_id.clone(), new_role);
         self.member_repo.save(&member).await?;
 
-        // 6. Log activity
+        // 7. Log activity
         let activity = OrgActivity::new(
             ActivityId::new(self.id_generator.generate()),
             org_id,
@@ -386,12 +432,52 @@ where
         })
     }
 
-    /// List members of an organization
+    /// Seat usage of an organization (any member)
+    pub async fn get_seat_usage(
+        &self,
+        org_id: &str,
+        requesting_user_id: &str,
+    ) -> Result<SeatUsageResponse, OrgDomainError> {
+        let org_id = OrgId::new(org_id.to_string());
+        let user_id = UserId::new(requesting_user_id.to_string());
+
+        // 1. Verify organization exists
+        let org = self
+            .org_repo
+            .find_by_id(&org_id)
+            .await?
+            .ok_or(OrgDomainError::OrgNotFound)?;
+
+        if org.is_deleted() {
+            return Err(OrgDomainError::OrgNotFound);
+        }
+
+        // 2. Verify requester is a member
+        self.member_repo
+            .find_by_org_and_user(&org_id, &user_id)
+            .await?
+            .ok_or(OrgDomainError::NotOrgMember)?;
+
+        // 3. Count members and pending invites
+        let usage = seat_usage(&org, self.member_repo.as_ref(), self.invite_repo.as_ref()).await?;
+
+        Ok(SeatUsageResponse {
+            max_seats: usage.max_seats,
+            members: usage.members,
+            pending_invites: usage.pending_invites,
+            used: usage.used(),
+            available: usage.available(),
+        })
+    }
+
+    /// List members of an organization (paginated)
     pub async fn list_members(
         &self,
         org_id: &str,
         requesting_user_id: &str,
-    ) -> Result<Vec<MemberResponse>, OrgDomainError> {
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<MemberListResponse, OrgDomainError> {
         let org_id = OrgId::new(org_id.to_string());
         let user_id = UserId::new(requesting_user_id.to_string());
 
@@ -412,8 +498,12 @@ where
             .await?
             .ok_or(OrgDomainError::NotOrgMember)?;
 
-        // 3. Get all members
-        let memberships = self.member_repo.find_all_by_org(&org_id).await?;
+        // 3. Get the requested page of members
+        let pagination = self.pagination.resolve(limit, offset);
+        let memberships = self
+            .member_repo
+            .find_page_by_org(&org_id, &pagination)
+            .await?;
 
         let mut members = Vec::new();
         for membership in memberships {
@@ -435,7 +525,10 @@ where
             }
         }
 
-        Ok(members)
+        Ok(MemberListResponse {
+            members,
+            limit: pagination.limit,
+        })
     }
 
     /// Update a member's role (owner only)
@@ -645,7 +738,10 @@ where
 
         // 5. Log activity for new owner promotion
         let mut metadata = HashMap::new();
-        metadata.insert("old_role".to_string(), old_new_owner_role.as_str().to_string());
+        metadata.insert(
+            "old_role".to_string(),
+            old_new_owner_role.as_str().to_string(),
+        );
         metadata.insert("new_role".to_string(), OrgRole::Owner.as_str().to_string());
         let activity = OrgActivity::new(
             ActivityId::new(self.id_generator.generate()),
@@ -707,6 +803,9 @@ where
                 name: org.name().as_str().to_string(),
                 slug: org.slug().as_str().to_string(),
                 is_personal: org.is_personal(),
+                auth_methods: org.auth_methods().as_str().to_string(),
+                activity_retention_days: org.activity_retention_days(),
+                allowed_ips: org.allowed_ips().to_vec(),
                 role: requester_membership.role().as_str().to_string(),
                 created_at: org.created_at(),
             },
@@ -751,7 +850,8 @@ where
             .map_err(|e| OrgDomainError::InternalError(e.to_string()))?
             .ok_or(OrgDomainError::UserNotFound)?;
 
-        // 5. Generate new tokens with org context
+        // 5. Generate new tokens with org context, continuing the caller's
+        //    session so switching can't outlive its maximum lifetime
         let org_context = Some(OrgContext {
             org_id: org.id().as_str().to_string(),
             org_role: membership.role().as_str().to_string(),
@@ -759,9 +859,17 @@ where
 
         let token_pair = self
             .token_service
-            .generate_token_pair(&user_id, user.email().as_str(), org_context)
+            .refresh_token_pair(
+                &user_id,
+                user.email().as_str(),
+                org_context,
+                cmd.session_started_at,
+            )
             .await
-            .map_err(|e| OrgDomainError::InternalError(e.to_string()))?;
+            .map_err(|e| match e {
+                AuthDomainError::TokenExpired => OrgDomainError::SessionExpired,
+                e => OrgDomainError::InternalError(e.to_string()),
+            })?;
 
         Ok(SwitchOrgResponse {
             access_token: token_pair.access_token,
@@ -772,6 +880,9 @@ where
                 name: org.name().as_str().to_string(),
                 slug: org.slug().as_str().to_string(),
                 is_personal: org.is_personal(),
+                auth_methods: org.auth_methods().as_str().to_string(),
+                activity_retention_days: org.activity_retention_days(),
+                allowed_ips: org.allowed_ips().to_vec(),
                 role: membership.role().as_str().to_string(),
                 created_at: org.created_at(),
             },
@@ -786,9 +897,17 @@ where
         let user_id = UserId::new(user_id.to_string());
 
         // Try last accessed first
-        if let Some(membership) = self.member_repo.find_last_accessed_by_user(&user_id).await? {
+        if let Some(membership) = self
+            .member_repo
+            .find_last_accessed_by_user(&user_id)
+            .await?
+        {
             // Verify org still exists and not deleted
-            if let Some(org) = self.org_repo.find_by_id(membership.organization_id()).await? {
+            if let Some(org) = self
+                .org_repo
+                .find_by_id(membership.organization_id())
+                .await?
+            {
                 if !org.is_deleted() {
                     return Ok(Some((
                         OrgId::new(org.id().as_str().to_string()),
@@ -799,7 +918,11 @@ where
         }
 
         // Fall back to personal org
-        if let Some(membership) = self.member_repo.find_personal_org_membership(&user_id).await? {
+        if let Some(membership) = self
+            .member_repo
+            .find_personal_org_membership(&user_id)
+            .await?
+        {
             return Ok(Some((
                 OrgId::new(membership.organization_id().as_str().to_string()),
                 *membership.role(),
@@ -827,7 +950,10 @@ where
             .ok_or(OrgDomainError::NotOrgMember)?;
 
         // 2. Get activities
-        let activities = self.activity_repo.find_by_org(&org_id, limit, offset).await?;
+        let activities = self
+            .activity_repo
+            .find_by_org(&org_id, limit, offset)
+            .await?;
 
         // 3. Map to responses (look up emails for actor and target)
         let mut responses = Vec::new();
@@ -868,3 +994,350 @@ where
         Ok(responses)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    use crate::modules::auth::infrastructure::services::{JwtConfig, JwtTokenService};
+    use crate::modules::organizations::application::services::InviteService;
+    use crate::shared::testing::{
+        InMemoryActivityRepository, InMemoryInviteRepository, InMemoryMemberRepository,
+        InMemoryOrganizationRepository, InMemoryUserRepository, SequentialIdGenerator,
+    };
+
+    type TestOrgService = OrgService<
+        InMemoryOrganizationRepository,
+        InMemoryMemberRepository,
+        InMemoryUserRepository,
+        JwtTokenService,
+        SequentialIdGenerator,
+        InMemoryActivityRepository,
+        InMemoryInviteRepository,
+    >;
+
+    type TestInviteService = InviteService<
+        InMemoryOrganizationRepository,
+        InMemoryMemberRepository,
+        InMemoryUserRepository,
+        InMemoryInviteRepository,
+        InMemoryActivityRepository,
+        SequentialIdGenerator,
+    >;
+
+    /// org-1 capped at two seats, owned by "owner", with alice, bob and carol registered
+    async fn services_with_seat_limit() -> (TestOrgService, TestInviteService) {
+        services(Some(2)).await
+    }
+
+    /// org-1 with the given seat cap, owned by "owner", with alice, bob and carol registered
+    async fn services(max_seats: Option<u32>) -> (TestOrgService, TestInviteService) {
+        let org_repo = Arc::new(InMemoryOrganizationRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        let user_repo = Arc::new(InMemoryUserRepository::new());
+        let invite_repo = Arc::new(InMemoryInviteRepository::with_members(member_repo.clone()));
+        let activity_repo = Arc::new(InMemoryActivityRepository::new());
+        let id_generator = Arc::new(SequentialIdGenerator::new());
+
+        let name = OrgName::new("Acme".to_string()).unwrap();
+        let slug = OrgSlug::generate(&name, "abcd");
+        let now = Utc::now();
+        org_repo
+            .save(&Organization::reconstruct(
+                OrgId::new("org-1".to_string()),
+                name,
+                slug,
+                false,
+                max_seats,
+                AuthMethods::default(),
+                None,
+                Vec::new(),
+                now,
+                now,
+                None,
+            ))
+            .await
+            .unwrap();
+        member_repo.seed("org-1", "owner", OrgRole::Owner);
+        user_repo.seed("owner", "owner@example.com");
+        user_repo.seed("alice", "alice@example.com");
+        user_repo.seed("bob", "bob@example.com");
+        user_repo.seed("carol", "carol@example.com");
+
+        let org_service = OrgService::new(
+            org_repo.clone(),
+            member_repo.clone(),
+            user_repo.clone(),
+            Arc::new(JwtTokenService::new(JwtConfig::new(
+                "access".to_string(),
+                "refresh".to_string(),
+                900,
+                604800,
+            ))),
+            id_generator.clone(),
+            activity_repo.clone(),
+            invite_repo.clone(),
+            PaginationConfig::default(),
+        );
+        let invite_service = InviteService::new(
+            org_repo,
+            member_repo,
+            user_repo,
+            invite_repo,
+            activity_repo,
+            id_generator,
+        );
+        (org_service, invite_service)
+    }
+
+    fn add_member(email: &str) -> AddMemberCommand {
+        AddMemberCommand {
+            org_id: "org-1".to_string(),
+            email: email.to_string(),
+            role: "member".to_string(),
+            requesting_user_id: "owner".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_member_beyond_max_seats_is_rejected() {
+        let (org_service, _) = services_with_seat_limit().await;
+
+        org_service
+            .add_member(add_member("alice@example.com"))
+            .await
+            .unwrap();
+        let result = org_service.add_member(add_member("bob@example.com")).await;
+
+        assert!(matches!(result, Err(OrgDomainError::SeatLimitReached(2))));
+        let usage = org_service.get_seat_usage("org-1", "owner").await.unwrap();
+        assert_eq!(usage.used, 2);
+        assert_eq!(usage.available, Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_cancelling_pending_invite_frees_a_seat() {
+        let (org_service, invite_service) = services_with_seat_limit().await;
+
+        let invite = invite_service
+            .send_invite(SendInviteCommand {
+                org_id: "org-1".to_string(),
+                invitee_email: "carol@example.com".to_string(),
+                role: "member".to_string(),
+                expires_in_days: None,
+                inviter_user_id: "owner".to_string(),
+            })
+            .await
+            .unwrap();
+        assert!(matches!(
+            org_service
+                .add_member(add_member("alice@example.com"))
+                .await,
+            Err(OrgDomainError::SeatLimitReached(2))
+        ));
+
+        invite_service
+            .cancel_invite(CancelInviteCommand {
+                org_id: "org-1".to_string(),
+                invite_id: invite.id,
+                requesting_user_id: "owner".to_string(),
+            })
+            .await
+            .unwrap();
+
+        assert!(
+            org_service
+                .add_member(add_member("alice@example.com"))
+                .await
+                .is_ok()
+        );
+    }
+
+    fn accept(invite_id: &str, user_id: &str) -> AcceptInviteCommand {
+        AcceptInviteCommand {
+            invite_id: invite_id.to_string(),
+            user_id: user_id.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invite_link_admits_up_to_its_cap_with_its_role() {
+        let (org_service, invite_service) = services(None).await;
+
+        let link = invite_service
+            .create_invite_link(CreateInviteLinkCommand {
+                org_id: "org-1".to_string(),
+                role: "admin".to_string(),
+                max_uses: 2,
+                expires_in_days: Some(1),
+                inviter_user_id: "owner".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(link.invitee_email, None);
+        assert_eq!(link.remaining_uses, 2);
+
+        invite_service
+            .accept_invite(accept(&link.id, "alice"))
+            .await
+            .unwrap();
+        // A second accept by the same user succeeds without using up the link
+        invite_service
+            .accept_invite(accept(&link.id, "alice"))
+            .await
+            .unwrap();
+        invite_service
+            .accept_invite(accept(&link.id, "bob"))
+            .await
+            .unwrap();
+        assert_eq!(
+            invite_service
+                .accept_invite(accept(&link.id, "carol"))
+                .await,
+            Err(OrgDomainError::InviteAlreadyProcessed)
+        );
+
+        let members = org_service
+            .list_members("org-1", "owner", None, None)
+            .await
+            .unwrap();
+        let mut joined: Vec<_> = members
+            .members
+            .iter()
+            .filter(|m| m.role == "admin")
+            .map(|m| m.user_id.as_str())
+            .collect();
+        joined.sort();
+        assert_eq!(joined, vec!["alice", "bob"]);
+    }
+
+    #[tokio::test]
+    async fn test_single_use_invite_is_consumed_on_first_accept() {
+        let (_, invite_service) = services(None).await;
+
+        let invite = invite_service
+            .send_invite(SendInviteCommand {
+                org_id: "org-1".to_string(),
+                invitee_email: "carol@example.com".to_string(),
+                role: "member".to_string(),
+                expires_in_days: Some(14),
+                inviter_user_id: "owner".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(invite.max_uses, 1);
+        assert!(invite.expires_at > Utc::now() + chrono::Duration::days(13));
+
+        invite_service
+            .accept_invite(accept(&invite.id, "carol"))
+            .await
+            .unwrap();
+        assert!(
+            invite_service
+                .list_org_invites("org-1", "owner")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_double_accept_succeeds_with_one_membership() {
+        let (org_service, invite_service) = services(None).await;
+
+        let invite = invite_service
+            .send_invite(SendInviteCommand {
+                org_id: "org-1".to_string(),
+                invitee_email: "carol@example.com".to_string(),
+                role: "member".to_string(),
+                expires_in_days: None,
+                inviter_user_id: "owner".to_string(),
+            })
+            .await
+            .unwrap();
+
+        invite_service
+            .accept_invite(accept(&invite.id, "carol"))
+            .await
+            .unwrap();
+        invite_service
+            .accept_invite(accept(&invite.id, "carol"))
+            .await
+            .unwrap();
+
+        let members = org_service
+            .list_members("org-1", "owner", None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            members
+                .members
+                .iter()
+                .filter(|m| m.user_id == "carol")
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invite_options_are_validated() {
+        let (_, invite_service) = services_with_seat_limit().await;
+        let link = |max_uses, expires_in_days| CreateInviteLinkCommand {
+            org_id: "org-1".to_string(),
+            role: "member".to_string(),
+            max_uses,
+            expires_in_days,
+            inviter_user_id: "owner".to_string(),
+        };
+
+        assert!(matches!(
+            invite_service.create_invite_link(link(0, None)).await,
+            Err(OrgDomainError::InvalidInviteOptions(_))
+        ));
+        assert!(matches!(
+            invite_service.create_invite_link(link(1, Some(365))).await,
+            Err(OrgDomainError::InvalidInviteOptions(_))
+        ));
+        // Two uses would need two free seats; only one is left
+        assert_eq!(
+            invite_service
+                .create_invite_link(link(2, None))
+                .await
+                .map(|_| ()),
+            Err(OrgDomainError::SeatLimitReached(2))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_switch_org_does_not_extend_the_session() {
+        let (org_service, _) = services(None).await;
+        let switch = |session_started_at| SwitchOrgCommand {
+            org_id: "org-1".to_string(),
+            user_id: "owner".to_string(),
+            device_fingerprint: "fp".to_string(),
+            session_started_at,
+        };
+        let tokens = JwtTokenService::new(JwtConfig::new(
+            "access".to_string(),
+            "refresh".to_string(),
+            900,
+            604800,
+        ));
+
+        let started = Utc::now().timestamp() - 3600;
+        let switched = org_service.switch_org(switch(started)).await.unwrap();
+        let claims = tokens
+            .validate_access_token(&switched.access_token)
+            .unwrap();
+        assert_eq!(claims.auth_time, started);
+        assert_eq!(claims.org_id.as_deref(), Some("org-1"));
+
+        // Past the maximum lifetime, switching can't mint a fresh session
+        let expired = Utc::now().timestamp() - 604800 - 60;
+        assert_eq!(
+            org_service.switch_org(switch(expired)).await.map(|_| ()),
+            Err(OrgDomainError::SessionExpired)
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use crate::modules::organizations::domain::{
+    OrgDomainError, Organization, OrganizationInviteRepository, OrganizationMemberRepository,
+    SeatUsage,
+};
+
+/// Count an organization's members and pending invites against its seat cap
+pub(crate) async fn seat_usage<MR, IR>(
+    org: &Organization,
+    member_repo: &MR,
+    invite_repo: &IR,
+) -> Result<SeatUsage, OrgDomainError>
+where
+    MR: OrganizationMemberRepository,
+    IR: OrganizationInviteRepository,
+{
+    let members = member_repo.find_all_by_org(org.id()).await?.len() as u32;
+    let pending_invites = invite_repo.count_pending_by_org(org.id().as_str()).await? as u32;
+
+    Ok(SeatUsage {
+        members,
+        pending_invites,
+        max_seats: org.max_seats(),
+    })
+}
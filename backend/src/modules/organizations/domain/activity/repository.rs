@@ -1,6 +1,8 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 use super::entity::OrgActivity;
+use super::value_objects::ActivityType;
 use crate::modules::organizations::domain::errors::OrgDomainError;
 use crate::modules::organizations::domain::organization::OrgId;
 
@@ -17,4 +19,13 @@ pub trait OrgActivityRepository: Send + Sync {
         limit: i64,
         offset: i64,
     ) -> Result<Vec<OrgActivity>, OrgDomainError>;
+
+    /// Delete an organization's activities created before `before`, except
+    /// those of the `keep` types. Returns the number deleted.
+    async fn delete_before(
+        &self,
+        org_id: &OrgId,
+        before: DateTime<Utc>,
+        keep: &[ActivityType],
+    ) -> Result<u64, OrgDomainError>;
 }
@@ -38,6 +38,13 @@ pub enum ActivityType {
 }
 
 impl ActivityType {
+    /// Membership and role changes, kept for the longer security retention window
+    pub const SECURITY_RELEVANT: [ActivityType; 3] = [
+        Self::MemberAdded,
+        Self::MemberRemoved,
+        Self::MemberRoleChanged,
+    ];
+
     pub fn from_str(s: &str) -> Result<Self, OrgDomainError> {
         match s {
             "org_created" => Ok(Self::OrgCreated),
@@ -100,7 +107,10 @@ mod tests {
         assert_eq!(ActivityType::OrgCreated.as_str(), "org_created");
         assert_eq!(ActivityType::MemberAdded.as_str(), "member_added");
         assert_eq!(ActivityType::MemberRemoved.as_str(), "member_removed");
-        assert_eq!(ActivityType::MemberRoleChanged.as_str(), "member_role_changed");
+        assert_eq!(
+            ActivityType::MemberRoleChanged.as_str(),
+            "member_role_changed"
+        );
         assert_eq!(ActivityType::OrgNameChanged.as_str(), "org_name_changed");
     }
 }
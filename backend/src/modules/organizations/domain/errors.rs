@@ -8,6 +8,10 @@ pub enum OrgDomainError {
     InvalidRole(String),
     InvalidActivityType(String),
     InvalidInviteStatus(String),
+    InvalidInviteOptions(String),
+    InvalidAuthMethods(String),
+    InvalidActivityRetention(String),
+    InvalidAllowedIps(String),
 
     // Organization errors
     OrgNotFound,
@@ -26,6 +30,9 @@ pub enum OrgDomainError {
     CannotRemoveLastOwner,
     CannotLeaveAsLastOwner,
     CannotDemoteLastOwner,
+    SeatLimitReached(u32),
+    IpNotAllowed(String),
+    SessionExpired,
 
     // Invite errors
     InviteNotFound,
@@ -47,6 +54,10 @@ impl fmt::Display for OrgDomainError {
             Self::InvalidRole(msg) => write!(f, "Invalid role: {}", msg),
             Self::InvalidActivityType(msg) => write!(f, "Invalid activity type: {}", msg),
             Self::InvalidInviteStatus(msg) => write!(f, "Invalid invite status: {}", msg),
+            Self::InvalidInviteOptions(msg) => write!(f, "Invalid invite options: {}", msg),
+            Self::InvalidAuthMethods(msg) => write!(f, "Invalid auth methods: {}", msg),
+            Self::InvalidActivityRetention(msg) => write!(f, "Invalid activity retention: {}", msg),
+            Self::InvalidAllowedIps(msg) => write!(f, "Invalid allowed IPs: {}", msg),
             Self::OrgNotFound => write!(f, "Organization not found"),
             Self::OrgAlreadyExists => write!(f, "Organization already exists"),
             Self::SlugTaken => write!(f, "Organization slug is already taken"),
@@ -56,9 +67,26 @@ impl fmt::Display for OrgDomainError {
             Self::AlreadyMember => write!(f, "User is already a member of this organization"),
             Self::UserNotFound => write!(f, "User not found"),
             Self::InsufficientPermissions => write!(f, "Insufficient permissions for this action"),
-            Self::CannotRemoveLastOwner => write!(f, "Cannot remove the last owner of the organization"),
-            Self::CannotLeaveAsLastOwner => write!(f, "Cannot leave as the last owner of the organization"),
-            Self::CannotDemoteLastOwner => write!(f, "Cannot demote the last owner of the organization"),
+            Self::CannotRemoveLastOwner => {
+                write!(f, "Cannot remove the last owner of the organization")
+            }
+            Self::CannotLeaveAsLastOwner => {
+                write!(f, "Cannot leave as the last owner of the organization")
+            }
+            Self::CannotDemoteLastOwner => {
+                write!(f, "Cannot demote the last owner of the organization")
+            }
+            Self::SeatLimitReached(max) => write!(
+                f,
+                "Organization has reached its limit of {} seats (members and pending invites)",
+                max
+            ),
+            Self::IpNotAllowed(ip) => write!(
+                f,
+                "Access from {} is not allowed by the organization's IP allowlist",
+                ip
+            ),
+            Self::SessionExpired => write!(f, "Session has reached its maximum lifetime"),
             Self::InviteNotFound => write!(f, "Invite not found"),
             Self::InviteAlreadyExists => write!(f, "An invite already exists for this user"),
             Self::InviteExpired => write!(f, "Invite has expired"),
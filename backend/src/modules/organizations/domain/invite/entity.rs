@@ -3,16 +3,20 @@ use chrono::{DateTime, Utc};
 use super::value_objects::{InviteId, InviteStatus};
 use crate::modules::organizations::domain::OrgRole;
 
-/// Represents a pending organization membership invitation
+/// Represents a pending organization membership invitation. Invites sent to an
+/// email are single-use; invite links have no invitee and admit anyone holding
+/// the link, up to `max_uses` times.
 #[derive(Debug, Clone)]
 pub struct OrganizationInvite {
     id: InviteId,
     organization_id: String,
     inviter_id: String,
-    invitee_email: String,
+    invitee_email: Option<String>,
     invitee_id: Option<String>,
     role: OrgRole,
     status: InviteStatus,
+    max_uses: i32,
+    uses: i32,
     expires_at: DateTime<Utc>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
@@ -34,10 +38,38 @@ impl OrganizationInvite {
             id,
             organization_id,
             inviter_id,
-            invitee_email,
+            invitee_email: Some(invitee_email),
             invitee_id,
             role,
             status: InviteStatus::Pending,
+            max_uses: 1,
+            uses: 0,
+            expires_at,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Create a new pending invite link admitting up to `max_uses` users
+    pub fn new_link(
+        id: InviteId,
+        organization_id: String,
+        inviter_id: String,
+        role: OrgRole,
+        max_uses: i32,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id,
+            organization_id,
+            inviter_id,
+            invitee_email: None,
+            invitee_id: None,
+            role,
+            status: InviteStatus::Pending,
+            max_uses,
+            uses: 0,
             expires_at,
             created_at: now,
             updated_at: now,
@@ -45,14 +77,17 @@ impl OrganizationInvite {
     }
 
     /// Reconstruct from persistence
+    #[allow(clippy::too_many_arguments)]
     pub fn reconstruct(
         id: InviteId,
         organization_id: String,
         inviter_id: String,
-        invitee_email: String,
+        invitee_email: Option<String>,
         invitee_id: Option<String>,
         role: OrgRole,
         status: InviteStatus,
+        max_uses: i32,
+        uses: i32,
         expires_at: DateTime<Utc>,
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
@@ -65,6 +100,8 @@ impl OrganizationInvite {
             invitee_id,
             role,
             status,
+            max_uses,
+            uses,
             expires_at,
             created_at,
             updated_at,
@@ -84,8 +121,8 @@ impl OrganizationInvite {
         &self.inviter_id
     }
 
-    pub fn invitee_email(&self) -> &str {
-        &self.invitee_email
+    pub fn invitee_email(&self) -> Option<&str> {
+        self.invitee_email.as_deref()
     }
 
     pub fn invitee_id(&self) -> Option<&str> {
@@ -100,6 +137,23 @@ impl OrganizationInvite {
         self.status
     }
 
+    pub fn max_uses(&self) -> i32 {
+        self.max_uses
+    }
+
+    pub fn uses(&self) -> i32 {
+        self.uses
+    }
+
+    pub fn remaining_uses(&self) -> i32 {
+        (self.max_uses - self.uses).max(0)
+    }
+
+    /// Whether this is an invite link rather than an invite to one person
+    pub fn is_link(&self) -> bool {
+        self.invitee_email.is_none()
+    }
+
     pub fn expires_at(&self) -> DateTime<Utc> {
         self.expires_at
     }
@@ -123,6 +177,15 @@ impl OrganizationInvite {
         self.updated_at = Utc::now();
     }
 
+    /// Use the invite once; it is accepted once no uses remain
+    pub fn record_use(&mut self) {
+        self.uses += 1;
+        if self.uses >= self.max_uses {
+            self.status = InviteStatus::Accepted;
+        }
+        self.updated_at = Utc::now();
+    }
+
     /// Decline the invite
     pub fn decline(&mut self) {
         self.status = InviteStatus::Declined;
@@ -2,7 +2,7 @@ use async_trait::async_trait;
 
 use super::entity::OrganizationInvite;
 use super::value_objects::InviteId;
-use crate::modules::organizations::domain::OrgDomainError;
+use crate::modules::organizations::domain::{OrgDomainError, OrganizationMember};
 
 #[async_trait]
 pub trait OrganizationInviteRepository: Send + Sync {
@@ -12,8 +12,18 @@ pub trait OrganizationInviteRepository: Send + Sync {
     /// Update an existing invite
     async fn update(&self, invite: &OrganizationInvite) -> Result<(), OrgDomainError>;
 
+    /// Create the membership an invite grants and save the used invite in one
+    /// transaction. Returns false, changing nothing, if the user is already a
+    /// member (e.g. a concurrent accept of the same invite won the race).
+    async fn accept(
+        &self,
+        invite: &OrganizationInvite,
+        member: &OrganizationMember,
+    ) -> Result<bool, OrgDomainError>;
+
     /// Find invite by ID
-    async fn find_by_id(&self, id: &InviteId) -> Result<Option<OrganizationInvite>, OrgDomainError>;
+    async fn find_by_id(&self, id: &InviteId)
+    -> Result<Option<OrganizationInvite>, OrgDomainError>;
 
     /// Find pending invite for org + email combination
     async fn find_pending_by_org_and_email(
@@ -28,6 +38,10 @@ pub trait OrganizationInviteRepository: Send + Sync {
         org_id: &str,
     ) -> Result<Vec<OrganizationInvite>, OrgDomainError>;
 
+    /// Count seats held by pending, unexpired invites for an organization: one per
+    /// invite, or one per remaining use of an invite link
+    async fn count_pending_by_org(&self, org_id: &str) -> Result<i64, OrgDomainError>;
+
     /// List pending invites for a user (by user_id)
     async fn list_pending_by_user(
         &self,
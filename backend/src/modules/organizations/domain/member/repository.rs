@@ -4,12 +4,14 @@ use super::entity::OrganizationMember;
 use crate::modules::auth::domain::UserId;
 use crate::modules::organizations::domain::errors::OrgDomainError;
 use crate::modules::organizations::domain::organization::{MemberId, OrgId};
+use crate::shared::Pagination;
 
 /// Repository trait for OrganizationMember persistence
 #[async_trait]
 pub trait OrganizationMemberRepository: Send + Sync {
     /// Find membership by ID
-    async fn find_by_id(&self, id: &MemberId) -> Result<Option<OrganizationMember>, OrgDomainError>;
+    async fn find_by_id(&self, id: &MemberId)
+    -> Result<Option<OrganizationMember>, OrgDomainError>;
 
     /// Find membership by organization and user
     async fn find_by_org_and_user(
@@ -24,6 +26,13 @@ pub trait OrganizationMemberRepository: Send + Sync {
         org_id: &OrgId,
     ) -> Result<Vec<OrganizationMember>, OrgDomainError>;
 
+    /// Find one page of an organization's memberships, oldest first
+    async fn find_page_by_org(
+        &self,
+        org_id: &OrgId,
+        pagination: &Pagination,
+    ) -> Result<Vec<OrganizationMember>, OrgDomainError>;
+
     /// Find all memberships for a user
     async fn find_all_by_user(
         &self,
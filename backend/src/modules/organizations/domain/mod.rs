@@ -1,5 +1,5 @@
-mod errors;
 pub mod activity;
+mod errors;
 pub mod invite;
 pub mod member;
 pub mod organization;
@@ -9,5 +9,6 @@ pub use errors::OrgDomainError;
 pub use invite::{InviteId, InviteStatus, OrganizationInvite, OrganizationInviteRepository};
 pub use member::{OrganizationMember, OrganizationMemberRepository};
 pub use organization::{
-    MemberId, OrgId, OrgName, OrgRole, OrgSlug, Organization, OrganizationRepository,
+    AuthMethods, MemberId, OrgId, OrgName, OrgRole, OrgSlug, Organization, OrganizationRepository,
+    SeatUsage,
 };
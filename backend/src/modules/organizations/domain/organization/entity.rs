@@ -1,7 +1,13 @@
 use chrono::{DateTime, Utc};
+use ipnet::IpNet;
+use std::net::IpAddr;
 
-use super::value_objects::{OrgId, OrgName, OrgSlug};
+use super::value_objects::{AuthMethods, OrgId, OrgName, OrgSlug};
 use crate::modules::organizations::domain::errors::OrgDomainError;
+use crate::modules::projects::domain::parse_network;
+
+/// Longest per-organization activity retention
+const MAX_ACTIVITY_RETENTION_DAYS: u32 = 3650;
 
 /// Organization - aggregate root
 #[derive(Debug, Clone)]
@@ -10,6 +16,14 @@ pub struct Organization {
     name: OrgName,
     slug: OrgSlug,
     is_personal: bool,
+    /// Seat cap from the organization's plan; None means unlimited
+    max_seats: Option<u32>,
+    /// Sign-in methods members may use; personal orgs always allow both
+    auth_methods: AuthMethods,
+    /// Days activity is kept before pruning; None uses the server default
+    activity_retention_days: Option<u32>,
+    /// CIDR ranges members may use the dashboard from; empty allows every address
+    allowed_ips: Vec<String>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
     deleted_at: Option<DateTime<Utc>>,
@@ -24,6 +38,10 @@ impl Organization {
             name,
             slug,
             is_personal: false,
+            max_seats: None,
+            auth_methods: AuthMethods::default(),
+            activity_retention_days: None,
+            allowed_ips: Vec::new(),
             created_at: now,
             updated_at: now,
             deleted_at: None,
@@ -38,6 +56,10 @@ impl Organization {
             name,
             slug,
             is_personal: true,
+            max_seats: None,
+            auth_methods: AuthMethods::default(),
+            activity_retention_days: None,
+            allowed_ips: Vec::new(),
             created_at: now,
             updated_at: now,
             deleted_at: None,
@@ -45,11 +67,16 @@ impl Organization {
     }
 
     /// Reconstruct from persistence layer
+    #[allow(clippy::too_many_arguments)]
     pub fn reconstruct(
         id: OrgId,
         name: OrgName,
         slug: OrgSlug,
         is_personal: bool,
+        max_seats: Option<u32>,
+        auth_methods: AuthMethods,
+        activity_retention_days: Option<u32>,
+        allowed_ips: Vec<String>,
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
         deleted_at: Option<DateTime<Utc>>,
@@ -59,6 +86,10 @@ impl Organization {
             name,
             slug,
             is_personal,
+            max_seats,
+            auth_methods,
+            activity_retention_days,
+            allowed_ips,
             created_at,
             updated_at,
             deleted_at,
@@ -82,6 +113,43 @@ impl Organization {
         self.is_personal
     }
 
+    pub fn max_seats(&self) -> Option<u32> {
+        self.max_seats
+    }
+
+    pub fn auth_methods(&self) -> AuthMethods {
+        self.auth_methods
+    }
+
+    pub fn activity_retention_days(&self) -> Option<u32> {
+        self.activity_retention_days
+    }
+
+    pub fn allowed_ips(&self) -> &[String] {
+        &self.allowed_ips
+    }
+
+    /// Ensure a member's request from `source` is allowed by the dashboard
+    /// IP allowlist. An empty allowlist allows every source; an unknown
+    /// source is only allowed then.
+    pub fn ensure_ip_allowed(&self, source: Option<IpAddr>) -> Result<(), OrgDomainError> {
+        let networks: Vec<IpNet> = self
+            .allowed_ips
+            .iter()
+            .filter_map(|entry| parse_network(entry))
+            .collect();
+        if networks.is_empty() {
+            return Ok(());
+        }
+        match source {
+            Some(ip) if networks.iter().any(|net| net.contains(&ip)) => Ok(()),
+            Some(ip) => Err(OrgDomainError::IpNotAllowed(ip.to_string())),
+            None => Err(OrgDomainError::IpNotAllowed(
+                "an unknown address".to_string(),
+            )),
+        }
+    }
+
     pub fn created_at(&self) -> DateTime<Utc> {
         self.created_at
     }
@@ -106,6 +174,52 @@ impl Organization {
         self.updated_at = Utc::now();
     }
 
+    /// Keep activity for `days` instead of the server default
+    pub fn set_activity_retention_days(&mut self, days: u32) -> Result<(), OrgDomainError> {
+        if !(1..=MAX_ACTIVITY_RETENTION_DAYS).contains(&days) {
+            return Err(OrgDomainError::InvalidActivityRetention(format!(
+                "must be between 1 and {} days",
+                MAX_ACTIVITY_RETENTION_DAYS
+            )));
+        }
+        self.activity_retention_days = Some(days);
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Restrict dashboard access to the given CIDR ranges or single
+    /// addresses; an empty list lifts the restriction
+    pub fn set_allowed_ips(&mut self, allowed_ips: Vec<String>) -> Result<(), OrgDomainError> {
+        if let Some(entry) = allowed_ips
+            .iter()
+            .find(|entry| parse_network(entry).is_none())
+        {
+            return Err(OrgDomainError::InvalidAllowedIps(format!(
+                "'{}' is not a CIDR range like 203.0.113.0/24 or a single address",
+                entry
+            )));
+        }
+        self.allowed_ips = allowed_ips
+            .into_iter()
+            .map(|entry| entry.trim().to_string())
+            .collect();
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
+    /// Restrict which sign-in methods members may use. Personal orgs have no
+    /// SSO to fall back on, so they always allow both.
+    pub fn set_auth_methods(&mut self, methods: AuthMethods) -> Result<(), OrgDomainError> {
+        if self.is_personal && methods != AuthMethods::Both {
+            return Err(OrgDomainError::InvalidAuthMethods(
+                "personal organizations must allow password sign-in".to_string(),
+            ));
+        }
+        self.auth_methods = methods;
+        self.updated_at = Utc::now();
+        Ok(())
+    }
+
     /// Soft delete the organization
     pub fn soft_delete(&mut self) -> Result<(), OrgDomainError> {
         if self.is_personal {
@@ -183,6 +297,23 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_personal_org_keeps_password_sign_in() {
+        let id = OrgId::new("org-456".to_string());
+        let name = OrgName::new("john".to_string()).unwrap();
+        let slug = OrgSlug::generate(&name, "efgh");
+        let mut personal = Organization::new_personal(id, name, slug);
+        assert!(matches!(
+            personal.set_auth_methods(AuthMethods::Oidc),
+            Err(OrgDomainError::InvalidAuthMethods(_))
+        ));
+
+        let mut org = create_test_org();
+        org.set_auth_methods(AuthMethods::Oidc).unwrap();
+        assert!(!org.auth_methods().allows_password());
+        assert!(org.auth_methods().allows_oidc());
+    }
+
     #[test]
     fn test_update_name() {
         let mut org = create_test_org();
@@ -195,4 +326,23 @@ mod tests {
         assert_eq!(org.name().as_str(), "Updated Org");
         assert!(org.updated_at() >= old_updated_at);
     }
+
+    #[test]
+    fn test_allowed_ips_are_validated() {
+        let mut org = create_test_org();
+
+        assert!(matches!(
+            org.set_allowed_ips(vec!["10.0.0.0/33".to_string()]),
+            Err(OrgDomainError::InvalidAllowedIps(_))
+        ));
+        org.set_allowed_ips(vec![" 10.0.0.0/8".to_string(), "2001:db8::1".to_string()])
+            .unwrap();
+
+        assert_eq!(org.allowed_ips(), ["10.0.0.0/8", "2001:db8::1"]);
+        assert!(
+            org.ensure_ip_allowed(Some("10.1.2.3".parse().unwrap()))
+                .is_ok()
+        );
+        assert!(org.ensure_ip_allowed(None).is_err());
+    }
 }
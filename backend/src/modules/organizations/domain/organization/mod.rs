@@ -4,4 +4,4 @@ mod value_objects;
 
 pub use entity::Organization;
 pub use repository::OrganizationRepository;
-pub use value_objects::{MemberId, OrgId, OrgName, OrgRole, OrgSlug};
+pub use value_objects::{AuthMethods, MemberId, OrgId, OrgName, OrgRole, OrgSlug, SeatUsage};
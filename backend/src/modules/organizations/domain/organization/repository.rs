@@ -16,6 +16,9 @@ pub trait OrganizationRepository: Send + Sync {
     /// Save organization (insert or update)
     async fn save(&self, org: &Organization) -> Result<(), OrgDomainError>;
 
+    /// Find all active (non-deleted) organizations
+    async fn find_all_active(&self) -> Result<Vec<Organization>, OrgDomainError>;
+
     /// Check if slug exists (for uniqueness validation)
     async fn slug_exists(&self, slug: &str) -> Result<bool, OrgDomainError>;
 }
@@ -129,10 +129,7 @@ impl OrgRole {
             "owner" => Ok(Self::Owner),
             "admin" => Ok(Self::Admin),
             "member" => Ok(Self::Member),
-            _ => Err(OrgDomainError::InvalidRole(format!(
-                "unknown role: {}",
-                s
-            ))),
+            _ => Err(OrgDomainError::InvalidRole(format!("unknown role: {}", s))),
         }
     }
 
@@ -165,6 +162,54 @@ impl OrgRole {
     }
 }
 
+/// Which sign-in methods an organization allows its members to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AuthMethods {
+    Password,
+    Oidc,
+    #[default]
+    Both,
+}
+
+impl AuthMethods {
+    pub fn from_str(s: &str) -> Result<Self, OrgDomainError> {
+        match s.to_lowercase().as_str() {
+            "password" => Ok(Self::Password),
+            "oidc" | "sso" => Ok(Self::Oidc),
+            "both" => Ok(Self::Both),
+            _ => Err(OrgDomainError::InvalidAuthMethods(format!(
+                "unknown auth methods '{}': use password, oidc or both",
+                s
+            ))),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Password => "password",
+            Self::Oidc => "oidc",
+            Self::Both => "both",
+        }
+    }
+
+    pub fn allows_password(&self) -> bool {
+        matches!(self, Self::Password | Self::Both)
+    }
+
+    pub fn allows_oidc(&self) -> bool {
+        matches!(self, Self::Oidc | Self::Both)
+    }
+
+    /// Whether a sign-in using `method` (password or OIDC) is allowed
+    pub fn permits(&self, method: AuthMethods) -> bool {
+        match method {
+            Self::Password => self.allows_password(),
+            Self::Oidc => self.allows_oidc(),
+            Self::Both => *self == Self::Both,
+        }
+    }
+}
+
 /// Member ID - wrapper around UUID string
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct MemberId(String);
@@ -189,6 +234,39 @@ impl From<String> for MemberId {
     }
 }
 
+/// Seats taken in an organization. Pending invites hold a seat until they
+/// are accepted, declined or cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeatUsage {
+    pub members: u32,
+    pub pending_invites: u32,
+    pub max_seats: Option<u32>,
+}
+
+impl SeatUsage {
+    pub fn used(&self) -> u32 {
+        self.members + self.pending_invites
+    }
+
+    /// Seats left, or None when the organization is unlimited
+    pub fn available(&self) -> Option<u32> {
+        self.max_seats.map(|max| max.saturating_sub(self.used()))
+    }
+
+    /// Fail unless one more seat can be taken
+    pub fn ensure_seat_available(&self) -> Result<(), OrgDomainError> {
+        self.ensure_seats_available(1)
+    }
+
+    /// Fail unless `count` more seats can be taken
+    pub fn ensure_seats_available(&self, count: u32) -> Result<(), OrgDomainError> {
+        match self.max_seats {
+            Some(max) if self.used() + count > max => Err(OrgDomainError::SeatLimitReached(max)),
+            _ => Ok(()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,22 +1,27 @@
+// Handlers are generic over every repository the organization service uses
+#![allow(clippy::type_complexity)]
+
 use axum::{
-    extract::{Path, Query, State},
-    http::{header::USER_AGENT, HeaderMap, StatusCode},
     Extension, Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode, header::USER_AGENT},
 };
-use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::modules::auth::application::ports::{IdGenerator, TokenService};
-use crate::modules::auth::domain::UserRepository;
-use crate::modules::auth::infrastructure::http::extractors::AuthClaims;
+use crate::modules::auth::domain::{TokenScope, UserRepository};
+use crate::modules::auth::infrastructure::http::extractors::{AuthClaims, MissingScope};
 use crate::modules::organizations::application::dto::*;
 use crate::modules::organizations::application::services::OrgService;
 use crate::modules::organizations::domain::{
-    OrgActivityRepository, OrgDomainError, OrganizationMemberRepository, OrganizationRepository,
+    OrgActivityRepository, OrgDomainError, OrganizationInviteRepository,
+    OrganizationMemberRepository, OrganizationRepository,
 };
+use crate::shared::PAGINATION_LIMIT_HEADER;
 
 // ============================================================================
 // Request/Response DTOs for HTTP layer
@@ -30,6 +35,10 @@ pub struct CreateOrgRequest {
 #[derive(Debug, Deserialize)]
 pub struct UpdateOrgRequest {
     pub name: Option<String>,
+    pub auth_methods: Option<String>,
+    pub activity_retention_days: Option<u32>,
+    /// Replaces the dashboard IP allowlist; an empty list allows every address
+    pub allowed_ips: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -54,6 +63,9 @@ pub struct OrgResponseDto {
     pub name: String,
     pub slug: String,
     pub is_personal: bool,
+    pub auth_methods: String,
+    pub activity_retention_days: Option<u32>,
+    pub allowed_ips: Vec<String>,
     pub role: String,
     pub created_at: DateTime<Utc>,
 }
@@ -68,6 +80,16 @@ pub struct MemberResponseDto {
     pub joined_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct SeatUsageResponseDto {
+    /// Null when the organization has no seat cap
+    pub max_seats: Option<u32>,
+    pub members: u32,
+    pub pending_invites: u32,
+    pub used: u32,
+    pub available: Option<u32>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct SwitchOrgResponseDto {
     pub access_token: String,
@@ -90,6 +112,9 @@ impl From<OrgResponse> for OrgResponseDto {
             name: r.name,
             slug: r.slug,
             is_personal: r.is_personal,
+            auth_methods: r.auth_methods,
+            activity_retention_days: r.activity_retention_days,
+            allowed_ips: r.allowed_ips,
             role: r.role,
             created_at: r.created_at,
         }
@@ -109,6 +134,18 @@ impl From<MemberResponse> for MemberResponseDto {
     }
 }
 
+impl From<SeatUsageResponse> for SeatUsageResponseDto {
+    fn from(r: SeatUsageResponse) -> Self {
+        Self {
+            max_seats: r.max_seats,
+            members: r.members,
+            pending_invites: r.pending_invites,
+            used: r.used,
+            available: r.available,
+        }
+    }
+}
+
 impl From<SwitchOrgResponse> for SwitchOrgResponseDto {
     fn from(r: SwitchOrgResponse) -> Self {
         Self {
@@ -132,6 +169,10 @@ fn to_error_response(e: OrgDomainError) -> (StatusCode, Json<ErrorResponse>) {
         | OrgDomainError::InvalidRole(_)
         | OrgDomainError::InvalidActivityType(_)
         | OrgDomainError::InvalidInviteStatus(_)
+        | OrgDomainError::InvalidInviteOptions(_)
+        | OrgDomainError::InvalidAuthMethods(_)
+        | OrgDomainError::InvalidActivityRetention(_)
+        | OrgDomainError::InvalidAllowedIps(_)
         | OrgDomainError::CannotInviteSelf => (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
@@ -179,6 +220,27 @@ fn to_error_response(e: OrgDomainError) -> (StatusCode, Json<ErrorResponse>) {
                 code: "FORBIDDEN".to_string(),
             }),
         ),
+        OrgDomainError::IpNotAllowed(_) => (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "IP_NOT_ALLOWED".to_string(),
+            }),
+        ),
+        OrgDomainError::SessionExpired => (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "TOKEN_EXPIRED".to_string(),
+            }),
+        ),
+        OrgDomainError::SeatLimitReached(_) => (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "SEAT_LIMIT_REACHED".to_string(),
+            }),
+        ),
         OrgDomainError::CannotDeletePersonalOrg
         | OrgDomainError::CannotRemoveLastOwner
         | OrgDomainError::CannotLeaveAsLastOwner
@@ -244,13 +306,29 @@ fn generate_device_fingerprint(headers: &HeaderMap) -> String {
     format!("{:x}", hasher.finalize())
 }
 
+fn insufficient_scope(message: &str) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::FORBIDDEN,
+        Json(ErrorResponse {
+            error: message.to_string(),
+            code: MissingScope::CODE.to_string(),
+        }),
+    )
+}
+
+impl From<MissingScope> for (StatusCode, Json<ErrorResponse>) {
+    fn from(err: MissingScope) -> Self {
+        insufficient_scope(&err.to_string())
+    }
+}
+
 // ============================================================================
 // Handlers
 // ============================================================================
 
 /// POST /api/orgs
-pub async fn create_org<OR, MR, UR, TS, ID, AR>(
-    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR>>>,
+pub async fn create_org<OR, MR, UR, TS, ID, AR, IR>(
+    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR, IR>>>,
     Extension(claims): Extension<AuthClaims>,
     Json(req): Json<CreateOrgRequest>,
 ) -> Result<(StatusCode, Json<OrgResponseDto>), (StatusCode, Json<ErrorResponse>)>
@@ -261,7 +339,10 @@ where
     TS: TokenService,
     ID: IdGenerator,
     AR: OrgActivityRepository,
+    IR: OrganizationInviteRepository,
 {
+    claims.require_scope(TokenScope::OrgsWrite)?;
+
     let cmd = CreateOrgCommand {
         name: req.name,
         user_id: claims.user_id,
@@ -275,8 +356,8 @@ where
 }
 
 /// GET /api/orgs
-pub async fn list_orgs<OR, MR, UR, TS, ID, AR>(
-    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR>>>,
+pub async fn list_orgs<OR, MR, UR, TS, ID, AR, IR>(
+    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR, IR>>>,
     Extension(claims): Extension<AuthClaims>,
 ) -> Result<Json<Vec<OrgResponseDto>>, (StatusCode, Json<ErrorResponse>)>
 where
@@ -286,7 +367,10 @@ where
     TS: TokenService,
     ID: IdGenerator,
     AR: OrgActivityRepository,
+    IR: OrganizationInviteRepository,
 {
+    claims.require_scope(TokenScope::OrgsRead)?;
+
     org_service
         .list_user_orgs(&claims.user_id)
         .await
@@ -295,8 +379,8 @@ where
 }
 
 /// GET /api/orgs/:id
-pub async fn get_org<OR, MR, UR, TS, ID, AR>(
-    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR>>>,
+pub async fn get_org<OR, MR, UR, TS, ID, AR, IR>(
+    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR, IR>>>,
     Extension(claims): Extension<AuthClaims>,
     Path(org_id): Path<String>,
 ) -> Result<Json<OrgResponseDto>, (StatusCode, Json<ErrorResponse>)>
@@ -307,7 +391,10 @@ where
     TS: TokenService,
     ID: IdGenerator,
     AR: OrgActivityRepository,
+    IR: OrganizationInviteRepository,
 {
+    claims.require_scope(TokenScope::OrgsRead)?;
+
     org_service
         .get_org(&org_id, &claims.user_id)
         .await
@@ -316,8 +403,8 @@ where
 }
 
 /// PATCH /api/orgs/:id
-pub async fn update_org<OR, MR, UR, TS, ID, AR>(
-    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR>>>,
+pub async fn update_org<OR, MR, UR, TS, ID, AR, IR>(
+    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR, IR>>>,
     Extension(claims): Extension<AuthClaims>,
     Path(org_id): Path<String>,
     Json(req): Json<UpdateOrgRequest>,
@@ -329,10 +416,16 @@ where
     TS: TokenService,
     ID: IdGenerator,
     AR: OrgActivityRepository,
+    IR: OrganizationInviteRepository,
 {
+    claims.require_scope(TokenScope::OrgsWrite)?;
+
     let cmd = UpdateOrgCommand {
         org_id,
         name: req.name,
+        auth_methods: req.auth_methods,
+        activity_retention_days: req.activity_retention_days,
+        allowed_ips: req.allowed_ips,
         requesting_user_id: claims.user_id,
     };
 
@@ -344,8 +437,8 @@ where
 }
 
 /// DELETE /api/orgs/:id
-pub async fn delete_org<OR, MR, UR, TS, ID, AR>(
-    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR>>>,
+pub async fn delete_org<OR, MR, UR, TS, ID, AR, IR>(
+    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR, IR>>>,
     Extension(claims): Extension<AuthClaims>,
     Path(org_id): Path<String>,
 ) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
@@ -356,7 +449,10 @@ where
     TS: TokenService,
     ID: IdGenerator,
     AR: OrgActivityRepository,
+    IR: OrganizationInviteRepository,
 {
+    claims.require_scope(TokenScope::OrgsWrite)?;
+
     let cmd = DeleteOrgCommand {
         org_id,
         requesting_user_id: claims.user_id,
@@ -369,12 +465,50 @@ where
         .map_err(to_error_response)
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ListMembersQuery {
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+/// GET /api/orgs/:id/seats
+pub async fn get_seat_usage<OR, MR, UR, TS, ID, AR, IR>(
+    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR, IR>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(org_id): Path<String>,
+) -> Result<Json<SeatUsageResponseDto>, (StatusCode, Json<ErrorResponse>)>
+where
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    UR: UserRepository,
+    TS: TokenService,
+    ID: IdGenerator,
+    AR: OrgActivityRepository,
+    IR: OrganizationInviteRepository,
+{
+    claims.require_scope(TokenScope::OrgsRead)?;
+
+    org_service
+        .get_seat_usage(&org_id, &claims.user_id)
+        .await
+        .map(|r| Json(r.into()))
+        .map_err(to_error_response)
+}
+
 /// GET /api/orgs/:id/members
-pub async fn list_members<OR, MR, UR, TS, ID, AR>(
-    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR>>>,
+///
+/// The effective page size is returned in the `X-Pagination-Limit` header.
+pub async fn list_members<OR, MR, UR, TS, ID, AR, IR>(
+    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR, IR>>>,
     Extension(claims): Extension<AuthClaims>,
     Path(org_id): Path<String>,
-) -> Result<Json<Vec<MemberResponseDto>>, (StatusCode, Json<ErrorResponse>)>
+    Query(query): Query<ListMembersQuery>,
+) -> Result<
+    ([(&'static str, String); 1], Json<Vec<MemberResponseDto>>),
+    (StatusCode, Json<ErrorResponse>),
+>
 where
     OR: OrganizationRepository,
     MR: OrganizationMemberRepository,
@@ -382,17 +516,25 @@ where
     TS: TokenService,
     ID: IdGenerator,
     AR: OrgActivityRepository,
+    IR: OrganizationInviteRepository,
 {
+    claims.require_scope(TokenScope::OrgsRead)?;
+
     org_service
-        .list_members(&org_id, &claims.user_id)
+        .list_members(&org_id, &claims.user_id, query.limit, query.offset)
         .await
-        .map(|members| Json(members.into_iter().map(|m| m.into()).collect()))
+        .map(|page| {
+            (
+                [(PAGINATION_LIMIT_HEADER, page.limit.to_string())],
+                Json(page.members.into_iter().map(|m| m.into()).collect()),
+            )
+        })
         .map_err(to_error_response)
 }
 
 /// POST /api/orgs/:id/members
-pub async fn add_member<OR, MR, UR, TS, ID, AR>(
-    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR>>>,
+pub async fn add_member<OR, MR, UR, TS, ID, AR, IR>(
+    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR, IR>>>,
     Extension(claims): Extension<AuthClaims>,
     Path(org_id): Path<String>,
     Json(req): Json<AddMemberRequest>,
@@ -404,7 +546,10 @@ where
     TS: TokenService,
     ID: IdGenerator,
     AR: OrgActivityRepository,
+    IR: OrganizationInviteRepository,
 {
+    claims.require_scope(TokenScope::OrgsWrite)?;
+
     let cmd = AddMemberCommand {
         org_id,
         email: req.email,
@@ -420,8 +565,8 @@ where
 }
 
 /// PATCH /api/orgs/:id/members/:uid
-pub async fn update_member_role<OR, MR, UR, TS, ID, AR>(
-    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR>>>,
+pub async fn update_member_role<OR, MR, UR, TS, ID, AR, IR>(
+    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR, IR>>>,
     Extension(claims): Extension<AuthClaims>,
     Path((org_id, target_user_id)): Path<(String, String)>,
     Json(req): Json<UpdateMemberRoleRequest>,
@@ -433,7 +578,10 @@ where
     TS: TokenService,
     ID: IdGenerator,
     AR: OrgActivityRepository,
+    IR: OrganizationInviteRepository,
 {
+    claims.require_scope(TokenScope::OrgsWrite)?;
+
     let cmd = UpdateMemberRoleCommand {
         org_id,
         target_user_id,
@@ -449,8 +597,8 @@ where
 }
 
 /// DELETE /api/orgs/:id/members/:uid
-pub async fn remove_member<OR, MR, UR, TS, ID, AR>(
-    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR>>>,
+pub async fn remove_member<OR, MR, UR, TS, ID, AR, IR>(
+    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR, IR>>>,
     Extension(claims): Extension<AuthClaims>,
     Path((org_id, target_user_id)): Path<(String, String)>,
 ) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
@@ -461,7 +609,10 @@ where
     TS: TokenService,
     ID: IdGenerator,
     AR: OrgActivityRepository,
+    IR: OrganizationInviteRepository,
 {
+    claims.require_scope(TokenScope::OrgsWrite)?;
+
     let cmd = RemoveMemberCommand {
         org_id,
         target_user_id,
@@ -476,8 +627,8 @@ where
 }
 
 /// POST /api/orgs/:id/leave
-pub async fn leave_org<OR, MR, UR, TS, ID, AR>(
-    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR>>>,
+pub async fn leave_org<OR, MR, UR, TS, ID, AR, IR>(
+    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR, IR>>>,
     Extension(claims): Extension<AuthClaims>,
     Path(org_id): Path<String>,
 ) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
@@ -488,7 +639,10 @@ where
     TS: TokenService,
     ID: IdGenerator,
     AR: OrgActivityRepository,
+    IR: OrganizationInviteRepository,
 {
+    claims.require_scope(TokenScope::OrgsWrite)?;
+
     let cmd = LeaveOrgCommand {
         org_id,
         user_id: claims.user_id,
@@ -502,8 +656,8 @@ where
 }
 
 /// POST /api/orgs/:id/transfer
-pub async fn transfer_ownership<OR, MR, UR, TS, ID, AR>(
-    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR>>>,
+pub async fn transfer_ownership<OR, MR, UR, TS, ID, AR, IR>(
+    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR, IR>>>,
     Extension(claims): Extension<AuthClaims>,
     Path(org_id): Path<String>,
     Json(req): Json<TransferOwnershipRequest>,
@@ -515,7 +669,10 @@ where
     TS: TokenService,
     ID: IdGenerator,
     AR: OrgActivityRepository,
+    IR: OrganizationInviteRepository,
 {
+    claims.require_scope(TokenScope::OrgsWrite)?;
+
     let cmd = TransferOwnershipCommand {
         org_id,
         new_owner_user_id: req.new_owner_user_id,
@@ -530,8 +687,8 @@ where
 }
 
 /// POST /api/orgs/:id/switch
-pub async fn switch_org<OR, MR, UR, TS, ID, AR>(
-    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR>>>,
+pub async fn switch_org<OR, MR, UR, TS, ID, AR, IR>(
+    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR, IR>>>,
     Extension(claims): Extension<AuthClaims>,
     headers: HeaderMap,
     Path(org_id): Path<String>,
@@ -543,13 +700,25 @@ where
     TS: TokenService,
     ID: IdGenerator,
     AR: OrgActivityRepository,
+    IR: OrganizationInviteRepository,
 {
+    // A personal access token must not mint itself a full dashboard session
+    let Some(session_started_at) = claims
+        .session_started_at
+        .filter(|_| claims.scopes.is_none())
+    else {
+        return Err(insufficient_scope(
+            "Switching organizations requires a dashboard session",
+        ));
+    };
+
     let device_fingerprint = generate_device_fingerprint(&headers);
 
     let cmd = SwitchOrgCommand {
         org_id,
         user_id: claims.user_id,
         device_fingerprint,
+        session_started_at,
     };
 
     org_service
@@ -600,8 +769,8 @@ impl From<ActivityResponse> for ActivityResponseDto {
 }
 
 /// GET /api/orgs/:id/activities
-pub async fn list_activities<OR, MR, UR, TS, ID, AR>(
-    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR>>>,
+pub async fn list_activities<OR, MR, UR, TS, ID, AR, IR>(
+    State(org_service): State<Arc<OrgService<OR, MR, UR, TS, ID, AR, IR>>>,
     Extension(claims): Extension<AuthClaims>,
     Path(org_id): Path<String>,
     Query(query): Query<ListActivitiesQuery>,
@@ -613,7 +782,10 @@ where
     TS: TokenService,
     ID: IdGenerator,
     AR: OrgActivityRepository,
+    IR: OrganizationInviteRepository,
 {
+    claims.require_scope(TokenScope::OrgsRead)?;
+
     org_service
         .list_activities(&org_id, &claims.user_id, query.limit, query.offset)
         .await
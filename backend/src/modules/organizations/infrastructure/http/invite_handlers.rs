@@ -1,7 +1,7 @@
 use axum::{
+    Extension, Json,
     extract::{Path, State},
     http::StatusCode,
-    Extension, Json,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -27,6 +27,14 @@ use super::handlers::ErrorResponse;
 pub struct SendInviteRequest {
     pub email: String,
     pub role: String,
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateInviteLinkRequest {
+    pub role: String,
+    pub max_uses: i32,
+    pub expires_in_days: Option<i64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -35,9 +43,11 @@ pub struct InviteResponseDto {
     pub organization_id: String,
     pub organization_name: String,
     pub inviter_email: String,
-    pub invitee_email: String,
+    pub invitee_email: Option<String>,
     pub role: String,
     pub status: String,
+    pub max_uses: i32,
+    pub remaining_uses: i32,
     pub expires_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
 }
@@ -57,6 +67,8 @@ impl From<InviteResponse> for InviteResponseDto {
             invitee_email: r.invitee_email,
             role: r.role,
             status: r.status,
+            max_uses: r.max_uses,
+            remaining_uses: r.remaining_uses,
             expires_at: r.expires_at,
             created_at: r.created_at,
         }
@@ -76,6 +88,7 @@ fn to_error_response(err: OrgDomainError) -> (StatusCode, Json<ErrorResponse>) {
         | OrgDomainError::InvalidRole(_)
         | OrgDomainError::InvalidActivityType(_)
         | OrgDomainError::InvalidInviteStatus(_)
+        | OrgDomainError::InvalidInviteOptions(_)
         | OrgDomainError::CannotInviteSelf => (StatusCode::BAD_REQUEST, "INVALID_INPUT"),
 
         OrgDomainError::OrgNotFound | OrgDomainError::InviteNotFound => {
@@ -89,9 +102,12 @@ fn to_error_response(err: OrgDomainError) -> (StatusCode, Json<ErrorResponse>) {
         OrgDomainError::InsufficientPermissions
         | OrgDomainError::NotOrgMember
         | OrgDomainError::UserDoesNotAllowInvites => (StatusCode::FORBIDDEN, "FORBIDDEN"),
+        OrgDomainError::SeatLimitReached(_) => (StatusCode::FORBIDDEN, "SEAT_LIMIT_REACHED"),
 
         OrgDomainError::InviteExpired => (StatusCode::GONE, "INVITE_EXPIRED"),
-        OrgDomainError::InviteAlreadyProcessed => (StatusCode::CONFLICT, "INVITE_ALREADY_PROCESSED"),
+        OrgDomainError::InviteAlreadyProcessed => {
+            (StatusCode::CONFLICT, "INVITE_ALREADY_PROCESSED")
+        }
 
         OrgDomainError::UserNotFound => (StatusCode::NOT_FOUND, "USER_NOT_FOUND"),
 
@@ -130,6 +146,7 @@ where
         org_id,
         invitee_email: req.email,
         role: req.role,
+        expires_in_days: req.expires_in_days,
         inviter_user_id: claims.user_id,
     };
 
@@ -140,6 +157,38 @@ where
         .map_err(to_error_response)
 }
 
+/// Create a multi-use invite link (POST /api/orgs/{id}/invite-links)
+///
+/// Users join by accepting the returned invite id.
+pub async fn create_invite_link<OR, MR, UR, IR, AR, ID>(
+    State(service): State<Arc<InviteService<OR, MR, UR, IR, AR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(org_id): Path<String>,
+    Json(req): Json<CreateInviteLinkRequest>,
+) -> Result<(StatusCode, Json<InviteResponseDto>), (StatusCode, Json<ErrorResponse>)>
+where
+    OR: OrganizationRepository + 'static,
+    MR: OrganizationMemberRepository + 'static,
+    UR: UserRepository + 'static,
+    IR: OrganizationInviteRepository + 'static,
+    AR: OrgActivityRepository + 'static,
+    ID: IdGenerator + 'static,
+{
+    let cmd = CreateInviteLinkCommand {
+        org_id,
+        role: req.role,
+        max_uses: req.max_uses,
+        expires_in_days: req.expires_in_days,
+        inviter_user_id: claims.user_id,
+    };
+
+    service
+        .create_invite_link(cmd)
+        .await
+        .map(|r| (StatusCode::CREATED, Json(r.into())))
+        .map_err(to_error_response)
+}
+
 /// List org's pending invites (GET /api/orgs/{id}/invites)
 pub async fn list_org_invites<OR, MR, UR, IR, AR, ID>(
     State(service): State<Arc<InviteService<OR, MR, UR, IR, AR, ID>>>,
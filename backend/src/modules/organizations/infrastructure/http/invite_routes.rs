@@ -1,7 +1,6 @@
 use axum::{
-    middleware,
+    Router, middleware,
     routing::{delete, get, post},
-    Router,
 };
 use std::sync::Arc;
 
@@ -15,7 +14,8 @@ use crate::modules::organizations::domain::{
     OrganizationRepository,
 };
 
-/// Create invite routes for organization invites (POST/GET/DELETE /api/orgs/{id}/invites)
+/// Create invite routes for organization invites (POST/GET/DELETE /api/orgs/{id}/invites,
+/// POST /api/orgs/{id}/invite-links)
 pub fn org_invite_routes<OR, MR, UR, IR, AR, ID, TS>(
     invite_service: Arc<InviteService<OR, MR, UR, IR, AR, ID>>,
     token_service: Arc<TS>,
@@ -38,6 +38,10 @@ where
             "/orgs/{id}/invites",
             get(invite_handlers::list_org_invites::<OR, MR, UR, IR, AR, ID>),
         )
+        .route(
+            "/orgs/{id}/invite-links",
+            post(invite_handlers::create_invite_link::<OR, MR, UR, IR, AR, ID>),
+        )
         .route(
             "/orgs/{id}/invites/{invite_id}",
             delete(invite_handlers::cancel_invite::<OR, MR, UR, IR, AR, ID>),
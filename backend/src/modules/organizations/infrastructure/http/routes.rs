@@ -1,7 +1,6 @@
 use axum::{
-    middleware,
+    Router, middleware,
     routing::{delete, get, patch, post},
-    Router,
 };
 use std::sync::Arc;
 
@@ -11,12 +10,13 @@ use crate::modules::auth::domain::UserRepository;
 use crate::modules::auth::infrastructure::http::middleware::auth_middleware;
 use crate::modules::organizations::application::services::OrgService;
 use crate::modules::organizations::domain::{
-    OrgActivityRepository, OrganizationMemberRepository, OrganizationRepository,
+    OrgActivityRepository, OrganizationInviteRepository, OrganizationMemberRepository,
+    OrganizationRepository,
 };
 
 /// Create organization routes (all protected)
-pub fn org_routes<OR, MR, UR, TS, ID, AR>(
-    org_service: Arc<OrgService<OR, MR, UR, TS, ID, AR>>,
+pub fn org_routes<OR, MR, UR, TS, ID, AR, IR>(
+    org_service: Arc<OrgService<OR, MR, UR, TS, ID, AR, IR>>,
     token_service: Arc<TS>,
 ) -> Router
 where
@@ -26,34 +26,69 @@ where
     TS: TokenService + 'static,
     ID: IdGenerator + 'static,
     AR: OrgActivityRepository + 'static,
+    IR: OrganizationInviteRepository + 'static,
 {
     Router::new()
         // Organization CRUD
-        .route("/orgs", post(handlers::create_org::<OR, MR, UR, TS, ID, AR>))
-        .route("/orgs", get(handlers::list_orgs::<OR, MR, UR, TS, ID, AR>))
-        .route("/orgs/{id}", get(handlers::get_org::<OR, MR, UR, TS, ID, AR>))
-        .route("/orgs/{id}", patch(handlers::update_org::<OR, MR, UR, TS, ID, AR>))
-        .route("/orgs/{id}", delete(handlers::delete_org::<OR, MR, UR, TS, ID, AR>))
+        .route(
+            "/orgs",
+            post(handlers::create_org::<OR, MR, UR, TS, ID, AR, IR>),
+        )
+        .route(
+            "/orgs",
+            get(handlers::list_orgs::<OR, MR, UR, TS, ID, AR, IR>),
+        )
+        .route(
+            "/orgs/{id}",
+            get(handlers::get_org::<OR, MR, UR, TS, ID, AR, IR>),
+        )
+        .route(
+            "/orgs/{id}",
+            patch(handlers::update_org::<OR, MR, UR, TS, ID, AR, IR>),
+        )
+        .route(
+            "/orgs/{id}",
+            delete(handlers::delete_org::<OR, MR, UR, TS, ID, AR, IR>),
+        )
         // Members
-        .route("/orgs/{id}/members", get(handlers::list_members::<OR, MR, UR, TS, ID, AR>))
-        .route("/orgs/{id}/members", post(handlers::add_member::<OR, MR, UR, TS, ID, AR>))
+        .route(
+            "/orgs/{id}/members",
+            get(handlers::list_members::<OR, MR, UR, TS, ID, AR, IR>),
+        )
+        .route(
+            "/orgs/{id}/members",
+            post(handlers::add_member::<OR, MR, UR, TS, ID, AR, IR>),
+        )
         .route(
             "/orgs/{id}/members/{uid}",
-            patch(handlers::update_member_role::<OR, MR, UR, TS, ID, AR>),
+            patch(handlers::update_member_role::<OR, MR, UR, TS, ID, AR, IR>),
         )
         .route(
             "/orgs/{id}/members/{uid}",
-            delete(handlers::remove_member::<OR, MR, UR, TS, ID, AR>),
+            delete(handlers::remove_member::<OR, MR, UR, TS, ID, AR, IR>),
+        )
+        .route(
+            "/orgs/{id}/seats",
+            get(handlers::get_seat_usage::<OR, MR, UR, TS, ID, AR, IR>),
         )
         // Activities
-        .route("/orgs/{id}/activities", get(handlers::list_activities::<OR, MR, UR, TS, ID, AR>))
+        .route(
+            "/orgs/{id}/activities",
+            get(handlers::list_activities::<OR, MR, UR, TS, ID, AR, IR>),
+        )
         // Leave, transfer, switch
-        .route("/orgs/{id}/leave", post(handlers::leave_org::<OR, MR, UR, TS, ID, AR>))
+        .route(
+            "/orgs/{id}/leave",
+            post(handlers::leave_org::<OR, MR, UR, TS, ID, AR, IR>),
+        )
         .route(
             "/orgs/{id}/transfer",
-            post(handlers::transfer_ownership::<OR, MR, UR, TS, ID, AR>),
+            post(handlers::transfer_ownership::<OR, MR, UR, TS, ID, AR, IR>),
+        )
+        .route(
+            "/orgs/{id}/switch",
+            post(handlers::switch_org::<OR, MR, UR, TS, ID, AR, IR>),
         )
-        .route("/orgs/{id}/switch", post(handlers::switch_org::<OR, MR, UR, TS, ID, AR>))
         // All routes require authentication
         .layer(middleware::from_fn_with_state(
             token_service,
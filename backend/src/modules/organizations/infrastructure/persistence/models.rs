@@ -8,6 +8,10 @@ pub struct OrganizationRow {
     pub name: String,
     pub slug: String,
     pub is_personal: bool,
+    pub max_seats: Option<i32>,
+    pub allowed_auth_methods: String,
+    pub activity_retention_days: Option<i32>,
+    pub allowed_ips: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub deleted_at: Option<DateTime<Utc>>,
@@ -64,10 +68,12 @@ pub struct OrgInviteRow {
     pub id: String,
     pub organization_id: String,
     pub inviter_id: String,
-    pub invitee_email: String,
+    pub invitee_email: Option<String>,
     pub invitee_id: Option<String>,
     pub role: String,
     pub status: String,
+    pub max_uses: i32,
+    pub uses: i32,
     pub expires_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -92,4 +93,30 @@ impl OrgActivityRepository for PostgresOrgActivityRepository {
 
         rows.into_iter().map(Self::row_to_activity).collect()
     }
+
+    async fn delete_before(
+        &self,
+        org_id: &OrgId,
+        before: DateTime<Utc>,
+        keep: &[ActivityType],
+    ) -> Result<u64, OrgDomainError> {
+        let keep: Vec<&str> = keep.iter().map(|t| t.as_str()).collect();
+
+        let result = sqlx::query(
+            r#"
+            DELETE FROM organization_activities
+            WHERE organization_id = $1
+              AND created_at < $2
+              AND activity_type <> ALL($3)
+            "#,
+        )
+        .bind(org_id.as_str())
+        .bind(before)
+        .bind(&keep)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| OrgDomainError::InternalError(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
 }
@@ -4,7 +4,9 @@ use std::sync::Arc;
 
 use super::models::OrgInviteRow;
 use crate::modules::organizations::domain::invite::{InviteId, InviteStatus, OrganizationInvite};
-use crate::modules::organizations::domain::{OrgDomainError, OrgRole, OrganizationInviteRepository};
+use crate::modules::organizations::domain::{
+    OrgDomainError, OrgRole, OrganizationInviteRepository, OrganizationMember,
+};
 
 pub struct PostgresInviteRepository {
     pool: Arc<PgPool>,
@@ -28,6 +30,8 @@ impl PostgresInviteRepository {
             row.invitee_id,
             role,
             status,
+            row.max_uses,
+            row.uses,
             row.expires_at,
             row.created_at,
             row.updated_at,
@@ -41,8 +45,8 @@ impl OrganizationInviteRepository for PostgresInviteRepository {
         sqlx::query(
             r#"
             INSERT INTO organization_invites
-                (id, organization_id, inviter_id, invitee_email, invitee_id, role, status, expires_at, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                (id, organization_id, inviter_id, invitee_email, invitee_id, role, status, max_uses, uses, expires_at, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
             "#,
         )
         .bind(invite.id().as_str())
@@ -52,6 +56,8 @@ impl OrganizationInviteRepository for PostgresInviteRepository {
         .bind(invite.invitee_id())
         .bind(invite.role().as_str())
         .bind(invite.status().as_str())
+        .bind(invite.max_uses())
+        .bind(invite.uses())
         .bind(invite.expires_at())
         .bind(invite.created_at())
         .bind(invite.updated_at())
@@ -66,12 +72,13 @@ impl OrganizationInviteRepository for PostgresInviteRepository {
         sqlx::query(
             r#"
             UPDATE organization_invites
-            SET status = $2, updated_at = $3
+            SET status = $2, uses = $3, updated_at = $4
             WHERE id = $1
             "#,
         )
         .bind(invite.id().as_str())
         .bind(invite.status().as_str())
+        .bind(invite.uses())
         .bind(invite.updated_at())
         .execute(self.pool.as_ref())
         .await
@@ -80,10 +87,70 @@ impl OrganizationInviteRepository for PostgresInviteRepository {
         Ok(())
     }
 
-    async fn find_by_id(&self, id: &InviteId) -> Result<Option<OrganizationInvite>, OrgDomainError> {
+    async fn accept(
+        &self,
+        invite: &OrganizationInvite,
+        member: &OrganizationMember,
+    ) -> Result<bool, OrgDomainError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| OrgDomainError::InternalError(e.to_string()))?;
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO organization_members (id, organization_id, user_id, role, last_accessed_at, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (organization_id, user_id) DO NOTHING
+            "#,
+        )
+        .bind(member.id().as_str())
+        .bind(member.organization_id().as_str())
+        .bind(member.user_id().as_str())
+        .bind(member.role().as_str())
+        .bind(member.last_accessed_at())
+        .bind(member.created_at())
+        .bind(member.updated_at())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| OrgDomainError::InternalError(e.to_string()))?
+        .rows_affected();
+
+        // Already a member: leave the invite's uses untouched
+        if inserted == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE organization_invites
+            SET status = $2, uses = $3, updated_at = $4
+            WHERE id = $1
+            "#,
+        )
+        .bind(invite.id().as_str())
+        .bind(invite.status().as_str())
+        .bind(invite.uses())
+        .bind(invite.updated_at())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| OrgDomainError::InternalError(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| OrgDomainError::InternalError(e.to_string()))?;
+
+        Ok(true)
+    }
+
+    async fn find_by_id(
+        &self,
+        id: &InviteId,
+    ) -> Result<Option<OrganizationInvite>, OrgDomainError> {
         let row: Option<OrgInviteRow> = sqlx::query_as(
             r#"
-            SELECT id, organization_id, inviter_id, invitee_email, invitee_id, role, status, expires_at, created_at, updated_at
+            SELECT id, organization_id, inviter_id, invitee_email, invitee_id, role, status, max_uses, uses, expires_at, created_at, updated_at
             FROM organization_invites
             WHERE id = $1
             "#,
@@ -103,7 +170,7 @@ impl OrganizationInviteRepository for PostgresInviteRepository {
     ) -> Result<Option<OrganizationInvite>, OrgDomainError> {
         let row: Option<OrgInviteRow> = sqlx::query_as(
             r#"
-            SELECT id, organization_id, inviter_id, invitee_email, invitee_id, role, status, expires_at, created_at, updated_at
+            SELECT id, organization_id, inviter_id, invitee_email, invitee_id, role, status, max_uses, uses, expires_at, created_at, updated_at
             FROM organization_invites
             WHERE organization_id = $1 AND invitee_email = $2 AND status = 'pending'
             "#,
@@ -123,7 +190,7 @@ impl OrganizationInviteRepository for PostgresInviteRepository {
     ) -> Result<Vec<OrganizationInvite>, OrgDomainError> {
         let rows: Vec<OrgInviteRow> = sqlx::query_as(
             r#"
-            SELECT id, organization_id, inviter_id, invitee_email, invitee_id, role, status, expires_at, created_at, updated_at
+            SELECT id, organization_id, inviter_id, invitee_email, invitee_id, role, status, max_uses, uses, expires_at, created_at, updated_at
             FROM organization_invites
             WHERE organization_id = $1 AND status = 'pending'
             ORDER BY created_at DESC
@@ -143,7 +210,7 @@ impl OrganizationInviteRepository for PostgresInviteRepository {
     ) -> Result<Vec<OrganizationInvite>, OrgDomainError> {
         let rows: Vec<OrgInviteRow> = sqlx::query_as(
             r#"
-            SELECT id, organization_id, inviter_id, invitee_email, invitee_id, role, status, expires_at, created_at, updated_at
+            SELECT id, organization_id, inviter_id, invitee_email, invitee_id, role, status, max_uses, uses, expires_at, created_at, updated_at
             FROM organization_invites
             WHERE invitee_id = $1 AND status = 'pending'
             ORDER BY created_at DESC
@@ -157,6 +224,22 @@ impl OrganizationInviteRepository for PostgresInviteRepository {
         rows.into_iter().map(Self::row_to_invite).collect()
     }
 
+    async fn count_pending_by_org(&self, org_id: &str) -> Result<i64, OrgDomainError> {
+        // An invite link holds a seat for each remaining use
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(max_uses - uses), 0)::BIGINT FROM organization_invites
+            WHERE organization_id = $1 AND status = 'pending' AND expires_at > NOW()
+            "#,
+        )
+        .bind(org_id)
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| OrgDomainError::InternalError(e.to_string()))?;
+
+        Ok(count.0)
+    }
+
     async fn count_pending_by_user(&self, user_id: &str) -> Result<i64, OrgDomainError> {
         let count: (i64,) = sqlx::query_as(
             r#"
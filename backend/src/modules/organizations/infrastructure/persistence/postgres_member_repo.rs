@@ -7,6 +7,7 @@ use crate::modules::auth::domain::UserId;
 use crate::modules::organizations::domain::{
     MemberId, OrgDomainError, OrgId, OrgRole, OrganizationMember, OrganizationMemberRepository,
 };
+use crate::shared::Pagination;
 
 pub struct PostgresOrganizationMemberRepository {
     pool: Arc<PgPool>,
@@ -37,7 +38,10 @@ impl PostgresOrganizationMemberRepository {
 
 #[async_trait]
 impl OrganizationMemberRepository for PostgresOrganizationMemberRepository {
-    async fn find_by_id(&self, id: &MemberId) -> Result<Option<OrganizationMember>, OrgDomainError> {
+    async fn find_by_id(
+        &self,
+        id: &MemberId,
+    ) -> Result<Option<OrganizationMember>, OrgDomainError> {
         let row: Option<OrganizationMemberRow> = sqlx::query_as(
             r#"
             SELECT id, organization_id, user_id, role, last_accessed_at, created_at, updated_at
@@ -94,6 +98,30 @@ impl OrganizationMemberRepository for PostgresOrganizationMemberRepository {
         rows.into_iter().map(Self::row_to_member).collect()
     }
 
+    async fn find_page_by_org(
+        &self,
+        org_id: &OrgId,
+        pagination: &Pagination,
+    ) -> Result<Vec<OrganizationMember>, OrgDomainError> {
+        let rows: Vec<OrganizationMemberRow> = sqlx::query_as(
+            r#"
+            SELECT id, organization_id, user_id, role, last_accessed_at, created_at, updated_at
+            FROM organization_members
+            WHERE organization_id = $1
+            ORDER BY created_at ASC, id
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(org_id.as_str())
+        .bind(pagination.limit)
+        .bind(pagination.offset)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| OrgDomainError::InternalError(e.to_string()))?;
+
+        rows.into_iter().map(Self::row_to_member).collect()
+    }
+
     async fn find_all_by_user(
         &self,
         user_id: &UserId,
@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use super::models::OrganizationRow;
 use crate::modules::organizations::domain::{
-    OrgDomainError, OrgId, OrgName, OrgSlug, Organization, OrganizationRepository,
+    AuthMethods, OrgDomainError, OrgId, OrgName, OrgSlug, Organization, OrganizationRepository,
 };
 
 pub struct PostgresOrganizationRepository {
@@ -20,12 +20,17 @@ impl PostgresOrganizationRepository {
         let id = OrgId::new(row.id);
         let name = OrgName::new(row.name)?;
         let slug = OrgSlug::from_string(row.slug)?;
+        let auth_methods = AuthMethods::from_str(&row.allowed_auth_methods)?;
 
         Ok(Organization::reconstruct(
             id,
             name,
             slug,
             row.is_personal,
+            row.max_seats.map(|max| max as u32),
+            auth_methods,
+            row.activity_retention_days.map(|days| days as u32),
+            row.allowed_ips,
             row.created_at,
             row.updated_at,
             row.deleted_at,
@@ -38,7 +43,8 @@ impl OrganizationRepository for PostgresOrganizationRepository {
     async fn find_by_id(&self, id: &OrgId) -> Result<Option<Organization>, OrgDomainError> {
         let row: Option<OrganizationRow> = sqlx::query_as(
             r#"
-            SELECT id, name, slug, is_personal, created_at, updated_at, deleted_at
+            SELECT id, name, slug, is_personal, max_seats, allowed_auth_methods, activity_retention_days,
+                   allowed_ips, created_at, updated_at, deleted_at
             FROM organizations
             WHERE id = $1
             "#,
@@ -54,7 +60,8 @@ impl OrganizationRepository for PostgresOrganizationRepository {
     async fn find_by_slug(&self, slug: &str) -> Result<Option<Organization>, OrgDomainError> {
         let row: Option<OrganizationRow> = sqlx::query_as(
             r#"
-            SELECT id, name, slug, is_personal, created_at, updated_at, deleted_at
+            SELECT id, name, slug, is_personal, max_seats, allowed_auth_methods, activity_retention_days,
+                   allowed_ips, created_at, updated_at, deleted_at
             FROM organizations
             WHERE LOWER(slug) = LOWER($1) AND deleted_at IS NULL
             "#,
@@ -70,11 +77,16 @@ impl OrganizationRepository for PostgresOrganizationRepository {
     async fn save(&self, org: &Organization) -> Result<(), OrgDomainError> {
         sqlx::query(
             r#"
-            INSERT INTO organizations (id, name, slug, is_personal, created_at, updated_at, deleted_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO organizations (id, name, slug, is_personal, max_seats, allowed_auth_methods,
+                                       activity_retention_days, allowed_ips, created_at, updated_at, deleted_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             ON CONFLICT (id) DO UPDATE SET
                 name = EXCLUDED.name,
                 slug = EXCLUDED.slug,
+                max_seats = EXCLUDED.max_seats,
+                allowed_auth_methods = EXCLUDED.allowed_auth_methods,
+                activity_retention_days = EXCLUDED.activity_retention_days,
+                allowed_ips = EXCLUDED.allowed_ips,
                 updated_at = EXCLUDED.updated_at,
                 deleted_at = EXCLUDED.deleted_at
             "#,
@@ -83,6 +95,10 @@ impl OrganizationRepository for PostgresOrganizationRepository {
         .bind(org.name().as_str())
         .bind(org.slug().as_str())
         .bind(org.is_personal())
+        .bind(org.max_seats().map(|max| max as i32))
+        .bind(org.auth_methods().as_str())
+        .bind(org.activity_retention_days().map(|days| days as i32))
+        .bind(org.allowed_ips())
         .bind(org.created_at())
         .bind(org.updated_at())
         .bind(org.deleted_at())
@@ -93,6 +109,22 @@ impl OrganizationRepository for PostgresOrganizationRepository {
         Ok(())
     }
 
+    async fn find_all_active(&self) -> Result<Vec<Organization>, OrgDomainError> {
+        let rows: Vec<OrganizationRow> = sqlx::query_as(
+            r#"
+            SELECT id, name, slug, is_personal, max_seats, allowed_auth_methods, activity_retention_days,
+                   allowed_ips, created_at, updated_at, deleted_at
+            FROM organizations
+            WHERE deleted_at IS NULL
+            "#,
+        )
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| OrgDomainError::InternalError(e.to_string()))?;
+
+        rows.into_iter().map(Self::row_to_org).collect()
+    }
+
     async fn slug_exists(&self, slug: &str) -> Result<bool, OrgDomainError> {
         let count: (i64,) = sqlx::query_as(
             r#"
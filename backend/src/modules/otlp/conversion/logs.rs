@@ -72,6 +72,7 @@ pub fn convert_otlp_logs(request: ExportLogsServiceRequest) -> Vec<LogInput> {
                     },
                     trace_id,
                     span_id,
+                    event_id: None,
                 });
             }
         }
@@ -143,4 +144,3 @@ mod tests {
         assert_eq!(logs[0].source, Some("test-service".to_string()));
     }
 }
-
@@ -14,12 +14,13 @@ pub fn convert_otlp_traces(request: ExportTraceServiceRequest) -> Vec<SpanInput>
         let resource = resource_spans.resource.as_ref();
         let service_name = resource.and_then(|r| r.get_service_name());
         let service_version = resource.and_then(|r| r.get_service_version());
-        let resource_attrs = resource.map(|r| r.to_json()).unwrap_or(serde_json::json!({}));
+        let resource_attrs = resource
+            .map(|r| r.to_json())
+            .unwrap_or(serde_json::json!({}));
 
         for scope_spans in resource_spans.scope_spans {
             for otlp_span in scope_spans.spans {
-                let start_time = parse_nano_timestamp(&otlp_span.start_time_unix_nano)
-                    .unwrap_or_else(Utc::now);
+                let start_time = parse_nano_timestamp(&otlp_span.start_time_unix_nano);
 
                 let end_time = parse_nano_timestamp(&otlp_span.end_time_unix_nano);
 
@@ -82,6 +83,8 @@ pub fn convert_otlp_traces(request: ExportTraceServiceRequest) -> Vec<SpanInput>
                     attributes,
                     events,
                     links,
+                    trace_state: Some(otlp_span.trace_state.clone()).filter(|s| !s.is_empty()),
+                    dropped_attributes_count: otlp_span.dropped_attributes_count,
                 });
             }
         }
@@ -0,0 +1,193 @@
+//! OTLP/HTTP content negotiation: requests are decoded per their Content-Type
+//! and answered in the same encoding
+
+use axum::{
+    Json,
+    http::{HeaderMap, StatusCode, header::CONTENT_TYPE},
+    response::{IntoResponse, Response},
+};
+use prost::Message;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::modules::otlp::types::proto;
+
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+pub const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub code: String,
+}
+
+/// Wire encoding of an OTLP/HTTP request and its response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtlpFormat {
+    Json,
+    Protobuf,
+}
+
+impl OtlpFormat {
+    /// Encoding named by the request's Content-Type, ignoring parameters such
+    /// as charset. Requests without one are read as JSON; other media types
+    /// are unsupported.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let Some(value) = headers.get(CONTENT_TYPE) else {
+            return Some(Self::Json);
+        };
+        let media_type = value.to_str().ok()?.split(';').next()?.trim();
+        if media_type.eq_ignore_ascii_case(PROTOBUF_CONTENT_TYPE) {
+            Some(Self::Protobuf)
+        } else if media_type.eq_ignore_ascii_case(JSON_CONTENT_TYPE) {
+            Some(Self::Json)
+        } else {
+            None
+        }
+    }
+
+    /// Decode a request body into the JSON-mapped type `T`; protobuf bodies
+    /// are decoded as `P` first
+    pub fn decode<T, P>(self, body: &[u8]) -> Result<T, OtlpError>
+    where
+        T: DeserializeOwned,
+        P: Message + Default + Into<T>,
+    {
+        match self {
+            Self::Json => serde_json::from_slice(body).map_err(|e| {
+                OtlpError::new(
+                    self,
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_JSON",
+                    format!("Invalid JSON: {}", e),
+                )
+            }),
+            Self::Protobuf => P::decode(body).map(Into::into).map_err(|e| {
+                OtlpError::new(
+                    self,
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_PROTOBUF",
+                    format!("Invalid protobuf: {}", e),
+                )
+            }),
+        }
+    }
+
+    /// Encode a successful response; protobuf responses are encoded as `P`
+    pub fn respond<T, P>(self, response: T) -> Response
+    where
+        T: Serialize + Into<P>,
+        P: Message,
+    {
+        match self {
+            Self::Json => Json(response).into_response(),
+            Self::Protobuf => (
+                [(CONTENT_TYPE, PROTOBUF_CONTENT_TYPE)],
+                response.into().encode_to_vec(),
+            )
+                .into_response(),
+        }
+    }
+}
+
+/// A failed OTLP request, reported as JSON or as a protobuf `google.rpc.Status`
+#[derive(Debug)]
+pub struct OtlpError {
+    format: OtlpFormat,
+    status: StatusCode,
+    code: &'static str,
+    error: String,
+}
+
+impl OtlpError {
+    pub fn new(
+        format: OtlpFormat,
+        status: StatusCode,
+        code: &'static str,
+        error: impl Into<String>,
+    ) -> Self {
+        Self {
+            format,
+            status,
+            code,
+            error: error.into(),
+        }
+    }
+
+    /// The request's Content-Type is neither JSON nor protobuf
+    pub fn unsupported_media_type() -> Self {
+        Self::new(
+            OtlpFormat::Json,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "UNSUPPORTED_FORMAT",
+            format!(
+                "Unsupported content type. Use {} or {}",
+                PROTOBUF_CONTENT_TYPE, JSON_CONTENT_TYPE
+            ),
+        )
+    }
+}
+
+impl IntoResponse for OtlpError {
+    fn into_response(self) -> Response {
+        match self.format {
+            OtlpFormat::Json => (
+                self.status,
+                Json(ErrorResponse {
+                    error: self.error,
+                    code: self.code.to_string(),
+                }),
+            )
+                .into_response(),
+            OtlpFormat::Protobuf => {
+                let status = proto::Status {
+                    code: rpc_code(self.status),
+                    message: self.error,
+                };
+                (
+                    self.status,
+                    [(CONTENT_TYPE, PROTOBUF_CONTENT_TYPE)],
+                    status.encode_to_vec(),
+                )
+                    .into_response()
+            }
+        }
+    }
+}
+
+/// gRPC status code matching an HTTP status, for `google.rpc.Status`
+fn rpc_code(status: StatusCode) -> i32 {
+    match status {
+        // INVALID_ARGUMENT
+        StatusCode::BAD_REQUEST | StatusCode::UNSUPPORTED_MEDIA_TYPE => 3,
+        // NOT_FOUND
+        StatusCode::NOT_FOUND => 5,
+        // RESOURCE_EXHAUSTED
+        StatusCode::PAYLOAD_TOO_LARGE | StatusCode::TOO_MANY_REQUESTS => 8,
+        // INTERNAL
+        _ => 13,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_follows_content_type() {
+        let format = |content_type: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(CONTENT_TYPE, content_type.parse().unwrap());
+            OtlpFormat::from_headers(&headers)
+        };
+        assert_eq!(format("application/x-protobuf"), Some(OtlpFormat::Protobuf));
+        assert_eq!(
+            format("application/json; charset=utf-8"),
+            Some(OtlpFormat::Json)
+        );
+        assert_eq!(format("text/plain"), None);
+        assert_eq!(
+            OtlpFormat::from_headers(&HeaderMap::new()),
+            Some(OtlpFormat::Json)
+        );
+    }
+}
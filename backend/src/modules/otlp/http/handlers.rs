@@ -1,40 +1,39 @@
 //! OTLP HTTP handlers
 
 use axum::{
+    Extension,
     body::Bytes,
     extract::State,
-    http::{header::CONTENT_TYPE, HeaderMap, StatusCode},
-    Extension, Json,
+    http::{HeaderMap, StatusCode},
+    response::Response,
 };
-use serde::Serialize;
 use std::sync::Arc;
 
+use super::encoding::{OtlpError, OtlpFormat};
+
 use crate::modules::auth::application::ports::IdGenerator;
-use crate::modules::logging::application::dto::IngestLogsCommand;
 use crate::modules::logging::application::LogService;
+use crate::modules::logging::application::dto::IngestLogsCommand;
 use crate::modules::logging::domain::{LogDomainError, LogRepository};
 use crate::modules::logging::infrastructure::ApiKeyContext;
-use crate::modules::metrics::application::dto::IngestMetricsCommand;
 use crate::modules::metrics::application::MetricsService;
+use crate::modules::metrics::application::dto::IngestMetricsCommand;
 use crate::modules::metrics::domain::{MetricsDomainError, MetricsRepository};
 use crate::modules::organizations::domain::OrganizationMemberRepository;
-use crate::modules::otlp::conversion::{convert_otlp_logs, convert_otlp_metrics, convert_otlp_traces};
+use crate::modules::otlp::conversion::{
+    convert_otlp_logs, convert_otlp_metrics, convert_otlp_traces,
+};
 use crate::modules::otlp::types::logs::{ExportLogsServiceRequest, ExportLogsServiceResponse};
 use crate::modules::otlp::types::metrics::{
     ExportMetricsServiceRequest, ExportMetricsServiceResponse,
 };
+use crate::modules::otlp::types::proto;
 use crate::modules::otlp::types::traces::{ExportTraceServiceRequest, ExportTraceServiceResponse};
 use crate::modules::projects::domain::ProjectRepository;
-use crate::modules::traces::application::dto::IngestSpansCommand;
 use crate::modules::traces::application::TraceService;
+use crate::modules::traces::application::dto::IngestSpansCommand;
 use crate::modules::traces::domain::{SpansRepository, TracesDomainError};
 
-#[derive(Debug, Serialize)]
-pub struct ErrorResponse {
-    pub error: String,
-    pub code: String,
-}
-
 // ============================================================================
 // Logs Handler
 // ============================================================================
@@ -44,48 +43,27 @@ pub async fn ingest_otlp_logs<LR, PR, OMR, ID>(
     Extension(ctx): Extension<ApiKeyContext>,
     headers: HeaderMap,
     body: Bytes,
-) -> Result<Json<ExportLogsServiceResponse>, (StatusCode, Json<ErrorResponse>)>
+) -> Result<Response, OtlpError>
 where
     LR: LogRepository,
     PR: ProjectRepository,
     OMR: OrganizationMemberRepository,
     ID: IdGenerator,
 {
-    let content_type = headers
-        .get(CONTENT_TYPE)
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("application/json");
-
-    // Parse the request based on content type
-    let request: ExportLogsServiceRequest = if content_type.contains("application/x-protobuf") {
-        // TODO: Add protobuf decoding when needed
-        return Err((
-            StatusCode::UNSUPPORTED_MEDIA_TYPE,
-            Json(ErrorResponse {
-                error: "Protobuf format not yet supported. Use application/json".to_string(),
-                code: "UNSUPPORTED_FORMAT".to_string(),
-            }),
-        ));
-    } else {
-        serde_json::from_slice(&body).map_err(|e| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: format!("Invalid JSON: {}", e),
-                    code: "INVALID_JSON".to_string(),
-                }),
-            )
-        })?
-    };
+    let format =
+        OtlpFormat::from_headers(&headers).ok_or_else(OtlpError::unsupported_media_type)?;
+    let request: ExportLogsServiceRequest =
+        format.decode::<_, proto::ExportLogsServiceRequest>(&body)?;
 
     // Convert OTLP logs to internal format
     let logs = convert_otlp_logs(request);
     let log_count = logs.len();
 
     if logs.is_empty() {
-        return Ok(Json(ExportLogsServiceResponse {
+        let response = ExportLogsServiceResponse {
             partial_success: None,
-        }));
+        };
+        return Ok(format.respond::<_, proto::ExportLogsServiceResponse>(response));
     }
 
     // Ingest logs
@@ -105,23 +83,19 @@ where
             }
             _ => (StatusCode::BAD_REQUEST, "Invalid request"),
         };
-        (
-            status,
-            Json(ErrorResponse {
-                error: msg.to_string(),
-                code: "INGESTION_ERROR".to_string(),
-            }),
-        )
+        OtlpError::new(format, status, "INGESTION_ERROR", msg)
     })?;
 
     // Build response
     let rejected = log_count as i64 - result.accepted as i64;
     let response = if rejected > 0 {
         ExportLogsServiceResponse {
-            partial_success: Some(crate::modules::otlp::types::logs::ExportLogsPartialSuccess {
-                rejected_log_records: rejected,
-                error_message: result.errors.join("; "),
-            }),
+            partial_success: Some(
+                crate::modules::otlp::types::logs::ExportLogsPartialSuccess {
+                    rejected_log_records: rejected,
+                    error_message: result.errors.join("; "),
+                },
+            ),
         }
     } else {
         ExportLogsServiceResponse {
@@ -129,7 +103,7 @@ where
         }
     };
 
-    Ok(Json(response))
+    Ok(format.respond::<_, proto::ExportLogsServiceResponse>(response))
 }
 
 // ============================================================================
@@ -141,45 +115,26 @@ pub async fn ingest_otlp_metrics<MR, PR, OMR, ID>(
     Extension(ctx): Extension<ApiKeyContext>,
     headers: HeaderMap,
     body: Bytes,
-) -> Result<Json<ExportMetricsServiceResponse>, (StatusCode, Json<ErrorResponse>)>
+) -> Result<Response, OtlpError>
 where
     MR: MetricsRepository,
     PR: ProjectRepository,
     OMR: OrganizationMemberRepository,
     ID: IdGenerator,
 {
-    let content_type = headers
-        .get(CONTENT_TYPE)
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("application/json");
-
-    let request: ExportMetricsServiceRequest = if content_type.contains("application/x-protobuf") {
-        return Err((
-            StatusCode::UNSUPPORTED_MEDIA_TYPE,
-            Json(ErrorResponse {
-                error: "Protobuf format not yet supported. Use application/json".to_string(),
-                code: "UNSUPPORTED_FORMAT".to_string(),
-            }),
-        ));
-    } else {
-        serde_json::from_slice(&body).map_err(|e| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: format!("Invalid JSON: {}", e),
-                    code: "INVALID_JSON".to_string(),
-                }),
-            )
-        })?
-    };
+    let format =
+        OtlpFormat::from_headers(&headers).ok_or_else(OtlpError::unsupported_media_type)?;
+    let request: ExportMetricsServiceRequest =
+        format.decode::<_, proto::ExportMetricsServiceRequest>(&body)?;
 
     let metrics = convert_otlp_metrics(request);
     let metric_count = metrics.len();
 
     if metrics.is_empty() {
-        return Ok(Json(ExportMetricsServiceResponse {
+        let response = ExportMetricsServiceResponse {
             partial_success: None,
-        }));
+        };
+        return Ok(format.respond::<_, proto::ExportMetricsServiceResponse>(response));
     }
 
     let cmd = IngestMetricsCommand {
@@ -198,13 +153,7 @@ where
             }
             _ => (StatusCode::BAD_REQUEST, "Invalid request"),
         };
-        (
-            status,
-            Json(ErrorResponse {
-                error: msg.to_string(),
-                code: "INGESTION_ERROR".to_string(),
-            }),
-        )
+        OtlpError::new(format, status, "INGESTION_ERROR", msg)
     })?;
 
     let rejected = metric_count as i64 - result.ingested as i64;
@@ -223,7 +172,7 @@ where
         }
     };
 
-    Ok(Json(response))
+    Ok(format.respond::<_, proto::ExportMetricsServiceResponse>(response))
 }
 
 // ============================================================================
@@ -235,45 +184,26 @@ pub async fn ingest_otlp_traces<SR, PR, OMR, ID>(
     Extension(ctx): Extension<ApiKeyContext>,
     headers: HeaderMap,
     body: Bytes,
-) -> Result<Json<ExportTraceServiceResponse>, (StatusCode, Json<ErrorResponse>)>
+) -> Result<Response, OtlpError>
 where
     SR: SpansRepository,
     PR: ProjectRepository,
     OMR: OrganizationMemberRepository,
     ID: IdGenerator,
 {
-    let content_type = headers
-        .get(CONTENT_TYPE)
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("application/json");
-
-    let request: ExportTraceServiceRequest = if content_type.contains("application/x-protobuf") {
-        return Err((
-            StatusCode::UNSUPPORTED_MEDIA_TYPE,
-            Json(ErrorResponse {
-                error: "Protobuf format not yet supported. Use application/json".to_string(),
-                code: "UNSUPPORTED_FORMAT".to_string(),
-            }),
-        ));
-    } else {
-        serde_json::from_slice(&body).map_err(|e| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: format!("Invalid JSON: {}", e),
-                    code: "INVALID_JSON".to_string(),
-                }),
-            )
-        })?
-    };
+    let format =
+        OtlpFormat::from_headers(&headers).ok_or_else(OtlpError::unsupported_media_type)?;
+    let request: ExportTraceServiceRequest =
+        format.decode::<_, proto::ExportTraceServiceRequest>(&body)?;
 
     let spans = convert_otlp_traces(request);
     let span_count = spans.len();
 
     if spans.is_empty() {
-        return Ok(Json(ExportTraceServiceResponse {
+        let response = ExportTraceServiceResponse {
             partial_success: None,
-        }));
+        };
+        return Ok(format.respond::<_, proto::ExportTraceServiceResponse>(response));
     }
 
     let cmd = IngestSpansCommand {
@@ -286,22 +216,20 @@ where
             TracesDomainError::ProjectNotFound | TracesDomainError::ProjectDeleted => {
                 (StatusCode::NOT_FOUND, "Project not found")
             }
+            TracesDomainError::BatchTooLarge(_) => {
+                (StatusCode::PAYLOAD_TOO_LARGE, "Span batch too large")
+            }
             TracesDomainError::InternalError(ref msg) => {
                 tracing::error!(error = %msg, "Internal error during OTLP traces ingestion");
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal error")
             }
             _ => (StatusCode::BAD_REQUEST, "Invalid request"),
         };
-        (
-            status,
-            Json(ErrorResponse {
-                error: msg.to_string(),
-                code: "INGESTION_ERROR".to_string(),
-            }),
-        )
+        OtlpError::new(format, status, "INGESTION_ERROR", msg)
     })?;
 
-    let rejected = span_count as i64 - result.ingested as i64;
+    // Resent spans were already stored, so they aren't rejected
+    let rejected = span_count as i64 - (result.ingested + result.duplicates) as i64;
     let response = if rejected > 0 {
         ExportTraceServiceResponse {
             partial_success: Some(
@@ -317,5 +245,212 @@ where
         }
     };
 
-    Ok(Json(response))
+    Ok(format.respond::<_, proto::ExportTraceServiceResponse>(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header::CONTENT_TYPE;
+    use axum::response::IntoResponse;
+    use prost::Message;
+    use serde_json::json;
+
+    use crate::modules::projects::domain::ProjectId;
+    use crate::modules::traces::domain::Span;
+    use crate::shared::PaginationConfig;
+    use crate::shared::testing::{
+        InMemoryMemberRepository, InMemoryProjectRepository, InMemorySpansRepository,
+        SequentialIdGenerator,
+    };
+
+    const TRACE_ID: [u8; 16] = [
+        0x4b, 0xf9, 0x2f, 0x35, 0x77, 0xb3, 0x4d, 0xa6, 0xa3, 0xce, 0x92, 0x9d, 0x0e, 0x0e, 0x47,
+        0x36,
+    ];
+    const SPAN_ID: [u8; 8] = [0x00, 0xf0, 0x67, 0xaa, 0x0b, 0xa9, 0x02, 0xb7];
+    const PARENT_SPAN_ID: [u8; 8] = [0x53, 0x99, 0x5c, 0x3f, 0x42, 0xcd, 0x8a, 0xd8];
+
+    fn json_batch() -> Vec<u8> {
+        json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [
+                        {"key": "service.name", "value": {"stringValue": "checkout"}}
+                    ]
+                },
+                "scopeSpans": [{
+                    "spans": [{
+                        "traceId": "4bf92f3577b34da6a3ce929d0e0e4736",
+                        "spanId": "00f067aa0ba902b7",
+                        "parentSpanId": "53995c3f42cd8ad8",
+                        "name": "POST /orders",
+                        "kind": 2,
+                        "startTimeUnixNano": "1700000000000000000",
+                        "endTimeUnixNano": "1700000000250000000",
+                        "attributes": [
+                            {"key": "http.status_code", "value": {"intValue": "500"}},
+                            {"key": "retry", "value": {"boolValue": true}}
+                        ],
+                        "events": [{
+                            "timeUnixNano": "1700000000100000000",
+                            "name": "exception",
+                            "attributes": [
+                                {"key": "exception.type", "value": {"stringValue": "Timeout"}}
+                            ]
+                        }],
+                        "status": {"code": 2, "message": "upstream timed out"}
+                    }]
+                }]
+            }]
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    fn protobuf_batch() -> Vec<u8> {
+        let attribute = |key: &str, value: proto::AnyValueKind| proto::KeyValue {
+            key: key.to_string(),
+            value: Some(proto::AnyValue { value: Some(value) }),
+        };
+        proto::ExportTraceServiceRequest {
+            resource_spans: vec![proto::ResourceSpans {
+                resource: Some(proto::Resource {
+                    attributes: vec![attribute(
+                        "service.name",
+                        proto::AnyValueKind::StringValue("checkout".to_string()),
+                    )],
+                    dropped_attributes_count: 0,
+                }),
+                scope_spans: vec![proto::ScopeSpans {
+                    scope: None,
+                    spans: vec![proto::Span {
+                        trace_id: TRACE_ID.to_vec(),
+                        span_id: SPAN_ID.to_vec(),
+                        parent_span_id: PARENT_SPAN_ID.to_vec(),
+                        name: "POST /orders".to_string(),
+                        kind: 2,
+                        start_time_unix_nano: 1_700_000_000_000_000_000,
+                        end_time_unix_nano: 1_700_000_000_250_000_000,
+                        attributes: vec![
+                            attribute("http.status_code", proto::AnyValueKind::IntValue(500)),
+                            attribute("retry", proto::AnyValueKind::BoolValue(true)),
+                        ],
+                        events: vec![proto::SpanEvent {
+                            time_unix_nano: 1_700_000_000_100_000_000,
+                            name: "exception".to_string(),
+                            attributes: vec![attribute(
+                                "exception.type",
+                                proto::AnyValueKind::StringValue("Timeout".to_string()),
+                            )],
+                            dropped_attributes_count: 0,
+                        }],
+                        status: Some(proto::SpanStatus {
+                            message: "upstream timed out".to_string(),
+                            code: 2,
+                        }),
+                        ..Default::default()
+                    }],
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        }
+        .encode_to_vec()
+    }
+
+    /// Post `body` with `content_type` to a fresh service, returning the
+    /// response and the spans it stored
+    async fn post_traces(content_type: &str, body: Vec<u8>) -> (Response, Vec<Span>) {
+        let spans_repo = Arc::new(InMemorySpansRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let project = project_repo.seed("project-1", "org-1");
+        let service = TraceService::new(
+            spans_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+        let ctx = ApiKeyContext {
+            project_id: ProjectId::new("project-1".to_string()),
+            project,
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, content_type.parse().unwrap());
+
+        let response = match ingest_otlp_traces(
+            State(Arc::new(service)),
+            Extension(ctx),
+            headers,
+            Bytes::from(body),
+        )
+        .await
+        {
+            Ok(response) => response,
+            Err(e) => e.into_response(),
+        };
+        (response, spans_repo.saved())
+    }
+
+    /// Everything ingestion keeps from a span, except when it was received
+    fn stored(spans: &[Span]) -> Vec<String> {
+        spans
+            .iter()
+            .map(|s| {
+                format!(
+                    "{:?} {:?}",
+                    (
+                        s.trace_id(),
+                        s.span_id(),
+                        s.parent_span_id(),
+                        s.name(),
+                        s.kind(),
+                        s.start_time(),
+                        s.end_time(),
+                    ),
+                    (
+                        s.status(),
+                        s.status_message(),
+                        s.service_name(),
+                        s.attributes(),
+                        s.resource_attributes(),
+                        s.events(),
+                    )
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_json_and_protobuf_batches_ingest_identically() {
+        let (json_response, json_spans) = post_traces("application/json", json_batch()).await;
+        let (proto_response, proto_spans) =
+            post_traces("application/x-protobuf", protobuf_batch()).await;
+
+        assert_eq!(json_response.status(), StatusCode::OK);
+        assert_eq!(proto_response.status(), StatusCode::OK);
+        assert_eq!(json_spans.len(), 1);
+        assert_eq!(stored(&json_spans), stored(&proto_spans));
+        assert_eq!(json_spans[0].trace_id(), "4bf92f3577b34da6a3ce929d0e0e4736");
+
+        // Each is answered in its own encoding
+        assert_eq!(json_response.headers()[CONTENT_TYPE], "application/json");
+        assert_eq!(
+            proto_response.headers()[CONTENT_TYPE],
+            "application/x-protobuf"
+        );
+        let body = axum::body::to_bytes(proto_response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let decoded = proto::ExportTraceServiceResponse::decode(body).unwrap();
+        assert_eq!(decoded.partial_success, None);
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_content_type_is_rejected() {
+        let (response, spans) = post_traces("text/plain", json_batch()).await;
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert!(spans.is_empty());
+    }
 }
@@ -1,6 +1,6 @@
 //! OTLP HTTP routes
 
-use axum::{middleware, routing::post, Router};
+use axum::{Router, extract::DefaultBodyLimit, middleware, routing::post};
 use std::sync::Arc;
 
 use super::handlers;
@@ -79,11 +79,14 @@ where
     AR: ApiKeyRepository + 'static,
     OR: OrganizationRepository + 'static,
 {
+    let max_bytes = service.batch_limits().max_bytes;
+
     Router::new()
         .route(
             "/traces",
             post(handlers::ingest_otlp_traces::<SR, PR, OMR, ID>),
         )
+        .layer(DefaultBodyLimit::max(max_bytes))
         .layer(middleware::from_fn_with_state(
             project_service,
             api_key_middleware::<PPR, AR, OR, OMR, ID>,
@@ -1,6 +1,7 @@
 pub mod common;
 pub mod logs;
 pub mod metrics;
+pub mod proto;
 pub mod traces;
 
 pub use common::*;
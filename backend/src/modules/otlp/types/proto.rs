@@ -0,0 +1,917 @@
+//! OTLP protobuf messages (opentelemetry-proto v1) for `application/x-protobuf`
+//! requests. Decoded messages convert into the JSON-mapped types so both
+//! encodings share one conversion path, and responses convert back.
+
+use base64::Engine;
+
+use super::{common, logs, metrics, traces};
+
+// ============================================================================
+// Common
+// ============================================================================
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AnyValue {
+    #[prost(oneof = "AnyValueKind", tags = "1, 2, 3, 4, 5, 6, 7")]
+    pub value: Option<AnyValueKind>,
+}
+
+/// Variant names mirror the `oneof` fields in the proto definition
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub enum AnyValueKind {
+    #[prost(string, tag = "1")]
+    StringValue(String),
+    #[prost(bool, tag = "2")]
+    BoolValue(bool),
+    #[prost(int64, tag = "3")]
+    IntValue(i64),
+    #[prost(double, tag = "4")]
+    DoubleValue(f64),
+    #[prost(message, tag = "5")]
+    ArrayValue(ArrayValue),
+    #[prost(message, tag = "6")]
+    KvlistValue(KeyValueList),
+    #[prost(bytes, tag = "7")]
+    BytesValue(Vec<u8>),
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ArrayValue {
+    #[prost(message, repeated, tag = "1")]
+    pub values: Vec<AnyValue>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct KeyValueList {
+    #[prost(message, repeated, tag = "1")]
+    pub values: Vec<KeyValue>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct KeyValue {
+    #[prost(string, tag = "1")]
+    pub key: String,
+    #[prost(message, optional, tag = "2")]
+    pub value: Option<AnyValue>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct InstrumentationScope {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    pub version: String,
+    #[prost(message, repeated, tag = "3")]
+    pub attributes: Vec<KeyValue>,
+    #[prost(uint32, tag = "4")]
+    pub dropped_attributes_count: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Resource {
+    #[prost(message, repeated, tag = "1")]
+    pub attributes: Vec<KeyValue>,
+    #[prost(uint32, tag = "2")]
+    pub dropped_attributes_count: u32,
+}
+
+/// `google.rpc.Status`, the error body for protobuf requests
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Status {
+    #[prost(int32, tag = "1")]
+    pub code: i32,
+    #[prost(string, tag = "2")]
+    pub message: String,
+}
+
+// ============================================================================
+// Logs
+// ============================================================================
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExportLogsServiceRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub resource_logs: Vec<ResourceLogs>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ResourceLogs {
+    #[prost(message, optional, tag = "1")]
+    pub resource: Option<Resource>,
+    #[prost(message, repeated, tag = "2")]
+    pub scope_logs: Vec<ScopeLogs>,
+    #[prost(string, tag = "3")]
+    pub schema_url: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ScopeLogs {
+    #[prost(message, optional, tag = "1")]
+    pub scope: Option<InstrumentationScope>,
+    #[prost(message, repeated, tag = "2")]
+    pub log_records: Vec<LogRecord>,
+    #[prost(string, tag = "3")]
+    pub schema_url: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct LogRecord {
+    #[prost(fixed64, tag = "1")]
+    pub time_unix_nano: u64,
+    #[prost(fixed64, tag = "11")]
+    pub observed_time_unix_nano: u64,
+    #[prost(int32, tag = "2")]
+    pub severity_number: i32,
+    #[prost(string, tag = "3")]
+    pub severity_text: String,
+    #[prost(message, optional, tag = "5")]
+    pub body: Option<AnyValue>,
+    #[prost(message, repeated, tag = "6")]
+    pub attributes: Vec<KeyValue>,
+    #[prost(uint32, tag = "7")]
+    pub dropped_attributes_count: u32,
+    #[prost(fixed32, tag = "8")]
+    pub flags: u32,
+    #[prost(bytes, tag = "9")]
+    pub trace_id: Vec<u8>,
+    #[prost(bytes, tag = "10")]
+    pub span_id: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExportLogsServiceResponse {
+    #[prost(message, optional, tag = "1")]
+    pub partial_success: Option<ExportLogsPartialSuccess>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExportLogsPartialSuccess {
+    #[prost(int64, tag = "1")]
+    pub rejected_log_records: i64,
+    #[prost(string, tag = "2")]
+    pub error_message: String,
+}
+
+// ============================================================================
+// Metrics
+// ============================================================================
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExportMetricsServiceRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub resource_metrics: Vec<ResourceMetrics>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ResourceMetrics {
+    #[prost(message, optional, tag = "1")]
+    pub resource: Option<Resource>,
+    #[prost(message, repeated, tag = "2")]
+    pub scope_metrics: Vec<ScopeMetrics>,
+    #[prost(string, tag = "3")]
+    pub schema_url: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ScopeMetrics {
+    #[prost(message, optional, tag = "1")]
+    pub scope: Option<InstrumentationScope>,
+    #[prost(message, repeated, tag = "2")]
+    pub metrics: Vec<Metric>,
+    #[prost(string, tag = "3")]
+    pub schema_url: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Metric {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    pub description: String,
+    #[prost(string, tag = "3")]
+    pub unit: String,
+    #[prost(oneof = "MetricData", tags = "5, 7, 9, 10, 11")]
+    pub data: Option<MetricData>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub enum MetricData {
+    #[prost(message, tag = "5")]
+    Gauge(Gauge),
+    #[prost(message, tag = "7")]
+    Sum(Sum),
+    #[prost(message, tag = "9")]
+    Histogram(Histogram),
+    #[prost(message, tag = "10")]
+    ExponentialHistogram(ExponentialHistogram),
+    #[prost(message, tag = "11")]
+    Summary(Summary),
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Gauge {
+    #[prost(message, repeated, tag = "1")]
+    pub data_points: Vec<NumberDataPoint>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Sum {
+    #[prost(message, repeated, tag = "1")]
+    pub data_points: Vec<NumberDataPoint>,
+    #[prost(int32, tag = "2")]
+    pub aggregation_temporality: i32,
+    #[prost(bool, tag = "3")]
+    pub is_monotonic: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Histogram {
+    #[prost(message, repeated, tag = "1")]
+    pub data_points: Vec<HistogramDataPoint>,
+    #[prost(int32, tag = "2")]
+    pub aggregation_temporality: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExponentialHistogram {
+    #[prost(message, repeated, tag = "1")]
+    pub data_points: Vec<ExponentialHistogramDataPoint>,
+    #[prost(int32, tag = "2")]
+    pub aggregation_temporality: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Summary {
+    #[prost(message, repeated, tag = "1")]
+    pub data_points: Vec<SummaryDataPoint>,
+}
+
+/// Value of a number data point or exemplar
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub enum NumberValue {
+    #[prost(double, tag = "4")]
+    AsDouble(f64),
+    #[prost(sfixed64, tag = "6")]
+    AsInt(i64),
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct NumberDataPoint {
+    #[prost(message, repeated, tag = "7")]
+    pub attributes: Vec<KeyValue>,
+    #[prost(fixed64, tag = "2")]
+    pub start_time_unix_nano: u64,
+    #[prost(fixed64, tag = "3")]
+    pub time_unix_nano: u64,
+    #[prost(oneof = "NumberValue", tags = "4, 6")]
+    pub value: Option<NumberValue>,
+    #[prost(message, repeated, tag = "5")]
+    pub exemplars: Vec<Exemplar>,
+    #[prost(uint32, tag = "8")]
+    pub flags: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct HistogramDataPoint {
+    #[prost(message, repeated, tag = "9")]
+    pub attributes: Vec<KeyValue>,
+    #[prost(fixed64, tag = "2")]
+    pub start_time_unix_nano: u64,
+    #[prost(fixed64, tag = "3")]
+    pub time_unix_nano: u64,
+    #[prost(fixed64, tag = "4")]
+    pub count: u64,
+    #[prost(double, optional, tag = "5")]
+    pub sum: Option<f64>,
+    #[prost(fixed64, repeated, tag = "6")]
+    pub bucket_counts: Vec<u64>,
+    #[prost(double, repeated, tag = "7")]
+    pub explicit_bounds: Vec<f64>,
+    #[prost(message, repeated, tag = "8")]
+    pub exemplars: Vec<Exemplar>,
+    #[prost(uint32, tag = "10")]
+    pub flags: u32,
+    #[prost(double, optional, tag = "11")]
+    pub min: Option<f64>,
+    #[prost(double, optional, tag = "12")]
+    pub max: Option<f64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExponentialHistogramDataPoint {
+    #[prost(message, repeated, tag = "1")]
+    pub attributes: Vec<KeyValue>,
+    #[prost(fixed64, tag = "2")]
+    pub start_time_unix_nano: u64,
+    #[prost(fixed64, tag = "3")]
+    pub time_unix_nano: u64,
+    #[prost(fixed64, tag = "4")]
+    pub count: u64,
+    #[prost(double, optional, tag = "5")]
+    pub sum: Option<f64>,
+    #[prost(sint32, tag = "6")]
+    pub scale: i32,
+    #[prost(fixed64, tag = "7")]
+    pub zero_count: u64,
+    #[prost(message, optional, tag = "8")]
+    pub positive: Option<Buckets>,
+    #[prost(message, optional, tag = "9")]
+    pub negative: Option<Buckets>,
+    #[prost(uint32, tag = "10")]
+    pub flags: u32,
+    #[prost(message, repeated, tag = "11")]
+    pub exemplars: Vec<Exemplar>,
+    #[prost(double, optional, tag = "12")]
+    pub min: Option<f64>,
+    #[prost(double, optional, tag = "13")]
+    pub max: Option<f64>,
+    #[prost(double, tag = "14")]
+    pub zero_threshold: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Buckets {
+    #[prost(sint32, tag = "1")]
+    pub offset: i32,
+    #[prost(uint64, repeated, tag = "2")]
+    pub bucket_counts: Vec<u64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SummaryDataPoint {
+    #[prost(message, repeated, tag = "7")]
+    pub attributes: Vec<KeyValue>,
+    #[prost(fixed64, tag = "2")]
+    pub start_time_unix_nano: u64,
+    #[prost(fixed64, tag = "3")]
+    pub time_unix_nano: u64,
+    #[prost(fixed64, tag = "4")]
+    pub count: u64,
+    #[prost(double, tag = "5")]
+    pub sum: f64,
+    #[prost(message, repeated, tag = "6")]
+    pub quantile_values: Vec<ValueAtQuantile>,
+    #[prost(uint32, tag = "8")]
+    pub flags: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ValueAtQuantile {
+    #[prost(double, tag = "1")]
+    pub quantile: f64,
+    #[prost(double, tag = "2")]
+    pub value: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Exemplar {
+    #[prost(message, repeated, tag = "7")]
+    pub filtered_attributes: Vec<KeyValue>,
+    #[prost(fixed64, tag = "2")]
+    pub time_unix_nano: u64,
+    #[prost(oneof = "ExemplarValue", tags = "3, 6")]
+    pub value: Option<ExemplarValue>,
+    #[prost(bytes, tag = "4")]
+    pub span_id: Vec<u8>,
+    #[prost(bytes, tag = "5")]
+    pub trace_id: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub enum ExemplarValue {
+    #[prost(double, tag = "3")]
+    AsDouble(f64),
+    #[prost(sfixed64, tag = "6")]
+    AsInt(i64),
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExportMetricsServiceResponse {
+    #[prost(message, optional, tag = "1")]
+    pub partial_success: Option<ExportMetricsPartialSuccess>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExportMetricsPartialSuccess {
+    #[prost(int64, tag = "1")]
+    pub rejected_data_points: i64,
+    #[prost(string, tag = "2")]
+    pub error_message: String,
+}
+
+// ============================================================================
+// Traces
+// ============================================================================
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExportTraceServiceRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub resource_spans: Vec<ResourceSpans>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ResourceSpans {
+    #[prost(message, optional, tag = "1")]
+    pub resource: Option<Resource>,
+    #[prost(message, repeated, tag = "2")]
+    pub scope_spans: Vec<ScopeSpans>,
+    #[prost(string, tag = "3")]
+    pub schema_url: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ScopeSpans {
+    #[prost(message, optional, tag = "1")]
+    pub scope: Option<InstrumentationScope>,
+    #[prost(message, repeated, tag = "2")]
+    pub spans: Vec<Span>,
+    #[prost(string, tag = "3")]
+    pub schema_url: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Span {
+    #[prost(bytes, tag = "1")]
+    pub trace_id: Vec<u8>,
+    #[prost(bytes, tag = "2")]
+    pub span_id: Vec<u8>,
+    #[prost(string, tag = "3")]
+    pub trace_state: String,
+    #[prost(bytes, tag = "4")]
+    pub parent_span_id: Vec<u8>,
+    #[prost(string, tag = "5")]
+    pub name: String,
+    #[prost(int32, tag = "6")]
+    pub kind: i32,
+    #[prost(fixed64, tag = "7")]
+    pub start_time_unix_nano: u64,
+    #[prost(fixed64, tag = "8")]
+    pub end_time_unix_nano: u64,
+    #[prost(message, repeated, tag = "9")]
+    pub attributes: Vec<KeyValue>,
+    #[prost(uint32, tag = "10")]
+    pub dropped_attributes_count: u32,
+    #[prost(message, repeated, tag = "11")]
+    pub events: Vec<SpanEvent>,
+    #[prost(uint32, tag = "12")]
+    pub dropped_events_count: u32,
+    #[prost(message, repeated, tag = "13")]
+    pub links: Vec<SpanLink>,
+    #[prost(uint32, tag = "14")]
+    pub dropped_links_count: u32,
+    #[prost(message, optional, tag = "15")]
+    pub status: Option<SpanStatus>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SpanEvent {
+    #[prost(fixed64, tag = "1")]
+    pub time_unix_nano: u64,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(message, repeated, tag = "3")]
+    pub attributes: Vec<KeyValue>,
+    #[prost(uint32, tag = "4")]
+    pub dropped_attributes_count: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SpanLink {
+    #[prost(bytes, tag = "1")]
+    pub trace_id: Vec<u8>,
+    #[prost(bytes, tag = "2")]
+    pub span_id: Vec<u8>,
+    #[prost(string, tag = "3")]
+    pub trace_state: String,
+    #[prost(message, repeated, tag = "4")]
+    pub attributes: Vec<KeyValue>,
+    #[prost(uint32, tag = "5")]
+    pub dropped_attributes_count: u32,
+    #[prost(fixed32, tag = "6")]
+    pub flags: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SpanStatus {
+    #[prost(string, tag = "2")]
+    pub message: String,
+    #[prost(int32, tag = "3")]
+    pub code: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExportTraceServiceResponse {
+    #[prost(message, optional, tag = "1")]
+    pub partial_success: Option<ExportTracePartialSuccess>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExportTracePartialSuccess {
+    #[prost(int64, tag = "1")]
+    pub rejected_spans: i64,
+    #[prost(string, tag = "2")]
+    pub error_message: String,
+}
+
+// ============================================================================
+// Conversion to the JSON-mapped types
+// ============================================================================
+
+/// Trace and span ids are hex in OTLP/JSON
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Unset (zero) timestamps are left empty, as when omitted from JSON
+fn nanos(value: u64) -> String {
+    if value == 0 {
+        String::new()
+    } else {
+        value.to_string()
+    }
+}
+
+fn key_values(attributes: Vec<KeyValue>) -> Vec<common::KeyValue> {
+    attributes.into_iter().map(Into::into).collect()
+}
+
+impl From<AnyValue> for common::AnyValue {
+    fn from(value: AnyValue) -> Self {
+        let mut json = common::AnyValue::default();
+        match value.value {
+            Some(AnyValueKind::StringValue(s)) => json.string_value = Some(s),
+            Some(AnyValueKind::BoolValue(b)) => json.bool_value = Some(b),
+            Some(AnyValueKind::IntValue(i)) => json.int_value = Some(i.to_string()),
+            Some(AnyValueKind::DoubleValue(d)) => json.double_value = Some(d),
+            Some(AnyValueKind::ArrayValue(array)) => {
+                json.array_value = Some(common::ArrayValue {
+                    values: array.values.into_iter().map(Into::into).collect(),
+                })
+            }
+            Some(AnyValueKind::KvlistValue(list)) => {
+                json.kvlist_value = Some(common::KeyValueList {
+                    values: key_values(list.values),
+                })
+            }
+            Some(AnyValueKind::BytesValue(bytes)) => {
+                json.bytes_value = Some(base64::engine::general_purpose::STANDARD.encode(bytes))
+            }
+            None => {}
+        }
+        json
+    }
+}
+
+impl From<KeyValue> for common::KeyValue {
+    fn from(kv: KeyValue) -> Self {
+        Self {
+            key: kv.key,
+            value: kv.value.map(Into::into).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<InstrumentationScope> for common::InstrumentationScope {
+    fn from(scope: InstrumentationScope) -> Self {
+        Self {
+            name: scope.name,
+            version: scope.version,
+            attributes: key_values(scope.attributes),
+            dropped_attributes_count: scope.dropped_attributes_count,
+        }
+    }
+}
+
+impl From<Resource> for common::Resource {
+    fn from(resource: Resource) -> Self {
+        Self {
+            attributes: key_values(resource.attributes),
+            dropped_attributes_count: resource.dropped_attributes_count,
+        }
+    }
+}
+
+impl From<ExportLogsServiceRequest> for logs::ExportLogsServiceRequest {
+    fn from(request: ExportLogsServiceRequest) -> Self {
+        Self {
+            resource_logs: request
+                .resource_logs
+                .into_iter()
+                .map(|rl| logs::ResourceLogs {
+                    resource: rl.resource.map(Into::into),
+                    scope_logs: rl
+                        .scope_logs
+                        .into_iter()
+                        .map(|sl| logs::ScopeLogs {
+                            scope: sl.scope.map(Into::into),
+                            log_records: sl.log_records.into_iter().map(Into::into).collect(),
+                            schema_url: sl.schema_url,
+                        })
+                        .collect(),
+                    schema_url: rl.schema_url,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<LogRecord> for logs::LogRecord {
+    fn from(record: LogRecord) -> Self {
+        Self {
+            time_unix_nano: nanos(record.time_unix_nano),
+            observed_time_unix_nano: nanos(record.observed_time_unix_nano),
+            severity_number: record.severity_number,
+            severity_text: record.severity_text,
+            body: record.body.map(Into::into),
+            attributes: key_values(record.attributes),
+            dropped_attributes_count: record.dropped_attributes_count,
+            flags: record.flags,
+            trace_id: hex(&record.trace_id),
+            span_id: hex(&record.span_id),
+        }
+    }
+}
+
+impl From<ExportMetricsServiceRequest> for metrics::ExportMetricsServiceRequest {
+    fn from(request: ExportMetricsServiceRequest) -> Self {
+        Self {
+            resource_metrics: request
+                .resource_metrics
+                .into_iter()
+                .map(|rm| metrics::ResourceMetrics {
+                    resource: rm.resource.map(Into::into),
+                    scope_metrics: rm
+                        .scope_metrics
+                        .into_iter()
+                        .map(|sm| metrics::ScopeMetrics {
+                            scope: sm.scope.map(Into::into),
+                            metrics: sm.metrics.into_iter().map(Into::into).collect(),
+                            schema_url: sm.schema_url,
+                        })
+                        .collect(),
+                    schema_url: rm.schema_url,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<Metric> for metrics::Metric {
+    fn from(metric: Metric) -> Self {
+        let mut json = metrics::Metric {
+            name: metric.name,
+            description: metric.description,
+            unit: metric.unit,
+            gauge: None,
+            sum: None,
+            histogram: None,
+            exponential_histogram: None,
+            summary: None,
+        };
+        match metric.data {
+            Some(MetricData::Gauge(gauge)) => {
+                json.gauge = Some(metrics::Gauge {
+                    data_points: gauge.data_points.into_iter().map(Into::into).collect(),
+                })
+            }
+            Some(MetricData::Sum(sum)) => {
+                json.sum = Some(metrics::Sum {
+                    data_points: sum.data_points.into_iter().map(Into::into).collect(),
+                    aggregation_temporality: sum.aggregation_temporality,
+                    is_monotonic: sum.is_monotonic,
+                })
+            }
+            Some(MetricData::Histogram(histogram)) => {
+                json.histogram = Some(metrics::Histogram {
+                    data_points: histogram.data_points.into_iter().map(Into::into).collect(),
+                    aggregation_temporality: histogram.aggregation_temporality,
+                })
+            }
+            Some(MetricData::ExponentialHistogram(histogram)) => {
+                json.exponential_histogram = Some(metrics::ExponentialHistogram {
+                    data_points: histogram.data_points.into_iter().map(Into::into).collect(),
+                    aggregation_temporality: histogram.aggregation_temporality,
+                })
+            }
+            Some(MetricData::Summary(summary)) => {
+                json.summary = Some(metrics::Summary {
+                    data_points: summary.data_points.into_iter().map(Into::into).collect(),
+                })
+            }
+            None => {}
+        }
+        json
+    }
+}
+
+impl From<NumberDataPoint> for metrics::NumberDataPoint {
+    fn from(point: NumberDataPoint) -> Self {
+        Self {
+            attributes: key_values(point.attributes),
+            start_time_unix_nano: nanos(point.start_time_unix_nano),
+            time_unix_nano: nanos(point.time_unix_nano),
+            as_double: match point.value {
+                Some(NumberValue::AsDouble(d)) => Some(d),
+                _ => None,
+            },
+            as_int: match point.value {
+                Some(NumberValue::AsInt(i)) => Some(i.to_string()),
+                _ => None,
+            },
+            exemplars: point.exemplars.into_iter().map(Into::into).collect(),
+            flags: point.flags,
+        }
+    }
+}
+
+impl From<HistogramDataPoint> for metrics::HistogramDataPoint {
+    fn from(point: HistogramDataPoint) -> Self {
+        Self {
+            attributes: key_values(point.attributes),
+            start_time_unix_nano: nanos(point.start_time_unix_nano),
+            time_unix_nano: nanos(point.time_unix_nano),
+            count: point.count.to_string(),
+            sum: point.sum,
+            bucket_counts: point.bucket_counts.iter().map(u64::to_string).collect(),
+            explicit_bounds: point.explicit_bounds,
+            exemplars: point.exemplars.into_iter().map(Into::into).collect(),
+            flags: point.flags,
+            min: point.min,
+            max: point.max,
+        }
+    }
+}
+
+impl From<ExponentialHistogramDataPoint> for metrics::ExponentialHistogramDataPoint {
+    fn from(point: ExponentialHistogramDataPoint) -> Self {
+        let buckets = |buckets: Buckets| metrics::Buckets {
+            offset: buckets.offset,
+            bucket_counts: buckets.bucket_counts.iter().map(u64::to_string).collect(),
+        };
+        Self {
+            attributes: key_values(point.attributes),
+            start_time_unix_nano: nanos(point.start_time_unix_nano),
+            time_unix_nano: nanos(point.time_unix_nano),
+            count: point.count.to_string(),
+            sum: point.sum,
+            scale: point.scale,
+            zero_count: point.zero_count.to_string(),
+            positive: point.positive.map(buckets),
+            negative: point.negative.map(buckets),
+            flags: point.flags,
+            exemplars: point.exemplars.into_iter().map(Into::into).collect(),
+            min: point.min,
+            max: point.max,
+            zero_threshold: Some(point.zero_threshold),
+        }
+    }
+}
+
+impl From<SummaryDataPoint> for metrics::SummaryDataPoint {
+    fn from(point: SummaryDataPoint) -> Self {
+        Self {
+            attributes: key_values(point.attributes),
+            start_time_unix_nano: nanos(point.start_time_unix_nano),
+            time_unix_nano: nanos(point.time_unix_nano),
+            count: point.count.to_string(),
+            sum: point.sum,
+            quantile_values: point
+                .quantile_values
+                .into_iter()
+                .map(|q| metrics::ValueAtQuantile {
+                    quantile: q.quantile,
+                    value: q.value,
+                })
+                .collect(),
+            flags: point.flags,
+        }
+    }
+}
+
+impl From<Exemplar> for metrics::Exemplar {
+    fn from(exemplar: Exemplar) -> Self {
+        Self {
+            filtered_attributes: key_values(exemplar.filtered_attributes),
+            time_unix_nano: nanos(exemplar.time_unix_nano),
+            as_double: match exemplar.value {
+                Some(ExemplarValue::AsDouble(d)) => Some(d),
+                _ => None,
+            },
+            as_int: match exemplar.value {
+                Some(ExemplarValue::AsInt(i)) => Some(i.to_string()),
+                _ => None,
+            },
+            span_id: hex(&exemplar.span_id),
+            trace_id: hex(&exemplar.trace_id),
+        }
+    }
+}
+
+impl From<ExportTraceServiceRequest> for traces::ExportTraceServiceRequest {
+    fn from(request: ExportTraceServiceRequest) -> Self {
+        Self {
+            resource_spans: request
+                .resource_spans
+                .into_iter()
+                .map(|rs| traces::ResourceSpans {
+                    resource: rs.resource.map(Into::into),
+                    scope_spans: rs
+                        .scope_spans
+                        .into_iter()
+                        .map(|ss| traces::ScopeSpans {
+                            scope: ss.scope.map(Into::into),
+                            spans: ss.spans.into_iter().map(Into::into).collect(),
+                            schema_url: ss.schema_url,
+                        })
+                        .collect(),
+                    schema_url: rs.schema_url,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<Span> for traces::OtlpSpan {
+    fn from(span: Span) -> Self {
+        Self {
+            trace_id: hex(&span.trace_id),
+            span_id: hex(&span.span_id),
+            trace_state: span.trace_state,
+            parent_span_id: hex(&span.parent_span_id),
+            name: span.name,
+            kind: span.kind,
+            start_time_unix_nano: nanos(span.start_time_unix_nano),
+            end_time_unix_nano: nanos(span.end_time_unix_nano),
+            attributes: key_values(span.attributes),
+            dropped_attributes_count: span.dropped_attributes_count,
+            events: span
+                .events
+                .into_iter()
+                .map(|e| traces::SpanEvent {
+                    time_unix_nano: nanos(e.time_unix_nano),
+                    name: e.name,
+                    attributes: key_values(e.attributes),
+                    dropped_attributes_count: e.dropped_attributes_count,
+                })
+                .collect(),
+            dropped_events_count: span.dropped_events_count,
+            links: span
+                .links
+                .into_iter()
+                .map(|l| traces::SpanLink {
+                    trace_id: hex(&l.trace_id),
+                    span_id: hex(&l.span_id),
+                    trace_state: l.trace_state,
+                    attributes: key_values(l.attributes),
+                    dropped_attributes_count: l.dropped_attributes_count,
+                    flags: l.flags,
+                })
+                .collect(),
+            dropped_links_count: span.dropped_links_count,
+            status: span.status.map(|s| traces::SpanStatus {
+                code: s.code,
+                message: s.message,
+            }),
+        }
+    }
+}
+
+// ============================================================================
+// Responses
+// ============================================================================
+
+impl From<logs::ExportLogsServiceResponse> for ExportLogsServiceResponse {
+    fn from(response: logs::ExportLogsServiceResponse) -> Self {
+        Self {
+            partial_success: response.partial_success.map(|p| ExportLogsPartialSuccess {
+                rejected_log_records: p.rejected_log_records,
+                error_message: p.error_message,
+            }),
+        }
+    }
+}
+
+impl From<metrics::ExportMetricsServiceResponse> for ExportMetricsServiceResponse {
+    fn from(response: metrics::ExportMetricsServiceResponse) -> Self {
+        Self {
+            partial_success: response
+                .partial_success
+                .map(|p| ExportMetricsPartialSuccess {
+                    rejected_data_points: p.rejected_data_points,
+                    error_message: p.error_message,
+                }),
+        }
+    }
+}
+
+impl From<traces::ExportTraceServiceResponse> for ExportTraceServiceResponse {
+    fn from(response: traces::ExportTraceServiceResponse) -> Self {
+        Self {
+            partial_success: response.partial_success.map(|p| ExportTracePartialSuccess {
+                rejected_spans: p.rejected_spans,
+                error_message: p.error_message,
+            }),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::modules::projects::domain::ApiKey;
+
+/// How long API key lookups are remembered
+#[derive(Debug, Clone, Copy)]
+pub struct ApiKeyCacheConfig {
+    /// For keys that authenticate; 0 never caches them
+    pub ttl: Duration,
+    /// For unknown, revoked and expired keys; 0 never caches them
+    pub negative_ttl: Duration,
+    pub max_entries: usize,
+}
+
+struct CachedLookup {
+    /// None when no key has the hash
+    key: Option<ApiKey>,
+    expires_at: Instant,
+}
+
+/// In-memory cache of API key lookups by key hash, so repeated ingest
+/// requests (and repeated attempts with a bad key) don't each query the
+/// database. Only the key is cached; its project is still read fresh.
+pub struct ApiKeyCache {
+    config: ApiKeyCacheConfig,
+    entries: Mutex<HashMap<String, CachedLookup>>,
+}
+
+impl ApiKeyCache {
+    pub fn new(config: ApiKeyCacheConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The remembered lookup for `key_hash`: `Some(None)` for a key known not
+    /// to exist, `None` when the database has to be asked
+    pub fn get(&self, key_hash: &str) -> Option<Option<ApiKey>> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key_hash) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.key.clone()),
+            Some(_) => {
+                entries.remove(key_hash);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Remember a lookup, for the negative TTL unless it found a usable key
+    pub fn insert(&self, key_hash: &str, key: Option<ApiKey>) {
+        let usable = key
+            .as_ref()
+            .is_some_and(|k| !k.is_revoked() && !k.is_expired());
+        let ttl = if usable {
+            self.config.ttl
+        } else {
+            self.config.negative_ttl
+        };
+        if ttl.is_zero() {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.config.max_entries && !entries.contains_key(key_hash) {
+            entries.retain(|_, entry| entry.expires_at > now);
+            // Still full: drop whatever expires soonest
+            if entries.len() >= self.config.max_entries
+                && let Some(oldest) = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.expires_at)
+                    .map(|(hash, _)| hash.clone())
+            {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            key_hash.to_string(),
+            CachedLookup {
+                key,
+                expires_at: now + ttl,
+            },
+        );
+    }
+
+    /// Forget `key_hash`, after the key is created or revoked here. Other
+    /// instances notice once their entry expires.
+    pub fn invalidate(&self, key_hash: &str) {
+        self.entries.lock().unwrap().remove(key_hash);
+    }
+}
@@ -1,4 +1,9 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::modules::projects::domain::{ProjectSettings, ServiceLink, TraceSamplingSettings};
 
 // ==================== Commands ====================
 
@@ -23,6 +28,8 @@ pub struct UpdateProjectCommand {
     pub retention_days: Option<i32>,
     pub metrics_retention_days: Option<i32>,
     pub traces_retention_days: Option<i32>,
+    /// Partial settings update, merged over the current settings
+    pub settings: Option<Value>,
     pub requesting_user_id: String,
 }
 
@@ -33,6 +40,23 @@ pub struct DeleteProjectCommand {
     pub requesting_user_id: String,
 }
 
+/// Command to switch a project's feature flags
+#[derive(Debug, Clone)]
+pub struct UpdateFeatureFlagsCommand {
+    pub project_id: String,
+    /// Flag values by name; `None` resets the flag to its default
+    pub flags: BTreeMap<String, Option<bool>>,
+    pub requesting_user_id: String,
+}
+
+/// Command to replace a project's trace sampling policy
+#[derive(Debug, Clone)]
+pub struct UpdateTraceSamplingCommand {
+    pub project_id: String,
+    pub policy: TraceSamplingSettings,
+    pub requesting_user_id: String,
+}
+
 /// Command to create an API key
 #[derive(Debug, Clone)]
 pub struct CreateApiKeyCommand {
@@ -50,6 +74,17 @@ pub struct RevokeApiKeyCommand {
     pub requesting_user_id: String,
 }
 
+/// Command to set a service's display metadata
+#[derive(Debug, Clone)]
+pub struct UpsertServiceMetadataCommand {
+    pub project_id: String,
+    pub service_name: String,
+    pub display_name: String,
+    pub team: Option<String>,
+    pub links: Vec<ServiceLink>,
+    pub requesting_user_id: String,
+}
+
 // ==================== Responses ====================
 
 /// Response for project data
@@ -62,10 +97,25 @@ pub struct ProjectResponse {
     pub retention_days: i32,
     pub metrics_retention_days: i32,
     pub traces_retention_days: i32,
+    pub settings: ProjectSettings,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// A page of projects
+#[derive(Debug, Clone)]
+pub struct ProjectListResponse {
+    pub projects: Vec<ProjectResponse>,
+    /// Effective page size after applying the default and maximum
+    pub limit: i64,
+}
+
+/// Effective value of every feature flag of a project
+#[derive(Debug, Clone)]
+pub struct FeatureFlagsResponse {
+    pub flags: BTreeMap<String, bool>,
+}
+
 /// Response for API key data (without the actual key)
 #[derive(Debug, Clone)]
 pub struct ApiKeyResponse {
@@ -87,3 +137,29 @@ pub struct ApiKeyCreatedResponse {
     pub created_at: DateTime<Utc>,
     pub expires_at: Option<DateTime<Utc>>,
 }
+
+/// Display metadata configured for a service
+#[derive(Debug, Clone)]
+pub struct ServiceMetadataResponse {
+    pub service_name: String,
+    pub display_name: String,
+    pub team: Option<String>,
+    pub links: Vec<ServiceLink>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A project's public read token, without the token itself
+#[derive(Debug, Clone)]
+pub struct PublicReadTokenResponse {
+    pub token_prefix: String,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Response after creating a public read token; the only time it is shown
+#[derive(Debug, Clone)]
+pub struct PublicReadTokenCreatedResponse {
+    pub token_prefix: String,
+    pub plain_token: String,
+    pub created_at: DateTime<Utc>,
+}
@@ -1,5 +1,8 @@
+pub mod api_key_cache;
 pub mod dto;
+pub mod ports;
 pub mod services;
 
+pub use api_key_cache::{ApiKeyCache, ApiKeyCacheConfig};
 pub use dto::*;
-pub use services::ProjectService;
+pub use services::{ProjectService, PublicReadTokenService, ServiceMetadataService};
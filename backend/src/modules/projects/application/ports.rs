@@ -0,0 +1,17 @@
+use async_trait::async_trait;
+
+use crate::modules::projects::domain::{Project, ProjectDomainError};
+
+/// Port for work other modules do when a project is created, such as
+/// provisioning its default alerting
+#[async_trait]
+pub trait ProjectCreatedHook: Send + Sync {
+    async fn on_project_created(&self, project: &Project) -> Result<(), ProjectDomainError>;
+}
+
+/// Port for checking that a user verified their email address, required by
+/// deployments before the user can create ingest credentials
+#[async_trait]
+pub trait EmailVerificationCheck: Send + Sync {
+    async fn is_email_verified(&self, user_id: &str) -> Result<bool, ProjectDomainError>;
+}
@@ -1,3 +1,7 @@
 pub mod project_service;
+pub mod public_token_service;
+pub mod service_metadata_service;
 
 pub use project_service::ProjectService;
+pub use public_token_service::PublicReadTokenService;
+pub use service_metadata_service::ServiceMetadataService;
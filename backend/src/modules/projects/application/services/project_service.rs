@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use chrono::{Duration, Utc};
+use ipnet::IpNet;
 use rand::RngCore;
 use sha2::{Digest, Sha256};
 
@@ -10,11 +11,14 @@ use crate::modules::organizations::domain::{
     OrgId, OrgRole, OrganizationMemberRepository, OrganizationRepository,
 };
 use crate::modules::projects::application::dto::*;
+use crate::modules::projects::application::ports::{EmailVerificationCheck, ProjectCreatedHook};
+use crate::modules::projects::application::{ApiKeyCache, ApiKeyCacheConfig};
 use crate::modules::projects::domain::{
     ApiKey, ApiKeyId, ApiKeyName, ApiKeyPrefix, ApiKeyRepository, MetricsRetentionDays, Project,
     ProjectDomainError, ProjectId, ProjectName, ProjectRepository, RetentionDays,
-    TracesRetentionDays,
+    TraceSamplingSettings, TracesRetentionDays,
 };
+use crate::shared::PaginationConfig;
 
 /// Project service - orchestrates all project and API key use cases
 pub struct ProjectService<PR, AR, OR, MR, ID>
@@ -30,6 +34,17 @@ where
     org_repo: Arc<OR>,
     member_repo: Arc<MR>,
     id_generator: Arc<ID>,
+    pagination: PaginationConfig,
+    /// Region this deployment serves; projects pinned elsewhere can't ingest here
+    deployment_region: Option<String>,
+    /// Proxies whose X-Forwarded-For header is trusted when resolving an ingest client's IP
+    trusted_proxies: Vec<IpNet>,
+    /// Runs after a project is created; failures are logged, not returned
+    created_hook: Option<Arc<dyn ProjectCreatedHook>>,
+    /// When set, users must have verified their email to create API keys
+    email_verification: Option<Arc<dyn EmailVerificationCheck>>,
+    /// Recent API key lookups; every ingest request queries the database without it
+    api_key_cache: Option<ApiKeyCache>,
 }
 
 impl<PR, AR, OR, MR, ID> ProjectService<PR, AR, OR, MR, ID>
@@ -40,12 +55,16 @@ where
     MR: OrganizationMemberRepository,
     ID: IdGenerator,
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         project_repo: Arc<PR>,
         api_key_repo: Arc<AR>,
         org_repo: Arc<OR>,
         member_repo: Arc<MR>,
         id_generator: Arc<ID>,
+        pagination: PaginationConfig,
+        deployment_region: Option<String>,
+        trusted_proxies: Vec<IpNet>,
     ) -> Self {
         Self {
             project_repo,
@@ -53,6 +72,49 @@ where
             org_repo,
             member_repo,
             id_generator,
+            pagination,
+            deployment_region,
+            trusted_proxies,
+            created_hook: None,
+            email_verification: None,
+            api_key_cache: None,
+        }
+    }
+
+    /// Run `hook` after each project is created
+    pub fn with_created_hook(mut self, hook: Arc<dyn ProjectCreatedHook>) -> Self {
+        self.created_hook = Some(hook);
+        self
+    }
+
+    /// Only let users with a verified email address create API keys
+    pub fn with_email_verification(mut self, check: Arc<dyn EmailVerificationCheck>) -> Self {
+        self.email_verification = Some(check);
+        self
+    }
+
+    /// Cache API key lookups, including those of keys that don't authenticate
+    pub fn with_api_key_cache(mut self, config: ApiKeyCacheConfig) -> Self {
+        self.api_key_cache = Some(ApiKeyCache::new(config));
+        self
+    }
+
+    pub fn trusted_proxies(&self) -> &[IpNet] {
+        &self.trusted_proxies
+    }
+
+    fn project_to_response(project: &Project) -> ProjectResponse {
+        ProjectResponse {
+            id: project.id().as_str().to_string(),
+            name: project.name().as_str().to_string(),
+            description: project.description().map(|s| s.to_string()),
+            org_id: project.organization_id().as_str().to_string(),
+            retention_days: project.retention_days().value(),
+            metrics_retention_days: project.metrics_retention_days().value(),
+            traces_retention_days: project.traces_retention_days().value(),
+            settings: project.settings().clone(),
+            created_at: project.created_at(),
+            updated_at: project.updated_at(),
         }
     }
 
@@ -190,49 +252,48 @@ where
         // 6. Save project
         self.project_repo.save(&project).await?;
 
-        Ok(ProjectResponse {
-            id: project.id().as_str().to_string(),
-            name: project.name().as_str().to_string(),
-            description: project.description().map(|s| s.to_string()),
-            org_id: project.organization_id().as_str().to_string(),
-            retention_days: project.retention_days().value(),
-            metrics_retention_days: project.metrics_retention_days().value(),
-            traces_retention_days: project.traces_retention_days().value(),
-            created_at: project.created_at(),
-            updated_at: project.updated_at(),
-        })
+        // 7. Let other modules provision the project; it exists either way
+        if let Some(hook) = &self.created_hook
+            && let Err(e) = hook.on_project_created(&project).await
+        {
+            tracing::warn!(
+                project_id = %project.id().as_str(),
+                error = %e,
+                "Failed to provision new project"
+            );
+        }
+
+        Ok(Self::project_to_response(&project))
     }
 
-    /// List all projects in an organization
+    /// List projects in an organization (paginated)
     pub async fn list_projects(
         &self,
         org_id: &str,
         requesting_user_id: &str,
-    ) -> Result<Vec<ProjectResponse>, ProjectDomainError> {
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<ProjectListResponse, ProjectDomainError> {
         let org_id = OrgId::new(org_id.to_string());
 
         // Verify user is a member (any role)
         self.verify_org_membership(&org_id, requesting_user_id, false)
             .await?;
 
-        // Get all projects
-        let projects = self.project_repo.find_by_org(&org_id).await?;
+        // Get the requested page of projects
+        let pagination = self.pagination.resolve(limit, offset);
+        let projects = self
+            .project_repo
+            .find_page_by_org(&org_id, &pagination)
+            .await?
+            .iter()
+            .map(Self::project_to_response)
+            .collect();
 
-        Ok(projects
-            .into_iter()
-            .filter(|p| !p.is_deleted())
-            .map(|p| ProjectResponse {
-                id: p.id().as_str().to_string(),
-                name: p.name().as_str().to_string(),
-                description: p.description().map(|s| s.to_string()),
-                org_id: p.organization_id().as_str().to_string(),
-                retention_days: p.retention_days().value(),
-                metrics_retention_days: p.metrics_retention_days().value(),
-                traces_retention_days: p.traces_retention_days().value(),
-                created_at: p.created_at(),
-                updated_at: p.updated_at(),
-            })
-            .collect())
+        Ok(ProjectListResponse {
+            projects,
+            limit: pagination.limit,
+        })
     }
 
     /// Get project details
@@ -246,17 +307,7 @@ where
             .verify_project_access(&project_id, requesting_user_id, false)
             .await?;
 
-        Ok(ProjectResponse {
-            id: project.id().as_str().to_string(),
-            name: project.name().as_str().to_string(),
-            description: project.description().map(|s| s.to_string()),
-            org_id: project.organization_id().as_str().to_string(),
-            retention_days: project.retention_days().value(),
-            metrics_retention_days: project.metrics_retention_days().value(),
-            traces_retention_days: project.traces_retention_days().value(),
-            created_at: project.created_at(),
-            updated_at: project.updated_at(),
-        })
+        Ok(Self::project_to_response(&project))
     }
 
     /// Update a project
@@ -301,7 +352,13 @@ where
             .map(TracesRetentionDays::new)
             .transpose()?;
 
-        // 4. Update project
+        // 4. Validate settings patch if provided
+        let new_settings = cmd
+            .settings
+            .map(|patch| project.settings().merge(patch))
+            .transpose()?;
+
+        // 5. Update project
         project.update(
             new_name,
             cmd.description,
@@ -309,21 +366,92 @@ where
             new_metrics_retention,
             new_traces_retention,
         );
+        if let Some(settings) = new_settings {
+            project.update_settings(settings);
+        }
         self.project_repo.save(&project).await?;
 
-        Ok(ProjectResponse {
-            id: project.id().as_str().to_string(),
-            name: project.name().as_str().to_string(),
-            description: project.description().map(|s| s.to_string()),
-            org_id: project.organization_id().as_str().to_string(),
-            retention_days: project.retention_days().value(),
-            metrics_retention_days: project.metrics_retention_days().value(),
-            traces_retention_days: project.traces_retention_days().value(),
-            created_at: project.created_at(),
-            updated_at: project.updated_at(),
+        Ok(Self::project_to_response(&project))
+    }
+
+    /// Get the effective feature flags of a project
+    pub async fn get_feature_flags(
+        &self,
+        project_id: &str,
+        requesting_user_id: &str,
+    ) -> Result<FeatureFlagsResponse, ProjectDomainError> {
+        let project_id = ProjectId::new(project_id.to_string());
+        let (project, _role) = self
+            .verify_project_access(&project_id, requesting_user_id, false)
+            .await?;
+
+        Ok(FeatureFlagsResponse {
+            flags: project.settings().feature_flag_values(),
         })
     }
 
+    /// Switch feature flags on or off; ingest picks the change up on its next
+    /// request since it reads settings with the project
+    pub async fn update_feature_flags(
+        &self,
+        cmd: UpdateFeatureFlagsCommand,
+    ) -> Result<FeatureFlagsResponse, ProjectDomainError> {
+        let project_id = ProjectId::new(cmd.project_id);
+        let (mut project, _role) = self
+            .verify_project_access(&project_id, &cmd.requesting_user_id, true)
+            .await?;
+
+        let mut flags = project.settings().feature_flags.clone();
+        for (name, value) in cmd.flags {
+            match value {
+                Some(enabled) => flags.insert(name, enabled),
+                None => flags.remove(&name),
+            };
+        }
+        let settings = project
+            .settings()
+            .merge(serde_json::json!({ "feature_flags": flags }))?;
+        project.update_settings(settings);
+        self.project_repo.save(&project).await?;
+
+        Ok(FeatureFlagsResponse {
+            flags: project.settings().feature_flag_values(),
+        })
+    }
+
+    /// Get a project's trace sampling policy
+    pub async fn get_trace_sampling(
+        &self,
+        project_id: &str,
+        requesting_user_id: &str,
+    ) -> Result<TraceSamplingSettings, ProjectDomainError> {
+        let project_id = ProjectId::new(project_id.to_string());
+        let (project, _role) = self
+            .verify_project_access(&project_id, requesting_user_id, false)
+            .await?;
+
+        Ok(project.settings().trace_sampling.clone())
+    }
+
+    /// Replace a project's trace sampling policy as a whole
+    pub async fn update_trace_sampling(
+        &self,
+        cmd: UpdateTraceSamplingCommand,
+    ) -> Result<TraceSamplingSettings, ProjectDomainError> {
+        let project_id = ProjectId::new(cmd.project_id);
+        let (mut project, _role) = self
+            .verify_project_access(&project_id, &cmd.requesting_user_id, true)
+            .await?;
+
+        let settings = project
+            .settings()
+            .merge(serde_json::json!({ "trace_sampling": cmd.policy }))?;
+        project.update_settings(settings);
+        self.project_repo.save(&project).await?;
+
+        Ok(project.settings().trace_sampling.clone())
+    }
+
     /// Delete a project (soft delete)
     pub async fn delete_project(
         &self,
@@ -356,6 +484,11 @@ where
         let (project, _role) = self
             .verify_project_access(&project_id, &cmd.requesting_user_id, true)
             .await?;
+        if let Some(check) = &self.email_verification
+            && !check.is_email_verified(&cmd.requesting_user_id).await?
+        {
+            return Err(ProjectDomainError::EmailNotVerified);
+        }
 
         // 2. Validate name
         let name = ApiKeyName::new(cmd.name)?;
@@ -383,6 +516,9 @@ where
 
         // 6. Save API key
         self.api_key_repo.save(&api_key).await?;
+        if let Some(cache) = &self.api_key_cache {
+            cache.invalidate(api_key.key_hash());
+        }
 
         Ok(ApiKeyCreatedResponse {
             id: api_key.id().as_str().to_string(),
@@ -445,6 +581,9 @@ where
 
         api_key.revoke();
         self.api_key_repo.save(&api_key).await?;
+        if let Some(cache) = &self.api_key_cache {
+            cache.invalidate(api_key.key_hash());
+        }
 
         Ok(())
     }
@@ -457,12 +596,18 @@ where
         // 1. Hash the key
         let key_hash = self.hash_api_key(plain_key);
 
-        // 2. Find API key by hash
-        let api_key = self
-            .api_key_repo
-            .find_by_hash(&key_hash)
-            .await?
-            .ok_or(ProjectDomainError::ApiKeyInvalid)?;
+        // 2. Find API key by hash, unless recently looked up
+        let api_key = match self.api_key_cache.as_ref().and_then(|c| c.get(&key_hash)) {
+            Some(cached) => cached,
+            None => {
+                let found = self.api_key_repo.find_by_hash(&key_hash).await?;
+                if let Some(cache) = &self.api_key_cache {
+                    cache.insert(&key_hash, found.clone());
+                }
+                found
+            }
+        }
+        .ok_or(ProjectDomainError::ApiKeyInvalid)?;
 
         // 3. Check if valid
         if api_key.is_revoked() {
@@ -483,10 +628,10 @@ where
             return Err(ProjectDomainError::ProjectNotFound);
         }
 
-        Ok((
-            ProjectId::new(project.id().as_str().to_string()),
-            project,
-        ))
+        // 5. Enforce data residency
+        project.ensure_region(self.deployment_region.as_deref())?;
+
+        Ok((ProjectId::new(project.id().as_str().to_string()), project))
     }
 }
 
@@ -495,3 +640,258 @@ fn base64_encode(data: &[u8]) -> String {
     use base64::Engine;
     base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(data)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::shared::testing::{
+        InMemoryApiKeyRepository, InMemoryMemberRepository, InMemoryOrganizationRepository,
+        InMemoryProjectRepository, SequentialIdGenerator,
+    };
+
+    const API_KEY: &str = "alt_pk_testkey123";
+
+    type TestProjectService = ProjectService<
+        InMemoryProjectRepository,
+        InMemoryApiKeyRepository,
+        InMemoryOrganizationRepository,
+        InMemoryMemberRepository,
+        SequentialIdGenerator,
+    >;
+
+    /// Service for a deployment in `deployment_region`, with one keyed project pinned to `project_region`
+    async fn setup(
+        deployment_region: Option<&str>,
+        project_region: Option<&str>,
+    ) -> TestProjectService {
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let mut project = project_repo.seed("project-1", "org-1");
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({ "region": project_region }))
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+
+        let service = ProjectService::new(
+            project_repo,
+            Arc::new(InMemoryApiKeyRepository::new()),
+            Arc::new(InMemoryOrganizationRepository::new()),
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+            deployment_region.map(String::from),
+            Vec::new(),
+        );
+        let api_key = ApiKey::new(
+            ApiKeyId::new("key-1".to_string()),
+            ProjectId::new("project-1".to_string()),
+            ApiKeyName::new("Ingest".to_string()).unwrap(),
+            ApiKeyPrefix::from_key(API_KEY),
+            service.hash_api_key(API_KEY),
+            None,
+        );
+        service.api_key_repo.save(&api_key).await.unwrap();
+        service
+    }
+
+    #[tokio::test]
+    async fn test_ingest_rejected_for_project_pinned_to_other_region() {
+        let service = setup(Some("us-east-1"), Some("eu-west-1")).await;
+
+        let err = service.validate_api_key(API_KEY).await.unwrap_err();
+
+        assert_eq!(
+            err,
+            ProjectDomainError::RegionMismatch {
+                project_region: "eu-west-1".to_string(),
+                deployment_region: "us-east-1".to_string(),
+            }
+        );
+        let message = err.to_string();
+        assert!(message.contains("pinned to region 'eu-west-1'"));
+        assert!(message.contains("serves 'us-east-1'"));
+    }
+
+    #[tokio::test]
+    async fn test_ingest_allowed_in_matching_or_unconfigured_region() {
+        let matching = setup(Some("eu-west-1"), Some("eu-west-1")).await;
+        assert!(matching.validate_api_key(API_KEY).await.is_ok());
+
+        let unpinned = setup(Some("us-east-1"), None).await;
+        assert!(unpinned.validate_api_key(API_KEY).await.is_ok());
+
+        let single_region = setup(None, Some("eu-west-1")).await;
+        assert!(single_region.validate_api_key(API_KEY).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_key_is_looked_up_once_within_negative_ttl() {
+        let service = setup(None, None)
+            .await
+            .with_api_key_cache(ApiKeyCacheConfig {
+                ttl: std::time::Duration::from_secs(60),
+                negative_ttl: std::time::Duration::from_secs(60),
+                max_entries: 100,
+            });
+
+        for _ in 0..3 {
+            assert_eq!(
+                service
+                    .validate_api_key("alt_pk_guessed")
+                    .await
+                    .unwrap_err(),
+                ProjectDomainError::ApiKeyInvalid
+            );
+        }
+        assert_eq!(service.api_key_repo.hash_lookups(), 1);
+
+        // Valid keys are cached too
+        service.validate_api_key(API_KEY).await.unwrap();
+        service.validate_api_key(API_KEY).await.unwrap();
+        assert_eq!(service.api_key_repo.hash_lookups(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_revoked_key_stops_working_and_its_replacement_works_at_once() {
+        use crate::modules::organizations::domain::{OrgName, OrgSlug, Organization};
+
+        let service = setup(None, None)
+            .await
+            .with_api_key_cache(ApiKeyCacheConfig {
+                ttl: std::time::Duration::from_secs(60),
+                negative_ttl: std::time::Duration::from_secs(60),
+                max_entries: 100,
+            });
+        let name = OrgName::new("Acme".to_string()).unwrap();
+        service
+            .org_repo
+            .save(&Organization::new(
+                OrgId::new("org-1".to_string()),
+                name.clone(),
+                OrgSlug::generate(&name, "org-1"),
+            ))
+            .await
+            .unwrap();
+        service.member_repo.seed("org-1", "admin", OrgRole::Admin);
+        service.validate_api_key(API_KEY).await.unwrap();
+
+        service
+            .revoke_api_key(RevokeApiKeyCommand {
+                project_id: "project-1".to_string(),
+                api_key_id: "key-1".to_string(),
+                requesting_user_id: "admin".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            service.validate_api_key(API_KEY).await.unwrap_err(),
+            ProjectDomainError::ApiKeyRevoked
+        );
+
+        let replacement = service
+            .create_api_key(CreateApiKeyCommand {
+                project_id: "project-1".to_string(),
+                name: "Ingest v2".to_string(),
+                expires_in_days: None,
+                requesting_user_id: "admin".to_string(),
+            })
+            .await
+            .unwrap();
+        assert!(
+            service
+                .validate_api_key(&replacement.plain_key)
+                .await
+                .is_ok()
+        );
+        assert_eq!(
+            service.validate_api_key(API_KEY).await.unwrap_err(),
+            ProjectDomainError::ApiKeyRevoked
+        );
+    }
+
+    /// Users listed here have verified their email
+    struct VerifiedUsers(Vec<&'static str>);
+
+    #[async_trait::async_trait]
+    impl EmailVerificationCheck for VerifiedUsers {
+        async fn is_email_verified(&self, user_id: &str) -> Result<bool, ProjectDomainError> {
+            Ok(self.0.contains(&user_id))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unverified_user_cannot_create_api_keys() {
+        use crate::modules::organizations::domain::{OrgName, OrgSlug, Organization};
+
+        let service = setup(None, None)
+            .await
+            .with_email_verification(Arc::new(VerifiedUsers(vec!["verified"])));
+        let name = OrgName::new("Acme".to_string()).unwrap();
+        service
+            .org_repo
+            .save(&Organization::new(
+                OrgId::new("org-1".to_string()),
+                name.clone(),
+                OrgSlug::generate(&name, "org-1"),
+            ))
+            .await
+            .unwrap();
+        service
+            .member_repo
+            .seed("org-1", "verified", OrgRole::Admin);
+        service
+            .member_repo
+            .seed("org-1", "unverified", OrgRole::Admin);
+        let create = |user: &str| CreateApiKeyCommand {
+            project_id: "project-1".to_string(),
+            name: "Ingest".to_string(),
+            expires_in_days: None,
+            requesting_user_id: user.to_string(),
+        };
+
+        assert_eq!(
+            service
+                .create_api_key(create("unverified"))
+                .await
+                .unwrap_err(),
+            ProjectDomainError::EmailNotVerified
+        );
+        assert!(service.create_api_key(create("verified")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_list_projects_pages_past_deleted_projects() {
+        use crate::modules::organizations::domain::{OrgName, OrgSlug, Organization};
+
+        let service = setup(None, None).await;
+        let name = OrgName::new("Acme".to_string()).unwrap();
+        service
+            .org_repo
+            .save(&Organization::new(
+                OrgId::new("org-1".to_string()),
+                name.clone(),
+                OrgSlug::generate(&name, "org-1"),
+            ))
+            .await
+            .unwrap();
+        service.member_repo.seed("org-1", "user-1", OrgRole::Member);
+        service.project_repo.seed("project-2", "org-1");
+        let mut deleted = service.project_repo.seed("project-3", "org-1");
+        deleted.soft_delete().unwrap();
+        service.project_repo.save(&deleted).await.unwrap();
+        service.project_repo.seed("project-4", "org-1");
+
+        let page = service
+            .list_projects("org-1", "user-1", Some(2), Some(1))
+            .await
+            .unwrap();
+
+        assert_eq!(page.limit, 2);
+        let ids: Vec<&str> = page.projects.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["project-2", "project-4"]);
+    }
+}
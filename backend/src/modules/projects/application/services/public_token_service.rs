@@ -0,0 +1,186 @@
+use std::sync::Arc;
+
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::modules::auth::domain::UserId;
+use crate::modules::organizations::domain::{OrgId, OrganizationMemberRepository};
+use crate::modules::projects::application::dto::*;
+use crate::modules::projects::domain::{
+    Project, ProjectDomainError, ProjectId, ProjectRepository, PublicReadToken,
+    PublicReadTokenRepository,
+};
+
+/// Prefix marking public read tokens, distinct from ingest API keys
+const TOKEN_PREFIX: &str = "alt_pub_";
+
+/// Characters of the random part kept in the displayed prefix
+const DISPLAYED_CHARS: usize = 8;
+
+/// Manages the per-project tokens that share query results without login
+pub struct PublicReadTokenService<TR, PR, MR>
+where
+    TR: PublicReadTokenRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+{
+    token_repo: Arc<TR>,
+    project_repo: Arc<PR>,
+    member_repo: Arc<MR>,
+}
+
+impl<TR, PR, MR> PublicReadTokenService<TR, PR, MR>
+where
+    TR: PublicReadTokenRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+{
+    pub fn new(token_repo: Arc<TR>, project_repo: Arc<PR>, member_repo: Arc<MR>) -> Self {
+        Self {
+            token_repo,
+            project_repo,
+            member_repo,
+        }
+    }
+
+    /// Generate a new token: the prefix plus 32 random bytes, base64 encoded
+    fn generate_token() -> String {
+        use base64::Engine;
+        let mut bytes = [0u8; 32];
+        rand::rng().fill_bytes(&mut bytes);
+        format!(
+            "{}{}",
+            TOKEN_PREFIX,
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+        )
+    }
+
+    fn hash_token(token: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(token.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Load the project, failing if it doesn't exist or was deleted
+    async fn active_project(&self, project_id: &ProjectId) -> Result<Project, ProjectDomainError> {
+        let project = self
+            .project_repo
+            .find_by_id(project_id)
+            .await?
+            .ok_or(ProjectDomainError::ProjectNotFound)?;
+
+        if project.is_deleted() {
+            return Err(ProjectDomainError::ProjectNotFound);
+        }
+
+        Ok(project)
+    }
+
+    /// Verify the user belongs to the project's organization, optionally as an admin
+    async fn verify_project_access(
+        &self,
+        project_id: &ProjectId,
+        user_id: &str,
+        require_admin: bool,
+    ) -> Result<(), ProjectDomainError> {
+        let project = self.active_project(project_id).await?;
+
+        let org_id = OrgId::new(project.organization_id().as_str().to_string());
+        let membership = self
+            .member_repo
+            .find_by_org_and_user(&org_id, &UserId::new(user_id.to_string()))
+            .await
+            .map_err(|e| ProjectDomainError::InternalError(e.to_string()))?
+            .ok_or(ProjectDomainError::NotOrgMember)?;
+
+        if require_admin && !membership.role().can_update_org() {
+            return Err(ProjectDomainError::InsufficientPermissions);
+        }
+
+        Ok(())
+    }
+
+    /// Show whether the project has a public read token
+    pub async fn get_token(
+        &self,
+        project_id: &str,
+        requesting_user_id: &str,
+    ) -> Result<PublicReadTokenResponse, ProjectDomainError> {
+        let project_id = ProjectId::new(project_id.to_string());
+        self.verify_project_access(&project_id, requesting_user_id, false)
+            .await?;
+
+        let token = self
+            .token_repo
+            .find_by_project(&project_id)
+            .await?
+            .ok_or(ProjectDomainError::PublicTokenNotFound)?;
+
+        Ok(PublicReadTokenResponse {
+            token_prefix: token.token_prefix().to_string(),
+            created_by: token.created_by().to_string(),
+            created_at: token.created_at(),
+        })
+    }
+
+    /// Create a public read token, replacing (and so revoking) any existing one
+    pub async fn create_token(
+        &self,
+        project_id: &str,
+        requesting_user_id: &str,
+    ) -> Result<PublicReadTokenCreatedResponse, ProjectDomainError> {
+        let project_id = ProjectId::new(project_id.to_string());
+        self.verify_project_access(&project_id, requesting_user_id, true)
+            .await?;
+
+        let plain_token = Self::generate_token();
+        let token_prefix = plain_token[..TOKEN_PREFIX.len() + DISPLAYED_CHARS].to_string();
+
+        let token = PublicReadToken::new(
+            project_id,
+            Self::hash_token(&plain_token),
+            token_prefix,
+            requesting_user_id.to_string(),
+        );
+        self.token_repo.save(&token).await?;
+
+        Ok(PublicReadTokenCreatedResponse {
+            token_prefix: token.token_prefix().to_string(),
+            plain_token,
+            created_at: token.created_at(),
+        })
+    }
+
+    /// Revoke the project's public read token
+    pub async fn revoke_token(
+        &self,
+        project_id: &str,
+        requesting_user_id: &str,
+    ) -> Result<(), ProjectDomainError> {
+        let project_id = ProjectId::new(project_id.to_string());
+        self.verify_project_access(&project_id, requesting_user_id, true)
+            .await?;
+
+        if !self.token_repo.delete(&project_id).await? {
+            return Err(ProjectDomainError::PublicTokenNotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the project a public read token grants access to
+    pub async fn validate_token(&self, plain_token: &str) -> Result<ProjectId, ProjectDomainError> {
+        if !plain_token.starts_with(TOKEN_PREFIX) {
+            return Err(ProjectDomainError::PublicTokenInvalid);
+        }
+
+        let token = self
+            .token_repo
+            .find_by_hash(&Self::hash_token(plain_token))
+            .await?
+            .ok_or(ProjectDomainError::PublicTokenInvalid)?;
+        let project = self.active_project(token.project_id()).await?;
+
+        Ok(ProjectId::new(project.id().as_str().to_string()))
+    }
+}
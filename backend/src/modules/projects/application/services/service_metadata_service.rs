@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use crate::modules::auth::domain::UserId;
+use crate::modules::organizations::domain::{OrgId, OrganizationMemberRepository};
+use crate::modules::projects::application::dto::*;
+use crate::modules::projects::domain::{
+    ProjectDomainError, ProjectId, ProjectRepository, ServiceMetadata, ServiceMetadataRepository,
+};
+
+/// Manages the per-project display metadata shown for services in queries
+pub struct ServiceMetadataService<SMR, PR, MR>
+where
+    SMR: ServiceMetadataRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+{
+    metadata_repo: Arc<SMR>,
+    project_repo: Arc<PR>,
+    member_repo: Arc<MR>,
+}
+
+impl<SMR, PR, MR> ServiceMetadataService<SMR, PR, MR>
+where
+    SMR: ServiceMetadataRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+{
+    pub fn new(metadata_repo: Arc<SMR>, project_repo: Arc<PR>, member_repo: Arc<MR>) -> Self {
+        Self {
+            metadata_repo,
+            project_repo,
+            member_repo,
+        }
+    }
+
+    fn to_response(metadata: ServiceMetadata) -> ServiceMetadataResponse {
+        ServiceMetadataResponse {
+            service_name: metadata.service_name().to_string(),
+            display_name: metadata.display_name().to_string(),
+            team: metadata.team().map(String::from),
+            links: metadata.links().to_vec(),
+            updated_at: metadata.updated_at(),
+        }
+    }
+
+    /// Verify the user belongs to the project's organization, optionally as an admin
+    async fn verify_project_access(
+        &self,
+        project_id: &ProjectId,
+        user_id: &str,
+        require_admin: bool,
+    ) -> Result<(), ProjectDomainError> {
+        let project = self
+            .project_repo
+            .find_by_id(project_id)
+            .await?
+            .ok_or(ProjectDomainError::ProjectNotFound)?;
+
+        if project.is_deleted() {
+            return Err(ProjectDomainError::ProjectNotFound);
+        }
+
+        let org_id = OrgId::new(project.organization_id().as_str().to_string());
+        let membership = self
+            .member_repo
+            .find_by_org_and_user(&org_id, &UserId::new(user_id.to_string()))
+            .await
+            .map_err(|e| ProjectDomainError::InternalError(e.to_string()))?
+            .ok_or(ProjectDomainError::NotOrgMember)?;
+
+        if require_admin && !membership.role().can_update_org() {
+            return Err(ProjectDomainError::InsufficientPermissions);
+        }
+
+        Ok(())
+    }
+
+    /// List the metadata configured for a project's services
+    pub async fn list_metadata(
+        &self,
+        project_id: &str,
+        requesting_user_id: &str,
+    ) -> Result<Vec<ServiceMetadataResponse>, ProjectDomainError> {
+        let project_id = ProjectId::new(project_id.to_string());
+        self.verify_project_access(&project_id, requesting_user_id, false)
+            .await?;
+
+        let mut metadata = self.metadata_repo.find_by_project(&project_id).await?;
+        metadata.sort_by(|a, b| a.service_name().cmp(b.service_name()));
+
+        Ok(metadata.into_iter().map(Self::to_response).collect())
+    }
+
+    /// Set a service's display metadata, replacing any already configured
+    pub async fn upsert_metadata(
+        &self,
+        cmd: UpsertServiceMetadataCommand,
+    ) -> Result<ServiceMetadataResponse, ProjectDomainError> {
+        let project_id = ProjectId::new(cmd.project_id);
+        self.verify_project_access(&project_id, &cmd.requesting_user_id, true)
+            .await?;
+
+        let metadata = ServiceMetadata::new(
+            project_id,
+            cmd.service_name,
+            cmd.display_name,
+            cmd.team,
+            cmd.links,
+        )?;
+        self.metadata_repo.save(&metadata).await?;
+
+        Ok(Self::to_response(metadata))
+    }
+
+    /// Remove a service's metadata so it's shown by its raw name again
+    pub async fn delete_metadata(
+        &self,
+        project_id: &str,
+        service_name: &str,
+        requesting_user_id: &str,
+    ) -> Result<(), ProjectDomainError> {
+        let project_id = ProjectId::new(project_id.to_string());
+        self.verify_project_access(&project_id, requesting_user_id, true)
+            .await?;
+
+        if !self.metadata_repo.delete(&project_id, service_name).await? {
+            return Err(ProjectDomainError::ServiceMetadataNotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -6,21 +6,41 @@ pub enum ProjectDomainError {
     InvalidProjectName(String),
     InvalidRetentionDays(String),
     InvalidApiKeyName(String),
+    InvalidSettings(String),
+    InvalidServiceMetadata(String),
 
     // Project errors
     ProjectNotFound,
     ProjectAlreadyExists,
     ProjectAlreadyDeleted,
 
+    // Service metadata errors
+    ServiceMetadataNotFound,
+
+    // Public read token errors
+    PublicTokenNotFound,
+    PublicTokenInvalid,
+
     // API Key errors
     ApiKeyNotFound,
     ApiKeyRevoked,
     ApiKeyExpired,
     ApiKeyInvalid,
 
+    // Data residency errors
+    RegionMismatch {
+        project_region: String,
+        deployment_region: String,
+    },
+
+    // Ingest source errors
+    IpNotAllowed(String),
+
     // Permission errors
     InsufficientPermissions,
     NotOrgMember,
+    /// The user must verify their email address before setting up ingest
+    EmailNotVerified,
 
     // Infrastructure errors
     InternalError(String),
@@ -32,15 +52,34 @@ impl fmt::Display for ProjectDomainError {
             Self::InvalidProjectName(msg) => write!(f, "Invalid project name: {}", msg),
             Self::InvalidRetentionDays(msg) => write!(f, "Invalid retention days: {}", msg),
             Self::InvalidApiKeyName(msg) => write!(f, "Invalid API key name: {}", msg),
+            Self::InvalidSettings(msg) => write!(f, "Invalid project settings: {}", msg),
+            Self::InvalidServiceMetadata(msg) => write!(f, "Invalid service metadata: {}", msg),
             Self::ProjectNotFound => write!(f, "Project not found"),
             Self::ProjectAlreadyExists => write!(f, "Project already exists in this organization"),
             Self::ProjectAlreadyDeleted => write!(f, "Project is already deleted"),
+            Self::ServiceMetadataNotFound => write!(f, "Service metadata not found"),
+            Self::PublicTokenNotFound => write!(f, "Project has no public read token"),
+            Self::PublicTokenInvalid => write!(f, "Invalid public read token"),
             Self::ApiKeyNotFound => write!(f, "API key not found"),
             Self::ApiKeyRevoked => write!(f, "API key has been revoked"),
             Self::ApiKeyExpired => write!(f, "API key has expired"),
             Self::ApiKeyInvalid => write!(f, "API key is invalid"),
+            Self::RegionMismatch {
+                project_region,
+                deployment_region,
+            } => write!(
+                f,
+                "Project data is pinned to region '{}'; this deployment serves '{}'. Send data to the {} endpoint",
+                project_region, deployment_region, project_region
+            ),
+            Self::IpNotAllowed(ip) => {
+                write!(f, "Ingest from {} is not allowed for this project", ip)
+            }
             Self::InsufficientPermissions => write!(f, "Insufficient permissions for this action"),
             Self::NotOrgMember => write!(f, "User is not a member of this organization"),
+            Self::EmailNotVerified => {
+                write!(f, "Verify your email address before creating API keys")
+            }
             Self::InternalError(msg) => write!(f, "Internal error: {}", msg),
         }
     }
@@ -1,10 +1,22 @@
 pub mod api_key;
 pub mod errors;
 pub mod project;
+pub mod public_token;
+pub mod service_metadata;
 
 pub use api_key::{ApiKey, ApiKeyId, ApiKeyName, ApiKeyPrefix, ApiKeyRepository};
 pub use errors::ProjectDomainError;
 pub use project::{
-    MetricsRetentionDays, Project, ProjectId, ProjectName, ProjectRepository, RetentionDays,
-    TracesRetentionDays,
+    ClockSkewMode, ClockSkewSettings, CoercionFailurePolicy, DuplicateSpanPolicy, FeatureFlag,
+    FieldMappingSettings, IngestMode, LabelLimitPolicy, LabelNormalizationSettings,
+    LateMetricsPolicy, LogGroupField, MetricTypePolicy, MetricsRetentionDays,
+    MissingTimestampPolicy, PlainTextParsingSettings, Project, ProjectId, ProjectName,
+    ProjectRepository, ProjectSettings, RedactedFieldAction, RedactionSettings, RetentionDays,
+    TraceCompletenessMode, TraceRetentionOverride, TraceSamplingSettings, TracesRetentionDays,
+    normalize_label_key, parse_network,
+};
+pub use public_token::{PublicReadToken, PublicReadTokenRepository};
+pub use service_metadata::{
+    ServiceDisplay, ServiceLink, ServiceMetadata, ServiceMetadataRepository,
+    resolve_service_displays,
 };
@@ -1,6 +1,11 @@
+use std::net::IpAddr;
+
 use chrono::{DateTime, Utc};
 
-use super::value_objects::{MetricsRetentionDays, ProjectId, ProjectName, RetentionDays, TracesRetentionDays};
+use super::settings::ProjectSettings;
+use super::value_objects::{
+    MetricsRetentionDays, ProjectId, ProjectName, RetentionDays, TracesRetentionDays,
+};
 use crate::modules::organizations::domain::OrgId;
 use crate::modules::projects::domain::errors::ProjectDomainError;
 
@@ -14,6 +19,7 @@ pub struct Project {
     retention_days: RetentionDays,
     metrics_retention_days: MetricsRetentionDays,
     traces_retention_days: TracesRetentionDays,
+    settings: ProjectSettings,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
     deleted_at: Option<DateTime<Utc>>,
@@ -39,6 +45,7 @@ impl Project {
             retention_days,
             metrics_retention_days,
             traces_retention_days,
+            settings: ProjectSettings::default(),
             created_at: now,
             updated_at: now,
             deleted_at: None,
@@ -55,6 +62,7 @@ impl Project {
         retention_days: RetentionDays,
         metrics_retention_days: MetricsRetentionDays,
         traces_retention_days: TracesRetentionDays,
+        settings: ProjectSettings,
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
         deleted_at: Option<DateTime<Utc>>,
@@ -67,6 +75,7 @@ impl Project {
             retention_days,
             metrics_retention_days,
             traces_retention_days,
+            settings,
             created_at,
             updated_at,
             deleted_at,
@@ -102,6 +111,46 @@ impl Project {
         self.traces_retention_days
     }
 
+    pub fn settings(&self) -> &ProjectSettings {
+        &self.settings
+    }
+
+    /// Data residency region the project is pinned to, if any
+    pub fn region(&self) -> Option<&str> {
+        self.settings.region.as_deref()
+    }
+
+    /// Ensure data for this project may be ingested by a deployment serving `deployment_region`.
+    /// Unpinned projects and deployments without a configured region are always allowed.
+    pub fn ensure_region(&self, deployment_region: Option<&str>) -> Result<(), ProjectDomainError> {
+        match (self.region(), deployment_region) {
+            (Some(pinned), Some(serving)) if pinned != serving => {
+                Err(ProjectDomainError::RegionMismatch {
+                    project_region: pinned.to_string(),
+                    deployment_region: serving.to_string(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Ensure ingest from `source` is allowed by the project's IP allowlist.
+    /// An empty allowlist allows every source; an unknown source is only
+    /// allowed then.
+    pub fn ensure_ip_allowed(&self, source: Option<IpAddr>) -> Result<(), ProjectDomainError> {
+        let networks = self.settings.allowed_networks();
+        if networks.is_empty() {
+            return Ok(());
+        }
+        match source {
+            Some(ip) if networks.iter().any(|net| net.contains(&ip)) => Ok(()),
+            Some(ip) => Err(ProjectDomainError::IpNotAllowed(ip.to_string())),
+            None => Err(ProjectDomainError::IpNotAllowed(
+                "an unknown address".to_string(),
+            )),
+        }
+    }
+
     pub fn created_at(&self) -> DateTime<Utc> {
         self.created_at
     }
@@ -146,6 +195,12 @@ impl Project {
         self.updated_at = Utc::now();
     }
 
+    /// Replace the project's ingestion settings
+    pub fn update_settings(&mut self, settings: ProjectSettings) {
+        self.settings = settings;
+        self.updated_at = Utc::now();
+    }
+
     /// Soft delete the project
     pub fn soft_delete(&mut self) -> Result<(), ProjectDomainError> {
         if self.deleted_at.is_some() {
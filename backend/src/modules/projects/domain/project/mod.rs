@@ -1,7 +1,18 @@
 pub mod entity;
 pub mod repository;
+pub mod settings;
 pub mod value_objects;
 
 pub use entity::Project;
 pub use repository::ProjectRepository;
-pub use value_objects::{MetricsRetentionDays, ProjectId, ProjectName, RetentionDays, TracesRetentionDays};
+pub use settings::{
+    ClockSkewMode, ClockSkewSettings, CoercionFailurePolicy, DuplicateSpanPolicy, FeatureFlag,
+    FieldMappingSettings, IngestMode, LabelLimitPolicy, LabelNormalizationSettings,
+    LateMetricsPolicy, LogGroupField, MetricTypePolicy, MissingTimestampPolicy,
+    PlainTextParsingSettings, ProjectSettings, RedactedFieldAction, RedactionSettings,
+    TraceCompletenessMode, TraceRetentionOverride, TraceSamplingSettings, normalize_label_key,
+    parse_network,
+};
+pub use value_objects::{
+    MetricsRetentionDays, ProjectId, ProjectName, RetentionDays, TracesRetentionDays,
+};
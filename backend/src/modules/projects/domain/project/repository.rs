@@ -4,6 +4,7 @@ use super::entity::Project;
 use super::value_objects::ProjectId;
 use crate::modules::organizations::domain::OrgId;
 use crate::modules::projects::domain::errors::ProjectDomainError;
+use crate::shared::Pagination;
 
 /// Repository trait for Project persistence
 #[async_trait]
@@ -11,8 +12,12 @@ pub trait ProjectRepository: Send + Sync {
     /// Find project by ID
     async fn find_by_id(&self, id: &ProjectId) -> Result<Option<Project>, ProjectDomainError>;
 
-    /// Find all projects in an organization
-    async fn find_by_org(&self, org_id: &OrgId) -> Result<Vec<Project>, ProjectDomainError>;
+    /// Find one page of an organization's projects that aren't deleted, newest first
+    async fn find_page_by_org(
+        &self,
+        org_id: &OrgId,
+        pagination: &Pagination,
+    ) -> Result<Vec<Project>, ProjectDomainError>;
 
     /// Save project (insert or update)
     async fn save(&self, project: &Project) -> Result<(), ProjectDomainError>;
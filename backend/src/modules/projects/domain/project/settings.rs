@@ -0,0 +1,1346 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::net::IpAddr;
+
+use chrono::{DateTime, SubsecRound, Utc};
+use ipnet::IpNet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::value_objects::{RetentionDays, TracesRetentionDays};
+use crate::modules::projects::domain::errors::ProjectDomainError;
+use crate::shared::{Tz, parse_timezone};
+
+/// What to do with metric points older than the out-of-order tolerance window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LateMetricsPolicy {
+    /// Store late points outside the in-order batch and log a warning
+    #[default]
+    Accept,
+    /// Reject late points and report them in the ingest response
+    Strict,
+}
+
+/// What to do with logs, metric points and spans ingested without a timestamp
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MissingTimestampPolicy {
+    /// Stamp them with the server's receive time and report how many were stamped
+    #[default]
+    Assign,
+    /// Reject them
+    Reject,
+}
+
+/// What happens to a metric point whose type differs from the one its name was
+/// first ingested with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricTypePolicy {
+    /// Reject the point and report it in the ingest response
+    #[default]
+    Enforce,
+    /// Store the point anyway, keeping the first registered type
+    Allow,
+}
+
+/// When the native log ingest endpoint acknowledges a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IngestMode {
+    /// After the logs are committed, with the stored counts
+    #[default]
+    Sync,
+    /// As soon as the validated logs are queued for writing, with a 202
+    Async,
+}
+
+impl IngestMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "sync" => Some(Self::Sync),
+            "async" => Some(Self::Async),
+            _ => None,
+        }
+    }
+}
+
+/// What happens to a span whose (trace_id, span_id) is already stored, as when
+/// an exporter retries a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateSpanPolicy {
+    /// Keep the stored span and drop the resent one
+    #[default]
+    Ignore,
+    /// Replace the stored span with the more recently received one
+    Update,
+}
+
+/// Whether span times from sources whose clocks run ahead of the server's are
+/// noticed at ingest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClockSkewMode {
+    /// Store span times as reported
+    #[default]
+    Off,
+    /// Record each source's estimated skew on its spans, keeping reported times
+    Record,
+    /// Record the skew and shift the source's span times back toward server time
+    Adjust,
+}
+
+/// Longest shift applied to a span's times by clock skew correction
+const MAX_CLOCK_SKEW_CORRECTION_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Clock skew estimation and correction for ingested spans
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClockSkewSettings {
+    pub mode: ClockSkewMode,
+    /// Skew up to this is treated as ordinary clock jitter and ignored
+    pub tolerance_ms: u64,
+    /// Largest shift applied in adjust mode; larger skews are corrected by
+    /// this much only
+    pub max_correction_ms: u64,
+}
+
+impl Default for ClockSkewSettings {
+    fn default() -> Self {
+        Self {
+            mode: ClockSkewMode::Off,
+            tolerance_ms: 1000,
+            max_correction_ms: 60 * 60 * 1000,
+        }
+    }
+}
+
+/// How traces still inside the completeness window are served
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceCompletenessMode {
+    /// Return them, flagged as incomplete
+    #[default]
+    Mark,
+    /// Hold them back from search and lookup until the window has passed
+    Buffer,
+}
+
+/// Longest completeness window a project can set
+const MAX_TRACE_COMPLETENESS_WINDOW_SECS: u64 = 60 * 60;
+
+/// Quiet period after a trace's most recently received span before the trace
+/// is treated as complete, so queries don't show traces still being exported
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraceCompletenessSettings {
+    /// Seconds without a new span before a trace is complete; 0 disables
+    pub window_secs: u64,
+    pub mode: TraceCompletenessMode,
+}
+
+impl TraceCompletenessSettings {
+    /// Time after which a trace last receiving a span before `now` is
+    /// complete, or `None` when every trace is treated as complete
+    pub fn settled_before(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        (self.window_secs > 0).then(|| now - chrono::Duration::seconds(self.window_secs as i64))
+    }
+}
+
+/// Precision ingested log timestamps are kept at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogTimestampPrecision {
+    Millisecond,
+    /// What a TIMESTAMPTZ column holds on its own
+    #[default]
+    Microsecond,
+    /// Keeps the sub-microsecond part too, stored alongside the timestamp
+    Nanosecond,
+}
+
+impl LogTimestampPrecision {
+    /// Drop the digits of `timestamp` finer than this precision
+    pub fn truncate(self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Self::Millisecond => timestamp.trunc_subsecs(3),
+            Self::Microsecond => timestamp.trunc_subsecs(6),
+            Self::Nanosecond => timestamp,
+        }
+    }
+}
+
+/// What happens to metadata fields named in the redaction settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactedFieldAction {
+    /// Remove the field entirely
+    #[default]
+    Drop,
+    /// Keep the field but replace its value with the mask
+    Mask,
+}
+
+/// Secrets scrubbed from logs at ingest, before anything is stored
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionSettings {
+    /// Metadata keys to redact, matched case-insensitively at any depth
+    pub fields: Vec<String>,
+    pub field_action: RedactedFieldAction,
+    /// Regular expressions whose matches in the log message are masked
+    pub message_patterns: Vec<String>,
+}
+
+/// Where the native ingest endpoint finds each canonical log field in
+/// arbitrarily shaped JSON logs. Paths are dot-separated (e.g. "log.level");
+/// unset fields are read from their canonical key. Once any mapping is set,
+/// incoming keys that aren't mapped to a field are kept as metadata.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FieldMappingSettings {
+    pub message: Option<String>,
+    pub level: Option<String>,
+    pub timestamp: Option<String>,
+    pub source: Option<String>,
+    pub trace_id: Option<String>,
+    pub span_id: Option<String>,
+    pub event_id: Option<String>,
+    /// Incoming paths moved to a new top-level key before fields are mapped
+    pub rename: BTreeMap<String, String>,
+    /// Incoming paths removed before anything else is applied
+    pub drop: Vec<String>,
+}
+
+impl FieldMappingSettings {
+    /// Whether the mapping would leave every log untouched
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Each canonical field with its configured source path
+    pub fn fields(&self) -> [(&'static str, Option<&str>); 7] {
+        [
+            ("message", self.message.as_deref()),
+            ("level", self.level.as_deref()),
+            ("timestamp", self.timestamp.as_deref()),
+            ("source", self.source.as_deref()),
+            ("trace_id", self.trace_id.as_deref()),
+            ("span_id", self.span_id.as_deref()),
+            ("event_id", self.event_id.as_deref()),
+        ]
+    }
+}
+
+/// How the plain-text ingest endpoint reads each line. Patterns are regular
+/// expressions; the value is taken from a group named after the field
+/// (`(?P<level>...)`), else the first group, else the whole match. The line
+/// is stored unchanged as the message.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlainTextParsingSettings {
+    /// Level of lines without an extracted level when the request doesn't
+    /// set one. Unset uses "info".
+    pub default_level: Option<String>,
+    pub level_pattern: Option<String>,
+    pub timestamp_pattern: Option<String>,
+    /// chrono format of the extracted timestamp (e.g. "%d/%b/%Y:%H:%M:%S %z");
+    /// times without an offset are read as UTC. Unset expects RFC 3339.
+    pub timestamp_format: Option<String>,
+}
+
+/// Days to keep traces by outcome. Unset values fall back to the project's
+/// traces retention.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraceRetentionOverride {
+    /// Traces with at least one error span
+    pub error_days: Option<i32>,
+    /// Traces without error spans
+    pub clean_days: Option<i32>,
+}
+
+/// Trace retention by error status, optionally per service
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraceRetentionSettings {
+    #[serde(flatten)]
+    pub default: TraceRetentionOverride,
+    /// Overrides keyed by the service of a trace's root span; unset values
+    /// fall back to the project-wide ones above
+    pub services: BTreeMap<String, TraceRetentionOverride>,
+}
+
+impl TraceRetentionSettings {
+    /// Whether traces are kept for the flat project retention only
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Days to keep logs whose metadata has every one of `tags`, e.g.
+/// {"category": "audit"}
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogRetentionRule {
+    pub tags: BTreeMap<String, String>,
+    pub days: i32,
+}
+
+/// Log retention by metadata tags. A log matching several rules is kept for
+/// the longest of them; one matching none falls back to the project's
+/// retention.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogRetentionSettings {
+    pub rules: Vec<LogRetentionRule>,
+}
+
+/// Sampling policy for ingested traces. Traces with an error span, or with
+/// a span at least as slow as the latency threshold, are always kept; the
+/// rest are sampled at the base rate, decided per trace id so a trace's
+/// spans are kept or dropped together. The error and latency rules see the
+/// spans of a trace that arrive in the same ingest request. Traces recently
+/// referenced by a metric exemplar are kept too, when the server tracks them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraceSamplingSettings {
+    /// Base fraction of traces kept, from 0.0 to 1.0. Unset keeps everything.
+    pub rate: Option<f64>,
+    /// Rates keyed by a span's effective service name, winning over the
+    /// project-wide rate (e.g. {"payments": 1.0} to never drop payments)
+    pub services: BTreeMap<String, f64>,
+    /// Keep every trace with an error span regardless of the rate
+    pub keep_errors: bool,
+    /// Keep every trace with a span lasting at least this long. Unset
+    /// samples slow traces like any other.
+    pub latency_threshold_ms: Option<u64>,
+    /// Keep every trace a metric exemplar points to, so a metric spike can
+    /// be followed to its example trace
+    pub keep_exemplar_traces: bool,
+}
+
+impl Default for TraceSamplingSettings {
+    fn default() -> Self {
+        Self {
+            rate: None,
+            services: BTreeMap::new(),
+            keep_errors: true,
+            latency_threshold_ms: None,
+            keep_exemplar_traces: true,
+        }
+    }
+}
+
+impl TraceSamplingSettings {
+    /// Whether a span makes its whole trace exempt from the base rate
+    pub fn always_keeps(&self, is_error: bool, duration_ns: Option<i64>) -> bool {
+        (self.keep_errors && is_error)
+            || self.latency_threshold_ms.zip(duration_ns).is_some_and(
+                |(threshold_ms, duration_ns)| duration_ns >= threshold_ms as i64 * 1_000_000,
+            )
+    }
+
+    /// Rate applied to spans of the given service
+    pub fn rate_for(&self, service_name: Option<&str>) -> f64 {
+        service_name
+            .and_then(|name| self.services.get(name))
+            .copied()
+            .or(self.rate)
+            .unwrap_or(1.0)
+    }
+}
+
+/// Span attribute keys kept at ingest. Entries are exact keys or prefixes
+/// ending in "*" (e.g. "http.*"). With an allowlist only matching keys are
+/// kept; denylisted keys are dropped either way.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpanAttributeFilterSettings {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+impl SpanAttributeFilterSettings {
+    /// Whether every attribute is kept
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// Whether an attribute with this key is kept
+    pub fn keeps(&self, key: &str) -> bool {
+        let matches = |pattern: &String| match pattern.strip_suffix('*') {
+            Some(prefix) => key.starts_with(prefix),
+            None => key == pattern,
+        };
+        (self.allow.is_empty() || self.allow.iter().any(matches)) && !self.deny.iter().any(matches)
+    }
+}
+
+/// Type a span attribute is stored as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeType {
+    String,
+    Int,
+    Double,
+    Bool,
+}
+
+impl AttributeType {
+    /// The value as this type, or None when it can't be converted without
+    /// losing meaning (e.g. "abc" as an int, or 1.5 as an int)
+    pub fn coerce(&self, value: &Value) -> Option<Value> {
+        match (self, value) {
+            (Self::String, Value::String(_)) => Some(value.clone()),
+            (Self::String, Value::Number(n)) => Some(Value::String(n.to_string())),
+            (Self::String, Value::Bool(b)) => Some(Value::String(b.to_string())),
+            (Self::Int, Value::Number(n)) => n
+                .as_i64()
+                .or_else(|| n.as_f64().filter(|f| f.fract() == 0.0).map(|f| f as i64))
+                .map(Value::from),
+            (Self::Int, Value::String(s)) => s.trim().parse::<i64>().ok().map(Value::from),
+            (Self::Double, Value::Number(n)) => n.as_f64().map(Value::from),
+            (Self::Double, Value::String(s)) => s
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|f| f.is_finite())
+                .map(Value::from),
+            (Self::Bool, Value::Bool(_)) => Some(value.clone()),
+            (Self::Bool, Value::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+                "true" => Some(Value::Bool(true)),
+                "false" => Some(Value::Bool(false)),
+                _ => None,
+            },
+            (Self::Bool, Value::Number(n)) => match n.as_i64() {
+                Some(1) => Some(Value::Bool(true)),
+                Some(0) => Some(Value::Bool(false)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// What happens to a span attribute whose value can't be coerced to its
+/// configured type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CoercionFailurePolicy {
+    /// Keep the value as sent and list its key on the span
+    #[default]
+    Flag,
+    /// Drop the attribute
+    Drop,
+}
+
+/// Types span attributes are stored as at ingest, keyed by attribute key
+/// (e.g. {"http.status_code": "int"}), so queries and aggregations see one
+/// type per key whatever the SDK sent
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpanAttributeTypeSettings {
+    pub types: BTreeMap<String, AttributeType>,
+    pub on_failure: CoercionFailurePolicy,
+}
+
+impl SpanAttributeTypeSettings {
+    /// Coerce the configured attributes in place, returning the keys whose
+    /// value couldn't be coerced. Those are dropped under the drop policy
+    /// and left as sent otherwise.
+    pub fn apply(&self, attributes: &mut Value) -> Vec<String> {
+        let Some(map) = attributes.as_object_mut() else {
+            return Vec::new();
+        };
+        let mut failed = Vec::new();
+        for (key, ty) in &self.types {
+            let Some(value) = map.get_mut(key) else {
+                continue;
+            };
+            match ty.coerce(value) {
+                Some(coerced) => *value = coerced,
+                None => failed.push(key.clone()),
+            }
+        }
+        if self.on_failure == CoercionFailurePolicy::Drop {
+            for key in &failed {
+                map.remove(key);
+            }
+        }
+        failed
+    }
+}
+
+/// What happens to a metric point with more labels than the project allows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelLimitPolicy {
+    /// Keep the first labels in key order and drop the rest
+    #[default]
+    Trim,
+    /// Reject the point and report it in the ingest response
+    Reject,
+}
+
+/// Largest label limit a project can set
+const MAX_METRIC_LABELS: usize = 1000;
+
+/// Cap on labels per metric point, bounding storage and series cardinality
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricLabelLimitSettings {
+    pub max_labels: usize,
+    pub policy: LabelLimitPolicy,
+}
+
+impl Default for MetricLabelLimitSettings {
+    fn default() -> Self {
+        Self {
+            max_labels: 64,
+            policy: LabelLimitPolicy::Trim,
+        }
+    }
+}
+
+/// How metric label keys are rewritten at ingest so equivalent series merge
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LabelNormalizationSettings {
+    /// Lowercase keys and replace anything but letters, digits and '_' with '_'
+    /// (e.g. "HTTP.Method" -> "http_method")
+    pub normalize_keys: bool,
+    /// Keys renamed after normalization, e.g. {"method": "http_method"}
+    pub rename: BTreeMap<String, String>,
+    /// Also store the labels as received alongside the normalized ones
+    pub preserve_raw: bool,
+}
+
+impl LabelNormalizationSettings {
+    /// Whether labels are stored exactly as received
+    pub fn is_empty(&self) -> bool {
+        !self.normalize_keys && self.rename.is_empty()
+    }
+}
+
+/// Log fields a metadata alias may not shadow in queries
+const RESERVED_LOG_FIELDS: [&str; 11] = [
+    "id",
+    "level",
+    "message",
+    "timestamp",
+    "received_at",
+    "source",
+    "trace_id",
+    "span_id",
+    "event_id",
+    "service",
+    "metadata",
+];
+
+/// Log levels a minimum ingest level may name, least severe first
+const LOG_LEVELS: [&str; 6] = ["trace", "debug", "info", "warn", "error", "fatal"];
+
+/// Longest time fired alerts may be held back to be grouped
+const MAX_ALERT_GROUPING_WINDOW_SECONDS: u32 = 3600;
+
+/// Bundling of alerts from different rules that likely share a root cause
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertGroupingSettings {
+    /// How long the first fired alert of a group waits for others before the
+    /// group is sent; 0 sends every alert on its own right away
+    pub window_seconds: u32,
+    /// Rule config field whose value groups alerts, e.g. "source" to group by
+    /// service. Alerts from rules without the field are sent on their own.
+    pub key: String,
+}
+
+impl Default for AlertGroupingSettings {
+    fn default() -> Self {
+        Self {
+            window_seconds: 0,
+            key: "source".to_string(),
+        }
+    }
+}
+
+/// Log field the log list is grouped by
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogGroupField {
+    Source,
+    Level,
+    /// Path into log metadata, e.g. ["http", "host"] for "metadata.http.host"
+    Metadata(Vec<String>),
+}
+
+impl LogGroupField {
+    /// Parse "source", "level" or "metadata.<path>"
+    pub fn parse(field: &str) -> Option<Self> {
+        match field {
+            "source" => Some(Self::Source),
+            "level" => Some(Self::Level),
+            _ => {
+                let path = field.strip_prefix("metadata.")?;
+                let segments: Vec<String> = path.split('.').map(String::from).collect();
+                if segments.iter().any(|s| s.is_empty()) {
+                    return None;
+                }
+                Some(Self::Metadata(segments))
+            }
+        }
+    }
+}
+
+impl fmt::Display for LogGroupField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Source => write!(f, "source"),
+            Self::Level => write!(f, "level"),
+            Self::Metadata(path) => write!(f, "metadata.{}", path.join(".")),
+        }
+    }
+}
+
+/// Normalized form of a metric label key: lowercase, with every character
+/// other than ASCII letters, digits and '_' replaced by '_'
+pub fn normalize_label_key(key: &str) -> String {
+    key.trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Ingest behaviors that can be switched on or off per project without a
+/// redeploy, stored by name in [`ProjectSettings::feature_flags`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureFlag {
+    /// Add "geo" metadata from the client IP in `geoip_field`
+    GeoipEnrichment,
+    /// Derive span metrics for projects with `derive_span_metrics`
+    SpanMetricDerivation,
+    /// Head-sample traces by the `trace_sampling` rates
+    TraceSampling,
+}
+
+impl FeatureFlag {
+    pub const ALL: [Self; 3] = [
+        Self::GeoipEnrichment,
+        Self::SpanMetricDerivation,
+        Self::TraceSampling,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::GeoipEnrichment => "geoip_enrichment",
+            Self::SpanMetricDerivation => "span_metric_derivation",
+            Self::TraceSampling => "trace_sampling",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|flag| flag.as_str() == name)
+    }
+
+    /// Whether the behavior is on for projects that don't set the flag; each
+    /// is still off until its own setting configures it
+    pub fn default_enabled(&self) -> bool {
+        match self {
+            Self::GeoipEnrichment | Self::SpanMetricDerivation | Self::TraceSampling => true,
+        }
+    }
+}
+
+/// Per-project ingestion settings, persisted as JSONB on the project row.
+/// Unknown or missing keys fall back to their defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectSettings {
+    pub late_metrics_policy: LateMetricsPolicy,
+    pub missing_timestamp_policy: MissingTimestampPolicy,
+    pub metric_type_policy: MetricTypePolicy,
+    /// Log ingest acknowledgment when a request doesn't choose one
+    pub ingest_mode: IngestMode,
+    /// Derive request/error/duration metrics from ingested spans
+    pub derive_span_metrics: bool,
+    /// Data residency region the project is pinned to (e.g. "eu-west-1").
+    /// Ingest is rejected by deployments serving a different region.
+    pub region: Option<String>,
+    pub redaction: RedactionSettings,
+    pub field_mapping: FieldMappingSettings,
+    pub plain_text: PlainTextParsingSettings,
+    /// CIDR ranges (or single addresses) ingest requests must come from.
+    /// Empty allows every source.
+    pub allowed_ips: Vec<String>,
+    /// Span attributes giving a span's effective service name, in priority
+    /// order (e.g. ["k8s.deployment.name", "service.name"]). The first one
+    /// present wins; empty keeps the reported service name.
+    pub service_name_attributes: Vec<String>,
+    pub trace_retention: TraceRetentionSettings,
+    pub log_retention: LogRetentionSettings,
+    pub trace_sampling: TraceSamplingSettings,
+    pub span_attribute_filter: SpanAttributeFilterSettings,
+    pub span_attribute_types: SpanAttributeTypeSettings,
+    pub trace_completeness: TraceCompletenessSettings,
+    /// Lowest `http.status_code` that marks a span with unset status as an
+    /// error (500 for server errors, 400 to include client errors). Unset
+    /// keeps span statuses as reported.
+    pub http_error_status_threshold: Option<u16>,
+    pub label_normalization: LabelNormalizationSettings,
+    pub metric_label_limit: MetricLabelLimitSettings,
+    pub duplicate_span_policy: DuplicateSpanPolicy,
+    pub alert_grouping: AlertGroupingSettings,
+    /// Primary field the log list is grouped by: "source", "level" or a
+    /// metadata path like "metadata.logger". Unset groups by source.
+    pub log_group_field: Option<String>,
+    /// Short names for metadata paths usable in log query filters and field
+    /// lists, e.g. {"route": "metadata.request.http.route"}
+    pub metadata_aliases: BTreeMap<String, String>,
+    /// Least severe log level stored at ingest ("trace" through "fatal");
+    /// less severe logs are dropped and counted. Unset stores every level.
+    pub min_ingest_level: Option<String>,
+    pub clock_skew: ClockSkewSettings,
+    pub log_timestamp_precision: LogTimestampPrecision,
+    /// Dot-separated metadata path of a client IP (e.g. "http.client_ip")
+    /// whose geolocation is added to the log as "geo" metadata at ingest.
+    /// Unset skips the lookup.
+    pub geoip_field: Option<String>,
+    /// IANA timezone (e.g. "Europe/Paris") whose local day, hour and minute
+    /// boundaries stats and timeseries buckets align to. Unset uses UTC.
+    pub timezone: Option<String>,
+    /// On/off switches by [`FeatureFlag`] name; unset flags use their default
+    pub feature_flags: BTreeMap<String, bool>,
+}
+
+impl ProjectSettings {
+    /// Load settings from their stored JSON, ignoring anything unparseable
+    pub fn from_json(value: Value) -> Self {
+        serde_json::from_value(value).unwrap_or_default()
+    }
+
+    /// Parsed ingest allowlist; entries that don't parse are skipped
+    pub fn allowed_networks(&self) -> Vec<IpNet> {
+        self.allowed_ips
+            .iter()
+            .filter_map(|entry| parse_network(entry))
+            .collect()
+    }
+
+    /// Field logs are grouped by, falling back to source
+    pub fn log_group_field(&self) -> LogGroupField {
+        self.log_group_field
+            .as_deref()
+            .and_then(LogGroupField::parse)
+            .unwrap_or(LogGroupField::Source)
+    }
+
+    /// Timezone stats are bucketed in, falling back to UTC
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+            .as_deref()
+            .and_then(parse_timezone)
+            .unwrap_or(Tz::UTC)
+    }
+
+    /// Whether the project has an ingest behavior switched on
+    pub fn feature_enabled(&self, flag: FeatureFlag) -> bool {
+        self.feature_flags
+            .get(flag.as_str())
+            .copied()
+            .unwrap_or_else(|| flag.default_enabled())
+    }
+
+    /// Every feature flag with its effective value
+    pub fn feature_flag_values(&self) -> BTreeMap<String, bool> {
+        FeatureFlag::ALL
+            .into_iter()
+            .map(|flag| (flag.as_str().to_string(), self.feature_enabled(flag)))
+            .collect()
+    }
+
+    /// Metadata path an alias stands for, without the "metadata." prefix
+    pub fn metadata_alias(&self, alias: &str) -> Option<&str> {
+        self.metadata_aliases
+            .get(alias)
+            .and_then(|path| path.strip_prefix("metadata."))
+    }
+
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).unwrap_or_else(|_| Value::Object(Default::default()))
+    }
+
+    /// Apply a partial JSON update on top of the current settings
+    pub fn merge(&self, patch: Value) -> Result<Self, ProjectDomainError> {
+        let Value::Object(patch) = patch else {
+            return Err(ProjectDomainError::InvalidSettings(
+                "settings must be a JSON object".to_string(),
+            ));
+        };
+
+        let mut current = self.to_json();
+        if let Value::Object(ref mut fields) = current {
+            fields.extend(patch);
+        }
+
+        let settings: Self = serde_json::from_value(current)
+            .map_err(|e| ProjectDomainError::InvalidSettings(e.to_string()))?;
+        if let Some(region) = &settings.region {
+            validate_region(region)?;
+        }
+        for pattern in &settings.redaction.message_patterns {
+            regex::Regex::new(pattern).map_err(|e| {
+                ProjectDomainError::InvalidSettings(format!(
+                    "invalid redaction pattern '{}': {}",
+                    pattern, e
+                ))
+            })?;
+        }
+        validate_field_mapping(&settings.field_mapping)?;
+        validate_plain_text_parsing(&settings.plain_text)?;
+        for entry in &settings.allowed_ips {
+            if parse_network(entry).is_none() {
+                return Err(ProjectDomainError::InvalidSettings(format!(
+                    "invalid allowed IP '{}': use a CIDR range like 203.0.113.0/24 or a single address",
+                    entry
+                )));
+            }
+        }
+        let retention = &settings.trace_retention;
+        for days in std::iter::once(&retention.default)
+            .chain(retention.services.values())
+            .flat_map(|o| [o.error_days, o.clean_days])
+            .flatten()
+        {
+            TracesRetentionDays::new(days)?;
+        }
+        for rule in &settings.log_retention.rules {
+            if rule.tags.is_empty() || rule.tags.keys().any(|key| key.trim().is_empty()) {
+                return Err(ProjectDomainError::InvalidSettings(
+                    "log retention rules need at least one metadata tag".to_string(),
+                ));
+            }
+            RetentionDays::new(rule.days)?;
+        }
+        if settings
+            .service_name_attributes
+            .iter()
+            .any(|attr| attr.trim().is_empty())
+        {
+            return Err(ProjectDomainError::InvalidSettings(
+                "service name attributes must not be empty".to_string(),
+            ));
+        }
+        for rate in settings
+            .trace_sampling
+            .rate
+            .iter()
+            .chain(settings.trace_sampling.services.values())
+        {
+            if !(0.0..=1.0).contains(rate) {
+                return Err(ProjectDomainError::InvalidSettings(format!(
+                    "invalid trace sampling rate {}: must be between 0.0 and 1.0",
+                    rate
+                )));
+            }
+        }
+        if settings.trace_sampling.latency_threshold_ms == Some(0) {
+            return Err(ProjectDomainError::InvalidSettings(
+                "trace sampling latency threshold must be positive".to_string(),
+            ));
+        }
+        let filter = &settings.span_attribute_filter;
+        for pattern in filter.allow.iter().chain(&filter.deny) {
+            let key = pattern.strip_suffix('*').unwrap_or(pattern);
+            if key.trim().is_empty() || key.contains('*') {
+                return Err(ProjectDomainError::InvalidSettings(format!(
+                    "invalid span attribute pattern '{}': use a key or a prefix ending in *",
+                    pattern
+                )));
+            }
+        }
+        if settings
+            .span_attribute_types
+            .types
+            .keys()
+            .any(|key| key.trim().is_empty())
+        {
+            return Err(ProjectDomainError::InvalidSettings(
+                "span attribute type keys must not be empty".to_string(),
+            ));
+        }
+        if settings.trace_completeness.window_secs > MAX_TRACE_COMPLETENESS_WINDOW_SECS {
+            return Err(ProjectDomainError::InvalidSettings(format!(
+                "trace completeness window must be at most {} seconds",
+                MAX_TRACE_COMPLETENESS_WINDOW_SECS
+            )));
+        }
+        if let Some(threshold) = settings.http_error_status_threshold
+            && !(100..=599).contains(&threshold)
+        {
+            return Err(ProjectDomainError::InvalidSettings(format!(
+                "invalid HTTP error status threshold {}: must be between 100 and 599",
+                threshold
+            )));
+        }
+        validate_label_normalization(&settings.label_normalization)?;
+        if !(1..=MAX_METRIC_LABELS).contains(&settings.metric_label_limit.max_labels) {
+            return Err(ProjectDomainError::InvalidSettings(format!(
+                "metric label limit must be between 1 and {}",
+                MAX_METRIC_LABELS
+            )));
+        }
+        if settings.alert_grouping.key.trim().is_empty() {
+            return Err(ProjectDomainError::InvalidSettings(
+                "alert grouping key must not be empty".to_string(),
+            ));
+        }
+        if settings.alert_grouping.window_seconds > MAX_ALERT_GROUPING_WINDOW_SECONDS {
+            return Err(ProjectDomainError::InvalidSettings(format!(
+                "alert grouping window must be at most {} seconds",
+                MAX_ALERT_GROUPING_WINDOW_SECONDS
+            )));
+        }
+        if let Some(level) = &settings.min_ingest_level
+            && !LOG_LEVELS.contains(&level.as_str())
+        {
+            return Err(ProjectDomainError::InvalidSettings(format!(
+                "invalid minimum ingest level '{}': use one of {}",
+                level,
+                LOG_LEVELS.join(", ")
+            )));
+        }
+        if settings.clock_skew.max_correction_ms > MAX_CLOCK_SKEW_CORRECTION_MS {
+            return Err(ProjectDomainError::InvalidSettings(format!(
+                "clock skew correction must be at most {} ms",
+                MAX_CLOCK_SKEW_CORRECTION_MS
+            )));
+        }
+        if let Some(field) = &settings.log_group_field
+            && LogGroupField::parse(field).is_none()
+        {
+            return Err(ProjectDomainError::InvalidSettings(format!(
+                "invalid log group field '{}': use source, level or metadata.<key>",
+                field
+            )));
+        }
+        if let Some(name) = settings
+            .feature_flags
+            .keys()
+            .find(|name| FeatureFlag::parse(name).is_none())
+        {
+            return Err(ProjectDomainError::InvalidSettings(format!(
+                "unknown feature flag '{}': use one of {}",
+                name,
+                FeatureFlag::ALL.map(|flag| flag.as_str()).join(", ")
+            )));
+        }
+        if let Some(timezone) = &settings.timezone
+            && parse_timezone(timezone).is_none()
+        {
+            return Err(ProjectDomainError::InvalidSettings(format!(
+                "invalid timezone '{}': use an IANA name like Europe/Paris",
+                timezone
+            )));
+        }
+        if let Some(field) = &settings.geoip_field
+            && (field.is_empty() || field.split('.').any(str::is_empty))
+        {
+            return Err(ProjectDomainError::InvalidSettings(format!(
+                "invalid GeoIP field '{}'",
+                field
+            )));
+        }
+        for (alias, path) in &settings.metadata_aliases {
+            let valid_name =
+                !alias.is_empty() && alias.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid_name || RESERVED_LOG_FIELDS.contains(&alias.as_str()) {
+                return Err(ProjectDomainError::InvalidSettings(format!(
+                    "invalid metadata alias '{}': use letters, digits and '_', not a log field name",
+                    alias
+                )));
+            }
+            if !matches!(LogGroupField::parse(path), Some(LogGroupField::Metadata(_))) {
+                return Err(ProjectDomainError::InvalidSettings(format!(
+                    "invalid path '{}' for metadata alias '{}': use metadata.<key>",
+                    path, alias
+                )));
+            }
+        }
+        Ok(settings)
+    }
+}
+
+/// Rename rules must map each (normalized) key once, onto a distinct target
+/// that isn't itself renamed, so the result doesn't depend on rule order
+fn validate_label_normalization(
+    settings: &LabelNormalizationSettings,
+) -> Result<(), ProjectDomainError> {
+    let invalid = |msg: String| Err(ProjectDomainError::InvalidSettings(msg));
+    let key = |k: &str| {
+        if settings.normalize_keys {
+            normalize_label_key(k)
+        } else {
+            k.to_string()
+        }
+    };
+
+    let mut sources = HashSet::new();
+    for from in settings.rename.keys() {
+        if from.trim().is_empty() {
+            return invalid("label rename sources must not be empty".to_string());
+        }
+        if !sources.insert(key(from)) {
+            return invalid(format!(
+                "label rename source '{}' collides with another rule after normalization",
+                from
+            ));
+        }
+    }
+
+    let mut targets = HashSet::new();
+    for to in settings.rename.values() {
+        if to.trim().is_empty() {
+            return invalid("label rename targets must not be empty".to_string());
+        }
+        if settings.normalize_keys && normalize_label_key(to) != *to {
+            return invalid(format!(
+                "label rename target '{}' is not a normalized key",
+                to
+            ));
+        }
+        if !targets.insert(to.clone()) {
+            return invalid(format!("more than one label is renamed to '{}'", to));
+        }
+        if sources.contains(to) {
+            return invalid(format!("label rename target '{}' is itself renamed", to));
+        }
+    }
+    Ok(())
+}
+
+/// Paths must have non-empty segments; rename targets must be distinct plain keys
+fn validate_field_mapping(mapping: &FieldMappingSettings) -> Result<(), ProjectDomainError> {
+    let invalid = |msg: String| Err(ProjectDomainError::InvalidSettings(msg));
+
+    let paths = mapping
+        .fields()
+        .into_iter()
+        .filter_map(|(_, path)| path)
+        .chain(mapping.rename.keys().map(String::as_str))
+        .chain(mapping.drop.iter().map(String::as_str));
+    for path in paths {
+        if path.is_empty() || path.split('.').any(str::is_empty) {
+            return invalid(format!("invalid field mapping path '{}'", path));
+        }
+    }
+
+    let mut targets = HashSet::new();
+    for target in mapping.rename.values() {
+        if target.is_empty() || target.contains('.') {
+            return invalid(format!(
+                "invalid rename target '{}': use a top-level key",
+                target
+            ));
+        }
+        if !targets.insert(target) {
+            return invalid(format!("more than one field is renamed to '{}'", target));
+        }
+    }
+    Ok(())
+}
+
+/// A CIDR range, or a single address as a one-address range
+pub fn parse_network(entry: &str) -> Option<IpNet> {
+    let entry = entry.trim();
+    entry
+        .parse::<IpNet>()
+        .ok()
+        .or_else(|| entry.parse::<IpAddr>().ok().map(IpNet::from))
+}
+
+/// Region names are short lowercase identifiers like "us-east-1"
+fn validate_plain_text_parsing(
+    settings: &PlainTextParsingSettings,
+) -> Result<(), ProjectDomainError> {
+    if let Some(level) = &settings.default_level
+        && !LOG_LEVELS.contains(&level.as_str())
+    {
+        return Err(ProjectDomainError::InvalidSettings(format!(
+            "invalid plain-text default level '{}': use one of {}",
+            level,
+            LOG_LEVELS.join(", ")
+        )));
+    }
+    for pattern in [&settings.level_pattern, &settings.timestamp_pattern]
+        .into_iter()
+        .flatten()
+    {
+        regex::Regex::new(pattern).map_err(|e| {
+            ProjectDomainError::InvalidSettings(format!(
+                "invalid plain-text pattern '{}': {}",
+                pattern, e
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+fn validate_region(region: &str) -> Result<(), ProjectDomainError> {
+    let valid = !region.is_empty()
+        && region.len() <= 32
+        && region
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(ProjectDomainError::InvalidSettings(format!(
+            "invalid region '{}': use lowercase letters, digits and '-'",
+            region
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_missing_keys_use_defaults() {
+        let settings = ProjectSettings::from_json(json!({}));
+        assert_eq!(settings.late_metrics_policy, LateMetricsPolicy::Accept);
+        assert_eq!(settings.metric_type_policy, MetricTypePolicy::Enforce);
+        assert!(!settings.derive_span_metrics);
+    }
+
+    #[test]
+    fn test_feature_flags_default_and_reject_unknown_names() {
+        let settings = ProjectSettings::default();
+        assert!(settings.feature_enabled(FeatureFlag::GeoipEnrichment));
+
+        let settings = settings
+            .merge(json!({"feature_flags": {"geoip_enrichment": false}}))
+            .unwrap();
+        assert!(!settings.feature_enabled(FeatureFlag::GeoipEnrichment));
+        assert!(settings.feature_enabled(FeatureFlag::TraceSampling));
+        assert_eq!(settings.feature_flag_values().len(), FeatureFlag::ALL.len());
+
+        assert!(
+            settings
+                .merge(json!({"feature_flags": {"telepathy": true}}))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_merge_updates_only_given_keys() {
+        let settings = ProjectSettings::default()
+            .merge(json!({"late_metrics_policy": "strict"}))
+            .unwrap();
+        assert_eq!(settings.late_metrics_policy, LateMetricsPolicy::Strict);
+    }
+
+    #[test]
+    fn test_merge_rejects_invalid_values() {
+        let settings = ProjectSettings::default();
+        assert!(
+            settings
+                .merge(json!({"late_metrics_policy": "sometimes"}))
+                .is_err()
+        );
+        assert!(settings.merge(json!("strict")).is_err());
+        assert!(settings.merge(json!({"region": "EU West"})).is_err());
+        assert!(
+            settings
+                .merge(json!({"redaction": {"message_patterns": ["(unclosed"]}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"field_mapping": {"message": "log..msg"}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"field_mapping": {"rename": {"a": "x", "b": "x"}}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"allowed_ips": ["10.0.0.0/33"]}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"service_name_attributes": ["k8s.deployment.name", " "]}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"trace_retention": {"services": {"api": {"error_days": 365}}}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"trace_sampling": {"services": {"api": 1.5}}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"log_retention": {"rules": [{"tags": {}, "days": 365}]}}))
+                .is_err()
+        );
+        assert!(settings
+            .merge(json!({"log_retention": {"rules": [{"tags": {"category": "audit"}, "days": 3650}]}}))
+            .is_err());
+        assert!(
+            settings
+                .merge(json!({"http_error_status_threshold": 600}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"trace_completeness": {"window_secs": 7200}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"trace_completeness": {"mode": "drop"}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"span_attribute_filter": {"deny": ["http.*.secret"]}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"span_attribute_types": {"types": {"http.status_code": "integer"}}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"span_attribute_types": {"types": {" ": "int"}}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"plain_text": {"level_pattern": "[unclosed"}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"trace_sampling": {"latency_threshold_ms": 0}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"plain_text": {"default_level": "loud"}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"label_normalization": {"rename": {"a": "x", "b": "x"}}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"label_normalization": {
+                    "normalize_keys": true,
+                    "rename": {"http.method": "method", "HTTP_METHOD": "verb"}
+                }}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"label_normalization": {"rename": {"a": "b", "b": "c"}}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"metric_label_limit": {"max_labels": 0}}))
+                .is_err()
+        );
+        assert!(settings.merge(json!({"log_group_field": "host"})).is_err());
+        assert!(
+            settings
+                .merge(json!({"metadata_aliases": {"level": "metadata.log.level"}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"metadata_aliases": {"route": "request.route"}}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"log_group_field": "metadata..host"}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"min_ingest_level": "verbose"}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"log_timestamp_precision": "picosecond"}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"geoip_field": "http..client_ip"}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"timezone": "Europe/Atlantis"}))
+                .is_err()
+        );
+        assert!(
+            settings
+                .merge(json!({"clock_skew": {"mode": "adjust", "max_correction_ms": 172_800_000}}))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_attribute_type_coercion() {
+        assert_eq!(AttributeType::Int.coerce(&json!(" 503 ")), Some(json!(503)));
+        assert_eq!(AttributeType::Int.coerce(&json!(200.0)), Some(json!(200)));
+        assert_eq!(AttributeType::Int.coerce(&json!(1.5)), None);
+        assert_eq!(AttributeType::Int.coerce(&json!("OK")), None);
+        assert_eq!(
+            AttributeType::Double.coerce(&json!("0.25")),
+            Some(json!(0.25))
+        );
+        assert_eq!(
+            AttributeType::Bool.coerce(&json!("TRUE")),
+            Some(json!(true))
+        );
+        assert_eq!(AttributeType::Bool.coerce(&json!(2)), None);
+        assert_eq!(AttributeType::String.coerce(&json!(42)), Some(json!("42")));
+        assert_eq!(AttributeType::String.coerce(&json!(["a"])), None);
+    }
+
+    #[test]
+    fn test_merge_sets_and_clears_region() {
+        let pinned = ProjectSettings::default()
+            .merge(json!({"region": "eu-west-1"}))
+            .unwrap();
+        assert_eq!(pinned.region.as_deref(), Some("eu-west-1"));
+
+        let cleared = pinned.merge(json!({"region": null})).unwrap();
+        assert_eq!(cleared.region, None);
+    }
+}
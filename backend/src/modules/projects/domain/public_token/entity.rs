@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+
+use crate::modules::projects::domain::project::ProjectId;
+
+/// Token granting unauthenticated read-only access to one project's queries,
+/// for sharing a dashboard publicly. Only its hash is stored.
+#[derive(Debug, Clone)]
+pub struct PublicReadToken {
+    project_id: ProjectId,
+    token_hash: String,
+    /// First characters of the token, shown so admins can recognize it
+    token_prefix: String,
+    created_by: String,
+    created_at: DateTime<Utc>,
+}
+
+impl PublicReadToken {
+    pub fn new(
+        project_id: ProjectId,
+        token_hash: String,
+        token_prefix: String,
+        created_by: String,
+    ) -> Self {
+        Self {
+            project_id,
+            token_hash,
+            token_prefix,
+            created_by,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn from_db(
+        project_id: ProjectId,
+        token_hash: String,
+        token_prefix: String,
+        created_by: String,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            project_id,
+            token_hash,
+            token_prefix,
+            created_by,
+            created_at,
+        }
+    }
+
+    pub fn project_id(&self) -> &ProjectId {
+        &self.project_id
+    }
+
+    pub fn token_hash(&self) -> &str {
+        &self.token_hash
+    }
+
+    pub fn token_prefix(&self) -> &str {
+        &self.token_prefix
+    }
+
+    pub fn created_by(&self) -> &str {
+        &self.created_by
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
@@ -0,0 +1,25 @@
+use async_trait::async_trait;
+
+use super::entity::PublicReadToken;
+use crate::modules::projects::domain::errors::ProjectDomainError;
+use crate::modules::projects::domain::project::ProjectId;
+
+/// Repository trait for public read tokens; a project has at most one
+#[async_trait]
+pub trait PublicReadTokenRepository: Send + Sync {
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<PublicReadToken>, ProjectDomainError>;
+
+    async fn find_by_project(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Option<PublicReadToken>, ProjectDomainError>;
+
+    /// Store a project's token, replacing any it already has
+    async fn save(&self, token: &PublicReadToken) -> Result<(), ProjectDomainError>;
+
+    /// Remove a project's token, returning whether it had one
+    async fn delete(&self, project_id: &ProjectId) -> Result<bool, ProjectDomainError>;
+}
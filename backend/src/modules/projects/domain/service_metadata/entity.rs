@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::modules::projects::domain::errors::ProjectDomainError;
+use crate::modules::projects::domain::project::ProjectId;
+
+const MAX_DISPLAY_NAME_LENGTH: usize = 100;
+const MAX_LINKS: usize = 10;
+
+/// A link shown next to a service, e.g. its runbook or repository
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServiceLink {
+    pub title: String,
+    pub url: String,
+}
+
+/// Human-friendly details for a service, keyed by its raw `service.name`
+#[derive(Debug, Clone)]
+pub struct ServiceMetadata {
+    project_id: ProjectId,
+    service_name: String,
+    display_name: String,
+    team: Option<String>,
+    links: Vec<ServiceLink>,
+    updated_at: DateTime<Utc>,
+}
+
+impl ServiceMetadata {
+    pub fn new(
+        project_id: ProjectId,
+        service_name: String,
+        display_name: String,
+        team: Option<String>,
+        links: Vec<ServiceLink>,
+    ) -> Result<Self, ProjectDomainError> {
+        let service_name = service_name.trim().to_string();
+        if service_name.is_empty() {
+            return Err(ProjectDomainError::InvalidServiceMetadata(
+                "Service name cannot be empty".to_string(),
+            ));
+        }
+        let display_name = display_name.trim().to_string();
+        if display_name.is_empty() || display_name.chars().count() > MAX_DISPLAY_NAME_LENGTH {
+            return Err(ProjectDomainError::InvalidServiceMetadata(format!(
+                "Display name must be 1-{} characters",
+                MAX_DISPLAY_NAME_LENGTH
+            )));
+        }
+        if links.len() > MAX_LINKS {
+            return Err(ProjectDomainError::InvalidServiceMetadata(format!(
+                "At most {} links are allowed",
+                MAX_LINKS
+            )));
+        }
+        if let Some(link) = links
+            .iter()
+            .find(|l| !(l.url.starts_with("https://") || l.url.starts_with("http://")))
+        {
+            return Err(ProjectDomainError::InvalidServiceMetadata(format!(
+                "Link '{}' must be an http(s) URL",
+                link.title
+            )));
+        }
+
+        Ok(Self {
+            project_id,
+            service_name,
+            display_name,
+            team: team.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()),
+            links,
+            updated_at: Utc::now(),
+        })
+    }
+
+    pub fn from_db(
+        project_id: ProjectId,
+        service_name: String,
+        display_name: String,
+        team: Option<String>,
+        links: Vec<ServiceLink>,
+        updated_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            project_id,
+            service_name,
+            display_name,
+            team,
+            links,
+            updated_at,
+        }
+    }
+
+    pub fn project_id(&self) -> &ProjectId {
+        &self.project_id
+    }
+
+    pub fn service_name(&self) -> &str {
+        &self.service_name
+    }
+
+    pub fn display_name(&self) -> &str {
+        &self.display_name
+    }
+
+    pub fn team(&self) -> Option<&str> {
+        self.team.as_deref()
+    }
+
+    pub fn links(&self) -> &[ServiceLink] {
+        &self.links
+    }
+
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+}
+
+/// How a service is shown in query responses. Without metadata the raw
+/// service name doubles as the display name.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ServiceDisplay {
+    pub name: String,
+    pub display_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<ServiceLink>,
+}
+
+impl ServiceDisplay {
+    pub fn resolve(name: &str, metadata: Option<&ServiceMetadata>) -> Self {
+        match metadata {
+            Some(m) => Self {
+                name: name.to_string(),
+                display_name: m.display_name.clone(),
+                team: m.team.clone(),
+                links: m.links.clone(),
+            },
+            None => Self {
+                name: name.to_string(),
+                display_name: name.to_string(),
+                team: None,
+                links: Vec::new(),
+            },
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod entity;
+pub mod repository;
+
+pub use entity::{ServiceDisplay, ServiceLink, ServiceMetadata};
+pub use repository::{ServiceMetadataRepository, resolve_service_displays};
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use super::entity::{ServiceDisplay, ServiceMetadata};
+use crate::modules::projects::domain::errors::ProjectDomainError;
+use crate::modules::projects::domain::project::ProjectId;
+
+/// Repository trait for per-project service metadata
+#[async_trait]
+pub trait ServiceMetadataRepository: Send + Sync {
+    /// All metadata configured for a project
+    async fn find_by_project(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Vec<ServiceMetadata>, ProjectDomainError>;
+
+    /// Metadata for the given services in one lookup; services without metadata are omitted
+    async fn find_by_services(
+        &self,
+        project_id: &ProjectId,
+        service_names: &[String],
+    ) -> Result<Vec<ServiceMetadata>, ProjectDomainError>;
+
+    /// Insert or replace metadata for a service
+    async fn save(&self, metadata: &ServiceMetadata) -> Result<(), ProjectDomainError>;
+
+    /// Remove a service's metadata, returning whether it existed
+    async fn delete(
+        &self,
+        project_id: &ProjectId,
+        service_name: &str,
+    ) -> Result<bool, ProjectDomainError>;
+}
+
+/// Resolve how each of `service_names` is displayed with a single batched
+/// lookup. Without a repository every service falls back to its raw name.
+pub async fn resolve_service_displays(
+    repo: Option<&dyn ServiceMetadataRepository>,
+    project_id: &ProjectId,
+    service_names: &[String],
+) -> Result<HashMap<String, ServiceDisplay>, ProjectDomainError> {
+    let mut names: Vec<String> = service_names.to_vec();
+    names.sort();
+    names.dedup();
+    if names.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let metadata: HashMap<String, ServiceMetadata> = match repo {
+        Some(repo) => repo
+            .find_by_services(project_id, &names)
+            .await?
+            .into_iter()
+            .map(|m| (m.service_name().to_string(), m))
+            .collect(),
+        None => HashMap::new(),
+    };
+
+    Ok(names
+        .into_iter()
+        .map(|name| {
+            let display = ServiceDisplay::resolve(&name, metadata.get(&name));
+            (name, display)
+        })
+        .collect())
+}
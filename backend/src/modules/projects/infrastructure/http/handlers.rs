@@ -1,18 +1,27 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
     Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use crate::modules::auth::application::ports::IdGenerator;
-use crate::modules::auth::infrastructure::http::extractors::AuthClaims;
+use crate::modules::auth::domain::TokenScope;
+use crate::modules::auth::infrastructure::http::extractors::{AuthClaims, MissingScope};
 use crate::modules::organizations::domain::{OrganizationMemberRepository, OrganizationRepository};
 use crate::modules::projects::application::dto::*;
-use crate::modules::projects::application::services::ProjectService;
-use crate::modules::projects::domain::{ApiKeyRepository, ProjectDomainError, ProjectRepository};
+use crate::modules::projects::application::services::{
+    ProjectService, PublicReadTokenService, ServiceMetadataService,
+};
+use crate::modules::projects::domain::{
+    ApiKeyRepository, ProjectDomainError, ProjectRepository, ProjectSettings,
+    PublicReadTokenRepository, ServiceLink, ServiceMetadataRepository, TraceSamplingSettings,
+};
+use crate::shared::PAGINATION_LIMIT_HEADER;
 
 // ============================================================================
 // Request/Response DTOs for HTTP layer
@@ -34,6 +43,13 @@ pub struct UpdateProjectRequest {
     pub retention_days: Option<i32>,
     pub metrics_retention_days: Option<i32>,
     pub traces_retention_days: Option<i32>,
+    pub settings: Option<Value>,
+}
+
+/// Flag values by name; null resets a flag to its default
+#[derive(Debug, Deserialize)]
+pub struct UpdateFeatureFlagsRequest {
+    pub flags: BTreeMap<String, Option<bool>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -51,10 +67,16 @@ pub struct ProjectResponseDto {
     pub retention_days: i32,
     pub metrics_retention_days: i32,
     pub traces_retention_days: i32,
+    pub settings: ProjectSettings,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct FeatureFlagsResponseDto {
+    pub flags: BTreeMap<String, bool>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ApiKeyResponseDto {
     pub id: String,
@@ -75,6 +97,37 @@ pub struct ApiKeyCreatedResponseDto {
     pub expires_at: Option<DateTime<Utc>>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct UpsertServiceMetadataRequest {
+    pub display_name: String,
+    pub team: Option<String>,
+    #[serde(default)]
+    pub links: Vec<ServiceLink>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServiceMetadataResponseDto {
+    pub service_name: String,
+    pub display_name: String,
+    pub team: Option<String>,
+    pub links: Vec<ServiceLink>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PublicReadTokenResponseDto {
+    pub token_prefix: String,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PublicReadTokenCreatedResponseDto {
+    pub token_prefix: String,
+    pub token: String,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ErrorResponse {
     pub error: String,
@@ -91,12 +144,19 @@ impl From<ProjectResponse> for ProjectResponseDto {
             retention_days: r.retention_days,
             metrics_retention_days: r.metrics_retention_days,
             traces_retention_days: r.traces_retention_days,
+            settings: r.settings,
             created_at: r.created_at,
             updated_at: r.updated_at,
         }
     }
 }
 
+impl From<FeatureFlagsResponse> for FeatureFlagsResponseDto {
+    fn from(r: FeatureFlagsResponse) -> Self {
+        Self { flags: r.flags }
+    }
+}
+
 impl From<ApiKeyResponse> for ApiKeyResponseDto {
     fn from(r: ApiKeyResponse) -> Self {
         Self {
@@ -123,6 +183,38 @@ impl From<ApiKeyCreatedResponse> for ApiKeyCreatedResponseDto {
     }
 }
 
+impl From<ServiceMetadataResponse> for ServiceMetadataResponseDto {
+    fn from(r: ServiceMetadataResponse) -> Self {
+        Self {
+            service_name: r.service_name,
+            display_name: r.display_name,
+            team: r.team,
+            links: r.links,
+            updated_at: r.updated_at,
+        }
+    }
+}
+
+impl From<PublicReadTokenResponse> for PublicReadTokenResponseDto {
+    fn from(r: PublicReadTokenResponse) -> Self {
+        Self {
+            token_prefix: r.token_prefix,
+            created_by: r.created_by,
+            created_at: r.created_at,
+        }
+    }
+}
+
+impl From<PublicReadTokenCreatedResponse> for PublicReadTokenCreatedResponseDto {
+    fn from(r: PublicReadTokenCreatedResponse) -> Self {
+        Self {
+            token_prefix: r.token_prefix,
+            token: r.plain_token,
+            created_at: r.created_at,
+        }
+    }
+}
+
 // ============================================================================
 // Error handling
 // ============================================================================
@@ -131,7 +223,9 @@ fn to_error_response(e: ProjectDomainError) -> (StatusCode, Json<ErrorResponse>)
     match e {
         ProjectDomainError::InvalidProjectName(_)
         | ProjectDomainError::InvalidRetentionDays(_)
-        | ProjectDomainError::InvalidApiKeyName(_) => (
+        | ProjectDomainError::InvalidApiKeyName(_)
+        | ProjectDomainError::InvalidSettings(_)
+        | ProjectDomainError::InvalidServiceMetadata(_) => (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 error: e.to_string(),
@@ -145,6 +239,27 @@ fn to_error_response(e: ProjectDomainError) -> (StatusCode, Json<ErrorResponse>)
                 code: "PROJECT_NOT_FOUND".to_string(),
             }),
         ),
+        ProjectDomainError::ServiceMetadataNotFound => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Service metadata not found".to_string(),
+                code: "SERVICE_METADATA_NOT_FOUND".to_string(),
+            }),
+        ),
+        ProjectDomainError::PublicTokenNotFound => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Project has no public read token".to_string(),
+                code: "PUBLIC_TOKEN_NOT_FOUND".to_string(),
+            }),
+        ),
+        ProjectDomainError::PublicTokenInvalid => (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Invalid public read token".to_string(),
+                code: "INVALID_PUBLIC_TOKEN".to_string(),
+            }),
+        ),
         ProjectDomainError::ApiKeyNotFound => (
             StatusCode::NOT_FOUND,
             Json(ErrorResponse {
@@ -173,6 +288,13 @@ fn to_error_response(e: ProjectDomainError) -> (StatusCode, Json<ErrorResponse>)
                 code: "FORBIDDEN".to_string(),
             }),
         ),
+        ProjectDomainError::EmailNotVerified => (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "EMAIL_NOT_VERIFIED".to_string(),
+            }),
+        ),
         ProjectDomainError::ApiKeyRevoked => (
             StatusCode::UNAUTHORIZED,
             Json(ErrorResponse {
@@ -201,6 +323,20 @@ fn to_error_response(e: ProjectDomainError) -> (StatusCode, Json<ErrorResponse>)
                 code: "PROJECT_DELETED".to_string(),
             }),
         ),
+        ProjectDomainError::RegionMismatch { .. } => (
+            StatusCode::MISDIRECTED_REQUEST,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "REGION_MISMATCH".to_string(),
+            }),
+        ),
+        ProjectDomainError::IpNotAllowed(_) => (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "IP_NOT_ALLOWED".to_string(),
+            }),
+        ),
         ProjectDomainError::InternalError(ref msg) => {
             tracing::error!(error = %msg, "Internal error occurred");
             (
@@ -214,6 +350,19 @@ fn to_error_response(e: ProjectDomainError) -> (StatusCode, Json<ErrorResponse>)
     }
 }
 
+/// 403 for personal access tokens that lack a route's scope
+impl From<MissingScope> for (StatusCode, Json<ErrorResponse>) {
+    fn from(err: MissingScope) -> Self {
+        (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: err.to_string(),
+                code: MissingScope::CODE.to_string(),
+            }),
+        )
+    }
+}
+
 // ============================================================================
 // Project Handlers
 // ============================================================================
@@ -232,6 +381,8 @@ where
     MR: OrganizationMemberRepository,
     ID: IdGenerator,
 {
+    claims.require_scope(TokenScope::ProjectsWrite)?;
+
     let cmd = CreateProjectCommand {
         org_id,
         name: req.name,
@@ -249,12 +400,26 @@ where
         .map_err(to_error_response)
 }
 
-/// List all projects in an organization
+#[derive(Debug, Deserialize)]
+pub struct ListProjectsQuery {
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+/// List projects in an organization
+///
+/// The effective page size is returned in the `X-Pagination-Limit` header.
 pub async fn list_projects<PR, AR, OR, MR, ID>(
     State(service): State<Arc<ProjectService<PR, AR, OR, MR, ID>>>,
     Extension(claims): Extension<AuthClaims>,
     Path(org_id): Path<String>,
-) -> Result<Json<Vec<ProjectResponseDto>>, (StatusCode, Json<ErrorResponse>)>
+    Query(query): Query<ListProjectsQuery>,
+) -> Result<
+    ([(&'static str, String); 1], Json<Vec<ProjectResponseDto>>),
+    (StatusCode, Json<ErrorResponse>),
+>
 where
     PR: ProjectRepository,
     AR: ApiKeyRepository,
@@ -262,10 +427,17 @@ where
     MR: OrganizationMemberRepository,
     ID: IdGenerator,
 {
+    claims.require_scope(TokenScope::ProjectsRead)?;
+
     service
-        .list_projects(&org_id, &claims.user_id)
+        .list_projects(&org_id, &claims.user_id, query.limit, query.offset)
         .await
-        .map(|projects| Json(projects.into_iter().map(Into::into).collect()))
+        .map(|page| {
+            (
+                [(PAGINATION_LIMIT_HEADER, page.limit.to_string())],
+                Json(page.projects.into_iter().map(Into::into).collect()),
+            )
+        })
         .map_err(to_error_response)
 }
 
@@ -282,6 +454,8 @@ where
     MR: OrganizationMemberRepository,
     ID: IdGenerator,
 {
+    claims.require_scope(TokenScope::ProjectsRead)?;
+
     service
         .get_project(&project_id, &claims.user_id)
         .await
@@ -303,6 +477,8 @@ where
     MR: OrganizationMemberRepository,
     ID: IdGenerator,
 {
+    claims.require_scope(TokenScope::ProjectsWrite)?;
+
     let cmd = UpdateProjectCommand {
         project_id,
         name: req.name,
@@ -310,6 +486,7 @@ where
         retention_days: req.retention_days,
         metrics_retention_days: req.metrics_retention_days,
         traces_retention_days: req.traces_retention_days,
+        settings: req.settings,
         requesting_user_id: claims.user_id,
     };
 
@@ -333,6 +510,8 @@ where
     MR: OrganizationMemberRepository,
     ID: IdGenerator,
 {
+    claims.require_scope(TokenScope::ProjectsWrite)?;
+
     let cmd = DeleteProjectCommand {
         project_id,
         requesting_user_id: claims.user_id,
@@ -345,6 +524,108 @@ where
         .map_err(to_error_response)
 }
 
+/// Get a project's effective feature flags
+pub async fn get_feature_flags<PR, AR, OR, MR, ID>(
+    State(service): State<Arc<ProjectService<PR, AR, OR, MR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(project_id): Path<String>,
+) -> Result<Json<FeatureFlagsResponseDto>, (StatusCode, Json<ErrorResponse>)>
+where
+    PR: ProjectRepository,
+    AR: ApiKeyRepository,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    claims.require_scope(TokenScope::ProjectsRead)?;
+
+    service
+        .get_feature_flags(&project_id, &claims.user_id)
+        .await
+        .map(|r| Json(r.into()))
+        .map_err(to_error_response)
+}
+
+/// Switch a project's feature flags on or off
+pub async fn update_feature_flags<PR, AR, OR, MR, ID>(
+    State(service): State<Arc<ProjectService<PR, AR, OR, MR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(project_id): Path<String>,
+    Json(req): Json<UpdateFeatureFlagsRequest>,
+) -> Result<Json<FeatureFlagsResponseDto>, (StatusCode, Json<ErrorResponse>)>
+where
+    PR: ProjectRepository,
+    AR: ApiKeyRepository,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    claims.require_scope(TokenScope::ProjectsWrite)?;
+
+    let cmd = UpdateFeatureFlagsCommand {
+        project_id,
+        flags: req.flags,
+        requesting_user_id: claims.user_id,
+    };
+
+    service
+        .update_feature_flags(cmd)
+        .await
+        .map(|r| Json(r.into()))
+        .map_err(to_error_response)
+}
+
+/// Get a project's trace sampling policy
+pub async fn get_trace_sampling<PR, AR, OR, MR, ID>(
+    State(service): State<Arc<ProjectService<PR, AR, OR, MR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(project_id): Path<String>,
+) -> Result<Json<TraceSamplingSettings>, (StatusCode, Json<ErrorResponse>)>
+where
+    PR: ProjectRepository,
+    AR: ApiKeyRepository,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    claims.require_scope(TokenScope::ProjectsRead)?;
+
+    service
+        .get_trace_sampling(&project_id, &claims.user_id)
+        .await
+        .map(Json)
+        .map_err(to_error_response)
+}
+
+/// Replace a project's trace sampling policy; omitted fields take their defaults
+pub async fn update_trace_sampling<PR, AR, OR, MR, ID>(
+    State(service): State<Arc<ProjectService<PR, AR, OR, MR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(project_id): Path<String>,
+    Json(policy): Json<TraceSamplingSettings>,
+) -> Result<Json<TraceSamplingSettings>, (StatusCode, Json<ErrorResponse>)>
+where
+    PR: ProjectRepository,
+    AR: ApiKeyRepository,
+    OR: OrganizationRepository,
+    MR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    claims.require_scope(TokenScope::ProjectsWrite)?;
+
+    let cmd = UpdateTraceSamplingCommand {
+        project_id,
+        policy,
+        requesting_user_id: claims.user_id,
+    };
+
+    service
+        .update_trace_sampling(cmd)
+        .await
+        .map(Json)
+        .map_err(to_error_response)
+}
+
 // ============================================================================
 // API Key Handlers
 // ============================================================================
@@ -363,6 +644,8 @@ where
     MR: OrganizationMemberRepository,
     ID: IdGenerator,
 {
+    claims.require_scope(TokenScope::ProjectsWrite)?;
+
     let cmd = CreateApiKeyCommand {
         project_id,
         name: req.name,
@@ -390,6 +673,8 @@ where
     MR: OrganizationMemberRepository,
     ID: IdGenerator,
 {
+    claims.require_scope(TokenScope::ProjectsRead)?;
+
     service
         .list_api_keys(&project_id, &claims.user_id)
         .await
@@ -410,6 +695,8 @@ where
     MR: OrganizationMemberRepository,
     ID: IdGenerator,
 {
+    claims.require_scope(TokenScope::ProjectsWrite)?;
+
     let cmd = RevokeApiKeyCommand {
         project_id,
         api_key_id,
@@ -422,3 +709,129 @@ where
         .map(|_| StatusCode::NO_CONTENT)
         .map_err(to_error_response)
 }
+
+// ============================================================================
+// Service Metadata Handlers
+// ============================================================================
+
+/// List the display metadata configured for a project's services
+pub async fn list_service_metadata<SMR, PR, MR>(
+    State(service): State<Arc<ServiceMetadataService<SMR, PR, MR>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(project_id): Path<String>,
+) -> Result<Json<Vec<ServiceMetadataResponseDto>>, (StatusCode, Json<ErrorResponse>)>
+where
+    SMR: ServiceMetadataRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+{
+    service
+        .list_metadata(&project_id, &claims.user_id)
+        .await
+        .map(|metadata| Json(metadata.into_iter().map(Into::into).collect()))
+        .map_err(to_error_response)
+}
+
+/// Set a service's display metadata
+pub async fn upsert_service_metadata<SMR, PR, MR>(
+    State(service): State<Arc<ServiceMetadataService<SMR, PR, MR>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path((project_id, service_name)): Path<(String, String)>,
+    Json(req): Json<UpsertServiceMetadataRequest>,
+) -> Result<Json<ServiceMetadataResponseDto>, (StatusCode, Json<ErrorResponse>)>
+where
+    SMR: ServiceMetadataRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+{
+    let cmd = UpsertServiceMetadataCommand {
+        project_id,
+        service_name,
+        display_name: req.display_name,
+        team: req.team,
+        links: req.links,
+        requesting_user_id: claims.user_id,
+    };
+
+    service
+        .upsert_metadata(cmd)
+        .await
+        .map(|r| Json(r.into()))
+        .map_err(to_error_response)
+}
+
+/// Remove a service's display metadata
+pub async fn delete_service_metadata<SMR, PR, MR>(
+    State(service): State<Arc<ServiceMetadataService<SMR, PR, MR>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path((project_id, service_name)): Path<(String, String)>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
+where
+    SMR: ServiceMetadataRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+{
+    service
+        .delete_metadata(&project_id, &service_name, &claims.user_id)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(to_error_response)
+}
+
+// ============================================================================
+// Public Read Token Handlers
+// ============================================================================
+
+/// Show the project's public read token (its prefix only)
+pub async fn get_public_token<TR, PR, MR>(
+    State(service): State<Arc<PublicReadTokenService<TR, PR, MR>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(project_id): Path<String>,
+) -> Result<Json<PublicReadTokenResponseDto>, (StatusCode, Json<ErrorResponse>)>
+where
+    TR: PublicReadTokenRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+{
+    service
+        .get_token(&project_id, &claims.user_id)
+        .await
+        .map(|r| Json(r.into()))
+        .map_err(to_error_response)
+}
+
+/// Create a public read token, replacing the project's current one
+pub async fn create_public_token<TR, PR, MR>(
+    State(service): State<Arc<PublicReadTokenService<TR, PR, MR>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(project_id): Path<String>,
+) -> Result<(StatusCode, Json<PublicReadTokenCreatedResponseDto>), (StatusCode, Json<ErrorResponse>)>
+where
+    TR: PublicReadTokenRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+{
+    service
+        .create_token(&project_id, &claims.user_id)
+        .await
+        .map(|r| (StatusCode::CREATED, Json(r.into())))
+        .map_err(to_error_response)
+}
+
+/// Revoke the project's public read token
+pub async fn revoke_public_token<TR, PR, MR>(
+    State(service): State<Arc<PublicReadTokenService<TR, PR, MR>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(project_id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
+where
+    TR: PublicReadTokenRepository,
+    PR: ProjectRepository,
+    MR: OrganizationMemberRepository,
+{
+    service
+        .revoke_token(&project_id, &claims.user_id)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(to_error_response)
+}
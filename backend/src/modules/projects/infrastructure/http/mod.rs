@@ -1,4 +1,4 @@
 pub mod handlers;
 pub mod routes;
 
-pub use routes::project_routes;
+pub use routes::{project_routes, public_token_routes, service_metadata_routes};
@@ -1,7 +1,6 @@
 use axum::{
-    middleware,
-    routing::{delete, get, patch, post},
-    Router,
+    Router, middleware,
+    routing::{delete, get, patch, post, put},
 };
 use std::sync::Arc;
 
@@ -9,8 +8,12 @@ use super::handlers;
 use crate::modules::auth::application::ports::{IdGenerator, TokenService};
 use crate::modules::auth::infrastructure::http::middleware::auth_middleware;
 use crate::modules::organizations::domain::{OrganizationMemberRepository, OrganizationRepository};
-use crate::modules::projects::application::services::ProjectService;
-use crate::modules::projects::domain::{ApiKeyRepository, ProjectRepository};
+use crate::modules::projects::application::services::{
+    ProjectService, PublicReadTokenService, ServiceMetadataService,
+};
+use crate::modules::projects::domain::{
+    ApiKeyRepository, ProjectRepository, PublicReadTokenRepository, ServiceMetadataRepository,
+};
 
 /// Create project routes (all protected)
 pub fn project_routes<PR, AR, OR, MR, TS, ID>(
@@ -48,6 +51,19 @@ where
             "/projects/{id}",
             delete(handlers::delete_project::<PR, AR, OR, MR, ID>),
         )
+        .route(
+            "/projects/{id}/feature-flags",
+            get(handlers::get_feature_flags::<PR, AR, OR, MR, ID>),
+        )
+        .route(
+            "/projects/{id}/feature-flags",
+            patch(handlers::update_feature_flags::<PR, AR, OR, MR, ID>),
+        )
+        .route(
+            "/projects/{id}/trace-sampling",
+            get(handlers::get_trace_sampling::<PR, AR, OR, MR, ID>)
+                .put(handlers::update_trace_sampling::<PR, AR, OR, MR, ID>),
+        )
         // API Keys
         .route(
             "/projects/{id}/api-keys",
@@ -68,3 +84,56 @@ where
         ))
         .with_state(project_service)
 }
+
+/// Create service metadata routes (all protected)
+pub fn service_metadata_routes<SMR, PR, MR, TS>(
+    service: Arc<ServiceMetadataService<SMR, PR, MR>>,
+    token_service: Arc<TS>,
+) -> Router
+where
+    SMR: ServiceMetadataRepository + 'static,
+    PR: ProjectRepository + 'static,
+    MR: OrganizationMemberRepository + 'static,
+    TS: TokenService + 'static,
+{
+    Router::new()
+        .route(
+            "/projects/{id}/services",
+            get(handlers::list_service_metadata::<SMR, PR, MR>),
+        )
+        .route(
+            "/projects/{id}/services/{service_name}",
+            put(handlers::upsert_service_metadata::<SMR, PR, MR>)
+                .delete(handlers::delete_service_metadata::<SMR, PR, MR>),
+        )
+        .layer(middleware::from_fn_with_state(
+            token_service,
+            auth_middleware::<TS>,
+        ))
+        .with_state(service)
+}
+
+/// Create public read token management routes (all protected)
+pub fn public_token_routes<TR, PR, MR, TS>(
+    service: Arc<PublicReadTokenService<TR, PR, MR>>,
+    token_service: Arc<TS>,
+) -> Router
+where
+    TR: PublicReadTokenRepository + 'static,
+    PR: ProjectRepository + 'static,
+    MR: OrganizationMemberRepository + 'static,
+    TS: TokenService + 'static,
+{
+    Router::new()
+        .route(
+            "/projects/{id}/public-token",
+            get(handlers::get_public_token::<TR, PR, MR>)
+                .post(handlers::create_public_token::<TR, PR, MR>)
+                .delete(handlers::revoke_public_token::<TR, PR, MR>),
+        )
+        .layer(middleware::from_fn_with_state(
+            token_service,
+            auth_middleware::<TS>,
+        ))
+        .with_state(service)
+}
@@ -1,5 +1,8 @@
 pub mod http;
 pub mod persistence;
 
-pub use http::project_routes;
-pub use persistence::{PostgresApiKeyRepository, PostgresProjectRepository};
+pub use http::{project_routes, public_token_routes, service_metadata_routes};
+pub use persistence::{
+    PostgresApiKeyRepository, PostgresProjectRepository, PostgresPublicReadTokenRepository,
+    PostgresServiceMetadataRepository,
+};
@@ -1,6 +1,10 @@
 pub mod models;
 pub mod postgres_api_key_repo;
 pub mod postgres_project_repo;
+pub mod postgres_public_token_repo;
+pub mod postgres_service_metadata_repo;
 
 pub use postgres_api_key_repo::PostgresApiKeyRepository;
 pub use postgres_project_repo::PostgresProjectRepository;
+pub use postgres_public_token_repo::PostgresPublicReadTokenRepository;
+pub use postgres_service_metadata_repo::PostgresServiceMetadataRepository;
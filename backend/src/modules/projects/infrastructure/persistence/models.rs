@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use serde_json::Value;
 use sqlx::FromRow;
 
 /// Database row for projects table
@@ -11,6 +12,7 @@ pub struct ProjectRow {
     pub retention_days: i32,
     pub metrics_retention_days: i32,
     pub traces_retention_days: i32,
+    pub settings: Value,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub deleted_at: Option<DateTime<Utc>>,
@@ -28,3 +30,24 @@ pub struct ApiKeyRow {
     pub expires_at: Option<DateTime<Utc>>,
     pub revoked_at: Option<DateTime<Utc>>,
 }
+
+/// Database row for service_metadata table
+#[derive(Debug, FromRow)]
+pub struct ServiceMetadataRow {
+    pub project_id: String,
+    pub service_name: String,
+    pub display_name: String,
+    pub team: Option<String>,
+    pub links: Value,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Database row for public_read_tokens table
+#[derive(Debug, FromRow)]
+pub struct PublicReadTokenRow {
+    pub project_id: String,
+    pub token_hash: String,
+    pub token_prefix: String,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
@@ -6,8 +6,9 @@ use super::models::ProjectRow;
 use crate::modules::organizations::domain::OrgId;
 use crate::modules::projects::domain::{
     MetricsRetentionDays, Project, ProjectDomainError, ProjectId, ProjectName, ProjectRepository,
-    RetentionDays, TracesRetentionDays,
+    ProjectSettings, RetentionDays, TracesRetentionDays,
 };
+use crate::shared::Pagination;
 
 pub struct PostgresProjectRepository {
     pool: Arc<PgPool>,
@@ -34,6 +35,7 @@ impl PostgresProjectRepository {
             retention_days,
             metrics_retention_days,
             traces_retention_days,
+            ProjectSettings::from_json(row.settings),
             row.created_at,
             row.updated_at,
             row.deleted_at,
@@ -47,7 +49,7 @@ impl ProjectRepository for PostgresProjectRepository {
         let row: Option<ProjectRow> = sqlx::query_as(
             r#"
             SELECT id, organization_id, name, description, retention_days,
-                   metrics_retention_days, traces_retention_days,
+                   metrics_retention_days, traces_retention_days, settings,
                    created_at, updated_at, deleted_at
             FROM projects
             WHERE id = $1
@@ -61,18 +63,25 @@ impl ProjectRepository for PostgresProjectRepository {
         row.map(Self::row_to_project).transpose()
     }
 
-    async fn find_by_org(&self, org_id: &OrgId) -> Result<Vec<Project>, ProjectDomainError> {
+    async fn find_page_by_org(
+        &self,
+        org_id: &OrgId,
+        pagination: &Pagination,
+    ) -> Result<Vec<Project>, ProjectDomainError> {
         let rows: Vec<ProjectRow> = sqlx::query_as(
             r#"
             SELECT id, organization_id, name, description, retention_days,
-                   metrics_retention_days, traces_retention_days,
+                   metrics_retention_days, traces_retention_days, settings,
                    created_at, updated_at, deleted_at
             FROM projects
             WHERE organization_id = $1 AND deleted_at IS NULL
-            ORDER BY created_at DESC
+            ORDER BY created_at DESC, id
+            LIMIT $2 OFFSET $3
             "#,
         )
         .bind(org_id.as_str())
+        .bind(pagination.limit)
+        .bind(pagination.offset)
         .fetch_all(self.pool.as_ref())
         .await
         .map_err(|e| ProjectDomainError::InternalError(e.to_string()))?;
@@ -84,15 +93,16 @@ impl ProjectRepository for PostgresProjectRepository {
         sqlx::query(
             r#"
             INSERT INTO projects (id, organization_id, name, description, retention_days,
-                                  metrics_retention_days, traces_retention_days,
+                                  metrics_retention_days, traces_retention_days, settings,
                                   created_at, updated_at, deleted_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             ON CONFLICT (id) DO UPDATE SET
                 name = EXCLUDED.name,
                 description = EXCLUDED.description,
                 retention_days = EXCLUDED.retention_days,
                 metrics_retention_days = EXCLUDED.metrics_retention_days,
                 traces_retention_days = EXCLUDED.traces_retention_days,
+                settings = EXCLUDED.settings,
                 updated_at = EXCLUDED.updated_at,
                 deleted_at = EXCLUDED.deleted_at
             "#,
@@ -104,6 +114,7 @@ impl ProjectRepository for PostgresProjectRepository {
         .bind(project.retention_days().value())
         .bind(project.metrics_retention_days().value())
         .bind(project.traces_retention_days().value())
+        .bind(project.settings().to_json())
         .bind(project.created_at())
         .bind(project.updated_at())
         .bind(project.deleted_at())
@@ -160,8 +171,8 @@ impl ProjectRepository for PostgresProjectRepository {
     async fn find_all_active(&self) -> Result<Vec<Project>, ProjectDomainError> {
         let rows: Vec<ProjectRow> = sqlx::query_as(
             r#"
-            SELECT id, organization_id, name, description, logs_retention_days,
-                   metrics_retention_days, traces_retention_days,
+            SELECT id, organization_id, name, description, retention_days,
+                   metrics_retention_days, traces_retention_days, settings,
                    created_at, updated_at, deleted_at
             FROM projects
             WHERE deleted_at IS NULL
@@ -171,8 +182,6 @@ impl ProjectRepository for PostgresProjectRepository {
         .await
         .map_err(|e| ProjectDomainError::InternalError(e.to_string()))?;
 
-        rows.into_iter()
-            .map(Self::row_to_project)
-            .collect()
+        rows.into_iter().map(Self::row_to_project).collect()
     }
 }
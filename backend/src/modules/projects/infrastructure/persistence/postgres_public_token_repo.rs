@@ -0,0 +1,103 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::models::PublicReadTokenRow;
+use crate::modules::projects::domain::{
+    ProjectDomainError, ProjectId, PublicReadToken, PublicReadTokenRepository,
+};
+
+pub struct PostgresPublicReadTokenRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresPublicReadTokenRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_token(row: PublicReadTokenRow) -> PublicReadToken {
+        PublicReadToken::from_db(
+            ProjectId::new(row.project_id),
+            row.token_hash,
+            row.token_prefix,
+            row.created_by,
+            row.created_at,
+        )
+    }
+}
+
+#[async_trait]
+impl PublicReadTokenRepository for PostgresPublicReadTokenRepository {
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<PublicReadToken>, ProjectDomainError> {
+        let row: Option<PublicReadTokenRow> = sqlx::query_as(
+            r#"
+            SELECT project_id, token_hash, token_prefix, created_by, created_at
+            FROM public_read_tokens
+            WHERE token_hash = $1
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| ProjectDomainError::InternalError(e.to_string()))?;
+
+        Ok(row.map(Self::row_to_token))
+    }
+
+    async fn find_by_project(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Option<PublicReadToken>, ProjectDomainError> {
+        let row: Option<PublicReadTokenRow> = sqlx::query_as(
+            r#"
+            SELECT project_id, token_hash, token_prefix, created_by, created_at
+            FROM public_read_tokens
+            WHERE project_id = $1
+            "#,
+        )
+        .bind(project_id.as_str())
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(|e| ProjectDomainError::InternalError(e.to_string()))?;
+
+        Ok(row.map(Self::row_to_token))
+    }
+
+    async fn save(&self, token: &PublicReadToken) -> Result<(), ProjectDomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO public_read_tokens (project_id, token_hash, token_prefix, created_by, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (project_id) DO UPDATE SET
+                token_hash = EXCLUDED.token_hash,
+                token_prefix = EXCLUDED.token_prefix,
+                created_by = EXCLUDED.created_by,
+                created_at = EXCLUDED.created_at
+            "#,
+        )
+        .bind(token.project_id().as_str())
+        .bind(token.token_hash())
+        .bind(token.token_prefix())
+        .bind(token.created_by())
+        .bind(token.created_at())
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| ProjectDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, project_id: &ProjectId) -> Result<bool, ProjectDomainError> {
+        let result = sqlx::query(r#"DELETE FROM public_read_tokens WHERE project_id = $1"#)
+            .bind(project_id.as_str())
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| ProjectDomainError::InternalError(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
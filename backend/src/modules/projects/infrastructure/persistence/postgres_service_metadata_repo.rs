@@ -0,0 +1,120 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::models::ServiceMetadataRow;
+use crate::modules::projects::domain::{
+    ProjectDomainError, ProjectId, ServiceMetadata, ServiceMetadataRepository,
+};
+
+pub struct PostgresServiceMetadataRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PostgresServiceMetadataRepository {
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool }
+    }
+
+    fn row_to_metadata(row: ServiceMetadataRow) -> Result<ServiceMetadata, ProjectDomainError> {
+        let links = serde_json::from_value(row.links)
+            .map_err(|e| ProjectDomainError::InternalError(e.to_string()))?;
+
+        Ok(ServiceMetadata::from_db(
+            ProjectId::new(row.project_id),
+            row.service_name,
+            row.display_name,
+            row.team,
+            links,
+            row.updated_at,
+        ))
+    }
+}
+
+#[async_trait]
+impl ServiceMetadataRepository for PostgresServiceMetadataRepository {
+    async fn find_by_project(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Vec<ServiceMetadata>, ProjectDomainError> {
+        let rows: Vec<ServiceMetadataRow> = sqlx::query_as(
+            r#"
+            SELECT project_id, service_name, display_name, team, links, updated_at
+            FROM service_metadata
+            WHERE project_id = $1
+            "#,
+        )
+        .bind(project_id.as_str())
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| ProjectDomainError::InternalError(e.to_string()))?;
+
+        rows.into_iter().map(Self::row_to_metadata).collect()
+    }
+
+    async fn find_by_services(
+        &self,
+        project_id: &ProjectId,
+        service_names: &[String],
+    ) -> Result<Vec<ServiceMetadata>, ProjectDomainError> {
+        let rows: Vec<ServiceMetadataRow> = sqlx::query_as(
+            r#"
+            SELECT project_id, service_name, display_name, team, links, updated_at
+            FROM service_metadata
+            WHERE project_id = $1 AND service_name = ANY($2)
+            "#,
+        )
+        .bind(project_id.as_str())
+        .bind(service_names)
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| ProjectDomainError::InternalError(e.to_string()))?;
+
+        rows.into_iter().map(Self::row_to_metadata).collect()
+    }
+
+    async fn save(&self, metadata: &ServiceMetadata) -> Result<(), ProjectDomainError> {
+        let links = serde_json::to_value(metadata.links())
+            .map_err(|e| ProjectDomainError::InternalError(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO service_metadata (project_id, service_name, display_name, team, links, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (project_id, service_name) DO UPDATE SET
+                display_name = EXCLUDED.display_name,
+                team = EXCLUDED.team,
+                links = EXCLUDED.links,
+                updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(metadata.project_id().as_str())
+        .bind(metadata.service_name())
+        .bind(metadata.display_name())
+        .bind(metadata.team())
+        .bind(links)
+        .bind(metadata.updated_at())
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| ProjectDomainError::InternalError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn delete(
+        &self,
+        project_id: &ProjectId,
+        service_name: &str,
+    ) -> Result<bool, ProjectDomainError> {
+        let result = sqlx::query(
+            r#"DELETE FROM service_metadata WHERE project_id = $1 AND service_name = $2"#,
+        )
+        .bind(project_id.as_str())
+        .bind(service_name)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| ProjectDomainError::InternalError(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,270 @@
+//! Decode Prometheus remote-write payloads into internal metric format
+
+use std::collections::HashMap;
+
+use chrono::DateTime;
+use prost::Message;
+
+use crate::modules::metrics::application::dto::MetricInput;
+use crate::modules::prometheus::types::{MetricMetadata, PromMetricType, WriteRequest};
+
+/// Suffixes Prometheus appends to cumulative series
+const COUNTER_SUFFIXES: [&str; 4] = ["_total", "_count", "_sum", "_bucket"];
+
+/// Decode a snappy-compressed protobuf `WriteRequest`.
+/// The snappy header states the decompressed size up front; payloads
+/// claiming more than `max_decompressed_bytes` are rejected before allocating.
+pub fn decode_write_request(
+    body: &[u8],
+    max_decompressed_bytes: usize,
+) -> Result<WriteRequest, String> {
+    let len =
+        snap::raw::decompress_len(body).map_err(|e| format!("Invalid snappy payload: {}", e))?;
+    if len > max_decompressed_bytes {
+        return Err(format!(
+            "Decompressed payload of {} bytes exceeds the {} byte limit",
+            len, max_decompressed_bytes
+        ));
+    }
+
+    let decompressed = snap::raw::Decoder::new()
+        .decompress_vec(body)
+        .map_err(|e| format!("Invalid snappy payload: {}", e))?;
+
+    WriteRequest::decode(decompressed.as_slice())
+        .map_err(|e| format!("Invalid remote-write protobuf: {}", e))
+}
+
+/// Convert every sample of every time series into a MetricInput.
+/// The `__name__` label becomes the metric name and the remaining labels become tags.
+pub fn convert_write_request(request: WriteRequest) -> Vec<MetricInput> {
+    let metadata: HashMap<&str, &MetricMetadata> = request
+        .metadata
+        .iter()
+        .map(|m| (m.metric_family_name.as_str(), m))
+        .collect();
+
+    let mut metrics = Vec::new();
+
+    for series in &request.timeseries {
+        let mut name = None;
+        let mut tags = HashMap::with_capacity(series.labels.len());
+        for label in &series.labels {
+            if label.name == "__name__" {
+                name = Some(label.value.clone());
+            } else {
+                tags.insert(label.name.clone(), label.value.clone());
+            }
+        }
+        // Series without a name can't be queried; drop them
+        let Some(name) = name else {
+            continue;
+        };
+
+        let family = family_metadata(&metadata, &name);
+        let metric_type =
+            infer_metric_type(&name, family.map(|m| PromMetricType::from_i32(m.r#type)));
+        let unit = family.map(|m| m.unit.clone()).filter(|u| !u.is_empty());
+        let description = family.map(|m| m.help.clone()).filter(|h| !h.is_empty());
+
+        for sample in &series.samples {
+            // NaN is used for staleness markers
+            if sample.value.is_nan() {
+                continue;
+            }
+
+            metrics.push(MetricInput {
+                name: name.clone(),
+                metric_type: metric_type.to_string(),
+                value: sample.value,
+                timestamp: DateTime::from_timestamp_millis(sample.timestamp),
+                unit: unit.clone(),
+                description: description.clone(),
+                tags: tags.clone(),
+                bucket_bounds: None,
+                bucket_counts: None,
+                histogram_sum: None,
+                histogram_count: None,
+                histogram_min: None,
+                histogram_max: None,
+                trace_id: None,
+                span_id: None,
+            });
+        }
+    }
+
+    metrics
+}
+
+/// Find metadata for a series, also trying the family name without a histogram/summary suffix
+fn family_metadata<'a>(
+    metadata: &HashMap<&str, &'a MetricMetadata>,
+    name: &str,
+) -> Option<&'a MetricMetadata> {
+    metadata.get(name).copied().or_else(|| {
+        ["_bucket", "_sum", "_count"]
+            .iter()
+            .find_map(|suffix| name.strip_suffix(suffix))
+            .and_then(|family| metadata.get(family).copied())
+    })
+}
+
+/// Map a series to "counter" or "gauge", from metadata when sent or naming conventions otherwise
+pub(crate) fn infer_metric_type(name: &str, family_type: Option<PromMetricType>) -> &'static str {
+    let cumulative_suffix = COUNTER_SUFFIXES.iter().any(|s| name.ends_with(s));
+    match family_type {
+        Some(PromMetricType::Counter) => "counter",
+        Some(PromMetricType::Histogram | PromMetricType::Summary) if cumulative_suffix => "counter",
+        Some(PromMetricType::Unknown) | None if cumulative_suffix => "counter",
+        _ => "gauge",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use chrono::Utc;
+
+    use crate::modules::metrics::application::MetricsService;
+    use crate::modules::metrics::application::dto::IngestMetricsCommand;
+    use crate::modules::metrics::domain::MetricType;
+    use crate::modules::prometheus::types::{Label, Sample, TimeSeries};
+    use crate::shared::testing::{
+        InMemoryMemberRepository, InMemoryMetricsRepository, InMemoryProjectRepository,
+        SequentialIdGenerator,
+    };
+
+    fn label(name: &str, value: &str) -> Label {
+        Label {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn payload(timestamp_ms: i64) -> Vec<u8> {
+        let request = WriteRequest {
+            timeseries: vec![
+                TimeSeries {
+                    labels: vec![
+                        label("__name__", "http_requests_total"),
+                        label("job", "api"),
+                        label("code", "200"),
+                    ],
+                    samples: vec![Sample {
+                        value: 42.0,
+                        timestamp: timestamp_ms,
+                    }],
+                },
+                TimeSeries {
+                    labels: vec![label("__name__", "process_resident_memory_bytes")],
+                    samples: vec![
+                        Sample {
+                            value: 1024.0,
+                            timestamp: timestamp_ms,
+                        },
+                        Sample {
+                            value: f64::NAN,
+                            timestamp: timestamp_ms,
+                        },
+                    ],
+                },
+                TimeSeries {
+                    labels: vec![label("job", "unnamed")],
+                    samples: vec![Sample {
+                        value: 1.0,
+                        timestamp: timestamp_ms,
+                    }],
+                },
+            ],
+            metadata: vec![MetricMetadata {
+                r#type: PromMetricType::Gauge as i32,
+                metric_family_name: "process_resident_memory_bytes".to_string(),
+                help: "Resident memory size in bytes.".to_string(),
+                unit: "bytes".to_string(),
+            }],
+        };
+
+        snap::raw::Encoder::new()
+            .compress_vec(&request.encode_to_vec())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_remote_write_payload_is_stored() {
+        let now = Utc::now();
+        let request = decode_write_request(&payload(now.timestamp_millis()), 1 << 20).unwrap();
+
+        let metrics_repo = Arc::new(InMemoryMetricsRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        let service = MetricsService::new(
+            metrics_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            chrono::Duration::hours(1),
+        );
+
+        let response = service
+            .ingest(IngestMetricsCommand {
+                project_id: "project-1".to_string(),
+                metrics: convert_write_request(request),
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.ingested, 2);
+
+        let stored = metrics_repo.saved();
+        let requests = stored
+            .iter()
+            .find(|m| m.name() == "http_requests_total")
+            .unwrap();
+        assert_eq!(requests.metric_type(), MetricType::Counter);
+        assert_eq!(requests.value(), 42.0);
+        assert_eq!(requests.tags()["job"], "api");
+        assert_eq!(requests.tags()["code"], "200");
+        assert!(!requests.tags().contains_key("__name__"));
+        assert_eq!(
+            requests.timestamp().timestamp_millis(),
+            now.timestamp_millis()
+        );
+
+        let memory = stored
+            .iter()
+            .find(|m| m.name() == "process_resident_memory_bytes")
+            .unwrap();
+        assert_eq!(memory.metric_type(), MetricType::Gauge);
+        assert_eq!(memory.unit(), Some("bytes"));
+    }
+
+    #[test]
+    fn test_rejects_uncompressed_payload() {
+        assert!(decode_write_request(b"not snappy", 1 << 20).is_err());
+    }
+
+    #[test]
+    fn test_rejects_payload_decompressing_past_limit() {
+        let body = payload(Utc::now().timestamp_millis());
+        let len = snap::raw::decompress_len(&body).unwrap();
+        assert!(decode_write_request(&body, len).is_ok());
+        assert!(decode_write_request(&body, len - 1).is_err());
+
+        // A few bytes whose header claims ~4 GB are refused without allocating
+        let bomb = [0xff, 0xff, 0xff, 0xff, 0x0f, 0x00];
+        let err = decode_write_request(&bomb, 1 << 20).unwrap_err();
+        assert!(err.contains("exceeds"));
+    }
+
+    #[test]
+    fn test_infer_metric_type_without_metadata() {
+        assert_eq!(infer_metric_type("http_requests_total", None), "counter");
+        assert_eq!(infer_metric_type("latency_seconds_bucket", None), "counter");
+        assert_eq!(infer_metric_type("temperature_celsius", None), "gauge");
+        assert_eq!(
+            infer_metric_type("rpc_duration_seconds", Some(PromMetricType::Summary)),
+            "gauge"
+        );
+    }
+}
@@ -0,0 +1,522 @@
+//! Parse the Prometheus / OpenMetrics text exposition format into internal metric format
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::modules::metrics::application::dto::MetricInput;
+use crate::modules::prometheus::conversion::infer_metric_type;
+use crate::modules::prometheus::types::PromMetricType;
+
+/// Text exposition flavour, which decides how sample timestamps are read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpositionFormat {
+    /// Prometheus text format 0.0.4: integer timestamps in milliseconds
+    Prometheus,
+    /// OpenMetrics 1.0: timestamps in (possibly fractional) seconds
+    OpenMetrics,
+}
+
+impl ExpositionFormat {
+    pub fn from_content_type(content_type: Option<&str>) -> Self {
+        match content_type {
+            Some(ct) if ct.trim_start().starts_with("application/openmetrics-text") => {
+                Self::OpenMetrics
+            }
+            _ => Self::Prometheus,
+        }
+    }
+
+    fn parse_timestamp(self, raw: &str) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Prometheus => raw
+                .parse::<i64>()
+                .ok()
+                .and_then(DateTime::from_timestamp_millis),
+            Self::OpenMetrics => raw
+                .parse::<f64>()
+                .ok()
+                .filter(|secs| secs.is_finite())
+                .and_then(|secs| DateTime::from_timestamp_millis((secs * 1000.0).round() as i64)),
+        }
+    }
+}
+
+/// HELP, TYPE and UNIT declared for a metric family
+#[derive(Default)]
+struct Family {
+    metric_type: Option<PromMetricType>,
+    help: Option<String>,
+    unit: Option<String>,
+}
+
+/// One sample line: `name{labels} value [timestamp]`
+struct Sample {
+    name: String,
+    labels: Vec<(String, String)>,
+    value: f64,
+    timestamp: Option<DateTime<Utc>>,
+}
+
+/// The `_bucket`, `_sum` and `_count` series of one histogram at one instant
+struct HistogramSeries {
+    family: String,
+    tags: HashMap<String, String>,
+    timestamp: Option<DateTime<Utc>>,
+    /// (upper bound, cumulative count)
+    buckets: Vec<(f64, f64)>,
+    sum: Option<f64>,
+    count: Option<f64>,
+}
+
+/// Parse an exposition into MetricInputs. Samples of counter, gauge and
+/// summary families map one to one; a histogram's `_bucket`, `_sum` and
+/// `_count` series are grouped by their labels (without `le`) and rebuilt
+/// into a single histogram metric per label set and timestamp.
+pub fn parse_exposition(text: &str, format: ExpositionFormat) -> Result<Vec<MetricInput>, String> {
+    let mut families: HashMap<String, Family> = HashMap::new();
+    let mut metrics = Vec::new();
+    let mut histograms: Vec<HistogramSeries> = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(comment) = line.strip_prefix('#') {
+            let mut parts = comment.trim_start().splitn(3, ' ');
+            let keyword = parts.next().unwrap_or_default();
+            if keyword == "EOF" {
+                break;
+            }
+            let (Some(name), rest) = (parts.next(), parts.next().unwrap_or_default().trim()) else {
+                continue;
+            };
+            let family = families.entry(name.to_string()).or_default();
+            match keyword {
+                "HELP" => family.help = Some(unescape(rest)),
+                "UNIT" => family.unit = Some(rest.to_string()),
+                "TYPE" => {
+                    family.metric_type = Some(parse_type(rest).ok_or_else(|| {
+                        format!("line {}: unknown metric type '{}'", line_no, rest)
+                    })?)
+                }
+                // Any other comment is ignored
+                _ => {}
+            }
+            continue;
+        }
+
+        let sample = parse_sample(line, format).map_err(|e| format!("line {}: {}", line_no, e))?;
+
+        let (family_name, family) = family_of(&families, &sample.name);
+        let family_type = family.and_then(|f| f.metric_type);
+        let unit = family
+            .and_then(|f| f.unit.clone())
+            .filter(|u| !u.is_empty());
+        let description = family
+            .and_then(|f| f.help.clone())
+            .filter(|h| !h.is_empty());
+
+        if family_type == Some(PromMetricType::Histogram) {
+            add_histogram_sample(&mut histograms, &family_name, sample)
+                .map_err(|e| format!("line {}: {}", line_no, e))?;
+            continue;
+        }
+
+        // NaN is used for staleness markers; _created holds a start time, not a value
+        if sample.value.is_nan()
+            || (sample.name != family_name && sample.name.ends_with("_created"))
+        {
+            continue;
+        }
+
+        metrics.push(MetricInput {
+            metric_type: infer_metric_type(&sample.name, family_type).to_string(),
+            value: sample.value,
+            timestamp: sample.timestamp,
+            unit,
+            description,
+            tags: sample.labels.into_iter().collect(),
+            name: sample.name,
+            bucket_bounds: None,
+            bucket_counts: None,
+            histogram_sum: None,
+            histogram_count: None,
+            histogram_min: None,
+            histogram_max: None,
+            trace_id: None,
+            span_id: None,
+        });
+    }
+
+    for series in histograms {
+        let family = families.get(&series.family);
+        let unit = family
+            .and_then(|f| f.unit.clone())
+            .filter(|u| !u.is_empty());
+        let description = family
+            .and_then(|f| f.help.clone())
+            .filter(|h| !h.is_empty());
+        metrics.push(build_histogram(series, unit, description)?);
+    }
+
+    Ok(metrics)
+}
+
+fn parse_type(raw: &str) -> Option<PromMetricType> {
+    Some(match raw {
+        "counter" => PromMetricType::Counter,
+        "gauge" => PromMetricType::Gauge,
+        "histogram" => PromMetricType::Histogram,
+        "gaugehistogram" => PromMetricType::GaugeHistogram,
+        "summary" => PromMetricType::Summary,
+        "info" => PromMetricType::Info,
+        "stateset" => PromMetricType::StateSet,
+        "unknown" | "untyped" => PromMetricType::Unknown,
+        _ => return None,
+    })
+}
+
+/// Find the family a sample belongs to, also trying the name without a series suffix
+fn family_of<'a>(
+    families: &'a HashMap<String, Family>,
+    name: &str,
+) -> (String, Option<&'a Family>) {
+    if let Some(family) = families.get(name) {
+        return (name.to_string(), Some(family));
+    }
+    ["_bucket", "_sum", "_count", "_total", "_created", "_info"]
+        .iter()
+        .filter_map(|suffix| name.strip_suffix(suffix))
+        .find_map(|base| families.get_key_value(base))
+        .map(|(base, family)| (base.clone(), Some(family)))
+        .unwrap_or_else(|| (name.to_string(), None))
+}
+
+fn add_histogram_sample(
+    histograms: &mut Vec<HistogramSeries>,
+    family: &str,
+    sample: Sample,
+) -> Result<(), String> {
+    let suffix = &sample.name[family.len()..];
+    let mut le = None;
+    let mut tags = HashMap::with_capacity(sample.labels.len());
+    for (name, value) in sample.labels {
+        if name == "le" {
+            le = Some(value);
+        } else {
+            tags.insert(name, value);
+        }
+    }
+
+    let index = match histograms
+        .iter()
+        .position(|h| h.family == family && h.tags == tags && h.timestamp == sample.timestamp)
+    {
+        Some(index) => index,
+        None => {
+            histograms.push(HistogramSeries {
+                family: family.to_string(),
+                tags,
+                timestamp: sample.timestamp,
+                buckets: Vec::new(),
+                sum: None,
+                count: None,
+            });
+            histograms.len() - 1
+        }
+    };
+    let series = &mut histograms[index];
+
+    match suffix {
+        "_bucket" => {
+            let le = le.ok_or_else(|| format!("{} is missing the 'le' label", sample.name))?;
+            let bound = le
+                .parse::<f64>()
+                .ok()
+                .filter(|b| !b.is_nan())
+                .ok_or_else(|| format!("invalid bucket bound le=\"{}\"", le))?;
+            series.buckets.push((bound, sample.value));
+        }
+        "_sum" => series.sum = Some(sample.value),
+        "_count" => series.count = Some(sample.value),
+        // _created and other series carry nothing the histogram needs
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Turn cumulative `le` buckets into per-bucket counts with the +Inf bucket last
+fn build_histogram(
+    mut series: HistogramSeries,
+    unit: Option<String>,
+    description: Option<String>,
+) -> Result<MetricInput, String> {
+    series
+        .buckets
+        .sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut bounds = Vec::with_capacity(series.buckets.len());
+    let mut counts = Vec::with_capacity(series.buckets.len() + 1);
+    let mut previous = 0.0;
+    let mut total = None;
+    for (bound, cumulative) in &series.buckets {
+        if *cumulative < previous {
+            return Err(format!(
+                "{}: bucket counts must not decrease as 'le' grows",
+                series.family
+            ));
+        }
+        counts.push((cumulative - previous).round() as i64);
+        previous = *cumulative;
+        if bound.is_infinite() {
+            total = Some(*cumulative);
+            break;
+        }
+        bounds.push(*bound);
+    }
+
+    // An exposition without a +Inf bucket still gives the total in _count
+    let count = series.count.or(total);
+    if let (None, Some(count)) = (total, count) {
+        counts.push((count - previous).max(0.0).round() as i64);
+    }
+
+    Ok(MetricInput {
+        name: series.family,
+        metric_type: "histogram".to_string(),
+        value: series.sum.unwrap_or(0.0),
+        timestamp: series.timestamp,
+        unit,
+        description,
+        tags: series.tags,
+        bucket_bounds: Some(bounds),
+        bucket_counts: Some(counts),
+        histogram_sum: series.sum,
+        histogram_count: count.map(|c| c.round() as i64),
+        histogram_min: None,
+        histogram_max: None,
+        trace_id: None,
+        span_id: None,
+    })
+}
+
+fn parse_sample(line: &str, format: ExpositionFormat) -> Result<Sample, String> {
+    let name_end = line
+        .find(|c: char| c == '{' || c.is_whitespace())
+        .ok_or_else(|| "sample has no value".to_string())?;
+    let name = &line[..name_end];
+    if name.is_empty() {
+        return Err("sample has no metric name".to_string());
+    }
+
+    let mut rest = &line[name_end..];
+    let mut labels = Vec::new();
+    if let Some(after_brace) = rest.strip_prefix('{') {
+        rest = parse_labels(after_brace, &mut labels)?;
+    }
+
+    let mut fields = rest.split_whitespace();
+    let raw_value = fields
+        .next()
+        .ok_or_else(|| "sample has no value".to_string())?;
+    let value = raw_value
+        .parse::<f64>()
+        .map_err(|_| format!("invalid sample value '{}'", raw_value))?;
+    // Anything after the timestamp is an OpenMetrics exemplar, which is not stored
+    let timestamp = match fields.next() {
+        Some("#") | None => None,
+        Some(raw) => Some(
+            format
+                .parse_timestamp(raw)
+                .ok_or_else(|| format!("invalid timestamp '{}'", raw))?,
+        ),
+    };
+
+    Ok(Sample {
+        name: name.to_string(),
+        labels,
+        value,
+        timestamp,
+    })
+}
+
+/// Parse `name="value",...}` and return what follows the closing brace
+fn parse_labels<'a>(
+    mut rest: &'a str,
+    labels: &mut Vec<(String, String)>,
+) -> Result<&'a str, String> {
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix('}') {
+            return Ok(after);
+        }
+
+        let eq = rest
+            .find('=')
+            .ok_or_else(|| "unterminated label set".to_string())?;
+        let name = rest[..eq].trim();
+        if name.is_empty() {
+            return Err("label has no name".to_string());
+        }
+        rest = rest[eq + 1..]
+            .trim_start()
+            .strip_prefix('"')
+            .ok_or_else(|| format!("value of label '{}' is not quoted", name))?;
+
+        let mut value = String::new();
+        let mut chars = rest.char_indices();
+        let end = loop {
+            match chars.next() {
+                Some((i, '"')) => break i,
+                Some((_, '\\')) => match chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, c)) => value.push(c),
+                    None => return Err(format!("unterminated value of label '{}'", name)),
+                },
+                Some((_, c)) => value.push(c),
+                None => return Err(format!("unterminated value of label '{}'", name)),
+            }
+        };
+        labels.push((name.to_string(), value));
+
+        rest = rest[end + 1..].trim_start();
+        rest = rest.strip_prefix(',').unwrap_or(rest);
+    }
+}
+
+/// Undo the `\\` and `\n` escapes allowed in HELP text
+fn unescape(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                out.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                out.push('\\');
+                chars.next();
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::modules::metrics::application::MetricsService;
+    use crate::modules::metrics::application::dto::IngestMetricsCommand;
+    use crate::modules::metrics::domain::MetricType;
+    use crate::shared::testing::{
+        InMemoryMemberRepository, InMemoryMetricsRepository, InMemoryProjectRepository,
+        SequentialIdGenerator,
+    };
+
+    fn exposition(timestamp: &str) -> String {
+        format!(
+            r#"# HELP http_requests Total HTTP requests.
+# TYPE http_requests counter
+http_requests_total{{method="get",path="/api/\"v1\""}} 1027 {ts}
+http_requests_created{{method="get",path="/api/\"v1\""}} 1700000000 {ts}
+# HELP request_duration_seconds Request latency.
+# TYPE request_duration_seconds histogram
+# UNIT request_duration_seconds seconds
+request_duration_seconds_bucket{{le="0.1",route="/"}} 3 {ts}
+request_duration_seconds_bucket{{le="0.5",route="/"}} 7 {ts}
+request_duration_seconds_bucket{{le="1",route="/"}} 9 {ts}
+request_duration_seconds_bucket{{le="+Inf",route="/"}} 10 {ts}
+request_duration_seconds_sum{{route="/"}} 4.2 {ts}
+request_duration_seconds_count{{route="/"}} 10 {ts}
+# EOF
+"#,
+            ts = timestamp
+        )
+    }
+
+    #[tokio::test]
+    async fn test_counter_and_histogram_exposition_is_stored() {
+        let now = Utc::now();
+        let seconds = format!("{}.250", now.timestamp());
+        let metrics =
+            parse_exposition(&exposition(&seconds), ExpositionFormat::OpenMetrics).unwrap();
+
+        let metrics_repo = Arc::new(InMemoryMetricsRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        let service = MetricsService::new(
+            metrics_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            chrono::Duration::hours(1),
+        );
+
+        let response = service
+            .ingest(IngestMetricsCommand {
+                project_id: "project-1".to_string(),
+                metrics,
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.ingested, 2);
+
+        let stored = metrics_repo.saved();
+        let requests = stored
+            .iter()
+            .find(|m| m.name() == "http_requests_total")
+            .unwrap();
+        assert_eq!(requests.metric_type(), MetricType::Counter);
+        assert_eq!(requests.value(), 1027.0);
+        assert_eq!(requests.tags()["path"], "/api/\"v1\"");
+        assert_eq!(requests.description(), Some("Total HTTP requests."));
+        assert_eq!(
+            requests.timestamp().timestamp_millis(),
+            now.timestamp() * 1000 + 250
+        );
+
+        let latency = stored
+            .iter()
+            .find(|m| m.name() == "request_duration_seconds")
+            .unwrap();
+        assert_eq!(latency.metric_type(), MetricType::Histogram);
+        assert_eq!(latency.unit(), Some("seconds"));
+        assert_eq!(latency.tags().len(), 1);
+        assert_eq!(latency.tags()["route"], "/");
+        let histogram = latency.histogram_data().unwrap();
+        assert_eq!(histogram.bucket_bounds(), &[0.1, 0.5, 1.0]);
+        assert_eq!(histogram.bucket_counts(), &[3, 4, 2, 1]);
+        assert_eq!(histogram.sum(), 4.2);
+        assert_eq!(histogram.count(), 10);
+    }
+
+    #[test]
+    fn test_prometheus_timestamps_are_milliseconds() {
+        let metrics = parse_exposition(
+            "# TYPE temperature gauge\ntemperature{room=\"a\"} 21.5 1700000000123\n",
+            ExpositionFormat::Prometheus,
+        )
+        .unwrap();
+
+        assert_eq!(metrics[0].metric_type, "gauge");
+        assert_eq!(
+            metrics[0].timestamp.unwrap().timestamp_millis(),
+            1_700_000_000_123
+        );
+    }
+
+    #[test]
+    fn test_malformed_lines_are_rejected() {
+        assert!(parse_exposition("up{job=\"api} 1\n", ExpositionFormat::Prometheus).is_err());
+        assert!(parse_exposition("up one\n", ExpositionFormat::Prometheus).is_err());
+        assert!(parse_exposition("# TYPE up bogus\n", ExpositionFormat::Prometheus).is_err());
+    }
+}
@@ -0,0 +1,143 @@
+//! Prometheus remote-write and text exposition HTTP handlers
+
+use axum::{
+    Extension, Json,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode, header},
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::logging::infrastructure::ApiKeyContext;
+use crate::modules::metrics::application::MetricsService;
+use crate::modules::metrics::application::dto::IngestMetricsCommand;
+use crate::modules::metrics::domain::{MetricsDomainError, MetricsRepository};
+use crate::modules::organizations::domain::OrganizationMemberRepository;
+use crate::modules::projects::domain::ProjectRepository;
+use crate::modules::prometheus::conversion::{convert_write_request, decode_write_request};
+use crate::modules::prometheus::exposition::{ExpositionFormat, parse_exposition};
+
+/// Largest remote-write payload accepted once decompressed
+#[derive(Debug, Clone, Copy)]
+pub struct RemoteWriteLimits {
+    pub max_decompressed_bytes: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub code: String,
+}
+
+/// POST /api/v1/ingest/prometheus/write - Prometheus remote-write (snappy + protobuf)
+///
+/// Prometheus retries 5xx responses and drops the batch on 4xx, so malformed
+/// payloads must map to 4xx.
+pub async fn remote_write<MR, PR, OMR, ID>(
+    State(service): State<Arc<MetricsService<MR, PR, OMR, ID>>>,
+    Extension(ctx): Extension<ApiKeyContext>,
+    Extension(limits): Extension<RemoteWriteLimits>,
+    body: Bytes,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
+where
+    MR: MetricsRepository,
+    PR: ProjectRepository,
+    OMR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let request = decode_write_request(&body, limits.max_decompressed_bytes).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: e,
+                code: "INVALID_PAYLOAD".to_string(),
+            }),
+        )
+    })?;
+
+    let metrics = convert_write_request(request);
+    if metrics.is_empty() {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    let cmd = IngestMetricsCommand {
+        project_id: ctx.project_id.as_str().to_string(),
+        metrics,
+    };
+
+    service.ingest(cmd).await.map_err(to_ingestion_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/v1/ingest/prometheus/exposition - Prometheus or OpenMetrics text format
+///
+/// A `Content-Type` of `application/openmetrics-text` reads sample timestamps
+/// as seconds; anything else is treated as the Prometheus text format (milliseconds).
+pub async fn exposition<MR, PR, OMR, ID>(
+    State(service): State<Arc<MetricsService<MR, PR, OMR, ID>>>,
+    Extension(ctx): Extension<ApiKeyContext>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
+where
+    MR: MetricsRepository,
+    PR: ProjectRepository,
+    OMR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let format = ExpositionFormat::from_content_type(
+        headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok()),
+    );
+    let metrics = std::str::from_utf8(&body)
+        .map_err(|_| "Exposition is not valid UTF-8".to_string())
+        .and_then(|text| parse_exposition(text, format))
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: e,
+                    code: "INVALID_PAYLOAD".to_string(),
+                }),
+            )
+        })?;
+    if metrics.is_empty() {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    let cmd = IngestMetricsCommand {
+        project_id: ctx.project_id.as_str().to_string(),
+        metrics,
+    };
+
+    service.ingest(cmd).await.map_err(to_ingestion_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn to_ingestion_error(e: MetricsDomainError) -> (StatusCode, Json<ErrorResponse>) {
+    let (status, msg) = match e {
+        MetricsDomainError::ProjectNotFound | MetricsDomainError::ProjectDeleted => {
+            (StatusCode::NOT_FOUND, "Project not found".to_string())
+        }
+        MetricsDomainError::InternalError(ref msg) => {
+            tracing::error!(error = %msg, "Internal error during Prometheus ingest");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Internal error".to_string(),
+            )
+        }
+        other => (StatusCode::BAD_REQUEST, other.to_string()),
+    };
+    (
+        status,
+        Json(ErrorResponse {
+            error: msg,
+            code: "INGESTION_ERROR".to_string(),
+        }),
+    )
+}
@@ -0,0 +1,47 @@
+//! Prometheus remote-write and text exposition HTTP routes
+
+use axum::{Extension, Router, middleware, routing::post};
+use std::sync::Arc;
+
+use super::handlers::{self, RemoteWriteLimits};
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::logging::infrastructure::http::middleware::api_key_middleware;
+use crate::modules::metrics::application::MetricsService;
+use crate::modules::metrics::domain::MetricsRepository;
+use crate::modules::organizations::domain::{OrganizationMemberRepository, OrganizationRepository};
+use crate::modules::projects::application::ProjectService;
+use crate::modules::projects::domain::{ApiKeyRepository, ProjectRepository};
+
+/// Prometheus remote-write and text exposition routes (requires API key middleware)
+pub fn prometheus_routes<MR, PR, OMR, ID, PPR, AR, OR>(
+    service: Arc<MetricsService<MR, PR, OMR, ID>>,
+    project_service: Arc<ProjectService<PPR, AR, OR, OMR, ID>>,
+    max_decompressed_bytes: usize,
+) -> Router
+where
+    MR: MetricsRepository + 'static,
+    PR: ProjectRepository + 'static,
+    OMR: OrganizationMemberRepository + 'static,
+    ID: IdGenerator + 'static,
+    PPR: ProjectRepository + 'static,
+    AR: ApiKeyRepository + 'static,
+    OR: OrganizationRepository + 'static,
+{
+    Router::new()
+        .route(
+            "/prometheus/write",
+            post(handlers::remote_write::<MR, PR, OMR, ID>),
+        )
+        .route(
+            "/prometheus/exposition",
+            post(handlers::exposition::<MR, PR, OMR, ID>),
+        )
+        .layer(middleware::from_fn_with_state(
+            project_service,
+            api_key_middleware::<PPR, AR, OR, OMR, ID>,
+        ))
+        .layer(Extension(RemoteWriteLimits {
+            max_decompressed_bytes,
+        }))
+        .with_state(service)
+}
@@ -0,0 +1,6 @@
+pub mod conversion;
+pub mod exposition;
+pub mod http;
+pub mod types;
+
+pub use http::prometheus_routes;
@@ -1,22 +1,172 @@
-//! Retention cleanup module for metrics and traces
+//! Retention cleanup module for logs, metrics, traces, organization activity
+//! and alert history
 //!
 //! This module provides background tasks to clean up old data based on
-//! per-project retention settings.
+//! per-project and per-organization retention settings. Logs, metrics and
+//! traces are deleted in bounded batches so cleanup doesn't hold long
+//! transactions or lock chunks that ingest writes to.
 
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 
-use crate::modules::metrics::domain::MetricsRepository;
-use crate::modules::projects::domain::ProjectRepository;
-use crate::modules::traces::domain::SpansRepository;
+use crate::modules::alerts::domain::{AlertDomainError, AlertRepository};
+use crate::modules::logging::domain::{LogCutoffs, LogDomainError, LogRepository, TaggedLogCutoff};
+use crate::modules::metrics::domain::{MetricsDomainError, MetricsRepository};
+use crate::modules::organizations::domain::{
+    ActivityType, OrgActivityRepository, OrgDomainError, Organization, OrganizationRepository,
+};
+use crate::modules::projects::domain::{Project, ProjectRepository, TraceRetentionOverride};
+use crate::modules::traces::domain::{
+    SpansRepository, TraceCutoff, TraceCutoffs, TracesDomainError,
+};
+
+/// How retention deletes are split into batches
+#[derive(Debug, Clone, Copy)]
+pub struct DeleteBatching {
+    /// Most rows (whole traces, for trace retention) one delete removes
+    pub batch_size: u32,
+    /// Pause between batches
+    pub pause: Duration,
+}
+
+/// Call `delete` with the batch size until a batch deletes fewer than that,
+/// pausing between batches, and return the total deleted
+async fn delete_in_batches<E, F, Fut>(batching: DeleteBatching, mut delete: F) -> Result<u64, E>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<u64, E>>,
+{
+    let batch_size = batching.batch_size.max(1);
+    let mut total = 0;
+    loop {
+        let deleted = delete(batch_size).await?;
+        total += deleted;
+        if deleted < batch_size as u64 {
+            return Ok(total);
+        }
+        tokio::time::sleep(batching.pause).await;
+    }
+}
+
+/// Start the logs retention cleanup background task.
+/// Runs every hour and deletes logs older than the project's retention period.
+pub async fn start_logs_cleanup<LR, PR>(
+    log_repo: Arc<LR>,
+    project_repo: Arc<PR>,
+    batching: DeleteBatching,
+    interval_secs: u64,
+) where
+    LR: LogRepository + 'static,
+    PR: ProjectRepository + 'static,
+{
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+    loop {
+        interval.tick().await;
+
+        // Get all active projects
+        let projects = match project_repo.find_all_active().await {
+            Ok(p) => p,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to fetch projects for logs cleanup");
+                continue;
+            }
+        };
+
+        for project in projects {
+            let retention_days = project.retention_days().value();
+
+            match cleanup_logs(log_repo.as_ref(), &project, Utc::now(), batching).await {
+                Ok(deleted) if deleted > 0 => {
+                    tracing::info!(
+                        project_id = %project.id().as_str(),
+                        deleted_count = deleted,
+                        retention_days = retention_days,
+                        "Cleaned up old logs"
+                    );
+                }
+                Err(e) => {
+                    tracing::error!(
+                        error = %e,
+                        project_id = %project.id().as_str(),
+                        "Failed to cleanup logs"
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Delete the project's expired logs as of `now`
+async fn cleanup_logs<LR: LogRepository>(
+    log_repo: &LR,
+    project: &Project,
+    now: DateTime<Utc>,
+    batching: DeleteBatching,
+) -> Result<u64, LogDomainError> {
+    let retention_days = project.retention_days().value();
+    let cutoff = now - chrono::Duration::days(retention_days as i64);
+    let rules = &project.settings().log_retention.rules;
+    if rules.is_empty() {
+        return delete_in_batches(batching, |limit| {
+            log_repo.delete_before(project.id(), cutoff, limit)
+        })
+        .await;
+    }
+
+    let cutoffs = LogCutoffs {
+        default: cutoff,
+        tagged: rules
+            .iter()
+            .map(|rule| TaggedLogCutoff {
+                tags: rule.tags.clone(),
+                cutoff: now - chrono::Duration::days(rule.days as i64),
+            })
+            .collect(),
+    };
+    delete_in_batches(batching, |limit| {
+        log_repo.delete_expired(project.id(), &cutoffs, limit)
+    })
+    .await
+}
+
+/// Start the ingest dedup key prune background task.
+/// Runs every `interval_secs` and forgets dedup keys whose window has passed.
+pub async fn start_dedup_key_prune<LR>(
+    log_repo: Arc<LR>,
+    batching: DeleteBatching,
+    interval_secs: u64,
+) where
+    LR: LogRepository + 'static,
+{
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+    loop {
+        interval.tick().await;
+
+        let now = Utc::now();
+        match delete_in_batches(batching, |limit| log_repo.prune_dedup_keys(now, limit)).await {
+            Ok(pruned) if pruned > 0 => {
+                tracing::info!(pruned_count = pruned, "Pruned expired ingest dedup keys");
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to prune ingest dedup keys");
+            }
+            _ => {}
+        }
+    }
+}
 
 /// Start the metrics retention cleanup background task.
 /// Runs every hour and deletes metrics older than the project's retention period.
 pub async fn start_metrics_cleanup<MR, PR>(
     metrics_repo: Arc<MR>,
     project_repo: Arc<PR>,
+    batching: DeleteBatching,
     interval_secs: u64,
 ) where
     MR: MetricsRepository + 'static,
@@ -38,9 +188,8 @@ pub async fn start_metrics_cleanup<MR, PR>(
 
         for project in projects {
             let retention_days = project.metrics_retention_days().value();
-            let cutoff = Utc::now() - chrono::Duration::days(retention_days as i64);
 
-            match metrics_repo.delete_before(project.id(), cutoff).await {
+            match cleanup_metrics(metrics_repo.as_ref(), &project, Utc::now(), batching).await {
                 Ok(deleted) if deleted > 0 => {
                     tracing::info!(
                         project_id = %project.id().as_str(),
@@ -62,11 +211,29 @@ pub async fn start_metrics_cleanup<MR, PR>(
     }
 }
 
+/// Delete the project's expired metrics as of `now`
+async fn cleanup_metrics<MR: MetricsRepository>(
+    metrics_repo: &MR,
+    project: &Project,
+    now: DateTime<Utc>,
+    batching: DeleteBatching,
+) -> Result<u64, MetricsDomainError> {
+    let retention_days = project.metrics_retention_days().value();
+    let cutoff = now - chrono::Duration::days(retention_days as i64);
+    delete_in_batches(batching, |limit| {
+        metrics_repo.delete_before(project.id(), cutoff, limit)
+    })
+    .await
+}
+
 /// Start the traces retention cleanup background task.
-/// Runs every hour and deletes spans older than the project's retention period.
+/// Runs every hour and deletes spans older than the project's retention period,
+/// or whole traces past their cutoff when the project sets trace retention by
+/// error status or service.
 pub async fn start_traces_cleanup<SR, PR>(
     spans_repo: Arc<SR>,
     project_repo: Arc<PR>,
+    batching: DeleteBatching,
     interval_secs: u64,
 ) where
     SR: SpansRepository + 'static,
@@ -88,9 +255,8 @@ pub async fn start_traces_cleanup<SR, PR>(
 
         for project in projects {
             let retention_days = project.traces_retention_days().value();
-            let cutoff = Utc::now() - chrono::Duration::days(retention_days as i64);
 
-            match spans_repo.delete_before(project.id(), cutoff).await {
+            match cleanup_traces(spans_repo.as_ref(), &project, Utc::now(), batching).await {
                 Ok(deleted) if deleted > 0 => {
                     tracing::info!(
                         project_id = %project.id().as_str(),
@@ -111,3 +277,535 @@ pub async fn start_traces_cleanup<SR, PR>(
         }
     }
 }
+
+/// Delete the project's expired spans as of `now`
+async fn cleanup_traces<SR: SpansRepository>(
+    spans_repo: &SR,
+    project: &Project,
+    now: DateTime<Utc>,
+    batching: DeleteBatching,
+) -> Result<u64, TracesDomainError> {
+    match trace_cutoffs(project, now) {
+        Some(cutoffs) => {
+            delete_in_batches(batching, |limit| {
+                spans_repo.delete_expired_traces(project.id(), &cutoffs, limit)
+            })
+            .await
+        }
+        None => {
+            let retention_days = project.traces_retention_days().value();
+            let cutoff = now - chrono::Duration::days(retention_days as i64);
+            delete_in_batches(batching, |limit| {
+                spans_repo.delete_before(project.id(), cutoff, limit)
+            })
+            .await
+        }
+    }
+}
+
+/// Per-trace cutoffs from the project's trace retention settings, or `None`
+/// when it only has the flat traces retention. Unset values fall back to the
+/// project-wide override, then to the traces retention days.
+fn trace_cutoffs(project: &Project, now: DateTime<Utc>) -> Option<TraceCutoffs> {
+    let settings = &project.settings().trace_retention;
+    if settings.is_empty() {
+        return None;
+    }
+
+    let fallback = project.traces_retention_days().value();
+    let cutoff = |days: Option<i32>| now - chrono::Duration::days(days.unwrap_or(fallback) as i64);
+    let resolve = |o: &TraceRetentionOverride| TraceCutoff {
+        error: cutoff(o.error_days.or(settings.default.error_days)),
+        clean: cutoff(o.clean_days.or(settings.default.clean_days)),
+    };
+
+    Some(TraceCutoffs {
+        default: resolve(&settings.default),
+        services: settings
+            .services
+            .iter()
+            .map(|(service, o)| (service.clone(), resolve(o)))
+            .collect(),
+    })
+}
+
+/// Server-wide organization activity retention
+#[derive(Debug, Clone, Copy)]
+pub struct ActivityRetention {
+    /// Days activity is kept when the organization sets no override
+    pub default_days: u32,
+    /// Days security-relevant activity is kept; 0 keeps it forever
+    pub security_days: u32,
+}
+
+/// Start the organization activity retention cleanup background task.
+/// Runs every hour and deletes activity older than the organization's
+/// retention period, keeping security-relevant activity for longer.
+pub async fn start_org_activity_cleanup<AR, OR>(
+    activity_repo: Arc<AR>,
+    org_repo: Arc<OR>,
+    retention: ActivityRetention,
+    interval_secs: u64,
+) where
+    AR: OrgActivityRepository + 'static,
+    OR: OrganizationRepository + 'static,
+{
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+    loop {
+        interval.tick().await;
+
+        let orgs = match org_repo.find_all_active().await {
+            Ok(o) => o,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to fetch organizations for activity cleanup");
+                continue;
+            }
+        };
+
+        for org in orgs {
+            match prune_org_activity(activity_repo.as_ref(), &org, retention, Utc::now()).await {
+                Ok(deleted) if deleted > 0 => {
+                    tracing::info!(
+                        org_id = %org.id().as_str(),
+                        deleted_count = deleted,
+                        "Cleaned up old organization activity"
+                    );
+                }
+                Err(e) => {
+                    tracing::error!(
+                        error = %e,
+                        org_id = %org.id().as_str(),
+                        "Failed to cleanup organization activity"
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Delete the organization's expired activity as of `now`. Security-relevant
+/// activity is never pruned sooner than the organization's own window.
+async fn prune_org_activity<AR: OrgActivityRepository>(
+    activity_repo: &AR,
+    org: &Organization,
+    retention: ActivityRetention,
+    now: DateTime<Utc>,
+) -> Result<u64, OrgDomainError> {
+    let days = org
+        .activity_retention_days()
+        .unwrap_or(retention.default_days);
+    let cutoff = now - chrono::Duration::days(days as i64);
+    let mut deleted = activity_repo
+        .delete_before(org.id(), cutoff, &ActivityType::SECURITY_RELEVANT)
+        .await?;
+
+    if retention.security_days > 0 {
+        let security_days = retention.security_days.max(days);
+        let cutoff = now - chrono::Duration::days(security_days as i64);
+        deleted += activity_repo.delete_before(org.id(), cutoff, &[]).await?;
+    }
+
+    Ok(deleted)
+}
+
+/// Start the alert history retention cleanup background task.
+/// Runs every `interval_secs` and deletes alerts resolved more than
+/// `retention_days` ago; firing alerts are kept however old they are.
+pub async fn start_alert_history_cleanup<AR>(
+    alert_repo: Arc<AR>,
+    retention_days: u32,
+    batching: DeleteBatching,
+    interval_secs: u64,
+) where
+    AR: AlertRepository + 'static,
+{
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+    loop {
+        interval.tick().await;
+
+        match prune_alert_history(alert_repo.as_ref(), retention_days, Utc::now(), batching).await {
+            Ok(deleted) if deleted > 0 => {
+                tracing::info!(deleted_count = deleted, "Cleaned up old alert history");
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to cleanup alert history");
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Delete alerts resolved more than `retention_days` before `now`
+async fn prune_alert_history<AR: AlertRepository>(
+    alert_repo: &AR,
+    retention_days: u32,
+    now: DateTime<Utc>,
+    batching: DeleteBatching,
+) -> Result<u64, AlertDomainError> {
+    let cutoff = now - chrono::Duration::days(retention_days as i64);
+    delete_in_batches(batching, |limit| {
+        alert_repo.delete_resolved_before(cutoff, limit)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use crate::modules::alerts::domain::{Alert, AlertId, AlertRuleId, AlertStatus};
+    use crate::modules::auth::domain::UserId;
+    use crate::modules::logging::domain::{LogEntry, LogId, LogLevel};
+    use crate::modules::metrics::domain::{MetricPoint, MetricType};
+    use crate::modules::organizations::domain::{ActivityId, OrgActivity, OrgId, OrgName, OrgSlug};
+    use crate::modules::projects::domain::ProjectId;
+    use crate::modules::traces::domain::{Span, SpanKind, SpanStatusCode};
+    use crate::shared::testing::{
+        InMemoryActivityRepository, InMemoryAlertRepository, InMemoryLogRepository,
+        InMemoryMetricsRepository, InMemoryProjectRepository, InMemorySpansRepository,
+    };
+
+    const BATCHES_OF_TEN: DeleteBatching = DeleteBatching {
+        batch_size: 10,
+        pause: Duration::ZERO,
+    };
+
+    fn span(trace_id: &str, service: &str, status: SpanStatusCode, start: DateTime<Utc>) -> Span {
+        Span::new(
+            format!("{}-root", trace_id),
+            ProjectId::new("project-1".to_string()),
+            trace_id.to_string(),
+            "root".to_string(),
+            None,
+            "GET /".to_string(),
+            SpanKind::Server,
+            start,
+            Some(start + chrono::Duration::milliseconds(20)),
+            status,
+            None,
+            Some(service.to_string()),
+            None,
+            json!({}),
+            json!({}),
+            vec![],
+            vec![],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_error_traces_outlive_clean_traces() {
+        let project_repo = InMemoryProjectRepository::new();
+        let mut project = project_repo.seed("project-1", "org-1");
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({
+                    "trace_retention": {
+                        "error_days": 30,
+                        "clean_days": 3,
+                        "services": { "checkout": { "clean_days": 14 } }
+                    }
+                }))
+                .unwrap(),
+        );
+
+        let now = Utc::now();
+        let ten_days_ago = now - chrono::Duration::days(10);
+        let spans_repo = InMemorySpansRepository::new();
+        spans_repo.seed(vec![
+            span("clean", "api", SpanStatusCode::Ok, ten_days_ago),
+            span("failed", "api", SpanStatusCode::Error, ten_days_ago),
+            span("checkout", "checkout", SpanStatusCode::Ok, ten_days_ago),
+        ]);
+
+        let deleted = cleanup_traces(&spans_repo, &project, now, BATCHES_OF_TEN)
+            .await
+            .unwrap();
+        let mut kept = Vec::new();
+        for trace_id in ["clean", "failed", "checkout"] {
+            if !spans_repo
+                .get_trace(project.id(), trace_id)
+                .await
+                .unwrap()
+                .is_empty()
+            {
+                kept.push(trace_id);
+            }
+        }
+
+        // The clean trace is past its 3 days; the error trace and the checkout
+        // service's clean trace are within their longer windows
+        assert_eq!(deleted, 1);
+        assert_eq!(kept, vec!["failed", "checkout"]);
+    }
+
+    fn log(id: &str, metadata: serde_json::Value, timestamp: DateTime<Utc>) -> LogEntry {
+        LogEntry::new(
+            LogId::new(id.to_string()),
+            ProjectId::new("project-1".to_string()),
+            LogLevel::Info,
+            "user signed in".to_string(),
+            Some(timestamp),
+            None,
+            Some(metadata),
+            None,
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_audit_tagged_logs_outlive_default_retention() {
+        let project_repo = InMemoryProjectRepository::new();
+        let mut project = project_repo.seed("project-1", "org-1");
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({
+                    "log_retention": { "rules": [
+                        { "tags": { "category": "audit" }, "days": 365 },
+                        { "tags": { "category": "audit", "env": "staging" }, "days": 90 },
+                        { "tags": { "category": "debug" }, "days": 1 }
+                    ]}
+                }))
+                .unwrap(),
+        );
+
+        // Older than the project's 30-day default
+        let now = Utc::now();
+        let old = now - chrono::Duration::days(120);
+        let log_repo = InMemoryLogRepository::new();
+        log_repo
+            .save_batch(&[
+                log("audit", json!({"category": "audit"}), old),
+                log(
+                    "staging-audit",
+                    json!({"category": "audit", "env": "staging"}),
+                    old,
+                ),
+                log("untagged", json!({"user": "ada"}), old),
+                log(
+                    "debug",
+                    json!({"category": "debug"}),
+                    now - chrono::Duration::days(2),
+                ),
+                log("recent", json!({}), now - chrono::Duration::days(2)),
+            ])
+            .await
+            .unwrap();
+
+        let deleted = cleanup_logs(&log_repo, &project, now, BATCHES_OF_TEN)
+            .await
+            .unwrap();
+        let mut kept: Vec<String> = log_repo
+            .saved()
+            .iter()
+            .map(|l| l.id().as_str().to_string())
+            .collect();
+        kept.sort();
+
+        // The longest matching rule wins: 365 days for staging audit logs too;
+        // debug logs are kept for just a day
+        assert_eq!(deleted, 2);
+        assert_eq!(kept, vec!["audit", "recent", "staging-audit"]);
+    }
+
+    fn metric(id: usize, project_id: &str, timestamp: DateTime<Utc>) -> MetricPoint {
+        MetricPoint::new(
+            format!("metric-{}", id),
+            ProjectId::new(project_id.to_string()),
+            "requests".to_string(),
+            MetricType::Gauge,
+            1.0,
+            timestamp,
+            None,
+            None,
+            HashMap::new(),
+            None,
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_expired_metrics_are_deleted_in_bounded_batches() {
+        let project = InMemoryProjectRepository::new().seed("project-1", "org-1");
+        let now = Utc::now();
+        let retention = chrono::Duration::days(project.metrics_retention_days().value() as i64);
+        let cutoff = now - retention;
+        let expired_at = cutoff - chrono::Duration::days(1);
+
+        let metrics_repo = InMemoryMetricsRepository::new();
+        let mut points: Vec<_> = (0..25)
+            .map(|i| {
+                metric(
+                    i,
+                    "project-1",
+                    expired_at - chrono::Duration::minutes(i as i64),
+                )
+            })
+            .collect();
+        points.extend((25..30).map(|i| metric(i, "project-1", now - chrono::Duration::hours(1))));
+        // Another project's old metrics follow that project's retention
+        points.push(metric(30, "project-2", expired_at));
+        metrics_repo.save_batch(&points).await.unwrap();
+
+        let batches = Mutex::new(Vec::new());
+        let (repo, batches_ref, project_id) = (&metrics_repo, &batches, project.id());
+        let deleted = delete_in_batches(BATCHES_OF_TEN, |limit| async move {
+            let deleted = repo.delete_before(project_id, cutoff, limit).await?;
+            batches_ref.lock().unwrap().push(deleted);
+            Ok::<_, MetricsDomainError>(deleted)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(deleted, 25);
+        assert_eq!(*batches.lock().unwrap(), vec![10, 10, 5]);
+        let mut kept: Vec<_> = metrics_repo
+            .saved()
+            .iter()
+            .map(|m| m.id().to_string())
+            .collect();
+        kept.sort();
+        let mut expected: Vec<_> = (25..31).map(|i| format!("metric-{}", i)).collect();
+        expected.sort();
+        assert_eq!(kept, expected);
+
+        // The task's own cleanup finds nothing left to delete
+        assert_eq!(
+            cleanup_metrics(&metrics_repo, &project, now, BATCHES_OF_TEN)
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    fn activity(id: &str, activity_type: ActivityType, created_at: DateTime<Utc>) -> OrgActivity {
+        OrgActivity::reconstruct(
+            ActivityId::new(id.to_string()),
+            OrgId::new("org-1".to_string()),
+            activity_type,
+            UserId::new("user-1".to_string()),
+            None,
+            None,
+            created_at,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_old_org_activity_is_pruned_except_security_types() {
+        let mut org = Organization::new(
+            OrgId::new("org-1".to_string()),
+            OrgName::new("Acme".to_string()).unwrap(),
+            OrgSlug::from_string("acme".to_string()).unwrap(),
+        );
+        org.set_activity_retention_days(30).unwrap();
+        let retention = ActivityRetention {
+            default_days: 90,
+            security_days: 365,
+        };
+
+        let now = Utc::now();
+        let activity_repo = InMemoryActivityRepository::new();
+        for a in [
+            activity(
+                "renamed",
+                ActivityType::OrgNameChanged,
+                now - chrono::Duration::days(60),
+            ),
+            activity(
+                "invited",
+                ActivityType::InviteSent,
+                now - chrono::Duration::days(10),
+            ),
+            activity(
+                "role",
+                ActivityType::MemberRoleChanged,
+                now - chrono::Duration::days(60),
+            ),
+            activity(
+                "added",
+                ActivityType::MemberAdded,
+                now - chrono::Duration::days(400),
+            ),
+        ] {
+            activity_repo.save(&a).await.unwrap();
+        }
+
+        let deleted = prune_org_activity(&activity_repo, &org, retention, now)
+            .await
+            .unwrap();
+        let kept: Vec<String> = activity_repo
+            .find_by_org(org.id(), 10, 0)
+            .await
+            .unwrap()
+            .iter()
+            .map(|a| a.id().as_str().to_string())
+            .collect();
+
+        // The rename is past the org's 30 days (though within the 90-day default);
+        // the role change is exempt until the 365-day security window
+        assert_eq!(deleted, 2);
+        assert_eq!(kept, vec!["role", "invited"]);
+    }
+
+    fn alert(id: &str, triggered_at: DateTime<Utc>, resolved_at: Option<DateTime<Utc>>) -> Alert {
+        Alert::from_db(
+            AlertId::new(id.to_string()),
+            AlertRuleId::new("rule-1".to_string()),
+            ProjectId::new("project-1".to_string()),
+            if resolved_at.is_some() {
+                AlertStatus::Resolved
+            } else {
+                AlertStatus::Firing
+            },
+            triggered_at,
+            resolved_at,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_old_resolved_alerts_are_pruned_but_firing_ones_kept() {
+        let now = Utc::now();
+        let days = |n: i64| now - chrono::Duration::days(n);
+        let alert_repo = InMemoryAlertRepository::new();
+        for a in [
+            alert("firing-old", days(400), None),
+            alert("resolved-old", days(200), Some(days(199))),
+            alert("resolved-older", days(300), Some(days(250))),
+            // Fired long ago but only resolved recently
+            alert("resolved-recent", days(120), Some(days(10))),
+            alert("firing-recent", days(1), None),
+        ] {
+            alert_repo.save(&a).await.unwrap();
+        }
+
+        let batches_of_one = DeleteBatching {
+            batch_size: 1,
+            pause: Duration::ZERO,
+        };
+        let deleted = prune_alert_history(&alert_repo, 90, now, batches_of_one)
+            .await
+            .unwrap();
+        let mut kept: Vec<String> = alert_repo
+            .find_by_project(&ProjectId::new("project-1".to_string()), 10, 0)
+            .await
+            .unwrap()
+            .iter()
+            .map(|a| a.id().as_str().to_string())
+            .collect();
+        kept.sort();
+
+        assert_eq!(deleted, 2);
+        assert_eq!(kept, vec!["firing-old", "firing-recent", "resolved-recent"]);
+    }
+}
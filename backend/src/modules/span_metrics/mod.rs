@@ -0,0 +1,360 @@
+//! Span metrics derivation module
+//!
+//! This module provides a background task that turns ingested spans into
+//! RED (rate, errors, duration) metrics for projects that opt in.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::metrics::domain::{MetricPoint, MetricType, MetricsRepository};
+use crate::modules::projects::domain::{FeatureFlag, ProjectId, ProjectRepository};
+use crate::modules::traces::domain::{
+    Span, SpanKind, SpanStatusCode, SpansRepository, normalize_span_name,
+};
+
+/// Per (service, span name) statistics for one window.
+/// Counts are adjusted for sampling, so a span kept at 10% counts as 10.
+#[derive(Debug, Default)]
+struct SpanGroupStats {
+    requests: f64,
+    errors: f64,
+    durations_ms: Vec<f64>,
+}
+
+/// Nearest-rank percentile over sorted values
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Aggregate spans into RED metric points stamped at `window_end`.
+///
+/// Only server spans count as requests; client and internal spans would count
+/// one request several times over. Spans are grouped by service and
+/// normalized span name; each group yields request/error counts, request
+/// rate, error rate and p50/p95/p99 latency. Sampled spans are scaled up by
+/// their inverse sampling probability.
+pub fn derive_red_metrics<ID: IdGenerator>(
+    project_id: &ProjectId,
+    spans: &[Span],
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+    id_generator: &ID,
+) -> Vec<MetricPoint> {
+    let mut groups: BTreeMap<(String, String), SpanGroupStats> = BTreeMap::new();
+    for span in spans.iter().filter(|s| s.kind() == SpanKind::Server) {
+        let key = (
+            span.service_name().unwrap_or("unknown").to_string(),
+            normalize_span_name(span.name()),
+        );
+        let stats = groups.entry(key).or_default();
+        stats.requests += span.adjusted_count();
+        if span.status() == SpanStatusCode::Error {
+            stats.errors += span.adjusted_count();
+        }
+        if let Some(duration_ns) = span.duration_ns() {
+            stats.durations_ms.push(duration_ns as f64 / 1_000_000.0);
+        }
+    }
+
+    let window_secs = (window_end - window_start).num_seconds().max(1) as f64;
+    let mut points = Vec::with_capacity(groups.len() * 7);
+
+    for ((service, span_name), mut stats) in groups {
+        let tags = HashMap::from([
+            ("service".to_string(), service),
+            ("span_name".to_string(), span_name),
+        ]);
+        let mut point = |name: &str, metric_type: MetricType, value: f64, unit: &str| {
+            points.push(MetricPoint::new(
+                id_generator.generate(),
+                project_id.clone(),
+                name.to_string(),
+                metric_type,
+                value,
+                window_end,
+                Some(unit.to_string()),
+                Some("Derived from spans".to_string()),
+                tags.clone(),
+                None,
+                None,
+            ));
+        };
+
+        let requests = stats.requests;
+        let errors = stats.errors;
+        point("span.requests", MetricType::Counter, requests, "1");
+        point("span.errors", MetricType::Counter, errors, "1");
+        point(
+            "span.request_rate",
+            MetricType::Gauge,
+            requests / window_secs,
+            "1/s",
+        );
+        point("span.error_rate", MetricType::Gauge, errors / requests, "1");
+
+        if !stats.durations_ms.is_empty() {
+            stats.durations_ms.sort_by(|a, b| a.total_cmp(b));
+            point(
+                "span.duration.p50",
+                MetricType::Gauge,
+                percentile(&stats.durations_ms, 50.0),
+                "ms",
+            );
+            point(
+                "span.duration.p95",
+                MetricType::Gauge,
+                percentile(&stats.durations_ms, 95.0),
+                "ms",
+            );
+            point(
+                "span.duration.p99",
+                MetricType::Gauge,
+                percentile(&stats.durations_ms, 99.0),
+                "ms",
+            );
+        }
+    }
+
+    points
+}
+
+/// Derive and store RED metrics for one project's window, returning the number of points written
+pub async fn derive_project_window<SR, MR, ID>(
+    spans_repo: &SR,
+    metrics_repo: &MR,
+    id_generator: &ID,
+    project_id: &ProjectId,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+) -> Result<u32, String>
+where
+    SR: SpansRepository,
+    MR: MetricsRepository,
+    ID: IdGenerator,
+{
+    let spans = spans_repo
+        .find_in_range(project_id, window_start, window_end)
+        .await
+        .map_err(|e| e.to_string())?;
+    if spans.is_empty() {
+        return Ok(0);
+    }
+
+    let points = derive_red_metrics(project_id, &spans, window_start, window_end, id_generator);
+    metrics_repo
+        .save_batch(&points)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Start the span metrics derivation background task.
+/// Each tick aggregates the previous complete window for projects with
+/// `derive_span_metrics` enabled and the derivation feature flag on.
+pub async fn start_span_metrics_derivation<SR, MR, PR, ID>(
+    spans_repo: Arc<SR>,
+    metrics_repo: Arc<MR>,
+    project_repo: Arc<PR>,
+    id_generator: Arc<ID>,
+    interval_secs: u64,
+) where
+    SR: SpansRepository + 'static,
+    MR: MetricsRepository + 'static,
+    PR: ProjectRepository + 'static,
+    ID: IdGenerator + 'static,
+{
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+    let window = chrono::Duration::seconds(interval_secs as i64);
+
+    loop {
+        interval.tick().await;
+
+        // Align windows to the interval so consecutive ticks never overlap
+        let now = Utc::now().timestamp();
+        let window_end = DateTime::from_timestamp(now - now.rem_euclid(interval_secs as i64), 0)
+            .unwrap_or_else(Utc::now);
+        let window_start = window_end - window;
+
+        let projects = match project_repo.find_all_active().await {
+            Ok(p) => p,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to fetch projects for span metrics");
+                continue;
+            }
+        };
+
+        for project in projects.iter().filter(|p| {
+            p.settings().derive_span_metrics
+                && p.settings()
+                    .feature_enabled(FeatureFlag::SpanMetricDerivation)
+        }) {
+            match derive_project_window(
+                spans_repo.as_ref(),
+                metrics_repo.as_ref(),
+                id_generator.as_ref(),
+                project.id(),
+                window_start,
+                window_end,
+            )
+            .await
+            {
+                Ok(written) if written > 0 => {
+                    tracing::debug!(
+                        project_id = %project.id().as_str(),
+                        points = written,
+                        "Derived span metrics"
+                    );
+                }
+                Err(e) => {
+                    tracing::error!(
+                        error = %e,
+                        project_id = %project.id().as_str(),
+                        "Failed to derive span metrics"
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::modules::traces::domain::SpanKind;
+    use crate::shared::testing::{
+        InMemoryMetricsRepository, InMemorySpansRepository, SequentialIdGenerator,
+    };
+
+    fn window() -> (DateTime<Utc>, DateTime<Utc>) {
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        (start, start + chrono::Duration::seconds(60))
+    }
+
+    fn span(name: &str, offset_secs: i64, duration_ms: i64, status: SpanStatusCode) -> Span {
+        let (window_start, _) = window();
+        let start = window_start + chrono::Duration::seconds(offset_secs);
+        Span::new(
+            format!("{}-{}", name, offset_secs),
+            ProjectId::new("project-1".to_string()),
+            "trace-1".to_string(),
+            format!("span-{}", offset_secs),
+            None,
+            name.to_string(),
+            SpanKind::Server,
+            start,
+            Some(start + chrono::Duration::milliseconds(duration_ms)),
+            status,
+            None,
+            Some("api".to_string()),
+            None,
+            json!({}),
+            json!({}),
+            vec![],
+            vec![],
+        )
+    }
+
+    fn value_of(points: &[MetricPoint], name: &str, span_name: &str) -> Option<f64> {
+        points
+            .iter()
+            .find(|p| p.name() == name && p.tags()["span_name"] == span_name)
+            .map(|p| p.value())
+    }
+
+    #[tokio::test]
+    async fn test_derives_red_metrics_from_seeded_spans() {
+        let spans_repo = InMemorySpansRepository::new();
+        let metrics_repo = InMemoryMetricsRepository::new();
+        let (start, end) = window();
+        spans_repo.seed(vec![
+            span("GET /users/1", 1, 10, SpanStatusCode::Ok),
+            span("GET /users/2", 2, 20, SpanStatusCode::Ok),
+            span("GET /users/3", 3, 30, SpanStatusCode::Error),
+            span("GET /users/4", 4, 40, SpanStatusCode::Ok),
+            span("SELECT users", 5, 5, SpanStatusCode::Ok),
+            // Outside the window
+            span("SELECT users", 90, 5, SpanStatusCode::Error),
+        ]);
+
+        let written = derive_project_window(
+            &spans_repo,
+            &metrics_repo,
+            &SequentialIdGenerator::new(),
+            &ProjectId::new("project-1".to_string()),
+            start,
+            end,
+        )
+        .await
+        .unwrap();
+
+        let points = metrics_repo.saved();
+        assert_eq!(written as usize, points.len());
+
+        let users = "GET /users/{id}";
+        assert_eq!(value_of(&points, "span.requests", users), Some(4.0));
+        assert_eq!(value_of(&points, "span.errors", users), Some(1.0));
+        assert_eq!(value_of(&points, "span.error_rate", users), Some(0.25));
+        assert_eq!(value_of(&points, "span.duration.p50", users), Some(20.0));
+        assert_eq!(value_of(&points, "span.duration.p99", users), Some(40.0));
+
+        assert_eq!(
+            value_of(&points, "span.requests", "SELECT users"),
+            Some(1.0)
+        );
+        assert_eq!(value_of(&points, "span.errors", "SELECT users"), Some(0.0));
+        assert!(points.iter().all(|p| p.timestamp() == end));
+    }
+
+    #[test]
+    fn test_sampled_spans_are_scaled_up() {
+        let (start, end) = window();
+        let spans = vec![
+            span("GET /orders", 1, 10, SpanStatusCode::Error)
+                .with_trace_state(Some("ot=th:e6666666666666".to_string())),
+            span("GET /orders", 2, 10, SpanStatusCode::Ok),
+        ];
+
+        let points = derive_red_metrics(
+            &ProjectId::new("project-1".to_string()),
+            &spans,
+            start,
+            end,
+            &SequentialIdGenerator::new(),
+        );
+
+        let requests = value_of(&points, "span.requests", "GET /orders").unwrap();
+        let errors = value_of(&points, "span.errors", "GET /orders").unwrap();
+        assert!((requests - 11.0).abs() < 1e-9);
+        assert!((errors - 10.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_empty_window_writes_nothing() {
+        let (start, end) = window();
+        let metrics_repo = InMemoryMetricsRepository::new();
+
+        let written = derive_project_window(
+            &InMemorySpansRepository::new(),
+            &metrics_repo,
+            &SequentialIdGenerator::new(),
+            &ProjectId::new("project-1".to_string()),
+            start,
+            end,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(written, 0);
+        assert!(metrics_repo.saved().is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+//! Aggregate StatsD samples over a flush interval into metric inputs
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+
+use crate::modules::metrics::application::dto::MetricInput;
+use crate::modules::statsd::parser::{StatsdKind, StatsdSample};
+
+/// Histogram bucket bounds for timer durations, in milliseconds
+const TIMER_BUCKET_BOUNDS_MS: [f64; 11] = [
+    5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0,
+];
+
+/// A metric name with its tags, identifying one series
+type SeriesKey = (String, BTreeMap<String, String>);
+
+/// Durations observed for a timer during the interval
+#[derive(Debug, Default)]
+struct TimerStats {
+    bucket_counts: Vec<f64>,
+    count: f64,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl TimerStats {
+    fn observe(&mut self, value: f64, weight: f64) {
+        if self.bucket_counts.is_empty() {
+            self.bucket_counts = vec![0.0; TIMER_BUCKET_BOUNDS_MS.len() + 1];
+            self.min = value;
+            self.max = value;
+        }
+        let bucket = TIMER_BUCKET_BOUNDS_MS
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(TIMER_BUCKET_BOUNDS_MS.len());
+        self.bucket_counts[bucket] += weight;
+        self.count += weight;
+        self.sum += value * weight;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+}
+
+/// Accumulates samples until flushed, the way a StatsD daemon does:
+/// counters are summed (scaled up by their sample rate) and written as the
+/// count for the interval, timers become one histogram per interval, sets
+/// the number of distinct members, and gauges their latest value. Gauges
+/// persist across flushes so signed deltas apply to the last known value.
+#[derive(Debug, Default)]
+pub struct StatsdAggregator {
+    counters: HashMap<SeriesKey, f64>,
+    timers: HashMap<SeriesKey, TimerStats>,
+    sets: HashMap<SeriesKey, HashSet<String>>,
+    gauges: HashMap<SeriesKey, f64>,
+    /// Gauges set or adjusted since the last flush
+    updated_gauges: HashSet<SeriesKey>,
+}
+
+impl StatsdAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, sample: StatsdSample) {
+        let weight = 1.0 / sample.sample_rate;
+        let key = (sample.name, sample.tags);
+        match sample.kind {
+            StatsdKind::Counter => *self.counters.entry(key).or_default() += sample.value * weight,
+            StatsdKind::Timer => self
+                .timers
+                .entry(key)
+                .or_default()
+                .observe(sample.value, weight),
+            StatsdKind::Set => {
+                if let Some(member) = sample.member {
+                    self.sets.entry(key).or_default().insert(member);
+                }
+            }
+            StatsdKind::Gauge { delta } => {
+                let gauge = self.gauges.entry(key.clone()).or_default();
+                if delta {
+                    *gauge += sample.value;
+                } else {
+                    *gauge = sample.value;
+                }
+                self.updated_gauges.insert(key);
+            }
+        }
+    }
+
+    /// Metric inputs for everything seen since the last flush, stamped `now`
+    pub fn flush(&mut self, now: DateTime<Utc>) -> Vec<MetricInput> {
+        let mut metrics = Vec::new();
+
+        for ((name, tags), count) in self.counters.drain() {
+            metrics.push(metric_input(name, "counter", count, tags, now));
+        }
+        for ((name, tags), members) in self.sets.drain() {
+            metrics.push(metric_input(name, "gauge", members.len() as f64, tags, now));
+        }
+        for key in self.updated_gauges.drain() {
+            let value = self.gauges[&key];
+            let (name, tags) = key;
+            metrics.push(metric_input(name, "gauge", value, tags, now));
+        }
+        for ((name, tags), stats) in self.timers.drain() {
+            let mut input = metric_input(name, "histogram", stats.sum, tags, now);
+            input.unit = Some("ms".to_string());
+            input.bucket_bounds = Some(TIMER_BUCKET_BOUNDS_MS.to_vec());
+            input.bucket_counts = Some(
+                stats
+                    .bucket_counts
+                    .iter()
+                    .map(|c| c.round() as i64)
+                    .collect(),
+            );
+            input.histogram_sum = Some(stats.sum);
+            input.histogram_count = Some(stats.count.round() as i64);
+            input.histogram_min = Some(stats.min);
+            input.histogram_max = Some(stats.max);
+            metrics.push(input);
+        }
+
+        metrics
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty()
+            && self.timers.is_empty()
+            && self.sets.is_empty()
+            && self.updated_gauges.is_empty()
+    }
+}
+
+fn metric_input(
+    name: String,
+    metric_type: &str,
+    value: f64,
+    tags: BTreeMap<String, String>,
+    timestamp: DateTime<Utc>,
+) -> MetricInput {
+    MetricInput {
+        name,
+        metric_type: metric_type.to_string(),
+        value,
+        timestamp: Some(timestamp),
+        unit: None,
+        description: None,
+        tags: tags.into_iter().collect(),
+        bucket_bounds: None,
+        bucket_counts: None,
+        histogram_sum: None,
+        histogram_count: None,
+        histogram_min: None,
+        histogram_max: None,
+        trace_id: None,
+        span_id: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::modules::metrics::application::MetricsService;
+    use crate::modules::metrics::application::dto::IngestMetricsCommand;
+    use crate::modules::metrics::domain::MetricType;
+    use crate::modules::statsd::parser::parse_statsd_lines;
+    use crate::shared::testing::{
+        InMemoryMemberRepository, InMemoryMetricsRepository, InMemoryProjectRepository,
+        SequentialIdGenerator,
+    };
+
+    fn aggregate(aggregator: &mut StatsdAggregator, body: &str) -> Vec<MetricInput> {
+        let (samples, errors) = parse_statsd_lines(body);
+        assert!(errors.is_empty());
+        samples.into_iter().for_each(|s| aggregator.add(s));
+        let mut metrics = aggregator.flush(Utc::now());
+        metrics.sort_by(|a, b| (&a.name, a.tags.len()).cmp(&(&b.name, b.tags.len())));
+        metrics
+    }
+
+    #[test]
+    fn test_lines_aggregate_into_metric_points() {
+        let mut aggregator = StatsdAggregator::new();
+        let metrics = aggregate(
+            &mut aggregator,
+            "requests:1|c|#route:/a\n\
+             requests:2|c|@0.5|#route:/a\n\
+             requests:1|c\n\
+             latency:20|ms\n\
+             latency:300|ms\n\
+             queue:10|g\n\
+             queue:+5|g\n\
+             visitors:alice|s\n\
+             visitors:bob|s\n\
+             visitors:alice|s",
+        );
+
+        let names: Vec<(&str, &str)> = metrics
+            .iter()
+            .map(|m| (m.name.as_str(), m.metric_type.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("latency", "histogram"),
+                ("queue", "gauge"),
+                ("requests", "counter"),
+                ("requests", "counter"),
+                ("visitors", "gauge"),
+            ]
+        );
+
+        let latency = &metrics[0];
+        assert_eq!(latency.unit.as_deref(), Some("ms"));
+        assert_eq!(latency.histogram_count, Some(2));
+        assert_eq!(latency.histogram_sum, Some(320.0));
+        assert_eq!(
+            (latency.histogram_min, latency.histogram_max),
+            (Some(20.0), Some(300.0))
+        );
+        let counts = latency.bucket_counts.as_ref().unwrap();
+        assert_eq!((counts[2], counts[6]), (1, 1));
+
+        assert_eq!(metrics[1].value, 15.0);
+        assert_eq!(metrics[2].value, 1.0);
+        assert!(metrics[2].tags.is_empty());
+        // 1 + 2 sent at a 50% sample rate
+        assert_eq!(metrics[3].value, 5.0);
+        assert_eq!(metrics[3].tags["route"], "/a");
+        assert_eq!(metrics[4].value, 2.0);
+
+        // Gauges carry over for deltas, but only changed series are written
+        let metrics = aggregate(&mut aggregator, "queue:-3|g");
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].value, 12.0);
+        assert!(aggregator.flush(Utc::now()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_flushed_metrics_are_stored() {
+        let metrics_repo = Arc::new(InMemoryMetricsRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        let service = MetricsService::new(
+            metrics_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            chrono::Duration::hours(1),
+        );
+
+        let mut aggregator = StatsdAggregator::new();
+        let metrics = aggregate(&mut aggregator, "jobs:3|c|#queue:mail\nrender:42|ms");
+        let response = service
+            .ingest(IngestMetricsCommand {
+                project_id: "project-1".to_string(),
+                metrics,
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.ingested, 2);
+
+        let stored = metrics_repo.saved();
+        let render = stored.iter().find(|m| m.name() == "render").unwrap();
+        assert_eq!(render.metric_type(), MetricType::Histogram);
+        assert_eq!(render.histogram_data().unwrap().count(), 1);
+        let jobs = stored.iter().find(|m| m.name() == "jobs").unwrap();
+        assert_eq!(jobs.metric_type(), MetricType::Counter);
+        assert_eq!(jobs.tags()["queue"], "mail");
+    }
+}
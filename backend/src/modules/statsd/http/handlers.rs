@@ -0,0 +1,99 @@
+//! StatsD HTTP handlers
+
+use axum::{Extension, Json, body::Bytes, extract::State, http::StatusCode};
+use chrono::Utc;
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::logging::infrastructure::ApiKeyContext;
+use crate::modules::metrics::application::MetricsService;
+use crate::modules::metrics::application::dto::IngestMetricsCommand;
+use crate::modules::metrics::domain::{MetricsDomainError, MetricsRepository};
+use crate::modules::organizations::domain::OrganizationMemberRepository;
+use crate::modules::projects::domain::ProjectRepository;
+use crate::modules::statsd::aggregator::StatsdAggregator;
+use crate::modules::statsd::parser::parse_statsd_lines;
+
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatsdIngestResponse {
+    /// Lines parsed
+    pub accepted: u32,
+    pub rejected: u32,
+    /// Metric points written after aggregation
+    pub ingested: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// POST /api/v1/ingest/statsd - newline-separated StatsD lines, aggregated as
+/// one flush interval
+pub async fn ingest_statsd<MR, PR, OMR, ID>(
+    State(service): State<Arc<MetricsService<MR, PR, OMR, ID>>>,
+    Extension(ctx): Extension<ApiKeyContext>,
+    body: Bytes,
+) -> Result<Json<StatsdIngestResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    MR: MetricsRepository,
+    PR: ProjectRepository,
+    OMR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let body = std::str::from_utf8(&body).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Body must be UTF-8 text".to_string(),
+                code: "INVALID_BODY".to_string(),
+            }),
+        )
+    })?;
+
+    let (samples, errors) = parse_statsd_lines(body);
+    let accepted = samples.len() as u32;
+    let mut aggregator = StatsdAggregator::new();
+    for sample in samples {
+        aggregator.add(sample);
+    }
+
+    let cmd = IngestMetricsCommand {
+        project_id: ctx.project_id.as_str().to_string(),
+        metrics: aggregator.flush(Utc::now()),
+    };
+
+    let response = service.ingest(cmd).await.map_err(|e| {
+        let (status, msg) = match e {
+            MetricsDomainError::ProjectNotFound | MetricsDomainError::ProjectDeleted => {
+                (StatusCode::NOT_FOUND, "Project not found".to_string())
+            }
+            MetricsDomainError::InternalError(ref msg) => {
+                tracing::error!(error = %msg, "Internal error during StatsD ingestion");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal error".to_string(),
+                )
+            }
+            other => (StatusCode::BAD_REQUEST, other.to_string()),
+        };
+        (
+            status,
+            Json(ErrorResponse {
+                error: msg,
+                code: "INGESTION_ERROR".to_string(),
+            }),
+        )
+    })?;
+
+    Ok(Json(StatsdIngestResponse {
+        accepted,
+        rejected: errors.len() as u32,
+        ingested: response.ingested,
+        errors,
+    }))
+}
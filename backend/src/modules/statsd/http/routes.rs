@@ -0,0 +1,36 @@
+//! StatsD HTTP routes
+
+use axum::{Router, middleware, routing::post};
+use std::sync::Arc;
+
+use super::handlers;
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::logging::infrastructure::http::middleware::api_key_middleware;
+use crate::modules::metrics::application::MetricsService;
+use crate::modules::metrics::domain::MetricsRepository;
+use crate::modules::organizations::domain::{OrganizationMemberRepository, OrganizationRepository};
+use crate::modules::projects::application::ProjectService;
+use crate::modules::projects::domain::{ApiKeyRepository, ProjectRepository};
+
+/// StatsD routes for metric ingestion (requires API key middleware)
+pub fn statsd_routes<MR, PR, OMR, ID, PPR, AR, OR>(
+    service: Arc<MetricsService<MR, PR, OMR, ID>>,
+    project_service: Arc<ProjectService<PPR, AR, OR, OMR, ID>>,
+) -> Router
+where
+    MR: MetricsRepository + 'static,
+    PR: ProjectRepository + 'static,
+    OMR: OrganizationMemberRepository + 'static,
+    ID: IdGenerator + 'static,
+    PPR: ProjectRepository + 'static,
+    AR: ApiKeyRepository + 'static,
+    OR: OrganizationRepository + 'static,
+{
+    Router::new()
+        .route("/statsd", post(handlers::ingest_statsd::<MR, PR, OMR, ID>))
+        .layer(middleware::from_fn_with_state(
+            project_service,
+            api_key_middleware::<PPR, AR, OR, OMR, ID>,
+        ))
+        .with_state(service)
+}
@@ -0,0 +1,84 @@
+//! StatsD UDP listener
+//!
+//! Samples are aggregated in memory and written once per flush interval.
+//! UDP senders cannot present an API key, so every sample is attributed to a
+//! single configured project.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::net::UdpSocket;
+
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::metrics::application::MetricsService;
+use crate::modules::metrics::application::dto::IngestMetricsCommand;
+use crate::modules::metrics::domain::MetricsRepository;
+use crate::modules::organizations::domain::OrganizationMemberRepository;
+use crate::modules::projects::domain::ProjectRepository;
+use crate::modules::statsd::aggregator::StatsdAggregator;
+use crate::modules::statsd::parser::parse_statsd_lines;
+
+/// Largest datagram a StatsD client can transmit over UDP
+const MAX_DATAGRAM_SIZE: usize = 65_535;
+
+/// Start the StatsD UDP listener. Datagrams may carry several newline-separated lines.
+pub async fn start_statsd_udp_listener<MR, PR, OMR, ID>(
+    service: Arc<MetricsService<MR, PR, OMR, ID>>,
+    host: String,
+    port: u16,
+    project_id: String,
+    flush_interval_secs: u64,
+) where
+    MR: MetricsRepository + 'static,
+    PR: ProjectRepository + 'static,
+    OMR: OrganizationMemberRepository + 'static,
+    ID: IdGenerator + 'static,
+{
+    let socket = match UdpSocket::bind((host.as_str(), port)).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            tracing::error!(error = %e, port, "Failed to bind StatsD UDP listener");
+            return;
+        }
+    };
+
+    let mut aggregator = StatsdAggregator::new();
+    let mut flush = tokio::time::interval(Duration::from_secs(flush_interval_secs));
+    let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+    loop {
+        tokio::select! {
+            received = socket.recv_from(&mut buf) => {
+                let len = match received {
+                    Ok((len, _)) => len,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Failed to receive StatsD datagram");
+                        continue;
+                    }
+                };
+                let (samples, errors) = parse_statsd_lines(&String::from_utf8_lossy(&buf[..len]));
+                if !errors.is_empty() {
+                    tracing::debug!(
+                        rejected = errors.len(),
+                        "StatsD datagram contained malformed lines"
+                    );
+                }
+                for sample in samples {
+                    aggregator.add(sample);
+                }
+            }
+            _ = flush.tick() => {
+                if aggregator.is_empty() {
+                    continue;
+                }
+                let cmd = IngestMetricsCommand {
+                    project_id: project_id.clone(),
+                    metrics: aggregator.flush(Utc::now()),
+                };
+                if let Err(e) = service.ingest(cmd).await {
+                    tracing::error!(error = %e, "Failed to ingest StatsD metrics");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod aggregator;
+pub mod http;
+pub mod listener;
+pub mod parser;
+
+pub use http::statsd_routes;
+pub use listener::start_statsd_udp_listener;
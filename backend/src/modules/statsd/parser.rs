@@ -0,0 +1,153 @@
+//! Parse StatsD lines, with DogStatsD tags, into samples
+
+use std::collections::BTreeMap;
+
+/// What a StatsD sample measures
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatsdKind {
+    /// `c`: added to the series' count for the flush interval
+    Counter,
+    /// `g`: sets the value, or adjusts it when the value is signed
+    Gauge { delta: bool },
+    /// `ms` (also DogStatsD `h` and `d`): one observed duration
+    Timer,
+    /// `s`: one member of a set whose distinct members are counted
+    Set,
+}
+
+/// A single parsed StatsD sample
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsdSample {
+    pub name: String,
+    pub kind: StatsdKind,
+    /// Numeric value; 0 for sets
+    pub value: f64,
+    /// Set member, as sent
+    pub member: Option<String>,
+    /// Fraction of events the client sent, from `@rate`
+    pub sample_rate: f64,
+    /// DogStatsD `#key:value` tags; tags without a value map to ""
+    pub tags: BTreeMap<String, String>,
+}
+
+/// Parse newline-separated StatsD lines. Blank lines are ignored; malformed
+/// lines are reported by line number.
+pub fn parse_statsd_lines(body: &str) -> (Vec<StatsdSample>, Vec<String>) {
+    let mut samples = Vec::new();
+    let mut errors = Vec::new();
+
+    for (idx, line) in body.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match parse_statsd_line(line) {
+            Ok(sample) => samples.push(sample),
+            Err(e) => errors.push(format!("Line {}: {}", idx, e)),
+        }
+    }
+
+    (samples, errors)
+}
+
+/// Parse one line: `name:value|type[|@sample_rate][|#tag:value,tag]`
+pub fn parse_statsd_line(line: &str) -> Result<StatsdSample, String> {
+    let (name, rest) = line
+        .split_once(':')
+        .ok_or("Missing ':' after metric name")?;
+    if name.is_empty() {
+        return Err("Empty metric name".to_string());
+    }
+
+    let mut sections = rest.split('|');
+    let raw_value = sections.next().unwrap_or_default();
+    let kind = match sections.next().ok_or("Missing metric type")? {
+        "c" => StatsdKind::Counter,
+        "g" => StatsdKind::Gauge {
+            delta: raw_value.starts_with('+') || raw_value.starts_with('-'),
+        },
+        "ms" | "h" | "d" => StatsdKind::Timer,
+        "s" => StatsdKind::Set,
+        other => return Err(format!("Unsupported metric type: {}", other)),
+    };
+
+    let (value, member) = match kind {
+        StatsdKind::Set if raw_value.is_empty() => return Err("Empty set member".to_string()),
+        StatsdKind::Set => (0.0, Some(raw_value.to_string())),
+        _ => {
+            let value: f64 = raw_value
+                .parse()
+                .ok()
+                .filter(|v: &f64| v.is_finite())
+                .ok_or_else(|| format!("Invalid value: {}", raw_value))?;
+            (value, None)
+        }
+    };
+
+    let mut sample_rate = 1.0;
+    let mut tags = BTreeMap::new();
+    for section in sections {
+        if let Some(rate) = section.strip_prefix('@') {
+            sample_rate = rate
+                .parse()
+                .ok()
+                .filter(|r| *r > 0.0 && *r <= 1.0)
+                .ok_or_else(|| format!("Invalid sample rate: {}", rate))?;
+        } else if let Some(tag_list) = section.strip_prefix('#') {
+            for tag in tag_list.split(',').filter(|t| !t.is_empty()) {
+                let (key, value) = tag.split_once(':').unwrap_or((tag, ""));
+                tags.insert(key.to_string(), value.to_string());
+            }
+        }
+        // Other extensions (e.g. DogStatsD `c:` container ids) are ignored
+    }
+
+    Ok(StatsdSample {
+        name: name.to_string(),
+        kind,
+        value,
+        member,
+        sample_rate,
+        tags,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_representative_lines() {
+        let counter = parse_statsd_line("page.views:1|c|@0.5|#env:prod,canary").unwrap();
+        assert_eq!(counter.name, "page.views");
+        assert_eq!(counter.kind, StatsdKind::Counter);
+        assert_eq!(counter.value, 1.0);
+        assert_eq!(counter.sample_rate, 0.5);
+        assert_eq!(counter.tags["env"], "prod");
+        assert_eq!(counter.tags["canary"], "");
+
+        let gauge = parse_statsd_line("queue.depth:-3|g").unwrap();
+        assert_eq!(gauge.kind, StatsdKind::Gauge { delta: true });
+        assert_eq!(gauge.value, -3.0);
+        assert_eq!(
+            parse_statsd_line("queue.depth:12|g").unwrap().kind,
+            StatsdKind::Gauge { delta: false }
+        );
+
+        let timer = parse_statsd_line("db.query:320.5|ms|#table:users").unwrap();
+        assert_eq!(timer.kind, StatsdKind::Timer);
+        assert_eq!(timer.value, 320.5);
+
+        let set = parse_statsd_line("users.unique:alice|s").unwrap();
+        assert_eq!(set.kind, StatsdKind::Set);
+        assert_eq!(set.member.as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn test_malformed_lines_are_reported() {
+        let (samples, errors) = parse_statsd_lines("a:1|c\n\nb:x|c\nc:1|q\nno_value\nd:1|c|@2");
+        assert_eq!(samples.len(), 1);
+        assert_eq!(errors.len(), 4);
+        assert!(errors[0].starts_with("Line 2:"));
+    }
+}
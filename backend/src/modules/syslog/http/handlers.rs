@@ -0,0 +1,81 @@
+//! Syslog HTTP handlers
+
+use axum::{Extension, Json, body::Bytes, extract::State, http::StatusCode};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::logging::application::LogService;
+use crate::modules::logging::domain::{LogDomainError, LogRepository};
+use crate::modules::logging::infrastructure::ApiKeyContext;
+use crate::modules::organizations::domain::OrganizationMemberRepository;
+use crate::modules::projects::domain::ProjectRepository;
+use crate::modules::syslog::parser::parse_syslog_batch;
+
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyslogIngestResponse {
+    pub accepted: u32,
+    pub rejected: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// POST /api/v1/ingest/syslog - newline-separated RFC 5424 messages
+pub async fn ingest_syslog<LR, PR, OMR, ID>(
+    State(service): State<Arc<LogService<LR, PR, OMR, ID>>>,
+    Extension(ctx): Extension<ApiKeyContext>,
+    body: Bytes,
+) -> Result<Json<SyslogIngestResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    LR: LogRepository,
+    PR: ProjectRepository,
+    OMR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let body = std::str::from_utf8(&body).map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Body must be UTF-8 text".to_string(),
+                code: "INVALID_BODY".to_string(),
+            }),
+        )
+    })?;
+
+    let cmd = parse_syslog_batch(ctx.project_id.as_str(), body);
+
+    let response = service.ingest_raw(cmd).await.map_err(|e| {
+        let (status, msg) = match e {
+            LogDomainError::ProjectNotFound | LogDomainError::ProjectDeleted => {
+                (StatusCode::NOT_FOUND, "Project not found".to_string())
+            }
+            LogDomainError::InternalError(ref msg) => {
+                tracing::error!(error = %msg, "Internal error during syslog ingestion");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal error".to_string(),
+                )
+            }
+            other => (StatusCode::BAD_REQUEST, other.to_string()),
+        };
+        (
+            status,
+            Json(ErrorResponse {
+                error: msg,
+                code: "INGESTION_ERROR".to_string(),
+            }),
+        )
+    })?;
+
+    Ok(Json(SyslogIngestResponse {
+        accepted: response.accepted,
+        rejected: response.rejected,
+        errors: response.errors,
+    }))
+}
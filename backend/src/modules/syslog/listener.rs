@@ -0,0 +1,65 @@
+//! Syslog UDP listener (RFC 5426)
+//!
+//! UDP senders cannot present an API key, so every datagram is attributed to
+//! a single configured project.
+
+use std::sync::Arc;
+
+use tokio::net::UdpSocket;
+
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::logging::application::LogService;
+use crate::modules::logging::domain::LogRepository;
+use crate::modules::organizations::domain::OrganizationMemberRepository;
+use crate::modules::projects::domain::ProjectRepository;
+use crate::modules::syslog::parser::parse_syslog_batch;
+
+/// Largest datagram a syslog sender can transmit over IPv4/IPv6 UDP
+const MAX_DATAGRAM_SIZE: usize = 65_535;
+
+/// Start the syslog UDP listener. Each datagram carries one message.
+pub async fn start_syslog_udp_listener<LR, PR, OMR, ID>(
+    service: Arc<LogService<LR, PR, OMR, ID>>,
+    host: String,
+    port: u16,
+    project_id: String,
+) where
+    LR: LogRepository + 'static,
+    PR: ProjectRepository + 'static,
+    OMR: OrganizationMemberRepository + 'static,
+    ID: IdGenerator + 'static,
+{
+    let socket = match UdpSocket::bind((host.as_str(), port)).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            tracing::error!(error = %e, port, "Failed to bind syslog UDP listener");
+            return;
+        }
+    };
+
+    let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+    loop {
+        let len = match socket.recv_from(&mut buf).await {
+            Ok((len, _)) => len,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to receive syslog datagram");
+                continue;
+            }
+        };
+
+        let datagram = String::from_utf8_lossy(&buf[..len]);
+        let cmd = parse_syslog_batch(&project_id, &datagram);
+        match service.ingest_raw(cmd).await {
+            Ok(response) if response.rejected > 0 => {
+                tracing::debug!(
+                    rejected = response.rejected,
+                    "Syslog datagram contained malformed messages"
+                );
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to ingest syslog datagram");
+            }
+            _ => {}
+        }
+    }
+}
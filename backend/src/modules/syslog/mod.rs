@@ -0,0 +1,6 @@
+pub mod http;
+pub mod listener;
+pub mod parser;
+
+pub use http::syslog_routes;
+pub use listener::start_syslog_udp_listener;
@@ -0,0 +1,320 @@
+//! Parse RFC 5424 syslog messages into internal log format
+
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value, json};
+
+use crate::modules::logging::application::dto::{DeadLetterInput, IngestRawLogsCommand, LogInput};
+
+/// Format name recorded on dead letters
+pub const SYSLOG_FORMAT: &str = "syslog";
+
+/// RFC 5424 placeholder for an absent header field
+const NILVALUE: &str = "-";
+
+/// Highest valid PRI value (facility 23, severity 7)
+const MAX_PRIORITY: u8 = 191;
+
+/// Build an ingest command from newline-separated syslog messages.
+/// Blank lines are ignored; malformed lines become dead letters.
+pub fn parse_syslog_batch(project_id: &str, body: &str) -> IngestRawLogsCommand {
+    let mut logs = Vec::new();
+    let mut dead_letters = Vec::new();
+
+    for (idx, line) in body.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_syslog_line(line) {
+            Ok(log) => logs.push(log),
+            Err(e) => dead_letters.push(DeadLetterInput {
+                payload: line.to_string(),
+                error: format!("Line {}: {}", idx, e),
+            }),
+        }
+    }
+
+    IngestRawLogsCommand {
+        project_id: project_id.to_string(),
+        format: SYSLOG_FORMAT.to_string(),
+        logs,
+        dead_letters,
+    }
+}
+
+/// Parse a single RFC 5424 message:
+/// `<PRI>VERSION TIMESTAMP HOSTNAME APP-NAME PROCID MSGID STRUCTURED-DATA [MSG]`
+pub fn parse_syslog_line(line: &str) -> Result<LogInput, String> {
+    let rest = line.strip_prefix('<').ok_or("Missing priority")?;
+    let (pri, rest) = rest.split_once('>').ok_or("Unterminated priority")?;
+    let priority: u8 = pri
+        .parse()
+        .ok()
+        .filter(|p| *p <= MAX_PRIORITY)
+        .ok_or_else(|| format!("Invalid priority: {}", pri))?;
+
+    let (version, rest) = next_field(rest)?;
+    if version != "1" {
+        return Err(format!("Unsupported version: {}", version));
+    }
+
+    let (timestamp, rest) = next_field(rest)?;
+    let timestamp = if timestamp == NILVALUE {
+        None
+    } else {
+        Some(
+            DateTime::parse_from_rfc3339(timestamp)
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|_| format!("Invalid timestamp: {}", timestamp))?,
+        )
+    };
+
+    let (hostname, rest) = next_field(rest)?;
+    let (app_name, rest) = next_field(rest)?;
+    let (procid, rest) = next_field(rest)?;
+    let (msgid, rest) = next_field(rest)?;
+    let (structured_data, rest) = parse_structured_data(rest)?;
+
+    let message = match rest {
+        "" => "",
+        msg => msg
+            .strip_prefix(' ')
+            .ok_or("Missing space before message")?,
+    };
+    // MSG may start with a UTF-8 byte order mark
+    let message = message.trim_start_matches('\u{feff}').trim_end();
+    if message.is_empty() {
+        return Err("Empty message".to_string());
+    }
+
+    let facility = priority / 8;
+    let severity = priority % 8;
+
+    let mut metadata = Map::new();
+    metadata.insert("facility".to_string(), json!(facility));
+    metadata.insert("severity".to_string(), json!(severity));
+    for (key, value) in [
+        ("hostname", hostname),
+        ("app_name", app_name),
+        ("procid", procid),
+        ("msgid", msgid),
+    ] {
+        if value != NILVALUE {
+            metadata.insert(key.to_string(), json!(value));
+        }
+    }
+    if !structured_data.is_empty() {
+        metadata.insert(
+            "structured_data".to_string(),
+            Value::Object(structured_data),
+        );
+    }
+
+    Ok(LogInput {
+        level: severity_to_level(severity).to_string(),
+        message: message.to_string(),
+        timestamp,
+        source: [app_name, hostname]
+            .into_iter()
+            .find(|v| *v != NILVALUE)
+            .map(str::to_string),
+        metadata: Some(Value::Object(metadata)),
+        trace_id: None,
+        span_id: None,
+        event_id: None,
+    })
+}
+
+/// Map a syslog severity (0-7) to a log level name (GELF levels use the same scale)
+pub fn severity_to_level(severity: u8) -> &'static str {
+    match severity {
+        0..=2 => "fatal", // emergency, alert, critical
+        3 => "error",
+        4 => "warn",
+        5 | 6 => "info", // notice, informational
+        _ => "debug",
+    }
+}
+
+/// Split off the next space-delimited header field
+fn next_field(input: &str) -> Result<(&str, &str), String> {
+    let (field, rest) = input.split_once(' ').ok_or("Truncated header")?;
+    if field.is_empty() {
+        return Err("Empty header field".to_string());
+    }
+    Ok((field, rest))
+}
+
+/// Parse STRUCTURED-DATA into `{sd_id: {param: value}}`, returning the unparsed remainder
+fn parse_structured_data(input: &str) -> Result<(Map<String, Value>, &str), String> {
+    let mut elements = Map::new();
+    if let Some(rest) = input.strip_prefix(NILVALUE) {
+        return Ok((elements, rest));
+    }
+
+    let mut rest = input;
+    while let Some(element) = rest.strip_prefix('[') {
+        let id_end = element
+            .find([' ', ']'])
+            .ok_or("Unterminated structured data element")?;
+        let (sd_id, mut params_input) = element.split_at(id_end);
+        if sd_id.is_empty() {
+            return Err("Empty structured data ID".to_string());
+        }
+
+        let mut params = Map::new();
+        loop {
+            if let Some(after) = params_input.strip_prefix(']') {
+                rest = after;
+                break;
+            }
+            let param = params_input
+                .strip_prefix(' ')
+                .ok_or("Malformed structured data parameter")?;
+            let (name, value_input) = param
+                .split_once("=\"")
+                .ok_or("Malformed structured data parameter")?;
+            let (value, after) = parse_param_value(value_input)?;
+            params.insert(name.to_string(), json!(value));
+            params_input = after;
+        }
+        elements.insert(sd_id.to_string(), Value::Object(params));
+    }
+
+    if elements.is_empty() {
+        return Err("Missing structured data".to_string());
+    }
+    Ok((elements, rest))
+}
+
+/// Read a quoted PARAM-VALUE up to its closing quote, unescaping `\"`, `\\` and `\]`
+fn parse_param_value(input: &str) -> Result<(String, &str), String> {
+    let mut value = String::new();
+    let mut chars = input.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '"' => return Ok((value, &input[idx + 1..])),
+            '\\' => match chars.next() {
+                Some((_, escaped @ ('"' | '\\' | ']'))) => value.push(escaped),
+                Some((_, other)) => {
+                    value.push('\\');
+                    value.push(other);
+                }
+                None => break,
+            },
+            _ => value.push(c),
+        }
+    }
+    Err("Unterminated structured data value".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::modules::logging::application::LogService;
+    use crate::modules::logging::domain::LogLevel;
+    use crate::shared::PaginationConfig;
+    use crate::shared::testing::{
+        InMemoryLogRepository, InMemoryMemberRepository, InMemoryProjectRepository,
+        SequentialIdGenerator,
+    };
+
+    const WELL_FORMED: &str = r#"<165>1 2003-10-11T22:14:15.003Z mymachine.example.com evntslog - ID47 [exampleSDID@32473 iut="3" eventSource="Application" eventID="1011"] An application event log entry"#;
+
+    #[test]
+    fn test_parses_well_formed_line() {
+        let log = parse_syslog_line(WELL_FORMED).unwrap();
+
+        // PRI 165 = facility 20 (local4), severity 5 (notice)
+        assert_eq!(log.level, "info");
+        assert_eq!(log.message, "An application event log entry");
+        assert_eq!(
+            log.timestamp,
+            Some(
+                DateTime::parse_from_rfc3339("2003-10-11T22:14:15.003Z")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )
+        );
+        assert_eq!(log.source.as_deref(), Some("evntslog"));
+
+        let metadata = log.metadata.unwrap();
+        assert_eq!(metadata["hostname"], "mymachine.example.com");
+        assert_eq!(metadata["app_name"], "evntslog");
+        assert_eq!(metadata["msgid"], "ID47");
+        assert_eq!(metadata["facility"], 20);
+        assert_eq!(metadata["severity"], 5);
+        assert!(metadata.get("procid").is_none());
+        assert_eq!(
+            metadata["structured_data"]["exampleSDID@32473"]["eventID"],
+            "1011"
+        );
+    }
+
+    #[test]
+    fn test_severity_mapping() {
+        assert_eq!(severity_to_level(0), "fatal");
+        assert_eq!(severity_to_level(3), "error");
+        assert_eq!(severity_to_level(4), "warn");
+        assert_eq!(severity_to_level(6), "info");
+        assert_eq!(severity_to_level(7), "debug");
+    }
+
+    #[test]
+    fn test_parses_nil_fields_and_escaped_values() {
+        let log =
+            parse_syslog_line(r#"<11>1 - - - - - [meta path="a\]b\"c"] disk failure"#).unwrap();
+
+        assert_eq!(log.level, "error");
+        assert_eq!(log.timestamp, None);
+        assert_eq!(log.source, None);
+        assert_eq!(
+            log.metadata.unwrap()["structured_data"]["meta"]["path"],
+            "a]b\"c"
+        );
+    }
+
+    #[test]
+    fn test_rejects_malformed_lines() {
+        assert!(parse_syslog_line("plain text line").is_err());
+        assert!(parse_syslog_line("<999>1 - - - - - - msg").is_err());
+        assert!(parse_syslog_line("<13>2 - - - - - - msg").is_err());
+        assert!(parse_syslog_line("<13>1 yesterday host app - - - msg").is_err());
+        assert!(parse_syslog_line("<13>1 - host app - - [unterminated msg").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_malformed_line_goes_to_dead_letter() {
+        let log_repo = Arc::new(InMemoryLogRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        let service = LogService::new(
+            log_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+
+        let body = format!("{}\r\n\nnot a syslog line\n", WELL_FORMED);
+        let response = service
+            .ingest_raw(parse_syslog_batch("project-1", &body))
+            .await
+            .unwrap();
+
+        assert_eq!(response.accepted, 1);
+        assert_eq!(response.rejected, 1);
+
+        let saved = log_repo.saved();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].level(), LogLevel::Info);
+
+        let dead_letters = log_repo.dead_letters();
+        assert_eq!(dead_letters.len(), 1);
+        assert_eq!(dead_letters[0].format(), SYSLOG_FORMAT);
+        assert_eq!(dead_letters[0].payload(), "not a syslog line");
+        assert_eq!(dead_letters[0].project_id().as_str(), "project-1");
+    }
+}
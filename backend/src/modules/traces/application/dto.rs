@@ -1,7 +1,11 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::modules::projects::domain::ServiceDisplay;
+
 // ==================== Ingest Commands ====================
 
 /// Single span input for ingestion
@@ -12,7 +16,9 @@ pub struct SpanInput {
     pub parent_span_id: Option<String>,
     pub name: String,
     pub kind: Option<String>,
-    pub start_time: DateTime<Utc>,
+    /// When missing, the project's missing-timestamp policy applies
+    #[serde(default)]
+    pub start_time: Option<DateTime<Utc>>,
     pub end_time: Option<DateTime<Utc>>,
     pub status: Option<String>,
     pub status_message: Option<String>,
@@ -26,6 +32,12 @@ pub struct SpanInput {
     pub events: Vec<SpanEventInput>,
     #[serde(default)]
     pub links: Vec<SpanLinkInput>,
+    /// W3C tracestate; an OpenTelemetry `th` threshold sets the sampling probability
+    #[serde(default)]
+    pub trace_state: Option<String>,
+    /// Attributes the sender already discarded
+    #[serde(default)]
+    pub dropped_attributes_count: u32,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -59,6 +71,8 @@ pub struct TraceQueryFilters {
     pub service_name: Option<String>,
     pub span_name: Option<String>,
     pub status: Option<String>,
+    /// Only spans of this kind: internal, server, client, producer or consumer
+    pub span_kind: Option<String>,
     pub start_time: Option<DateTime<Utc>>,
     pub end_time: Option<DateTime<Utc>>,
     pub min_duration_ms: Option<i64>,
@@ -67,6 +81,56 @@ pub struct TraceQueryFilters {
     pub offset: Option<i64>,
 }
 
+/// Query for span counts
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SpanCountQuery {
+    pub service_name: Option<String>,
+    pub span_name: Option<String>,
+    pub status: Option<String>,
+    pub span_kind: Option<String>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+    /// Scale each span by its inverse sampling probability to estimate true volume
+    #[serde(default)]
+    pub adjusted: bool,
+}
+
+/// Command to count spans
+#[derive(Debug, Clone)]
+pub struct CountSpansCommand {
+    pub project_id: String,
+    pub query: SpanCountQuery,
+    pub requesting_user_id: String,
+    /// Skip the query result cache
+    pub bypass_cache: bool,
+}
+
+/// Query for a span duration histogram
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DurationHistogramQuery {
+    pub service_name: Option<String>,
+    pub span_name: Option<String>,
+    pub status: Option<String>,
+    /// Only spans of this kind, e.g. "server" for request latency
+    pub span_kind: Option<String>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+    /// Comma-separated ascending bucket upper bounds in milliseconds
+    pub buckets: Option<String>,
+    /// Buckets to derive from the observed duration range when `buckets` is omitted
+    pub bucket_count: Option<usize>,
+}
+
+/// Command to build a span duration histogram
+#[derive(Debug, Clone)]
+pub struct DurationHistogramCommand {
+    pub project_id: String,
+    pub query: DurationHistogramQuery,
+    pub requesting_user_id: String,
+    /// Skip the query result cache
+    pub bypass_cache: bool,
+}
+
 /// Command to search traces
 #[derive(Debug, Clone)]
 pub struct SearchTracesCommand {
@@ -83,6 +147,15 @@ pub struct GetTraceCommand {
     pub requesting_user_id: String,
 }
 
+/// Command to compare two traces span by span
+#[derive(Debug, Clone)]
+pub struct CompareTracesCommand {
+    pub project_id: String,
+    pub base_trace_id: String,
+    pub compare_trace_id: String,
+    pub requesting_user_id: String,
+}
+
 /// Command to list service names
 #[derive(Debug, Clone)]
 pub struct ListServicesCommand {
@@ -96,6 +169,24 @@ pub struct ListServicesCommand {
 #[derive(Debug, Clone, Serialize)]
 pub struct IngestSpansResponse {
     pub ingested: u32,
+    /// Spans already stored under the same trace and span id, ignored or
+    /// updated per the project's duplicate span policy
+    pub duplicates: u32,
+    /// Spans started at the server's receive time because they had no start time
+    pub server_timestamps: u32,
+    /// Span and event attributes dropped beyond the per-span attribute limit
+    pub truncated_attributes: u32,
+    /// Events dropped beyond the per-span event limit
+    pub truncated_events: u32,
+    /// Spans dropped by the project's head sampling
+    pub sampled_out: u32,
+    /// Span attributes whose value couldn't be coerced to the type the
+    /// project configures for their key, dropped or flagged per its policy
+    pub uncoercible_attributes: u32,
+    /// Estimated clock skew in ms of each source found running ahead of the
+    /// server, keyed by service name
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub clock_skew_ms: BTreeMap<String, i64>,
 }
 
 /// Span response for API
@@ -118,6 +209,14 @@ pub struct SpanResponse {
     pub attributes: Value,
     pub events: Vec<SpanEventResponse>,
     pub links: Vec<SpanLinkResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sampling_probability: Option<f64>,
+    pub dropped_attributes_count: u32,
+    /// Display details for `service_name`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<ServiceDisplay>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -140,11 +239,16 @@ pub struct TraceSummaryResponse {
     pub trace_id: String,
     pub root_span_name: Option<String>,
     pub services: Vec<String>,
+    /// Display details for each of `services`, in the same order
+    pub service_details: Vec<ServiceDisplay>,
     pub span_count: i64,
     pub error_count: i64,
     pub start_time: DateTime<Utc>,
     pub end_time: Option<DateTime<Utc>>,
     pub duration_ms: Option<f64>,
+    /// A span was received within the project's completeness window, so
+    /// more may still arrive
+    pub incomplete: bool,
 }
 
 /// Response for trace search
@@ -152,6 +256,33 @@ pub struct TraceSummaryResponse {
 pub struct TraceSearchResponse {
     pub traces: Vec<TraceSummaryResponse>,
     pub total: i64,
+    /// Effective page size after applying the default and maximum; sent as
+    /// the `x-pagination-limit` header
+    #[serde(skip)]
+    pub limit: i64,
+}
+
+/// Response for span counts
+#[derive(Debug, Clone, Serialize)]
+pub struct SpanCountResponse {
+    /// Stored span count, or the estimated true count when `adjusted` is set
+    pub count: f64,
+    pub adjusted: bool,
+}
+
+/// Span count for durations in `[lower_ms, upper_ms)`; the last bucket has no upper bound
+#[derive(Debug, Clone, Serialize)]
+pub struct DurationBucketResponse {
+    pub lower_ms: f64,
+    pub upper_ms: Option<f64>,
+    pub count: i64,
+}
+
+/// Span duration histogram
+#[derive(Debug, Clone, Serialize)]
+pub struct DurationHistogramResponse {
+    pub buckets: Vec<DurationBucketResponse>,
+    pub total: i64,
 }
 
 /// Full trace with all spans
@@ -160,11 +291,54 @@ pub struct TraceResponse {
     pub trace_id: String,
     pub spans: Vec<SpanResponse>,
     pub services: Vec<String>,
+    /// Display details for each of `services`, in the same order
+    pub service_details: Vec<ServiceDisplay>,
     pub duration_ms: Option<f64>,
+    /// A span was received within the project's completeness window, so
+    /// more may still arrive
+    pub incomplete: bool,
 }
 
 /// Response for service names list
 #[derive(Debug, Clone, Serialize)]
 pub struct ServicesResponse {
     pub services: Vec<String>,
+    /// Display details for each of `services`, in the same order
+    pub service_details: Vec<ServiceDisplay>,
+}
+
+/// Presence of an aligned span in the two compared traces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpanMatch {
+    Both,
+    BaseOnly,
+    CompareOnly,
+}
+
+/// One aligned span in a trace comparison
+#[derive(Debug, Clone, Serialize)]
+pub struct SpanComparisonResponse {
+    /// Normalized span name used for alignment
+    pub name: String,
+    pub service_name: Option<String>,
+    /// Depth in the span tree (root = 0)
+    pub depth: usize,
+    pub presence: SpanMatch,
+    pub base_span_id: Option<String>,
+    pub compare_span_id: Option<String>,
+    pub base_duration_ms: Option<f64>,
+    pub compare_duration_ms: Option<f64>,
+    /// compare - base, only set when the span is present in both traces
+    pub delta_ms: Option<f64>,
+}
+
+/// Side-by-side comparison of two traces
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceComparisonResponse {
+    pub base_trace_id: String,
+    pub compare_trace_id: String,
+    pub base_duration_ms: Option<f64>,
+    pub compare_duration_ms: Option<f64>,
+    pub spans: Vec<SpanComparisonResponse>,
 }
@@ -1,16 +1,41 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
 use serde_json::json;
 
 use crate::modules::auth::application::ports::IdGenerator;
 use crate::modules::auth::domain::UserId;
 use crate::modules::organizations::domain::{OrgId, OrganizationMemberRepository};
-use crate::modules::projects::domain::{ProjectId, ProjectRepository};
+use crate::modules::projects::domain::{
+    ClockSkewMode, ClockSkewSettings, CoercionFailurePolicy, DuplicateSpanPolicy, FeatureFlag,
+    MissingTimestampPolicy, ProjectId, ProjectRepository, ProjectSettings, ServiceDisplay,
+    ServiceMetadataRepository, TraceCompletenessMode, resolve_service_displays,
+};
 use crate::modules::traces::application::dto::*;
 use crate::modules::traces::domain::{
-    Pagination, Span, SpanEvent, SpanKind, SpanLink, SpanStatusCode, SpansRepository,
-    TraceFilters, TracesDomainError,
+    MAX_ATTRIBUTES_PER_SPAN, MAX_EVENTS_PER_SPAN, Span, SpanBatchLimits, SpanEvent, SpanKind,
+    SpanLink, SpanStatusCode, SpansRepository, TraceFilters, TracesDomainError, derive_http_status,
+    effective_service_name, head_sample, normalize_span_name, retain_attributes,
+    truncate_attributes,
 };
+use crate::shared::{IdFormatPolicy, PaginationConfig, QueryCache, TraceKeepSet};
+
+/// Buckets derived from the observed duration range when none are given
+const DEFAULT_HISTOGRAM_BUCKETS: usize = 10;
+
+/// Upper limit on histogram buckets, given or derived
+const MAX_HISTOGRAM_BUCKETS: usize = 50;
+
+const NANOS_PER_MS: f64 = 1_000_000.0;
+
+/// A span positioned in its trace tree, used to line up two traces
+struct AlignedSpan<'a> {
+    key: String,
+    name: String,
+    depth: usize,
+    span: &'a Span,
+}
 
 pub struct TraceService<SR, PR, OMR, ID>
 where
@@ -23,6 +48,14 @@ where
     project_repo: Arc<PR>,
     member_repo: Arc<OMR>,
     id_generator: Arc<ID>,
+    pagination: PaginationConfig,
+    id_format_policy: IdFormatPolicy,
+    batch_limits: SpanBatchLimits,
+    /// Display names for the services in query results; raw names are shown without it
+    service_metadata: Option<Arc<dyn ServiceMetadataRepository>>,
+    query_cache: Option<Arc<QueryCache>>,
+    /// Traces referenced by metric exemplars, kept by sampling
+    trace_keep_set: Option<Arc<TraceKeepSet>>,
 }
 
 impl<SR, PR, OMR, ID> TraceService<SR, PR, OMR, ID>
@@ -37,15 +70,130 @@ where
         project_repo: Arc<PR>,
         member_repo: Arc<OMR>,
         id_generator: Arc<ID>,
+        pagination: PaginationConfig,
     ) -> Self {
         Self {
             spans_repo,
             project_repo,
             member_repo,
             id_generator,
+            pagination,
+            id_format_policy: IdFormatPolicy::default(),
+            batch_limits: SpanBatchLimits::default(),
+            service_metadata: None,
+            query_cache: None,
+            trace_keep_set: None,
         }
     }
 
+    /// Set how trace and span ids on ingested spans are validated
+    pub fn with_id_format_policy(mut self, id_format_policy: IdFormatPolicy) -> Self {
+        self.id_format_policy = id_format_policy;
+        self
+    }
+
+    /// Set the span count and payload size caps for a single ingest request
+    pub fn with_batch_limits(mut self, batch_limits: SpanBatchLimits) -> Self {
+        self.batch_limits = batch_limits;
+        self
+    }
+
+    pub fn batch_limits(&self) -> SpanBatchLimits {
+        self.batch_limits
+    }
+
+    /// Resolve service display names in query results from `repo`
+    pub fn with_service_metadata(mut self, repo: Arc<dyn ServiceMetadataRepository>) -> Self {
+        self.service_metadata = Some(repo);
+        self
+    }
+
+    /// Cache results of aggregations over ranges that have stopped changing
+    pub fn with_query_cache(mut self, cache: Arc<QueryCache>) -> Self {
+        self.query_cache = Some(cache);
+        self
+    }
+
+    /// Keep traces that metric exemplars recorded in `keep_set` point to
+    pub fn with_trace_keep_set(mut self, keep_set: Arc<TraceKeepSet>) -> Self {
+        self.trace_keep_set = Some(keep_set);
+        self
+    }
+
+    /// Whether a metric exemplar recently referenced the trace
+    fn exemplar_linked(&self, project_id: &ProjectId, trace_id: &str) -> bool {
+        self.trace_keep_set
+            .as_ref()
+            .is_some_and(|keep_set| keep_set.contains(project_id.as_str(), trace_id))
+    }
+
+    /// The cache and key for a query, unless the query isn't cached
+    fn query_cache_key(
+        &self,
+        bypass: bool,
+        kind: &str,
+        project_id: &ProjectId,
+        end: Option<DateTime<Utc>>,
+        params: &impl std::fmt::Debug,
+    ) -> Option<(&QueryCache, String)> {
+        let cache = self.query_cache.as_deref().filter(|_| !bypass)?;
+        let key = cache.key(kind, project_id.as_str(), end, params)?;
+        Some((cache, key))
+    }
+
+    /// Look up display details for all of `names` at once
+    async fn service_displays(
+        &self,
+        project_id: &ProjectId,
+        names: &[String],
+    ) -> Result<HashMap<String, ServiceDisplay>, TracesDomainError> {
+        resolve_service_displays(self.service_metadata.as_deref(), project_id, names)
+            .await
+            .map_err(|e| TracesDomainError::InternalError(e.to_string()))
+    }
+
+    fn details_for(
+        names: &[String],
+        displays: &HashMap<String, ServiceDisplay>,
+    ) -> Vec<ServiceDisplay> {
+        names
+            .iter()
+            .map(|name| {
+                displays
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| ServiceDisplay::resolve(name, None))
+            })
+            .collect()
+    }
+
+    fn parse_trace_id(&self, id: &str) -> Result<String, TracesDomainError> {
+        self.id_format_policy
+            .trace_id(id)
+            .map_err(TracesDomainError::InvalidTraceId)
+    }
+
+    fn parse_span_id(&self, id: &str) -> Result<String, TracesDomainError> {
+        self.id_format_policy
+            .span_id(id)
+            .map_err(TracesDomainError::InvalidSpanId)
+    }
+
+    /// Load the project's ingestion settings
+    async fn ingest_settings(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<ProjectSettings, TracesDomainError> {
+        let project = self
+            .project_repo
+            .find_by_id(project_id)
+            .await
+            .map_err(|e| TracesDomainError::InternalError(e.to_string()))?
+            .ok_or(TracesDomainError::ProjectNotFound)?;
+
+        Ok(project.settings().clone())
+    }
+
     async fn verify_project_access(
         &self,
         project_id: &ProjectId,
@@ -78,7 +226,7 @@ where
         Ok(())
     }
 
-    fn span_to_response(span: &Span) -> SpanResponse {
+    fn span_to_response(span: &Span, services: &HashMap<String, ServiceDisplay>) -> SpanResponse {
         let duration_ms = span.duration_ns().map(|ns| ns as f64 / 1_000_000.0);
 
         SpanResponse {
@@ -115,7 +263,99 @@ where
                     attributes: l.attributes.clone(),
                 })
                 .collect(),
+            trace_state: span.trace_state().map(String::from),
+            sampling_probability: span.sampling_probability(),
+            dropped_attributes_count: span.dropped_attributes_count(),
+            service: span
+                .service_name()
+                .and_then(|name| services.get(name).cloned()),
+        }
+    }
+
+    /// Trace duration from the earliest span start to the latest span end
+    fn trace_duration_ms(spans: &[Span]) -> Option<f64> {
+        let min_start = spans.iter().map(|s| s.start_time()).min();
+        let max_end = spans.iter().filter_map(|s| s.end_time()).max();
+
+        match (min_start, max_end) {
+            (Some(start), Some(end)) => {
+                let duration_ns = (end - start).num_nanoseconds().unwrap_or(0);
+                Some(duration_ns as f64 / 1_000_000.0)
+            }
+            _ => None,
+        }
+    }
+
+    /// Flatten a trace into depth-first order, keying each span by the normalized
+    /// names on its path from the root plus its occurrence among identical siblings
+    fn aligned_spans(spans: &[Span]) -> Vec<AlignedSpan<'_>> {
+        let span_ids: std::collections::HashSet<&str> = spans.iter().map(|s| s.span_id()).collect();
+        let mut children: HashMap<Option<&str>, Vec<&Span>> = HashMap::new();
+        for span in spans {
+            // Spans whose parent is missing from the trace are treated as roots
+            let parent = span.parent_span_id().filter(|p| span_ids.contains(p));
+            children.entry(parent).or_default().push(span);
+        }
+        for siblings in children.values_mut() {
+            siblings.sort_by_key(|s| s.start_time());
+        }
+
+        let mut result = Vec::with_capacity(spans.len());
+        let mut occurrences: HashMap<String, usize> = HashMap::new();
+        let mut stack: Vec<(&Span, usize, String)> = children
+            .get(&None)
+            .map(|roots| roots.iter().rev().map(|s| (*s, 0, String::new())).collect())
+            .unwrap_or_default();
+
+        while let Some((span, depth, parent_path)) = stack.pop() {
+            let name = normalize_span_name(span.name());
+            let path = format!(
+                "{}/{}:{}",
+                parent_path,
+                span.service_name().unwrap_or(""),
+                name
+            );
+            let occurrence = occurrences.entry(path.clone()).or_insert(0);
+            let key = format!("{}#{}", path, occurrence);
+            *occurrence += 1;
+
+            if let Some(kids) = children.get(&Some(span.span_id())) {
+                for child in kids.iter().rev() {
+                    stack.push((*child, depth + 1, key.clone()));
+                }
+            }
+
+            result.push(AlignedSpan {
+                key,
+                name,
+                depth,
+                span,
+            });
+        }
+
+        result
+    }
+
+    /// Estimated skew in ms of each source whose clocks run ahead of the
+    /// server. A span can't end after the server received it, so a source's
+    /// latest span time beyond `received_at` plus the tolerance is skew.
+    /// Clocks running behind look like export delay and aren't estimated.
+    fn clock_skews(
+        spans: &[Span],
+        received_at: DateTime<Utc>,
+        settings: &ClockSkewSettings,
+    ) -> BTreeMap<String, i64> {
+        let mut skews = BTreeMap::new();
+        for span in spans {
+            let reported = span.end_time().unwrap_or(span.start_time());
+            let skew = (reported - received_at).num_milliseconds();
+            if skew > settings.tolerance_ms as i64 {
+                let source = span.service_name().unwrap_or("unknown").to_string();
+                let entry = skews.entry(source).or_insert(skew);
+                *entry = (*entry).max(skew);
+            }
         }
+        skews
     }
 
     /// Ingest spans (called via API key auth, no user verification needed)
@@ -123,11 +363,48 @@ where
         &self,
         cmd: IngestSpansCommand,
     ) -> Result<IngestSpansResponse, TracesDomainError> {
+        if cmd.spans.len() > self.batch_limits.max_spans {
+            return Err(TracesDomainError::BatchTooLarge(format!(
+                "{} spans exceeds the limit of {}",
+                cmd.spans.len(),
+                self.batch_limits.max_spans
+            )));
+        }
+
         let project_id = ProjectId::new(cmd.project_id);
+        let settings = self.ingest_settings(&project_id).await?;
+        let received_at = Utc::now();
+        let mut server_timestamps = 0u32;
+        let mut truncated_attributes = 0u32;
+        let mut truncated_events = 0u32;
+        let mut sampled_out = 0u32;
+        let mut uncoercible_attributes = 0u32;
 
-        let mut spans = Vec::with_capacity(cmd.spans.len());
+        let sampling = settings
+            .feature_enabled(FeatureFlag::TraceSampling)
+            .then_some(&settings.trace_sampling);
+        // Spans with their base sampling rate, sampled once the whole batch is known
+        let mut candidates = Vec::with_capacity(cmd.spans.len());
+
+        for mut input in cmd.spans {
+            let trace_id = self.parse_trace_id(&input.trace_id)?;
+            let span_id = self.parse_span_id(&input.span_id)?;
+            let start_time = match input.start_time {
+                Some(start_time) => start_time,
+                None if settings.missing_timestamp_policy == MissingTimestampPolicy::Reject => {
+                    return Err(TracesDomainError::MissingStartTime);
+                }
+                None => {
+                    server_timestamps += 1;
+                    received_at
+                }
+            };
+            let parent_span_id = input
+                .parent_span_id
+                .as_deref()
+                .map(|id| self.parse_span_id(id))
+                .transpose()?;
 
-        for input in cmd.spans {
             let kind = input
                 .kind
                 .as_deref()
@@ -141,53 +418,165 @@ where
                 .map(SpanStatusCode::from_str)
                 .transpose()?
                 .unwrap_or_default();
+            // Coerced first so the status and service name see the configured types
+            let uncoercible = settings.span_attribute_types.apply(&mut input.attributes);
+            uncoercible_attributes += uncoercible.len() as u32;
+            let (coercion_dropped, uncoercible) = match settings.span_attribute_types.on_failure {
+                CoercionFailurePolicy::Drop => (uncoercible.len() as u32, Vec::new()),
+                CoercionFailurePolicy::Flag => (0, uncoercible),
+            };
+            let status = match settings.http_error_status_threshold {
+                Some(threshold) => derive_http_status(status, &input.attributes, threshold),
+                None => status,
+            };
 
-            let events: Vec<SpanEvent> = input
-                .events
+            let mut event_inputs = input.events;
+            if event_inputs.len() > MAX_EVENTS_PER_SPAN {
+                truncated_events += (event_inputs.len() - MAX_EVENTS_PER_SPAN) as u32;
+                event_inputs.truncate(MAX_EVENTS_PER_SPAN);
+            }
+            let events: Vec<SpanEvent> = event_inputs
                 .into_iter()
-                .map(|e| SpanEvent {
-                    name: e.name,
-                    timestamp: e.timestamp,
-                    attributes: e.attributes,
+                .map(|mut e| {
+                    truncated_attributes +=
+                        truncate_attributes(&mut e.attributes, MAX_ATTRIBUTES_PER_SPAN);
+                    SpanEvent {
+                        name: e.name,
+                        timestamp: e.timestamp,
+                        attributes: e.attributes,
+                    }
                 })
                 .collect();
 
             let links: Vec<SpanLink> = input
                 .links
                 .into_iter()
-                .map(|l| SpanLink {
-                    trace_id: l.trace_id,
-                    span_id: l.span_id,
-                    attributes: l.attributes,
+                .map(|l| {
+                    Ok(SpanLink {
+                        trace_id: self.parse_trace_id(&l.trace_id)?,
+                        span_id: self.parse_span_id(&l.span_id)?,
+                        attributes: l.attributes,
+                    })
                 })
-                .collect();
+                .collect::<Result<_, TracesDomainError>>()?;
+
+            let service_name = effective_service_name(
+                &settings.service_name_attributes,
+                &input.resource_attributes,
+                &input.attributes,
+                input.service_name,
+            );
+            let sampling_rate =
+                sampling.map_or(1.0, |policy| policy.rate_for(service_name.as_deref()));
+
+            // Filtered and truncated only after status and service name are derived from the full set
+            let mut attributes = input.attributes;
+            let filtered = if settings.span_attribute_filter.is_empty() {
+                0
+            } else {
+                retain_attributes(&mut attributes, |key| {
+                    settings.span_attribute_filter.keeps(key)
+                })
+            };
+            let truncated = truncate_attributes(&mut attributes, MAX_ATTRIBUTES_PER_SPAN);
+            truncated_attributes += truncated;
 
             let span = Span::new(
                 self.id_generator.generate(),
                 project_id.clone(),
-                input.trace_id,
-                input.span_id,
-                input.parent_span_id,
+                trace_id,
+                span_id,
+                parent_span_id,
                 input.name,
                 kind,
-                input.start_time,
+                start_time,
                 input.end_time,
                 status,
                 input.status_message,
-                input.service_name,
+                service_name,
                 input.service_version,
                 input.resource_attributes,
-                input.attributes,
+                attributes,
                 events,
                 links,
-            );
+            )
+            .with_trace_state(input.trace_state)
+            .with_dropped_attributes(
+                input.dropped_attributes_count + coercion_dropped + filtered + truncated,
+            )
+            .with_uncoercible_attributes(uncoercible);
 
-            spans.push(span);
+            candidates.push((span, sampling_rate));
         }
 
-        let ingested = self.spans_repo.save_batch(&spans).await?;
+        // Traces with an error or slow span, or that an exemplar points to, are kept
+        // whole; the rest are sampled at their base rate
+        let always_kept: HashSet<String> = sampling
+            .map(|policy| {
+                candidates
+                    .iter()
+                    .filter(|(span, _)| {
+                        policy.always_keeps(
+                            span.status() == SpanStatusCode::Error,
+                            span.duration_ns(),
+                        ) || (policy.keep_exemplar_traces
+                            && self.exemplar_linked(&project_id, span.trace_id()))
+                    })
+                    .map(|(span, _)| span.trace_id().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut spans = Vec::with_capacity(candidates.len());
+        for (span, sampling_rate) in candidates {
+            if always_kept.contains(span.trace_id()) {
+                spans.push(span);
+            } else if head_sample(span.trace_id(), sampling_rate) {
+                spans.push(span.with_head_sampling(sampling_rate));
+            } else {
+                sampled_out += 1;
+            }
+        }
 
-        Ok(IngestSpansResponse { ingested })
+        let clock_skew_ms = match settings.clock_skew.mode {
+            ClockSkewMode::Off => BTreeMap::new(),
+            _ => Self::clock_skews(&spans, received_at, &settings.clock_skew),
+        };
+        if !clock_skew_ms.is_empty() {
+            let max_correction = settings.clock_skew.max_correction_ms as i64;
+            spans = spans
+                .into_iter()
+                .map(|span| {
+                    let source = span.service_name().unwrap_or("unknown");
+                    let Some(&skew) = clock_skew_ms.get(source) else {
+                        return span;
+                    };
+                    let correction = match settings.clock_skew.mode {
+                        ClockSkewMode::Adjust => skew.min(max_correction),
+                        _ => 0,
+                    };
+                    span.with_clock_skew(skew, correction)
+                })
+                .collect();
+        }
+
+        let result = self
+            .spans_repo
+            .save_batch(
+                &spans,
+                settings.duplicate_span_policy == DuplicateSpanPolicy::Update,
+            )
+            .await?;
+
+        Ok(IngestSpansResponse {
+            ingested: result.saved,
+            duplicates: result.duplicates,
+            server_timestamps,
+            truncated_attributes,
+            truncated_events,
+            sampled_out,
+            uncoercible_attributes,
+            clock_skew_ms,
+        })
     }
 
     /// Search traces (requires user auth)
@@ -205,60 +594,293 @@ where
             .as_deref()
             .map(SpanStatusCode::from_str)
             .transpose()?;
+        let span_kind = cmd
+            .filters
+            .span_kind
+            .as_deref()
+            .map(SpanKind::from_str)
+            .transpose()?;
+
+        let completeness = self.ingest_settings(&project_id).await?.trace_completeness;
+        let settled_before = completeness.settled_before(Utc::now());
+        let buffered = completeness.mode == TraceCompletenessMode::Buffer;
 
         let filters = TraceFilters {
             service_name: cmd.filters.service_name,
             span_name: cmd.filters.span_name,
             status,
+            span_kind,
             start_time: cmd.filters.start_time,
             end_time: cmd.filters.end_time,
             min_duration_ns: cmd.filters.min_duration_ms.map(|ms| ms * 1_000_000),
             max_duration_ns: cmd.filters.max_duration_ms.map(|ms| ms * 1_000_000),
+            settled_before: settled_before.filter(|_| buffered),
         };
 
-        let pagination = Pagination {
-            limit: cmd.filters.limit.unwrap_or(100).min(1000),
-            offset: cmd.filters.offset.unwrap_or(0),
-        };
+        let pagination = self
+            .pagination
+            .resolve(cmd.filters.limit, cmd.filters.offset);
 
         let result = self
             .spans_repo
             .search_traces(&project_id, &filters, &pagination)
             .await?;
 
+        let names: Vec<String> = result
+            .traces
+            .iter()
+            .flat_map(|t| t.service_names.iter().cloned())
+            .collect();
+        let displays = self.service_displays(&project_id, &names).await?;
+
         let traces = result
             .traces
             .into_iter()
             .map(|t| TraceSummaryResponse {
                 trace_id: t.trace_id,
                 root_span_name: t.root_span_name,
+                service_details: Self::details_for(&t.service_names, &displays),
                 services: t.service_names,
                 span_count: t.span_count,
                 error_count: t.error_count,
                 start_time: t.start_time,
                 end_time: t.end_time,
                 duration_ms: t.duration_ns.map(|ns| ns as f64 / 1_000_000.0),
+                incomplete: settled_before.is_some_and(|at| t.last_received_at > at),
             })
             .collect();
 
         Ok(TraceSearchResponse {
             traces,
             total: result.total,
+            limit: pagination.limit,
         })
     }
 
+    /// Count spans, optionally scaled up by sampling probability (requires user auth)
+    pub async fn count_spans(
+        &self,
+        cmd: CountSpansCommand,
+    ) -> Result<SpanCountResponse, TracesDomainError> {
+        let project_id = ProjectId::new(cmd.project_id);
+        self.verify_project_access(&project_id, &cmd.requesting_user_id)
+            .await?;
+
+        let cached = self.query_cache_key(
+            cmd.bypass_cache,
+            "traces.count_spans",
+            &project_id,
+            cmd.query.end_time,
+            &cmd.query,
+        );
+        if let Some((cache, key)) = &cached
+            && let Some(response) = cache.get(key)
+        {
+            return Ok(response);
+        }
+
+        let status = cmd
+            .query
+            .status
+            .as_deref()
+            .map(SpanStatusCode::from_str)
+            .transpose()?;
+        let span_kind = cmd
+            .query
+            .span_kind
+            .as_deref()
+            .map(SpanKind::from_str)
+            .transpose()?;
+
+        let filters = TraceFilters {
+            service_name: cmd.query.service_name,
+            span_name: cmd.query.span_name,
+            status,
+            span_kind,
+            start_time: cmd.query.start_time,
+            end_time: cmd.query.end_time,
+            ..Default::default()
+        };
+
+        let counts = self.spans_repo.count_spans(&project_id, &filters).await?;
+
+        let response = SpanCountResponse {
+            count: if cmd.query.adjusted {
+                counts.adjusted
+            } else {
+                counts.spans as f64
+            },
+            adjusted: cmd.query.adjusted,
+        };
+        if let Some((cache, key)) = cached {
+            cache.insert(key, response.clone());
+        }
+        Ok(response)
+    }
+
+    /// Histogram of span durations (requires user auth).
+    /// Bucket boundaries are taken from the query or spread log-uniformly over the observed range.
+    pub async fn duration_histogram(
+        &self,
+        cmd: DurationHistogramCommand,
+    ) -> Result<DurationHistogramResponse, TracesDomainError> {
+        let project_id = ProjectId::new(cmd.project_id);
+        self.verify_project_access(&project_id, &cmd.requesting_user_id)
+            .await?;
+
+        let cached = self.query_cache_key(
+            cmd.bypass_cache,
+            "traces.duration_histogram",
+            &project_id,
+            cmd.query.end_time,
+            &cmd.query,
+        );
+        if let Some((cache, key)) = &cached
+            && let Some(response) = cache.get(key)
+        {
+            return Ok(response);
+        }
+
+        let status = cmd
+            .query
+            .status
+            .as_deref()
+            .map(SpanStatusCode::from_str)
+            .transpose()?;
+        let span_kind = cmd
+            .query
+            .span_kind
+            .as_deref()
+            .map(SpanKind::from_str)
+            .transpose()?;
+
+        let filters = TraceFilters {
+            service_name: cmd.query.service_name,
+            span_name: cmd.query.span_name,
+            status,
+            span_kind,
+            start_time: cmd.query.start_time,
+            end_time: cmd.query.end_time,
+            ..Default::default()
+        };
+
+        let boundaries = match cmd.query.buckets.as_deref() {
+            Some(buckets) => Self::parse_bucket_boundaries(buckets)?,
+            None => {
+                let bucket_count = cmd
+                    .query
+                    .bucket_count
+                    .unwrap_or(DEFAULT_HISTOGRAM_BUCKETS)
+                    .clamp(1, MAX_HISTOGRAM_BUCKETS);
+                match self
+                    .spans_repo
+                    .duration_range(&project_id, &filters)
+                    .await?
+                {
+                    Some((min, max)) => Self::derive_bucket_boundaries(min, max, bucket_count),
+                    None => vec![],
+                }
+            }
+        };
+
+        let buckets = self
+            .spans_repo
+            .duration_histogram(&project_id, &filters, &boundaries)
+            .await?;
+
+        let response = DurationHistogramResponse {
+            total: buckets.iter().map(|b| b.count).sum(),
+            buckets: buckets
+                .into_iter()
+                .map(|b| DurationBucketResponse {
+                    lower_ms: b.lower_ns as f64 / NANOS_PER_MS,
+                    upper_ms: b.upper_ns.map(|ns| ns as f64 / NANOS_PER_MS),
+                    count: b.count,
+                })
+                .collect(),
+        };
+        if let Some((cache, key)) = cached {
+            cache.insert(key, response.clone());
+        }
+        Ok(response)
+    }
+
+    /// Parse comma-separated millisecond boundaries into ascending nanosecond upper bounds
+    fn parse_bucket_boundaries(buckets: &str) -> Result<Vec<i64>, TracesDomainError> {
+        let boundaries = buckets
+            .split(',')
+            .map(|b| {
+                b.trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|ms| ms.is_finite() && *ms > 0.0)
+                    .map(|ms| (ms * NANOS_PER_MS).round() as i64)
+                    .ok_or_else(|| {
+                        TracesDomainError::InvalidHistogramBuckets(format!(
+                            "'{}' is not a positive number of milliseconds",
+                            b.trim()
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if boundaries.len() >= MAX_HISTOGRAM_BUCKETS {
+            return Err(TracesDomainError::InvalidHistogramBuckets(format!(
+                "at most {} boundaries are allowed",
+                MAX_HISTOGRAM_BUCKETS - 1
+            )));
+        }
+        if boundaries.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(TracesDomainError::InvalidHistogramBuckets(
+                "boundaries must be strictly ascending".to_string(),
+            ));
+        }
+        Ok(boundaries)
+    }
+
+    /// Log-spaced boundaries splitting [min, max] into `bucket_count` buckets.
+    /// The first bucket starts at 0 and the last is unbounded, so both ends are covered.
+    fn derive_bucket_boundaries(min_ns: i64, max_ns: i64, bucket_count: usize) -> Vec<i64> {
+        let min = min_ns.max(1) as f64;
+        let max = max_ns.max(1) as f64;
+        if max <= min {
+            return vec![];
+        }
+        let ratio = (max / min).powf(1.0 / bucket_count as f64);
+        let mut boundaries: Vec<i64> = (1..bucket_count)
+            .map(|i| (min * ratio.powi(i as i32)).round() as i64)
+            .collect();
+        boundaries.dedup();
+        boundaries
+    }
+
     /// Get a specific trace with all spans (requires user auth)
-    pub async fn get_trace(&self, cmd: GetTraceCommand) -> Result<TraceResponse, TracesDomainError> {
+    pub async fn get_trace(
+        &self,
+        cmd: GetTraceCommand,
+    ) -> Result<TraceResponse, TracesDomainError> {
         let project_id = ProjectId::new(cmd.project_id);
         self.verify_project_access(&project_id, &cmd.requesting_user_id)
             .await?;
 
-        let spans = self.spans_repo.get_trace(&project_id, &cmd.trace_id).await?;
+        let spans = self
+            .spans_repo
+            .get_trace(&project_id, &cmd.trace_id)
+            .await?;
 
         if spans.is_empty() {
             return Err(TracesDomainError::TraceNotFound);
         }
 
+        let completeness = self.ingest_settings(&project_id).await?.trace_completeness;
+        let incomplete = completeness
+            .settled_before(Utc::now())
+            .is_some_and(|at| spans.iter().any(|s| s.received_at() > at));
+        // Buffered traces aren't queryable until they settle
+        if incomplete && completeness.mode == TraceCompletenessMode::Buffer {
+            return Err(TracesDomainError::TraceNotFound);
+        }
+
         // Collect unique service names
         let services: Vec<String> = spans
             .iter()
@@ -268,25 +890,102 @@ where
             .into_iter()
             .collect();
 
-        // Calculate trace duration from min start_time to max end_time
-        let min_start = spans.iter().map(|s| s.start_time()).min();
-        let max_end = spans.iter().filter_map(|s| s.end_time()).max();
+        let duration_ms = Self::trace_duration_ms(&spans);
+        let displays = self.service_displays(&project_id, &services).await?;
 
-        let duration_ms = match (min_start, max_end) {
-            (Some(start), Some(end)) => {
-                let duration_ns = (end - start).num_nanoseconds().unwrap_or(0);
-                Some(duration_ns as f64 / 1_000_000.0)
-            }
-            _ => None,
-        };
-
-        let span_responses: Vec<SpanResponse> = spans.iter().map(Self::span_to_response).collect();
+        let span_responses: Vec<SpanResponse> = spans
+            .iter()
+            .map(|span| Self::span_to_response(span, &displays))
+            .collect();
 
         Ok(TraceResponse {
             trace_id: cmd.trace_id,
             spans: span_responses,
+            service_details: Self::details_for(&services, &displays),
             services,
             duration_ms,
+            incomplete,
+        })
+    }
+
+    /// Compare two traces span by span (requires user auth)
+    pub async fn compare_traces(
+        &self,
+        cmd: CompareTracesCommand,
+    ) -> Result<TraceComparisonResponse, TracesDomainError> {
+        let project_id = ProjectId::new(cmd.project_id);
+        self.verify_project_access(&project_id, &cmd.requesting_user_id)
+            .await?;
+
+        let base = self
+            .spans_repo
+            .get_trace(&project_id, &cmd.base_trace_id)
+            .await?;
+        let compare = self
+            .spans_repo
+            .get_trace(&project_id, &cmd.compare_trace_id)
+            .await?;
+
+        if base.is_empty() || compare.is_empty() {
+            return Err(TracesDomainError::TraceNotFound);
+        }
+
+        let base_spans = Self::aligned_spans(&base);
+        let compare_spans = Self::aligned_spans(&compare);
+        let mut unmatched: HashMap<&str, &AlignedSpan> =
+            compare_spans.iter().map(|a| (a.key.as_str(), a)).collect();
+
+        let to_ms = |span: &Span| span.duration_ns().map(|ns| ns as f64 / 1_000_000.0);
+
+        let mut spans = Vec::with_capacity(base_spans.len().max(compare_spans.len()));
+        for aligned in &base_spans {
+            let other = unmatched.remove(aligned.key.as_str());
+            let base_duration_ms = to_ms(aligned.span);
+            let compare_duration_ms = other.and_then(|o| to_ms(o.span));
+
+            spans.push(SpanComparisonResponse {
+                name: aligned.name.clone(),
+                service_name: aligned.span.service_name().map(String::from),
+                depth: aligned.depth,
+                presence: if other.is_some() {
+                    SpanMatch::Both
+                } else {
+                    SpanMatch::BaseOnly
+                },
+                base_span_id: Some(aligned.span.span_id().to_string()),
+                compare_span_id: other.map(|o| o.span.span_id().to_string()),
+                base_duration_ms,
+                compare_duration_ms,
+                delta_ms: base_duration_ms
+                    .zip(compare_duration_ms)
+                    .map(|(base, compare)| compare - base),
+            });
+        }
+
+        // Spans that only exist in the compared trace, in tree order
+        for aligned in compare_spans
+            .iter()
+            .filter(|a| unmatched.contains_key(a.key.as_str()))
+        {
+            spans.push(SpanComparisonResponse {
+                name: aligned.name.clone(),
+                service_name: aligned.span.service_name().map(String::from),
+                depth: aligned.depth,
+                presence: SpanMatch::CompareOnly,
+                base_span_id: None,
+                compare_span_id: Some(aligned.span.span_id().to_string()),
+                base_duration_ms: None,
+                compare_duration_ms: to_ms(aligned.span),
+                delta_ms: None,
+            });
+        }
+
+        Ok(TraceComparisonResponse {
+            base_trace_id: cmd.base_trace_id,
+            compare_trace_id: cmd.compare_trace_id,
+            base_duration_ms: Self::trace_duration_ms(&base),
+            compare_duration_ms: Self::trace_duration_ms(&compare),
+            spans,
         })
     }
 
@@ -300,7 +999,1400 @@ where
             .await?;
 
         let services = self.spans_repo.get_service_names(&project_id).await?;
+        let displays = self.service_displays(&project_id, &services).await?;
+
+        Ok(ServicesResponse {
+            service_details: Self::details_for(&services, &displays),
+            services,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+
+    use crate::modules::organizations::domain::OrgRole;
+    use crate::modules::traces::domain::{
+        DurationBucket, ErrorRateUnit, Pagination, SpanCounts, SpanKind, SpanSaveResult,
+        SpanStatusCode, TraceCutoffs, TraceSearchResult,
+    };
+    use crate::shared::testing::{
+        InMemoryMemberRepository, InMemoryProjectRepository, InMemoryServiceMetadataRepository,
+        InMemorySpansRepository, SequentialIdGenerator,
+    };
+
+    /// Spans repository that records the pagination it was queried with
+    struct RecordingSpansRepository {
+        last_pagination: Mutex<Option<Pagination>>,
+        traces: Mutex<HashMap<String, Vec<Span>>>,
+    }
+
+    #[async_trait]
+    impl SpansRepository for RecordingSpansRepository {
+        async fn save_batch(
+            &self,
+            spans: &[Span],
+            _update_duplicates: bool,
+        ) -> Result<SpanSaveResult, TracesDomainError> {
+            Ok(SpanSaveResult {
+                saved: spans.len() as u32,
+                duplicates: 0,
+            })
+        }
+
+        async fn get_trace(
+            &self,
+            _project_id: &ProjectId,
+            trace_id: &str,
+        ) -> Result<Vec<Span>, TracesDomainError> {
+            Ok(self
+                .traces
+                .lock()
+                .unwrap()
+                .get(trace_id)
+                .cloned()
+                .unwrap_or_default())
+        }
+
+        async fn search_traces(
+            &self,
+            _project_id: &ProjectId,
+            _filters: &TraceFilters,
+            pagination: &Pagination,
+        ) -> Result<TraceSearchResult, TracesDomainError> {
+            *self.last_pagination.lock().unwrap() = Some(*pagination);
+            Ok(TraceSearchResult {
+                traces: vec![],
+                total: 0,
+            })
+        }
+
+        async fn count_spans(
+            &self,
+            _project_id: &ProjectId,
+            _filters: &TraceFilters,
+        ) -> Result<SpanCounts, TracesDomainError> {
+            Ok(SpanCounts::default())
+        }
+
+        async fn duration_histogram(
+            &self,
+            _project_id: &ProjectId,
+            _filters: &TraceFilters,
+            bucket_boundaries: &[i64],
+        ) -> Result<Vec<DurationBucket>, TracesDomainError> {
+            Ok(DurationBucket::empty_buckets(bucket_boundaries))
+        }
+
+        async fn count_errors(
+            &self,
+            _project_id: &ProjectId,
+            _filters: &TraceFilters,
+            _unit: ErrorRateUnit,
+        ) -> Result<(i64, i64), TracesDomainError> {
+            Ok((0, 0))
+        }
 
-        Ok(ServicesResponse { services })
+        async fn duration_range(
+            &self,
+            _project_id: &ProjectId,
+            _filters: &TraceFilters,
+        ) -> Result<Option<(i64, i64)>, TracesDomainError> {
+            Ok(None)
+        }
+
+        async fn find_in_range(
+            &self,
+            _project_id: &ProjectId,
+            _start: DateTime<Utc>,
+            _end: DateTime<Utc>,
+        ) -> Result<Vec<Span>, TracesDomainError> {
+            Ok(vec![])
+        }
+
+        async fn get_service_names(
+            &self,
+            _project_id: &ProjectId,
+        ) -> Result<Vec<String>, TracesDomainError> {
+            Ok(vec![])
+        }
+
+        async fn delete_before(
+            &self,
+            _project_id: &ProjectId,
+            _before: DateTime<Utc>,
+            _limit: u32,
+        ) -> Result<u64, TracesDomainError> {
+            Ok(0)
+        }
+
+        async fn delete_expired_traces(
+            &self,
+            _project_id: &ProjectId,
+            _cutoffs: &TraceCutoffs,
+            _limit: u32,
+        ) -> Result<u64, TracesDomainError> {
+            Ok(0)
+        }
+    }
+
+    fn create_service() -> (
+        TraceService<
+            RecordingSpansRepository,
+            InMemoryProjectRepository,
+            InMemoryMemberRepository,
+            SequentialIdGenerator,
+        >,
+        Arc<RecordingSpansRepository>,
+    ) {
+        let spans_repo = Arc::new(RecordingSpansRepository {
+            last_pagination: Mutex::new(None),
+            traces: Mutex::new(HashMap::new()),
+        });
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        project_repo.seed("project-1", "org-1");
+        member_repo.seed("org-1", "user-1", OrgRole::Member);
+
+        let service = TraceService::new(
+            spans_repo.clone(),
+            project_repo,
+            member_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::new(20, 50),
+        );
+        (service, spans_repo)
+    }
+
+    fn search_command(limit: Option<i64>) -> SearchTracesCommand {
+        SearchTracesCommand {
+            project_id: "project-1".to_string(),
+            filters: TraceQueryFilters {
+                limit,
+                ..Default::default()
+            },
+            requesting_user_id: "user-1".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_traces_clamps_limit_to_max() {
+        let (service, spans_repo) = create_service();
+
+        let response = service
+            .search_traces(search_command(Some(5000)))
+            .await
+            .unwrap();
+
+        assert_eq!(response.limit, 50);
+        assert_eq!(
+            spans_repo.last_pagination.lock().unwrap().unwrap().limit,
+            50
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_traces_uses_default_limit() {
+        let (service, spans_repo) = create_service();
+
+        let response = service.search_traces(search_command(None)).await.unwrap();
+
+        assert_eq!(response.limit, 20);
+        assert_eq!(
+            spans_repo.last_pagination.lock().unwrap().unwrap().limit,
+            20
+        );
+    }
+
+    /// Build a span starting at `start_ms` and lasting `duration_ms` milliseconds
+    fn span(
+        trace_id: &str,
+        span_id: &str,
+        parent: Option<&str>,
+        name: &str,
+        start_ms: i64,
+        duration_ms: i64,
+    ) -> Span {
+        let start = DateTime::from_timestamp_millis(1_700_000_000_000 + start_ms).unwrap();
+        Span::new(
+            format!("{}-{}", trace_id, span_id),
+            ProjectId::new("project-1".to_string()),
+            trace_id.to_string(),
+            span_id.to_string(),
+            parent.map(String::from),
+            name.to_string(),
+            SpanKind::Server,
+            start,
+            Some(start + chrono::Duration::milliseconds(duration_ms)),
+            SpanStatusCode::Ok,
+            None,
+            Some("api".to_string()),
+            None,
+            json!({}),
+            json!({}),
+            vec![],
+            vec![],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_compare_traces_aligns_spans_by_normalized_path() {
+        let (service, spans_repo) = create_service();
+        {
+            let mut traces = spans_repo.traces.lock().unwrap();
+            traces.insert(
+                "trace-a".to_string(),
+                vec![
+                    span("trace-a", "a1", None, "GET /users/1", 0, 100),
+                    span("trace-a", "a2", Some("a1"), "SELECT users", 10, 40),
+                    span("trace-a", "a3", Some("a1"), "cache lookup", 60, 5),
+                ],
+            );
+            traces.insert(
+                "trace-b".to_string(),
+                vec![
+                    span("trace-b", "b1", None, "GET /users/2", 0, 150),
+                    span("trace-b", "b2", Some("b1"), "SELECT users", 10, 90),
+                ],
+            );
+        }
+
+        let response = service
+            .compare_traces(CompareTracesCommand {
+                project_id: "project-1".to_string(),
+                base_trace_id: "trace-a".to_string(),
+                compare_trace_id: "trace-b".to_string(),
+                requesting_user_id: "user-1".to_string(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.spans.len(), 3);
+
+        let root = &response.spans[0];
+        assert_eq!(root.name, "GET /users/{id}");
+        assert!(matches!(root.presence, SpanMatch::Both));
+        assert_eq!(root.delta_ms, Some(50.0));
+
+        let query = &response.spans[1];
+        assert_eq!(query.depth, 1);
+        assert_eq!(query.delta_ms, Some(50.0));
+
+        let cache = &response.spans[2];
+        assert!(matches!(cache.presence, SpanMatch::BaseOnly));
+        assert_eq!(cache.delta_ms, None);
+    }
+
+    #[tokio::test]
+    async fn test_compare_traces_missing_trace_is_not_found() {
+        let (service, _) = create_service();
+
+        let result = service
+            .compare_traces(CompareTracesCommand {
+                project_id: "project-1".to_string(),
+                base_trace_id: "missing".to_string(),
+                compare_trace_id: "also-missing".to_string(),
+                requesting_user_id: "user-1".to_string(),
+            })
+            .await;
+
+        assert!(matches!(result, Err(TracesDomainError::TraceNotFound)));
+    }
+
+    fn sampled_span_input(span_id: &str, trace_state: Option<&str>) -> SpanInput {
+        SpanInput {
+            trace_id: "4bf92f3577b34da6a3ce929d0e0e4736".to_string(),
+            span_id: span_id.to_string(),
+            parent_span_id: None,
+            name: "GET /users".to_string(),
+            kind: Some("server".to_string()),
+            start_time: Some(Utc::now()),
+            end_time: None,
+            status: None,
+            status_message: None,
+            service_name: Some("api".to_string()),
+            service_version: None,
+            resource_attributes: json!({}),
+            attributes: json!({}),
+            events: vec![],
+            links: vec![],
+            trace_state: trace_state.map(String::from),
+            dropped_attributes_count: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_count_spans_adjusts_for_sampling_probability() {
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        project_repo.seed("project-1", "org-1");
+        member_repo.seed("org-1", "user-1", OrgRole::Member);
+        let service = TraceService::new(
+            Arc::new(InMemorySpansRepository::new()),
+            project_repo,
+            member_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+
+        // Sampled at 10%: rejection threshold 0.9 * 2^56
+        service
+            .ingest(IngestSpansCommand {
+                project_id: "project-1".to_string(),
+                spans: vec![sampled_span_input(
+                    "00f067aa0ba902b7",
+                    Some("vendor=x,ot=th:e6666666666666;rv:0123456789abcd"),
+                )],
+            })
+            .await
+            .unwrap();
+
+        let count = |adjusted| {
+            service.count_spans(CountSpansCommand {
+                project_id: "project-1".to_string(),
+                query: SpanCountQuery {
+                    adjusted,
+                    ..Default::default()
+                },
+                requesting_user_id: "user-1".to_string(),
+                bypass_cache: false,
+            })
+        };
+
+        let adjusted = count(true).await.unwrap();
+        assert!(adjusted.adjusted);
+        assert!((adjusted.count - 10.0).abs() < 1e-9);
+        assert_eq!(count(false).await.unwrap().count, 1.0);
+
+        // Spans without a sampling threshold count once either way
+        service
+            .ingest(IngestSpansCommand {
+                project_id: "project-1".to_string(),
+                spans: vec![sampled_span_input("00f067aa0ba902b8", None)],
+            })
+            .await
+            .unwrap();
+        assert!((count(true).await.unwrap().count - 11.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_service_sampling_override_wins_over_project_rate() {
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let mut project = project_repo.seed("project-1", "org-1");
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({"trace_sampling": {"rate": 0.1, "services": {"payments": 1.0}}}))
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+        let service = TraceService::new(
+            Arc::new(InMemorySpansRepository::new()),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+        let start = Utc::now();
+        // 200 distinct traces per service
+        let spans = |service_name: &str, first: u32| {
+            (first..first + 200)
+                .map(|i| SpanInput {
+                    trace_id: format!("{:032x}", i),
+                    service_name: Some(service_name.to_string()),
+                    start_time: Some(start),
+                    ..sampled_span_input("00f067aa0ba902b7", None)
+                })
+                .collect::<Vec<_>>()
+        };
+        let ingest = |spans| {
+            service.ingest(IngestSpansCommand {
+                project_id: "project-1".to_string(),
+                spans,
+            })
+        };
+
+        let payments = ingest(spans("payments", 1)).await.unwrap();
+        assert_eq!(payments.ingested, 200);
+        assert_eq!(payments.sampled_out, 0);
+
+        let api = ingest(spans("api", 1001)).await.unwrap();
+        assert!(
+            api.ingested > 0 && api.ingested < 50,
+            "kept {}",
+            api.ingested
+        );
+        assert_eq!(api.ingested + api.sampled_out, 200);
+
+        // Decisions follow the trace id, so a resent batch keeps the same traces
+        let resent = ingest(spans("api", 1001)).await.unwrap();
+        assert_eq!(resent.sampled_out, api.sampled_out);
+        assert_eq!(resent.duplicates, api.ingested);
+    }
+
+    #[tokio::test]
+    async fn test_sampling_policy_keeps_error_and_slow_traces() {
+        let spans_repo = Arc::new(InMemorySpansRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let mut project = project_repo.seed("project-1", "org-1");
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({"trace_sampling": {"rate": 0.1, "latency_threshold_ms": 500}}))
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+        let service = TraceService::new(
+            spans_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+        let start = Utc::now();
+        let span = |trace: u32, span_id: &str, duration_ms: i64| SpanInput {
+            trace_id: format!("{:032x}", trace),
+            start_time: Some(start),
+            end_time: Some(start + chrono::Duration::milliseconds(duration_ms)),
+            ..sampled_span_input(span_id, None)
+        };
+        // Traces the base rate alone would drop
+        let mut dropped_by_rate = (1..).filter(|i: &u32| !head_sample(&format!("{:032x}", i), 0.1));
+        let error_trace = dropped_by_rate.next().unwrap();
+        let slow_trace = dropped_by_rate.next().unwrap();
+
+        let mut spans = vec![
+            SpanInput {
+                status: Some("error".to_string()),
+                ..span(error_trace, "00f067aa0ba902b1", 5)
+            },
+            span(slow_trace, "00f067aa0ba902b2", 800),
+            // A fast span of the slow trace is kept along with it
+            span(slow_trace, "00f067aa0ba902b3", 5),
+        ];
+        spans.extend((1001..1201).map(|i| span(i, "00f067aa0ba902b4", 5)));
+
+        let response = service
+            .ingest(IngestSpansCommand {
+                project_id: "project-1".to_string(),
+                spans,
+            })
+            .await
+            .unwrap();
+
+        let saved = spans_repo.saved();
+        let kept = |trace: u32| {
+            saved
+                .iter()
+                .filter(|s| s.trace_id() == format!("{:032x}", trace))
+                .count()
+        };
+        assert_eq!(kept(error_trace), 1);
+        assert_eq!(kept(slow_trace), 2);
+        let fast_kept = response.ingested - 3;
+        assert!(fast_kept > 0 && fast_kept < 50, "kept {}", fast_kept);
+        assert_eq!(fast_kept + response.sampled_out, 200);
+    }
+
+    #[tokio::test]
+    async fn test_exemplar_trace_kept_despite_zero_rate() {
+        let spans_repo = Arc::new(InMemorySpansRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let mut project = project_repo.seed("project-1", "org-1");
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({"trace_sampling": {"rate": 0.0}}))
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+        let keep_set = Arc::new(TraceKeepSet::new(crate::shared::TraceKeepSetConfig {
+            ttl: std::time::Duration::from_secs(60),
+            max_entries: 100,
+        }));
+        let service = TraceService::new(
+            spans_repo.clone(),
+            project_repo.clone(),
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        )
+        .with_trace_keep_set(keep_set.clone());
+        let linked = format!("{:032x}", 1);
+        keep_set.insert("project-1", &linked);
+        let span = |trace: u32| SpanInput {
+            trace_id: format!("{:032x}", trace),
+            ..sampled_span_input("00f067aa0ba902b7", None)
+        };
+        let ingest = || {
+            service.ingest(IngestSpansCommand {
+                project_id: "project-1".to_string(),
+                spans: vec![span(1), span(2)],
+            })
+        };
+
+        let response = ingest().await.unwrap();
+        assert_eq!(response.ingested, 1);
+        assert_eq!(response.sampled_out, 1);
+        assert_eq!(spans_repo.saved()[0].trace_id(), linked);
+
+        // The project can opt out, leaving exemplar traces to the base rate
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({"trace_sampling": {"rate": 0.0, "keep_exemplar_traces": false}}))
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+        let response = ingest().await.unwrap();
+        assert_eq!(response.ingested, 0);
+    }
+
+    #[tokio::test]
+    async fn test_ingest_rejects_malformed_trace_id() {
+        let spans_repo = Arc::new(InMemorySpansRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        let service = TraceService::new(
+            spans_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+        let mut input = sampled_span_input("00f067aa0ba902b7", None);
+        input.trace_id = "trace-1".to_string();
+
+        let result = service
+            .ingest(IngestSpansCommand {
+                project_id: "project-1".to_string(),
+                spans: vec![input.clone()],
+            })
+            .await;
+        assert!(matches!(result, Err(TracesDomainError::InvalidTraceId(_))));
+        let project_id = ProjectId::new("project-1".to_string());
+        assert!(
+            spans_repo
+                .get_trace(&project_id, "trace-1")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // The lenient policy keeps the old behaviour
+        let response = service
+            .with_id_format_policy(IdFormatPolicy::Lenient)
+            .ingest(IngestSpansCommand {
+                project_id: "project-1".to_string(),
+                spans: vec![input],
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.ingested, 1);
+    }
+
+    #[tokio::test]
+    async fn test_spans_grouped_by_preferred_service_attribute() {
+        let spans_repo = Arc::new(InMemorySpansRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        let mut project = project_repo.seed("project-1", "org-1");
+        project.update_settings(
+            project
+                .settings()
+                .merge(
+                    json!({ "service_name_attributes": ["k8s.deployment.name", "service.name"] }),
+                )
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+        member_repo.seed("org-1", "user-1", OrgRole::Member);
+        let service = TraceService::new(
+            spans_repo.clone(),
+            project_repo,
+            member_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+
+        // Two deployments report the same service.name; a third span has no deployment
+        let span = |span_id: &str, deployment: Option<&str>| SpanInput {
+            resource_attributes: match deployment {
+                Some(d) => json!({ "service.name": "api", "k8s.deployment.name": d }),
+                None => json!({ "service.name": "api" }),
+            },
+            ..sampled_span_input(span_id, None)
+        };
+        service
+            .ingest(IngestSpansCommand {
+                project_id: "project-1".to_string(),
+                spans: vec![
+                    span("00f067aa0ba902b1", Some("api-canary")),
+                    span("00f067aa0ba902b2", Some("api-stable")),
+                    span("00f067aa0ba902b3", None),
+                ],
+            })
+            .await
+            .unwrap();
+
+        let services = service
+            .list_services(ListServicesCommand {
+                project_id: "project-1".to_string(),
+                requesting_user_id: "user-1".to_string(),
+            })
+            .await
+            .unwrap()
+            .services;
+        assert_eq!(services, vec!["api", "api-canary", "api-stable"]);
+
+        // RED metrics are tagged with the effective service name
+        let project_id = ProjectId::new("project-1".to_string());
+        let now = Utc::now();
+        let spans = spans_repo
+            .find_in_range(
+                &project_id,
+                now - chrono::Duration::minutes(1),
+                now + chrono::Duration::minutes(1),
+            )
+            .await
+            .unwrap();
+        let points = crate::modules::span_metrics::derive_red_metrics(
+            &project_id,
+            &spans,
+            now - chrono::Duration::minutes(1),
+            now,
+            &SequentialIdGenerator::new(),
+        );
+        let mut tagged: Vec<&str> = points
+            .iter()
+            .filter(|p| p.name() == "span.requests")
+            .filter_map(|p| p.tags().get("service").map(String::as_str))
+            .collect();
+        tagged.sort();
+        assert_eq!(tagged, vec!["api", "api-canary", "api-stable"]);
+    }
+
+    #[tokio::test]
+    async fn test_unset_status_derived_from_http_status_code() {
+        let spans_repo = Arc::new(InMemorySpansRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let mut project = project_repo.seed("project-1", "org-1");
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({ "http_error_status_threshold": 500 }))
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+        let service = TraceService::new(
+            spans_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+
+        let span = |span_id: &str, status: Option<&str>, code: u16| SpanInput {
+            status: status.map(String::from),
+            attributes: json!({ "http.status_code": code }),
+            ..sampled_span_input(span_id, None)
+        };
+        service
+            .ingest(IngestSpansCommand {
+                project_id: "project-1".to_string(),
+                spans: vec![
+                    span("00f067aa0ba902b1", None, 500),
+                    span("00f067aa0ba902b2", Some("ok"), 200),
+                    span("00f067aa0ba902b3", None, 404),
+                ],
+            })
+            .await
+            .unwrap();
+
+        let spans = spans_repo
+            .get_trace(
+                &ProjectId::new("project-1".to_string()),
+                "4bf92f3577b34da6a3ce929d0e0e4736",
+            )
+            .await
+            .unwrap();
+        let status = |span_id: &str| {
+            spans
+                .iter()
+                .find(|s| s.span_id() == span_id)
+                .unwrap()
+                .status()
+        };
+        assert_eq!(status("00f067aa0ba902b1"), SpanStatusCode::Error);
+        assert_eq!(status("00f067aa0ba902b2"), SpanStatusCode::Ok);
+        assert_eq!(status("00f067aa0ba902b3"), SpanStatusCode::Unset);
+    }
+
+    #[tokio::test]
+    async fn test_spans_from_skewed_source_shifted_toward_server_time() {
+        let spans_repo = Arc::new(InMemorySpansRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let mut project = project_repo.seed("project-1", "org-1");
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({ "clock_skew": { "mode": "adjust", "max_correction_ms": 60_000 } }))
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+        let service = TraceService::new(
+            spans_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+
+        // The api's clock runs 30s ahead, the worker's 2h ahead
+        let span = |span_id: &str, service_name: &str, ahead: chrono::Duration| {
+            let start = Utc::now() + ahead;
+            SpanInput {
+                start_time: Some(start),
+                end_time: Some(start + chrono::Duration::milliseconds(250)),
+                service_name: Some(service_name.to_string()),
+                ..sampled_span_input(span_id, None)
+            }
+        };
+        let before = Utc::now();
+        let response = service
+            .ingest(IngestSpansCommand {
+                project_id: "project-1".to_string(),
+                spans: vec![
+                    span("00f067aa0ba902b1", "api", chrono::Duration::seconds(30)),
+                    span("00f067aa0ba902b2", "worker", chrono::Duration::hours(2)),
+                    span("00f067aa0ba902b3", "db", chrono::Duration::zero()),
+                ],
+            })
+            .await
+            .unwrap();
+        let after = Utc::now();
+
+        assert_eq!(
+            response.clock_skew_ms.keys().collect::<Vec<_>>(),
+            vec!["api", "worker"]
+        );
+        let spans = spans_repo
+            .get_trace(
+                &ProjectId::new("project-1".to_string()),
+                "4bf92f3577b34da6a3ce929d0e0e4736",
+            )
+            .await
+            .unwrap();
+        let find = |span_id: &str| spans.iter().find(|s| s.span_id() == span_id).unwrap();
+
+        let api = find("00f067aa0ba902b1");
+        assert!(api.end_time().unwrap() <= after + chrono::Duration::milliseconds(1));
+        assert!(api.start_time() >= before - chrono::Duration::seconds(1));
+        assert_eq!(api.duration_ns(), Some(250_000_000));
+        assert!(
+            api.attributes()["clock_skew.estimated_ms"]
+                .as_i64()
+                .unwrap()
+                >= 30_000
+        );
+
+        // Corrected by no more than the policy allows
+        let worker = find("00f067aa0ba902b2");
+        assert_eq!(worker.attributes()["clock_skew.corrected_ms"], 60_000);
+        assert!(worker.start_time() > after + chrono::Duration::minutes(118));
+
+        let db = find("00f067aa0ba902b3");
+        assert!(db.attributes().get("clock_skew.estimated_ms").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reingested_span_is_counted_as_duplicate() {
+        let spans_repo = Arc::new(InMemorySpansRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        let service = TraceService::new(
+            spans_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+
+        let start_time = Utc::now();
+        let batch = || IngestSpansCommand {
+            project_id: "project-1".to_string(),
+            spans: vec![SpanInput {
+                start_time: Some(start_time),
+                ..sampled_span_input("00f067aa0ba902b7", None)
+            }],
+        };
+
+        let first = service.ingest(batch()).await.unwrap();
+        assert_eq!((first.ingested, first.duplicates), (1, 0));
+
+        // A retried export resends the same span
+        let retry = service.ingest(batch()).await.unwrap();
+        assert_eq!((retry.ingested, retry.duplicates), (0, 1));
+
+        let spans = spans_repo
+            .get_trace(
+                &ProjectId::new("project-1".to_string()),
+                "4bf92f3577b34da6a3ce929d0e0e4736",
+            )
+            .await
+            .unwrap();
+        assert_eq!(spans.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_span_batch_is_rejected() {
+        let spans_repo = Arc::new(InMemorySpansRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        let service = TraceService::new(
+            spans_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        )
+        .with_batch_limits(SpanBatchLimits {
+            max_spans: 2,
+            ..Default::default()
+        });
+        let batch = |count: usize| IngestSpansCommand {
+            project_id: "project-1".to_string(),
+            spans: (0..count)
+                .map(|i| sampled_span_input(&format!("00f067aa0ba902c{}", i), None))
+                .collect(),
+        };
+
+        let result = service.ingest(batch(3)).await;
+        assert!(matches!(result, Err(TracesDomainError::BatchTooLarge(_))));
+        let project_id = ProjectId::new("project-1".to_string());
+        assert!(
+            spans_repo
+                .get_trace(&project_id, "4bf92f3577b34da6a3ce929d0e0e4736")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // A batch within the limit still ingests
+        let response = service.ingest(batch(2)).await.unwrap();
+        assert_eq!(response.ingested, 2);
+    }
+
+    #[tokio::test]
+    async fn test_truncated_attributes_are_reported() {
+        let spans_repo = Arc::new(InMemorySpansRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        let service = TraceService::new(
+            spans_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+        let attributes: serde_json::Map<String, serde_json::Value> = (0..MAX_ATTRIBUTES_PER_SPAN
+            + 6)
+            .map(|i| (format!("attr.{:03}", i), json!(i)))
+            .collect();
+        let event = SpanEventInput {
+            name: "retry".to_string(),
+            timestamp: Utc::now(),
+            attributes: json!({}),
+        };
+
+        let response = service
+            .ingest(IngestSpansCommand {
+                project_id: "project-1".to_string(),
+                spans: vec![SpanInput {
+                    attributes: serde_json::Value::Object(attributes),
+                    events: vec![event; MAX_EVENTS_PER_SPAN + 2],
+                    ..sampled_span_input("00f067aa0ba902b7", None)
+                }],
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.ingested, 1);
+        assert_eq!(response.truncated_attributes, 6);
+        assert_eq!(response.truncated_events, 2);
+
+        let spans = spans_repo
+            .get_trace(
+                &ProjectId::new("project-1".to_string()),
+                "4bf92f3577b34da6a3ce929d0e0e4736",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            spans[0].attributes().as_object().unwrap().len(),
+            MAX_ATTRIBUTES_PER_SPAN
+        );
+        assert_eq!(spans[0].events().len(), MAX_EVENTS_PER_SPAN);
+    }
+
+    #[tokio::test]
+    async fn test_span_attribute_filter_strips_denied_keys() {
+        let spans_repo = Arc::new(InMemorySpansRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let mut project = project_repo.seed("project-1", "org-1");
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({ "span_attribute_filter": {
+                    "allow": ["http.*", "db.system", "user.email"],
+                    "deny": ["user.email"]
+                }}))
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+        let service = TraceService::new(
+            spans_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+
+        service
+            .ingest(IngestSpansCommand {
+                project_id: "project-1".to_string(),
+                spans: vec![SpanInput {
+                    attributes: json!({
+                        "http.method": "GET",
+                        "http.route": "/users",
+                        "db.system": "postgres",
+                        "user.email": "a@example.com",
+                        "request.id": "r-1"
+                    }),
+                    dropped_attributes_count: 3,
+                    ..sampled_span_input("00f067aa0ba902b7", None)
+                }],
+            })
+            .await
+            .unwrap();
+
+        let spans = spans_repo
+            .get_trace(
+                &ProjectId::new("project-1".to_string()),
+                "4bf92f3577b34da6a3ce929d0e0e4736",
+            )
+            .await
+            .unwrap();
+        let mut kept: Vec<&String> = spans[0].attributes().as_object().unwrap().keys().collect();
+        kept.sort();
+        assert_eq!(kept, vec!["db.system", "http.method", "http.route"]);
+        // Added to what the sender had already dropped
+        assert_eq!(spans[0].dropped_attributes_count(), 5);
+    }
+
+    async fn ingest_with_attribute_types(policy: &str) -> (IngestSpansResponse, Span) {
+        let spans_repo = Arc::new(InMemorySpansRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let mut project = project_repo.seed("project-1", "org-1");
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({ "span_attribute_types": {
+                    "types": {"http.status_code": "int", "retry.count": "int"},
+                    "on_failure": policy
+                }}))
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+        let service = TraceService::new(
+            spans_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+
+        let response = service
+            .ingest(IngestSpansCommand {
+                project_id: "project-1".to_string(),
+                spans: vec![SpanInput {
+                    attributes: json!({ "http.status_code": "503", "retry.count": "many" }),
+                    ..sampled_span_input("00f067aa0ba902b7", None)
+                }],
+            })
+            .await
+            .unwrap();
+        let mut spans = spans_repo
+            .get_trace(
+                &ProjectId::new("project-1".to_string()),
+                "4bf92f3577b34da6a3ce929d0e0e4736",
+            )
+            .await
+            .unwrap();
+        (response, spans.remove(0))
+    }
+
+    #[tokio::test]
+    async fn test_attribute_types_coerce_and_flag_failures() {
+        let (response, span) = ingest_with_attribute_types("flag").await;
+
+        assert_eq!(response.uncoercible_attributes, 1);
+        assert_eq!(span.attributes()["http.status_code"], json!(503));
+        // Kept as sent and listed so the sender can be fixed
+        assert_eq!(span.attributes()["retry.count"], json!("many"));
+        assert_eq!(
+            span.attributes()["attribute_coercion.failed"],
+            json!(["retry.count"])
+        );
+        assert_eq!(span.dropped_attributes_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_attribute_types_drop_uncoercible_values() {
+        let (response, span) = ingest_with_attribute_types("drop").await;
+
+        assert_eq!(response.uncoercible_attributes, 1);
+        assert_eq!(span.attributes(), &json!({ "http.status_code": 503 }));
+        assert_eq!(span.dropped_attributes_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_trace_resolves_service_display_names() {
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        project_repo.seed("project-1", "org-1");
+        member_repo.seed("org-1", "user-1", OrgRole::Member);
+        let metadata_repo = Arc::new(InMemoryServiceMetadataRepository::new());
+        metadata_repo.seed("project-1", "api", "Public API");
+        let service = TraceService::new(
+            Arc::new(InMemorySpansRepository::new()),
+            project_repo,
+            member_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        )
+        .with_service_metadata(metadata_repo.clone());
+        service
+            .ingest(IngestSpansCommand {
+                project_id: "project-1".to_string(),
+                spans: vec![
+                    sampled_span_input("00f067aa0ba902b1", None),
+                    SpanInput {
+                        service_name: Some("payments".to_string()),
+                        ..sampled_span_input("00f067aa0ba902b2", None)
+                    },
+                ],
+            })
+            .await
+            .unwrap();
+
+        let response = service
+            .get_trace(GetTraceCommand {
+                project_id: "project-1".to_string(),
+                trace_id: "4bf92f3577b34da6a3ce929d0e0e4736".to_string(),
+                requesting_user_id: "user-1".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let display_name = |span_id: &str| {
+            let span = response
+                .spans
+                .iter()
+                .find(|s| s.span_id == span_id)
+                .unwrap();
+            span.service.as_ref().unwrap().display_name.clone()
+        };
+        assert_eq!(display_name("00f067aa0ba902b1"), "Public API");
+        // No metadata: falls back to the raw name
+        assert_eq!(display_name("00f067aa0ba902b2"), "payments");
+        assert_eq!(metadata_repo.lookups(), 1);
+    }
+
+    /// Root span of `trace_id` received `received_secs_ago` seconds ago
+    fn received_span(trace_id: &str, received_secs_ago: i64) -> Span {
+        let start = Utc::now() - chrono::Duration::seconds(received_secs_ago + 1);
+        Span::reconstruct(
+            format!("{}-root", trace_id),
+            ProjectId::new("project-1".to_string()),
+            trace_id.to_string(),
+            "root".to_string(),
+            None,
+            "GET /checkout".to_string(),
+            SpanKind::Server,
+            start,
+            Some(start + chrono::Duration::milliseconds(200)),
+            Some(200_000_000),
+            SpanStatusCode::Ok,
+            None,
+            Utc::now() - chrono::Duration::seconds(received_secs_ago),
+            Some("api".to_string()),
+            None,
+            json!({}),
+            json!({}),
+            vec![],
+            vec![],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_traces_inside_completeness_window_marked_or_buffered() {
+        let spans_repo = Arc::new(InMemorySpansRepository::new());
+        spans_repo.seed(vec![
+            received_span("trace-settled", 300),
+            received_span("trace-recent", 5),
+        ]);
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        let mut project = project_repo.seed("project-1", "org-1");
+        member_repo.seed("org-1", "user-1", OrgRole::Member);
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({ "trace_completeness": { "window_secs": 60 } }))
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+        let service = TraceService::new(
+            spans_repo,
+            project_repo.clone(),
+            member_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+        let get = |trace_id: &str| GetTraceCommand {
+            project_id: "project-1".to_string(),
+            trace_id: trace_id.to_string(),
+            requesting_user_id: "user-1".to_string(),
+        };
+        let search = || SearchTracesCommand {
+            project_id: "project-1".to_string(),
+            filters: TraceQueryFilters::default(),
+            requesting_user_id: "user-1".to_string(),
+        };
+
+        // Mark mode: both are returned, only the recent one flagged
+        let response = service.search_traces(search()).await.unwrap();
+        let incomplete: HashMap<String, bool> = response
+            .traces
+            .into_iter()
+            .map(|t| (t.trace_id, t.incomplete))
+            .collect();
+        assert!(incomplete["trace-recent"]);
+        assert!(!incomplete["trace-settled"]);
+        assert!(
+            service
+                .get_trace(get("trace-recent"))
+                .await
+                .unwrap()
+                .incomplete
+        );
+        assert!(
+            !service
+                .get_trace(get("trace-settled"))
+                .await
+                .unwrap()
+                .incomplete
+        );
+
+        // Buffer mode: the recent trace isn't queryable until the window passes
+        project.update_settings(
+            project
+                .settings()
+                .merge(json!({ "trace_completeness": { "window_secs": 60, "mode": "buffer" } }))
+                .unwrap(),
+        );
+        project_repo.save(&project).await.unwrap();
+        let response = service.search_traces(search()).await.unwrap();
+        assert_eq!(response.total, 1);
+        assert_eq!(response.traces[0].trace_id, "trace-settled");
+        assert!(!response.traces[0].incomplete);
+        assert!(matches!(
+            service.get_trace(get("trace-recent")).await,
+            Err(TracesDomainError::TraceNotFound)
+        ));
+        assert!(service.get_trace(get("trace-settled")).await.is_ok());
+    }
+
+    fn histogram_service(
+        spans: Vec<Span>,
+    ) -> TraceService<
+        InMemorySpansRepository,
+        InMemoryProjectRepository,
+        InMemoryMemberRepository,
+        SequentialIdGenerator,
+    > {
+        let spans_repo = Arc::new(InMemorySpansRepository::new());
+        spans_repo.seed(spans);
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        let member_repo = Arc::new(InMemoryMemberRepository::new());
+        project_repo.seed("project-1", "org-1");
+        member_repo.seed("org-1", "user-1", OrgRole::Member);
+        TraceService::new(
+            spans_repo,
+            project_repo,
+            member_repo,
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        )
+    }
+
+    fn histogram_command(query: DurationHistogramQuery) -> DurationHistogramCommand {
+        DurationHistogramCommand {
+            project_id: "project-1".to_string(),
+            query,
+            requesting_user_id: "user-1".to_string(),
+            bypass_cache: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_duration_histogram_distributes_spans_into_buckets() {
+        let service = histogram_service(vec![
+            span("trace-1", "a", None, "GET /", 0, 3),
+            span("trace-1", "b", None, "GET /", 0, 10),
+            span("trace-1", "c", None, "GET /", 0, 42),
+            span("trace-1", "d", None, "GET /", 0, 99),
+            span("trace-1", "e", None, "GET /", 0, 2_000),
+            // Ends before it starts: invalid duration, excluded
+            span("trace-1", "f", None, "GET /", 0, -5),
+        ]);
+
+        let response = service
+            .duration_histogram(histogram_command(DurationHistogramQuery {
+                buckets: Some("10, 100,1000".to_string()),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+
+        let counts: Vec<i64> = response.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![1, 3, 0, 1]);
+        assert_eq!(response.total, 5);
+        assert_eq!(response.buckets[0].lower_ms, 0.0);
+        assert_eq!(response.buckets[1].lower_ms, 10.0);
+        assert_eq!(response.buckets[1].upper_ms, Some(100.0));
+        assert_eq!(response.buckets[3].upper_ms, None);
+    }
+
+    #[tokio::test]
+    async fn test_duration_histogram_derives_buckets_from_observed_range() {
+        let service = histogram_service(vec![
+            span("trace-1", "a", None, "GET /", 0, 1),
+            span("trace-1", "b", None, "GET /", 0, 10),
+            span("trace-1", "c", None, "GET /", 0, 100),
+            span("trace-1", "d", None, "GET /", 0, 1_000),
+        ]);
+
+        let response = service
+            .duration_histogram(histogram_command(DurationHistogramQuery {
+                bucket_count: Some(3),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+
+        // 1ms..1s split log-uniformly: [0, 10), [10, 100), [100, ∞)
+        let bounds: Vec<Option<f64>> = response.buckets.iter().map(|b| b.upper_ms).collect();
+        assert_eq!(bounds, vec![Some(10.0), Some(100.0), None]);
+        let counts: Vec<i64> = response.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![1, 1, 2]);
+    }
+
+    /// Like `span`, but an outgoing call made by the service
+    fn client_span(trace_id: &str, span_id: &str, parent: Option<&str>, duration_ms: i64) -> Span {
+        let server = span(trace_id, span_id, parent, "HTTP GET", 0, duration_ms);
+        Span::new(
+            server.id().to_string(),
+            server.project_id().clone(),
+            trace_id.to_string(),
+            span_id.to_string(),
+            parent.map(String::from),
+            server.name().to_string(),
+            SpanKind::Client,
+            server.start_time(),
+            server.end_time(),
+            SpanStatusCode::Ok,
+            None,
+            Some("api".to_string()),
+            None,
+            json!({}),
+            json!({}),
+            vec![],
+            vec![],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_span_kind_filter_scopes_search_and_latency() {
+        let service = histogram_service(vec![
+            span("trace-1", "a", None, "GET /", 0, 10),
+            client_span("trace-1", "b", Some("a"), 200),
+            // A background job's outgoing call, with no server span
+            client_span("trace-2", "c", None, 300),
+        ]);
+
+        let traces = service
+            .search_traces(SearchTracesCommand {
+                project_id: "project-1".to_string(),
+                filters: TraceQueryFilters {
+                    span_kind: Some("server".to_string()),
+                    ..Default::default()
+                },
+                requesting_user_id: "user-1".to_string(),
+            })
+            .await
+            .unwrap()
+            .traces;
+        assert_eq!(traces.len(), 1);
+        assert_eq!(traces[0].trace_id, "trace-1");
+
+        let counts = |span_kind: Option<&str>| {
+            let query = DurationHistogramQuery {
+                span_kind: span_kind.map(String::from),
+                buckets: Some("100".to_string()),
+                ..Default::default()
+            };
+            let service = &service;
+            async move {
+                let response = service
+                    .duration_histogram(histogram_command(query))
+                    .await
+                    .unwrap();
+                response.buckets.iter().map(|b| b.count).collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(counts(Some("server")).await, vec![1, 0]);
+        assert_eq!(counts(None).await, vec![1, 2]);
+
+        let result = service
+            .duration_histogram(histogram_command(DurationHistogramQuery {
+                span_kind: Some("frontend".to_string()),
+                ..Default::default()
+            }))
+            .await;
+        assert!(matches!(result, Err(TracesDomainError::InvalidSpanKind(_))));
+    }
+
+    #[tokio::test]
+    async fn test_duration_histogram_rejects_unordered_buckets() {
+        let service = histogram_service(vec![]);
+
+        for buckets in ["100,10", "10,abc", "0,5"] {
+            let result = service
+                .duration_histogram(histogram_command(DurationHistogramQuery {
+                    buckets: Some(buckets.to_string()),
+                    ..Default::default()
+                }))
+                .await;
+            assert!(matches!(
+                result,
+                Err(TracesDomainError::InvalidHistogramBuckets(_))
+            ));
+        }
     }
 }
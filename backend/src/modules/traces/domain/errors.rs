@@ -17,12 +17,21 @@ pub enum TracesDomainError {
     #[error("Invalid span ID: {0}")]
     InvalidSpanId(String),
 
+    #[error("Span start time is required")]
+    MissingStartTime,
+
     #[error("Too many spans in trace: {0}")]
     TooManySpans(usize),
 
     #[error("Too many attributes: {0}")]
     TooManyAttributes(usize),
 
+    #[error("Span batch too large: {0}")]
+    BatchTooLarge(String),
+
+    #[error("Invalid histogram buckets: {0}")]
+    InvalidHistogramBuckets(String),
+
     #[error("Trace not found")]
     TraceNotFound,
 
@@ -3,6 +3,9 @@ pub mod span;
 
 pub use errors::TracesDomainError;
 pub use span::{
-    Pagination, Span, SpanEvent, SpanKind, SpanLink, SpansRepository, SpanStatusCode,
-    TraceFilters, TraceSearchResult, TraceSummary, MAX_ATTRIBUTES_PER_SPAN, MAX_SPANS_PER_TRACE,
+    DurationBucket, ErrorRateUnit, MAX_ATTRIBUTES_PER_SPAN, MAX_EVENTS_PER_SPAN,
+    MAX_SPANS_PER_TRACE, Pagination, Span, SpanBatchLimits, SpanCounts, SpanEvent, SpanKind,
+    SpanLink, SpanSaveResult, SpanStatusCode, SpansRepository, TraceCutoff, TraceCutoffs,
+    TraceFilters, TraceSearchResult, TraceSummary, derive_http_status, effective_service_name,
+    head_sample, normalize_span_name, retain_attributes, truncate_attributes,
 };
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde_json::Value;
 
-use super::value_objects::{SpanEvent, SpanKind, SpanLink, SpanStatusCode};
+use super::value_objects::{SpanEvent, SpanKind, SpanLink, SpanStatusCode, sampling_probability};
 use crate::modules::projects::domain::ProjectId;
 
 /// Span - a single unit of work within a distributed trace
@@ -26,6 +26,10 @@ pub struct Span {
     attributes: Value,
     events: Vec<SpanEvent>,
     links: Vec<SpanLink>,
+    trace_state: Option<String>,
+    sampling_probability: Option<f64>,
+    /// Attributes discarded by the sender or at ingest
+    dropped_attributes_count: u32,
 }
 
 impl Span {
@@ -51,9 +55,7 @@ impl Span {
         links: Vec<SpanLink>,
     ) -> Self {
         // Calculate duration if end_time is provided
-        let duration_ns = end_time.map(|end| {
-            (end - start_time).num_nanoseconds().unwrap_or(0)
-        });
+        let duration_ns = end_time.map(|end| (end - start_time).num_nanoseconds().unwrap_or(0));
 
         Self {
             id,
@@ -75,6 +77,9 @@ impl Span {
             attributes,
             events,
             links,
+            trace_state: None,
+            sampling_probability: None,
+            dropped_attributes_count: 0,
         }
     }
 
@@ -121,6 +126,9 @@ impl Span {
             attributes,
             events,
             links,
+            trace_state: None,
+            sampling_probability: None,
+            dropped_attributes_count: 0,
         }
     }
 
@@ -201,6 +209,80 @@ impl Span {
         &self.links
     }
 
+    /// W3C tracestate the span was recorded with
+    pub fn trace_state(&self) -> Option<&str> {
+        self.trace_state.as_deref()
+    }
+
+    /// Sampling probability derived from the tracestate, if the sampler reported one
+    pub fn sampling_probability(&self) -> Option<f64> {
+        self.sampling_probability
+    }
+
+    pub fn dropped_attributes_count(&self) -> u32 {
+        self.dropped_attributes_count
+    }
+
+    /// Estimated number of spans this one represents (inverse of the sampling probability)
+    pub fn adjusted_count(&self) -> f64 {
+        self.sampling_probability.map_or(1.0, |p| 1.0 / p)
+    }
+
+    /// Attach the W3C tracestate and derive the sampling probability from it
+    pub fn with_trace_state(mut self, trace_state: Option<String>) -> Self {
+        let trace_state = trace_state.filter(|s| !s.is_empty());
+        self.sampling_probability = trace_state.as_deref().and_then(sampling_probability);
+        self.trace_state = trace_state;
+        self
+    }
+
+    /// Count `count` more attributes as dropped
+    pub fn with_dropped_attributes(mut self, count: u32) -> Self {
+        self.dropped_attributes_count = self.dropped_attributes_count.saturating_add(count);
+        self
+    }
+
+    /// Record that the span survived head sampling at `rate`, on top of any
+    /// sampling upstream
+    pub fn with_head_sampling(mut self, rate: f64) -> Self {
+        if rate < 1.0 {
+            self.sampling_probability = Some(self.sampling_probability.unwrap_or(1.0) * rate);
+        }
+        self
+    }
+
+    /// Record the estimated clock skew of the span's source in its attributes
+    /// and move the span and its events `corrected_ms` earlier
+    pub fn with_clock_skew(mut self, estimated_ms: i64, corrected_ms: i64) -> Self {
+        if !self.attributes.is_object() {
+            self.attributes = Value::Object(Default::default());
+        }
+        self.attributes["clock_skew.estimated_ms"] = estimated_ms.into();
+        if corrected_ms > 0 {
+            let shift = chrono::Duration::milliseconds(corrected_ms);
+            self.attributes["clock_skew.corrected_ms"] = corrected_ms.into();
+            self.start_time -= shift;
+            self.end_time = self.end_time.map(|end| end - shift);
+            for event in &mut self.events {
+                event.timestamp -= shift;
+            }
+        }
+        self
+    }
+
+    /// List attributes whose value didn't match the project's configured
+    /// type in the span's attributes, so they can be found and fixed at the source
+    pub fn with_uncoercible_attributes(mut self, keys: Vec<String>) -> Self {
+        if keys.is_empty() {
+            return self;
+        }
+        if !self.attributes.is_object() {
+            self.attributes = Value::Object(Default::default());
+        }
+        self.attributes["attribute_coercion.failed"] = keys.into();
+        self
+    }
+
     /// Check if this is a root span
     pub fn is_root(&self) -> bool {
         self.parent_span_id.is_none()
@@ -3,7 +3,12 @@ pub mod repository;
 pub mod value_objects;
 
 pub use entity::Span;
-pub use repository::{Pagination, SpansRepository, TraceFilters, TraceSearchResult, TraceSummary};
+pub use repository::{
+    DurationBucket, ErrorRateUnit, Pagination, SpanCounts, SpanSaveResult, SpansRepository,
+    TraceCutoff, TraceCutoffs, TraceFilters, TraceSearchResult, TraceSummary,
+};
 pub use value_objects::{
-    SpanEvent, SpanKind, SpanLink, SpanStatusCode, MAX_ATTRIBUTES_PER_SPAN, MAX_SPANS_PER_TRACE,
+    MAX_ATTRIBUTES_PER_SPAN, MAX_EVENTS_PER_SPAN, MAX_SPANS_PER_TRACE, SpanBatchLimits, SpanEvent,
+    SpanKind, SpanLink, SpanStatusCode, derive_http_status, effective_service_name, head_sample,
+    normalize_span_name, retain_attributes, truncate_attributes,
 };
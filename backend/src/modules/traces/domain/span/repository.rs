@@ -1,10 +1,14 @@
+use std::collections::BTreeMap;
+
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use serde::Deserialize;
 
 use super::entity::Span;
-use super::value_objects::SpanStatusCode;
-use crate::modules::traces::domain::errors::TracesDomainError;
+use super::value_objects::{SpanKind, SpanStatusCode};
 use crate::modules::projects::domain::ProjectId;
+use crate::modules::traces::domain::errors::TracesDomainError;
+pub use crate::shared::Pagination;
 
 /// Filters for trace queries
 #[derive(Debug, Clone, Default)]
@@ -12,25 +16,40 @@ pub struct TraceFilters {
     pub service_name: Option<String>,
     pub span_name: Option<String>,
     pub status: Option<SpanStatusCode>,
+    /// Only spans of this kind, e.g. server spans for request latency
+    pub span_kind: Option<SpanKind>,
     pub start_time: Option<DateTime<Utc>>,
     pub end_time: Option<DateTime<Utc>>,
     pub min_duration_ns: Option<i64>,
     pub max_duration_ns: Option<i64>,
+    /// Only traces with no span received after this, leaving out traces
+    /// still inside the project's completeness window
+    pub settled_before: Option<DateTime<Utc>>,
 }
 
-/// Pagination parameters
+/// Retention cutoff for traces with and without error spans
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceCutoff {
+    pub error: DateTime<Utc>,
+    pub clean: DateTime<Utc>,
+}
+
+/// Per-trace retention cutoffs. A trace is deleted, whole, once its latest span
+/// started before the cutoff for its error status and its root span's service.
 #[derive(Debug, Clone)]
-pub struct Pagination {
-    pub limit: i64,
-    pub offset: i64,
+pub struct TraceCutoffs {
+    pub default: TraceCutoff,
+    pub services: BTreeMap<String, TraceCutoff>,
 }
 
-impl Default for Pagination {
-    fn default() -> Self {
-        Self {
-            limit: 100,
-            offset: 0,
-        }
+impl TraceCutoffs {
+    /// The latest of all cutoffs: no trace with a span after it can expire
+    pub fn latest(&self) -> DateTime<Utc> {
+        std::iter::once(&self.default)
+            .chain(self.services.values())
+            .flat_map(|c| [c.error, c.clean])
+            .max()
+            .unwrap_or(self.default.clean)
     }
 }
 
@@ -45,6 +64,8 @@ pub struct TraceSummary {
     pub start_time: DateTime<Utc>,
     pub end_time: Option<DateTime<Utc>>,
     pub duration_ns: Option<i64>,
+    /// When the most recent of the trace's spans was received
+    pub last_received_at: DateTime<Utc>,
 }
 
 /// Result of a trace search
@@ -54,11 +75,68 @@ pub struct TraceSearchResult {
     pub total: i64,
 }
 
+/// Span counts for a filter
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SpanCounts {
+    /// Spans actually stored
+    pub spans: i64,
+    /// Sum of each span's inverse sampling probability
+    pub adjusted: f64,
+}
+
+/// What an error rate is a fraction of
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorRateUnit {
+    /// Error spans among all spans
+    #[default]
+    Spans,
+    /// Traces with an error span among all traces
+    Traces,
+}
+
+/// Spans whose duration falls in `[lower_ns, upper_ns)`; the last bucket is unbounded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurationBucket {
+    pub lower_ns: i64,
+    pub upper_ns: Option<i64>,
+    pub count: i64,
+}
+
+impl DurationBucket {
+    /// Empty buckets for ascending upper bounds; the first bucket starts at 0
+    pub fn empty_buckets(bucket_boundaries: &[i64]) -> Vec<DurationBucket> {
+        std::iter::once(0)
+            .chain(bucket_boundaries.iter().copied())
+            .zip(bucket_boundaries.iter().copied().map(Some).chain([None]))
+            .map(|(lower_ns, upper_ns)| DurationBucket {
+                lower_ns,
+                upper_ns,
+                count: 0,
+            })
+            .collect()
+    }
+}
+
+/// Outcome of saving a batch of spans
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpanSaveResult {
+    pub saved: u32,
+    /// Spans whose (trace_id, span_id) was already stored
+    pub duplicates: u32,
+}
+
 /// Repository trait for spans persistence
 #[async_trait]
 pub trait SpansRepository: Send + Sync {
-    /// Save a batch of spans
-    async fn save_batch(&self, spans: &[Span]) -> Result<u32, TracesDomainError>;
+    /// Save a batch of spans. A span already stored under the same project,
+    /// trace id, span id and start time is a duplicate: it replaces the stored
+    /// span when `update_duplicates` is set and is dropped otherwise.
+    async fn save_batch(
+        &self,
+        spans: &[Span],
+        update_duplicates: bool,
+    ) -> Result<SpanSaveResult, TracesDomainError>;
 
     /// Get all spans for a trace
     async fn get_trace(
@@ -75,13 +153,67 @@ pub trait SpansRepository: Send + Sync {
         pagination: &Pagination,
     ) -> Result<TraceSearchResult, TracesDomainError>;
 
+    /// Count spans matching filters, raw and adjusted for sampling
+    async fn count_spans(
+        &self,
+        project_id: &ProjectId,
+        filters: &TraceFilters,
+    ) -> Result<SpanCounts, TracesDomainError>;
+
+    /// Count spans per duration bucket. `bucket_boundaries` are ascending upper bounds in ns.
+    /// Spans without a valid (non-negative) duration are excluded.
+    async fn duration_histogram(
+        &self,
+        project_id: &ProjectId,
+        filters: &TraceFilters,
+        bucket_boundaries: &[i64],
+    ) -> Result<Vec<DurationBucket>, TracesDomainError>;
+
+    /// Count spans matching filters, or the traces they belong to, as
+    /// `(errors, total)`. A trace is in error when any of its matching spans is.
+    async fn count_errors(
+        &self,
+        project_id: &ProjectId,
+        filters: &TraceFilters,
+        unit: ErrorRateUnit,
+    ) -> Result<(i64, i64), TracesDomainError>;
+
+    /// Shortest and longest valid duration (ns) among spans matching filters
+    async fn duration_range(
+        &self,
+        project_id: &ProjectId,
+        filters: &TraceFilters,
+    ) -> Result<Option<(i64, i64)>, TracesDomainError>;
+
+    /// Get all spans that started within [start, end)
+    async fn find_in_range(
+        &self,
+        project_id: &ProjectId,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<Span>, TracesDomainError>;
+
     /// Get distinct service names for a project
-    async fn get_service_names(&self, project_id: &ProjectId) -> Result<Vec<String>, TracesDomainError>;
+    async fn get_service_names(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Vec<String>, TracesDomainError>;
 
-    /// Delete spans older than a given timestamp
+    /// Delete up to `limit` of the oldest spans older than a given timestamp,
+    /// returning how many were deleted
     async fn delete_before(
         &self,
         project_id: &ProjectId,
         before: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<u64, TracesDomainError>;
+
+    /// Delete up to `limit` whole traces past their cutoff, returning the number
+    /// of spans deleted
+    async fn delete_expired_traces(
+        &self,
+        project_id: &ProjectId,
+        cutoffs: &TraceCutoffs,
+        limit: u32,
     ) -> Result<u64, TracesDomainError>;
 }
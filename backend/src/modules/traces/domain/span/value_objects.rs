@@ -1,3 +1,5 @@
+use serde_json::Value;
+
 use crate::modules::traces::domain::errors::TracesDomainError;
 
 /// Span Kind - describes the relationship between the span and its parent
@@ -94,9 +96,183 @@ pub struct SpanLink {
     pub attributes: serde_json::Value,
 }
 
+/// A span's effective service name: the first attribute in `priority` found on
+/// the span's resource, or failing that on the span itself, wins. Without a
+/// match the reported service name is kept.
+pub fn effective_service_name(
+    priority: &[String],
+    resource_attributes: &Value,
+    attributes: &Value,
+    reported: Option<String>,
+) -> Option<String> {
+    let lookup = |attrs: &Value, key: &str| match attrs.get(key)? {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    };
+    priority
+        .iter()
+        .find_map(|key| lookup(resource_attributes, key).or_else(|| lookup(attributes, key)))
+        .or(reported)
+}
+
+/// HTTP response code attributes, current semantic conventions first
+const HTTP_STATUS_ATTRIBUTES: [&str; 2] = ["http.response.status_code", "http.status_code"];
+
+/// Derive a span's status from its HTTP response code: an unset status becomes
+/// Error when the code is at least `threshold`. Explicit statuses are kept.
+pub fn derive_http_status(
+    status: SpanStatusCode,
+    attributes: &Value,
+    threshold: u16,
+) -> SpanStatusCode {
+    if status != SpanStatusCode::Unset {
+        return status;
+    }
+    let code = HTTP_STATUS_ATTRIBUTES
+        .iter()
+        .find_map(|key| match attributes.get(key)? {
+            Value::Number(n) => n.as_u64(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        });
+    match code {
+        Some(code) if code >= u64::from(threshold) => SpanStatusCode::Error,
+        _ => status,
+    }
+}
+
+/// Normalize a span name so equivalent operations compare equal across traces.
+/// Path segments that look like identifiers (numbers, UUIDs, long hex strings)
+/// are replaced with `{id}`, e.g. `GET /users/42` -> `GET /users/{id}`.
+pub fn normalize_span_name(name: &str) -> String {
+    name.trim()
+        .split(' ')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            part.split('/')
+                .map(|segment| {
+                    if is_identifier_segment(segment) {
+                        "{id}"
+                    } else {
+                        segment
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_identifier_segment(segment: &str) -> bool {
+    if segment.is_empty() {
+        return false;
+    }
+    if segment.chars().all(|c| c.is_ascii_digit()) {
+        return true;
+    }
+    let hex_len = segment.chars().filter(|c| *c != '-').count();
+    hex_len >= 16 && segment.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+}
+
+/// Number of bits in an OpenTelemetry consistent-probability sampling threshold
+const SAMPLING_THRESHOLD_BITS: i32 = 56;
+
+/// Extract the effective sampling probability from a W3C `tracestate` header.
+///
+/// Reads the OpenTelemetry `ot=th:<hex>` rejection threshold: a span is kept
+/// when its randomness is at or above the threshold, so the probability is
+/// `1 - th / 2^56`. Returns `None` when no valid threshold is present.
+pub fn sampling_probability(trace_state: &str) -> Option<f64> {
+    let ot_value = trace_state
+        .split(',')
+        .filter_map(|member| member.trim().split_once('='))
+        .find(|(key, _)| *key == "ot")
+        .map(|(_, value)| value)?;
+
+    let threshold = ot_value
+        .split(';')
+        .find_map(|field| field.strip_prefix("th:"))?;
+    if threshold.is_empty() || threshold.len() > 14 {
+        return None;
+    }
+
+    // Trailing zeros are omitted on the wire; pad back to 14 hex digits
+    let rejected = u64::from_str_radix(&format!("{:0<14}", threshold), 16).ok()?;
+    let probability = 1.0 - rejected as f64 / 2f64.powi(SAMPLING_THRESHOLD_BITS);
+    (probability > 0.0).then_some(probability)
+}
+
+/// Whether a trace is kept by head sampling at `rate`. Hashes the trace id so
+/// every span of a trace, in any batch, gets the same decision, and a trace
+/// kept at some rate is also kept at every higher one.
+pub fn head_sample(trace_id: &str, rate: f64) -> bool {
+    if rate >= 1.0 {
+        return true;
+    }
+    // FNV-1a, then a MurmurHash3 finalizer so ids differing only in their last
+    // characters still spread over the top bits, which stand in for the
+    // trace's sampling randomness
+    let mut hash = trace_id.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ceb9fe1a85ec53);
+    hash ^= hash >> 33;
+    let randomness = hash >> (64 - SAMPLING_THRESHOLD_BITS);
+    let threshold = (1.0 - rate) * 2f64.powi(SAMPLING_THRESHOLD_BITS);
+    randomness as f64 >= threshold
+}
+
 /// Limits for spans
 pub const MAX_SPANS_PER_TRACE: usize = 500;
 pub const MAX_ATTRIBUTES_PER_SPAN: usize = 64;
+pub const MAX_EVENTS_PER_SPAN: usize = 128;
+
+/// Caps on a single span ingest request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpanBatchLimits {
+    pub max_spans: usize,
+    pub max_bytes: usize,
+}
+
+impl Default for SpanBatchLimits {
+    fn default() -> Self {
+        Self {
+            max_spans: 10_000,
+            max_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+/// Drop attributes whose key `keep` rejects, returning how many were dropped
+pub fn retain_attributes(attributes: &mut Value, keep: impl Fn(&str) -> bool) -> u32 {
+    let Some(map) = attributes.as_object_mut() else {
+        return 0;
+    };
+    let before = map.len();
+    map.retain(|key, _| keep(key));
+    (before - map.len()) as u32
+}
+
+/// Drop attributes beyond `max` keys, returning how many were dropped
+pub fn truncate_attributes(attributes: &mut Value, max: usize) -> u32 {
+    let Some(map) = attributes.as_object_mut() else {
+        return 0;
+    };
+    if map.len() <= max {
+        return 0;
+    }
+
+    let dropped: Vec<String> = map.keys().skip(max).cloned().collect();
+    for key in &dropped {
+        map.remove(key);
+    }
+    dropped.len() as u32
+}
 
 #[cfg(test)]
 mod tests {
@@ -104,19 +280,111 @@ mod tests {
 
     #[test]
     fn test_span_kind_from_str() {
-        assert!(matches!(SpanKind::from_str("internal"), Ok(SpanKind::Internal)));
+        assert!(matches!(
+            SpanKind::from_str("internal"),
+            Ok(SpanKind::Internal)
+        ));
         assert!(matches!(SpanKind::from_str("server"), Ok(SpanKind::Server)));
         assert!(matches!(SpanKind::from_str("client"), Ok(SpanKind::Client)));
-        assert!(matches!(SpanKind::from_str("producer"), Ok(SpanKind::Producer)));
-        assert!(matches!(SpanKind::from_str("consumer"), Ok(SpanKind::Consumer)));
+        assert!(matches!(
+            SpanKind::from_str("producer"),
+            Ok(SpanKind::Producer)
+        ));
+        assert!(matches!(
+            SpanKind::from_str("consumer"),
+            Ok(SpanKind::Consumer)
+        ));
         assert!(SpanKind::from_str("invalid").is_err());
     }
 
+    #[test]
+    fn test_normalize_span_name() {
+        assert_eq!(normalize_span_name("GET /users/42"), "GET /users/{id}");
+        assert_eq!(
+            normalize_span_name("GET /orders/550e8400-e29b-41d4-a716-446655440000/items"),
+            "GET /orders/{id}/items"
+        );
+        assert_eq!(normalize_span_name("  SELECT  users "), "SELECT users");
+        assert_eq!(normalize_span_name("/v2/cafe"), "/v2/cafe");
+    }
+
+    #[test]
+    fn test_truncate_attributes() {
+        let mut attributes = serde_json::json!({"a": 1, "b": 2, "c": 3});
+        assert_eq!(truncate_attributes(&mut attributes, 2), 1);
+        assert_eq!(attributes, serde_json::json!({"a": 1, "b": 2}));
+        assert_eq!(truncate_attributes(&mut attributes, 2), 0);
+
+        let mut not_an_object = Value::Null;
+        assert_eq!(truncate_attributes(&mut not_an_object, 0), 0);
+    }
+
+    #[test]
+    fn test_sampling_probability_from_trace_state() {
+        assert_eq!(sampling_probability("ot=th:0"), Some(1.0));
+        assert_eq!(
+            sampling_probability("vendor=abc,ot=th:8;rv:1a2b3c4d5e6f70"),
+            Some(0.5)
+        );
+        let ten_percent = sampling_probability("ot=th:e6666666666666").unwrap();
+        assert!((ten_percent - 0.1).abs() < 1e-12);
+        assert_eq!(sampling_probability("ot=rv:1a2b3c4d5e6f70"), None);
+        assert_eq!(sampling_probability("vendor=th:8"), None);
+        assert_eq!(sampling_probability("ot=th:zz"), None);
+    }
+
+    #[test]
+    fn test_derive_http_status() {
+        let attrs = |code: Value| serde_json::json!({ "http.status_code": code });
+        let unset = SpanStatusCode::Unset;
+        assert_eq!(
+            derive_http_status(unset, &attrs(500.into()), 500),
+            SpanStatusCode::Error
+        );
+        assert_eq!(
+            derive_http_status(unset, &attrs("503".into()), 500),
+            SpanStatusCode::Error
+        );
+        assert_eq!(
+            derive_http_status(unset, &attrs(404.into()), 500),
+            SpanStatusCode::Unset
+        );
+        assert_eq!(
+            derive_http_status(unset, &attrs(404.into()), 400),
+            SpanStatusCode::Error
+        );
+        assert_eq!(
+            derive_http_status(
+                unset,
+                &serde_json::json!({ "http.response.status_code": 502 }),
+                500
+            ),
+            SpanStatusCode::Error
+        );
+        assert_eq!(
+            derive_http_status(SpanStatusCode::Ok, &attrs(500.into()), 500),
+            SpanStatusCode::Ok
+        );
+        assert_eq!(
+            derive_http_status(unset, &serde_json::json!({}), 500),
+            SpanStatusCode::Unset
+        );
+    }
+
     #[test]
     fn test_span_status_from_str() {
-        assert!(matches!(SpanStatusCode::from_str("unset"), Ok(SpanStatusCode::Unset)));
-        assert!(matches!(SpanStatusCode::from_str("ok"), Ok(SpanStatusCode::Ok)));
-        assert!(matches!(SpanStatusCode::from_str("error"), Ok(SpanStatusCode::Error)));
+        assert!(matches!(
+            SpanStatusCode::from_str("unset"),
+            Ok(SpanStatusCode::Unset)
+        ));
+        assert!(matches!(
+            SpanStatusCode::from_str("ok"),
+            Ok(SpanStatusCode::Ok)
+        ));
+        assert!(matches!(
+            SpanStatusCode::from_str("error"),
+            Ok(SpanStatusCode::Error)
+        ));
         assert!(SpanStatusCode::from_str("invalid").is_err());
     }
 }
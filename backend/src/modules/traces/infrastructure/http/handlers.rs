@@ -1,7 +1,7 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
     Extension, Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -11,9 +11,10 @@ use crate::modules::auth::infrastructure::http::extractors::AuthClaims;
 use crate::modules::logging::infrastructure::ApiKeyContext;
 use crate::modules::organizations::domain::OrganizationMemberRepository;
 use crate::modules::projects::domain::ProjectRepository;
-use crate::modules::traces::application::dto::*;
 use crate::modules::traces::application::TraceService;
+use crate::modules::traces::application::dto::*;
 use crate::modules::traces::domain::{SpansRepository, TracesDomainError};
+use crate::shared::{LimitedJson, PAGINATION_LIMIT_HEADER, bypasses_query_cache};
 
 #[derive(Debug, Serialize)]
 pub struct ErrorResponse {
@@ -27,13 +28,21 @@ fn to_error_response(e: TracesDomainError) -> (StatusCode, Json<ErrorResponse>)
         | TracesDomainError::InvalidSpanStatus(msg)
         | TracesDomainError::InvalidSpanName(msg)
         | TracesDomainError::InvalidTraceId(msg)
-        | TracesDomainError::InvalidSpanId(msg) => (
+        | TracesDomainError::InvalidSpanId(msg)
+        | TracesDomainError::InvalidHistogramBuckets(msg) => (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
                 error: msg,
                 code: "VALIDATION_ERROR".to_string(),
             }),
         ),
+        TracesDomainError::MissingStartTime => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "VALIDATION_ERROR".to_string(),
+            }),
+        ),
         TracesDomainError::TooManySpans(count) => (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse {
@@ -48,6 +57,13 @@ fn to_error_response(e: TracesDomainError) -> (StatusCode, Json<ErrorResponse>)
                 code: "TOO_MANY_ATTRIBUTES".to_string(),
             }),
         ),
+        TracesDomainError::BatchTooLarge(msg) => (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(ErrorResponse {
+                error: msg,
+                code: "BATCH_TOO_LARGE".to_string(),
+            }),
+        ),
         TracesDomainError::TraceNotFound => (
             StatusCode::NOT_FOUND,
             Json(ErrorResponse {
@@ -94,7 +110,7 @@ pub struct IngestSpansRequest {
 pub async fn ingest_spans<SR, PR, OMR, ID>(
     State(service): State<Arc<TraceService<SR, PR, OMR, ID>>>,
     Extension(ctx): Extension<ApiKeyContext>,
-    Json(request): Json<IngestSpansRequest>,
+    LimitedJson(request): LimitedJson<IngestSpansRequest>,
 ) -> Result<(StatusCode, Json<IngestSpansResponse>), (StatusCode, Json<ErrorResponse>)>
 where
     SR: SpansRepository,
@@ -121,7 +137,10 @@ pub async fn search_traces<SR, PR, OMR, ID>(
     Extension(claims): Extension<AuthClaims>,
     Path(project_id): Path<String>,
     Query(filters): Query<TraceQueryFilters>,
-) -> Result<Json<TraceSearchResponse>, (StatusCode, Json<ErrorResponse>)>
+) -> Result<
+    ([(&'static str, String); 1], Json<TraceSearchResponse>),
+    (StatusCode, Json<ErrorResponse>),
+>
 where
     SR: SpansRepository,
     PR: ProjectRepository,
@@ -134,7 +153,66 @@ where
         requesting_user_id: claims.user_id,
     };
 
-    let response = service.search_traces(cmd).await.map_err(to_error_response)?;
+    let response = service
+        .search_traces(cmd)
+        .await
+        .map_err(to_error_response)?;
+
+    Ok((
+        [(PAGINATION_LIMIT_HEADER, response.limit.to_string())],
+        Json(response),
+    ))
+}
+
+pub async fn count_spans<SR, PR, OMR, ID>(
+    State(service): State<Arc<TraceService<SR, PR, OMR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(project_id): Path<String>,
+    headers: HeaderMap,
+    Query(query): Query<SpanCountQuery>,
+) -> Result<Json<SpanCountResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    SR: SpansRepository,
+    PR: ProjectRepository,
+    OMR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let cmd = CountSpansCommand {
+        project_id,
+        query,
+        requesting_user_id: claims.user_id,
+        bypass_cache: bypasses_query_cache(&headers),
+    };
+
+    let response = service.count_spans(cmd).await.map_err(to_error_response)?;
+
+    Ok(Json(response))
+}
+
+pub async fn duration_histogram<SR, PR, OMR, ID>(
+    State(service): State<Arc<TraceService<SR, PR, OMR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(project_id): Path<String>,
+    headers: HeaderMap,
+    Query(query): Query<DurationHistogramQuery>,
+) -> Result<Json<DurationHistogramResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    SR: SpansRepository,
+    PR: ProjectRepository,
+    OMR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let cmd = DurationHistogramCommand {
+        project_id,
+        query,
+        requesting_user_id: claims.user_id,
+        bypass_cache: bypasses_query_cache(&headers),
+    };
+
+    let response = service
+        .duration_histogram(cmd)
+        .await
+        .map_err(to_error_response)?;
 
     Ok(Json(response))
 }
@@ -177,7 +255,43 @@ where
         requesting_user_id: claims.user_id,
     };
 
-    let response = service.list_services(cmd).await.map_err(to_error_response)?;
+    let response = service
+        .list_services(cmd)
+        .await
+        .map_err(to_error_response)?;
+
+    Ok(Json(response))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompareTracesQuery {
+    pub base: String,
+    pub compare: String,
+}
+
+pub async fn compare_traces<SR, PR, OMR, ID>(
+    State(service): State<Arc<TraceService<SR, PR, OMR, ID>>>,
+    Extension(claims): Extension<AuthClaims>,
+    Path(project_id): Path<String>,
+    Query(query): Query<CompareTracesQuery>,
+) -> Result<Json<TraceComparisonResponse>, (StatusCode, Json<ErrorResponse>)>
+where
+    SR: SpansRepository,
+    PR: ProjectRepository,
+    OMR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let cmd = CompareTracesCommand {
+        project_id,
+        base_trace_id: query.base,
+        compare_trace_id: query.compare,
+        requesting_user_id: claims.user_id,
+    };
+
+    let response = service
+        .compare_traces(cmd)
+        .await
+        .map_err(to_error_response)?;
 
     Ok(Json(response))
 }
@@ -1,7 +1,8 @@
 use axum::{
+    Router,
+    extract::DefaultBodyLimit,
     middleware,
     routing::{get, post},
-    Router,
 };
 use std::sync::Arc;
 
@@ -29,8 +30,11 @@ where
     AR: ApiKeyRepository + 'static,
     OR: OrganizationRepository + 'static,
 {
+    let max_bytes = service.batch_limits().max_bytes;
+
     Router::new()
         .route("/traces", post(handlers::ingest_spans::<SR, PR, OMR, ID>))
+        .layer(DefaultBodyLimit::max(max_bytes))
         .layer(middleware::from_fn_with_state(
             project_service,
             api_key_middleware::<PPR, AR, OR, OMR, ID>,
@@ -54,6 +58,12 @@ where
         .route("/", get(handlers::search_traces::<SR, PR, OMR, ID>))
         .route("/{trace_id}", get(handlers::get_trace::<SR, PR, OMR, ID>))
         .route("/services", get(handlers::list_services::<SR, PR, OMR, ID>))
+        .route("/count", get(handlers::count_spans::<SR, PR, OMR, ID>))
+        .route(
+            "/histogram",
+            get(handlers::duration_histogram::<SR, PR, OMR, ID>),
+        )
+        .route("/compare", get(handlers::compare_traces::<SR, PR, OMR, ID>))
         .layer(middleware::from_fn_with_state(
             token_service,
             auth_middleware::<TS>,
@@ -24,6 +24,8 @@ pub struct SpanRow {
     pub attributes: Value,
     pub events: Value,
     pub links: Value,
+    pub trace_state: Option<String>,
+    pub dropped_attributes_count: i32,
 }
 
 /// Row for trace summary queries
@@ -37,4 +39,5 @@ pub struct TraceSummaryRow {
     pub start_time: DateTime<Utc>,
     pub end_time: Option<DateTime<Utc>>,
     pub duration_ns: Option<i64>,
+    pub last_received_at: DateTime<Utc>,
 }
@@ -5,8 +5,9 @@ use sqlx::PgPool;
 
 use crate::modules::projects::domain::ProjectId;
 use crate::modules::traces::domain::{
-    Pagination, Span, SpanEvent, SpanKind, SpanLink, SpanStatusCode, SpansRepository,
-    TraceFilters, TraceSearchResult, TracesDomainError, TraceSummary,
+    DurationBucket, ErrorRateUnit, Pagination, Span, SpanCounts, SpanEvent, SpanKind, SpanLink,
+    SpanSaveResult, SpanStatusCode, SpansRepository, TraceCutoffs, TraceFilters, TraceSearchResult,
+    TraceSummary, TracesDomainError,
 };
 use crate::modules::traces::infrastructure::persistence::models::{SpanRow, TraceSummaryRow};
 
@@ -47,71 +48,113 @@ impl TimescaleSpanRepository {
             row.attributes,
             events,
             links,
-        ))
+        )
+        .with_trace_state(row.trace_state)
+        .with_dropped_attributes(row.dropped_attributes_count.max(0) as u32))
     }
 }
 
 #[async_trait]
 impl SpansRepository for TimescaleSpanRepository {
-    async fn save_batch(&self, spans: &[Span]) -> Result<u32, TracesDomainError> {
+    async fn save_batch(
+        &self,
+        spans: &[Span],
+        update_duplicates: bool,
+    ) -> Result<SpanSaveResult, TracesDomainError> {
+        let mut result = SpanSaveResult::default();
         if spans.is_empty() {
-            return Ok(0);
+            return Ok(result);
         }
 
+        // A resent span conflicts on the unique span index. Returns whether the
+        // row was inserted (xmax = 0) or updated; no row means it was skipped.
+        let on_conflict = if update_duplicates {
+            r#"
+                DO UPDATE SET
+                    parent_span_id = EXCLUDED.parent_span_id,
+                    name = EXCLUDED.name,
+                    kind = EXCLUDED.kind,
+                    end_time = EXCLUDED.end_time,
+                    duration_ns = EXCLUDED.duration_ns,
+                    status = EXCLUDED.status,
+                    status_message = EXCLUDED.status_message,
+                    received_at = EXCLUDED.received_at,
+                    service_name = EXCLUDED.service_name,
+                    service_version = EXCLUDED.service_version,
+                    resource_attributes = EXCLUDED.resource_attributes,
+                    attributes = EXCLUDED.attributes,
+                    events = EXCLUDED.events,
+                    links = EXCLUDED.links,
+                    trace_state = EXCLUDED.trace_state,
+                    sampling_probability = EXCLUDED.sampling_probability,
+                    dropped_attributes_count = EXCLUDED.dropped_attributes_count
+                WHERE spans.received_at < EXCLUDED.received_at
+            "#
+        } else {
+            "DO NOTHING"
+        };
+        let sql = format!(
+            r#"
+            INSERT INTO spans (
+                id, project_id, trace_id, span_id, parent_span_id, name, kind,
+                start_time, end_time, duration_ns, status, status_message, received_at,
+                service_name, service_version, resource_attributes, attributes, events, links,
+                trace_state, sampling_probability, dropped_attributes_count
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22)
+            ON CONFLICT (project_id, trace_id, span_id, start_time) {on_conflict}
+            RETURNING (xmax = 0) AS inserted
+            "#
+        );
+
         let mut tx = self
             .pool
             .begin()
             .await
             .map_err(|e| TracesDomainError::InternalError(e.to_string()))?;
 
-        let mut count = 0u32;
-
         for span in spans {
             let events_json = json!(span.events());
             let links_json = json!(span.links());
 
-            sqlx::query(
-                r#"
-                INSERT INTO spans (
-                    id, project_id, trace_id, span_id, parent_span_id, name, kind,
-                    start_time, end_time, duration_ns, status, status_message, received_at,
-                    service_name, service_version, resource_attributes, attributes, events, links
-                )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
-                ON CONFLICT (project_id, start_time, id) DO NOTHING
-                "#,
-            )
-            .bind(span.id())
-            .bind(span.project_id().as_str())
-            .bind(span.trace_id())
-            .bind(span.span_id())
-            .bind(span.parent_span_id())
-            .bind(span.name())
-            .bind(span.kind().as_str())
-            .bind(span.start_time())
-            .bind(span.end_time())
-            .bind(span.duration_ns())
-            .bind(span.status().as_str())
-            .bind(span.status_message())
-            .bind(Utc::now())
-            .bind(span.service_name())
-            .bind(span.service_version())
-            .bind(span.resource_attributes())
-            .bind(span.attributes())
-            .bind(&events_json)
-            .bind(&links_json)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| TracesDomainError::InternalError(e.to_string()))?;
-
-            count += 1;
+            let inserted: Option<bool> = sqlx::query_scalar(&sql)
+                .bind(span.id())
+                .bind(span.project_id().as_str())
+                .bind(span.trace_id())
+                .bind(span.span_id())
+                .bind(span.parent_span_id())
+                .bind(span.name())
+                .bind(span.kind().as_str())
+                .bind(span.start_time())
+                .bind(span.end_time())
+                .bind(span.duration_ns())
+                .bind(span.status().as_str())
+                .bind(span.status_message())
+                .bind(Utc::now())
+                .bind(span.service_name())
+                .bind(span.service_version())
+                .bind(span.resource_attributes())
+                .bind(span.attributes())
+                .bind(&events_json)
+                .bind(&links_json)
+                .bind(span.trace_state())
+                .bind(span.sampling_probability())
+                .bind(span.dropped_attributes_count() as i32)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| TracesDomainError::InternalError(e.to_string()))?;
+
+            match inserted {
+                Some(true) => result.saved += 1,
+                _ => result.duplicates += 1,
+            }
         }
 
         tx.commit()
             .await
             .map_err(|e| TracesDomainError::InternalError(e.to_string()))?;
 
-        Ok(count)
+        Ok(result)
     }
 
     async fn get_trace(
@@ -123,7 +166,8 @@ impl SpansRepository for TimescaleSpanRepository {
             r#"
             SELECT id, project_id, trace_id, span_id, parent_span_id, name, kind,
                    start_time, end_time, duration_ns, status, status_message, received_at,
-                   service_name, service_version, resource_attributes, attributes, events, links
+                   service_name, service_version, resource_attributes, attributes, events, links,
+                   trace_state, dropped_attributes_count
             FROM spans
             WHERE project_id = $1 AND trace_id = $2
             ORDER BY start_time ASC
@@ -160,6 +204,10 @@ impl SpansRepository for TimescaleSpanRepository {
             conditions.push(format!("status = ${}", param_idx));
             param_idx += 1;
         }
+        if filters.span_kind.is_some() {
+            conditions.push(format!("kind = ${}", param_idx));
+            param_idx += 1;
+        }
         if filters.start_time.is_some() {
             conditions.push(format!("start_time >= ${}", param_idx));
             param_idx += 1;
@@ -176,6 +224,13 @@ impl SpansRepository for TimescaleSpanRepository {
             conditions.push(format!("duration_ns <= ${}", param_idx));
             param_idx += 1;
         }
+        if filters.settled_before.is_some() {
+            conditions.push(format!(
+                "trace_id NOT IN (SELECT trace_id FROM spans WHERE project_id = $1 AND received_at > ${})",
+                param_idx
+            ));
+            param_idx += 1;
+        }
 
         let where_clause = conditions.join(" AND ");
 
@@ -183,7 +238,8 @@ impl SpansRepository for TimescaleSpanRepository {
         let query = format!(
             r#"
             WITH filtered_spans AS (
-                SELECT trace_id, name, service_name, start_time, end_time, duration_ns, status, parent_span_id
+                SELECT trace_id, name, service_name, start_time, end_time, duration_ns, status, parent_span_id,
+                       received_at
                 FROM spans
                 WHERE {}
             ),
@@ -196,12 +252,13 @@ impl SpansRepository for TimescaleSpanRepository {
                     COUNT(*) FILTER (WHERE status = 'error') as error_count,
                     MIN(start_time) as start_time,
                     MAX(end_time) as end_time,
-                    EXTRACT(EPOCH FROM (MAX(end_time) - MIN(start_time))) * 1000000000 as duration_ns
+                    EXTRACT(EPOCH FROM (MAX(end_time) - MIN(start_time))) * 1000000000 as duration_ns,
+                    MAX(received_at) as last_received_at
                 FROM filtered_spans
                 GROUP BY trace_id
             )
             SELECT trace_id, root_span_name, service_names, span_count, error_count,
-                   start_time, end_time, duration_ns::BIGINT
+                   start_time, end_time, duration_ns::BIGINT, last_received_at
             FROM trace_stats
             ORDER BY start_time DESC
             LIMIT ${} OFFSET ${}
@@ -223,6 +280,9 @@ impl SpansRepository for TimescaleSpanRepository {
         if let Some(ref status) = filters.status {
             query_builder = query_builder.bind(status.as_str());
         }
+        if let Some(kind) = filters.span_kind {
+            query_builder = query_builder.bind(kind.as_str());
+        }
         if let Some(start_time) = filters.start_time {
             query_builder = query_builder.bind(start_time);
         }
@@ -235,6 +295,9 @@ impl SpansRepository for TimescaleSpanRepository {
         if let Some(max_duration) = filters.max_duration_ns {
             query_builder = query_builder.bind(max_duration);
         }
+        if let Some(settled_before) = filters.settled_before {
+            query_builder = query_builder.bind(settled_before);
+        }
 
         query_builder = query_builder.bind(pagination.limit).bind(pagination.offset);
 
@@ -265,6 +328,9 @@ impl SpansRepository for TimescaleSpanRepository {
         if let Some(ref status) = filters.status {
             count_builder = count_builder.bind(status.as_str());
         }
+        if let Some(kind) = filters.span_kind {
+            count_builder = count_builder.bind(kind.as_str());
+        }
         if let Some(start_time) = filters.start_time {
             count_builder = count_builder.bind(start_time);
         }
@@ -277,6 +343,9 @@ impl SpansRepository for TimescaleSpanRepository {
         if let Some(max_duration) = filters.max_duration_ns {
             count_builder = count_builder.bind(max_duration);
         }
+        if let Some(settled_before) = filters.settled_before {
+            count_builder = count_builder.bind(settled_before);
+        }
 
         let total: i64 = count_builder
             .fetch_one(&self.pool)
@@ -294,12 +363,360 @@ impl SpansRepository for TimescaleSpanRepository {
                 start_time: row.start_time,
                 end_time: row.end_time,
                 duration_ns: row.duration_ns,
+                last_received_at: row.last_received_at,
             })
             .collect();
 
         Ok(TraceSearchResult { traces, total })
     }
 
+    async fn count_spans(
+        &self,
+        project_id: &ProjectId,
+        filters: &TraceFilters,
+    ) -> Result<SpanCounts, TracesDomainError> {
+        let mut conditions = vec!["project_id = $1".to_string()];
+        let mut param_idx = 2;
+
+        if filters.service_name.is_some() {
+            conditions.push(format!("service_name = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.span_name.is_some() {
+            conditions.push(format!("name ILIKE ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.status.is_some() {
+            conditions.push(format!("status = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.span_kind.is_some() {
+            conditions.push(format!("kind = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.start_time.is_some() {
+            conditions.push(format!("start_time >= ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.end_time.is_some() {
+            conditions.push(format!("start_time <= ${}", param_idx));
+        }
+
+        // Spans without a sampling probability were kept unconditionally and count once
+        let query = format!(
+            r#"
+            SELECT COUNT(*) as spans,
+                   COALESCE(SUM(1.0 / COALESCE(sampling_probability, 1.0)), 0)::DOUBLE PRECISION as adjusted
+            FROM spans
+            WHERE {}
+            "#,
+            conditions.join(" AND ")
+        );
+
+        let mut query_builder = sqlx::query_as::<_, (i64, f64)>(&query);
+        query_builder = query_builder.bind(project_id.as_str());
+
+        if let Some(ref service_name) = filters.service_name {
+            query_builder = query_builder.bind(service_name);
+        }
+        if let Some(ref span_name) = filters.span_name {
+            query_builder = query_builder.bind(format!("%{}%", span_name));
+        }
+        if let Some(ref status) = filters.status {
+            query_builder = query_builder.bind(status.as_str());
+        }
+        if let Some(kind) = filters.span_kind {
+            query_builder = query_builder.bind(kind.as_str());
+        }
+        if let Some(start_time) = filters.start_time {
+            query_builder = query_builder.bind(start_time);
+        }
+        if let Some(end_time) = filters.end_time {
+            query_builder = query_builder.bind(end_time);
+        }
+
+        let (spans, adjusted) = query_builder
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| TracesDomainError::InternalError(e.to_string()))?;
+
+        Ok(SpanCounts { spans, adjusted })
+    }
+
+    async fn duration_histogram(
+        &self,
+        project_id: &ProjectId,
+        filters: &TraceFilters,
+        bucket_boundaries: &[i64],
+    ) -> Result<Vec<DurationBucket>, TracesDomainError> {
+        let mut conditions = vec![
+            "project_id = $1".to_string(),
+            "duration_ns IS NOT NULL".to_string(),
+            "duration_ns >= 0".to_string(),
+        ];
+        let mut param_idx = 3;
+
+        if filters.service_name.is_some() {
+            conditions.push(format!("service_name = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.span_name.is_some() {
+            conditions.push(format!("name ILIKE ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.status.is_some() {
+            conditions.push(format!("status = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.span_kind.is_some() {
+            conditions.push(format!("kind = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.start_time.is_some() {
+            conditions.push(format!("start_time >= ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.end_time.is_some() {
+            conditions.push(format!("start_time <= ${}", param_idx));
+        }
+
+        // width_bucket returns 0 below the first boundary and n at or above the last,
+        // which lines up with the indexes of DurationBucket::empty_buckets
+        let query = format!(
+            r#"
+            SELECT width_bucket(duration_ns, $2::BIGINT[]) as bucket, COUNT(*) as count
+            FROM spans
+            WHERE {}
+            GROUP BY bucket
+            "#,
+            conditions.join(" AND ")
+        );
+
+        let mut query_builder = sqlx::query_as::<_, (i32, i64)>(&query);
+        query_builder = query_builder
+            .bind(project_id.as_str())
+            .bind(bucket_boundaries);
+
+        if let Some(ref service_name) = filters.service_name {
+            query_builder = query_builder.bind(service_name);
+        }
+        if let Some(ref span_name) = filters.span_name {
+            query_builder = query_builder.bind(format!("%{}%", span_name));
+        }
+        if let Some(ref status) = filters.status {
+            query_builder = query_builder.bind(status.as_str());
+        }
+        if let Some(kind) = filters.span_kind {
+            query_builder = query_builder.bind(kind.as_str());
+        }
+        if let Some(start_time) = filters.start_time {
+            query_builder = query_builder.bind(start_time);
+        }
+        if let Some(end_time) = filters.end_time {
+            query_builder = query_builder.bind(end_time);
+        }
+
+        let rows = query_builder
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| TracesDomainError::InternalError(e.to_string()))?;
+
+        let mut buckets = DurationBucket::empty_buckets(bucket_boundaries);
+        for (bucket, count) in rows {
+            if let Some(b) = buckets.get_mut(bucket as usize) {
+                b.count = count;
+            }
+        }
+        Ok(buckets)
+    }
+
+    async fn count_errors(
+        &self,
+        project_id: &ProjectId,
+        filters: &TraceFilters,
+        unit: ErrorRateUnit,
+    ) -> Result<(i64, i64), TracesDomainError> {
+        let mut conditions = vec!["project_id = $1".to_string()];
+        let mut param_idx = 2;
+
+        if filters.service_name.is_some() {
+            conditions.push(format!("service_name = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.span_name.is_some() {
+            conditions.push(format!("name ILIKE ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.status.is_some() {
+            conditions.push(format!("status = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.span_kind.is_some() {
+            conditions.push(format!("kind = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.start_time.is_some() {
+            conditions.push(format!("start_time >= ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.end_time.is_some() {
+            conditions.push(format!("start_time <= ${}", param_idx));
+        }
+
+        // Both counts come from one scan of the window
+        let query = match unit {
+            ErrorRateUnit::Spans => format!(
+                r#"
+                SELECT COUNT(*) FILTER (WHERE status = 'error') as errors, COUNT(*) as total
+                FROM spans
+                WHERE {}
+                "#,
+                conditions.join(" AND ")
+            ),
+            ErrorRateUnit::Traces => format!(
+                r#"
+                SELECT COUNT(*) FILTER (WHERE has_error) as errors, COUNT(*) as total
+                FROM (
+                    SELECT trace_id, BOOL_OR(status = 'error') as has_error
+                    FROM spans
+                    WHERE {}
+                    GROUP BY trace_id
+                ) traces
+                "#,
+                conditions.join(" AND ")
+            ),
+        };
+
+        let mut query_builder = sqlx::query_as::<_, (i64, i64)>(&query);
+        query_builder = query_builder.bind(project_id.as_str());
+
+        if let Some(ref service_name) = filters.service_name {
+            query_builder = query_builder.bind(service_name);
+        }
+        if let Some(ref span_name) = filters.span_name {
+            query_builder = query_builder.bind(format!("%{}%", span_name));
+        }
+        if let Some(ref status) = filters.status {
+            query_builder = query_builder.bind(status.as_str());
+        }
+        if let Some(kind) = filters.span_kind {
+            query_builder = query_builder.bind(kind.as_str());
+        }
+        if let Some(start_time) = filters.start_time {
+            query_builder = query_builder.bind(start_time);
+        }
+        if let Some(end_time) = filters.end_time {
+            query_builder = query_builder.bind(end_time);
+        }
+
+        query_builder
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| TracesDomainError::InternalError(e.to_string()))
+    }
+
+    async fn duration_range(
+        &self,
+        project_id: &ProjectId,
+        filters: &TraceFilters,
+    ) -> Result<Option<(i64, i64)>, TracesDomainError> {
+        let mut conditions = vec![
+            "project_id = $1".to_string(),
+            "duration_ns IS NOT NULL".to_string(),
+            "duration_ns >= 0".to_string(),
+        ];
+        let mut param_idx = 2;
+
+        if filters.service_name.is_some() {
+            conditions.push(format!("service_name = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.span_name.is_some() {
+            conditions.push(format!("name ILIKE ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.status.is_some() {
+            conditions.push(format!("status = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.span_kind.is_some() {
+            conditions.push(format!("kind = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.start_time.is_some() {
+            conditions.push(format!("start_time >= ${}", param_idx));
+            param_idx += 1;
+        }
+        if filters.end_time.is_some() {
+            conditions.push(format!("start_time <= ${}", param_idx));
+        }
+
+        let query = format!(
+            r#"
+            SELECT MIN(duration_ns), MAX(duration_ns)
+            FROM spans
+            WHERE {}
+            "#,
+            conditions.join(" AND ")
+        );
+
+        let mut query_builder = sqlx::query_as::<_, (Option<i64>, Option<i64>)>(&query);
+        query_builder = query_builder.bind(project_id.as_str());
+
+        if let Some(ref service_name) = filters.service_name {
+            query_builder = query_builder.bind(service_name);
+        }
+        if let Some(ref span_name) = filters.span_name {
+            query_builder = query_builder.bind(format!("%{}%", span_name));
+        }
+        if let Some(ref status) = filters.status {
+            query_builder = query_builder.bind(status.as_str());
+        }
+        if let Some(kind) = filters.span_kind {
+            query_builder = query_builder.bind(kind.as_str());
+        }
+        if let Some(start_time) = filters.start_time {
+            query_builder = query_builder.bind(start_time);
+        }
+        if let Some(end_time) = filters.end_time {
+            query_builder = query_builder.bind(end_time);
+        }
+
+        let (min, max) = query_builder
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| TracesDomainError::InternalError(e.to_string()))?;
+
+        Ok(min.zip(max))
+    }
+
+    async fn find_in_range(
+        &self,
+        project_id: &ProjectId,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Span>, TracesDomainError> {
+        let rows: Vec<SpanRow> = sqlx::query_as(
+            r#"
+            SELECT id, project_id, trace_id, span_id, parent_span_id, name, kind,
+                   start_time, end_time, duration_ns, status, status_message, received_at,
+                   service_name, service_version, resource_attributes, attributes, events, links,
+                   trace_state, dropped_attributes_count
+            FROM spans
+            WHERE project_id = $1 AND start_time >= $2 AND start_time < $3
+            ORDER BY start_time ASC
+            "#,
+        )
+        .bind(project_id.as_str())
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| TracesDomainError::InternalError(e.to_string()))?;
+
+        rows.into_iter().map(Self::row_to_span).collect()
+    }
+
     async fn get_service_names(
         &self,
         project_id: &ProjectId,
@@ -324,15 +741,87 @@ impl SpansRepository for TimescaleSpanRepository {
         &self,
         project_id: &ProjectId,
         before: chrono::DateTime<chrono::Utc>,
+        limit: u32,
     ) -> Result<u64, TracesDomainError> {
         let result = sqlx::query(
             r#"
             DELETE FROM spans
-            WHERE project_id = $1 AND start_time < $2
+            WHERE project_id = $1 AND (start_time, id) IN (
+                SELECT start_time, id
+                FROM spans
+                WHERE project_id = $1 AND start_time < $2
+                ORDER BY start_time
+                LIMIT $3
+            )
             "#,
         )
         .bind(project_id.as_str())
         .bind(before)
+        .bind(limit as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| TracesDomainError::InternalError(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+    async fn delete_expired_traces(
+        &self,
+        project_id: &ProjectId,
+        cutoffs: &TraceCutoffs,
+        limit: u32,
+    ) -> Result<u64, TracesDomainError> {
+        let services: Vec<&str> = cutoffs.services.keys().map(String::as_str).collect();
+        let error_cutoffs: Vec<_> = cutoffs.services.values().map(|c| c.error).collect();
+        let clean_cutoffs: Vec<_> = cutoffs.services.values().map(|c| c.clean).collect();
+
+        // Only traces with a span before the latest cutoff can expire; each one is
+        // then judged on all of its spans
+        let result = sqlx::query(
+            r#"
+            WITH candidates AS (
+                SELECT DISTINCT trace_id
+                FROM spans
+                WHERE project_id = $1 AND start_time < $2
+            ),
+            traces AS (
+                SELECT
+                    s.trace_id,
+                    MAX(s.start_time) AS last_start,
+                    BOOL_OR(s.status = 'error') AS has_error,
+                    (ARRAY_AGG(s.service_name ORDER BY s.start_time)
+                        FILTER (WHERE s.parent_span_id IS NULL))[1] AS root_service
+                FROM spans s
+                JOIN candidates c ON c.trace_id = s.trace_id
+                WHERE s.project_id = $1
+                GROUP BY s.trace_id
+            ),
+            overrides AS (
+                SELECT *
+                FROM UNNEST($5::text[], $6::timestamptz[], $7::timestamptz[])
+                    AS o(service, error_cutoff, clean_cutoff)
+            ),
+            expired AS (
+                SELECT t.trace_id
+                FROM traces t
+                LEFT JOIN overrides o ON o.service = t.root_service
+                WHERE t.last_start < CASE
+                    WHEN t.has_error THEN COALESCE(o.error_cutoff, $3)
+                    ELSE COALESCE(o.clean_cutoff, $4)
+                END
+                LIMIT $8
+            )
+            DELETE FROM spans
+            WHERE project_id = $1 AND trace_id IN (SELECT trace_id FROM expired)
+            "#,
+        )
+        .bind(project_id.as_str())
+        .bind(cutoffs.latest())
+        .bind(cutoffs.default.error)
+        .bind(cutoffs.default.clean)
+        .bind(&services)
+        .bind(&error_cutoffs)
+        .bind(&clean_cutoffs)
+        .bind(limit as i64)
         .execute(&self.pool)
         .await
         .map_err(|e| TracesDomainError::InternalError(e.to_string()))?;
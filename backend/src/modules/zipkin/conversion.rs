@@ -0,0 +1,207 @@
+//! Convert Zipkin v2 spans to internal span format
+
+use chrono::DateTime;
+use serde_json::{Map, Value, json};
+
+use crate::modules::traces::application::dto::{SpanEventInput, SpanInput};
+use crate::modules::zipkin::types::{ZipkinEndpoint, ZipkinSpan};
+
+/// Convert a batch of Zipkin spans to internal SpanInput format
+pub fn convert_zipkin_spans(spans: Vec<ZipkinSpan>) -> Vec<SpanInput> {
+    spans.into_iter().map(convert_span).collect()
+}
+
+fn convert_span(span: ZipkinSpan) -> SpanInput {
+    let start_time = span.timestamp.and_then(DateTime::from_timestamp_micros);
+    let end_time = start_time
+        .zip(span.duration)
+        .map(|(start, micros)| start + chrono::Duration::microseconds(micros));
+
+    // Zipkin marks failures with an "error" tag whose value is the message
+    let error = span.tags.get("error").cloned();
+    let status = error.as_ref().map(|_| "error".to_string());
+    let status_message = error.filter(|msg| !msg.is_empty());
+
+    let mut attributes: Map<String, Value> = span
+        .tags
+        .into_iter()
+        .map(|(key, value)| (key, Value::String(value)))
+        .collect();
+    if let Some(remote) = &span.remote_endpoint {
+        if let Some(peer) = &remote.service_name {
+            attributes.insert("peer.service".to_string(), json!(peer));
+        }
+        insert_endpoint_address(&mut attributes, "net.peer", remote);
+    }
+
+    let local = span.local_endpoint.unwrap_or_default();
+    let mut resource_attributes = Map::new();
+    if let Some(service) = &local.service_name {
+        resource_attributes.insert("service.name".to_string(), json!(service));
+    }
+    insert_endpoint_address(&mut resource_attributes, "net.host", &local);
+
+    let events = span
+        .annotations
+        .into_iter()
+        .filter_map(|a| {
+            DateTime::from_timestamp_micros(a.timestamp).map(|timestamp| SpanEventInput {
+                name: a.value,
+                timestamp,
+                attributes: json!({}),
+            })
+        })
+        .collect();
+
+    SpanInput {
+        trace_id: pad_trace_id(&span.trace_id),
+        span_id: span.id,
+        parent_span_id: span.parent_id.filter(|id| !id.is_empty()),
+        name: span.name.unwrap_or_else(|| "unknown".to_string()),
+        kind: Some(
+            span.kind
+                .map(|k| k.to_lowercase())
+                .unwrap_or_else(|| "internal".to_string()),
+        ),
+        start_time,
+        end_time,
+        status,
+        status_message,
+        service_name: local.service_name,
+        service_version: None,
+        resource_attributes: Value::Object(resource_attributes),
+        attributes: Value::Object(attributes),
+        events,
+        links: vec![],
+        trace_state: None,
+        dropped_attributes_count: 0,
+    }
+}
+
+/// Zipkin allows 64-bit trace IDs; left-pad them to the 128-bit form OTLP uses
+fn pad_trace_id(trace_id: &str) -> String {
+    format!("{:0>32}", trace_id.to_lowercase())
+}
+
+fn insert_endpoint_address(
+    attributes: &mut Map<String, Value>,
+    prefix: &str,
+    endpoint: &ZipkinEndpoint,
+) {
+    if let Some(ip) = endpoint.ipv4.as_ref().or(endpoint.ipv6.as_ref()) {
+        attributes.insert(format!("{}.ip", prefix), json!(ip));
+    }
+    if let Some(port) = endpoint.port {
+        attributes.insert(format!("{}.port", prefix), json!(port));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::modules::projects::domain::ProjectId;
+    use crate::modules::traces::application::TraceService;
+    use crate::modules::traces::application::dto::IngestSpansCommand;
+    use crate::modules::traces::domain::{SpanKind, SpanStatusCode, SpansRepository};
+    use crate::shared::PaginationConfig;
+    use crate::shared::testing::{
+        InMemoryMemberRepository, InMemoryProjectRepository, InMemorySpansRepository,
+        SequentialIdGenerator,
+    };
+
+    const BATCH: &str = r#"[
+        {
+            "traceId": "463ac35c9f6413ad",
+            "id": "a2fb4a1d1a96d312",
+            "name": "get /api",
+            "kind": "SERVER",
+            "timestamp": 1704067200000000,
+            "duration": 207000,
+            "localEndpoint": {"serviceName": "frontend", "ipv4": "10.0.0.1"},
+            "tags": {"http.method": "GET", "http.path": "/api"}
+        },
+        {
+            "traceId": "463ac35c9f6413ad",
+            "id": "b7ad6b7169203331",
+            "parentId": "a2fb4a1d1a96d312",
+            "name": "query",
+            "kind": "CLIENT",
+            "timestamp": 1704067200010000,
+            "duration": 1500,
+            "localEndpoint": {"serviceName": "frontend"},
+            "remoteEndpoint": {"serviceName": "postgres", "port": 5432},
+            "annotations": [{"timestamp": 1704067200010500, "value": "retry"}],
+            "tags": {"error": "connection reset"}
+        }
+    ]"#;
+
+    #[tokio::test]
+    async fn test_ingest_zipkin_batch() {
+        let spans_repo = Arc::new(InMemorySpansRepository::new());
+        let project_repo = Arc::new(InMemoryProjectRepository::new());
+        project_repo.seed("project-1", "org-1");
+        let service = TraceService::new(
+            spans_repo.clone(),
+            project_repo,
+            Arc::new(InMemoryMemberRepository::new()),
+            Arc::new(SequentialIdGenerator::new()),
+            PaginationConfig::default(),
+        );
+
+        let zipkin: Vec<ZipkinSpan> = serde_json::from_str(BATCH).unwrap();
+        let response = service
+            .ingest(IngestSpansCommand {
+                project_id: "project-1".to_string(),
+                spans: convert_zipkin_spans(zipkin),
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.ingested, 2);
+
+        let project_id = ProjectId::new("project-1".to_string());
+        let spans = spans_repo
+            .get_trace(&project_id, "0000000000000000463ac35c9f6413ad")
+            .await
+            .unwrap();
+        assert_eq!(spans.len(), 2);
+
+        let server = spans
+            .iter()
+            .find(|s| s.span_id() == "a2fb4a1d1a96d312")
+            .unwrap();
+        assert_eq!(server.service_name(), Some("frontend"));
+        assert_eq!(server.kind(), SpanKind::Server);
+        assert_eq!(server.duration_ns(), Some(207_000_000));
+        assert_eq!(server.attributes()["http.method"], "GET");
+        assert_eq!(server.resource_attributes()["net.host.ip"], "10.0.0.1");
+
+        let client = spans
+            .iter()
+            .find(|s| s.span_id() == "b7ad6b7169203331")
+            .unwrap();
+        assert_eq!(client.kind(), SpanKind::Client);
+        assert_eq!(client.parent_span_id(), Some("a2fb4a1d1a96d312"));
+        assert_eq!(client.duration_ns(), Some(1_500_000));
+        assert_eq!(client.status(), SpanStatusCode::Error);
+        assert_eq!(client.status_message(), Some("connection reset"));
+        assert_eq!(client.attributes()["peer.service"], "postgres");
+        assert_eq!(client.events().len(), 1);
+    }
+
+    #[test]
+    fn test_local_span_defaults_to_internal() {
+        let span: ZipkinSpan = serde_json::from_str(
+            r#"{"traceId": "463ac35c9f6413ad48485a3953bb6124", "id": "a2fb4a1d1a96d312"}"#,
+        )
+        .unwrap();
+
+        let input = convert_span(span);
+
+        assert_eq!(input.kind.as_deref(), Some("internal"));
+        assert_eq!(input.trace_id, "463ac35c9f6413ad48485a3953bb6124");
+        assert_eq!(input.end_time, None);
+        assert_eq!(input.status, None);
+    }
+}
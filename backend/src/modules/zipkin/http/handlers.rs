@@ -0,0 +1,82 @@
+//! Zipkin HTTP handlers
+
+use axum::{Extension, Json, body::Bytes, extract::State, http::StatusCode};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::logging::infrastructure::ApiKeyContext;
+use crate::modules::organizations::domain::OrganizationMemberRepository;
+use crate::modules::projects::domain::ProjectRepository;
+use crate::modules::traces::application::TraceService;
+use crate::modules::traces::application::dto::IngestSpansCommand;
+use crate::modules::traces::domain::{SpansRepository, TracesDomainError};
+use crate::modules::zipkin::conversion::convert_zipkin_spans;
+use crate::modules::zipkin::types::ZipkinSpan;
+
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub code: String,
+}
+
+/// POST /api/v2/spans - Zipkin v2 JSON span ingestion
+pub async fn ingest_zipkin_spans<SR, PR, OMR, ID>(
+    State(service): State<Arc<TraceService<SR, PR, OMR, ID>>>,
+    Extension(ctx): Extension<ApiKeyContext>,
+    body: Bytes,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)>
+where
+    SR: SpansRepository,
+    PR: ProjectRepository,
+    OMR: OrganizationMemberRepository,
+    ID: IdGenerator,
+{
+    let zipkin_spans: Vec<ZipkinSpan> = serde_json::from_slice(&body).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!("Invalid JSON: {}", e),
+                code: "INVALID_JSON".to_string(),
+            }),
+        )
+    })?;
+
+    if zipkin_spans.is_empty() {
+        return Ok(StatusCode::ACCEPTED);
+    }
+
+    let cmd = IngestSpansCommand {
+        project_id: ctx.project_id.as_str().to_string(),
+        spans: convert_zipkin_spans(zipkin_spans),
+    };
+
+    service.ingest(cmd).await.map_err(|e| {
+        let (status, msg) = match e {
+            TracesDomainError::ProjectNotFound | TracesDomainError::ProjectDeleted => {
+                (StatusCode::NOT_FOUND, "Project not found".to_string())
+            }
+            TracesDomainError::BatchTooLarge(ref msg) => {
+                (StatusCode::PAYLOAD_TOO_LARGE, msg.clone())
+            }
+            TracesDomainError::InternalError(ref msg) => {
+                tracing::error!(error = %msg, "Internal error during Zipkin span ingestion");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal error".to_string(),
+                )
+            }
+            other => (StatusCode::BAD_REQUEST, other.to_string()),
+        };
+        (
+            status,
+            Json(ErrorResponse {
+                error: msg,
+                code: "INGESTION_ERROR".to_string(),
+            }),
+        )
+    })?;
+
+    // Zipkin collectors answer 202 Accepted with an empty body
+    Ok(StatusCode::ACCEPTED)
+}
@@ -0,0 +1,42 @@
+//! Zipkin HTTP routes
+
+use axum::{Router, extract::DefaultBodyLimit, middleware, routing::post};
+use std::sync::Arc;
+
+use super::handlers;
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::logging::infrastructure::http::middleware::api_key_middleware;
+use crate::modules::organizations::domain::{OrganizationMemberRepository, OrganizationRepository};
+use crate::modules::projects::application::ProjectService;
+use crate::modules::projects::domain::{ApiKeyRepository, ProjectRepository};
+use crate::modules::traces::application::TraceService;
+use crate::modules::traces::domain::SpansRepository;
+
+/// Zipkin routes for span ingestion (requires API key middleware)
+pub fn zipkin_routes<SR, PR, OMR, ID, PPR, AR, OR>(
+    service: Arc<TraceService<SR, PR, OMR, ID>>,
+    project_service: Arc<ProjectService<PPR, AR, OR, OMR, ID>>,
+) -> Router
+where
+    SR: SpansRepository + 'static,
+    PR: ProjectRepository + 'static,
+    OMR: OrganizationMemberRepository + 'static,
+    ID: IdGenerator + 'static,
+    PPR: ProjectRepository + 'static,
+    AR: ApiKeyRepository + 'static,
+    OR: OrganizationRepository + 'static,
+{
+    let max_bytes = service.batch_limits().max_bytes;
+
+    Router::new()
+        .route(
+            "/spans",
+            post(handlers::ingest_zipkin_spans::<SR, PR, OMR, ID>),
+        )
+        .layer(DefaultBodyLimit::max(max_bytes))
+        .layer(middleware::from_fn_with_state(
+            project_service,
+            api_key_middleware::<PPR, AR, OR, OMR, ID>,
+        ))
+        .with_state(service)
+}
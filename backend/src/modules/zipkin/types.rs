@@ -0,0 +1,56 @@
+//! Zipkin v2 JSON span types
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A Zipkin v2 span. Timestamps and durations are in microseconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZipkinSpan {
+    /// Trace ID (hex string, 16 or 32 chars)
+    pub trace_id: String,
+    /// Span ID (hex string, 16 chars)
+    pub id: String,
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// CLIENT, SERVER, PRODUCER or CONSUMER; absent for local spans
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// Start time in microseconds since epoch
+    #[serde(default)]
+    pub timestamp: Option<i64>,
+    /// Duration in microseconds
+    #[serde(default)]
+    pub duration: Option<i64>,
+    #[serde(default)]
+    pub local_endpoint: Option<ZipkinEndpoint>,
+    #[serde(default)]
+    pub remote_endpoint: Option<ZipkinEndpoint>,
+    #[serde(default)]
+    pub annotations: Vec<ZipkinAnnotation>,
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZipkinEndpoint {
+    #[serde(default)]
+    pub service_name: Option<String>,
+    #[serde(default)]
+    pub ipv4: Option<String>,
+    #[serde(default)]
+    pub ipv6: Option<String>,
+    #[serde(default)]
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZipkinAnnotation {
+    /// Microseconds since epoch
+    pub timestamp: i64,
+    pub value: String,
+}
@@ -0,0 +1,97 @@
+use axum::{Router, extract::DefaultBodyLimit};
+
+/// Largest auth request body, in bytes, unless configured
+const DEFAULT_AUTH_BYTES: usize = 64 * 1024;
+
+/// Largest ingest request body, in bytes, unless configured
+const DEFAULT_INGEST_BYTES: usize = 10 * 1024 * 1024;
+
+/// Largest body for every other API request, in bytes, unless configured
+/// (axum's own default)
+const DEFAULT_QUERY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Request body size limits for each group of routes. Bodies over a group's
+/// limit are rejected with 413 by the extractor reading them. A route that
+/// sets its own `DefaultBodyLimit` (like trace ingest) keeps it, as the
+/// innermost layer wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyLimits {
+    pub auth: usize,
+    pub ingest: usize,
+    pub query: usize,
+}
+
+impl Default for BodyLimits {
+    fn default() -> Self {
+        Self {
+            auth: DEFAULT_AUTH_BYTES,
+            ingest: DEFAULT_INGEST_BYTES,
+            query: DEFAULT_QUERY_BYTES,
+        }
+    }
+}
+
+impl BodyLimits {
+    /// Merge the route groups into one router, each capped at its own limit
+    pub fn apply(&self, auth: Router, ingest: Router, query: Router) -> Router {
+        Router::new()
+            .merge(auth.layer(DefaultBodyLimit::max(self.auth)))
+            .merge(ingest.layer(DefaultBodyLimit::max(self.ingest)))
+            .merge(query.layer(DefaultBodyLimit::max(self.query)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::{Body, Bytes},
+        http::{Request, StatusCode},
+        routing::post,
+    };
+    use tower::ServiceExt;
+
+    fn app(limits: BodyLimits) -> Router {
+        let echo = post(|body: Bytes| async move { body.len().to_string() });
+        limits.apply(
+            Router::new().route("/api/auth/login", echo.clone()),
+            Router::new().route("/api/v1/ingest/logs", echo.clone()),
+            Router::new().route("/api/projects", echo),
+        )
+    }
+
+    async fn status(app: &Router, uri: &str, size: usize) -> StatusCode {
+        let request = Request::post(uri)
+            .body(Body::from(vec![b'x'; size]))
+            .unwrap();
+        app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_each_group_enforces_its_own_limit() {
+        let app = app(BodyLimits {
+            auth: 1024,
+            ingest: 64 * 1024,
+            query: 4 * 1024,
+        });
+
+        assert_eq!(status(&app, "/api/auth/login", 512).await, StatusCode::OK);
+        assert_eq!(
+            status(&app, "/api/auth/login", 8 * 1024).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        // The same body is well within the ingest limit
+        assert_eq!(
+            status(&app, "/api/v1/ingest/logs", 8 * 1024).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&app, "/api/v1/ingest/logs", 128 * 1024).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(
+            status(&app, "/api/projects", 8 * 1024).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use axum::{body::Body, extract::ConnectInfo, http::Request};
+use ipnet::IpNet;
+use std::net::{IpAddr, SocketAddr};
+
+/// Resolve the client's IP. X-Forwarded-For is only honoured when the
+/// connection comes from a trusted proxy, and is read right to left skipping
+/// further trusted proxies, so a client can't spoof its address by sending
+/// the header itself.
+pub fn client_ip(request: &Request<Body>, trusted_proxies: &[IpNet]) -> Option<IpAddr> {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()?
+        .0
+        .ip();
+    let is_trusted = |ip: &IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+    if !is_trusted(&peer) {
+        return Some(peer);
+    }
+
+    let forwarded: Vec<&str> = request
+        .headers()
+        .get_all("X-Forwarded-For")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .collect();
+
+    let mut client = peer;
+    for entry in forwarded.into_iter().rev() {
+        // An unparseable hop means the chain can't be trusted past this point
+        client = entry.trim().parse().ok()?;
+        if !is_trusted(&client) {
+            break;
+        }
+    }
+    Some(client)
+}
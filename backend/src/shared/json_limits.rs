@@ -0,0 +1,186 @@
+use axum::{
+    Json,
+    body::{Body, Bytes},
+    extract::{FromRequest, Request},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+/// Deepest nesting of objects and arrays accepted in an ingest payload
+const DEFAULT_MAX_DEPTH: usize = 64;
+
+/// Most object members and array elements accepted in an ingest payload, counted
+/// across the whole document
+const DEFAULT_MAX_ENTRIES: usize = 1_000_000;
+
+/// Structural limits checked on untrusted JSON bodies before they are deserialized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsonLimits {
+    pub max_depth: usize,
+    pub max_entries: usize,
+}
+
+impl Default for JsonLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_entries: DEFAULT_MAX_ENTRIES,
+        }
+    }
+}
+
+impl JsonLimits {
+    /// Scan the raw body without building it, stopping at the first limit exceeded.
+    /// Malformed JSON is left for the deserializer to report.
+    pub fn check(&self, body: &[u8]) -> Result<(), String> {
+        // One flag per open container: whether it has seen an entry yet
+        let mut open: Vec<bool> = Vec::new();
+        let mut entries = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+
+        for &byte in body {
+            if in_string {
+                match byte {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            if byte.is_ascii_whitespace() {
+                continue;
+            }
+            if matches!(byte, b'}' | b']') {
+                open.pop();
+                continue;
+            }
+            if byte == b',' {
+                entries += 1;
+            } else if let Some(has_entry) = open.last_mut()
+                && !*has_entry
+            {
+                *has_entry = true;
+                entries += 1;
+            }
+            if entries > self.max_entries {
+                return Err(format!(
+                    "JSON payload exceeds {} object members and array elements",
+                    self.max_entries
+                ));
+            }
+            match byte {
+                b'"' => in_string = true,
+                b'{' | b'[' => {
+                    open.push(false);
+                    if open.len() > self.max_depth {
+                        return Err(format!(
+                            "JSON payload nests deeper than {} levels",
+                            self.max_depth
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// JSON extractor for ingest bodies that enforces the request's [`JsonLimits`]
+/// (from an `Extension` layer, or the defaults) before deserializing.
+/// Otherwise behaves like [`Json`], including its rejections.
+pub struct LimitedJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for LimitedJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let limits = req
+            .extensions()
+            .get::<JsonLimits>()
+            .copied()
+            .unwrap_or_default();
+        let (parts, body) = req.into_parts();
+        let bytes = Bytes::from_request(Request::from_parts(parts.clone(), body), state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        if let Err(message) = limits.check(&bytes) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": message, "code": "PAYLOAD_TOO_COMPLEX" })),
+            )
+                .into_response());
+        }
+
+        let req = Request::from_parts(parts, Body::from(bytes));
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header::CONTENT_TYPE;
+    use serde_json::Value;
+
+    fn nested(depth: usize) -> String {
+        format!("{}1{}", r#"{"a":"#.repeat(depth), "}".repeat(depth))
+    }
+
+    #[test]
+    fn test_check_depth_and_entries() {
+        let limits = JsonLimits {
+            max_depth: 4,
+            max_entries: 5,
+        };
+
+        assert!(limits.check(nested(4).as_bytes()).is_ok());
+        assert!(limits.check(nested(5).as_bytes()).is_err());
+        // Brackets and commas inside strings are not structure
+        assert!(limits.check(br#"{"a":"[[[[[,,,,,,\"[[["}"#).is_ok());
+        assert!(limits.check(br#"{"a":[1,2,3],"b":{}}"#).is_ok());
+        assert!(limits.check(br#"{"a":[1,2,3],"b":{"c":1}}"#).is_err());
+    }
+
+    async fn extract(body: String) -> Result<Value, StatusCode> {
+        let req = Request::builder()
+            .method("POST")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap();
+        LimitedJson::<Value>::from_request(req, &())
+            .await
+            .map(|LimitedJson(value)| value)
+            .map_err(|rejection| rejection.status())
+    }
+
+    #[tokio::test]
+    async fn test_deeply_nested_metadata_is_rejected() {
+        let log = |metadata: String| {
+            format!(
+                r#"{{"logs":[{{"level":"info","message":"hi","metadata":{}}}]}}"#,
+                metadata
+            )
+        };
+
+        let normal = extract(log(r#"{"user":{"id":42,"roles":["admin"]}}"#.to_string())).await;
+        assert!(normal.is_ok());
+
+        // Within serde_json's own recursion limit, so only the configured depth rejects it
+        let deep = extract(log(nested(100))).await;
+        assert_eq!(deep.unwrap_err(), StatusCode::BAD_REQUEST);
+    }
+}
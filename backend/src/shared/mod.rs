@@ -0,0 +1,24 @@
+pub mod body_limits;
+pub mod client_ip;
+pub mod json_limits;
+pub mod object_store;
+pub mod pagination;
+pub mod query_cache;
+pub mod startup_retry;
+pub mod timezone;
+pub mod trace_context;
+pub mod trace_keep_set;
+
+pub use body_limits::BodyLimits;
+pub use client_ip::client_ip;
+pub use json_limits::{JsonLimits, LimitedJson};
+pub use object_store::{ObjectStore, S3Config, S3ObjectStore};
+pub use pagination::{PAGINATION_LIMIT_HEADER, Pagination, PaginationConfig};
+pub use query_cache::{QueryCache, QueryCacheConfig, bypasses_query_cache};
+pub use startup_retry::{RetryPolicy, retry_with_backoff};
+pub use timezone::{Tz, local_bucket_start, parse_timezone};
+pub use trace_context::IdFormatPolicy;
+pub use trace_keep_set::{TraceKeepSet, TraceKeepSetConfig};
+
+#[cfg(test)]
+pub mod testing;
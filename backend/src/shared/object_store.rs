@@ -0,0 +1,191 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+/// Error writing to object storage
+#[derive(Debug, thiserror::Error)]
+#[error("Object store error: {0}")]
+pub struct ObjectStoreError(pub String);
+
+/// Write-only access to a bucket of objects
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// Write `body` to `key`, replacing any existing object
+    async fn put(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        content_type: &str,
+    ) -> Result<(), ObjectStoreError>;
+}
+
+/// Connection settings for an S3-compatible bucket
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    /// Base URL of the service, e.g. https://s3.eu-west-1.amazonaws.com
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+/// S3-compatible object store using path-style URLs and SigV4 signing
+pub struct S3ObjectStore {
+    client: reqwest::Client,
+    config: S3Config,
+}
+
+impl S3ObjectStore {
+    pub fn new(client: reqwest::Client, config: S3Config) -> Self {
+        Self { client, config }
+    }
+
+    /// Authorization header value for a PUT of `path` signed at `now`
+    fn authorization(
+        &self,
+        host: &str,
+        path: &str,
+        content_type: &str,
+        payload_hash: &str,
+        now: DateTime<Utc>,
+    ) -> String {
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let signed_headers = "content-type;host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "PUT\n{}\n\ncontent-type:{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            path, content_type, host, payload_hash, amz_date, signed_headers, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.config.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{:x}",
+            amz_date,
+            scope,
+            Sha256::digest(canonical_request.as_bytes())
+        );
+        let key = signing_key(
+            &self.config.secret_access_key,
+            &date,
+            &self.config.region,
+            "s3",
+        );
+        let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.config.access_key_id, scope, signed_headers, signature
+        )
+    }
+}
+
+#[async_trait]
+impl ObjectStore for S3ObjectStore {
+    async fn put(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        content_type: &str,
+    ) -> Result<(), ObjectStoreError> {
+        let endpoint = reqwest::Url::parse(&self.config.endpoint)
+            .map_err(|e| ObjectStoreError(format!("invalid endpoint: {}", e)))?;
+        let host = match (endpoint.host_str(), endpoint.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(ObjectStoreError("endpoint has no host".to_string())),
+        };
+        let path = format!(
+            "{}/{}/{}",
+            endpoint.path().trim_end_matches('/'),
+            uri_encode(&self.config.bucket),
+            uri_encode(key)
+        );
+
+        let now = Utc::now();
+        let payload_hash = format!("{:x}", Sha256::digest(&body));
+        let authorization = self.authorization(&host, &path, content_type, &payload_hash, now);
+
+        let url = format!("{}://{}{}", endpoint.scheme(), host, path);
+        let response = self
+            .client
+            .put(&url)
+            .header("content-type", content_type)
+            .header("x-amz-content-sha256", &payload_hash)
+            .header("x-amz-date", now.format("%Y%m%dT%H%M%SZ").to_string())
+            .header("authorization", authorization)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| ObjectStoreError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let detail = response.text().await.unwrap_or_default();
+            return Err(ObjectStoreError(format!(
+                "PUT {} returned {}: {}",
+                key, status, detail
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Percent-encode everything but unreserved characters and `/`
+fn uri_encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                encoded.push(b as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    encoded
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// SigV4 signing key for a date (YYYYMMDD), region and service
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    hmac_sha256(&key, b"aws4_request")
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing_key_matches_aws_example() {
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex(&key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_uri_encode_keeps_slashes() {
+        assert_eq!(
+            uri_encode("metrics/project_id=p 1/date=2026-01-02/metrics_1h.parquet"),
+            "metrics/project_id%3Dp%201/date%3D2026-01-02/metrics_1h.parquet"
+        );
+    }
+}
@@ -0,0 +1,81 @@
+/// Response header carrying the effective page size; every paginated list
+/// endpoint reports it here rather than in the body
+pub const PAGINATION_LIMIT_HEADER: &str = "x-pagination-limit";
+
+/// Pagination parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self {
+            limit: 100,
+            offset: 0,
+        }
+    }
+}
+
+/// Page size limits shared by all list endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaginationConfig {
+    default_limit: i64,
+    max_limit: i64,
+}
+
+impl PaginationConfig {
+    /// Create a config, keeping the default within `1..=max_limit`
+    pub fn new(default_limit: i64, max_limit: i64) -> Self {
+        let max_limit = max_limit.max(1);
+        Self {
+            default_limit: default_limit.clamp(1, max_limit),
+            max_limit,
+        }
+    }
+
+    /// Build the effective pagination for a request.
+    /// A missing limit falls back to the default, an oversized one is clamped
+    /// to the maximum, and negative offsets are treated as zero.
+    pub fn resolve(&self, limit: Option<i64>, offset: Option<i64>) -> Pagination {
+        Pagination {
+            limit: limit.unwrap_or(self.default_limit).clamp(1, self.max_limit),
+            offset: offset.unwrap_or(0).max(0),
+        }
+    }
+}
+
+impl Default for PaginationConfig {
+    fn default() -> Self {
+        Self::new(100, 1000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_uses_default_when_omitted() {
+        let config = PaginationConfig::new(25, 200);
+        let page = config.resolve(None, None);
+        assert_eq!(page.limit, 25);
+        assert_eq!(page.offset, 0);
+    }
+
+    #[test]
+    fn test_resolve_clamps_to_max() {
+        let config = PaginationConfig::new(25, 200);
+        assert_eq!(config.resolve(Some(10_000), Some(5)).limit, 200);
+        assert_eq!(config.resolve(Some(0), None).limit, 1);
+        assert_eq!(config.resolve(Some(50), Some(-3)).offset, 0);
+    }
+
+    #[test]
+    fn test_default_never_exceeds_max() {
+        let config = PaginationConfig::new(500, 100);
+        assert_eq!(config.resolve(None, None).limit, 100);
+        assert_eq!(config.resolve(Some(1000), None).limit, 100);
+    }
+}
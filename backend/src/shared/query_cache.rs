@@ -0,0 +1,144 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::http::HeaderMap;
+use chrono::{DateTime, Utc};
+
+/// Request header that skips the query cache when set to "bypass"
+pub const QUERY_CACHE_HEADER: &str = "x-query-cache";
+
+/// Whether the request asks for a fresh result
+pub fn bypasses_query_cache(headers: &HeaderMap) -> bool {
+    headers
+        .get(QUERY_CACHE_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("bypass"))
+}
+
+/// How long results are kept and which queries qualify
+#[derive(Debug, Clone, Copy)]
+pub struct QueryCacheConfig {
+    pub ttl: Duration,
+    pub max_entries: usize,
+    /// Ranges ending this close to now may still gain data and aren't cached
+    pub live_window: chrono::Duration,
+}
+
+struct CachedResult {
+    value: Arc<dyn Any + Send + Sync>,
+    expires_at: Instant,
+}
+
+/// Short-lived, size-bounded in-memory cache of aggregation query results.
+/// Keys name the query kind, project and normalized parameters, so results of
+/// different types never share a key.
+pub struct QueryCache {
+    config: QueryCacheConfig,
+    entries: Mutex<HashMap<String, CachedResult>>,
+}
+
+impl QueryCache {
+    pub fn new(config: QueryCacheConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Key for a query of `kind` in `project_id` over a range ending at `end`,
+    /// or `None` when its result may still change and must not be cached.
+    /// `params` must already be normalized (sorted lists, ordered maps) so
+    /// equal queries format alike.
+    pub fn key(
+        &self,
+        kind: &str,
+        project_id: &str,
+        end: Option<DateTime<Utc>>,
+        params: &impl Debug,
+    ) -> Option<String> {
+        self.is_cacheable(end, Utc::now())
+            .then(|| format!("{}:{}:{:?}", kind, project_id, params))
+    }
+
+    /// Whether a result over a range ending at `end` may be cached at `now`.
+    /// Open ranges end "now" and are never cached.
+    fn is_cacheable(&self, end: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+        end.is_some_and(|end| end <= now - self.config.live_window)
+    }
+
+    pub fn get<T: Clone + Send + Sync + 'static>(&self, key: &str) -> Option<T> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => {
+                entry.value.downcast_ref::<T>().cloned()
+            }
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert<T: Send + Sync + 'static>(&self, key: String, value: T) {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.config.max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, entry| entry.expires_at > now);
+            // Still full: drop whatever expires soonest
+            if entries.len() >= self.config.max_entries
+                && let Some(oldest) = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.expires_at)
+                    .map(|(key, _)| key.clone())
+            {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            key,
+            CachedResult {
+                value: Arc::new(value),
+                expires_at: now + self.config.ttl,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(max_entries: usize) -> QueryCache {
+        QueryCache::new(QueryCacheConfig {
+            ttl: Duration::from_secs(60),
+            max_entries,
+            live_window: chrono::Duration::minutes(1),
+        })
+    }
+
+    #[test]
+    fn test_size_bound_evicts_oldest_entry() {
+        let cache = cache(2);
+        cache.insert("a".to_string(), 1u32);
+        cache.insert("b".to_string(), 2u32);
+        cache.insert("c".to_string(), 3u32);
+        assert_eq!(cache.get::<u32>("a"), None);
+        assert_eq!(cache.get::<u32>("b"), Some(2));
+        assert_eq!(cache.get::<u32>("c"), Some(3));
+        // A hit of the wrong type is a miss
+        assert_eq!(cache.get::<String>("c"), None);
+    }
+
+    #[test]
+    fn test_ranges_ending_now_are_not_cacheable() {
+        let cache = cache(10);
+        let now = Utc::now();
+        assert!(!cache.is_cacheable(None, now));
+        assert!(!cache.is_cacheable(Some(now - chrono::Duration::seconds(30)), now));
+        assert!(cache.is_cacheable(Some(now - chrono::Duration::minutes(5)), now));
+    }
+}
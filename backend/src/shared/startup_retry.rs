@@ -0,0 +1,125 @@
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// Wait before the first retry; doubles after each failed attempt
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Longest wait between attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// How long startup keeps retrying a dependency that isn't reachable yet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Give up once this much time has passed; zero fails on the first error
+    pub timeout: Duration,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            initial_backoff: INITIAL_BACKOFF,
+            max_backoff: MAX_BACKOFF,
+        }
+    }
+}
+
+/// Call `attempt` until it succeeds, backing off exponentially between failures.
+/// Returns the last error once the next wait would run past the policy's timeout.
+pub async fn retry_with_backoff<T, E, F, Fut>(
+    what: &str,
+    policy: RetryPolicy,
+    mut attempt: F,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Display,
+{
+    let deadline = Instant::now() + policy.timeout;
+    let mut backoff = policy.initial_backoff;
+    let mut attempts = 1u32;
+
+    loop {
+        match attempt().await {
+            Ok(value) => return Ok(value),
+            Err(e) if Instant::now() + backoff > deadline => return Err(e),
+            Err(e) => {
+                tracing::warn!(
+                    attempt = attempts,
+                    retry_in_ms = backoff.as_millis() as u64,
+                    error = %e,
+                    "Failed to connect to {}, retrying",
+                    what
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(policy.max_backoff);
+                attempts += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn fast_policy(timeout_ms: u64) -> RetryPolicy {
+        RetryPolicy {
+            timeout: Duration::from_millis(timeout_ms),
+            initial_backoff: Duration::from_millis(5),
+            max_backoff: Duration::from_millis(20),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_then_available_succeeds_within_budget() {
+        let attempts = AtomicU32::new(0);
+
+        // Refuses the first three connections, like a database still starting up
+        let result = retry_with_backoff("database", fast_policy(1_000), || async {
+            match attempts.fetch_add(1, Ordering::SeqCst) {
+                0..=2 => Err("connection refused"),
+                _ => Ok("connected"),
+            }
+        })
+        .await;
+
+        assert_eq!(result, Ok("connected"));
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_timeout() {
+        let attempts = AtomicU32::new(0);
+
+        let result: Result<(), _> = retry_with_backoff("database", fast_policy(50), || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err("connection refused")
+        })
+        .await;
+
+        assert_eq!(result, Err("connection refused"));
+        assert!(attempts.load(Ordering::SeqCst) > 1);
+    }
+
+    #[tokio::test]
+    async fn test_zero_timeout_fails_immediately() {
+        let attempts = AtomicU32::new(0);
+
+        let result: Result<(), _> = retry_with_backoff("database", fast_policy(0), || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err("connection refused")
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,2412 @@
+//! In-memory repositories shared by service tests
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use tokio::sync::Semaphore;
+
+use crate::modules::alerts::domain::{
+    Alert, AlertChannel, AlertChannelId, AlertChannelRepository, AlertDomainError, AlertId,
+    AlertRepository, AlertRule, AlertRuleId, AlertRuleRepository, DigestEntry,
+    NotificationPreference, NotificationPreferenceRepository, OrgAlertDefaults,
+    OrgAlertDefaultsRepository,
+};
+use crate::modules::auth::application::ports::IdGenerator;
+use crate::modules::auth::domain::{
+    AuthDomainError, Email, PasswordHash, User, UserId, UserRepository, Username,
+};
+use crate::modules::logging::domain::{
+    DeadLetter, DedupSaveResult, LogCutoffs, LogDomainError, LogEntry, LogFilters, LogGroup,
+    LogQueryResult, LogRepository, LogStats, MetadataFilter, MetadataOperator,
+    Pagination as LogPagination, SortOrder,
+};
+use crate::modules::metrics::domain::{
+    AggregatedMetric, LabeledRollup, MetricFilters, MetricPoint, MetricQueryResult, MetricType,
+    MetricsDomainError, MetricsRepository, PercentileBucket, RollupInterval, StepRange,
+};
+use crate::modules::organizations::domain::{
+    ActivityType, InviteId, InviteStatus, MemberId, OrgActivity, OrgActivityRepository,
+    OrgDomainError, OrgId, OrgRole, Organization, OrganizationInvite, OrganizationInviteRepository,
+    OrganizationMember, OrganizationMemberRepository, OrganizationRepository,
+};
+use crate::modules::projects::domain::{
+    ApiKey, ApiKeyId, ApiKeyRepository, LogGroupField, MetricsRetentionDays, Project,
+    ProjectDomainError, ProjectId, ProjectName, ProjectRepository, PublicReadToken,
+    PublicReadTokenRepository, RetentionDays, ServiceMetadata, ServiceMetadataRepository,
+    TracesRetentionDays,
+};
+use crate::modules::traces::domain::{
+    DurationBucket, ErrorRateUnit, Pagination, Span, SpanCounts, SpanSaveResult, SpanStatusCode,
+    SpansRepository, TraceCutoffs, TraceFilters, TraceSearchResult, TraceSummary,
+    TracesDomainError,
+};
+use crate::shared::object_store::{ObjectStore, ObjectStoreError};
+use crate::shared::{Tz, local_bucket_start};
+
+pub struct InMemoryProjectRepository {
+    projects: Mutex<HashMap<String, Project>>,
+}
+
+impl InMemoryProjectRepository {
+    pub fn new() -> Self {
+        Self {
+            projects: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Insert a project with default retention settings
+    pub fn seed(&self, project_id: &str, org_id: &str) -> Project {
+        let project = Project::new(
+            ProjectId::new(project_id.to_string()),
+            OrgId::new(org_id.to_string()),
+            ProjectName::new(format!("Project {}", project_id)).unwrap(),
+            None,
+            RetentionDays::default(),
+            MetricsRetentionDays::default(),
+            TracesRetentionDays::default(),
+        );
+        self.projects
+            .lock()
+            .unwrap()
+            .insert(project_id.to_string(), project.clone());
+        project
+    }
+}
+
+#[async_trait]
+impl ProjectRepository for InMemoryProjectRepository {
+    async fn find_by_id(&self, id: &ProjectId) -> Result<Option<Project>, ProjectDomainError> {
+        Ok(self.projects.lock().unwrap().get(id.as_str()).cloned())
+    }
+
+    async fn find_page_by_org(
+        &self,
+        org_id: &OrgId,
+        pagination: &Pagination,
+    ) -> Result<Vec<Project>, ProjectDomainError> {
+        let mut projects: Vec<Project> = self
+            .projects
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|p| p.organization_id().as_str() == org_id.as_str() && !p.is_deleted())
+            .cloned()
+            .collect();
+        projects.sort_by(|a, b| a.id().as_str().cmp(b.id().as_str()));
+        Ok(projects
+            .into_iter()
+            .skip(pagination.offset as usize)
+            .take(pagination.limit as usize)
+            .collect())
+    }
+
+    async fn save(&self, project: &Project) -> Result<(), ProjectDomainError> {
+        self.projects
+            .lock()
+            .unwrap()
+            .insert(project.id().as_str().to_string(), project.clone());
+        Ok(())
+    }
+
+    async fn exists_by_name_and_org(
+        &self,
+        name: &str,
+        org_id: &OrgId,
+    ) -> Result<bool, ProjectDomainError> {
+        Ok(self
+            .projects
+            .lock()
+            .unwrap()
+            .values()
+            .any(|p| p.name().as_str() == name && p.organization_id().as_str() == org_id.as_str()))
+    }
+
+    async fn exists_by_name_and_org_excluding(
+        &self,
+        name: &str,
+        org_id: &OrgId,
+        exclude_id: &ProjectId,
+    ) -> Result<bool, ProjectDomainError> {
+        Ok(self.projects.lock().unwrap().values().any(|p| {
+            p.name().as_str() == name
+                && p.organization_id().as_str() == org_id.as_str()
+                && p.id().as_str() != exclude_id.as_str()
+        }))
+    }
+
+    async fn find_all_active(&self) -> Result<Vec<Project>, ProjectDomainError> {
+        Ok(self
+            .projects
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|p| !p.is_deleted())
+            .cloned()
+            .collect())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryApiKeyRepository {
+    keys: Mutex<HashMap<String, ApiKey>>,
+    hash_lookups: Mutex<usize>,
+}
+
+impl InMemoryApiKeyRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of `find_by_hash` calls so far
+    pub fn hash_lookups(&self) -> usize {
+        *self.hash_lookups.lock().unwrap()
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for InMemoryApiKeyRepository {
+    async fn find_by_id(&self, id: &ApiKeyId) -> Result<Option<ApiKey>, ProjectDomainError> {
+        Ok(self.keys.lock().unwrap().get(id.as_str()).cloned())
+    }
+
+    async fn find_by_hash(&self, hash: &str) -> Result<Option<ApiKey>, ProjectDomainError> {
+        *self.hash_lookups.lock().unwrap() += 1;
+        Ok(self
+            .keys
+            .lock()
+            .unwrap()
+            .values()
+            .find(|k| k.key_hash() == hash)
+            .cloned())
+    }
+
+    async fn find_by_project(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Vec<ApiKey>, ProjectDomainError> {
+        Ok(self
+            .keys
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|k| k.project_id().as_str() == project_id.as_str())
+            .cloned()
+            .collect())
+    }
+
+    async fn save(&self, api_key: &ApiKey) -> Result<(), ProjectDomainError> {
+        self.keys
+            .lock()
+            .unwrap()
+            .insert(api_key.id().as_str().to_string(), api_key.clone());
+        Ok(())
+    }
+
+    async fn revoke(&self, id: &ApiKeyId) -> Result<(), ProjectDomainError> {
+        if let Some(key) = self.keys.lock().unwrap().get_mut(id.as_str()) {
+            key.revoke();
+        }
+        Ok(())
+    }
+}
+
+/// Service metadata keyed by (project id, service name); counts batched lookups
+#[derive(Default)]
+pub struct InMemoryServiceMetadataRepository {
+    metadata: Mutex<HashMap<(String, String), ServiceMetadata>>,
+    lookups: Mutex<usize>,
+}
+
+impl InMemoryServiceMetadataRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn seed(&self, project_id: &str, service_name: &str, display_name: &str) {
+        let metadata = ServiceMetadata::new(
+            ProjectId::new(project_id.to_string()),
+            service_name.to_string(),
+            display_name.to_string(),
+            None,
+            Vec::new(),
+        )
+        .unwrap();
+        self.metadata
+            .lock()
+            .unwrap()
+            .insert((project_id.to_string(), service_name.to_string()), metadata);
+    }
+
+    /// Number of `find_by_services` calls made
+    pub fn lookups(&self) -> usize {
+        *self.lookups.lock().unwrap()
+    }
+}
+
+#[async_trait]
+impl ServiceMetadataRepository for InMemoryServiceMetadataRepository {
+    async fn find_by_project(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Vec<ServiceMetadata>, ProjectDomainError> {
+        Ok(self
+            .metadata
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|m| m.project_id() == project_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn find_by_services(
+        &self,
+        project_id: &ProjectId,
+        service_names: &[String],
+    ) -> Result<Vec<ServiceMetadata>, ProjectDomainError> {
+        *self.lookups.lock().unwrap() += 1;
+        let metadata = self.metadata.lock().unwrap();
+        Ok(service_names
+            .iter()
+            .filter_map(|name| metadata.get(&(project_id.as_str().to_string(), name.clone())))
+            .cloned()
+            .collect())
+    }
+
+    async fn save(&self, metadata: &ServiceMetadata) -> Result<(), ProjectDomainError> {
+        self.metadata.lock().unwrap().insert(
+            (
+                metadata.project_id().as_str().to_string(),
+                metadata.service_name().to_string(),
+            ),
+            metadata.clone(),
+        );
+        Ok(())
+    }
+
+    async fn delete(
+        &self,
+        project_id: &ProjectId,
+        service_name: &str,
+    ) -> Result<bool, ProjectDomainError> {
+        Ok(self
+            .metadata
+            .lock()
+            .unwrap()
+            .remove(&(project_id.as_str().to_string(), service_name.to_string()))
+            .is_some())
+    }
+}
+
+/// Public read tokens keyed by project id
+#[derive(Default)]
+pub struct InMemoryPublicReadTokenRepository {
+    tokens: Mutex<HashMap<String, PublicReadToken>>,
+}
+
+impl InMemoryPublicReadTokenRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl PublicReadTokenRepository for InMemoryPublicReadTokenRepository {
+    async fn find_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<PublicReadToken>, ProjectDomainError> {
+        Ok(self
+            .tokens
+            .lock()
+            .unwrap()
+            .values()
+            .find(|t| t.token_hash() == token_hash)
+            .cloned())
+    }
+
+    async fn find_by_project(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Option<PublicReadToken>, ProjectDomainError> {
+        Ok(self
+            .tokens
+            .lock()
+            .unwrap()
+            .get(project_id.as_str())
+            .cloned())
+    }
+
+    async fn save(&self, token: &PublicReadToken) -> Result<(), ProjectDomainError> {
+        self.tokens
+            .lock()
+            .unwrap()
+            .insert(token.project_id().as_str().to_string(), token.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, project_id: &ProjectId) -> Result<bool, ProjectDomainError> {
+        Ok(self
+            .tokens
+            .lock()
+            .unwrap()
+            .remove(project_id.as_str())
+            .is_some())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryOrganizationRepository {
+    orgs: Mutex<HashMap<String, Organization>>,
+}
+
+impl InMemoryOrganizationRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl OrganizationRepository for InMemoryOrganizationRepository {
+    async fn find_by_id(&self, id: &OrgId) -> Result<Option<Organization>, OrgDomainError> {
+        Ok(self.orgs.lock().unwrap().get(id.as_str()).cloned())
+    }
+
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<Organization>, OrgDomainError> {
+        Ok(self
+            .orgs
+            .lock()
+            .unwrap()
+            .values()
+            .find(|o| o.slug().as_str().eq_ignore_ascii_case(slug))
+            .cloned())
+    }
+
+    async fn save(&self, org: &Organization) -> Result<(), OrgDomainError> {
+        self.orgs
+            .lock()
+            .unwrap()
+            .insert(org.id().as_str().to_string(), org.clone());
+        Ok(())
+    }
+
+    async fn find_all_active(&self) -> Result<Vec<Organization>, OrgDomainError> {
+        Ok(self
+            .orgs
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|o| !o.is_deleted())
+            .cloned()
+            .collect())
+    }
+
+    async fn slug_exists(&self, slug: &str) -> Result<bool, OrgDomainError> {
+        Ok(self.find_by_slug(slug).await?.is_some())
+    }
+}
+
+pub struct InMemoryMemberRepository {
+    members: Mutex<Vec<OrganizationMember>>,
+}
+
+impl InMemoryMemberRepository {
+    pub fn new() -> Self {
+        Self {
+            members: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Add a user to an organization with the given role
+    pub fn seed(&self, org_id: &str, user_id: &str, role: OrgRole) -> OrganizationMember {
+        let member = OrganizationMember::new(
+            MemberId::new(format!("member-{}-{}", org_id, user_id)),
+            OrgId::new(org_id.to_string()),
+            UserId::new(user_id.to_string()),
+            role,
+        );
+        self.members.lock().unwrap().push(member.clone());
+        member
+    }
+}
+
+#[async_trait]
+impl OrganizationMemberRepository for InMemoryMemberRepository {
+    async fn find_by_id(
+        &self,
+        id: &MemberId,
+    ) -> Result<Option<OrganizationMember>, OrgDomainError> {
+        Ok(self
+            .members
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|m| m.id().as_str() == id.as_str())
+            .cloned())
+    }
+
+    async fn find_by_org_and_user(
+        &self,
+        org_id: &OrgId,
+        user_id: &UserId,
+    ) -> Result<Option<OrganizationMember>, OrgDomainError> {
+        Ok(self
+            .members
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|m| {
+                m.organization_id().as_str() == org_id.as_str()
+                    && m.user_id().as_str() == user_id.as_str()
+            })
+            .cloned())
+    }
+
+    async fn find_all_by_org(
+        &self,
+        org_id: &OrgId,
+    ) -> Result<Vec<OrganizationMember>, OrgDomainError> {
+        Ok(self
+            .members
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|m| m.organization_id().as_str() == org_id.as_str())
+            .cloned()
+            .collect())
+    }
+
+    async fn find_page_by_org(
+        &self,
+        org_id: &OrgId,
+        pagination: &Pagination,
+    ) -> Result<Vec<OrganizationMember>, OrgDomainError> {
+        Ok(self
+            .find_all_by_org(org_id)
+            .await?
+            .into_iter()
+            .skip(pagination.offset as usize)
+            .take(pagination.limit as usize)
+            .collect())
+    }
+
+    async fn find_all_by_user(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<OrganizationMember>, OrgDomainError> {
+        Ok(self
+            .members
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|m| m.user_id().as_str() == user_id.as_str())
+            .cloned()
+            .collect())
+    }
+
+    async fn find_last_accessed_by_user(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Option<OrganizationMember>, OrgDomainError> {
+        Ok(self
+            .members
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|m| m.user_id().as_str() == user_id.as_str())
+            .filter(|m| m.last_accessed_at().is_some())
+            .max_by_key(|m| m.last_accessed_at())
+            .cloned())
+    }
+
+    async fn find_personal_org_membership(
+        &self,
+        _user_id: &UserId,
+    ) -> Result<Option<OrganizationMember>, OrgDomainError> {
+        Ok(None)
+    }
+
+    async fn save(&self, member: &OrganizationMember) -> Result<(), OrgDomainError> {
+        let mut members = self.members.lock().unwrap();
+        members.retain(|m| m.id().as_str() != member.id().as_str());
+        members.push(member.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, id: &MemberId) -> Result<(), OrgDomainError> {
+        self.members
+            .lock()
+            .unwrap()
+            .retain(|m| m.id().as_str() != id.as_str());
+        Ok(())
+    }
+
+    async fn count_owners(&self, org_id: &OrgId) -> Result<u32, OrgDomainError> {
+        Ok(self
+            .members
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|m| m.organization_id().as_str() == org_id.as_str())
+            .filter(|m| matches!(m.role(), OrgRole::Owner))
+            .count() as u32)
+    }
+
+    async fn count_owners_for_update(&self, org_id: &OrgId) -> Result<u32, OrgDomainError> {
+        self.count_owners(org_id).await
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryUserRepository {
+    users: Mutex<Vec<User>>,
+}
+
+impl InMemoryUserRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a user with the given id and email
+    pub fn seed(&self, user_id: &str, email: &str) -> User {
+        let user = User::new_oauth(
+            UserId::new(user_id.to_string()),
+            Email::new(email.to_string()).unwrap(),
+        );
+        self.users.lock().unwrap().push(user.clone());
+        user
+    }
+}
+
+#[async_trait]
+impl UserRepository for InMemoryUserRepository {
+    async fn find_by_id(&self, id: &UserId) -> Result<Option<User>, AuthDomainError> {
+        Ok(self
+            .users
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|u| u.id().as_str() == id.as_str())
+            .cloned())
+    }
+
+    async fn find_by_email(&self, email: &Email) -> Result<Option<User>, AuthDomainError> {
+        Ok(self
+            .users
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|u| u.email().as_str() == email.as_str())
+            .cloned())
+    }
+
+    async fn find_by_username(&self, username: &Username) -> Result<Option<User>, AuthDomainError> {
+        Ok(self
+            .users
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|u| u.username() == Some(username))
+            .cloned())
+    }
+
+    async fn save(&self, user: &User) -> Result<(), AuthDomainError> {
+        let mut users = self.users.lock().unwrap();
+        users.retain(|u| u.id().as_str() != user.id().as_str());
+        users.push(user.clone());
+        Ok(())
+    }
+
+    async fn update_password_hash(
+        &self,
+        id: &UserId,
+        password_hash: &PasswordHash,
+    ) -> Result<(), AuthDomainError> {
+        let mut users = self.users.lock().unwrap();
+        if let Some(user) = users.iter_mut().find(|u| u.id() == id) {
+            user.update_password(password_hash.clone());
+        }
+        Ok(())
+    }
+
+    async fn exists_by_email(&self, email: &Email) -> Result<bool, AuthDomainError> {
+        Ok(self.find_by_email(email).await?.is_some())
+    }
+
+    async fn exists_by_username(&self, username: &Username) -> Result<bool, AuthDomainError> {
+        Ok(self.find_by_username(username).await?.is_some())
+    }
+}
+
+pub struct InMemoryInviteRepository {
+    invites: Mutex<HashMap<String, OrganizationInvite>>,
+    members: Arc<InMemoryMemberRepository>,
+}
+
+impl InMemoryInviteRepository {
+    pub fn new() -> Self {
+        Self::with_members(Arc::new(InMemoryMemberRepository::new()))
+    }
+
+    /// Accepting an invite adds the membership to `members`
+    pub fn with_members(members: Arc<InMemoryMemberRepository>) -> Self {
+        Self {
+            invites: Mutex::new(HashMap::new()),
+            members,
+        }
+    }
+
+    fn pending(&self, matches: impl Fn(&OrganizationInvite) -> bool) -> Vec<OrganizationInvite> {
+        self.invites
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|i| i.status() == InviteStatus::Pending && matches(i))
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl OrganizationInviteRepository for InMemoryInviteRepository {
+    async fn save(&self, invite: &OrganizationInvite) -> Result<(), OrgDomainError> {
+        self.invites
+            .lock()
+            .unwrap()
+            .insert(invite.id().as_str().to_string(), invite.clone());
+        Ok(())
+    }
+
+    async fn update(&self, invite: &OrganizationInvite) -> Result<(), OrgDomainError> {
+        self.save(invite).await
+    }
+
+    async fn accept(
+        &self,
+        invite: &OrganizationInvite,
+        member: &OrganizationMember,
+    ) -> Result<bool, OrgDomainError> {
+        let mut members = self.members.members.lock().unwrap();
+        if members.iter().any(|m| {
+            m.organization_id().as_str() == member.organization_id().as_str()
+                && m.user_id().as_str() == member.user_id().as_str()
+        }) {
+            return Ok(false);
+        }
+        members.push(member.clone());
+        self.invites
+            .lock()
+            .unwrap()
+            .insert(invite.id().as_str().to_string(), invite.clone());
+        Ok(true)
+    }
+
+    async fn find_by_id(
+        &self,
+        id: &InviteId,
+    ) -> Result<Option<OrganizationInvite>, OrgDomainError> {
+        Ok(self.invites.lock().unwrap().get(id.as_str()).cloned())
+    }
+
+    async fn find_pending_by_org_and_email(
+        &self,
+        org_id: &str,
+        email: &str,
+    ) -> Result<Option<OrganizationInvite>, OrgDomainError> {
+        Ok(self
+            .pending(|i| i.organization_id() == org_id && i.invitee_email() == Some(email))
+            .pop())
+    }
+
+    async fn list_pending_by_org(
+        &self,
+        org_id: &str,
+    ) -> Result<Vec<OrganizationInvite>, OrgDomainError> {
+        Ok(self.pending(|i| i.organization_id() == org_id))
+    }
+
+    async fn count_pending_by_org(&self, org_id: &str) -> Result<i64, OrgDomainError> {
+        Ok(self
+            .pending(|i| i.organization_id() == org_id && !i.is_expired())
+            .iter()
+            .map(|i| i.remaining_uses() as i64)
+            .sum())
+    }
+
+    async fn list_pending_by_user(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<OrganizationInvite>, OrgDomainError> {
+        Ok(self.pending(|i| i.invitee_id() == Some(user_id)))
+    }
+
+    async fn count_pending_by_user(&self, user_id: &str) -> Result<i64, OrgDomainError> {
+        Ok(self.pending(|i| i.invitee_id() == Some(user_id)).len() as i64)
+    }
+
+    async fn delete(&self, id: &InviteId) -> Result<(), OrgDomainError> {
+        self.invites.lock().unwrap().remove(id.as_str());
+        Ok(())
+    }
+
+    async fn mark_expired(&self) -> Result<i64, OrgDomainError> {
+        let mut invites = self.invites.lock().unwrap();
+        let mut count = 0;
+        for invite in invites.values_mut() {
+            if invite.status() == InviteStatus::Pending && invite.is_expired() {
+                invite.mark_expired();
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryActivityRepository {
+    activities: Mutex<Vec<OrgActivity>>,
+}
+
+impl InMemoryActivityRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl OrgActivityRepository for InMemoryActivityRepository {
+    async fn save(&self, activity: &OrgActivity) -> Result<(), OrgDomainError> {
+        self.activities.lock().unwrap().push(activity.clone());
+        Ok(())
+    }
+
+    async fn find_by_org(
+        &self,
+        org_id: &OrgId,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<OrgActivity>, OrgDomainError> {
+        Ok(self
+            .activities
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|a| a.organization_id().as_str() == org_id.as_str())
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_before(
+        &self,
+        org_id: &OrgId,
+        before: DateTime<Utc>,
+        keep: &[ActivityType],
+    ) -> Result<u64, OrgDomainError> {
+        let mut activities = self.activities.lock().unwrap();
+        let len = activities.len();
+        activities.retain(|a| {
+            a.organization_id().as_str() != org_id.as_str()
+                || a.created_at() >= before
+                || keep.contains(&a.activity_type())
+        });
+        Ok((len - activities.len()) as u64)
+    }
+}
+
+/// Deterministic ID generator: id-1, id-2, ...
+pub struct SequentialIdGenerator {
+    counter: Mutex<u64>,
+}
+
+impl SequentialIdGenerator {
+    pub fn new() -> Self {
+        Self {
+            counter: Mutex::new(0),
+        }
+    }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn generate(&self) -> String {
+        let mut counter = self.counter.lock().unwrap();
+        *counter += 1;
+        format!("id-{}", counter)
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryAlertRepository {
+    alerts: Mutex<Vec<Alert>>,
+}
+
+impl InMemoryAlertRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl AlertRepository for InMemoryAlertRepository {
+    async fn save(&self, alert: &Alert) -> Result<(), AlertDomainError> {
+        self.alerts.lock().unwrap().push(alert.clone());
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &AlertId) -> Result<Option<Alert>, AlertDomainError> {
+        Ok(self
+            .alerts
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|a| a.id().as_str() == id.as_str())
+            .cloned())
+    }
+
+    async fn find_by_project(
+        &self,
+        project_id: &ProjectId,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Alert>, AlertDomainError> {
+        Ok(self
+            .alerts
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|a| a.project_id().as_str() == project_id.as_str())
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn find_by_rule(
+        &self,
+        rule_id: &AlertRuleId,
+        limit: i64,
+    ) -> Result<Vec<Alert>, AlertDomainError> {
+        Ok(self
+            .alerts
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|a| a.rule_id().as_str() == rule_id.as_str())
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn find_firing_by_rule(
+        &self,
+        rule_id: &AlertRuleId,
+    ) -> Result<Option<Alert>, AlertDomainError> {
+        Ok(self
+            .alerts
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|a| a.rule_id().as_str() == rule_id.as_str() && a.is_firing())
+            .cloned())
+    }
+
+    async fn update(&self, alert: &Alert) -> Result<(), AlertDomainError> {
+        let mut alerts = self.alerts.lock().unwrap();
+        if let Some(existing) = alerts
+            .iter_mut()
+            .find(|a| a.id().as_str() == alert.id().as_str())
+        {
+            *existing = alert.clone();
+        }
+        Ok(())
+    }
+
+    async fn count_by_project(&self, project_id: &ProjectId) -> Result<i64, AlertDomainError> {
+        Ok(self
+            .alerts
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|a| a.project_id().as_str() == project_id.as_str())
+            .count() as i64)
+    }
+
+    async fn delete_resolved_before(
+        &self,
+        before: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<u64, AlertDomainError> {
+        let mut alerts = self.alerts.lock().unwrap();
+        let mut expired: Vec<(DateTime<Utc>, String)> = alerts
+            .iter()
+            .filter_map(|a| {
+                let resolved_at = a.resolved_at().filter(|_| !a.is_firing())?;
+                (resolved_at < before).then(|| (resolved_at, a.id().as_str().to_string()))
+            })
+            .collect();
+        expired.sort();
+        expired.truncate(limit as usize);
+        alerts.retain(|a| !expired.iter().any(|(_, id)| id == a.id().as_str()));
+        Ok(expired.len() as u64)
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryAlertRuleRepository {
+    rules: Mutex<Vec<AlertRule>>,
+    channels: Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl InMemoryAlertRuleRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl AlertRuleRepository for InMemoryAlertRuleRepository {
+    async fn save(&self, rule: &AlertRule) -> Result<(), AlertDomainError> {
+        self.rules.lock().unwrap().push(rule.clone());
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: &AlertRuleId) -> Result<Option<AlertRule>, AlertDomainError> {
+        Ok(self
+            .rules
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|r| r.id().as_str() == id.as_str())
+            .cloned())
+    }
+
+    async fn find_by_project(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Vec<AlertRule>, AlertDomainError> {
+        Ok(self
+            .rules
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.project_id().as_str() == project_id.as_str())
+            .cloned()
+            .collect())
+    }
+
+    async fn count_by_project(&self, project_id: &ProjectId) -> Result<i64, AlertDomainError> {
+        Ok(self
+            .rules
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.project_id().as_str() == project_id.as_str())
+            .count() as i64)
+    }
+
+    async fn find_all_enabled(&self) -> Result<Vec<AlertRule>, AlertDomainError> {
+        Ok(self
+            .rules
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.is_enabled())
+            .cloned()
+            .collect())
+    }
+
+    async fn update(&self, rule: &AlertRule) -> Result<(), AlertDomainError> {
+        let mut rules = self.rules.lock().unwrap();
+        if let Some(existing) = rules
+            .iter_mut()
+            .find(|r| r.id().as_str() == rule.id().as_str())
+        {
+            *existing = rule.clone();
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, id: &AlertRuleId) -> Result<(), AlertDomainError> {
+        self.rules
+            .lock()
+            .unwrap()
+            .retain(|r| r.id().as_str() != id.as_str());
+        Ok(())
+    }
+
+    async fn name_exists(
+        &self,
+        project_id: &ProjectId,
+        name: &str,
+        exclude_id: Option<&AlertRuleId>,
+    ) -> Result<bool, AlertDomainError> {
+        Ok(self.rules.lock().unwrap().iter().any(|r| {
+            r.project_id().as_str() == project_id.as_str()
+                && r.name() == name
+                && exclude_id.is_none_or(|id| r.id().as_str() != id.as_str())
+        }))
+    }
+
+    async fn set_channels(
+        &self,
+        rule_id: &AlertRuleId,
+        channel_ids: &[String],
+    ) -> Result<(), AlertDomainError> {
+        self.channels
+            .lock()
+            .unwrap()
+            .insert(rule_id.as_str().to_string(), channel_ids.to_vec());
+        Ok(())
+    }
+
+    async fn get_channel_ids(
+        &self,
+        rule_id: &AlertRuleId,
+    ) -> Result<Vec<String>, AlertDomainError> {
+        Ok(self
+            .channels
+            .lock()
+            .unwrap()
+            .get(rule_id.as_str())
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryAlertChannelRepository {
+    channels: Mutex<Vec<AlertChannel>>,
+}
+
+impl InMemoryAlertChannelRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn seed(&self, channel: AlertChannel) {
+        self.channels.lock().unwrap().push(channel);
+    }
+}
+
+#[async_trait]
+impl AlertChannelRepository for InMemoryAlertChannelRepository {
+    async fn save(&self, channel: &AlertChannel) -> Result<(), AlertDomainError> {
+        self.channels.lock().unwrap().push(channel.clone());
+        Ok(())
+    }
+
+    async fn find_by_id(
+        &self,
+        id: &AlertChannelId,
+    ) -> Result<Option<AlertChannel>, AlertDomainError> {
+        Ok(self
+            .channels
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|c| c.id().as_str() == id.as_str())
+            .cloned())
+    }
+
+    async fn find_by_project(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Vec<AlertChannel>, AlertDomainError> {
+        Ok(self
+            .channels
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|c| c.belongs_to_project(project_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn find_by_organization(
+        &self,
+        org_id: &OrgId,
+    ) -> Result<Vec<AlertChannel>, AlertDomainError> {
+        Ok(self
+            .channels
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|c| {
+                c.organization_id()
+                    .is_some_and(|id| id.as_str() == org_id.as_str())
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn find_by_ids(&self, ids: &[String]) -> Result<Vec<AlertChannel>, AlertDomainError> {
+        Ok(self
+            .channels
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|c| c.is_enabled() && ids.iter().any(|id| id == c.id().as_str()))
+            .cloned()
+            .collect())
+    }
+
+    async fn update(&self, channel: &AlertChannel) -> Result<(), AlertDomainError> {
+        let mut channels = self.channels.lock().unwrap();
+        if let Some(existing) = channels
+            .iter_mut()
+            .find(|c| c.id().as_str() == channel.id().as_str())
+        {
+            *existing = channel.clone();
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, id: &AlertChannelId) -> Result<(), AlertDomainError> {
+        self.channels
+            .lock()
+            .unwrap()
+            .retain(|c| c.id().as_str() != id.as_str());
+        Ok(())
+    }
+
+    async fn name_exists(
+        &self,
+        project_id: &ProjectId,
+        name: &str,
+        exclude_id: Option<&AlertChannelId>,
+    ) -> Result<bool, AlertDomainError> {
+        Ok(self.channels.lock().unwrap().iter().any(|c| {
+            c.belongs_to_project(project_id)
+                && c.name() == name
+                && exclude_id.is_none_or(|id| c.id().as_str() != id.as_str())
+        }))
+    }
+
+    async fn org_name_exists(
+        &self,
+        org_id: &OrgId,
+        name: &str,
+        exclude_id: Option<&AlertChannelId>,
+    ) -> Result<bool, AlertDomainError> {
+        Ok(self.channels.lock().unwrap().iter().any(|c| {
+            c.organization_id()
+                .is_some_and(|id| id.as_str() == org_id.as_str())
+                && c.name() == name
+                && exclude_id.is_none_or(|id| c.id().as_str() != id.as_str())
+        }))
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryNotificationPreferenceRepository {
+    preferences: Mutex<HashMap<String, NotificationPreference>>,
+    digest_entries: Mutex<Vec<DigestEntry>>,
+}
+
+impl InMemoryNotificationPreferenceRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn queued(&self) -> Vec<DigestEntry> {
+        self.digest_entries.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl NotificationPreferenceRepository for InMemoryNotificationPreferenceRepository {
+    async fn find_by_user(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Option<NotificationPreference>, AlertDomainError> {
+        Ok(self
+            .preferences
+            .lock()
+            .unwrap()
+            .get(user_id.as_str())
+            .cloned())
+    }
+
+    async fn find_by_users(
+        &self,
+        user_ids: &[UserId],
+    ) -> Result<Vec<NotificationPreference>, AlertDomainError> {
+        let preferences = self.preferences.lock().unwrap();
+        Ok(user_ids
+            .iter()
+            .filter_map(|id| preferences.get(id.as_str()).cloned())
+            .collect())
+    }
+
+    async fn find_digest_enabled(&self) -> Result<Vec<NotificationPreference>, AlertDomainError> {
+        Ok(self
+            .preferences
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|p| p.digest_enabled())
+            .cloned()
+            .collect())
+    }
+
+    async fn save(&self, preference: &NotificationPreference) -> Result<(), AlertDomainError> {
+        self.preferences.lock().unwrap().insert(
+            preference.user_id().as_str().to_string(),
+            preference.clone(),
+        );
+        Ok(())
+    }
+
+    async fn queue_digest_entry(&self, entry: &DigestEntry) -> Result<(), AlertDomainError> {
+        self.digest_entries.lock().unwrap().push(entry.clone());
+        Ok(())
+    }
+
+    async fn take_digest_entries(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<DigestEntry>, AlertDomainError> {
+        let mut entries = self.digest_entries.lock().unwrap();
+        let (taken, kept) = entries
+            .drain(..)
+            .partition(|e| e.user_id.as_str() == user_id.as_str());
+        *entries = kept;
+        Ok(taken)
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryOrgAlertDefaultsRepository {
+    defaults: Mutex<HashMap<String, OrgAlertDefaults>>,
+}
+
+impl InMemoryOrgAlertDefaultsRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl OrgAlertDefaultsRepository for InMemoryOrgAlertDefaultsRepository {
+    async fn find_by_org(
+        &self,
+        org_id: &OrgId,
+    ) -> Result<Option<OrgAlertDefaults>, AlertDomainError> {
+        Ok(self.defaults.lock().unwrap().get(org_id.as_str()).cloned())
+    }
+
+    async fn save(&self, defaults: &OrgAlertDefaults) -> Result<(), AlertDomainError> {
+        self.defaults
+            .lock()
+            .unwrap()
+            .insert(defaults.org_id().as_str().to_string(), defaults.clone());
+        Ok(())
+    }
+}
+
+/// Cutoff for a log with the given metadata: the earliest among the tag rules
+/// it matches, like the Timescale repository's `LEAST(...)`
+fn log_cutoff(cutoffs: &LogCutoffs, metadata: Option<&serde_json::Value>) -> DateTime<Utc> {
+    cutoffs
+        .tagged
+        .iter()
+        .filter(|rule| {
+            rule.tags.iter().all(|(key, value)| {
+                metadata
+                    .and_then(|m| m.get(key))
+                    .and_then(serde_json::Value::as_str)
+                    == Some(value)
+            })
+        })
+        .map(|rule| rule.cutoff)
+        .min()
+        .unwrap_or(cutoffs.default)
+}
+
+/// Remove up to `limit` of the oldest items `expired_at` picks out, like a
+/// bounded retention DELETE; returns how many were removed
+fn delete_oldest<T>(
+    items: &mut Vec<T>,
+    limit: u32,
+    expired_at: impl Fn(&T) -> Option<DateTime<Utc>>,
+) -> u64 {
+    let mut expired: Vec<(DateTime<Utc>, usize)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| expired_at(item).map(|t| (t, i)))
+        .collect();
+    expired.sort();
+    let mut remove: Vec<usize> = expired
+        .into_iter()
+        .take(limit as usize)
+        .map(|(_, i)| i)
+        .collect();
+    remove.sort_unstable();
+    for i in remove.iter().rev() {
+        items.remove(*i);
+    }
+    remove.len() as u64
+}
+
+#[derive(Default)]
+pub struct InMemorySpansRepository {
+    spans: Mutex<Vec<Span>>,
+}
+
+impl InMemorySpansRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn seed(&self, spans: Vec<Span>) {
+        self.spans.lock().unwrap().extend(spans);
+    }
+
+    pub fn saved(&self) -> Vec<Span> {
+        self.spans.lock().unwrap().clone()
+    }
+
+    /// Spans in the project matching the filters
+    fn matching(&self, project_id: &ProjectId, filters: &TraceFilters) -> Vec<Span> {
+        self.spans
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|s| s.project_id().as_str() == project_id.as_str())
+            .filter(|s| {
+                filters
+                    .service_name
+                    .as_deref()
+                    .is_none_or(|n| s.service_name() == Some(n))
+            })
+            .filter(|s| filters.status.is_none_or(|st| s.status() == st))
+            .filter(|s| filters.span_kind.is_none_or(|kind| s.kind() == kind))
+            .filter(|s| filters.start_time.is_none_or(|t| s.start_time() >= t))
+            .filter(|s| filters.end_time.is_none_or(|t| s.start_time() <= t))
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl SpansRepository for InMemorySpansRepository {
+    async fn save_batch(
+        &self,
+        spans: &[Span],
+        update_duplicates: bool,
+    ) -> Result<SpanSaveResult, TracesDomainError> {
+        let mut stored = self.spans.lock().unwrap();
+        let mut result = SpanSaveResult::default();
+        for span in spans {
+            let existing = stored.iter_mut().find(|s| {
+                s.project_id().as_str() == span.project_id().as_str()
+                    && s.trace_id() == span.trace_id()
+                    && s.span_id() == span.span_id()
+                    && s.start_time() == span.start_time()
+            });
+            match existing {
+                Some(existing) => {
+                    if update_duplicates {
+                        *existing = span.clone();
+                    }
+                    result.duplicates += 1;
+                }
+                None => {
+                    stored.push(span.clone());
+                    result.saved += 1;
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    async fn get_trace(
+        &self,
+        project_id: &ProjectId,
+        trace_id: &str,
+    ) -> Result<Vec<Span>, TracesDomainError> {
+        Ok(self
+            .spans
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|s| s.project_id().as_str() == project_id.as_str() && s.trace_id() == trace_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn search_traces(
+        &self,
+        project_id: &ProjectId,
+        filters: &TraceFilters,
+        pagination: &Pagination,
+    ) -> Result<TraceSearchResult, TracesDomainError> {
+        let mut by_trace: BTreeMap<String, Vec<Span>> = BTreeMap::new();
+        for span in self.matching(project_id, filters) {
+            by_trace
+                .entry(span.trace_id().to_string())
+                .or_default()
+                .push(span);
+        }
+        if let Some(settled_before) = filters.settled_before {
+            let stored = self.spans.lock().unwrap();
+            by_trace.retain(|trace_id, _| {
+                !stored.iter().any(|s| {
+                    s.project_id().as_str() == project_id.as_str()
+                        && s.trace_id() == trace_id
+                        && s.received_at() > settled_before
+                })
+            });
+        }
+        let mut traces: Vec<TraceSummary> = by_trace
+            .into_iter()
+            .map(|(trace_id, spans)| {
+                let start_time = spans.iter().map(|s| s.start_time()).min().unwrap();
+                let end_time = spans.iter().filter_map(|s| s.end_time()).max();
+                let mut service_names: Vec<String> = spans
+                    .iter()
+                    .filter_map(|s| s.service_name().map(String::from))
+                    .collect();
+                service_names.sort();
+                service_names.dedup();
+                TraceSummary {
+                    trace_id,
+                    root_span_name: spans
+                        .iter()
+                        .find(|s| s.parent_span_id().is_none())
+                        .map(|s| s.name().to_string()),
+                    service_names,
+                    span_count: spans.len() as i64,
+                    error_count: spans
+                        .iter()
+                        .filter(|s| s.status() == SpanStatusCode::Error)
+                        .count() as i64,
+                    start_time,
+                    end_time,
+                    duration_ns: end_time.and_then(|end| (end - start_time).num_nanoseconds()),
+                    last_received_at: spans.iter().map(|s| s.received_at()).max().unwrap(),
+                }
+            })
+            .collect();
+        traces.sort_by_key(|t| std::cmp::Reverse(t.start_time));
+        let total = traces.len() as i64;
+        let traces = traces
+            .into_iter()
+            .skip(pagination.offset as usize)
+            .take(pagination.limit as usize)
+            .collect();
+        Ok(TraceSearchResult { traces, total })
+    }
+
+    async fn count_spans(
+        &self,
+        project_id: &ProjectId,
+        filters: &TraceFilters,
+    ) -> Result<SpanCounts, TracesDomainError> {
+        let matching = self.matching(project_id, filters);
+        Ok(SpanCounts {
+            spans: matching.len() as i64,
+            adjusted: matching.iter().map(|s| s.adjusted_count()).sum(),
+        })
+    }
+
+    async fn duration_histogram(
+        &self,
+        project_id: &ProjectId,
+        filters: &TraceFilters,
+        bucket_boundaries: &[i64],
+    ) -> Result<Vec<DurationBucket>, TracesDomainError> {
+        let mut buckets = DurationBucket::empty_buckets(bucket_boundaries);
+        for duration in self
+            .matching(project_id, filters)
+            .iter()
+            .filter_map(|s| s.duration_ns())
+            .filter(|d| *d >= 0)
+        {
+            buckets[bucket_boundaries.partition_point(|&b| b <= duration)].count += 1;
+        }
+        Ok(buckets)
+    }
+
+    async fn count_errors(
+        &self,
+        project_id: &ProjectId,
+        filters: &TraceFilters,
+        unit: ErrorRateUnit,
+    ) -> Result<(i64, i64), TracesDomainError> {
+        let matching = self.matching(project_id, filters);
+        let is_error = |s: &Span| s.status() == SpanStatusCode::Error;
+        Ok(match unit {
+            ErrorRateUnit::Spans => (
+                matching.iter().filter(|s| is_error(s)).count() as i64,
+                matching.len() as i64,
+            ),
+            ErrorRateUnit::Traces => {
+                let mut traces: HashMap<&str, bool> = HashMap::new();
+                for span in &matching {
+                    *traces.entry(span.trace_id()).or_default() |= is_error(span);
+                }
+                (
+                    traces.values().filter(|error| **error).count() as i64,
+                    traces.len() as i64,
+                )
+            }
+        })
+    }
+
+    async fn duration_range(
+        &self,
+        project_id: &ProjectId,
+        filters: &TraceFilters,
+    ) -> Result<Option<(i64, i64)>, TracesDomainError> {
+        let durations: Vec<i64> = self
+            .matching(project_id, filters)
+            .iter()
+            .filter_map(|s| s.duration_ns())
+            .filter(|d| *d >= 0)
+            .collect();
+        Ok(durations
+            .iter()
+            .min()
+            .copied()
+            .zip(durations.iter().max().copied()))
+    }
+
+    async fn find_in_range(
+        &self,
+        project_id: &ProjectId,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<Span>, TracesDomainError> {
+        Ok(self
+            .spans
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|s| s.project_id().as_str() == project_id.as_str())
+            .filter(|s| s.start_time() >= start && s.start_time() < end)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_service_names(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Vec<String>, TracesDomainError> {
+        let names: std::collections::BTreeSet<String> = self
+            .spans
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|s| s.project_id().as_str() == project_id.as_str())
+            .filter_map(|s| s.service_name().map(String::from))
+            .collect();
+        Ok(names.into_iter().collect())
+    }
+
+    async fn delete_before(
+        &self,
+        project_id: &ProjectId,
+        before: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<u64, TracesDomainError> {
+        let mut spans = self.spans.lock().unwrap();
+        Ok(delete_oldest(&mut spans, limit, |s| {
+            (s.project_id().as_str() == project_id.as_str() && s.start_time() < before)
+                .then(|| s.start_time())
+        }))
+    }
+
+    async fn delete_expired_traces(
+        &self,
+        project_id: &ProjectId,
+        cutoffs: &TraceCutoffs,
+        limit: u32,
+    ) -> Result<u64, TracesDomainError> {
+        let mut spans = self.spans.lock().unwrap();
+        let mut traces: HashMap<String, Vec<&Span>> = HashMap::new();
+        for span in spans
+            .iter()
+            .filter(|s| s.project_id().as_str() == project_id.as_str())
+        {
+            traces
+                .entry(span.trace_id().to_string())
+                .or_default()
+                .push(span);
+        }
+
+        let expired: Vec<String> = traces
+            .into_iter()
+            .filter(|(_, trace)| {
+                let last_start = trace.iter().map(|s| s.start_time()).max().unwrap();
+                let has_error = trace.iter().any(|s| s.status() == SpanStatusCode::Error);
+                let root_service = trace
+                    .iter()
+                    .filter(|s| s.parent_span_id().is_none())
+                    .min_by_key(|s| s.start_time())
+                    .and_then(|s| s.service_name());
+                let cutoff = root_service
+                    .and_then(|service| cutoffs.services.get(service))
+                    .unwrap_or(&cutoffs.default);
+                last_start
+                    < if has_error {
+                        cutoff.error
+                    } else {
+                        cutoff.clean
+                    }
+            })
+            .map(|(trace_id, _)| trace_id)
+            .take(limit as usize)
+            .collect();
+
+        let before = spans.len();
+        spans.retain(|s| {
+            s.project_id().as_str() != project_id.as_str()
+                || !expired.iter().any(|t| t == s.trace_id())
+        });
+        Ok((before - spans.len()) as u64)
+    }
+}
+
+/// The `percentile` (0-1) of `values`, interpolating linearly between the
+/// two nearest ranks like Postgres' `percentile_cont`. `values` must be sorted.
+fn interpolated_percentile(values: &[f64], percentile: f64) -> Option<f64> {
+    let last = values.len().checked_sub(1)?;
+    let rank = percentile * last as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    Some(values[lower] + (values[upper] - values[lower]) * (rank - lower as f64))
+}
+
+#[derive(Default)]
+pub struct InMemoryMetricsRepository {
+    metrics: Mutex<Vec<MetricPoint>>,
+    /// (project id, name) -> registered type
+    types: Mutex<HashMap<(String, String), MetricType>>,
+    /// (project id, day) -> exported object key
+    exports: Mutex<BTreeMap<(String, NaiveDate), String>>,
+}
+
+impl InMemoryMetricsRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All points saved so far
+    pub fn saved(&self) -> Vec<MetricPoint> {
+        self.metrics.lock().unwrap().clone()
+    }
+
+    /// Points of the project passing the name and time filters
+    fn matching(&self, project_id: &ProjectId, filters: &MetricFilters) -> Vec<MetricPoint> {
+        self.metrics
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|m| {
+                m.project_id().as_str() == project_id.as_str()
+                    && filters
+                        .names
+                        .as_ref()
+                        .is_none_or(|n| n.iter().any(|n| n == m.name()))
+                    && filters.start_time.is_none_or(|t| m.timestamp() >= t)
+                    && filters.end_time.is_none_or(|t| m.timestamp() <= t)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Matching points aggregated per name, type and `bucket_secs` wide bucket
+    fn aggregate(
+        &self,
+        project_id: &ProjectId,
+        filters: &MetricFilters,
+        bucket_secs: i64,
+        timezone: Tz,
+    ) -> Vec<AggregatedMetric> {
+        let mut buckets: BTreeMap<(String, String, DateTime<Utc>), AggregatedMetric> =
+            BTreeMap::new();
+        for m in self.matching(project_id, filters) {
+            let bucket = local_bucket_start(m.timestamp(), bucket_secs, timezone);
+            let key = (
+                m.name().to_string(),
+                m.metric_type().as_str().to_string(),
+                bucket,
+            );
+            let value = m.value();
+            buckets
+                .entry(key)
+                .and_modify(|a| {
+                    a.min_value = a.min_value.min(value);
+                    a.max_value = a.max_value.max(value);
+                    a.sum_value += value;
+                    a.sample_count += 1;
+                    a.avg_value = a.sum_value / a.sample_count as f64;
+                })
+                .or_insert_with(|| AggregatedMetric {
+                    project_id: project_id.as_str().to_string(),
+                    name: m.name().to_string(),
+                    metric_type: m.metric_type().as_str().to_string(),
+                    bucket,
+                    avg_value: value,
+                    min_value: value,
+                    max_value: value,
+                    sum_value: value,
+                    sample_count: 1,
+                });
+        }
+        buckets.into_values().collect()
+    }
+}
+
+#[async_trait]
+impl MetricsRepository for InMemoryMetricsRepository {
+    async fn save_batch(&self, metrics: &[MetricPoint]) -> Result<u32, MetricsDomainError> {
+        self.metrics.lock().unwrap().extend_from_slice(metrics);
+        Ok(metrics.len() as u32)
+    }
+
+    async fn query(
+        &self,
+        project_id: &ProjectId,
+        filters: &MetricFilters,
+        rollup: RollupInterval,
+        _limit: Option<i64>,
+        _offset: Option<i64>,
+    ) -> Result<MetricQueryResult, MetricsDomainError> {
+        let metrics = self.aggregate(project_id, filters, rollup.width_secs(), Tz::UTC);
+        Ok(MetricQueryResult {
+            total: metrics.len() as i64,
+            metrics,
+        })
+    }
+
+    async fn query_steps(
+        &self,
+        project_id: &ProjectId,
+        filters: &MetricFilters,
+        range: &StepRange,
+    ) -> Result<Vec<AggregatedMetric>, MetricsDomainError> {
+        Ok(range.fill(self.aggregate(project_id, filters, range.step_secs(), range.timezone())))
+    }
+
+    async fn query_step_percentiles(
+        &self,
+        project_id: &ProjectId,
+        filters: &MetricFilters,
+        range: &StepRange,
+        percentile: f64,
+    ) -> Result<Vec<PercentileBucket>, MetricsDomainError> {
+        let mut values: HashMap<(String, DateTime<Utc>), Vec<f64>> = HashMap::new();
+        for m in self.matching(project_id, filters) {
+            values
+                .entry((m.name().to_string(), range.align(m.timestamp())))
+                .or_default()
+                .push(m.value());
+        }
+        let buckets = self
+            .aggregate(project_id, filters, range.step_secs(), range.timezone())
+            .into_iter()
+            .map(|metric| {
+                let mut bucket_values = values
+                    .remove(&(metric.name.clone(), metric.bucket))
+                    .unwrap_or_default();
+                bucket_values.sort_by(f64::total_cmp);
+                PercentileBucket {
+                    value: interpolated_percentile(&bucket_values, percentile),
+                    metric,
+                }
+            })
+            .collect();
+        Ok(range.fill_percentiles(buckets))
+    }
+
+    async fn find_metric_types(
+        &self,
+        project_id: &ProjectId,
+        names: &[String],
+    ) -> Result<HashMap<String, MetricType>, MetricsDomainError> {
+        let types = self.types.lock().unwrap();
+        Ok(names
+            .iter()
+            .filter_map(|name| {
+                types
+                    .get(&(project_id.as_str().to_string(), name.clone()))
+                    .map(|t| (name.clone(), *t))
+            })
+            .collect())
+    }
+
+    async fn register_metric_types(
+        &self,
+        project_id: &ProjectId,
+        types: &[(String, MetricType)],
+    ) -> Result<(), MetricsDomainError> {
+        let mut registered = self.types.lock().unwrap();
+        for (name, metric_type) in types {
+            registered
+                .entry((project_id.as_str().to_string(), name.clone()))
+                .or_insert(*metric_type);
+        }
+        Ok(())
+    }
+
+    async fn get_metric_names(
+        &self,
+        project_id: &ProjectId,
+    ) -> Result<Vec<String>, MetricsDomainError> {
+        let mut names: Vec<String> = self
+            .metrics
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|m| m.project_id().as_str() == project_id.as_str())
+            .map(|m| m.name().to_string())
+            .collect();
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
+    async fn query_labeled_rollups(
+        &self,
+        project_id: &ProjectId,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        rollup: RollupInterval,
+    ) -> Result<Vec<LabeledRollup>, MetricsDomainError> {
+        let width = rollup.width_secs();
+        let mut buckets: BTreeMap<(String, String, BTreeMap<String, String>, i64), LabeledRollup> =
+            BTreeMap::new();
+        for m in self.metrics.lock().unwrap().iter() {
+            if m.project_id().as_str() != project_id.as_str()
+                || m.timestamp() < start
+                || m.timestamp() >= end
+            {
+                continue;
+            }
+            let bucket = m.timestamp().timestamp().div_euclid(width) * width;
+            let labels: BTreeMap<String, String> = m
+                .tags()
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            let key = (
+                m.name().to_string(),
+                m.metric_type().as_str().to_string(),
+                labels.clone(),
+                bucket,
+            );
+            let value = m.value();
+            buckets
+                .entry(key)
+                .and_modify(|r| {
+                    r.min_value = r.min_value.min(value);
+                    r.max_value = r.max_value.max(value);
+                    r.sum_value += value;
+                    r.sample_count += 1;
+                    r.avg_value = r.sum_value / r.sample_count as f64;
+                })
+                .or_insert_with(|| LabeledRollup {
+                    name: m.name().to_string(),
+                    metric_type: m.metric_type().as_str().to_string(),
+                    labels,
+                    bucket: DateTime::from_timestamp(bucket, 0).unwrap(),
+                    avg_value: value,
+                    min_value: value,
+                    max_value: value,
+                    sum_value: value,
+                    sample_count: 1,
+                });
+        }
+        Ok(buckets.into_values().collect())
+    }
+
+    async fn exported_days(
+        &self,
+        project_id: &ProjectId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<NaiveDate>, MetricsDomainError> {
+        Ok(self
+            .exports
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|(p, day)| p == project_id.as_str() && (from..=to).contains(day))
+            .map(|(_, day)| *day)
+            .collect())
+    }
+
+    async fn record_export(
+        &self,
+        project_id: &ProjectId,
+        day: NaiveDate,
+        object_key: &str,
+    ) -> Result<(), MetricsDomainError> {
+        self.exports.lock().unwrap().insert(
+            (project_id.as_str().to_string(), day),
+            object_key.to_string(),
+        );
+        Ok(())
+    }
+
+    async fn delete_before(
+        &self,
+        project_id: &ProjectId,
+        before: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<u64, MetricsDomainError> {
+        let mut metrics = self.metrics.lock().unwrap();
+        Ok(delete_oldest(&mut metrics, limit, |m| {
+            (m.project_id().as_str() == project_id.as_str() && m.timestamp() < before)
+                .then(|| m.timestamp())
+        }))
+    }
+}
+
+/// Object store keeping written objects in memory
+#[derive(Default)]
+pub struct InMemoryObjectStore {
+    objects: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+impl InMemoryObjectStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keys of all objects written so far, in order
+    pub fn keys(&self) -> Vec<String> {
+        self.objects.lock().unwrap().keys().cloned().collect()
+    }
+
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        self.objects.lock().unwrap().get(key).cloned()
+    }
+}
+
+#[async_trait]
+impl ObjectStore for InMemoryObjectStore {
+    async fn put(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        _content_type: &str,
+    ) -> Result<(), ObjectStoreError> {
+        self.objects.lock().unwrap().insert(key.to_string(), body);
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryLogRepository {
+    logs: Mutex<Vec<LogEntry>>,
+    dead_letters: Mutex<Vec<DeadLetter>>,
+    /// (project id, event id) -> when the claim expires
+    event_ids: Mutex<HashMap<(String, String), DateTime<Utc>>>,
+    /// When set, each batch write waits for a permit
+    write_gate: Option<Arc<Semaphore>>,
+    /// Last ingest sequence handed out, like the logs table's sequence
+    last_sequence: Mutex<i64>,
+}
+
+impl InMemoryLogRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Repository whose batch writes block until `gate` hands out a permit each
+    pub fn gated(gate: Arc<Semaphore>) -> Self {
+        Self {
+            write_gate: Some(gate),
+            ..Self::default()
+        }
+    }
+
+    async fn pass_gate(&self) {
+        if let Some(gate) = &self.write_gate {
+            gate.acquire().await.unwrap().forget();
+        }
+    }
+
+    fn store(&self, log: &LogEntry) {
+        let mut sequence = self.last_sequence.lock().unwrap();
+        *sequence += 1;
+        self.logs
+            .lock()
+            .unwrap()
+            .push(log.clone().with_sequence(Some(*sequence)));
+    }
+
+    /// All log entries saved so far
+    pub fn saved(&self) -> Vec<LogEntry> {
+        self.logs.lock().unwrap().clone()
+    }
+
+    /// All dead letters saved so far
+    pub fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.dead_letters.lock().unwrap().clone()
+    }
+
+    /// Number of dedup keys still stored, expired or not
+    pub fn dedup_key_count(&self) -> usize {
+        self.event_ids.lock().unwrap().len()
+    }
+
+    /// Level and time bucket of each of the project's logs in the range
+    fn bucketed(
+        &self,
+        project_id: &ProjectId,
+        bucket_interval: &str,
+        timezone: Tz,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+    ) -> Vec<(String, DateTime<Utc>)> {
+        let width_secs = match bucket_interval {
+            "1 minute" => 60,
+            "1 hour" => 3600,
+            _ => 86_400,
+        };
+        self.logs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|log| log.project_id() == project_id)
+            .filter(|log| start_time.is_none_or(|start| log.timestamp() >= start))
+            .filter(|log| end_time.is_none_or(|end| log.timestamp() <= end))
+            .map(|log| {
+                (
+                    log.level().to_string(),
+                    local_bucket_start(log.timestamp(), width_secs, timezone),
+                )
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl LogRepository for InMemoryLogRepository {
+    async fn save_batch(&self, logs: &[LogEntry]) -> Result<u32, LogDomainError> {
+        self.pass_gate().await;
+        logs.iter().for_each(|log| self.store(log));
+        Ok(logs.len() as u32)
+    }
+
+    async fn save_batch_dedup(
+        &self,
+        logs: &[LogEntry],
+        window: chrono::Duration,
+    ) -> Result<DedupSaveResult, LogDomainError> {
+        self.pass_gate().await;
+        let now = Utc::now();
+        let mut event_ids = self.event_ids.lock().unwrap();
+        let mut result = DedupSaveResult::default();
+        for log in logs {
+            let Some(event_id) = log.event_id() else {
+                continue;
+            };
+            let key = (log.project_id().as_str().to_string(), event_id.to_string());
+            if event_ids.get(&key).is_some_and(|expires| *expires > now) {
+                result.duplicates += 1;
+                continue;
+            }
+            event_ids.insert(key, now + window);
+            self.store(log);
+            result.saved += 1;
+        }
+        Ok(result)
+    }
+
+    async fn prune_dedup_keys(
+        &self,
+        now: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<u64, LogDomainError> {
+        let mut event_ids = self.event_ids.lock().unwrap();
+        let expired: Vec<_> = event_ids
+            .iter()
+            .filter(|(_, expires)| **expires <= now)
+            .map(|(key, _)| key.clone())
+            .take(limit as usize)
+            .collect();
+        expired.iter().for_each(|key| {
+            event_ids.remove(key);
+        });
+        Ok(expired.len() as u64)
+    }
+
+    async fn save_dead_letters(&self, letters: &[DeadLetter]) -> Result<u32, LogDomainError> {
+        self.dead_letters.lock().unwrap().extend_from_slice(letters);
+        Ok(letters.len() as u32)
+    }
+
+    async fn query(
+        &self,
+        project_id: &ProjectId,
+        _filters: &LogFilters,
+        pagination: &LogPagination,
+        sort: SortOrder,
+        include_total: bool,
+    ) -> Result<LogQueryResult, LogDomainError> {
+        let mut logs: Vec<LogEntry> = self
+            .logs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|l| l.project_id().as_str() == project_id.as_str())
+            .cloned()
+            .collect();
+        logs.sort_by_key(|l| (l.timestamp(), l.sequence()));
+        if sort == SortOrder::Descending {
+            logs.reverse();
+        }
+        let total = logs.len() as i64;
+        let logs: Vec<LogEntry> = logs
+            .into_iter()
+            .skip(pagination.offset as usize)
+            .take(pagination.limit as usize)
+            .collect();
+        Ok(LogQueryResult {
+            has_more: pagination.offset + (logs.len() as i64) < total,
+            logs,
+            total: include_total.then_some(total),
+        })
+    }
+
+    async fn count(
+        &self,
+        project_id: &ProjectId,
+        filters: &LogFilters,
+    ) -> Result<i64, LogDomainError> {
+        Ok(self
+            .logs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|l| l.project_id().as_str() == project_id.as_str())
+            .filter(|l| {
+                filters
+                    .levels
+                    .as_ref()
+                    .is_none_or(|ls| ls.contains(&l.level()))
+            })
+            .filter(|l| {
+                filters
+                    .min_level
+                    .is_none_or(|min| l.level().severity() >= min.severity())
+            })
+            .filter(|l| filters.start_time.is_none_or(|t| l.timestamp() >= t))
+            .filter(|l| filters.end_time.is_none_or(|t| l.timestamp() <= t))
+            .filter(|l| {
+                filters
+                    .source
+                    .as_deref()
+                    .is_none_or(|s| l.source() == Some(s))
+            })
+            .filter(|l| {
+                filters
+                    .search
+                    .as_deref()
+                    .is_none_or(|q| l.message().contains(q))
+            })
+            .filter(|l| {
+                filters
+                    .metadata_filters
+                    .iter()
+                    .all(|f| metadata_matches(l.metadata(), f))
+            })
+            .count() as i64)
+    }
+
+    async fn count_pair(
+        &self,
+        project_id: &ProjectId,
+        first: &LogFilters,
+        second: &LogFilters,
+    ) -> Result<(i64, i64), LogDomainError> {
+        Ok((
+            self.count(project_id, first).await?,
+            self.count(project_id, second).await?,
+        ))
+    }
+
+    async fn get_stats(&self, _project_id: &ProjectId) -> Result<LogStats, LogDomainError> {
+        Ok(LogStats {
+            total_count: 0,
+            counts_by_level: vec![],
+            oldest_log: None,
+            newest_log: None,
+        })
+    }
+
+    async fn delete_before(
+        &self,
+        project_id: &ProjectId,
+        before: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<u64, LogDomainError> {
+        let mut logs = self.logs.lock().unwrap();
+        Ok(delete_oldest(&mut logs, limit, |l| {
+            (l.project_id().as_str() == project_id.as_str() && l.timestamp() < before)
+                .then(|| l.timestamp())
+        }))
+    }
+
+    async fn delete_expired(
+        &self,
+        project_id: &ProjectId,
+        cutoffs: &LogCutoffs,
+        limit: u32,
+    ) -> Result<u64, LogDomainError> {
+        let mut logs = self.logs.lock().unwrap();
+        Ok(delete_oldest(&mut logs, limit, |l| {
+            (l.project_id().as_str() == project_id.as_str()
+                && l.timestamp() < log_cutoff(cutoffs, l.metadata()))
+            .then(|| l.timestamp())
+        }))
+    }
+
+    async fn get_volume_over_time(
+        &self,
+        project_id: &ProjectId,
+        bucket_interval: &str,
+        timezone: Tz,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(DateTime<Utc>, i64)>, LogDomainError> {
+        let mut counts: BTreeMap<DateTime<Utc>, i64> = BTreeMap::new();
+        for (_, bucket) in
+            self.bucketed(project_id, bucket_interval, timezone, start_time, end_time)
+        {
+            *counts.entry(bucket).or_default() += 1;
+        }
+        Ok(counts.into_iter().collect())
+    }
+
+    async fn get_levels_over_time(
+        &self,
+        project_id: &ProjectId,
+        bucket_interval: &str,
+        timezone: Tz,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(String, DateTime<Utc>, i64)>, LogDomainError> {
+        let mut counts: BTreeMap<(DateTime<Utc>, String), i64> = BTreeMap::new();
+        for (level, bucket) in
+            self.bucketed(project_id, bucket_interval, timezone, start_time, end_time)
+        {
+            *counts.entry((bucket, level)).or_default() += 1;
+        }
+        Ok(counts
+            .into_iter()
+            .map(|((bucket, level), count)| (level, bucket, count))
+            .collect())
+    }
+
+    async fn get_top_sources(
+        &self,
+        _project_id: &ProjectId,
+        _limit: i32,
+        _start_time: Option<DateTime<Utc>>,
+        _end_time: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(String, i64, i64)>, LogDomainError> {
+        Ok(vec![])
+    }
+
+    async fn group_by_field(
+        &self,
+        project_id: &ProjectId,
+        field: &LogGroupField,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+        limit: i32,
+        samples: i32,
+    ) -> Result<Vec<LogGroup>, LogDomainError> {
+        let value_of = |log: &LogEntry| -> Option<String> {
+            match field {
+                LogGroupField::Source => log.source().map(String::from),
+                LogGroupField::Level => Some(log.level().as_str().to_string()),
+                LogGroupField::Metadata(path) => {
+                    match path.iter().try_fold(log.metadata()?, |v, key| v.get(key))? {
+                        serde_json::Value::Null => None,
+                        serde_json::Value::String(s) => Some(s.clone()),
+                        other => Some(other.to_string()),
+                    }
+                }
+            }
+        };
+
+        let mut logs: Vec<LogEntry> = self
+            .logs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|l| l.project_id().as_str() == project_id.as_str())
+            .filter(|l| start_time.is_none_or(|t| l.timestamp() >= t))
+            .filter(|l| end_time.is_none_or(|t| l.timestamp() <= t))
+            .cloned()
+            .collect();
+        logs.sort_by_key(|l| std::cmp::Reverse(l.timestamp()));
+
+        let mut groups: Vec<LogGroup> = Vec::new();
+        for log in logs {
+            let value = value_of(&log);
+            match groups.iter_mut().find(|g| g.value == value) {
+                Some(group) => {
+                    group.count += 1;
+                    group.samples.push(log);
+                }
+                None => groups.push(LogGroup {
+                    value,
+                    count: 1,
+                    samples: vec![log],
+                }),
+            }
+        }
+        groups.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+        groups.truncate(limit as usize);
+        for group in &mut groups {
+            group.samples.truncate(samples.max(1) as usize);
+        }
+        Ok(groups)
+    }
+}
+
+/// Whether log metadata passes a filter the way the Postgres repository
+/// applies it: a dotted key is a literal top-level key or a nested path
+fn metadata_matches(metadata: Option<&serde_json::Value>, filter: &MetadataFilter) -> bool {
+    let value = metadata.and_then(|metadata| {
+        metadata.get(&filter.key).or_else(|| {
+            filter
+                .key
+                .split('.')
+                .try_fold(metadata, |value, part| value.get(part))
+        })
+    });
+    let number = |v: &serde_json::Value| v.as_f64();
+    let compare = |ord: fn(f64, f64) -> bool| {
+        value
+            .and_then(number)
+            .zip(filter.value.as_ref().and_then(number))
+            .is_some_and(|(a, b)| ord(a, b))
+    };
+    match filter.operator {
+        MetadataOperator::Exists => value.is_some(),
+        MetadataOperator::Eq => value.is_some() && value == filter.value.as_ref(),
+        MetadataOperator::Neq => value.is_none() || value != filter.value.as_ref(),
+        MetadataOperator::Contains => value.zip(filter.value.as_ref()).is_some_and(|(v, q)| {
+            let needle = q.as_str().map_or_else(|| q.to_string(), String::from);
+            v.to_string()
+                .to_lowercase()
+                .contains(&needle.to_lowercase())
+        }),
+        MetadataOperator::Gt => compare(|a, b| a > b),
+        MetadataOperator::Lt => compare(|a, b| a < b),
+        MetadataOperator::Gte => compare(|a, b| a >= b),
+        MetadataOperator::Lte => compare(|a, b| a <= b),
+    }
+}
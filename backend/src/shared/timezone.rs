@@ -0,0 +1,82 @@
+//! Time buckets aligned to wall-clock boundaries in a named timezone, so a
+//! daily bucket runs from local midnight to local midnight
+
+use chrono::{DateTime, Duration, LocalResult, TimeZone, Utc};
+pub use chrono_tz::Tz;
+
+/// Parse an IANA timezone name such as "Europe/Paris" or "UTC"
+pub fn parse_timezone(name: &str) -> Option<Tz> {
+    name.trim().parse().ok()
+}
+
+/// Seconds since the epoch of the wall-clock time `t` shows in `tz`
+pub fn local_secs(t: DateTime<Utc>, tz: Tz) -> i64 {
+    t.with_timezone(&tz).naive_local().and_utc().timestamp()
+}
+
+/// The instant the wall clock in `tz` reads `secs` (seconds since the epoch).
+/// Ambiguous times take the earlier instant; times skipped by a DST change
+/// map to the end of the gap.
+pub fn from_local_secs(secs: i64, tz: Tz) -> DateTime<Utc> {
+    let naive = DateTime::from_timestamp(secs, 0)
+        .unwrap_or_default()
+        .naive_utc();
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => t.with_timezone(&Utc),
+        LocalResult::None => tz
+            .from_local_datetime(&(naive + Duration::hours(1)))
+            .earliest()
+            .map(|t| t.with_timezone(&Utc))
+            .unwrap_or_else(|| naive.and_utc()),
+    }
+}
+
+/// Start of the `width_secs`-wide bucket containing `t`, with buckets aligned
+/// to multiples of the width in `tz`'s wall-clock time
+pub fn local_bucket_start(t: DateTime<Utc>, width_secs: i64, tz: Tz) -> DateTime<Utc> {
+    let local = local_secs(t, tz);
+    from_local_secs(local.div_euclid(width_secs) * width_secs, tz)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_daily_bucket_starts_at_local_midnight() {
+        let tz = parse_timezone("America/New_York").unwrap();
+        // 03:30 UTC is still the previous evening in New York (UTC-5 in winter)
+        assert_eq!(
+            local_bucket_start(utc("2024-01-15T03:30:00Z"), 86_400, tz),
+            utc("2024-01-14T05:00:00Z")
+        );
+        assert_eq!(
+            local_bucket_start(utc("2024-01-15T05:00:00Z"), 86_400, tz),
+            utc("2024-01-15T05:00:00Z")
+        );
+        // Summer time moves midnight an hour earlier in UTC
+        assert_eq!(
+            local_bucket_start(utc("2024-07-15T12:00:00Z"), 86_400, tz),
+            utc("2024-07-15T04:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_utc_buckets_align_to_epoch_multiples() {
+        let t = utc("2024-01-15T03:30:00Z");
+        assert_eq!(
+            local_bucket_start(t, 3600, Tz::UTC),
+            utc("2024-01-15T03:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_unknown_timezone_is_rejected() {
+        assert!(parse_timezone("Mars/Olympus_Mons").is_none());
+        assert_eq!(parse_timezone(" Asia/Kolkata"), Some(Tz::Asia__Kolkata));
+    }
+}
@@ -0,0 +1,122 @@
+/// Hex digits in a W3C trace id (16 bytes)
+pub const TRACE_ID_HEX_LEN: usize = 32;
+
+/// Hex digits in a W3C span id (8 bytes)
+pub const SPAN_ID_HEX_LEN: usize = 16;
+
+/// Longest id accepted by the lenient policy
+const LENIENT_MAX_LEN: usize = 64;
+
+/// How ingest treats trace and span ids that aren't in W3C hex format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdFormatPolicy {
+    /// Require lowercase hex of the exact W3C length
+    #[default]
+    Strict,
+    /// Lowercase, drop `-` separators and widen 64-bit trace ids, then require the W3C format
+    Normalize,
+    /// Accept any non-empty id up to 64 characters
+    Lenient,
+}
+
+impl IdFormatPolicy {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "strict" => Some(Self::Strict),
+            "normalize" => Some(Self::Normalize),
+            "lenient" => Some(Self::Lenient),
+            _ => None,
+        }
+    }
+
+    /// Validate a trace id, returning it in the form to store
+    pub fn trace_id(&self, id: &str) -> Result<String, String> {
+        self.apply(id, TRACE_ID_HEX_LEN)
+    }
+
+    /// Validate a span id, returning it in the form to store
+    pub fn span_id(&self, id: &str) -> Result<String, String> {
+        self.apply(id, SPAN_ID_HEX_LEN)
+    }
+
+    fn apply(&self, id: &str, hex_len: usize) -> Result<String, String> {
+        let id = match self {
+            Self::Lenient => {
+                return if id.is_empty() || id.len() > LENIENT_MAX_LEN {
+                    Err(format!("'{}' must be 1-{} characters", id, LENIENT_MAX_LEN))
+                } else {
+                    Ok(id.to_string())
+                };
+            }
+            Self::Strict => id.to_string(),
+            Self::Normalize => {
+                let id: String = id.trim().chars().filter(|c| *c != '-').collect();
+                let id = id.to_lowercase();
+                // 64-bit trace ids (e.g. B3) are left-padded to 128 bits
+                if hex_len == TRACE_ID_HEX_LEN && id.len() == SPAN_ID_HEX_LEN {
+                    format!("{:0>width$}", id, width = TRACE_ID_HEX_LEN)
+                } else {
+                    id
+                }
+            }
+        };
+
+        let is_hex = id
+            .chars()
+            .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c));
+        if id.len() != hex_len || !is_hex {
+            return Err(format!(
+                "'{}' is not {} lowercase hex characters",
+                id, hex_len
+            ));
+        }
+        // All-zero ids are invalid in W3C trace context
+        if id.chars().all(|c| c == '0') {
+            return Err(format!("'{}' must not be all zeros", id));
+        }
+
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+
+    #[test]
+    fn test_strict_requires_w3c_format() {
+        let policy = IdFormatPolicy::Strict;
+        assert_eq!(policy.trace_id(TRACE_ID).unwrap(), TRACE_ID);
+        assert!(policy.span_id("00f067aa0ba902b7").is_ok());
+        assert!(policy.trace_id(&TRACE_ID.to_uppercase()).is_err());
+        assert!(policy.trace_id("trace-123").is_err());
+        assert!(policy.trace_id(&"0".repeat(32)).is_err());
+        assert!(policy.span_id(TRACE_ID).is_err());
+    }
+
+    #[test]
+    fn test_normalize_repairs_common_variants() {
+        let policy = IdFormatPolicy::Normalize;
+        assert_eq!(
+            policy
+                .trace_id("4BF92F35-77B3-4DA6-A3CE-929D0E0E4736")
+                .unwrap(),
+            TRACE_ID
+        );
+        assert_eq!(
+            policy.trace_id("463ac35c9f6413ad").unwrap(),
+            "0000000000000000463ac35c9f6413ad"
+        );
+        assert!(policy.trace_id("not-a-trace").is_err());
+    }
+
+    #[test]
+    fn test_lenient_accepts_any_short_id() {
+        let policy = IdFormatPolicy::Lenient;
+        assert_eq!(policy.trace_id("trace-123").unwrap(), "trace-123");
+        assert!(policy.trace_id("").is_err());
+        assert!(policy.trace_id(&"a".repeat(65)).is_err());
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long exemplar trace ids are remembered and how many at most
+#[derive(Debug, Clone, Copy)]
+pub struct TraceKeepSetConfig {
+    pub ttl: Duration,
+    pub max_entries: usize,
+}
+
+/// Trace ids recently referenced by metric exemplars, per project. The trace
+/// sampler keeps these traces whatever their sample rate, so a metric spike
+/// can always be followed to its example trace. Only spans arriving within
+/// the TTL after the exemplar benefit; spans sampled out earlier are gone.
+pub struct TraceKeepSet {
+    config: TraceKeepSetConfig,
+    entries: Mutex<HashMap<(String, String), Instant>>,
+}
+
+impl TraceKeepSet {
+    pub fn new(config: TraceKeepSetConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Remember `trace_id` in `project_id`, refreshing its expiry
+    pub fn insert(&self, project_id: &str, trace_id: &str) {
+        let now = Instant::now();
+        let key = (project_id.to_string(), trace_id.to_lowercase());
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.config.max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, expires_at| *expires_at > now);
+            // Still full: drop whatever expires soonest
+            if entries.len() >= self.config.max_entries
+                && let Some(oldest) = entries
+                    .iter()
+                    .min_by_key(|(_, expires_at)| **expires_at)
+                    .map(|(key, _)| key.clone())
+            {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(key, now + self.config.ttl);
+    }
+
+    /// Whether an unexpired exemplar references `trace_id` in `project_id`
+    pub fn contains(&self, project_id: &str, trace_id: &str) -> bool {
+        let key = (project_id.to_string(), trace_id.to_lowercase());
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(&key) {
+            Some(expires_at) if *expires_at > Instant::now() => true,
+            Some(_) => {
+                entries.remove(&key);
+                false
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_are_per_project_bounded_and_expire() {
+        let keep = TraceKeepSet::new(TraceKeepSetConfig {
+            ttl: Duration::from_secs(60),
+            max_entries: 2,
+        });
+        keep.insert("p1", "AAAA");
+        assert!(keep.contains("p1", "aaaa"));
+        assert!(!keep.contains("p2", "aaaa"));
+
+        keep.insert("p1", "bbbb");
+        keep.insert("p1", "cccc");
+        assert!(!keep.contains("p1", "aaaa"));
+        assert!(keep.contains("p1", "cccc"));
+
+        let expired = TraceKeepSet::new(TraceKeepSetConfig {
+            ttl: Duration::ZERO,
+            max_entries: 10,
+        });
+        expired.insert("p1", "aaaa");
+        assert!(!expired.contains("p1", "aaaa"));
+    }
+}